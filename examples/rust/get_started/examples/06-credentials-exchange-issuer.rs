@@ -63,6 +63,7 @@ async fn main(ctx: Context) -> Result<()> {
         None,
         None,
         true,
+        None,
     );
 
     let mut pre_trusted_identities = BTreeMap::<Identifier, PreTrustedIdentity>::new();
@@ -85,17 +85,23 @@ pub use ockam_node::{
 /// TCP transport
 pub mod tcp {
     pub use ockam_transport_tcp::{
-        TcpConnection, TcpConnectionMode, TcpConnectionOptions, TcpInletOptions, TcpListener,
-        TcpListenerInfo, TcpListenerOptions, TcpOutletOptions, TcpSenderInfo, TcpTransport,
-        TcpTransportExtension, TCP,
+        HttpRewrite, PortalCompression, PortalPeerIdentifier, PortalTlsCertificate,
+        PortalTlsVerification, TcpConnection, TcpConnectionMode, TcpConnectionOptions,
+        TcpEgressAllowList, TcpEgressRule, TcpInletLimits, TcpInletLoadBalancing, TcpInletOptions,
+        TcpListener, TcpListenerInfo, TcpListenerOptions, TcpOutletOptions, TcpPortalRateLimit,
+        TcpPortalStats, TcpProxy, TcpProxyProtocol, TcpSenderInfo, TcpSocketOptions, TcpTransport,
+        TcpTransportExtension, DEFAULT_TCP_KEEPALIVE_INTERVAL, DEFAULT_TCP_KEEPALIVE_TIME,
+        OCKAM_TCP_NO_PROXY, OCKAM_TCP_PROXY, PROXY_PROTOCOL_OCKAM_IDENTIFIER_TLV, TCP,
     };
 }
 #[cfg(feature = "ockam_transport_udp")]
 /// UDP transport
 pub mod udp {
     pub use ockam_transport_udp::{
-        UdpBindArguments, UdpBindOptions, UdpPunctureNegotiation, UdpPunctureNegotiationListener,
-        UdpPunctureNegotiationListenerOptions, UdpTransport, UdpTransportExtension, UDP,
+        RendezvousService, UdpBindArguments, UdpBindOptions, UdpInlet, UdpInletOptions,
+        UdpOutletOptions, UdpPunctureNegotiation, UdpPunctureNegotiationListener,
+        UdpPunctureNegotiationListenerOptions, UdpTransport, UdpTransportExtension,
+        DEFAULT_UDP_FLOW_IDLE_TIMEOUT, UDP,
     };
 }
 pub use relay_service::{RelayService, RelayServiceOptions};
@@ -134,6 +140,7 @@ pub mod compat {
     //! Compatibility adapter, mostly for `no_std` use.
     //!
     //! Most user code should not use these types.
+    pub use ockam_core::compat::time;
     pub use ockam_core::compat::*;
     pub use ockam_node::compat::*;
     pub use ockam_node::tokio;
@@ -185,6 +185,7 @@ impl BooleanExpr {
     /// Parse a string as a boolean expression
     #[cfg(feature = "std")]
     pub fn parse(input: &mut &str) -> Result<BooleanExpr, crate::ParseError> {
+        let input_length = input.len();
         parsers::expr
             .parse_next(input)
             .map_err(|e| {
@@ -207,9 +208,10 @@ impl BooleanExpr {
                 if input.is_empty() {
                     Ok(expr)
                 } else {
-                    Err(crate::ParseError::message(format!(
-                        "successfully parsed: `{expr}`, but `{input}` cannot be parsed"
-                    )))
+                    Err(crate::ParseError::syntax(
+                        format!("successfully parsed: `{expr}`, but `{input}` cannot be parsed"),
+                        input_length - input.len(),
+                    ))
                 }
             })
     }
@@ -488,6 +490,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_boolean_expr_error_positions() {
+        let position = |mut input: &str| BooleanExpr::parse(&mut input).unwrap_err().position();
+        assert_eq!(position("na*me"), Some(2));
+        assert_eq!(position("a and b not c"), Some(7));
+        assert_eq!(position("(a and b) or (c and d))"), Some(22));
+    }
+
     /// HELPERS
 
     /// Test the parsing of a name
@@ -535,8 +545,8 @@ mod tests {
         let input_copy = input.to_string();
         match BooleanExpr::parse(input) {
             Ok(actual) => panic!("there should be an error '{expected}', when parsing {input_copy}. This expression was parsed instead {actual:?}"),
-            Err(crate::ParseError::Message(e)) => assert!(e.contains(expected), "actual error message:\n{e}\nexpected message:\n{expected}"),
-            Err(e) => panic!("expected a Message ParseError, got: {e}"),
+            Err(crate::ParseError::Message(e) | crate::ParseError::Syntax(e, _)) => assert!(e.contains(expected), "actual error message:\n{e}\nexpected message:\n{expected}"),
+            Err(e) => panic!("expected a Message or Syntax ParseError, got: {e}"),
         }
     }
 }
@@ -12,6 +12,8 @@ pub enum ParseError {
     Float(ParseFloatError),
     Other(String),
     Message(String),
+    /// Syntax error found at a given offset of the parsed expression
+    Syntax(String, usize),
     TypeMismatch(Expr, Expr),
 }
 
@@ -33,6 +35,18 @@ impl ParseError {
     pub fn message<S: Into<String>>(s: S) -> Self {
         ParseError::Message(s.into())
     }
+
+    pub fn syntax<S: Into<String>>(s: S, position: usize) -> Self {
+        ParseError::Syntax(s.into(), position)
+    }
+
+    /// Return the offset of the parsed expression where the error was found, if known
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            ParseError::Syntax(_, position) => Some(*position),
+            _ => None,
+        }
+    }
 }
 
 impl EvalError {
@@ -70,7 +84,7 @@ impl From<ParseFloatError> for ParseError {
 impl From<wast::Error> for ParseError {
     #[track_caller]
     fn from(e: wast::Error) -> Self {
-        Self::Other(format!("{e:?}"))
+        Self::Syntax(e.message(), e.span().offset())
     }
 }
 
@@ -82,6 +96,7 @@ impl fmt::Display for ParseError {
             ParseError::Int(e) => write!(f, "{e}"),
             ParseError::Utf8(e) => write!(f, "{e}"),
             ParseError::Message(m) => f.write_str(m),
+            ParseError::Syntax(m, position) => write!(f, "{m} at position {position}"),
             ParseError::TypeMismatch(a, b) => write!(f, "{a} and {b} are not of the same type"),
         }
     }
@@ -108,6 +123,7 @@ impl std::error::Error for ParseError {
             ParseError::Utf8(e) => Some(e),
             ParseError::Other(_) => None,
             ParseError::Message(_) => None,
+            ParseError::Syntax(..) => None,
             ParseError::TypeMismatch(..) => None,
         }
     }
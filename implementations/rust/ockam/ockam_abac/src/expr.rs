@@ -610,6 +610,14 @@ mod tests {
         test_failure("a or b", &format!("The first identifier of the expression: `a or b` must be an operation. The available operations are: {}", OPERATORS.join(", ")));
     }
 
+    #[test]
+    fn syntax_errors_have_a_position() {
+        let position = |s: &str| parse(s).unwrap_err().position();
+        assert_eq!(position("(= subject.a \"b\""), Some(0));
+        assert_eq!(position("(and (= subject.a \"b\") [1 2)"), Some(23));
+        assert_eq!(position("(= subject.a \"b\"))"), Some(17));
+    }
+
     /// HELPERS
     fn test_failure(s: &str, expected_message: &str) {
         match parse(s) {
@@ -29,10 +29,10 @@ pub fn parse(s: &str) -> Result<Option<Expr>, ParseError> {
     enum Op {
         Next,
         Value(Expr),
-        ListStart,
-        ListEnd,
-        SeqStart,
-        SeqEnd,
+        ListStart(usize),
+        ListEnd(usize),
+        SeqStart(usize),
+        SeqEnd(usize),
     }
 
     let lx = Lexer::new(s);
@@ -84,17 +84,17 @@ pub fn parse(s: &str) -> Result<Option<Expr>, ParseError> {
                             ctrl.push(Op::Next)
                         }
                         TokenKind::LParen => {
-                            ctrl.push(Op::ListStart);
+                            ctrl.push(Op::ListStart(token.offset));
                             ctrl.push(Op::Next)
                         }
                         TokenKind::RParen => {
-                            ctrl.push(Op::ListEnd)
+                            ctrl.push(Op::ListEnd(token.offset))
                         }
                         TokenKind::Reserved if token.reserved(s) == "]" => {
-                            ctrl.push(Op::SeqEnd)
+                            ctrl.push(Op::SeqEnd(token.offset))
                         }
                         TokenKind::Reserved if token.reserved(s) == "[" => {
-                            ctrl.push(Op::SeqStart);
+                            ctrl.push(Op::SeqStart(token.offset));
                             ctrl.push(Op::Next)
                         }
                         TokenKind::Keyword if token.keyword(s) == "true" => {
@@ -115,7 +115,7 @@ pub fn parse(s: &str) -> Result<Option<Expr>, ParseError> {
                                 ctrl.push(Op::Value(Expr::Ident(keyword.to_string())));
                                 ctrl.push(Op::Next)
                             } else {
-                                return Err(ParseError::message(format!("invalid keyword token '{keyword}'")))
+                                return Err(ParseError::syntax(format!("invalid keyword token '{keyword}'"), token.offset))
                             }
                         }
                         TokenKind::Reserved  => {
@@ -124,7 +124,7 @@ pub fn parse(s: &str) -> Result<Option<Expr>, ParseError> {
                                 ctrl.push(Op::Value(Expr::Ident(reserved.to_string())));
                                 ctrl.push(Op::Next)
                             } else {
-                                return Err(ParseError::message(format!("invalid reserved token '{reserved}'")))
+                                return Err(ParseError::syntax(format!("invalid reserved token '{reserved}'"), token.offset))
                             }
                         }
                         TokenKind::Annotation => {
@@ -133,39 +133,41 @@ pub fn parse(s: &str) -> Result<Option<Expr>, ParseError> {
                                 ctrl.push(Op::Value(Expr::Ident(annotation.to_string())));
                                 ctrl.push(Op::Next)
                             } else {
-                                return Err(ParseError::message(format!("invalid annotation token '{annotation}'")))
+                                return Err(ParseError::syntax(format!("invalid annotation token '{annotation}'"), token.offset))
                             }
                         }
                     }
                 }
             }
             Op::Value(x) => vals.push(x),
-            Op::ListEnd => {
+            Op::ListEnd(end) => {
                 let mut v = Vec::new();
-                while let Some(x) = ctrl.pop() {
-                    match x {
-                        Op::ListStart => break,
-                        Op::Value(x)  => v.push(x),
-                        Op::ListEnd   => return Err(ParseError::message("')' without matching '('")),
-                        Op::SeqStart  => return Err(ParseError::message("'[' without matching ']'")),
-                        Op::SeqEnd    => return Err(ParseError::message("']' without matching '['")),
-                        Op::Next      => unreachable!("consecutive next operations are impossible")
+                loop {
+                    match ctrl.pop() {
+                        Some(Op::ListStart(_)) => break,
+                        Some(Op::Value(x))     => v.push(x),
+                        Some(Op::SeqStart(p))  => return Err(ParseError::syntax("'[' without matching ']'", p)),
+                        Some(Op::ListEnd(p))   => return Err(ParseError::syntax("')' without matching '('", p)),
+                        Some(Op::SeqEnd(p))    => return Err(ParseError::syntax("']' without matching '['", p)),
+                        Some(Op::Next)         => unreachable!("consecutive next operations are impossible"),
+                        None                   => return Err(ParseError::syntax("')' without matching '('", end)),
                     }
                 }
                 v.reverse();
                 ctrl.push(Op::Value(Expr::List(v)));
                 ctrl.push(Op::Next)
             }
-            Op::SeqEnd => {
+            Op::SeqEnd(end) => {
                 let mut v = Vec::new();
-                while let Some(x) = ctrl.pop() {
-                    match x {
-                        Op::SeqStart  => break,
-                        Op::Value(x)  => v.push(x),
-                        Op::ListEnd   => return Err(ParseError::message("')' without matching '('")),
-                        Op::ListStart => return Err(ParseError::message("'(' without matching ')'")),
-                        Op::SeqEnd    => return Err(ParseError::message("']' without matching '['")),
-                        Op::Next      => unreachable!("consecutive next operations are impossible")
+                loop {
+                    match ctrl.pop() {
+                        Some(Op::SeqStart(_))  => break,
+                        Some(Op::Value(x))     => v.push(x),
+                        Some(Op::ListStart(p)) => return Err(ParseError::syntax("'(' without matching ')'", p)),
+                        Some(Op::ListEnd(p))   => return Err(ParseError::syntax("')' without matching '('", p)),
+                        Some(Op::SeqEnd(p))    => return Err(ParseError::syntax("']' without matching '['", p)),
+                        Some(Op::Next)         => unreachable!("consecutive next operations are impossible"),
+                        None                   => return Err(ParseError::syntax("']' without matching '['", end)),
                     }
                 }
                 v.reverse();
@@ -177,8 +179,8 @@ pub fn parse(s: &str) -> Result<Option<Expr>, ParseError> {
                 ctrl.push(Op::Value(Expr::Seq(v)));
                 ctrl.push(Op::Next)
             }
-            Op::ListStart => return Err(ParseError::message("unclosed '('")),
-            Op::SeqStart  => return Err(ParseError::message("unclosed '['"))
+            Op::ListStart(p) => return Err(ParseError::syntax("unclosed '('", p)),
+            Op::SeqStart(p)  => return Err(ParseError::syntax("unclosed '['", p))
         }
     }
 
@@ -54,6 +54,18 @@ pub enum ResourceType {
     #[n(6)]
     #[strum(serialize = "relay")]
     Relay,
+    #[n(7)]
+    #[strum(serialize = "udp-inlet")]
+    UdpInlet,
+    #[n(8)]
+    #[strum(serialize = "udp-outlet")]
+    UdpOutlet,
+    #[n(9)]
+    #[strum(serialize = "tun-inlet")]
+    TunInlet,
+    #[n(10)]
+    #[strum(serialize = "tun-outlet")]
+    TunOutlet,
 }
 
 impl ResourceType {
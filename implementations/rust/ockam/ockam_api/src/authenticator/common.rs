@@ -1,6 +1,8 @@
 use crate::authenticator::direct::{OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE, OCKAM_ROLE_ATTRIBUTE_KEY};
 use crate::authenticator::AuthorityMembersRepository;
-use ockam::identity::{Identifier, IdentitiesAttributes};
+use ockam::identity::{
+    Identifier, IdentitiesAttributes, DELEGATED_ATTRIBUTES_ATTRIBUTE, DELEGATED_ISSUER_ATTRIBUTE,
+};
 use ockam_core::Result;
 use std::collections::BTreeMap;
 use std::sync::Arc;
@@ -39,6 +41,24 @@ impl EnrollerAccessControlChecks {
         false
     }
 
+    /// Return true if the attributes make their subject a delegated issuer, or set
+    /// the scope of a delegated issuer
+    pub(crate) fn check_str_attributes_is_delegation(
+        attributes: &BTreeMap<String, String>,
+    ) -> bool {
+        attributes.contains_key(DELEGATED_ISSUER_ATTRIBUTE)
+            || attributes.contains_key(DELEGATED_ATTRIBUTES_ATTRIBUTE)
+    }
+
+    /// Return true if the attributes make their subject a delegated issuer, or set
+    /// the scope of a delegated issuer
+    pub(crate) fn check_bin_attributes_is_delegation(
+        attributes: &BTreeMap<Vec<u8>, Vec<u8>>,
+    ) -> bool {
+        attributes.contains_key(DELEGATED_ISSUER_ATTRIBUTE.as_bytes())
+            || attributes.contains_key(DELEGATED_ATTRIBUTES_ATTRIBUTE.as_bytes())
+    }
+
     pub(crate) async fn check_is_member(
         members: Arc<dyn AuthorityMembersRepository>,
         identifier: &Identifier,
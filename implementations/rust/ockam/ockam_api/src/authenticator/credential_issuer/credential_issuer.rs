@@ -1,10 +1,19 @@
 use core::time::Duration;
 
-use crate::authenticator::direct::AccountAuthorityInfo;
+use crate::authenticator::direct::{
+    AccountAuthorityInfo, OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE, OCKAM_ROLE_ATTRIBUTE_KEY,
+};
+use crate::authenticator::x509::{CertificateSigningRequest, CertificateTemplate, X509Certificate};
 use crate::authenticator::AuthorityMembersRepository;
-use ockam::identity::models::{CredentialAndPurposeKey, CredentialSchemaIdentifier};
-use ockam::identity::utils::AttributesBuilder;
-use ockam::identity::{Attributes, Credentials, Identifier, IdentitiesAttributes};
+use ockam::identity::models::{
+    AttributeDefinition, AttributeType, CredentialAndPurposeKey, CredentialSchema,
+    CredentialSchemaIdentifier, RevocationListAndPurposeKey,
+};
+use ockam::identity::utils::{now, AttributesBuilder};
+use ockam::identity::{
+    Attributes, Credentials, Identifier, IdentitiesAttributes, TimestampInSeconds,
+    DELEGATED_ATTRIBUTES_ATTRIBUTE, DELEGATED_ISSUER_ATTRIBUTE,
+};
 use ockam_core::compat::sync::Arc;
 use ockam_core::Result;
 
@@ -14,9 +23,18 @@ pub const TRUST_CONTEXT_ID: &[u8] = b"trust_context_id";
 /// Identifier for the schema of a project credential
 pub const PROJECT_MEMBER_SCHEMA: CredentialSchemaIdentifier = CredentialSchemaIdentifier(1);
 
+/// Name of the attribute set on admin credentials to allow the creation of any relay
+const OCKAM_RELAY_ATTRIBUTE: &str = "ockam-relay";
+
 /// Maximum duration for a valid credential in seconds (30 days)
 pub const DEFAULT_CREDENTIAL_VALIDITY: Duration = Duration::from_secs(30 * 24 * 3600);
 
+/// Maximum duration for a valid X.509 certificate in seconds (1 hour)
+pub const DEFAULT_X509_CERTIFICATE_VALIDITY: Duration = Duration::from_secs(3600);
+
+/// Tolerated clock drift between the authority and the services verifying its X.509 certificates
+const X509_CERTIFICATE_CLOCK_DRIFT: TimestampInSeconds = TimestampInSeconds(60);
+
 /// This struct runs as a Worker to issue credentials based on a request/response protocol
 pub struct CredentialIssuer {
     members: Arc<dyn AuthorityMembersRepository>,
@@ -27,6 +45,7 @@ pub struct CredentialIssuer {
     credential_ttl: Duration,
 
     account_authority: Option<AccountAuthorityInfo>,
+    issuer_credential: Option<CredentialAndPurposeKey>,
 }
 
 impl CredentialIssuer {
@@ -42,6 +61,7 @@ impl CredentialIssuer {
         credential_ttl: Option<Duration>,
         account_authority: Option<AccountAuthorityInfo>,
         disable_trust_context_id: bool,
+        issuer_credential: Option<CredentialAndPurposeKey>,
     ) -> Self {
         let subject_attributes = AttributesBuilder::with_schema(PROJECT_MEMBER_SCHEMA);
         let subject_attributes = if !disable_trust_context_id {
@@ -63,6 +83,7 @@ impl CredentialIssuer {
             subject_attributes,
             credential_ttl: credential_ttl.unwrap_or(DEFAULT_CREDENTIAL_VALIDITY),
             account_authority,
+            issuer_credential,
         }
     }
 
@@ -83,19 +104,10 @@ impl CredentialIssuer {
                 {
                     let mut subject_attributes = self.subject_attributes.clone();
                     subject_attributes.map.insert(
-                        "ockam-relay".as_bytes().to_vec().into(),
+                        OCKAM_RELAY_ATTRIBUTE.as_bytes().to_vec().into(),
                         "*".as_bytes().to_vec().into(),
                     );
-                    let credential = self
-                        .credentials
-                        .credentials_creation()
-                        .issue_credential(
-                            &self.issuer,
-                            subject,
-                            subject_attributes,
-                            self.credential_ttl,
-                        )
-                        .await?;
+                    let credential = self.issue(subject, subject_attributes).await?;
                     info!("Successfully issued a credential for admin {}", subject);
 
                     return Ok(Some(credential));
@@ -117,19 +129,168 @@ impl CredentialIssuer {
                 .insert(key.clone().into(), value.clone().into());
         }
 
-        let credential = self
-            .credentials
-            .credentials_creation()
-            .issue_credential(
-                &self.issuer,
+        let credential = self.issue(subject, subject_attributes).await?;
+
+        info!("Successfully issued a credential for {}", subject);
+
+        Ok(Some(credential))
+    }
+
+    /// Issue a credential, delegated by the parent authority if this issuer has a credential
+    async fn issue(
+        &self,
+        subject: &Identifier,
+        subject_attributes: Attributes,
+    ) -> Result<CredentialAndPurposeKey> {
+        let credentials_creation = self.credentials.credentials_creation();
+        match self.issuer_credential.clone() {
+            Some(issuer_credential) => {
+                credentials_creation
+                    .issue_delegated_credential(
+                        &self.issuer,
+                        issuer_credential,
+                        subject,
+                        subject_attributes,
+                        self.credential_ttl,
+                    )
+                    .await
+            }
+            None => {
+                credentials_creation
+                    .issue_credential(
+                        &self.issuer,
+                        subject,
+                        subject_attributes,
+                        self.credential_ttl,
+                    )
+                    .await
+            }
+        }
+    }
+
+    /// Return the schema of the member credentials if the authority has published one
+    pub fn get_credential_schema(&self) -> Option<CredentialSchema> {
+        self.credentials
+            .credential_schemas()
+            .get(PROJECT_MEMBER_SCHEMA)
+    }
+
+    /// Issue a short-lived X.509 certificate for a signing key of a member, so that
+    /// the member can authenticate to TLS services trusting this authority
+    #[instrument(skip_all, fields(subject = %subject))]
+    pub async fn issue_x509_certificate(
+        &self,
+        subject: &Identifier,
+        request: &CertificateSigningRequest,
+    ) -> Result<Option<X509Certificate>> {
+        if self.members.get_member(subject).await?.is_none() {
+            return Ok(None);
+        }
+
+        let public_key = request
+            .verify(
+                self.credentials.purpose_keys().purpose_keys_verification(),
                 subject,
-                subject_attributes,
-                self.credential_ttl,
             )
             .await?;
 
-        info!("Successfully issued a credential for {}", subject);
+        let now = now()?;
+        let template = CertificateTemplate {
+            serial_number: rand::random::<[u8; 16]>().to_vec(),
+            issuer: self.issuer.clone(),
+            subject: subject.clone(),
+            public_key,
+            not_before: TimestampInSeconds(now.0.saturating_sub(X509_CERTIFICATE_CLOCK_DRIFT.0)),
+            not_after: now + TimestampInSeconds(DEFAULT_X509_CERTIFICATE_VALIDITY.as_secs()),
+            is_ca: false,
+        };
+        let certificate = self.sign_x509_certificate(template).await?;
+        info!("Successfully issued an X.509 certificate for {}", subject);
 
-        Ok(Some(credential))
+        Ok(Some(certificate))
+    }
+
+    /// Return the self-signed X.509 certificate of this issuer, which must be trusted
+    /// by the TLS services authenticating the members
+    #[instrument(skip_all)]
+    pub async fn get_x509_ca_certificate(&self) -> Result<X509Certificate> {
+        let purpose_key = self
+            .credentials
+            .purpose_keys()
+            .purpose_keys_creation()
+            .get_or_create_credential_purpose_key(&self.issuer)
+            .await?;
+        let template = CertificateTemplate {
+            // The serial number stays the same as long as the key is not rotated
+            serial_number: purpose_key.data().created_at.0.to_be_bytes().to_vec(),
+            issuer: self.issuer.clone(),
+            subject: self.issuer.clone(),
+            public_key: purpose_key.public_key().clone(),
+            not_before: purpose_key.data().created_at,
+            not_after: purpose_key.data().expires_at,
+            is_ca: true,
+        };
+        self.sign_x509_certificate(template).await
+    }
+
+    /// Sign a certificate with the credential purpose key of this issuer
+    async fn sign_x509_certificate(
+        &self,
+        template: CertificateTemplate,
+    ) -> Result<X509Certificate> {
+        let purpose_keys_creation = self.credentials.purpose_keys().purpose_keys_creation();
+        let issuer_key = purpose_keys_creation
+            .get_or_create_credential_purpose_key(&self.issuer)
+            .await?;
+        template
+            .sign(
+                purpose_keys_creation.vault().credential_vault.clone(),
+                issuer_key.key(),
+                issuer_key.public_key(),
+            )
+            .await
+    }
+
+    /// Return the revocation list of this issuer
+    #[instrument(skip_all)]
+    pub async fn get_revocation_list(&self) -> Result<RevocationListAndPurposeKey> {
+        self.credentials
+            .get_or_issue_revocation_list(&self.issuer)
+            .await
+    }
+}
+
+/// Return the schema of the project member credentials, given the definitions of the
+/// member attributes. The attributes set by the authority itself are always accepted.
+/// Only admins can set the enroller role and the delegation attributes of a member
+pub fn project_member_schema(attributes: Vec<AttributeDefinition>) -> CredentialSchema {
+    let mut schema = CredentialSchema {
+        identifier: PROJECT_MEMBER_SCHEMA,
+        attributes,
+    };
+    let reserved = [
+        (TRUST_CONTEXT_ID, vec![]),
+        (OCKAM_RELAY_ATTRIBUTE.as_bytes(), vec![]),
+        (
+            OCKAM_ROLE_ATTRIBUTE_KEY.as_bytes(),
+            vec![OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE.to_string()],
+        ),
+        (
+            DELEGATED_ISSUER_ATTRIBUTE.as_bytes(),
+            vec!["true".to_string()],
+        ),
+        (DELEGATED_ATTRIBUTES_ATTRIBUTE.as_bytes(), vec![]),
+    ];
+    for (name, allowed_values) in reserved {
+        let name = String::from_utf8_lossy(name).to_string();
+        if schema.get_attribute(&name).is_none() {
+            schema.attributes.push(AttributeDefinition {
+                name,
+                attribute_type: AttributeType::String,
+                allowed_values,
+                required: false,
+            });
+        }
     }
+    schema
 }
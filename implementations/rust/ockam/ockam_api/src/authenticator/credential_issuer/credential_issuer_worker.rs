@@ -4,7 +4,9 @@ use tracing::trace;
 
 use crate::authenticator::credential_issuer::CredentialIssuer;
 use crate::authenticator::direct::AccountAuthorityInfo;
+use crate::authenticator::x509::CertificateSigningRequest;
 use crate::authenticator::AuthorityMembersRepository;
+use ockam::identity::models::CredentialAndPurposeKey;
 use ockam::identity::{
     Credentials, Identifier, IdentitiesAttributes, IdentitySecureChannelLocalInfo,
 };
@@ -32,6 +34,7 @@ impl CredentialIssuerWorker {
         credential_ttl: Option<Duration>,
         account_authority: Option<AccountAuthorityInfo>,
         disable_trust_context_id: bool,
+        issuer_credential: Option<CredentialAndPurposeKey>,
     ) -> Self {
         Self {
             credential_issuer: CredentialIssuer::new(
@@ -43,6 +46,7 @@ impl CredentialIssuerWorker {
                 credential_ttl,
                 account_authority,
                 disable_trust_context_id,
+                issuer_credential,
             ),
         }
     }
@@ -86,6 +90,45 @@ impl Worker for CredentialIssuerWorker {
                     Err(error) => Response::internal_error(&req, &error.to_string()).to_vec()?,
                 }
             }
+            (Some(Method::Get), "/schema") => {
+                match self.credential_issuer.get_credential_schema() {
+                    Some(schema) => Response::ok().with_headers(&req).body(schema).to_vec()?,
+                    None => Response::not_found(&req, "no credential schema").to_vec()?,
+                }
+            }
+            (Some(Method::Get), "/revocation_list") => {
+                match self.credential_issuer.get_revocation_list().await {
+                    Ok(revocation_list) => Response::ok()
+                        .with_headers(&req)
+                        .body(revocation_list)
+                        .to_vec()?,
+                    Err(error) => Response::internal_error(&req, &error.to_string()).to_vec()?,
+                }
+            }
+            (Some(Method::Post), "/x509_certificate") => {
+                let request: CertificateSigningRequest = dec.decode()?;
+                match self
+                    .credential_issuer
+                    .issue_x509_certificate(&from, &request)
+                    .await
+                {
+                    Ok(Some(certificate)) => Response::ok()
+                        .with_headers(&req)
+                        .body(certificate)
+                        .to_vec()?,
+                    Ok(None) => Response::forbidden(&req, "unauthorized member").to_vec()?,
+                    Err(error) => Response::internal_error(&req, &error.to_string()).to_vec()?,
+                }
+            }
+            (Some(Method::Get), "/x509_ca_certificate") => {
+                match self.credential_issuer.get_x509_ca_certificate().await {
+                    Ok(certificate) => Response::ok()
+                        .with_headers(&req)
+                        .body(certificate)
+                        .to_vec()?,
+                    Err(error) => Response::internal_error(&req, &error.to_string()).to_vec()?,
+                }
+            }
             _ => Response::unknown_path(&req).to_vec()?,
         };
 
@@ -7,7 +7,7 @@ use ockam_core::api::Request;
 use ockam_core::async_trait;
 use ockam_node::Context;
 
-use crate::authenticator::direct::types::AddMember;
+use crate::authenticator::direct::types::{AddMember, ListMembers, MembersPage};
 use crate::cloud::{AuthorityNodeClient, HasSecureClient};
 use crate::nodes::service::default_address::DefaultAddress;
 
@@ -34,6 +34,13 @@ pub trait Members {
         &self,
         ctx: &Context,
     ) -> miette::Result<HashMap<Identifier, AttributesEntry>>;
+
+    /// Return a page of the members having some given attributes
+    async fn list_members_page(
+        &self,
+        ctx: &Context,
+        list_members: ListMembers,
+    ) -> miette::Result<MembersPage>;
 }
 
 #[async_trait]
@@ -99,4 +106,18 @@ impl Members for AuthorityNodeClient {
             .success()
             .into_diagnostic()
     }
+
+    async fn list_members_page(
+        &self,
+        ctx: &Context,
+        list_members: ListMembers,
+    ) -> miette::Result<MembersPage> {
+        let req = Request::get("/members_page").body(list_members);
+        self.get_secure_client()
+            .ask(ctx, DefaultAddress::DIRECT_AUTHENTICATOR, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
 }
@@ -1,13 +1,15 @@
 use either::Either;
 use std::collections::{BTreeMap, HashMap};
 
-use ockam::identity::utils::now;
+use ockam::identity::utils::{now, AttributesBuilder};
 use ockam::identity::Identifier;
-use ockam::identity::{AttributesEntry, IdentitiesAttributes};
+use ockam::identity::{AttributesEntry, Credentials, IdentitiesAttributes};
 use ockam_core::compat::sync::Arc;
 use ockam_core::Result;
 
 use crate::authenticator::common::EnrollerAccessControlChecks;
+use crate::authenticator::credential_issuer::PROJECT_MEMBER_SCHEMA;
+use crate::authenticator::direct::types::{ListMembers, MembersPage};
 use crate::authenticator::{AuthorityMember, AuthorityMembersRepository};
 
 /// Identity attribute key that indicates the role of the subject
@@ -24,6 +26,8 @@ pub type DirectAuthenticatorResult<T> = Either<T, DirectAuthenticatorError>;
 pub struct DirectAuthenticator {
     members: Arc<dyn AuthorityMembersRepository>,
     identities_attributes: Arc<IdentitiesAttributes>,
+    credentials: Arc<Credentials>,
+    issuer: Identifier,
     account_authority: Option<AccountAuthorityInfo>,
 }
 #[derive(Clone)]
@@ -61,11 +65,15 @@ impl DirectAuthenticator {
     pub fn new(
         members: Arc<dyn AuthorityMembersRepository>,
         identities_attributes: Arc<IdentitiesAttributes>,
+        credentials: Arc<Credentials>,
+        issuer: &Identifier,
         account_authority: Option<AccountAuthorityInfo>,
     ) -> Self {
         Self {
             members,
             identities_attributes,
+            credentials,
+            issuer: issuer.clone(),
             account_authority,
         }
     }
@@ -96,6 +104,20 @@ impl DirectAuthenticator {
             ))));
         }
 
+        // Check that the attributes are valid for the published schema, if any
+        let mut builder = AttributesBuilder::with_schema(PROJECT_MEMBER_SCHEMA);
+        for (key, value) in attributes {
+            builder = builder.with_attribute(key.as_str(), value.as_str());
+        }
+        if let Err(err) = self
+            .credentials
+            .credential_schemas()
+            .validate(&builder.build())
+        {
+            warn!("Invalid attributes for member {}: {}", identifier, err);
+            return Ok(Either::Right(DirectAuthenticatorError(err.to_string())));
+        }
+
         let attrs = attributes
             .iter()
             .map(|(k, v)| (k.as_bytes().to_vec(), v.as_bytes().to_vec()))
@@ -116,6 +138,19 @@ impl DirectAuthenticator {
             }
         }
 
+        // Only admins can make a member a delegated issuer, as an enroller
+        if EnrollerAccessControlChecks::check_bin_attributes_is_delegation(&attrs)
+            && !check.is_admin
+        {
+            warn!(
+                "Not admin {} is trying to make {} a delegated issuer",
+                enroller, identifier
+            );
+            return Ok(Either::Right(DirectAuthenticatorError(
+                "Not admin is trying to create a delegated issuer".to_string(),
+            )));
+        }
+
         let member =
             AuthorityMember::new(identifier.clone(), attrs, enroller.clone(), now()?, false);
 
@@ -211,6 +246,33 @@ impl DirectAuthenticator {
         Ok(Either::Left(res))
     }
 
+    /// Return the members having some given attributes, one page at a time
+    #[instrument(skip_all, fields(enroller = %enroller))]
+    pub async fn list_members_page(
+        &self,
+        enroller: &Identifier,
+        list_members: &ListMembers,
+    ) -> Result<DirectAuthenticatorResult<MembersPage>> {
+        let members = match self.list_members(enroller).await? {
+            Either::Left(members) => members,
+            Either::Right(error) => return Ok(Either::Right(error)),
+        };
+
+        let matching: BTreeMap<Identifier, AttributesEntry> = members
+            .into_iter()
+            .filter(|(_, entry)| list_members.matches(entry))
+            .collect();
+        let total = matching.len() as u64;
+        let limit = list_members.limit().unwrap_or(total) as usize;
+        let page = matching
+            .into_iter()
+            .skip(list_members.offset() as usize)
+            .take(limit)
+            .collect();
+
+        Ok(Either::Left(MembersPage::new(page, total)))
+    }
+
     #[instrument(skip_all, fields(enroller = %enroller, identifier = %identifier))]
     pub async fn delete_member(
         &self,
@@ -265,6 +327,12 @@ impl DirectAuthenticator {
 
         self.members.delete_member(identifier).await?;
 
+        // Revoke the credentials which were already issued to that member,
+        // so that they are rejected before their expiration
+        self.credentials
+            .revoke_subject(&self.issuer, identifier)
+            .await?;
+
         info!("Successfully deleted member {}", identifier);
 
         Ok(Either::Left(()))
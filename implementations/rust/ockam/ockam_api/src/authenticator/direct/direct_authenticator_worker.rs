@@ -2,13 +2,15 @@ use either::Either;
 use minicbor::Decoder;
 use tracing::trace;
 
-use ockam::identity::{Identifier, IdentitiesAttributes, IdentitySecureChannelLocalInfo};
+use ockam::identity::{
+    Credentials, Identifier, IdentitiesAttributes, IdentitySecureChannelLocalInfo,
+};
 use ockam_core::api::{Method, RequestHeader, Response};
 use ockam_core::compat::sync::Arc;
 use ockam_core::{Result, Routed, Worker};
 use ockam_node::Context;
 
-use crate::authenticator::direct::types::AddMember;
+use crate::authenticator::direct::types::{AddMember, ListMembers};
 use crate::authenticator::direct::DirectAuthenticator;
 use crate::authenticator::AuthorityMembersRepository;
 
@@ -22,12 +24,16 @@ impl DirectAuthenticatorWorker {
     pub fn new(
         members: Arc<dyn AuthorityMembersRepository>,
         identities_attributes: Arc<IdentitiesAttributes>,
+        credentials: Arc<Credentials>,
+        issuer: &Identifier,
         account_authority: Option<AccountAuthorityInfo>,
     ) -> Self {
         Self {
             authenticator: DirectAuthenticator::new(
                 members,
                 identities_attributes,
+                credentials,
+                issuer,
                 account_authority,
             ),
         }
@@ -87,6 +93,22 @@ impl Worker for DirectAuthenticatorWorker {
                     Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
                 }
             }
+            (Some(Method::Get), ["members_page"]) => {
+                let list_members: ListMembers = if req.has_body() {
+                    dec.decode()?
+                } else {
+                    ListMembers::new()
+                };
+                let res = self
+                    .authenticator
+                    .list_members_page(&from, &list_members)
+                    .await?;
+
+                match res {
+                    Either::Left(page) => Response::ok().with_headers(&req).body(page).to_vec()?,
+                    Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
+                }
+            }
             (Some(Method::Get), [""]) | (Some(Method::Get), ["members"]) => {
                 let res = self.authenticator.list_members(&from).await?;
 
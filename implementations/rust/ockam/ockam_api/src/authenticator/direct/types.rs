@@ -1,5 +1,6 @@
 use minicbor::{Decode, Encode};
-use ockam::identity::Identifier;
+use ockam::identity::{AttributesEntry, Identifier, TimestampInSeconds};
+use serde::Serialize;
 use std::collections::BTreeMap;
 use std::time::Duration;
 
@@ -79,3 +80,139 @@ impl CreateToken {
         self.ttl_secs
     }
 }
+
+/// Description of an enrollment token which has not been used or revoked yet.
+/// The one-time code of the token is never returned.
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TokenInfo {
+    #[n(1)] reference: String,
+    #[n(2)] issued_by: Identifier,
+    #[n(3)] created_at: TimestampInSeconds,
+    #[n(4)] expires_at: TimestampInSeconds,
+    #[n(5)] remaining_uses: u64,
+    #[b(6)] attributes: BTreeMap<String, String>,
+}
+
+impl TokenInfo {
+    pub fn new(
+        reference: String,
+        issued_by: Identifier,
+        created_at: TimestampInSeconds,
+        expires_at: TimestampInSeconds,
+        remaining_uses: u64,
+        attributes: BTreeMap<String, String>,
+    ) -> Self {
+        Self {
+            reference,
+            issued_by,
+            created_at,
+            expires_at,
+            remaining_uses,
+            attributes,
+        }
+    }
+
+    pub fn reference(&self) -> &str {
+        &self.reference
+    }
+
+    pub fn issued_by(&self) -> &Identifier {
+        &self.issued_by
+    }
+
+    pub fn created_at(&self) -> TimestampInSeconds {
+        self.created_at
+    }
+
+    pub fn expires_at(&self) -> TimestampInSeconds {
+        self.expires_at
+    }
+
+    pub fn remaining_uses(&self) -> u64 {
+        self.remaining_uses
+    }
+
+    pub fn attributes(&self) -> &BTreeMap<String, String> {
+        &self.attributes
+    }
+}
+
+/// Selection of a page of members, having all the given attributes
+#[derive(Debug, Default, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ListMembers {
+    #[b(1)] attributes: BTreeMap<String, String>,
+    #[n(2)] offset: u64,
+    #[n(3)] limit: Option<u64>,
+}
+
+impl ListMembers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_attributes(mut self, attributes: BTreeMap<String, String>) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    pub fn with_offset(mut self, offset: u64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_limit(mut self, limit: Option<u64>) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    pub fn attributes(&self) -> &BTreeMap<String, String> {
+        &self.attributes
+    }
+
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    pub fn limit(&self) -> Option<u64> {
+        self.limit
+    }
+
+    /// Return true if the entry contains all the selected attributes
+    pub fn matches(&self, entry: &AttributesEntry) -> bool {
+        self.attributes.iter().all(|(key, value)| {
+            entry.attrs().get(key.as_bytes()).map(|v| v.as_slice()) == Some(value.as_bytes())
+        })
+    }
+}
+
+/// A page of members, sorted by identifier.
+/// The total is the number of members matching the selected attributes, across all pages.
+#[derive(Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct MembersPage {
+    #[n(1)] members: BTreeMap<Identifier, AttributesEntry>,
+    #[n(2)] total: u64,
+}
+
+impl MembersPage {
+    pub fn new(members: BTreeMap<Identifier, AttributesEntry>, total: u64) -> Self {
+        Self { members, total }
+    }
+
+    pub fn members(&self) -> &BTreeMap<Identifier, AttributesEntry> {
+        &self.members
+    }
+
+    pub fn into_members(self) -> BTreeMap<Identifier, AttributesEntry> {
+        self.members
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+}
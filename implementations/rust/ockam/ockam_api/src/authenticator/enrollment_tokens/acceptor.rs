@@ -47,9 +47,12 @@ impl EnrollmentTokenAcceptor {
         let token = match self.tokens.use_token(otc, now()?).await {
             Ok(Some(token)) => token,
             Ok(None) => {
-                warn!("Unknown enrollment token received from {}", from);
+                warn!(
+                    "Unknown, expired or used up enrollment token received from {}",
+                    from
+                );
                 return Ok(Either::Right(EnrollmentTokenAcceptorError(
-                    "Unknown enrollment token".to_string(),
+                    "Unknown enrollment token. It may have expired or reached its maximum number of uses".to_string(),
                 )));
             }
             Err(err) => {
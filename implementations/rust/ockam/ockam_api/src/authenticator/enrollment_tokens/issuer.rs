@@ -10,6 +10,7 @@ use ockam_core::compat::time::Duration;
 use ockam_core::Result;
 
 use crate::authenticator::common::EnrollerAccessControlChecks;
+use crate::authenticator::direct::types::TokenInfo;
 use crate::authenticator::direct::AccountAuthorityInfo;
 use crate::authenticator::one_time_code::OneTimeCode;
 use crate::authenticator::{
@@ -84,6 +85,30 @@ impl EnrollmentTokenIssuer {
             }
         }
 
+        // Only admins can make a member a delegated issuer, as an enroller
+        if EnrollerAccessControlChecks::check_str_attributes_is_delegation(&attrs)
+            && !check.is_admin
+        {
+            warn!(
+                "Not admin {} is trying to issue an enrollment token for a delegated issuer",
+                enroller
+            );
+            return Ok(Either::Right(EnrollmentTokenIssuerError(
+                "Not admin is trying to issue an enrollment token for a delegated issuer"
+                    .to_string(),
+            )));
+        }
+
+        if ttl_count == Some(0) {
+            warn!(
+                "{} is trying to issue an enrollment token that can't be used",
+                enroller
+            );
+            return Ok(Either::Right(EnrollmentTokenIssuerError(
+                "The usage count of an enrollment token must be at least 1".to_string(),
+            )));
+        }
+
         let one_time_code = OneTimeCode::new();
         let reference: String = rand::thread_rng()
             .sample_iter(&Alphanumeric)
@@ -112,4 +137,108 @@ impl EnrollmentTokenIssuer {
 
         Ok(Either::Left(one_time_code))
     }
+
+    /// Return the tokens which have not been used nor revoked yet.
+    /// Admins can see all the tokens, other enrollers only see the tokens that they issued.
+    #[instrument(skip_all, fields(enroller = %enroller))]
+    pub async fn list_tokens(
+        &self,
+        enroller: &Identifier,
+    ) -> Result<EnrollmentTokenIssuerResult<Vec<TokenInfo>>> {
+        let check = EnrollerAccessControlChecks::check_identifier(
+            self.members.clone(),
+            self.identities_attributes.clone(),
+            enroller,
+            &self.account_authority,
+        )
+        .await?;
+
+        if !check.is_enroller {
+            warn!(
+                "Non-enroller {} is trying to list enrollment tokens",
+                enroller
+            );
+            return Ok(Either::Right(EnrollmentTokenIssuerError(
+                "Non-enroller is trying to list enrollment tokens".to_string(),
+            )));
+        }
+
+        let tokens = self
+            .tokens
+            .get_tokens(now()?)
+            .await?
+            .into_iter()
+            .filter(|t| check.is_admin || &t.issued_by == enroller)
+            .map(|t| {
+                TokenInfo::new(
+                    t.reference(),
+                    t.issued_by,
+                    t.created_at,
+                    t.expires_at,
+                    t.ttl_count,
+                    t.attrs,
+                )
+            })
+            .collect();
+
+        Ok(Either::Left(tokens))
+    }
+
+    /// Revoke a token, given its reference, so that it can't be used anymore.
+    /// Admins can revoke any token, other enrollers can only revoke the tokens that they issued.
+    #[instrument(skip_all, fields(enroller = %enroller, reference = %reference))]
+    pub async fn revoke_token(
+        &self,
+        enroller: &Identifier,
+        reference: &str,
+    ) -> Result<EnrollmentTokenIssuerResult<()>> {
+        let check = EnrollerAccessControlChecks::check_identifier(
+            self.members.clone(),
+            self.identities_attributes.clone(),
+            enroller,
+            &self.account_authority,
+        )
+        .await?;
+
+        if !check.is_enroller {
+            warn!(
+                "Non-enroller {} is trying to revoke an enrollment token",
+                enroller
+            );
+            return Ok(Either::Right(EnrollmentTokenIssuerError(
+                "Non-enroller is trying to revoke an enrollment token".to_string(),
+            )));
+        }
+
+        let token = self
+            .tokens
+            .get_tokens(now()?)
+            .await?
+            .into_iter()
+            .find(|t| t.reference.as_deref() == Some(reference));
+
+        match token {
+            Some(token) if check.is_admin || &token.issued_by == enroller => {
+                self.tokens.delete_token(reference).await?;
+                info!(
+                    "Successfully revoked the enrollment token. Reference: {}",
+                    reference
+                );
+                Ok(Either::Left(()))
+            }
+            Some(_) => {
+                warn!(
+                    "Not admin {} is trying to revoke an enrollment token issued by another enroller",
+                    enroller
+                );
+                Ok(Either::Right(EnrollmentTokenIssuerError(
+                    "Not admin is trying to revoke an enrollment token issued by another enroller"
+                        .to_string(),
+                )))
+            }
+            None => Ok(Either::Right(EnrollmentTokenIssuerError(format!(
+                "Enrollment token {reference} not found"
+            )))),
+        }
+    }
 }
@@ -6,7 +6,7 @@ use ockam_core::async_trait;
 use ockam_core::compat::time::Duration;
 use ockam_node::Context;
 
-use crate::authenticator::direct::types::CreateToken;
+use crate::authenticator::direct::types::{CreateToken, TokenInfo};
 use crate::authenticator::one_time_code::OneTimeCode;
 use crate::cloud::{AuthorityNodeClient, HasSecureClient};
 use crate::nodes::service::default_address::DefaultAddress;
@@ -20,6 +20,10 @@ pub trait TokenIssuer {
         duration: Option<Duration>,
         ttl_count: Option<u64>,
     ) -> miette::Result<OneTimeCode>;
+
+    async fn list_tokens(&self, ctx: &Context) -> miette::Result<Vec<TokenInfo>>;
+
+    async fn revoke_token(&self, ctx: &Context, reference: &str) -> miette::Result<()>;
 }
 
 #[async_trait]
@@ -44,4 +48,24 @@ impl TokenIssuer for AuthorityNodeClient {
             .success()
             .into_diagnostic()
     }
+
+    async fn list_tokens(&self, ctx: &Context) -> miette::Result<Vec<TokenInfo>> {
+        let req = Request::get("/tokens");
+        self.get_secure_client()
+            .ask(ctx, DefaultAddress::ENROLLMENT_TOKEN_ISSUER, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
+
+    async fn revoke_token(&self, ctx: &Context, reference: &str) -> miette::Result<()> {
+        let req = Request::delete(format!("/tokens/{reference}"));
+        self.get_secure_client()
+            .tell(ctx, DefaultAddress::ENROLLMENT_TOKEN_ISSUER, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
 }
@@ -66,8 +66,9 @@ impl Worker for EnrollmentTokenIssuerWorker {
             body   = %req.has_body(),
             "request"
         }
-        let res = match (req.method(), req.path()) {
-            (Some(Method::Post), "/") | (Some(Method::Post), "/tokens") => {
+        let path_segments = req.path_segments::<5>();
+        let res = match (req.method(), path_segments.as_slice()) {
+            (Some(Method::Post), [""]) | (Some(Method::Post), ["tokens"]) => {
                 let att: CreateToken = dec.decode()?;
                 let duration = att.ttl_secs().map(Duration::from_secs);
                 let ttl_count = att.ttl_count();
@@ -82,6 +83,22 @@ impl Worker for EnrollmentTokenIssuerWorker {
                     Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
                 }
             }
+            (Some(Method::Get), ["tokens"]) => {
+                let res = self.issuer.list_tokens(&from).await?;
+                match res {
+                    Either::Left(tokens) => {
+                        Response::ok().with_headers(&req).body(tokens).to_vec()?
+                    }
+                    Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
+                }
+            }
+            (Some(Method::Delete), ["tokens", reference]) => {
+                let res = self.issuer.revoke_token(&from, reference).await?;
+                match res {
+                    Either::Left(_) => Response::ok().with_headers(&req).to_vec()?,
+                    Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
+                }
+            }
             _ => Response::unknown_path(&req).to_vec()?,
         };
         c.send(return_route, res).await
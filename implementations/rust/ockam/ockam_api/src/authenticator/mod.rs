@@ -2,6 +2,7 @@ pub mod credential_issuer;
 pub mod direct;
 pub mod enrollment_tokens;
 pub mod one_time_code;
+pub mod x509;
 
 pub(crate) mod common;
 
@@ -17,4 +17,11 @@ pub trait AuthorityEnrollmentTokenRepository: Send + Sync + 'static {
 
     /// Store a newly issued enrolment token
     async fn store_new_token(&self, token: EnrollmentToken) -> Result<()>;
+
+    /// Return the tokens which are not expired yet
+    async fn get_tokens(&self, now: TimestampInSeconds) -> Result<Vec<EnrollmentToken>>;
+
+    /// Delete a token given its reference.
+    /// Return true if a token has been deleted
+    async fn delete_token(&self, reference: &str) -> Result<bool>;
 }
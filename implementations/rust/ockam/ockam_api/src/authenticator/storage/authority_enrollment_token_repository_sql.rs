@@ -107,6 +107,21 @@ impl AuthorityEnrollmentTokenRepository for AuthorityEnrollmentTokenSqlxDatabase
 
         query.execute(&*self.database.pool).await.void()
     }
+
+    async fn get_tokens(&self, now: TimestampInSeconds) -> Result<Vec<EnrollmentToken>> {
+        let query = query_as("SELECT one_time_code, reference, issued_by, created_at, expires_at, ttl_count, attributes FROM authority_enrollment_token WHERE expires_at > $1 ORDER BY created_at")
+            .bind(now.0 as i64);
+        let rows: Vec<EnrollmentTokenRow> =
+            query.fetch_all(&*self.database.pool).await.into_core()?;
+        rows.into_iter().map(|r| r.try_into()).collect()
+    }
+
+    async fn delete_token(&self, reference: &str) -> Result<bool> {
+        let query =
+            query("DELETE FROM authority_enrollment_token WHERE reference = $1").bind(reference);
+        let res = query.execute(&*self.database.pool).await.into_core()?;
+        Ok(res.rows_affected() > 0)
+    }
 }
 
 // Database serialization / deserialization
@@ -329,4 +344,53 @@ mod tests {
         })
         .await
     }
+
+    #[tokio::test]
+    async fn test_authority_enrollment_token_repository_list_and_delete_tokens() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn AuthorityEnrollmentTokenRepository> =
+                Arc::new(AuthorityEnrollmentTokenSqlxDatabase::new(db));
+
+            let issued_by = Identifier::from_str(
+                "I0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+            )
+            .unwrap();
+            let created_at = now()?;
+
+            let token = |reference: &str, expires_at: TimestampInSeconds| EnrollmentToken {
+                one_time_code: OneTimeCode::new(),
+                reference: Some(reference.to_string()),
+                issued_by: issued_by.clone(),
+                created_at,
+                expires_at,
+                ttl_count: 1,
+                attrs: BTreeMap::default(),
+            };
+
+            repository
+                .store_new_token(token("token1", created_at + 10))
+                .await?;
+            repository
+                .store_new_token(token("token2", created_at + 10))
+                .await?;
+            repository
+                .store_new_token(token("expired", created_at))
+                .await?;
+
+            // expired tokens are not listed
+            let tokens = repository.get_tokens(now()?).await?;
+            let references: Vec<String> = tokens.iter().map(|t| t.reference()).collect();
+            assert_eq!(references, vec!["token1", "token2"]);
+
+            // a deleted token can not be used anymore
+            assert!(repository.delete_token("token1").await?);
+            assert!(!repository.delete_token("token1").await?);
+            let tokens = repository.get_tokens(now()?).await?;
+            assert_eq!(tokens.len(), 1);
+            assert_eq!(tokens[0].reference(), "token2");
+
+            Ok(())
+        })
+        .await
+    }
 }
@@ -0,0 +1,155 @@
+//! Minimal DER encoding of the ASN.1 types used in X.509 certificates
+
+use chrono::{DateTime, Datelike};
+use ockam::identity::TimestampInSeconds;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+
+const BOOLEAN: u8 = 0x01;
+const INTEGER: u8 = 0x02;
+const BIT_STRING: u8 = 0x03;
+const OCTET_STRING: u8 = 0x04;
+const OBJECT_IDENTIFIER: u8 = 0x06;
+const UTF8_STRING: u8 = 0x0c;
+const UTC_TIME: u8 = 0x17;
+const GENERALIZED_TIME: u8 = 0x18;
+const SEQUENCE: u8 = 0x30;
+const SET: u8 = 0x31;
+
+/// Encode a value given its tag and its encoded content
+pub(crate) fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![tag];
+    let length = content.len();
+    if length < 0x80 {
+        encoded.push(length as u8);
+    } else {
+        let length_bytes = length.to_be_bytes();
+        let first = length_bytes.iter().position(|b| *b != 0).unwrap_or(0);
+        encoded.push(0x80 | (length_bytes.len() - first) as u8);
+        encoded.extend_from_slice(&length_bytes[first..]);
+    }
+    encoded.extend_from_slice(content);
+    encoded
+}
+
+pub(crate) fn sequence(items: &[Vec<u8>]) -> Vec<u8> {
+    tlv(SEQUENCE, &items.concat())
+}
+
+pub(crate) fn set(items: &[Vec<u8>]) -> Vec<u8> {
+    tlv(SET, &items.concat())
+}
+
+pub(crate) fn boolean(value: bool) -> Vec<u8> {
+    tlv(BOOLEAN, &[if value { 0xff } else { 0x00 }])
+}
+
+/// Encode an unsigned integer given as big-endian bytes
+pub(crate) fn unsigned_integer(bytes: &[u8]) -> Vec<u8> {
+    let first = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    let mut content = bytes[first..].to_vec();
+    // a leading zero is necessary when the most significant bit is set, or for the value 0
+    if content.first().map(|b| b & 0x80 != 0).unwrap_or(true) {
+        content.insert(0, 0);
+    }
+    tlv(INTEGER, &content)
+}
+
+pub(crate) fn integer(value: u64) -> Vec<u8> {
+    unsigned_integer(&value.to_be_bytes())
+}
+
+/// Encode a bit string where the last `unused_bits` bits of the last byte are not part of the value
+pub(crate) fn bit_string_with_unused_bits(bytes: &[u8], unused_bits: u8) -> Vec<u8> {
+    let mut content = vec![unused_bits];
+    content.extend_from_slice(bytes);
+    tlv(BIT_STRING, &content)
+}
+
+pub(crate) fn bit_string(bytes: &[u8]) -> Vec<u8> {
+    bit_string_with_unused_bits(bytes, 0)
+}
+
+pub(crate) fn octet_string(bytes: &[u8]) -> Vec<u8> {
+    tlv(OCTET_STRING, bytes)
+}
+
+pub(crate) fn utf8_string(value: &str) -> Vec<u8> {
+    tlv(UTF8_STRING, value.as_bytes())
+}
+
+/// Encode an object identifier given its arcs, for example &[2, 5, 4, 3]
+pub(crate) fn oid(arcs: &[u64]) -> Vec<u8> {
+    let mut content = vec![];
+    let first = arcs.first().copied().unwrap_or(0) * 40 + arcs.get(1).copied().unwrap_or(0);
+    for arc in [first].iter().chain(arcs.iter().skip(2)) {
+        let mut arc_bytes = vec![(arc & 0x7f) as u8];
+        let mut rest = arc >> 7;
+        while rest > 0 {
+            arc_bytes.insert(0, (rest & 0x7f) as u8 | 0x80);
+            rest >>= 7;
+        }
+        content.extend(arc_bytes);
+    }
+    tlv(OBJECT_IDENTIFIER, &content)
+}
+
+/// Encode a time as an UTCTime before 2050 and as a GeneralizedTime after, as required by RFC 5280
+pub(crate) fn time(timestamp: TimestampInSeconds) -> Result<Vec<u8>> {
+    let date_time = DateTime::from_timestamp(timestamp.0 as i64, 0).ok_or_else(|| {
+        Error::new(
+            Origin::Api,
+            Kind::Invalid,
+            format!("invalid certificate date {}", timestamp.0),
+        )
+    })?;
+    if date_time.year() < 2050 {
+        let formatted = date_time.format("%y%m%d%H%M%SZ").to_string();
+        Ok(tlv(UTC_TIME, formatted.as_bytes()))
+    } else {
+        let formatted = date_time.format("%Y%m%d%H%M%SZ").to_string();
+        Ok(tlv(GENERALIZED_TIME, formatted.as_bytes()))
+    }
+}
+
+/// Encode a value with an explicit context-specific tag, for example [0] EXPLICIT
+pub(crate) fn explicit(tag_number: u8, content: &[u8]) -> Vec<u8> {
+    tlv(0xa0 | tag_number, content)
+}
+
+/// Encode a primitive value with an implicit context-specific tag, for example [6] IMPLICIT
+pub(crate) fn implicit(tag_number: u8, content: &[u8]) -> Vec<u8> {
+    tlv(0x80 | tag_number, content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_values() {
+        assert_eq!(integer(0), vec![0x02, 0x01, 0x00]);
+        assert_eq!(integer(128), vec![0x02, 0x02, 0x00, 0x80]);
+        assert_eq!(integer(2), vec![0x02, 0x01, 0x02]);
+
+        // commonName
+        assert_eq!(oid(&[2, 5, 4, 3]), vec![0x06, 0x03, 0x55, 0x04, 0x03]);
+        // ecdsa-with-SHA256
+        assert_eq!(
+            oid(&[1, 2, 840, 10045, 4, 3, 2]),
+            vec![0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02]
+        );
+
+        let long_content = vec![0; 300];
+        assert_eq!(&octet_string(&long_content)[..4], &[0x04, 0x82, 0x01, 0x2c]);
+
+        assert_eq!(
+            time(TimestampInSeconds(0)).unwrap(),
+            tlv(UTC_TIME, b"700101000000Z")
+        );
+        assert_eq!(
+            time(TimestampInSeconds(2524608000)).unwrap(),
+            tlv(GENERALIZED_TIME, b"20500101000000Z")
+        );
+    }
+}
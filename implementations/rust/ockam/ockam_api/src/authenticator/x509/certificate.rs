@@ -0,0 +1,237 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use minicbor::{Decode, Encode};
+
+use ockam::identity::models::{PurposeKeyAttestation, PurposePublicKey};
+use ockam::identity::{Identifier, PurposeKeyVerification, TimestampInSeconds};
+use ockam_core::compat::sync::Arc;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use ockam_vault::{Signature, SigningSecretKeyHandle, VaultForSigning, VerifyingPublicKey};
+
+use crate::authenticator::x509::asn1::*;
+
+/// Prefix of the URI stored in the subject alternative name of a certificate
+/// to identify the Ockam identity of its subject
+pub const OCKAM_IDENTIFIER_URI_PREFIX: &str = "ockam:";
+
+/// Request sent to an authority to get an X.509 certificate for a purpose key.
+/// The purpose key attestation proves that the key belongs to the requesting identity
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CertificateSigningRequest {
+    #[n(1)] purpose_key_attestation: PurposeKeyAttestation,
+}
+
+impl CertificateSigningRequest {
+    /// Create a request for the key attested by a credential purpose key attestation
+    pub fn new(purpose_key_attestation: PurposeKeyAttestation) -> Self {
+        Self {
+            purpose_key_attestation,
+        }
+    }
+
+    /// Return the attestation of the key to certify
+    pub fn purpose_key_attestation(&self) -> &PurposeKeyAttestation {
+        &self.purpose_key_attestation
+    }
+
+    /// Verify that the requested key is a signing key belonging to the given identity
+    /// and return that key
+    pub async fn verify(
+        &self,
+        purpose_keys_verification: Arc<PurposeKeyVerification>,
+        subject: &Identifier,
+    ) -> Result<VerifyingPublicKey> {
+        let data = purpose_keys_verification
+            .verify_purpose_key_attestation(Some(subject), &self.purpose_key_attestation)
+            .await?;
+        match data.public_key {
+            PurposePublicKey::CredentialSigning(public_key) => Ok(public_key.into()),
+            PurposePublicKey::SecureChannelStatic(_) => Err(Error::new(
+                Origin::Api,
+                Kind::Invalid,
+                "only a signing key can be certified",
+            )),
+        }
+    }
+}
+
+/// DER-encoded X.509 certificate
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct X509Certificate {
+    #[cbor(n(1), with = "minicbor::bytes")] der: Vec<u8>,
+}
+
+impl X509Certificate {
+    /// Create a certificate from its DER encoding
+    pub fn new(der: Vec<u8>) -> Self {
+        Self { der }
+    }
+
+    /// Return the DER encoding of the certificate
+    pub fn der(&self) -> &[u8] {
+        &self.der
+    }
+
+    /// Return the PEM encoding of the certificate
+    pub fn to_pem(&self) -> String {
+        let encoded = STANDARD.encode(&self.der);
+        let mut pem = "-----BEGIN CERTIFICATE-----\n".to_string();
+        for line in encoded.as_bytes().chunks(64) {
+            pem.push_str(&String::from_utf8_lossy(line));
+            pem.push('\n');
+        }
+        pem.push_str("-----END CERTIFICATE-----\n");
+        pem
+    }
+}
+
+/// Content of a certificate issued by an authority
+pub struct CertificateTemplate {
+    /// Serial number of the certificate, as big-endian bytes
+    pub serial_number: Vec<u8>,
+    /// Identifier of the authority
+    pub issuer: Identifier,
+    /// Identifier of the subject, which is added to the subject alternative name
+    pub subject: Identifier,
+    /// Certified public key
+    pub public_key: VerifyingPublicKey,
+    /// Start of the validity of the certificate
+    pub not_before: TimestampInSeconds,
+    /// End of the validity of the certificate
+    pub not_after: TimestampInSeconds,
+    /// True for the certificate of the authority itself,
+    /// which is used by TLS services as a trust anchor
+    pub is_ca: bool,
+}
+
+impl CertificateTemplate {
+    /// Encode the certificate and sign it with the key of the issuer
+    pub async fn sign(
+        &self,
+        vault: Arc<dyn VaultForSigning>,
+        issuer_key: &SigningSecretKeyHandle,
+        issuer_public_key: &VerifyingPublicKey,
+    ) -> Result<X509Certificate> {
+        let signature_algorithm = signature_algorithm(issuer_public_key);
+        let tbs_certificate = self.tbs_certificate(signature_algorithm.clone())?;
+
+        // The vault hashes the data before signing it with an ECDSA key
+        let signature = match vault.sign(issuer_key, &tbs_certificate).await? {
+            Signature::EdDSACurve25519(signature) => signature.0.to_vec(),
+            Signature::ECDSASHA256CurveP256(signature) => sequence(&[
+                unsigned_integer(&signature.0[..32]),
+                unsigned_integer(&signature.0[32..]),
+            ]),
+        };
+
+        Ok(X509Certificate::new(sequence(&[
+            tbs_certificate,
+            signature_algorithm,
+            bit_string(&signature),
+        ])))
+    }
+
+    fn tbs_certificate(&self, signature_algorithm: Vec<u8>) -> Result<Vec<u8>> {
+        Ok(sequence(&[
+            // version 3
+            explicit(0, &integer(2)),
+            unsigned_integer(&self.serial_number),
+            signature_algorithm,
+            name(&self.issuer),
+            sequence(&[time(self.not_before)?, time(self.not_after)?]),
+            name(&self.subject),
+            subject_public_key_info(&self.public_key),
+            explicit(3, &self.extensions()),
+        ]))
+    }
+
+    fn extensions(&self) -> Vec<u8> {
+        let mut extensions = vec![];
+        if self.is_ca {
+            extensions.push(extension(
+                BASIC_CONSTRAINTS,
+                true,
+                sequence(&[boolean(true)]),
+            ));
+            // digitalSignature, keyCertSign, cRLSign
+            extensions.push(extension(
+                KEY_USAGE,
+                true,
+                bit_string_with_unused_bits(&[0x86], 1),
+            ));
+        } else {
+            extensions.push(extension(BASIC_CONSTRAINTS, true, sequence(&[])));
+            // digitalSignature
+            extensions.push(extension(
+                KEY_USAGE,
+                true,
+                bit_string_with_unused_bits(&[0x80], 7),
+            ));
+            extensions.push(extension(
+                EXTENDED_KEY_USAGE,
+                false,
+                sequence(&[oid(SERVER_AUTH), oid(CLIENT_AUTH)]),
+            ));
+        }
+        let uri = format!("{OCKAM_IDENTIFIER_URI_PREFIX}{}", self.subject);
+        extensions.push(extension(
+            SUBJECT_ALTERNATIVE_NAME,
+            false,
+            sequence(&[implicit(6, uri.as_bytes())]),
+        ));
+        sequence(&extensions)
+    }
+}
+
+const COMMON_NAME: &[u64] = &[2, 5, 4, 3];
+const ED25519: &[u64] = &[1, 3, 101, 112];
+const EC_PUBLIC_KEY: &[u64] = &[1, 2, 840, 10045, 2, 1];
+const PRIME256V1: &[u64] = &[1, 2, 840, 10045, 3, 1, 7];
+const ECDSA_WITH_SHA256: &[u64] = &[1, 2, 840, 10045, 4, 3, 2];
+const BASIC_CONSTRAINTS: &[u64] = &[2, 5, 29, 19];
+const KEY_USAGE: &[u64] = &[2, 5, 29, 15];
+const EXTENDED_KEY_USAGE: &[u64] = &[2, 5, 29, 37];
+const SUBJECT_ALTERNATIVE_NAME: &[u64] = &[2, 5, 29, 17];
+const SERVER_AUTH: &[u64] = &[1, 3, 6, 1, 5, 5, 7, 3, 1];
+const CLIENT_AUTH: &[u64] = &[1, 3, 6, 1, 5, 5, 7, 3, 2];
+
+fn name(identifier: &Identifier) -> Vec<u8> {
+    sequence(&[set(&[sequence(&[
+        oid(COMMON_NAME),
+        utf8_string(&identifier.to_string()),
+    ])])])
+}
+
+fn signature_algorithm(public_key: &VerifyingPublicKey) -> Vec<u8> {
+    match public_key {
+        VerifyingPublicKey::EdDSACurve25519(_) => sequence(&[oid(ED25519)]),
+        VerifyingPublicKey::ECDSASHA256CurveP256(_) => sequence(&[oid(ECDSA_WITH_SHA256)]),
+    }
+}
+
+fn subject_public_key_info(public_key: &VerifyingPublicKey) -> Vec<u8> {
+    match public_key {
+        VerifyingPublicKey::EdDSACurve25519(public_key) => {
+            sequence(&[sequence(&[oid(ED25519)]), bit_string(&public_key.0)])
+        }
+        VerifyingPublicKey::ECDSASHA256CurveP256(public_key) => sequence(&[
+            sequence(&[oid(EC_PUBLIC_KEY), oid(PRIME256V1)]),
+            bit_string(&public_key.0),
+        ]),
+    }
+}
+
+fn extension(id: &[u64], critical: bool, value: Vec<u8>) -> Vec<u8> {
+    let mut items = vec![oid(id)];
+    // the default value FALSE must not be encoded
+    if critical {
+        items.push(boolean(true));
+    }
+    items.push(octet_string(&value));
+    sequence(&items)
+}
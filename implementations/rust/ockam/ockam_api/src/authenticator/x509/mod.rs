@@ -0,0 +1,4 @@
+mod asn1;
+mod certificate;
+
+pub use certificate::*;
@@ -1,7 +1,7 @@
 use std::collections::BTreeMap;
 use tracing::info;
 
-use crate::authenticator::credential_issuer::CredentialIssuerWorker;
+use crate::authenticator::credential_issuer::{project_member_schema, CredentialIssuerWorker};
 use crate::authenticator::direct::{AccountAuthorityInfo, DirectAuthenticatorWorker};
 use crate::authenticator::enrollment_tokens::{
     EnrollmentTokenAcceptorWorker, EnrollmentTokenIssuerWorker,
@@ -72,6 +72,11 @@ impl Authority {
         Self::bootstrap_repository(members.clone(), configuration).await?;
 
         let identities = Identities::create_with_node(database, node_name).build();
+        if let Some(attribute_schema) = configuration.attribute_schema.clone() {
+            identities
+                .credential_schemas()
+                .register(project_member_schema(attribute_schema));
+        }
 
         let secure_channels =
             SecureChannels::from_identities(identities.clone(), secure_channel_repository);
@@ -159,6 +164,8 @@ impl Authority {
         let direct = DirectAuthenticatorWorker::new(
             self.members.clone(),
             self.secure_channels.identities().identities_attributes(),
+            self.secure_channels.identities().credentials(),
+            &self.identifier,
             self.account_authority.clone(),
         );
 
@@ -235,6 +242,7 @@ impl Authority {
             ttl,
             self.account_authority.clone(),
             configuration.disable_trust_context_id,
+            configuration.issuer_credential.clone(),
         );
 
         let address = DefaultAddress::CREDENTIAL_ISSUER.to_string();
@@ -1,4 +1,4 @@
-use ockam::identity::models::ChangeHistory;
+use ockam::identity::models::{AttributeDefinition, ChangeHistory, CredentialAndPurposeKey};
 use serde::{Deserialize, Serialize};
 
 use ockam::identity::Identifier;
@@ -55,6 +55,15 @@ pub struct Configuration {
     /// Will not include trust_context_id and project id into credential
     /// Set to true after old clients are updated
     pub disable_trust_context_id: bool,
+
+    /// Optional definitions of the attributes which can be attested in a member credential.
+    /// If set, the issued and presented credentials are validated against that schema
+    pub attribute_schema: Option<Vec<AttributeDefinition>>,
+
+    /// Credential issued to this authority by a parent authority, authorizing it to issue
+    /// credentials on its behalf. If set, the issued credentials are delegated credentials
+    /// which can be verified with the parent authority
+    pub issuer_credential: Option<CredentialAndPurposeKey>,
 }
 
 /// Local and private functions for the authority configuration
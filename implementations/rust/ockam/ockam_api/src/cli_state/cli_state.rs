@@ -1,11 +1,14 @@
 use rand::random;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use tokio::sync::broadcast::{channel, Receiver, Sender};
 
 use ockam::SqlxDatabase;
 use ockam_core::env::get_env_with_default;
 use ockam_node::database::DatabaseConfiguration;
 use ockam_node::Executor;
+use ockam_vault::storage::SecretsLock;
 
 use crate::cli_state::error::Result;
 use crate::cli_state::CliStateError;
@@ -47,6 +50,9 @@ pub struct CliState {
     /// Broadcast channel to be notified of major events during a process supported by the
     /// CliState API
     notifications: Sender<Notification>,
+    /// Locks protecting the secrets of the encrypted vaults, shared by all the vaults
+    /// created with this CliState
+    vault_locks: Arc<Mutex<HashMap<String, SecretsLock>>>,
 }
 
 impl CliState {
@@ -87,6 +93,10 @@ impl CliState {
         Self::make_application_database_configuration(&self.dir)
     }
 
+    pub(super) fn vault_locks(&self) -> Arc<Mutex<HashMap<String, SecretsLock>>> {
+        self.vault_locks.clone()
+    }
+
     pub fn subscribe_to_notifications(&self) -> Receiver<Notification> {
         self.notifications.subscribe()
     }
@@ -224,6 +234,7 @@ impl CliState {
             // is eventually used to trace user journeys.
             exporting_enabled: ExportingEnabled::Off,
             notifications,
+            vault_locks: Default::default(),
         };
         Ok(state)
     }
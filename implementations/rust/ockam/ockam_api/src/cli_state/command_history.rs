@@ -0,0 +1,43 @@
+use chrono::Utc;
+
+use super::Result;
+use crate::cli_state::CommandExecution;
+use crate::CliState;
+
+impl CliState {
+    /// Record the execution of a command, at the current time.
+    /// The error is the error message of the command if it failed
+    #[instrument(skip_all, fields(command_name = command_name))]
+    pub async fn add_command_execution(
+        &self,
+        command_name: &str,
+        arguments: &str,
+        user_name: &str,
+        error: Option<String>,
+    ) -> Result<()> {
+        let command_execution =
+            CommandExecution::new(command_name, arguments, user_name, Utc::now(), error);
+        Ok(self
+            .command_history_repository()
+            .store_command_execution(&command_execution)
+            .await?)
+    }
+
+    /// Return the most recent command executions, in chronological order
+    #[instrument(skip_all)]
+    pub async fn get_command_history(&self, limit: Option<u64>) -> Result<Vec<CommandExecution>> {
+        Ok(self
+            .command_history_repository()
+            .get_command_executions(limit)
+            .await?)
+    }
+
+    /// Delete all the recorded command executions
+    #[instrument(skip_all)]
+    pub async fn clear_command_history(&self) -> Result<()> {
+        Ok(self
+            .command_history_repository()
+            .delete_command_executions()
+            .await?)
+    }
+}
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use ockam::identity::models::CredentialAndPurposeKey;
+use ockam::identity::{
+    CredentialSqlxDatabase, CredentialsVerification, Identifier, PurposeKeyVerification,
+};
+use ockam_vault::SoftwareVaultForVerifyingSignatures;
+
+use crate::cli_state::CliState;
+
+use super::Result;
+
+/// The following functions give access to the credentials cached by nodes,
+/// with the scope they were retrieved for
+impl CliState {
+    /// Return all the credentials cached by a given node
+    #[instrument(skip_all, fields(node_name = node_name))]
+    pub async fn get_cached_credentials(
+        &self,
+        node_name: &str,
+    ) -> Result<Vec<(CredentialAndPurposeKey, String)>> {
+        Ok(CredentialSqlxDatabase::new(self.database(), node_name)
+            .get_all()
+            .await?)
+    }
+
+    /// Verify the signatures of a cached credential.
+    /// The purpose key signing the credential is verified against the change history of the issuer,
+    /// which must have been stored locally, for example when importing a project.
+    #[instrument(skip_all, fields(issuer = %issuer))]
+    pub async fn verify_cached_credential(
+        &self,
+        issuer: &Identifier,
+        credential: &CredentialAndPurposeKey,
+    ) -> Result<()> {
+        let verifying_vault = SoftwareVaultForVerifyingSignatures::create();
+        CredentialsVerification::verify_credential_static(
+            Arc::new(PurposeKeyVerification::new(
+                verifying_vault.clone(),
+                self.change_history_repository(),
+            )),
+            verifying_vault,
+            None,
+            &[issuer.clone()],
+            credential,
+        )
+        .await?;
+        Ok(())
+    }
+}
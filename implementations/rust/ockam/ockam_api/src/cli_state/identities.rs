@@ -1,11 +1,13 @@
+use core::time::Duration;
+
 use colorful::Colorful;
 use ockam::identity::models::ChangeHistory;
-use ockam::identity::{Identifier, Identity};
+use ockam::identity::{Identifier, Identity, Purpose, PurposeKeyInfo};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::Error;
 use ockam_vault::{HandleToSecret, SigningSecretKeyHandle};
 
-use crate::cli_state::{random_name, CliState, Result};
+use crate::cli_state::{random_name, CliState, NodeInfo, Result};
 use crate::colors::color_primary;
 use crate::{fmt_log, fmt_ok};
 
@@ -55,8 +57,9 @@ impl CliState {
     }
 
     /// Create an identity with specific key id.
-    /// This method is used when the vault is a KMS vault and we just need to store a key id
-    /// for the identity key existing in the KMS
+    /// This method is used when the vault is a KMS or PKCS#11 vault and we just need to store
+    /// a key id for the identity key existing in the KMS or in the token.
+    /// For a PKCS#11 vault, the key id is the `CKA_ID` attribute of the key
     #[instrument(skip_all, fields(name = %name, vault_name = %vault_name, key_id = %key_id))]
     pub async fn create_identity_with_key_id(
         &self,
@@ -66,12 +69,12 @@ impl CliState {
     ) -> Result<NamedIdentity> {
         let vault = self.get_named_vault(vault_name).await?;
 
-        // Check that the vault is an AWS KMS vault
-        if !vault.use_aws_kms() {
+        // Check that the vault is an AWS KMS or a PKCS#11 vault
+        if !vault.use_aws_kms() && vault.pkcs11().is_none() {
             return Err(Error::new(
                 Origin::Api,
                 Kind::Misuse,
-                format!("Vault {vault_name} is not a KMS vault"),
+                format!("Vault {vault_name} is not a KMS or PKCS#11 vault"),
             ))?;
         };
 
@@ -319,6 +322,60 @@ impl CliState {
         Ok(self.identities_repository().set_as_default(name).await?)
     }
 
+    /// Rotate the primary key of an identity:
+    ///
+    ///  - a new key is created in the vault of the identity and the previous key is deleted
+    ///  - the change is appended to the identity change history, the identifier stays the same
+    ///
+    /// Since the nodes refer to their identity by identifier they don't need to be updated
+    /// but the running nodes must be restarted in order to use the new key.
+    /// Return the nodes using this identity.
+    #[instrument(skip_all, fields(name = %name))]
+    pub async fn rotate_identity_key(&self, name: &str) -> Result<Vec<NodeInfo>> {
+        let named_identity = self.get_named_identity(name).await?;
+        let vault = self.get_named_vault(&named_identity.vault_name()).await?;
+        let identities = self.make_identities(self.make_vault(vault).await?).await?;
+        identities
+            .identities_creation()
+            .rotate_identity(&named_identity.identifier())
+            .await?;
+        self.get_nodes_by_identity_name(name).await
+    }
+
+    /// Return the active and retired purpose keys of an identity
+    #[instrument(skip_all, fields(name = %name))]
+    pub async fn list_purpose_keys(&self, name: &str) -> Result<Vec<PurposeKeyInfo>> {
+        let named_identity = self.get_named_identity(name).await?;
+        let vault = self.get_named_vault(&named_identity.vault_name()).await?;
+        let identities = self.make_identities(self.make_vault(vault).await?).await?;
+        Ok(identities
+            .purpose_keys()
+            .purpose_keys_creation()
+            .list_purpose_keys(&named_identity.identifier())
+            .await?)
+    }
+
+    /// Rotate a purpose key of an identity. The previous key stays usable until the end
+    /// of the grace period, and the running nodes must be restarted in order to use the new key.
+    /// Return the new key and the nodes using this identity.
+    #[instrument(skip_all, fields(name = %name))]
+    pub async fn rotate_purpose_key(
+        &self,
+        name: &str,
+        purpose: Purpose,
+        grace_period: Duration,
+    ) -> Result<(PurposeKeyInfo, Vec<NodeInfo>)> {
+        let named_identity = self.get_named_identity(name).await?;
+        let vault = self.get_named_vault(&named_identity.vault_name()).await?;
+        let identities = self.make_identities(self.make_vault(vault).await?).await?;
+        let purpose_key = identities
+            .purpose_keys()
+            .purpose_keys_creation()
+            .rotate_purpose_key(&named_identity.identifier(), purpose, grace_period)
+            .await?;
+        Ok((purpose_key, self.get_nodes_by_identity_name(name).await?))
+    }
+
     /// Delete an identity by name:
     ///
     ///  - check that the identity is not used by a node first
@@ -349,6 +406,34 @@ impl CliState {
     }
 }
 
+/// The following methods allow to move identities between machines.
+/// Only the change history of an identity is exported or imported, never its private keys.
+impl CliState {
+    /// Return the change history of a named identity, as a hex-encoded string
+    #[instrument(skip_all, fields(name = %name))]
+    pub async fn export_identity_change_history(&self, name: &str) -> Result<String> {
+        let named_identity = self.get_named_identity(name).await?;
+        Ok(self
+            .get_change_history(&named_identity.identifier())
+            .await?
+            .export_as_string()?)
+    }
+
+    /// Verify and store the hex-encoded change history of an identity created elsewhere.
+    ///
+    /// The identity is not associated to a name or a vault since its keys are not present
+    /// on this machine. It can be used as a subject, for example to add it to a project,
+    /// but it can't be used to create secure channels.
+    #[instrument(skip_all)]
+    pub async fn import_identity_change_history(&self, change_history: &str) -> Result<Identity> {
+        let identity = Identity::create(change_history.trim()).await?;
+        self.change_history_repository()
+            .update_identity(&identity, false)
+            .await?;
+        Ok(identity)
+    }
+}
+
 /// Support methods
 impl CliState {
     /// Once a identity has been created, store it.
@@ -524,6 +609,62 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_rotate_identity_key() -> Result<()> {
+        let cli = CliState::test().await?;
+        let identity = cli.create_identity_with_name("name").await?;
+        let _ = cli
+            .create_node_with_identifier("node", &identity.identifier())
+            .await?;
+        let before = cli.get_identity(&identity.identifier()).await?;
+
+        // the rotation adds a change with a new key and keeps the same identifier
+        let nodes = cli.rotate_identity_key(&identity.name()).await?;
+        let after = cli.get_identity(&identity.identifier()).await?;
+        assert_eq!(after.identifier(), before.identifier());
+        assert_eq!(after.changes().len(), before.changes().len() + 1);
+        assert_ne!(
+            after.get_latest_public_key()?,
+            before.get_latest_public_key()?
+        );
+
+        // the nodes using the identity are returned
+        let node_names: Vec<String> = nodes.iter().map(|n| n.name()).collect();
+        assert_eq!(node_names, vec!["node".to_string()]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rotate_purpose_key() -> Result<()> {
+        let cli = CliState::test().await?;
+        let identity = cli.create_identity_with_name("name").await?;
+
+        let (purpose_key, _) = cli
+            .rotate_purpose_key(
+                &identity.name(),
+                Purpose::SecureChannel,
+                Duration::from_secs(3600),
+            )
+            .await?;
+        let (rotated, _) = cli
+            .rotate_purpose_key(
+                &identity.name(),
+                Purpose::SecureChannel,
+                Duration::from_secs(3600),
+            )
+            .await?;
+
+        // the previous key is still listed during its grace period
+        let purpose_keys = cli.list_purpose_keys(&identity.name()).await?;
+        assert_eq!(purpose_keys.len(), 2);
+        assert!(purpose_keys.contains(&rotated));
+        let retired = purpose_keys.iter().find(|k| k.is_retired()).unwrap();
+        assert_eq!(retired.public_key, purpose_key.public_key);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_delete_identity() -> Result<()> {
         let cli = CliState::test().await?;
@@ -546,4 +687,58 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_export_and_import_identity_change_history() -> Result<()> {
+        let exporter = CliState::test().await?;
+        let identity = exporter.create_identity_with_name("name").await?;
+        let exported = exporter.export_identity_change_history("name").await?;
+
+        // the identity can be imported on another machine
+        let importer = CliState::system().await?;
+        let imported = importer.import_identity_change_history(&exported).await?;
+        assert_eq!(imported.identifier(), &identity.identifier());
+
+        let result = importer.get_identity(&identity.identifier()).await;
+        assert!(result.is_ok());
+
+        // an invalid change history is rejected
+        let result = importer.import_identity_change_history("not hex").await;
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_import_rotated_identity_change_history() -> Result<()> {
+        let exporter = CliState::test().await?;
+        let identity = exporter.create_identity_with_name("name").await?;
+        exporter.rotate_identity_key("name").await?;
+        exporter.rotate_identity_key("name").await?;
+        let exported = exporter.export_identity_change_history("name").await?;
+
+        // the complete change history is imported, with all its rotations
+        let importer = CliState::test().await?;
+        let imported = importer.import_identity_change_history(&exported).await?;
+        assert_eq!(imported.identifier(), &identity.identifier());
+        assert_eq!(imported.changes().len(), 3);
+
+        // a change history with a missing change is rejected
+        let mut change_history = ChangeHistory::import_from_string(&exported)?;
+        change_history.0.remove(1);
+        let result = importer
+            .import_identity_change_history(&change_history.export_as_string()?)
+            .await;
+        assert!(result.is_err());
+
+        // a change history which is older than the known one is rejected
+        let mut change_history = ChangeHistory::import_from_string(&exported)?;
+        change_history.0.pop();
+        let result = importer
+            .import_identity_change_history(&change_history.export_as_string()?)
+            .await;
+        assert!(result.is_err());
+
+        Ok(())
+    }
 }
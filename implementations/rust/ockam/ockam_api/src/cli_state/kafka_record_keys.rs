@@ -0,0 +1,117 @@
+use ockam_core::compat::rand::{thread_rng, RngCore};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_vault::storage::SecretsRepository;
+use ockam_vault::{
+    AeadAlgorithm, AeadSecret, AeadSecretKeyHandle, HandleToSecret, AEAD_SECRET_LENGTH,
+};
+
+use super::Result;
+use crate::CliState;
+
+/// Length of the random handle of the secret in the vault of the node
+const RECORD_KEYS_SECRET_HANDLE_LENGTH: usize = 16;
+
+impl CliState {
+    /// Return the secret used by the Kafka outlet of a node to derive the deterministic
+    /// encryption keys of the record keys. The secret is created the first time it is used.
+    ///
+    /// The secret is stored in the vault of the node, and encrypted at rest if that vault is
+    /// encrypted. Only the handle of the secret is stored with the node
+    #[instrument(skip_all)]
+    pub async fn get_or_create_kafka_record_keys_secret(&self, node_name: &str) -> Result<Vec<u8>> {
+        let repository = self.kafka_record_keys_repository();
+        let named_vault = self.get_node_vault(node_name).await?;
+        let secrets = self.make_secrets_repository(&named_vault).await?;
+        if let Some(handle) = repository.get_record_keys_secret_handle(node_name).await? {
+            return get_record_keys_secret(secrets.as_ref(), node_name, &handle).await;
+        }
+
+        let mut secret = [0; AEAD_SECRET_LENGTH];
+        thread_rng().fill_bytes(&mut secret);
+        let mut handle = vec![0; RECORD_KEYS_SECRET_HANDLE_LENGTH];
+        thread_rng().fill_bytes(&mut handle);
+        let handle = AeadSecretKeyHandle::new(HandleToSecret::new(handle));
+        secrets
+            .store_aead_secret(&handle, AeadAlgorithm::default(), AeadSecret(secret))
+            .await?;
+        repository
+            .store_record_keys_secret_handle(node_name, &handle)
+            .await?;
+
+        // another secret might have been stored concurrently
+        let stored_handle = repository
+            .get_record_keys_secret_handle(node_name)
+            .await?
+            .unwrap_or(handle.clone());
+        if stored_handle != handle {
+            secrets.delete_aead_secret(&handle).await?;
+        }
+        get_record_keys_secret(secrets.as_ref(), node_name, &stored_handle).await
+    }
+
+    /// Delete the secret of the Kafka outlet of a node, from the vault of the node, if any
+    #[instrument(skip_all)]
+    pub async fn delete_kafka_record_keys_secret(&self, node_name: &str) -> Result<()> {
+        let repository = self.kafka_record_keys_repository();
+        if let Some(handle) = repository.get_record_keys_secret_handle(node_name).await? {
+            let named_vault = self.get_node_vault(node_name).await?;
+            let secrets = self.make_secrets_repository(&named_vault).await?;
+            secrets.delete_aead_secret(&handle).await?;
+            repository
+                .delete_record_keys_secret_handle(node_name)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Return the secret referenced by a handle from the vault of a node
+async fn get_record_keys_secret(
+    secrets: &dyn SecretsRepository,
+    node_name: &str,
+    handle: &AeadSecretKeyHandle,
+) -> Result<Vec<u8>> {
+    match secrets.get_aead_secret(handle).await? {
+        Some((_, secret)) => Ok(secret.0.to_vec()),
+        None => Err(ockam_core::Error::new(
+            Origin::Api,
+            Kind::NotFound,
+            format!(
+                "The Kafka record keys secret of the node {node_name} is missing from its vault"
+            ),
+        ))?,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_kafka_record_keys_secret_is_stored_in_the_node_vault() -> Result<()> {
+        let cli = CliState::test().await?;
+        let _ = cli.create_node("node").await?;
+
+        // the secret is created once and then reused
+        let secret = cli.get_or_create_kafka_record_keys_secret("node").await?;
+        assert_eq!(secret.len(), AEAD_SECRET_LENGTH);
+        let actual = cli.get_or_create_kafka_record_keys_secret("node").await?;
+        assert_eq!(actual, secret);
+
+        // only the handle of the secret is stored with the node
+        let handle = cli
+            .kafka_record_keys_repository()
+            .get_record_keys_secret_handle("node")
+            .await?
+            .unwrap();
+        let named_vault = cli.get_node_vault("node").await?;
+        let secrets = cli.make_secrets_repository(&named_vault).await?;
+        let (_, stored) = secrets.get_aead_secret(&handle).await?.unwrap();
+        assert_eq!(stored.0.to_vec(), secret);
+
+        // the secret is removed from the vault with the node
+        cli.remove_node("node").await?;
+        assert!(secrets.get_aead_secret(&handle).await?.is_none());
+        Ok(())
+    }
+}
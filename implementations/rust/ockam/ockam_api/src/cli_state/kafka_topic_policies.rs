@@ -0,0 +1,47 @@
+use ockam_core::Address;
+
+use super::Result;
+use crate::kafka::TopicPolicies;
+use crate::CliState;
+
+impl CliState {
+    /// Store the topic policies of a Kafka inlet
+    #[instrument(skip_all)]
+    pub async fn store_kafka_topic_policies(
+        &self,
+        node_name: &str,
+        inlet_address: &Address,
+        topic_policies: &TopicPolicies,
+    ) -> Result<()> {
+        Ok(self
+            .kafka_topic_policies_repository()
+            .store_topic_policies(node_name, inlet_address, topic_policies)
+            .await?)
+    }
+
+    /// Get the topic policies of a Kafka inlet by node name and inlet address
+    #[instrument(skip_all)]
+    pub async fn get_kafka_topic_policies(
+        &self,
+        node_name: &str,
+        inlet_address: &Address,
+    ) -> Result<TopicPolicies> {
+        Ok(self
+            .kafka_topic_policies_repository()
+            .get_topic_policies(node_name, inlet_address)
+            .await?)
+    }
+
+    /// Delete the topic policies of a Kafka inlet
+    #[instrument(skip_all)]
+    pub async fn delete_kafka_topic_policies(
+        &self,
+        node_name: &str,
+        inlet_address: &Address,
+    ) -> Result<()> {
+        Ok(self
+            .kafka_topic_policies_repository()
+            .delete_topic_policies(node_name, inlet_address)
+            .await?)
+    }
+}
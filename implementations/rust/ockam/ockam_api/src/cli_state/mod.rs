@@ -8,14 +8,19 @@ pub use vaults::*;
 
 #[allow(clippy::module_inception)]
 pub mod cli_state;
+mod command_history;
+mod credentials;
 pub mod enrollments;
 pub mod error;
 pub mod identities;
 mod identities_attributes;
 pub mod journeys;
+mod kafka_record_keys;
+mod kafka_topic_policies;
 pub mod nodes;
 pub mod policies;
 pub mod projects;
+mod recipes;
 pub mod repositories;
 mod resources;
 pub mod secure_channels;
@@ -2,6 +2,7 @@ use minicbor::{Decode, Encode};
 use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
 use std::process;
+use std::time::Duration;
 
 use nix::errno::Errno;
 
@@ -25,6 +26,9 @@ use crate::config::lookup::InternetAddress;
 
 use crate::ConnectionStatus;
 
+/// Name of the file storing the arguments used to start a node in the background
+const NODE_CREATE_ARGUMENTS_FILE: &str = "create_arguments.json";
+
 /// The methods below support the creation and update of local nodes
 impl CliState {
     /// Create a node, with some optional associated values, and start it
@@ -161,6 +165,8 @@ impl CliState {
 
         // set another node as the default node
         if node_exists {
+            // the vault of the node might not be available, for example if it is locked
+            let _ = self.delete_kafka_record_keys_secret(node_name).await;
             repository.delete_node(node_name).await?;
             let other_nodes = repository.get_nodes().await?;
             if let Some(other_node) = other_nodes.first() {
@@ -181,6 +187,9 @@ impl CliState {
     pub async fn stop_node(&self, node_name: &str, force: bool) -> Result<()> {
         let node = self.get_node(node_name).await?;
         self.nodes_repository().set_no_node_pid(node_name).await?;
+        self.nodes_repository()
+            .delete_node_heartbeat(node_name)
+            .await?;
         if let Some(pid) = node.pid() {
             // avoid killing the current process, return successfully instead.
             // this is useful when we need to stop all the nodes, for example
@@ -292,6 +301,28 @@ impl CliState {
     pub async fn set_node_pid(&self, node_name: &str, pid: u32) -> Result<()> {
         Ok(self.nodes_repository().set_node_pid(node_name, pid).await?)
     }
+
+    /// Record the last heartbeat of a running node
+    #[instrument(skip_all, fields(node_name = node_name))]
+    pub async fn set_node_heartbeat(
+        &self,
+        node_name: &str,
+        heartbeat: &NodeHeartbeat,
+    ) -> Result<()> {
+        Ok(self
+            .nodes_repository()
+            .set_node_heartbeat(node_name, heartbeat)
+            .await?)
+    }
+
+    /// Delete the heartbeat of a node which is stopping
+    #[instrument(skip_all, fields(node_name = node_name))]
+    pub async fn delete_node_heartbeat(&self, node_name: &str) -> Result<()> {
+        Ok(self
+            .nodes_repository()
+            .delete_node_heartbeat(node_name)
+            .await?)
+    }
 }
 
 /// The following methods return nodes data
@@ -316,6 +347,27 @@ impl CliState {
         Ok(self.nodes_repository().get_nodes().await?)
     }
 
+    /// Return the last heartbeat of a node, if it has ever been started
+    #[instrument(skip_all, fields(node_name = node_name))]
+    pub async fn get_node_heartbeat(&self, node_name: &str) -> Result<Option<NodeHeartbeat>> {
+        Ok(self
+            .nodes_repository()
+            .get_node_heartbeat(node_name)
+            .await?)
+    }
+
+    /// Return the liveness of a node, using both its process id and its last heartbeat
+    #[instrument(skip_all, fields(node_name = node_name))]
+    pub async fn get_node_liveness(&self, node_name: &str) -> Result<NodeLiveness> {
+        let node = self.get_node(node_name).await?;
+        let heartbeat = self.get_node_heartbeat(node_name).await?;
+        Ok(NodeLiveness::new(
+            &node.status(),
+            heartbeat.as_ref(),
+            *now()?,
+        ))
+    }
+
     /// Return information about the default node (if there is one)
     #[instrument(skip_all)]
     pub async fn get_default_node(&self) -> Result<NodeInfo> {
@@ -377,6 +429,29 @@ impl CliState {
             ))?;
         Ok(current_log_file.path())
     }
+
+    /// Store the command line arguments used to start a node in the background,
+    /// so that the node can be restarted later with exactly the same options
+    #[instrument(skip_all, fields(node_name = node_name))]
+    pub fn set_node_create_arguments(&self, node_name: &str, arguments: &[String]) -> Result<()> {
+        let path = self
+            .create_node_dir(node_name)?
+            .join(NODE_CREATE_ARGUMENTS_FILE);
+        std::fs::write(path, serde_json::to_string(arguments)?)?;
+        Ok(())
+    }
+
+    /// Return the command line arguments used to start a node in the background, if they were
+    /// stored. Nodes created before those arguments were stored don't have them.
+    #[instrument(skip_all, fields(node_name = node_name))]
+    pub fn get_node_create_arguments(&self, node_name: &str) -> Result<Option<Vec<String>>> {
+        let path = self.node_dir(node_name).join(NODE_CREATE_ARGUMENTS_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let arguments = std::fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&arguments)?))
+    }
 }
 
 /// Private functions
@@ -489,6 +564,77 @@ impl Display for NodeProcessStatus {
     }
 }
 
+/// Interval between two heartbeats of a running node
+pub const NODE_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Number of missed heartbeats after which a node is considered as stale
+const MISSED_HEARTBEATS_BEFORE_STALE: u64 = 3;
+
+/// Heartbeat periodically stored by a running node, with some health signals
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct NodeHeartbeat {
+    /// UNIX timestamp in seconds of the heartbeat
+    pub last_seen_at: u64,
+    /// Number of workers running on the node
+    pub workers_count: u32,
+    /// Number of secure channels created by the node
+    pub secure_channels: u32,
+    /// Number of TCP inlets created on the node
+    pub tcp_inlets: u32,
+    /// Number of TCP outlets created on the node
+    pub tcp_outlets: u32,
+}
+
+impl NodeHeartbeat {
+    /// Return true if the heartbeat is too old for the node to be considered alive
+    pub fn is_stale(&self, now: u64) -> bool {
+        now.saturating_sub(self.last_seen_at)
+            > NODE_HEARTBEAT_INTERVAL.as_secs() * MISSED_HEARTBEATS_BEFORE_STALE
+    }
+}
+
+/// Liveness of a node, computed from its process status and its last heartbeat.
+///
+/// The process id stored for a node can be wrong, for example when the node process
+/// was killed and its pid reused by another process. In that case the heartbeat
+/// stops being updated and the node is reported as stale
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeLiveness {
+    /// The node process is running and its heartbeat is recent
+    Up,
+    /// A process is running with the node pid but the node stopped sending heartbeats
+    Stale,
+    /// The node is not running
+    Down,
+}
+
+impl NodeLiveness {
+    pub fn new(status: &NodeProcessStatus, heartbeat: Option<&NodeHeartbeat>, now: u64) -> Self {
+        match (status, heartbeat) {
+            // nodes started before heartbeats were introduced only have a process status
+            (NodeProcessStatus::Running(_), None) => NodeLiveness::Up,
+            (NodeProcessStatus::Running(_), Some(heartbeat)) if heartbeat.is_stale(now) => {
+                NodeLiveness::Stale
+            }
+            (NodeProcessStatus::Running(_), Some(_)) => NodeLiveness::Up,
+            // the node is alive even if its pid was not recorded
+            (_, Some(heartbeat)) if !heartbeat.is_stale(now) => NodeLiveness::Up,
+            _ => NodeLiveness::Down,
+        }
+    }
+}
+
+impl Display for NodeLiveness {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NodeLiveness::Up => write!(f, "UP"),
+            NodeLiveness::Stale => write!(f, "STALE"),
+            NodeLiveness::Down => write!(f, "DOWN"),
+        }
+    }
+}
+
 /// This struct contains all the data associated to a node
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct NodeInfo {
@@ -793,4 +939,48 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_node_liveness() {
+        let heartbeat = |last_seen_at| NodeHeartbeat {
+            last_seen_at,
+            workers_count: 10,
+            secure_channels: 0,
+            tcp_inlets: 0,
+            tcp_outlets: 0,
+        };
+        let now = 1000;
+        let recent = heartbeat(now - NODE_HEARTBEAT_INTERVAL.as_secs());
+        let old = heartbeat(now - 10 * NODE_HEARTBEAT_INTERVAL.as_secs());
+        let running = NodeProcessStatus::Running(1234);
+        let stopped = NodeProcessStatus::Stopped;
+
+        // a running node with a recent heartbeat is up
+        assert_eq!(
+            NodeLiveness::new(&running, Some(&recent), now),
+            NodeLiveness::Up
+        );
+
+        // a running process which does not send heartbeats anymore is stale
+        assert_eq!(
+            NodeLiveness::new(&running, Some(&old), now),
+            NodeLiveness::Stale
+        );
+
+        // a recent heartbeat means that the node is up, even without a process id
+        assert_eq!(
+            NodeLiveness::new(&stopped, Some(&recent), now),
+            NodeLiveness::Up
+        );
+
+        // otherwise the node is down
+        assert_eq!(
+            NodeLiveness::new(&stopped, Some(&old), now),
+            NodeLiveness::Down
+        );
+        assert_eq!(NodeLiveness::new(&stopped, None, now), NodeLiveness::Down);
+
+        // a running node without any heartbeat is considered up
+        assert_eq!(NodeLiveness::new(&running, None, now), NodeLiveness::Up);
+    }
 }
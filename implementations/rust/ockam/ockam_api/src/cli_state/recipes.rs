@@ -0,0 +1,46 @@
+use super::Result;
+use crate::cli_state::RecipeResource;
+use crate::CliState;
+
+impl CliState {
+    /// Record a resource created by a recipe
+    #[instrument(skip_all, fields(recipe_name = recipe_name))]
+    pub async fn store_recipe_resource(
+        &self,
+        recipe_name: &str,
+        resource: &RecipeResource,
+    ) -> Result<()> {
+        Ok(self
+            .recipes_repository()
+            .store_recipe_resource(recipe_name, resource)
+            .await?)
+    }
+
+    /// Return the resources created by a recipe, in their creation order
+    #[instrument(skip_all, fields(recipe_name = recipe_name))]
+    pub async fn get_recipe_resources(&self, recipe_name: &str) -> Result<Vec<RecipeResource>> {
+        Ok(self
+            .recipes_repository()
+            .get_recipe_resources(recipe_name)
+            .await?)
+    }
+
+    /// Forget a resource created by a recipe
+    #[instrument(skip_all, fields(recipe_name = recipe_name))]
+    pub async fn delete_recipe_resource(
+        &self,
+        recipe_name: &str,
+        resource: &RecipeResource,
+    ) -> Result<()> {
+        Ok(self
+            .recipes_repository()
+            .delete_recipe_resource(recipe_name, resource)
+            .await?)
+    }
+
+    /// Return the names of the recipes which created resources
+    #[instrument(skip_all)]
+    pub async fn get_recipe_names(&self) -> Result<Vec<String>> {
+        Ok(self.recipes_repository().get_recipe_names().await?)
+    }
+}
@@ -48,6 +48,18 @@ impl CliState {
         Arc::new(TcpPortalsSqlxDatabase::new(self.database()))
     }
 
+    pub(super) fn kafka_record_keys_repository(&self) -> Arc<dyn KafkaRecordKeysRepository> {
+        Arc::new(KafkaRecordKeysSqlxDatabase::new(self.database()))
+    }
+
+    pub(super) fn kafka_topic_policies_repository(&self) -> Arc<dyn KafkaTopicPoliciesRepository> {
+        Arc::new(KafkaTopicPoliciesSqlxDatabase::new(self.database()))
+    }
+
+    pub(super) fn recipes_repository(&self) -> Arc<dyn RecipesRepository> {
+        Arc::new(RecipesSqlxDatabase::new(self.database()))
+    }
+
     pub(super) fn projects_repository(&self) -> Arc<dyn ProjectsRepository> {
         Arc::new(ProjectsSqlxDatabase::new(self.database()))
     }
@@ -60,6 +72,10 @@ impl CliState {
         Arc::new(UsersSqlxDatabase::new(self.database()))
     }
 
+    pub(super) fn command_history_repository(&self) -> Arc<dyn CommandHistoryRepository> {
+        Arc::new(CommandHistorySqlxDatabase::new(self.database()))
+    }
+
     pub(super) fn user_journey_repository(&self) -> Arc<dyn JourneysRepository> {
         Arc::new(JourneysSqlxDatabase::new(self.application_database()))
     }
@@ -44,6 +44,18 @@ impl CliState {
         }
     }
 
+    #[instrument(skip_all, fields(space_id = space_id))]
+    pub async fn get_space(&self, space_id: &str) -> Result<Space> {
+        match self.spaces_repository().get_space(space_id).await? {
+            Some(space) => Ok(space),
+            None => Err(Error::new(
+                Origin::Api,
+                Kind::NotFound,
+                format!("there is no space with id {space_id}"),
+            ))?,
+        }
+    }
+
     #[instrument(skip_all, fields(name = name))]
     pub async fn get_space_by_name(&self, name: &str) -> Result<Space> {
         match self.spaces_repository().get_space_by_name(name).await? {
@@ -106,6 +118,11 @@ mod test {
         let result = cli.get_default_space().await?;
         assert_eq!(result, updated_space1);
 
+        // a space can be retrieved by id
+        let result = cli.get_space("1").await?;
+        assert_eq!(result, updated_space1);
+        assert!(cli.get_space("2").await.is_err());
+
         Ok(())
     }
 }
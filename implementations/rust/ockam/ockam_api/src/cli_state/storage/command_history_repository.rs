@@ -0,0 +1,75 @@
+use chrono::{DateTime, Utc};
+
+use ockam_core::async_trait;
+use ockam_core::Result;
+
+/// The CommandHistoryRepository stores the executions of the commands modifying
+/// the Ockam configuration, so that they can be audited later on
+#[async_trait]
+pub trait CommandHistoryRepository: Send + Sync + 'static {
+    /// Store the execution of a command
+    async fn store_command_execution(&self, command_execution: &CommandExecution) -> Result<()>;
+
+    /// Return the most recent command executions, in chronological order.
+    /// All the command executions are returned if no limit is given
+    async fn get_command_executions(&self, limit: Option<u64>) -> Result<Vec<CommandExecution>>;
+
+    /// Delete all the command executions
+    async fn delete_command_executions(&self) -> Result<()>;
+}
+
+/// The execution of a command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandExecution {
+    command_name: String,
+    arguments: String,
+    user_name: String,
+    executed_at: DateTime<Utc>,
+    error: Option<String>,
+}
+
+impl CommandExecution {
+    pub fn new(
+        command_name: &str,
+        arguments: &str,
+        user_name: &str,
+        executed_at: DateTime<Utc>,
+        error: Option<String>,
+    ) -> CommandExecution {
+        Self {
+            command_name: command_name.to_string(),
+            arguments: arguments.to_string(),
+            user_name: user_name.to_string(),
+            executed_at,
+            error,
+        }
+    }
+
+    /// Name of the command, for example `tcp-inlet create`
+    pub fn command_name(&self) -> String {
+        self.command_name.clone()
+    }
+
+    /// Arguments of the command, without secrets
+    pub fn arguments(&self) -> String {
+        self.arguments.clone()
+    }
+
+    /// Name of the OS user who executed the command
+    pub fn user_name(&self) -> String {
+        self.user_name.clone()
+    }
+
+    pub fn executed_at(&self) -> DateTime<Utc> {
+        self.executed_at
+    }
+
+    /// Error message if the command failed
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
@@ -0,0 +1,166 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use sqlx::*;
+use tracing::debug;
+
+use ockam::{FromSqlxError, SqlxDatabase, ToVoid};
+use ockam_core::async_trait;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::Result;
+use ockam_node::database::Nullable;
+
+use crate::cli_state::storage::command_history_repository::{
+    CommandExecution, CommandHistoryRepository,
+};
+
+#[derive(Clone)]
+pub struct CommandHistorySqlxDatabase {
+    database: SqlxDatabase,
+}
+
+impl CommandHistorySqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase) -> Self {
+        debug!("create a repository for the command history");
+        Self { database }
+    }
+
+    /// Create a new in-memory database
+    #[allow(unused)]
+    pub async fn create() -> Result<Arc<Self>> {
+        Ok(Arc::new(Self::new(
+            SqlxDatabase::in_memory("command history").await?,
+        )))
+    }
+}
+
+#[async_trait]
+impl CommandHistoryRepository for CommandHistorySqlxDatabase {
+    async fn store_command_execution(&self, command_execution: &CommandExecution) -> Result<()> {
+        let query = query(
+            r#"
+            INSERT INTO command_execution (command_name, arguments, user_name, executed_at, error)
+            VALUES ($1, $2, $3, $4, $5)"#,
+        )
+        .bind(command_execution.command_name())
+        .bind(command_execution.arguments())
+        .bind(command_execution.user_name())
+        .bind(
+            command_execution
+                .executed_at()
+                .to_rfc3339_opts(SecondsFormat::Micros, true),
+        )
+        .bind(command_execution.error());
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn get_command_executions(&self, limit: Option<u64>) -> Result<Vec<CommandExecution>> {
+        let sql = r#"
+            SELECT command_name, arguments, user_name, executed_at, error FROM command_execution
+            ORDER BY executed_at DESC"#;
+        let rows: Vec<CommandExecutionRow> = match limit {
+            Some(limit) => {
+                query_as(&format!("{sql} LIMIT $1"))
+                    .bind(limit as i64)
+                    .fetch_all(&*self.database.pool)
+                    .await
+            }
+            None => query_as(sql).fetch_all(&*self.database.pool).await,
+        }
+        .into_core()?;
+        // the most recent executions are selected, then returned in chronological order
+        rows.iter()
+            .rev()
+            .map(|r| r.command_execution())
+            .collect::<Result<Vec<_>>>()
+    }
+
+    async fn delete_command_executions(&self) -> Result<()> {
+        let query = query("DELETE FROM command_execution");
+        query.execute(&*self.database.pool).await.void()
+    }
+}
+
+// Database serialization / deserialization
+
+/// Low-level representation of a row in the command_execution table
+#[derive(sqlx::FromRow)]
+struct CommandExecutionRow {
+    command_name: String,
+    arguments: String,
+    user_name: String,
+    executed_at: String,
+    error: Nullable<String>,
+}
+
+impl CommandExecutionRow {
+    fn command_execution(&self) -> Result<CommandExecution> {
+        Ok(CommandExecution::new(
+            &self.command_name,
+            &self.arguments,
+            &self.user_name,
+            self.executed_at()?,
+            self.error.to_option(),
+        ))
+    }
+
+    fn executed_at(&self) -> Result<DateTime<Utc>> {
+        Ok(DateTime::parse_from_rfc3339(&self.executed_at)
+            .map_err(|e| {
+                ockam_core::Error::new(Origin::Api, Kind::Serialization, format!("{e:?}"))
+            })?
+            .into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, SubsecRound};
+    use ockam_node::database::with_dbs;
+
+    #[tokio::test]
+    async fn test_repository() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn CommandHistoryRepository> =
+                Arc::new(CommandHistorySqlxDatabase::new(db));
+
+            // the execution times are stored with a microsecond precision
+            let now = Utc::now().trunc_subsecs(6);
+            let create = CommandExecution::new(
+                "node create",
+                "ockam node create n1",
+                "alice",
+                now - Duration::seconds(2),
+                None,
+            );
+            let delete = CommandExecution::new(
+                "node delete",
+                "ockam node delete n2",
+                "bob",
+                now - Duration::seconds(1),
+                Some("node not found".to_string()),
+            );
+            let reset = CommandExecution::new("reset", "ockam reset -y", "alice", now, None);
+            repository.store_command_execution(&delete).await?;
+            repository.store_command_execution(&create).await?;
+            repository.store_command_execution(&reset).await?;
+
+            // the executions are returned in chronological order
+            let actual = repository.get_command_executions(None).await?;
+            assert_eq!(actual, vec![create, delete.clone(), reset.clone()]);
+
+            // only the most recent executions are returned when a limit is given
+            let actual = repository.get_command_executions(Some(2)).await?;
+            assert_eq!(actual, vec![delete, reset]);
+
+            repository.delete_command_executions().await?;
+            let actual = repository.get_command_executions(None).await?;
+            assert!(actual.is_empty());
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,25 @@
+use ockam_core::async_trait;
+use ockam_core::Result;
+use ockam_vault::AeadSecretKeyHandle;
+
+/// The KafkaRecordKeysRepository is responsible for accessing the handle of the secret
+/// of a Kafka outlet, from which the deterministic encryption keys of the record keys are derived.
+/// The secret itself is stored in the vault of the node
+#[async_trait]
+pub trait KafkaRecordKeysRepository: Send + Sync + 'static {
+    /// Store the secret handle of a node, unless the node already has a secret
+    async fn store_record_keys_secret_handle(
+        &self,
+        node_name: &str,
+        handle: &AeadSecretKeyHandle,
+    ) -> Result<()>;
+
+    /// Return the secret handle of a node, if any
+    async fn get_record_keys_secret_handle(
+        &self,
+        node_name: &str,
+    ) -> Result<Option<AeadSecretKeyHandle>>;
+
+    /// Delete the secret handle of a node
+    async fn delete_record_keys_secret_handle(&self, node_name: &str) -> Result<()>;
+}
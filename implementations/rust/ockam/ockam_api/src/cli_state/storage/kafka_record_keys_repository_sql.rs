@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use sqlx::*;
+use tracing::debug;
+
+use ockam::{FromSqlxError, SqlxDatabase, ToVoid};
+use ockam_core::async_trait;
+use ockam_core::Result;
+use ockam_vault::{AeadSecretKeyHandle, HandleToSecret};
+
+use crate::cli_state::storage::kafka_record_keys_repository::KafkaRecordKeysRepository;
+
+#[derive(Clone)]
+pub struct KafkaRecordKeysSqlxDatabase {
+    database: SqlxDatabase,
+}
+
+impl KafkaRecordKeysSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase) -> Self {
+        debug!("create a repository for kafka record keys");
+        Self { database }
+    }
+
+    /// Create a new in-memory database
+    #[allow(unused)]
+    pub async fn create() -> Result<Arc<Self>> {
+        Ok(Arc::new(Self::new(
+            SqlxDatabase::in_memory("kafka record keys").await?,
+        )))
+    }
+}
+
+#[async_trait]
+impl KafkaRecordKeysRepository for KafkaRecordKeysSqlxDatabase {
+    async fn store_record_keys_secret_handle(
+        &self,
+        node_name: &str,
+        handle: &AeadSecretKeyHandle,
+    ) -> Result<()> {
+        let query = query(
+            r#"
+            INSERT INTO kafka_record_keys_secret (node_name, secret_handle)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING"#,
+        )
+        .bind(node_name)
+        .bind(handle);
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn get_record_keys_secret_handle(
+        &self,
+        node_name: &str,
+    ) -> Result<Option<AeadSecretKeyHandle>> {
+        let query =
+            query_as("SELECT secret_handle FROM kafka_record_keys_secret WHERE node_name = $1")
+                .bind(node_name);
+        let row: Option<KafkaRecordKeysSecretRow> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        Ok(row.map(|r| r.secret_handle()))
+    }
+
+    async fn delete_record_keys_secret_handle(&self, node_name: &str) -> Result<()> {
+        let query =
+            query("DELETE FROM kafka_record_keys_secret WHERE node_name = $1").bind(node_name);
+        query.execute(&*self.database.pool).await.void()
+    }
+}
+
+// Database serialization / deserialization
+
+/// Low-level representation of a row in the kafka_record_keys_secret table
+#[derive(sqlx::FromRow)]
+struct KafkaRecordKeysSecretRow {
+    secret_handle: Vec<u8>,
+}
+
+impl KafkaRecordKeysSecretRow {
+    fn secret_handle(&self) -> AeadSecretKeyHandle {
+        AeadSecretKeyHandle::new(HandleToSecret::new(self.secret_handle.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_node::database::with_dbs;
+
+    #[tokio::test]
+    async fn test_repository() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn KafkaRecordKeysRepository> =
+                Arc::new(KafkaRecordKeysSqlxDatabase::new(db));
+
+            let handle1 = AeadSecretKeyHandle::new(HandleToSecret::new(vec![1; 8]));
+            let handle2 = AeadSecretKeyHandle::new(HandleToSecret::new(vec![2; 8]));
+
+            repository
+                .store_record_keys_secret_handle("node_name", &handle1)
+                .await?;
+            let actual = repository
+                .get_record_keys_secret_handle("node_name")
+                .await?;
+            assert_eq!(actual, Some(handle1.clone()));
+
+            // an existing secret is never replaced
+            repository
+                .store_record_keys_secret_handle("node_name", &handle2)
+                .await?;
+            let actual = repository
+                .get_record_keys_secret_handle("node_name")
+                .await?;
+            assert_eq!(actual, Some(handle1));
+
+            repository
+                .delete_record_keys_secret_handle("node_name")
+                .await?;
+            let actual = repository
+                .get_record_keys_secret_handle("node_name")
+                .await?;
+            assert_eq!(actual, None);
+
+            Ok(())
+        })
+        .await
+    }
+}
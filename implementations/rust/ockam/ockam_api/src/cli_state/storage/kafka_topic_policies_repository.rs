@@ -0,0 +1,26 @@
+use crate::kafka::TopicPolicies;
+use ockam_core::Result;
+use ockam_core::{async_trait, Address};
+
+/// The KafkaTopicPoliciesRepository is responsible for accessing the topic policies
+/// configured on the Kafka inlets
+#[async_trait]
+pub trait KafkaTopicPoliciesRepository: Send + Sync + 'static {
+    /// Store the topic policies of a Kafka inlet, replacing its previous policies
+    async fn store_topic_policies(
+        &self,
+        node_name: &str,
+        inlet_address: &Address,
+        topic_policies: &TopicPolicies,
+    ) -> Result<()>;
+
+    /// Return the topic policies of a Kafka inlet for a given node name and inlet address
+    async fn get_topic_policies(
+        &self,
+        node_name: &str,
+        inlet_address: &Address,
+    ) -> Result<TopicPolicies>;
+
+    /// Delete the topic policies of a Kafka inlet for a given node name and inlet address
+    async fn delete_topic_policies(&self, node_name: &str, inlet_address: &Address) -> Result<()>;
+}
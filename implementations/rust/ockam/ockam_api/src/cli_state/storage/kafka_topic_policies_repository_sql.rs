@@ -0,0 +1,188 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use sqlx::*;
+use tracing::debug;
+
+use ockam::{FromSqlxError, SqlxDatabase, ToVoid};
+use ockam_core::Result;
+use ockam_core::{async_trait, Address};
+use ockam_node::database::Boolean;
+
+use crate::cli_state::storage::kafka_topic_policies_repository::KafkaTopicPoliciesRepository;
+use crate::kafka::{TopicMode, TopicPolicies, TopicPolicy};
+
+/// Mode of the rows storing the allowed topics
+const ALLOWED_TOPIC_MODE: &str = "allow";
+
+#[derive(Clone)]
+pub struct KafkaTopicPoliciesSqlxDatabase {
+    database: SqlxDatabase,
+}
+
+impl KafkaTopicPoliciesSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase) -> Self {
+        debug!("create a repository for kafka topic policies");
+        Self { database }
+    }
+
+    /// Create a new in-memory database
+    #[allow(unused)]
+    pub async fn create() -> Result<Arc<Self>> {
+        Ok(Arc::new(Self::new(
+            SqlxDatabase::in_memory("kafka topic policies").await?,
+        )))
+    }
+}
+
+#[async_trait]
+impl KafkaTopicPoliciesRepository for KafkaTopicPoliciesSqlxDatabase {
+    async fn store_topic_policies(
+        &self,
+        node_name: &str,
+        inlet_address: &Address,
+        topic_policies: &TopicPolicies,
+    ) -> Result<()> {
+        let mut transaction = self.database.begin().await.into_core()?;
+
+        let query1 =
+            query("DELETE FROM kafka_topic_policy WHERE node_name = $1 AND inlet_address = $2")
+                .bind(node_name)
+                .bind(inlet_address.to_string());
+        query1.execute(&mut *transaction).await.void()?;
+
+        let allowed_topics = topic_policies
+            .allowed_topics()
+            .iter()
+            .map(|topic| (topic.as_str(), ALLOWED_TOPIC_MODE, false));
+        let policies = topic_policies
+            .policies()
+            .iter()
+            .map(|p| (p.topic(), p.mode().as_str(), p.encrypt_keys()));
+
+        for (topic, mode, encrypt_keys) in allowed_topics.chain(policies) {
+            let query2 = query(
+                r#"
+                INSERT INTO kafka_topic_policy (node_name, inlet_address, topic, mode, encrypt_keys)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT DO NOTHING"#,
+            )
+            .bind(node_name)
+            .bind(inlet_address.to_string())
+            .bind(topic)
+            .bind(mode)
+            .bind(encrypt_keys);
+            query2.execute(&mut *transaction).await.void()?;
+        }
+
+        transaction.commit().await.void()
+    }
+
+    async fn get_topic_policies(
+        &self,
+        node_name: &str,
+        inlet_address: &Address,
+    ) -> Result<TopicPolicies> {
+        let query = query_as(
+            r#"
+            SELECT topic, mode, encrypt_keys FROM kafka_topic_policy
+            WHERE node_name = $1 AND inlet_address = $2
+            ORDER BY topic"#,
+        )
+        .bind(node_name)
+        .bind(inlet_address.to_string());
+        let rows: Vec<KafkaTopicPolicyRow> =
+            query.fetch_all(&*self.database.pool).await.into_core()?;
+
+        let mut topic_policies = TopicPolicies::new();
+        for row in rows {
+            topic_policies = if row.mode == ALLOWED_TOPIC_MODE {
+                topic_policies.with_allowed_topic(row.topic)
+            } else {
+                topic_policies.with_policy(row.topic_policy()?)
+            };
+        }
+        Ok(topic_policies)
+    }
+
+    async fn delete_topic_policies(&self, node_name: &str, inlet_address: &Address) -> Result<()> {
+        let query =
+            query("DELETE FROM kafka_topic_policy WHERE node_name = $1 AND inlet_address = $2")
+                .bind(node_name)
+                .bind(inlet_address.to_string());
+        query.execute(&*self.database.pool).await.void()
+    }
+}
+
+// Database serialization / deserialization
+
+/// Low-level representation of a row in the kafka_topic_policy table
+#[derive(sqlx::FromRow)]
+struct KafkaTopicPolicyRow {
+    topic: String,
+    mode: String,
+    encrypt_keys: Boolean,
+}
+
+impl KafkaTopicPolicyRow {
+    fn topic_policy(&self) -> Result<TopicPolicy> {
+        let policy = TopicPolicy::new(&self.topic, TopicMode::from_str(&self.mode)?);
+        Ok(if self.encrypt_keys.to_bool() {
+            policy.with_encrypted_keys()
+        } else {
+            policy
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_node::database::with_dbs;
+
+    #[tokio::test]
+    async fn test_repository() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn KafkaTopicPoliciesRepository> =
+                Arc::new(KafkaTopicPoliciesSqlxDatabase::new(db));
+
+            let inlet_address = Address::from_string("kafka_inlet");
+            let encrypted_topic = TopicPolicy::new("payments", TopicMode::Encrypt);
+            let topic_policies = TopicPolicies::new()
+                .with_allowed_topic("logs.*")
+                .with_policy(TopicPolicy::new("logs.access", TopicMode::Plaintext))
+                .with_policy(encrypted_topic.with_encrypted_keys());
+
+            repository
+                .store_topic_policies("node_name", &inlet_address, &topic_policies)
+                .await?;
+            let actual = repository
+                .get_topic_policies("node_name", &inlet_address)
+                .await?;
+            assert_eq!(actual, topic_policies);
+
+            // the policies are replaced
+            let topic_policies =
+                TopicPolicies::new().with_policy(TopicPolicy::new("*", TopicMode::Deny));
+            repository
+                .store_topic_policies("node_name", &inlet_address, &topic_policies)
+                .await?;
+            let actual = repository
+                .get_topic_policies("node_name", &inlet_address)
+                .await?;
+            assert_eq!(actual, topic_policies);
+
+            repository
+                .delete_topic_policies("node_name", &inlet_address)
+                .await?;
+            let actual = repository
+                .get_topic_policies("node_name", &inlet_address)
+                .await?;
+            assert!(actual.is_empty());
+
+            Ok(())
+        })
+        .await
+    }
+}
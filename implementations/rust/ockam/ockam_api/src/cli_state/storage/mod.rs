@@ -1,13 +1,21 @@
+pub use command_history_repository::*;
+pub use command_history_repository_sql::*;
 pub use enrollments_repository::*;
 pub use enrollments_repository_sql::*;
 pub use identities_repository::*;
 pub use identities_repository_sql::*;
 pub use journeys_repository::*;
 pub use journeys_repository_sql::*;
+pub use kafka_record_keys_repository::*;
+pub use kafka_record_keys_repository_sql::*;
+pub use kafka_topic_policies_repository::*;
+pub use kafka_topic_policies_repository_sql::*;
 pub use nodes_repository::*;
 pub use nodes_repository_sql::*;
 pub use projects_repository::*;
 pub use projects_repository_sql::*;
+pub use recipes_repository::*;
+pub use recipes_repository_sql::*;
 pub use spaces_repository::*;
 pub use spaces_repository_sql::*;
 pub use tcp_portals_repository::*;
@@ -17,16 +25,24 @@ pub use users_repository_sql::*;
 pub use vaults_repository::*;
 pub use vaults_repository_sql::*;
 
+mod command_history_repository;
+mod command_history_repository_sql;
 mod enrollments_repository;
 mod enrollments_repository_sql;
 mod identities_repository;
 mod identities_repository_sql;
 mod journeys_repository;
 mod journeys_repository_sql;
+mod kafka_record_keys_repository;
+mod kafka_record_keys_repository_sql;
+mod kafka_topic_policies_repository;
+mod kafka_topic_policies_repository_sql;
 mod nodes_repository;
 mod nodes_repository_sql;
 mod projects_repository;
 mod projects_repository_sql;
+mod recipes_repository;
+mod recipes_repository_sql;
 mod spaces_repository;
 mod spaces_repository_sql;
 mod tcp_portals_repository;
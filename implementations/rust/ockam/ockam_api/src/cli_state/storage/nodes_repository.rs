@@ -2,7 +2,7 @@ use ockam::identity::Identifier;
 use ockam_core::async_trait;
 use ockam_core::Result;
 
-use crate::cli_state::NodeInfo;
+use crate::cli_state::{NodeHeartbeat, NodeInfo};
 use crate::config::lookup::InternetAddress;
 
 /// This trait supports the storage of node data:
@@ -11,6 +11,7 @@ use crate::config::lookup::InternetAddress;
 ///  - a node is always associated to an identifier
 ///  - a node can be associated to a (single) project
 ///  - when a node is running we can persist its process id and its TCP listener address
+///  - a running node periodically stores a heartbeat, to detect nodes which are not alive anymore
 ///  - one of the nodes is always set as the default node
 ///  - a node can be set as an authority node. The purpose of this flag is to be able to display
 ///    the node status without being able to start a TCP connection since the TCP listener might not be accessible
@@ -70,6 +71,15 @@ pub trait NodesRepository: Send + Sync + 'static {
     /// Unset the process id of a node
     async fn set_no_node_pid(&self, node_name: &str) -> Result<()>;
 
+    /// Store the last heartbeat of a node
+    async fn set_node_heartbeat(&self, node_name: &str, heartbeat: &NodeHeartbeat) -> Result<()>;
+
+    /// Return the last heartbeat of a node
+    async fn get_node_heartbeat(&self, node_name: &str) -> Result<Option<NodeHeartbeat>>;
+
+    /// Delete the heartbeat of a node when it is stopped
+    async fn delete_node_heartbeat(&self, node_name: &str) -> Result<()>;
+
     /// Associate a node to a project
     async fn set_node_project_name(&self, node_name: &str, project_name: &str) -> Result<()>;
 
@@ -12,7 +12,7 @@ use ockam_core::async_trait;
 use ockam_core::Result;
 use ockam_node::database::{Boolean, Nullable};
 
-use crate::cli_state::{NodeInfo, NodesRepository};
+use crate::cli_state::{NodeHeartbeat, NodeInfo, NodesRepository};
 use crate::config::lookup::InternetAddress;
 
 #[derive(Clone)]
@@ -151,6 +151,17 @@ impl NodesRepository for NodesSqlxDatabase {
         let query = sqlx::query("DELETE FROM node_project WHERE node_name = $1").bind(node_name);
         query.execute(&mut *transaction).await.void()?;
 
+        let query = sqlx::query("DELETE FROM node_heartbeat WHERE node_name = $1").bind(node_name);
+        query.execute(&mut *transaction).await.void()?;
+
+        let query =
+            sqlx::query("DELETE FROM kafka_topic_policy WHERE node_name = $1").bind(node_name);
+        query.execute(&mut *transaction).await.void()?;
+
+        let query = sqlx::query("DELETE FROM kafka_record_keys_secret WHERE node_name = $1")
+            .bind(node_name);
+        query.execute(&mut *transaction).await.void()?;
+
         transaction.commit().await.void()
     }
 
@@ -209,6 +220,37 @@ impl NodesRepository for NodesSqlxDatabase {
         query.execute(&*self.database.pool).await.void()
     }
 
+    async fn set_node_heartbeat(&self, node_name: &str, heartbeat: &NodeHeartbeat) -> Result<()> {
+        let query = query(
+            r#"
+        INSERT INTO node_heartbeat (node_name, last_seen_at, workers_count, secure_channels, tcp_inlets, tcp_outlets)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (node_name)
+        DO UPDATE SET last_seen_at = $2, workers_count = $3, secure_channels = $4, tcp_inlets = $5, tcp_outlets = $6"#,
+        )
+        .bind(node_name)
+        .bind(heartbeat.last_seen_at as i64)
+        .bind(heartbeat.workers_count as i32)
+        .bind(heartbeat.secure_channels as i32)
+        .bind(heartbeat.tcp_inlets as i32)
+        .bind(heartbeat.tcp_outlets as i32);
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn get_node_heartbeat(&self, node_name: &str) -> Result<Option<NodeHeartbeat>> {
+        let query = query_as("SELECT last_seen_at, workers_count, secure_channels, tcp_inlets, tcp_outlets FROM node_heartbeat WHERE node_name = $1").bind(node_name);
+        let row: Option<NodeHeartbeatRow> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        Ok(row.map(|r| r.node_heartbeat()))
+    }
+
+    async fn delete_node_heartbeat(&self, node_name: &str) -> Result<()> {
+        let query = query("DELETE FROM node_heartbeat WHERE node_name = $1").bind(node_name);
+        query.execute(&*self.database.pool).await.void()
+    }
+
     async fn set_node_project_name(&self, node_name: &str, project_name: &str) -> Result<()> {
         let query = query(
             r#"
@@ -284,6 +326,27 @@ impl NodeRow {
     }
 }
 
+#[derive(FromRow)]
+pub(crate) struct NodeHeartbeatRow {
+    last_seen_at: i64,
+    workers_count: i64,
+    secure_channels: i64,
+    tcp_inlets: i64,
+    tcp_outlets: i64,
+}
+
+impl NodeHeartbeatRow {
+    pub(crate) fn node_heartbeat(&self) -> NodeHeartbeat {
+        NodeHeartbeat {
+            last_seen_at: self.last_seen_at as u64,
+            workers_count: self.workers_count as u32,
+            secure_channels: self.secure_channels as u32,
+            tcp_inlets: self.tcp_inlets as u32,
+            tcp_outlets: self.tcp_outlets as u32,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::cli_state::NodeInfo;
@@ -399,6 +462,45 @@ mod test {
         .await
     }
 
+    #[tokio::test]
+    async fn test_node_heartbeat() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn NodesRepository> = Arc::new(NodesSqlxDatabase::new(db));
+
+            // there is no heartbeat for a node which has never been started
+            let result = repository.get_node_heartbeat("node1").await?;
+            assert_eq!(result, None);
+
+            // a heartbeat can be stored, then updated
+            let mut heartbeat = NodeHeartbeat {
+                last_seen_at: 1000,
+                workers_count: 12,
+                secure_channels: 1,
+                tcp_inlets: 2,
+                tcp_outlets: 3,
+            };
+            repository.set_node_heartbeat("node1", &heartbeat).await?;
+            heartbeat.last_seen_at = 1010;
+            heartbeat.workers_count = 14;
+            repository.set_node_heartbeat("node1", &heartbeat).await?;
+            let result = repository.get_node_heartbeat("node1").await?;
+            assert_eq!(result, Some(heartbeat.clone()));
+
+            // the heartbeat can be deleted when the node is stopped
+            repository.delete_node_heartbeat("node1").await?;
+            let result = repository.get_node_heartbeat("node1").await?;
+            assert_eq!(result, None);
+
+            // the heartbeat is deleted with the node
+            repository.set_node_heartbeat("node1", &heartbeat).await?;
+            repository.delete_node("node1").await?;
+            let result = repository.get_node_heartbeat("node1").await?;
+            assert_eq!(result, None);
+            Ok(())
+        })
+        .await
+    }
+
     /// HELPERS
     async fn create_identity() -> Result<Identifier> {
         let identities = identities().await?;
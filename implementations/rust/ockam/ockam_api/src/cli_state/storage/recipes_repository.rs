@@ -0,0 +1,60 @@
+use ockam_core::async_trait;
+use ockam_core::Result;
+
+/// The RecipesRepository stores the resources created by the recipes run with `ockam run`,
+/// so that they can be deleted later on
+#[async_trait]
+pub trait RecipesRepository: Send + Sync + 'static {
+    /// Store a resource created by a recipe.
+    /// If the resource is already stored for that recipe, its creation order is kept
+    async fn store_recipe_resource(
+        &self,
+        recipe_name: &str,
+        resource: &RecipeResource,
+    ) -> Result<()>;
+
+    /// Return the resources created by a recipe, in their creation order
+    async fn get_recipe_resources(&self, recipe_name: &str) -> Result<Vec<RecipeResource>>;
+
+    /// Delete a resource created by a recipe
+    async fn delete_recipe_resource(
+        &self,
+        recipe_name: &str,
+        resource: &RecipeResource,
+    ) -> Result<()>;
+
+    /// Return the names of all the recipes which created resources
+    async fn get_recipe_names(&self) -> Result<Vec<String>>;
+}
+
+/// A resource created by a recipe
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecipeResource {
+    kind: String,
+    name: String,
+    node_name: String,
+}
+
+impl RecipeResource {
+    pub fn new(kind: &str, name: &str, node_name: &str) -> RecipeResource {
+        Self {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            node_name: node_name.to_string(),
+        }
+    }
+
+    /// Kind of resource, for example `node` or `tcp-inlet`
+    pub fn kind(&self) -> String {
+        self.kind.clone()
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Name of the node hosting the resource, or the node name if the resource is a node
+    pub fn node_name(&self) -> String {
+        self.node_name.clone()
+    }
+}
@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use sqlx::*;
+use tracing::debug;
+
+use ockam::{FromSqlxError, SqlxDatabase, ToVoid};
+use ockam_core::async_trait;
+use ockam_core::Result;
+
+use crate::cli_state::storage::recipes_repository::{RecipeResource, RecipesRepository};
+
+#[derive(Clone)]
+pub struct RecipesSqlxDatabase {
+    database: SqlxDatabase,
+}
+
+impl RecipesSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase) -> Self {
+        debug!("create a repository for recipes");
+        Self { database }
+    }
+
+    /// Create a new in-memory database
+    #[allow(unused)]
+    pub async fn create() -> Result<Arc<Self>> {
+        Ok(Arc::new(Self::new(
+            SqlxDatabase::in_memory("recipes").await?,
+        )))
+    }
+}
+
+#[async_trait]
+impl RecipesRepository for RecipesSqlxDatabase {
+    async fn store_recipe_resource(
+        &self,
+        recipe_name: &str,
+        resource: &RecipeResource,
+    ) -> Result<()> {
+        let query = query(
+            r#"
+            INSERT INTO recipe_resource (recipe_name, resource_kind, resource_name, node_name, position)
+            VALUES ($1, $2, $3, $4,
+              (SELECT COALESCE(MAX(position), 0) + 1 FROM recipe_resource WHERE recipe_name = $1))
+            ON CONFLICT DO NOTHING"#,
+        )
+        .bind(recipe_name)
+        .bind(resource.kind())
+        .bind(resource.name())
+        .bind(resource.node_name());
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn get_recipe_resources(&self, recipe_name: &str) -> Result<Vec<RecipeResource>> {
+        let query = query_as(
+            r#"
+            SELECT resource_kind, resource_name, node_name FROM recipe_resource
+            WHERE recipe_name = $1
+            ORDER BY position"#,
+        )
+        .bind(recipe_name);
+        let rows: Vec<RecipeResourceRow> =
+            query.fetch_all(&*self.database.pool).await.into_core()?;
+        Ok(rows.iter().map(|r| r.recipe_resource()).collect())
+    }
+
+    async fn delete_recipe_resource(
+        &self,
+        recipe_name: &str,
+        resource: &RecipeResource,
+    ) -> Result<()> {
+        let query = query(
+            r#"
+            DELETE FROM recipe_resource
+            WHERE recipe_name = $1 AND resource_kind = $2 AND resource_name = $3 AND node_name = $4"#,
+        )
+        .bind(recipe_name)
+        .bind(resource.kind())
+        .bind(resource.name())
+        .bind(resource.node_name());
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn get_recipe_names(&self) -> Result<Vec<String>> {
+        let query =
+            query_as("SELECT DISTINCT recipe_name FROM recipe_resource ORDER BY recipe_name");
+        let rows: Vec<RecipeNameRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
+        Ok(rows.into_iter().map(|r| r.recipe_name).collect())
+    }
+}
+
+// Database serialization / deserialization
+
+/// Low-level representation of a row in the recipe_resource table
+#[derive(sqlx::FromRow)]
+struct RecipeResourceRow {
+    resource_kind: String,
+    resource_name: String,
+    node_name: String,
+}
+
+impl RecipeResourceRow {
+    fn recipe_resource(&self) -> RecipeResource {
+        RecipeResource::new(&self.resource_kind, &self.resource_name, &self.node_name)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct RecipeNameRow {
+    recipe_name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_node::database::with_dbs;
+
+    #[tokio::test]
+    async fn test_repository() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn RecipesRepository> = Arc::new(RecipesSqlxDatabase::new(db));
+
+            let node = RecipeResource::new("node", "n1", "n1");
+            let inlet = RecipeResource::new("tcp-inlet", "web", "n1");
+            let relay = RecipeResource::new("relay", "default", "n2");
+            repository.store_recipe_resource("recipe", &node).await?;
+            repository.store_recipe_resource("recipe", &inlet).await?;
+            repository.store_recipe_resource("other", &relay).await?;
+
+            // storing a resource again keeps its creation order
+            repository.store_recipe_resource("recipe", &node).await?;
+            let actual = repository.get_recipe_resources("recipe").await?;
+            assert_eq!(actual, vec![node.clone(), inlet.clone()]);
+
+            let actual = repository.get_recipe_names().await?;
+            assert_eq!(actual, vec!["other".to_string(), "recipe".to_string()]);
+
+            repository.delete_recipe_resource("recipe", &node).await?;
+            let actual = repository.get_recipe_resources("recipe").await?;
+            assert_eq!(actual, vec![inlet]);
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -1,4 +1,5 @@
 use std::path::PathBuf;
+use std::time::Duration;
 
 use sqlx::*;
 
@@ -7,7 +8,10 @@ use ockam_core::async_trait;
 use ockam_core::Result;
 use ockam_node::database::{Boolean, Nullable};
 
-use crate::cli_state::{NamedVault, UseAwsKms, VaultType, VaultsRepository};
+use crate::cli_state::{
+    NamedVault, Pkcs11VaultConfig, UseAwsKms, VaultEncryption, VaultEncryptionMethod, VaultType,
+    VaultsRepository,
+};
 
 #[derive(Clone)]
 pub struct VaultsSqlxDatabase {
@@ -39,15 +43,26 @@ impl VaultsRepository for VaultsSqlxDatabase {
         let query = query(
             r#"
         INSERT INTO
-            vault (name, path, is_default, is_kms)
-            VALUES ($1, $2, $3, $4)
+            vault (name, path, is_default, is_kms, pkcs11_module, pkcs11_slot, pkcs11_pin_env,
+                encryption_method, encryption_salt, encryption_key_check, encryption_idle_timeout)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
             ON CONFLICT (name)
-            DO UPDATE SET path = $2, is_default = $3, is_kms = $4"#,
+            DO UPDATE SET path = $2, is_default = $3, is_kms = $4,
+                pkcs11_module = $5, pkcs11_slot = $6, pkcs11_pin_env = $7,
+                encryption_method = $8, encryption_salt = $9, encryption_key_check = $10,
+                encryption_idle_timeout = $11"#,
         )
         .bind(name)
         .bind(vault_type.path().map(|p| p.to_string_lossy().to_string()))
         .bind(!default_exists)
-        .bind(vault_type.use_aws_kms());
+        .bind(vault_type.use_aws_kms())
+        .bind(pkcs11_module(&vault_type))
+        .bind(pkcs11_slot(&vault_type))
+        .bind(pkcs11_pin_env(&vault_type))
+        .bind(encryption_method(&vault_type))
+        .bind(encryption_salt(&vault_type))
+        .bind(encryption_key_check(&vault_type))
+        .bind(encryption_idle_timeout(&vault_type));
         query.execute(&mut *transaction).await.void()?;
 
         transaction.commit().await.void()?;
@@ -55,10 +70,24 @@ impl VaultsRepository for VaultsSqlxDatabase {
     }
 
     async fn update_vault(&self, name: &str, vault_type: VaultType) -> Result<()> {
-        let query = query("UPDATE vault SET path = $1, is_kms = $2 WHERE name = $3")
-            .bind(vault_type.path().map(|p| p.to_string_lossy().to_string()))
-            .bind(vault_type.use_aws_kms())
-            .bind(name);
+        let query = query(
+            r#"
+        UPDATE vault
+            SET path = $1, is_kms = $2, pkcs11_module = $3, pkcs11_slot = $4, pkcs11_pin_env = $5,
+                encryption_method = $6, encryption_salt = $7, encryption_key_check = $8,
+                encryption_idle_timeout = $9
+            WHERE name = $10"#,
+        )
+        .bind(vault_type.path().map(|p| p.to_string_lossy().to_string()))
+        .bind(vault_type.use_aws_kms())
+        .bind(pkcs11_module(&vault_type))
+        .bind(pkcs11_slot(&vault_type))
+        .bind(pkcs11_pin_env(&vault_type))
+        .bind(encryption_method(&vault_type))
+        .bind(encryption_salt(&vault_type))
+        .bind(encryption_key_check(&vault_type))
+        .bind(encryption_idle_timeout(&vault_type))
+        .bind(name);
         query.execute(&*self.database.pool).await.void()
     }
 
@@ -68,7 +97,12 @@ impl VaultsRepository for VaultsSqlxDatabase {
     }
 
     async fn get_database_vault(&self) -> Result<Option<NamedVault>> {
-        let query = query_as("SELECT name, path, is_default, is_kms FROM vault WHERE path is NULL");
+        let query = query_as(
+            r#"
+        SELECT name, path, is_default, is_kms, pkcs11_module, pkcs11_slot, pkcs11_pin_env,
+            encryption_method, encryption_salt, encryption_key_check, encryption_idle_timeout
+            FROM vault WHERE path is NULL"#,
+        );
         let row: Option<VaultRow> = query
             .fetch_optional(&*self.database.pool)
             .await
@@ -77,8 +111,13 @@ impl VaultsRepository for VaultsSqlxDatabase {
     }
 
     async fn get_named_vault(&self, name: &str) -> Result<Option<NamedVault>> {
-        let query =
-            query_as("SELECT name, path, is_default, is_kms FROM vault WHERE name = $1").bind(name);
+        let query = query_as(
+            r#"
+        SELECT name, path, is_default, is_kms, pkcs11_module, pkcs11_slot, pkcs11_pin_env,
+            encryption_method, encryption_salt, encryption_key_check, encryption_idle_timeout
+            FROM vault WHERE name = $1"#,
+        )
+        .bind(name);
         let row: Option<VaultRow> = query
             .fetch_optional(&*self.database.pool)
             .await
@@ -87,7 +126,12 @@ impl VaultsRepository for VaultsSqlxDatabase {
     }
 
     async fn get_named_vaults(&self) -> Result<Vec<NamedVault>> {
-        let query = query_as("SELECT name, path, is_default, is_kms FROM vault");
+        let query = query_as(
+            r#"
+        SELECT name, path, is_default, is_kms, pkcs11_module, pkcs11_slot, pkcs11_pin_env,
+            encryption_method, encryption_salt, encryption_key_check, encryption_idle_timeout
+            FROM vault"#,
+        );
         let rows: Vec<VaultRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
         rows.iter().map(|r| r.named_vault()).collect()
     }
@@ -95,12 +139,56 @@ impl VaultsRepository for VaultsSqlxDatabase {
 
 // Database serialization / deserialization
 
+fn pkcs11_module(vault_type: &VaultType) -> Option<String> {
+    vault_type
+        .pkcs11()
+        .map(|p| p.module_path().to_string_lossy().to_string())
+}
+
+fn pkcs11_slot(vault_type: &VaultType) -> Option<i64> {
+    vault_type.pkcs11().map(|p| p.slot() as i64)
+}
+
+fn pkcs11_pin_env(vault_type: &VaultType) -> Option<String> {
+    vault_type
+        .pkcs11()
+        .and_then(|p| p.pin_env_var().map(|v| v.to_string()))
+}
+
+fn encryption_method(vault_type: &VaultType) -> Option<String> {
+    vault_type
+        .encryption()
+        .map(|e| e.method().name().to_string())
+}
+
+fn encryption_salt(vault_type: &VaultType) -> Option<Vec<u8>> {
+    vault_type.encryption().map(|e| e.salt().to_vec())
+}
+
+fn encryption_key_check(vault_type: &VaultType) -> Option<Vec<u8>> {
+    vault_type.encryption().map(|e| e.key_check().to_vec())
+}
+
+fn encryption_idle_timeout(vault_type: &VaultType) -> Option<i64> {
+    vault_type
+        .encryption()
+        .and_then(|e| e.idle_timeout())
+        .map(|t| t.as_secs() as i64)
+}
+
 #[derive(FromRow)]
 pub(crate) struct VaultRow {
     name: String,
     path: Nullable<String>,
     is_default: Boolean,
     is_kms: Boolean,
+    pkcs11_module: Nullable<String>,
+    pkcs11_slot: Nullable<i64>,
+    pkcs11_pin_env: Nullable<String>,
+    encryption_method: Nullable<String>,
+    encryption_salt: Nullable<Vec<u8>>,
+    encryption_key_check: Nullable<Vec<u8>>,
+    encryption_idle_timeout: Nullable<i64>,
 }
 
 impl VaultRow {
@@ -113,15 +201,41 @@ impl VaultRow {
     }
 
     pub(crate) fn vault_type(&self) -> VaultType {
-        match self.path.to_option() {
+        let vault_type = match self.path.to_option() {
             None => VaultType::database(UseAwsKms::from(self.is_kms.to_bool())),
             Some(p) => VaultType::local_file(
                 PathBuf::from(p).as_path(),
                 UseAwsKms::from(self.is_kms.to_bool()),
             ),
+        };
+        vault_type
+            .with_pkcs11(self.pkcs11())
+            .with_encryption(self.encryption())
+    }
+
+    pub(crate) fn pkcs11(&self) -> Option<Pkcs11VaultConfig> {
+        match (self.pkcs11_module.to_option(), self.pkcs11_slot.to_option()) {
+            (Some(module), Some(slot)) => Some(Pkcs11VaultConfig::new(
+                module,
+                slot as u64,
+                self.pkcs11_pin_env.to_option(),
+            )),
+            _ => None,
         }
     }
 
+    pub(crate) fn encryption(&self) -> Option<VaultEncryption> {
+        let method = self.encryption_method.to_option()?;
+        Some(VaultEncryption::new(
+            VaultEncryptionMethod::from_name(&method)?,
+            self.encryption_salt.to_option().unwrap_or_default(),
+            self.encryption_key_check.to_option().unwrap_or_default(),
+            self.encryption_idle_timeout
+                .to_option()
+                .map(|t| Duration::from_secs(t as u64)),
+        ))
+    }
+
     pub(crate) fn is_default(&self) -> bool {
         self.is_default.to_bool()
     }
@@ -187,4 +301,74 @@ mod test {
         })
         .await
     }
+
+    #[tokio::test]
+    async fn test_store_pkcs11_vault() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn VaultsRepository> = Arc::new(VaultsSqlxDatabase::new(db));
+
+            // It is possible to create a vault storing its signing keys in a PKCS#11 token
+            let pkcs11 = Pkcs11VaultConfig::new(
+                "/usr/lib/softhsm/libsofthsm2.so",
+                1234,
+                Some("OCKAM_PKCS11_PIN".to_string()),
+            );
+            let vault_type = VaultType::local_file("path", UseAwsKms::No);
+            let vault_type = vault_type.with_pkcs11(Some(pkcs11));
+            let hsm = repository.store_vault("hsm", vault_type.clone()).await?;
+            let expected = NamedVault::new("hsm", vault_type.clone(), true);
+            assert_eq!(hsm, expected);
+
+            // The token configuration is retrieved with the vault
+            let result = repository.get_named_vault("hsm").await?;
+            assert_eq!(result, Some(expected));
+
+            // The token configuration can be removed
+            let vault_type = VaultType::local_file("path", UseAwsKms::No);
+            repository.update_vault("hsm", vault_type.clone()).await?;
+            let result = repository.get_named_vault("hsm").await?;
+            assert_eq!(result, Some(NamedVault::new("hsm", vault_type, true)));
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_store_encrypted_vault() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn VaultsRepository> = Arc::new(VaultsSqlxDatabase::new(db));
+
+            // It is possible to create a vault encrypting its secrets at rest
+            let encryption = VaultEncryption::new(
+                VaultEncryptionMethod::Passphrase,
+                vec![1, 2, 3],
+                vec![4, 5, 6],
+                Some(Duration::from_secs(900)),
+            );
+            let vault_type = VaultType::database(UseAwsKms::No);
+            let vault_type = vault_type.with_encryption(Some(encryption));
+            let encrypted = repository
+                .store_vault("encrypted", vault_type.clone())
+                .await?;
+            let expected = NamedVault::new("encrypted", vault_type, true);
+            assert_eq!(encrypted, expected);
+
+            // The encryption is retrieved with the vault
+            let result = repository.get_named_vault("encrypted").await?;
+            assert_eq!(result, Some(expected));
+
+            // A vault can be encrypted with a key stored in the keychain, without idle timeout
+            let encryption =
+                VaultEncryption::new(VaultEncryptionMethod::Keychain, vec![], vec![7, 8], None);
+            let vault_type = VaultType::local_file("path", UseAwsKms::No);
+            let vault_type = vault_type.with_encryption(Some(encryption));
+            repository
+                .update_vault("encrypted", vault_type.clone())
+                .await?;
+            let result = repository.get_named_vault("encrypted").await?;
+            assert_eq!(result, Some(NamedVault::new("encrypted", vault_type, true)));
+            Ok(())
+        })
+        .await
+    }
 }
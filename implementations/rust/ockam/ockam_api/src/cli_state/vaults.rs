@@ -4,22 +4,40 @@ use std::fmt::{Debug, Display, Formatter};
 use std::fs::OpenOptions;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
-use ockam::identity::{Identities, Vault};
+use ockam::identity::{Identities, Identity, Vault};
+use ockam_core::env::get_env;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_node::database::SqlxDatabase;
+use ockam_vault::storage::{
+    SecretsEncryptionKey, SecretsLock, SecretsRepository, SecretsSqlxDatabase, VaultBackup,
+};
+use ockam_vault::{SigningKeyType, VaultForSigning};
 use ockam_vault_aws::AwsSigningVault;
+#[cfg(feature = "pkcs11")]
+use ockam_vault_pkcs11::{Pkcs11Config, Pkcs11SigningVault};
 
-use crate::cli_state::{random_name, CliState, CliStateError, Result};
+use crate::cli_state::{random_name, CliState, CliStateError, NamedIdentity, Result};
 use crate::colors::color_primary;
 use crate::output::Output;
 use crate::{fmt_log, fmt_ok};
+use keychain::{
+    check_keychain_support, delete_keychain_key, read_keychain_key, store_keychain_key,
+};
 
 static DEFAULT_VAULT_NAME: &str = "default";
 
+/// Environment variable used to unlock the vaults encrypted with a passphrase
+pub const OCKAM_VAULT_PASSPHRASE: &str = "OCKAM_VAULT_PASSPHRASE";
+
+/// Environment variable used to provide the recovery key of a vault backup
+pub const OCKAM_VAULT_RECOVERY_KEY: &str = "OCKAM_VAULT_RECOVERY_KEY";
+
 /// The methods below support the creation and update of local vaults
 ///
 ///  - by default private keys are stored locally but they can also be stored in a KMS
+///    or in a PKCS#11 token
 ///  - keys stored locally are stored with other application data in the local database if the default vault is used
 ///  - any additional vault stores its keys in a separate file
 ///
@@ -35,44 +53,145 @@ impl CliState {
         path: Option<PathBuf>,
         use_aws_kms: UseAwsKms,
     ) -> Result<NamedVault> {
-        let vaults_repository = self.vaults_repository();
+        self.create_vault(vault_name, path, use_aws_kms, None).await
+    }
 
-        // determine the vault name to use if not given by the user
-        let vault_name = match vault_name {
-            Some(vault_name) => vault_name.clone(),
-            None => self.make_vault_name().await?,
+    /// Create a vault storing its signing keys in a PKCS#11 token.
+    /// The other secrets are stored like the secrets of a vault created with `create_named_vault`.
+    ///
+    /// The token is opened once to check its configuration before the vault is stored
+    #[instrument(skip_all, fields(vault_name = vault_name.clone()))]
+    pub async fn create_named_pkcs11_vault(
+        &self,
+        vault_name: Option<String>,
+        path: Option<PathBuf>,
+        pkcs11: Pkcs11VaultConfig,
+    ) -> Result<NamedVault> {
+        pkcs11.make_signing_vault().await?;
+        self.create_vault(vault_name, path, UseAwsKms::No, Some(pkcs11))
+            .await
+    }
+
+    /// Create a vault encrypting its secrets at rest.
+    ///
+    /// The secrets are encrypted with a key derived from the passphrase if one is given,
+    /// otherwise with a random key stored in the keychain of the operating system.
+    /// If an idle timeout is given, the vault is locked again after not being used for that
+    /// duration and must be unlocked with [`CliState::unlock_named_vault`]
+    #[instrument(skip_all, fields(vault_name = vault_name.clone()))]
+    pub async fn create_named_encrypted_vault(
+        &self,
+        vault_name: Option<String>,
+        path: Option<PathBuf>,
+        use_aws_kms: UseAwsKms,
+        passphrase: Option<String>,
+        idle_timeout: Option<Duration>,
+    ) -> Result<NamedVault> {
+        let (method, salt, key) = match passphrase {
+            Some(passphrase) => {
+                let salt = SecretsEncryptionKey::random_salt();
+                let key = SecretsEncryptionKey::from_passphrase(&passphrase, &salt)?;
+                (VaultEncryptionMethod::Passphrase, salt, key)
+            }
+            None => {
+                check_keychain_support()?;
+                (
+                    VaultEncryptionMethod::Keychain,
+                    vec![],
+                    SecretsEncryptionKey::random(),
+                )
+            }
         };
+        let encryption = VaultEncryption::new(method, salt, key.make_key_check()?, idle_timeout);
 
-        // verify that a vault with that name does not exist
-        if vaults_repository
-            .get_named_vault(&vault_name)
-            .await?
-            .is_some()
-        {
-            return Err(CliStateError::AlreadyExists {
-                resource: "vault".to_string(),
-                name: vault_name.to_string(),
-            });
+        let named_vault = self
+            .create_vault(vault_name, path, use_aws_kms, None)
+            .await?;
+        let vault_name = named_vault.name();
+        if method == VaultEncryptionMethod::Keychain {
+            if let Err(e) = store_keychain_key(&vault_name, &key) {
+                self.delete_named_vault(&vault_name).await?;
+                return Err(e);
+            }
         }
 
-        // Determine if the vault needs to be created at a specific path
-        // or if data can be stored in the main database directly
-        match path {
-            None => match self.vaults_repository().get_database_vault().await? {
-                None => Ok(vaults_repository
-                    .store_vault(&vault_name, VaultType::database(use_aws_kms))
-                    .await?),
-                Some(_) => {
-                    let path = self.make_vault_path(&vault_name);
-                    Ok(self
-                        .create_local_vault(vault_name, &path, use_aws_kms)
-                        .await?)
-                }
-            },
-            Some(path) => Ok(self
-                .create_local_vault(vault_name, &path, use_aws_kms)
-                .await?),
+        let vault_type = named_vault.vault_type().with_encryption(Some(encryption));
+        self.vaults_repository()
+            .update_vault(&vault_name, vault_type.clone())
+            .await?;
+        let named_vault = NamedVault::new(&vault_name, vault_type, named_vault.is_default());
+        if let Some(encryption) = named_vault.encryption() {
+            self.secrets_lock(&vault_name, encryption).unlock(key);
+        }
+        Ok(named_vault)
+    }
+
+    /// Unlock the secrets of an encrypted vault and return true if the vault was locked.
+    ///
+    /// A passphrase is required if the vault is encrypted with a passphrase.
+    /// The vaults already created by this process with [`CliState::make_vault`] can use their
+    /// secrets again, until they are locked again after being idle
+    #[instrument(skip_all, fields(vault_name = vault_name))]
+    pub async fn unlock_named_vault(
+        &self,
+        vault_name: &str,
+        passphrase: Option<String>,
+    ) -> Result<bool> {
+        let named_vault = self.get_named_vault(vault_name).await?;
+        let encryption = named_vault.encryption().ok_or_else(|| {
+            ockam_core::Error::new(
+                Origin::Api,
+                Kind::Invalid,
+                format!("The vault {vault_name} is not encrypted"),
+            )
+        })?;
+        let key = encryption.make_key(vault_name, passphrase)?;
+        let lock = self.secrets_lock(vault_name, encryption);
+        let was_locked = lock.is_locked();
+        lock.unlock(key);
+        Ok(was_locked)
+    }
+
+    /// Export the secrets of a vault, wrapped with a recovery key.
+    ///
+    /// The vaults storing their signing keys in a KMS or in a PKCS#11 token cannot be backed up
+    #[instrument(skip_all, fields(vault_name = vault_name))]
+    pub async fn backup_named_vault(
+        &self,
+        vault_name: &str,
+        recovery_key: &str,
+    ) -> Result<VaultBackup> {
+        let named_vault = self.get_named_vault(vault_name).await?;
+        if named_vault.use_aws_kms() || named_vault.pkcs11().is_some() {
+            return Err(ockam_core::Error::new(
+                Origin::Api,
+                Kind::Unsupported,
+                format!(
+                    "The vault {vault_name} cannot be backed up: its signing keys are stored \
+                    outside of the vault"
+                ),
+            ))?;
         }
+        let secrets_repository = self.make_secrets_repository(&named_vault).await?;
+        Ok(VaultBackup::export(secrets_repository.as_ref(), recovery_key).await?)
+    }
+
+    /// Restore the secrets of a backup into a vault and return the number of restored secrets.
+    ///
+    /// Nothing is restored if the recovery key is incorrect, if the backup is corrupted,
+    /// or if the vault already contains different secrets for some of the key ids of the backup
+    #[instrument(skip_all, fields(vault_name = vault_name))]
+    pub async fn restore_named_vault(
+        &self,
+        vault_name: &str,
+        backup: &VaultBackup,
+        recovery_key: &str,
+    ) -> Result<usize> {
+        let named_vault = self.get_named_vault(vault_name).await?;
+        let secrets_repository = self.make_secrets_repository(&named_vault).await?;
+        Ok(backup
+            .restore(secrets_repository.as_ref(), recovery_key)
+            .await?)
     }
 
     /// Delete an existing vault
@@ -103,6 +222,12 @@ impl CliState {
         let vault = repository.get_named_vault(vault_name).await?;
         if let Some(vault) = vault {
             repository.delete_named_vault(vault_name).await?;
+            self.vault_locks().lock().unwrap().remove(vault_name);
+            if let Some(encryption) = vault.encryption() {
+                if encryption.method() == VaultEncryptionMethod::Keychain {
+                    let _ = delete_keychain_key(vault_name);
+                }
+            }
             match vault.vault_type {
                 VaultType::DatabaseVault { .. } => {
                     self.purpose_keys_repository().delete_all().await?;
@@ -199,6 +324,7 @@ impl CliState {
                     vault_name.to_string(),
                     &self.make_vault_path(vault_name),
                     UseAwsKms::No,
+                    None,
                 )
                 .await?;
             self.notify_message(fmt_ok!(
@@ -268,13 +394,16 @@ impl CliState {
             VaultType::LocalFileVault {
                 path: old_path,
                 use_aws_kms,
+                pkcs11,
+                encryption,
             } => {
                 // copy the file to the new location
                 std::fs::copy(&old_path, path)?;
                 // update the path in the database
-                repository
-                    .update_vault(vault_name, VaultType::local_file(path, use_aws_kms))
-                    .await?;
+                let vault_type = VaultType::local_file(path, use_aws_kms)
+                    .with_pkcs11(pkcs11)
+                    .with_encryption(encryption);
+                repository.update_vault(vault_name, vault_type).await?;
                 // remove the old file
                 std::fs::remove_file(old_path)?;
             }
@@ -282,27 +411,147 @@ impl CliState {
         Ok(())
     }
 
-    /// Make a concrete vault based on the NamedVault metadata
-    #[instrument(skip_all, fields(vault_name = named_vault.name))]
-    pub async fn make_vault(&self, named_vault: NamedVault) -> Result<Vault> {
-        let db = match named_vault.vault_type {
-            VaultType::DatabaseVault { .. } => self.database(),
-            VaultType::LocalFileVault { ref path, .. } =>
-            // TODO: Avoid creating multiple dbs with the same file
-            {
-                SqlxDatabase::create_sqlite(path.as_path()).await?
+    /// Migrate the identities using a vault to another vault.
+    /// For example this allows to move identity keys from a software vault to an AWS KMS vault.
+    ///
+    /// Secret keys can not be exported from all vaults, so for each identity of the source vault:
+    ///
+    ///  - a new key is created in the target vault and added to the identity change history.
+    ///    That change is signed with the previous key and the identity keeps the same identifier
+    ///  - the new change history is verified, as well as a signature made with the new key
+    ///  - the change history is stored and the identity is associated to the target vault.
+    ///    If this fails, the previous change history is restored
+    ///  - the previous key is deleted from the source vault
+    ///
+    /// Return the migrated identities
+    #[instrument(skip_all, fields(from = from, to = to))]
+    pub async fn migrate_vault(&self, from: &str, to: &str) -> Result<Vec<NamedIdentity>> {
+        if from == to {
+            return Err(ockam_core::Error::new(
+                Origin::Api,
+                Kind::Invalid,
+                format!("The vault {from} cannot be migrated to itself"),
+            ))?;
+        }
+        let source = self.get_named_vault(from).await?;
+        let target = self.get_named_vault(to).await?;
+        let use_aws_kms = target.use_aws_kms();
+        let key_type = || {
+            if use_aws_kms {
+                SigningKeyType::ECDSASHA256CurveP256
+            } else {
+                SigningKeyType::EdDSACurve25519
             }
         };
 
-        if named_vault.vault_type.use_aws_kms() {
-            let mut vault = Vault::create_with_database(db);
+        let source_vault = self.make_vault(source).await?;
+        let source_identities = self.make_identities(source_vault.clone()).await?;
+        let target_vault = self.make_vault(target).await?;
+        let target_identities = self.make_identities(target_vault.clone()).await?;
+
+        let identities_repository = self.identities_repository();
+        let change_history_repository = self.change_history_repository();
+        let mut migrated = vec![];
+        for named_identity in identities_repository
+            .get_named_identities_by_vault_name(from)
+            .await?
+        {
+            let identifier = named_identity.identifier();
+            let identity = source_identities.get_identity(&identifier).await?;
+            let previous_key = source_identities
+                .identities_keys()
+                .get_secret_key(&identity)
+                .await?;
+
+            // create the new key in the target vault and check that it can be used
+            let options = target_identities
+                .identities_creation()
+                .identity_builder()
+                .with_random_key(key_type())
+                .build_options()
+                .await?;
+            let new_key = options.signing_secret_key_handle().clone();
+            let migrated_identity = source_identities
+                .identities_keys()
+                .rotate_key_to_vault(
+                    identity.clone(),
+                    options,
+                    target_vault.identity_vault.clone(),
+                )
+                .await?;
+            let migrated_identity = Identity::import_from_change_history(
+                Some(&identifier),
+                migrated_identity.change_history().clone(),
+                target_vault.verifying_vault.clone(),
+            )
+            .await?;
+            let data = identifier.to_string();
+            let signature = target_vault
+                .identity_vault
+                .sign(&new_key, data.as_bytes())
+                .await?;
+            if !target_vault
+                .verifying_vault
+                .verify_signature(
+                    &migrated_identity.get_latest_public_key()?,
+                    data.as_bytes(),
+                    &signature,
+                )
+                .await?
+            {
+                return Err(ockam_core::Error::new(
+                    Origin::Api,
+                    Kind::Invalid,
+                    format!("The new key of the identity {identifier} can not be verified"),
+                ))?;
+            }
+
+            // store the new change history and associate the identity to the target vault
+            change_history_repository
+                .store_change_history(&identifier, migrated_identity.change_history().clone())
+                .await?;
+            match identities_repository
+                .store_named_identity(&identifier, &named_identity.name(), to)
+                .await
+            {
+                Ok(identity) => migrated.push(identity),
+                Err(e) => {
+                    change_history_repository
+                        .store_change_history(&identifier, identity.change_history().clone())
+                        .await?;
+                    return Err(e)?;
+                }
+            }
+
+            // the previous key is not needed anymore
+            if source_vault
+                .identity_vault
+                .delete_signing_secret_key(previous_key)
+                .await
+                .is_err()
+            {
+                warn!("The previous key of the identity {identifier} could not be deleted from the vault {from}");
+            }
+        }
+        Ok(migrated)
+    }
+
+    /// Make a concrete vault based on the NamedVault metadata
+    #[instrument(skip_all, fields(vault_name = named_vault.name))]
+    pub async fn make_vault(&self, named_vault: NamedVault) -> Result<Vault> {
+        let secrets_repository = self.make_secrets_repository(&named_vault).await?;
+        let mut vault = Vault::create_with_secrets_repository(secrets_repository);
+
+        if let Some(pkcs11) = named_vault.vault_type.pkcs11() {
+            let pkcs11_vault = pkcs11.make_signing_vault().await?;
+            vault.identity_vault = pkcs11_vault.clone();
+            vault.credential_vault = pkcs11_vault;
+        } else if named_vault.vault_type.use_aws_kms() {
             let aws_vault = Arc::new(AwsSigningVault::create().await?);
             vault.identity_vault = aws_vault.clone();
             vault.credential_vault = aws_vault;
-            Ok(vault)
-        } else {
-            Ok(Vault::create_with_database(db))
         }
+        Ok(vault)
     }
 }
 
@@ -318,6 +567,58 @@ impl CliState {
 
 /// Private functions
 impl CliState {
+    /// Create a vault with a given name, storing its signing keys in a PKCS#11 token if a
+    /// configuration is given
+    async fn create_vault(
+        &self,
+        vault_name: Option<String>,
+        path: Option<PathBuf>,
+        use_aws_kms: UseAwsKms,
+        pkcs11: Option<Pkcs11VaultConfig>,
+    ) -> Result<NamedVault> {
+        let vaults_repository = self.vaults_repository();
+
+        // determine the vault name to use if not given by the user
+        let vault_name = match vault_name {
+            Some(vault_name) => vault_name.clone(),
+            None => self.make_vault_name().await?,
+        };
+
+        // verify that a vault with that name does not exist
+        if vaults_repository
+            .get_named_vault(&vault_name)
+            .await?
+            .is_some()
+        {
+            return Err(CliStateError::AlreadyExists {
+                resource: "vault".to_string(),
+                name: vault_name.to_string(),
+            });
+        }
+
+        // Determine if the vault needs to be created at a specific path
+        // or if data can be stored in the main database directly
+        match path {
+            None => match self.vaults_repository().get_database_vault().await? {
+                None => Ok(vaults_repository
+                    .store_vault(
+                        &vault_name,
+                        VaultType::database(use_aws_kms).with_pkcs11(pkcs11),
+                    )
+                    .await?),
+                Some(_) => {
+                    let path = self.make_vault_path(&vault_name);
+                    Ok(self
+                        .create_local_vault(vault_name, &path, use_aws_kms, pkcs11)
+                        .await?)
+                }
+            },
+            Some(path) => Ok(self
+                .create_local_vault(vault_name, &path, use_aws_kms, pkcs11)
+                .await?),
+        }
+    }
+
     /// Create the database vault if it doesn't exist already
     async fn create_database_vault(
         &self,
@@ -342,6 +643,7 @@ impl CliState {
         vault_name: String,
         path: &PathBuf,
         use_aws_kms: UseAwsKms,
+        pkcs11: Option<Pkcs11VaultConfig>,
     ) -> Result<NamedVault> {
         // check if the new file can be created
         let path_taken = self
@@ -363,9 +665,10 @@ impl CliState {
                 .create_new(true)
                 .open(path)?;
         };
+        let vault_type = VaultType::local_file(path, use_aws_kms);
         Ok(self
             .vaults_repository()
-            .store_vault(&vault_name, VaultType::local_file(path, use_aws_kms))
+            .store_vault(&vault_name, vault_type.with_pkcs11(pkcs11))
             .await?)
     }
 
@@ -384,6 +687,68 @@ impl CliState {
         }
     }
 
+    /// Return the repository storing the secrets of a vault.
+    /// The secrets of an encrypted vault are decrypted with its unlocked key
+    pub(super) async fn make_secrets_repository(
+        &self,
+        named_vault: &NamedVault,
+    ) -> Result<Arc<dyn SecretsRepository>> {
+        let db = match named_vault.vault_type {
+            VaultType::DatabaseVault { .. } => self.database(),
+            VaultType::LocalFileVault { ref path, .. } =>
+            // TODO: Avoid creating multiple dbs with the same file
+            {
+                SqlxDatabase::create_sqlite(path.as_path()).await?
+            }
+        };
+
+        let secrets_repository = SecretsSqlxDatabase::new(db);
+        Ok(match named_vault.encryption() {
+            Some(encryption) => {
+                let lock = self.unlocked_secrets_lock(&named_vault.name, encryption)?;
+                Arc::new(secrets_repository.with_encryption(lock))
+            }
+            None => Arc::new(secrets_repository),
+        })
+    }
+
+    /// Return the lock protecting the secrets of an encrypted vault.
+    /// The same lock is shared by all the vaults created for that vault name
+    fn secrets_lock(&self, vault_name: &str, encryption: &VaultEncryption) -> SecretsLock {
+        let vault_locks = self.vault_locks();
+        let mut vault_locks = vault_locks.lock().unwrap();
+        vault_locks
+            .entry(vault_name.to_string())
+            .or_insert_with(|| SecretsLock::new(encryption.idle_timeout()))
+            .clone()
+    }
+
+    /// Return the lock protecting the secrets of an encrypted vault.
+    /// If the vault is locked, it is unlocked with the key stored in the keychain or with the
+    /// passphrase set with $OCKAM_VAULT_PASSPHRASE
+    fn unlocked_secrets_lock(
+        &self,
+        vault_name: &str,
+        encryption: &VaultEncryption,
+    ) -> Result<SecretsLock> {
+        let lock = self.secrets_lock(vault_name, encryption);
+        if lock.is_locked() {
+            let passphrase: Option<String> = get_env(OCKAM_VAULT_PASSPHRASE)?;
+            if encryption.method() == VaultEncryptionMethod::Passphrase && passphrase.is_none() {
+                return Err(ockam_core::Error::new(
+                    Origin::Api,
+                    Kind::Invalid,
+                    format!(
+                        "The vault {vault_name} is encrypted. \
+                        Its passphrase must be set with ${OCKAM_VAULT_PASSPHRASE}"
+                    ),
+                ))?;
+            }
+            lock.unlock(encryption.make_key(vault_name, passphrase)?);
+        }
+        Ok(lock)
+    }
+
     /// Decide which path to use for a vault path:
     ///   - otherwise return a new path alongside the database $OCKAM_HOME/vault-{vault_name}
     fn make_vault_path(&self, vault_name: &str) -> PathBuf {
@@ -402,10 +767,18 @@ pub struct NamedVault {
 pub enum VaultType {
     DatabaseVault {
         use_aws_kms: UseAwsKms,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pkcs11: Option<Pkcs11VaultConfig>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        encryption: Option<VaultEncryption>,
     },
     LocalFileVault {
         path: PathBuf,
         use_aws_kms: UseAwsKms,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pkcs11: Option<Pkcs11VaultConfig>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        encryption: Option<VaultEncryption>,
     },
 }
 
@@ -422,6 +795,12 @@ impl Display for VaultType {
         if self.use_aws_kms() {
             writeln!(f, "Uses AWS KMS: true",)?;
         }
+        if let Some(pkcs11) = self.pkcs11() {
+            writeln!(f, "Uses PKCS#11: {pkcs11}")?;
+        }
+        if let Some(encryption) = self.encryption() {
+            writeln!(f, "Encrypted: {encryption}")?;
+        }
         Ok(())
     }
 }
@@ -444,16 +823,40 @@ impl UseAwsKms {
 
 impl VaultType {
     pub fn database(use_aws_kms: UseAwsKms) -> Self {
-        VaultType::DatabaseVault { use_aws_kms }
+        VaultType::DatabaseVault {
+            use_aws_kms,
+            pkcs11: None,
+            encryption: None,
+        }
     }
 
     pub fn local_file(path: impl Into<PathBuf>, use_aws_kms: UseAwsKms) -> Self {
         VaultType::LocalFileVault {
             path: path.into(),
             use_aws_kms,
+            pkcs11: None,
+            encryption: None,
         }
     }
 
+    /// Store the signing keys of the vault in a PKCS#11 token
+    pub fn with_pkcs11(mut self, pkcs11_config: Option<Pkcs11VaultConfig>) -> Self {
+        match &mut self {
+            VaultType::DatabaseVault { pkcs11, .. } => *pkcs11 = pkcs11_config,
+            VaultType::LocalFileVault { pkcs11, .. } => *pkcs11 = pkcs11_config,
+        }
+        self
+    }
+
+    /// Encrypt the secrets of the vault at rest
+    pub fn with_encryption(mut self, vault_encryption: Option<VaultEncryption>) -> Self {
+        match &mut self {
+            VaultType::DatabaseVault { encryption, .. } => *encryption = vault_encryption,
+            VaultType::LocalFileVault { encryption, .. } => *encryption = vault_encryption,
+        }
+        self
+    }
+
     pub fn path(&self) -> Option<&Path> {
         match self {
             VaultType::DatabaseVault { .. } => None,
@@ -463,13 +866,313 @@ impl VaultType {
 
     pub fn use_aws_kms(&self) -> bool {
         match self {
-            VaultType::DatabaseVault { use_aws_kms } => use_aws_kms == &UseAwsKms::Yes,
-            VaultType::LocalFileVault {
-                path: _,
-                use_aws_kms,
-            } => use_aws_kms == &UseAwsKms::Yes,
+            VaultType::DatabaseVault { use_aws_kms, .. } => use_aws_kms == &UseAwsKms::Yes,
+            VaultType::LocalFileVault { use_aws_kms, .. } => use_aws_kms == &UseAwsKms::Yes,
+        }
+    }
+
+    pub fn pkcs11(&self) -> Option<&Pkcs11VaultConfig> {
+        match self {
+            VaultType::DatabaseVault { pkcs11, .. } => pkcs11.as_ref(),
+            VaultType::LocalFileVault { pkcs11, .. } => pkcs11.as_ref(),
+        }
+    }
+
+    pub fn encryption(&self) -> Option<&VaultEncryption> {
+        match self {
+            VaultType::DatabaseVault { encryption, .. } => encryption.as_ref(),
+            VaultType::LocalFileVault { encryption, .. } => encryption.as_ref(),
+        }
+    }
+}
+
+/// Encryption of the secrets stored by a vault.
+///
+/// Neither the passphrase nor the key are stored, only the data necessary to derive the key
+/// from the passphrase and to check that a key is the correct one
+#[derive(Debug, PartialEq, Eq, Clone, serde::Serialize, serde::Deserialize)]
+pub struct VaultEncryption {
+    method: VaultEncryptionMethod,
+    salt: Vec<u8>,
+    key_check: Vec<u8>,
+    idle_timeout: Option<Duration>,
+}
+
+impl VaultEncryption {
+    /// Create a new vault encryption
+    pub fn new(
+        method: VaultEncryptionMethod,
+        salt: Vec<u8>,
+        key_check: Vec<u8>,
+        idle_timeout: Option<Duration>,
+    ) -> Self {
+        Self {
+            method,
+            salt,
+            key_check,
+            idle_timeout,
+        }
+    }
+
+    /// Return the way the encryption key is obtained
+    pub fn method(&self) -> VaultEncryptionMethod {
+        self.method
+    }
+
+    /// Return the salt used to derive the key from a passphrase
+    pub fn salt(&self) -> &[u8] {
+        self.salt.as_slice()
+    }
+
+    /// Return the value used to check the key
+    pub fn key_check(&self) -> &[u8] {
+        self.key_check.as_slice()
+    }
+
+    /// Return the duration after which an unused vault is locked again
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.idle_timeout
+    }
+
+    /// Return the encryption key of a vault, derived from a passphrase or read from the keychain.
+    /// An error is returned if the key is not the one used to encrypt the secrets
+    pub fn make_key(
+        &self,
+        vault_name: &str,
+        passphrase: Option<String>,
+    ) -> Result<SecretsEncryptionKey> {
+        let key = match self.method {
+            VaultEncryptionMethod::Passphrase => {
+                let passphrase = passphrase.ok_or_else(|| {
+                    ockam_core::Error::new(
+                        Origin::Api,
+                        Kind::Invalid,
+                        format!("A passphrase is required to unlock the vault {vault_name}"),
+                    )
+                })?;
+                SecretsEncryptionKey::from_passphrase(&passphrase, &self.salt)?
+            }
+            VaultEncryptionMethod::Keychain => read_keychain_key(vault_name)?,
+        };
+        key.verify_key_check(&self.key_check)?;
+        Ok(key)
+    }
+}
+
+impl Display for VaultEncryption {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.method {
+            VaultEncryptionMethod::Passphrase => write!(f, "with a passphrase")?,
+            VaultEncryptionMethod::Keychain => write!(f, "with a key stored in the OS keychain")?,
+        }
+        if let Some(idle_timeout) = self.idle_timeout {
+            write!(
+                f,
+                ", locked after {}s of inactivity",
+                idle_timeout.as_secs()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Way of obtaining the key encrypting the secrets of a vault
+#[derive(Debug, PartialEq, Eq, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub enum VaultEncryptionMethod {
+    /// The key is derived from a passphrase with argon2
+    Passphrase,
+    /// The key is a random key stored in the keychain of the operating system
+    Keychain,
+}
+
+impl VaultEncryptionMethod {
+    /// Return the name of the method, as stored in the database
+    pub fn name(&self) -> &'static str {
+        match self {
+            VaultEncryptionMethod::Passphrase => "passphrase",
+            VaultEncryptionMethod::Keychain => "keychain",
+        }
+    }
+
+    /// Return the method corresponding to a name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "passphrase" => Some(VaultEncryptionMethod::Passphrase),
+            "keychain" => Some(VaultEncryptionMethod::Keychain),
+            _ => None,
+        }
+    }
+}
+
+/// Access to the keys stored in the keychain of the operating system.
+///
+/// The keychain is only available with the `keyring` feature, otherwise an error is
+/// returned when a vault uses a keychain key
+#[cfg(feature = "keyring")]
+mod keychain {
+    use super::*;
+    use ockam_vault::{AeadSecret, AEAD_SECRET_LENGTH};
+
+    /// Service name used for the keychain entries of the vaults encrypted with a keychain key
+    static KEYCHAIN_SERVICE: &str = "ockam";
+
+    /// Return an error if the keychain of the operating system cannot be used
+    pub(super) fn check_keychain_support() -> Result<()> {
+        Ok(())
+    }
+
+    /// Return the keychain entry storing the encryption key of a vault
+    fn keychain_entry(vault_name: &str) -> Result<keyring::Entry> {
+        let user = format!("vault-{vault_name}");
+        let entry = keyring::Entry::new(KEYCHAIN_SERVICE, &user);
+        Ok(entry.map_err(keychain_error)?)
+    }
+
+    pub(super) fn store_keychain_key(vault_name: &str, key: &SecretsEncryptionKey) -> Result<()> {
+        let encoded = hex::encode(key.secret().0);
+        keychain_entry(vault_name)?
+            .set_password(&encoded)
+            .map_err(keychain_error)?;
+        Ok(())
+    }
+
+    pub(super) fn read_keychain_key(vault_name: &str) -> Result<SecretsEncryptionKey> {
+        let encoded = keychain_entry(vault_name)?
+            .get_password()
+            .map_err(keychain_error)?;
+        let secret: [u8; AEAD_SECRET_LENGTH] = hex::decode(encoded)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| {
+                ockam_core::Error::new(
+                    Origin::Api,
+                    Kind::Serialization,
+                    format!("The keychain entry of the vault {vault_name} is not a valid key"),
+                )
+            })?;
+        Ok(SecretsEncryptionKey::new(AeadSecret(secret)))
+    }
+
+    pub(super) fn delete_keychain_key(vault_name: &str) -> Result<()> {
+        keychain_entry(vault_name)?
+            .delete_credential()
+            .map_err(keychain_error)?;
+        Ok(())
+    }
+
+    fn keychain_error(e: keyring::Error) -> ockam_core::Error {
+        ockam_core::Error::new(
+            Origin::Api,
+            Kind::Io,
+            format!("Cannot access the keychain: {e}"),
+        )
+    }
+}
+
+#[cfg(not(feature = "keyring"))]
+mod keychain {
+    use super::*;
+
+    /// Return an error since the keychain of the operating system cannot be used
+    pub(super) fn check_keychain_support() -> Result<()> {
+        Err(keychain_unsupported())?
+    }
+
+    pub(super) fn store_keychain_key(_vault_name: &str, _key: &SecretsEncryptionKey) -> Result<()> {
+        Err(keychain_unsupported())?
+    }
+
+    pub(super) fn read_keychain_key(_vault_name: &str) -> Result<SecretsEncryptionKey> {
+        Err(keychain_unsupported())?
+    }
+
+    pub(super) fn delete_keychain_key(_vault_name: &str) -> Result<()> {
+        Err(keychain_unsupported())?
+    }
+
+    fn keychain_unsupported() -> ockam_core::Error {
+        ockam_core::Error::new(
+            Origin::Api,
+            Kind::Unsupported,
+            "Vault keys can only be stored in the OS keychain with the `keyring` feature",
+        )
+    }
+}
+
+/// Configuration of the PKCS#11 token storing the signing keys of a vault.
+///
+/// The PIN of the token is not stored, only the name of the environment variable
+/// containing it when the vault is used
+#[derive(Debug, PartialEq, Eq, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Pkcs11VaultConfig {
+    module_path: PathBuf,
+    slot: u64,
+    pin_env_var: Option<String>,
+}
+
+impl Pkcs11VaultConfig {
+    /// Create a configuration for the token present in a slot of a PKCS#11 module
+    pub fn new(module_path: impl Into<PathBuf>, slot: u64, pin_env_var: Option<String>) -> Self {
+        Self {
+            module_path: module_path.into(),
+            slot,
+            pin_env_var,
         }
     }
+
+    /// Return the path of the PKCS#11 module
+    pub fn module_path(&self) -> &Path {
+        self.module_path.as_path()
+    }
+
+    /// Return the slot of the token
+    pub fn slot(&self) -> u64 {
+        self.slot
+    }
+
+    /// Return the name of the environment variable containing the user PIN, if a login is needed
+    pub fn pin_env_var(&self) -> Option<&str> {
+        self.pin_env_var.as_deref()
+    }
+
+    /// Open the token and return a vault for its signing keys.
+    /// The PIN is read from its environment variable
+    #[cfg(feature = "pkcs11")]
+    pub async fn make_signing_vault(&self) -> Result<Arc<dyn VaultForSigning>> {
+        let mut config = Pkcs11Config::new(&self.module_path, self.slot);
+        if let Some(pin_env_var) = &self.pin_env_var {
+            let pin: String = get_env(pin_env_var)?.ok_or_else(|| {
+                ockam_core::Error::new(
+                    Origin::Api,
+                    Kind::NotFound,
+                    format!("The PIN of the PKCS#11 token must be set with ${pin_env_var}"),
+                )
+            })?;
+            config = config.with_pin(pin);
+        }
+        Ok(Arc::new(Pkcs11SigningVault::create(config).await?))
+    }
+
+    /// Return an error since PKCS#11 tokens are only supported with the `pkcs11` feature
+    #[cfg(not(feature = "pkcs11"))]
+    pub async fn make_signing_vault(&self) -> Result<Arc<dyn VaultForSigning>> {
+        Err(ockam_core::Error::new(
+            Origin::Api,
+            Kind::Unsupported,
+            "PKCS#11 vaults are only supported with the `pkcs11` feature",
+        ))?
+    }
+}
+
+impl Display for Pkcs11VaultConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "module {}, slot {}",
+            self.module_path.to_string_lossy(),
+            self.slot
+        )
+    }
 }
 
 impl NamedVault {
@@ -502,6 +1205,16 @@ impl NamedVault {
         self.vault_type.use_aws_kms()
     }
 
+    /// Return the configuration of the PKCS#11 token storing the signing keys, if any
+    pub fn pkcs11(&self) -> Option<&Pkcs11VaultConfig> {
+        self.vault_type.pkcs11()
+    }
+
+    /// Return the encryption of the secrets of the vault, if they are encrypted at rest
+    pub fn encryption(&self) -> Option<&VaultEncryption> {
+        self.vault_type.encryption()
+    }
+
     /// Return the vault path if the vault data is stored in a local file
     pub fn path(&self) -> Option<&Path> {
         self.vault_type.path()
@@ -538,6 +1251,12 @@ impl Output for NamedVault {
         if self.vault_type.use_aws_kms() {
             writeln!(output, "Uses AWS KMS: true",)?;
         }
+        if let Some(pkcs11) = self.vault_type.pkcs11() {
+            writeln!(output, "Uses PKCS#11: {pkcs11}")?;
+        }
+        if let Some(encryption) = self.vault_type.encryption() {
+            writeln!(output, "Encrypted: {encryption}")?;
+        }
         Ok(output)
     }
 }
@@ -695,6 +1414,42 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_migrate_vault() -> Result<()> {
+        let cli = CliState::test().await?;
+
+        // create an identity in a first vault
+        let _ = cli.get_or_create_named_vault("vault1").await?;
+        let identity = cli
+            .create_identity_with_name_and_vault("name", "vault1")
+            .await?;
+        let before = cli.get_identity(&identity.identifier()).await?;
+
+        // migrate it to a second vault
+        let _ = cli.get_or_create_named_vault("vault2").await?;
+        let migrated = cli.migrate_vault("vault1", "vault2").await?;
+        assert_eq!(migrated.len(), 1);
+        assert_eq!(migrated[0].vault_name(), "vault2");
+        assert_eq!(cli.get_named_identity("name").await?.vault_name(), "vault2");
+
+        // the identity keeps its identifier and its latest key is now in the second vault
+        let after = cli.get_identity(&identity.identifier()).await?;
+        assert_eq!(after.identifier(), before.identifier());
+        assert_eq!(after.changes().len(), before.changes().len() + 1);
+        let vault2 = cli.make_vault(cli.get_named_vault("vault2").await?).await?;
+        let identities = cli.make_identities(vault2).await?;
+        assert!(identities
+            .identities_keys()
+            .get_secret_key(&after)
+            .await
+            .is_ok());
+
+        // a vault can not be migrated to itself
+        assert!(cli.migrate_vault("vault2", "vault2").await.is_err());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_create_vault_with_no_user_path() -> Result<()> {
         let cli = CliState::test().await?;
@@ -814,4 +1569,128 @@ mod tests {
 
         Ok(())
     }
+
+    #[cfg(not(feature = "keyring"))]
+    #[tokio::test]
+    async fn test_keychain_encrypted_vault_requires_the_keyring_feature() -> Result<()> {
+        let cli = CliState::test().await?;
+
+        let result = cli
+            .create_named_encrypted_vault(
+                Some("encrypted".to_string()),
+                None,
+                UseAwsKms::No,
+                None,
+                None,
+            )
+            .await;
+        assert!(result.is_err());
+
+        // no vault is created
+        assert!(cli.get_named_vault("encrypted").await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_vault() -> Result<()> {
+        let cli = CliState::test().await?;
+
+        // create a vault encrypted with a passphrase
+        let passphrase = "correct horse battery staple".to_string();
+        let named_vault = cli
+            .create_named_encrypted_vault(
+                Some("encrypted".to_string()),
+                None,
+                UseAwsKms::No,
+                Some(passphrase.clone()),
+                None,
+            )
+            .await?;
+        let encryption = named_vault.encryption().unwrap().clone();
+        assert_eq!(encryption.method(), VaultEncryptionMethod::Passphrase);
+
+        // the vault is unlocked after its creation
+        let vault = cli.make_vault(named_vault.clone()).await?;
+        let handle = vault
+            .identity_vault
+            .generate_signing_secret_key(SigningKeyType::EdDSACurve25519)
+            .await?;
+        vault.identity_vault.sign(&handle, b"data").await?;
+
+        // the secrets are not stored in clear
+        let result = cli.secrets_repository().get_signing_secret(&handle).await;
+        assert!(result.is_err());
+
+        // the secrets cannot be used once the vault is locked
+        cli.secrets_lock("encrypted", &encryption).lock();
+        assert!(vault.identity_vault.sign(&handle, b"data").await.is_err());
+
+        // the vault cannot be unlocked with an incorrect passphrase
+        let result = cli
+            .unlock_named_vault("encrypted", Some("incorrect".to_string()))
+            .await;
+        assert!(result.is_err());
+
+        // the vault can be unlocked with its passphrase
+        let was_locked = cli
+            .unlock_named_vault("encrypted", Some(passphrase.clone()))
+            .await?;
+        assert!(was_locked);
+        vault.identity_vault.sign(&handle, b"data").await?;
+
+        // unlocking an unlocked vault is reported
+        let was_locked = cli
+            .unlock_named_vault("encrypted", Some(passphrase))
+            .await?;
+        assert!(!was_locked);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_backup_and_restore_vault() -> Result<()> {
+        let cli = CliState::test().await?;
+
+        let source = cli
+            .create_named_vault(Some("source".to_string()), None, UseAwsKms::No)
+            .await?;
+        let source_vault = cli.make_vault(source).await?;
+        let handle = source_vault
+            .identity_vault
+            .generate_signing_secret_key(SigningKeyType::EdDSACurve25519)
+            .await?;
+        let public_key = source_vault
+            .identity_vault
+            .get_verifying_public_key(&handle)
+            .await?;
+
+        let backup = cli.backup_named_vault("source", "recovery").await?;
+
+        // the backup cannot be restored with an incorrect recovery key
+        let target = cli
+            .create_named_vault(
+                Some("target".to_string()),
+                Some(cli.make_vault_path("target")),
+                UseAwsKms::No,
+            )
+            .await?;
+        let result = cli
+            .restore_named_vault("target", &backup, "incorrect")
+            .await;
+        assert!(result.is_err());
+
+        // the keys of the source vault can be used in the target vault after a restore
+        let restored = cli
+            .restore_named_vault("target", &backup, "recovery")
+            .await?;
+        assert_eq!(restored, 1);
+        let target_vault = cli.make_vault(target).await?;
+        let restored_public_key = target_vault
+            .identity_vault
+            .get_verifying_public_key(&handle)
+            .await?;
+        assert_eq!(public_key, restored_public_key);
+
+        Ok(())
+    }
 }
@@ -365,6 +365,10 @@ impl Output for Project {
         )?;
         Ok(f)
     }
+
+    fn as_fields(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(self).ok()
+    }
 }
 
 #[cfg(test)]
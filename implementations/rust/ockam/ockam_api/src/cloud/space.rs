@@ -8,7 +8,9 @@ use ockam_core::api::Request;
 use ockam_core::async_trait;
 use ockam_node::Context;
 
+use crate::cloud::email_address::EmailAddress;
 use crate::cloud::project::{Project, ProjectsOrchestratorApi};
+use crate::cloud::share::RoleInShare;
 use crate::cloud::{ControllerClient, HasSecureClient};
 use crate::colors::OckamColor;
 use crate::nodes::InMemoryNode;
@@ -82,6 +84,50 @@ impl CreateSpace {
     }
 }
 
+/// A user who is a member of a space, with their role in that space
+#[derive(Encode, Decode, Serialize, Debug, Clone, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SpaceMember {
+    #[n(1)] pub email: EmailAddress,
+    #[n(2)] pub role: RoleInShare,
+}
+
+impl Output for SpaceMember {
+    fn item(&self) -> crate::Result<String> {
+        let mut w = String::new();
+        write!(w, "Member")?;
+        write!(w, "\n  Email: {}", self.email)?;
+        write!(w, "\n  Role: {}", self.role)?;
+        Ok(w)
+    }
+
+    fn as_list_item(&self) -> crate::Result<String> {
+        Ok(format!(
+            "{} {}",
+            self.email
+                .to_string()
+                .color(OckamColor::PrimaryResource.color()),
+            self.role
+        ))
+    }
+}
+
+#[derive(Encode, Decode, Debug)]
+#[cfg_attr(test, derive(Clone))]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct AddSpaceMember {
+    #[n(1)] pub email: EmailAddress,
+    #[n(2)] pub role: RoleInShare,
+}
+
+impl AddSpaceMember {
+    pub fn new(email: EmailAddress, role: RoleInShare) -> Self {
+        Self { email, role }
+    }
+}
+
 #[async_trait]
 pub trait Spaces {
     async fn create_space(
@@ -100,6 +146,27 @@ pub trait Spaces {
     async fn delete_space_by_name(&self, ctx: &Context, space_name: &str) -> miette::Result<()>;
 
     async fn get_spaces(&self, ctx: &Context) -> miette::Result<Vec<Space>>;
+
+    async fn add_space_member(
+        &self,
+        ctx: &Context,
+        space_id: &str,
+        email: &EmailAddress,
+        role: RoleInShare,
+    ) -> miette::Result<SpaceMember>;
+
+    async fn remove_space_member(
+        &self,
+        ctx: &Context,
+        space_id: &str,
+        email: &EmailAddress,
+    ) -> miette::Result<()>;
+
+    async fn get_space_members(
+        &self,
+        ctx: &Context,
+        space_id: &str,
+    ) -> miette::Result<Vec<SpaceMember>>;
 }
 
 #[async_trait]
@@ -201,6 +268,57 @@ impl Spaces for InMemoryNode {
         }
         Ok(spaces)
     }
+
+    #[instrument(skip_all, fields(space_id = space_id, email = %email))]
+    async fn add_space_member(
+        &self,
+        ctx: &Context,
+        space_id: &str,
+        email: &EmailAddress,
+        role: RoleInShare,
+    ) -> miette::Result<SpaceMember> {
+        let controller = self.create_controller().await?;
+        let member = controller
+            .add_space_member(ctx, space_id, email, role)
+            .await?;
+        self.get_space_members(ctx, space_id).await?;
+        Ok(member)
+    }
+
+    #[instrument(skip_all, fields(space_id = space_id, email = %email))]
+    async fn remove_space_member(
+        &self,
+        ctx: &Context,
+        space_id: &str,
+        email: &EmailAddress,
+    ) -> miette::Result<()> {
+        let controller = self.create_controller().await?;
+        controller.remove_space_member(ctx, space_id, email).await?;
+        self.get_space_members(ctx, space_id).await?;
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(space_id = space_id))]
+    async fn get_space_members(
+        &self,
+        ctx: &Context,
+        space_id: &str,
+    ) -> miette::Result<Vec<SpaceMember>> {
+        let controller = self.create_controller().await?;
+        let members = controller.list_space_members(ctx, space_id).await?;
+
+        // keep the list of users of the local space in sync with its members
+        let space = self.cli_state.get_space(space_id).await?;
+        let users: Vec<String> = members.iter().map(|m| m.email.to_string()).collect();
+        self.cli_state
+            .store_space(
+                &space.id,
+                &space.name,
+                users.iter().map(|u| u.as_ref()).collect(),
+            )
+            .await?;
+        Ok(members)
+    }
 }
 
 impl ControllerClient {
@@ -250,6 +368,52 @@ impl ControllerClient {
             .into_diagnostic()?
             .miette_success("list spaces")
     }
+
+    pub async fn add_space_member(
+        &self,
+        ctx: &Context,
+        space_id: &str,
+        email: &EmailAddress,
+        role: RoleInShare,
+    ) -> miette::Result<SpaceMember> {
+        trace!(target: TARGET, space = %space_id, email = %email, "adding space member");
+        let req = Request::post(format!("/v0/{space_id}/members"))
+            .body(AddSpaceMember::new(email.clone(), role));
+        self.get_secure_client()
+            .ask(ctx, "spaces", req)
+            .await
+            .into_diagnostic()?
+            .miette_success("add space member")
+    }
+
+    pub async fn remove_space_member(
+        &self,
+        ctx: &Context,
+        space_id: &str,
+        email: &EmailAddress,
+    ) -> miette::Result<()> {
+        trace!(target: TARGET, space = %space_id, email = %email, "removing space member");
+        let req = Request::delete(format!("/v0/{space_id}/members/{email}"));
+        self.get_secure_client()
+            .tell(ctx, "spaces", req)
+            .await
+            .into_diagnostic()?
+            .miette_success("remove space member")
+    }
+
+    pub async fn list_space_members(
+        &self,
+        ctx: &Context,
+        space_id: &str,
+    ) -> miette::Result<Vec<SpaceMember>> {
+        trace!(target: TARGET, space = %space_id, "listing space members");
+        let req = Request::get(format!("/v0/{space_id}/members"));
+        self.get_secure_client()
+            .ask(ctx, "spaces", req)
+            .await
+            .into_diagnostic()?
+            .miette_success("list space members")
+    }
 }
 
 #[cfg(test)]
@@ -273,6 +437,14 @@ pub mod tests {
         fn create_space(cs: CreateSpace) -> TestResult {
             validate_with_schema("create_space", cs)
         }
+
+        fn space_member(m: SpaceMember) -> TestResult {
+            validate_with_schema("space_member", m)
+        }
+
+        fn add_space_member(m: AddSpaceMember) -> TestResult {
+            validate_with_schema("add_space_member", m)
+        }
     }
 
     impl Arbitrary for Space {
@@ -285,6 +457,25 @@ pub mod tests {
         }
     }
 
+    impl Arbitrary for SpaceMember {
+        fn arbitrary(g: &mut Gen) -> Self {
+            SpaceMember {
+                email: EmailAddress::arbitrary(g),
+                role: g
+                    .choose(&[RoleInShare::Admin, RoleInShare::Guest, RoleInShare::Service])
+                    .unwrap()
+                    .clone(),
+            }
+        }
+    }
+
+    impl Arbitrary for AddSpaceMember {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let member = SpaceMember::arbitrary(g);
+            AddSpaceMember::new(member.email, member.role)
+        }
+    }
+
     impl Arbitrary for CreateSpace {
         fn arbitrary(g: &mut Gen) -> Self {
             CreateSpace {
@@ -0,0 +1,180 @@
+use super::dns::{DnsMessage, DnsRecord, RecordData};
+use super::socket::{bind_multicast_socket, mdns_group_address, MDNS_PORT};
+use super::DISCOVERY_SERVICE_TYPE;
+use ockam::identity::Identifier;
+use ockam_core::{async_trait, Address, DenyAll, Processor, Result};
+use ockam_node::{Context, ProcessorBuilder};
+use std::net::{IpAddr, SocketAddr};
+use tokio::net::UdpSocket;
+
+/// Time to live of the advertised records, in seconds
+const RECORDS_TTL: u32 = 120;
+/// Maximum size of a multicast DNS message
+const MAX_MESSAGE_SIZE: usize = 9000;
+
+/// Information about a node, advertised on the local network
+#[derive(Clone, Debug)]
+pub struct NodeAdvertisement {
+    node_name: String,
+    identifier: Identifier,
+    listener_address: SocketAddr,
+}
+
+impl NodeAdvertisement {
+    pub fn new(node_name: String, identifier: Identifier, listener_address: SocketAddr) -> Self {
+        Self {
+            node_name,
+            identifier,
+            listener_address,
+        }
+    }
+
+    /// The instance name must be a single DNS label, which is shorter than an identifier
+    fn instance_label(&self) -> String {
+        self.identifier.to_string().chars().take(63).collect()
+    }
+
+    /// Response advertising the node. A zero TTL tells the browsers that the node is gone
+    pub(super) fn response(&self, ttl: u32) -> DnsMessage {
+        let label = self.instance_label();
+        let instance = format!("{label}.{DISCOVERY_SERVICE_TYPE}");
+        let host = format!("{label}.local");
+
+        let mut records = vec![
+            DnsRecord {
+                name: DISCOVERY_SERVICE_TYPE.to_string(),
+                ttl,
+                data: RecordData::Ptr(instance.clone()),
+            },
+            DnsRecord {
+                name: instance.clone(),
+                ttl,
+                data: RecordData::Srv {
+                    port: self.listener_address.port(),
+                    target: host.clone(),
+                },
+            },
+            DnsRecord {
+                name: instance,
+                ttl,
+                data: RecordData::Txt(vec![
+                    format!("node={}", self.node_name),
+                    format!("identifier={}", self.identifier),
+                ]),
+            },
+        ];
+
+        // Without a specific address, the browsers use the source address of the response
+        match self.listener_address.ip() {
+            ip if ip.is_unspecified() => {}
+            IpAddr::V4(ip) => records.push(DnsRecord {
+                name: host,
+                ttl,
+                data: RecordData::A(ip),
+            }),
+            IpAddr::V6(ip) => records.push(DnsRecord {
+                name: host,
+                ttl,
+                data: RecordData::Aaaa(ip),
+            }),
+        }
+
+        DnsMessage::response(records)
+    }
+}
+
+/// Processor answering the multicast DNS queries for the nodes of the local network
+pub struct MdnsAdvertiser {
+    advertisement: NodeAdvertisement,
+    socket: UdpSocket,
+    buf: Vec<u8>,
+}
+
+impl MdnsAdvertiser {
+    /// Start advertising a node on the local network, and return the processor address
+    pub async fn start(ctx: &Context, advertisement: NodeAdvertisement) -> Result<Address> {
+        if advertisement.listener_address.ip().is_loopback() {
+            warn!(
+                "The TCP listener {} of the node {} is not reachable from the local network",
+                advertisement.listener_address, advertisement.node_name
+            );
+        }
+
+        let address = Address::random_tagged("MdnsAdvertiser");
+        let processor = Self {
+            advertisement,
+            socket: bind_multicast_socket()?,
+            buf: vec![0; MAX_MESSAGE_SIZE],
+        };
+        ProcessorBuilder::new(processor)
+            .with_address(address.clone())
+            .with_incoming_access_control(DenyAll)
+            .with_outgoing_access_control(DenyAll)
+            .start(ctx)
+            .await?;
+
+        Ok(address)
+    }
+
+    async fn send_records(&self, ttl: u32, destination: SocketAddr) -> Result<()> {
+        let response = self.advertisement.response(ttl).encode()?;
+        if let Err(err) = self.socket.send_to(&response, destination).await {
+            warn!(%err, %destination, "Failed to send an mDNS response");
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Processor for MdnsAdvertiser {
+    type Context = Context;
+
+    #[instrument(skip_all, name = "MdnsAdvertiser::initialize")]
+    async fn initialize(&mut self, _ctx: &mut Self::Context) -> Result<()> {
+        info!(
+            "advertising the node {} on the local network",
+            self.advertisement.node_name
+        );
+        self.send_records(RECORDS_TTL, mdns_group_address()).await
+    }
+
+    #[instrument(skip_all, name = "MdnsAdvertiser::shutdown")]
+    async fn shutdown(&mut self, _ctx: &mut Self::Context) -> Result<()> {
+        self.send_records(0, mdns_group_address()).await
+    }
+
+    #[instrument(skip_all, name = "MdnsAdvertiser::process")]
+    async fn process(&mut self, _ctx: &mut Self::Context) -> Result<bool> {
+        let (len, source) = match self.socket.recv_from(&mut self.buf).await {
+            Ok(received) => received,
+            Err(err) => {
+                warn!(%err, "Failed to receive an mDNS message");
+                return Ok(true);
+            }
+        };
+
+        let Some(query) = DnsMessage::decode(&self.buf[..len]) else {
+            trace!(%source, "Ignoring a malformed mDNS message");
+            return Ok(true);
+        };
+        if query.is_response
+            || !query
+                .questions
+                .iter()
+                .any(|q| q.asks_for(DISCOVERY_SERVICE_TYPE))
+        {
+            return Ok(true);
+        }
+
+        // One-shot queries, sent from another port, expect a unicast response
+        let destination = if source.port() == MDNS_PORT {
+            mdns_group_address()
+        } else {
+            source
+        };
+        debug!(%source, "Answering an mDNS query for the Ockam nodes");
+        self.send_records(RECORDS_TTL, destination).await?;
+
+        Ok(true)
+    }
+}
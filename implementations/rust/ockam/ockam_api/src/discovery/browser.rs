@@ -0,0 +1,155 @@
+use super::dns::{DnsMessage, RecordData, TYPE_PTR};
+use super::socket::{bind_query_socket, mdns_group_address};
+use super::{DiscoveredNode, DISCOVERY_SERVICE_TYPE};
+use ockam::identity::Identifier;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::Result;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::time::{timeout_at, Instant};
+
+/// Query the local network for the nodes advertising themselves, and return the nodes
+/// answering before the timeout, ordered by name
+pub async fn discover_nodes(timeout: Duration) -> Result<Vec<DiscoveredNode>> {
+    let socket = bind_query_socket()?;
+    let query = DnsMessage::query(DISCOVERY_SERVICE_TYPE, TYPE_PTR).encode()?;
+    socket
+        .send_to(&query, mdns_group_address())
+        .await
+        .map_err(|e| ockam_core::Error::new(Origin::Transport, Kind::Io, e))?;
+
+    let deadline = Instant::now() + timeout;
+    let mut buf = vec![0; 9000];
+    let mut nodes: Vec<DiscoveredNode> = vec![];
+    while let Ok(received) = timeout_at(deadline, socket.recv_from(&mut buf)).await {
+        let (len, source) = match received {
+            Ok(received) => received,
+            Err(err) => {
+                warn!(%err, "Failed to receive an mDNS response");
+                continue;
+            }
+        };
+        let Some(response) = DnsMessage::decode(&buf[..len]) else {
+            continue;
+        };
+        for node in nodes_from_response(&response, source.ip()) {
+            if !nodes.iter().any(|n| n.identifier == node.identifier) {
+                nodes.push(node);
+            }
+        }
+    }
+
+    nodes.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(nodes)
+}
+
+/// Extract the nodes advertised in a response. The address of a node is the source address
+/// of the response when the response doesn't contain an address for the node host
+fn nodes_from_response(response: &DnsMessage, source: IpAddr) -> Vec<DiscoveredNode> {
+    if !response.is_response {
+        return vec![];
+    }
+
+    let instances = response.records.iter().filter_map(|r| match &r.data {
+        RecordData::Ptr(instance) if r.ttl > 0 && r.name == DISCOVERY_SERVICE_TYPE => {
+            Some(instance)
+        }
+        _ => None,
+    });
+
+    let mut nodes = vec![];
+    for instance in instances {
+        let records = || response.records.iter().filter(|r| &r.name == instance);
+        let Some((port, target)) = records().find_map(|r| match &r.data {
+            RecordData::Srv { port, target } => Some((*port, target)),
+            _ => None,
+        }) else {
+            continue;
+        };
+        let entries: Vec<&String> = records()
+            .filter_map(|r| match &r.data {
+                RecordData::Txt(entries) => Some(entries),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        let entry = |key: &str| {
+            entries
+                .iter()
+                .find_map(|e| e.strip_prefix(key).and_then(|e| e.strip_prefix('=')))
+        };
+        let (Some(name), Some(identifier)) = (entry("node"), entry("identifier")) else {
+            continue;
+        };
+        let Ok(identifier) = Identifier::from_str(identifier) else {
+            debug!(%identifier, "Ignoring a node advertised with an invalid identifier");
+            continue;
+        };
+        let ip = response
+            .records
+            .iter()
+            .filter(|r| &r.name == target)
+            .find_map(|r| match r.data {
+                RecordData::A(ip) => Some(IpAddr::V4(ip)),
+                RecordData::Aaaa(ip) => Some(IpAddr::V6(ip)),
+                _ => None,
+            })
+            .unwrap_or(source);
+
+        nodes.push(DiscoveredNode {
+            name: name.to_string(),
+            identifier,
+            address: SocketAddr::new(ip, port),
+        });
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::NodeAdvertisement;
+    use std::net::Ipv4Addr;
+
+    const IDENTIFIER: &str = "I0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    #[test]
+    fn test_nodes_from_response() -> Result<()> {
+        let identifier = Identifier::from_str(IDENTIFIER)?;
+        let source = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20));
+
+        // The address of the listener is advertised
+        let advertisement = NodeAdvertisement::new(
+            "n1".to_string(),
+            identifier.clone(),
+            SocketAddr::from_str("192.168.1.10:4000").unwrap(),
+        );
+        let response = DnsMessage::decode(&advertisement.response(120).encode()?).unwrap();
+        let nodes = nodes_from_response(&response, source);
+        assert_eq!(
+            nodes,
+            vec![DiscoveredNode {
+                name: "n1".to_string(),
+                identifier: identifier.clone(),
+                address: SocketAddr::from_str("192.168.1.10:4000").unwrap(),
+            }]
+        );
+        assert_eq!(nodes[0].multiaddr(), "/ip4/192.168.1.10/tcp/4000");
+
+        // The listener listens on all the interfaces
+        let advertisement = NodeAdvertisement::new(
+            "n1".to_string(),
+            identifier,
+            SocketAddr::from_str("0.0.0.0:4000").unwrap(),
+        );
+        let response = DnsMessage::decode(&advertisement.response(120).encode()?).unwrap();
+        let nodes = nodes_from_response(&response, source);
+        assert_eq!(nodes[0].address, SocketAddr::new(source, 4000));
+
+        // A node which is shutting down is ignored
+        let response = DnsMessage::decode(&advertisement.response(0).encode()?).unwrap();
+        assert!(nodes_from_response(&response, source).is_empty());
+        Ok(())
+    }
+}
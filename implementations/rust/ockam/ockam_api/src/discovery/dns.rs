@@ -0,0 +1,346 @@
+//! Minimal encoding and decoding of the DNS messages exchanged with multicast DNS
+
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::Result;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+pub(super) const TYPE_A: u16 = 1;
+pub(super) const TYPE_PTR: u16 = 12;
+pub(super) const TYPE_TXT: u16 = 16;
+pub(super) const TYPE_AAAA: u16 = 28;
+pub(super) const TYPE_SRV: u16 = 33;
+pub(super) const TYPE_ANY: u16 = 255;
+
+const CLASS_IN: u16 = 1;
+/// Top bit of the class: "unicast response" for a question, "cache flush" for a record
+const CLASS_FLAG: u16 = 0x8000;
+/// Flags of a response: it is a response, and it is authoritative
+const RESPONSE_FLAGS: u16 = 0x8400;
+const HEADER_SIZE: usize = 12;
+const MAX_LABEL_SIZE: usize = 63;
+/// Maximum number of compression pointers followed when reading a name
+const MAX_POINTERS: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct DnsMessage {
+    pub(super) is_response: bool,
+    pub(super) questions: Vec<DnsQuestion>,
+    /// Records of the answer, authority and additional sections
+    pub(super) records: Vec<DnsRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct DnsQuestion {
+    pub(super) name: String,
+    pub(super) record_type: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct DnsRecord {
+    pub(super) name: String,
+    pub(super) ttl: u32,
+    pub(super) data: RecordData,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum RecordData {
+    A(Ipv4Addr),
+    Aaaa(Ipv6Addr),
+    Ptr(String),
+    Srv {
+        port: u16,
+        target: String,
+    },
+    Txt(Vec<String>),
+    /// A record of a type which is not used for the discovery of nodes
+    Other(u16),
+}
+
+impl DnsQuestion {
+    /// Return true if the question asks for the records of a name
+    pub(super) fn asks_for(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+            && (self.record_type == TYPE_PTR || self.record_type == TYPE_ANY)
+    }
+}
+
+impl RecordData {
+    fn record_type(&self) -> u16 {
+        match self {
+            RecordData::A(_) => TYPE_A,
+            RecordData::Aaaa(_) => TYPE_AAAA,
+            RecordData::Ptr(_) => TYPE_PTR,
+            RecordData::Srv { .. } => TYPE_SRV,
+            RecordData::Txt(_) => TYPE_TXT,
+            RecordData::Other(record_type) => *record_type,
+        }
+    }
+}
+
+impl DnsMessage {
+    pub(super) fn query(name: &str, record_type: u16) -> Self {
+        Self {
+            is_response: false,
+            questions: vec![DnsQuestion {
+                name: name.to_string(),
+                record_type,
+            }],
+            records: vec![],
+        }
+    }
+
+    pub(super) fn response(records: Vec<DnsRecord>) -> Self {
+        Self {
+            is_response: true,
+            questions: vec![],
+            records,
+        }
+    }
+
+    /// Encode the message, with all its records in the answer section
+    pub(super) fn encode(&self) -> Result<Vec<u8>> {
+        let mut data = Vec::with_capacity(512);
+        let flags = if self.is_response { RESPONSE_FLAGS } else { 0 };
+        for value in [
+            0,
+            flags,
+            self.questions.len() as u16,
+            self.records.len() as u16,
+            0,
+            0,
+        ] {
+            data.extend_from_slice(&value.to_be_bytes());
+        }
+
+        for question in &self.questions {
+            encode_name(&mut data, &question.name)?;
+            data.extend_from_slice(&question.record_type.to_be_bytes());
+            data.extend_from_slice(&CLASS_IN.to_be_bytes());
+        }
+
+        for record in &self.records {
+            encode_name(&mut data, &record.name)?;
+            data.extend_from_slice(&record.data.record_type().to_be_bytes());
+            // PTR records are shared by all the instances of a service
+            let class = match record.data {
+                RecordData::Ptr(_) => CLASS_IN,
+                _ => CLASS_IN | CLASS_FLAG,
+            };
+            data.extend_from_slice(&class.to_be_bytes());
+            data.extend_from_slice(&record.ttl.to_be_bytes());
+
+            let mut rdata = vec![];
+            match &record.data {
+                RecordData::A(address) => rdata.extend_from_slice(&address.octets()),
+                RecordData::Aaaa(address) => rdata.extend_from_slice(&address.octets()),
+                RecordData::Ptr(name) => encode_name(&mut rdata, name)?,
+                RecordData::Srv { port, target } => {
+                    // Priority and weight
+                    rdata.extend_from_slice(&[0, 0, 0, 0]);
+                    rdata.extend_from_slice(&port.to_be_bytes());
+                    encode_name(&mut rdata, target)?;
+                }
+                RecordData::Txt(entries) => {
+                    for entry in entries {
+                        let len = u8::try_from(entry.len()).map_err(|_| invalid("TXT entry"))?;
+                        rdata.push(len);
+                        rdata.extend_from_slice(entry.as_bytes());
+                    }
+                }
+                RecordData::Other(_) => {}
+            }
+            data.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+            data.extend_from_slice(&rdata);
+        }
+
+        Ok(data)
+    }
+
+    /// Decode a message, `None` if it is malformed
+    pub(super) fn decode(data: &[u8]) -> Option<Self> {
+        let header = data.get(..HEADER_SIZE)?;
+        let read_u16 = |offset: usize| u16::from_be_bytes([header[offset], header[offset + 1]]);
+        let is_response = read_u16(2) & 0x8000 != 0;
+        let questions_count = read_u16(4);
+        let records_count = read_u16(6) as usize + read_u16(8) as usize + read_u16(10) as usize;
+
+        let mut position = HEADER_SIZE;
+        let mut questions = vec![];
+        for _ in 0..questions_count {
+            let (name, next) = decode_name(data, position)?;
+            let record_type = u16_at(data, next)?;
+            position = next + 4;
+            questions.push(DnsQuestion { name, record_type });
+        }
+
+        let mut records = vec![];
+        for _ in 0..records_count {
+            let (name, next) = decode_name(data, position)?;
+            let record_type = u16_at(data, next)?;
+            let ttl = u32::from_be_bytes(data.get(next + 4..next + 8)?.try_into().ok()?);
+            let len = u16_at(data, next + 8)? as usize;
+            let start = next + 10;
+            let rdata = data.get(start..start + len)?;
+            position = start + len;
+
+            let record_data = match record_type {
+                TYPE_A => RecordData::A(Ipv4Addr::from(<[u8; 4]>::try_from(rdata).ok()?)),
+                TYPE_AAAA => RecordData::Aaaa(Ipv6Addr::from(<[u8; 16]>::try_from(rdata).ok()?)),
+                // Names may point to any part of the message
+                TYPE_PTR => RecordData::Ptr(decode_name(data, start)?.0),
+                TYPE_SRV => RecordData::Srv {
+                    port: u16_at(rdata, 4)?,
+                    target: decode_name(data, start + 6)?.0,
+                },
+                TYPE_TXT => RecordData::Txt(decode_txt(rdata)?),
+                other => RecordData::Other(other),
+            };
+            records.push(DnsRecord {
+                name,
+                ttl,
+                data: record_data,
+            });
+        }
+
+        Some(Self {
+            is_response,
+            questions,
+            records,
+        })
+    }
+}
+
+fn encode_name(data: &mut Vec<u8>, name: &str) -> Result<()> {
+    for label in name.trim_end_matches('.').split('.') {
+        if label.is_empty() || label.len() > MAX_LABEL_SIZE {
+            return Err(invalid(name));
+        }
+        data.push(label.len() as u8);
+        data.extend_from_slice(label.as_bytes());
+    }
+    data.push(0);
+    Ok(())
+}
+
+/// Decode the name starting at a position, and return the position following it
+fn decode_name(data: &[u8], mut position: usize) -> Option<(String, usize)> {
+    let mut labels = vec![];
+    let mut next = None;
+    let mut pointers = 0;
+    loop {
+        let len = *data.get(position)? as usize;
+        if len & 0xC0 == 0xC0 {
+            // Compression pointer to the rest of the name
+            pointers += 1;
+            if pointers > MAX_POINTERS {
+                return None;
+            }
+            next.get_or_insert(position + 2);
+            position = ((len & 0x3F) << 8) | *data.get(position + 1)? as usize;
+            continue;
+        }
+        if len > MAX_LABEL_SIZE {
+            return None;
+        }
+        if len == 0 {
+            return Some((labels.join("."), next.unwrap_or(position + 1)));
+        }
+        let label = data.get(position + 1..position + 1 + len)?;
+        labels.push(String::from_utf8_lossy(label).into_owned());
+        position += 1 + len;
+    }
+}
+
+fn decode_txt(mut rdata: &[u8]) -> Option<Vec<String>> {
+    let mut entries = vec![];
+    while let Some((len, rest)) = rdata.split_first() {
+        let entry = rest.get(..*len as usize)?;
+        entries.push(String::from_utf8_lossy(entry).into_owned());
+        rdata = &rest[*len as usize..];
+    }
+    Some(entries)
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn invalid(value: &str) -> ockam_core::Error {
+    ockam_core::Error::new(
+        Origin::Transport,
+        Kind::Invalid,
+        format!("{value} can't be encoded in a DNS message"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_response() -> Result<()> {
+        let response = DnsMessage::response(vec![
+            DnsRecord {
+                name: "_ockam._tcp.local".into(),
+                ttl: 120,
+                data: RecordData::Ptr("n1._ockam._tcp.local".into()),
+            },
+            DnsRecord {
+                name: "n1._ockam._tcp.local".into(),
+                ttl: 120,
+                data: RecordData::Srv {
+                    port: 4000,
+                    target: "n1.local".into(),
+                },
+            },
+            DnsRecord {
+                name: "n1._ockam._tcp.local".into(),
+                ttl: 120,
+                data: RecordData::Txt(vec!["node=n1".into(), "identifier=I123".into()]),
+            },
+            DnsRecord {
+                name: "n1.local".into(),
+                ttl: 120,
+                data: RecordData::A(Ipv4Addr::new(192, 168, 1, 10)),
+            },
+        ]);
+        assert_eq!(DnsMessage::decode(&response.encode()?), Some(response));
+
+        let query = DnsMessage::query("_ockam._tcp.local", TYPE_PTR);
+        let decoded = DnsMessage::decode(&query.encode()?).unwrap();
+        assert!(decoded.questions[0].asks_for("_OCKAM._tcp.local"));
+        assert_eq!(decoded, query);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_compressed_names() {
+        let mut data = vec![0, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 0];
+        // _ockam._tcp.local PTR n1.<pointer to _ockam._tcp.local>
+        data.extend_from_slice(b"\x06_ockam\x04_tcp\x05local\x00");
+        data.extend_from_slice(&[0, 12, 0, 1, 0, 0, 0, 120, 0, 5]);
+        data.extend_from_slice(b"\x02n1\xC0\x0C");
+
+        let message = DnsMessage::decode(&data).unwrap();
+        assert_eq!(
+            message.records[0].data,
+            RecordData::Ptr("n1._ockam._tcp.local".into())
+        );
+
+        // A pointer loop is rejected
+        let mut data = data.clone();
+        let len = data.len();
+        data[len - 1] = (len - 2) as u8;
+        assert_eq!(DnsMessage::decode(&data), None);
+    }
+
+    #[test]
+    fn test_invalid_names_are_rejected() {
+        assert!(DnsMessage::query("a..local", TYPE_PTR).encode().is_err());
+        assert!(DnsMessage::query(&"a".repeat(64), TYPE_PTR)
+            .encode()
+            .is_err());
+    }
+}
@@ -0,0 +1,68 @@
+//! Discovery of the nodes of a local network with multicast DNS.
+//!
+//! A node started with discovery enabled advertises its TCP listener and its identifier
+//! as an instance of the `_ockam._tcp.local` service. Other nodes, or the command line,
+//! can then find it without any Orchestrator project or relay.
+//!
+//! The advertised identifier is only a hint: the identity of a discovered node must still
+//! be verified when a secure channel is created to it.
+
+mod advertiser;
+mod browser;
+mod dns;
+mod socket;
+
+pub use advertiser::*;
+pub use browser::*;
+
+use crate::colors::color_primary;
+use crate::output::Output;
+use ockam::identity::Identifier;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+use std::net::SocketAddr;
+
+/// Name of the DNS-SD service advertised by the nodes
+pub const DISCOVERY_SERVICE_TYPE: &str = "_ockam._tcp.local";
+
+/// A node found on the local network
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct DiscoveredNode {
+    pub name: String,
+    pub identifier: Identifier,
+    /// Address of the TCP listener of the node
+    pub address: SocketAddr,
+}
+
+impl DiscoveredNode {
+    /// Route to the node, to be used as the `--to` argument of other commands
+    pub fn multiaddr(&self) -> String {
+        let ip = match self.address {
+            SocketAddr::V4(address) => format!("/ip4/{}", address.ip()),
+            SocketAddr::V6(address) => format!("/ip6/{}", address.ip()),
+        };
+        format!("{ip}/tcp/{}", self.address.port())
+    }
+}
+
+impl Display for DiscoveredNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Node {} with identifier {} listening at {}",
+            color_primary(&self.name),
+            color_primary(self.identifier.to_string()),
+            color_primary(self.address.to_string()),
+        )
+    }
+}
+
+impl Output for DiscoveredNode {
+    fn item(&self) -> crate::Result<String> {
+        Ok(format!("{}", self))
+    }
+
+    fn as_fields(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(self).ok()
+    }
+}
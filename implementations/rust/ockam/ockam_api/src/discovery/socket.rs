@@ -0,0 +1,50 @@
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::Result;
+use socket2::{Domain, Protocol, Socket, Type};
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket as StdUdpSocket};
+use tokio::net::UdpSocket;
+
+pub(super) const MDNS_GROUP: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+pub(super) const MDNS_PORT: u16 = 5353;
+
+pub(super) fn mdns_group_address() -> SocketAddr {
+    SocketAddr::V4(SocketAddrV4::new(MDNS_GROUP, MDNS_PORT))
+}
+
+/// Bind the mDNS port and join the mDNS multicast group.
+///
+/// The port is shared with the other mDNS responders of the host, for example avahi or
+/// the other nodes advertising themselves.
+pub(super) fn bind_multicast_socket() -> Result<UdpSocket> {
+    let socket = bind_reusable(MDNS_PORT).map_err(io_error)?;
+    socket
+        .join_multicast_v4(&MDNS_GROUP, &Ipv4Addr::UNSPECIFIED)
+        .map_err(io_error)?;
+    socket.set_multicast_loop_v4(true).map_err(io_error)?;
+    from_std(socket)
+}
+
+/// Bind an ephemeral port to send queries to the mDNS multicast group
+pub(super) fn bind_query_socket() -> Result<UdpSocket> {
+    let socket = StdUdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).map_err(io_error)?;
+    from_std(socket)
+}
+
+fn from_std(socket: StdUdpSocket) -> Result<UdpSocket> {
+    socket.set_nonblocking(true).map_err(io_error)?;
+    UdpSocket::from_std(socket).map_err(io_error)
+}
+
+/// Bind a port which can also be bound by the other sockets of the host
+fn bind_reusable(port: u16) -> std::io::Result<StdUdpSocket> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_reuse_address(true)?;
+    #[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos"))))]
+    socket.set_reuse_port(true)?;
+    socket.bind(&SocketAddr::from((Ipv4Addr::UNSPECIFIED, port)).into())?;
+    Ok(socket.into())
+}
+
+fn io_error(error: std::io::Error) -> ockam_core::Error {
+    ockam_core::Error::new(Origin::Transport, Kind::Io, error)
+}
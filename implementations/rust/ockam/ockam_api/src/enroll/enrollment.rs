@@ -1,9 +1,12 @@
 use crate::authenticator::one_time_code::OneTimeCode;
+use crate::authenticator::x509::{CertificateSigningRequest, X509Certificate};
 use crate::cloud::enroll::auth0::{AuthenticateOidcToken, OidcToken};
 use crate::cloud::HasSecureClient;
 use crate::nodes::service::default_address::DefaultAddress;
 use miette::IntoDiagnostic;
-use ockam::identity::models::CredentialAndPurposeKey;
+use ockam::identity::models::{
+    CredentialAndPurposeKey, CredentialSchema, RevocationListAndPurposeKey,
+};
 use ockam::identity::SecureClient;
 use ockam_core::api::{Reply, Request, Status};
 use ockam_core::async_trait;
@@ -39,6 +42,24 @@ pub trait Enrollment {
     ) -> miette::Result<EnrollStatus>;
 
     async fn issue_credential(&self, ctx: &Context) -> miette::Result<CredentialAndPurposeKey>;
+
+    async fn get_revocation_list(
+        &self,
+        ctx: &Context,
+    ) -> miette::Result<RevocationListAndPurposeKey>;
+
+    async fn get_credential_schema(
+        &self,
+        ctx: &Context,
+    ) -> miette::Result<Option<CredentialSchema>>;
+
+    async fn issue_x509_certificate(
+        &self,
+        ctx: &Context,
+        request: CertificateSigningRequest,
+    ) -> miette::Result<X509Certificate>;
+
+    async fn get_x509_ca_certificate(&self, ctx: &Context) -> miette::Result<X509Certificate>;
 }
 
 #[async_trait]
@@ -74,6 +95,34 @@ impl<T: HasSecureClient + Send + Sync> Enrollment for T {
     async fn issue_credential(&self, ctx: &Context) -> miette::Result<CredentialAndPurposeKey> {
         self.get_secure_client().issue_credential(ctx).await
     }
+
+    async fn get_revocation_list(
+        &self,
+        ctx: &Context,
+    ) -> miette::Result<RevocationListAndPurposeKey> {
+        self.get_secure_client().get_revocation_list(ctx).await
+    }
+
+    async fn get_credential_schema(
+        &self,
+        ctx: &Context,
+    ) -> miette::Result<Option<CredentialSchema>> {
+        self.get_secure_client().get_credential_schema(ctx).await
+    }
+
+    async fn issue_x509_certificate(
+        &self,
+        ctx: &Context,
+        request: CertificateSigningRequest,
+    ) -> miette::Result<X509Certificate> {
+        self.get_secure_client()
+            .issue_x509_certificate(ctx, request)
+            .await
+    }
+
+    async fn get_x509_ca_certificate(&self, ctx: &Context) -> miette::Result<X509Certificate> {
+        self.get_secure_client().get_x509_ca_certificate(ctx).await
+    }
 }
 
 // FiXME: this has duplicate with AuthorityNodeClient
@@ -155,4 +204,58 @@ impl Enrollment for SecureClient {
             .success()
             .into_diagnostic()
     }
+
+    #[instrument(skip_all)]
+    async fn get_revocation_list(
+        &self,
+        ctx: &Context,
+    ) -> miette::Result<RevocationListAndPurposeKey> {
+        let req = Request::get("/revocation_list");
+        trace!(target: TARGET, "getting the revocation list");
+        self.ask(ctx, DefaultAddress::CREDENTIAL_ISSUER, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
+
+    #[instrument(skip_all)]
+    async fn get_credential_schema(
+        &self,
+        ctx: &Context,
+    ) -> miette::Result<Option<CredentialSchema>> {
+        let req = Request::get("/schema");
+        trace!(target: TARGET, "getting the credential schema");
+        self.ask(ctx, DefaultAddress::CREDENTIAL_ISSUER, req)
+            .await
+            .into_diagnostic()?
+            .found()
+            .into_diagnostic()
+    }
+
+    #[instrument(skip_all)]
+    async fn issue_x509_certificate(
+        &self,
+        ctx: &Context,
+        request: CertificateSigningRequest,
+    ) -> miette::Result<X509Certificate> {
+        let req = Request::post("/x509_certificate").body(request);
+        trace!(target: TARGET, "getting an X.509 certificate");
+        self.ask(ctx, DefaultAddress::CREDENTIAL_ISSUER, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
+
+    #[instrument(skip_all)]
+    async fn get_x509_ca_certificate(&self, ctx: &Context) -> miette::Result<X509Certificate> {
+        let req = Request::get("/x509_ca_certificate");
+        trace!(target: TARGET, "getting the X.509 certificate of the authority");
+        self.ask(ctx, DefaultAddress::CREDENTIAL_ISSUER, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
 }
@@ -0,0 +1,470 @@
+use core::mem;
+use ockam::identity::Identifier;
+use ockam_core::compat::sync::Arc;
+use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
+use ockam_core::{
+    errcode::{Kind, Origin},
+    route, Address, AllowOnwardAddress, AllowSourceAddresses, AnyIncomingAccessControl,
+    AnyOutgoingAccessControl, Encodable, Error, IncomingAccessControl, LocalInfo, LocalMessage,
+    NeutralMessage, OutgoingAccessControl, Result, Route, Routed, Worker,
+};
+use ockam_node::{Context, WorkerBuilder};
+use ockam_transport_tcp::{HttpRequestPart, HttpRequestReader, PortalMessage, MAX_PAYLOAD_SIZE};
+
+use crate::http_gateway::listener::GatewayRoute;
+use crate::http_gateway::responses::ResponseQueue;
+use crate::http_gateway::routes::find_route;
+
+/// The connection of a client to the TCP outlet of a route
+enum Backend {
+    NotStarted,
+    /// The ping was sent to the outlet, the bytes of the requests are kept until its pong
+    Connecting(Vec<u8>),
+    /// The route to the outlet worker handling this connection
+    Connected(Route),
+    Disconnected,
+}
+
+/// Handles a connection accepted by an HTTP gateway.
+///
+/// The requests sent by the client are parsed, and each request is sent to the TCP outlet of
+/// its route, when the identity of the client is authorized by the policy of the route.
+/// Otherwise the gateway responds itself with a `404 Not Found` or a `403 Forbidden`.
+///
+/// The connection to the outlet of a route is opened with the first request for this route and
+/// the responses of the outlet are relayed by a [`HttpGatewayBackend`] worker.
+/// The responses are sent to the client in the order of its requests, even when it pipelines
+/// requests for different routes.
+pub(super) struct HttpGatewayConnection {
+    routes: Arc<Vec<GatewayRoute>>,
+    identifier: Option<Identifier>,
+    reader: HttpRequestReader,
+    // the route of the current request, None if it was rejected
+    current: Option<usize>,
+    responses: ResponseQueue,
+    // true once the connection must be closed after sending the pending responses,
+    // the next requests are ignored
+    closing: bool,
+    backends: Vec<Backend>,
+    backend_addresses: Vec<Address>,
+    backend_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    // The route to the client inlet, known once the ping is received
+    client_route: Option<Route>,
+    // The information about the secure channel of the client, required by the policies
+    // of the route outlets
+    local_info: Vec<LocalInfo>,
+}
+
+#[ockam::worker]
+impl Worker for HttpGatewayConnection {
+    type Message = NeutralMessage;
+    type Context = Context;
+
+    async fn handle_message(
+        &mut self,
+        context: &mut Self::Context,
+        routed_message: Routed<Self::Message>,
+    ) -> Result<()> {
+        let source_address = routed_message.src_addr();
+        if let Some(index) = self
+            .backend_addresses
+            .iter()
+            .position(|address| address == &source_address)
+        {
+            return self
+                .handle_backend_message(context, index, routed_message)
+                .await;
+        }
+
+        self.local_info = routed_message.local_message().local_info();
+        let portal_message = PortalMessage::decode(routed_message.payload())?;
+
+        match portal_message {
+            // the connections to the outlets are not compressed, since the requests
+            // need to be inspected, so the compression proposed by the inlet is ignored
+            PortalMessage::Ping
+            | PortalMessage::PingWithCompression(_)
+            | PortalMessage::PingWithClient(_, _) => {
+                let client_route = routed_message.return_route();
+                self.client_route = Some(client_route.clone());
+                let pong = LocalMessage::new()
+                    .with_onward_route(client_route)
+                    .with_return_route(route![context.address()])
+                    .with_payload(PortalMessage::Pong.encode()?);
+                context.forward(pong).await?;
+            }
+            PortalMessage::Payload(_, _) if self.closing => {}
+            PortalMessage::Payload(bytes, _) => match self.reader.read(bytes) {
+                Ok(parts) => {
+                    for part in parts {
+                        self.handle_request_part(context, part).await?;
+                    }
+                }
+                Err(e) => {
+                    debug!("invalid HTTP request: {e}");
+                    self.closing = true;
+                    self.respond(context, "400 Bad Request").await?;
+                }
+            },
+            PortalMessage::Disconnect => self.stop(context).await?,
+            PortalMessage::Pong
+            | PortalMessage::PongWithCompression(_)
+            | PortalMessage::CompressedPayload(_)
+            | PortalMessage::Resume(_) => {
+                return Err(Error::new(
+                    Origin::Transport,
+                    Kind::Protocol,
+                    "unexpected portal message received by an HTTP gateway",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl HttpGatewayConnection {
+    async fn handle_request_part(
+        &mut self,
+        context: &mut Context,
+        part: HttpRequestPart,
+    ) -> Result<()> {
+        match part {
+            HttpRequestPart::Head(head) => {
+                let routes = self.routes.iter().map(|route| &route.route);
+                self.current = None;
+                match find_route(routes, head.host(), head.path()) {
+                    None => {
+                        debug!("no HTTP route for {} {}", head.method(), head.path());
+                        self.respond(context, "404 Not Found").await?
+                    }
+                    Some(index) if !self.is_authorized(index).await? => {
+                        debug!(
+                            "{} {} rejected by the policy of the route {}",
+                            head.method(),
+                            head.path(),
+                            self.routes[index].route
+                        );
+                        self.respond(context, "403 Forbidden").await?
+                    }
+                    Some(index) => {
+                        self.current = Some(index);
+                        self.responses.expect_route_response(index, head.method());
+                        let mut bytes = vec![];
+                        head.encode(&mut bytes);
+                        self.send_to_backend(context, index, bytes).await?
+                    }
+                }
+            }
+            // the body of a rejected request is dropped
+            HttpRequestPart::Data(bytes) => {
+                if let Some(index) = self.current {
+                    self.send_to_backend(context, index, bytes).await?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Return true if the identity of the client is authorized by the policy of a route.
+    /// A client without identity is never authorized
+    async fn is_authorized(&self, index: usize) -> Result<bool> {
+        match &self.identifier {
+            Some(identifier) => {
+                self.routes[index]
+                    .access_control
+                    .is_identity_authorized(identifier)
+                    .await
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn send_to_backend(
+        &mut self,
+        context: &mut Context,
+        index: usize,
+        bytes: Vec<u8>,
+    ) -> Result<()> {
+        match &mut self.backends[index] {
+            Backend::NotStarted => {
+                self.start_backend(context, index).await?;
+                self.backends[index] = Backend::Connecting(bytes);
+            }
+            Backend::Connecting(pending) => pending.extend_from_slice(&bytes),
+            Backend::Connected(outlet_route) => {
+                let outlet_route = outlet_route.clone();
+                self.send_payload(context, outlet_route, &bytes).await?
+            }
+            Backend::Disconnected => {
+                debug!("the connection to {} is closed", self.routes[index].route);
+            }
+        }
+        Ok(())
+    }
+
+    /// Start the worker relaying the responses of the outlet of a route to the client,
+    /// then open the connection to the outlet
+    async fn start_backend(&self, context: &mut Context, index: usize) -> Result<()> {
+        let client_route = self.client_route.clone().ok_or_else(not_connected)?;
+        let backend_address = self.backend_addresses[index].clone();
+
+        // allow forwarding the `pong` message to the connection worker
+        let outgoing_access_control = AnyOutgoingAccessControl::new(vec![
+            Arc::new(AllowOnwardAddress::new(context.address())),
+            self.backend_outgoing_access_control.clone(),
+        ]);
+        WorkerBuilder::new(HttpGatewayBackend {
+            connection_address: context.address(),
+        })
+        .with_address(backend_address.clone())
+        .with_outgoing_access_control(outgoing_access_control)
+        .start(context)
+        .await?;
+
+        let ping = LocalMessage::new()
+            .with_onward_route(route![self.routes[index].outlet_address.clone()])
+            .with_return_route(client_route)
+            .push_front_return_route(&backend_address)
+            .with_payload(PortalMessage::Ping.encode()?)
+            .with_local_info(self.local_info.clone());
+        context.forward(ping).await
+    }
+
+    async fn handle_backend_message(
+        &mut self,
+        context: &mut Context,
+        index: usize,
+        routed_message: Routed<NeutralMessage>,
+    ) -> Result<()> {
+        match PortalMessage::decode(routed_message.payload())? {
+            PortalMessage::Pong => {
+                let outlet_route = routed_message.return_route();
+                let backend = mem::replace(
+                    &mut self.backends[index],
+                    Backend::Connected(outlet_route.clone()),
+                );
+                if let Backend::Connecting(pending) = backend {
+                    self.send_payload(context, outlet_route, &pending).await?;
+                }
+            }
+            PortalMessage::Payload(bytes, _) => {
+                if let Err(e) = self.responses.push_route_bytes(index, bytes) {
+                    debug!(
+                        "invalid HTTP response from {}: {e}",
+                        self.routes[index].route
+                    );
+                    self.responses.close_route(index);
+                    self.closing = true;
+                }
+                self.flush_responses(context).await?;
+            }
+            // the client is disconnected once it received the responses of the other routes
+            PortalMessage::Disconnect => {
+                self.backends[index] = Backend::Disconnected;
+                self.responses.close_route(index);
+                self.closing = true;
+                self.flush_responses(context).await?;
+            }
+            _ => {
+                return Err(Error::new(
+                    Origin::Transport,
+                    Kind::Protocol,
+                    "unexpected portal message received from an HTTP route",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Respond to the client, with a status and a text body,
+    /// after the responses to its previous requests
+    async fn respond(&mut self, context: &mut Context, status: &str) -> Result<()> {
+        let body = format!("{status}\n");
+        let response = format!(
+            "HTTP/1.1 {status}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        self.responses.push_gateway_response(response.into_bytes());
+        self.flush_responses(context).await
+    }
+
+    /// Send the responses which are ready to the client.
+    /// The client is disconnected when the connection is closing and all the responses were sent
+    async fn flush_responses(&mut self, context: &mut Context) -> Result<()> {
+        let ready = self.responses.pop_ready();
+        for chunk in ready.chunks(MAX_PAYLOAD_SIZE) {
+            self.send_to_client(context, PortalMessage::Payload(chunk, None))
+                .await?;
+        }
+        if self.closing && self.responses.is_empty() {
+            self.send_to_client(context, PortalMessage::Disconnect)
+                .await?;
+            self.stop(context).await?;
+        }
+        Ok(())
+    }
+
+    async fn send_to_client(
+        &self,
+        context: &mut Context,
+        portal_message: PortalMessage<'_>,
+    ) -> Result<()> {
+        let client_route = self.client_route.clone().ok_or_else(not_connected)?;
+        let message = LocalMessage::new()
+            .with_onward_route(client_route)
+            .with_return_route(route![context.address()])
+            .with_payload(portal_message.encode()?);
+        context.forward(message).await
+    }
+
+    /// Send bytes to the outlet worker of a route
+    async fn send_payload(
+        &self,
+        context: &mut Context,
+        outlet_route: Route,
+        buffer: &[u8],
+    ) -> Result<()> {
+        for chunk in buffer.chunks(MAX_PAYLOAD_SIZE) {
+            let message = LocalMessage::new()
+                .with_onward_route(outlet_route.clone())
+                .with_return_route(route![context.address()])
+                .with_payload(PortalMessage::Payload(chunk, None).encode()?)
+                .with_local_info(self.local_info.clone());
+
+            context.forward(message).await?;
+        }
+        Ok(())
+    }
+
+    /// Close the connections to the outlets, then stop the workers of this connection
+    async fn stop(&mut self, context: &mut Context) -> Result<()> {
+        for (backend, address) in self.backends.iter().zip(self.backend_addresses.iter()) {
+            if let Backend::Connected(outlet_route) = backend {
+                let message = LocalMessage::new()
+                    .with_onward_route(outlet_route.clone())
+                    .with_return_route(route![context.address()])
+                    .with_payload(PortalMessage::Disconnect.encode()?)
+                    .with_local_info(self.local_info.clone());
+                context.forward(message).await?;
+            }
+            if !matches!(backend, Backend::NotStarted) {
+                context.stop_worker(address.clone()).await?;
+            }
+        }
+        context.stop_worker(context.address()).await
+    }
+
+    /// Create the worker handling a connection accepted by an HTTP gateway.
+    /// Returns its address
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn create(
+        context: &Context,
+        routes: Arc<Vec<GatewayRoute>>,
+        identifier: Option<Identifier>,
+        flow_controls: &FlowControls,
+        secure_channel_flow_control_id: Option<FlowControlId>,
+        spawner_flow_control_id: FlowControlId,
+        incoming_access_control: Arc<dyn IncomingAccessControl>,
+        outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    ) -> Result<Address> {
+        let address = Address::random_tagged("HttpGatewayConnection");
+        let backend_addresses: Vec<Address> = routes
+            .iter()
+            .map(|_| Address::random_tagged("HttpGatewayBackend"))
+            .collect();
+
+        let flow_control_id = FlowControls::generate_flow_control_id();
+        for route in routes.iter() {
+            flow_controls.add_consumer(route.outlet_address.clone(), &flow_control_id);
+        }
+        flow_controls.add_producer(
+            address.clone(),
+            &flow_control_id,
+            Some(&spawner_flow_control_id),
+            vec![],
+        );
+        if let Some(secure_channel_flow_control_id) = secure_channel_flow_control_id.as_ref() {
+            flow_controls.add_consumer(address.clone(), secure_channel_flow_control_id);
+        }
+
+        let worker = Self {
+            backends: routes.iter().map(|_| Backend::NotStarted).collect(),
+            responses: ResponseQueue::new(routes.len()),
+            routes,
+            identifier,
+            reader: HttpRequestReader::new(),
+            current: None,
+            closing: false,
+            backend_addresses: backend_addresses.clone(),
+            backend_outgoing_access_control: outgoing_access_control.clone(),
+            client_route: None,
+            local_info: vec![],
+        };
+
+        // the backend workers forward the messages of the outlets, which are sent to the client
+        // by this worker, in the order of the requests
+        WorkerBuilder::new(worker)
+            .with_address(address.clone())
+            .with_incoming_access_control_arc(Arc::new(AnyIncomingAccessControl::new(vec![
+                Arc::new(AllowSourceAddresses(backend_addresses)),
+                incoming_access_control,
+            ])))
+            .with_outgoing_access_control_arc(Arc::new(AnyOutgoingAccessControl::new(vec![
+                Arc::new(FlowControlOutgoingAccessControl::new(
+                    flow_controls,
+                    flow_control_id,
+                    Some(spawner_flow_control_id),
+                )),
+                outgoing_access_control,
+            ])))
+            .start(context)
+            .await?;
+
+        Ok(address)
+    }
+}
+
+/// Relays the messages of the outlet of a route to the connection worker of an HTTP gateway
+struct HttpGatewayBackend {
+    connection_address: Address,
+}
+
+#[ockam::worker]
+impl Worker for HttpGatewayBackend {
+    type Message = NeutralMessage;
+    type Context = Context;
+
+    async fn handle_message(
+        &mut self,
+        context: &mut Self::Context,
+        routed_message: Routed<Self::Message>,
+    ) -> Result<()> {
+        match PortalMessage::decode(routed_message.payload())? {
+            // the connection worker sends the requests to the return route of the pong,
+            // and sends the responses to the client in the order of its requests
+            PortalMessage::Pong | PortalMessage::Payload(_, _) | PortalMessage::Disconnect => {
+                let local_message = routed_message
+                    .into_local_message()
+                    .set_onward_route(route![self.connection_address.clone()]);
+                context.forward(local_message).await?
+            }
+            _ => {
+                return Err(Error::new(
+                    Origin::Transport,
+                    Kind::Protocol,
+                    "unexpected portal message received by an HTTP gateway",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn not_connected() -> Error {
+    Error::new(
+        Origin::Transport,
+        Kind::Misuse,
+        "the HTTP gateway connection is not established yet",
+    )
+}
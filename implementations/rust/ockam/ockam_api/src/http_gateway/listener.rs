@@ -0,0 +1,116 @@
+use crate::http_gateway::connection::HttpGatewayConnection;
+use crate::http_gateway::HttpRoute;
+use ockam::identity::IdentitySecureChannelLocalInfo;
+use ockam::{Any, Context, Result, Routed, Worker};
+use ockam_abac::PolicyAccessControl;
+use ockam_core::flow_control::{FlowControlId, FlowControls};
+use ockam_core::{Address, IncomingAccessControl, OutgoingAccessControl};
+use ockam_node::WorkerBuilder;
+use std::sync::Arc;
+
+/// A route of an HTTP gateway, with the TCP outlet to its target and the access control
+/// evaluating its policy for each request
+#[derive(Clone)]
+pub(crate) struct GatewayRoute {
+    pub(crate) route: HttpRoute,
+    pub(crate) outlet_address: Address,
+    pub(crate) access_control: PolicyAccessControl,
+}
+
+/// Accepts the connections of the HTTP clients, like the postgres outlet listener.
+///
+/// Each connection is handled by a new worker, sending the requests to the TCP outlets of
+/// their routes with the identity of the client.
+pub(crate) struct HttpGatewayListener {
+    routes: Arc<Vec<GatewayRoute>>,
+    incoming_access_control: Arc<dyn IncomingAccessControl>,
+    outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    spawner_flow_control_id: FlowControlId,
+}
+
+impl HttpGatewayListener {
+    pub(crate) async fn create(
+        context: &Context,
+        listener_address: Address,
+        routes: Vec<GatewayRoute>,
+        default_secure_channel_listener_flow_control_id: FlowControlId,
+        incoming_access_control: Arc<dyn IncomingAccessControl>,
+        outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    ) -> Result<()> {
+        let flow_controls = context.flow_controls();
+        flow_controls.add_consumer(
+            listener_address.clone(),
+            &default_secure_channel_listener_flow_control_id,
+        );
+
+        let spawner_flow_control_id = FlowControls::generate_flow_control_id();
+        flow_controls.add_spawner(listener_address.clone(), &spawner_flow_control_id);
+
+        let worker = Self {
+            routes: Arc::new(routes),
+            incoming_access_control,
+            outgoing_access_control,
+            spawner_flow_control_id,
+        };
+
+        let incoming = worker.incoming_access_control.clone();
+
+        WorkerBuilder::new(worker)
+            .with_address(listener_address)
+            .with_incoming_access_control_arc(incoming)
+            .start(context)
+            .await
+            .map(|_| ())
+    }
+}
+
+#[ockam::worker]
+impl Worker for HttpGatewayListener {
+    type Message = Any;
+    type Context = Context;
+
+    async fn handle_message(
+        &mut self,
+        context: &mut Context,
+        message: Routed<Self::Message>,
+    ) -> Result<()> {
+        let identifier = IdentitySecureChannelLocalInfo::find_info(message.local_message())
+            .map(|info| info.their_identity_id())
+            .ok();
+        let source_address = message.src_addr();
+        let mut message = message.into_local_message();
+
+        // Remove our address
+        message = message.pop_front_onward_route()?;
+
+        // Retrieve the flow id from the previous hop if it exists
+        let secure_channel_flow_control_id = context
+            .flow_controls()
+            .find_flow_control_with_producer_address(&source_address)
+            .map(|x| x.flow_control_id().clone());
+
+        let worker_address = HttpGatewayConnection::create(
+            context,
+            self.routes.clone(),
+            identifier,
+            &context.flow_controls().clone(),
+            secure_channel_flow_control_id,
+            self.spawner_flow_control_id.clone(),
+            self.incoming_access_control.clone(),
+            self.outgoing_access_control.clone(),
+        )
+        .await?;
+
+        message = message.push_front_onward_route(&worker_address);
+
+        trace!(
+            "forwarding message: onward={:?}; return={:?}; worker={:?}",
+            &message.onward_route_ref(),
+            &message.return_route_ref(),
+            worker_address
+        );
+
+        context.forward(message).await?;
+        Ok(())
+    }
+}
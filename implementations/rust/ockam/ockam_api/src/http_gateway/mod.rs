@@ -0,0 +1,16 @@
+//! HTTP gateways: the requests of the clients are routed to several TCP outlets depending on
+//! their host and path, and each route only accepts the identities authorized by its own policy,
+//! evaluated with the attributes of their credentials.
+
+mod connection;
+mod listener;
+mod responses;
+mod routes;
+
+pub(crate) use listener::{GatewayRoute, HttpGatewayListener};
+pub use routes::HttpRoute;
+
+/// Address of the TCP outlet to the target of a route of an HTTP gateway
+pub fn route_outlet_address(service_address: &str, index: usize) -> String {
+    format!("{service_address}_route_{index}")
+}
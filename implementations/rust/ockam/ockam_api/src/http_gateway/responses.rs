@@ -0,0 +1,159 @@
+use ockam_core::compat::collections::VecDeque;
+use ockam_core::Result;
+use ockam_transport_tcp::{HttpResponsePart, HttpResponseReader};
+
+/// A response expected by the client of an HTTP gateway
+enum ExpectedResponse {
+    /// A response of the gateway itself, for a rejected request
+    Gateway(Vec<u8>),
+    /// A response of the outlet of a route
+    Route(usize),
+}
+
+/// The responses received from the outlet of a route, which were not sent to the client yet
+#[derive(Default)]
+struct RouteResponses {
+    reader: HttpResponseReader,
+    parts: VecDeque<HttpResponsePart>,
+    closed: bool,
+}
+
+/// Keeps the responses sent to the client of an HTTP gateway in the order of its requests.
+///
+/// An HTTP/1.1 client can pipeline its requests and expects the responses in the same order,
+/// but the requests for different routes are answered by different outlets, and the rejected
+/// requests are answered by the gateway itself. The response to a request is only sent once
+/// the responses to the previous requests are complete. Until then, it is buffered.
+pub(super) struct ResponseQueue {
+    expected: VecDeque<ExpectedResponse>,
+    routes: Vec<RouteResponses>,
+}
+
+impl ResponseQueue {
+    pub(super) fn new(routes_count: usize) -> Self {
+        Self {
+            expected: VecDeque::new(),
+            routes: (0..routes_count)
+                .map(|_| RouteResponses::default())
+                .collect(),
+        }
+    }
+
+    /// Expect a response from the outlet of a route, to a request sent with a given method
+    pub(super) fn expect_route_response(&mut self, index: usize, method: &str) {
+        self.routes[index].reader.expect_response(method);
+        self.expected.push_back(ExpectedResponse::Route(index));
+    }
+
+    /// Add a response of the gateway, sent after the responses to the previous requests
+    pub(super) fn push_gateway_response(&mut self, response: Vec<u8>) {
+        self.expected.push_back(ExpectedResponse::Gateway(response));
+    }
+
+    /// Add the bytes received from the outlet of a route
+    pub(super) fn push_route_bytes(&mut self, index: usize, bytes: &[u8]) -> Result<()> {
+        let route = &mut self.routes[index];
+        let parts = route.reader.read(bytes)?;
+        route.parts.extend(parts);
+        Ok(())
+    }
+
+    /// The connection to the outlet of a route was closed.
+    /// The responses which were not received from this route are skipped
+    pub(super) fn close_route(&mut self, index: usize) {
+        self.routes[index].closed = true;
+    }
+
+    /// Return the bytes which can be sent to the client: the responses to the first requests,
+    /// and the beginning of the response currently received
+    pub(super) fn pop_ready(&mut self) -> Vec<u8> {
+        let mut ready = vec![];
+        while let Some(expected) = self.expected.front_mut() {
+            match expected {
+                ExpectedResponse::Gateway(response) => ready.append(response),
+                ExpectedResponse::Route(index) => {
+                    let route = &mut self.routes[*index];
+                    if !Self::pop_route_response(route, &mut ready) {
+                        break;
+                    }
+                }
+            }
+            self.expected.pop_front();
+        }
+        ready
+    }
+
+    /// Return true if all the expected responses were returned
+    pub(super) fn is_empty(&self) -> bool {
+        self.expected.is_empty()
+    }
+
+    /// Append the received parts of the current response of a route.
+    /// Return true if this response is complete
+    fn pop_route_response(route: &mut RouteResponses, ready: &mut Vec<u8>) -> bool {
+        while let Some(part) = route.parts.pop_front() {
+            match part {
+                HttpResponsePart::Data(bytes) => ready.extend_from_slice(&bytes),
+                HttpResponsePart::End => return true,
+            }
+        }
+        route.closed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_responses_to_pipelined_requests_are_sent_in_order() {
+        let mut queue = ResponseQueue::new(2);
+        queue.expect_route_response(0, "GET");
+        queue
+            .push_gateway_response(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n".to_vec());
+        queue.expect_route_response(1, "GET");
+
+        // the second route answers first
+        queue
+            .push_route_bytes(1, b"HTTP/1.1 200 OK\r\nContent-Length: 1\r\n\r\nb")
+            .unwrap();
+        assert!(queue.pop_ready().is_empty());
+
+        queue
+            .push_route_bytes(0, b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\na")
+            .unwrap();
+        assert_eq!(
+            queue.pop_ready(),
+            b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\na".to_vec()
+        );
+        assert!(!queue.is_empty());
+
+        queue.push_route_bytes(0, b"a").unwrap();
+        assert_eq!(
+            queue.pop_ready(),
+            b"a\
+              HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n\
+              HTTP/1.1 200 OK\r\nContent-Length: 1\r\n\r\nb"
+                .to_vec()
+        );
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn the_responses_of_a_closed_route_are_skipped() {
+        let mut queue = ResponseQueue::new(2);
+        queue.expect_route_response(0, "GET");
+        queue.expect_route_response(1, "GET");
+        queue
+            .push_route_bytes(1, b"HTTP/1.1 204 No Content\r\n\r\n")
+            .unwrap();
+        assert!(queue.pop_ready().is_empty());
+
+        queue.close_route(0);
+        assert_eq!(
+            queue.pop_ready(),
+            b"HTTP/1.1 204 No Content\r\n\r\n".to_vec()
+        );
+        assert!(queue.is_empty());
+    }
+}
@@ -0,0 +1,162 @@
+use minicbor::{Decode, Encode};
+use ockam_abac::PolicyExpression;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+
+/// A route of an HTTP gateway: the requests for a host and a path are sent to a target,
+/// when the identity of the client is authorized by the policy of the route.
+///
+/// A route without host matches the requests for any host.
+#[derive(Clone, Debug, PartialEq, Eq, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct HttpRoute {
+    #[n(1)] host: Option<String>,
+    #[n(2)] path_prefix: String,
+    #[n(3)] target: String,
+    #[n(4)] policy_expression: Option<PolicyExpression>,
+}
+
+impl HttpRoute {
+    /// Route the requests with a path starting with `path_prefix` to a target, as `HOST:PORT`
+    pub fn new(path_prefix: impl Into<String>, target: impl Into<String>) -> Self {
+        Self {
+            host: None,
+            path_prefix: path_prefix.into(),
+            target: target.into(),
+            policy_expression: None,
+        }
+    }
+
+    /// Only route the requests for a host
+    pub fn with_host(mut self, host: impl Into<String>) -> Self {
+        self.host = Some(host.into());
+        self
+    }
+
+    /// Only route the requests of the identities authorized by a policy.
+    /// Without a policy, the policy of the "tcp-outlet" resource type is used
+    pub fn with_policy_expression(mut self, policy_expression: PolicyExpression) -> Self {
+        self.policy_expression = Some(policy_expression);
+        self
+    }
+
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+
+    pub fn path_prefix(&self) -> &str {
+        &self.path_prefix
+    }
+
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    pub fn policy_expression(&self) -> Option<PolicyExpression> {
+        self.policy_expression.clone()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if !self.path_prefix.starts_with('/') {
+            return Err(Error::new(
+                Origin::Api,
+                Kind::Invalid,
+                format!("The path of an HTTP route must start with /: {}", self),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Return true if the route accepts a request for a host and a path.
+    /// The path prefix only matches whole path segments: `/api` matches `/api/users`
+    /// but not `/apis`
+    fn matches(&self, host: Option<&str>, path: &str) -> bool {
+        if let Some(route_host) = &self.host {
+            if !host.is_some_and(|host| host.eq_ignore_ascii_case(route_host)) {
+                return false;
+            }
+        }
+        let path = path.split(['?', '#']).next().unwrap_or_default();
+        let prefix = self.path_prefix.trim_end_matches('/');
+        match path.strip_prefix(prefix) {
+            Some(rest) => rest.is_empty() || rest.starts_with('/'),
+            None => false,
+        }
+    }
+}
+
+impl std::fmt::Display for HttpRoute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{} => {}",
+            self.host.as_deref().unwrap_or_default(),
+            self.path_prefix,
+            self.target
+        )
+    }
+}
+
+/// Return the index of the route of a request: the routes for the host of the request
+/// come first, then the routes with the longest path prefix
+pub(crate) fn find_route<'a>(
+    routes: impl IntoIterator<Item = &'a HttpRoute>,
+    host: Option<&str>,
+    path: &str,
+) -> Option<usize> {
+    routes
+        .into_iter()
+        .enumerate()
+        .filter(|(_, route)| route.matches(host, path))
+        .max_by_key(|(index, route)| {
+            (
+                route.host.is_some(),
+                route.path_prefix.trim_end_matches('/').len(),
+                // the first route wins among the equally specific ones
+                usize::MAX - index,
+            )
+        })
+        .map(|(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_most_specific_route_is_used() {
+        let routes = vec![
+            HttpRoute::new("/", "127.0.0.1:8000"),
+            HttpRoute::new("/api", "127.0.0.1:8001"),
+            HttpRoute::new("/api/admin/", "127.0.0.1:8002"),
+            HttpRoute::new("/", "127.0.0.1:8003").with_host("docs.example.com"),
+        ];
+
+        assert_eq!(
+            find_route(&routes, Some("example.com"), "/index.html"),
+            Some(0)
+        );
+        assert_eq!(find_route(&routes, Some("example.com"), "/apis"), Some(0));
+        assert_eq!(
+            find_route(&routes, Some("example.com"), "/api?page=2"),
+            Some(1)
+        );
+        assert_eq!(find_route(&routes, None, "/api/users"), Some(1));
+        assert_eq!(find_route(&routes, None, "/api/admin"), Some(2));
+        assert_eq!(find_route(&routes, None, "/api/admin/users"), Some(2));
+        assert_eq!(
+            find_route(&routes, Some("DOCS.example.com"), "/api"),
+            Some(3)
+        );
+
+        let routes = vec![HttpRoute::new("/api", "127.0.0.1:8001")];
+        assert_eq!(find_route(&routes, None, "/"), None);
+    }
+
+    #[test]
+    fn the_path_of_a_route_must_be_absolute() {
+        assert!(HttpRoute::new("/api", "127.0.0.1:8001").validate().is_ok());
+        assert!(HttpRoute::new("api", "127.0.0.1:8001").validate().is_err());
+    }
+}
@@ -89,6 +89,8 @@ mod test {
             ConsumerPublishing::None,
             consumer_policy_access_control,
             producer_policy_access_control,
+            None,
+            None,
         );
 
         let mut interceptor_multiaddr = MultiAddr::default();
@@ -115,6 +117,10 @@ mod test {
         KafkaPortalListener::create(
             context,
             true,
+            false,
+            Default::default(),
+            Default::default(),
+            Default::default(),
             inlet_controller,
             secure_channel_controller,
             listener_address,
@@ -9,20 +9,30 @@ mod outlet_service;
 mod portal_listener;
 mod portal_worker;
 mod protocol_aware;
+pub(crate) mod record_encryption;
 pub(crate) mod secure_channel_map;
+mod stats;
+mod topic_policy;
 
 pub(crate) use inlet_controller::KafkaInletController;
 use ockam::identity::Identifier;
 use ockam_abac::expr::{eq, or, str};
 use ockam_abac::{subject_has_credential_policy_expression, subject_identifier_attribute, Expr};
 use ockam_core::Address;
-pub(crate) use outlet_service::OutletManagerService;
+pub(crate) use outlet_service::{OutletManagerService, RecordKeysService};
 pub(crate) use portal_listener::KafkaPortalListener;
+pub use record_encryption::RecordEncryption;
 pub use secure_channel_map::ConsumerPublishing;
 pub use secure_channel_map::ConsumerResolution;
+pub(crate) use stats::KafkaPortalStats;
+pub use topic_policy::{TopicMode, TopicPolicies, TopicPolicy};
 
 pub const KAFKA_OUTLET_INTERCEPTOR_ADDRESS: &str = "kafka_interceptor";
 pub const KAFKA_OUTLET_BOOTSTRAP_ADDRESS: &str = "kafka_bootstrap";
+/// Address of the outlet to the Schema Registry of the Kafka cluster
+pub const KAFKA_OUTLET_SCHEMA_REGISTRY_ADDRESS: &str = "kafka_schema_registry";
+/// Address of the service giving the keys used to deterministically encrypt the record keys
+pub const KAFKA_OUTLET_RECORD_KEYS_ADDRESS: &str = "kafka_record_keys";
 
 pub fn kafka_outlet_address(broker_id: i32) -> Address {
     format!("kafka_outlet_{}", broker_id).into()
@@ -3,6 +3,7 @@ use crate::nodes::models::portal::{CreateOutlet, OutletStatus};
 use crate::nodes::NODEMANAGER_ADDR;
 use minicbor::Decoder;
 use ockam::compat::tokio::sync::Mutex;
+use ockam::tcp::TcpEgressAllowList;
 use ockam::transport::HostnamePort;
 use ockam_abac::PolicyExpression;
 use ockam_core::api::{Request, ResponseHeader, Status};
@@ -25,6 +26,8 @@ pub(crate) struct KafkaOutletController {
     inner: Arc<Mutex<KafkaOutletMapInner>>,
     policy_expression: Option<PolicyExpression>,
     tls: bool,
+    /// Brokers which can be reached, since their addresses are sent by the cluster
+    egress_allow_list: Option<TcpEgressAllowList>,
 }
 
 #[derive(Debug)]
@@ -36,6 +39,7 @@ impl KafkaOutletController {
     pub(crate) fn new(
         policy_expression: Option<PolicyExpression>,
         tls: bool,
+        egress_allow_list: Option<TcpEgressAllowList>,
     ) -> KafkaOutletController {
         Self {
             inner: Arc::new(Mutex::new(KafkaOutletMapInner {
@@ -43,6 +47,7 @@ impl KafkaOutletController {
             })),
             policy_expression,
             tls,
+            egress_allow_list,
         }
     }
 
@@ -64,6 +69,7 @@ impl KafkaOutletController {
                 kafka_outlet_address(broker_id),
                 self.policy_expression.clone(),
                 self.tls,
+                self.egress_allow_list.clone(),
             )
             .await?;
             inner.broker_map.insert(broker_id, socket_address);
@@ -77,12 +83,16 @@ impl KafkaOutletController {
         worker_address: Address,
         policy_expression: Option<PolicyExpression>,
         tls: bool,
+        egress_allow_list: Option<TcpEgressAllowList>,
     ) -> Result<SocketAddr> {
         let hostname_port = HostnamePort::from_str(&kafka_address)?;
         let mut payload = CreateOutlet::new(hostname_port, tls, Some(worker_address), false);
         if let Some(expr) = policy_expression {
             payload.set_policy_expression(expr);
         }
+        if let Some(egress_allow_list) = egress_allow_list {
+            payload.set_egress_allow_list(egress_allow_list);
+        }
         let buffer: Vec<u8> = context
             .send_and_receive(
                 route![NODEMANAGER_ADDR],
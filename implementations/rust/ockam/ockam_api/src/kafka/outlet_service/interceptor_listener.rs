@@ -2,6 +2,7 @@ use crate::kafka::outlet_controller::KafkaOutletController;
 use crate::kafka::portal_worker::KafkaPortalWorker;
 use crate::kafka::protocol_aware::OutletInterceptorImpl;
 use crate::kafka::KAFKA_OUTLET_INTERCEPTOR_ADDRESS;
+use ockam::tcp::TcpEgressAllowList;
 use ockam::{Any, Context, Result, Routed, Worker};
 use ockam_abac::PolicyExpression;
 use ockam_core::flow_control::{FlowControlId, FlowControls};
@@ -28,6 +29,7 @@ impl OutletManagerService {
         request_incoming_access_control: Arc<dyn IncomingAccessControl>,
         response_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
         tls: bool,
+        egress_allow_list: Option<TcpEgressAllowList>,
     ) -> Result<()> {
         let flow_controls = context.flow_controls();
 
@@ -41,8 +43,10 @@ impl OutletManagerService {
 
         flow_controls.add_spawner(worker_address.clone(), &spawner_flow_control_id);
 
+        let outlet_controller =
+            KafkaOutletController::new(policy_expression, tls, egress_allow_list);
         let worker = OutletManagerService {
-            outlet_controller: KafkaOutletController::new(policy_expression, tls),
+            outlet_controller,
             request_incoming_access_control,
             response_outgoing_access_control,
             spawner_flow_control_id: spawner_flow_control_id.clone(),
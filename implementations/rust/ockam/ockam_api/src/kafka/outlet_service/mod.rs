@@ -1,2 +1,4 @@
 mod interceptor_listener;
+mod record_keys_service;
 pub(crate) use interceptor_listener::OutletManagerService;
+pub(crate) use record_keys_service::RecordKeysService;
@@ -0,0 +1,59 @@
+use crate::kafka::record_encryption::{derive_topic_key, TopicKeyRequest, TopicKeyResponse};
+use crate::kafka::KAFKA_OUTLET_RECORD_KEYS_ADDRESS;
+use ockam::{Context, Result, Routed, Worker};
+use ockam_core::flow_control::FlowControlId;
+use ockam_core::{Address, IncomingAccessControl};
+use ockam_node::WorkerBuilder;
+use std::sync::Arc;
+
+/// This service gives the Kafka inlets the keys used to deterministically encrypt
+/// the record keys of each topic. The keys are derived from a secret which is persisted
+/// by the outlet node, so that a record key is encrypted the same way across restarts.
+pub(crate) struct RecordKeysService {
+    secret: Vec<u8>,
+}
+
+impl RecordKeysService {
+    pub(crate) async fn create(
+        context: &Context,
+        default_secure_channel_listener_flow_control_id: FlowControlId,
+        secret: Vec<u8>,
+        incoming_access_control: Arc<dyn IncomingAccessControl>,
+    ) -> Result<()> {
+        let worker_address = Address::from_string(KAFKA_OUTLET_RECORD_KEYS_ADDRESS);
+        context.flow_controls().add_consumer(
+            worker_address.clone(),
+            &default_secure_channel_listener_flow_control_id,
+        );
+
+        WorkerBuilder::new(RecordKeysService { secret })
+            .with_address(worker_address)
+            .with_incoming_access_control_arc(incoming_access_control)
+            .start(context)
+            .await
+            .map(|_| ())
+    }
+}
+
+#[ockam::worker]
+impl Worker for RecordKeysService {
+    type Message = Vec<u8>;
+    type Context = Context;
+
+    async fn handle_message(
+        &mut self,
+        context: &mut Context,
+        message: Routed<Self::Message>,
+    ) -> Result<()> {
+        let return_route = message.return_route();
+        let request: TopicKeyRequest = minicbor::decode(&message.into_body()?)?;
+        debug!("sending the record keys of the topic {}", request.topic);
+
+        let response = TopicKeyResponse {
+            key: derive_topic_key(&self.secret, &request.topic),
+        };
+        context
+            .send(return_route, minicbor::to_vec(response)?)
+            .await
+    }
+}
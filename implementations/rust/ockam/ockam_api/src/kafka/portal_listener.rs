@@ -9,6 +9,7 @@ use crate::kafka::inlet_controller::KafkaInletController;
 use crate::kafka::portal_worker::KafkaPortalWorker;
 use crate::kafka::protocol_aware::TopicUuidMap;
 use crate::kafka::secure_channel_map::controller::KafkaSecureChannelControllerImpl;
+use crate::kafka::{KafkaPortalStats, RecordEncryption, TopicPolicies};
 
 /// First point of ingress of kafka connections, at the first message it spawns new stateful workers
 /// to take care of the connection.
@@ -19,6 +20,10 @@ pub(crate) struct KafkaPortalListener {
     request_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
     response_incoming_access_control: Arc<dyn IncomingAccessControl>,
     encrypt_content: bool,
+    preserve_schema_ids: bool,
+    topic_policies: TopicPolicies,
+    record_encryption: RecordEncryption,
+    stats: Arc<KafkaPortalStats>,
 }
 
 #[ockam::worker]
@@ -51,6 +56,10 @@ impl Worker for KafkaPortalListener {
         let worker_address = KafkaPortalWorker::create_inlet_side_kafka_portal(
             context,
             self.encrypt_content,
+            self.preserve_schema_ids,
+            self.topic_policies.clone(),
+            self.record_encryption.clone(),
+            self.stats.clone(),
             self.secure_channel_controller.clone(),
             self.uuid_to_name.clone(),
             self.inlet_controller.clone(),
@@ -78,9 +87,14 @@ impl Worker for KafkaPortalListener {
 }
 
 impl KafkaPortalListener {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn create(
         context: &Context,
         encrypt_content: bool,
+        preserve_schema_ids: bool,
+        topic_policies: TopicPolicies,
+        record_encryption: RecordEncryption,
+        stats: Arc<KafkaPortalStats>,
         inlet_controller: KafkaInletController,
         secure_channel_controller: KafkaSecureChannelControllerImpl,
         listener_address: Address,
@@ -94,6 +108,10 @@ impl KafkaPortalListener {
             request_outgoing_access_control: outgoing_access_control,
             response_incoming_access_control: incoming_access_control,
             encrypt_content,
+            preserve_schema_ids,
+            topic_policies,
+            record_encryption,
+            stats,
         };
 
         context.start_worker(listener_address, s).await
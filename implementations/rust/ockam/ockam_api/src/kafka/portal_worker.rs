@@ -16,7 +16,9 @@ use crate::kafka::inlet_controller::KafkaInletController;
 use crate::kafka::length_delimited::{length_encode, KafkaMessageDecoder};
 use crate::kafka::protocol_aware::{InletInterceptorImpl, KafkaMessageInterceptor, TopicUuidMap};
 use crate::kafka::secure_channel_map::controller::KafkaSecureChannelControllerImpl;
-use crate::kafka::KAFKA_OUTLET_BOOTSTRAP_ADDRESS;
+use crate::kafka::{
+    KafkaPortalStats, RecordEncryption, TopicPolicies, KAFKA_OUTLET_BOOTSTRAP_ADDRESS,
+};
 
 /// By default, kafka supports up to 1MB messages. 16MB is the maximum suggested
 pub(crate) const MAX_KAFKA_MESSAGE_SIZE: u32 = 16 * 1024 * 1024;
@@ -127,7 +129,9 @@ impl Worker for KafkaPortalWorker {
                     context.stop_worker(context.address()).await?;
                 }
             }
-            PortalMessage::Ping => self.forward(context, routed_message).await?,
+            PortalMessage::Ping | PortalMessage::PingWithClient(None, _) => {
+                self.forward(context, routed_message).await?
+            }
 
             PortalMessage::Pong => {
                 match self.receiving {
@@ -157,6 +161,27 @@ impl Worker for KafkaPortalWorker {
                     }
                 }
             }
+            // compression is never negotiated by the kafka portals, since the payloads
+            // need to be inspected
+            PortalMessage::PingWithCompression(_)
+            | PortalMessage::PingWithClient(Some(_), _)
+            | PortalMessage::PongWithCompression(_)
+            | PortalMessage::CompressedPayload(_) => {
+                return Err(Error::new(
+                    Origin::Transport,
+                    Kind::Protocol,
+                    "compressed portal messages are not supported by kafka portals",
+                ));
+            }
+            // the payloads are modified by the kafka portals, so the bytes lost while
+            // a connection is suspended can't be sent again
+            PortalMessage::Resume(_) => {
+                return Err(Error::new(
+                    Origin::Transport,
+                    Kind::Protocol,
+                    "resumed connections are not supported by kafka portals",
+                ));
+            }
         }
 
         Ok(())
@@ -389,6 +414,10 @@ impl KafkaPortalWorker {
     pub(crate) async fn create_inlet_side_kafka_portal(
         context: &mut Context,
         encrypt_content: bool,
+        preserve_schema_ids: bool,
+        topic_policies: TopicPolicies,
+        record_encryption: RecordEncryption,
+        stats: Arc<KafkaPortalStats>,
         secure_channel_controller: KafkaSecureChannelControllerImpl,
         uuid_to_name: TopicUuidMap,
         inlet_map: KafkaInletController,
@@ -403,6 +432,10 @@ impl KafkaPortalWorker {
             uuid_to_name,
             inlet_map,
             encrypt_content,
+            preserve_schema_ids,
+            topic_policies,
+            record_encryption,
+            stats,
         ));
 
         let requests_worker_address = Address::random_tagged("KafkaPortalWorker.requests");
@@ -813,11 +846,17 @@ mod test {
             ConsumerPublishing::None,
             consumer_policy_access_control,
             producer_policy_access_control,
+            None,
+            None,
         );
 
         KafkaPortalWorker::create_inlet_side_kafka_portal(
             context,
             true,
+            false,
+            Default::default(),
+            Default::default(),
+            Default::default(),
             secure_channel_controller,
             Default::default(),
             inlet_map,
@@ -899,6 +938,8 @@ mod test {
             ConsumerPublishing::None,
             consumer_policy_access_control,
             producer_policy_access_control,
+            None,
+            None,
         );
 
         let inlet_map = KafkaInletController::new(
@@ -912,6 +953,10 @@ mod test {
         let portal_inlet_address = KafkaPortalWorker::create_inlet_side_kafka_portal(
             context,
             true,
+            false,
+            Default::default(),
+            Default::default(),
+            Default::default(),
             secure_channel_controller,
             Default::default(),
             inlet_map.clone(),
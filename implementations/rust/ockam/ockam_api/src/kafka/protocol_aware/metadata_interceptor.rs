@@ -19,6 +19,7 @@ use tinyvec::alloc;
 use tracing::warn;
 
 use crate::kafka::portal_worker::InterceptError;
+use crate::kafka::protocol_aware::sasl::SaslState;
 use crate::kafka::protocol_aware::utils::decode_body;
 use crate::kafka::protocol_aware::{CorrelationId, KafkaMessageInterceptor, RequestInfo};
 
@@ -27,6 +28,7 @@ use crate::kafka::protocol_aware::{CorrelationId, KafkaMessageInterceptor, Reque
 #[derive(Clone)]
 pub(crate) struct OutletInterceptorImpl {
     request_map: Arc<Mutex<HashMap<CorrelationId, RequestInfo>>>,
+    sasl_state: Arc<SaslState>,
     outlet_controller: KafkaOutletController,
     flow_control_id: FlowControlId,
 }
@@ -38,6 +40,7 @@ impl OutletInterceptorImpl {
     ) -> Self {
         Self {
             request_map: Arc::new(Mutex::new(HashMap::new())),
+            sasl_state: Default::default(),
             outlet_controller,
             flow_control_id,
         }
@@ -51,6 +54,11 @@ impl KafkaMessageInterceptor for OutletInterceptorImpl {
         _context: &mut Context,
         mut original: BytesMut,
     ) -> Result<BytesMut, InterceptError> {
+        // SASL tokens exchanged after a version 0 handshake don't have any kafka header
+        if self.sasl_state.is_raw_request() {
+            return Ok(original);
+        }
+
         let mut buffer = original.peek_bytes(0..original.len());
 
         let api_key_num = buffer
@@ -91,11 +99,16 @@ impl KafkaMessageInterceptor for OutletInterceptorImpl {
             api_key
         );
 
-        if api_key == ApiKey::MetadataKey {
+        if api_key == ApiKey::SaslHandshakeKey {
+            self.sasl_state
+                .intercept_handshake_request(&mut buffer, &header)?;
+        }
+
+        if api_key == ApiKey::MetadataKey || api_key == ApiKey::SaslHandshakeKey {
             self.request_map.lock().unwrap().insert(
                 header.correlation_id,
                 RequestInfo {
-                    request_api_key: ApiKey::MetadataKey,
+                    request_api_key: api_key,
                     request_api_version: header.request_api_version,
                 },
             );
@@ -109,6 +122,10 @@ impl KafkaMessageInterceptor for OutletInterceptorImpl {
         context: &mut Context,
         mut original: BytesMut,
     ) -> Result<BytesMut, InterceptError> {
+        if self.sasl_state.is_raw_response(&original) {
+            return Ok(original);
+        }
+
         let mut buffer = original.peek_bytes(0..original.len());
 
         // we can/need to decode only mapped requests
@@ -149,6 +166,11 @@ impl KafkaMessageInterceptor for OutletInterceptorImpl {
                 request_info.request_api_key
             );
 
+            if request_info.request_api_key == ApiKey::SaslHandshakeKey {
+                self.sasl_state
+                    .intercept_handshake_response(&mut buffer, &request_info)?;
+            }
+
             if request_info.request_api_key == ApiKey::MetadataKey {
                 let response: MetadataResponse =
                     decode_body(&mut buffer, request_info.request_api_version)?;
@@ -1,8 +1,9 @@
 use crate::kafka::portal_worker::InterceptError;
 use crate::kafka::secure_channel_map::controller::KafkaSecureChannelControllerImpl;
-use crate::kafka::KafkaInletController;
+use crate::kafka::topic_policy::TopicSettings;
+use crate::kafka::{KafkaInletController, KafkaPortalStats, RecordEncryption, TopicPolicies};
 use bytes::BytesMut;
-use kafka_protocol::messages::ApiKey;
+use kafka_protocol::messages::{ApiKey, TopicName};
 use minicbor::{Decode, Encode};
 use ockam_core::compat::{
     collections::HashMap,
@@ -11,10 +12,13 @@ use ockam_core::compat::{
 };
 use ockam_core::{async_trait, Address};
 use ockam_node::Context;
+use sasl::SaslState;
+use std::io::{Error, ErrorKind};
 
 mod metadata_interceptor;
 mod request;
 mod response;
+mod sasl;
 mod tests;
 
 pub(super) mod utils;
@@ -50,10 +54,15 @@ pub(crate) trait KafkaMessageInterceptor: Send + Sync + 'static {
 #[derive(Clone)]
 pub(crate) struct InletInterceptorImpl {
     request_map: Arc<Mutex<HashMap<CorrelationId, RequestInfo>>>,
+    sasl_state: Arc<SaslState>,
     uuid_to_name: TopicUuidMap,
     secure_channel_controller: KafkaSecureChannelControllerImpl,
     inlet_map: KafkaInletController,
     encrypt_content: bool,
+    preserve_schema_ids: bool,
+    topic_policies: TopicPolicies,
+    record_encryption: RecordEncryption,
+    stats: Arc<KafkaPortalStats>,
 }
 
 #[async_trait]
@@ -84,19 +93,86 @@ struct MessageWrapper {
     #[n(2)] content: Vec<u8>
 }
 
+/// Length of the prefix added by the Schema Registry serializers: a magic byte then
+/// the schema id, as a big-endian 32-bit integer
+const SCHEMA_ID_PREFIX_LENGTH: usize = 5;
+const SCHEMA_ID_MAGIC_BYTE: u8 = 0;
+
+/// Split a record value between its schema id prefix and its content, when the value
+/// was serialized for a Schema Registry.
+///
+/// An encoded [`MessageWrapper`] is a CBOR map, which never starts with the magic byte.
+fn split_schema_id(value: &[u8]) -> (&[u8], &[u8]) {
+    if value.len() >= SCHEMA_ID_PREFIX_LENGTH && value[0] == SCHEMA_ID_MAGIC_BYTE {
+        value.split_at(SCHEMA_ID_PREFIX_LENGTH)
+    } else {
+        (&[], value)
+    }
+}
+
 impl InletInterceptorImpl {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         secure_channel_controller: KafkaSecureChannelControllerImpl,
         uuid_to_name: TopicUuidMap,
         inlet_map: KafkaInletController,
         encrypt_content: bool,
+        preserve_schema_ids: bool,
+        topic_policies: TopicPolicies,
+        record_encryption: RecordEncryption,
+        stats: Arc<KafkaPortalStats>,
     ) -> InletInterceptorImpl {
         Self {
             request_map: Arc::new(Mutex::new(Default::default())),
+            sasl_state: Default::default(),
             uuid_to_name,
             secure_channel_controller,
             inlet_map,
             encrypt_content,
+            preserve_schema_ids,
+            topic_policies,
+            record_encryption,
+            stats,
         }
     }
+
+    /// Return the settings of a topic. The connection is closed when the topic is denied
+    fn topic_settings(&self, topic_name: &str) -> Result<TopicSettings, InterceptError> {
+        self.topic_policies
+            .settings_for(
+                topic_name,
+                self.encrypt_content,
+                self.record_encryption.encrypt_keys(),
+            )
+            .ok_or_else(|| {
+                warn!("the topic {topic_name} is denied, closing connection");
+                InterceptError::Io(Error::from(ErrorKind::PermissionDenied))
+            })
+    }
+
+    /// Return the name of a topic used in a fetch request or response
+    fn fetched_topic_name(
+        &self,
+        api_version: i16,
+        topic_name: &TopicName,
+        topic_id: impl ToString,
+    ) -> Result<String, InterceptError> {
+        if api_version <= 12 {
+            return Ok(topic_name.0.to_string());
+        }
+
+        // fetch operation using version >= 13 don't use topic name
+        // anymore but uses uuid instead, we built a map using
+        // previous Metadata requests
+        let topic_id = topic_id.to_string();
+        self.uuid_to_name
+            .lock()
+            .unwrap()
+            .get(&topic_id)
+            .cloned()
+            .ok_or_else(|| {
+                warn!("missing map from uuid {topic_id} to name");
+                InterceptError::Io(Error::from(ErrorKind::InvalidData))
+            })
+    }
 }
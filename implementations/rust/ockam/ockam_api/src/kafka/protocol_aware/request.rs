@@ -1,6 +1,6 @@
 use bytes::{Bytes, BytesMut};
 use kafka_protocol::messages::fetch_request::FetchRequest;
-use kafka_protocol::messages::produce_request::ProduceRequest;
+use kafka_protocol::messages::produce_request::{PartitionProduceData, ProduceRequest};
 use kafka_protocol::messages::request_header::RequestHeader;
 use kafka_protocol::messages::ApiKey;
 use kafka_protocol::protocol::buf::ByteBuf;
@@ -16,7 +16,9 @@ use tracing::warn;
 
 use crate::kafka::portal_worker::InterceptError;
 use crate::kafka::protocol_aware::utils::{decode_body, encode_request};
-use crate::kafka::protocol_aware::{InletInterceptorImpl, MessageWrapper, RequestInfo};
+use crate::kafka::protocol_aware::{
+    split_schema_id, InletInterceptorImpl, MessageWrapper, RequestInfo,
+};
 
 impl InletInterceptorImpl {
     /// Parse request and map request <=> response.
@@ -26,6 +28,12 @@ impl InletInterceptorImpl {
         context: &mut Context,
         mut original: BytesMut,
     ) -> Result<BytesMut, InterceptError> {
+        // SASL tokens exchanged after a version 0 handshake don't have any kafka header
+        if self.sasl_state.is_raw_request() {
+            debug!("request: forwarding a sasl token");
+            return Ok(original);
+        }
+
         // let's clone the view of the buffer without cloning the content
         let mut buffer = original.peek_bytes(0..original.len());
 
@@ -75,16 +83,29 @@ impl InletInterceptorImpl {
             }
 
             ApiKey::ProduceKey => {
-                if self.encrypt_content {
+                if self.encrypt_content || !self.topic_policies.is_empty() {
                     return self
                         .handle_produce_request(context, &mut buffer, &header)
                         .await;
                 }
+                // the records are forwarded as they are, they are only counted
+                self.count_produce_request(&mut buffer, &header);
             }
             ApiKey::FetchKey => {
                 self.handle_fetch_request(context, &mut buffer, &header)
                     .await?;
             }
+            ApiKey::SaslHandshakeKey => {
+                self.sasl_state
+                    .intercept_handshake_request(&mut buffer, &header)?;
+                self.request_map.lock().unwrap().insert(
+                    header.correlation_id,
+                    RequestInfo {
+                        request_api_key: api_key,
+                        request_api_version: header.request_api_version,
+                    },
+                );
+            }
             ApiKey::MetadataKey | ApiKey::FindCoordinatorKey => {
                 self.request_map.lock().unwrap().insert(
                     header.correlation_id,
@@ -123,23 +144,10 @@ impl InletInterceptorImpl {
         // we intercept every partition interested in the kafka client
         // and create a relay for each
         for topic in &request.topics {
-            let topic_id = if header.request_api_version <= 12 {
-                topic.topic.0.to_string()
-            } else {
-                // fetch operation using version >= 13 don't use topic name
-                // anymore but uses uuid instead, we built a map using
-                // previous Metadata requests
-                let topic_id = topic.topic_id.to_string();
-                self.uuid_to_name
-                    .lock()
-                    .unwrap()
-                    .get(&topic_id)
-                    .cloned()
-                    .ok_or_else(|| {
-                        warn!("missing map from uuid {topic_id} to name");
-                        InterceptError::Io(Error::from(ErrorKind::InvalidData))
-                    })?
-            };
+            let topic_id =
+                self.fetched_topic_name(header.request_api_version, &topic.topic, topic.topic_id)?;
+            // the consumer must not receive the records of a denied topic
+            self.topic_settings(&topic_id)?;
 
             let partitions: Vec<i32> = topic
                 .partitions
@@ -175,40 +183,61 @@ impl InletInterceptorImpl {
         // for each we wrap the content and add the secure channel identifier of
         // the encrypted content
         for (topic_name, topic) in request.topic_data.iter_mut() {
+            let settings = self.topic_settings(topic_name)?;
+            if !settings.encrypt_values {
+                self.count_produced_topic(topic_name, &topic.partition_data);
+                continue;
+            }
+
             for data in &mut topic.partition_data {
                 if let Some(content) = data.records.take() {
+                    self.stats
+                        .add_produced_batch(topic_name, content.len(), true);
                     let mut content = BytesMut::from(content.as_ref());
                     let mut records = RecordBatchDecoder::decode(&mut content)
                         .map_err(|_| InterceptError::Io(Error::from(ErrorKind::InvalidData)))?;
 
                     for record in records.iter_mut() {
                         if let Some(record_value) = record.value.take() {
-                            let encrypted_content = self
-                                .secure_channel_controller
-                                .encrypt_content_for(
+                            record.value = Some(
+                                self.encrypt_record_field(
                                     context,
                                     topic_name,
                                     data.index,
-                                    record_value.to_vec(),
+                                    &record_value,
                                 )
-                                .await
-                                .map_err(InterceptError::Ockam)?;
-
-                            // TODO: to target multiple consumers we could duplicate
-                            //  the content with a dedicated encryption for each consumer
-                            let wrapper = MessageWrapper {
-                                consumer_decryptor_address: encrypted_content
-                                    .consumer_decryptor_address,
-                                content: encrypted_content.content,
-                            };
-
-                            let mut write_buffer = Vec::with_capacity(1024);
-                            let mut encoder = Encoder::new(&mut write_buffer);
-                            encoder.encode(wrapper).map_err(|_err| {
-                                InterceptError::Io(Error::from(ErrorKind::InvalidData))
-                            })?;
-
-                            record.value = Some(write_buffer.into());
+                                .await?,
+                            );
+                        }
+                        if settings.encrypt_keys {
+                            if let Some(record_key) = record.key.take() {
+                                record.key = Some(
+                                    self.encrypt_record_key(
+                                        context,
+                                        topic_name,
+                                        data.index,
+                                        &record_key,
+                                    )
+                                    .await?,
+                                );
+                            }
+                        }
+                        for (name, value) in record.headers.iter_mut() {
+                            if !self.record_encryption.is_encrypted_header(name.as_str()) {
+                                continue;
+                            }
+                            if let Some(header_value) = value.take() {
+                                *value = Some(
+                                    self.wrap_encrypted_content(
+                                        context,
+                                        topic_name,
+                                        data.index,
+                                        &[],
+                                        &header_value,
+                                    )
+                                    .await?,
+                                );
+                            }
                         }
                     }
 
@@ -235,4 +264,111 @@ impl InletInterceptorImpl {
             ApiKey::ProduceKey,
         )
     }
+
+    /// Count the records of a produce request which is not modified
+    fn count_produce_request(&self, buffer: &mut Bytes, header: &RequestHeader) {
+        let result: Result<ProduceRequest, _> = decode_body(buffer, header.request_api_version);
+        if let Ok(request) = result {
+            for (topic_name, topic) in &request.topic_data {
+                self.count_produced_topic(topic_name, &topic.partition_data);
+            }
+        }
+    }
+
+    fn count_produced_topic(&self, topic_name: &str, partition_data: &[PartitionProduceData]) {
+        for data in partition_data {
+            if let Some(content) = &data.records {
+                self.stats
+                    .add_produced_batch(topic_name, content.len(), false);
+            }
+        }
+    }
+
+    /// Encrypt the value, or the key, of a record and wrap it with the secure channel
+    /// identifier of the encrypted content
+    async fn encrypt_record_field(
+        &self,
+        context: &mut Context,
+        topic_name: &str,
+        partition_id: i32,
+        field: &[u8],
+    ) -> Result<Bytes, InterceptError> {
+        let (schema_id, content) = self.split_preserved_schema_id(field);
+        self.wrap_encrypted_content(context, topic_name, partition_id, schema_id, content)
+            .await
+    }
+
+    /// Encrypt the key of a record. A deterministically encrypted key is encoded as a CBOR
+    /// byte string, which never starts with the schema id magic byte either
+    async fn encrypt_record_key(
+        &self,
+        context: &mut Context,
+        topic_name: &str,
+        partition_id: i32,
+        record_key: &[u8],
+    ) -> Result<Bytes, InterceptError> {
+        if !self.record_encryption.deterministic_keys() {
+            return self
+                .encrypt_record_field(context, topic_name, partition_id, record_key)
+                .await;
+        }
+
+        let (schema_id, content) = self.split_preserved_schema_id(record_key);
+        let encrypted_key = self
+            .secure_channel_controller
+            .encrypt_record_key_for(context, topic_name, content)
+            .await
+            .map_err(InterceptError::Ockam)?;
+
+        let mut write_buffer = schema_id.to_vec();
+        let mut encoder = Encoder::new(&mut write_buffer);
+        encoder
+            .bytes(&encrypted_key)
+            .map_err(|_err| InterceptError::Io(Error::from(ErrorKind::InvalidData)))?;
+
+        Ok(write_buffer.into())
+    }
+
+    /// The schema id stays readable, for the consumers to resolve
+    /// the schema of the decrypted content
+    fn split_preserved_schema_id<'a>(&self, field: &'a [u8]) -> (&'a [u8], &'a [u8]) {
+        if self.preserve_schema_ids {
+            split_schema_id(field)
+        } else {
+            (&[], field)
+        }
+    }
+
+    /// Encrypt some content and wrap it with the secure channel identifier of the
+    /// encrypted content, after a prefix which is kept in clear
+    async fn wrap_encrypted_content(
+        &self,
+        context: &mut Context,
+        topic_name: &str,
+        partition_id: i32,
+        prefix: &[u8],
+        content: &[u8],
+    ) -> Result<Bytes, InterceptError> {
+        let encrypted_content = self
+            .secure_channel_controller
+            .encrypt_content_for(context, topic_name, partition_id, content.to_vec())
+            .await
+            .map_err(InterceptError::Ockam)?;
+
+        // TODO: to target multiple consumers we could duplicate
+        //  the content with a dedicated encryption for each consumer
+        let wrapper = MessageWrapper {
+            consumer_decryptor_address: encrypted_content.consumer_decryptor_address,
+            content: encrypted_content.content,
+        };
+
+        let mut write_buffer = Vec::with_capacity(1024);
+        write_buffer.extend_from_slice(prefix);
+        let mut encoder = Encoder::new(&mut write_buffer);
+        encoder
+            .encode(wrapper)
+            .map_err(|_err| InterceptError::Io(Error::from(ErrorKind::InvalidData)))?;
+
+        Ok(write_buffer.into())
+    }
 }
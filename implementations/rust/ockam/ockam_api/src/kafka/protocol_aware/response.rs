@@ -2,7 +2,7 @@ use std::io::{Error, ErrorKind};
 use std::net::SocketAddr;
 
 use bytes::{Bytes, BytesMut};
-use kafka_protocol::messages::fetch_response::FetchResponse;
+use kafka_protocol::messages::fetch_response::{FetchResponse, PartitionData};
 use kafka_protocol::messages::find_coordinator_response::FindCoordinatorResponse;
 use kafka_protocol::messages::metadata_response::MetadataResponse;
 use kafka_protocol::messages::response_header::ResponseHeader;
@@ -10,7 +10,7 @@ use kafka_protocol::messages::{ApiKey, ApiVersionsResponse};
 use kafka_protocol::protocol::buf::ByteBuf;
 use kafka_protocol::protocol::{Decodable, StrBytes};
 use kafka_protocol::records::{
-    Compression, RecordBatchDecoder, RecordBatchEncoder, RecordEncodeOptions,
+    Compression, Record, RecordBatchDecoder, RecordBatchEncoder, RecordEncodeOptions,
 };
 use minicbor::decode::Decoder;
 use ockam_node::Context;
@@ -19,7 +19,10 @@ use tracing::{trace, warn};
 use crate::kafka::inlet_controller::KafkaInletController;
 use crate::kafka::portal_worker::InterceptError;
 use crate::kafka::protocol_aware::utils::{decode_body, encode_response};
-use crate::kafka::protocol_aware::{InletInterceptorImpl, MessageWrapper, RequestInfo};
+use crate::kafka::protocol_aware::{
+    split_schema_id, InletInterceptorImpl, MessageWrapper, RequestInfo,
+};
+use crate::kafka::topic_policy::TopicSettings;
 
 impl InletInterceptorImpl {
     pub(crate) async fn intercept_response_impl(
@@ -27,6 +30,11 @@ impl InletInterceptorImpl {
         context: &mut Context,
         mut original: BytesMut,
     ) -> Result<BytesMut, InterceptError> {
+        if self.sasl_state.is_raw_response(&original) {
+            debug!("response: forwarding a sasl token");
+            return Ok(original);
+        }
+
         // let's clone the view of the buffer without cloning the content
         let mut buffer = original.peek_bytes(0..original.len());
 
@@ -75,12 +83,19 @@ impl InletInterceptorImpl {
                     debug!("api versions response: {:#?}", response);
                 }
 
+                ApiKey::SaslHandshakeKey => {
+                    self.sasl_state
+                        .intercept_handshake_response(&mut buffer, &request_info)?;
+                }
+
                 ApiKey::FetchKey => {
-                    if self.encrypt_content {
+                    if self.encrypt_content || !self.topic_policies.is_empty() {
                         return self
                             .handle_fetch_response(context, &mut buffer, &request_info, &header)
                             .await;
                     }
+                    // the records are forwarded as they are, they are only counted
+                    self.count_fetch_response(&mut buffer, &request_info);
                 }
 
                 ApiKey::FindCoordinatorKey => {
@@ -231,32 +246,33 @@ impl InletInterceptorImpl {
         // we take every record batch content, unwrap and decode it
         // using the relative secure channel
         for response in response.responses.iter_mut() {
+            let topic_name = self.fetched_topic_name(
+                request_info.request_api_version,
+                &response.topic,
+                response.topic_id,
+            )?;
+            let settings = self.topic_settings(&topic_name)?;
+            if !settings.encrypt_values {
+                self.count_fetched_topic(&topic_name, &response.partitions);
+                continue;
+            }
+
             for partition in response.partitions.iter_mut() {
                 if let Some(content) = partition.records.take() {
+                    let fetched_bytes = content.len();
                     let mut content = BytesMut::from(content.as_ref());
                     let mut records = RecordBatchDecoder::decode(&mut content)
                         .map_err(|_| InterceptError::Io(Error::from(ErrorKind::InvalidData)))?;
 
-                    for record in records.iter_mut() {
-                        if let Some(record_value) = record.value.take() {
-                            let message_wrapper: MessageWrapper =
-                                Decoder::new(record_value.as_ref()).decode().map_err(|_| {
-                                    InterceptError::Io(Error::from(ErrorKind::InvalidData))
-                                })?;
-
-                            let decrypted_content = self
-                                .secure_channel_controller
-                                .decrypt_content_for(
-                                    context,
-                                    &message_wrapper.consumer_decryptor_address,
-                                    message_wrapper.content,
-                                )
-                                .await
-                                .map_err(InterceptError::Ockam)?;
-
-                            record.value = Some(decrypted_content.into());
-                        }
+                    let result = self
+                        .decrypt_records(context, &topic_name, &settings, &mut records)
+                        .await;
+                    self.stats
+                        .add_fetched_batch(&topic_name, fetched_bytes, result.is_ok());
+                    if result.is_err() {
+                        self.stats.add_decryption_failure(&topic_name);
                     }
+                    result?;
 
                     let mut encoded = BytesMut::new();
                     RecordBatchEncoder::encode(
@@ -280,4 +296,118 @@ impl InletInterceptorImpl {
             ApiKey::FetchKey,
         )
     }
+
+    /// Decrypt the values of the records fetched from an encrypted topic, and their keys and
+    /// headers when they are encrypted too
+    async fn decrypt_records(
+        &self,
+        context: &mut Context,
+        topic_name: &str,
+        settings: &TopicSettings,
+        records: &mut [Record],
+    ) -> Result<(), InterceptError> {
+        for record in records.iter_mut() {
+            if let Some(record_value) = record.value.take() {
+                record.value = Some(self.decrypt_record_field(context, &record_value).await?);
+            }
+            if settings.encrypt_keys {
+                if let Some(record_key) = record.key.take() {
+                    record.key = Some(
+                        self.decrypt_record_key(context, topic_name, &record_key)
+                            .await?,
+                    );
+                }
+            }
+            for (name, value) in record.headers.iter_mut() {
+                if !self.record_encryption.is_encrypted_header(name.as_str()) {
+                    continue;
+                }
+                if let Some(header_value) = value.take() {
+                    *value = Some(self.decrypt_record_field(context, &header_value).await?);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Count the records of a fetch response which is not modified
+    fn count_fetch_response(&self, buffer: &mut Bytes, request_info: &RequestInfo) {
+        let result: Result<FetchResponse, _> =
+            decode_body(buffer, request_info.request_api_version);
+        if let Ok(response) = result {
+            for response in &response.responses {
+                let topic_name = self.fetched_topic_name(
+                    request_info.request_api_version,
+                    &response.topic,
+                    response.topic_id,
+                );
+                if let Ok(topic_name) = topic_name {
+                    self.count_fetched_topic(&topic_name, &response.partitions);
+                }
+            }
+        }
+    }
+
+    fn count_fetched_topic(&self, topic_name: &str, partitions: &[PartitionData]) {
+        for partition in partitions {
+            if let Some(content) = &partition.records {
+                self.stats
+                    .add_fetched_batch(topic_name, content.len(), false);
+            }
+        }
+    }
+
+    /// Unwrap and decrypt the value, or the key, of a record
+    async fn decrypt_record_field(
+        &self,
+        context: &mut Context,
+        field: &[u8],
+    ) -> Result<Bytes, InterceptError> {
+        let (schema_id, wrapper) = split_schema_id(field);
+        let message_wrapper: MessageWrapper = Decoder::new(wrapper)
+            .decode()
+            .map_err(|_| InterceptError::Io(Error::from(ErrorKind::InvalidData)))?;
+
+        let decrypted_content = self
+            .secure_channel_controller
+            .decrypt_content_for(
+                context,
+                &message_wrapper.consumer_decryptor_address,
+                message_wrapper.content,
+            )
+            .await
+            .map_err(InterceptError::Ockam)?;
+
+        let mut value = schema_id.to_vec();
+        value.extend_from_slice(&decrypted_content);
+        Ok(value.into())
+    }
+
+    /// Decrypt the key of a record, encrypted either like the record values or
+    /// deterministically
+    async fn decrypt_record_key(
+        &self,
+        context: &mut Context,
+        topic_name: &str,
+        field: &[u8],
+    ) -> Result<Bytes, InterceptError> {
+        if !self.record_encryption.deterministic_keys() {
+            return self.decrypt_record_field(context, field).await;
+        }
+
+        let (schema_id, encoded_key) = split_schema_id(field);
+        let encrypted_key = Decoder::new(encoded_key)
+            .bytes()
+            .map_err(|_| InterceptError::Io(Error::from(ErrorKind::InvalidData)))?;
+
+        let record_key = self
+            .secure_channel_controller
+            .decrypt_record_key_for(context, topic_name, encrypted_key)
+            .await
+            .map_err(InterceptError::Ockam)?;
+
+        let mut key = schema_id.to_vec();
+        key.extend_from_slice(&record_key);
+        Ok(key.into())
+    }
 }
@@ -0,0 +1,222 @@
+use bytes::Bytes;
+use kafka_protocol::messages::request_header::RequestHeader;
+use kafka_protocol::messages::{SaslHandshakeRequest, SaslHandshakeResponse};
+use ockam_core::compat::sync::Mutex;
+use std::io::{Error, ErrorKind};
+use tracing::warn;
+
+use crate::kafka::portal_worker::InterceptError;
+use crate::kafka::protocol_aware::utils::decode_body;
+use crate::kafka::protocol_aware::RequestInfo;
+
+/// SASL mechanisms whose tokens can be forwarded when they are not wrapped in
+/// `SaslAuthenticate` messages
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SaslMechanism {
+    Plain,
+    ScramSha256,
+    ScramSha512,
+    OAuthBearer,
+}
+
+impl SaslMechanism {
+    pub(crate) fn parse(name: &str) -> Option<Self> {
+        match name {
+            "PLAIN" => Some(Self::Plain),
+            "SCRAM-SHA-256" => Some(Self::ScramSha256),
+            "SCRAM-SHA-512" => Some(Self::ScramSha512),
+            "OAUTHBEARER" => Some(Self::OAuthBearer),
+            _ => None,
+        }
+    }
+
+    /// Number of tokens sent by the client for a successful authentication
+    fn client_tokens(&self) -> usize {
+        match self {
+            // client-first-message, then client-final-message
+            Self::ScramSha256 | Self::ScramSha512 => 2,
+            Self::Plain | Self::OAuthBearer => 1,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+enum SaslStage {
+    /// Every message is a kafka request or response
+    #[default]
+    KafkaMessages,
+    /// A handshake was requested, the client waits for the list of enabled mechanisms
+    Handshake {
+        version: i16,
+        mechanism: Option<SaslMechanism>,
+    },
+    /// The handshake succeeded with a version 0 request: the SASL tokens are exchanged
+    /// as opaque length-delimited packets, without any kafka header
+    RawTokens {
+        mechanism: SaslMechanism,
+        /// Client tokens still expected before the end of the authentication
+        remaining_requests: usize,
+        /// Client tokens which the server didn't answer yet
+        pending_responses: usize,
+    },
+}
+
+/// Tracks the SASL authentication of a single kafka connection, so that the SASL tokens
+/// are forwarded as they are instead of being parsed as kafka messages.
+///
+/// With a `SaslHandshake` version 1, the tokens of every mechanism are wrapped in
+/// `SaslAuthenticate` messages and are forwarded like any other kafka message.
+#[derive(Debug, Default)]
+pub(crate) struct SaslState {
+    stage: Mutex<SaslStage>,
+}
+
+impl SaslState {
+    /// Return true if the request is a SASL token, which must be forwarded as it is
+    pub(crate) fn is_raw_request(&self) -> bool {
+        let mut stage = self.stage.lock().unwrap();
+        match &mut *stage {
+            SaslStage::RawTokens {
+                remaining_requests,
+                pending_responses,
+                ..
+            } if *remaining_requests > 0 => {
+                *remaining_requests -= 1;
+                *pending_responses += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Return true if the response is a SASL token, which must be forwarded as it is
+    pub(crate) fn is_raw_response(&self, token: &[u8]) -> bool {
+        let mut stage = self.stage.lock().unwrap();
+        let SaslStage::RawTokens {
+            mechanism,
+            remaining_requests,
+            pending_responses,
+        } = &mut *stage
+        else {
+            return false;
+        };
+        if *pending_responses == 0 {
+            return false;
+        }
+        *pending_responses -= 1;
+
+        // A failed OAUTHBEARER authentication returns an error challenge,
+        // which the client acknowledges before the server closes the connection
+        if *mechanism == SaslMechanism::OAuthBearer && !token.is_empty() {
+            *remaining_requests += 1;
+        }
+
+        if *remaining_requests == 0 && *pending_responses == 0 {
+            debug!("sasl authentication with {mechanism:?} completed");
+            *stage = SaslStage::KafkaMessages;
+        }
+        true
+    }
+
+    pub(crate) fn intercept_handshake_request(
+        &self,
+        buffer: &mut Bytes,
+        header: &RequestHeader,
+    ) -> Result<(), InterceptError> {
+        let request: SaslHandshakeRequest = decode_body(buffer, header.request_api_version)?;
+        let mechanism = SaslMechanism::parse(request.mechanism.as_str());
+        debug!(
+            "sasl handshake request: version {}, mechanism {}",
+            header.request_api_version,
+            request.mechanism.as_str()
+        );
+
+        *self.stage.lock().unwrap() = SaslStage::Handshake {
+            version: header.request_api_version,
+            mechanism,
+        };
+        Ok(())
+    }
+
+    pub(crate) fn intercept_handshake_response(
+        &self,
+        buffer: &mut Bytes,
+        request_info: &RequestInfo,
+    ) -> Result<(), InterceptError> {
+        let response: SaslHandshakeResponse =
+            decode_body(buffer, request_info.request_api_version)?;
+
+        let mut stage = self.stage.lock().unwrap();
+        let SaslStage::Handshake { version, mechanism } = *stage else {
+            return Ok(());
+        };
+        *stage = SaslStage::KafkaMessages;
+
+        if response.error_code != 0 || version > 0 {
+            return Ok(());
+        }
+        match mechanism {
+            Some(mechanism) => {
+                *stage = SaslStage::RawTokens {
+                    mechanism,
+                    remaining_requests: mechanism.client_tokens(),
+                    pending_responses: 0,
+                };
+                Ok(())
+            }
+            None => {
+                // the tokens can't be told apart from the kafka messages
+                warn!("unsupported sasl mechanism for a version 0 handshake, closing connection");
+                Err(InterceptError::Io(Error::from(ErrorKind::Unsupported)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw_tokens(mechanism: SaslMechanism) -> SaslState {
+        SaslState {
+            stage: Mutex::new(SaslStage::RawTokens {
+                mechanism,
+                remaining_requests: mechanism.client_tokens(),
+                pending_responses: 0,
+            }),
+        }
+    }
+
+    #[test]
+    fn scram_tokens_are_forwarded_until_the_final_server_message() {
+        let state = raw_tokens(SaslMechanism::ScramSha512);
+
+        assert!(state.is_raw_request());
+        assert!(state.is_raw_response(b"r=nonce,s=salt,i=4096"));
+        assert!(state.is_raw_request());
+        assert!(state.is_raw_response(b"v=signature"));
+
+        // the following messages are kafka messages again
+        assert!(!state.is_raw_request());
+        assert!(!state.is_raw_response(b""));
+    }
+
+    #[test]
+    fn oauthbearer_error_challenge_is_acknowledged() {
+        let state = raw_tokens(SaslMechanism::OAuthBearer);
+
+        assert!(state.is_raw_request());
+        assert!(state.is_raw_response(b"{\"status\":\"invalid_token\"}"));
+        // the client acknowledges the error with a single 0x01 byte
+        assert!(state.is_raw_request());
+        assert!(state.is_raw_response(b""));
+        assert!(!state.is_raw_request());
+    }
+
+    #[test]
+    fn kafka_messages_are_not_tokens() {
+        let state = SaslState::default();
+        assert!(!state.is_raw_request());
+        assert!(!state.is_raw_response(b""));
+    }
+}
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod test {
     use crate::kafka::inlet_controller::KafkaInletController;
+    use crate::kafka::protocol_aware::split_schema_id;
     use crate::kafka::protocol_aware::utils::{encode_request, encode_response};
     use crate::kafka::protocol_aware::InletInterceptorImpl;
     use crate::kafka::protocol_aware::KafkaMessageInterceptor;
@@ -61,6 +62,8 @@ mod test {
             ConsumerPublishing::None,
             consumer_policy_access_control,
             producer_policy_access_control,
+            None,
+            None,
         );
 
         let interceptor = InletInterceptorImpl::new(
@@ -68,6 +71,10 @@ mod test {
             Default::default(),
             inlet_map,
             true,
+            false,
+            Default::default(),
+            Default::default(),
+            Default::default(),
         );
 
         let mut correlation_id = 0;
@@ -201,4 +208,16 @@ mod test {
         }
         Ok(())
     }
+
+    #[test]
+    fn schema_id_is_split_from_the_record_content() {
+        // magic byte, then schema id 7
+        let value = [0, 0, 0, 0, 7, b'a', b'v', b'r', b'o'];
+        assert_eq!(split_schema_id(&value), (&value[..5], &value[5..]));
+
+        // a value which wasn't serialized for a Schema Registry
+        let value = [0xA2, 0x01, 0x02];
+        assert_eq!(split_schema_id(&value), (&[][..], &value[..]));
+        assert_eq!(split_schema_id(&[0, 1]), (&[][..], &[0, 1][..]));
+    }
 }
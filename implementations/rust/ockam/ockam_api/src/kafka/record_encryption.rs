@@ -0,0 +1,135 @@
+use hmac::{Hmac, Mac};
+use minicbor::{Decode, Encode};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use sha2::Sha256;
+
+/// Encryption of the record keys and headers, in addition to the record values.
+///
+/// The producers and the consumers of a topic must use the same settings, since the
+/// encrypted keys and headers can't be told apart from plaintext ones.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RecordEncryption {
+    #[n(1)] encrypt_keys: bool,
+    #[n(2)] deterministic_keys: bool,
+    #[n(3)] encrypted_headers: Vec<String>,
+}
+
+impl RecordEncryption {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Encrypt the record keys of the topics without a topic policy
+    pub fn with_encrypted_keys(mut self) -> Self {
+        self.encrypt_keys = true;
+        self
+    }
+
+    /// Always encrypt a given record key the same way, so that the records with the same key
+    /// are still partitioned and compacted together. The keys of each topic are provided by
+    /// the Kafka outlet
+    pub fn with_deterministic_keys(mut self) -> Self {
+        self.deterministic_keys = true;
+        self
+    }
+
+    /// Encrypt the values of the record headers with a given name
+    pub fn with_encrypted_header(mut self, name: impl Into<String>) -> Self {
+        self.encrypted_headers.push(name.into());
+        self
+    }
+
+    pub fn encrypt_keys(&self) -> bool {
+        self.encrypt_keys
+    }
+
+    pub fn deterministic_keys(&self) -> bool {
+        self.deterministic_keys
+    }
+
+    pub fn encrypted_headers(&self) -> &[String] {
+        &self.encrypted_headers
+    }
+
+    pub(crate) fn is_encrypted_header(&self, name: &str) -> bool {
+        self.encrypted_headers.iter().any(|header| header == name)
+    }
+
+    /// Check that the header names are valid
+    pub fn validate(&self) -> Result<()> {
+        if self
+            .encrypted_headers
+            .iter()
+            .any(|header| header.is_empty())
+        {
+            return Err(Error::new(
+                Origin::Api,
+                Kind::Invalid,
+                "The name of an encrypted header can't be empty",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Request sent by a Kafka inlet to the record keys service of a Kafka outlet
+#[derive(Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub(crate) struct TopicKeyRequest {
+    #[n(1)] pub(crate) topic: String,
+}
+
+/// Key from which a Kafka inlet derives the keys used to deterministically encrypt the
+/// record keys of a topic
+#[derive(Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub(crate) struct TopicKeyResponse {
+    #[cbor(n(1), with = "minicbor::bytes")] pub(crate) key: Vec<u8>,
+}
+
+/// Derive the key of a topic from the secret of a Kafka outlet
+pub(crate) fn derive_topic_key(secret: &[u8], topic: &str) -> Vec<u8> {
+    hmac_sha256(secret, &[b"kafka topic key", topic.as_bytes()])
+}
+
+pub(crate) fn hmac_sha256(key: &[u8], data: &[&[u8]]) -> Vec<u8> {
+    // HMAC accepts keys of any length
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("invalid HMAC key length");
+    for chunk in data {
+        mac.update(chunk);
+    }
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn topic_keys_are_stable_and_distinct() {
+        let secret = [7; 32];
+        let key = derive_topic_key(&secret, "payments");
+
+        assert_eq!(key.len(), 32);
+        assert_eq!(key, derive_topic_key(&secret, "payments"));
+        assert_ne!(key, derive_topic_key(&secret, "orders"));
+        assert_ne!(key, derive_topic_key(&[8; 32], "payments"));
+    }
+
+    #[test]
+    fn encrypted_headers_are_matched_by_name() {
+        let record_encryption = RecordEncryption::new().with_encrypted_header("user-id");
+
+        assert!(record_encryption.is_encrypted_header("user-id"));
+        assert!(!record_encryption.is_encrypted_header("trace-id"));
+        assert!(RecordEncryption::new()
+            .with_encrypted_header("")
+            .validate()
+            .is_err());
+    }
+}
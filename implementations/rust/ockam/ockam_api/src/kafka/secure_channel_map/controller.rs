@@ -1,3 +1,4 @@
+use crate::kafka::secure_channel_map::record_keys::TopicKey;
 use crate::kafka::secure_channel_map::{KafkaEncryptedContent, TopicPartition};
 use crate::kafka::{ConsumerPublishing, ConsumerResolution};
 use crate::nodes::NodeManager;
@@ -8,8 +9,10 @@ use ockam::identity::{
 use ockam_abac::PolicyAccessControl;
 use ockam_core::compat::collections::{HashMap, HashSet};
 use ockam_core::{route, Address};
+use ockam_multiaddr::MultiAddr;
 use ockam_node::Context;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 
 #[derive(Clone)]
@@ -22,6 +25,8 @@ pub(crate) struct KafkaSecureChannelControllerImpl {
 /// and uses them to encrypt the content.
 /// Multiple secure channels may be created for the same topic/partition
 /// but each will be explicitly labeled.
+/// The secure channels are lazily replaced once they are older than the key rotation
+/// interval, so that a consumer which is not authorized anymore can't read the new records.
 impl KafkaSecureChannelControllerImpl {
     /// Encrypts the content specifically for the consumer waiting for that topic name and
     /// partition.
@@ -128,10 +133,19 @@ impl KafkaSecureChannelControllerImpl {
     }
 }
 
+/// Key exchange only secure channel used to encrypt the records of a topic partition
+#[derive(Clone)]
+pub(crate) struct TopicEncryptor {
+    pub(crate) encryptor_address: Address,
+    /// Identifier of the consumer which received the key
+    pub(crate) consumer: Identifier,
+    pub(crate) created_at: Instant,
+}
+
 pub struct InnerSecureChannelControllerImpl {
     // we identify the secure channel instance by using the decryptor address of the consumer
     // which is known to both parties
-    pub(crate) topic_encryptor_map: HashMap<TopicPartition, Address>,
+    pub(crate) topic_encryptor_map: HashMap<TopicPartition, TopicEncryptor>,
     // since topic/partition is using a key exchange only secure channel,
     // we need another secure channel for each consumer identifier
     // to make sure the relative credential is properly updated
@@ -145,9 +159,15 @@ pub struct InnerSecureChannelControllerImpl {
     pub(crate) secure_channels: Arc<SecureChannels>,
     pub(crate) consumer_policy_access_control: PolicyAccessControl,
     pub(crate) producer_policy_access_control: PolicyAccessControl,
+    // the keys are never rotated when it's not set
+    pub(crate) key_rotation_interval: Option<Duration>,
+    // service of the Kafka outlet giving the keys to deterministically encrypt the record keys
+    pub(crate) record_keys_service: Option<MultiAddr>,
+    pub(crate) topic_key_map: HashMap<String, TopicKey>,
 }
 
 impl KafkaSecureChannelControllerImpl {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         node_manager: Arc<NodeManager>,
         secure_channels: Arc<SecureChannels>,
@@ -155,6 +175,8 @@ impl KafkaSecureChannelControllerImpl {
         consumer_publishing: ConsumerPublishing,
         consumer_policy_access_control: PolicyAccessControl,
         producer_policy_access_control: PolicyAccessControl,
+        key_rotation_interval: Option<Duration>,
+        record_keys_service: Option<MultiAddr>,
     ) -> KafkaSecureChannelControllerImpl {
         Self {
             inner: Arc::new(Mutex::new(InnerSecureChannelControllerImpl {
@@ -167,6 +189,9 @@ impl KafkaSecureChannelControllerImpl {
                 consumer_publishing,
                 consumer_policy_access_control,
                 producer_policy_access_control,
+                key_rotation_interval,
+                record_keys_service,
+                topic_key_map: Default::default(),
             })),
         }
     }
@@ -4,6 +4,7 @@ use ockam_core::Address;
 use ockam_multiaddr::MultiAddr;
 
 pub(crate) mod controller;
+mod record_keys;
 pub(crate) mod relays;
 mod secure_channels;
 
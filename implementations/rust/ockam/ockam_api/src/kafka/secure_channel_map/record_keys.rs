@@ -0,0 +1,153 @@
+use crate::kafka::record_encryption::{hmac_sha256, TopicKeyRequest, TopicKeyResponse};
+use crate::kafka::secure_channel_map::controller::KafkaSecureChannelControllerImpl;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{AsyncTryClone, Error, Result};
+use ockam_node::Context;
+use ockam_vault::{AeadAlgorithm, AeadSecretKeyHandle, VaultForSecureChannels};
+use std::sync::Arc;
+
+/// Length of the nonce prepended to a deterministically encrypted record key
+const NONCE_LENGTH: usize = 12;
+
+/// Keys used to deterministically encrypt the record keys of a topic
+#[derive(Clone)]
+pub(crate) struct TopicKey {
+    /// Key used to derive the nonce of a record key from its content
+    nonce_key: Vec<u8>,
+    encryption_key: AeadSecretKeyHandle,
+}
+
+impl TopicKey {
+    /// Derive the nonce and encryption keys from the key sent by the Kafka outlet
+    async fn derive(vault: &Arc<dyn VaultForSecureChannels>, topic_key: &[u8]) -> Result<Self> {
+        let nonce_key = hmac_sha256(topic_key, &[b"nonce"]);
+        let encryption_key = vault
+            .import_secret_buffer(hmac_sha256(topic_key, &[b"encryption"]))
+            .await?;
+        let encryption_key = vault
+            .convert_secret_buffer_to_aead_key(encryption_key, AeadAlgorithm::AesGcm)
+            .await?;
+        Ok(Self {
+            nonce_key,
+            encryption_key,
+        })
+    }
+
+    /// Like with AES-SIV, the nonce is derived from the record key, so that a record key
+    /// is always encrypted the same way while two different keys never share a nonce
+    fn nonce_for(&self, record_key: &[u8]) -> Vec<u8> {
+        let mut nonce = hmac_sha256(&self.nonce_key, &[record_key]);
+        nonce.truncate(NONCE_LENGTH);
+        nonce
+    }
+}
+
+impl KafkaSecureChannelControllerImpl {
+    /// Encrypts a record key with the key of its topic, so that the records with the same
+    /// key are still sent to the same partition, and compacted together.
+    /// The key of the topic is retrieved from the Kafka outlet the first time it is used.
+    pub async fn encrypt_record_key_for(
+        &self,
+        context: &mut Context,
+        topic_name: &str,
+        record_key: &[u8],
+    ) -> Result<Vec<u8>> {
+        let (vault, topic_key) = self.get_or_fetch_topic_key(context, topic_name).await?;
+
+        let nonce = topic_key.nonce_for(record_key);
+        let mut encrypted_key = nonce.clone();
+        vault
+            .aead_encrypt(
+                &mut encrypted_key,
+                &topic_key.encryption_key,
+                record_key,
+                &nonce,
+                topic_name.as_bytes(),
+            )
+            .await?;
+        Ok(encrypted_key)
+    }
+
+    /// Decrypts a record key encrypted with [`Self::encrypt_record_key_for`]
+    pub async fn decrypt_record_key_for(
+        &self,
+        context: &mut Context,
+        topic_name: &str,
+        encrypted_key: &[u8],
+    ) -> Result<Vec<u8>> {
+        if encrypted_key.len() < NONCE_LENGTH {
+            return Err(invalid_record_key(topic_name));
+        }
+        let (vault, topic_key) = self.get_or_fetch_topic_key(context, topic_name).await?;
+
+        let (nonce, cipher_text) = encrypted_key.split_at(NONCE_LENGTH);
+        let record_key = vault
+            .aead_decrypt(
+                &topic_key.encryption_key,
+                cipher_text,
+                nonce,
+                topic_name.as_bytes(),
+            )
+            .await?;
+
+        // the nonce must be the one derived from the decrypted key
+        if topic_key.nonce_for(&record_key) != nonce {
+            return Err(invalid_record_key(topic_name));
+        }
+        Ok(record_key)
+    }
+
+    async fn get_or_fetch_topic_key(
+        &self,
+        context: &mut Context,
+        topic_name: &str,
+    ) -> Result<(Arc<dyn VaultForSecureChannels>, TopicKey)> {
+        let mut inner = self.inner.lock().await;
+        let vault = inner.secure_channels.vault().secure_channel_vault.clone();
+        if let Some(topic_key) = inner.topic_key_map.get(topic_name) {
+            return Ok((vault, topic_key.clone()));
+        }
+
+        let record_keys_service = inner.record_keys_service.clone().ok_or_else(|| {
+            Error::new(
+                Origin::Api,
+                Kind::Invalid,
+                "The record keys can't be encrypted deterministically without a Kafka outlet",
+            )
+        })?;
+
+        debug!("retrieving the record keys of the topic {topic_name}");
+        let connection = inner
+            .node_manager
+            .make_connection(
+                Arc::new(context.async_try_clone().await?),
+                &record_keys_service,
+                inner.node_manager.identifier(),
+                None,
+                None,
+            )
+            .await?;
+        let request = TopicKeyRequest {
+            topic: topic_name.to_string(),
+        };
+        let response: Result<Vec<u8>> = context
+            .send_and_receive(connection.route()?, minicbor::to_vec(request)?)
+            .await;
+        connection.close(context, &inner.node_manager).await?;
+        let response: TopicKeyResponse = minicbor::decode(&response?)?;
+
+        let topic_key = TopicKey::derive(&vault, &response.key).await?;
+        inner
+            .topic_key_map
+            .insert(topic_name.to_string(), topic_key.clone());
+        Ok((vault, topic_key))
+    }
+}
+
+fn invalid_record_key(topic_name: &str) -> Error {
+    Error::new(
+        Origin::Api,
+        Kind::Invalid,
+        format!("Invalid encrypted record key for the topic {topic_name}"),
+    )
+}
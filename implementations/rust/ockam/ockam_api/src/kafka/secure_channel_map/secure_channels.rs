@@ -1,5 +1,5 @@
 use crate::kafka::secure_channel_map::controller::{
-    InnerSecureChannelControllerImpl, KafkaSecureChannelControllerImpl,
+    InnerSecureChannelControllerImpl, KafkaSecureChannelControllerImpl, TopicEncryptor,
 };
 use crate::kafka::ConsumerResolution;
 use crate::nodes::service::SecureChannelType;
@@ -10,6 +10,7 @@ use ockam_core::{Address, Error, Result};
 use ockam_multiaddr::proto::{Secure, Service};
 use ockam_multiaddr::MultiAddr;
 use ockam_node::Context;
+use std::time::Instant;
 use tokio::sync::MutexGuard;
 
 impl KafkaSecureChannelControllerImpl {
@@ -74,6 +75,8 @@ impl KafkaSecureChannelControllerImpl {
     }
 
     /// Creates a secure channel from the producer to the consumer needed to encrypt messages.
+    /// The secure channel is replaced, and new keys are exchanged, once it is older than the
+    /// key rotation interval.
     /// Returns the relative secure channel entry.
     pub(crate) async fn get_or_create_secure_channel_for(
         &self,
@@ -83,101 +86,50 @@ impl KafkaSecureChannelControllerImpl {
     ) -> Result<SecureChannelRegistryEntry> {
         let mut inner = self.inner.lock().await;
 
-        // when we have only one consumer, we use the same secure channel for all topics
+        // when we have only one consumer, it reads all the partitions of a topic
         let topic_partition_key = match &inner.consumer_resolution {
-            ConsumerResolution::SingleNode(_) | ConsumerResolution::None => ("".to_string(), 0i32),
+            ConsumerResolution::SingleNode(_) | ConsumerResolution::None => {
+                (topic_name.to_string(), 0i32)
+            }
             ConsumerResolution::ViaRelay(_) => (topic_name.to_string(), partition),
         };
 
-        let encryptor_address = {
-            if let Some(encryptor_address) = inner.topic_encryptor_map.get(&topic_partition_key) {
-                encryptor_address.clone()
-            } else {
-                // destination is without the final service
-                let destination = match inner.consumer_resolution.clone() {
-                    ConsumerResolution::SingleNode(mut destination) => {
-                        debug!("creating new direct secure channel to consumer: {destination}");
-                        // remove /secure/api service from the destination if present
-                        if let Some(service) = destination.last() {
-                            let service: Option<Secure> = service.cast();
-                            if let Some(service) = service {
-                                if service.as_bytes()
-                                    == DefaultAddress::SECURE_CHANNEL_LISTENER.as_bytes()
-                                {
-                                    destination.pop_back();
-                                }
-                            }
-                        }
-                        destination
-                    }
-                    ConsumerResolution::ViaRelay(mut destination) => {
-                        // consumer_ is the arbitrary chosen prefix by both parties
-                        let topic_partition_address =
-                            format!("forward_to_consumer_{topic_name}_{partition}");
-                        debug!(
-                            "creating new secure channel via relay to {topic_partition_address}"
-                        );
-                        destination.push_back(Service::new(topic_partition_address))?;
-                        destination
-                    }
-                    ConsumerResolution::None => {
-                        return Err(Error::new(
-                            Origin::Transport,
-                            Kind::Invalid,
-                            "cannot encrypt messages with consumer key when consumer route resolution is not set",
-                        ));
+        let previous = inner.topic_encryptor_map.get(&topic_partition_key).cloned();
+        let encryptor_address = match previous {
+            Some(previous) if !Self::is_expired(&inner, &previous) => previous.encryptor_address,
+            previous => {
+                // an expired key is not used anymore, even if a new key can't be exchanged
+                if let Some(previous) = &previous {
+                    debug!("rotating the key of the topic {topic_name}, partition {partition}");
+                    inner.topic_encryptor_map.remove(&topic_partition_key);
+                    // the consumer keeps its decryptor to read the records already encrypted
+                    if let Err(err) =
+                        Self::delete_secure_channel(&inner, context, &previous.encryptor_address)
+                            .await
+                    {
+                        warn!("cannot delete the secure channel of an expired key: {err}");
                     }
-                };
-
-                let producer_encryptor_address = Self::create_key_exchange_only_secure_channel(
-                    &inner,
-                    context,
-                    destination.clone(),
-                )
-                .await?;
+                }
 
-                if let Some(entry) = inner
-                    .secure_channels
-                    .secure_channel_registry()
-                    .get_channel_by_encryptor_address(&producer_encryptor_address)
-                {
-                    if let Err(error) = Self::validate_consumer_credentials(&inner, &entry).await {
-                        Self::delete_secure_channel(&inner, context, &producer_encryptor_address)
-                            .await?;
-                        return Err(error);
-                    };
+                let encryptor =
+                    Self::create_topic_encryptor(&inner, context, topic_name, partition).await?;
 
-                    // creates a dedicated secure channel to the consumer to keep the
-                    // credentials up to date
-                    if !inner.identity_encryptor_map.contains_key(entry.their_id()) {
-                        if let Err(err) =
-                            Self::create_secure_channel(&inner, context, destination).await
-                        {
-                            Self::delete_secure_channel(
-                                &inner,
-                                context,
-                                &producer_encryptor_address,
-                            )
-                            .await?;
-                            return Err(err);
-                        }
-                    }
-                } else {
-                    return Err(Error::new(
-                        Origin::Transport,
-                        Kind::Internal,
-                        format!(
-                            "cannot find secure channel address `{producer_encryptor_address}` in local registry"
-                        ),
-                    ));
+                // the partition was assigned to another consumer of the group, or a consumer
+                // was enrolled in the meantime
+                if previous.is_some_and(|previous| previous.consumer != encryptor.consumer) {
+                    info!(
+                        consumer = %encryptor.consumer,
+                        "sharing a new key of the topic {topic_name}, partition {partition}"
+                    );
                 }
 
+                let encryptor_address = encryptor.encryptor_address.clone();
                 inner
                     .topic_encryptor_map
-                    .insert(topic_partition_key, producer_encryptor_address.clone());
+                    .insert(topic_partition_key, encryptor);
 
                 debug!("created secure channel");
-                producer_encryptor_address
+                encryptor_address
             }
         };
 
@@ -194,6 +146,95 @@ impl KafkaSecureChannelControllerImpl {
             })
     }
 
+    fn is_expired(
+        inner: &MutexGuard<'_, InnerSecureChannelControllerImpl>,
+        encryptor: &TopicEncryptor,
+    ) -> bool {
+        inner
+            .key_rotation_interval
+            .is_some_and(|interval| encryptor.created_at.elapsed() >= interval)
+    }
+
+    /// Exchanges a new key with the consumer of a topic partition, after checking that
+    /// the consumer is authorized
+    async fn create_topic_encryptor(
+        inner: &MutexGuard<'_, InnerSecureChannelControllerImpl>,
+        context: &Context,
+        topic_name: &str,
+        partition: i32,
+    ) -> Result<TopicEncryptor> {
+        // destination is without the final service
+        let destination = match inner.consumer_resolution.clone() {
+            ConsumerResolution::SingleNode(mut destination) => {
+                debug!("creating new direct secure channel to consumer: {destination}");
+                // remove /secure/api service from the destination if present
+                if let Some(service) = destination.last() {
+                    let service: Option<Secure> = service.cast();
+                    if let Some(service) = service {
+                        if service.as_bytes() == DefaultAddress::SECURE_CHANNEL_LISTENER.as_bytes()
+                        {
+                            destination.pop_back();
+                        }
+                    }
+                }
+                destination
+            }
+            ConsumerResolution::ViaRelay(mut destination) => {
+                // consumer_ is the arbitrary chosen prefix by both parties
+                let topic_partition_address =
+                    format!("forward_to_consumer_{topic_name}_{partition}");
+                debug!("creating new secure channel via relay to {topic_partition_address}");
+                destination.push_back(Service::new(topic_partition_address))?;
+                destination
+            }
+            ConsumerResolution::None => {
+                return Err(Error::new(
+                    Origin::Transport,
+                    Kind::Invalid,
+                    "cannot encrypt messages with consumer key when consumer route resolution is not set",
+                ));
+            }
+        };
+
+        let producer_encryptor_address =
+            Self::create_key_exchange_only_secure_channel(inner, context, destination.clone())
+                .await?;
+
+        let Some(entry) = inner
+            .secure_channels
+            .secure_channel_registry()
+            .get_channel_by_encryptor_address(&producer_encryptor_address)
+        else {
+            return Err(Error::new(
+                Origin::Transport,
+                Kind::Internal,
+                format!(
+                    "cannot find secure channel address `{producer_encryptor_address}` in local registry"
+                ),
+            ));
+        };
+
+        if let Err(error) = Self::validate_consumer_credentials(inner, &entry).await {
+            Self::delete_secure_channel(inner, context, &producer_encryptor_address).await?;
+            return Err(error);
+        };
+
+        // creates a dedicated secure channel to the consumer to keep the
+        // credentials up to date
+        if !inner.identity_encryptor_map.contains_key(entry.their_id()) {
+            if let Err(err) = Self::create_secure_channel(inner, context, destination).await {
+                Self::delete_secure_channel(inner, context, &producer_encryptor_address).await?;
+                return Err(err);
+            }
+        }
+
+        Ok(TopicEncryptor {
+            encryptor_address: producer_encryptor_address,
+            consumer: entry.their_id().clone(),
+            created_at: Instant::now(),
+        })
+    }
+
     async fn validate_consumer_credentials(
         inner: &MutexGuard<'_, InnerSecureChannelControllerImpl>,
         entry: &SecureChannelRegistryEntry,
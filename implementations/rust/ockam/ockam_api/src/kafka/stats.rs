@@ -0,0 +1,117 @@
+use crate::nodes::models::services::KafkaTopicStats;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::Mutex;
+
+/// Traffic counters of a Kafka inlet, per topic, shared by all the connections it accepted
+#[derive(Debug, Default)]
+pub(crate) struct KafkaPortalStats {
+    topics: Mutex<BTreeMap<String, TopicCounters>>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct TopicCounters {
+    produced_batches: u64,
+    encrypted_produced_batches: u64,
+    produced_bytes: u64,
+    fetched_batches: u64,
+    decrypted_fetched_batches: u64,
+    fetched_bytes: u64,
+    decryption_failures: u64,
+}
+
+impl KafkaPortalStats {
+    /// Count the records sent by a producer to a partition of a topic.
+    /// The size is the size of the records before their encryption
+    pub(crate) fn add_produced_batch(&self, topic_name: &str, bytes: usize, encrypted: bool) {
+        self.update(topic_name, |counters| {
+            counters.produced_batches += 1;
+            counters.produced_bytes += bytes as u64;
+            if encrypted {
+                counters.encrypted_produced_batches += 1;
+            }
+        })
+    }
+
+    /// Count the records fetched by a consumer from a partition of a topic.
+    /// The size is the size of the records returned by the broker, before their decryption
+    pub(crate) fn add_fetched_batch(&self, topic_name: &str, bytes: usize, decrypted: bool) {
+        self.update(topic_name, |counters| {
+            counters.fetched_batches += 1;
+            counters.fetched_bytes += bytes as u64;
+            if decrypted {
+                counters.decrypted_fetched_batches += 1;
+            }
+        })
+    }
+
+    /// Count the fetched records which could not be decrypted
+    pub(crate) fn add_decryption_failure(&self, topic_name: &str) {
+        self.update(topic_name, |counters| counters.decryption_failures += 1)
+    }
+
+    /// Return the counters of each topic, sorted by topic name
+    pub(crate) fn topics(&self) -> Vec<KafkaTopicStats> {
+        self.topics
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(topic, counters)| KafkaTopicStats {
+                topic: topic.clone(),
+                produced_batches: counters.produced_batches,
+                encrypted_produced_batches: counters.encrypted_produced_batches,
+                produced_bytes: counters.produced_bytes,
+                fetched_batches: counters.fetched_batches,
+                decrypted_fetched_batches: counters.decrypted_fetched_batches,
+                fetched_bytes: counters.fetched_bytes,
+                decryption_failures: counters.decryption_failures,
+            })
+            .collect()
+    }
+
+    fn update(&self, topic_name: &str, f: impl FnOnce(&mut TopicCounters)) {
+        let mut topics = self.topics.lock().unwrap();
+        match topics.get_mut(topic_name) {
+            Some(counters) => f(counters),
+            None => {
+                let mut counters = TopicCounters::default();
+                f(&mut counters);
+                topics.insert(topic_name.to_string(), counters);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_batches_per_topic() {
+        let stats = KafkaPortalStats::default();
+        stats.add_produced_batch("payments", 10, true);
+        stats.add_produced_batch("payments", 5, false);
+        stats.add_fetched_batch("payments", 20, true);
+        stats.add_decryption_failure("payments");
+        stats.add_fetched_batch("logs", 7, false);
+
+        let topics = stats.topics();
+        assert_eq!(topics.len(), 2);
+
+        let logs = &topics[0];
+        assert_eq!(logs.topic, "logs");
+        assert_eq!(logs.produced_batches, 0);
+        assert_eq!(logs.fetched_batches, 1);
+        assert_eq!(logs.decrypted_fetched_batches, 0);
+        assert_eq!(logs.fetched_bytes, 7);
+
+        let payments = &topics[1];
+        assert_eq!(payments.topic, "payments");
+        assert_eq!(payments.produced_batches, 2);
+        assert_eq!(payments.encrypted_produced_batches, 1);
+        assert_eq!(payments.produced_bytes, 15);
+        assert_eq!(payments.fetched_batches, 1);
+        assert_eq!(payments.decrypted_fetched_batches, 1);
+        assert_eq!(payments.fetched_bytes, 20);
+        assert_eq!(payments.decryption_failures, 1);
+    }
+}
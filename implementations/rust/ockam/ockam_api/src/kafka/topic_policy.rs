@@ -0,0 +1,350 @@
+use minicbor::{Decode, Encode};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Suffix of the topic patterns matching every topic starting with the same prefix
+const TOPIC_WILDCARD: char = '*';
+
+/// How the records of a topic are handled by a Kafka inlet
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum TopicMode {
+    /// The record values are encrypted end-to-end
+    #[n(1)] Encrypt,
+    /// The records are forwarded as they are
+    #[n(2)] Plaintext,
+    /// The topic can neither be produced to nor consumed from
+    #[n(3)] Deny,
+}
+
+impl TopicMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TopicMode::Encrypt => "encrypt",
+            TopicMode::Plaintext => "plaintext",
+            TopicMode::Deny => "deny",
+        }
+    }
+}
+
+impl FromStr for TopicMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "encrypt" => Ok(TopicMode::Encrypt),
+            "plaintext" => Ok(TopicMode::Plaintext),
+            "deny" => Ok(TopicMode::Deny),
+            _ => Err(topic_policy_error(format!("Unknown topic mode {s}"))),
+        }
+    }
+}
+
+/// Settings of the topics matching a name or a prefix.
+///
+/// It's parsed from `TOPIC:SETTINGS`, where `TOPIC` is a topic name or a prefix followed
+/// by `*`, and `SETTINGS` is a comma-separated list containing a mode (`encrypt`,
+/// `plaintext` or `deny`) and optionally `keys`, to encrypt the record keys as well.
+#[derive(Clone, Debug, PartialEq, Eq, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TopicPolicy {
+    #[n(1)] topic: String,
+    #[n(2)] mode: TopicMode,
+    #[n(3)] encrypt_keys: bool,
+}
+
+impl TopicPolicy {
+    pub fn new(topic: impl Into<String>, mode: TopicMode) -> Self {
+        Self {
+            topic: topic.into(),
+            mode,
+            encrypt_keys: false,
+        }
+    }
+
+    /// Encrypt the record keys, in addition to the record values
+    pub fn with_encrypted_keys(mut self) -> Self {
+        self.encrypt_keys = true;
+        self
+    }
+
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    pub fn mode(&self) -> TopicMode {
+        self.mode
+    }
+
+    pub fn encrypt_keys(&self) -> bool {
+        self.encrypt_keys
+    }
+
+    fn validate(&self) -> Result<()> {
+        validate_topic_pattern(&self.topic)?;
+        if self.encrypt_keys && self.mode != TopicMode::Encrypt {
+            return Err(topic_policy_error(format!(
+                "The keys of the topic {} can only be encrypted with the encrypt mode",
+                self.topic
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Display for TopicPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.topic, self.mode.as_str())?;
+        if self.encrypt_keys {
+            write!(f, ",keys")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for TopicPolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (topic, settings) = s
+            .trim()
+            .split_once(':')
+            .ok_or_else(|| topic_policy_error(format!("Missing settings in {s}")))?;
+
+        let mut mode = None;
+        let mut encrypt_keys = false;
+        for setting in settings.split(',').map(str::trim) {
+            if setting == "keys" {
+                encrypt_keys = true;
+            } else if mode.replace(TopicMode::from_str(setting)?).is_some() {
+                return Err(topic_policy_error(format!("Several modes in {s}")));
+            }
+        }
+
+        let policy = Self {
+            topic: topic.to_string(),
+            mode: mode.ok_or_else(|| topic_policy_error(format!("Missing mode in {s}")))?,
+            encrypt_keys,
+        };
+        policy.validate()?;
+        Ok(policy)
+    }
+}
+
+/// The topics which can be used through a Kafka inlet, and how their records are encrypted.
+///
+/// When a topic matches several policies, the policy for its exact name is used, or else
+/// the policy with the longest prefix. The topics without a policy are encrypted depending
+/// on the inlet configuration.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TopicPolicies {
+    #[n(1)] allowed_topics: Vec<String>,
+    #[n(2)] policies: Vec<TopicPolicy>,
+}
+
+/// Encryption of the records of an allowed topic
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct TopicSettings {
+    pub(crate) encrypt_values: bool,
+    pub(crate) encrypt_keys: bool,
+}
+
+impl TopicPolicies {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only allow the topics matching the allowed patterns. All the topics which are not
+    /// denied are allowed when no pattern is given
+    pub fn with_allowed_topic(mut self, topic: impl Into<String>) -> Self {
+        self.allowed_topics.push(topic.into());
+        self
+    }
+
+    pub fn with_policy(mut self, policy: TopicPolicy) -> Self {
+        self.policies.push(policy);
+        self
+    }
+
+    pub fn allowed_topics(&self) -> &[String] {
+        &self.allowed_topics
+    }
+
+    pub fn policies(&self) -> &[TopicPolicy] {
+        &self.policies
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.allowed_topics.is_empty() && self.policies.is_empty()
+    }
+
+    /// Check that all the topic patterns are valid
+    pub fn validate(&self) -> Result<()> {
+        for topic in &self.allowed_topics {
+            validate_topic_pattern(topic)?;
+        }
+        self.policies
+            .iter()
+            .try_for_each(|policy| policy.validate())
+    }
+
+    /// Return the settings of a topic, or `None` if the topic is denied.
+    /// `encrypt_content` and `encrypt_keys` are the encryption of the topics without a policy
+    pub(crate) fn settings_for(
+        &self,
+        topic: &str,
+        encrypt_content: bool,
+        encrypt_keys: bool,
+    ) -> Option<TopicSettings> {
+        if !self.allowed_topics.is_empty()
+            && !self.allowed_topics.iter().any(|t| topic_matches(t, topic))
+        {
+            return None;
+        }
+
+        let policy = self
+            .policies
+            .iter()
+            .filter(|policy| topic_matches(&policy.topic, topic))
+            .max_by_key(|policy| pattern_specificity(&policy.topic));
+
+        match policy {
+            None => Some(TopicSettings {
+                encrypt_values: encrypt_content,
+                encrypt_keys: encrypt_content && encrypt_keys,
+            }),
+            Some(policy) => match policy.mode {
+                TopicMode::Deny => None,
+                TopicMode::Plaintext => Some(TopicSettings {
+                    encrypt_values: false,
+                    encrypt_keys: false,
+                }),
+                TopicMode::Encrypt => Some(TopicSettings {
+                    encrypt_values: true,
+                    encrypt_keys: policy.encrypt_keys,
+                }),
+            },
+        }
+    }
+}
+
+fn topic_matches(pattern: &str, topic: &str) -> bool {
+    match pattern.strip_suffix(TOPIC_WILDCARD) {
+        Some(prefix) => topic.starts_with(prefix),
+        None => pattern == topic,
+    }
+}
+
+/// An exact topic name is more specific than any prefix
+fn pattern_specificity(pattern: &str) -> (bool, usize) {
+    (!pattern.ends_with(TOPIC_WILDCARD), pattern.len())
+}
+
+/// Kafka topic names only contain ASCII alphanumerics, '.', '_' and '-'
+fn validate_topic_pattern(pattern: &str) -> Result<()> {
+    let name = pattern.strip_suffix(TOPIC_WILDCARD).unwrap_or(pattern);
+    let is_valid = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    if !is_valid || (name.is_empty() && !pattern.ends_with(TOPIC_WILDCARD)) {
+        return Err(topic_policy_error(format!(
+            "Invalid topic pattern {pattern}"
+        )));
+    }
+    Ok(())
+}
+
+fn topic_policy_error(message: String) -> Error {
+    Error::new(Origin::Api, Kind::Invalid, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENCRYPTED: Option<TopicSettings> = Some(TopicSettings {
+        encrypt_values: true,
+        encrypt_keys: false,
+    });
+    const PLAINTEXT: Option<TopicSettings> = Some(TopicSettings {
+        encrypt_values: false,
+        encrypt_keys: false,
+    });
+
+    #[test]
+    fn parse_topic_policy() -> Result<()> {
+        let policy = TopicPolicy::from_str("payments:encrypt,keys")?;
+        let expected = TopicPolicy::new("payments", TopicMode::Encrypt);
+        assert_eq!(policy, expected.with_encrypted_keys());
+        assert_eq!(policy.to_string(), "payments:encrypt,keys");
+        assert_eq!(
+            TopicPolicy::from_str("logs.*:plaintext")?,
+            TopicPolicy::new("logs.*", TopicMode::Plaintext)
+        );
+
+        assert!(TopicPolicy::from_str("payments").is_err());
+        assert!(TopicPolicy::from_str("payments:keys").is_err());
+        assert!(TopicPolicy::from_str("payments:deny,plaintext").is_err());
+        assert!(TopicPolicy::from_str("logs:plaintext,keys").is_err());
+        assert!(TopicPolicy::from_str("lo*gs:deny").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn most_specific_policy_is_used() {
+        let audit = TopicPolicy::new("logs.audit", TopicMode::Encrypt);
+        let policies = TopicPolicies::new()
+            .with_policy(TopicPolicy::new("*", TopicMode::Deny))
+            .with_policy(TopicPolicy::new("logs.*", TopicMode::Plaintext))
+            .with_policy(audit.with_encrypted_keys());
+
+        assert_eq!(policies.settings_for("payments", true, false), None);
+        assert_eq!(policies.settings_for("logs.access", true, true), PLAINTEXT);
+        assert_eq!(
+            policies.settings_for("logs.audit", false, false),
+            Some(TopicSettings {
+                encrypt_values: true,
+                encrypt_keys: true,
+            })
+        );
+    }
+
+    #[test]
+    fn only_allowed_topics_are_used() {
+        let policies = TopicPolicies::new()
+            .with_allowed_topic("orders.*")
+            .with_policy(TopicPolicy::new("orders.internal", TopicMode::Deny));
+
+        assert_eq!(policies.settings_for("orders.eu", true, false), ENCRYPTED);
+        assert_eq!(policies.settings_for("orders.eu", false, false), PLAINTEXT);
+        assert_eq!(policies.settings_for("orders.internal", true, false), None);
+        assert_eq!(policies.settings_for("payments", true, false), None);
+        let no_policies = TopicPolicies::new();
+        assert_eq!(no_policies.settings_for("payments", true, false), ENCRYPTED);
+    }
+
+    #[test]
+    fn keys_are_encrypted_by_default_with_the_values() {
+        let policies = TopicPolicies::new()
+            .with_policy(TopicPolicy::new("orders", TopicMode::Encrypt))
+            .with_policy(TopicPolicy::new("logs", TopicMode::Plaintext));
+
+        assert_eq!(
+            policies.settings_for("payments", true, true),
+            Some(TopicSettings {
+                encrypt_values: true,
+                encrypt_keys: true,
+            })
+        );
+        assert_eq!(policies.settings_for("payments", false, true), PLAINTEXT);
+        // the policies tell explicitly if the keys are encrypted
+        assert_eq!(policies.settings_for("orders", true, true), ENCRYPTED);
+        assert_eq!(policies.settings_for("logs", true, true), PLAINTEXT);
+    }
+}
@@ -24,15 +24,20 @@ pub mod authenticator;
 pub mod cli_state;
 pub mod cloud;
 pub mod config;
+pub mod discovery;
 pub mod echoer;
 pub mod enroll;
 pub mod error;
 pub mod hop;
+pub mod http_gateway;
 pub mod kafka;
 pub mod minicbor_url;
+pub mod mqtt;
 pub mod nodes;
 pub mod okta;
 pub mod port_range;
+pub mod postgres;
+pub mod tun;
 pub mod uppercase;
 mod version;
 
@@ -0,0 +1,230 @@
+use crate::mqtt::packet::{
+    replace_server_reference, subscribed_topic_filters, Connect, Packet, ProtocolVersion, Publish,
+    CONNACK, CONNECT, DISCONNECT, PUBLISH, SUBSCRIBE,
+};
+use crate::mqtt::TopicKeys;
+use bytes::Bytes;
+use ockam_core::compat::collections::HashMap;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use ockam_node::Context;
+
+/// Intercepts the packets exchanged by an MQTT client and the broker, for a single connection.
+///
+/// The server references sent by an MQTT 5 broker are replaced by the address of the inlet,
+/// and when the topic keys are available, the payloads published by the client are encrypted
+/// while the payloads delivered by the broker are decrypted.
+pub(super) struct MqttInterceptor {
+    inlet_bind_address: String,
+    topic_keys: Option<Arc<TopicKeys>>,
+    state: Mutex<ConnectionState>,
+}
+
+#[derive(Default)]
+struct ConnectionState {
+    /// Set by the CONNECT packet, which is always the first packet of a connection
+    version: Option<ProtocolVersion>,
+    /// Topics of the aliases set by the client
+    client_topic_aliases: HashMap<u16, String>,
+    /// Topics of the aliases set by the broker
+    broker_topic_aliases: HashMap<u16, String>,
+}
+
+enum Sender {
+    Client,
+    Broker,
+}
+
+impl MqttInterceptor {
+    pub(super) fn new(inlet_bind_address: String, topic_keys: Option<Arc<TopicKeys>>) -> Self {
+        Self {
+            inlet_bind_address,
+            topic_keys,
+            state: Default::default(),
+        }
+    }
+
+    pub(super) async fn intercept_client_packet(
+        &self,
+        context: &mut Context,
+        packet: Packet,
+    ) -> Result<Packet> {
+        match packet.packet_type() {
+            CONNECT => self.intercept_connect(context, packet).await,
+            PUBLISH => {
+                self.intercept_publish(context, packet, Sender::Client)
+                    .await
+            }
+            SUBSCRIBE => self.intercept_subscribe(context, packet).await,
+            _ => Ok(packet),
+        }
+    }
+
+    pub(super) async fn intercept_broker_packet(
+        &self,
+        context: &mut Context,
+        packet: Packet,
+    ) -> Result<Packet> {
+        match packet.packet_type() {
+            PUBLISH => {
+                self.intercept_publish(context, packet, Sender::Broker)
+                    .await
+            }
+            CONNACK | DISCONNECT if self.version()? == ProtocolVersion::V5 => {
+                let replaced = replace_server_reference(&packet, &self.inlet_bind_address)?;
+                Ok(replaced.unwrap_or(packet))
+            }
+            _ => Ok(packet),
+        }
+    }
+
+    async fn intercept_connect(&self, context: &mut Context, packet: Packet) -> Result<Packet> {
+        let mut connect = Connect::decode(&packet)?;
+        self.state.lock().unwrap().version = Some(connect.version);
+
+        let (topic_keys, will) = match (&self.topic_keys, connect.will.as_mut()) {
+            (Some(topic_keys), Some(will)) if is_encrypted(&will.topic, &will.payload) => {
+                (topic_keys, will)
+            }
+            _ => return Ok(packet),
+        };
+        let payload = topic_keys
+            .encrypt(context, &will.topic, &will.payload)
+            .await?;
+        will.payload = payload.into();
+        will.remove_payload_format_indicator();
+        connect.encode()
+    }
+
+    async fn intercept_publish(
+        &self,
+        context: &mut Context,
+        packet: Packet,
+        sender: Sender,
+    ) -> Result<Packet> {
+        let topic_keys = match &self.topic_keys {
+            Some(topic_keys) => topic_keys,
+            None => return Ok(packet),
+        };
+        let mut publish = Publish::decode(&packet, self.version()?)?;
+        let topic = self.resolve_topic(&publish, &sender)?;
+        if !is_encrypted(&topic, &publish.payload) {
+            return Ok(packet);
+        }
+
+        let payload = match sender {
+            Sender::Client => {
+                publish.remove_payload_format_indicator();
+                topic_keys
+                    .encrypt(context, &topic, &publish.payload)
+                    .await?
+            }
+            // A message which can't be decrypted, for example because it was published without
+            // going through an MQTT inlet, must not prevent the delivery of the other packets
+            Sender::Broker => match topic_keys.decrypt(context, &topic, &publish.payload).await {
+                Ok(payload) => payload,
+                Err(error) => {
+                    warn!(
+                        %topic,
+                        %error,
+                        "could not decrypt the payload of a message, forwarding it unchanged"
+                    );
+                    return Ok(packet);
+                }
+            },
+        };
+        publish.payload = Bytes::from(payload);
+        Ok(publish.encode())
+    }
+
+    /// Fetch the keys of the topics subscribed by the client, when their filters don't have
+    /// wildcards, so that their messages can be decrypted as soon as they are delivered
+    async fn intercept_subscribe(&self, context: &mut Context, packet: Packet) -> Result<Packet> {
+        let topic_keys = match &self.topic_keys {
+            Some(topic_keys) => topic_keys,
+            None => return Ok(packet),
+        };
+        for subscription in subscribed_topic_filters(&packet, self.version()?)? {
+            let topic_filter = topic_filter_of(&subscription);
+            if !topic_filter.starts_with('$') && !topic_filter.contains(['+', '#']) {
+                topic_keys.fetch(context, topic_filter).await?;
+            }
+        }
+        Ok(packet)
+    }
+
+    /// Return the topic of a PUBLISH packet. With MQTT 5, the topic name can be replaced
+    /// by an alias, set by a previous packet of the same sender
+    fn resolve_topic(&self, publish: &Publish, sender: &Sender) -> Result<String> {
+        let topic_alias = match publish.topic_alias() {
+            Some(topic_alias) => topic_alias,
+            None => return Ok(publish.topic.clone()),
+        };
+
+        let mut state = self.state.lock().unwrap();
+        let topic_aliases = match sender {
+            Sender::Client => &mut state.client_topic_aliases,
+            Sender::Broker => &mut state.broker_topic_aliases,
+        };
+        if !publish.topic.is_empty() {
+            topic_aliases.insert(topic_alias, publish.topic.clone());
+            return Ok(publish.topic.clone());
+        }
+        topic_aliases.get(&topic_alias).cloned().ok_or_else(|| {
+            Error::new(
+                Origin::Transport,
+                Kind::Protocol,
+                format!("unknown mqtt topic alias {topic_alias}"),
+            )
+        })
+    }
+
+    fn version(&self) -> Result<ProtocolVersion> {
+        self.state.lock().unwrap().version.ok_or_else(|| {
+            Error::new(
+                Origin::Transport,
+                Kind::Protocol,
+                "the first mqtt packet of a connection must be a CONNECT packet",
+            )
+        })
+    }
+}
+
+/// Return the topic filter of a subscription, which is `$share/{group}/{topic filter}`
+/// for a shared subscription
+fn topic_filter_of(subscription: &str) -> &str {
+    let shared_subscription = subscription
+        .strip_prefix("$share/")
+        .and_then(|shared| shared.split_once('/'));
+    match shared_subscription {
+        Some((_group, topic_filter)) => topic_filter,
+        None => subscription,
+    }
+}
+
+/// The payloads of the system topics, starting with '$', are produced by the broker and never
+/// encrypted. An empty payload is not encrypted either, since publishing an empty retained
+/// message deletes the message retained for a topic
+fn is_encrypted(topic: &str, payload: &[u8]) -> bool {
+    !topic.starts_with('$') && !payload.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn topic_filters_of_shared_subscriptions() {
+        assert_eq!(topic_filter_of("sensors/+"), "sensors/+");
+        assert_eq!(topic_filter_of("$share/group/alerts"), "alerts");
+        assert_eq!(topic_filter_of("$SYS/broker/uptime"), "$SYS/broker/uptime");
+    }
+
+    #[test]
+    fn system_topics_and_empty_payloads_are_not_encrypted() {
+        assert!(is_encrypted("sensors/temperature", b"21.5"));
+        assert!(!is_encrypted("$SYS/broker/uptime", b"10"));
+        assert!(!is_encrypted("sensors/temperature", b""));
+    }
+}
@@ -0,0 +1,18 @@
+//! MQTT portals: the packets exchanged by an MQTT client and a broker are parsed by the
+//! inlet, so that the broker addresses sent to the clients can be rewritten and the
+//! payloads of the published messages can be encrypted end-to-end, from the publishers
+//! to the subscribers.
+
+mod interceptor;
+mod packet;
+mod portal_listener;
+mod portal_worker;
+mod topic_keys;
+
+pub(crate) use portal_listener::MqttPortalListener;
+pub(crate) use topic_keys::{TopicKeys, TopicKeysService};
+
+/// Address of the outlet to the MQTT broker
+pub const MQTT_OUTLET_BROKER_ADDRESS: &str = "mqtt_broker";
+/// Address of the service giving the keys used to encrypt the payloads of each topic
+pub const MQTT_OUTLET_TOPIC_KEYS_ADDRESS: &str = "mqtt_topic_keys";
@@ -0,0 +1,621 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+
+pub(super) const CONNECT: u8 = 1;
+pub(super) const CONNACK: u8 = 2;
+pub(super) const PUBLISH: u8 = 3;
+pub(super) const SUBSCRIBE: u8 = 8;
+pub(super) const DISCONNECT: u8 = 14;
+
+/// The remaining length of a packet is encoded on 4 bytes at most
+const MAX_REMAINING_LENGTH_BYTES: usize = 4;
+
+const WILL_FLAG: u8 = 0x04;
+const PAYLOAD_FORMAT_INDICATOR: u8 = 0x01;
+const TOPIC_ALIAS: u8 = 0x23;
+const SERVER_REFERENCE: u8 = 0x1C;
+
+/// Version of the MQTT protocol used by a connection, given by its CONNECT packet.
+/// The packets of MQTT 3.1 and 3.1.1 have the same layout
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum ProtocolVersion {
+    V3,
+    V5,
+}
+
+/// An MQTT control packet: its first byte, with the packet type and flags,
+/// then its variable header and payload
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct Packet {
+    first_byte: u8,
+    body: Bytes,
+}
+
+impl Packet {
+    pub(super) fn new(packet_type: u8, flags: u8, body: impl Into<Bytes>) -> Self {
+        Self {
+            first_byte: (packet_type << 4) | (flags & 0x0f),
+            body: body.into(),
+        }
+    }
+
+    pub(super) fn packet_type(&self) -> u8 {
+        self.first_byte >> 4
+    }
+
+    pub(super) fn flags(&self) -> u8 {
+        self.first_byte & 0x0f
+    }
+
+    pub(super) fn encode(&self, buffer: &mut BytesMut) {
+        buffer.put_u8(self.first_byte);
+        write_variable_integer(buffer, self.body.len() as u32);
+        buffer.put_slice(&self.body);
+    }
+}
+
+/// Accumulates the bytes received on a connection, and splits them into complete packets
+pub(super) struct MqttPacketDecoder {
+    buffer: BytesMut,
+}
+
+impl MqttPacketDecoder {
+    pub(super) fn new() -> Self {
+        Self {
+            buffer: BytesMut::new(),
+        }
+    }
+
+    /// Return the packets completed by the incoming bytes
+    pub(super) fn extract_complete_packets(
+        &mut self,
+        incoming: &[u8],
+        max_packet_size: u32,
+    ) -> Result<Vec<Packet>> {
+        self.buffer.extend_from_slice(incoming);
+
+        let mut packets = Vec::new();
+        while let Some((header_length, remaining_length)) = self.peek_fixed_header()? {
+            if remaining_length > max_packet_size {
+                return Err(malformed("mqtt packet is bigger than maximum size"));
+            }
+            let packet_length = header_length + remaining_length as usize;
+            if self.buffer.len() < packet_length {
+                break;
+            }
+            let mut packet = self.buffer.split_to(packet_length);
+            let first_byte = packet[0];
+            let body = packet.split_off(header_length).freeze();
+            packets.push(Packet { first_byte, body });
+        }
+        Ok(packets)
+    }
+
+    /// Return the length of the fixed header of the next packet and its remaining length,
+    /// once they have been received
+    fn peek_fixed_header(&self) -> Result<Option<(usize, u32)>> {
+        let mut remaining_length = 0;
+        for index in 0..MAX_REMAINING_LENGTH_BYTES {
+            let byte = match self.buffer.get(1 + index) {
+                Some(byte) => *byte,
+                None => return Ok(None),
+            };
+            remaining_length |= ((byte & 0x7f) as u32) << (7 * index);
+            if byte & 0x80 == 0 {
+                return Ok(Some((2 + index, remaining_length)));
+            }
+        }
+        Err(malformed("invalid mqtt remaining length"))
+    }
+}
+
+/// Property of an MQTT 5 packet, with its encoded value
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct Property {
+    id: u8,
+    value: Bytes,
+}
+
+impl Property {
+    fn string(id: u8, value: &str) -> Self {
+        let mut buffer = BytesMut::new();
+        write_string(&mut buffer, value);
+        Self {
+            id,
+            value: buffer.freeze(),
+        }
+    }
+}
+
+/// A PUBLISH packet
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct Publish {
+    flags: u8,
+    pub(super) topic: String,
+    packet_id: Option<u16>,
+    /// Properties of an MQTT 5 packet
+    properties: Option<Vec<Property>>,
+    pub(super) payload: Bytes,
+}
+
+impl Publish {
+    pub(super) fn decode(packet: &Packet, version: ProtocolVersion) -> Result<Self> {
+        let flags = packet.flags();
+        let mut reader = Reader::new(&packet.body);
+        let topic = reader.read_string()?;
+        // only the packets with a QoS of 1 or 2 have an identifier
+        let packet_id = if flags & 0x06 != 0 {
+            Some(reader.read_u16()?)
+        } else {
+            None
+        };
+        let properties = match version {
+            ProtocolVersion::V3 => None,
+            ProtocolVersion::V5 => Some(reader.read_properties()?),
+        };
+        let payload = packet.body.slice(reader.position..);
+        Ok(Self {
+            flags,
+            topic,
+            packet_id,
+            properties,
+            payload,
+        })
+    }
+
+    pub(super) fn encode(&self) -> Packet {
+        let mut body = BytesMut::new();
+        write_string(&mut body, &self.topic);
+        if let Some(packet_id) = self.packet_id {
+            body.put_u16(packet_id);
+        }
+        if let Some(properties) = &self.properties {
+            write_properties(&mut body, properties);
+        }
+        body.put_slice(&self.payload);
+        Packet::new(PUBLISH, self.flags, body)
+    }
+
+    /// Alias of the topic, when the topic name is replaced by an alias
+    pub(super) fn topic_alias(&self) -> Option<u16> {
+        self.properties
+            .as_ref()?
+            .iter()
+            .find(|property| property.id == TOPIC_ALIAS && property.value.len() == 2)
+            .map(|property| u16::from_be_bytes([property.value[0], property.value[1]]))
+    }
+
+    /// An encrypted payload is binary data, even when it was a UTF-8 string
+    pub(super) fn remove_payload_format_indicator(&mut self) {
+        if let Some(properties) = self.properties.as_mut() {
+            properties.retain(|property| property.id != PAYLOAD_FORMAT_INDICATOR);
+        }
+    }
+}
+
+/// A CONNECT packet. Only its will message can be modified, the other fields
+/// are kept as they are
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct Connect {
+    pub(super) version: ProtocolVersion,
+    /// Variable header and client identifier
+    header: Bytes,
+    pub(super) will: Option<Will>,
+    /// User name and password
+    credentials: Bytes,
+}
+
+/// Message published by the broker when a client is disconnected unexpectedly
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct Will {
+    /// Properties of an MQTT 5 will
+    properties: Option<Vec<Property>>,
+    pub(super) topic: String,
+    pub(super) payload: Bytes,
+}
+
+impl Will {
+    pub(super) fn remove_payload_format_indicator(&mut self) {
+        if let Some(properties) = self.properties.as_mut() {
+            properties.retain(|property| property.id != PAYLOAD_FORMAT_INDICATOR);
+        }
+    }
+}
+
+impl Connect {
+    pub(super) fn decode(packet: &Packet) -> Result<Self> {
+        let mut reader = Reader::new(&packet.body);
+        let protocol_name = reader.read_string()?;
+        let version = match (protocol_name.as_str(), reader.read_u8()?) {
+            ("MQIsdp", 3) | ("MQTT", 4) => ProtocolVersion::V3,
+            ("MQTT", 5) => ProtocolVersion::V5,
+            (name, level) => {
+                return Err(malformed(&format!(
+                    "unsupported mqtt protocol {name} with level {level}"
+                )));
+            }
+        };
+        let connect_flags = reader.read_u8()?;
+        let _keep_alive = reader.read_u16()?;
+        if version == ProtocolVersion::V5 {
+            reader.read_properties()?;
+        }
+        let _client_identifier = reader.read_binary()?;
+        let header = packet.body.slice(..reader.position);
+
+        let will = if connect_flags & WILL_FLAG != 0 {
+            let properties = match version {
+                ProtocolVersion::V3 => None,
+                ProtocolVersion::V5 => Some(reader.read_properties()?),
+            };
+            let topic = reader.read_string()?;
+            let payload = Bytes::copy_from_slice(reader.read_binary()?);
+            Some(Will {
+                properties,
+                topic,
+                payload,
+            })
+        } else {
+            None
+        };
+
+        Ok(Self {
+            version,
+            header,
+            will,
+            credentials: packet.body.slice(reader.position..),
+        })
+    }
+
+    pub(super) fn encode(&self) -> Result<Packet> {
+        let mut body = BytesMut::from(self.header.as_ref());
+        if let Some(will) = &self.will {
+            if let Some(properties) = &will.properties {
+                write_properties(&mut body, properties);
+            }
+            write_string(&mut body, &will.topic);
+            write_binary(&mut body, &will.payload)?;
+        }
+        body.put_slice(&self.credentials);
+        Ok(Packet::new(CONNECT, 0, body))
+    }
+}
+
+/// Return the topic filters of a SUBSCRIBE packet
+pub(super) fn subscribed_topic_filters(
+    packet: &Packet,
+    version: ProtocolVersion,
+) -> Result<Vec<String>> {
+    let mut reader = Reader::new(&packet.body);
+    let _packet_id = reader.read_u16()?;
+    if version == ProtocolVersion::V5 {
+        reader.read_properties()?;
+    }
+    let mut topic_filters = Vec::new();
+    while reader.remaining() > 0 {
+        topic_filters.push(reader.read_string()?);
+        let _subscription_options = reader.read_u8()?;
+    }
+    Ok(topic_filters)
+}
+
+/// Replace the server reference sent by an MQTT 5 broker in a CONNACK or DISCONNECT packet,
+/// so that the clients connect again through the inlet instead of reaching another broker
+/// directly. Return `None` if the packet doesn't have a server reference
+pub(super) fn replace_server_reference(packet: &Packet, server: &str) -> Result<Option<Packet>> {
+    // a CONNACK starts with its flags and reason code, a DISCONNECT with its reason code
+    let prefix_length = match packet.packet_type() {
+        CONNACK => 2,
+        DISCONNECT => 1,
+        _ => return Ok(None),
+    };
+    // a DISCONNECT packet can omit its properties
+    if packet.body.len() <= prefix_length {
+        return Ok(None);
+    }
+
+    let mut reader = Reader::new(&packet.body);
+    reader.read_bytes(prefix_length)?;
+    let mut properties = reader.read_properties()?;
+    let server_reference = properties
+        .iter_mut()
+        .find(|property| property.id == SERVER_REFERENCE);
+    match server_reference {
+        Some(property) => *property = Property::string(SERVER_REFERENCE, server),
+        None => return Ok(None),
+    }
+
+    let mut body = BytesMut::from(&packet.body[..prefix_length]);
+    write_properties(&mut body, &properties);
+    body.put_slice(&packet.body[reader.position..]);
+    Ok(Some(Packet::new(
+        packet.packet_type(),
+        packet.flags(),
+        body,
+    )))
+}
+
+struct Reader<'a> {
+    buffer: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(buffer: &'a [u8]) -> Self {
+        Self {
+            buffer,
+            position: 0,
+        }
+    }
+
+    fn remaining(&self) -> usize {
+        self.buffer.len() - self.position
+    }
+
+    fn read_bytes(&mut self, length: usize) -> Result<&'a [u8]> {
+        if self.remaining() < length {
+            return Err(malformed("truncated mqtt packet"));
+        }
+        let bytes = &self.buffer[self.position..self.position + length];
+        self.position += length;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16> {
+        let bytes = self.read_bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_variable_integer(&mut self) -> Result<u32> {
+        let mut value = 0;
+        for index in 0..MAX_REMAINING_LENGTH_BYTES {
+            let byte = self.read_u8()?;
+            value |= ((byte & 0x7f) as u32) << (7 * index);
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(malformed("invalid mqtt variable byte integer"))
+    }
+
+    /// Read binary data prefixed by its length
+    fn read_binary(&mut self) -> Result<&'a [u8]> {
+        let length = self.read_u16()?;
+        self.read_bytes(length as usize)
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        String::from_utf8(self.read_binary()?.to_vec())
+            .map_err(|_| malformed("invalid mqtt utf-8 string"))
+    }
+
+    fn read_properties(&mut self) -> Result<Vec<Property>> {
+        let length = self.read_variable_integer()? as usize;
+        let mut reader = Reader::new(self.read_bytes(length)?);
+        let mut properties = Vec::new();
+        while reader.remaining() > 0 {
+            let id = reader.read_u8()?;
+            let start = reader.position;
+            match id {
+                0x01 | 0x17 | 0x19 | 0x24 | 0x25 | 0x28 | 0x29 | 0x2A => {
+                    reader.read_u8()?;
+                }
+                0x13 | 0x21 | 0x22 | 0x23 => {
+                    reader.read_u16()?;
+                }
+                0x02 | 0x11 | 0x18 | 0x27 => {
+                    reader.read_bytes(4)?;
+                }
+                0x0B => {
+                    reader.read_variable_integer()?;
+                }
+                0x03 | 0x08 | 0x09 | 0x12 | 0x15 | 0x16 | 0x1A | 0x1C | 0x1F => {
+                    reader.read_binary()?;
+                }
+                // user property: a pair of strings
+                0x26 => {
+                    reader.read_binary()?;
+                    reader.read_binary()?;
+                }
+                _ => return Err(malformed(&format!("unknown mqtt property {id}"))),
+            }
+            properties.push(Property {
+                id,
+                value: Bytes::copy_from_slice(&reader.buffer[start..reader.position]),
+            });
+        }
+        Ok(properties)
+    }
+}
+
+fn write_variable_integer(buffer: &mut BytesMut, mut value: u32) {
+    loop {
+        let mut byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value > 0 {
+            byte |= 0x80;
+        }
+        buffer.put_u8(byte);
+        if value == 0 {
+            break;
+        }
+    }
+}
+
+fn write_string(buffer: &mut BytesMut, value: &str) {
+    buffer.put_u16(value.len() as u16);
+    buffer.put_slice(value.as_bytes());
+}
+
+fn write_binary(buffer: &mut BytesMut, value: &[u8]) -> Result<()> {
+    let length =
+        u16::try_from(value.len()).map_err(|_| malformed("mqtt binary data is too long"))?;
+    buffer.put_u16(length);
+    buffer.put_slice(value);
+    Ok(())
+}
+
+fn write_properties(buffer: &mut BytesMut, properties: &[Property]) {
+    let length: usize = properties
+        .iter()
+        .map(|property| 1 + property.value.len())
+        .sum();
+    write_variable_integer(buffer, length as u32);
+    for property in properties {
+        buffer.put_u8(property.id);
+        buffer.put_slice(&property.value);
+    }
+}
+
+fn malformed(message: &str) -> Error {
+    Error::new(Origin::Transport, Kind::Protocol, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(packet: &Packet) -> BytesMut {
+        let mut buffer = BytesMut::new();
+        packet.encode(&mut buffer);
+        buffer
+    }
+
+    fn publish_packet(version: ProtocolVersion) -> Packet {
+        let mut body = BytesMut::new();
+        write_string(&mut body, "sensors/temperature");
+        body.put_u16(7);
+        if version == ProtocolVersion::V5 {
+            let payload_format_indicator = Property {
+                id: PAYLOAD_FORMAT_INDICATOR,
+                value: Bytes::from_static(&[1]),
+            };
+            let topic_alias = Property {
+                id: TOPIC_ALIAS,
+                value: Bytes::from_static(&[0, 3]),
+            };
+            write_properties(&mut body, &[payload_format_indicator, topic_alias]);
+        }
+        body.put_slice(b"21.5");
+        // QoS 1
+        Packet::new(PUBLISH, 0x02, body)
+    }
+
+    #[test]
+    fn packets_are_split_across_and_within_messages() -> Result<()> {
+        let packet = publish_packet(ProtocolVersion::V3);
+        let mut bytes = encode(&packet);
+        bytes.extend_from_slice(&encode(&packet));
+
+        let mut decoder = MqttPacketDecoder::new();
+        let (first, second) = bytes.split_at(5);
+        assert!(decoder.extract_complete_packets(first, 1024)?.is_empty());
+        let packets = decoder.extract_complete_packets(second, 1024)?;
+        assert_eq!(packets, vec![packet.clone(), packet]);
+
+        let too_big = encode(&publish_packet(ProtocolVersion::V3));
+        assert!(decoder.extract_complete_packets(&too_big, 4).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn remaining_lengths_use_several_bytes() -> Result<()> {
+        let packet = Packet::new(PUBLISH, 0, vec![0; 20_000]);
+        let bytes = encode(&packet);
+        assert_eq!(&bytes[1..4], &[0xA0, 0x9C, 0x01]);
+
+        let mut decoder = MqttPacketDecoder::new();
+        let packets = decoder.extract_complete_packets(&bytes, 1024 * 1024)?;
+        assert_eq!(packets, vec![packet]);
+        Ok(())
+    }
+
+    #[test]
+    fn publish_packets_are_decoded_and_encoded() -> Result<()> {
+        for version in [ProtocolVersion::V3, ProtocolVersion::V5] {
+            let packet = publish_packet(version);
+            let publish = Publish::decode(&packet, version)?;
+            assert_eq!(publish.topic, "sensors/temperature");
+            assert_eq!(publish.packet_id, Some(7));
+            assert_eq!(publish.payload, Bytes::from_static(b"21.5"));
+            assert_eq!(publish.encode(), packet);
+        }
+
+        let packet = publish_packet(ProtocolVersion::V5);
+        let mut publish = Publish::decode(&packet, ProtocolVersion::V5)?;
+        assert_eq!(publish.topic_alias(), Some(3));
+        publish.remove_payload_format_indicator();
+        assert_eq!(publish.properties.map(|p| p.len()), Some(1));
+        Ok(())
+    }
+
+    #[test]
+    fn the_will_of_a_connect_packet_can_be_replaced() -> Result<()> {
+        let mut body = BytesMut::new();
+        write_string(&mut body, "MQTT");
+        body.put_u8(4);
+        // will and user name flags
+        body.put_u8(WILL_FLAG | 0x80);
+        body.put_u16(60);
+        write_string(&mut body, "client-1");
+        write_string(&mut body, "clients/status");
+        write_binary(&mut body, b"offline")?;
+        write_string(&mut body, "user");
+        let packet = Packet::new(CONNECT, 0, body);
+
+        let mut connect = Connect::decode(&packet)?;
+        assert_eq!(connect.version, ProtocolVersion::V3);
+        assert_eq!(connect.encode()?, packet);
+
+        if let Some(will) = connect.will.as_mut() {
+            assert_eq!(will.topic, "clients/status");
+            will.payload = Bytes::from_static(b"gone");
+        }
+        let connect = Connect::decode(&connect.encode()?)?;
+        let will_payload = connect.will.map(|will| will.payload);
+        assert_eq!(will_payload, Some(Bytes::from_static(b"gone")));
+        assert_eq!(connect.credentials.len(), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn the_topic_filters_of_a_subscribe_packet_are_returned() -> Result<()> {
+        let mut body = BytesMut::new();
+        body.put_u16(1);
+        write_properties(&mut body, &[]);
+        write_string(&mut body, "sensors/+");
+        body.put_u8(1);
+        write_string(&mut body, "alerts");
+        body.put_u8(0);
+        let packet = Packet::new(SUBSCRIBE, 0x02, body);
+
+        let topic_filters = subscribed_topic_filters(&packet, ProtocolVersion::V5)?;
+        assert_eq!(topic_filters, vec!["sensors/+", "alerts"]);
+        Ok(())
+    }
+
+    #[test]
+    fn server_references_are_replaced() -> Result<()> {
+        let mut body = BytesMut::from(&[0, 0x9C][..]);
+        let server_reference = Property::string(SERVER_REFERENCE, "broker-2:1883");
+        write_properties(&mut body, &[server_reference]);
+        let packet = Packet::new(CONNACK, 0, body);
+
+        let replaced = replace_server_reference(&packet, "127.0.0.1:1883")?;
+        let replaced = replaced.map(|packet| packet.body);
+        let mut expected = BytesMut::from(&[0, 0x9C][..]);
+        let server_reference = Property::string(SERVER_REFERENCE, "127.0.0.1:1883");
+        write_properties(&mut expected, &[server_reference]);
+        assert_eq!(replaced, Some(expected.freeze()));
+
+        // a DISCONNECT packet without properties is not modified
+        let packet = Packet::new(DISCONNECT, 0, vec![0x9D]);
+        assert!(replace_server_reference(&packet, "127.0.0.1:1883")?.is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use ockam_core::{
+    route, Address, Any, IncomingAccessControl, OutgoingAccessControl, Routed, Worker,
+};
+use ockam_node::Context;
+use std::sync::Arc;
+use tracing::trace;
+
+use crate::mqtt::interceptor::MqttInterceptor;
+use crate::mqtt::portal_worker::MqttPortalWorker;
+use crate::mqtt::TopicKeys;
+
+/// First point of ingress of the MQTT connections accepted by an inlet. At the first message
+/// of a connection, it spawns the stateful workers relaying the packets of that connection.
+pub(crate) struct MqttPortalListener {
+    inlet_bind_address: String,
+    topic_keys: Option<Arc<TopicKeys>>,
+    request_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    response_incoming_access_control: Arc<dyn IncomingAccessControl>,
+}
+
+#[ockam::worker]
+impl Worker for MqttPortalListener {
+    type Message = Any;
+    type Context = Context;
+
+    async fn handle_message(
+        &mut self,
+        context: &mut Self::Context,
+        message: Routed<Self::Message>,
+    ) -> ockam::Result<()> {
+        trace!("received first message!");
+
+        let mut message = message.into_local_message();
+
+        // Remove our address
+        message = message.pop_front_onward_route()?;
+
+        let next_hop = message.next_on_onward_route()?;
+
+        // Retrieve the flow id from the next hop if it exists
+        let flow_control_id = context
+            .flow_controls()
+            .find_flow_control_with_producer_address(&next_hop)
+            .map(|x| x.flow_control_id().clone());
+
+        let inlet_responder_address = message.return_route_ref().next()?.clone();
+
+        let interceptor = Arc::new(MqttInterceptor::new(
+            self.inlet_bind_address.clone(),
+            self.topic_keys.clone(),
+        ));
+        let worker_address = MqttPortalWorker::create_inlet_side_mqtt_portal(
+            context,
+            interceptor,
+            flow_control_id,
+            route![inlet_responder_address],
+            self.request_outgoing_access_control.clone(),
+            self.response_incoming_access_control.clone(),
+        )
+        .await?;
+
+        message = message.push_front_onward_route(&worker_address);
+        context.forward(message).await?;
+
+        Ok(())
+    }
+}
+
+impl MqttPortalListener {
+    pub(crate) async fn create(
+        context: &Context,
+        inlet_bind_address: String,
+        topic_keys: Option<Arc<TopicKeys>>,
+        listener_address: Address,
+        incoming_access_control: Arc<dyn IncomingAccessControl>,
+        outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    ) -> ockam_core::Result<()> {
+        let s = Self {
+            inlet_bind_address,
+            topic_keys,
+            request_outgoing_access_control: outgoing_access_control,
+            response_incoming_access_control: incoming_access_control,
+        };
+
+        context.start_worker(listener_address, s).await
+    }
+}
@@ -0,0 +1,287 @@
+use bytes::{Bytes, BytesMut};
+use core::sync::atomic::AtomicBool;
+use core::sync::atomic::Ordering;
+use ockam_core::compat::sync::Arc;
+use ockam_core::flow_control::FlowControlId;
+use ockam_core::{
+    errcode::{Kind, Origin},
+    route, Address, Encodable, Error, IncomingAccessControl, LocalInfo, LocalMessage,
+    NeutralMessage, OutgoingAccessControl, Result, Route, Routed, Worker,
+};
+use ockam_node::{Context, WorkerBuilder};
+use ockam_transport_tcp::{PortalMessage, MAX_PAYLOAD_SIZE};
+
+use crate::mqtt::interceptor::MqttInterceptor;
+use crate::mqtt::packet::MqttPacketDecoder;
+use crate::mqtt::MQTT_OUTLET_BROKER_ADDRESS;
+
+/// The MQTT protocol allows packets of up to 256MB, 16MB is the maximum accepted by the portals
+const MAX_MQTT_PACKET_SIZE: u32 = 16 * 1024 * 1024;
+
+enum Receiving {
+    ClientPackets,
+    BrokerPackets,
+}
+
+/// Relays the packets exchanged by an MQTT client and the broker, between the TCP inlet
+/// and the TCP outlet, like the Kafka portal workers.
+///
+/// The packets sent by the client and the packets sent by the broker are handled by two
+/// different workers, sharing the same interceptor. Since an MQTT packet can be split across
+/// several portal messages, the packets are decoded before being intercepted.
+pub(super) struct MqttPortalWorker {
+    // The instance of worker managing the other direction
+    // The first one to receive the disconnect message will stop both workers
+    other_worker_address: Address,
+    receiving: Receiving,
+    interceptor: Arc<MqttInterceptor>,
+    disconnect_received: Arc<AtomicBool>,
+    decoder: MqttPacketDecoder,
+    // Since we know the next step beforehand we simply ignore the provided onward route
+    // and use the one we know.
+    fixed_onward_route: Option<Route>,
+}
+
+#[ockam::worker]
+impl Worker for MqttPortalWorker {
+    type Message = NeutralMessage;
+    type Context = Context;
+
+    async fn handle_message(
+        &mut self,
+        context: &mut Self::Context,
+        routed_message: Routed<Self::Message>,
+    ) -> Result<()> {
+        let onward_route = routed_message.onward_route();
+        let return_route = routed_message.return_route();
+        let local_info = routed_message.local_message().local_info();
+        let portal_message = PortalMessage::decode(routed_message.payload())?;
+
+        match portal_message {
+            PortalMessage::Payload(message, _) => {
+                if let Some(encoded_packets) = self.intercept_packets(context, message).await? {
+                    self.split_and_send(
+                        context,
+                        onward_route,
+                        return_route,
+                        encoded_packets,
+                        local_info.as_slice(),
+                    )
+                    .await?;
+                }
+            }
+            PortalMessage::Disconnect => {
+                self.forward(context, routed_message).await?;
+
+                // The first one to receive disconnect and to swap the atomic will stop both workers
+                let disconnect_received = self.disconnect_received.swap(true, Ordering::SeqCst);
+                if !disconnect_received {
+                    trace!(
+                        "{:?} received disconnect event from {:?}",
+                        context.address(),
+                        return_route
+                    );
+                    context
+                        .stop_worker(self.other_worker_address.clone())
+                        .await?;
+                    context.stop_worker(context.address()).await?;
+                }
+            }
+            PortalMessage::Ping | PortalMessage::PingWithClient(None, _) => {
+                self.forward(context, routed_message).await?
+            }
+            PortalMessage::Pong => {
+                match self.receiving {
+                    Receiving::ClientPackets => {
+                        // if we receive a pong message it means it must be from the other worker
+                        if routed_message.src_addr() == self.other_worker_address {
+                            if let Some(fixed_onward_route) = self.fixed_onward_route.as_ref() {
+                                debug!(
+                                    "updating onward route from {} to {}",
+                                    fixed_onward_route,
+                                    routed_message.return_route()
+                                );
+                                self.fixed_onward_route = Some(routed_message.return_route());
+                            }
+                        }
+                    }
+                    Receiving::BrokerPackets => {
+                        // forward the pong also to the other worker to update the fixed
+                        // onward route with the final route
+                        let mut local_message = routed_message.local_message().clone();
+                        local_message = local_message
+                            .set_onward_route(route![self.other_worker_address.clone()]);
+                        context.forward(local_message).await?;
+
+                        self.forward(context, routed_message).await?
+                    }
+                }
+            }
+            // compression is never negotiated by the mqtt portals, since the packets
+            // need to be inspected
+            PortalMessage::PingWithCompression(_)
+            | PortalMessage::PingWithClient(Some(_), _)
+            | PortalMessage::PongWithCompression(_)
+            | PortalMessage::CompressedPayload(_) => {
+                return Err(Error::new(
+                    Origin::Transport,
+                    Kind::Protocol,
+                    "compressed portal messages are not supported by mqtt portals",
+                ));
+            }
+            // the packets are modified by the mqtt portals, so the bytes lost while
+            // a connection is suspended can't be sent again
+            PortalMessage::Resume(_) => {
+                return Err(Error::new(
+                    Origin::Transport,
+                    Kind::Protocol,
+                    "resumed connections are not supported by mqtt portals",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl MqttPortalWorker {
+    async fn forward(
+        &self,
+        context: &mut Context,
+        routed_message: Routed<NeutralMessage>,
+    ) -> Result<()> {
+        let mut local_message = routed_message.into_local_message();
+        local_message = if let Some(fixed_onward_route) = &self.fixed_onward_route {
+            local_message
+                .set_onward_route(fixed_onward_route.clone())
+                .push_front_return_route(&self.other_worker_address)
+        } else {
+            // Since we force the return route next step (fixed_onward_route in the other worker),
+            // we can omit the previous return route.
+            local_message
+                .pop_front_onward_route()?
+                .set_return_route(route![self.other_worker_address.clone()])
+        };
+        context.forward(local_message).await
+    }
+
+    async fn split_and_send(
+        &self,
+        context: &mut Context,
+        mut provided_onward_route: Route,
+        mut provided_return_route: Route,
+        buffer: Bytes,
+        local_info: &[LocalInfo],
+    ) -> Result<()> {
+        let return_route: Route;
+        let onward_route;
+
+        if let Some(fixed_onward_route) = &self.fixed_onward_route {
+            // To correctly proxy messages to the inlet or outlet side
+            // we invert the return route when a message pass through
+            return_route = provided_return_route
+                .modify()
+                .prepend(self.other_worker_address.clone())
+                .into();
+            onward_route = fixed_onward_route.clone();
+        } else {
+            return_route = route![self.other_worker_address.clone()];
+            onward_route = provided_onward_route.modify().pop_front().into();
+        };
+
+        for chunk in buffer.chunks(MAX_PAYLOAD_SIZE) {
+            let message = LocalMessage::new()
+                .with_onward_route(onward_route.clone())
+                .with_return_route(return_route.clone())
+                .with_payload(PortalMessage::Payload(chunk, None).encode()?)
+                .with_local_info(local_info.to_vec());
+
+            context.forward(message).await?;
+        }
+        Ok(())
+    }
+
+    /// Return the packets completed by the received bytes, once intercepted and encoded again
+    async fn intercept_packets(
+        &mut self,
+        context: &mut Context,
+        bytes: &[u8],
+    ) -> Result<Option<Bytes>> {
+        let packets = self
+            .decoder
+            .extract_complete_packets(bytes, MAX_MQTT_PACKET_SIZE)?;
+        if packets.is_empty() {
+            return Ok(None);
+        }
+
+        let mut encoded_packets = BytesMut::new();
+        for packet in packets {
+            let packet = match self.receiving {
+                Receiving::ClientPackets => {
+                    self.interceptor
+                        .intercept_client_packet(context, packet)
+                        .await?
+                }
+                Receiving::BrokerPackets => {
+                    self.interceptor
+                        .intercept_broker_packet(context, packet)
+                        .await?
+                }
+            };
+            packet.encode(&mut encoded_packets);
+        }
+        Ok(Some(encoded_packets.freeze()))
+    }
+
+    /// Create the two workers relaying the packets of a connection accepted by an MQTT inlet.
+    /// Returns the address of the worker receiving the packets sent by the client
+    pub(super) async fn create_inlet_side_mqtt_portal(
+        context: &mut Context,
+        interceptor: Arc<MqttInterceptor>,
+        flow_control_id: Option<FlowControlId>,
+        inlet_responder_route: Route,
+        request_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        response_incoming_access_control: Arc<dyn IncomingAccessControl>,
+    ) -> Result<Address> {
+        let client_worker_address = Address::random_tagged("MqttPortalWorker.client");
+        let broker_worker_address = Address::random_tagged("MqttPortalWorker.broker");
+        let disconnect_received = Arc::new(AtomicBool::new(false));
+
+        let client_worker = Self {
+            interceptor: interceptor.clone(),
+            other_worker_address: broker_worker_address.clone(),
+            receiving: Receiving::ClientPackets,
+            disconnect_received: disconnect_received.clone(),
+            decoder: MqttPacketDecoder::new(),
+            fixed_onward_route: None,
+        };
+        let broker_worker = Self {
+            interceptor,
+            other_worker_address: client_worker_address.clone(),
+            receiving: Receiving::BrokerPackets,
+            disconnect_received,
+            decoder: MqttPacketDecoder::new(),
+            fixed_onward_route: Some(inlet_responder_route),
+        };
+
+        WorkerBuilder::new(client_worker)
+            .with_address(client_worker_address.clone())
+            .with_outgoing_access_control_arc(request_outgoing_access_control)
+            .start(context)
+            .await?;
+
+        if let Some(flow_control_id) = flow_control_id {
+            let flow_controls = context.flow_controls();
+            flow_controls.add_consumer(broker_worker_address.clone(), &flow_control_id);
+            flow_controls.add_consumer(MQTT_OUTLET_BROKER_ADDRESS, &flow_control_id);
+        }
+
+        WorkerBuilder::new(broker_worker)
+            .with_address(broker_worker_address)
+            .with_incoming_access_control_arc(response_incoming_access_control)
+            .start(context)
+            .await?;
+
+        Ok(client_worker_address)
+    }
+}
@@ -0,0 +1,184 @@
+use crate::kafka::record_encryption::{hmac_sha256, TopicKeyRequest, TopicKeyResponse};
+use crate::mqtt::MQTT_OUTLET_TOPIC_KEYS_ADDRESS;
+use crate::nodes::NodeManager;
+use ockam::{Context, Result, Routed, Worker};
+use ockam_core::compat::collections::HashMap;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::flow_control::FlowControlId;
+use ockam_core::{Address, AsyncTryClone, Error, IncomingAccessControl};
+use ockam_multiaddr::MultiAddr;
+use ockam_node::WorkerBuilder;
+use ockam_vault::{AeadAlgorithm, AeadSecretKeyHandle, VaultForSecureChannels};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Length of the random nonce prepended to an encrypted payload
+const NONCE_LENGTH: usize = 12;
+
+/// This service gives the MQTT inlets the key used to encrypt the payloads of each topic.
+/// The keys are derived from the same persisted secret as the keys of the Kafka record keys,
+/// with a different label, so that the payloads can still be decrypted after a restart.
+pub(crate) struct TopicKeysService {
+    secret: Vec<u8>,
+}
+
+impl TopicKeysService {
+    pub(crate) async fn create(
+        context: &Context,
+        default_secure_channel_listener_flow_control_id: FlowControlId,
+        secret: Vec<u8>,
+        incoming_access_control: Arc<dyn IncomingAccessControl>,
+    ) -> Result<()> {
+        let worker_address = Address::from_string(MQTT_OUTLET_TOPIC_KEYS_ADDRESS);
+        context.flow_controls().add_consumer(
+            worker_address.clone(),
+            &default_secure_channel_listener_flow_control_id,
+        );
+
+        WorkerBuilder::new(TopicKeysService { secret })
+            .with_address(worker_address)
+            .with_incoming_access_control_arc(incoming_access_control)
+            .start(context)
+            .await
+            .map(|_| ())
+    }
+}
+
+#[ockam::worker]
+impl Worker for TopicKeysService {
+    type Message = Vec<u8>;
+    type Context = Context;
+
+    async fn handle_message(
+        &mut self,
+        context: &mut Context,
+        message: Routed<Self::Message>,
+    ) -> Result<()> {
+        let return_route = message.return_route();
+        let request: TopicKeyRequest = minicbor::decode(&message.into_body()?)?;
+        debug!(
+            "sending the payload key of the mqtt topic {}",
+            request.topic
+        );
+
+        let response = TopicKeyResponse {
+            key: hmac_sha256(&self.secret, &[b"mqtt topic key", request.topic.as_bytes()]),
+        };
+        context
+            .send(return_route, minicbor::to_vec(response)?)
+            .await
+    }
+}
+
+/// Keys of the topics used by the clients of an MQTT inlet. They are retrieved from the
+/// MQTT outlet the first time a topic is used, and shared by all the connections
+pub(crate) struct TopicKeys {
+    node_manager: Arc<NodeManager>,
+    vault: Arc<dyn VaultForSecureChannels>,
+    topic_keys_service: MultiAddr,
+    keys: Mutex<HashMap<String, AeadSecretKeyHandle>>,
+}
+
+impl TopicKeys {
+    pub(crate) fn new(
+        node_manager: Arc<NodeManager>,
+        vault: Arc<dyn VaultForSecureChannels>,
+        topic_keys_service: MultiAddr,
+    ) -> Self {
+        Self {
+            node_manager,
+            vault,
+            topic_keys_service,
+            keys: Default::default(),
+        }
+    }
+
+    /// Encrypt the payload of a message published on a topic.
+    /// The encrypted payload starts with the random nonce used to encrypt it
+    pub(crate) async fn encrypt(
+        &self,
+        context: &mut Context,
+        topic: &str,
+        payload: &[u8],
+    ) -> Result<Vec<u8>> {
+        let key = self.get_or_fetch_key(context, topic).await?;
+        let mut encrypted_payload = rand::random::<[u8; NONCE_LENGTH]>().to_vec();
+        let nonce = encrypted_payload.clone();
+        self.vault
+            .aead_encrypt(
+                &mut encrypted_payload,
+                &key,
+                payload,
+                &nonce,
+                topic.as_bytes(),
+            )
+            .await?;
+        Ok(encrypted_payload)
+    }
+
+    /// Decrypt a payload encrypted with [`Self::encrypt`].
+    /// The topic is authenticated, so that a message can't be replayed on another topic
+    pub(crate) async fn decrypt(
+        &self,
+        context: &mut Context,
+        topic: &str,
+        encrypted_payload: &[u8],
+    ) -> Result<Vec<u8>> {
+        if encrypted_payload.len() < NONCE_LENGTH {
+            return Err(Error::new(
+                Origin::Api,
+                Kind::Invalid,
+                format!("Invalid encrypted payload for the mqtt topic {topic}"),
+            ));
+        }
+        let key = self.get_or_fetch_key(context, topic).await?;
+        let (nonce, cipher_text) = encrypted_payload.split_at(NONCE_LENGTH);
+        self.vault
+            .aead_decrypt(&key, cipher_text, nonce, topic.as_bytes())
+            .await
+    }
+
+    /// Fetch the key of a topic, if it hasn't been retrieved yet
+    pub(crate) async fn fetch(&self, context: &mut Context, topic: &str) -> Result<()> {
+        self.get_or_fetch_key(context, topic).await.map(|_| ())
+    }
+
+    async fn get_or_fetch_key(
+        &self,
+        context: &mut Context,
+        topic: &str,
+    ) -> Result<AeadSecretKeyHandle> {
+        let mut keys = self.keys.lock().await;
+        if let Some(key) = keys.get(topic) {
+            return Ok(key.clone());
+        }
+
+        debug!("retrieving the payload key of the mqtt topic {topic}");
+        let connection = self
+            .node_manager
+            .make_connection(
+                Arc::new(context.async_try_clone().await?),
+                &self.topic_keys_service,
+                self.node_manager.identifier(),
+                None,
+                None,
+            )
+            .await?;
+        let request = TopicKeyRequest {
+            topic: topic.to_string(),
+        };
+        let response: Result<Vec<u8>> = context
+            .send_and_receive(connection.route()?, minicbor::to_vec(request)?)
+            .await;
+        connection.close(context, &self.node_manager).await?;
+        let response: TopicKeyResponse = minicbor::decode(&response?)?;
+
+        let key = self.vault.import_secret_buffer(response.key).await?;
+        let key = self
+            .vault
+            .convert_secret_buffer_to_aead_key(key, AeadAlgorithm::AesGcm)
+            .await?;
+        keys.insert(topic.to_string(), key.clone());
+        Ok(key)
+    }
+}
@@ -11,4 +11,6 @@ pub mod relay;
 pub mod secure_channel;
 pub mod services;
 pub mod transport;
+pub mod tun_portal;
+pub mod udp_portal;
 pub mod workers;
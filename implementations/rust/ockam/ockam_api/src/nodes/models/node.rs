@@ -3,14 +3,18 @@
 use crate::cli_state::{NodeInfo, NodeProcessStatus};
 use crate::colors::color_primary;
 use crate::nodes::models::portal::{InletStatus, OutletStatus};
-use crate::nodes::models::services::ServiceStatus;
+use crate::nodes::models::relay::RelayInfo;
+use crate::nodes::models::services::{KafkaInletStats, ServiceStatus};
 use crate::nodes::models::transport::TransportStatus;
-use crate::output::Output;
+use crate::nodes::models::workers::WorkerStatus;
+use crate::output::{colorize_connection_status, Output};
 use crate::terminal::fmt;
 use minicbor::{Decode, Encode};
 use ockam::identity::{Identifier, SecureChannelListener};
-use ockam_core::Result;
+use ockam_core::{Address, Result};
 use ockam_multiaddr::MultiAddr;
+use ockam_node::debugger::{DebuggerGraph, TraceDirection, TraceEvent, TraceEvents, TraceFilter};
+use ockam_node::NodeEvent;
 use serde::Serialize;
 
 use crate::config::lookup::InternetAddress;
@@ -24,6 +28,9 @@ pub struct NodeStatus {
     #[n(1)] pub name: String,
     #[n(2)] pub identifier: Identifier,
     #[n(3)] pub status: NodeProcessStatus,
+    /// True when the node has started all the services it was configured with.
+    /// This is missing when the status is returned by an older node
+    #[n(4)] pub services_initialized: Option<bool>,
 }
 
 impl NodeStatus {
@@ -32,8 +39,20 @@ impl NodeStatus {
             name: name.into(),
             identifier,
             status,
+            services_initialized: Some(false),
         }
     }
+
+    pub fn with_services_initialized(mut self, services_initialized: bool) -> Self {
+        self.services_initialized = Some(services_initialized);
+        self
+    }
+
+    /// Return true if the node is running and has started all its services.
+    /// The nodes which don't report the initialization of their services are ready once running
+    pub fn is_ready(&self) -> bool {
+        self.status.is_running() && self.services_initialized.unwrap_or(true)
+    }
 }
 
 impl From<&NodeInfo> for NodeStatus {
@@ -42,10 +61,30 @@ impl From<&NodeInfo> for NodeStatus {
             name: node.name(),
             identifier: node.identifier(),
             status: node.status(),
+            services_initialized: Some(false),
         }
     }
 }
 
+/// Response body for a node stats request: the resources used by the node process
+/// and the messages handled by its workers
+#[derive(Debug, Clone, Serialize, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct NodeStats {
+    #[n(1)] pub name: String,
+    #[n(2)] pub pid: u32,
+    /// CPU usage of the node process since the previous stats request, in percent of one CPU
+    #[n(3)] pub cpu_usage: f32,
+    /// Resident memory of the node process, in bytes
+    #[n(4)] pub memory: u64,
+    #[n(5)] pub workers: Vec<WorkerStatus>,
+    /// Traffic of the topics used through the Kafka inlets of the node
+    #[n(6)] pub kafka_inlets: Vec<KafkaInletStats>,
+}
+
+/// Response body for a node resources request: a summary of the node and of all the
+/// transports, secure channel listeners, portals, relays and services running on it
 #[derive(Debug, Serialize, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
@@ -62,6 +101,7 @@ pub struct NodeResources {
     #[n(9)] pub inlets: Vec<InletStatus>,
     #[n(10)] pub outlets: Vec<OutletStatus>,
     #[n(11)] pub services: Vec<ServiceStatus>,
+    #[n(12)] pub relays: Vec<RelayInfo>,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -73,6 +113,7 @@ impl NodeResources {
         listeners: Vec<SecureChannelListener>,
         inlets: Vec<InletStatus>,
         outlets: Vec<OutletStatus>,
+        relays: Vec<RelayInfo>,
         services: Vec<ServiceStatus>,
     ) -> Result<Self> {
         Ok(Self {
@@ -90,6 +131,7 @@ impl NodeResources {
             inlets,
             outlets,
             services,
+            relays,
         })
     }
 
@@ -109,6 +151,7 @@ impl NodeResources {
             inlets: vec![],
             outlets: vec![],
             services: vec![],
+            relays: vec![],
         })
     }
 }
@@ -151,9 +194,19 @@ impl Display for NodeResources {
         }
 
         if self.secure_channel_listeners.is_empty() {
-            writeln!(f, "{}{}No Secure Channels", fmt::PADDING, fmt::INDENTATION)?;
+            writeln!(
+                f,
+                "{}{}No Secure Channel Listeners",
+                fmt::PADDING,
+                fmt::INDENTATION
+            )?;
         } else {
-            writeln!(f, "{}{}Secure Channels:", fmt::PADDING, fmt::INDENTATION)?;
+            writeln!(
+                f,
+                "{}{}Secure Channel Listeners:",
+                fmt::PADDING,
+                fmt::INDENTATION
+            )?;
             for s in &self.secure_channel_listeners {
                 writeln!(
                     f,
@@ -165,19 +218,41 @@ impl Display for NodeResources {
             }
         }
 
-        if self.inlets.is_empty() && self.outlets.is_empty() {
-            writeln!(f, "{}{}No Portals", fmt::PADDING, fmt::INDENTATION)?;
+        if self.inlets.is_empty() {
+            writeln!(f, "{}{}No Inlets", fmt::PADDING, fmt::INDENTATION)?;
         } else {
-            writeln!(f, "{}{}Portals:", fmt::PADDING, fmt::INDENTATION)?;
+            writeln!(f, "{}{}Inlets:", fmt::PADDING, fmt::INDENTATION)?;
             for i in &self.inlets {
                 writeln!(f, "{}{}{}", fmt::PADDING, fmt::INDENTATION.repeat(2), i)?;
             }
+        }
 
+        if self.outlets.is_empty() {
+            writeln!(f, "{}{}No Outlets", fmt::PADDING, fmt::INDENTATION)?;
+        } else {
+            writeln!(f, "{}{}Outlets:", fmt::PADDING, fmt::INDENTATION)?;
             for o in &self.outlets {
                 writeln!(f, "{}{}{}", fmt::PADDING, fmt::INDENTATION.repeat(2), o)?;
             }
         }
 
+        if self.relays.is_empty() {
+            writeln!(f, "{}{}No Relays", fmt::PADDING, fmt::INDENTATION)?;
+        } else {
+            writeln!(f, "{}{}Relays:", fmt::PADDING, fmt::INDENTATION)?;
+            for r in &self.relays {
+                writeln!(
+                    f,
+                    "{}{}Relay {} to {} is {}",
+                    fmt::PADDING,
+                    fmt::INDENTATION.repeat(2),
+                    color_primary(r.alias()),
+                    color_primary(r.destination_address().to_string()),
+                    colorize_connection_status(r.connection_status())
+                )?;
+            }
+        }
+
         if self.services.is_empty() {
             writeln!(f, "{}{}No Services", fmt::PADDING, fmt::INDENTATION)?;
         } else {
@@ -210,3 +285,297 @@ impl Display for RouteToNode {
         Ok(())
     }
 }
+
+/// Response body for a node debug graph request: the context inheritance and
+/// message flow graphs logged by the debugger of a running node
+#[derive(Debug, Clone, Serialize, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct DebugGraph {
+    #[n(1)] pub mailboxes: Vec<DebugMailbox>,
+    /// Edges from the address of a context to the addresses of the contexts created from it
+    #[n(2)] pub inheritance: Vec<DebugGraphEdge>,
+    /// Edges from the source address of the messages to the address of their destination
+    #[n(3)] pub message_flow: Vec<DebugGraphEdge>,
+    /// Both graphs in the graphviz DOT format
+    #[n(4)] pub dot: String,
+}
+
+#[derive(Debug, Clone, Serialize, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct DebugMailbox {
+    #[n(1)] pub address: String,
+    #[n(2)] pub incoming_access_control: String,
+    #[n(3)] pub outgoing_access_control: String,
+}
+
+#[derive(Debug, Clone, Serialize, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct DebugGraphEdge {
+    #[n(1)] pub from: String,
+    #[n(2)] pub to: String,
+}
+
+impl From<DebuggerGraph> for DebugGraph {
+    fn from(graph: DebuggerGraph) -> Self {
+        let edges = |edges: Vec<(Address, Address)>| {
+            edges
+                .into_iter()
+                .map(|(from, to)| DebugGraphEdge {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                })
+                .collect()
+        };
+        Self {
+            dot: graph.to_dot(),
+            mailboxes: graph
+                .mailboxes
+                .into_iter()
+                .map(|m| DebugMailbox {
+                    address: m.address.to_string(),
+                    incoming_access_control: m.incoming_access_control,
+                    outgoing_access_control: m.outgoing_access_control,
+                })
+                .collect(),
+            inheritance: edges(graph.inheritance),
+            message_flow: edges(graph.message_flow),
+        }
+    }
+}
+
+/// Request body to subscribe to the messages sent and received by the workers of a node
+#[derive(Debug, Clone, Serialize, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct StartTraceRequest {
+    /// Only trace the messages sent or received by these addresses. All the messages are traced if empty
+    #[n(1)] pub addresses: Vec<String>,
+    /// Fraction of the messages to trace, between 0 and 1
+    #[n(2)] pub sample_rate: f64,
+}
+
+impl StartTraceRequest {
+    pub fn new(addresses: Vec<String>, sample_rate: f64) -> Self {
+        Self {
+            addresses,
+            sample_rate,
+        }
+    }
+}
+
+impl From<StartTraceRequest> for TraceFilter {
+    fn from(request: StartTraceRequest) -> Self {
+        TraceFilter::default()
+            .with_addresses(request.addresses.iter().map(Address::from_string).collect())
+            .with_sample_rate(request.sample_rate)
+    }
+}
+
+/// Request body to unlock the encrypted vault of a node
+#[derive(Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct UnlockVaultRequest {
+    #[n(1)] pub vault_name: String,
+    /// Passphrase of the vault, if the vault is encrypted with a passphrase
+    #[n(2)] pub passphrase: Option<String>,
+}
+
+impl UnlockVaultRequest {
+    pub fn new(vault_name: impl Into<String>, passphrase: Option<String>) -> Self {
+        Self {
+            vault_name: vault_name.into(),
+            passphrase,
+        }
+    }
+}
+
+impl std::fmt::Debug for UnlockVaultRequest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UnlockVaultRequest")
+            .field("vault_name", &self.vault_name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Response body for a request to unlock the encrypted vault of a node
+#[derive(Debug, Clone, Serialize, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct UnlockVaultResponse {
+    #[n(1)] pub vault_name: String,
+    /// False if the vault was already unlocked on the node
+    #[n(2)] pub was_locked: bool,
+}
+
+impl UnlockVaultResponse {
+    pub fn new(vault_name: impl Into<String>, was_locked: bool) -> Self {
+        Self {
+            vault_name: vault_name.into(),
+            was_locked,
+        }
+    }
+}
+
+/// Response body for a trace subscription request
+#[derive(Debug, Clone, Serialize, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TraceSubscription {
+    #[n(1)] pub id: u64,
+}
+
+/// Response body for a trace poll request: the messages traced since the previous poll
+#[derive(Debug, Clone, Serialize, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TraceEventsStatus {
+    #[n(1)] pub events: Vec<TraceEventStatus>,
+    /// Number of events dropped because the client did not poll the events fast enough
+    #[n(2)] pub dropped: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TraceEventStatus {
+    #[n(1)] pub sequence: u64,
+    /// "incoming" or "outgoing"
+    #[n(2)] pub direction: String,
+    #[n(3)] pub source: String,
+    #[n(4)] pub destination: String,
+    #[n(5)] pub context: String,
+    #[n(6)] pub payload_size: u64,
+    /// Time of the event, in seconds since the Unix epoch
+    #[n(7)] pub timestamp: u64,
+}
+
+impl From<TraceEvents> for TraceEventsStatus {
+    fn from(events: TraceEvents) -> Self {
+        Self {
+            events: events
+                .events
+                .into_iter()
+                .map(TraceEventStatus::from)
+                .collect(),
+            dropped: events.dropped,
+        }
+    }
+}
+
+impl From<TraceEvent> for TraceEventStatus {
+    fn from(event: TraceEvent) -> Self {
+        Self {
+            sequence: event.sequence,
+            direction: match event.direction {
+                TraceDirection::Incoming => "incoming",
+                TraceDirection::Outgoing => "outgoing",
+            }
+            .to_string(),
+            source: event.source.to_string(),
+            destination: event.destination.to_string(),
+            context: event.context.to_string(),
+            payload_size: event.payload_size as u64,
+            timestamp: event.timestamp,
+        }
+    }
+}
+
+impl Display for TraceEventStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let arrow = if self.direction == "incoming" {
+            "<-"
+        } else {
+            "->"
+        };
+        write!(
+            f,
+            "#{} {} {} {} {} ({} bytes)",
+            self.sequence, self.context, arrow, self.source, self.destination, self.payload_size
+        )
+    }
+}
+
+/// Request body to subscribe to the events of a node.
+/// The events are sent as messages to the subscriber route
+#[derive(Debug, Clone, Serialize, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SubscribeNodeEvents {
+    /// Route of the worker receiving the events, for example /service/my_agent
+    #[n(1)] pub route: MultiAddr,
+    /// Kinds of the events to receive, for example "worker_started". All the events are sent if empty
+    #[n(2)] pub kinds: Vec<String>,
+}
+
+impl SubscribeNodeEvents {
+    pub fn new(route: MultiAddr, kinds: Vec<String>) -> Self {
+        Self { route, kinds }
+    }
+}
+
+/// Response body describing a subscription to the events of a node
+#[derive(Debug, Clone, Serialize, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct NodeEventsSubscriptionStatus {
+    #[n(1)] pub id: String,
+    #[n(2)] pub route: MultiAddr,
+    #[n(3)] pub kinds: Vec<String>,
+}
+
+/// Message sent to the subscribers of the events of a node
+#[derive(Debug, Clone, Serialize, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct NodeEventStatus {
+    /// Kind of the event, for example "secure_channel_established"
+    #[n(1)] pub kind: String,
+    /// Address of the worker, processor, secure channel or portal concerned by the event
+    #[n(2)] pub address: Option<String>,
+    /// Identifier of the other side of a secure channel, or of the subject of a credential
+    #[n(3)] pub identifier: Option<String>,
+    /// Socket address of the peer of a portal
+    #[n(4)] pub peer: Option<String>,
+    /// Expiration time of a refreshed or expiring credential, in seconds since the Unix epoch
+    #[n(5)] pub expires_at: Option<u64>,
+    /// Time of the event, in seconds since the Unix epoch
+    #[n(6)] pub timestamp: u64,
+}
+
+impl NodeEventStatus {
+    pub fn new(event: NodeEvent, timestamp: u64) -> Self {
+        let kind = event.kind().to_string();
+        let (address, identifier, peer, expires_at) = match event {
+            NodeEvent::WorkerStarted { address }
+            | NodeEvent::WorkerStopped { address }
+            | NodeEvent::ProcessorStarted { address }
+            | NodeEvent::ProcessorStopped { address }
+            | NodeEvent::SecureChannelClosed { address } => (Some(address), None, None, None),
+            NodeEvent::SecureChannelEstablished {
+                address,
+                their_identifier,
+            } => (Some(address), Some(their_identifier), None, None),
+            NodeEvent::PortalConnected { address, peer } => (Some(address), None, Some(peer), None),
+            NodeEvent::CredentialRefreshed {
+                subject,
+                expires_at,
+            }
+            | NodeEvent::CredentialExpiring {
+                subject,
+                expires_at,
+            } => (None, Some(subject), None, Some(expires_at)),
+        };
+        Self {
+            kind,
+            address: address.map(|a| a.to_string()),
+            identifier,
+            peer,
+            expires_at,
+            timestamp,
+        }
+    }
+}
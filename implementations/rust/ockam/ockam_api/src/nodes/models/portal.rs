@@ -7,6 +7,11 @@ use std::time::Duration;
 
 use minicbor::{Decode, Encode};
 use ockam::identity::Identifier;
+use ockam::tcp::{
+    HttpRewrite, PortalCompression, PortalTlsCertificate, PortalTlsVerification,
+    TcpEgressAllowList, TcpInletLimits, TcpInletLoadBalancing, TcpPortalRateLimit,
+    TcpSocketOptions,
+};
 use ockam::transport::HostnamePort;
 use ockam_abac::PolicyExpression;
 use ockam_core::{Address, IncomingAccessControl, OutgoingAccessControl, Route};
@@ -59,6 +64,37 @@ pub struct CreateInlet {
     /// Disable fallback to TCP.
     /// TCP won't be used to transfer data between the Inlet and the Outlet.
     #[n(12)] pub disable_tcp_fallback: bool,
+    /// Socket options of the connections accepted by the inlet.
+    /// If not set, the socket options of the node are used.
+    #[n(13)] pub(crate) socket_options: Option<TcpSocketOptions>,
+    /// Compression algorithm proposed to the outlet for the payloads.
+    /// If not set, the payloads are not compressed.
+    #[n(14)] pub(crate) compression: Option<PortalCompression>,
+    /// Maximum throughput of the connections accepted by the inlet.
+    /// If not set, the throughput is not limited.
+    #[n(15)] pub(crate) rate_limit: Option<TcpPortalRateLimit>,
+    /// Routes used when the outlet address is not reachable, in decreasing order of priority.
+    #[n(16)] pub(crate) fallback_outlet_addrs: Option<Vec<MultiAddr>>,
+    /// Rewriting of the HTTP requests sent through the inlet.
+    /// If not set, the traffic is not parsed as HTTP.
+    #[n(17)] pub(crate) http_rewrite: Option<HttpRewrite>,
+    /// Certificate used to terminate TLS on the connections accepted by the inlet.
+    /// If not set, the traffic is forwarded as is.
+    #[n(18)] pub(crate) tls_certificate: Option<PortalTlsCertificate>,
+    /// Limits of the connections accepted by the inlet.
+    /// If not set, the connections are not limited and never time out.
+    #[n(19)] pub(crate) limits: Option<TcpInletLimits>,
+    /// Strategy spreading the connections across the outlet address and the fallback
+    /// addresses. If not set, the fallback addresses are only used when the outlet address
+    /// is not reachable.
+    #[n(20)] pub(crate) load_balancing: Option<TcpInletLoadBalancing>,
+    /// Expect a PROXY protocol v2 header at the start of the accepted connections, and send
+    /// the address of the original client to the outlet.
+    #[n(21)] pub(crate) proxy_protocol: Option<bool>,
+    /// Keep the accepted connections open during that grace period when the connection to
+    /// the outlet is lost, and resume them once it's back.
+    /// If not set, the connections are closed when the connection to the outlet is lost.
+    #[n(22)] pub(crate) resumption: Option<Duration>,
 }
 
 impl CreateInlet {
@@ -86,6 +122,16 @@ impl CreateInlet {
             secure_channel_identifier: None,
             enable_udp_puncture,
             disable_tcp_fallback,
+            socket_options: None,
+            compression: None,
+            rate_limit: None,
+            fallback_outlet_addrs: None,
+            http_rewrite: None,
+            tls_certificate: None,
+            limits: None,
+            load_balancing: None,
+            proxy_protocol: None,
+            resumption: None,
         }
     }
 
@@ -114,6 +160,16 @@ impl CreateInlet {
             secure_channel_identifier: None,
             enable_udp_puncture,
             disable_tcp_fallback,
+            socket_options: None,
+            compression: None,
+            rate_limit: None,
+            fallback_outlet_addrs: None,
+            http_rewrite: None,
+            tls_certificate: None,
+            limits: None,
+            load_balancing: None,
+            proxy_protocol: None,
+            resumption: None,
         }
     }
 
@@ -129,6 +185,46 @@ impl CreateInlet {
         self.secure_channel_identifier = Some(identifier);
     }
 
+    pub fn set_socket_options(&mut self, socket_options: TcpSocketOptions) {
+        self.socket_options = Some(socket_options);
+    }
+
+    pub fn set_compression(&mut self, compression: PortalCompression) {
+        self.compression = Some(compression);
+    }
+
+    pub fn set_rate_limit(&mut self, rate_limit: TcpPortalRateLimit) {
+        self.rate_limit = Some(rate_limit);
+    }
+
+    pub fn set_fallback_outlet_addrs(&mut self, fallback_outlet_addrs: Vec<MultiAddr>) {
+        self.fallback_outlet_addrs = Some(fallback_outlet_addrs);
+    }
+
+    pub fn set_http_rewrite(&mut self, http_rewrite: HttpRewrite) {
+        self.http_rewrite = Some(http_rewrite);
+    }
+
+    pub fn set_tls_certificate(&mut self, tls_certificate: PortalTlsCertificate) {
+        self.tls_certificate = Some(tls_certificate);
+    }
+
+    pub fn set_limits(&mut self, limits: TcpInletLimits) {
+        self.limits = Some(limits);
+    }
+
+    pub fn set_load_balancing(&mut self, load_balancing: TcpInletLoadBalancing) {
+        self.load_balancing = Some(load_balancing);
+    }
+
+    pub fn set_proxy_protocol(&mut self, proxy_protocol: bool) {
+        self.proxy_protocol = Some(proxy_protocol);
+    }
+
+    pub fn set_resumption(&mut self, grace_period: Duration) {
+        self.resumption = Some(grace_period);
+    }
+
     pub fn listen_addr(&self) -> String {
         self.listen_addr.clone()
     }
@@ -176,6 +272,28 @@ pub struct CreateOutlet {
     /// If not set, the policy set for the [TCP outlet resource type](ockam_abac::ResourceType::TcpOutlet)
     /// will be used.
     #[n(5)] pub policy_expression: Option<PolicyExpression>,
+    /// Socket options of the connections to the target of the outlet.
+    /// If not set, the socket options of the node are used.
+    #[n(6)] pub socket_options: Option<TcpSocketOptions>,
+    /// Compression algorithms accepted when an inlet proposes one.
+    /// If not set, the payloads are not compressed.
+    #[n(7)] pub compressions: Option<Vec<PortalCompression>>,
+    /// Maximum throughput of the connections to the target of the outlet.
+    /// If not set, the throughput is not limited.
+    #[n(8)] pub rate_limit: Option<TcpPortalRateLimit>,
+    /// Verification of the certificate of the target when tls is true.
+    /// If not set, the system certificate authorities and the target hostname are used.
+    #[n(9)] pub tls_verification: Option<PortalTlsVerification>,
+    /// Destinations the outlet is allowed to connect to.
+    /// If not set, the outlet connects to its target without any check.
+    #[n(10)] pub egress_allow_list: Option<TcpEgressAllowList>,
+    /// Send a PROXY protocol v2 header to the target, with the address of the client of the
+    /// inlet and the identifier of the inlet node.
+    #[n(11)] pub proxy_protocol: Option<bool>,
+    /// Keep the connections to the target open during that grace period when the connection
+    /// to an inlet is lost, so that the inlet can resume them.
+    /// If not set, the connections can't be resumed.
+    #[n(12)] pub resumption: Option<Duration>,
 }
 
 impl CreateOutlet {
@@ -191,12 +309,47 @@ impl CreateOutlet {
             worker_addr,
             reachable_from_default_secure_channel,
             policy_expression: None,
+            socket_options: None,
+            compressions: None,
+            rate_limit: None,
+            tls_verification: None,
+            egress_allow_list: None,
+            proxy_protocol: None,
+            resumption: None,
         }
     }
 
     pub fn set_policy_expression(&mut self, expression: PolicyExpression) {
         self.policy_expression = Some(expression);
     }
+
+    pub fn set_socket_options(&mut self, socket_options: TcpSocketOptions) {
+        self.socket_options = Some(socket_options);
+    }
+
+    pub fn set_compressions(&mut self, compressions: Vec<PortalCompression>) {
+        self.compressions = Some(compressions);
+    }
+
+    pub fn set_rate_limit(&mut self, rate_limit: TcpPortalRateLimit) {
+        self.rate_limit = Some(rate_limit);
+    }
+
+    pub fn set_tls_verification(&mut self, tls_verification: PortalTlsVerification) {
+        self.tls_verification = Some(tls_verification);
+    }
+
+    pub fn set_egress_allow_list(&mut self, egress_allow_list: TcpEgressAllowList) {
+        self.egress_allow_list = Some(egress_allow_list);
+    }
+
+    pub fn set_proxy_protocol(&mut self, proxy_protocol: bool) {
+        self.proxy_protocol = Some(proxy_protocol);
+    }
+
+    pub fn set_resumption(&mut self, grace_period: Duration) {
+        self.resumption = Some(grace_period);
+    }
 }
 
 /// Response body when interacting with a portal endpoint
@@ -261,6 +414,67 @@ impl Output for InletStatus {
     fn item(&self) -> crate::Result<String> {
         Ok(format!("{}", self))
     }
+
+    fn as_fields(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(self).ok()
+    }
+}
+
+/// Response body for an inlet stats request: the traffic of the inlet connections
+/// since the inlet was created
+#[derive(Clone, Debug, Decode, Encode, Serialize, Deserialize, PartialEq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct InletStats {
+    #[n(1)] pub alias: String,
+    #[n(2)] pub bind_addr: String,
+    /// Number of bytes read from the TCP clients and sent to the outlet
+    #[n(3)] pub bytes_in: u64,
+    /// Number of bytes received from the outlet and written to the TCP clients
+    #[n(4)] pub bytes_out: u64,
+    #[n(5)] pub active_connections: u64,
+    #[n(6)] pub total_connections: u64,
+    /// Number of connections refused because the inlet had too many open connections
+    #[n(7)] pub rejected_max_connections: u64,
+    /// Number of connections refused because their IP address had too many open connections
+    #[n(8)] pub rejected_max_connections_per_ip: u64,
+    /// Number of connections closed because they were idle
+    #[n(9)] pub idle_timeouts: u64,
+}
+
+impl Display for InletStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Inlet {} at {}: {} active connection(s), {} in total, {} byte(s) in, {} byte(s) out",
+            color_primary(&self.alias),
+            color_primary(&self.bind_addr),
+            color_primary(self.active_connections.to_string()),
+            color_primary(self.total_connections.to_string()),
+            color_primary(self.bytes_in.to_string()),
+            color_primary(self.bytes_out.to_string()),
+        )?;
+        let rejected_connections =
+            self.rejected_max_connections + self.rejected_max_connections_per_ip;
+        if rejected_connections > 0 || self.idle_timeouts > 0 {
+            write!(
+                f,
+                ", {} rejected connection(s) ({} over max_connections, {} over \
+                max_connections_per_ip), {} idle timeout(s)",
+                color_primary(rejected_connections.to_string()),
+                color_primary(self.rejected_max_connections.to_string()),
+                color_primary(self.rejected_max_connections_per_ip.to_string()),
+                color_primary(self.idle_timeouts.to_string()),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Output for InletStats {
+    fn item(&self) -> crate::Result<String> {
+        Ok(format!("{}", self))
+    }
 }
 
 /// Response body when interacting with a portal endpoint
@@ -322,6 +536,40 @@ impl Output for OutletStatus {
     }
 }
 
+/// Request body to check that the target of an outlet is reachable
+#[derive(Clone, Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PingOutlet {
+    /// Maximum time to wait for a TCP connection to the target of the outlet
+    #[n(1)] pub timeout: Duration,
+}
+
+impl PingOutlet {
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+}
+
+/// Response body when checking that the target of an outlet is reachable
+#[derive(Clone, Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct OutletPingStatus {
+    #[n(1)] pub worker_addr: Address,
+    #[n(2)] pub socket_addr: SocketAddr,
+    /// Time taken to open a TCP connection to the target, if the connection succeeded
+    #[n(3)] pub latency: Option<Duration>,
+    /// Reason why the TCP connection to the target failed
+    #[n(4)] pub error: Option<String>,
+}
+
+impl OutletPingStatus {
+    pub fn is_reachable(&self) -> bool {
+        self.latency.is_some()
+    }
+}
+
 #[derive(Debug)]
 pub enum OutletAccessControl {
     AccessControl(
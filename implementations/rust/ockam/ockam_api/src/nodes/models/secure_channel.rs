@@ -6,7 +6,10 @@ use minicbor::{Decode, Encode};
 use serde::Serialize;
 
 use ockam::identity::models::CredentialAndPurposeKey;
-use ockam::identity::{Identifier, SecureChannel, SecureChannelListener, DEFAULT_TIMEOUT};
+use ockam::identity::{
+    HandshakeFailure, HandshakeStatistics, Identifier, SecureChannel, SecureChannelListener,
+    SecureChannelRegistryEntry, TimestampInSeconds, DEFAULT_TIMEOUT,
+};
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{route, Address, Result};
 use ockam_multiaddr::MultiAddr;
@@ -14,7 +17,7 @@ use ockam_multiaddr::MultiAddr;
 use crate::colors::color_primary;
 use crate::error::ApiError;
 use crate::nodes::registry::SecureChannelInfo;
-use crate::output::Output;
+use crate::output::{human_readable_time, Output};
 use crate::{route_to_multiaddr, try_route_to_multiaddr};
 
 //Requests
@@ -219,11 +222,22 @@ pub struct ShowSecureChannelResponse {
     #[n(2)] pub route: Option<String>,
     #[n(3)] pub authorized_identifiers: Option<Vec<String>>,
     #[n(4)] pub flow_control_id: Option<FlowControlId>,
+    #[n(5)] pub their_identifier: Option<String>,
+    #[n(6)] pub created_at: Option<TimestampInSeconds>,
+    #[n(7)] pub last_activity: Option<TimestampInSeconds>,
 }
 
 impl ShowSecureChannelResponse {
-    pub fn new(info: Option<SecureChannelInfo>) -> Self {
+    pub fn new(
+        info: Option<SecureChannelInfo>,
+        registry_entry: Option<SecureChannelRegistryEntry>,
+    ) -> Self {
         Self {
+            their_identifier: info
+                .as_ref()
+                .map(|info| info.sc().their_identifier().to_string()),
+            created_at: registry_entry.as_ref().map(|entry| entry.created_at()),
+            last_activity: registry_entry.as_ref().map(|entry| entry.last_activity()),
             channel: info
                 .clone()
                 .map(|info| info.sc().encryptor_address().to_string()),
@@ -245,7 +259,7 @@ impl Output for ShowSecureChannelResponse {
     fn item(&self) -> crate::Result<String> {
         let s = match &self.channel {
             Some(addr) => {
-                format!(
+                let mut s = format!(
                     "\n  Secure Channel:\n{} {}\n{} {}\n{} {}",
                     "  •         At: ".light_magenta(),
                     try_route_to_multiaddr(&route![addr.to_string()])?
@@ -261,7 +275,29 @@ impl Output for ShowSecureChannelResponse {
                         .map(|id| id.clone().light_yellow().to_string())
                         .collect::<Vec<String>>()
                         .join("\n\t")
-                )
+                );
+                if let Some(their_identifier) = &self.their_identifier {
+                    s.push_str(&format!(
+                        "\n{} {}",
+                        "  •       Peer: ".light_magenta(),
+                        their_identifier.clone().light_yellow()
+                    ));
+                }
+                if let Some(created_at) = self.created_at {
+                    s.push_str(&format!(
+                        "\n{} {}",
+                        "  •    Created: ".light_magenta(),
+                        human_readable_time(created_at).light_yellow()
+                    ));
+                }
+                if let Some(last_activity) = self.last_activity {
+                    s.push_str(&format!(
+                        "\n{} {}",
+                        "  •  Last used: ".light_magenta(),
+                        human_readable_time(last_activity).light_yellow()
+                    ));
+                }
+                s
             }
             None => format!("{}", "Channel not found".red()),
         };
@@ -269,3 +305,120 @@ impl Output for ShowSecureChannelResponse {
         Ok(s)
     }
 }
+
+/// Response body with the attempts, durations and failures
+/// of the secure channel handshakes of a node
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SecureChannelDiagnosticsResponse {
+    #[n(1)] pub attempts: u64,
+    #[n(2)] pub successes: u64,
+    #[n(3)] pub failures: Vec<HandshakeFailureCount>,
+    #[n(4)] pub average_duration_ms: Option<u64>,
+    #[n(5)] pub max_duration_ms: Option<u64>,
+    #[n(6)] pub recent_failures: Vec<HandshakeFailureDetails>,
+}
+
+/// Number of handshake failures for a given reason
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct HandshakeFailureCount {
+    #[n(1)] pub reason: String,
+    #[n(2)] pub count: u64,
+}
+
+/// Description of a handshake failure
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct HandshakeFailureDetails {
+    #[n(1)] pub role: String,
+    #[n(2)] pub reason: String,
+    #[n(3)] pub message: String,
+    #[n(4)] pub failed_at: TimestampInSeconds,
+}
+
+impl From<HandshakeStatistics> for SecureChannelDiagnosticsResponse {
+    fn from(statistics: HandshakeStatistics) -> Self {
+        Self {
+            attempts: statistics.attempts,
+            successes: statistics.successes,
+            failures: statistics
+                .failures
+                .iter()
+                .map(|(reason, count)| HandshakeFailureCount {
+                    reason: reason.to_string(),
+                    count: *count,
+                })
+                .collect(),
+            average_duration_ms: statistics.average_duration().map(|d| d.as_millis() as u64),
+            max_duration_ms: (statistics.successes > 0)
+                .then_some(statistics.max_duration.as_millis() as u64),
+            recent_failures: statistics
+                .recent_failures
+                .into_iter()
+                .map(HandshakeFailureDetails::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<HandshakeFailure> for HandshakeFailureDetails {
+    fn from(failure: HandshakeFailure) -> Self {
+        Self {
+            role: if failure.is_initiator {
+                "initiator".to_string()
+            } else {
+                "responder".to_string()
+            },
+            reason: failure.reason.to_string(),
+            message: failure.message,
+            failed_at: failure.failed_at,
+        }
+    }
+}
+
+impl Output for SecureChannelDiagnosticsResponse {
+    fn item(&self) -> crate::Result<String> {
+        let mut s = format!(
+            "\n  Secure Channel Handshakes:\n{} {}\n{} {}",
+            "  •   Attempts: ".light_magenta(),
+            self.attempts.to_string().light_yellow(),
+            "  •  Successes: ".light_magenta(),
+            self.successes.to_string().light_yellow(),
+        );
+        if let Some(average_duration_ms) = self.average_duration_ms {
+            s.push_str(&format!(
+                "\n{} {}",
+                "  •   Duration: ".light_magenta(),
+                format!(
+                    "{average_duration_ms}ms on average, {}ms at most",
+                    self.max_duration_ms.unwrap_or(average_duration_ms)
+                )
+                .light_yellow()
+            ));
+        }
+        for failure in &self.failures {
+            s.push_str(&format!(
+                "\n{} {}",
+                "  •     Failed: ".light_magenta(),
+                format!("{} ({})", failure.count, failure.reason).light_yellow()
+            ));
+        }
+        if !self.recent_failures.is_empty() {
+            s.push_str("\n\n  Recent failures:");
+            for failure in &self.recent_failures {
+                s.push_str(&format!(
+                    "\n  • {} {} {}: {}",
+                    human_readable_time(failure.failed_at).light_magenta(),
+                    failure.role.clone().light_yellow(),
+                    failure.reason.clone().light_yellow(),
+                    failure.message
+                ));
+            }
+        }
+        Ok(s)
+    }
+}
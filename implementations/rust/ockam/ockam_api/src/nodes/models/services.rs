@@ -1,8 +1,11 @@
 use crate::colors::{color_primary, color_warn};
-use crate::kafka::{ConsumerPublishing, ConsumerResolution};
+use crate::http_gateway::HttpRoute;
+use crate::kafka::{ConsumerPublishing, ConsumerResolution, RecordEncryption, TopicPolicies};
 use crate::output::Output;
+use crate::postgres::RoleMapping;
 use crate::terminal::fmt;
 use minicbor::{Decode, Encode};
+use ockam::tcp::TcpEgressAllowList;
 use ockam_abac::PolicyExpression;
 use ockam_core::compat::net::SocketAddr;
 use ockam_core::Address;
@@ -10,6 +13,7 @@ use ockam_multiaddr::MultiAddr;
 use serde::Serialize;
 use std::fmt::Display;
 use std::fmt::Write;
+use std::time::Duration;
 
 #[derive(Debug, Clone, Decode, Encode)]
 #[rustfmt::skip]
@@ -60,6 +64,10 @@ pub struct StartKafkaOutletRequest {
     #[n(1)] bootstrap_server_addr: String,
     #[n(2)] tls: bool,
     #[n(3)] policy_expression: Option<PolicyExpression>,
+    #[n(4)] egress_allow_list: Option<TcpEgressAllowList>,
+    #[n(5)] schema_registry_addr: Option<String>,
+    #[n(6)] schema_registry_tls: bool,
+    #[n(7)] deterministic_record_keys: bool,
 }
 
 impl StartKafkaOutletRequest {
@@ -72,9 +80,29 @@ impl StartKafkaOutletRequest {
             bootstrap_server_addr,
             tls,
             policy_expression,
+            egress_allow_list: None,
+            schema_registry_addr: None,
+            schema_registry_tls: false,
+            deterministic_record_keys: false,
         }
     }
 
+    /// Restrict the brokers which can be reached, when they are advertised by the cluster
+    pub fn set_egress_allow_list(&mut self, egress_allow_list: TcpEgressAllowList) {
+        self.egress_allow_list = Some(egress_allow_list);
+    }
+
+    /// Expose the Schema Registry of the cluster to the Kafka inlets
+    pub fn set_schema_registry(&mut self, schema_registry_addr: String, tls: bool) {
+        self.schema_registry_addr = Some(schema_registry_addr);
+        self.schema_registry_tls = tls;
+    }
+
+    /// Give the Kafka inlets the keys used to deterministically encrypt the record keys
+    pub fn set_deterministic_record_keys(&mut self) {
+        self.deterministic_record_keys = true;
+    }
+
     pub fn bootstrap_server_addr(&self) -> String {
         self.bootstrap_server_addr.clone()
     }
@@ -86,6 +114,22 @@ impl StartKafkaOutletRequest {
     pub fn policy_expression(&self) -> Option<PolicyExpression> {
         self.policy_expression.clone()
     }
+
+    pub fn egress_allow_list(&self) -> Option<TcpEgressAllowList> {
+        self.egress_allow_list.clone()
+    }
+
+    pub fn schema_registry_addr(&self) -> Option<String> {
+        self.schema_registry_addr.clone()
+    }
+
+    pub fn schema_registry_tls(&self) -> bool {
+        self.schema_registry_tls
+    }
+
+    pub fn deterministic_record_keys(&self) -> bool {
+        self.deterministic_record_keys
+    }
 }
 
 #[derive(Debug, Clone, Decode, Encode)]
@@ -101,6 +145,10 @@ pub struct StartKafkaInletRequest {
     #[n(7)] inlet_policy_expression: Option<PolicyExpression>,
     #[n(8)] consumer_policy_expression: Option<PolicyExpression>,
     #[n(9)] producer_policy_expression: Option<PolicyExpression>,
+    #[n(10)] schema_registry_bind_address: Option<SocketAddr>,
+    #[n(11)] topic_policies: Option<TopicPolicies>,
+    #[n(12)] key_rotation_interval: Option<Duration>,
+    #[n(13)] record_encryption: Option<RecordEncryption>,
 }
 
 impl StartKafkaInletRequest {
@@ -126,9 +174,36 @@ impl StartKafkaInletRequest {
             inlet_policy_expression,
             consumer_policy_expression,
             producer_policy_expression,
+            schema_registry_bind_address: None,
+            topic_policies: None,
+            key_rotation_interval: None,
+            record_encryption: None,
         }
     }
 
+    /// Proxy the Schema Registry exposed by the Kafka outlet on the given address, and keep
+    /// the schema ids of the records readable when their content is encrypted
+    pub fn set_schema_registry_bind_address(&mut self, bind_address: SocketAddr) {
+        self.schema_registry_bind_address = Some(bind_address);
+    }
+
+    /// Restrict the topics which can be used through the inlet, and configure the
+    /// encryption of each topic
+    pub fn set_topic_policies(&mut self, topic_policies: TopicPolicies) {
+        self.topic_policies = Some(topic_policies);
+    }
+
+    /// Exchange new record encryption keys with the consumers once the keys are older than
+    /// the interval. The keys are never rotated when it's not set
+    pub fn set_key_rotation_interval(&mut self, key_rotation_interval: Duration) {
+        self.key_rotation_interval = Some(key_rotation_interval);
+    }
+
+    /// Encrypt the record keys and some record headers, in addition to the record values
+    pub fn set_record_encryption(&mut self, record_encryption: RecordEncryption) {
+        self.record_encryption = Some(record_encryption);
+    }
+
     pub fn bind_address(&self) -> SocketAddr {
         self.bind_address
     }
@@ -162,6 +237,246 @@ impl StartKafkaInletRequest {
     pub fn producer_policy_expression(&self) -> Option<PolicyExpression> {
         self.producer_policy_expression.clone()
     }
+
+    pub fn schema_registry_bind_address(&self) -> Option<SocketAddr> {
+        self.schema_registry_bind_address
+    }
+
+    pub fn topic_policies(&self) -> TopicPolicies {
+        self.topic_policies.clone().unwrap_or_default()
+    }
+
+    pub fn key_rotation_interval(&self) -> Option<Duration> {
+        self.key_rotation_interval
+    }
+
+    pub fn record_encryption(&self) -> RecordEncryption {
+        self.record_encryption.clone().unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct StartMqttOutletRequest {
+    #[n(1)] broker_addr: String,
+    #[n(2)] tls: bool,
+    #[n(3)] policy_expression: Option<PolicyExpression>,
+}
+
+impl StartMqttOutletRequest {
+    pub fn new(
+        broker_addr: String,
+        tls: bool,
+        policy_expression: Option<PolicyExpression>,
+    ) -> Self {
+        Self {
+            broker_addr,
+            tls,
+            policy_expression,
+        }
+    }
+
+    pub fn broker_addr(&self) -> String {
+        self.broker_addr.clone()
+    }
+
+    pub fn tls(&self) -> bool {
+        self.tls
+    }
+
+    pub fn policy_expression(&self) -> Option<PolicyExpression> {
+        self.policy_expression.clone()
+    }
+}
+
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct StartMqttInletRequest {
+    #[n(1)] bind_address: SocketAddr,
+    #[n(2)] mqtt_outlet_route: MultiAddr,
+    #[n(3)] encrypt_payloads: bool,
+    #[n(4)] policy_expression: Option<PolicyExpression>,
+}
+
+impl StartMqttInletRequest {
+    pub fn new(
+        bind_address: SocketAddr,
+        mqtt_outlet_route: MultiAddr,
+        encrypt_payloads: bool,
+        policy_expression: Option<PolicyExpression>,
+    ) -> Self {
+        Self {
+            bind_address,
+            mqtt_outlet_route,
+            encrypt_payloads,
+            policy_expression,
+        }
+    }
+
+    pub fn bind_address(&self) -> SocketAddr {
+        self.bind_address
+    }
+
+    pub fn mqtt_outlet_route(&self) -> MultiAddr {
+        self.mqtt_outlet_route.clone()
+    }
+
+    /// Encrypt the payloads published by the clients of the inlet, and decrypt the payloads
+    /// delivered to them, with a key per topic given by the MQTT outlet
+    pub fn encrypt_payloads(&self) -> bool {
+        self.encrypt_payloads
+    }
+
+    pub fn policy_expression(&self) -> Option<PolicyExpression> {
+        self.policy_expression.clone()
+    }
+}
+
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct StartPostgresOutletRequest {
+    #[n(1)] database_addr: String,
+    #[n(2)] role_mapping: RoleMapping,
+    #[n(3)] password_file: Option<String>,
+    #[n(4)] policy_expression: Option<PolicyExpression>,
+}
+
+impl StartPostgresOutletRequest {
+    pub fn new(
+        database_addr: String,
+        role_mapping: RoleMapping,
+        password_file: Option<String>,
+        policy_expression: Option<PolicyExpression>,
+    ) -> Self {
+        Self {
+            database_addr,
+            role_mapping,
+            password_file,
+            policy_expression,
+        }
+    }
+
+    pub fn database_addr(&self) -> String {
+        self.database_addr.clone()
+    }
+
+    pub fn role_mapping(&self) -> RoleMapping {
+        self.role_mapping.clone()
+    }
+
+    /// Path of the password file, read by the node running the outlet
+    pub fn password_file(&self) -> Option<String> {
+        self.password_file.clone()
+    }
+
+    pub fn policy_expression(&self) -> Option<PolicyExpression> {
+        self.policy_expression.clone()
+    }
+}
+
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct StartHttpGatewayRequest {
+    #[n(1)] routes: Vec<HttpRoute>,
+    #[n(2)] policy_expression: Option<PolicyExpression>,
+}
+
+impl StartHttpGatewayRequest {
+    pub fn new(routes: Vec<HttpRoute>, policy_expression: Option<PolicyExpression>) -> Self {
+        Self {
+            routes,
+            policy_expression,
+        }
+    }
+
+    pub fn routes(&self) -> Vec<HttpRoute> {
+        self.routes.clone()
+    }
+
+    /// Policy of the identities allowed to connect to the gateway, before the policies
+    /// of the routes are evaluated for each request
+    pub fn policy_expression(&self) -> Option<PolicyExpression> {
+        self.policy_expression.clone()
+    }
+}
+
+/// Response body for a Kafka inlet stats request: the traffic of each topic used by the
+/// clients of the inlet
+#[derive(Clone, Debug, Decode, Encode, Serialize, PartialEq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct KafkaInletStats {
+    #[n(1)] pub address: String,
+    #[n(2)] pub topics: Vec<KafkaTopicStats>,
+}
+
+impl Display for KafkaInletStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Kafka inlet {}: {} topic(s)",
+            color_primary(&self.address),
+            color_primary(self.topics.len().to_string()),
+        )?;
+        for topic in &self.topics {
+            write!(f, "\n{}{}", fmt::PADDING, topic)?;
+        }
+        Ok(())
+    }
+}
+
+impl Output for KafkaInletStats {
+    fn item(&self) -> crate::Result<String> {
+        Ok(format!("{}", self))
+    }
+}
+
+/// Traffic of a topic going through a Kafka inlet. A batch contains the records
+/// of one partition in a produce request or in a fetch response
+#[derive(Clone, Debug, Decode, Encode, Serialize, PartialEq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct KafkaTopicStats {
+    #[n(1)] pub topic: String,
+    #[n(2)] pub produced_batches: u64,
+    #[n(3)] pub encrypted_produced_batches: u64,
+    /// Size of the produced records, before their encryption
+    #[n(4)] pub produced_bytes: u64,
+    #[n(5)] pub fetched_batches: u64,
+    #[n(6)] pub decrypted_fetched_batches: u64,
+    /// Size of the fetched records, before their decryption
+    #[n(7)] pub fetched_bytes: u64,
+    /// Number of fetched batches which could not be decrypted
+    #[n(8)] pub decryption_failures: u64,
+}
+
+impl Display for KafkaTopicStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} produced batch(es) ({} encrypted, {} byte(s)), \
+            {} fetched batch(es) ({} decrypted, {} byte(s))",
+            color_primary(&self.topic),
+            color_primary(self.produced_batches.to_string()),
+            color_primary(self.encrypted_produced_batches.to_string()),
+            color_primary(self.produced_bytes.to_string()),
+            color_primary(self.fetched_batches.to_string()),
+            color_primary(self.decrypted_fetched_batches.to_string()),
+            color_primary(self.fetched_bytes.to_string()),
+        )?;
+        if self.decryption_failures > 0 {
+            write!(
+                f,
+                ", {} decryption failure(s)",
+                color_warn(self.decryption_failures.to_string())
+            )?;
+        }
+        Ok(())
+    }
 }
 
 /// Request body when instructing a node to start an Uppercase service
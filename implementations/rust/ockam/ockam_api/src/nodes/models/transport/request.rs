@@ -7,11 +7,18 @@ use minicbor::{Decode, Encode};
 pub struct CreateTcpConnection {
     /// The address payload for the transport
     #[n(1)] pub addr: String,
+    /// URL of the proxy used for the connection, instead of the proxy set in the environment
+    #[n(2)] pub proxy: Option<String>,
 }
 
 impl CreateTcpConnection {
     pub fn new(addr: String) -> Self {
-        Self { addr }
+        Self { addr, proxy: None }
+    }
+
+    pub fn with_proxy(mut self, proxy: Option<String>) -> Self {
+        self.proxy = proxy;
+        self
     }
 }
 
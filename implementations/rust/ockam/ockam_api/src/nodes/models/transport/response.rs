@@ -138,4 +138,8 @@ impl Output for TransportStatus {
     fn item(&self) -> crate::Result<String> {
         Ok(format!("{}", self))
     }
+
+    fn as_fields(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(self).ok()
+    }
 }
@@ -0,0 +1,183 @@
+//! TUN inlets and outlets request/response types
+
+use std::fmt::{Display, Formatter};
+
+use minicbor::{Decode, Encode};
+use ockam::identity::Identifier;
+use ockam_abac::PolicyExpression;
+use ockam_core::Address;
+use ockam_multiaddr::MultiAddr;
+use serde::Serialize;
+
+use crate::colors::color_primary;
+use crate::error::ApiError;
+use crate::output::Output;
+use crate::tun::TunNetwork;
+
+/// Configuration of the TUN device of an inlet or an outlet
+#[derive(Clone, Debug, Decode, Encode, Serialize, PartialEq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TunInterface {
+    /// Name of the TUN device. If it ends with `%d`, the kernel replaces it with a number
+    #[n(1)] pub name: String,
+    /// Address of the TUN device, with the prefix length of the network shared by the
+    /// inlets and the outlet
+    #[n(2)] pub address: TunNetwork,
+    /// MTU of the TUN device
+    #[n(3)] pub mtu: u16,
+    /// Other networks reached through the TUN device
+    #[n(4)] pub routes: Vec<TunNetwork>,
+}
+
+impl TunInterface {
+    pub fn new(name: String, address: TunNetwork, mtu: u16, routes: Vec<TunNetwork>) -> Self {
+        Self {
+            name,
+            address,
+            mtu,
+            routes,
+        }
+    }
+}
+
+/// Request body to create a TUN inlet
+#[derive(Clone, Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CreateTunInlet {
+    /// The TUN device of the inlet
+    #[n(1)] pub(crate) interface: TunInterface,
+    /// The address of the TUN outlet
+    #[n(2)] pub(crate) outlet_addr: MultiAddr,
+    /// A human-friendly alias for this inlet
+    #[n(3)] pub(crate) alias: String,
+    /// An authorised identity for secure channels.
+    /// Only set for non-project addresses as for projects the project's
+    /// authorised identity will be used.
+    #[n(4)] pub(crate) authorized: Option<Identifier>,
+    /// The expression for the access control policy for this inlet.
+    /// If not set, the policy set for the
+    /// [TUN inlet resource type](ockam_abac::ResourceType::TunInlet) will be used.
+    #[n(5)] pub(crate) policy_expression: Option<PolicyExpression>,
+}
+
+impl CreateTunInlet {
+    pub fn new(
+        interface: TunInterface,
+        outlet_addr: MultiAddr,
+        alias: String,
+        authorized: Option<Identifier>,
+    ) -> Self {
+        Self {
+            interface,
+            outlet_addr,
+            alias,
+            authorized,
+            policy_expression: None,
+        }
+    }
+
+    pub fn set_policy_expression(&mut self, expression: PolicyExpression) {
+        self.policy_expression = Some(expression);
+    }
+}
+
+/// Request body to create a TUN outlet
+#[derive(Clone, Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CreateTunOutlet {
+    /// The TUN device of the outlet
+    #[n(1)] pub interface: TunInterface,
+    /// The address of the outlet worker
+    #[n(2)] pub worker_addr: Option<Address>,
+    /// The expression for the access control policy for this outlet.
+    /// If not set, the policy set for the
+    /// [TUN outlet resource type](ockam_abac::ResourceType::TunOutlet) will be used.
+    #[n(3)] pub policy_expression: Option<PolicyExpression>,
+}
+
+impl CreateTunOutlet {
+    pub fn new(interface: TunInterface, worker_addr: Option<Address>) -> Self {
+        Self {
+            interface,
+            worker_addr,
+            policy_expression: None,
+        }
+    }
+
+    pub fn set_policy_expression(&mut self, expression: PolicyExpression) {
+        self.policy_expression = Some(expression);
+    }
+}
+
+/// Response body when interacting with a TUN inlet
+#[derive(Clone, Debug, Decode, Encode, Serialize, PartialEq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TunInletStatus {
+    #[n(1)] pub alias: String,
+    /// Actual name of the TUN device
+    #[n(2)] pub device: String,
+    #[n(3)] pub interface: TunInterface,
+    #[n(4)] pub outlet_addr: String,
+    /// Route to the outlet, once the inlet is connected
+    #[n(5)] pub outlet_route: Option<String>,
+}
+
+impl Display for TunInletStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "TUN Inlet {} on device {} with address {} is connected to {}",
+            color_primary(&self.alias),
+            color_primary(&self.device),
+            color_primary(self.interface.address.to_string()),
+            color_primary(&self.outlet_addr),
+        )
+    }
+}
+
+impl Output for TunInletStatus {
+    fn item(&self) -> Result<String, ApiError> {
+        Ok(format!("{}", self))
+    }
+
+    fn as_fields(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(self).ok()
+    }
+}
+
+/// Response body when interacting with a TUN outlet
+#[derive(Clone, Debug, Decode, Encode, Serialize, PartialEq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TunOutletStatus {
+    #[n(1)] pub worker_addr: Address,
+    /// Actual name of the TUN device
+    #[n(2)] pub device: String,
+    #[n(3)] pub interface: TunInterface,
+}
+
+impl Display for TunOutletStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "TUN Outlet at {} on device {} with address {}",
+            color_primary(self.worker_addr.address()),
+            color_primary(&self.device),
+            color_primary(self.interface.address.to_string()),
+        )
+    }
+}
+
+impl Output for TunOutletStatus {
+    fn item(&self) -> Result<String, ApiError> {
+        Ok(format!("{}", self))
+    }
+
+    fn as_fields(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(self).ok()
+    }
+}
@@ -0,0 +1,167 @@
+//! UDP inlets and outlets request/response types
+
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use minicbor::{Decode, Encode};
+use ockam::identity::Identifier;
+use ockam::transport::HostnamePort;
+use ockam_abac::PolicyExpression;
+use ockam_core::Address;
+use ockam_multiaddr::MultiAddr;
+use serde::Serialize;
+
+use crate::colors::color_primary;
+use crate::error::ApiError;
+use crate::output::Output;
+
+/// Request body to create a UDP inlet
+#[derive(Clone, Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CreateUdpInlet {
+    /// The UDP address the inlet should listen at
+    #[n(1)] pub(crate) listen_addr: String,
+    /// The address of the UDP outlet
+    #[n(2)] pub(crate) outlet_addr: MultiAddr,
+    /// A human-friendly alias for this inlet
+    #[n(3)] pub(crate) alias: String,
+    /// An authorised identity for secure channels.
+    /// Only set for non-project addresses as for projects the project's
+    /// authorised identity will be used.
+    #[n(4)] pub(crate) authorized: Option<Identifier>,
+    /// The expression for the access control policy for this inlet.
+    /// If not set, the policy set for the
+    /// [UDP inlet resource type](ockam_abac::ResourceType::UdpInlet) will be used.
+    #[n(5)] pub(crate) policy_expression: Option<PolicyExpression>,
+    /// Time after which the flow of a UDP peer without any traffic is closed.
+    /// If not set, the default idle timeout is used.
+    #[n(6)] pub(crate) idle_timeout: Option<Duration>,
+}
+
+impl CreateUdpInlet {
+    pub fn new(
+        listen_addr: String,
+        outlet_addr: MultiAddr,
+        alias: String,
+        authorized: Option<Identifier>,
+    ) -> Self {
+        Self {
+            listen_addr,
+            outlet_addr,
+            alias,
+            authorized,
+            policy_expression: None,
+            idle_timeout: None,
+        }
+    }
+
+    pub fn set_policy_expression(&mut self, expression: PolicyExpression) {
+        self.policy_expression = Some(expression);
+    }
+
+    pub fn set_idle_timeout(&mut self, idle_timeout: Duration) {
+        self.idle_timeout = Some(idle_timeout);
+    }
+}
+
+/// Request body to create a UDP outlet
+#[derive(Clone, Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CreateUdpOutlet {
+    /// The address of the UDP server the outlet sends datagrams to
+    #[n(1)] pub hostname_port: HostnamePort,
+    /// The address of the outlet worker
+    #[n(2)] pub worker_addr: Option<Address>,
+    /// The expression for the access control policy for this outlet.
+    /// If not set, the policy set for the
+    /// [UDP outlet resource type](ockam_abac::ResourceType::UdpOutlet) will be used.
+    #[n(3)] pub policy_expression: Option<PolicyExpression>,
+    /// Time after which the flow of an inlet peer without any traffic is closed.
+    /// If not set, the default idle timeout is used.
+    #[n(4)] pub idle_timeout: Option<Duration>,
+}
+
+impl CreateUdpOutlet {
+    pub fn new(hostname_port: HostnamePort, worker_addr: Option<Address>) -> Self {
+        Self {
+            hostname_port,
+            worker_addr,
+            policy_expression: None,
+            idle_timeout: None,
+        }
+    }
+
+    pub fn set_policy_expression(&mut self, expression: PolicyExpression) {
+        self.policy_expression = Some(expression);
+    }
+
+    pub fn set_idle_timeout(&mut self, idle_timeout: Duration) {
+        self.idle_timeout = Some(idle_timeout);
+    }
+}
+
+/// Response body when interacting with a UDP inlet
+#[derive(Clone, Debug, Decode, Encode, Serialize, PartialEq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct UdpInletStatus {
+    #[n(1)] pub alias: String,
+    #[n(2)] pub bind_addr: String,
+    #[n(3)] pub outlet_addr: String,
+    /// Route to the outlet, once the inlet is connected
+    #[n(4)] pub outlet_route: Option<String>,
+}
+
+impl Display for UdpInletStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "UDP Inlet {} at {} is connected to {}",
+            color_primary(&self.alias),
+            color_primary(&self.bind_addr),
+            color_primary(&self.outlet_addr),
+        )
+    }
+}
+
+impl Output for UdpInletStatus {
+    fn item(&self) -> Result<String, ApiError> {
+        Ok(format!("{}", self))
+    }
+
+    fn as_fields(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(self).ok()
+    }
+}
+
+/// Response body when interacting with a UDP outlet
+#[derive(Clone, Debug, Decode, Encode, Serialize, PartialEq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct UdpOutletStatus {
+    #[n(1)] pub worker_addr: Address,
+    #[n(2)] pub to: String,
+}
+
+impl Display for UdpOutletStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "UDP Outlet at {} sends datagrams to {}",
+            color_primary(self.worker_addr.address()),
+            color_primary(&self.to),
+        )
+    }
+}
+
+impl Output for UdpOutletStatus {
+    fn item(&self) -> Result<String, ApiError> {
+        Ok(format!("{}", self))
+    }
+
+    fn as_fields(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(self).ok()
+    }
+}
@@ -3,33 +3,78 @@ use crate::output::Output;
 use crate::Result;
 use colorful::Colorful;
 use minicbor::{Decode, Encode};
+use ockam_node::{WorkerInfo, WorkerMetrics};
+use serde::Serialize;
 
-#[derive(Debug, Clone, Decode, Encode)]
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct WorkerStatus {
     #[n(2)] pub addr: String,
+    /// Either "worker" or "processor"
+    #[n(3)] pub worker_type: String,
+    /// All the addresses of the worker, including the primary one
+    #[n(4)] pub addresses: Vec<String>,
+    /// Number of messages waiting to be handled by the worker
+    #[n(5)] pub mailbox_count: u64,
+    /// Total number of messages sent to the worker since it was started
+    #[n(6)] pub messages_count: u64,
 }
 
-impl WorkerStatus {
-    pub fn new(addr: impl Into<String>) -> Self {
-        Self { addr: addr.into() }
+impl From<WorkerInfo> for WorkerStatus {
+    fn from(info: WorkerInfo) -> Self {
+        Self {
+            addr: info.primary_address.address().to_string(),
+            worker_type: if info.is_processor {
+                "processor"
+            } else {
+                "worker"
+            }
+            .to_string(),
+            addresses: info
+                .addresses
+                .iter()
+                .map(|a| a.address().to_string())
+                .collect(),
+            mailbox_count: info.mailbox_count as u64,
+            messages_count: info.messages_count as u64,
+        }
     }
 }
 
 impl Output for WorkerStatus {
     fn item(&self) -> Result<String> {
-        Ok(format!(
-            "Worker {}",
+        let mut output = format!(
+            "{} {}",
+            capitalize(&self.worker_type),
             self.addr
                 .to_string()
                 .color(OckamColor::PrimaryResource.color())
-        ))
+        );
+        let aliases: Vec<&str> = self
+            .addresses
+            .iter()
+            .map(|a| a.as_str())
+            .filter(|a| *a != self.addr)
+            .collect();
+        if !aliases.is_empty() {
+            output.push_str(&format!("\nAliases: {}", aliases.join(", ")));
+        }
+        output.push_str(&format!("\nMailbox: {} message(s)", self.mailbox_count));
+        Ok(output)
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
     }
 }
 
 /// Response body for listing workers
-#[derive(Debug, Clone, Decode, Encode)]
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct WorkerList {
@@ -41,3 +86,102 @@ impl WorkerList {
         Self { list }
     }
 }
+
+/// Runtime metrics of a worker or processor
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct WorkerMetricsStatus {
+    #[n(1)] pub addr: String,
+    /// Either "worker" or "processor"
+    #[n(2)] pub worker_type: String,
+    /// Number of messages waiting to be handled by the worker
+    #[n(3)] pub queue_depth: u64,
+    /// Total number of messages sent to the worker since it was started
+    #[n(4)] pub messages_received: u64,
+    /// Total number of messages handled by the worker since it was started
+    #[n(5)] pub messages_handled: u64,
+    /// Number of messages for which the worker returned an error
+    #[n(6)] pub errors: u64,
+    /// Total time spent handling messages, in microseconds
+    #[n(7)] pub total_processing_time_us: u64,
+    /// Longest time spent handling a single message, in microseconds
+    #[n(8)] pub max_processing_time_us: u64,
+    /// Distribution of the message processing times
+    #[n(9)] pub latency: Vec<LatencyBucketStatus>,
+}
+
+/// Number of messages handled by a worker within a given time
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct LatencyBucketStatus {
+    /// Maximum processing time of the messages of this bucket, in microseconds.
+    /// The last bucket has no upper bound
+    #[n(1)] pub upper_bound_us: Option<u64>,
+    #[n(2)] pub count: u64,
+}
+
+impl From<WorkerMetrics> for WorkerMetricsStatus {
+    fn from(metrics: WorkerMetrics) -> Self {
+        Self {
+            addr: metrics.address.address().to_string(),
+            worker_type: if metrics.is_processor {
+                "processor"
+            } else {
+                "worker"
+            }
+            .to_string(),
+            queue_depth: metrics.queue_depth,
+            messages_received: metrics.messages_received,
+            messages_handled: metrics.messages_handled,
+            errors: metrics.errors,
+            total_processing_time_us: metrics.total_processing_time.as_micros() as u64,
+            max_processing_time_us: metrics.max_processing_time.as_micros() as u64,
+            latency: metrics
+                .latency
+                .buckets
+                .iter()
+                .map(|b| LatencyBucketStatus {
+                    upper_bound_us: b.upper_bound.map(|d| d.as_micros() as u64),
+                    count: b.count,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl WorkerMetricsStatus {
+    /// Average time spent handling a message, in microseconds
+    pub fn mean_processing_time_us(&self) -> Option<u64> {
+        if self.messages_handled == 0 {
+            None
+        } else {
+            Some(self.total_processing_time_us / self.messages_handled)
+        }
+    }
+}
+
+impl Output for WorkerMetricsStatus {
+    fn item(&self) -> Result<String> {
+        let mut output = format!(
+            "{} {}",
+            capitalize(&self.worker_type),
+            self.addr
+                .to_string()
+                .color(OckamColor::PrimaryResource.color())
+        );
+        output.push_str(&format!("\nQueue depth: {}", self.queue_depth));
+        output.push_str(&format!(
+            "\nMessages: {} received, {} handled, {} error(s)",
+            self.messages_received, self.messages_handled, self.errors
+        ));
+        if let Some(mean) = self.mean_processing_time_us() {
+            output.push_str(&format!(
+                "\nProcessing time: {}µs on average, {}µs at most",
+                mean, self.max_processing_time_us
+            ));
+        }
+        Ok(output)
+    }
+}
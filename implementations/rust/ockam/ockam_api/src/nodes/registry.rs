@@ -1,13 +1,21 @@
 use crate::cli_state::random_name;
+use crate::kafka::KafkaPortalStats;
+use crate::nodes::connection::Connection;
 use crate::nodes::models::relay::RelayInfo;
+use crate::nodes::models::tun_portal::TunInterface;
 use crate::session::sessions::{ReplacerOutputKind, Session};
+use crate::tun::TunPortal;
 use crate::DefaultAddress;
 use ockam::identity::Identifier;
 use ockam::identity::{SecureChannel, SecureChannelListener};
+use ockam::tcp::{TcpEgressAllowList, TcpPortalStats};
+use ockam::transport::HostnamePort;
 use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::Arc;
 use ockam_core::{Address, Route};
 use ockam_multiaddr::MultiAddr;
 use ockam_node::compat::asynchronous::RwLock;
+use ockam_node::CancellationToken;
 use std::borrow::Borrow;
 use std::fmt::Display;
 use std::net::SocketAddr;
@@ -111,16 +119,85 @@ impl Display for KafkaServiceKind {
 #[derive(Clone)]
 pub(crate) struct KafkaServiceInfo {
     kind: KafkaServiceKind,
+    /// Traffic counters of an inlet
+    stats: Option<Arc<KafkaPortalStats>>,
 }
 
 impl KafkaServiceInfo {
     pub fn new(kind: KafkaServiceKind) -> Self {
-        Self { kind }
+        Self { kind, stats: None }
+    }
+
+    pub fn with_stats(mut self, stats: Arc<KafkaPortalStats>) -> Self {
+        self.stats = Some(stats);
+        self
     }
 
     pub fn kind(&self) -> &KafkaServiceKind {
         &self.kind
     }
+
+    pub fn stats(&self) -> Option<Arc<KafkaPortalStats>> {
+        self.stats.clone()
+    }
+}
+
+#[derive(Eq, PartialEq, Clone)]
+pub enum MqttServiceKind {
+    Inlet,
+    Outlet,
+}
+
+impl Display for MqttServiceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MqttServiceKind::Inlet => write!(f, "inlet"),
+            MqttServiceKind::Outlet => write!(f, "outlet"),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct MqttServiceInfo {
+    kind: MqttServiceKind,
+    /// Alias of the TCP inlet accepting the connections of the clients of an inlet
+    inlet_alias: Option<String>,
+}
+
+impl MqttServiceInfo {
+    pub fn inlet(inlet_alias: String) -> Self {
+        Self {
+            kind: MqttServiceKind::Inlet,
+            inlet_alias: Some(inlet_alias),
+        }
+    }
+
+    pub fn outlet() -> Self {
+        Self {
+            kind: MqttServiceKind::Outlet,
+            inlet_alias: None,
+        }
+    }
+
+    pub fn kind(&self) -> &MqttServiceKind {
+        &self.kind
+    }
+
+    pub fn inlet_alias(&self) -> Option<String> {
+        self.inlet_alias.clone()
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct PostgresOutletInfo {
+    /// Address of the TCP outlet to the database
+    pub(crate) database_outlet: Address,
+}
+
+#[derive(Clone)]
+pub(crate) struct HttpGatewayInfo {
+    /// Addresses of the TCP outlets to the targets of the routes
+    pub(crate) route_outlets: Vec<Address>,
 }
 
 #[derive(Clone)]
@@ -128,14 +205,22 @@ pub(crate) struct InletInfo {
     pub(crate) bind_addr: String,
     pub(crate) outlet_addr: MultiAddr,
     pub(crate) session: Session,
+    /// Traffic counters, kept when the inlet is re-created by its session
+    pub(crate) stats: Arc<TcpPortalStats>,
 }
 
 impl InletInfo {
-    pub(crate) fn new(bind_addr: &str, outlet_addr: MultiAddr, session: Session) -> Self {
+    pub(crate) fn new(
+        bind_addr: &str,
+        outlet_addr: MultiAddr,
+        session: Session,
+        stats: Arc<TcpPortalStats>,
+    ) -> Self {
         Self {
             bind_addr: bind_addr.to_owned(),
             outlet_addr,
             session,
+            stats,
         }
     }
 }
@@ -144,6 +229,8 @@ impl InletInfo {
 pub struct OutletInfo {
     pub(crate) socket_addr: SocketAddr,
     pub(crate) worker_addr: Address,
+    /// Destinations allowed for the outlet, shared with its workers
+    pub(crate) egress_allow_list: Option<Arc<std::sync::RwLock<TcpEgressAllowList>>>,
 }
 
 impl OutletInfo {
@@ -155,8 +242,48 @@ impl OutletInfo {
         Self {
             socket_addr: *socket_addr,
             worker_addr,
+            egress_allow_list: None,
         }
     }
+
+    pub(crate) fn with_egress_allow_list(
+        mut self,
+        egress_allow_list: Option<Arc<std::sync::RwLock<TcpEgressAllowList>>>,
+    ) -> Self {
+        self.egress_allow_list = egress_allow_list;
+        self
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct UdpInletInfo {
+    pub(crate) bind_addr: String,
+    pub(crate) outlet_addr: MultiAddr,
+    pub(crate) outlet_route: Route,
+    pub(crate) processor_addr: Address,
+    /// Connection to the outlet node, closed when the inlet is deleted
+    pub(crate) connection: Connection,
+}
+
+#[derive(Clone)]
+pub(crate) struct UdpOutletInfo {
+    pub(crate) to: HostnamePort,
+}
+
+#[derive(Clone)]
+pub(crate) struct TunInletInfo {
+    pub(crate) interface: TunInterface,
+    pub(crate) outlet_addr: MultiAddr,
+    pub(crate) outlet_route: Route,
+    pub(crate) portal: TunPortal,
+    /// Connection to the outlet node, closed when the inlet is deleted
+    pub(crate) connection: Connection,
+}
+
+#[derive(Clone)]
+pub(crate) struct TunOutletInfo {
+    pub(crate) interface: TunInterface,
+    pub(crate) portal: TunPortal,
 }
 
 #[derive(Clone)]
@@ -193,6 +320,14 @@ impl From<RegistryRelayInfo> for RelayInfo {
     }
 }
 
+#[derive(Clone)]
+pub(crate) struct NodeEventsSubscriptionInfo {
+    pub(crate) route: MultiAddr,
+    pub(crate) kinds: Vec<String>,
+    /// Cancelled to stop forwarding the events to the subscriber
+    pub(crate) cancellation: CancellationToken,
+}
+
 #[derive(Default)]
 pub(crate) struct Registry {
     pub(crate) secure_channels: SecureChannelRegistry,
@@ -200,10 +335,18 @@ pub(crate) struct Registry {
     pub(crate) uppercase_services: RegistryOf<Address, UppercaseServiceInfo>,
     pub(crate) echoer_services: RegistryOf<Address, EchoerServiceInfo>,
     pub(crate) kafka_services: RegistryOf<Address, KafkaServiceInfo>,
+    pub(crate) mqtt_services: RegistryOf<Address, MqttServiceInfo>,
+    pub(crate) postgres_outlets: RegistryOf<Address, PostgresOutletInfo>,
+    pub(crate) http_gateways: RegistryOf<Address, HttpGatewayInfo>,
     pub(crate) hop_services: RegistryOf<Address, HopServiceInfo>,
     pub(crate) relays: RegistryOf<String, RegistryRelayInfo>,
     pub(crate) inlets: RegistryOf<String, InletInfo>,
     pub(crate) outlets: RegistryOf<Address, OutletInfo>,
+    pub(crate) udp_inlets: RegistryOf<String, UdpInletInfo>,
+    pub(crate) udp_outlets: RegistryOf<Address, UdpOutletInfo>,
+    pub(crate) tun_inlets: RegistryOf<String, TunInletInfo>,
+    pub(crate) tun_outlets: RegistryOf<Address, TunOutletInfo>,
+    pub(crate) node_events_subscriptions: RegistryOf<String, NodeEventsSubscriptionInfo>,
 }
 
 pub(crate) struct RegistryOf<K, V> {
@@ -8,17 +8,24 @@ use ockam_core::api::{RequestHeader, Response};
 pub(crate) mod background_node_client;
 pub mod default_address;
 mod flow_controls;
+mod heartbeat;
+pub mod http_gateway_services;
 pub(crate) mod in_memory_node;
 pub mod kafka_services;
 pub mod messages;
+pub mod mqtt_services;
+mod node_events;
 mod node_services;
 pub(crate) mod policy;
+pub mod postgres_services;
 mod projects;
 pub mod relay;
 mod secure_channel;
 pub mod tcp_inlets;
 pub mod tcp_outlets;
 mod transport;
+pub mod tun_portals;
+pub mod udp_portals;
 pub mod workers;
 
 mod http;
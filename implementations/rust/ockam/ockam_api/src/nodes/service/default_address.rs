@@ -5,6 +5,8 @@ pub struct DefaultAddress;
 
 impl DefaultAddress {
     pub const OUTLET_SERVICE: &'static str = "outlet";
+    pub const UDP_OUTLET_SERVICE: &'static str = "udp_outlet";
+    pub const TUN_OUTLET_SERVICE: &'static str = "tun_outlet";
     pub const RELAY_SERVICE: &'static str = "forwarding_service";
     pub const STATIC_RELAY_SERVICE: &'static str = "static_forwarding_service";
     pub const UPPERCASE_SERVICE: &'static str = "uppercase";
@@ -21,6 +23,10 @@ impl DefaultAddress {
     pub const OKTA_IDENTITY_PROVIDER: &'static str = "okta";
     pub const KAFKA_OUTLET: &'static str = "kafka_outlet";
     pub const KAFKA_INLET: &'static str = "kafka_inlet";
+    pub const MQTT_OUTLET: &'static str = "mqtt_outlet";
+    pub const MQTT_INLET: &'static str = "mqtt_inlet";
+    pub const POSTGRES_OUTLET: &'static str = "postgres_outlet";
+    pub const HTTP_GATEWAY: &'static str = "http_gateway";
 
     pub fn get_rendezvous_server_address() -> Address {
         let server_address =
@@ -29,7 +35,9 @@ impl DefaultAddress {
     }
 
     pub fn is_valid(name: &str) -> bool {
-        matches!(name, |Self::OUTLET_SERVICE| Self::RELAY_SERVICE
+        matches!(name, |Self::OUTLET_SERVICE| Self::UDP_OUTLET_SERVICE
+            | Self::TUN_OUTLET_SERVICE
+            | Self::RELAY_SERVICE
             | Self::STATIC_RELAY_SERVICE
             | Self::UPPERCASE_SERVICE
             | Self::ECHO_SERVICE
@@ -42,12 +50,18 @@ impl DefaultAddress {
             | Self::ENROLLMENT_TOKEN_ACCEPTOR
             | Self::OKTA_IDENTITY_PROVIDER
             | Self::KAFKA_INLET
-            | Self::KAFKA_OUTLET)
+            | Self::KAFKA_OUTLET
+            | Self::MQTT_INLET
+            | Self::MQTT_OUTLET
+            | Self::POSTGRES_OUTLET
+            | Self::HTTP_GATEWAY)
     }
 
     pub fn iter() -> impl Iterator<Item = &'static str> {
         [
             Self::OUTLET_SERVICE,
+            Self::UDP_OUTLET_SERVICE,
+            Self::TUN_OUTLET_SERVICE,
             Self::RELAY_SERVICE,
             Self::STATIC_RELAY_SERVICE,
             Self::UPPERCASE_SERVICE,
@@ -62,6 +76,10 @@ impl DefaultAddress {
             Self::OKTA_IDENTITY_PROVIDER,
             Self::KAFKA_INLET,
             Self::KAFKA_OUTLET,
+            Self::MQTT_INLET,
+            Self::MQTT_OUTLET,
+            Self::POSTGRES_OUTLET,
+            Self::HTTP_GATEWAY,
         ]
         .iter()
         .copied()
@@ -76,6 +94,8 @@ mod test {
     fn test_default_address_is_valid() {
         assert!(!DefaultAddress::is_valid("foo"));
         assert!(DefaultAddress::is_valid(DefaultAddress::OUTLET_SERVICE));
+        assert!(DefaultAddress::is_valid(DefaultAddress::UDP_OUTLET_SERVICE));
+        assert!(DefaultAddress::is_valid(DefaultAddress::TUN_OUTLET_SERVICE));
         assert!(DefaultAddress::is_valid(DefaultAddress::RELAY_SERVICE));
         assert!(DefaultAddress::is_valid(
             DefaultAddress::STATIC_RELAY_SERVICE
@@ -101,5 +121,9 @@ mod test {
         ));
         assert!(DefaultAddress::is_valid(DefaultAddress::KAFKA_INLET));
         assert!(DefaultAddress::is_valid(DefaultAddress::KAFKA_OUTLET));
+        assert!(DefaultAddress::is_valid(DefaultAddress::MQTT_INLET));
+        assert!(DefaultAddress::is_valid(DefaultAddress::MQTT_OUTLET));
+        assert!(DefaultAddress::is_valid(DefaultAddress::POSTGRES_OUTLET));
+        assert!(DefaultAddress::is_valid(DefaultAddress::HTTP_GATEWAY));
     }
 }
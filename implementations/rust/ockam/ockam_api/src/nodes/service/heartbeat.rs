@@ -0,0 +1,92 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use ockam::identity::utils::now;
+use ockam_core::{async_trait, Address, DenyAll, Processor, Result};
+use ockam_node::Context;
+
+use crate::cli_state::{CliState, NodeHeartbeat, NODE_HEARTBEAT_INTERVAL};
+use crate::nodes::registry::Registry;
+
+/// This processor periodically stores a heartbeat for the node in the database,
+/// with some health signals, so that the node liveness can be checked
+/// without relying only on its process id
+pub(crate) struct NodeHeartbeatProcessor {
+    cli_state: CliState,
+    node_name: String,
+    registry: Arc<Registry>,
+    interval: Duration,
+}
+
+impl NodeHeartbeatProcessor {
+    /// Start the heartbeat processor of a node
+    pub(crate) async fn start(
+        ctx: &Context,
+        cli_state: CliState,
+        node_name: String,
+        registry: Arc<Registry>,
+    ) -> Result<()> {
+        let processor = Self {
+            cli_state,
+            node_name,
+            registry,
+            interval: NODE_HEARTBEAT_INTERVAL,
+        };
+        ctx.start_processor_with_access_control(
+            Address::random_tagged("NodeHeartbeatProcessor"),
+            processor,
+            DenyAll,
+            DenyAll,
+        )
+        .await
+    }
+
+    async fn heartbeat(&self, ctx: &Context) -> Result<NodeHeartbeat> {
+        Ok(NodeHeartbeat {
+            last_seen_at: *now()?,
+            workers_count: ctx.list_workers().await?.len() as u32,
+            secure_channels: self.registry.secure_channels.list().await.len() as u32,
+            tcp_inlets: self.registry.inlets.keys().await.len() as u32,
+            tcp_outlets: self.registry.outlets.keys().await.len() as u32,
+        })
+    }
+}
+
+#[async_trait]
+impl Processor for NodeHeartbeatProcessor {
+    type Context = Context;
+
+    async fn shutdown(&mut self, _ctx: &mut Self::Context) -> Result<()> {
+        if let Err(e) = self.cli_state.delete_node_heartbeat(&self.node_name).await {
+            warn!(
+                "Failed to delete the heartbeat of the node {}: {e}",
+                self.node_name
+            );
+        }
+        Ok(())
+    }
+
+    async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
+        // a failed heartbeat is only logged, the node is reported as stale if it keeps failing
+        match self.heartbeat(ctx).await {
+            Ok(heartbeat) => {
+                if let Err(e) = self
+                    .cli_state
+                    .set_node_heartbeat(&self.node_name, &heartbeat)
+                    .await
+                {
+                    warn!(
+                        "Failed to store the heartbeat of the node {}: {e}",
+                        self.node_name
+                    );
+                }
+            }
+            Err(e) => warn!(
+                "Failed to collect the heartbeat of the node {}: {e}",
+                self.node_name
+            ),
+        }
+        tokio::time::sleep(self.interval).await;
+        Ok(true)
+    }
+}
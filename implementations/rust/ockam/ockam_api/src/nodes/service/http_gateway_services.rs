@@ -0,0 +1,199 @@
+use ockam::transport::HostnamePort;
+use ockam::{Address, Context, Result};
+use ockam_abac::PolicyExpression;
+use ockam_abac::{Action, Resource, ResourceType};
+use ockam_core::api::{Error, Response};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use super::NodeManagerWorker;
+use crate::error::ApiError;
+use crate::http_gateway::{route_outlet_address, GatewayRoute, HttpGatewayListener, HttpRoute};
+use crate::nodes::models::portal::OutletAccessControl;
+use crate::nodes::models::services::{
+    DeleteServiceRequest, StartHttpGatewayRequest, StartServiceRequest,
+};
+use crate::nodes::registry::HttpGatewayInfo;
+use crate::nodes::service::default_address::DefaultAddress;
+use crate::nodes::service::tcp_outlets::OutletServiceOptions;
+use crate::nodes::InMemoryNode;
+
+impl NodeManagerWorker {
+    pub(super) async fn start_http_gateway_service(
+        &self,
+        context: &Context,
+        body: StartServiceRequest<StartHttpGatewayRequest>,
+    ) -> Result<Response<()>, Response<Error>> {
+        let request = body.request();
+        match self
+            .node_manager
+            .start_http_gateway_service(
+                context,
+                Address::from_string(body.address()),
+                request.routes(),
+                request.policy_expression(),
+            )
+            .await
+        {
+            Ok(_) => Ok(Response::ok().body(())),
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
+
+    pub(super) async fn delete_http_gateway_service(
+        &self,
+        ctx: &Context,
+        delete_service_request: DeleteServiceRequest,
+    ) -> Result<Response<()>, Response<Error>> {
+        let address = delete_service_request.address();
+        match self
+            .node_manager
+            .delete_http_gateway_service(ctx, address.clone())
+            .await
+        {
+            Ok(true) => Ok(Response::ok()),
+            Ok(false) => Err(Response::not_found_no_request(&format!(
+                "HTTP gateway at address '{address}' not found"
+            ))),
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
+}
+
+impl InMemoryNode {
+    /// Start an HTTP gateway: each request is sent to the target of its route, when the
+    /// identity of the client is authorized by the policy of this route.
+    /// The policy expression of the gateway is checked for each connection
+    pub async fn start_http_gateway_service(
+        &self,
+        context: &Context,
+        service_address: Address,
+        routes: Vec<HttpRoute>,
+        policy_expression: Option<PolicyExpression>,
+    ) -> Result<()> {
+        if self
+            .registry
+            .http_gateways
+            .contains_key(&service_address)
+            .await
+        {
+            return Err(ApiError::core(format!(
+                "HTTP gateway already exists at {service_address}"
+            )));
+        }
+        if routes.is_empty() {
+            return Err(ApiError::core("An HTTP gateway needs at least one route"));
+        }
+        let mut targets = vec![];
+        for route in &routes {
+            route.validate()?;
+            targets.push(HostnamePort::from_str(route.target())?);
+        }
+
+        let default_secure_channel_listener_flow_control_id = context
+            .flow_controls()
+            .get_flow_control_with_spawner(&DefaultAddress::SECURE_CHANNEL_LISTENER.into())
+            .ok_or_else(|| {
+                ApiError::core("Unable to get flow control for secure channel listener")
+            })?;
+
+        let policy_access_control = self
+            .policy_access_control(
+                self.project_authority().clone(),
+                Resource::new(service_address.to_string(), ResourceType::TcpOutlet),
+                Action::HandleMessage,
+                policy_expression,
+            )
+            .await?;
+
+        // each target is only reachable through the workers of the gateway, and with the
+        // policy of its route, which is also evaluated by the gateway for each request
+        let mut gateway_routes = vec![];
+        for (index, (route, target)) in routes.into_iter().zip(targets).enumerate() {
+            let outlet_address =
+                Address::from_string(route_outlet_address(service_address.address(), index));
+            let result = self
+                .create_outlet(
+                    context,
+                    target,
+                    false,
+                    Some(outlet_address.clone()),
+                    false,
+                    OutletAccessControl::WithPolicyExpression(route.policy_expression()),
+                    OutletServiceOptions::default(),
+                )
+                .await;
+            if let Err(e) = result {
+                self.delete_route_outlets(&gateway_routes).await;
+                return Err(ApiError::core(e.to_string()));
+            }
+
+            let access_control = self
+                .policy_access_control(
+                    self.project_authority().clone(),
+                    Resource::new(outlet_address.to_string(), ResourceType::TcpOutlet),
+                    Action::HandleMessage,
+                    route.policy_expression(),
+                )
+                .await?;
+            gateway_routes.push(GatewayRoute {
+                route,
+                outlet_address,
+                access_control,
+            });
+        }
+        let route_outlets = gateway_routes
+            .iter()
+            .map(|route| route.outlet_address.clone())
+            .collect();
+
+        let result = HttpGatewayListener::create(
+            context,
+            service_address.clone(),
+            gateway_routes.clone(),
+            default_secure_channel_listener_flow_control_id,
+            Arc::new(policy_access_control.create_incoming()),
+            Arc::new(policy_access_control.create_outgoing(context).await?),
+        )
+        .await;
+        if let Err(e) = result {
+            self.delete_route_outlets(&gateway_routes).await;
+            return Err(e);
+        }
+
+        self.registry
+            .http_gateways
+            .insert(service_address, HttpGatewayInfo { route_outlets })
+            .await;
+
+        Ok(())
+    }
+
+    /// Delete an HTTP gateway with the TCP outlets of its routes.
+    /// Return false if there is no HTTP gateway at this address
+    pub async fn delete_http_gateway_service(
+        &self,
+        ctx: &Context,
+        address: Address,
+    ) -> Result<bool> {
+        debug!(address = %address, "Deleting HTTP gateway");
+        let info = match self.registry.http_gateways.get(&address).await {
+            Some(info) => info,
+            None => return Ok(false),
+        };
+        for outlet_address in &info.route_outlets {
+            self.delete_outlet(outlet_address).await?;
+        }
+        ctx.stop_worker(address.clone()).await?;
+        self.registry.http_gateways.remove(&address).await;
+        Ok(true)
+    }
+
+    async fn delete_route_outlets(&self, routes: &[GatewayRoute]) {
+        for route in routes {
+            if let Err(e) = self.delete_outlet(&route.outlet_address).await {
+                warn!("Failed to delete the outlet {}: {e}", route.outlet_address);
+            }
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use ockam::tcp::TcpEgressAllowList;
 use ockam::transport::HostnamePort;
 use ockam::{Address, Context, Result};
 use ockam_abac::PolicyExpression;
@@ -6,26 +7,32 @@ use ockam_core::api::{Error, Response};
 use ockam_core::compat::net::SocketAddr;
 use ockam_core::compat::rand::random_string;
 use ockam_core::route;
-use ockam_multiaddr::proto::Project;
+use ockam_multiaddr::proto::{Project, Service};
 use ockam_multiaddr::MultiAddr;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use super::NodeManagerWorker;
 use crate::error::ApiError;
 use crate::kafka::secure_channel_map::controller::KafkaSecureChannelControllerImpl;
-use crate::kafka::OutletManagerService;
 use crate::kafka::{
     kafka_policy_expression, ConsumerPublishing, ConsumerResolution, KafkaInletController,
-    KafkaPortalListener, KAFKA_OUTLET_BOOTSTRAP_ADDRESS, KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
+    KafkaPortalListener, KafkaPortalStats, RecordEncryption, TopicPolicies,
+    KAFKA_OUTLET_BOOTSTRAP_ADDRESS, KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
+    KAFKA_OUTLET_RECORD_KEYS_ADDRESS, KAFKA_OUTLET_SCHEMA_REGISTRY_ADDRESS,
 };
+use crate::kafka::{OutletManagerService, RecordKeysService};
 use crate::nodes::models::portal::OutletAccessControl;
 use crate::nodes::models::services::{
-    DeleteServiceRequest, StartKafkaInletRequest, StartKafkaOutletRequest, StartServiceRequest,
+    DeleteServiceRequest, KafkaInletStats, StartKafkaInletRequest, StartKafkaOutletRequest,
+    StartServiceRequest,
 };
 use crate::nodes::registry::{KafkaServiceInfo, KafkaServiceKind};
 use crate::nodes::service::default_address::DefaultAddress;
-use crate::nodes::InMemoryNode;
+use crate::nodes::service::tcp_inlets::InletServiceOptions;
+use crate::nodes::service::tcp_outlets::OutletServiceOptions;
+use crate::nodes::{InMemoryNode, NodeManager};
 use crate::port_range::PortRange;
 
 impl NodeManagerWorker {
@@ -49,6 +56,10 @@ impl NodeManagerWorker {
                 request.inlet_policy_expression(),
                 request.consumer_policy_expression(),
                 request.producer_policy_expression(),
+                request.schema_registry_bind_address(),
+                request.topic_policies(),
+                request.key_rotation_interval(),
+                request.record_encryption(),
             )
             .await
         {
@@ -71,6 +82,10 @@ impl NodeManagerWorker {
                 request.bootstrap_server_addr(),
                 request.tls(),
                 request.policy_expression(),
+                request.egress_allow_list(),
+                request.schema_registry_addr(),
+                request.schema_registry_tls(),
+                request.deterministic_record_keys(),
             )
             .await
         {
@@ -104,6 +119,49 @@ impl NodeManagerWorker {
             Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
         }
     }
+
+    pub(super) async fn get_kafka_inlet_stats(
+        &self,
+        address: &str,
+    ) -> Result<Response<KafkaInletStats>, Response<Error>> {
+        match self
+            .node_manager
+            .get_kafka_inlet_stats(&Address::from_string(address))
+            .await
+        {
+            Some(stats) => Ok(Response::ok().body(stats)),
+            None => Err(Response::not_found_no_request(&format!(
+                "Kafka inlet at address '{address}' not found"
+            ))),
+        }
+    }
+}
+
+impl NodeManager {
+    /// Return the traffic of each topic used by the clients of a Kafka inlet
+    pub async fn get_kafka_inlet_stats(&self, address: &Address) -> Option<KafkaInletStats> {
+        let stats = self.registry.kafka_services.get(address).await?.stats()?;
+        Some(KafkaInletStats {
+            address: address.address().to_string(),
+            topics: stats.topics(),
+        })
+    }
+
+    /// Return the traffic of all the Kafka inlets of the node
+    pub async fn list_kafka_inlet_stats(&self) -> Vec<KafkaInletStats> {
+        self.registry
+            .kafka_services
+            .entries()
+            .await
+            .into_iter()
+            .filter_map(|(address, info)| {
+                info.stats().map(|stats| KafkaInletStats {
+                    address: address.address().to_string(),
+                    topics: stats.topics(),
+                })
+            })
+            .collect()
+    }
 }
 
 impl InMemoryNode {
@@ -121,7 +179,14 @@ impl InMemoryNode {
         inlet_policy_expression: Option<PolicyExpression>,
         consumer_policy_expression: Option<PolicyExpression>,
         producer_policy_expression: Option<PolicyExpression>,
+        schema_registry_bind_address: Option<SocketAddr>,
+        topic_policies: TopicPolicies,
+        key_rotation_interval: Option<Duration>,
+        record_encryption: RecordEncryption,
     ) -> Result<()> {
+        topic_policies.validate()?;
+        record_encryption.validate()?;
+
         let consumer_policy_access_control = self
             .policy_access_control(
                 self.project_authority().clone(),
@@ -146,6 +211,15 @@ impl InMemoryNode {
             )
             .await?;
 
+        // the keys of each topic are given by the Kafka outlet
+        let record_keys_service = if record_encryption.deterministic_keys() {
+            let mut record_keys_service = outlet_node_multiaddr.clone();
+            record_keys_service.push_back(Service::new(KAFKA_OUTLET_RECORD_KEYS_ADDRESS))?;
+            Some(record_keys_service)
+        } else {
+            None
+        };
+
         let secure_channel_controller = KafkaSecureChannelControllerImpl::new(
             self.node_manager.clone(),
             self.secure_channels.clone(),
@@ -153,6 +227,8 @@ impl InMemoryNode {
             consumer_publishing,
             consumer_policy_access_control,
             producer_policy_access_control,
+            key_rotation_interval,
+            record_keys_service,
         );
 
         self.node_manager
@@ -203,7 +279,7 @@ impl InMemoryNode {
                 KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
                 KAFKA_OUTLET_BOOTSTRAP_ADDRESS
             ],
-            outlet_node_multiaddr,
+            outlet_node_multiaddr.clone(),
             inlet_alias,
             inlet_policy_expression.clone(),
             None,
@@ -212,9 +288,32 @@ impl InMemoryNode {
             None,
             false,
             false,
+            InletServiceOptions::default(),
         )
         .await?;
 
+        // the Schema Registry is reached with plain HTTP requests, which don't go
+        // through the interceptor
+        if let Some(schema_registry_bind_address) = schema_registry_bind_address {
+            self.create_inlet(
+                context,
+                schema_registry_bind_address.to_string(),
+                route![],
+                route![KAFKA_OUTLET_SCHEMA_REGISTRY_ADDRESS],
+                outlet_node_multiaddr,
+                format!("kafka-schema-registry-{}", random_string()),
+                inlet_policy_expression.clone(),
+                None,
+                None,
+                false,
+                None,
+                false,
+                false,
+                InletServiceOptions::default(),
+            )
+            .await?;
+        }
+
         let policy_access_control = self
             .policy_access_control(
                 self.project_authority().clone(),
@@ -227,9 +326,22 @@ impl InMemoryNode {
             )
             .await?;
 
+        self.cli_state
+            .store_kafka_topic_policies(
+                &self.node_name,
+                &local_interceptor_address,
+                &topic_policies,
+            )
+            .await?;
+
+        let stats = Arc::new(KafkaPortalStats::default());
         KafkaPortalListener::create(
             context,
             encrypt_content,
+            schema_registry_bind_address.is_some(),
+            topic_policies,
+            record_encryption,
+            stats.clone(),
             inlet_controller,
             secure_channel_controller,
             local_interceptor_address.clone(),
@@ -242,13 +354,14 @@ impl InMemoryNode {
             .kafka_services
             .insert(
                 local_interceptor_address,
-                KafkaServiceInfo::new(KafkaServiceKind::Inlet),
+                KafkaServiceInfo::new(KafkaServiceKind::Inlet).with_stats(stats),
             )
             .await;
 
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn start_kafka_outlet_service(
         &self,
         context: &Context,
@@ -256,6 +369,10 @@ impl InMemoryNode {
         bootstrap_server_addr: String,
         tls: bool,
         outlet_policy_expression: Option<PolicyExpression>,
+        egress_allow_list: Option<TcpEgressAllowList>,
+        schema_registry_addr: Option<String>,
+        schema_registry_tls: bool,
+        deterministic_record_keys: bool,
     ) -> Result<()> {
         let default_secure_channel_listener_flow_control_id = context
             .flow_controls()
@@ -275,11 +392,12 @@ impl InMemoryNode {
 
         OutletManagerService::create(
             context,
-            default_secure_channel_listener_flow_control_id,
+            default_secure_channel_listener_flow_control_id.clone(),
             outlet_policy_expression.clone(),
             Arc::new(policy_access_control.create_incoming()),
             Arc::new(policy_access_control.create_outgoing(context).await?),
             tls,
+            egress_allow_list,
         )
         .await?;
 
@@ -290,13 +408,43 @@ impl InMemoryNode {
                 tls,
                 Some(KAFKA_OUTLET_BOOTSTRAP_ADDRESS.into()),
                 false,
-                OutletAccessControl::WithPolicyExpression(outlet_policy_expression),
+                OutletAccessControl::WithPolicyExpression(outlet_policy_expression.clone()),
+                OutletServiceOptions::default(),
             )
             .await
         {
             return Err(ApiError::core(e.to_string()));
         };
 
+        // the inlets reach the Schema Registry directly through their secure channel
+        if let Some(schema_registry_addr) = schema_registry_addr {
+            self.create_outlet(
+                context,
+                HostnamePort::from_str(&schema_registry_addr)?,
+                schema_registry_tls,
+                Some(KAFKA_OUTLET_SCHEMA_REGISTRY_ADDRESS.into()),
+                true,
+                OutletAccessControl::WithPolicyExpression(outlet_policy_expression),
+                OutletServiceOptions::default(),
+            )
+            .await
+            .map_err(|e| ApiError::core(e.to_string()))?;
+        }
+
+        if deterministic_record_keys {
+            let secret = self
+                .cli_state
+                .get_or_create_kafka_record_keys_secret(&self.node_name)
+                .await?;
+            RecordKeysService::create(
+                context,
+                default_secure_channel_listener_flow_control_id,
+                secret,
+                Arc::new(policy_access_control.create_incoming()),
+            )
+            .await?;
+        }
+
         {
             self.registry
                 .kafka_services
@@ -326,10 +474,21 @@ impl InMemoryNode {
                     match e.kind() {
                         KafkaServiceKind::Inlet => {
                             ctx.stop_worker(address.clone()).await?;
+                            self.cli_state
+                                .delete_kafka_topic_policies(&self.node_name, &address)
+                                .await?;
                         }
                         KafkaServiceKind::Outlet => {
                             ctx.stop_worker(KAFKA_OUTLET_INTERCEPTOR_ADDRESS).await?;
                             ctx.stop_worker(KAFKA_OUTLET_BOOTSTRAP_ADDRESS).await?;
+                            self.delete_outlet(&KAFKA_OUTLET_SCHEMA_REGISTRY_ADDRESS.into())
+                                .await?;
+                            // the record keys service only runs for deterministic keys
+                            let record_keys =
+                                Address::from_string(KAFKA_OUTLET_RECORD_KEYS_ADDRESS);
+                            if ctx.list_workers().await?.contains(&record_keys) {
+                                ctx.stop_worker(record_keys).await?;
+                            }
                         }
                     }
                     self.registry.kafka_services.remove(&address).await;
@@ -1,5 +1,6 @@
 use crate::cloud::project::Project;
 use crate::cloud::{AuthorityNodeClient, CredentialsEnabled, ProjectNodeClient};
+use crate::discovery::{MdnsAdvertiser, NodeAdvertisement};
 use crate::nodes::connection::{
     Connection, ConnectionBuilder, PlainTcpInstantiator, ProjectInstantiator,
     SecureChannelInstantiator,
@@ -7,6 +8,7 @@ use crate::nodes::connection::{
 use crate::nodes::models::portal::OutletStatus;
 use crate::nodes::models::transport::{TransportMode, TransportType};
 use crate::nodes::registry::Registry;
+use crate::nodes::service::heartbeat::NodeHeartbeatProcessor;
 use crate::nodes::service::http::HttpServer;
 use crate::nodes::service::{
     CredentialRetrieverCreators, NodeManagerCredentialRetrieverOptions, NodeManagerTrustOptions,
@@ -23,7 +25,8 @@ use ockam::identity::{
 };
 use ockam::tcp::TcpTransport;
 use ockam::udp::{
-    UdpPunctureNegotiationListener, UdpPunctureNegotiationListenerOptions, UdpTransport,
+    RendezvousService, UdpBindArguments, UdpBindOptions, UdpPunctureNegotiationListener,
+    UdpPunctureNegotiationListenerOptions, UdpTransport, UDP,
 };
 use ockam::{RelayService, RelayServiceOptions};
 use ockam_abac::expr::str;
@@ -32,14 +35,16 @@ use ockam_abac::{
 };
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{
-    route, AllowAll, AsyncTryClone, CachedIncomingAccessControl, CachedOutgoingAccessControl,
-    IncomingAccessControl, OutgoingAccessControl,
+    route, Address, AllowAll, AsyncTryClone, CachedIncomingAccessControl,
+    CachedOutgoingAccessControl, IncomingAccessControl, OutgoingAccessControl, Route,
 };
 use ockam_multiaddr::MultiAddr;
 use ockam_node::Context;
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use sysinfo::System;
 
 /// Node manager provides high-level operations to
 ///  - send messages
@@ -53,12 +58,18 @@ pub struct NodeManager {
     pub(super) api_transport_flow_control_id: FlowControlId,
     pub(crate) tcp_transport: TcpTransport,
     pub(crate) udp_transport: Option<UdpTransport>,
+    /// UDP address of the rendezvous service used to open UDP punctures with other nodes
+    rendezvous_server_address: Address,
     pub(crate) secure_channels: Arc<SecureChannels>,
     pub(crate) api_sc_listener: Option<SecureChannelListener>,
     pub(crate) credential_retriever_creators: CredentialRetrieverCreators,
     pub(super) project_authority: Option<Identifier>,
     pub(crate) registry: Arc<Registry>,
     pub(crate) medic_handle: MedicHandle,
+    /// Set once the node process has started all the services it was configured with
+    services_initialized: AtomicBool,
+    /// System information, kept between two stats requests to compute the CPU usage of the node
+    pub(super) system: Mutex<System>,
 }
 
 impl NodeManager {
@@ -152,12 +163,18 @@ impl NodeManager {
             api_transport_flow_control_id: transport_options.api_transport_flow_control_id,
             tcp_transport: transport_options.tcp_transport,
             udp_transport: transport_options.udp_transport.clone(),
+            rendezvous_server_address: transport_options
+                .rendezvous_server_address
+                .map(|address| (UDP, address).into())
+                .unwrap_or_else(DefaultAddress::get_rendezvous_server_address),
             secure_channels,
             api_sc_listener: None,
             credential_retriever_creators,
             project_authority: trust_options.project_authority,
             registry,
             medic_handle,
+            services_initialized: AtomicBool::new(false),
+            system: Mutex::new(System::new()),
         };
 
         debug!("initializing services");
@@ -166,6 +183,17 @@ impl NodeManager {
 
         let s = Arc::new(s);
 
+        if general_options.persistent {
+            debug!("start the heartbeat");
+            NodeHeartbeatProcessor::start(
+                ctx,
+                s.cli_state.clone(),
+                s.node_name.clone(),
+                s.registry.clone(),
+            )
+            .await?;
+        }
+
         if let Some(http_server_port) = general_options.http_server_port {
             debug!("start the http server");
             HttpServer::start(s.clone(), http_server_port)
@@ -174,15 +202,11 @@ impl NodeManager {
         }
 
         if let Some(udp_transport) = transport_options.udp_transport.as_ref() {
-            let rendezvous_route = route![
-                DefaultAddress::get_rendezvous_server_address(),
-                DefaultAddress::RENDEZVOUS_SERVICE
-            ];
             UdpPunctureNegotiationListener::create(
                 ctx,
                 DefaultAddress::UDP_PUNCTURE_NEGOTIATION_LISTENER,
                 udp_transport,
-                rendezvous_route,
+                s.rendezvous_route(),
                 UdpPunctureNegotiationListenerOptions::new(), // FIXME
             )
             .await?;
@@ -193,6 +217,35 @@ impl NodeManager {
                     api_sc_listener.flow_control_id(),
                 );
             }
+
+            // A publicly reachable node can pair other nodes which are behind a NAT
+            if let Some(bind_address) = &transport_options.rendezvous_service_bind_address {
+                debug!("start the rendezvous service on {bind_address}");
+                RendezvousService::start(ctx, DefaultAddress::RENDEZVOUS_SERVICE).await?;
+                let bind = udp_transport
+                    .bind(
+                        UdpBindArguments::new().with_bind_address(bind_address)?,
+                        UdpBindOptions::new(),
+                    )
+                    .await?;
+                ctx.flow_controls()
+                    .add_consumer(DefaultAddress::RENDEZVOUS_SERVICE, bind.flow_control_id());
+                info!(
+                    "started the rendezvous service on {} for the node: {}",
+                    bind.bind_address(),
+                    s.node_name
+                );
+            }
+        }
+
+        if let Some(listener_address) = transport_options.discovery_listener_address {
+            debug!("start the mDNS advertiser on {listener_address}");
+            let advertisement = NodeAdvertisement::new(
+                s.node_name.clone(),
+                s.node_identifier.clone(),
+                listener_address,
+            );
+            MdnsAdvertiser::start(ctx, advertisement).await?;
         }
 
         info!("created a node manager for the node: {}", s.node_name);
@@ -365,6 +418,25 @@ impl NodeManager {
         self.node_name.clone()
     }
 
+    /// Route to the UDP rendezvous service used to open UDP punctures with other nodes
+    pub(super) fn rendezvous_route(&self) -> Route {
+        route![
+            self.rendezvous_server_address.clone(),
+            DefaultAddress::RENDEZVOUS_SERVICE
+        ]
+    }
+
+    /// Mark the services started by the node process as initialized.
+    /// The node then reports itself as ready in its status
+    pub fn set_services_initialized(&self) {
+        self.services_initialized.store(true, Ordering::Release);
+    }
+
+    /// Return true if the node process has started all the services it was configured with
+    pub fn are_services_initialized(&self) -> bool {
+        self.services_initialized.load(Ordering::Acquire)
+    }
+
     pub fn tcp_transport(&self) -> &TcpTransport {
         &self.tcp_transport
     }
@@ -580,6 +652,9 @@ pub struct NodeManagerTransportOptions {
     api_transport_flow_control_id: FlowControlId,
     tcp_transport: TcpTransport,
     udp_transport: Option<UdpTransport>,
+    rendezvous_service_bind_address: Option<String>,
+    rendezvous_server_address: Option<String>,
+    discovery_listener_address: Option<SocketAddr>,
 }
 
 impl NodeManagerTransportOptions {
@@ -592,6 +667,29 @@ impl NodeManagerTransportOptions {
             api_transport_flow_control_id,
             tcp_transport,
             udp_transport,
+            rendezvous_service_bind_address: None,
+            rendezvous_server_address: None,
+            discovery_listener_address: None,
         }
     }
+
+    /// Start a UDP rendezvous service listening on the given address.
+    /// This requires the UDP transport to be enabled
+    pub fn with_rendezvous_service(mut self, bind_address: Option<String>) -> Self {
+        self.rendezvous_service_bind_address = bind_address;
+        self
+    }
+
+    /// Open the UDP punctures with the rendezvous service at the given address.
+    /// Without an address, the `OCKAM_RENDEZVOUS_SERVER` environment variable is used
+    pub fn with_rendezvous_server(mut self, server_address: Option<String>) -> Self {
+        self.rendezvous_server_address = server_address;
+        self
+    }
+
+    /// Advertise the given TCP listener address of the node on the local network with mDNS
+    pub fn with_discovery(mut self, listener_address: Option<SocketAddr>) -> Self {
+        self.discovery_listener_address = listener_address;
+        self
+    }
 }
@@ -1,13 +1,14 @@
 use miette::IntoDiagnostic;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tracing::trace;
 
 use minicbor::{Decode, Encode};
 
+use ockam::identity::Identifier;
 use ockam_core::api::{Error, Request, Response};
-use ockam_core::{self, async_trait, AsyncTryClone, Result};
+use ockam_core::{self, async_trait, AsyncTryClone, Result, Route};
 use ockam_multiaddr::MultiAddr;
 use ockam_node::{Context, MessageSendReceiveOptions};
 
@@ -25,6 +26,15 @@ pub trait Messages {
         message: Vec<u8>,
         timeout: Option<Duration>,
     ) -> miette::Result<Vec<u8>>;
+
+    /// Send a message and return its reply, with a report describing how the message was delivered
+    async fn send_message_with_report(
+        &self,
+        ctx: &Context,
+        to: &MultiAddr,
+        message: Vec<u8>,
+        timeout: Option<Duration>,
+    ) -> miette::Result<MessageReport>;
 }
 
 #[async_trait]
@@ -37,6 +47,20 @@ impl Messages for NodeManager {
         message: Vec<u8>,
         timeout: Option<Duration>,
     ) -> miette::Result<Vec<u8>> {
+        Ok(self
+            .send_message_with_report(ctx, to, message, timeout)
+            .await?
+            .reply)
+    }
+
+    #[instrument(skip_all)]
+    async fn send_message_with_report(
+        &self,
+        ctx: &Context,
+        to: &MultiAddr,
+        message: Vec<u8>,
+        timeout: Option<Duration>,
+    ) -> miette::Result<MessageReport> {
         let msg_length = message.len();
         let connection_ctx = Arc::new(ctx.async_try_clone().await.into_diagnostic()?);
         let connection = self
@@ -51,12 +75,32 @@ impl Messages for NodeManager {
         } else {
             MessageSendReceiveOptions::new()
         };
-        Ok(ctx
-            .send_and_receive_extended::<Vec<u8>>(route, message, options)
+        let peer_identifier = self.peer_identifier(&route);
+        let started_at = Instant::now();
+        let reply = ctx
+            .send_and_receive_extended::<Vec<u8>>(route.clone(), message, options)
             .await
             .into_diagnostic()?
             .into_body()
-            .into_diagnostic()?)
+            .into_diagnostic()?;
+        Ok(MessageReport {
+            reply,
+            route: route.to_string(),
+            peer_identifier,
+            round_trip_time: started_at.elapsed(),
+        })
+    }
+}
+
+impl NodeManager {
+    /// Return the identifier of the other side of the innermost secure channel of a route, if any
+    fn peer_identifier(&self, route: &Route) -> Option<Identifier> {
+        let registry = self.secure_channels.secure_channel_registry();
+        route
+            .iter()
+            .filter_map(|address| registry.get_channel_by_encryptor_address(address))
+            .last()
+            .map(|entry| entry.their_id().clone())
     }
 }
 
@@ -73,6 +117,18 @@ impl Messages for BackgroundNodeClient {
         let request = Request::post("v0/message").body(SendMessage::new(to, message));
         Ok(self.clone().set_timeout(timeout).ask(ctx, request).await?)
     }
+
+    #[instrument(skip_all)]
+    async fn send_message_with_report(
+        &self,
+        ctx: &Context,
+        to: &MultiAddr,
+        message: Vec<u8>,
+        timeout: Option<Duration>,
+    ) -> miette::Result<MessageReport> {
+        let request = Request::post("v0/message/report").body(SendMessage::new(to, message));
+        Ok(self.clone().set_timeout(timeout).ask(ctx, request).await?)
+    }
 }
 
 impl NodeManagerWorker {
@@ -98,6 +154,29 @@ impl NodeManagerWorker {
             }
         }
     }
+
+    pub(crate) async fn send_message_with_report(
+        &self,
+        ctx: &Context,
+        send_message: SendMessage,
+    ) -> Result<Response<MessageReport>, Response<Error>> {
+        let multiaddr = send_message.multiaddr()?;
+        let msg = send_message.message.to_vec();
+
+        let res = self
+            .node_manager
+            .send_message_with_report(ctx, &multiaddr, msg, None)
+            .await;
+        match res {
+            Ok(r) => Ok(Response::ok().body(r)),
+            Err(err) => {
+                error!(target: TARGET, ?err, "Failed to send message");
+                Err(Response::internal_error_no_request(
+                    "Failed to send message",
+                ))
+            }
+        }
+    }
 }
 
 #[derive(Encode, Decode, Debug)]
@@ -122,3 +201,18 @@ impl SendMessage {
             .map_err(|_err| ApiError::core(format!("Invalid route: {}", self.route)))
     }
 }
+
+/// Reply to a message, with some information about its delivery
+#[derive(Encode, Decode, Debug)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct MessageReport {
+    /// Body of the reply
+    #[n(1)] pub reply: Vec<u8>,
+    /// Route used to send the message
+    #[n(2)] pub route: String,
+    /// Identifier of the other side of the secure channel used to send the message, if any
+    #[n(3)] pub peer_identifier: Option<Identifier>,
+    /// Time elapsed between sending the message and receiving its reply
+    #[n(4)] pub round_trip_time: Duration,
+}
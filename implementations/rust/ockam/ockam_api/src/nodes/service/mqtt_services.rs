@@ -0,0 +1,268 @@
+use ockam::transport::HostnamePort;
+use ockam::{Address, Context, Result};
+use ockam_abac::PolicyExpression;
+use ockam_abac::{Action, Resource, ResourceType};
+use ockam_core::api::{Error, Response};
+use ockam_core::compat::net::SocketAddr;
+use ockam_core::compat::rand::random_string;
+use ockam_core::route;
+use ockam_multiaddr::proto::Service;
+use ockam_multiaddr::MultiAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use super::NodeManagerWorker;
+use crate::error::ApiError;
+use crate::mqtt::{
+    MqttPortalListener, TopicKeys, TopicKeysService, MQTT_OUTLET_BROKER_ADDRESS,
+    MQTT_OUTLET_TOPIC_KEYS_ADDRESS,
+};
+use crate::nodes::models::portal::OutletAccessControl;
+use crate::nodes::models::services::{
+    DeleteServiceRequest, StartMqttInletRequest, StartMqttOutletRequest, StartServiceRequest,
+};
+use crate::nodes::registry::{MqttServiceInfo, MqttServiceKind};
+use crate::nodes::service::default_address::DefaultAddress;
+use crate::nodes::service::tcp_inlets::InletServiceOptions;
+use crate::nodes::service::tcp_outlets::OutletServiceOptions;
+use crate::nodes::InMemoryNode;
+
+impl NodeManagerWorker {
+    pub(super) async fn start_mqtt_inlet_service(
+        &self,
+        context: &Context,
+        body: StartServiceRequest<StartMqttInletRequest>,
+    ) -> Result<Response<()>, Response<Error>> {
+        let request = body.request();
+        match self
+            .node_manager
+            .start_mqtt_inlet_service(
+                context,
+                Address::from_string(body.address()),
+                request.bind_address(),
+                request.mqtt_outlet_route(),
+                request.encrypt_payloads(),
+                request.policy_expression(),
+            )
+            .await
+        {
+            Ok(_) => Ok(Response::ok().body(())),
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
+
+    pub(super) async fn start_mqtt_outlet_service(
+        &self,
+        context: &Context,
+        body: StartServiceRequest<StartMqttOutletRequest>,
+    ) -> Result<Response<()>, Response<Error>> {
+        let request = body.request();
+        match self
+            .node_manager
+            .start_mqtt_outlet_service(
+                context,
+                Address::from_string(body.address()),
+                request.broker_addr(),
+                request.tls(),
+                request.policy_expression(),
+            )
+            .await
+        {
+            Ok(_) => Ok(Response::ok().body(())),
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
+
+    pub(crate) async fn delete_mqtt_service(
+        &self,
+        ctx: &Context,
+        delete_service_request: DeleteServiceRequest,
+        kind: MqttServiceKind,
+    ) -> Result<Response<()>, Response<Error>> {
+        match self
+            .node_manager
+            .delete_mqtt_service(ctx, delete_service_request.address(), kind)
+            .await
+        {
+            Ok(DeleteMqttServiceResult::ServiceDeleted) => Ok(Response::ok()),
+            Ok(DeleteMqttServiceResult::ServiceNotFound { address, kind }) => {
+                Err(Response::not_found_no_request(&format!(
+                    "Service at address '{address}' with kind {kind} not found"
+                )))
+            }
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
+}
+
+impl InMemoryNode {
+    /// Start an MQTT inlet: the clients connect to the bind address, and their packets are
+    /// intercepted before being sent to the MQTT outlet. When `encrypt_payloads` is set, the
+    /// published payloads are encrypted with the keys given by the outlet
+    pub async fn start_mqtt_inlet_service(
+        &self,
+        context: &Context,
+        service_address: Address,
+        bind_address: SocketAddr,
+        outlet_node_multiaddr: MultiAddr,
+        encrypt_payloads: bool,
+        policy_expression: Option<PolicyExpression>,
+    ) -> Result<()> {
+        let topic_keys = if encrypt_payloads {
+            let mut topic_keys_service = outlet_node_multiaddr.clone();
+            topic_keys_service.push_back(Service::new(MQTT_OUTLET_TOPIC_KEYS_ADDRESS))?;
+            Some(Arc::new(TopicKeys::new(
+                self.node_manager.clone(),
+                self.secure_channels.vault().secure_channel_vault.clone(),
+                topic_keys_service,
+            )))
+        } else {
+            None
+        };
+
+        let inlet_alias = format!("mqtt-inlet-{}", random_string());
+        self.create_inlet(
+            context,
+            bind_address.to_string(),
+            route![service_address.clone()],
+            route![MQTT_OUTLET_BROKER_ADDRESS],
+            outlet_node_multiaddr,
+            inlet_alias.clone(),
+            policy_expression.clone(),
+            None,
+            None,
+            true,
+            None,
+            false,
+            false,
+            InletServiceOptions::default(),
+        )
+        .await?;
+
+        let policy_access_control = self
+            .policy_access_control(
+                self.project_authority().clone(),
+                Resource::new(service_address.to_string(), ResourceType::TcpInlet),
+                Action::HandleMessage,
+                policy_expression,
+            )
+            .await?;
+
+        MqttPortalListener::create(
+            context,
+            bind_address.to_string(),
+            topic_keys,
+            service_address.clone(),
+            Arc::new(policy_access_control.create_incoming()),
+            Arc::new(policy_access_control.create_outgoing(context).await?),
+        )
+        .await?;
+
+        self.registry
+            .mqtt_services
+            .insert(service_address, MqttServiceInfo::inlet(inlet_alias))
+            .await;
+
+        Ok(())
+    }
+
+    /// Start an MQTT outlet, forwarding the packets of the MQTT inlets to the broker, and
+    /// giving the inlets the keys used to encrypt the payloads of each topic
+    pub async fn start_mqtt_outlet_service(
+        &self,
+        context: &Context,
+        service_address: Address,
+        broker_addr: String,
+        tls: bool,
+        policy_expression: Option<PolicyExpression>,
+    ) -> Result<()> {
+        let default_secure_channel_listener_flow_control_id = context
+            .flow_controls()
+            .get_flow_control_with_spawner(&DefaultAddress::SECURE_CHANNEL_LISTENER.into())
+            .ok_or_else(|| {
+                ApiError::core("Unable to get flow control for secure channel listener")
+            })?;
+
+        let policy_access_control = self
+            .policy_access_control(
+                self.project_authority().clone(),
+                Resource::new(service_address.to_string(), ResourceType::TcpOutlet),
+                Action::HandleMessage,
+                policy_expression.clone(),
+            )
+            .await?;
+
+        // the packets are intercepted by the inlets, the outlet only relays them to the broker
+        self.create_outlet(
+            context,
+            HostnamePort::from_str(&broker_addr)?,
+            tls,
+            Some(MQTT_OUTLET_BROKER_ADDRESS.into()),
+            true,
+            OutletAccessControl::WithPolicyExpression(policy_expression),
+            OutletServiceOptions::default(),
+        )
+        .await
+        .map_err(|e| ApiError::core(e.to_string()))?;
+
+        // the topic keys are derived from the secret of the Kafka record keys,
+        // with a label specific to MQTT
+        let secret = self
+            .cli_state
+            .get_or_create_kafka_record_keys_secret(&self.node_name)
+            .await?;
+        TopicKeysService::create(
+            context,
+            default_secure_channel_listener_flow_control_id,
+            secret,
+            Arc::new(policy_access_control.create_incoming()),
+        )
+        .await?;
+
+        self.registry
+            .mqtt_services
+            .insert(service_address, MqttServiceInfo::outlet())
+            .await;
+
+        Ok(())
+    }
+
+    /// Delete an MQTT service from the registry, with its TCP inlet or outlet.
+    /// The expected kind must match the actual kind
+    pub async fn delete_mqtt_service(
+        &self,
+        ctx: &Context,
+        address: Address,
+        kind: MqttServiceKind,
+    ) -> Result<DeleteMqttServiceResult> {
+        debug!(address = %address, kind = %kind, "Deleting mqtt service");
+        let info = match self.registry.mqtt_services.get(&address).await {
+            Some(info) if info.kind() == &kind => info,
+            _ => return Ok(DeleteMqttServiceResult::ServiceNotFound { address, kind }),
+        };
+
+        match kind {
+            MqttServiceKind::Inlet => {
+                if let Some(inlet_alias) = info.inlet_alias() {
+                    self.delete_inlet(&inlet_alias).await?;
+                }
+                ctx.stop_worker(address.clone()).await?;
+            }
+            MqttServiceKind::Outlet => {
+                self.delete_outlet(&MQTT_OUTLET_BROKER_ADDRESS.into())
+                    .await?;
+                ctx.stop_worker(MQTT_OUTLET_TOPIC_KEYS_ADDRESS).await?;
+            }
+        }
+        self.registry.mqtt_services.remove(&address).await;
+        Ok(DeleteMqttServiceResult::ServiceDeleted)
+    }
+}
+
+pub enum DeleteMqttServiceResult {
+    ServiceDeleted,
+    ServiceNotFound {
+        address: Address,
+        kind: MqttServiceKind,
+    },
+}
@@ -0,0 +1,159 @@
+use ockam::Result;
+use ockam_core::api::{Error, RequestHeader, Response};
+use ockam_core::compat::time::now;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Address, AllowAll, DenyAll};
+use ockam_multiaddr::MultiAddr;
+use ockam_node::{CancellationToken, Context};
+
+use crate::cli_state::random_name;
+use crate::local_multiaddr_to_route;
+use crate::nodes::models::node::{
+    NodeEventStatus, NodeEventsSubscriptionStatus, SubscribeNodeEvents,
+};
+use crate::nodes::registry::NodeEventsSubscriptionInfo;
+
+use super::{NodeManager, NodeManagerWorker};
+
+impl NodeManagerWorker {
+    pub(super) async fn subscribe_node_events(
+        &self,
+        ctx: &Context,
+        req: &RequestHeader,
+        request: SubscribeNodeEvents,
+    ) -> Result<Response<NodeEventsSubscriptionStatus>, Response<Error>> {
+        match self
+            .node_manager
+            .subscribe_node_events(ctx, request.route, request.kinds)
+            .await
+        {
+            Ok(body) => Ok(Response::ok().with_headers(req).body(body)),
+            Err(err) => Err(Response::bad_request(
+                req,
+                &format!("Failed to subscribe to the node events: {err}"),
+            )),
+        }
+    }
+
+    pub(super) async fn list_node_events_subscriptions(
+        &self,
+    ) -> Result<Response<Vec<NodeEventsSubscriptionStatus>>, Response<Error>> {
+        Ok(Response::ok().body(self.node_manager.list_node_events_subscriptions().await))
+    }
+
+    pub(super) async fn unsubscribe_node_events(
+        &self,
+        req: &RequestHeader,
+        id: &str,
+    ) -> Result<Response<()>, Response<Error>> {
+        match self.node_manager.unsubscribe_node_events(id).await {
+            Ok(()) => Ok(Response::ok().with_headers(req).body(())),
+            Err(_) => Err(Response::not_found(
+                req,
+                &format!("Subscription {id} not found."),
+            )),
+        }
+    }
+}
+
+impl NodeManager {
+    /// Send the events of this node to a worker, as `NodeEventStatus` messages, until the
+    /// subscription is deleted or the worker can not be reached anymore.
+    /// If `kinds` is not empty, only the events of these kinds are sent
+    pub async fn subscribe_node_events(
+        &self,
+        ctx: &Context,
+        route: MultiAddr,
+        kinds: Vec<String>,
+    ) -> Result<NodeEventsSubscriptionStatus> {
+        let to = local_multiaddr_to_route(&route)?;
+        let id = random_name();
+        let cancellation = CancellationToken::new();
+        self.registry
+            .node_events_subscriptions
+            .insert(
+                id.clone(),
+                NodeEventsSubscriptionInfo {
+                    route: route.clone(),
+                    kinds: kinds.clone(),
+                    cancellation: cancellation.clone(),
+                },
+            )
+            .await;
+
+        // subscribe before returning so that no event published after this call is missed
+        let mut subscription = ctx.subscribe_events();
+        let sender = ctx
+            .new_detached(
+                Address::random_tagged("NodeEvents.sender"),
+                DenyAll,
+                AllowAll,
+            )
+            .await?;
+        let registry = self.registry.clone();
+        let subscription_id = id.clone();
+        let subscription_kinds = kinds.clone();
+        ctx.runtime().spawn(async move {
+            loop {
+                let event = tokio::select! {
+                    _ = cancellation.cancelled() => break,
+                    event = subscription.next() => match event {
+                        Some(event) => event,
+                        None => break,
+                    },
+                };
+                if !subscription_kinds.is_empty()
+                    && !subscription_kinds.iter().any(|k| k == event.kind())
+                {
+                    continue;
+                }
+                let status = NodeEventStatus::new(event, now().unwrap_or_default());
+                let sent = match minicbor::to_vec(&status) {
+                    Ok(message) => sender.send(to.clone(), message).await,
+                    Err(e) => Err(ockam_core::Error::new(Origin::Api, Kind::Serialization, e)),
+                };
+                if let Err(e) = sent {
+                    warn!(
+                        "Stopping the node events subscription {} to {}: {}",
+                        subscription_id, to, e
+                    );
+                    break;
+                }
+            }
+            registry
+                .node_events_subscriptions
+                .remove(&subscription_id)
+                .await;
+        });
+
+        Ok(NodeEventsSubscriptionStatus { id, route, kinds })
+    }
+
+    pub async fn list_node_events_subscriptions(&self) -> Vec<NodeEventsSubscriptionStatus> {
+        self.registry
+            .node_events_subscriptions
+            .entries()
+            .await
+            .into_iter()
+            .map(|(id, info)| NodeEventsSubscriptionStatus {
+                id,
+                route: info.route,
+                kinds: info.kinds,
+            })
+            .collect()
+    }
+
+    pub async fn unsubscribe_node_events(&self, id: &str) -> Result<()> {
+        match self.registry.node_events_subscriptions.remove(id).await {
+            Some(info) => {
+                info.cancellation.cancel();
+                Ok(())
+            }
+            None => Err(ockam_core::Error::new(
+                Origin::Api,
+                Kind::NotFound,
+                format!("subscription {id} not found"),
+            )),
+        }
+    }
+}
@@ -4,15 +4,20 @@ use ockam::{Address, Context, Result};
 use ockam_abac::{Action, Resource, ResourceType};
 use ockam_core::api::{Error, Response};
 use ockam_node::WorkerBuilder;
+use sysinfo::Pid;
 
 use crate::echoer::Echoer;
 use crate::error::ApiError;
 use crate::hop::Hop;
-use crate::nodes::models::node::{NodeResources, NodeStatus};
+use crate::nodes::models::node::{
+    DebugGraph, NodeResources, NodeStats, NodeStatus, StartTraceRequest, TraceEventsStatus,
+    TraceSubscription, UnlockVaultRequest, UnlockVaultResponse,
+};
 use crate::nodes::models::services::{
     ServiceStatus, StartEchoerServiceRequest, StartHopServiceRequest, StartUppercaseServiceRequest,
 };
-use crate::nodes::registry::KafkaServiceKind;
+use crate::nodes::models::workers::{WorkerMetricsStatus, WorkerStatus};
+use crate::nodes::registry::{KafkaServiceKind, MqttServiceKind};
 use crate::nodes::service::default_address::DefaultAddress;
 use crate::nodes::NodeManager;
 use crate::uppercase::Uppercase;
@@ -99,6 +104,94 @@ impl NodeManagerWorker {
             Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
         }
     }
+
+    #[instrument(skip_all)]
+    pub(super) async fn get_node_metrics(
+        &self,
+        ctx: &Context,
+    ) -> Result<Response<Vec<WorkerMetricsStatus>>, Response<Error>> {
+        match self.node_manager.get_node_metrics(ctx).await {
+            Ok(metrics) => Ok(Response::ok().body(metrics)),
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
+
+    pub(super) async fn get_debug_graph(&self) -> Result<Response<DebugGraph>, Response<Error>> {
+        match self.node_manager.get_debug_graph() {
+            Some(graph) => Ok(Response::ok().body(graph)),
+            None => Err(Response::not_found_no_request(
+                "The debugger is not available, the node must be built with the 'debugger' feature",
+            )),
+        }
+    }
+
+    pub(super) async fn start_trace(
+        &self,
+        request: StartTraceRequest,
+    ) -> Result<Response<TraceSubscription>, Response<Error>> {
+        match ockam_node::debugger::subscribe(request.into()) {
+            Some(id) => Ok(Response::ok().body(TraceSubscription { id })),
+            None => Err(Response::not_found_no_request(
+                "The debugger is not available, the node must be built with the 'debugger' feature",
+            )),
+        }
+    }
+
+    pub(super) async fn poll_trace(
+        &self,
+        id: &str,
+    ) -> Result<Response<TraceEventsStatus>, Response<Error>> {
+        let events = parse_trace_subscription_id(id).and_then(|id| {
+            ockam_node::debugger::poll_trace_events(id).ok_or_else(|| {
+                Response::not_found_no_request(&format!("Trace subscription {id} not found"))
+            })
+        })?;
+        Ok(Response::ok().body(TraceEventsStatus::from(events)))
+    }
+
+    pub(super) async fn unlock_vault(
+        &self,
+        request: UnlockVaultRequest,
+    ) -> Result<Response<UnlockVaultResponse>, Response<Error>> {
+        match self
+            .node_manager
+            .cli_state
+            .unlock_named_vault(&request.vault_name, request.passphrase)
+            .await
+        {
+            Ok(was_locked) => {
+                Ok(Response::ok().body(UnlockVaultResponse::new(request.vault_name, was_locked)))
+            }
+            Err(e) => Err(Response::bad_request_no_request(&e.to_string())),
+        }
+    }
+
+    pub(super) async fn stop_trace(&self, id: &str) -> Result<Response, Response<Error>> {
+        let id = parse_trace_subscription_id(id)?;
+        if ockam_node::debugger::unsubscribe(id) {
+            Ok(Response::ok())
+        } else {
+            Err(Response::not_found_no_request(&format!(
+                "Trace subscription {id} not found"
+            )))
+        }
+    }
+
+    pub(super) async fn get_node_stats(
+        &self,
+        ctx: &Context,
+    ) -> Result<Response<NodeStats>, Response<Error>> {
+        match self.node_manager.get_node_stats(ctx).await {
+            Ok(node_stats) => Ok(Response::ok().body(node_stats)),
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
+}
+
+fn parse_trace_subscription_id(id: &str) -> Result<u64, Response<Error>> {
+    id.parse().map_err(|_| {
+        Response::bad_request_no_request(&format!("Invalid trace subscription id: {id}"))
+    })
 }
 
 impl NodeManager {
@@ -169,6 +262,42 @@ impl NodeManager {
                     },
                 ))
             });
+        self.registry
+            .mqtt_services
+            .entries()
+            .await
+            .iter()
+            .for_each(|(address, info)| {
+                list.push(ServiceStatus::new(
+                    address.address(),
+                    match info.kind() {
+                        MqttServiceKind::Inlet => DefaultAddress::MQTT_INLET,
+                        MqttServiceKind::Outlet => DefaultAddress::MQTT_OUTLET,
+                    },
+                ))
+            });
+        self.registry
+            .postgres_outlets
+            .keys()
+            .await
+            .iter()
+            .for_each(|address| {
+                list.push(ServiceStatus::new(
+                    address.address(),
+                    DefaultAddress::POSTGRES_OUTLET,
+                ))
+            });
+        self.registry
+            .http_gateways
+            .keys()
+            .await
+            .iter()
+            .for_each(|address| {
+                list.push(ServiceStatus::new(
+                    address.address(),
+                    DefaultAddress::HTTP_GATEWAY,
+                ))
+            });
         list
     }
 
@@ -253,7 +382,51 @@ impl NodeManager {
 
     pub async fn get_node_status(&self) -> Result<NodeStatus> {
         let node = self.cli_state.get_node(&self.node_name).await?;
-        Ok(NodeStatus::from(&node))
+        Ok(NodeStatus::from(&node).with_services_initialized(self.are_services_initialized()))
+    }
+
+    /// Return the CPU and memory used by the node process, and the messages handled by its workers
+    /// Return the graphs logged by the debugger so far,
+    /// or `None` if the node was built without the `debugger` feature
+    pub fn get_debug_graph(&self) -> Option<DebugGraph> {
+        ockam_node::debugger::graph().map(DebugGraph::from)
+    }
+
+    pub async fn get_node_stats(&self, ctx: &Context) -> Result<NodeStats> {
+        let workers = ctx
+            .list_workers_info()
+            .await?
+            .into_iter()
+            .map(WorkerStatus::from)
+            .collect();
+        let pid = std::process::id();
+        let (cpu_usage, memory) = {
+            let mut system = self.system.lock().unwrap();
+            let sys_pid = Pid::from_u32(pid);
+            system.refresh_process(sys_pid);
+            system
+                .process(sys_pid)
+                .map(|p| (p.cpu_usage(), p.memory()))
+                .unwrap_or_default()
+        };
+        Ok(NodeStats {
+            name: self.node_name.clone(),
+            pid,
+            cpu_usage,
+            memory,
+            workers,
+            kafka_inlets: self.list_kafka_inlet_stats().await,
+        })
+    }
+
+    /// Return the runtime metrics of the workers and processors of the node
+    pub async fn get_node_metrics(&self, ctx: &Context) -> Result<Vec<WorkerMetricsStatus>> {
+        Ok(ctx
+            .metrics()
+            .await?
+            .into_iter()
+            .map(WorkerMetricsStatus::from)
+            .collect())
     }
 
     pub async fn get_node_resources(&self) -> Result<NodeResources> {
@@ -266,6 +439,7 @@ impl NodeManager {
         let listeners = self.list_secure_channel_listeners().await;
         let inlets = self.list_inlets().await;
         let outlets = self.list_outlets().await;
+        let relays = self.get_relays().await;
         let services = self.list_services().await;
         NodeResources::from_parts(
             node,
@@ -274,6 +448,7 @@ impl NodeManager {
             listeners,
             inlets,
             outlets,
+            relays,
             services,
         )
     }
@@ -0,0 +1,170 @@
+use ockam::transport::HostnamePort;
+use ockam::{Address, Context, Result};
+use ockam_abac::PolicyExpression;
+use ockam_abac::{Action, Resource, ResourceType};
+use ockam_core::api::{Error, Response};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use super::NodeManagerWorker;
+use crate::error::ApiError;
+use crate::nodes::models::portal::OutletAccessControl;
+use crate::nodes::models::services::{
+    DeleteServiceRequest, StartPostgresOutletRequest, StartServiceRequest,
+};
+use crate::nodes::registry::PostgresOutletInfo;
+use crate::nodes::service::default_address::DefaultAddress;
+use crate::nodes::service::tcp_outlets::OutletServiceOptions;
+use crate::nodes::InMemoryNode;
+use crate::postgres::{
+    database_outlet_address, DatabaseOutlet, PasswordFile, PostgresOutletListener, RoleMapping,
+};
+
+impl NodeManagerWorker {
+    pub(super) async fn start_postgres_outlet_service(
+        &self,
+        context: &Context,
+        body: StartServiceRequest<StartPostgresOutletRequest>,
+    ) -> Result<Response<()>, Response<Error>> {
+        let request = body.request();
+        match self
+            .node_manager
+            .start_postgres_outlet_service(
+                context,
+                Address::from_string(body.address()),
+                request.database_addr(),
+                request.role_mapping(),
+                request.password_file(),
+                request.policy_expression(),
+            )
+            .await
+        {
+            Ok(_) => Ok(Response::ok().body(())),
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
+
+    pub(super) async fn delete_postgres_outlet_service(
+        &self,
+        ctx: &Context,
+        delete_service_request: DeleteServiceRequest,
+    ) -> Result<Response<()>, Response<Error>> {
+        let address = delete_service_request.address();
+        match self
+            .node_manager
+            .delete_postgres_outlet_service(ctx, address.clone())
+            .await
+        {
+            Ok(true) => Ok(Response::ok()),
+            Ok(false) => Err(Response::not_found_no_request(&format!(
+                "Postgres outlet at address '{address}' not found"
+            ))),
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
+}
+
+impl InMemoryNode {
+    /// Start a postgres outlet: the clients connect with the database role found in the
+    /// attributes of their identity, and the outlet authenticates this role to the database
+    /// with the password found in the password file
+    pub async fn start_postgres_outlet_service(
+        &self,
+        context: &Context,
+        service_address: Address,
+        database_addr: String,
+        role_mapping: RoleMapping,
+        password_file: Option<String>,
+        policy_expression: Option<PolicyExpression>,
+    ) -> Result<()> {
+        if self
+            .registry
+            .postgres_outlets
+            .contains_key(&service_address)
+            .await
+        {
+            return Err(ApiError::core(format!(
+                "postgres outlet already exists at {service_address}"
+            )));
+        }
+
+        let database_address = HostnamePort::from_str(&database_addr)?;
+        let password_file = match password_file {
+            Some(path) => Some(Arc::new(PasswordFile::read(path)?)),
+            None => None,
+        };
+
+        let default_secure_channel_listener_flow_control_id = context
+            .flow_controls()
+            .get_flow_control_with_spawner(&DefaultAddress::SECURE_CHANNEL_LISTENER.into())
+            .ok_or_else(|| {
+                ApiError::core("Unable to get flow control for secure channel listener")
+            })?;
+
+        let policy_access_control = self
+            .policy_access_control(
+                self.project_authority().clone(),
+                Resource::new(service_address.to_string(), ResourceType::TcpOutlet),
+                Action::HandleMessage,
+                policy_expression.clone(),
+            )
+            .await?;
+
+        // the database is only reachable through the workers of the postgres outlet
+        let database_outlet =
+            Address::from_string(database_outlet_address(service_address.address()));
+        self.create_outlet(
+            context,
+            database_address.clone(),
+            false,
+            Some(database_outlet.clone()),
+            false,
+            OutletAccessControl::WithPolicyExpression(policy_expression),
+            OutletServiceOptions::default(),
+        )
+        .await
+        .map_err(|e| ApiError::core(e.to_string()))?;
+
+        PostgresOutletListener::create(
+            context,
+            service_address.clone(),
+            DatabaseOutlet {
+                outlet_address: database_outlet.clone(),
+                database_address,
+                role_mapping,
+                password_file,
+            },
+            default_secure_channel_listener_flow_control_id,
+            self.secure_channels.identities().identities_attributes(),
+            self.project_authority(),
+            Arc::new(policy_access_control.create_incoming()),
+            Arc::new(policy_access_control.create_outgoing(context).await?),
+        )
+        .await?;
+
+        self.registry
+            .postgres_outlets
+            .insert(service_address, PostgresOutletInfo { database_outlet })
+            .await;
+
+        Ok(())
+    }
+
+    /// Delete a postgres outlet with the TCP outlet of its database.
+    /// Return false if there is no postgres outlet at this address
+    pub async fn delete_postgres_outlet_service(
+        &self,
+        ctx: &Context,
+        address: Address,
+    ) -> Result<bool> {
+        debug!(address = %address, "Deleting postgres outlet");
+        let info = match self.registry.postgres_outlets.get(&address).await {
+            Some(info) => info,
+            None => return Ok(false),
+        };
+        self.delete_outlet(&info.database_outlet).await?;
+        ctx.stop_worker(address.clone()).await?;
+        self.registry.postgres_outlets.remove(&address).await;
+        Ok(true)
+    }
+}
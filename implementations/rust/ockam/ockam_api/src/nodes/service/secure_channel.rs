@@ -24,7 +24,7 @@ use crate::nodes::models::secure_channel::ShowSecureChannelListenerRequest;
 use crate::nodes::models::secure_channel::ShowSecureChannelRequest;
 use crate::nodes::models::secure_channel::{
     CreateSecureChannelResponse, DeleteSecureChannelListenerResponse, DeleteSecureChannelResponse,
-    ShowSecureChannelResponse,
+    SecureChannelDiagnosticsResponse, ShowSecureChannelResponse,
 };
 use crate::nodes::registry::SecureChannelInfo;
 use crate::nodes::service::default_address::DefaultAddress;
@@ -102,11 +102,31 @@ impl NodeManagerWorker {
                 .get_secure_channel(&address)
                 .await
                 .map(|secure_channel| {
-                    Response::ok().body(ShowSecureChannelResponse::new(Some(secure_channel)))
+                    let registry_entry = self
+                        .node_manager
+                        .secure_channels
+                        .secure_channel_registry()
+                        .get_channel_by_encryptor_address(secure_channel.sc().encryptor_address());
+                    Response::ok().body(ShowSecureChannelResponse::new(
+                        Some(secure_channel),
+                        registry_entry,
+                    ))
                 })?;
 
         Ok(response)
     }
+
+    pub fn show_secure_channel_diagnostics(
+        &self,
+    ) -> Result<Response<SecureChannelDiagnosticsResponse>, Response<Error>> {
+        let statistics = self
+            .node_manager
+            .secure_channels
+            .secure_channel_registry()
+            .handshake_diagnostics()
+            .statistics();
+        Ok(Response::ok().body(SecureChannelDiagnosticsResponse::from(statistics)))
+    }
 }
 
 /// SECURE CHANNEL LISTENERS
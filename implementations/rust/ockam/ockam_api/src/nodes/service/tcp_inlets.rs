@@ -1,7 +1,7 @@
 use std::net::SocketAddr;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::task::JoinHandle;
 
 use tokio::time::timeout;
@@ -9,7 +9,10 @@ use tokio::time::timeout;
 use crate::address::get_free_address_for;
 use crate::DefaultAddress;
 use ockam::identity::Identifier;
-use ockam::tcp::TcpInletOptions;
+use ockam::tcp::{
+    HttpRewrite, PortalCompression, PortalTlsCertificate, TcpInletLimits, TcpInletLoadBalancing,
+    TcpInletOptions, TcpPortalRateLimit, TcpPortalStats, TcpSocketOptions,
+};
 use ockam::udp::{UdpPunctureNegotiation, UdpTransport};
 use ockam::Result;
 use ockam_abac::{Action, PolicyExpression, Resource, ResourceType};
@@ -20,12 +23,12 @@ use ockam_core::{
 };
 use ockam_multiaddr::proto::Project as ProjectProto;
 use ockam_multiaddr::{MultiAddr, Protocol};
-use ockam_node::Context;
+use ockam_node::{Context, MessageSendReceiveOptions};
 use ockam_transport_tcp::TcpInlet;
 
 use crate::error::ApiError;
 use crate::nodes::connection::Connection;
-use crate::nodes::models::portal::{CreateInlet, InletStatus};
+use crate::nodes::models::portal::{CreateInlet, InletStats, InletStatus};
 use crate::nodes::registry::InletInfo;
 use crate::nodes::{BackgroundNodeClient, InMemoryNode};
 use crate::session::sessions::{
@@ -36,6 +39,45 @@ use crate::session::MedicHandle;
 
 use super::{NodeManager, NodeManagerWorker, SecureChannelType};
 
+/// Minimum time between two checks of the routes with a higher priority than the active route
+const FAILBACK_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Minimum time between two health checks of the outlets of a load balanced inlet
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Maximum time for an outlet node to answer a health check
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// HTTP header containing the identifier used by an inlet to connect to its outlet
+pub const OCKAM_IDENTIFIER_HTTP_HEADER: &str = "X-Ockam-Identifier";
+
+/// Optional settings of a TCP inlet, the default values keep the inlet behaviour unchanged
+#[derive(Clone, Debug, Default)]
+pub struct InletServiceOptions {
+    /// Socket options of the connections accepted by the inlet
+    pub socket_options: TcpSocketOptions,
+    /// Compression algorithm proposed to the outlet, which accepts it or not
+    pub compression: Option<PortalCompression>,
+    /// Maximum throughput of each connection, unlimited when not set
+    pub rate_limit: Option<TcpPortalRateLimit>,
+    /// Routes used when the outlet address is not reachable, in decreasing order of priority
+    pub fallback_outlet_addrs: Vec<MultiAddr>,
+    /// Rewriting of the HTTP requests sent through the inlet
+    pub http_rewrite: Option<HttpRewrite>,
+    /// Certificate used to terminate the TLS connections of the clients
+    pub tls_certificate: Option<PortalTlsCertificate>,
+    /// Limits on the number of connections and their idle time, no limits when not set
+    pub limits: Option<TcpInletLimits>,
+    /// Strategy spreading the connections across the outlet address and the fallback routes.
+    /// When not set, the fallback routes are only used if the outlet is not reachable
+    pub load_balancing: Option<TcpInletLoadBalancing>,
+    /// Expect a PROXY protocol v2 header at the start of each accepted connection
+    pub proxy_protocol: bool,
+    /// Grace period during which the connections are kept open when the route to the outlet
+    /// breaks, the connections are closed right away when not set
+    pub resumption: Option<Duration>,
+}
+
 impl NodeManagerWorker {
     pub(super) async fn get_inlets(&self) -> Result<Response<Vec<InletStatus>>, Response<Error>> {
         let inlets = self.node_manager.list_inlets().await;
@@ -61,7 +103,29 @@ impl NodeManagerWorker {
             secure_channel_identifier,
             enable_udp_puncture,
             disable_tcp_fallback,
+            socket_options,
+            compression,
+            rate_limit,
+            fallback_outlet_addrs,
+            http_rewrite,
+            tls_certificate,
+            limits,
+            load_balancing,
+            proxy_protocol,
+            resumption,
         } = create_inlet;
+        let options = InletServiceOptions {
+            socket_options: socket_options.unwrap_or_default(),
+            compression,
+            rate_limit,
+            fallback_outlet_addrs: fallback_outlet_addrs.unwrap_or_default(),
+            http_rewrite,
+            tls_certificate,
+            limits,
+            load_balancing,
+            proxy_protocol: proxy_protocol.unwrap_or(false),
+            resumption,
+        };
         match self
             .node_manager
             .create_inlet(
@@ -78,6 +142,7 @@ impl NodeManagerWorker {
                 secure_channel_identifier,
                 enable_udp_puncture,
                 disable_tcp_fallback,
+                options,
             )
             .await
         {
@@ -107,6 +172,18 @@ impl NodeManagerWorker {
             ))),
         }
     }
+
+    pub(super) async fn get_inlet_stats(
+        &self,
+        alias: &str,
+    ) -> Result<Response<InletStats>, Response<Error>> {
+        match self.node_manager.get_inlet_stats(alias).await {
+            Some(stats) => Ok(Response::ok().body(stats)),
+            None => Err(Response::not_found_no_request(&format!(
+                "Inlet with alias {alias} not found"
+            ))),
+        }
+    }
 }
 
 impl NodeManager {
@@ -128,19 +205,54 @@ impl NodeManager {
         enable_udp_puncture: bool,
         // TODO: Introduce mode enum
         disable_tcp_fallback: bool,
+        options: InletServiceOptions,
     ) -> Result<InletStatus> {
+        let InletServiceOptions {
+            socket_options,
+            compression,
+            rate_limit,
+            fallback_outlet_addrs,
+            http_rewrite,
+            tls_certificate,
+            limits,
+            load_balancing,
+            proxy_protocol,
+            resumption,
+        } = options;
         info!("Handling request to create inlet portal");
         debug! {
             listen_addr = %listen_addr,
             prefix = %prefix_route,
             suffix = %suffix_route,
             outlet_addr = %outlet_addr,
+            fallback_outlet_addrs = ?fallback_outlet_addrs,
             %alias,
             %enable_udp_puncture,
             %disable_tcp_fallback,
             "Creating inlet portal"
         }
 
+        if let Some(http_rewrite) = &http_rewrite {
+            http_rewrite.validate()?;
+        }
+        if let Some(tls_certificate) = &tls_certificate {
+            tls_certificate.validate()?;
+        }
+        if load_balancing.is_some() && enable_udp_puncture {
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::Invalid,
+                "Load balancing can't be used with UDP puncture",
+            ));
+        }
+        if load_balancing.is_some() && resumption.is_some() {
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::Invalid,
+                "Load balancing can't be used with the resumption of the connections",
+            ));
+        }
+
         let udp_transport = if enable_udp_puncture {
             Some(self.udp_transport.clone().ok_or(ockam_core::Error::new(
                 Origin::Transport,
@@ -193,12 +305,14 @@ impl NodeManager {
             }
         }
 
+        let stats = Arc::new(TcpPortalStats::default());
         let replacer = InletSessionReplacer {
             node_manager: self.clone(),
             udp_transport,
             context: Arc::new(ctx.async_try_clone().await?),
             listen_addr: listen_addr.to_string(),
             outlet_addr: outlet_addr.clone(),
+            fallback_outlet_addrs,
             prefix_route,
             suffix_route,
             authorized,
@@ -207,9 +321,23 @@ impl NodeManager {
             policy_expression,
             secure_channel_identifier,
             disable_tcp_fallback,
+            socket_options,
+            compression,
+            rate_limit,
+            http_rewrite,
+            tls_certificate,
+            limits: limits.unwrap_or_default(),
+            load_balancing,
+            proxy_protocol,
+            resumption,
+            stats: stats.clone(),
             connection: None,
             inlet: None,
             handle: None,
+            active_outlet_addr: 0,
+            last_failback_check: None,
+            balanced_outlets: vec![],
+            last_health_check: None,
         };
 
         let _ = self
@@ -244,7 +372,12 @@ impl NodeManager {
             .inlets
             .insert(
                 alias.clone(),
-                InletInfo::new(&listen_addr.to_string(), outlet_addr.clone(), session),
+                InletInfo::new(
+                    &listen_addr.to_string(),
+                    outlet_addr.clone(),
+                    session,
+                    stats,
+                ),
             )
             .await;
 
@@ -327,6 +460,22 @@ impl NodeManager {
         }
     }
 
+    /// Return the traffic counters of an inlet
+    pub async fn get_inlet_stats(&self, alias: &str) -> Option<InletStats> {
+        let inlet_info = self.registry.inlets.get(alias).await?;
+        Some(InletStats {
+            alias: alias.to_string(),
+            bind_addr: inlet_info.bind_addr.clone(),
+            bytes_in: inlet_info.stats.bytes_received(),
+            bytes_out: inlet_info.stats.bytes_sent(),
+            active_connections: inlet_info.stats.active_connections(),
+            total_connections: inlet_info.stats.total_connections(),
+            rejected_max_connections: inlet_info.stats.rejected_max_connections(),
+            rejected_max_connections_per_ip: inlet_info.stats.rejected_max_connections_per_ip(),
+            idle_timeouts: inlet_info.stats.idle_timeouts(),
+        })
+    }
+
     pub async fn list_inlets(&self) -> Vec<InletStatus> {
         self.registry
             .inlets
@@ -383,6 +532,7 @@ impl InMemoryNode {
         secure_channel_identifier: Option<Identifier>,
         enable_udp_puncture: bool,
         disable_tcp_fallback: bool,
+        options: InletServiceOptions,
     ) -> Result<InletStatus> {
         self.node_manager
             .create_inlet(
@@ -399,6 +549,7 @@ impl InMemoryNode {
                 secure_channel_identifier,
                 enable_udp_puncture,
                 disable_tcp_fallback,
+                options,
             )
             .await
     }
@@ -410,6 +561,8 @@ struct InletSessionReplacer {
     context: Arc<Context>,
     listen_addr: String,
     outlet_addr: MultiAddr,
+    /// Routes used when the outlet address is not reachable, in decreasing order of priority
+    fallback_outlet_addrs: Vec<MultiAddr>,
     prefix_route: Route,
     suffix_route: Route,
     authorized: Option<Identifier>,
@@ -418,11 +571,47 @@ struct InletSessionReplacer {
     policy_expression: Option<PolicyExpression>,
     secure_channel_identifier: Option<Identifier>,
     disable_tcp_fallback: bool,
+    /// Socket options of the connections accepted by the inlet
+    socket_options: TcpSocketOptions,
+    /// Compression proposed to the outlet
+    compression: Option<PortalCompression>,
+    /// Maximum throughput of the connections accepted by the inlet
+    rate_limit: Option<TcpPortalRateLimit>,
+    /// Rewriting of the HTTP requests, without the identifier header
+    http_rewrite: Option<HttpRewrite>,
+    /// Certificate used to terminate TLS on the connections accepted by the inlet
+    tls_certificate: Option<PortalTlsCertificate>,
+    /// Limits of the connections accepted by the inlet
+    limits: TcpInletLimits,
+    /// Strategy spreading the connections across all the outlet addresses. If not set, the
+    /// fallback addresses are only used when the addresses with a higher priority are down
+    load_balancing: Option<TcpInletLoadBalancing>,
+    /// True if the connections accepted by the inlet start with a PROXY protocol header
+    proxy_protocol: bool,
+    /// Grace period during which the connections are kept open when the connection to the
+    /// outlet is lost. The inlet is then kept, and its connections resume on the new route
+    resumption: Option<Duration>,
+    /// Traffic counters shared by all the successive inlets
+    stats: Arc<TcpPortalStats>,
 
     // current status
     connection: Option<Connection>,
     inlet: Option<Arc<TcpInlet>>,
     handle: Option<JoinHandle<()>>,
+    /// Index of the route currently used, in the list of all the outlet addresses
+    active_outlet_addr: usize,
+    last_failback_check: Option<Instant>,
+    /// Outlets currently used by a load balanced inlet, in the order of the outlet addresses
+    balanced_outlets: Vec<BalancedOutlet>,
+    last_health_check: Option<Instant>,
+}
+
+/// Connection to one of the outlets of a load balanced inlet
+struct BalancedOutlet {
+    /// Index of the outlet address, in the list of all the outlet addresses
+    index: usize,
+    connection: Connection,
+    route: Route,
 }
 
 impl InletSessionReplacer {
@@ -430,6 +619,13 @@ impl InletSessionReplacer {
         self.udp_transport.is_some()
     }
 
+    /// All the routes to the outlet, in decreasing order of priority
+    fn outlet_addrs(&self) -> Vec<MultiAddr> {
+        let mut outlet_addrs = vec![self.outlet_addr.clone()];
+        outlet_addrs.extend(self.fallback_outlet_addrs.iter().cloned());
+        outlet_addrs
+    }
+
     async fn access_control(
         &self,
     ) -> Result<(
@@ -504,11 +700,6 @@ impl InletSessionReplacer {
             )
             .await?;
 
-        let rendezvous_route = route![
-            DefaultAddress::get_rendezvous_server_address(),
-            DefaultAddress::RENDEZVOUS_SERVICE
-        ];
-
         let (mut receiver, sender) = UdpPunctureNegotiation::start_negotiation(
             &self.context,
             route![
@@ -516,7 +707,7 @@ impl InletSessionReplacer {
                 DefaultAddress::UDP_PUNCTURE_NEGOTIATION_LISTENER
             ],
             udp_transport,
-            rendezvous_route,
+            self.node_manager.rendezvous_route(),
         )
         .await?;
 
@@ -601,6 +792,284 @@ impl InletSessionReplacer {
     }
 }
 
+impl InletSessionReplacer {
+    /// Connect to an outlet and return the full route used by the inlet to reach it
+    async fn connect_outlet(&self, outlet_addr: &MultiAddr) -> Result<(Connection, Route)> {
+        let connection = self
+            .node_manager
+            .make_connection(
+                self.context.clone(),
+                outlet_addr,
+                self.identifier(),
+                self.authorized.clone(),
+                Some(self.wait_for_outlet_duration),
+            )
+            .await?;
+
+        let connection_route = connection.route()?;
+
+        //we expect a fully normalized MultiAddr
+        let normalized_route = route![
+            self.prefix_route.clone(),
+            connection_route,
+            self.suffix_route.clone()
+        ];
+        Ok((connection, normalized_route))
+    }
+
+    fn identifier(&self) -> Identifier {
+        self.secure_channel_identifier
+            .clone()
+            .unwrap_or(self.node_manager.identifier())
+    }
+
+    /// Options of the inlet
+    fn inlet_options(
+        &self,
+        incoming_ac: Arc<dyn IncomingAccessControl>,
+        outgoing_ac: Arc<dyn OutgoingAccessControl>,
+    ) -> TcpInletOptions {
+        let options = TcpInletOptions::new()
+            .with_incoming_access_control(incoming_ac)
+            .with_outgoing_access_control(outgoing_ac)
+            .with_stats(self.stats.clone())
+            .with_socket_options(self.socket_options.clone());
+        let options = match self.compression {
+            Some(compression) => options.with_compression(compression),
+            None => options,
+        };
+        let options = match self.rate_limit {
+            Some(rate_limit) => options.with_rate_limit(rate_limit),
+            None => options,
+        };
+        let options = match &self.http_rewrite {
+            Some(http_rewrite) => options.with_http_rewrite(
+                http_rewrite
+                    .clone()
+                    .with_header(OCKAM_IDENTIFIER_HTTP_HEADER, self.identifier().to_string()),
+            ),
+            None => options,
+        };
+        let options = match &self.tls_certificate {
+            Some(tls_certificate) => options.with_tls_certificate(tls_certificate.clone()),
+            None => options,
+        };
+        let options = options
+            .with_limits(self.limits)
+            .with_proxy_protocol(self.proxy_protocol);
+        let options = match self.resumption {
+            Some(grace_period) => options.with_resumption(grace_period),
+            None => options,
+        };
+        match self.load_balancing {
+            Some(load_balancing) => options.with_load_balancing(load_balancing),
+            None => options,
+        }
+    }
+
+    /// Connect to the outlet with a given route and create the inlet
+    async fn create_with_outlet_addr(
+        &mut self,
+        outlet_addr: &MultiAddr,
+        incoming_ac: Arc<dyn IncomingAccessControl>,
+        outgoing_ac: Arc<dyn OutgoingAccessControl>,
+    ) -> Result<ReplacerOutcome> {
+        let (connection, normalized_route) = self.connect_outlet(outlet_addr).await?;
+        let options = self.inlet_options(incoming_ac, outgoing_ac);
+
+        let options = if self.enable_udp_puncture() && self.disable_tcp_fallback {
+            options.paused()
+        } else {
+            options
+        };
+
+        let inlet = match &self.inlet {
+            // The inlet is kept when its connections can be resumed: they resume as soon as
+            // the new route is set
+            Some(inlet) if self.resumption.is_some() => {
+                if self.enable_udp_puncture() && self.disable_tcp_fallback {
+                    inlet.pause();
+                }
+                inlet.update_routes(vec![normalized_route.clone()])?;
+                inlet.clone()
+            }
+            // TODO: Instead just update the route in the existing inlet
+            // Finally, attempt to create a new inlet using the new route:
+            _ => {
+                let inlet = self
+                    .node_manager
+                    .tcp_transport
+                    .create_inlet(self.listen_addr.clone(), normalized_route.clone(), options)
+                    .await?
+                    .clone();
+                Arc::new(inlet)
+            }
+        };
+        let inlet_address = inlet.processor_address().clone();
+        self.inlet = Some(inlet.clone());
+
+        if self.enable_udp_puncture() {
+            info!("Spawning UDP puncture future");
+            self.spawn_udp_puncture(&connection, inlet, self.disable_tcp_fallback)
+                .await?;
+            info!("Spawned UDP puncture future");
+        }
+
+        Ok(ReplacerOutcome {
+            ping_route: connection.transport_route(),
+            kind: ReplacerOutputKind::Inlet(CurrentInletStatus {
+                worker: inlet_address,
+                route: normalized_route,
+                connection_status: ConnectionStatus::Up,
+            }),
+        })
+    }
+
+    /// Connect to all the reachable outlets and create an inlet spreading its connections
+    /// across them
+    async fn create_load_balanced(
+        &mut self,
+        incoming_ac: Arc<dyn IncomingAccessControl>,
+        outgoing_ac: Arc<dyn OutgoingAccessControl>,
+    ) -> Result<ReplacerOutcome> {
+        for (index, outlet_addr) in self.outlet_addrs().into_iter().enumerate() {
+            debug!(%outlet_addr, "connecting a load balanced tcp inlet");
+            match timeout(MAX_CONNECT_TIME, self.connect_outlet(&outlet_addr)).await {
+                Err(_) => warn!(%outlet_addr, "timeout connecting a load balanced tcp inlet"),
+                Ok(Err(e)) => {
+                    warn!(%outlet_addr, err = %e, "error connecting a load balanced tcp inlet")
+                }
+                Ok(Ok((connection, route))) => self.balanced_outlets.push(BalancedOutlet {
+                    index,
+                    connection,
+                    route,
+                }),
+            }
+        }
+
+        let routes = self.balanced_routes();
+        let (ping_route, route) = match self.balanced_outlets.first() {
+            Some(outlet) => (outlet.connection.transport_route(), outlet.route.clone()),
+            None => return Err(ApiError::core("none of the outlets is reachable")),
+        };
+
+        let options = self.inlet_options(incoming_ac, outgoing_ac);
+        let inlet = self
+            .node_manager
+            .tcp_transport
+            .create_inlet(self.listen_addr.clone(), route.clone(), options)
+            .await?;
+        inlet.update_routes(routes)?;
+        let inlet_address = inlet.processor_address().clone();
+        self.inlet = Some(Arc::new(inlet));
+        self.last_health_check = Some(Instant::now());
+        info!(
+            listen_addr = %self.listen_addr,
+            outlets = self.balanced_outlets.len(),
+            "the tcp inlet is load balanced"
+        );
+
+        // The session pings the first outlet. If that outlet is ejected, the session is
+        // eventually recreated with the outlets which are still reachable
+        Ok(ReplacerOutcome {
+            ping_route,
+            kind: ReplacerOutputKind::Inlet(CurrentInletStatus {
+                worker: inlet_address,
+                route,
+                connection_status: ConnectionStatus::Up,
+            }),
+        })
+    }
+
+    /// Routes to the outlets currently used by a load balanced inlet
+    fn balanced_routes(&self) -> Vec<Route> {
+        self.balanced_outlets
+            .iter()
+            .map(|outlet| outlet.route.clone())
+            .collect()
+    }
+
+    /// Eject the outlets of a load balanced inlet which don't answer the health checks anymore,
+    /// and use again the outlets which are reachable again.
+    /// Return true if the inlet must be recreated because no outlet is reachable
+    async fn check_balanced_outlets(&mut self) -> bool {
+        if let Some(last_check) = self.last_health_check {
+            if last_check.elapsed() < HEALTH_CHECK_INTERVAL {
+                return false;
+            }
+        }
+        self.last_health_check = Some(Instant::now());
+        let inlet = match &self.inlet {
+            Some(inlet) => inlet.clone(),
+            None => return false,
+        };
+        let outlet_addrs = self.outlet_addrs();
+
+        let mut changed = false;
+        for outlet in std::mem::take(&mut self.balanced_outlets) {
+            if self.is_healthy(&outlet.connection).await {
+                self.balanced_outlets.push(outlet);
+                continue;
+            }
+            warn!(
+                outlet_addr = %outlet_addrs[outlet.index],
+                "ejecting an unresponsive outlet from a load balanced tcp inlet"
+            );
+            if let Err(err) = outlet
+                .connection
+                .close(&self.context, &self.node_manager)
+                .await
+            {
+                debug!(?err, "Failed to close the connection to an ejected outlet");
+            }
+            changed = true;
+        }
+
+        for (index, outlet_addr) in outlet_addrs.iter().enumerate() {
+            if self
+                .balanced_outlets
+                .iter()
+                .any(|outlet| outlet.index == index)
+            {
+                continue;
+            }
+            if let Ok(Ok((connection, route))) =
+                timeout(MAX_CONNECT_TIME, self.connect_outlet(outlet_addr)).await
+            {
+                info!(%outlet_addr, "an outlet of a load balanced tcp inlet is reachable again");
+                self.balanced_outlets.push(BalancedOutlet {
+                    index,
+                    connection,
+                    route,
+                });
+                changed = true;
+            }
+        }
+        self.balanced_outlets.sort_by_key(|outlet| outlet.index);
+
+        if self.balanced_outlets.is_empty() {
+            return true;
+        }
+        if changed {
+            if let Err(err) = inlet.update_routes(self.balanced_routes()) {
+                warn!(%err, "Failed to update the routes of a load balanced tcp inlet");
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Send a health check to the node of an outlet, through an existing connection
+    async fn is_healthy(&self, connection: &Connection) -> bool {
+        let echo_route = route![connection.transport_route(), DefaultAddress::ECHO_SERVICE];
+        let options = MessageSendReceiveOptions::new().with_timeout(HEALTH_CHECK_TIMEOUT);
+        self.context
+            .send_and_receive_extended::<Vec<u8>>(echo_route, b"health check".to_vec(), options)
+            .await
+            .is_ok()
+    }
+}
+
 #[async_trait]
 impl SessionReplacer for InletSessionReplacer {
     async fn create(&mut self) -> std::result::Result<ReplacerOutcome, ockam_core::Error> {
@@ -610,94 +1079,86 @@ impl SessionReplacer for InletSessionReplacer {
         // possible that there is just a single secure channel used to go directly
         // to another node.
 
-        self.close().await;
-        debug!(%self.outlet_addr, "creating new tcp inlet");
-
+        self.release().await;
         let (incoming_ac, outgoing_ac) = self.access_control().await?;
 
-        // The future that recreates the inlet:
-        let future = async {
-            let connection = self
-                .node_manager
-                .make_connection(
-                    self.context.clone(),
-                    &self.outlet_addr,
-                    self.secure_channel_identifier
-                        .clone()
-                        .unwrap_or(self.node_manager.identifier()),
-                    self.authorized.clone(),
-                    Some(self.wait_for_outlet_duration),
-                )
-                .await?;
-
-            let connection_route = connection.route()?;
-
-            //we expect a fully normalized MultiAddr
-            let normalized_route = route![
-                self.prefix_route.clone(),
-                connection_route,
-                self.suffix_route.clone()
-            ];
-            let options = TcpInletOptions::new()
-                .with_incoming_access_control(incoming_ac)
-                .with_outgoing_access_control(outgoing_ac);
-
-            let options = if self.enable_udp_puncture() && self.disable_tcp_fallback {
-                options.paused()
-            } else {
-                options
-            };
+        if self.load_balancing.is_some() {
+            return self.create_load_balanced(incoming_ac, outgoing_ac).await;
+        }
 
-            // TODO: Instead just update the route in the existing inlet
-            // Finally, attempt to create a new inlet using the new route:
-            let inlet = self
-                .node_manager
-                .tcp_transport
-                .create_inlet(self.listen_addr.clone(), normalized_route.clone(), options)
-                .await?
-                .clone();
-            let inlet_address = inlet.processor_address().clone();
-            let inlet = Arc::new(inlet);
-            self.inlet = Some(inlet.clone());
-
-            if self.enable_udp_puncture() {
-                info!("Spawning UDP puncture future");
-                self.spawn_udp_puncture(&connection, inlet, self.disable_tcp_fallback)
-                    .await?;
-                info!("Spawned UDP puncture future");
-            }
-
-            Ok(ReplacerOutcome {
-                ping_route: connection.transport_route(),
-                kind: ReplacerOutputKind::Inlet(CurrentInletStatus {
-                    worker: inlet_address,
-                    route: normalized_route,
-                    connection_status: ConnectionStatus::Up,
-                }),
-            })
-        };
+        // The routes are tried in order of priority, until one of them works
+        let mut last_error = ApiError::core("no route to the outlet");
+        for (index, outlet_addr) in self.outlet_addrs().into_iter().enumerate() {
+            debug!(%outlet_addr, "creating new tcp inlet");
+
+            // Each attempt is given some limited time to succeed.
+            let future = self.create_with_outlet_addr(
+                &outlet_addr,
+                incoming_ac.clone(),
+                outgoing_ac.clone(),
+            );
+            match timeout(MAX_RECOVERY_TIME, future).await {
+                Err(_) => {
+                    warn!(%outlet_addr, "timeout creating new tcp inlet");
+                    last_error = ApiError::core("timeout");
+                }
+                Ok(Err(e)) => {
+                    warn!(%outlet_addr, err = %e, "error creating new tcp inlet");
+                    last_error = e;
+                }
+                Ok(Ok(outcome)) => {
+                    if index > 0 {
+                        info!(%outlet_addr, "the tcp inlet is using a fallback route");
+                    }
+                    self.active_outlet_addr = index;
+                    return Ok(outcome);
+                }
+            }
+            // Release what was created by the failed attempt before trying the next route
+            self.release().await;
+        }
+        Err(last_error)
+    }
 
-        // The above future is given some limited time to succeed.
-        match timeout(MAX_RECOVERY_TIME, future).await {
-            Err(_) => {
-                warn!(%self.outlet_addr, "timeout creating new tcp inlet");
-                Err(ApiError::core("timeout"))
+    async fn should_be_replaced(&mut self) -> bool {
+        if self.load_balancing.is_some() {
+            return self.check_balanced_outlets().await;
+        }
+        if self.active_outlet_addr == 0 {
+            return false;
+        }
+        if let Some(last_check) = self.last_failback_check {
+            if last_check.elapsed() < FAILBACK_CHECK_INTERVAL {
+                return false;
             }
-            Ok(Err(e)) => {
-                warn!(%self.outlet_addr, err = %e, "error creating new tcp inlet");
-                Err(e)
+        }
+        self.last_failback_check = Some(Instant::now());
+
+        // Check if a route with a higher priority than the active one is reachable again
+        let outlet_addrs = self.outlet_addrs();
+        for outlet_addr in outlet_addrs.iter().take(self.active_outlet_addr) {
+            let connection = self.node_manager.make_connection(
+                self.context.clone(),
+                outlet_addr,
+                self.secure_channel_identifier
+                    .clone()
+                    .unwrap_or(self.node_manager.identifier()),
+                self.authorized.clone(),
+                Some(self.wait_for_outlet_duration),
+            );
+            if let Ok(Ok(connection)) = timeout(MAX_CONNECT_TIME, connection).await {
+                if let Err(err) = connection.close(&self.context, &self.node_manager).await {
+                    debug!(?err, "Failed to close the connection checking a route");
+                }
+                info!(%outlet_addr, "a tcp inlet route with a higher priority is reachable");
+                return true;
             }
-            Ok(Ok(route)) => Ok(route),
         }
+        false
     }
 
     async fn close(&mut self) {
-        if let Some(connection) = self.connection.take() {
-            let result = connection.close(&self.context, &self.node_manager).await;
-            if let Err(err) = result {
-                error!(?err, "Failed to close connection");
-            }
-        }
+        self.close_connections().await;
 
         if let Some(inlet) = self.inlet.take() {
             // The previous inlet worker needs to be stopped:
@@ -718,6 +1179,37 @@ impl SessionReplacer for InletSessionReplacer {
     }
 }
 
+impl InletSessionReplacer {
+    /// Release the connections to the outlets before creating new ones. The inlet is kept if
+    /// its connections can be resumed
+    async fn release(&mut self) {
+        if self.resumption.is_some() {
+            self.close_connections().await;
+        } else {
+            self.close().await;
+        }
+    }
+
+    async fn close_connections(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            let result = connection.close(&self.context, &self.node_manager).await;
+            if let Err(err) = result {
+                error!(?err, "Failed to close connection");
+            }
+        }
+
+        for outlet in std::mem::take(&mut self.balanced_outlets) {
+            let result = outlet
+                .connection
+                .close(&self.context, &self.node_manager)
+                .await;
+            if let Err(err) = result {
+                error!(?err, "Failed to close connection");
+            }
+        }
+    }
+}
+
 #[async_trait]
 pub trait Inlets {
     #[allow(clippy::too_many_arguments)]
@@ -734,10 +1226,17 @@ pub trait Inlets {
         secure_channel_identifier: &Option<Identifier>,
         enable_udp_puncture: bool,
         disable_tcp_fallback: bool,
+        options: &InletServiceOptions,
     ) -> miette::Result<Reply<InletStatus>>;
 
     async fn show_inlet(&self, ctx: &Context, alias: &str) -> miette::Result<Reply<InletStatus>>;
 
+    async fn get_inlet_stats(
+        &self,
+        ctx: &Context,
+        alias: &str,
+    ) -> miette::Result<Reply<InletStats>>;
+
     async fn delete_inlet(&self, ctx: &Context, inlet_alias: &str) -> miette::Result<Reply<()>>;
 }
 
@@ -756,6 +1255,7 @@ impl Inlets for BackgroundNodeClient {
         secure_channel_identifier: &Option<Identifier>,
         enable_udp_puncture: bool,
         disable_tcp_fallback: bool,
+        options: &InletServiceOptions,
     ) -> miette::Result<Reply<InletStatus>> {
         let request = {
             let via_project = outlet_addr.matches(0, &[ProjectProto::CODE.into()]);
@@ -790,6 +1290,34 @@ impl Inlets for BackgroundNodeClient {
                 payload.set_secure_channel_identifier(identifier.clone())
             }
             payload.set_wait_ms(wait_for_outlet_timeout.as_millis() as u64);
+            payload.set_socket_options(options.socket_options.clone());
+            if let Some(compression) = options.compression {
+                payload.set_compression(compression);
+            }
+            if let Some(rate_limit) = options.rate_limit {
+                payload.set_rate_limit(rate_limit);
+            }
+            if !options.fallback_outlet_addrs.is_empty() {
+                payload.set_fallback_outlet_addrs(options.fallback_outlet_addrs.clone());
+            }
+            if let Some(http_rewrite) = options.http_rewrite.clone() {
+                payload.set_http_rewrite(http_rewrite);
+            }
+            if let Some(tls_certificate) = options.tls_certificate.clone() {
+                payload.set_tls_certificate(tls_certificate);
+            }
+            if let Some(limits) = options.limits {
+                payload.set_limits(limits);
+            }
+            if let Some(load_balancing) = options.load_balancing {
+                payload.set_load_balancing(load_balancing);
+            }
+            if options.proxy_protocol {
+                payload.set_proxy_protocol(options.proxy_protocol);
+            }
+            if let Some(resumption) = options.resumption {
+                payload.set_resumption(resumption);
+            }
             Request::post("/node/inlet").body(payload)
         };
         self.ask_and_get_reply(ctx, request).await
@@ -800,6 +1328,15 @@ impl Inlets for BackgroundNodeClient {
         self.ask_and_get_reply(ctx, request).await
     }
 
+    async fn get_inlet_stats(
+        &self,
+        ctx: &Context,
+        alias: &str,
+    ) -> miette::Result<Reply<InletStats>> {
+        let request = Request::get(format!("/node/inlet/{alias}/stats"));
+        self.ask_and_get_reply(ctx, request).await
+    }
+
     async fn delete_inlet(&self, ctx: &Context, inlet_alias: &str) -> miette::Result<Reply<()>> {
         let request = Request::delete(format!("/node/inlet/{inlet_alias}"));
         self.tell_and_get_reply(ctx, request).await
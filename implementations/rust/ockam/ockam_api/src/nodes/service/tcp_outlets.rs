@@ -1,19 +1,50 @@
-use ockam::tcp::TcpOutletOptions;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use ockam::identity::IdentitySecureChannelLocalInfo;
+use ockam::tcp::{
+    PortalCompression, PortalPeerIdentifier, PortalTlsVerification, TcpEgressAllowList,
+    TcpOutletOptions, TcpPortalRateLimit, TcpSocketOptions,
+};
 use ockam::transport::HostnamePort;
 use ockam::{Address, Result};
 use ockam_abac::{Action, PolicyExpression, Resource, ResourceType};
 use ockam_core::api::{Error, Request, RequestHeader, Response};
-use ockam_core::async_trait;
 use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{async_trait, LocalInfo};
 use ockam_node::Context;
+use tokio::net::TcpStream;
 
-use crate::nodes::models::portal::{CreateOutlet, OutletAccessControl, OutletStatus};
+use crate::nodes::models::portal::{
+    CreateOutlet, OutletAccessControl, OutletPingStatus, OutletStatus, PingOutlet,
+};
 use crate::nodes::registry::OutletInfo;
 use crate::nodes::service::default_address::DefaultAddress;
 use crate::nodes::BackgroundNodeClient;
 
 use super::{NodeManager, NodeManagerWorker};
 
+/// Optional settings of a TCP outlet, the default values keep the outlet behaviour unchanged
+#[derive(Clone, Debug, Default)]
+pub struct OutletServiceOptions {
+    /// Socket options of the connections opened by the outlet
+    pub socket_options: TcpSocketOptions,
+    /// Compression algorithms accepted when an inlet proposes one of them.
+    /// Unlike an inlet, which proposes a single algorithm, an outlet can accept several ones
+    pub compressions: Vec<PortalCompression>,
+    /// Maximum throughput of each connection, unlimited when not set
+    pub rate_limit: Option<TcpPortalRateLimit>,
+    /// Verification of the certificate of the TLS server when the outlet originates TLS
+    pub tls_verification: Option<PortalTlsVerification>,
+    /// Destinations which can be reached by the outlet, any destination when not set
+    pub egress_allow_list: Option<TcpEgressAllowList>,
+    /// Send a PROXY protocol v2 header with the address of the inlet client to the target
+    pub proxy_protocol: bool,
+    /// Grace period during which the connections to the target are kept open when the route
+    /// to an inlet breaks, the connections are closed right away when not set
+    pub resumption: Option<Duration>,
+}
+
 impl NodeManagerWorker {
     #[instrument(skip_all)]
     pub(super) async fn create_outlet(
@@ -27,7 +58,23 @@ impl NodeManagerWorker {
             reachable_from_default_secure_channel,
             policy_expression,
             tls,
+            socket_options,
+            compressions,
+            rate_limit,
+            tls_verification,
+            egress_allow_list,
+            proxy_protocol,
+            resumption,
         } = create_outlet;
+        let options = OutletServiceOptions {
+            socket_options: socket_options.unwrap_or_default(),
+            compressions: compressions.unwrap_or_default(),
+            rate_limit,
+            tls_verification,
+            egress_allow_list,
+            proxy_protocol: proxy_protocol.unwrap_or(false),
+            resumption,
+        };
 
         match self
             .node_manager
@@ -38,6 +85,7 @@ impl NodeManagerWorker {
                 worker_addr,
                 reachable_from_default_secure_channel,
                 OutletAccessControl::WithPolicyExpression(policy_expression),
+                options,
             )
             .await
         {
@@ -46,6 +94,21 @@ impl NodeManagerWorker {
         }
     }
 
+    pub(super) async fn update_outlet_egress_allow_list(
+        &self,
+        worker_addr: &Address,
+        egress_allow_list: TcpEgressAllowList,
+    ) -> Result<Response<()>, Response<Error>> {
+        match self
+            .node_manager
+            .update_outlet_egress_allow_list(worker_addr, egress_allow_list)
+            .await
+        {
+            Ok(()) => Ok(Response::ok()),
+            Err(e) => Err(Response::bad_request_no_request(&format!("{e:?}"))),
+        }
+    }
+
     pub(super) async fn delete_outlet(
         &self,
         worker_addr: &Address,
@@ -77,6 +140,23 @@ impl NodeManagerWorker {
         }
     }
 
+    pub(super) async fn ping_outlet(
+        &self,
+        worker_addr: &Address,
+        ping_outlet: PingOutlet,
+    ) -> Result<Response<OutletPingStatus>, Response<Error>> {
+        match self
+            .node_manager
+            .ping_outlet(worker_addr, ping_outlet.timeout)
+            .await
+        {
+            Some(status) => Ok(Response::ok().body(status)),
+            None => Err(Response::not_found_no_request(&format!(
+                "Outlet with address {worker_addr} not found"
+            ))),
+        }
+    }
+
     pub(super) async fn get_outlets(&self, req: &RequestHeader) -> Response<Vec<OutletStatus>> {
         Response::ok()
             .with_headers(req)
@@ -96,7 +176,17 @@ impl NodeManager {
         worker_addr: Option<Address>,
         reachable_from_default_secure_channel: bool,
         access_control: OutletAccessControl,
+        options: OutletServiceOptions,
     ) -> Result<OutletStatus> {
+        let OutletServiceOptions {
+            socket_options,
+            compressions,
+            rate_limit,
+            tls_verification,
+            egress_allow_list,
+            proxy_protocol,
+            resumption,
+        } = options;
         let worker_addr = self
             .registry
             .outlets
@@ -120,6 +210,11 @@ impl NodeManager {
             ));
         }
 
+        if let Some(egress_allow_list) = &egress_allow_list {
+            egress_allow_list.validate()?;
+        }
+        let egress_allow_list = egress_allow_list.map(|list| Arc::new(RwLock::new(list)));
+
         let (incoming_ac, outgoing_ac) = match access_control {
             OutletAccessControl::AccessControl((incoming_ac, outgoing_ac)) => {
                 (incoming_ac, outgoing_ac)
@@ -140,7 +235,31 @@ impl NodeManager {
             let options = TcpOutletOptions::new()
                 .with_incoming_access_control(incoming_ac)
                 .with_outgoing_access_control(outgoing_ac)
-                .with_tls(tls);
+                .with_tls(tls)
+                .with_socket_options(socket_options)
+                .with_proxy_protocol(proxy_protocol)
+                .with_peer_identifier(SecureChannelPeerIdentifier);
+            let options = compressions
+                .into_iter()
+                .fold(options, |options, c| options.with_compression(c));
+            let options = match rate_limit {
+                Some(rate_limit) => options.with_rate_limit(rate_limit),
+                None => options,
+            };
+            let options = match tls_verification {
+                Some(tls_verification) => options.with_tls_verification(tls_verification),
+                None => options,
+            };
+            let options = match &egress_allow_list {
+                Some(egress_allow_list) => {
+                    options.with_egress_allow_list(egress_allow_list.clone())
+                }
+                None => options,
+            };
+            let options = match resumption {
+                Some(grace_period) => options.with_resumption(grace_period),
+                None => options,
+            };
             let options = if self.project_authority().is_none() {
                 options.as_consumer(&self.api_transport_flow_control_id)
             } else {
@@ -174,7 +293,8 @@ impl NodeManager {
                     .outlets
                     .insert(
                         worker_addr.clone(),
-                        OutletInfo::new(&socket_addr, Some(&worker_addr)),
+                        OutletInfo::new(&socket_addr, Some(&worker_addr))
+                            .with_egress_allow_list(egress_allow_list),
                     )
                     .await;
 
@@ -194,6 +314,41 @@ impl NodeManager {
         })
     }
 
+    /// Replace the destinations allowed for an outlet.
+    /// The new allow-list applies to the connections opened after the update
+    pub async fn update_outlet_egress_allow_list(
+        &self,
+        worker_addr: &Address,
+        egress_allow_list: TcpEgressAllowList,
+    ) -> Result<()> {
+        info!(%worker_addr, "Handling request to update the egress allow-list of an outlet");
+        egress_allow_list.validate()?;
+        let outlet = self
+            .registry
+            .outlets
+            .get(worker_addr)
+            .await
+            .ok_or_else(|| {
+                ockam_core::Error::new(
+                    Origin::Node,
+                    Kind::NotFound,
+                    format!("Outlet with address {worker_addr} not found"),
+                )
+            })?;
+        match outlet.egress_allow_list {
+            Some(current) => {
+                *current.write().unwrap() = egress_allow_list;
+                Ok(())
+            }
+            None => {
+                let message = format!(
+                    "Outlet with address {worker_addr} was created without an egress allow-list"
+                );
+                Err(ockam_core::Error::new(Origin::Node, Kind::Invalid, message))
+            }
+        }
+    }
+
     pub async fn delete_outlet(&self, worker_addr: &Address) -> Result<Option<OutletInfo>> {
         info!(%worker_addr, "Handling request to delete outlet portal");
         if let Some(deleted_outlet) = self.registry.outlets.remove(worker_addr).await {
@@ -221,6 +376,37 @@ impl NodeManager {
         }
     }
 
+    /// Open a TCP connection to the target of an outlet, to check that it is reachable from this node.
+    /// Return None if the outlet doesn't exist.
+    pub async fn ping_outlet(
+        &self,
+        worker_addr: &Address,
+        timeout: Duration,
+    ) -> Option<OutletPingStatus> {
+        info!(%worker_addr, "Handling request to ping outlet portal");
+        let outlet = self.registry.outlets.get(worker_addr).await?;
+        let start = Instant::now();
+        let (latency, error) =
+            match tokio::time::timeout(timeout, TcpStream::connect(outlet.socket_addr)).await {
+                Ok(Ok(_)) => (Some(start.elapsed()), None),
+                Ok(Err(e)) => (None, Some(e.to_string())),
+                Err(_) => (
+                    None,
+                    Some(format!(
+                        "the connection timed out after {}ms",
+                        timeout.as_millis()
+                    )),
+                ),
+            };
+        debug!(%worker_addr, socket_addr = %outlet.socket_addr, ?latency, ?error, "Outlet pinged");
+        Some(OutletPingStatus {
+            worker_addr: outlet.worker_addr,
+            socket_addr: outlet.socket_addr,
+            latency,
+            error,
+        })
+    }
+
     pub(super) async fn show_outlet(&self, worker_addr: &Address) -> Option<OutletStatus> {
         info!(%worker_addr, "Handling request to show outlet portal");
         if let Some(outlet_to_show) = self.registry.outlets.get(worker_addr).await {
@@ -237,6 +423,19 @@ impl NodeManager {
     }
 }
 
+/// Identify the inlet of a connection with the identifier of the other side of the secure
+/// channel used to reach the outlet
+#[derive(Debug)]
+struct SecureChannelPeerIdentifier;
+
+impl PortalPeerIdentifier for SecureChannelPeerIdentifier {
+    fn peer_identifier(&self, local_info: &[LocalInfo]) -> Option<String> {
+        IdentitySecureChannelLocalInfo::find_info_from_list(local_info)
+            .ok()
+            .map(|info| info.their_identity_id().to_string())
+    }
+}
+
 #[async_trait]
 pub trait Outlets {
     async fn create_outlet(
@@ -246,7 +445,22 @@ pub trait Outlets {
         tls: bool,
         from: Option<&Address>,
         policy_expression: Option<PolicyExpression>,
+        options: &OutletServiceOptions,
     ) -> miette::Result<OutletStatus>;
+
+    async fn ping_outlet(
+        &self,
+        ctx: &Context,
+        worker_addr: &Address,
+        timeout: Duration,
+    ) -> miette::Result<OutletPingStatus>;
+
+    async fn update_outlet_egress_allow_list(
+        &self,
+        ctx: &Context,
+        worker_addr: &Address,
+        egress_allow_list: TcpEgressAllowList,
+    ) -> miette::Result<()>;
 }
 
 #[async_trait]
@@ -259,13 +473,59 @@ impl Outlets for BackgroundNodeClient {
         tls: bool,
         from: Option<&Address>,
         policy_expression: Option<PolicyExpression>,
+        options: &OutletServiceOptions,
     ) -> miette::Result<OutletStatus> {
         let mut payload = CreateOutlet::new(to, tls, from.cloned(), true);
         if let Some(policy_expression) = policy_expression {
             payload.set_policy_expression(policy_expression);
         }
+        payload.set_socket_options(options.socket_options.clone());
+        if !options.compressions.is_empty() {
+            payload.set_compressions(options.compressions.clone());
+        }
+        if let Some(rate_limit) = options.rate_limit {
+            payload.set_rate_limit(rate_limit);
+        }
+        if let Some(tls_verification) = options.tls_verification.clone() {
+            payload.set_tls_verification(tls_verification);
+        }
+        if let Some(egress_allow_list) = options.egress_allow_list.clone() {
+            payload.set_egress_allow_list(egress_allow_list);
+        }
+        if options.proxy_protocol {
+            payload.set_proxy_protocol(options.proxy_protocol);
+        }
+        if let Some(resumption) = options.resumption {
+            payload.set_resumption(resumption);
+        }
         let req = Request::post("/node/outlet").body(payload);
         let result: OutletStatus = self.ask(ctx, req).await?;
         Ok(result)
     }
+
+    #[instrument(skip_all, fields(worker_addr = % worker_addr))]
+    async fn ping_outlet(
+        &self,
+        ctx: &Context,
+        worker_addr: &Address,
+        timeout: Duration,
+    ) -> miette::Result<OutletPingStatus> {
+        let req = Request::post(format!("/node/outlet/{}/ping", worker_addr.address()))
+            .body(PingOutlet::new(timeout));
+        // leave some time for the node to reply once the connection has timed out
+        self.ask_with_timeout(ctx, req, timeout + Duration::from_secs(10))
+            .await
+    }
+
+    #[instrument(skip_all, fields(worker_addr = % worker_addr))]
+    async fn update_outlet_egress_allow_list(
+        &self,
+        ctx: &Context,
+        worker_addr: &Address,
+        egress_allow_list: TcpEgressAllowList,
+    ) -> miette::Result<()> {
+        let req = Request::put(format!("/node/outlet/{}/egress", worker_addr.address()))
+            .body(egress_allow_list);
+        self.tell(ctx, req).await
+    }
 }
@@ -1,6 +1,7 @@
 use std::net::SocketAddr;
+use std::str::FromStr;
 
-use ockam::tcp::{TcpConnectionOptions, TcpListenerOptions};
+use ockam::tcp::{TcpConnectionOptions, TcpListenerOptions, TcpProxy};
 use ockam::Result;
 use ockam_core::api::{Error, RequestHeader, Response};
 use ockam_node::Context;
@@ -42,9 +43,13 @@ impl NodeManager {
     async fn create_tcp_connection(
         &self,
         address: String,
+        proxy: Option<String>,
         ctx: &Context,
     ) -> Result<TransportStatus> {
-        let options = TcpConnectionOptions::new();
+        let mut options = TcpConnectionOptions::new();
+        if let Some(proxy) = proxy {
+            options = options.with_proxy(TcpProxy::from_str(&proxy)?);
+        }
 
         // Add all Hop workers as consumers for Demo purposes
         // Production nodes should not run any Hop workers
@@ -150,11 +155,11 @@ impl NodeManagerWorker {
         ctx: &Context,
         create: CreateTcpConnection,
     ) -> Result<Response<TransportStatus>, Response<Error>> {
-        let CreateTcpConnection { addr, .. } = create;
+        let CreateTcpConnection { addr, proxy } = create;
         info!("Handling request to create a new TCP connection: {addr}");
 
         self.node_manager
-            .create_tcp_connection(addr.to_string(), ctx)
+            .create_tcp_connection(addr.to_string(), proxy, ctx)
             .await
             .map(|status| Response::ok().body(status))
             .map_err(|msg| {
@@ -0,0 +1,468 @@
+use std::sync::Arc;
+
+use ockam::identity::Identifier;
+use ockam::{Address, Result};
+use ockam_abac::{Action, PolicyExpression, Resource, ResourceType};
+use ockam_core::api::{Error, Request, Response};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{async_trait, AsyncTryClone};
+use ockam_multiaddr::proto::Project as ProjectProto;
+use ockam_multiaddr::MultiAddr;
+use ockam_node::Context;
+
+use crate::nodes::models::tun_portal::{
+    CreateTunInlet, CreateTunOutlet, TunInletStatus, TunInterface, TunOutletStatus,
+};
+use crate::nodes::registry::{TunInletInfo, TunOutletInfo};
+use crate::nodes::service::default_address::DefaultAddress;
+use crate::nodes::BackgroundNodeClient;
+use crate::tun::{start_tun_inlet, start_tun_outlet};
+
+use super::{NodeManager, NodeManagerWorker};
+
+impl NodeManagerWorker {
+    #[instrument(skip_all)]
+    pub(super) async fn create_tun_inlet(
+        &self,
+        ctx: &Context,
+        create_inlet: CreateTunInlet,
+    ) -> Result<Response<TunInletStatus>, Response<Error>> {
+        let CreateTunInlet {
+            interface,
+            outlet_addr,
+            alias,
+            authorized,
+            policy_expression,
+        } = create_inlet;
+        match self
+            .node_manager
+            .create_tun_inlet(
+                ctx,
+                interface,
+                outlet_addr,
+                alias,
+                authorized,
+                policy_expression,
+            )
+            .await
+        {
+            Ok(status) => Ok(Response::ok().body(status)),
+            Err(e) => Err(Response::bad_request_no_request(&format!("{e:?}"))),
+        }
+    }
+
+    pub(super) async fn delete_tun_inlet(
+        &self,
+        ctx: &Context,
+        alias: &str,
+    ) -> Result<Response<TunInletStatus>, Response<Error>> {
+        match self.node_manager.delete_tun_inlet(ctx, alias).await {
+            Ok(status) => Ok(Response::ok().body(status)),
+            Err(e) => Err(Response::bad_request_no_request(&format!("{e:?}"))),
+        }
+    }
+
+    pub(super) async fn show_tun_inlet(
+        &self,
+        alias: &str,
+    ) -> Result<Response<TunInletStatus>, Response<Error>> {
+        match self.node_manager.show_tun_inlet(alias).await {
+            Some(inlet) => Ok(Response::ok().body(inlet)),
+            None => Err(Response::not_found_no_request(&format!(
+                "TUN Inlet with alias {alias} not found"
+            ))),
+        }
+    }
+
+    pub(super) async fn get_tun_inlets(
+        &self,
+    ) -> Result<Response<Vec<TunInletStatus>>, Response<Error>> {
+        Ok(Response::ok().body(self.node_manager.list_tun_inlets().await))
+    }
+
+    #[instrument(skip_all)]
+    pub(super) async fn create_tun_outlet(
+        &self,
+        ctx: &Context,
+        create_outlet: CreateTunOutlet,
+    ) -> Result<Response<TunOutletStatus>, Response<Error>> {
+        let CreateTunOutlet {
+            interface,
+            worker_addr,
+            policy_expression,
+        } = create_outlet;
+        match self
+            .node_manager
+            .create_tun_outlet(ctx, interface, worker_addr, policy_expression)
+            .await
+        {
+            Ok(status) => Ok(Response::ok().body(status)),
+            Err(e) => Err(Response::bad_request_no_request(&format!("{e:?}"))),
+        }
+    }
+
+    pub(super) async fn delete_tun_outlet(
+        &self,
+        ctx: &Context,
+        worker_addr: &Address,
+    ) -> Result<Response<TunOutletStatus>, Response<Error>> {
+        match self.node_manager.delete_tun_outlet(ctx, worker_addr).await {
+            Ok(status) => Ok(Response::ok().body(status)),
+            Err(e) => Err(Response::bad_request_no_request(&format!("{e:?}"))),
+        }
+    }
+
+    pub(super) async fn show_tun_outlet(
+        &self,
+        worker_addr: &Address,
+    ) -> Result<Response<TunOutletStatus>, Response<Error>> {
+        match self.node_manager.show_tun_outlet(worker_addr).await {
+            Some(outlet) => Ok(Response::ok().body(outlet)),
+            None => Err(Response::not_found_no_request(&format!(
+                "TUN Outlet with address {worker_addr} not found"
+            ))),
+        }
+    }
+
+    pub(super) async fn get_tun_outlets(
+        &self,
+    ) -> Result<Response<Vec<TunOutletStatus>>, Response<Error>> {
+        Ok(Response::ok().body(self.node_manager.list_tun_outlets().await))
+    }
+}
+
+impl NodeManager {
+    #[instrument(skip_all)]
+    pub async fn create_tun_inlet(
+        &self,
+        ctx: &Context,
+        interface: TunInterface,
+        outlet_addr: MultiAddr,
+        alias: String,
+        authorized: Option<Identifier>,
+        policy_expression: Option<PolicyExpression>,
+    ) -> Result<TunInletStatus> {
+        info!(
+            address = %interface.address, %outlet_addr, %alias,
+            "Handling request to create a TUN inlet"
+        );
+
+        if self.registry.tun_inlets.contains_key(&alias).await {
+            let message = format!("A TUN inlet with alias '{alias}' already exists");
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::AlreadyExists,
+                message,
+            ));
+        }
+
+        // Use the authority of the project when the outlet is reached via a project
+        let project = outlet_addr
+            .first()
+            .and_then(|p| p.cast::<ProjectProto>().map(|p| p.to_string()));
+        let authority = match project {
+            Some(project) => self
+                .cli_state
+                .projects()
+                .get_project_by_name(&project)
+                .await
+                .ok()
+                .map(|project| project.authority_identifier())
+                .transpose()?,
+            None => None,
+        }
+        .or(self.project_authority());
+
+        let (incoming_ac, outgoing_ac) = self
+            .access_control(
+                ctx,
+                authority,
+                Resource::new(alias.clone(), ResourceType::TunInlet),
+                Action::HandleMessage,
+                policy_expression,
+            )
+            .await?;
+
+        let connection = self
+            .make_connection(
+                Arc::new(ctx.async_try_clone().await?),
+                &outlet_addr,
+                self.identifier(),
+                authorized,
+                None,
+            )
+            .await?;
+        let outlet_route = connection.route()?;
+
+        let portal = match start_tun_inlet(
+            ctx,
+            &interface,
+            outlet_route.clone(),
+            incoming_ac,
+            outgoing_ac,
+        )
+        .await
+        {
+            Ok(portal) => portal,
+            Err(e) => {
+                if let Err(err) = connection.close(ctx, self).await {
+                    debug!(%err, "Failed to close the connection of a TUN inlet");
+                }
+                return Err(e);
+            }
+        };
+
+        let info = TunInletInfo {
+            interface,
+            outlet_addr,
+            outlet_route,
+            portal,
+            connection,
+        };
+        let status = Self::tun_inlet_status(&alias, &info);
+        self.registry.tun_inlets.insert(alias, info).await;
+
+        Ok(status)
+    }
+
+    pub async fn delete_tun_inlet(&self, ctx: &Context, alias: &str) -> Result<TunInletStatus> {
+        info!(%alias, "Handling request to delete a TUN inlet");
+        let Some(inlet) = self.registry.tun_inlets.remove(alias).await else {
+            let message = format!("TUN Inlet with alias {alias} not found");
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::NotFound,
+                message,
+            ));
+        };
+
+        if let Err(err) = inlet.portal.stop(ctx).await {
+            warn!(%alias, %err, "Failed to stop the TUN inlet processor");
+        }
+        inlet.connection.close(ctx, self).await?;
+        self.resources().delete_resource(&alias.into()).await?;
+
+        Ok(Self::tun_inlet_status(alias, &inlet))
+    }
+
+    pub async fn show_tun_inlet(&self, alias: &str) -> Option<TunInletStatus> {
+        let inlet = self.registry.tun_inlets.get(alias).await?;
+        Some(Self::tun_inlet_status(alias, &inlet))
+    }
+
+    pub async fn list_tun_inlets(&self) -> Vec<TunInletStatus> {
+        self.registry
+            .tun_inlets
+            .entries()
+            .await
+            .iter()
+            .map(|(alias, inlet)| Self::tun_inlet_status(alias, inlet))
+            .collect()
+    }
+
+    fn tun_inlet_status(alias: &str, inlet: &TunInletInfo) -> TunInletStatus {
+        TunInletStatus {
+            alias: alias.to_string(),
+            device: inlet.portal.device_name().to_string(),
+            interface: inlet.interface.clone(),
+            outlet_addr: inlet.outlet_addr.to_string(),
+            outlet_route: Some(inlet.outlet_route.to_string()),
+        }
+    }
+
+    #[instrument(skip_all)]
+    pub async fn create_tun_outlet(
+        &self,
+        ctx: &Context,
+        interface: TunInterface,
+        worker_addr: Option<Address>,
+        policy_expression: Option<PolicyExpression>,
+    ) -> Result<TunOutletStatus> {
+        let worker_addr = worker_addr.unwrap_or_else(|| DefaultAddress::TUN_OUTLET_SERVICE.into());
+        info!(
+            address = %interface.address, %worker_addr,
+            "Handling request to create a TUN outlet"
+        );
+
+        if self.registry.tun_outlets.contains_key(&worker_addr).await {
+            let message = format!("A TUN outlet with address '{worker_addr}' already exists");
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::AlreadyExists,
+                message,
+            ));
+        }
+
+        let (incoming_ac, outgoing_ac) = self
+            .access_control(
+                ctx,
+                self.project_authority(),
+                Resource::new(worker_addr.address(), ResourceType::TunOutlet),
+                Action::HandleMessage,
+                policy_expression,
+            )
+            .await?;
+
+        let mut consumers = vec![];
+        if self.project_authority().is_none() {
+            consumers.push(self.api_transport_flow_control_id.clone());
+        }
+        // Accept messages from the default secure channel listener
+        if let Some(flow_control_id) = ctx
+            .flow_controls()
+            .get_flow_control_with_spawner(&DefaultAddress::SECURE_CHANNEL_LISTENER.into())
+        {
+            consumers.push(flow_control_id);
+        }
+
+        let portal = start_tun_outlet(
+            ctx,
+            worker_addr.clone(),
+            &interface,
+            &consumers,
+            incoming_ac,
+            outgoing_ac,
+        )
+        .await?;
+
+        let info = TunOutletInfo { interface, portal };
+        let status = Self::tun_outlet_status(&worker_addr, &info);
+        self.registry.tun_outlets.insert(worker_addr, info).await;
+
+        Ok(status)
+    }
+
+    pub async fn delete_tun_outlet(
+        &self,
+        ctx: &Context,
+        worker_addr: &Address,
+    ) -> Result<TunOutletStatus> {
+        info!(%worker_addr, "Handling request to delete a TUN outlet");
+        let Some(outlet) = self.registry.tun_outlets.remove(worker_addr).await else {
+            let message = format!("TUN Outlet with address {worker_addr} not found");
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::NotFound,
+                message,
+            ));
+        };
+
+        if let Err(err) = outlet.portal.stop(ctx).await {
+            warn!(%worker_addr, %err, "Failed to stop the TUN outlet processor");
+        }
+        self.resources()
+            .delete_resource(&worker_addr.address().into())
+            .await?;
+
+        Ok(Self::tun_outlet_status(worker_addr, &outlet))
+    }
+
+    pub async fn show_tun_outlet(&self, worker_addr: &Address) -> Option<TunOutletStatus> {
+        let outlet = self.registry.tun_outlets.get(worker_addr).await?;
+        Some(Self::tun_outlet_status(worker_addr, &outlet))
+    }
+
+    pub async fn list_tun_outlets(&self) -> Vec<TunOutletStatus> {
+        self.registry
+            .tun_outlets
+            .entries()
+            .await
+            .iter()
+            .map(|(worker_addr, outlet)| Self::tun_outlet_status(worker_addr, outlet))
+            .collect()
+    }
+
+    fn tun_outlet_status(worker_addr: &Address, outlet: &TunOutletInfo) -> TunOutletStatus {
+        TunOutletStatus {
+            worker_addr: worker_addr.clone(),
+            device: outlet.portal.device_name().to_string(),
+            interface: outlet.interface.clone(),
+        }
+    }
+}
+
+#[async_trait]
+pub trait TunPortals {
+    async fn create_tun_inlet(
+        &self,
+        ctx: &Context,
+        interface: TunInterface,
+        outlet_addr: &MultiAddr,
+        alias: &str,
+        authorized: Option<Identifier>,
+        policy_expression: Option<PolicyExpression>,
+    ) -> miette::Result<TunInletStatus>;
+
+    async fn delete_tun_inlet(&self, ctx: &Context, alias: &str) -> miette::Result<()>;
+
+    async fn create_tun_outlet(
+        &self,
+        ctx: &Context,
+        interface: TunInterface,
+        from: Option<&Address>,
+        policy_expression: Option<PolicyExpression>,
+    ) -> miette::Result<TunOutletStatus>;
+
+    async fn delete_tun_outlet(&self, ctx: &Context, worker_addr: &Address) -> miette::Result<()>;
+}
+
+#[async_trait]
+impl TunPortals for BackgroundNodeClient {
+    #[instrument(skip_all, fields(outlet_addr = %outlet_addr, alias = %alias))]
+    async fn create_tun_inlet(
+        &self,
+        ctx: &Context,
+        interface: TunInterface,
+        outlet_addr: &MultiAddr,
+        alias: &str,
+        authorized: Option<Identifier>,
+        policy_expression: Option<PolicyExpression>,
+    ) -> miette::Result<TunInletStatus> {
+        let mut payload = CreateTunInlet::new(
+            interface,
+            outlet_addr.clone(),
+            alias.to_string(),
+            authorized,
+        );
+        if let Some(policy_expression) = policy_expression {
+            payload.set_policy_expression(policy_expression);
+        }
+        self.ask(ctx, Request::post("/node/tun/inlet").body(payload))
+            .await
+    }
+
+    #[instrument(skip_all, fields(alias = %alias))]
+    async fn delete_tun_inlet(&self, ctx: &Context, alias: &str) -> miette::Result<()> {
+        let _: TunInletStatus = self
+            .ask(ctx, Request::delete(format!("/node/tun/inlet/{alias}")))
+            .await?;
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(from = ?from))]
+    async fn create_tun_outlet(
+        &self,
+        ctx: &Context,
+        interface: TunInterface,
+        from: Option<&Address>,
+        policy_expression: Option<PolicyExpression>,
+    ) -> miette::Result<TunOutletStatus> {
+        let mut payload = CreateTunOutlet::new(interface, from.cloned());
+        if let Some(policy_expression) = policy_expression {
+            payload.set_policy_expression(policy_expression);
+        }
+        self.ask(ctx, Request::post("/node/tun/outlet").body(payload))
+            .await
+    }
+
+    #[instrument(skip_all, fields(worker_addr = %worker_addr))]
+    async fn delete_tun_outlet(&self, ctx: &Context, worker_addr: &Address) -> miette::Result<()> {
+        let _: TunOutletStatus = self
+            .ask(
+                ctx,
+                Request::delete(format!("/node/tun/outlet/{}", worker_addr.address())),
+            )
+            .await?;
+        Ok(())
+    }
+}
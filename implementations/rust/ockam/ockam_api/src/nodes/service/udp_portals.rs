@@ -0,0 +1,511 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use ockam::identity::Identifier;
+use ockam::transport::HostnamePort;
+use ockam::udp::{UdpInletOptions, UdpOutletOptions, UdpTransport};
+use ockam::{Address, Result};
+use ockam_abac::{Action, PolicyExpression, Resource, ResourceType};
+use ockam_core::api::{Error, Request, Response};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{async_trait, AsyncTryClone};
+use ockam_multiaddr::proto::Project as ProjectProto;
+use ockam_multiaddr::MultiAddr;
+use ockam_node::Context;
+
+use crate::nodes::models::udp_portal::{
+    CreateUdpInlet, CreateUdpOutlet, UdpInletStatus, UdpOutletStatus,
+};
+use crate::nodes::registry::{UdpInletInfo, UdpOutletInfo};
+use crate::nodes::service::default_address::DefaultAddress;
+use crate::nodes::BackgroundNodeClient;
+
+use super::{NodeManager, NodeManagerWorker};
+
+impl NodeManagerWorker {
+    #[instrument(skip_all)]
+    pub(super) async fn create_udp_inlet(
+        &self,
+        ctx: &Context,
+        create_inlet: CreateUdpInlet,
+    ) -> Result<Response<UdpInletStatus>, Response<Error>> {
+        let CreateUdpInlet {
+            listen_addr,
+            outlet_addr,
+            alias,
+            authorized,
+            policy_expression,
+            idle_timeout,
+        } = create_inlet;
+        match self
+            .node_manager
+            .create_udp_inlet(
+                ctx,
+                listen_addr,
+                outlet_addr,
+                alias,
+                authorized,
+                policy_expression,
+                idle_timeout,
+            )
+            .await
+        {
+            Ok(status) => Ok(Response::ok().body(status)),
+            Err(e) => Err(Response::bad_request_no_request(&format!("{e:?}"))),
+        }
+    }
+
+    pub(super) async fn delete_udp_inlet(
+        &self,
+        ctx: &Context,
+        alias: &str,
+    ) -> Result<Response<UdpInletStatus>, Response<Error>> {
+        match self.node_manager.delete_udp_inlet(ctx, alias).await {
+            Ok(status) => Ok(Response::ok().body(status)),
+            Err(e) => Err(Response::bad_request_no_request(&format!("{e:?}"))),
+        }
+    }
+
+    pub(super) async fn show_udp_inlet(
+        &self,
+        alias: &str,
+    ) -> Result<Response<UdpInletStatus>, Response<Error>> {
+        match self.node_manager.show_udp_inlet(alias).await {
+            Some(inlet) => Ok(Response::ok().body(inlet)),
+            None => Err(Response::not_found_no_request(&format!(
+                "UDP Inlet with alias {alias} not found"
+            ))),
+        }
+    }
+
+    pub(super) async fn get_udp_inlets(
+        &self,
+    ) -> Result<Response<Vec<UdpInletStatus>>, Response<Error>> {
+        Ok(Response::ok().body(self.node_manager.list_udp_inlets().await))
+    }
+
+    #[instrument(skip_all)]
+    pub(super) async fn create_udp_outlet(
+        &self,
+        ctx: &Context,
+        create_outlet: CreateUdpOutlet,
+    ) -> Result<Response<UdpOutletStatus>, Response<Error>> {
+        let CreateUdpOutlet {
+            hostname_port,
+            worker_addr,
+            policy_expression,
+            idle_timeout,
+        } = create_outlet;
+        match self
+            .node_manager
+            .create_udp_outlet(
+                ctx,
+                hostname_port,
+                worker_addr,
+                policy_expression,
+                idle_timeout,
+            )
+            .await
+        {
+            Ok(status) => Ok(Response::ok().body(status)),
+            Err(e) => Err(Response::bad_request_no_request(&format!("{e:?}"))),
+        }
+    }
+
+    pub(super) async fn delete_udp_outlet(
+        &self,
+        worker_addr: &Address,
+    ) -> Result<Response<UdpOutletStatus>, Response<Error>> {
+        match self.node_manager.delete_udp_outlet(worker_addr).await {
+            Ok(status) => Ok(Response::ok().body(status)),
+            Err(e) => Err(Response::bad_request_no_request(&format!("{e:?}"))),
+        }
+    }
+
+    pub(super) async fn show_udp_outlet(
+        &self,
+        worker_addr: &Address,
+    ) -> Result<Response<UdpOutletStatus>, Response<Error>> {
+        match self.node_manager.show_udp_outlet(worker_addr).await {
+            Some(outlet) => Ok(Response::ok().body(outlet)),
+            None => Err(Response::not_found_no_request(&format!(
+                "UDP Outlet with address {worker_addr} not found"
+            ))),
+        }
+    }
+
+    pub(super) async fn get_udp_outlets(
+        &self,
+    ) -> Result<Response<Vec<UdpOutletStatus>>, Response<Error>> {
+        Ok(Response::ok().body(self.node_manager.list_udp_outlets().await))
+    }
+}
+
+impl NodeManager {
+    /// Return the UDP transport of the node, which is only started for nodes created with UDP
+    fn udp_portal_transport(&self) -> Result<UdpTransport> {
+        self.udp_transport.clone().ok_or(ockam_core::Error::new(
+            Origin::Transport,
+            Kind::Invalid,
+            "UDP portals can only be created on a node with UDP enabled",
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip_all)]
+    pub async fn create_udp_inlet(
+        &self,
+        ctx: &Context,
+        listen_addr: String,
+        outlet_addr: MultiAddr,
+        alias: String,
+        authorized: Option<Identifier>,
+        policy_expression: Option<PolicyExpression>,
+        idle_timeout: Option<Duration>,
+    ) -> Result<UdpInletStatus> {
+        info!(%listen_addr, %outlet_addr, %alias, "Handling request to create a UDP inlet");
+        let udp_transport = self.udp_portal_transport()?;
+
+        if self.registry.udp_inlets.contains_key(&alias).await {
+            let message = format!("A UDP inlet with alias '{alias}' already exists");
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::AlreadyExists,
+                message,
+            ));
+        }
+
+        // Use the authority of the project when the outlet is reached via a project
+        let project = outlet_addr
+            .first()
+            .and_then(|p| p.cast::<ProjectProto>().map(|p| p.to_string()));
+        let authority = match project {
+            Some(project) => self
+                .cli_state
+                .projects()
+                .get_project_by_name(&project)
+                .await
+                .ok()
+                .map(|project| project.authority_identifier())
+                .transpose()?,
+            None => None,
+        }
+        .or(self.project_authority());
+
+        let (incoming_ac, outgoing_ac) = self
+            .access_control(
+                ctx,
+                authority,
+                Resource::new(alias.clone(), ResourceType::UdpInlet),
+                Action::HandleMessage,
+                policy_expression,
+            )
+            .await?;
+
+        let connection = self
+            .make_connection(
+                Arc::new(ctx.async_try_clone().await?),
+                &outlet_addr,
+                self.identifier(),
+                authorized,
+                None,
+            )
+            .await?;
+        let outlet_route = connection.route()?;
+
+        let options = UdpInletOptions::new()
+            .with_incoming_access_control(incoming_ac)
+            .with_outgoing_access_control(outgoing_ac);
+        let options = match idle_timeout {
+            Some(idle_timeout) => options.with_idle_timeout(idle_timeout),
+            None => options,
+        };
+
+        let inlet = match udp_transport
+            .create_inlet(listen_addr, outlet_route.clone(), options)
+            .await
+        {
+            Ok(inlet) => inlet,
+            Err(e) => {
+                if let Err(err) = connection.close(ctx, self).await {
+                    debug!(%err, "Failed to close the connection of a UDP inlet");
+                }
+                return Err(e);
+            }
+        };
+
+        let info = UdpInletInfo {
+            bind_addr: inlet.socket_address().to_string(),
+            outlet_addr,
+            outlet_route,
+            processor_addr: inlet.processor_address().clone(),
+            connection,
+        };
+        let status = Self::udp_inlet_status(&alias, &info);
+        self.registry.udp_inlets.insert(alias, info).await;
+
+        Ok(status)
+    }
+
+    pub async fn delete_udp_inlet(&self, ctx: &Context, alias: &str) -> Result<UdpInletStatus> {
+        info!(%alias, "Handling request to delete a UDP inlet");
+        let Some(inlet) = self.registry.udp_inlets.remove(alias).await else {
+            let message = format!("UDP Inlet with alias {alias} not found");
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::NotFound,
+                message,
+            ));
+        };
+
+        if let Err(err) = self
+            .udp_portal_transport()?
+            .stop_inlet(inlet.processor_addr.clone())
+            .await
+        {
+            warn!(%alias, %err, "Failed to stop the UDP inlet processor");
+        }
+        inlet.connection.close(ctx, self).await?;
+        self.resources().delete_resource(&alias.into()).await?;
+
+        Ok(Self::udp_inlet_status(alias, &inlet))
+    }
+
+    pub async fn show_udp_inlet(&self, alias: &str) -> Option<UdpInletStatus> {
+        let inlet = self.registry.udp_inlets.get(alias).await?;
+        Some(Self::udp_inlet_status(alias, &inlet))
+    }
+
+    pub async fn list_udp_inlets(&self) -> Vec<UdpInletStatus> {
+        self.registry
+            .udp_inlets
+            .entries()
+            .await
+            .iter()
+            .map(|(alias, inlet)| Self::udp_inlet_status(alias, inlet))
+            .collect()
+    }
+
+    fn udp_inlet_status(alias: &str, inlet: &UdpInletInfo) -> UdpInletStatus {
+        UdpInletStatus {
+            alias: alias.to_string(),
+            bind_addr: inlet.bind_addr.clone(),
+            outlet_addr: inlet.outlet_addr.to_string(),
+            outlet_route: Some(inlet.outlet_route.to_string()),
+        }
+    }
+
+    #[instrument(skip_all)]
+    pub async fn create_udp_outlet(
+        &self,
+        ctx: &Context,
+        hostname_port: HostnamePort,
+        worker_addr: Option<Address>,
+        policy_expression: Option<PolicyExpression>,
+        idle_timeout: Option<Duration>,
+    ) -> Result<UdpOutletStatus> {
+        let worker_addr = worker_addr.unwrap_or_else(|| DefaultAddress::UDP_OUTLET_SERVICE.into());
+        info!(%hostname_port, %worker_addr, "Handling request to create a UDP outlet");
+        let udp_transport = self.udp_portal_transport()?;
+
+        if self.registry.udp_outlets.contains_key(&worker_addr).await {
+            let message = format!("A UDP outlet with address '{worker_addr}' already exists");
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::AlreadyExists,
+                message,
+            ));
+        }
+
+        let (incoming_ac, outgoing_ac) = self
+            .access_control(
+                ctx,
+                self.project_authority(),
+                Resource::new(worker_addr.address(), ResourceType::UdpOutlet),
+                Action::HandleMessage,
+                policy_expression,
+            )
+            .await?;
+
+        let options = UdpOutletOptions::new()
+            .with_incoming_access_control(incoming_ac)
+            .with_outgoing_access_control(outgoing_ac);
+        let options = match idle_timeout {
+            Some(idle_timeout) => options.with_idle_timeout(idle_timeout),
+            None => options,
+        };
+        let options = if self.project_authority().is_none() {
+            options.as_consumer(&self.api_transport_flow_control_id)
+        } else {
+            options
+        };
+        // Accept messages from the default secure channel listener
+        let options = match ctx
+            .flow_controls()
+            .get_flow_control_with_spawner(&DefaultAddress::SECURE_CHANNEL_LISTENER.into())
+        {
+            Some(flow_control_id) => options.as_consumer(&flow_control_id),
+            None => options,
+        };
+
+        udp_transport
+            .create_outlet(worker_addr.clone(), hostname_port.clone(), options)
+            .await?;
+
+        self.registry
+            .udp_outlets
+            .insert(worker_addr.clone(), UdpOutletInfo { to: hostname_port })
+            .await;
+
+        Ok(self
+            .show_udp_outlet(&worker_addr)
+            .await
+            .expect("the outlet was just registered"))
+    }
+
+    pub async fn delete_udp_outlet(&self, worker_addr: &Address) -> Result<UdpOutletStatus> {
+        info!(%worker_addr, "Handling request to delete a UDP outlet");
+        let Some(outlet) = self.registry.udp_outlets.remove(worker_addr).await else {
+            let message = format!("UDP Outlet with address {worker_addr} not found");
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::NotFound,
+                message,
+            ));
+        };
+
+        if let Err(err) = self
+            .udp_portal_transport()?
+            .stop_outlet(worker_addr.clone())
+            .await
+        {
+            warn!(%worker_addr, %err, "Failed to stop the UDP outlet worker");
+        }
+        self.resources()
+            .delete_resource(&worker_addr.address().into())
+            .await?;
+
+        Ok(UdpOutletStatus {
+            worker_addr: worker_addr.clone(),
+            to: outlet.to.to_string(),
+        })
+    }
+
+    pub async fn show_udp_outlet(&self, worker_addr: &Address) -> Option<UdpOutletStatus> {
+        let outlet = self.registry.udp_outlets.get(worker_addr).await?;
+        Some(UdpOutletStatus {
+            worker_addr: worker_addr.clone(),
+            to: outlet.to.to_string(),
+        })
+    }
+
+    pub async fn list_udp_outlets(&self) -> Vec<UdpOutletStatus> {
+        self.registry
+            .udp_outlets
+            .entries()
+            .await
+            .into_iter()
+            .map(|(worker_addr, outlet)| UdpOutletStatus {
+                worker_addr,
+                to: outlet.to.to_string(),
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+pub trait UdpPortals {
+    #[allow(clippy::too_many_arguments)]
+    async fn create_udp_inlet(
+        &self,
+        ctx: &Context,
+        listen_addr: &str,
+        outlet_addr: &MultiAddr,
+        alias: &str,
+        authorized: Option<Identifier>,
+        policy_expression: Option<PolicyExpression>,
+        idle_timeout: Option<Duration>,
+    ) -> miette::Result<UdpInletStatus>;
+
+    async fn delete_udp_inlet(&self, ctx: &Context, alias: &str) -> miette::Result<()>;
+
+    async fn create_udp_outlet(
+        &self,
+        ctx: &Context,
+        to: HostnamePort,
+        from: Option<&Address>,
+        policy_expression: Option<PolicyExpression>,
+        idle_timeout: Option<Duration>,
+    ) -> miette::Result<UdpOutletStatus>;
+
+    async fn delete_udp_outlet(&self, ctx: &Context, worker_addr: &Address) -> miette::Result<()>;
+}
+
+#[async_trait]
+impl UdpPortals for BackgroundNodeClient {
+    #[instrument(skip_all, fields(listen_addr = %listen_addr, outlet_addr = %outlet_addr))]
+    async fn create_udp_inlet(
+        &self,
+        ctx: &Context,
+        listen_addr: &str,
+        outlet_addr: &MultiAddr,
+        alias: &str,
+        authorized: Option<Identifier>,
+        policy_expression: Option<PolicyExpression>,
+        idle_timeout: Option<Duration>,
+    ) -> miette::Result<UdpInletStatus> {
+        let mut payload = CreateUdpInlet::new(
+            listen_addr.to_string(),
+            outlet_addr.clone(),
+            alias.to_string(),
+            authorized,
+        );
+        if let Some(policy_expression) = policy_expression {
+            payload.set_policy_expression(policy_expression);
+        }
+        if let Some(idle_timeout) = idle_timeout {
+            payload.set_idle_timeout(idle_timeout);
+        }
+        self.ask(ctx, Request::post("/node/udp/inlet").body(payload))
+            .await
+    }
+
+    #[instrument(skip_all, fields(alias = %alias))]
+    async fn delete_udp_inlet(&self, ctx: &Context, alias: &str) -> miette::Result<()> {
+        let _: UdpInletStatus = self
+            .ask(ctx, Request::delete(format!("/node/udp/inlet/{alias}")))
+            .await?;
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(to = %to, from = ?from))]
+    async fn create_udp_outlet(
+        &self,
+        ctx: &Context,
+        to: HostnamePort,
+        from: Option<&Address>,
+        policy_expression: Option<PolicyExpression>,
+        idle_timeout: Option<Duration>,
+    ) -> miette::Result<UdpOutletStatus> {
+        let mut payload = CreateUdpOutlet::new(to, from.cloned());
+        if let Some(policy_expression) = policy_expression {
+            payload.set_policy_expression(policy_expression);
+        }
+        if let Some(idle_timeout) = idle_timeout {
+            payload.set_idle_timeout(idle_timeout);
+        }
+        self.ask(ctx, Request::post("/node/udp/outlet").body(payload))
+            .await
+    }
+
+    #[instrument(skip_all, fields(worker_addr = %worker_addr))]
+    async fn delete_udp_outlet(&self, ctx: &Context, worker_addr: &Address) -> miette::Result<()> {
+        let _: UdpOutletStatus = self
+            .ask(
+                ctx,
+                Request::delete(format!("/node/udp/outlet/{}", worker_addr.address())),
+            )
+            .await?;
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use crate::nodes::models::policies::SetPolicyRequest;
-use crate::nodes::registry::KafkaServiceKind;
+use crate::nodes::registry::{KafkaServiceKind, MqttServiceKind};
 use crate::nodes::service::{encode_response, TARGET};
 use crate::nodes::{InMemoryNode, NODEMANAGER_ADDR};
 use crate::DefaultAddress;
@@ -58,6 +58,33 @@ impl NodeManagerWorker {
             // ==*== Basic node information ==*==
             (Get, ["node"]) => encode_response(req, self.get_node_status().await)?,
             (Get, ["node", "resources"]) => encode_response(req, self.get_node_resources().await)?,
+            (Get, ["node", "stats"]) => encode_response(req, self.get_node_stats(ctx).await)?,
+            (Get, ["node", "metrics"]) => encode_response(req, self.get_node_metrics(ctx).await)?,
+            (Get, ["node", "debug", "graph"]) => {
+                encode_response(req, self.get_debug_graph().await)?
+            }
+            (Post, ["node", "events", "subscriptions"]) => encode_response(
+                req,
+                self.subscribe_node_events(ctx, req, dec.decode()?).await,
+            )?,
+            (Get, ["node", "events", "subscriptions"]) => {
+                encode_response(req, self.list_node_events_subscriptions().await)?
+            }
+            (Delete, ["node", "events", "subscriptions", id]) => {
+                encode_response(req, self.unsubscribe_node_events(req, id).await)?
+            }
+            (Post, ["node", "debug", "trace"]) => {
+                encode_response(req, self.start_trace(dec.decode()?).await)?
+            }
+            (Get, ["node", "debug", "trace", id]) => {
+                encode_response(req, self.poll_trace(id).await)?
+            }
+            (Delete, ["node", "debug", "trace", id]) => {
+                encode_response(req, self.stop_trace(id).await)?
+            }
+            (Post, ["node", "vault", "unlock"]) => {
+                encode_response(req, self.unlock_vault(dec.decode()?).await)?
+            }
 
             // ==*== Tcp Connection ==*==
             (Get, ["node", "tcp", "connection"]) => self.get_tcp_connections(req).await.to_vec()?,
@@ -99,6 +126,9 @@ impl NodeManagerWorker {
             (Get, ["node", "show_secure_channel"]) => {
                 encode_response(req, self.show_secure_channel(dec.decode()?).await)?
             }
+            (Get, ["node", "secure_channel_diagnostics"]) => {
+                encode_response(req, self.show_secure_channel_diagnostics())?
+            }
             (Post, ["node", "secure_channel_listener"]) => encode_response(
                 req,
                 self.create_secure_channel_listener(dec.decode()?, ctx)
@@ -141,6 +171,43 @@ impl NodeManagerWorker {
                 self.delete_kafka_service(ctx, dec.decode()?, KafkaServiceKind::Inlet)
                     .await,
             )?,
+            (Get, ["node", "services", DefaultAddress::KAFKA_INLET, address, "stats"]) => {
+                encode_response(req, self.get_kafka_inlet_stats(address).await)?
+            }
+            (Post, ["node", "services", DefaultAddress::MQTT_OUTLET]) => encode_response(
+                req,
+                self.start_mqtt_outlet_service(ctx, dec.decode()?).await,
+            )?,
+            (Delete, ["node", "services", DefaultAddress::MQTT_OUTLET]) => encode_response(
+                req,
+                self.delete_mqtt_service(ctx, dec.decode()?, MqttServiceKind::Outlet)
+                    .await,
+            )?,
+            (Post, ["node", "services", DefaultAddress::MQTT_INLET]) => {
+                encode_response(req, self.start_mqtt_inlet_service(ctx, dec.decode()?).await)?
+            }
+            (Delete, ["node", "services", DefaultAddress::MQTT_INLET]) => encode_response(
+                req,
+                self.delete_mqtt_service(ctx, dec.decode()?, MqttServiceKind::Inlet)
+                    .await,
+            )?,
+            (Post, ["node", "services", DefaultAddress::POSTGRES_OUTLET]) => encode_response(
+                req,
+                self.start_postgres_outlet_service(ctx, dec.decode()?).await,
+            )?,
+            (Delete, ["node", "services", DefaultAddress::POSTGRES_OUTLET]) => encode_response(
+                req,
+                self.delete_postgres_outlet_service(ctx, dec.decode()?)
+                    .await,
+            )?,
+            (Post, ["node", "services", DefaultAddress::HTTP_GATEWAY]) => encode_response(
+                req,
+                self.start_http_gateway_service(ctx, dec.decode()?).await,
+            )?,
+            (Delete, ["node", "services", DefaultAddress::HTTP_GATEWAY]) => encode_response(
+                req,
+                self.delete_http_gateway_service(ctx, dec.decode()?).await,
+            )?,
             (Get, ["node", "services"]) => encode_response(req, self.list_services().await)?,
             (Get, ["node", "services", service_type]) => {
                 encode_response(req, self.list_services_of_type(service_type).await)?
@@ -161,6 +228,9 @@ impl NodeManagerWorker {
             // ==*== Inlets & Outlets ==*==
             (Get, ["node", "inlet"]) => encode_response(req, self.get_inlets().await)?,
             (Get, ["node", "inlet", alias]) => encode_response(req, self.show_inlet(alias).await)?,
+            (Get, ["node", "inlet", alias, "stats"]) => {
+                encode_response(req, self.get_inlet_stats(alias).await)?
+            }
             (Get, ["node", "outlet"]) => self.get_outlets(req).await.to_vec()?,
             (Get, ["node", "outlet", addr]) => {
                 let addr: Address = addr.to_string().into();
@@ -172,6 +242,19 @@ impl NodeManagerWorker {
             (Post, ["node", "outlet"]) => {
                 encode_response(req, self.create_outlet(ctx, dec.decode()?).await)?
             }
+            (Post, ["node", "outlet", addr, "ping"]) => {
+                let addr: Address = addr.to_string().into();
+                encode_response(req, self.ping_outlet(&addr, dec.decode()?).await)?
+            }
+            (Put, ["node", "outlet", addr, "egress"]) => {
+                let addr: Address = addr.to_string().into();
+                let egress_allow_list = dec.decode()?;
+                encode_response(
+                    req,
+                    self.update_outlet_egress_allow_list(&addr, egress_allow_list)
+                        .await,
+                )?
+            }
             (Delete, ["node", "outlet", addr]) => {
                 let addr: Address = addr.to_string().into();
                 encode_response(req, self.delete_outlet(&addr).await)?
@@ -181,6 +264,54 @@ impl NodeManagerWorker {
             }
             (Delete, ["node", "portal"]) => todo!(),
 
+            // ==*== UDP Inlets & Outlets ==*==
+            (Get, ["node", "udp", "inlet"]) => encode_response(req, self.get_udp_inlets().await)?,
+            (Get, ["node", "udp", "inlet", alias]) => {
+                encode_response(req, self.show_udp_inlet(alias).await)?
+            }
+            (Post, ["node", "udp", "inlet"]) => {
+                encode_response(req, self.create_udp_inlet(ctx, dec.decode()?).await)?
+            }
+            (Delete, ["node", "udp", "inlet", alias]) => {
+                encode_response(req, self.delete_udp_inlet(ctx, alias).await)?
+            }
+            (Get, ["node", "udp", "outlet"]) => encode_response(req, self.get_udp_outlets().await)?,
+            (Get, ["node", "udp", "outlet", addr]) => {
+                let addr: Address = addr.to_string().into();
+                encode_response(req, self.show_udp_outlet(&addr).await)?
+            }
+            (Post, ["node", "udp", "outlet"]) => {
+                encode_response(req, self.create_udp_outlet(ctx, dec.decode()?).await)?
+            }
+            (Delete, ["node", "udp", "outlet", addr]) => {
+                let addr: Address = addr.to_string().into();
+                encode_response(req, self.delete_udp_outlet(&addr).await)?
+            }
+
+            // ==*== TUN Inlets & Outlets ==*==
+            (Get, ["node", "tun", "inlet"]) => encode_response(req, self.get_tun_inlets().await)?,
+            (Get, ["node", "tun", "inlet", alias]) => {
+                encode_response(req, self.show_tun_inlet(alias).await)?
+            }
+            (Post, ["node", "tun", "inlet"]) => {
+                encode_response(req, self.create_tun_inlet(ctx, dec.decode()?).await)?
+            }
+            (Delete, ["node", "tun", "inlet", alias]) => {
+                encode_response(req, self.delete_tun_inlet(ctx, alias).await)?
+            }
+            (Get, ["node", "tun", "outlet"]) => encode_response(req, self.get_tun_outlets().await)?,
+            (Get, ["node", "tun", "outlet", addr]) => {
+                let addr: Address = addr.to_string().into();
+                encode_response(req, self.show_tun_outlet(&addr).await)?
+            }
+            (Post, ["node", "tun", "outlet"]) => {
+                encode_response(req, self.create_tun_outlet(ctx, dec.decode()?).await)?
+            }
+            (Delete, ["node", "tun", "outlet", addr]) => {
+                let addr: Address = addr.to_string().into();
+                encode_response(req, self.delete_tun_outlet(ctx, &addr).await)?
+            }
+
             // ==*== Flow Controls ==*==
             (Post, ["node", "flow_controls", "add_consumer"]) => {
                 encode_response(req, self.add_consumer(ctx, dec.decode()?).await)?
@@ -210,6 +341,9 @@ impl NodeManagerWorker {
             (Post, ["v0", "message"]) => {
                 encode_response(req, self.send_message(ctx, dec.decode()?).await)?
             }
+            (Post, ["v0", "message", "report"]) => {
+                encode_response(req, self.send_message_with_report(ctx, dec.decode()?).await)?
+            }
 
             // ==*== Catch-all for Unimplemented APIs ==*==
             _ => {
@@ -5,20 +5,17 @@ use ockam_core::Result;
 use ockam_node::Context;
 
 impl NodeManagerWorker {
-    /// Return the current list of workers
+    /// Return the current list of workers and processors, with their type and mailbox count
     pub async fn list_workers(
         &self,
         ctx: &Context,
     ) -> Result<Response<WorkerList>, Response<Error>> {
-        let workers = match ctx.list_workers().await {
+        let workers = match ctx.list_workers_info().await {
             Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
             Ok(workers) => Ok(workers),
         }?;
 
-        let list = workers
-            .into_iter()
-            .map(|addr| WorkerStatus::new(addr.address()))
-            .collect();
+        let list = workers.into_iter().map(WorkerStatus::from).collect();
 
         Ok(Response::ok().body(WorkerList::new(list)))
     }
@@ -0,0 +1,376 @@
+use crate::postgres::messages::{
+    fatal_error, malformed, password_message, sasl_initial_response, sasl_response, startup_code,
+    Authentication, MessageDecoder, StartupMessage, AUTHENTICATION, CANCEL_REQUEST_CODE,
+    ERROR_RESPONSE, GSSENC_REQUEST_CODE, PROTOCOL_VERSION_3, SSL_REQUEST_CODE,
+};
+use crate::postgres::scram::{ScramClient, SCRAM_SHA_256};
+use crate::postgres::PasswordFile;
+use bytes::{BufMut, BytesMut};
+use ockam::transport::HostnamePort;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::{Error, Result};
+
+/// Error code of PostgreSQL for an invalid authorization
+const INVALID_AUTHORIZATION: &str = "28000";
+
+/// Answer of the outlet to the requests of a TLS or GSSAPI encryption: the connection is
+/// already encrypted by the secure channel, and the startup message must be readable
+const ENCRYPTION_NOT_SUPPORTED: &[u8] = b"N";
+
+/// Result of the interception of some bytes
+#[derive(Default)]
+pub(super) struct Intercepted {
+    /// Bytes sent to the recipient of the intercepted bytes
+    pub(super) forward: BytesMut,
+    /// Bytes sent back to the sender of the intercepted bytes
+    pub(super) reply: BytesMut,
+}
+
+/// Intercepts the messages exchanged by a PostgreSQL client and the database while the
+/// connection is started, for a single connection.
+///
+/// The role requested by the client is replaced by the role of its identity, and the
+/// authentication requested by the database is answered by the outlet with the password of
+/// this role. Once the database accepts the connection, the bytes are relayed unchanged.
+pub(super) struct PostgresInterceptor {
+    role: Option<String>,
+    database_address: HostnamePort,
+    password_file: Option<Arc<PasswordFile>>,
+    state: Mutex<ConnectionState>,
+}
+
+struct ConnectionState {
+    phase: Phase,
+    client_decoder: MessageDecoder,
+    database_decoder: MessageDecoder,
+    /// Database requested by the client, used to find the password of the role
+    database: String,
+}
+
+enum Phase {
+    /// Waiting for the startup message of the client
+    Startup,
+    /// The database is authenticating the role
+    Authenticating { scram: Option<ScramClient> },
+    /// The connection is accepted, or cancels a query
+    Ready,
+    /// The connection was refused, the client is expected to disconnect
+    Rejected,
+}
+
+impl PostgresInterceptor {
+    pub(super) fn new(
+        role: Option<String>,
+        database_address: HostnamePort,
+        password_file: Option<Arc<PasswordFile>>,
+    ) -> Self {
+        Self {
+            role,
+            database_address,
+            password_file,
+            state: Mutex::new(ConnectionState {
+                phase: Phase::Startup,
+                client_decoder: MessageDecoder::new(),
+                database_decoder: MessageDecoder::new(),
+                database: String::new(),
+            }),
+        }
+    }
+
+    /// Intercept the bytes sent by the client to the database
+    pub(super) fn intercept_client_bytes(&self, bytes: &[u8]) -> Result<Intercepted> {
+        let mut state = self.state.lock().unwrap();
+        let mut intercepted = Intercepted::default();
+        match state.phase {
+            Phase::Ready => intercepted.forward.extend_from_slice(bytes),
+            Phase::Startup => {
+                state.client_decoder.extend(bytes);
+                self.intercept_startup(&mut state, &mut intercepted)?;
+            }
+            // the database only accepts messages from the client once it is authenticated
+            Phase::Authenticating { .. } => {
+                self.reject(
+                    &mut state,
+                    &mut intercepted.reply,
+                    "unexpected message during the authentication",
+                );
+            }
+            Phase::Rejected => {}
+        }
+        Ok(intercepted)
+    }
+
+    /// Intercept the bytes sent by the database to the client
+    pub(super) fn intercept_database_bytes(&self, bytes: &[u8]) -> Result<Intercepted> {
+        let mut state = self.state.lock().unwrap();
+        let mut intercepted = Intercepted::default();
+        match state.phase {
+            Phase::Ready | Phase::Startup => intercepted.forward.extend_from_slice(bytes),
+            Phase::Authenticating { .. } => {
+                state.database_decoder.extend(bytes);
+                self.intercept_authentication(&mut state, &mut intercepted)?;
+            }
+            Phase::Rejected => {}
+        }
+        Ok(intercepted)
+    }
+
+    fn intercept_startup(
+        &self,
+        state: &mut ConnectionState,
+        intercepted: &mut Intercepted,
+    ) -> Result<()> {
+        while let Some(body) = state.client_decoder.next_startup_message()? {
+            match startup_code(&body)? {
+                SSL_REQUEST_CODE | GSSENC_REQUEST_CODE => {
+                    intercepted.reply.put_slice(ENCRYPTION_NOT_SUPPORTED);
+                }
+                CANCEL_REQUEST_CODE => {
+                    // the key of the query is enough to cancel it, the role is not needed
+                    intercepted.forward.put_u32(body.len() as u32 + 4);
+                    intercepted.forward.put_slice(&body);
+                    state.phase = Phase::Ready;
+                }
+                PROTOCOL_VERSION_3 => {
+                    let role = match &self.role {
+                        Some(role) => role,
+                        None => {
+                            let message = "no database role is granted to this identity";
+                            self.reject(state, &mut intercepted.reply, message);
+                            return Ok(());
+                        }
+                    };
+                    let mut startup_message = StartupMessage::decode(&body)?;
+                    // the database is named after the requested user by default
+                    let database = startup_message
+                        .parameter("database")
+                        .or_else(|| startup_message.parameter("user"))
+                        .unwrap_or(role)
+                        .to_string();
+                    startup_message.set_parameter("database", &database);
+                    startup_message.set_parameter("user", role);
+                    debug!("starting a postgres connection to {database} as {role}");
+
+                    intercepted.forward.put_slice(&startup_message.encode());
+                    state.database = database;
+                    state.phase = Phase::Authenticating { scram: None };
+                }
+                version => {
+                    let message = format!("unsupported protocol version {version}");
+                    self.reject(state, &mut intercepted.reply, &message);
+                    return Ok(());
+                }
+            }
+            if !matches!(state.phase, Phase::Startup) {
+                break;
+            }
+        }
+
+        if matches!(state.phase, Phase::Ready) {
+            intercepted
+                .forward
+                .put_slice(&state.client_decoder.take_remaining());
+        }
+        Ok(())
+    }
+
+    fn intercept_authentication(
+        &self,
+        state: &mut ConnectionState,
+        intercepted: &mut Intercepted,
+    ) -> Result<()> {
+        while let Some(message) = state.database_decoder.next_message()? {
+            if message.tag != AUTHENTICATION {
+                message.encode(&mut intercepted.forward);
+                // the errors of the database are sent to the client, which then disconnects
+                if message.tag == ERROR_RESPONSE {
+                    state.phase = Phase::Ready;
+                    break;
+                }
+                continue;
+            }
+
+            match Authentication::decode(&message.body)? {
+                Authentication::Ok => {
+                    message.encode(&mut intercepted.forward);
+                    state.phase = Phase::Ready;
+                    break;
+                }
+                Authentication::CleartextPassword => {
+                    let password = match self.password(state) {
+                        Some(password) => password,
+                        None => {
+                            self.reject_missing_password(state, intercepted);
+                            return Ok(());
+                        }
+                    };
+                    password_message(&password).encode(&mut intercepted.reply);
+                }
+                Authentication::Sasl { mechanisms } => {
+                    if !mechanisms
+                        .iter()
+                        .any(|mechanism| mechanism == SCRAM_SHA_256)
+                    {
+                        let message = "the SASL mechanisms of the database are not supported";
+                        self.reject(state, &mut intercepted.forward, message);
+                        return Ok(());
+                    }
+                    let password = match self.password(state) {
+                        Some(password) => password,
+                        None => {
+                            self.reject_missing_password(state, intercepted);
+                            return Ok(());
+                        }
+                    };
+                    let scram = ScramClient::new(&password);
+                    let client_first_message = scram.client_first_message();
+                    sasl_initial_response(SCRAM_SHA_256, client_first_message.as_bytes())
+                        .encode(&mut intercepted.reply);
+                    state.phase = Phase::Authenticating { scram: Some(scram) };
+                }
+                Authentication::SaslContinue(data) => {
+                    let client_final_message = match &mut state.phase {
+                        Phase::Authenticating { scram: Some(scram) } => {
+                            scram.client_final_message(&String::from_utf8_lossy(&data))?
+                        }
+                        _ => return Err(unexpected_message()),
+                    };
+                    sasl_response(client_final_message.as_bytes()).encode(&mut intercepted.reply);
+                }
+                Authentication::SaslFinal(data) => match &state.phase {
+                    Phase::Authenticating { scram: Some(scram) } => {
+                        scram.verify_server_final_message(&String::from_utf8_lossy(&data))?
+                    }
+                    _ => return Err(unexpected_message()),
+                },
+                Authentication::Md5Password => {
+                    let message = "the MD5 authentication is not supported, \
+                                   the database must use SCRAM-SHA-256";
+                    self.reject(state, &mut intercepted.forward, message);
+                    return Ok(());
+                }
+                Authentication::Other(code) => {
+                    let message = format!("the authentication method {code} is not supported");
+                    self.reject(state, &mut intercepted.forward, &message);
+                    return Ok(());
+                }
+            }
+        }
+
+        // the messages following the authentication are relayed unchanged
+        if matches!(state.phase, Phase::Ready) {
+            intercepted
+                .forward
+                .put_slice(&state.database_decoder.take_remaining());
+        }
+        Ok(())
+    }
+
+    fn password(&self, state: &ConnectionState) -> Option<String> {
+        let role = self.role.as_deref()?;
+        let password = self.password_file.as_ref()?.password(
+            &self.database_address.hostname(),
+            self.database_address.port(),
+            &state.database,
+            role,
+        )?;
+        Some(password.to_string())
+    }
+
+    fn reject_missing_password(&self, state: &mut ConnectionState, intercepted: &mut Intercepted) {
+        let message = format!(
+            "no password is configured for the role {}",
+            self.role.as_deref().unwrap_or_default()
+        );
+        self.reject(state, &mut intercepted.forward, &message);
+    }
+
+    /// Send an error to the client and ignore the next messages of the connection
+    fn reject(&self, state: &mut ConnectionState, client_bytes: &mut BytesMut, message: &str) {
+        warn!("refusing a postgres connection: {message}");
+        fatal_error(INVALID_AUTHORIZATION, message).encode(client_bytes);
+        state.phase = Phase::Rejected;
+    }
+}
+
+fn unexpected_message() -> Error {
+    malformed("unexpected postgres authentication message")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::postgres::messages::Message;
+
+    fn startup_message_bytes(user: &str) -> BytesMut {
+        let mut body = BytesMut::new();
+        body.put_u32(PROTOCOL_VERSION_3);
+        body.put_slice(format!("user\0{user}\0\0").as_bytes());
+        let mut bytes = BytesMut::new();
+        bytes.put_u32(body.len() as u32 + 4);
+        bytes.put_slice(&body);
+        bytes
+    }
+
+    #[test]
+    fn the_role_is_replaced_and_authenticated_by_the_outlet() -> Result<()> {
+        let password_file = PasswordFile::parse("localhost:5432:*:reporting:secret")?;
+        let interceptor = PostgresInterceptor::new(
+            Some("reporting".to_string()),
+            HostnamePort::new("localhost", 5432),
+            Some(Arc::new(password_file)),
+        );
+
+        // the client asks for TLS first
+        let mut ssl_request = BytesMut::new();
+        ssl_request.put_u32(8);
+        ssl_request.put_u32(SSL_REQUEST_CODE);
+        let intercepted = interceptor.intercept_client_bytes(&ssl_request)?;
+        assert!(intercepted.forward.is_empty());
+        assert_eq!(&intercepted.reply[..], ENCRYPTION_NOT_SUPPORTED);
+
+        let intercepted = interceptor.intercept_client_bytes(&startup_message_bytes("alice"))?;
+        assert!(intercepted.reply.is_empty());
+        let startup_message = StartupMessage::decode(&intercepted.forward[4..])?;
+        assert_eq!(startup_message.parameter("user"), Some("reporting"));
+        assert_eq!(startup_message.parameter("database"), Some("alice"));
+
+        // the password is sent back to the database, the client never sees the request
+        let mut password_request = BytesMut::new();
+        Message::new(AUTHENTICATION, vec![0, 0, 0, 3]).encode(&mut password_request);
+        let intercepted = interceptor.intercept_database_bytes(&password_request)?;
+        assert!(intercepted.forward.is_empty());
+        let mut expected = BytesMut::new();
+        password_message("secret").encode(&mut expected);
+        assert_eq!(intercepted.reply, expected);
+
+        let mut accepted = BytesMut::new();
+        Message::new(AUTHENTICATION, vec![0, 0, 0, 0]).encode(&mut accepted);
+        Message::new(b'Z', vec![b'I']).encode(&mut accepted);
+        let intercepted = interceptor.intercept_database_bytes(&accepted)?;
+        assert_eq!(intercepted.forward, accepted);
+        assert!(intercepted.reply.is_empty());
+
+        // the next messages are relayed unchanged
+        let mut query = BytesMut::new();
+        Message::new(b'Q', b"select 1\0".to_vec()).encode(&mut query);
+        let intercepted = interceptor.intercept_client_bytes(&query)?;
+        assert_eq!(intercepted.forward, query);
+        Ok(())
+    }
+
+    #[test]
+    fn the_clients_without_role_are_rejected() -> Result<()> {
+        let interceptor =
+            PostgresInterceptor::new(None, HostnamePort::new("localhost", 5432), None);
+
+        let intercepted = interceptor.intercept_client_bytes(&startup_message_bytes("alice"))?;
+        assert!(intercepted.forward.is_empty());
+        assert_eq!(intercepted.reply[0], ERROR_RESPONSE);
+
+        let intercepted = interceptor.intercept_client_bytes(&startup_message_bytes("alice"))?;
+        assert!(intercepted.forward.is_empty());
+        assert!(intercepted.reply.is_empty());
+        Ok(())
+    }
+}
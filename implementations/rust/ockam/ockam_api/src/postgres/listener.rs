@@ -0,0 +1,142 @@
+use crate::postgres::interceptor::PostgresInterceptor;
+use crate::postgres::portal_worker::PostgresPortalWorker;
+use crate::postgres::{PasswordFile, RoleMapping};
+use ockam::identity::{Identifier, IdentitiesAttributes, IdentitySecureChannelLocalInfo};
+use ockam::transport::HostnamePort;
+use ockam::{Any, Context, Result, Routed, Worker};
+use ockam_core::flow_control::{FlowControlId, FlowControls};
+use ockam_core::{Address, IncomingAccessControl, OutgoingAccessControl};
+use ockam_node::WorkerBuilder;
+use std::sync::Arc;
+
+/// Accepts the connections of the PostgreSQL clients, like the Kafka outlet manager service.
+///
+/// Each connection is relayed to the TCP outlet of the database by a new pair of portal
+/// workers, using the database role granted to the identity of the client.
+pub(crate) struct PostgresOutletListener {
+    database: DatabaseOutlet,
+    identities_attributes: Arc<IdentitiesAttributes>,
+    authority: Option<Identifier>,
+    request_incoming_access_control: Arc<dyn IncomingAccessControl>,
+    response_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    spawner_flow_control_id: FlowControlId,
+}
+
+/// The TCP outlet of the database, and how to connect to it
+pub(crate) struct DatabaseOutlet {
+    pub(crate) outlet_address: Address,
+    pub(crate) database_address: HostnamePort,
+    pub(crate) role_mapping: RoleMapping,
+    pub(crate) password_file: Option<Arc<PasswordFile>>,
+}
+
+impl PostgresOutletListener {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn create(
+        context: &Context,
+        listener_address: Address,
+        database: DatabaseOutlet,
+        default_secure_channel_listener_flow_control_id: FlowControlId,
+        identities_attributes: Arc<IdentitiesAttributes>,
+        authority: Option<Identifier>,
+        request_incoming_access_control: Arc<dyn IncomingAccessControl>,
+        response_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    ) -> Result<()> {
+        let flow_controls = context.flow_controls();
+        flow_controls.add_consumer(
+            listener_address.clone(),
+            &default_secure_channel_listener_flow_control_id,
+        );
+
+        let spawner_flow_control_id = FlowControls::generate_flow_control_id();
+        flow_controls.add_spawner(listener_address.clone(), &spawner_flow_control_id);
+
+        let worker = Self {
+            database,
+            identities_attributes,
+            authority,
+            request_incoming_access_control,
+            response_outgoing_access_control,
+            spawner_flow_control_id,
+        };
+
+        let incoming = worker.request_incoming_access_control.clone();
+
+        WorkerBuilder::new(worker)
+            .with_address(listener_address)
+            .with_incoming_access_control_arc(incoming)
+            .start(context)
+            .await
+            .map(|_| ())
+    }
+
+    /// Return the database role of the identity at the other end of a secure channel
+    async fn role_of(&self, message: &Routed<Any>) -> Result<Option<String>> {
+        let identifier = IdentitySecureChannelLocalInfo::find_info(message.local_message())
+            .map(|info| info.their_identity_id())
+            .ok();
+        let attributes = match (identifier, &self.authority) {
+            (Some(identifier), Some(authority)) => {
+                self.identities_attributes
+                    .get_attributes(&identifier, authority)
+                    .await?
+            }
+            _ => None,
+        };
+        Ok(self.database.role_mapping.role_for(attributes.as_ref()))
+    }
+}
+
+#[ockam::worker]
+impl Worker for PostgresOutletListener {
+    type Message = Any;
+    type Context = Context;
+
+    async fn handle_message(
+        &mut self,
+        context: &mut Context,
+        message: Routed<Self::Message>,
+    ) -> Result<()> {
+        let role = self.role_of(&message).await?;
+        let source_address = message.src_addr();
+        let mut message = message.into_local_message();
+
+        // Remove our address
+        message = message.pop_front_onward_route()?;
+
+        // Retrieve the flow id from the previous hop if it exists
+        let secure_channel_flow_control_id = context
+            .flow_controls()
+            .find_flow_control_with_producer_address(&source_address)
+            .map(|x| x.flow_control_id().clone());
+
+        let interceptor = PostgresInterceptor::new(
+            role,
+            self.database.database_address.clone(),
+            self.database.password_file.clone(),
+        );
+        let worker_address = PostgresPortalWorker::create_outlet_side_postgres_portal(
+            context,
+            Arc::new(interceptor),
+            self.database.outlet_address.clone(),
+            &context.flow_controls().clone(),
+            secure_channel_flow_control_id,
+            Some(self.spawner_flow_control_id.clone()),
+            self.request_incoming_access_control.clone(),
+            self.response_outgoing_access_control.clone(),
+        )
+        .await?;
+
+        message = message.push_front_onward_route(&worker_address);
+
+        trace!(
+            "forwarding message: onward={:?}; return={:?}; worker={:?}",
+            &message.onward_route_ref(),
+            &message.return_route_ref(),
+            worker_address
+        );
+
+        context.forward(message).await?;
+        Ok(())
+    }
+}
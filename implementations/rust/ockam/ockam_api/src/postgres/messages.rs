@@ -0,0 +1,316 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+
+/// Code sent by a client instead of a protocol version to ask for a TLS connection
+pub(super) const SSL_REQUEST_CODE: u32 = 80877103;
+/// Code sent by a client instead of a protocol version to ask for a GSSAPI encryption
+pub(super) const GSSENC_REQUEST_CODE: u32 = 80877104;
+/// Code sent by a client instead of a protocol version to cancel a running query
+pub(super) const CANCEL_REQUEST_CODE: u32 = 80877102;
+/// Version 3.0 of the protocol, the only one supported since PostgreSQL 7.4
+pub(super) const PROTOCOL_VERSION_3: u32 = 196608;
+
+pub(super) const AUTHENTICATION: u8 = b'R';
+pub(super) const ERROR_RESPONSE: u8 = b'E';
+const PASSWORD_MESSAGE: u8 = b'p';
+
+/// The messages exchanged while a connection is started are small. Bigger messages are
+/// only exchanged once the connection is authenticated, and are not decoded
+const MAX_MESSAGE_LENGTH: usize = 1024 * 1024;
+
+/// A message of the protocol, once the connection is started: its type and its content
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct Message {
+    pub(super) tag: u8,
+    pub(super) body: Bytes,
+}
+
+impl Message {
+    pub(super) fn new(tag: u8, body: impl Into<Bytes>) -> Self {
+        Self {
+            tag,
+            body: body.into(),
+        }
+    }
+
+    pub(super) fn encode(&self, buffer: &mut BytesMut) {
+        buffer.put_u8(self.tag);
+        // the length includes itself, but not the message type
+        buffer.put_u32(self.body.len() as u32 + 4);
+        buffer.put_slice(&self.body);
+    }
+}
+
+/// Accumulates the bytes received on a connection, and splits them into complete messages
+pub(super) struct MessageDecoder {
+    buffer: BytesMut,
+}
+
+impl MessageDecoder {
+    pub(super) fn new() -> Self {
+        Self {
+            buffer: BytesMut::new(),
+        }
+    }
+
+    pub(super) fn extend(&mut self, incoming: &[u8]) {
+        self.buffer.extend_from_slice(incoming);
+    }
+
+    /// Return the next message sent by a client before its connection is started. These
+    /// messages don't have a type, their content starts with a protocol version or a code
+    pub(super) fn next_startup_message(&mut self) -> Result<Option<Bytes>> {
+        Ok(self
+            .next_frame(0)?
+            .map(|mut frame| frame.split_off(4).freeze()))
+    }
+
+    /// Return the next complete message
+    pub(super) fn next_message(&mut self) -> Result<Option<Message>> {
+        Ok(self.next_frame(1)?.map(|mut frame| {
+            let body = frame.split_off(5).freeze();
+            Message::new(frame[0], body)
+        }))
+    }
+
+    /// Return the bytes which don't make a complete message yet
+    pub(super) fn take_remaining(&mut self) -> BytesMut {
+        self.buffer.split()
+    }
+
+    fn next_frame(&mut self, tag_length: usize) -> Result<Option<BytesMut>> {
+        let length = match self.buffer.get(tag_length..tag_length + 4) {
+            Some(bytes) => u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize,
+            None => return Ok(None),
+        };
+        if !(4..=MAX_MESSAGE_LENGTH).contains(&length) {
+            return Err(malformed("invalid postgres message length"));
+        }
+        if self.buffer.len() < tag_length + length {
+            return Ok(None);
+        }
+        Ok(Some(self.buffer.split_to(tag_length + length)))
+    }
+}
+
+/// Return the protocol version or request code starting a startup message
+pub(super) fn startup_code(body: &[u8]) -> Result<u32> {
+    match body.get(..4) {
+        Some(bytes) => Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+        None => Err(malformed("truncated postgres startup message")),
+    }
+}
+
+/// The startup message of a client, with the parameters of the connection,
+/// like the user and the database
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct StartupMessage {
+    parameters: Vec<(String, String)>,
+}
+
+impl StartupMessage {
+    pub(super) fn decode(body: &[u8]) -> Result<Self> {
+        // the parameters follow the protocol version, and end with an empty name
+        let mut strings = body[4..].split(|byte| *byte == 0).map(|string| {
+            String::from_utf8(string.to_vec())
+                .map_err(|_| malformed("invalid postgres startup parameter"))
+        });
+        let mut parameters = Vec::new();
+        loop {
+            let name = strings.next().transpose()?.unwrap_or_default();
+            if name.is_empty() {
+                break;
+            }
+            let value = strings
+                .next()
+                .transpose()?
+                .ok_or_else(|| malformed("truncated postgres startup message"))?;
+            parameters.push((name, value));
+        }
+        Ok(Self { parameters })
+    }
+
+    pub(super) fn encode(&self) -> Bytes {
+        let mut body = BytesMut::new();
+        body.put_u32(PROTOCOL_VERSION_3);
+        for (name, value) in &self.parameters {
+            put_string(&mut body, name);
+            put_string(&mut body, value);
+        }
+        body.put_u8(0);
+
+        let mut buffer = BytesMut::with_capacity(body.len() + 4);
+        buffer.put_u32(body.len() as u32 + 4);
+        buffer.put_slice(&body);
+        buffer.freeze()
+    }
+
+    pub(super) fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(parameter_name, _)| parameter_name == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub(super) fn set_parameter(&mut self, name: &str, value: &str) {
+        match self.parameters.iter_mut().find(|(n, _)| n == name) {
+            Some((_, parameter_value)) => *parameter_value = value.to_string(),
+            None => self.parameters.push((name.to_string(), value.to_string())),
+        }
+    }
+}
+
+/// Authentication request sent by the database
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) enum Authentication {
+    Ok,
+    CleartextPassword,
+    Md5Password,
+    Sasl { mechanisms: Vec<String> },
+    SaslContinue(Bytes),
+    SaslFinal(Bytes),
+    Other(u32),
+}
+
+impl Authentication {
+    pub(super) fn decode(body: &Bytes) -> Result<Self> {
+        let authentication = match startup_code(body)? {
+            0 => Authentication::Ok,
+            3 => Authentication::CleartextPassword,
+            5 => Authentication::Md5Password,
+            10 => Authentication::Sasl {
+                mechanisms: body[4..]
+                    .split(|byte| *byte == 0)
+                    .filter(|mechanism| !mechanism.is_empty())
+                    .map(|mechanism| String::from_utf8_lossy(mechanism).to_string())
+                    .collect(),
+            },
+            11 => Authentication::SaslContinue(body.slice(4..)),
+            12 => Authentication::SaslFinal(body.slice(4..)),
+            code => Authentication::Other(code),
+        };
+        Ok(authentication)
+    }
+}
+
+/// Password sent in clear text, when the database asks for it
+pub(super) fn password_message(password: &str) -> Message {
+    let mut body = BytesMut::new();
+    put_string(&mut body, password);
+    Message::new(PASSWORD_MESSAGE, body)
+}
+
+/// First message of a SASL authentication, with the selected mechanism
+pub(super) fn sasl_initial_response(mechanism: &str, data: &[u8]) -> Message {
+    let mut body = BytesMut::new();
+    put_string(&mut body, mechanism);
+    body.put_u32(data.len() as u32);
+    body.put_slice(data);
+    Message::new(PASSWORD_MESSAGE, body)
+}
+
+/// Next messages of a SASL authentication
+pub(super) fn sasl_response(data: &[u8]) -> Message {
+    Message::new(PASSWORD_MESSAGE, Bytes::copy_from_slice(data))
+}
+
+/// Fatal error sent to a client, which then closes its connection
+pub(super) fn fatal_error(code: &str, message: &str) -> Message {
+    let mut body = BytesMut::new();
+    for (field, value) in [
+        (b'S', "FATAL"),
+        (b'V', "FATAL"),
+        (b'C', code),
+        (b'M', message),
+    ] {
+        body.put_u8(field);
+        put_string(&mut body, value);
+    }
+    body.put_u8(0);
+    Message::new(ERROR_RESPONSE, body)
+}
+
+fn put_string(buffer: &mut BytesMut, value: &str) {
+    buffer.put_slice(value.as_bytes());
+    buffer.put_u8(0);
+}
+
+pub(super) fn malformed(message: &str) -> Error {
+    Error::new(Origin::Transport, Kind::Protocol, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn startup_message_bytes() -> BytesMut {
+        let mut body = BytesMut::new();
+        body.put_u32(PROTOCOL_VERSION_3);
+        body.put_slice(b"user\0alice\0application_name\0psql\0\0");
+        let mut bytes = BytesMut::new();
+        bytes.put_u32(body.len() as u32 + 4);
+        bytes.put_slice(&body);
+        bytes
+    }
+
+    #[test]
+    fn startup_messages_are_decoded_once_complete() -> Result<()> {
+        let bytes = startup_message_bytes();
+        let mut decoder = MessageDecoder::new();
+        decoder.extend(&bytes[..10]);
+        assert!(decoder.next_startup_message()?.is_none());
+        decoder.extend(&bytes[10..]);
+
+        let body = decoder.next_startup_message()?.unwrap();
+        assert_eq!(startup_code(&body)?, PROTOCOL_VERSION_3);
+        let mut startup_message = StartupMessage::decode(&body)?;
+        assert_eq!(startup_message.parameter("user"), Some("alice"));
+        assert_eq!(startup_message.parameter("database"), None);
+        assert_eq!(startup_message.encode(), bytes.freeze());
+
+        startup_message.set_parameter("user", "reporting");
+        startup_message.set_parameter("database", "alice");
+        let encoded = startup_message.encode();
+        let decoded = StartupMessage::decode(&encoded[4..])?;
+        assert_eq!(decoded.parameter("user"), Some("reporting"));
+        assert_eq!(decoded.parameter("database"), Some("alice"));
+        Ok(())
+    }
+
+    #[test]
+    fn typed_messages_are_split() -> Result<()> {
+        let mut bytes = BytesMut::new();
+        Message::new(AUTHENTICATION, vec![0, 0, 0, 3]).encode(&mut bytes);
+        fatal_error("28P01", "authentication failed").encode(&mut bytes);
+
+        let mut decoder = MessageDecoder::new();
+        decoder.extend(&bytes[..bytes.len() - 1]);
+        let message = decoder.next_message()?.unwrap();
+        assert_eq!(
+            Authentication::decode(&message.body)?,
+            Authentication::CleartextPassword
+        );
+        assert!(decoder.next_message()?.is_none());
+
+        decoder.extend(&bytes[bytes.len() - 1..]);
+        let message = decoder.next_message()?.unwrap();
+        assert_eq!(message.tag, ERROR_RESPONSE);
+        assert!(decoder.take_remaining().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn sasl_mechanisms_are_decoded() -> Result<()> {
+        let mut body = BytesMut::new();
+        body.put_u32(10);
+        body.put_slice(b"SCRAM-SHA-256-PLUS\0SCRAM-SHA-256\0\0");
+        let authentication = Authentication::decode(&body.freeze())?;
+        let mechanisms = vec![
+            "SCRAM-SHA-256-PLUS".to_string(),
+            "SCRAM-SHA-256".to_string(),
+        ];
+        assert_eq!(authentication, Authentication::Sasl { mechanisms });
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+//! PostgreSQL outlets: the startup of each connection is handled by the outlet, so that
+//! the clients connect with the database role granted by the credential of their identity,
+//! and the outlet authenticates this role with a password which never leaves its node.
+
+mod interceptor;
+mod listener;
+mod messages;
+mod passfile;
+mod portal_worker;
+mod role_mapping;
+mod scram;
+
+pub(crate) use listener::{DatabaseOutlet, PostgresOutletListener};
+pub use passfile::PasswordFile;
+pub use role_mapping::{RoleMapping, DEFAULT_ROLE_ATTRIBUTE};
+
+/// Address of the TCP outlet to the database of a postgres outlet
+pub fn database_outlet_address(service_address: &str) -> String {
+    format!("{service_address}_database")
+}
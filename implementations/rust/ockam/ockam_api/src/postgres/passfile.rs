@@ -0,0 +1,140 @@
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use std::path::Path;
+
+/// Passwords of the database roles, in the format of a PostgreSQL password file:
+/// `hostname:port:database:username:password` on each line, where the first four fields
+/// can be `*` to match anything.
+///
+/// The file stays on the node running the outlet, so that the clients never know the
+/// passwords of the database.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PasswordFile {
+    entries: Vec<PasswordEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct PasswordEntry {
+    hostname: String,
+    port: String,
+    database: String,
+    username: String,
+    password: String,
+}
+
+impl PasswordFile {
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            Error::new(
+                Origin::Api,
+                Kind::Io,
+                format!("Can't read the password file {}: {e}", path.display()),
+            )
+        })?;
+        Self::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut entries = vec![];
+        for (index, line) in content.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields = split_fields(line);
+            match <[String; 5]>::try_from(fields) {
+                Ok([hostname, port, database, username, password]) => {
+                    entries.push(PasswordEntry {
+                        hostname,
+                        port,
+                        database,
+                        username,
+                        password,
+                    });
+                }
+                Err(_) => {
+                    return Err(Error::new(
+                        Origin::Api,
+                        Kind::Invalid,
+                        format!("Invalid entry at line {} of the password file", index + 1),
+                    ));
+                }
+            }
+        }
+        Ok(Self { entries })
+    }
+
+    /// Return the password of the first entry matching a connection
+    pub fn password(&self, hostname: &str, port: u16, database: &str, user: &str) -> Option<&str> {
+        let port = port.to_string();
+        self.entries
+            .iter()
+            .find(|entry| {
+                matches(&entry.hostname, hostname)
+                    && matches(&entry.port, &port)
+                    && matches(&entry.database, database)
+                    && matches(&entry.username, user)
+            })
+            .map(|entry| entry.password.as_str())
+    }
+}
+
+fn matches(pattern: &str, value: &str) -> bool {
+    pattern == "*" || pattern == value
+}
+
+/// Split a line on the `:` characters which are not escaped with a `\`
+fn split_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    fields.last_mut().unwrap().push(escaped);
+                }
+            }
+            ':' => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_first_matching_entry_is_used() -> Result<()> {
+        let password_file = PasswordFile::parse(
+            "# reporting database\n\
+             db.local:5432:reports:reporting:s3cr\\:et\n\
+             \n\
+             *:*:*:reporting:other\n\
+             *:5432:*:admin:p\\\\ss\n",
+        )?;
+        assert_eq!(
+            password_file.password("db.local", 5432, "reports", "reporting"),
+            Some("s3cr:et")
+        );
+        assert_eq!(
+            password_file.password("db.local", 5433, "reports", "reporting"),
+            Some("other")
+        );
+        assert_eq!(
+            password_file.password("localhost", 5432, "app", "admin"),
+            Some("p\\ss")
+        );
+        assert_eq!(
+            password_file.password("localhost", 5433, "app", "admin"),
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn entries_need_five_fields() {
+        assert!(PasswordFile::parse("localhost:5432:postgres:password").is_err());
+    }
+}
@@ -0,0 +1,296 @@
+use bytes::BytesMut;
+use core::sync::atomic::AtomicBool;
+use core::sync::atomic::Ordering;
+use ockam_core::compat::sync::Arc;
+use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
+use ockam_core::{
+    errcode::{Kind, Origin},
+    route, Address, AllowOnwardAddress, AllowSourceAddress, AnyIncomingAccessControl,
+    AnyOutgoingAccessControl, Encodable, Error, IncomingAccessControl, LocalInfo, LocalMessage,
+    NeutralMessage, OutgoingAccessControl, Result, Route, Routed, Worker,
+};
+use ockam_node::{Context, WorkerBuilder};
+use ockam_transport_tcp::{PortalMessage, MAX_PAYLOAD_SIZE};
+
+use crate::postgres::interceptor::PostgresInterceptor;
+
+enum Receiving {
+    ClientMessages,
+    DatabaseMessages,
+}
+
+/// Relays the messages exchanged by a PostgreSQL client and the database, between the
+/// secure channel of the client and the TCP outlet of the database, like the Kafka portal
+/// workers on the outlet side.
+///
+/// The messages sent by the client and the messages sent by the database are handled by two
+/// different workers, sharing the same interceptor. The messages which the interceptor
+/// sends back to their sender, like the authentication of the role, are given to the other
+/// worker, which relays them unchanged.
+pub(super) struct PostgresPortalWorker {
+    // The instance of worker managing the other direction
+    // The first one to receive the disconnect message will stop both workers
+    other_worker_address: Address,
+    receiving: Receiving,
+    interceptor: Arc<PostgresInterceptor>,
+    disconnect_received: Arc<AtomicBool>,
+    // The route to the database outlet for the worker receiving the client messages, and
+    // the route to the client inlet, known once the pong is received, for the other worker
+    peer_route: Option<Route>,
+    // The information about the secure channel of the client, required by the policy
+    // of the database outlet, including for the messages sent by the interceptor
+    local_info: Vec<LocalInfo>,
+}
+
+#[ockam::worker]
+impl Worker for PostgresPortalWorker {
+    type Message = NeutralMessage;
+    type Context = Context;
+
+    async fn handle_message(
+        &mut self,
+        context: &mut Self::Context,
+        routed_message: Routed<Self::Message>,
+    ) -> Result<()> {
+        let from_other_worker = routed_message.src_addr() == self.other_worker_address;
+        if !from_other_worker && matches!(self.receiving, Receiving::ClientMessages) {
+            self.local_info = routed_message.local_message().local_info();
+        }
+        let portal_message = PortalMessage::decode(routed_message.payload())?;
+
+        match portal_message {
+            // the messages of the other worker were already intercepted
+            PortalMessage::Payload(message, _) if from_other_worker => {
+                self.send_bytes(context, message).await?
+            }
+            PortalMessage::Payload(message, _) => {
+                let intercepted = match self.receiving {
+                    Receiving::ClientMessages => {
+                        self.interceptor.intercept_client_bytes(message)?
+                    }
+                    Receiving::DatabaseMessages => {
+                        self.interceptor.intercept_database_bytes(message)?
+                    }
+                };
+                if !intercepted.forward.is_empty() {
+                    self.send_bytes(context, &intercepted.forward).await?;
+                }
+                if !intercepted.reply.is_empty() {
+                    self.send_to_other_worker(context, intercepted.reply)
+                        .await?;
+                }
+            }
+            PortalMessage::Disconnect => {
+                let return_route = routed_message.return_route();
+                self.forward(context, routed_message).await?;
+
+                // The first one to receive disconnect and to swap the atomic will stop both workers
+                let disconnect_received = self.disconnect_received.swap(true, Ordering::SeqCst);
+                if !disconnect_received {
+                    trace!(
+                        "{:?} received disconnect event from {:?}",
+                        context.address(),
+                        return_route
+                    );
+                    context
+                        .stop_worker(self.other_worker_address.clone())
+                        .await?;
+                    context.stop_worker(context.address()).await?;
+                }
+            }
+            PortalMessage::Ping | PortalMessage::PingWithClient(None, _) => {
+                self.forward(context, routed_message).await?
+            }
+            PortalMessage::Pong => match self.receiving {
+                Receiving::ClientMessages => {
+                    // if we receive a pong message it means it must be from the other worker
+                    if from_other_worker {
+                        debug!("updating the route to the database outlet worker");
+                        self.peer_route = Some(routed_message.return_route());
+                    }
+                }
+                Receiving::DatabaseMessages => {
+                    // forward the pong also to the other worker to update its route
+                    // with the route of the database outlet worker
+                    let local_message = routed_message
+                        .local_message()
+                        .clone()
+                        .set_onward_route(route![self.other_worker_address.clone()]);
+                    context.forward(local_message).await?;
+
+                    let mut client_route = routed_message.onward_route();
+                    client_route.step()?;
+                    self.peer_route = Some(client_route);
+                    self.forward(context, routed_message).await?
+                }
+            },
+            // compression is never negotiated by the postgres outlets, since the messages
+            // need to be inspected
+            PortalMessage::PingWithCompression(_)
+            | PortalMessage::PingWithClient(Some(_), _)
+            | PortalMessage::PongWithCompression(_)
+            | PortalMessage::CompressedPayload(_) => {
+                return Err(Error::new(
+                    Origin::Transport,
+                    Kind::Protocol,
+                    "compressed portal messages are not supported by postgres outlets",
+                ));
+            }
+            // the messages are modified by the postgres outlets, so the bytes lost while
+            // a connection is suspended can't be sent again
+            PortalMessage::Resume(_) => {
+                return Err(Error::new(
+                    Origin::Transport,
+                    Kind::Protocol,
+                    "resumed connections are not supported by postgres outlets",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl PostgresPortalWorker {
+    async fn forward(
+        &self,
+        context: &mut Context,
+        routed_message: Routed<NeutralMessage>,
+    ) -> Result<()> {
+        let mut local_message = routed_message.into_local_message();
+        local_message = match (&self.receiving, &self.peer_route) {
+            (Receiving::ClientMessages, Some(peer_route)) => local_message
+                .set_onward_route(peer_route.clone())
+                .push_front_return_route(&self.other_worker_address),
+            // Since the other worker forces the route to the database,
+            // we can omit the previous return route.
+            _ => local_message
+                .pop_front_onward_route()?
+                .set_return_route(route![self.other_worker_address.clone()]),
+        };
+        context.forward(local_message).await
+    }
+
+    /// Send bytes to the peer of this worker, the database or the client
+    async fn send_bytes(&self, context: &mut Context, buffer: &[u8]) -> Result<()> {
+        let onward_route = self.peer_route.clone().ok_or_else(|| {
+            Error::new(
+                Origin::Transport,
+                Kind::Misuse,
+                "the postgres connection is not established yet",
+            )
+        })?;
+        let return_route = route![self.other_worker_address.clone()];
+
+        for chunk in buffer.chunks(MAX_PAYLOAD_SIZE) {
+            let message = LocalMessage::new()
+                .with_onward_route(onward_route.clone())
+                .with_return_route(return_route.clone())
+                .with_payload(PortalMessage::Payload(chunk, None).encode()?)
+                .with_local_info(self.local_info.clone());
+
+            context.forward(message).await?;
+        }
+        Ok(())
+    }
+
+    /// Give bytes to the other worker, which sends them unchanged to its peer
+    async fn send_to_other_worker(&self, context: &mut Context, buffer: BytesMut) -> Result<()> {
+        for chunk in buffer.chunks(MAX_PAYLOAD_SIZE) {
+            let message = LocalMessage::new()
+                .with_onward_route(route![self.other_worker_address.clone()])
+                .with_return_route(route![context.address()])
+                .with_payload(PortalMessage::Payload(chunk, None).encode()?);
+
+            context.forward(message).await?;
+        }
+        Ok(())
+    }
+
+    /// Create the two workers relaying the messages of a connection accepted by a postgres
+    /// outlet. Returns the address of the worker receiving the messages sent by the client
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn create_outlet_side_postgres_portal(
+        context: &Context,
+        interceptor: Arc<PostgresInterceptor>,
+        database_outlet_address: Address,
+        flow_controls: &FlowControls,
+        secure_channel_flow_control_id: Option<FlowControlId>,
+        spawner_flow_control_id: Option<FlowControlId>,
+        request_incoming_access_control: Arc<dyn IncomingAccessControl>,
+        response_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    ) -> Result<Address> {
+        let client_worker_address = Address::random_tagged("PostgresPortalWorker.client");
+        let database_worker_address = Address::random_tagged("PostgresPortalWorker.database");
+        let disconnect_received = Arc::new(AtomicBool::new(false));
+
+        let client_worker = Self {
+            interceptor: interceptor.clone(),
+            other_worker_address: database_worker_address.clone(),
+            receiving: Receiving::ClientMessages,
+            disconnect_received: disconnect_received.clone(),
+            peer_route: Some(route![database_outlet_address.clone()]),
+            local_info: vec![],
+        };
+        let database_worker = Self {
+            interceptor,
+            other_worker_address: client_worker_address.clone(),
+            receiving: Receiving::DatabaseMessages,
+            disconnect_received,
+            peer_route: None,
+            local_info: vec![],
+        };
+
+        let flow_control_id = FlowControls::generate_flow_control_id();
+
+        // the database outlet receives the messages of the client worker, and the database
+        // worker receives the replies of the interceptor
+        flow_controls.add_consumer(database_outlet_address, &flow_control_id);
+        flow_controls.add_consumer(database_worker_address.clone(), &flow_control_id);
+
+        flow_controls.add_producer(
+            client_worker_address.clone(),
+            &flow_control_id,
+            spawner_flow_control_id.as_ref(),
+            vec![],
+        );
+
+        if let Some(secure_channel_flow_control_id) = secure_channel_flow_control_id.as_ref() {
+            flow_controls.add_consumer(
+                client_worker_address.clone(),
+                secure_channel_flow_control_id,
+            );
+        }
+
+        // allow the other worker to forward the `pong` message
+        WorkerBuilder::new(client_worker)
+            .with_address(client_worker_address.clone())
+            .with_incoming_access_control_arc(Arc::new(AnyIncomingAccessControl::new(vec![
+                Arc::new(AllowSourceAddress(database_worker_address.clone())),
+                request_incoming_access_control,
+            ])))
+            .with_outgoing_access_control_arc(Arc::new(FlowControlOutgoingAccessControl::new(
+                flow_controls,
+                flow_control_id.clone(),
+                spawner_flow_control_id.clone(),
+            )))
+            .start(context)
+            .await?;
+
+        // allow forwarding the `pong` message to the other worker
+        let response_outgoing_access_control = {
+            AnyOutgoingAccessControl::new(vec![
+                Arc::new(AllowOnwardAddress::new(client_worker_address.clone())),
+                response_outgoing_access_control,
+            ])
+        };
+
+        WorkerBuilder::new(database_worker)
+            .with_address(database_worker_address)
+            .with_outgoing_access_control(response_outgoing_access_control)
+            .start(context)
+            .await?;
+
+        Ok(client_worker_address)
+    }
+}
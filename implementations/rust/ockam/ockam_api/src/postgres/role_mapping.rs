@@ -0,0 +1,89 @@
+use minicbor::{Decode, Encode};
+use ockam::identity::AttributesEntry;
+
+/// Name of the credential attribute holding the database role of an identity
+pub const DEFAULT_ROLE_ATTRIBUTE: &str = "postgres_role";
+
+/// Selects the database role used by a client, from the attributes of its identity.
+///
+/// The role requested by the client in its startup message is ignored, so that a client
+/// can only connect with the role its credential grants.
+#[derive(Clone, Debug, PartialEq, Eq, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RoleMapping {
+    #[n(1)] role_attribute: String,
+    #[n(2)] default_role: Option<String>,
+}
+
+impl Default for RoleMapping {
+    fn default() -> Self {
+        Self::new(DEFAULT_ROLE_ATTRIBUTE)
+    }
+}
+
+impl RoleMapping {
+    pub fn new(role_attribute: impl Into<String>) -> Self {
+        Self {
+            role_attribute: role_attribute.into(),
+            default_role: None,
+        }
+    }
+
+    /// Role used by the identities without the role attribute.
+    /// Without a default role, these identities can't connect to the database
+    pub fn with_default_role(mut self, default_role: impl Into<String>) -> Self {
+        self.default_role = Some(default_role.into());
+        self
+    }
+
+    pub fn role_attribute(&self) -> &str {
+        &self.role_attribute
+    }
+
+    pub fn default_role(&self) -> Option<&str> {
+        self.default_role.as_deref()
+    }
+
+    /// Return the role of an identity, given its attributes attested by the project authority
+    pub(crate) fn role_for(&self, attributes: Option<&AttributesEntry>) -> Option<String> {
+        attributes
+            .and_then(|entry| entry.attrs().get(self.role_attribute.as_bytes()))
+            .and_then(|role| String::from_utf8(role.clone()).ok())
+            .filter(|role| !role.is_empty())
+            .or_else(|| self.default_role.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::Result;
+
+    #[test]
+    fn the_role_is_taken_from_the_attributes() -> Result<()> {
+        let attributes =
+            AttributesEntry::single(b"postgres_role".to_vec(), b"reporting".to_vec(), None, None)?;
+        let other_attributes =
+            AttributesEntry::single(b"kafka_role".to_vec(), b"admin".to_vec(), None, None)?;
+
+        let role_mapping = RoleMapping::default();
+        assert_eq!(
+            role_mapping.role_for(Some(&attributes)),
+            Some("reporting".to_string())
+        );
+        assert_eq!(role_mapping.role_for(Some(&other_attributes)), None);
+        assert_eq!(role_mapping.role_for(None), None);
+
+        let role_mapping = role_mapping.with_default_role("readonly");
+        assert_eq!(
+            role_mapping.role_for(Some(&attributes)),
+            Some("reporting".to_string())
+        );
+        assert_eq!(
+            role_mapping.role_for(Some(&other_attributes)),
+            Some("readonly".to_string())
+        );
+        Ok(())
+    }
+}
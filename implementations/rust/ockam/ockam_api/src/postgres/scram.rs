@@ -0,0 +1,178 @@
+use crate::kafka::record_encryption::hmac_sha256;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// The only SASL mechanism supported by PostgreSQL without channel binding
+pub(super) const SCRAM_SHA_256: &str = "SCRAM-SHA-256";
+
+/// Header of the client messages: no channel binding and no authorization identity
+const GS2_HEADER: &str = "n,,";
+
+/// Client side of a SCRAM-SHA-256 authentication, as described in RFC 5802 and RFC 7677
+pub(super) struct ScramClient {
+    password: String,
+    client_first_bare: String,
+    state: ScramState,
+}
+
+enum ScramState {
+    Started { nonce: String },
+    Proved { server_signature: Vec<u8> },
+}
+
+impl ScramClient {
+    /// PostgreSQL ignores the user name of the SCRAM messages, and uses the one of the
+    /// startup message instead
+    pub(super) fn new(password: &str) -> Self {
+        let mut nonce = [0u8; 18];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        Self::with_nonce("", password, &STANDARD.encode(nonce))
+    }
+
+    fn with_nonce(username: &str, password: &str, nonce: &str) -> Self {
+        Self {
+            password: password.to_string(),
+            client_first_bare: format!("n={username},r={nonce}"),
+            state: ScramState::Started {
+                nonce: nonce.to_string(),
+            },
+        }
+    }
+
+    /// Message starting the authentication
+    pub(super) fn client_first_message(&self) -> String {
+        format!("{GS2_HEADER}{}", self.client_first_bare)
+    }
+
+    /// Answer to the salt and iteration count sent by the server, with the proof that the
+    /// password is known
+    pub(super) fn client_final_message(&mut self, server_first_message: &str) -> Result<String> {
+        let nonce = match &self.state {
+            ScramState::Started { nonce } => nonce,
+            ScramState::Proved { .. } => return Err(scram_error("unexpected SCRAM message")),
+        };
+        let server_nonce = attribute(server_first_message, 'r')?;
+        if !server_nonce.starts_with(nonce.as_str()) {
+            return Err(scram_error("invalid SCRAM server nonce"));
+        }
+        let salt = STANDARD
+            .decode(attribute(server_first_message, 's')?)
+            .map_err(|_| scram_error("invalid SCRAM salt"))?;
+        let iterations: u32 = attribute(server_first_message, 'i')?
+            .parse()
+            .map_err(|_| scram_error("invalid SCRAM iteration count"))?;
+
+        let salted_password = salted_password(self.password.as_bytes(), &salt, iterations);
+        let client_key = hmac_sha256(&salted_password, &[b"Client Key"]);
+        let stored_key = Sha256::digest(&client_key);
+
+        let client_final_without_proof =
+            format!("c={},r={server_nonce}", STANDARD.encode(GS2_HEADER));
+        let auth_message = format!(
+            "{},{server_first_message},{client_final_without_proof}",
+            self.client_first_bare
+        );
+        let client_signature = hmac_sha256(&stored_key, &[auth_message.as_bytes()]);
+        let proof: Vec<u8> = client_key
+            .iter()
+            .zip(client_signature)
+            .map(|(key, signature)| key ^ signature)
+            .collect();
+
+        let server_key = hmac_sha256(&salted_password, &[b"Server Key"]);
+        self.state = ScramState::Proved {
+            server_signature: hmac_sha256(&server_key, &[auth_message.as_bytes()]),
+        };
+        Ok(format!(
+            "{client_final_without_proof},p={}",
+            STANDARD.encode(proof)
+        ))
+    }
+
+    /// Check that the server knows the password too
+    pub(super) fn verify_server_final_message(&self, server_final_message: &str) -> Result<()> {
+        let server_signature = match &self.state {
+            ScramState::Proved { server_signature } => server_signature,
+            ScramState::Started { .. } => return Err(scram_error("unexpected SCRAM message")),
+        };
+        if let Ok(error) = attribute(server_final_message, 'e') {
+            return Err(scram_error(&format!(
+                "SCRAM authentication failed: {error}"
+            )));
+        }
+        let signature = STANDARD
+            .decode(attribute(server_final_message, 'v')?)
+            .map_err(|_| scram_error("invalid SCRAM server signature"))?;
+        if signature != *server_signature {
+            return Err(scram_error("invalid SCRAM server signature"));
+        }
+        Ok(())
+    }
+}
+
+/// The `Hi` function of RFC 5802, which is PBKDF2 with HMAC-SHA-256
+fn salted_password(password: &[u8], salt: &[u8], iterations: u32) -> Vec<u8> {
+    let mut u = hmac_sha256(password, &[salt, &1u32.to_be_bytes()]);
+    let mut result = u.clone();
+    for _ in 1..iterations {
+        u = hmac_sha256(password, &[u.as_slice()]);
+        for (r, byte) in result.iter_mut().zip(&u) {
+            *r ^= byte;
+        }
+    }
+    result
+}
+
+fn attribute(message: &str, name: char) -> Result<&str> {
+    message
+        .split(',')
+        .find_map(|attribute| attribute.strip_prefix(name)?.strip_prefix('='))
+        .ok_or_else(|| scram_error(&format!("missing SCRAM attribute {name}")))
+}
+
+fn scram_error(message: &str) -> Error {
+    Error::new(Origin::Transport, Kind::Protocol, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Example of RFC 7677
+    #[test]
+    fn authenticate_with_scram_sha_256() -> Result<()> {
+        let mut client = ScramClient::with_nonce("user", "pencil", "rOprNGfwEbeRWgbNEkqO");
+        assert_eq!(
+            client.client_first_message(),
+            "n,,n=user,r=rOprNGfwEbeRWgbNEkqO"
+        );
+
+        let client_final_message = client.client_final_message(
+            "r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,\
+             s=W22ZaJ0SNY7soEsUEjb6gQ==,i=4096",
+        )?;
+        assert_eq!(
+            client_final_message,
+            "c=biws,r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,\
+             p=dHzbZapWIk4jUhN+Ute9ytag9zjfMHgsqmmiz7AndVQ="
+        );
+
+        client.verify_server_final_message("v=6rriTRBi23WpRR/wtup+mMhUZUn/dB5nLTJRsjl95G4=")?;
+        assert!(client.verify_server_final_message("v=AAAA").is_err());
+        assert!(client
+            .verify_server_final_message("e=invalid-proof")
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn reject_a_server_nonce_not_extending_the_client_one() {
+        let mut client = ScramClient::with_nonce("", "pencil", "abc");
+        let result = client.client_final_message("r=xyz,s=W22ZaJ0SNY7soEsUEjb6gQ==,i=4096");
+        assert!(result.is_err());
+    }
+}
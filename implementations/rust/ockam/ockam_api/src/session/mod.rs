@@ -29,6 +29,7 @@ pub struct Medic {
     registry: Arc<Registry>,
     pings: JoinSet<(String, Result<(), Error>)>,
     replacements: JoinSet<(String, Result<ReplacerOutcome, Error>)>,
+    replacement_checks: JoinSet<(String, bool)>,
 }
 
 #[derive(Debug, Clone, Encode, Decode)]
@@ -54,6 +55,7 @@ impl Medic {
             registry,
             pings: JoinSet::new(),
             replacements: JoinSet::new(),
+            replacement_checks: JoinSet::new(),
         }
     }
 
@@ -137,6 +139,12 @@ impl Medic {
                             self.pings
                                 .spawn(async move { (key, sender.forward(local_message).await) });
                         };
+
+                        // check if a healthy session should still be replaced with a better one
+                        let replacer = session.replacer();
+                        let key = session.key().to_string();
+                        self.replacement_checks
+                            .spawn(async move { (key, replacer.should_be_replaced().await) });
                     } else {
                         // We reached the maximum number of failures
                         match session.connection_status() {
@@ -215,6 +223,24 @@ impl Medic {
                         }
                     }
                 },
+                c = self.replacement_checks.join_next(),
+                    if !self.replacement_checks.is_empty() => match c {
+                    None                 => log::debug!("no replacement checks"),
+                    Some(Err(e))         => log::error!("task failed: {e:?}"),
+                    Some(Ok((_, false))) => {}
+                    Some(Ok((key, true))) => {
+                        if let Some(session) = self.session(&key).await {
+                            if session.connection_status() == ConnectionStatus::Up {
+                                log::info!(%key, "replacing healthy session");
+                                session.degraded();
+                                let replacer = session.replacer();
+                                self.replacements.spawn(async move {
+                                    (key, replacer.recreate().await)
+                                });
+                            }
+                        }
+                    }
+                },
                 Some(message) = ping_receiver.recv() => {
                     log::trace!("received pong");
                     if let Some(session) = self.session(&message.key).await {
@@ -337,6 +363,7 @@ mod tests {
     struct MockReplacer {
         pub called: Arc<AtomicBool>,
         pub can_return: Arc<AtomicBool>,
+        pub should_be_replaced: Arc<AtomicBool>,
     }
 
     impl MockReplacer {
@@ -344,6 +371,7 @@ mod tests {
             Self {
                 called: Arc::new(AtomicBool::new(false)),
                 can_return: Arc::new(AtomicBool::new(false)),
+                should_be_replaced: Arc::new(AtomicBool::new(false)),
             }
         }
     }
@@ -366,6 +394,10 @@ mod tests {
         }
 
         async fn close(&mut self) {}
+
+        async fn should_be_replaced(&mut self) -> bool {
+            self.should_be_replaced.swap(false, Ordering::AcqRel)
+        }
     }
 
     #[ockam::test]
@@ -419,6 +451,7 @@ mod tests {
                     bind_addr: "127.0.0.1:10000".to_string(),
                     outlet_addr: MultiAddr::default(),
                     session: session.clone(),
+                    stats: Default::default(),
                 },
             )
             .await;
@@ -451,4 +484,58 @@ mod tests {
         medic_task.abort();
         ctx.stop().await
     }
+
+    #[ockam::test]
+    async fn test_replace_healthy_session(ctx: &mut Context) -> Result<()> {
+        let registry = Arc::new(Registry::default());
+        let medic = Medic::new_extended(
+            registry.clone(),
+            Duration::from_secs(1),
+            Duration::from_secs(1),
+        );
+        let medic_task = medic.start(ctx.async_try_clone().await?).await?;
+        ctx.start_worker(Address::from_string("echo"), Echoer)
+            .await?;
+        ctx.start_worker(Address::from_string("hop"), Hop).await?;
+
+        let mock_replacer = MockReplacer::new();
+        mock_replacer.can_return.store(true, Ordering::Release);
+        let session = Session::new(mock_replacer.clone());
+
+        // the session is healthy
+        session.up(ReplacerOutcome {
+            ping_route: route!["hop"],
+            kind: ReplacerOutputKind::Inlet(CurrentInletStatus {
+                route: route!["hop"],
+                worker: Address::from_string("echo"),
+                connection_status: ConnectionStatus::Up,
+            }),
+        });
+        registry
+            .inlets
+            .insert(
+                "inlet-1".into(),
+                crate::nodes::registry::InletInfo {
+                    bind_addr: "127.0.0.1:10000".to_string(),
+                    outlet_addr: MultiAddr::default(),
+                    session: session.clone(),
+                    stats: Default::default(),
+                },
+            )
+            .await;
+
+        // but the replacer asks for a replacement, for example to use a preferred route
+        mock_replacer
+            .should_be_replaced
+            .store(true, Ordering::Release);
+        while !mock_replacer.called.load(Ordering::Acquire) {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        while session.connection_status() != ConnectionStatus::Up {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        medic_task.abort();
+        ctx.stop().await
+    }
 }
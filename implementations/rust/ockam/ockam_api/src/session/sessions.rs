@@ -24,6 +24,12 @@ pub const MAX_CONNECT_TIME: Duration = Duration::from_secs(15);
 pub trait SessionReplacer: Send + 'static {
     async fn create(&mut self) -> Result<ReplacerOutcome, Error>;
     async fn close(&mut self) -> ();
+
+    /// Return true if the session should be replaced even though it is healthy,
+    /// for example to move back to a preferred route
+    async fn should_be_replaced(&mut self) -> bool {
+        false
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -66,6 +72,10 @@ impl InnerSessionReplacer {
         self.inner.lock().await.close().await
     }
 
+    pub async fn should_be_replaced(&self) -> bool {
+        self.inner.lock().await.should_be_replaced().await
+    }
+
     pub async fn recreate(&self) -> Result<ReplacerOutcome, Error> {
         self.close().await;
         self.create().await
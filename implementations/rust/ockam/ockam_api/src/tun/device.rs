@@ -0,0 +1,131 @@
+use crate::nodes::models::tun_portal::TunInterface;
+use nix::libc;
+use nix::sys::ioctl::ioctl_param_type;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::Result;
+use std::ffi::CStr;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::OpenOptionsExt;
+use tokio::io::unix::AsyncFd;
+use tokio::process::Command;
+
+nix::ioctl_write_int!(tun_set_iff, b'T', 202);
+
+/// A TUN device, removed with its addresses and routes once it is dropped
+#[derive(Debug)]
+pub(super) struct TunDevice {
+    name: String,
+    file: AsyncFd<File>,
+}
+
+impl TunDevice {
+    /// Create a TUN device exchanging IP packets without any additional header
+    pub(super) fn create(name: &str) -> Result<Self> {
+        if name.is_empty() || name.len() >= libc::IFNAMSIZ {
+            return Err(error(format!(
+                "the name of a TUN device must have between 1 and {} characters",
+                libc::IFNAMSIZ - 1
+            )));
+        }
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK | libc::O_CLOEXEC)
+            .open("/dev/net/tun")
+            .map_err(|e| error(format!("failed to open /dev/net/tun: {e}")))?;
+
+        // Safety: an ifreq structure only contains integers, for which zero is a valid value
+        let mut request: libc::ifreq = unsafe { core::mem::zeroed() };
+        for (c, b) in request.ifr_name.iter_mut().zip(name.as_bytes()) {
+            *c = *b as libc::c_char;
+        }
+        request.ifr_ifru.ifru_flags = (libc::IFF_TUN | libc::IFF_NO_PI) as libc::c_short;
+
+        // Safety: the request outlives the call, which fills in the actual name of the device
+        unsafe {
+            tun_set_iff(
+                file.as_raw_fd(),
+                &mut request as *mut libc::ifreq as ioctl_param_type,
+            )
+        }
+        .map_err(|e| error(format!("failed to create the TUN device {name}: {e}")))?;
+
+        // Safety: the kernel returns a nul-terminated name
+        let name = unsafe { CStr::from_ptr(request.ifr_name.as_ptr()) }
+            .to_string_lossy()
+            .into_owned();
+        let file = AsyncFd::new(file).map_err(|e| error(e.to_string()))?;
+
+        Ok(Self { name, file })
+    }
+
+    /// Actual name of the device
+    pub(super) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Set the address, MTU and routes of the device, and bring it up
+    pub(super) async fn configure(&self, interface: &TunInterface) -> Result<()> {
+        let mtu = interface.mtu.to_string();
+        ip(&[
+            "addr",
+            "add",
+            &interface.address.to_string(),
+            "dev",
+            &self.name,
+        ])
+        .await?;
+        ip(&["link", "set", "dev", &self.name, "mtu", &mtu, "up"]).await?;
+        for route in &interface.routes {
+            let network = route.network().to_string();
+            ip(&["route", "replace", &network, "dev", &self.name]).await?;
+        }
+        Ok(())
+    }
+
+    /// Read the next IP packet sent to the device
+    pub(super) async fn recv(&self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            let mut guard = self.file.readable().await?;
+            match guard.try_io(|file| file.get_ref().read(buf)) {
+                Ok(result) => return result,
+                Err(_would_block) => continue,
+            }
+        }
+    }
+
+    /// Write an IP packet to the device
+    pub(super) async fn send(&self, packet: &[u8]) -> std::io::Result<()> {
+        loop {
+            let mut guard = self.file.writable().await?;
+            match guard.try_io(|file| file.get_ref().write(packet)) {
+                Ok(result) => return result.map(|_| ()),
+                Err(_would_block) => continue,
+            }
+        }
+    }
+}
+
+/// Run an `ip` command to configure a device
+async fn ip(args: &[&str]) -> Result<()> {
+    let output = Command::new("ip")
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| error(format!("failed to run ip {}: {e}", args.join(" "))))?;
+    if !output.status.success() {
+        return Err(error(format!(
+            "ip {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+fn error(message: String) -> ockam_core::Error {
+    ockam_core::Error::new(Origin::Node, Kind::Io, message)
+}
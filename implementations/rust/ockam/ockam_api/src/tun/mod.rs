@@ -0,0 +1,53 @@
+//! IP-level portals bridging TUN devices through secure channels
+//!
+//! A TUN inlet reads the IP packets sent to its TUN device and forwards them to a TUN outlet,
+//! which writes them to its own TUN device. The outlet routes the reply packets back to the
+//! inlet owning their destination address.
+//!
+//! TUN devices are only supported on Linux, by nodes built with the `tun` feature.
+
+mod network;
+
+pub use network::*;
+
+use ockam_core::{Address, Result};
+use ockam_node::Context;
+
+cfg_if::cfg_if! {
+    if #[cfg(all(feature = "tun", target_os = "linux"))] {
+        mod device;
+        mod packet;
+        mod portal;
+
+        pub(crate) use portal::*;
+    } else {
+        mod unsupported;
+
+        pub(crate) use unsupported::*;
+    }
+}
+
+/// A TUN device bridged by a portal
+#[derive(Clone, Debug)]
+pub struct TunPortal {
+    device_name: String,
+    worker_address: Address,
+    processor_address: Address,
+}
+
+impl TunPortal {
+    /// Name of the TUN device, chosen by the kernel when the requested name ends with `%d`
+    pub fn device_name(&self) -> &str {
+        &self.device_name
+    }
+
+    /// Address of the worker receiving the packets of the other side of the portal
+    pub fn worker_address(&self) -> &Address {
+        &self.worker_address
+    }
+
+    /// Stop the portal, which removes its TUN device
+    pub(crate) async fn stop(&self, ctx: &Context) -> Result<()> {
+        ctx.stop_processor(self.processor_address.clone()).await
+    }
+}
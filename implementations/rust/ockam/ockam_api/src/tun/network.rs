@@ -0,0 +1,175 @@
+use minicbor::{decode, encode, Decode, Decoder, Encode, Encoder};
+use ockam_core::errcode::{Kind, Origin};
+use serde::{Serialize, Serializer};
+use std::fmt::{Display, Formatter};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+/// Default name of the TUN device of a portal, `%d` being replaced by a number by the kernel
+pub const DEFAULT_TUN_DEVICE_NAME: &str = "ockam%d";
+
+/// Default MTU of the TUN device of a portal
+pub const DEFAULT_TUN_MTU: u16 = 1500;
+
+/// An IP address with the prefix length of its network, for example `10.20.0.1/24`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TunNetwork {
+    address: IpAddr,
+    prefix_len: u8,
+}
+
+impl TunNetwork {
+    pub fn new(address: IpAddr, prefix_len: u8) -> ockam_core::Result<Self> {
+        let max_prefix_len = Self::max_prefix_len(&address);
+        if prefix_len > max_prefix_len {
+            return Err(ockam_core::Error::new(
+                Origin::Api,
+                Kind::Invalid,
+                format!("the prefix length of {address} can't be greater than {max_prefix_len}"),
+            ));
+        }
+        Ok(Self {
+            address,
+            prefix_len,
+        })
+    }
+
+    pub fn address(&self) -> IpAddr {
+        self.address
+    }
+
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Return the network containing the address, with all the host bits cleared
+    pub fn network(&self) -> TunNetwork {
+        let address = match self.address {
+            IpAddr::V4(address) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - self.prefix_len as u32)
+                    .unwrap_or(0);
+                IpAddr::V4(Ipv4Addr::from(u32::from(address) & mask))
+            }
+            IpAddr::V6(address) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - self.prefix_len as u32)
+                    .unwrap_or(0);
+                IpAddr::V6(Ipv6Addr::from(u128::from(address) & mask))
+            }
+        };
+        TunNetwork {
+            address,
+            prefix_len: self.prefix_len,
+        }
+    }
+
+    /// Return true if the address is part of this network
+    pub fn contains(&self, address: &IpAddr) -> bool {
+        match TunNetwork::new(*address, self.prefix_len) {
+            Ok(other) => other.network() == self.network(),
+            Err(_) => false,
+        }
+    }
+
+    fn max_prefix_len(address: &IpAddr) -> u8 {
+        match address {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        }
+    }
+}
+
+impl Display for TunNetwork {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix_len)
+    }
+}
+
+impl FromStr for TunNetwork {
+    type Err = ockam_core::Error;
+
+    /// Parse an address with an optional prefix length. Without a prefix length,
+    /// the network only contains that address
+    fn from_str(s: &str) -> ockam_core::Result<Self> {
+        let invalid = || {
+            ockam_core::Error::new(
+                Origin::Api,
+                Kind::Invalid,
+                format!("{s} is not a valid IP network, for example 10.20.0.1/24"),
+            )
+        };
+        let (address, prefix_len) = match s.split_once('/') {
+            Some((address, prefix_len)) => (address, Some(prefix_len)),
+            None => (s, None),
+        };
+        let address = IpAddr::from_str(address).map_err(|_| invalid())?;
+        let prefix_len = match prefix_len {
+            Some(prefix_len) => u8::from_str(prefix_len).map_err(|_| invalid())?,
+            None => Self::max_prefix_len(&address),
+        };
+        Self::new(address, prefix_len)
+    }
+}
+
+impl<C> Encode<C> for TunNetwork {
+    fn encode<W: encode::Write>(
+        &self,
+        e: &mut Encoder<W>,
+        ctx: &mut C,
+    ) -> Result<(), encode::Error<W::Error>> {
+        self.to_string().encode(e, ctx)
+    }
+}
+
+impl<'b, C> Decode<'b, C> for TunNetwork {
+    fn decode(d: &mut Decoder<'b>, ctx: &mut C) -> Result<Self, decode::Error> {
+        let network: &str = d.decode_with(ctx)?;
+        TunNetwork::from_str(network).map_err(|_| decode::Error::message("invalid IP network"))
+    }
+}
+
+impl Serialize for TunNetwork {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_network() -> ockam_core::Result<()> {
+        let network = TunNetwork::from_str("10.20.0.1/24")?;
+        assert_eq!(network.address(), IpAddr::V4(Ipv4Addr::new(10, 20, 0, 1)));
+        assert_eq!(network.prefix_len(), 24);
+        assert_eq!(network.to_string(), "10.20.0.1/24");
+
+        assert_eq!(TunNetwork::from_str("10.20.0.1")?.prefix_len(), 32);
+        assert_eq!(
+            TunNetwork::from_str("fd00::1/64")?.network().to_string(),
+            "fd00::/64"
+        );
+
+        assert!(TunNetwork::from_str("10.20.0.1/33").is_err());
+        assert!(TunNetwork::from_str("10.20.0/24").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_network_contains() -> ockam_core::Result<()> {
+        let network = TunNetwork::from_str("10.20.0.1/24")?;
+        assert_eq!(network.network().to_string(), "10.20.0.0/24");
+        assert!(network.contains(&IpAddr::V4(Ipv4Addr::new(10, 20, 0, 42))));
+        assert!(!network.contains(&IpAddr::V4(Ipv4Addr::new(10, 20, 1, 42))));
+        assert!(!network.contains(&IpAddr::V6(Ipv6Addr::LOCALHOST)));
+
+        let any = TunNetwork::from_str("0.0.0.0/0")?;
+        assert!(any.contains(&IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1))));
+        Ok(())
+    }
+}
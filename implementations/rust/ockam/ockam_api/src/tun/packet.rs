@@ -0,0 +1,68 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Source address of an IP packet, `None` if it is not a valid IPv4 or IPv6 packet
+pub(super) fn packet_source(packet: &[u8]) -> Option<IpAddr> {
+    match packet.first()? >> 4 {
+        4 => Some(IpAddr::V4(Ipv4Addr::from(ipv4(packet, 12)?))),
+        6 => Some(IpAddr::V6(Ipv6Addr::from(ipv6(packet, 8)?))),
+        _ => None,
+    }
+}
+
+/// Destination address of an IP packet, `None` if it is not a valid IPv4 or IPv6 packet
+pub(super) fn packet_destination(packet: &[u8]) -> Option<IpAddr> {
+    match packet.first()? >> 4 {
+        4 => Some(IpAddr::V4(Ipv4Addr::from(ipv4(packet, 16)?))),
+        6 => Some(IpAddr::V6(Ipv6Addr::from(ipv6(packet, 24)?))),
+        _ => None,
+    }
+}
+
+fn ipv4(packet: &[u8], offset: usize) -> Option<[u8; 4]> {
+    packet.get(offset..offset + 4)?.try_into().ok()
+}
+
+fn ipv6(packet: &[u8], offset: usize) -> Option<[u8; 16]> {
+    packet.get(offset..offset + 16)?.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ipv4_packet_addresses() {
+        let mut packet = vec![0u8; 20];
+        packet[0] = 0x45;
+        packet[12..16].copy_from_slice(&[10, 20, 0, 2]);
+        packet[16..20].copy_from_slice(&[192, 168, 1, 5]);
+
+        assert_eq!(
+            packet_source(&packet),
+            Some(IpAddr::V4(Ipv4Addr::new(10, 20, 0, 2)))
+        );
+        assert_eq!(
+            packet_destination(&packet),
+            Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 5)))
+        );
+        assert_eq!(packet_destination(&packet[..19]), None);
+    }
+
+    #[test]
+    fn test_ipv6_packet_addresses() {
+        let mut packet = vec![0u8; 40];
+        packet[0] = 0x60;
+        packet[8..24].copy_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        packet[24..40].copy_from_slice(&Ipv6Addr::UNSPECIFIED.octets());
+
+        assert_eq!(
+            packet_source(&packet),
+            Some(IpAddr::V6(Ipv6Addr::LOCALHOST))
+        );
+        assert_eq!(
+            packet_destination(&packet),
+            Some(IpAddr::V6(Ipv6Addr::UNSPECIFIED))
+        );
+        assert_eq!(packet_source(&[0x10; 40]), None);
+    }
+}
@@ -0,0 +1,307 @@
+use super::device::TunDevice;
+use super::packet::{packet_destination, packet_source};
+use super::{TunNetwork, TunPortal};
+use crate::nodes::models::tun_portal::TunInterface;
+use minicbor::{Decode, Encode};
+use ockam_core::compat::collections::HashMap;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::flow_control::FlowControlId;
+use ockam_core::{
+    async_trait, route, Address, Any, Decodable, DenyAll, Encodable, IncomingAccessControl,
+    LocalMessage, Message, OutgoingAccessControl, Processor, Result, Route, Routed, Worker,
+};
+use ockam_node::{Context, ProcessorBuilder, WorkerBuilder};
+use std::net::IpAddr;
+
+/// Message exchanged between a TUN Inlet and a TUN Outlet
+#[derive(Encode, Decode, Debug, Clone)]
+#[rustfmt::skip]
+enum TunPortalMessage {
+    /// An IP packet read from the TUN device of one side, to be written to the other side
+    #[n(0)] Packet(#[n(0)] Vec<u8>),
+}
+
+impl Encodable for TunPortalMessage {
+    fn encode(self) -> Result<Vec<u8>> {
+        Ok(minicbor::to_vec(self)?)
+    }
+}
+
+impl Decodable for TunPortalMessage {
+    fn decode(data: &[u8]) -> Result<Self> {
+        Ok(minicbor::decode(data)?)
+    }
+}
+
+impl Message for TunPortalMessage {}
+
+/// Destination of the packets read from the TUN device of a portal
+enum PacketRouting {
+    /// An Inlet sends all its packets to the Outlet
+    Outlet(Route),
+    /// An Outlet sends each packet to the Inlet owning its destination address.
+    /// Inlets are known from the source address of the packets they send
+    Inlets {
+        network: TunNetwork,
+        inlets: Mutex<HashMap<IpAddr, Route>>,
+    },
+}
+
+impl PacketRouting {
+    /// Remember the route back to the sender of a packet. Return false if the packet must be
+    /// dropped because its source address is not part of the network of the Outlet
+    fn learn(&self, packet: &[u8], return_route: Route) -> bool {
+        match self {
+            PacketRouting::Outlet(_) => true,
+            PacketRouting::Inlets { network, inlets } => match packet_source(packet) {
+                Some(source) if network.contains(&source) => {
+                    inlets.lock().unwrap().insert(source, return_route);
+                    true
+                }
+                _ => false,
+            },
+        }
+    }
+
+    fn route(&self, packet: &[u8]) -> Option<Route> {
+        match self {
+            PacketRouting::Outlet(route) => Some(route.clone()),
+            PacketRouting::Inlets { inlets, .. } => {
+                let destination = packet_destination(packet)?;
+                inlets.lock().unwrap().get(&destination).cloned()
+            }
+        }
+    }
+}
+
+/// Start a TUN Inlet, sending the packets of its TUN device to a TUN Outlet
+pub(crate) async fn start_tun_inlet(
+    ctx: &Context,
+    interface: &TunInterface,
+    outlet_route: Route,
+    incoming_access_control: Arc<dyn IncomingAccessControl>,
+    outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+) -> Result<TunPortal> {
+    let worker_address = Address::random_tagged("TunInletWorker");
+
+    // Accept the packets sent back by the Outlet through the secure channel
+    let flow_controls = ctx.flow_controls();
+    if let Some(flow_control_id) = flow_controls
+        .find_flow_control_with_producer_address(outlet_route.next()?)
+        .map(|x| x.flow_control_id().clone())
+    {
+        flow_controls.add_consumer(worker_address.clone(), &flow_control_id);
+    }
+
+    start_tun_portal(
+        ctx,
+        worker_address,
+        interface,
+        PacketRouting::Outlet(outlet_route),
+        incoming_access_control,
+        outgoing_access_control,
+    )
+    .await
+}
+
+/// Start a TUN Outlet, writing the packets of the TUN Inlets to its TUN device
+pub(crate) async fn start_tun_outlet(
+    ctx: &Context,
+    worker_address: Address,
+    interface: &TunInterface,
+    consumers: &[FlowControlId],
+    incoming_access_control: Arc<dyn IncomingAccessControl>,
+    outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+) -> Result<TunPortal> {
+    for flow_control_id in consumers {
+        ctx.flow_controls()
+            .add_consumer(worker_address.clone(), flow_control_id);
+    }
+
+    let routing = PacketRouting::Inlets {
+        network: interface.address,
+        inlets: Default::default(),
+    };
+    start_tun_portal(
+        ctx,
+        worker_address,
+        interface,
+        routing,
+        incoming_access_control,
+        outgoing_access_control,
+    )
+    .await
+}
+
+async fn start_tun_portal(
+    ctx: &Context,
+    worker_address: Address,
+    interface: &TunInterface,
+    routing: PacketRouting,
+    incoming_access_control: Arc<dyn IncomingAccessControl>,
+    outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+) -> Result<TunPortal> {
+    let device = TunDevice::create(&interface.name)?;
+    device.configure(interface).await?;
+    info!(device = %device.name(), address = %interface.address, "Created a TUN device");
+
+    let portal = TunPortal {
+        device_name: device.name().to_string(),
+        worker_address,
+        processor_address: Address::random_tagged("TunPortalProcessor"),
+    };
+    let device = Arc::new(device);
+    let routing = Arc::new(routing);
+
+    let worker = TunPortalWorker {
+        device: device.clone(),
+        routing: routing.clone(),
+    };
+    WorkerBuilder::new(worker)
+        .with_address(portal.worker_address.clone())
+        .with_incoming_access_control_arc(incoming_access_control)
+        .with_outgoing_access_control(DenyAll)
+        .start(ctx)
+        .await?;
+
+    let processor = TunPortalProcessor {
+        buf: vec![0; interface.mtu as usize],
+        device,
+        routing,
+        worker_address: portal.worker_address.clone(),
+    };
+    ProcessorBuilder::new(processor)
+        .with_address(portal.processor_address.clone())
+        .with_incoming_access_control(DenyAll)
+        .with_outgoing_access_control_arc(outgoing_access_control)
+        .start(ctx)
+        .await?;
+
+    Ok(portal)
+}
+
+/// Worker writing the packets received from the other side of the portal to the TUN device
+struct TunPortalWorker {
+    device: Arc<TunDevice>,
+    routing: Arc<PacketRouting>,
+}
+
+#[async_trait]
+impl Worker for TunPortalWorker {
+    type Message = Any;
+    type Context = Context;
+
+    #[instrument(skip_all, name = "TunPortalWorker::handle_message")]
+    async fn handle_message(&mut self, _ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        let return_route = msg.return_route();
+        let TunPortalMessage::Packet(packet) = TunPortalMessage::decode(msg.payload())?;
+
+        if !self.routing.learn(&packet, return_route) {
+            debug!("Dropping a packet whose source is not part of the TUN outlet network");
+            return Ok(());
+        }
+        if let Err(err) = self.device.send(&packet).await {
+            warn!(%err, device = %self.device.name(), "Failed to write a packet to a TUN device");
+        }
+
+        Ok(())
+    }
+}
+
+/// Processor sending the packets read from the TUN device to the other side of the portal
+struct TunPortalProcessor {
+    device: Arc<TunDevice>,
+    routing: Arc<PacketRouting>,
+    worker_address: Address,
+    buf: Vec<u8>,
+}
+
+#[async_trait]
+impl Processor for TunPortalProcessor {
+    type Context = Context;
+
+    #[instrument(skip_all, name = "TunPortalProcessor::shutdown")]
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        let _ = ctx.stop_worker(self.worker_address.clone()).await;
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "TunPortalProcessor::process")]
+    async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
+        let len = match self.device.recv(&mut self.buf).await {
+            Ok(len) => len,
+            Err(err) => {
+                error!(%err, device = %self.device.name(), "Failed to read from a TUN device");
+                return Ok(false);
+            }
+        };
+        let packet = &self.buf[..len];
+
+        let Some(route) = self.routing.route(packet) else {
+            trace!("Dropping a packet without any route to its destination");
+            return Ok(true);
+        };
+        let msg = LocalMessage::new()
+            .with_onward_route(route)
+            .with_return_route(route![self.worker_address.clone()])
+            .with_payload(TunPortalMessage::Packet(packet.to_vec()).encode()?);
+
+        if let Err(err) = ctx.forward(msg).await {
+            warn!(%err, "Failed to forward a packet of a TUN portal");
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn ipv4_packet(source: [u8; 4], destination: [u8; 4]) -> Vec<u8> {
+        let mut packet = vec![0u8; 20];
+        packet[0] = 0x45;
+        packet[12..16].copy_from_slice(&source);
+        packet[16..20].copy_from_slice(&destination);
+        packet
+    }
+
+    #[test]
+    fn test_outlet_routes_replies_to_inlets() -> Result<()> {
+        let routing = PacketRouting::Inlets {
+            network: TunNetwork::from_str("10.20.0.1/24")?,
+            inlets: Default::default(),
+        };
+        let inlet1 = route!["secure_channel_1", "inlet1"];
+        let inlet2 = route!["secure_channel_2", "inlet2"];
+
+        assert!(routing.learn(
+            &ipv4_packet([10, 20, 0, 2], [192, 168, 1, 5]),
+            inlet1.clone()
+        ));
+        assert!(routing.learn(
+            &ipv4_packet([10, 20, 0, 3], [192, 168, 1, 5]),
+            inlet2.clone()
+        ));
+        // Sources outside the network of the outlet are rejected
+        assert!(!routing.learn(
+            &ipv4_packet([10, 30, 0, 2], [192, 168, 1, 5]),
+            inlet2.clone()
+        ));
+
+        assert_eq!(
+            routing.route(&ipv4_packet([192, 168, 1, 5], [10, 20, 0, 2])),
+            Some(inlet1)
+        );
+        assert_eq!(
+            routing.route(&ipv4_packet([192, 168, 1, 5], [10, 20, 0, 3])),
+            Some(inlet2)
+        );
+        assert_eq!(
+            routing.route(&ipv4_packet([192, 168, 1, 5], [10, 30, 0, 2])),
+            None
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use super::TunPortal;
+use crate::nodes::models::tun_portal::TunInterface;
+use ockam_core::compat::sync::Arc;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::flow_control::FlowControlId;
+use ockam_core::{Address, IncomingAccessControl, OutgoingAccessControl, Result, Route};
+use ockam_node::Context;
+
+pub(crate) async fn start_tun_inlet(
+    _ctx: &Context,
+    _interface: &TunInterface,
+    _outlet_route: Route,
+    _incoming_access_control: Arc<dyn IncomingAccessControl>,
+    _outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+) -> Result<TunPortal> {
+    Err(unsupported())
+}
+
+pub(crate) async fn start_tun_outlet(
+    _ctx: &Context,
+    _worker_address: Address,
+    _interface: &TunInterface,
+    _consumers: &[FlowControlId],
+    _incoming_access_control: Arc<dyn IncomingAccessControl>,
+    _outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+) -> Result<TunPortal> {
+    Err(unsupported())
+}
+
+fn unsupported() -> ockam_core::Error {
+    ockam_core::Error::new(
+        Origin::Node,
+        Kind::Unsupported,
+        "TUN portals are only supported on Linux, by nodes built with the `tun` feature",
+    )
+}
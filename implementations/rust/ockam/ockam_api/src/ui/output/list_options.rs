@@ -0,0 +1,197 @@
+use std::cmp::Ordering;
+
+use miette::miette;
+use serde_json::Value;
+
+use crate::Result;
+
+/// Options used to select and sort the fields of the items displayed as a list.
+///
+/// A field is the name of an attribute of the JSON representation of an item.
+/// Nested attributes can be accessed by separating their names with a dot, e.g. `status.pid`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListOptions {
+    fields: Vec<String>,
+    sort_by: Option<String>,
+}
+
+impl ListOptions {
+    pub fn new(fields: Vec<String>, sort_by: Option<String>) -> Self {
+        Self {
+            fields: fields
+                .into_iter()
+                .map(|f| f.trim().to_string())
+                .filter(|f| !f.is_empty())
+                .collect(),
+            sort_by: sort_by
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty()),
+        }
+    }
+
+    /// Return true if the items must be displayed as they are, in their original order
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty() && self.sort_by.is_none()
+    }
+
+    pub fn has_fields(&self) -> bool {
+        !self.fields.is_empty()
+    }
+
+    /// Return the indices of the items, ordered by the value of the `sort_by` field.
+    /// Items without a value for that field are placed last.
+    pub fn sorted_indices(&self, items: &[Value]) -> Result<Vec<usize>> {
+        let mut indices: Vec<usize> = (0..items.len()).collect();
+        if let Some(sort_by) = &self.sort_by {
+            check_field_exists(sort_by, items)?;
+            indices.sort_by(|a, b| {
+                compare_values(
+                    get_field(&items[*a], sort_by),
+                    get_field(&items[*b], sort_by),
+                )
+            });
+        }
+        Ok(indices)
+    }
+
+    /// Render the selected fields of the items as a table with a header line
+    pub fn table(&self, items: &[&Value]) -> Result<Vec<String>> {
+        for field in &self.fields {
+            check_field_exists(field, items.iter().copied())?;
+        }
+        let rows: Vec<Vec<String>> = items
+            .iter()
+            .map(|item| {
+                self.fields
+                    .iter()
+                    .map(|f| format_value(get_field(item, f)))
+                    .collect()
+            })
+            .collect();
+        let header: Vec<String> = self.fields.iter().map(|f| f.to_uppercase()).collect();
+        let widths: Vec<usize> = (0..self.fields.len())
+            .map(|col| {
+                rows.iter()
+                    .map(|row| row[col].chars().count())
+                    .chain(std::iter::once(header[col].chars().count()))
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+        Ok(std::iter::once(header)
+            .chain(rows)
+            .map(|row| {
+                row.iter()
+                    .zip(&widths)
+                    .map(|(value, width)| format!("{value:width$}", width = *width))
+                    .collect::<Vec<_>>()
+                    .join("  ")
+                    .trim_end()
+                    .to_string()
+            })
+            .collect())
+    }
+}
+
+fn get_field<'a>(item: &'a Value, field: &str) -> Option<&'a Value> {
+    item.pointer(&format!("/{}", field.replace('.', "/")))
+}
+
+/// Return an error listing the available fields if no item has a value for the given field
+fn check_field_exists<'a>(field: &str, items: impl IntoIterator<Item = &'a Value>) -> Result<()> {
+    let mut available = vec![];
+    for item in items {
+        if get_field(item, field).is_some() {
+            return Ok(());
+        }
+        if let Value::Object(map) = item {
+            for key in map.keys() {
+                if !available.contains(key) {
+                    available.push(key.clone());
+                }
+            }
+        }
+    }
+    Err(miette!(
+        "Unknown field '{field}'. The available fields are: {}",
+        available.join(", ")
+    ))?
+}
+
+fn compare_values(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    match (a, b) {
+        (None | Some(Value::Null), None | Some(Value::Null)) => Ordering::Equal,
+        (None | Some(Value::Null), _) => Ordering::Greater,
+        (_, None | Some(Value::Null)) => Ordering::Less,
+        (Some(Value::Number(a)), Some(Value::Number(b))) => a
+            .as_f64()
+            .partial_cmp(&b.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Some(a), Some(b)) => format_value(Some(a)).cmp(&format_value(Some(b))),
+    }
+}
+
+fn format_value(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => "-".to_string(),
+        Some(Value::String(s)) => s.clone(),
+        Some(value) => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn nodes() -> Vec<Value> {
+        vec![
+            json!({"name": "n2", "pid": 20, "status": {"running": true}}),
+            json!({"name": "n1", "pid": null, "status": {"running": false}}),
+            json!({"name": "n3", "pid": 3, "status": {"running": true}}),
+        ]
+    }
+
+    #[test]
+    fn sort_items_by_field() {
+        let items = nodes();
+        let options = ListOptions::new(vec![], Some("name".to_string()));
+        assert_eq!(options.sorted_indices(&items).unwrap(), vec![1, 0, 2]);
+
+        // numbers are compared numerically and missing values are placed last
+        let options = ListOptions::new(vec![], Some("pid".to_string()));
+        assert_eq!(options.sorted_indices(&items).unwrap(), vec![2, 0, 1]);
+
+        let options = ListOptions::new(vec![], None);
+        assert_eq!(options.sorted_indices(&items).unwrap(), vec![0, 1, 2]);
+
+        let options = ListOptions::new(vec![], Some("unknown".to_string()));
+        assert!(options.sorted_indices(&items).is_err());
+    }
+
+    #[test]
+    fn select_fields() {
+        let items = nodes();
+        let options = ListOptions::new(
+            vec!["name".to_string(), " status.running".to_string()],
+            None,
+        );
+        let table = options.table(&items.iter().collect::<Vec<_>>()).unwrap();
+        assert_eq!(
+            table,
+            vec![
+                "NAME  STATUS.RUNNING",
+                "n2    true",
+                "n1    false",
+                "n3    true"
+            ]
+        );
+
+        let options = ListOptions::new(vec!["pid".to_string()], None);
+        let table = options.table(&items.iter().collect::<Vec<_>>()).unwrap();
+        assert_eq!(table, vec!["PID", "20", "-", "3"]);
+
+        let options = ListOptions::new(vec!["unknown".to_string()], None);
+        assert!(options.table(&items.iter().collect::<Vec<_>>()).is_err());
+    }
+}
@@ -1,9 +1,11 @@
 mod encode_format;
+mod list_options;
 mod ockam_abac;
 mod output_format;
 mod utils;
 
 pub use encode_format::EncodeFormat;
+pub use list_options::ListOptions;
 pub use output_format::OutputFormat;
 pub use utils::*;
 
@@ -42,6 +44,12 @@ pub trait Output {
     fn as_list_item(&self) -> Result<String> {
         self.item()
     }
+
+    /// Return the JSON representation of the item, used to select and sort its fields
+    /// when it is displayed in a list. Items which can't be displayed that way return `None`
+    fn as_fields(&self) -> Option<serde_json::Value> {
+        None
+    }
 }
 
 impl Output for String {
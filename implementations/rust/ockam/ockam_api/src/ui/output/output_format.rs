@@ -1,4 +1,4 @@
-/// There are two available formats, plain text and JSON,
+/// There are three available formats, plain text, JSON and YAML,
 /// which are handled by the Terminal struct.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum OutputFormat {
@@ -7,6 +7,7 @@ pub enum OutputFormat {
         pretty: bool,
         jq_query: Option<String>,
     },
+    Yaml,
 }
 
 impl OutputFormat {
@@ -17,6 +18,10 @@ impl OutputFormat {
     pub fn is_json(&self) -> bool {
         matches!(self, Self::Json { .. })
     }
+
+    pub fn is_yaml(&self) -> bool {
+        matches!(self, Self::Yaml)
+    }
 }
 
 #[cfg(test)]
@@ -35,5 +40,10 @@ mod tests {
         };
         assert!(json.is_json());
         assert!(!json.is_plain());
+
+        let yaml = OutputFormat::Yaml;
+        assert!(yaml.is_yaml());
+        assert!(!yaml.is_json());
+        assert!(!yaml.is_plain());
     }
 }
@@ -8,9 +8,10 @@ pub use fmt::{get_separator_width, ICON_PADDING, PADDING};
 use std::fmt::Write as _;
 use std::fmt::{Debug, Display};
 use std::io::Write;
+use std::sync::Arc;
 use std::time::Duration;
 
-use crate::ui::output::OutputFormat;
+use crate::ui::output::{ListOptions, OutputFormat};
 use crate::{Result, UiError};
 
 use colorful::Colorful;
@@ -35,9 +36,13 @@ pub struct Terminal<T: TerminalWriter + Debug, WriteMode = ToStdErr> {
     quiet: bool,
     no_input: bool,
     output_format: OutputFormat,
+    list_options: ListOptions,
     mode: WriteMode,
     max_width_col_count: usize,
     max_height_row_count: usize,
+    /// When set, the stdout outputs are kept instead of being written, so that a caller can
+    /// report them in its own output. Shared by the clones of the terminal
+    captured_stdout: Arc<std::sync::Mutex<Option<Vec<Output>>>>,
 }
 
 impl<T: TerminalWriter + Debug, W> Terminal<T, W> {
@@ -45,6 +50,34 @@ impl<T: TerminalWriter + Debug, W> Terminal<T, W> {
         self.quiet
     }
 
+    /// Keep the stdout outputs of this terminal and of its clones instead of writing them
+    pub fn capture_stdout(&self) {
+        if let Ok(mut captured) = self.captured_stdout.lock() {
+            *captured = Some(vec![]);
+        }
+    }
+
+    /// Stop keeping the stdout outputs and return their plain and JSON versions.
+    /// Several JSON outputs are returned as a JSON array
+    pub fn take_captured_stdout(&self) -> (Option<String>, Option<serde_json::Value>) {
+        let outputs = match self.captured_stdout.lock() {
+            Ok(mut captured) => captured.take().unwrap_or_default(),
+            Err(_) => vec![],
+        };
+        let plain: Vec<String> = outputs
+            .iter()
+            .filter_map(|o| o.plain.clone().or_else(|| o.machine.clone()))
+            .collect();
+        let mut json: Vec<serde_json::Value> = outputs.into_iter().filter_map(|o| o.json).collect();
+        let plain = (!plain.is_empty()).then(|| plain.join("\n"));
+        let json = match json.len() {
+            0 => None,
+            1 => json.pop(),
+            _ => Some(serde_json::Value::Array(json)),
+        };
+        (plain, json)
+    }
+
     fn log_msg(&self, msg: impl AsRef<str>) {
         if !self.logging_enabled {
             return;
@@ -101,6 +134,7 @@ impl<W: TerminalWriter + Debug> Terminal<W> {
         no_input: bool,
         output_format: OutputFormat,
     ) -> Self {
+        let quiet = Self::should_be_quiet(quiet);
         let no_color = Self::should_disable_color(no_color);
         let no_input = Self::should_disable_user_input(no_input);
         let stdout = W::stdout(no_color);
@@ -113,9 +147,11 @@ impl<W: TerminalWriter + Debug> Terminal<W> {
             quiet,
             no_input,
             output_format,
+            list_options: ListOptions::default(),
             mode: ToStdErr,
             max_width_col_count,
             max_height_row_count: 5,
+            captured_stdout: Arc::new(std::sync::Mutex::new(None)),
         }
     }
 
@@ -123,6 +159,12 @@ impl<W: TerminalWriter + Debug> Terminal<W> {
         Self::new(logging_enabled, true, false, false, OutputFormat::Plain)
     }
 
+    /// Set the options used to select and sort the fields of the items displayed by `build_list`
+    pub fn with_list_options(mut self, list_options: ListOptions) -> Self {
+        self.list_options = list_options;
+        self
+    }
+
     /// Prompt the user for a confirmation.
     pub fn confirm(&self, msg: impl AsRef<str>) -> Result<ConfirmResult> {
         if !self.can_ask_for_user_input() {
@@ -138,6 +180,45 @@ impl<W: TerminalWriter + Debug> Terminal<W> {
         ))
     }
 
+    /// Prompt the user for a text value.
+    /// The value is only accepted once the `validate` function returns `Ok`, otherwise
+    /// the validation error is displayed and the user is prompted again.
+    pub fn ask_for_input(
+        &self,
+        prompt: impl AsRef<str>,
+        default: Option<String>,
+        validate: impl FnMut(&String) -> std::result::Result<(), String>,
+    ) -> Result<String> {
+        if !self.can_ask_for_user_input() {
+            return Err(miette!(
+                "Cannot ask for user input in a non-interactive terminal"
+            ))?;
+        }
+        let mut input = dialoguer::Input::<String>::new()
+            .with_prompt(prompt.as_ref())
+            .allow_empty(true)
+            .validate_with(validate);
+        if let Some(default) = default {
+            input = input.default(default);
+        }
+        Ok(input.interact_text().map_err(UiError::Dialoguer)?)
+    }
+
+    /// Prompt the user for a secret value, which is not echoed.
+    /// If `confirm` is true, the user is asked to type the value twice
+    pub fn ask_for_password(&self, prompt: impl AsRef<str>, confirm: bool) -> Result<String> {
+        if !self.can_ask_for_user_input() {
+            return Err(miette!(
+                "Cannot ask for user input in a non-interactive terminal"
+            ))?;
+        }
+        let mut password = dialoguer::Password::new().with_prompt(prompt.as_ref());
+        if confirm {
+            password = password.with_confirmation("Confirm", "The values don't match");
+        }
+        Ok(password.interact().map_err(UiError::Dialoguer)?)
+    }
+
     pub fn confirmed_with_flag_or_prompt(
         &self,
         flag: bool,
@@ -194,6 +275,12 @@ impl<W: TerminalWriter + Debug> Terminal<W> {
         !self.no_input && self.stderr.is_tty() && !self.quiet
     }
 
+    fn should_be_quiet(quiet: bool) -> bool {
+        // If global argument `--quiet` is passed or the `OCKAM_QUIET` env var is set, only the final
+        // output of a command is displayed: no log messages, spinners or progress messages.
+        quiet || get_env_with_default("OCKAM_QUIET", false).unwrap_or(false)
+    }
+
     fn should_disable_color(no_color: bool) -> bool {
         // If global argument `--no-color` is passed or the `NO_COLOR` env var is set, colors
         // will be stripped out from output messages. Otherwise, let the terminal decide.
@@ -265,6 +352,10 @@ impl<W: TerminalWriter + Debug> Terminal<W, ToStdErr> {
             return Ok(fmt_info!("{empty_message}"));
         }
 
+        if !self.list_options.is_empty() {
+            return self.build_list_with_options(items);
+        }
+
         let mut output = String::new();
 
         for (idx, item) in items.iter().enumerate() {
@@ -282,6 +373,41 @@ impl<W: TerminalWriter + Debug> Terminal<W, ToStdErr> {
         Ok(output)
     }
 
+    /// Build a list where the items are sorted and/or reduced to the selected fields
+    fn build_list_with_options(&self, items: &[impl crate::output::Output]) -> Result<String> {
+        let values = items
+            .iter()
+            .map(|item| item.as_fields())
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| {
+                miette!("The --fields and --sort-by options are not supported by this command")
+            })?;
+        let indices = self.list_options.sorted_indices(&values)?;
+
+        let mut output = String::new();
+        if self.list_options.has_fields() {
+            let rows: Vec<&serde_json::Value> = indices.iter().map(|idx| &values[*idx]).collect();
+            for line in self.list_options.table(&rows)? {
+                writeln!(output, "{}", &fmt_list!("{line}"))?;
+            }
+            return Ok(output);
+        }
+
+        for (n, idx) in indices.into_iter().enumerate() {
+            // Add a newline before each item except the first one
+            if n > 0 {
+                writeln!(output)?;
+            }
+
+            let item = items[idx].as_list_item()?;
+            for line in item.lines() {
+                writeln!(output, "{}", &fmt_list!("{line}"))?;
+            }
+        }
+
+        Ok(output)
+    }
+
     pub fn stdout(self) -> Terminal<W, ToStdOut> {
         Terminal {
             stdout: self.stdout,
@@ -290,11 +416,14 @@ impl<W: TerminalWriter + Debug> Terminal<W, ToStdErr> {
             quiet: self.quiet,
             no_input: self.no_input,
             output_format: self.output_format,
+            list_options: self.list_options,
             mode: ToStdOut {
                 output: Output::new(),
+                one_per_line: false,
             },
             max_width_col_count: self.max_width_col_count,
             max_height_row_count: self.max_height_row_count,
+            captured_stdout: self.captured_stdout,
         }
     }
 }
@@ -320,6 +449,13 @@ impl<W: TerminalWriter + Debug> Terminal<W, ToStdOut> {
         Ok(self)
     }
 
+    /// End the output with a newline even if stdout is not a TTY, so that the outputs
+    /// written for several items are read as one item per line
+    pub fn one_per_line(mut self) -> Self {
+        self.mode.one_per_line = true;
+        self
+    }
+
     // This function is deprecated in favor of the `json_obj` function above.
     pub fn json<T: Display>(mut self, msg: T) -> Self {
         self.mode.output.json = Some(serde_json::from_str(&msg.to_string()).unwrap());
@@ -334,6 +470,12 @@ impl<W: TerminalWriter + Debug> Terminal<W, ToStdOut> {
         {
             return Err(miette!("At least one output format must be defined"))?;
         }
+        if let Ok(mut captured) = self.captured_stdout.lock() {
+            if let Some(captured) = captured.as_mut() {
+                captured.push(self.mode.output.clone());
+                return Ok(());
+            }
+        }
 
         let plain = self.mode.output.plain.clone();
         let machine = self.mode.output.machine.clone();
@@ -356,6 +498,11 @@ impl<W: TerminalWriter + Debug> Terminal<W, ToStdOut> {
                         _ => unreachable!(),
                     }
                 }
+                // If the fields of a list were explicitly selected or sorted, the plain
+                // output is used even if not interactive, so that it can be processed by scripts
+                else if plain.is_some() && !self.list_options.is_empty() {
+                    plain.unwrap_or_default()
+                }
                 // If not interactive, use the following priority: Machine -> JSON -> Plain
                 else {
                     match (machine, json, plain) {
@@ -376,6 +523,15 @@ impl<W: TerminalWriter + Debug> Terminal<W, ToStdOut> {
                     return Ok(());
                 }
             },
+            // The YAML output is derived from the JSON output
+            OutputFormat::Yaml => match json {
+                Some(json) => serde_yaml::to_string(&json).into_diagnostic()?,
+                // If not set, no fallback is provided
+                None => {
+                    warn!("YAML output is not defined for this command");
+                    return Ok(());
+                }
+            },
         };
 
         if self.logging_enabled {
@@ -384,7 +540,7 @@ impl<W: TerminalWriter + Debug> Terminal<W, ToStdOut> {
             // Remove any trailing newline characters.
             // A newline will be added if stdout is a TTY.
             let msg = msg.trim_end().trim_end_matches('\n');
-            if self.stdout.is_tty() {
+            if self.stdout.is_tty() || self.mode.one_per_line {
                 self.stdout.write_line(msg)?;
             } else {
                 self.stdout.write(msg)?;
@@ -476,7 +632,7 @@ impl<W: TerminalWriter + Debug> Terminal<W> {
         output_messages: &[String],
         is_finished: &Mutex<bool>,
     ) -> miette::Result<()> {
-        if output_messages.is_empty() {
+        if output_messages.is_empty() || self.quiet {
             return Ok(());
         }
         let pb = match self.progress_bar() {
@@ -511,6 +667,7 @@ pub struct ToStdErr;
 #[derive(Clone, Debug)]
 pub struct ToStdOut {
     pub(self) output: Output,
+    pub(self) one_per_line: bool,
 }
 
 /// The command's output message to be displayed to the user in various formats
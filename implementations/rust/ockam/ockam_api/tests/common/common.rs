@@ -43,6 +43,8 @@ pub async fn default_configuration() -> Result<Configuration> {
         account_authority: None,
         enforce_admin_checks: false,
         disable_trust_context_id: false,
+        attribute_schema: None,
+        issuer_credential: None,
     };
 
     // Hack to create Authority Identity using the same vault and storage
@@ -75,8 +77,20 @@ pub async fn start_authority(
     ctx: &Context,
     secure_channels: Arc<SecureChannels>,
     number_of_admins: usize,
+) -> Result<AuthorityInfo> {
+    start_authority_with_admin_checks(ctx, secure_channels, number_of_admins, false).await
+}
+
+// Start an Authority like `start_authority`, where enrollers are only admins if
+// `enforce_admin_checks` is false
+pub async fn start_authority_with_admin_checks(
+    ctx: &Context,
+    secure_channels: Arc<SecureChannels>,
+    number_of_admins: usize,
+    enforce_admin_checks: bool,
 ) -> Result<AuthorityInfo> {
     let mut configuration = default_configuration().await?;
+    configuration.enforce_admin_checks = enforce_admin_checks;
 
     let account_authority = secure_channels
         .identities()
@@ -1,12 +1,18 @@
 use minicbor::bytes::ByteSlice;
-use ockam::identity::models::CredentialAndPurposeKey;
+use ockam::identity::models::{
+    AttributeDefinition, AttributeType, CredentialAndPurposeKey, CredentialSchema,
+    RevocationListAndPurposeKey,
+};
 use ockam::identity::utils::now;
 use ockam::identity::{identities, SecureChannelSqlxDatabase};
 use ockam::identity::{
     Identities, SecureChannelListenerOptions, SecureChannelOptions, SecureChannels,
 };
 use ockam::route;
-use ockam_api::authenticator::credential_issuer::CredentialIssuerWorker;
+use ockam_api::authenticator::credential_issuer::{project_member_schema, CredentialIssuerWorker};
+use ockam_api::authenticator::x509::{
+    CertificateSigningRequest, X509Certificate, OCKAM_IDENTIFIER_URI_PREFIX,
+};
 use ockam_api::authenticator::{
     AuthorityMembersRepository, AuthorityMembersSqlxDatabase, PreTrustedIdentity,
 };
@@ -15,6 +21,7 @@ use ockam_core::compat::collections::BTreeMap;
 use ockam_core::{Address, Result};
 use ockam_node::api::Client;
 use ockam_node::Context;
+use ockam_vault::{EdDSACurve25519Signature, Signature};
 use std::sync::Arc;
 
 #[ockam_macros::test]
@@ -61,6 +68,15 @@ async fn credential(ctx: &mut Context) -> Result<()> {
     );
     let identities_verification = identities.identities_verification();
 
+    // Publish a schema for the member attributes
+    let schema = project_member_schema(vec![AttributeDefinition {
+        name: "attr".to_string(),
+        attribute_type: AttributeType::String,
+        allowed_values: vec![],
+        required: true,
+    }]);
+    identities.credential_schemas().register(schema.clone());
+
     // Create the CredentialIssuer:
     let options = SecureChannelListenerOptions::new();
     let sc_flow_control_id = options.spawner_flow_control_id();
@@ -78,6 +94,7 @@ async fn credential(ctx: &mut Context) -> Result<()> {
         None,
         None,
         true,
+        None,
     );
     ctx.start_worker(auth_worker_addr.clone(), auth).await?;
 
@@ -114,5 +131,88 @@ async fn credential(ctx: &mut Context) -> Result<()> {
             .map
             .get::<ByteSlice>(b"attr".as_slice().into())
     );
+
+    // The revocation list of the issuer can be retrieved and verified
+    let revocation_list: RevocationListAndPurposeKey = client
+        .ask(ctx, Request::get("/revocation_list"))
+        .await?
+        .success()?;
+    identities
+        .credentials()
+        .credentials_verification()
+        .receive_revocation_list(&[auth_identifier.clone()], &revocation_list)
+        .await?;
+    assert!(revocation_list
+        .get_revocation_list_data()?
+        .revoked
+        .is_empty());
+
+    // The schema of the member credentials can be retrieved
+    let published_schema: CredentialSchema =
+        client.ask(ctx, Request::get("/schema")).await?.success()?;
+    assert_eq!(published_schema, schema);
+
+    // An X.509 certificate can be issued for a signing key of the member
+    let purpose_keys_creation = identities.purpose_keys().purpose_keys_creation();
+    let member_key = purpose_keys_creation
+        .get_or_create_credential_purpose_key(&member_identifier)
+        .await?;
+    let request = CertificateSigningRequest::new(member_key.attestation().clone());
+    let certificate: X509Certificate = client
+        .ask(ctx, Request::post("/x509_certificate").body(request))
+        .await?
+        .success()?;
+    assert!(certificate
+        .to_pem()
+        .starts_with("-----BEGIN CERTIFICATE-----\n"));
+    let uri = format!("{OCKAM_IDENTIFIER_URI_PREFIX}{member_identifier}");
+    assert!(contains(certificate.der(), uri.as_bytes()));
+
+    // The certificate is signed with the key of the authority certificate
+    let ca_certificate: X509Certificate = client
+        .ask(ctx, Request::get("/x509_ca_certificate"))
+        .await?
+        .success()?;
+    let authority_key = purpose_keys_creation
+        .get_or_create_credential_purpose_key(&auth_identifier)
+        .await?;
+    for certificate in [certificate, ca_certificate] {
+        let (tbs_certificate, signature) = split_certificate(certificate.der());
+        let signature = Signature::EdDSACurve25519(EdDSACurve25519Signature(signature));
+        assert!(
+            identities
+                .vault()
+                .verifying_vault
+                .verify_signature(authority_key.public_key(), tbs_certificate, &signature)
+                .await?
+        );
+    }
     Ok(())
 }
+
+fn contains(bytes: &[u8], value: &[u8]) -> bool {
+    bytes.windows(value.len()).any(|w| w == value)
+}
+
+/// Return the (header length, content length) of a DER-encoded value
+fn der_length(der: &[u8]) -> (usize, usize) {
+    let first = der[1] as usize;
+    if first < 0x80 {
+        (2, first)
+    } else {
+        let n = first & 0x7f;
+        let length = der[2..2 + n]
+            .iter()
+            .fold(0, |acc, b| (acc << 8) | *b as usize);
+        (2 + n, length)
+    }
+}
+
+/// Return the signed part of an Ed25519 certificate and its signature
+fn split_certificate(der: &[u8]) -> (&[u8], [u8; 64]) {
+    let (header_length, _) = der_length(der);
+    let content = &der[header_length..];
+    let (tbs_header_length, tbs_length) = der_length(content);
+    let signature = der[der.len() - 64..].try_into().unwrap();
+    (&content[..tbs_header_length + tbs_length], signature)
+}
@@ -1,6 +1,9 @@
-use crate::common::common::{change_client_identifier, start_authority, AuthorityInfo};
-use ockam::identity::secure_channels;
+use crate::common::common::{
+    change_client_identifier, start_authority, start_authority_with_admin_checks, AuthorityInfo,
+};
 use ockam::identity::utils::now;
+use ockam::identity::{secure_channels, DELEGATED_ISSUER_ATTRIBUTE};
+use ockam_api::authenticator::direct::types::ListMembers;
 use ockam_api::authenticator::direct::Members;
 use ockam_api::authenticator::direct::{
     OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE, OCKAM_ROLE_ATTRIBUTE_KEY,
@@ -271,6 +274,83 @@ async fn enroller_can_list_members(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn members_can_be_filtered_and_paginated(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+
+    let AuthorityInfo { admins, .. } = start_authority(ctx, secure_channels.clone(), 1).await?;
+    let admin = &admins[0];
+
+    for i in 0..5 {
+        let member = secure_channels
+            .identities()
+            .identities_creation()
+            .create_identity()
+            .await?;
+        let mut attributes = BTreeMap::<String, String>::default();
+        let team = if i % 2 == 0 { "blue" } else { "red" };
+        attributes.insert("team".to_string(), team.to_string());
+        admin
+            .client
+            .add_member(ctx, member, attributes)
+            .await
+            .unwrap();
+    }
+
+    // all the members are returned when no attributes or limit are specified
+    let page = admin
+        .client
+        .list_members_page(ctx, ListMembers::new())
+        .await
+        .unwrap();
+    assert_eq!(page.members().len(), 5);
+    assert_eq!(page.total(), 5);
+
+    // only the members having the selected attributes are returned
+    let mut blue = BTreeMap::<String, String>::default();
+    blue.insert("team".to_string(), "blue".to_string());
+    let page = admin
+        .client
+        .list_members_page(ctx, ListMembers::new().with_attributes(blue.clone()))
+        .await
+        .unwrap();
+    assert_eq!(page.members().len(), 3);
+    assert_eq!(page.total(), 3);
+
+    // the members are returned one page at a time
+    let first_page = admin
+        .client
+        .list_members_page(
+            ctx,
+            ListMembers::new()
+                .with_attributes(blue.clone())
+                .with_limit(Some(2)),
+        )
+        .await
+        .unwrap();
+    assert_eq!(first_page.members().len(), 2);
+    assert_eq!(first_page.total(), 3);
+
+    let second_page = admin
+        .client
+        .list_members_page(
+            ctx,
+            ListMembers::new()
+                .with_attributes(blue)
+                .with_offset(2)
+                .with_limit(Some(2)),
+        )
+        .await
+        .unwrap();
+    assert_eq!(second_page.members().len(), 1);
+    assert_eq!(second_page.total(), 3);
+    for identifier in second_page.members().keys() {
+        assert!(!first_page.members().contains_key(identifier));
+    }
+
+    Ok(())
+}
+
 #[ockam_macros::test]
 async fn enroller_can_add_member(ctx: &mut Context) -> Result<()> {
     let secure_channels = secure_channels().await?;
@@ -470,6 +550,60 @@ async fn enroller_cant_add_enroller(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn enroller_cant_add_delegated_issuer(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+
+    let AuthorityInfo { admins, .. } =
+        start_authority_with_admin_checks(ctx, secure_channels.clone(), 1, true).await?;
+    let admin = &admins[0];
+
+    let enroller = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let member = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let mut attributes_enroller = BTreeMap::<String, String>::default();
+    attributes_enroller.insert(
+        OCKAM_ROLE_ATTRIBUTE_KEY.to_string(),
+        OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE.to_string(),
+    );
+    admin
+        .client
+        .add_member(ctx, enroller.clone(), attributes_enroller)
+        .await
+        .unwrap();
+
+    let enroller_client = change_client_identifier(&admin.client, &enroller, None);
+
+    let mut attributes_delegated_issuer = BTreeMap::<String, String>::default();
+    attributes_delegated_issuer.insert(DELEGATED_ISSUER_ATTRIBUTE.to_string(), "true".to_string());
+    let res = enroller_client
+        .add_member(ctx, member.clone(), attributes_delegated_issuer.clone())
+        .await;
+    assert!(res.is_err());
+
+    let members = admin.client.list_member_ids(ctx).await.unwrap();
+    assert!(!members.contains(&member));
+
+    // an admin can make a member a delegated issuer
+    admin
+        .client
+        .add_member(ctx, member.clone(), attributes_delegated_issuer)
+        .await
+        .unwrap();
+
+    let members = admin.client.list_member_ids(ctx).await.unwrap();
+    assert!(members.contains(&member));
+
+    Ok(())
+}
+
 #[ockam_macros::test]
 #[ignore] // TODO with admin credentials.  For now, all enrollers have rights to add/remove enrollers
 async fn enroller_cant_delete_enroller(ctx: &mut Context) -> Result<()> {
@@ -8,6 +8,8 @@ use tokio::runtime::Runtime;
 use tokio::time::timeout;
 
 use ockam_api::nodes::models::portal::OutletAccessControl;
+use ockam_api::nodes::service::tcp_inlets::InletServiceOptions;
+use ockam_api::nodes::service::tcp_outlets::OutletServiceOptions;
 use ockam_api::test_utils::{start_tcp_echo_server, TestNode};
 use ockam_core::env::FromString;
 use ockam_core::errcode::{Kind, Origin};
@@ -138,6 +140,7 @@ pub fn measure_buffer_latency_two_nodes_portal() {
                     Some(Address::from_string("outlet")),
                     true,
                     OutletAccessControl::AccessControl((Arc::new(AllowAll), Arc::new(AllowAll))),
+                    OutletServiceOptions::default(),
                 )
                 .await?;
 
@@ -162,6 +165,7 @@ pub fn measure_buffer_latency_two_nodes_portal() {
                     None,
                     false,
                     false,
+                    InletServiceOptions::default(),
                 )
                 .await?;
 
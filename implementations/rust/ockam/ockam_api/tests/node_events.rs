@@ -0,0 +1,47 @@
+use ockam_api::nodes::models::node::NodeEventStatus;
+use ockam_api::test_utils::{start_manager_for_tests, TestNode};
+use ockam_core::{Address, AllowAll};
+use ockam_multiaddr::MultiAddr;
+use ockam_node::{Context, NullWorker};
+use std::str::FromStr;
+
+#[ockam_macros::test]
+async fn node_events_are_sent_to_subscribers(context: &mut Context) -> ockam::Result<()> {
+    TestNode::clean().await?;
+    let node_manager_handle = start_manager_for_tests(context, None, None).await?;
+    let node_manager = &node_manager_handle.node_manager;
+
+    let mut subscriber = context
+        .new_detached("events_subscriber", AllowAll, AllowAll)
+        .await?;
+    let subscription = node_manager
+        .subscribe_node_events(
+            context,
+            MultiAddr::from_str("/service/events_subscriber")?,
+            vec!["worker_started".to_string()],
+        )
+        .await?;
+    assert_eq!(node_manager.list_node_events_subscriptions().await.len(), 1);
+
+    // only the worker_started events are received
+    context.start_worker("null_worker", NullWorker).await?;
+    context.stop_worker("null_worker").await?;
+    loop {
+        let message = subscriber.receive::<Vec<u8>>().await?.into_body()?;
+        let event: NodeEventStatus = minicbor::decode(&message).unwrap();
+        assert_eq!(event.kind, "worker_started");
+        if event.address == Some(Address::from("null_worker").to_string()) {
+            break;
+        }
+    }
+
+    node_manager
+        .unsubscribe_node_events(&subscription.id)
+        .await?;
+    assert!(node_manager
+        .list_node_events_subscriptions()
+        .await
+        .is_empty());
+
+    context.stop().await
+}
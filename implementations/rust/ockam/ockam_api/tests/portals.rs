@@ -1,5 +1,7 @@
 use ockam_api::config::lookup::InternetAddress;
 use ockam_api::nodes::models::portal::OutletAccessControl;
+use ockam_api::nodes::service::tcp_inlets::InletServiceOptions;
+use ockam_api::nodes::service::tcp_outlets::OutletServiceOptions;
 use ockam_api::test_utils::{
     start_manager_for_tests, start_passthrough_server, start_tcp_echo_server, Disruption, TestNode,
 };
@@ -34,6 +36,7 @@ async fn inlet_outlet_local_successful(context: &mut Context) -> ockam::Result<(
             Some(Address::from_string("outlet")),
             true,
             OutletAccessControl::AccessControl((Arc::new(AllowAll), Arc::new(AllowAll))),
+            OutletServiceOptions::default(),
         )
         .await?;
 
@@ -59,6 +62,7 @@ async fn inlet_outlet_local_successful(context: &mut Context) -> ockam::Result<(
             None,
             false,
             false,
+            InletServiceOptions::default(),
         )
         .await?;
 
@@ -110,6 +114,7 @@ fn portal_node_goes_down_reconnect() {
                     Some(Address::from_string("outlet")),
                     true,
                     OutletAccessControl::AccessControl((Arc::new(AllowAll), Arc::new(AllowAll))),
+                    OutletServiceOptions::default(),
                 )
                 .await?;
 
@@ -134,6 +139,7 @@ fn portal_node_goes_down_reconnect() {
                     None,
                     false,
                     false,
+                    InletServiceOptions::default(),
                 )
                 .await?;
 
@@ -178,6 +184,7 @@ fn portal_node_goes_down_reconnect() {
                     Some(Address::from_string("outlet")),
                     true,
                     OutletAccessControl::AccessControl((Arc::new(AllowAll), Arc::new(AllowAll))),
+                    OutletServiceOptions::default(),
                 )
                 .await?;
 
@@ -252,6 +259,7 @@ fn portal_low_bandwidth_connection_keep_working_for_60s() {
                     Some(Address::from_string("outlet")),
                     true,
                     OutletAccessControl::AccessControl((Arc::new(AllowAll), Arc::new(AllowAll))),
+                    OutletServiceOptions::default(),
                 )
                 .await?;
 
@@ -288,6 +296,7 @@ fn portal_low_bandwidth_connection_keep_working_for_60s() {
                     None,
                     false,
                     false,
+                    InletServiceOptions::default(),
                 )
                 .await?;
 
@@ -370,6 +379,7 @@ fn portal_heavy_load_exchanged() {
                     Some(Address::from_string("outlet")),
                     true,
                     OutletAccessControl::AccessControl((Arc::new(AllowAll), Arc::new(AllowAll))),
+                    OutletServiceOptions::default(),
                 )
                 .await?;
 
@@ -399,6 +409,7 @@ fn portal_heavy_load_exchanged() {
                     None,
                     false,
                     false,
+                    InletServiceOptions::default(),
                 )
                 .await?;
 
@@ -513,6 +524,7 @@ fn test_portal_payload_transfer(outgoing_disruption: Disruption, incoming_disrup
                     Some(Address::from_string("outlet")),
                     true,
                     OutletAccessControl::AccessControl((Arc::new(AllowAll), Arc::new(AllowAll))),
+                    OutletServiceOptions::default(),
                 )
                 .await?;
 
@@ -549,6 +561,7 @@ fn test_portal_payload_transfer(outgoing_disruption: Disruption, incoming_disrup
                     None,
                     false,
                     false,
+                    InletServiceOptions::default(),
                 )
                 .await?;
 
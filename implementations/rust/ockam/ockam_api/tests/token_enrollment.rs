@@ -1,6 +1,8 @@
-use crate::common::common::{change_client_identifier, start_authority, AuthorityInfo};
-use ockam::identity::secure_channels;
+use crate::common::common::{
+    change_client_identifier, start_authority, start_authority_with_admin_checks, AuthorityInfo,
+};
 use ockam::identity::utils::now;
+use ockam::identity::{secure_channels, DELEGATED_ISSUER_ATTRIBUTE};
 use ockam_api::authenticator::direct::Members;
 use ockam_api::authenticator::direct::{
     OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE, OCKAM_ROLE_ATTRIBUTE_KEY,
@@ -272,6 +274,51 @@ async fn enroller_cant_add_enroller(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn enroller_cant_issue_token_for_delegated_issuer(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+
+    let AuthorityInfo { admins, .. } =
+        start_authority_with_admin_checks(ctx, secure_channels.clone(), 1, true).await?;
+    let admin = &admins[0];
+
+    let mut attributes = BTreeMap::<String, String>::default();
+    attributes.insert(
+        OCKAM_ROLE_ATTRIBUTE_KEY.to_string(),
+        OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE.to_string(),
+    );
+    let otc = admin
+        .client
+        .create_token(ctx, attributes, None, None)
+        .await
+        .unwrap();
+
+    let enroller = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let enroller_client = change_client_identifier(&admin.client, &enroller, None);
+
+    enroller_client.present_token(ctx, otc).await.unwrap();
+
+    let mut attributes = BTreeMap::<String, String>::default();
+    attributes.insert(DELEGATED_ISSUER_ATTRIBUTE.to_string(), "true".to_string());
+    let res = enroller_client
+        .create_token(ctx, attributes.clone(), None, None)
+        .await;
+    assert!(res.is_err());
+
+    // an admin can issue a token for a delegated issuer
+    admin
+        .client
+        .create_token(ctx, attributes, None, None)
+        .await
+        .unwrap();
+
+    Ok(())
+}
+
 #[ockam_macros::test]
 async fn token_expiration(ctx: &mut Context) -> Result<()> {
     let secure_channels = secure_channels().await?;
@@ -404,3 +451,64 @@ async fn usage_count2(ctx: &mut Context) -> Result<()> {
 
     Ok(())
 }
+
+#[ockam_macros::test]
+async fn usage_count_zero_is_rejected(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+
+    let AuthorityInfo { admins, .. } = start_authority(ctx, secure_channels.clone(), 1).await?;
+    let admin = &admins[0];
+
+    let res = admin
+        .client
+        .create_token(ctx, Default::default(), None, Some(0))
+        .await;
+    assert!(res.is_err());
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn admin_can_list_and_revoke_tokens(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+
+    let AuthorityInfo { admins, .. } = start_authority(ctx, secure_channels.clone(), 1).await?;
+    let admin = &admins[0];
+
+    let mut attributes = BTreeMap::<String, String>::default();
+    attributes.insert("KEY".to_string(), "VALUE".to_string());
+    let otc = admin
+        .client
+        .create_token(ctx, attributes.clone(), None, Some(3))
+        .await
+        .unwrap();
+
+    let tokens = admin.client.list_tokens(ctx).await.unwrap();
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].remaining_uses(), 3);
+    assert_eq!(tokens[0].attributes(), &attributes);
+    assert_eq!(tokens[0].issued_by(), &admin.identifier);
+
+    admin
+        .client
+        .revoke_token(ctx, tokens[0].reference())
+        .await
+        .unwrap();
+    assert!(admin.client.list_tokens(ctx).await.unwrap().is_empty());
+
+    // a revoked token can not be used anymore
+    let member = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let member_client = change_client_identifier(&admin.client, &member, None);
+    let res = member_client.present_token(ctx, otc).await;
+    assert!(res.is_err());
+
+    // an unknown token can not be revoked
+    let res = admin.client.revoke_token(ctx, tokens[0].reference()).await;
+    assert!(res.is_err());
+
+    Ok(())
+}
@@ -12,7 +12,7 @@ use ockam_api::address::get_free_address;
 use ockam_api::authenticator::direct::{
     OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE, OCKAM_ROLE_ATTRIBUTE_KEY,
 };
-use ockam_api::nodes::service::tcp_inlets::Inlets;
+use ockam_api::nodes::service::tcp_inlets::{InletServiceOptions, Inlets};
 use ockam_api::ConnectionStatus;
 use ockam_core::api::Reply;
 use ockam_multiaddr::MultiAddr;
@@ -226,6 +226,7 @@ impl AppState {
                 &None,
                 false,
                 false,
+                &InletServiceOptions::default(),
             )
             .await
             .map_err(|err| {
@@ -5,6 +5,7 @@ use ockam::transport::{resolve_peer, HostnamePort};
 use ockam::Address;
 use ockam_api::address::extract_address_value;
 use ockam_api::nodes::models::portal::OutletAccessControl;
+use ockam_api::nodes::service::tcp_outlets::OutletServiceOptions;
 use std::sync::Arc;
 use tracing::{debug, info};
 
@@ -41,6 +42,7 @@ impl AppState {
                 Some(worker_addr.clone()),
                 true,
                 OutletAccessControl::AccessControl((Arc::new(incoming_ac), Arc::new(outgoing_ac))),
+                OutletServiceOptions::default(),
             )
             .await
         {
@@ -7,6 +7,7 @@ use crate::state::{AppState, ModelState};
 use ockam::transport::HostnamePort;
 use ockam::Address;
 use ockam_api::nodes::models::portal::{OutletAccessControl, OutletStatus};
+use ockam_api::nodes::service::tcp_outlets::OutletServiceOptions;
 
 impl ModelState {
     pub fn add_tcp_outlet(&mut self, status: OutletStatus) {
@@ -78,6 +79,7 @@ impl AppState {
                         Arc::new(incoming_ac),
                         Arc::new(outgoing_ac),
                     )),
+                    OutletServiceOptions::default(),
                 )
                 .await
                 .map_err(|e| {
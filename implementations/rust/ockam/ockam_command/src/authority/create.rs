@@ -10,6 +10,7 @@ use tokio_retry::strategy::FixedInterval;
 use tokio_retry::Retry;
 use tracing::{debug, error, info};
 
+use ockam::identity::models::{AttributeDefinition, CredentialAndPurposeKey};
 use ockam::identity::utils::now;
 use ockam::identity::{Identifier, Identity, TimestampInSeconds, Vault};
 use ockam::Context;
@@ -127,6 +128,18 @@ pub struct CreateCommand {
     /// TODO: Set to true after old clients are updated
     #[arg(long, value_name = "DISABLE_TRUST_CONTEXT_ID", default_value_t = false)]
     disable_trust_context_id: bool,
+
+    /// Schema of the member attributes, used to validate the issued and presented credentials.
+    /// Format: [{"name": "role", "type": "string", "allowed_values": ["admin", "user"], "required": true}, ...]
+    /// The supported types are "string", "integer" and "boolean"
+    #[arg(long, value_name = "JSON_ARRAY", value_parser = parse_attribute_schema)]
+    attribute_schema: Option<AttributeSchema>,
+
+    /// Hex-encoded credential issued to this authority by a parent authority, with the
+    /// attribute "ockam-delegated-issuer=true". If set, this authority issues delegated
+    /// credentials which can be verified by the nodes trusting the parent authority
+    #[arg(long, value_name = "CREDENTIAL_HEX", default_value = None)]
+    issuer_credential: Option<String>,
 }
 
 impl CreateCommand {
@@ -230,6 +243,14 @@ impl CreateCommand {
         if self.disable_trust_context_id {
             args.push("--disable_trust_context_id".to_string());
         }
+        if let Some(attribute_schema) = &self.attribute_schema {
+            args.push("--attribute-schema".to_string());
+            args.push(attribute_schema.to_string());
+        }
+        if let Some(issuer_credential) = &self.issuer_credential {
+            args.push("--issuer-credential".to_string());
+            args.push(issuer_credential.clone());
+        }
         args.push(self.node_name.to_string());
 
         run_ockam(args, opts.global_args.quiet).await
@@ -342,6 +363,13 @@ impl CreateCommand {
             None => None,
         };
 
+        let issuer_credential = match &self.issuer_credential {
+            Some(issuer_credential) => Some(
+                CredentialAndPurposeKey::decode_from_string(issuer_credential).into_diagnostic()?,
+            ),
+            None => None,
+        };
+
         // Create an identity for exporting opentelemetry traces
         let exporter = "ockam-opentelemetry-exporter";
         let exporter_identity = match opts.state.get_named_identity(exporter).await {
@@ -404,6 +432,8 @@ impl CreateCommand {
             account_authority,
             enforce_admin_checks: self.enforce_admin_checks,
             disable_trust_context_id: self.disable_trust_context_id,
+            attribute_schema: self.attribute_schema.clone().map(|s| s.0),
+            issuer_credential,
         };
 
         // create the authority identity
@@ -538,8 +568,32 @@ impl Display for TrustedIdentities {
     }
 }
 
+/// Return the schema of the member attributes passed as a JSON string on the command line
+fn parse_attribute_schema(values: &str) -> Result<AttributeSchema> {
+    serde_json::from_str::<AttributeSchema>(values).map_err(|e| {
+        crate::Error::new(
+            exitcode::CONFIG,
+            miette!("Cannot parse the attribute schema: {}", e),
+        )
+    })
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+struct AttributeSchema(Vec<AttributeDefinition>);
+
+impl Display for AttributeSchema {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(
+            serde_json::to_string(self)
+                .map_err(|_| fmt::Error)?
+                .as_str(),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use ockam::identity::models::AttributeType;
     use ockam::identity::{identities, Identifier};
     use ockam_api::authenticator::direct::{
         OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE, OCKAM_ROLE_ATTRIBUTE_KEY,
@@ -579,6 +633,26 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_attribute_schema() {
+        let schema = r#"[{"name": "role", "type": "string", "allowed_values": ["admin", "user"], "required": true}, {"name": "level", "type": "integer"}]"#;
+        let actual = parse_attribute_schema(schema).unwrap();
+
+        assert_eq!(actual.0.len(), 2);
+        assert_eq!(actual.0[0].name, "role");
+        assert_eq!(actual.0[0].attribute_type, AttributeType::String);
+        assert_eq!(actual.0[0].allowed_values, vec!["admin", "user"]);
+        assert!(actual.0[0].required);
+        assert_eq!(actual.0[1].attribute_type, AttributeType::Integer);
+        assert!(actual.0[1].allowed_values.is_empty());
+        assert!(!actual.0[1].required);
+
+        // the schema can be passed again to a child process
+        assert_eq!(parse_attribute_schema(&actual.to_string()).unwrap(), actual);
+
+        assert!(parse_attribute_schema(r#"[{"name": "role", "type": "date"}]"#).is_err());
+    }
+
     /// HELPERS
     async fn create_identity() -> Result<Identifier> {
         let identities = identities().await?;
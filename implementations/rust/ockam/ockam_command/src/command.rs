@@ -8,7 +8,7 @@ use tracing::{instrument, warn};
 
 use ockam_core::OCKAM_TRACER_NAME;
 
-use crate::command_events::{add_command_error_event, add_command_event};
+use crate::command_events::{add_command_error_event, add_command_event, add_command_execution};
 use crate::command_global_opts::CommandGlobalOpts;
 use crate::docs;
 use crate::global_args::GlobalArgs;
@@ -90,6 +90,7 @@ impl OckamCommand {
 
         let tracer = global::tracer(OCKAM_TRACER_NAME);
         let command_name = self.subcommand.name();
+        let is_background_node = self.subcommand.is_background_node();
         let result =
             if let Some(opentelemetry_context) = self.subcommand.get_opentelemetry_context() {
                 let context = Context::current();
@@ -120,6 +121,14 @@ impl OckamCommand {
                 arguments.join(" "),
             )?
         };
+        if !is_background_node {
+            let error = result.as_ref().err().map(|e| format!("{e}"));
+            if let Err(e) =
+                add_command_execution(options.state.clone(), &command_name, &arguments, error)
+            {
+                warn!("Failed to record the command execution, error={e}");
+            }
+        }
         options.shutdown();
         result
     }
@@ -65,6 +65,102 @@ pub fn add_command_error_event(
     Ok(())
 }
 
+/// Names of the arguments which values are secrets and must not be stored in the command history
+const SECRET_ARGUMENTS: &[&str] = &[
+    "--enrollment-ticket",
+    "--credential",
+    "--ticket",
+    "--token",
+    "--password",
+    "--secret",
+];
+
+/// A command containing one of these words does not modify the Ockam configuration
+const READ_ONLY_COMMAND_WORDS: &[&str] = &[
+    "list",
+    "list-ids",
+    "show",
+    "status",
+    "logs",
+    "env",
+    "stats",
+    "top",
+    "debug-graph",
+    "trace",
+    "verify",
+    "test",
+    "validate",
+    "info",
+    "version",
+    "ping",
+    "export",
+    "completion",
+    "markdown",
+    "manpages",
+    "tui",
+    "environment",
+];
+
+/// This function records the execution of a command modifying the Ockam configuration
+/// in the command history, with its result
+pub fn add_command_execution(
+    cli_state: CliState,
+    command_name: &str,
+    command_arguments: &[String],
+    error: Option<String>,
+) -> miette::Result<()> {
+    if is_read_only_command(command_name) {
+        return Ok(());
+    }
+    let arguments =
+        sanitize_command_arguments(redact_secret_arguments(command_arguments).join(" "));
+    let command_name = command_name.to_string();
+    Executor::execute_future(async move {
+        cli_state
+            .add_command_execution(&command_name, &arguments, &current_user_name(), error)
+            .await
+    })
+    .into_diagnostic()??;
+    Ok(())
+}
+
+/// Return true if the command does not modify the Ockam configuration
+pub fn is_read_only_command(command_name: &str) -> bool {
+    command_name
+        .split_whitespace()
+        .any(|word| READ_ONLY_COMMAND_WORDS.contains(&word))
+}
+
+/// Replace the values of the secret arguments with a placeholder
+pub fn redact_secret_arguments(command_args: &[String]) -> Vec<String> {
+    let mut redacted = vec![];
+    let mut is_secret_value = false;
+    for arg in command_args {
+        if is_secret_value {
+            redacted.push("<redacted>".to_string());
+            is_secret_value = false;
+            continue;
+        }
+        match arg.split_once('=') {
+            Some((name, _)) if SECRET_ARGUMENTS.contains(&name) => {
+                redacted.push(format!("{name}=<redacted>"));
+            }
+            _ => {
+                is_secret_value = SECRET_ARGUMENTS.contains(&arg.as_str());
+                redacted.push(arg.clone());
+            }
+        }
+    }
+    redacted
+}
+
+/// Return the name of the OS user running the command
+fn current_user_name() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
 /// The ockam project enroll command arguments contain the enrollment ticket which is sensitive
 /// information (because it could be potentially reused), so it should be removed from the user event.
 pub fn sanitize_command_arguments(command_args: String) -> String {
@@ -90,4 +186,32 @@ mod tests {
             "ockam node create n1".to_string()
         );
     }
+
+    #[test]
+    fn redact_secrets() {
+        let args = |s: &str| s.split(' ').map(|a| a.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            redact_secret_arguments(&args(
+                "ockam node create n1 --enrollment-ticket abcd --foreground"
+            )),
+            args("ockam node create n1 --enrollment-ticket <redacted> --foreground")
+        );
+        assert_eq!(
+            redact_secret_arguments(&args("ockam credential store --credential=abcd")),
+            args("ockam credential store --credential=<redacted>")
+        );
+        assert_eq!(
+            redact_secret_arguments(&args("ockam tcp-inlet create --to outlet")),
+            args("ockam tcp-inlet create --to outlet")
+        );
+    }
+
+    #[test]
+    fn read_only_commands() {
+        assert!(is_read_only_command("node list"));
+        assert!(is_read_only_command("tcp-inlet show"));
+        assert!(is_read_only_command("status"));
+        assert!(!is_read_only_command("node create"));
+        assert!(!is_read_only_command("history clear"));
+    }
 }
@@ -57,7 +57,8 @@ impl CommandGlobalOpts {
             global_args.no_color,
             global_args.no_input,
             global_args.output_format()?,
-        );
+        )
+        .with_list_options(global_args.list_options());
         let tracing_guard =
             Self::setup_logging_tracing(cmd, &logging_configuration, &tracing_configuration);
 
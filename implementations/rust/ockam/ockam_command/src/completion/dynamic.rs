@@ -0,0 +1,239 @@
+use clap::{Arg, Command};
+use clap_complete::Shell;
+
+use ockam_api::CliState;
+
+/// Resources which are stored in the CliState and whose names can be proposed as completions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Resource {
+    Node,
+    Identity,
+    Vault,
+    Space,
+    Project,
+}
+
+impl Resource {
+    /// Return the resource named by an argument value name (e.g. `NODE_NAME`), or,
+    /// for a generic `NAME` argument, the resource managed by the top-level subcommand
+    fn from_value_name(value_name: &str, top_level_subcommand: Option<&str>) -> Option<Self> {
+        let value_name = value_name.to_uppercase();
+        let resource = match value_name.as_str() {
+            "NAME" => top_level_subcommand?.to_uppercase(),
+            _ => value_name,
+        };
+        if resource.starts_with("NODE") {
+            Some(Resource::Node)
+        } else if resource.starts_with("IDENTITY") {
+            Some(Resource::Identity)
+        } else if resource.starts_with("VAULT") {
+            Some(Resource::Vault)
+        } else if resource.starts_with("SPACE") {
+            Some(Resource::Space)
+        } else if resource.starts_with("PROJECT") {
+            Some(Resource::Project)
+        } else {
+            None
+        }
+    }
+
+    /// Return the names of the existing resources
+    pub(crate) async fn names(&self, state: &CliState) -> miette::Result<Vec<String>> {
+        Ok(match self {
+            Resource::Node => state.get_nodes().await?.iter().map(|n| n.name()).collect(),
+            Resource::Identity => state
+                .get_named_identities()
+                .await?
+                .iter()
+                .map(|i| i.name())
+                .collect(),
+            Resource::Vault => state
+                .get_named_vaults()
+                .await?
+                .iter()
+                .map(|v| v.name())
+                .collect(),
+            Resource::Space => state
+                .get_spaces()
+                .await?
+                .into_iter()
+                .map(|s| s.name)
+                .collect(),
+            Resource::Project => state
+                .projects()
+                .get_projects()
+                .await?
+                .iter()
+                .map(|p| p.name().to_string())
+                .collect(),
+        })
+    }
+}
+
+/// Return the resource whose names can complete the word at `index` in `words`,
+/// where `words[0]` is the name of the binary.
+///
+/// `None` is returned when the word is a subcommand, a flag, or the value of an argument
+/// which doesn't refer to a resource. In that case the static completion is used.
+pub(crate) fn resource_to_complete(
+    mut command: Command,
+    words: &[String],
+    index: usize,
+) -> Option<Resource> {
+    if index == 0 || index > words.len() {
+        return None;
+    }
+    // Propagate the global arguments and set the number of values of each argument
+    command.build();
+
+    let current = words.get(index).map(|w| w.as_str()).unwrap_or_default();
+    if current.starts_with('-') {
+        return None;
+    }
+
+    // Find the subcommand being completed and the positional arguments given so far
+    let mut command = &command;
+    let mut path: Vec<&str> = vec![];
+    let mut positionals = 0;
+    let mut i = 1;
+    while i < index {
+        let word = words[i].as_str();
+        if let Some(name) = word.strip_prefix("--") {
+            // Skip the value of an option when it is given as a separate word
+            if !name.contains('=') && find_long(command, name).is_some_and(takes_value) {
+                i += 1;
+            }
+        } else if word.starts_with('-') && word.len() == 2 {
+            let short = word.chars().nth(1);
+            if command
+                .get_arguments()
+                .find(|a| a.get_short() == short)
+                .is_some_and(takes_value)
+            {
+                i += 1;
+            }
+        } else if positionals == 0 && command.find_subcommand(word).is_some() {
+            command = command.find_subcommand(word)?;
+            path.push(command.get_name());
+        } else {
+            positionals += 1;
+        }
+        i += 1;
+    }
+
+    let previous = words[index - 1].as_str();
+    let arg = match previous.strip_prefix("--") {
+        Some(name) if index > 1 => find_long(command, name).filter(|a| takes_value(a)),
+        _ => None,
+    };
+    let arg = match arg {
+        Some(arg) => arg,
+        // Names of existing resources are not useful to name a new one
+        None if path.last() == Some(&"create") => return None,
+        None => command
+            .get_positionals()
+            .filter(|a| !a.is_hide_set())
+            .nth(positionals)?,
+    };
+
+    let value_name = arg
+        .get_value_names()
+        .and_then(|names| names.first().map(|n| n.to_string()))
+        .unwrap_or_else(|| arg.get_id().as_str().to_string());
+    Resource::from_value_name(&value_name, path.first().copied())
+}
+
+/// Return a script which extends the static completion of a shell with the names of the
+/// resources stored in the CliState, or None if the shell only supports static completion
+pub(crate) fn dynamic_completion_script(shell: Shell) -> Option<&'static str> {
+    match shell {
+        Shell::Bash => Some(BASH_SCRIPT),
+        Shell::Zsh => Some(ZSH_SCRIPT),
+        Shell::Fish => Some(FISH_SCRIPT),
+        _ => None,
+    }
+}
+
+fn find_long<'a>(command: &'a Command, name: &str) -> Option<&'a Arg> {
+    command.get_arguments().find(|a| a.get_long() == Some(name))
+}
+
+fn takes_value(arg: &Arg) -> bool {
+    arg.get_num_args().is_some_and(|n| n.takes_values())
+}
+
+const BASH_SCRIPT: &str = r#"
+_ockam_resources() {
+    local names
+    if names=$(OCKAM_DISABLE_UPGRADE_CHECK=true ockam completion --complete "${COMP_CWORD}" -- "${COMP_WORDS[@]}" 2>/dev/null); then
+        COMPREPLY=( $(compgen -W "${names}" -- "${COMP_WORDS[COMP_CWORD]}") )
+    else
+        _ockam "$@"
+    fi
+}
+
+complete -F _ockam_resources -o nosort -o bashdefault -o default ockam
+"#;
+
+const ZSH_SCRIPT: &str = r#"
+_ockam_resources() {
+    local names
+    if names=$(OCKAM_DISABLE_UPGRADE_CHECK=true ockam completion --complete $((CURRENT - 1)) -- "${words[@]}" 2>/dev/null); then
+        local -a resources
+        resources=("${(@f)names}")
+        compadd -a resources
+    else
+        _ockam "$@"
+    fi
+}
+
+compdef _ockam_resources ockam
+"#;
+
+const FISH_SCRIPT: &str = r#"
+function __ockam_resources
+    set -l words (commandline -opc) (commandline -ct)
+    env OCKAM_DISABLE_UPGRADE_CHECK=true ockam completion --complete (math (count $words) - 1) -- $words 2>/dev/null
+end
+
+complete -c ockam -a '(__ockam_resources)'
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OckamCommand;
+    use clap::CommandFactory;
+
+    fn resource(line: &str) -> Option<Resource> {
+        let mut words: Vec<String> = line.split_whitespace().map(|w| w.to_string()).collect();
+        if line.ends_with(' ') {
+            words.push("".to_string());
+        }
+        resource_to_complete(OckamCommand::command(), &words, words.len() - 1)
+    }
+
+    #[test]
+    fn complete_resource_names() {
+        assert_eq!(resource("ockam node show "), Some(Resource::Node));
+        assert_eq!(resource("ockam node stop n"), Some(Resource::Node));
+        assert_eq!(resource("ockam identity show "), Some(Resource::Identity));
+        assert_eq!(resource("ockam vault show "), Some(Resource::Vault));
+        assert_eq!(
+            resource("ockam tcp-inlet create --at "),
+            Some(Resource::Node)
+        );
+        assert_eq!(
+            resource("ockam node create n --identity "),
+            Some(Resource::Identity)
+        );
+    }
+
+    #[test]
+    fn do_not_complete_other_words() {
+        assert_eq!(resource("ockam node "), None);
+        assert_eq!(resource("ockam node show --"), None);
+        assert_eq!(resource("ockam node create "), None);
+        assert_eq!(resource("ockam node show n "), None);
+    }
+}
@@ -1,10 +1,17 @@
 use std::io;
+use std::io::Write;
 
 use clap::{Args, CommandFactory};
 use clap_complete::{generate, Shell};
+use miette::IntoDiagnostic;
 
+use ockam_api::CliState;
+
+use crate::completion::dynamic::{dynamic_completion_script, resource_to_complete};
 use crate::{docs, OckamCommand};
 
+mod dynamic;
+
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
 const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
@@ -19,22 +26,59 @@ const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
 )]
 pub struct CompletionCommand {
     /// The type of shell
-    #[arg(display_order = 900, long, short)]
-    shell: Shell,
+    #[arg(display_order = 900, long, short, required_unless_present = "complete")]
+    shell: Option<Shell>,
+
+    /// Print the names of the resources which can complete the word at the given index
+    /// of the command line. This is used by the generated completion scripts
+    #[arg(hide = true, long, value_name = "INDEX", conflicts_with = "shell")]
+    complete: Option<usize>,
+
+    /// Words of the command line to complete
+    #[arg(hide = true, last = true)]
+    words: Vec<String>,
 }
 
 impl CompletionCommand {
     pub fn run(self) -> miette::Result<()> {
-        generate(
-            self.shell,
-            &mut OckamCommand::command(),
-            "ockam",
-            &mut io::stdout(),
-        );
+        if let Some(index) = self.complete {
+            return Self::complete(&self.words, index);
+        }
+        if let Some(shell) = self.shell {
+            generate(
+                shell,
+                &mut OckamCommand::command(),
+                "ockam",
+                &mut io::stdout(),
+            );
+            if let Some(script) = dynamic_completion_script(shell) {
+                io::stdout()
+                    .write_all(script.as_bytes())
+                    .into_diagnostic()?;
+            }
+        }
         Ok(())
     }
 
     pub fn name(&self) -> String {
         "completion".to_string()
     }
+
+    /// Print the names of the resources completing the word at `index`, one per line.
+    /// An error is returned if the word doesn't refer to a resource, so that the completion
+    /// script falls back to the static completion.
+    fn complete(words: &[String], index: usize) -> miette::Result<()> {
+        let resource = resource_to_complete(OckamCommand::command(), words, index)
+            .ok_or_else(|| miette::miette!("There are no resources to complete"))?;
+        let runtime = tokio::runtime::Runtime::new().into_diagnostic()?;
+        let names = runtime.block_on(async {
+            let state = CliState::with_default_dir()?;
+            resource.names(&state).await
+        })?;
+        let mut stdout = io::stdout().lock();
+        for name in names {
+            writeln!(stdout, "{name}").into_diagnostic()?;
+        }
+        Ok(())
+    }
 }
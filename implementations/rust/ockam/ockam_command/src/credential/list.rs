@@ -2,28 +2,35 @@ use clap::Args;
 use colorful::Colorful;
 use miette::IntoDiagnostic;
 
-use ockam::identity::{CredentialSqlxDatabase, Identifier};
+use ockam::identity::Identifier;
 use ockam_api::colors::OckamColor;
 
 use crate::credential::CredentialOutput;
 use crate::node::NodeOpts;
 use crate::util::async_cmd;
 use crate::util::parsers::identity_identifier_parser;
-use crate::CommandGlobalOpts;
-use crate::Result;
+use crate::{docs, CommandGlobalOpts};
 
+const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
+
+/// List the credentials cached by a node, with their remaining time to live
 #[derive(Clone, Debug, Args)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
 pub struct ListCommand {
     #[command(flatten)]
     pub node_opts: NodeOpts,
 
-    /// Subject Identifier
+    /// Only list the credentials issued for this subject Identifier
     #[arg(long, value_name = "SUBJECT", value_parser = identity_identifier_parser)]
     subject: Option<Identifier>,
 
-    /// Issuer Identifier
+    /// Only list the credentials issued by this Identifier
     #[arg(long, value_name = "ISSUER", value_parser = identity_identifier_parser)]
     issuer: Option<Identifier>,
+
+    /// Verify the signature of each credential against the change history of its issuer
+    #[arg(long)]
+    verify: bool,
 }
 
 impl ListCommand {
@@ -42,25 +49,54 @@ impl ListCommand {
             Some(name) => name,
             None => opts.state.get_default_node().await?.name(),
         };
-        let database = opts.state.database();
-        let storage = CredentialSqlxDatabase::new(database, &node_name);
-
-        let credentials = storage.get_all().await.into_diagnostic()?;
 
-        let credentials = credentials
-            .into_iter()
-            .map(|c| CredentialOutput::from_credential(c.0, c.1, true))
-            .collect::<Result<Vec<CredentialOutput>>>()?;
+        let mut credentials = vec![];
+        for (credential, scope) in opts.state.get_cached_credentials(&node_name).await? {
+            let is_verified = if self.verify {
+                let issuer = credential
+                    .purpose_key_attestation
+                    .get_attestation_data()
+                    .into_diagnostic()?
+                    .subject;
+                Some(
+                    opts.state
+                        .verify_cached_credential(&issuer, &credential)
+                        .await
+                        .is_ok(),
+                )
+            } else {
+                None
+            };
+            let credential = CredentialOutput::from_credential(credential, scope, is_verified)?;
+            if self
+                .subject
+                .as_ref()
+                .is_some_and(|s| s != credential.subject())
+                || self
+                    .issuer
+                    .as_ref()
+                    .is_some_and(|i| i != credential.issuer())
+            {
+                continue;
+            }
+            credentials.push(credential);
+        }
+        // Show the credentials of each identity together
+        credentials.sort_by(|c1, c2| c1.subject().cmp(c2.subject()));
 
         let list = opts.terminal.build_list(
             &credentials,
             &format!(
-                "No Credentials found for vault: {}",
+                "No Credentials found for node: {}",
                 node_name.color(OckamColor::PrimaryResource.color())
             ),
         )?;
 
-        opts.terminal.stdout().plain(list).write_line()?;
+        opts.terminal
+            .stdout()
+            .plain(list)
+            .json_obj(&credentials)?
+            .write_line()?;
 
         Ok(())
     }
@@ -1,22 +1,25 @@
 use clap::{Args, Subcommand};
 use colorful::core::StrMarker;
 use colorful::Colorful;
+use serde::Serialize;
 use serde_json::json;
 
 pub(crate) use issue::IssueCommand;
-use ockam::identity::models::{CredentialAndPurposeKey, CredentialSchemaIdentifier};
+use ockam::identity::models::CredentialAndPurposeKey;
 use ockam::identity::{Identifier, TimestampInSeconds};
 use ockam_api::output::Output;
 use ockam_core::compat::collections::HashMap;
+pub(crate) use schema::SchemaCommand;
 pub(crate) use store::StoreCommand;
 pub(crate) use verify::VerifyCommand;
 
 use crate::credential::list::ListCommand;
 use crate::error::Error;
-use crate::{CommandGlobalOpts, Result};
+use crate::{Command, CommandGlobalOpts, Result};
 
 pub(crate) mod issue;
 pub(crate) mod list;
+pub(crate) mod schema;
 pub(crate) mod store;
 pub(crate) mod verify;
 
@@ -35,6 +38,7 @@ pub enum CredentialSubcommand {
     Issue(IssueCommand),
     Store(StoreCommand),
     Verify(VerifyCommand),
+    Schema(SchemaCommand),
 }
 
 impl CredentialSubcommand {
@@ -44,6 +48,7 @@ impl CredentialSubcommand {
             CredentialSubcommand::Issue(c) => c.name(),
             CredentialSubcommand::Store(c) => c.name(),
             CredentialSubcommand::Verify(c) => c.name(),
+            CredentialSubcommand::Schema(c) => c.name(),
         }
     }
 }
@@ -55,6 +60,7 @@ impl CredentialCommand {
             CredentialSubcommand::Issue(c) => c.run(opts),
             CredentialSubcommand::Store(c) => c.run(opts),
             CredentialSubcommand::Verify(c) => c.run(opts),
+            CredentialSubcommand::Schema(c) => c.run(opts),
         }
     }
 
@@ -63,6 +69,7 @@ impl CredentialCommand {
     }
 }
 
+#[derive(Serialize)]
 pub struct CredentialOutput {
     credential: String,
     scope: String,
@@ -70,8 +77,11 @@ pub struct CredentialOutput {
     issuer: Identifier,
     created_at: TimestampInSeconds,
     expires_at: TimestampInSeconds,
-    is_verified: bool,
-    schema: CredentialSchemaIdentifier,
+    /// Number of seconds before the credential expires, 0 if it is already expired
+    expires_in: u64,
+    /// None if the credential has not been verified
+    is_verified: Option<bool>,
+    schema: u64,
     attributes: HashMap<String, String>,
 }
 
@@ -79,7 +89,7 @@ impl CredentialOutput {
     pub fn from_credential(
         credential: CredentialAndPurposeKey,
         scope: String,
-        is_verified: bool,
+        is_verified: Option<bool>,
     ) -> Result<Self> {
         let str = hex::encode(credential.encode_as_cbor_bytes()?);
         let credential_data = credential.credential.get_credential_data()?;
@@ -101,6 +111,11 @@ impl CredentialOutput {
             }
         }
 
+        let expires_in = credential_data
+            .expires_at
+            .0
+            .saturating_sub(ockam::identity::utils::now()?.0);
+
         let s = Self {
             credential: str,
             scope,
@@ -108,21 +123,47 @@ impl CredentialOutput {
             issuer: purpose_key_data.subject,
             created_at: credential_data.created_at,
             expires_at: credential_data.expires_at,
+            expires_in,
             is_verified,
-            schema: credential_data.subject_attributes.schema,
+            schema: credential_data.subject_attributes.schema.0,
             attributes,
         };
 
         Ok(s)
     }
+
+    pub fn subject(&self) -> &Identifier {
+        &self.subject
+    }
+
+    pub fn issuer(&self) -> &Identifier {
+        &self.issuer
+    }
+
+    /// Remaining time before the credential expires, for example `2h 5m 10s`
+    fn expires_in_text(&self) -> String {
+        if self.expires_in == 0 {
+            return "expired".to_string();
+        }
+        let (hours, minutes, seconds) = (
+            self.expires_in / 3600,
+            (self.expires_in % 3600) / 60,
+            self.expires_in % 60,
+        );
+        match (hours, minutes) {
+            (0, 0) => format!("{seconds}s"),
+            (0, _) => format!("{minutes}m {seconds}s"),
+            _ => format!("{hours}h {minutes}m {seconds}s"),
+        }
+    }
 }
 
 impl Output for CredentialOutput {
     fn item(&self) -> ockam_api::Result<String> {
-        let is_verified = if self.is_verified {
-            "✔︎".light_green()
-        } else {
-            "✕".light_red()
+        let is_verified = match self.is_verified {
+            Some(true) => "✔︎".light_green(),
+            Some(false) => "✕".light_red(),
+            None => "not checked, use --verify".light_yellow(),
         };
 
         let attributes = json!(self.attributes).to_string();
@@ -135,6 +176,7 @@ impl Output for CredentialOutput {
             \tis_verified: {is_verified}\n\
             \tcreated_at:  {created_at}\n\
             \texpires_at:  {expires_at}\n\
+            \texpires_in:  {expires_in}\n\
             \tschema:      {schema}\n\
             \tattributes:  {attributes}\n\
             \tbinary:      {credential}",
@@ -144,7 +186,8 @@ impl Output for CredentialOutput {
             is_verified = is_verified,
             created_at = self.created_at.0,
             expires_at = self.expires_at.0,
-            schema = self.schema.0,
+            expires_in = self.expires_in_text(),
+            schema = self.schema,
             attributes = attributes,
             credential = self.credential
         );
@@ -0,0 +1,103 @@
+use std::fmt::Write;
+
+use async_trait::async_trait;
+use clap::Args;
+use serde::Serialize;
+
+use ockam::identity::models::CredentialSchema;
+use ockam::Context;
+use ockam_api::colors::{color_primary, color_warn};
+use ockam_api::enroll::enrollment::Enrollment;
+use ockam_api::output::Output;
+use ockam_api::terminal::fmt;
+use ockam_multiaddr::MultiAddr;
+
+use crate::project_member::authority_client;
+use crate::shared_args::IdentityOpts;
+use crate::{docs, Command, CommandGlobalOpts, Result};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/schema/after_long_help.txt");
+
+/// Show the schema of the member credentials published by a Project authority
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct SchemaCommand {
+    #[command(flatten)]
+    identity_opts: IdentityOpts,
+
+    /// The route to the Project to get the schema from
+    #[arg(long, short, value_name = "ROUTE_TO_PROJECT")]
+    to: Option<MultiAddr>,
+}
+
+#[async_trait]
+impl Command for SchemaCommand {
+    const NAME: &'static str = "credential schema";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        let (authority_node_client, project_name) =
+            authority_client(ctx, &opts, &self.identity_opts, &self.to).await?;
+
+        match authority_node_client.get_credential_schema(ctx).await? {
+            Some(schema) => {
+                let schema = SchemaOutput { schema };
+                opts.terminal
+                    .stdout()
+                    .plain(schema.item()?)
+                    .json_obj(&schema.schema)?
+                    .write_line()?;
+            }
+            None => {
+                opts.terminal
+                    .stdout()
+                    .plain(format!(
+                        "The authority of the Project {} has not published a credential schema",
+                        color_primary(project_name)
+                    ))
+                    .json("null")
+                    .write_line()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct SchemaOutput {
+    schema: CredentialSchema,
+}
+
+impl Output for SchemaOutput {
+    fn item(&self) -> ockam_api::Result<String> {
+        let mut f = String::new();
+        writeln!(
+            f,
+            "{}Schema {}",
+            fmt::PADDING,
+            color_primary(self.schema.identifier.0.to_string())
+        )?;
+        for attribute in self.schema.attributes.iter() {
+            let required = if attribute.required { ", required" } else { "" };
+            writeln!(
+                f,
+                "{}{}{}: {}{}",
+                fmt::PADDING,
+                fmt::INDENTATION,
+                color_primary(&attribute.name),
+                attribute.attribute_type,
+                required
+            )?;
+            if !attribute.allowed_values.is_empty() {
+                writeln!(
+                    f,
+                    "{}{}{}Allowed values: {}",
+                    fmt::PADDING,
+                    fmt::INDENTATION,
+                    fmt::INDENTATION,
+                    color_warn(attribute.allowed_values.join(", "))
+                )?;
+            }
+        }
+        Ok(f)
+    }
+}
@@ -0,0 +1,207 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use clap::Args;
+use console::{Key, Term};
+use miette::{miette, IntoDiagnostic};
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use ockam_api::colors::{color_error, color_primary};
+use ockam_api::nodes::service::tcp_inlets::Inlets;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_core::api::Request;
+use ockam_node::Context;
+
+use crate::node::show::get_node_resources;
+use crate::util::api;
+use crate::util::parsers::duration_parser;
+use crate::{docs, Command, CommandGlobalOpts, Result};
+
+use view::{Dashboard, NodeView};
+
+mod view;
+
+/// Maximum time to wait for the response of a node when refreshing the dashboard
+const NODE_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
+
+/// Display an interactive dashboard of the local nodes, their secure channels, portals and relays
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct DashboardCommand {
+    /// Time to wait between two refreshes of the dashboard
+    #[arg(long, value_name = "DURATION", default_value = "2s", value_parser = duration_parser)]
+    interval: Duration,
+}
+
+/// Effect of a key press on the dashboard
+enum KeyAction {
+    Redraw,
+    Refresh,
+    Quit,
+}
+
+#[async_trait]
+impl Command for DashboardCommand {
+    const NAME: &'static str = "tui";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        let term = Term::stdout();
+        if !term.is_term() {
+            return Err(miette!(
+                "The dashboard can only be displayed in an interactive terminal"
+            ))?;
+        }
+
+        // Keys are read on a separate thread since reading from the terminal is blocking.
+        // The thread is not joined: it stops with the process once the dashboard is closed.
+        let (sender, keys) = mpsc::channel(16);
+        let reader = term.clone();
+        std::thread::spawn(move || {
+            while let Ok(key) = reader.read_key() {
+                if sender.blocking_send(key).is_err() {
+                    break;
+                }
+            }
+        });
+
+        term.hide_cursor().into_diagnostic()?;
+        let result = self.run_dashboard(ctx, &opts, &term, keys).await;
+        term.clear_screen().into_diagnostic()?;
+        term.show_cursor().into_diagnostic()?;
+        result
+    }
+}
+
+impl DashboardCommand {
+    /// Draw the dashboard and refresh it periodically or after an action, until the user quits
+    async fn run_dashboard(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        term: &Term,
+        mut keys: mpsc::Receiver<Key>,
+    ) -> Result<()> {
+        let mut dashboard = Dashboard::new();
+        let mut interval = tokio::time::interval(self.interval);
+        let mut refresh = true;
+        loop {
+            if refresh {
+                dashboard.update(load_nodes(ctx, opts).await?);
+            }
+            term.clear_screen().into_diagnostic()?;
+            term.write_str(&dashboard.render()).into_diagnostic()?;
+
+            refresh = tokio::select! {
+                key = keys.recv() => {
+                    let key = match key {
+                        Some(key) => key,
+                        None => return Ok(()),
+                    };
+                    match handle_key(ctx, opts, &mut dashboard, key).await {
+                        KeyAction::Redraw => false,
+                        KeyAction::Refresh => true,
+                        KeyAction::Quit => return Ok(()),
+                    }
+                }
+                _ = interval.tick() => true,
+            };
+        }
+    }
+}
+
+async fn handle_key(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    dashboard: &mut Dashboard,
+    key: Key,
+) -> KeyAction {
+    match key {
+        Key::ArrowUp | Key::Char('k') => dashboard.select_previous(),
+        Key::ArrowDown | Key::Char('j') => dashboard.select_next(),
+        Key::Tab | Key::BackTab => dashboard.toggle_focus(),
+        Key::Char('r') => return KeyAction::Refresh,
+        Key::Char('q') | Key::Escape | Key::CtrlC => return KeyAction::Quit,
+        Key::Char('s') => {
+            if let Some(node) = dashboard.selected_node().filter(|n| n.is_running) {
+                let node_name = node.name.clone();
+                let message = match opts.state.stop_node(&node_name, false).await {
+                    Ok(()) => format!("Node {} has been stopped", color_primary(&node_name)),
+                    Err(e) => {
+                        color_error(format!("Failed to stop the node {node_name}: {e}")).to_string()
+                    }
+                };
+                dashboard.set_message(message);
+                return KeyAction::Refresh;
+            }
+        }
+        Key::Char('d') => {
+            if let (Some(node), Some(inlet)) =
+                (dashboard.selected_node(), dashboard.selected_inlet())
+            {
+                let (node_name, alias) = (node.name.clone(), inlet.alias.clone());
+                let message = match delete_inlet(ctx, opts, &node_name, &alias).await {
+                    Ok(()) => format!(
+                        "TCP Inlet {} on node {} has been deleted",
+                        color_primary(&alias),
+                        color_primary(&node_name)
+                    ),
+                    Err(e) => color_error(format!("Failed to delete the TCP Inlet {alias}: {e}"))
+                        .to_string(),
+                };
+                dashboard.set_message(message);
+                return KeyAction::Refresh;
+            }
+        }
+        _ => {}
+    }
+    KeyAction::Redraw
+}
+
+/// Return all the local nodes, with the resources of the running ones
+async fn load_nodes(ctx: &Context, opts: &CommandGlobalOpts) -> Result<Vec<NodeView>> {
+    let mut nodes = vec![];
+    for node in opts.state.get_nodes().await? {
+        let mut view = NodeView::new(node.name(), node.is_running());
+        if view.is_running {
+            // A node which doesn't respond is still displayed, without its resources
+            if let Err(e) = load_node_resources(ctx, opts, &mut view).await {
+                warn!(node = %view.name, %e, "Failed to retrieve the node resources");
+            }
+        }
+        nodes.push(view);
+    }
+    Ok(nodes)
+}
+
+async fn load_node_resources(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    view: &mut NodeView,
+) -> miette::Result<()> {
+    let mut node = BackgroundNodeClient::create(ctx, &opts.state, &Some(view.name.clone())).await?;
+    node.set_timeout_mut(NODE_REQUEST_TIMEOUT);
+    view.resources = Some(get_node_resources(ctx, &opts.state, &mut node, false).await?);
+    view.secure_channels = node.ask(ctx, api::list_secure_channels()).await?;
+    view.relays = node.ask(ctx, Request::get("/node/relay")).await?;
+    Ok(())
+}
+
+async fn delete_inlet(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    node_name: &str,
+    alias: &str,
+) -> Result<()> {
+    let node = BackgroundNodeClient::create(ctx, &opts.state, &Some(node_name.to_string())).await?;
+    node.delete_inlet(ctx, alias).await?.success()?;
+    Ok(())
+}
@@ -0,0 +1,329 @@
+use std::fmt::Write;
+
+use colorful::Colorful;
+
+use ockam_api::colors::{color_error, color_ok, color_primary, color_warn};
+use ockam_api::nodes::models::node::NodeResources;
+use ockam_api::nodes::models::portal::InletStatus;
+use ockam_api::nodes::models::relay::RelayInfo;
+
+/// Resources of a node displayed by the dashboard
+pub(crate) struct NodeView {
+    pub(crate) name: String,
+    pub(crate) is_running: bool,
+    pub(crate) resources: Option<NodeResources>,
+    pub(crate) secure_channels: Vec<String>,
+    pub(crate) relays: Vec<RelayInfo>,
+}
+
+impl NodeView {
+    pub(crate) fn new(name: String, is_running: bool) -> Self {
+        Self {
+            name,
+            is_running,
+            resources: None,
+            secure_channels: vec![],
+            relays: vec![],
+        }
+    }
+
+    fn inlets(&self) -> &[InletStatus] {
+        self.resources
+            .as_ref()
+            .map(|r| r.inlets.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+/// List which receives the keyboard navigation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Focus {
+    Nodes,
+    Inlets,
+}
+
+/// State of the dashboard: the displayed nodes and the current selection
+pub(crate) struct Dashboard {
+    nodes: Vec<NodeView>,
+    focus: Focus,
+    selected_node: usize,
+    selected_inlet: usize,
+    message: Option<String>,
+}
+
+impl Dashboard {
+    pub(crate) fn new() -> Self {
+        Self {
+            nodes: vec![],
+            focus: Focus::Nodes,
+            selected_node: 0,
+            selected_inlet: 0,
+            message: None,
+        }
+    }
+
+    /// Replace the displayed nodes, keeping the same node selected if it still exists
+    pub(crate) fn update(&mut self, nodes: Vec<NodeView>) {
+        let selected = self.selected_node().map(|n| n.name.clone());
+        self.nodes = nodes;
+        self.selected_node = selected
+            .and_then(|name| self.nodes.iter().position(|n| n.name == name))
+            .unwrap_or(0)
+            .min(self.nodes.len().saturating_sub(1));
+        self.clamp_inlet_selection();
+    }
+
+    pub(crate) fn selected_node(&self) -> Option<&NodeView> {
+        self.nodes.get(self.selected_node)
+    }
+
+    /// Return the selected inlet when the inlets list has the focus
+    pub(crate) fn selected_inlet(&self) -> Option<&InletStatus> {
+        if self.focus != Focus::Inlets {
+            return None;
+        }
+        self.selected_node()
+            .and_then(|n| n.inlets().get(self.selected_inlet))
+    }
+
+    pub(crate) fn set_message(&mut self, message: impl Into<String>) {
+        self.message = Some(message.into());
+    }
+
+    pub(crate) fn toggle_focus(&mut self) {
+        self.focus = match self.focus {
+            Focus::Nodes if self.selected_node().is_some_and(|n| !n.inlets().is_empty()) => {
+                Focus::Inlets
+            }
+            _ => Focus::Nodes,
+        };
+    }
+
+    pub(crate) fn select_next(&mut self) {
+        match self.focus {
+            Focus::Nodes => {
+                if self.selected_node + 1 < self.nodes.len() {
+                    self.selected_node += 1;
+                    self.selected_inlet = 0;
+                }
+            }
+            Focus::Inlets => {
+                let inlets = self.selected_node().map(|n| n.inlets().len()).unwrap_or(0);
+                if self.selected_inlet + 1 < inlets {
+                    self.selected_inlet += 1;
+                }
+            }
+        }
+    }
+
+    pub(crate) fn select_previous(&mut self) {
+        match self.focus {
+            Focus::Nodes => {
+                if self.selected_node > 0 {
+                    self.selected_node -= 1;
+                    self.selected_inlet = 0;
+                }
+            }
+            Focus::Inlets => self.selected_inlet = self.selected_inlet.saturating_sub(1),
+        }
+    }
+
+    fn clamp_inlet_selection(&mut self) {
+        let inlets = self.selected_node().map(|n| n.inlets().len()).unwrap_or(0);
+        if inlets == 0 {
+            self.focus = Focus::Nodes;
+            self.selected_inlet = 0;
+        } else {
+            self.selected_inlet = self.selected_inlet.min(inlets - 1);
+        }
+    }
+
+    /// Render the dashboard as the text to display on the terminal
+    pub(crate) fn render(&self) -> String {
+        let mut out = String::new();
+        let _ = self.write(&mut out);
+        out
+    }
+
+    fn write(&self, out: &mut String) -> std::fmt::Result {
+        writeln!(out, "{}", color_primary("Ockam nodes"))?;
+        writeln!(out)?;
+        if self.nodes.is_empty() {
+            writeln!(
+                out,
+                "  No nodes found. Create one with `ockam node create`."
+            )?;
+        }
+        for (idx, node) in self.nodes.iter().enumerate() {
+            let is_selected = idx == self.selected_node;
+            let marker = if is_selected && self.focus == Focus::Nodes {
+                ">"
+            } else {
+                " "
+            };
+            let status = if node.is_running {
+                color_ok("UP")
+            } else {
+                color_error("DOWN")
+            };
+            writeln!(out, "{marker} {} {status}", color_primary(&node.name))?;
+        }
+
+        let node = match self.selected_node() {
+            Some(node) => node,
+            None => return self.write_footer(out),
+        };
+        writeln!(out)?;
+        writeln!(out, "Node {}", color_primary(&node.name))?;
+        if !node.is_running {
+            writeln!(out, "  {}", color_warn("The node is not running"))?;
+            return self.write_footer(out);
+        }
+
+        writeln!(out, "  Secure channels ({})", node.secure_channels.len())?;
+        for channel in &node.secure_channels {
+            writeln!(out, "      {channel}")?;
+        }
+
+        writeln!(out, "  TCP inlets ({})", node.inlets().len())?;
+        for (idx, inlet) in node.inlets().iter().enumerate() {
+            let marker = if self.focus == Focus::Inlets && idx == self.selected_inlet {
+                ">"
+            } else {
+                " "
+            };
+            writeln!(
+                out,
+                "    {marker} {} {} => {} {}",
+                color_primary(&inlet.alias),
+                inlet.bind_addr,
+                inlet.outlet_addr,
+                inlet.status
+            )?;
+        }
+
+        let outlets = node
+            .resources
+            .as_ref()
+            .map(|r| r.outlets.as_slice())
+            .unwrap_or_default();
+        writeln!(out, "  TCP outlets ({})", outlets.len())?;
+        for outlet in outlets {
+            writeln!(
+                out,
+                "      {} => {}",
+                color_primary(outlet.worker_addr.address()),
+                outlet.socket_addr
+            )?;
+        }
+
+        writeln!(out, "  Relays ({})", node.relays.len())?;
+        for relay in &node.relays {
+            writeln!(
+                out,
+                "      {} at {} {}",
+                color_primary(relay.alias()),
+                relay.destination_address(),
+                relay.connection_status()
+            )?;
+        }
+        self.write_footer(out)
+    }
+
+    fn write_footer(&self, out: &mut String) -> std::fmt::Result {
+        writeln!(out)?;
+        if let Some(message) = &self.message {
+            writeln!(out, "{message}")?;
+        }
+        writeln!(
+            out,
+            "{}",
+            "↑/↓ select  tab switch list  s stop node  d delete inlet  r refresh  q quit".dim()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_api::cli_state::NodeProcessStatus;
+    use ockam_api::nodes::models::node::RouteToNode;
+    use ockam_api::ConnectionStatus;
+
+    fn node(name: &str, inlets: &[&str]) -> NodeView {
+        let mut node = NodeView::new(name.to_string(), true);
+        node.resources = Some(NodeResources {
+            name: name.to_string(),
+            identity_name: "identity".to_string(),
+            is_default: false,
+            status: NodeProcessStatus::Running(1),
+            route: RouteToNode {
+                short: "/node/n".parse().unwrap(),
+                verbose: None,
+            },
+            http_server_address: None,
+            transports: vec![],
+            secure_channel_listeners: vec![],
+            inlets: inlets
+                .iter()
+                .map(|alias| {
+                    InletStatus::new(
+                        "127.0.0.1:5000",
+                        None,
+                        *alias,
+                        None,
+                        None,
+                        ConnectionStatus::Up,
+                        "/service/outlet",
+                    )
+                })
+                .collect(),
+            outlets: vec![],
+            services: vec![],
+            relays: vec![],
+        });
+        node
+    }
+
+    #[test]
+    fn navigate_nodes_and_inlets() {
+        let mut dashboard = Dashboard::new();
+        dashboard.update(vec![node("n1", &[]), node("n2", &["i1", "i2"])]);
+        assert_eq!(dashboard.selected_node().unwrap().name, "n1");
+
+        // The focus stays on the nodes when the selected node has no inlets
+        dashboard.toggle_focus();
+        assert!(dashboard.selected_inlet().is_none());
+
+        dashboard.select_next();
+        dashboard.select_next();
+        assert_eq!(dashboard.selected_node().unwrap().name, "n2");
+
+        dashboard.toggle_focus();
+        assert_eq!(dashboard.selected_inlet().unwrap().alias, "i1");
+        dashboard.select_next();
+        dashboard.select_next();
+        assert_eq!(dashboard.selected_inlet().unwrap().alias, "i2");
+        dashboard.select_previous();
+        assert_eq!(dashboard.selected_inlet().unwrap().alias, "i1");
+    }
+
+    #[test]
+    fn keep_the_selection_after_an_update() {
+        let mut dashboard = Dashboard::new();
+        dashboard.update(vec![node("n1", &[]), node("n2", &["i1", "i2"])]);
+        dashboard.select_next();
+        dashboard.toggle_focus();
+        dashboard.select_next();
+
+        // The selected inlet is deleted
+        dashboard.update(vec![node("n0", &[]), node("n1", &[]), node("n2", &["i1"])]);
+        assert_eq!(dashboard.selected_node().unwrap().name, "n2");
+        assert_eq!(dashboard.selected_inlet().unwrap().alias, "i1");
+
+        // The selected node is deleted
+        dashboard.update(vec![node("n0", &[])]);
+        assert_eq!(dashboard.selected_node().unwrap().name, "n0");
+        assert!(dashboard.selected_inlet().is_none());
+    }
+}
@@ -1,4 +1,5 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
 use std::process;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -10,14 +11,18 @@ use r3bl_rs_utils_core::UnicodeString;
 use r3bl_tui::{
     ColorWheel, ColorWheelConfig, ColorWheelSpeed, GradientGenerationPolicy, TextColorizationPolicy,
 };
+use serde::Serialize;
 use tokio::sync::Mutex;
 use tokio::try_join;
 use tracing::{error, info, instrument, warn};
 
+use ockam::identity::Identifier;
 use ockam::Context;
+use ockam_api::authenticator::direct::Members;
 use ockam_api::cli_state::journeys::{JourneyEvent, USER_EMAIL, USER_NAME};
 use ockam_api::cli_state::random_name;
 use ockam_api::cloud::enroll::auth0::*;
+use ockam_api::cloud::project::models::ProjectModel;
 use ockam_api::cloud::project::Project;
 use ockam_api::cloud::project::ProjectsOrchestratorApi;
 use ockam_api::cloud::space::{Space, Spaces};
@@ -34,6 +39,8 @@ use crate::enroll::OidcServiceExt;
 use crate::error::Error;
 use crate::operation::util::check_for_project_completion;
 use crate::project::util::check_project_readiness;
+use crate::project_member::{authority_client, create_member_attributes, get_project};
+use crate::shared_args::IdentityOpts;
 use crate::util::async_cmd;
 use crate::{docs, CommandGlobalOpts, Result};
 
@@ -63,6 +70,39 @@ pub struct EnrollCommand {
     #[arg(long)]
     pub authorization_code_flow: bool,
 
+    /// Use this flag on machines without a browser, such as remote servers. Instead of
+    /// opening a browser, this command prints a one-time code and a verification URL that
+    /// you can open from any other device. The enrollment completes once you have signed in
+    #[arg(long, conflicts_with = "authorization_code_flow")]
+    pub device_code: bool,
+
+    /// Path to the change history of an Identity created on another machine, for example
+    /// an air-gapped one, as printed by `ockam identity show --full --encoding hex`.
+    /// The Identity is imported without its private key and added as a member of your
+    /// default Project, using your own Identity, which must already be enrolled.
+    /// An enrollment bundle is then written out so that it can be transferred back
+    /// to the other machine
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["authorization_code_flow", "device_code", "force"]
+    )]
+    pub identity_file: Option<PathBuf>,
+
+    /// Path of the file where the enrollment bundle is written when using `--identity-file`.
+    /// If not set, the bundle is written to stdout
+    #[arg(long, value_name = "PATH", requires = "identity_file")]
+    pub bundle_file: Option<PathBuf>,
+
+    /// Attributes in `key=value` format to be attached to the Identity imported with
+    /// `--identity-file`. You can specify this option multiple times for multiple attributes
+    #[arg(
+        long = "attribute",
+        value_name = "ATTRIBUTE",
+        requires = "identity_file"
+    )]
+    pub attributes: Vec<String>,
+
     /// By default this command skips the enrollment process if the Identity you specified
     /// (using `--identity`), or the default Identity, is already enrolled, by checking
     /// its status. Use this flag to force the execution of the Identity enrollment
@@ -89,10 +129,13 @@ impl EnrollCommand {
     }
 
     async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
-        if opts.global_args.output_format()?.is_json() {
+        if let Some(identity_file) = &self.identity_file {
+            return self.enroll_identity_file(ctx, &opts, identity_file).await;
+        }
+        if !opts.global_args.output_format()?.is_plain() {
             return Err(miette::miette!(
             "This command is interactive and requires you to open a web browser to complete enrollment. \
-            Please try running it again without '--output json'."
+            Please try running it again without '--output'."
         ));
         }
         self.run_impl(ctx, opts.clone()).await?;
@@ -105,6 +148,7 @@ impl EnrollCommand {
     fields(
         enroller = ? self.identity, // https://docs.rs/tracing/latest/tracing/
         authorization_code_flow = % self.authorization_code_flow,
+        device_code = % self.device_code,
         force = % self.force,
         skip_orchestrator_resources_creation = % self.skip_orchestrator_resources_creation,
     ))]
@@ -271,6 +315,8 @@ impl EnrollCommand {
         let oidc_service = OidcService::default();
         let token = if self.authorization_code_flow {
             oidc_service.get_token_with_pkce().await.into_diagnostic()?
+        } else if self.device_code {
+            oidc_service.get_token_with_device_code(opts).await?
         } else {
             oidc_service.get_token_interactively(opts).await?
         };
@@ -295,6 +341,128 @@ impl EnrollCommand {
     }
 }
 
+impl EnrollCommand {
+    /// Import an Identity exported from another machine and add it as a member
+    /// of the default Project, then write out an enrollment bundle for that machine.
+    ///
+    /// The imported Identity has no private key on this machine, so it can't retrieve
+    /// its own credential here. Once the bundle is imported on the other machine,
+    /// with `ockam project import`, its nodes get their credentials from the Project authority.
+    #[instrument(skip_all, fields(identity_file = %identity_file.display()))]
+    async fn enroll_identity_file(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        identity_file: &PathBuf,
+    ) -> miette::Result<()> {
+        if opts
+            .state
+            .identity_should_enroll(&self.identity, false)
+            .await?
+        {
+            return Err(miette!(
+                "Your Identity must be enrolled before it can enroll other Identities. Please run {} first.",
+                color_primary("ockam enroll")
+            ));
+        }
+
+        let contents = std::fs::read(identity_file)
+            .into_diagnostic()
+            .wrap_err(format!(
+                "Unable to read the Identity file {}",
+                identity_file.display()
+            ))?;
+        // The change history can be hex-encoded, as printed by
+        // `ockam identity show --full --encoding hex`, or binary
+        let change_history = match std::str::from_utf8(&contents) {
+            Ok(text) if hex::decode(text.trim()).is_ok() => text.trim().to_string(),
+            _ => hex::encode(&contents),
+        };
+        let identity = opts
+            .state
+            .import_identity_change_history(&change_history)
+            .await
+            .wrap_err("The Identity file doesn't contain a valid Identity change history")?;
+        let identifier = identity.identifier().clone();
+
+        let identity_opts = IdentityOpts {
+            identity_name: self.identity.clone(),
+        };
+        let project = get_project(&opts.state, &None).await?;
+        let (authority_node_client, project_name) =
+            authority_client(ctx, opts, &identity_opts, &None).await?;
+        let attributes = create_member_attributes(&self.attributes, &None, false)?;
+        authority_node_client
+            .add_member(ctx, identifier.clone(), attributes.clone())
+            .await
+            .wrap_err(format!(
+                "Failed to add the Identity {} to the Project {}",
+                identifier, project_name
+            ))?;
+
+        let bundle = EnrollmentBundle {
+            member_identifier: identifier.clone(),
+            member_attributes: attributes,
+            project: project.model().clone(),
+        };
+        let bundle_json = serde_json::to_string_pretty(&bundle).into_diagnostic()?;
+        match &self.bundle_file {
+            Some(bundle_file) => {
+                std::fs::write(bundle_file, &bundle_json)
+                    .into_diagnostic()
+                    .wrap_err(format!(
+                        "Unable to write the enrollment bundle to {}",
+                        bundle_file.display()
+                    ))?;
+                opts.terminal
+                    .clone()
+                    .stdout()
+                    .plain(
+                        fmt_ok!(
+                            "Identifier {} is now a member of the Project {}\n",
+                            color_primary(identifier.to_string()),
+                            color_primary(&project_name)
+                        ) + &fmt_log!(
+                            "The enrollment bundle was written to {}. Copy it to the other machine and run {}",
+                            color_primary(bundle_file.display().to_string()),
+                            color_primary(format!(
+                                "ockam project import --project-file {}",
+                                bundle_file.display()
+                            ))
+                        ),
+                    )
+                    .json(&bundle_json)
+                    .write_line()?;
+            }
+            None => {
+                opts.terminal.write_line(&fmt_ok!(
+                    "Identifier {} is now a member of the Project {}",
+                    color_primary(identifier.to_string()),
+                    color_primary(&project_name)
+                ))?;
+                opts.terminal
+                    .clone()
+                    .stdout()
+                    .plain(&bundle_json)
+                    .json(&bundle_json)
+                    .write_line()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The bundle written out for an Identity enrolled with `--identity-file`.
+/// It is a Project file, importable with `ockam project import`, which also records
+/// the Identifier of the enrolled member and its attributes.
+#[derive(Serialize)]
+struct EnrollmentBundle {
+    member_identifier: Identifier,
+    member_attributes: BTreeMap<String, String>,
+    #[serde(flatten)]
+    project: ProjectModel,
+}
+
 fn display_header(opts: &CommandGlobalOpts) {
     let ockam_header = include_str!("../../static/ockam_ascii.txt").trim();
     let gradient_steps = Vec::from(
@@ -23,6 +23,10 @@ pub trait OidcServiceExt {
     /// Retrieve a token by having the user copy and paste a device code in their browser
     async fn get_token_interactively(&self, opts: &CommandGlobalOpts) -> Result<OidcToken>;
 
+    /// Retrieve a token by printing a device code and its verification URL, without
+    /// ever trying to open a browser on this machine
+    async fn get_token_with_device_code(&self, opts: &CommandGlobalOpts) -> Result<OidcToken>;
+
     /// Retrieve a token using the device code get a token from the OIDC service
     async fn get_token(&self, opts: &CommandGlobalOpts) -> Result<OidcToken>;
 
@@ -132,6 +136,36 @@ impl OidcServiceExt for OidcService {
         self.get_token_from_browser(opts, device_code, uri).await
     }
 
+    #[instrument(skip_all)]
+    async fn get_token_with_device_code(&self, opts: &CommandGlobalOpts) -> Result<OidcToken> {
+        let device_code = self.device_code().await?;
+
+        // If the terminal is quiet, write only the code at stdout so it can be processed
+        if opts.terminal.is_quiet() {
+            opts.terminal
+                .clone()
+                .stdout()
+                .plain(device_code.user_code.to_string())
+                .write_line()?;
+        } else {
+            opts.terminal.write_line(&fmt_log!(
+                "To activate this machine, open the following URL on any device with a browser:\n"
+            ))?;
+            opts.terminal
+                .write_line(&fmt_log!("{}\n", color_uri(&device_code.verification_uri)))?;
+            opts.terminal.write_line(&fmt_log!(
+                "And enter the one-time code: {}\n",
+                format!(" {} ", device_code.user_code).bg_white().black()
+            ))?;
+            opts.terminal.write_line(&fmt_log!(
+                "You can also open {} to skip entering the code.\n",
+                color_uri(&device_code.verification_uri_complete)
+            ))?;
+        }
+
+        self.poll_token(device_code, opts).await
+    }
+
     async fn get_token(&self, opts: &CommandGlobalOpts) -> Result<OidcToken> {
         let dc = self.device_code().await?;
         let uri = dc.verification_uri_complete.to_string();
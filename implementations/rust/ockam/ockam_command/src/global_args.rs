@@ -1,6 +1,6 @@
 use clap::Args;
 use clap::{ArgAction, ValueEnum};
-use ockam_api::output::OutputFormat;
+use ockam_api::output::{ListOptions, OutputFormat};
 
 use ockam_core::env::get_env_with_default;
 
@@ -18,8 +18,10 @@ pub struct GlobalArgs {
     )]
     help: Option<bool>,
 
-    /// Do not print any log messages to stderr and disable confirmation prompts.
-    /// This is useful for scripting and automation, where you don't want the process to block on stdin.
+    /// Do not print any log messages, spinners or progress messages to stderr and disable confirmation prompts.
+    /// Only the final result of the command is printed to stdout.
+    /// This is useful for scripting and automation, for example in cron jobs or CI pipelines,
+    /// where you don't want the process to block on stdin. It can also be set with the `OCKAM_QUIET` environment variable.
     #[arg(global = true, long, short, default_value_t = quiet_default_value())]
     pub quiet: bool,
 
@@ -48,6 +50,7 @@ pub struct GlobalArgs {
     /// is usually an identifier that can be used as input for other commands. If stdout is a tty,
     /// the output will contain human-readable information about the command execution.
     /// The 'json' format can be customized with the `--jq` and `--pretty` options.
+    /// The 'yaml' format contains the same data as the 'json' format.
     #[arg(global = true, long = "output", value_enum)]
     pub output_format: Option<OutputFormatArg>,
 
@@ -59,6 +62,15 @@ pub struct GlobalArgs {
     #[arg(global = true, long)]
     pub pretty: bool,
 
+    /// Comma-separated list of the fields to display for each item of a list, as a table.
+    /// Nested fields are separated by a dot, e.g. `status.pid`
+    #[arg(global = true, long, value_name = "FIELDS", value_delimiter = ',')]
+    pub fields: Vec<String>,
+
+    /// Field used to sort the items of a list
+    #[arg(global = true, long, value_name = "FIELD")]
+    pub sort_by: Option<String>,
+
     // if test_argument_parser is true, command arguments are checked
     // but the command is not executed.
     #[arg(global = true, long, hide = true)]
@@ -66,7 +78,8 @@ pub struct GlobalArgs {
 }
 
 fn quiet_default_value() -> bool {
-    get_env_with_default("QUIET", false).unwrap_or(false)
+    get_env_with_default("OCKAM_QUIET", false).unwrap_or(false)
+        || get_env_with_default("QUIET", false).unwrap_or(false)
 }
 
 fn no_color_default_value() -> bool {
@@ -88,6 +101,8 @@ impl Default for GlobalArgs {
             output_format: None,
             jq_query: None,
             pretty: false,
+            fields: vec![],
+            sort_by: None,
             test_argument_parser: false,
         }
     }
@@ -100,6 +115,10 @@ impl GlobalArgs {
         clone
     }
 
+    pub fn list_options(&self) -> ListOptions {
+        ListOptions::new(self.fields.clone(), self.sort_by.clone())
+    }
+
     pub fn output_format(&self) -> miette::Result<OutputFormat> {
         match (&self.jq_query, &self.output_format) {
             (None, Some(OutputFormatArg::Plain)) | (None, None) => Ok(OutputFormat::Plain),
@@ -107,9 +126,13 @@ impl GlobalArgs {
                 pretty: self.pretty,
                 jq_query: None,
             }),
+            (None, Some(OutputFormatArg::Yaml)) => Ok(OutputFormat::Yaml),
             (Some(_), Some(OutputFormatArg::Plain)) => {
                 Err(miette::miette!("Cannot use --jq with --output plain"))
             }
+            (Some(_), Some(OutputFormatArg::Yaml)) => {
+                Err(miette::miette!("Cannot use --jq with --output yaml"))
+            }
             (Some(_), Some(OutputFormatArg::Json)) | (Some(_), None) => Ok(OutputFormat::Json {
                 pretty: self.pretty,
                 jq_query: self.jq_query.clone(),
@@ -122,4 +145,5 @@ impl GlobalArgs {
 pub enum OutputFormatArg {
     Plain,
     Json,
+    Yaml,
 }
@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+
+use ockam::Context;
+use ockam_api::fmt_ok;
+
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/clear/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/clear/after_long_help.txt");
+
+/// Delete all the recorded commands from the history
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ClearCommand {
+    /// Confirm the deletion without prompting
+    #[arg(display_order = 901, long, short)]
+    yes: bool,
+}
+
+#[async_trait]
+impl Command for ClearCommand {
+    const NAME: &'static str = "history clear";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        if opts.terminal.confirmed_with_flag_or_prompt(
+            self.yes,
+            "Are you sure you want to delete the history of the commands?",
+        )? {
+            opts.state.clear_command_history().await?;
+            opts.terminal
+                .stdout()
+                .plain(fmt_ok!("The history of the commands has been deleted"))
+                .write_line()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use std::fmt::Write;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+use serde::Serialize;
+
+use ockam::Context;
+use ockam_api::cli_state::CommandExecution;
+use ockam_api::colors::color_primary;
+use ockam_api::output::Output;
+
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/list/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
+
+/// List the commands which modified the Ockam configuration
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ListCommand {
+    /// Only list this number of the most recent commands
+    #[arg(long, value_name = "COUNT")]
+    pub limit: Option<u64>,
+}
+
+#[async_trait]
+impl Command for ListCommand {
+    const NAME: &'static str = "history list";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let executions: Vec<CommandExecutionOutput> = opts
+            .state
+            .get_command_history(self.limit)
+            .await?
+            .into_iter()
+            .map(CommandExecutionOutput::from)
+            .collect();
+
+        let plain = opts
+            .terminal
+            .build_list(&executions, "No commands have been recorded")?;
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(serde_json::to_string(&executions).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct CommandExecutionOutput {
+    command_name: String,
+    arguments: String,
+    user_name: String,
+    executed_at: String,
+    error: Option<String>,
+}
+
+impl From<CommandExecution> for CommandExecutionOutput {
+    fn from(execution: CommandExecution) -> Self {
+        Self {
+            command_name: execution.command_name(),
+            arguments: execution.arguments(),
+            user_name: execution.user_name(),
+            executed_at: execution.executed_at().to_rfc3339(),
+            error: execution.error(),
+        }
+    }
+}
+
+impl Output for CommandExecutionOutput {
+    fn item(&self) -> ockam_api::Result<String> {
+        let mut w = String::new();
+        write!(w, "Command {}", color_primary(&self.command_name))?;
+        write!(w, "\n  Arguments: {}", self.arguments)?;
+        write!(w, "\n  User: {}", self.user_name)?;
+        write!(w, "\n  Executed at: {}", self.executed_at)?;
+        match &self.error {
+            Some(error) => write!(w, "\n  Result: {}", format!("failed, {error}").light_red())?,
+            None => write!(w, "\n  Result: {}", "succeeded".light_green())?,
+        }
+        Ok(w)
+    }
+
+    fn as_list_item(&self) -> ockam_api::Result<String> {
+        let result = match &self.error {
+            Some(_) => "✕".light_red(),
+            None => "✔︎".light_green(),
+        };
+        Ok(format!(
+            "{result} {} {} {}",
+            self.executed_at.clone().light_gray(),
+            color_primary(&self.user_name),
+            self.arguments
+        ))
+    }
+
+    fn as_fields(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(self).ok()
+    }
+}
@@ -0,0 +1,47 @@
+use clap::{Args, Subcommand};
+
+pub use clear::ClearCommand;
+pub use list::ListCommand;
+
+use crate::{docs, Command, CommandGlobalOpts};
+
+mod clear;
+mod list;
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+
+/// Inspect the history of the commands which modified the Ockam configuration
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    long_about = docs::about(LONG_ABOUT),
+)]
+pub struct HistoryCommand {
+    #[command(subcommand)]
+    subcommand: HistorySubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum HistorySubcommand {
+    #[command(display_order = 800)]
+    List(ListCommand),
+    #[command(display_order = 800)]
+    Clear(ClearCommand),
+}
+
+impl HistoryCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        match self.subcommand {
+            HistorySubcommand::List(c) => c.run(opts),
+            HistorySubcommand::Clear(c) => c.run(opts),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match &self.subcommand {
+            HistorySubcommand::List(c) => c.name(),
+            HistorySubcommand::Clear(c) => c.name(),
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use async_trait::async_trait;
+use std::fmt::Write;
+use std::str::FromStr;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::miette;
+use serde::Serialize;
+
+use ockam::Context;
+use ockam_abac::PolicyExpression;
+use ockam_api::colors::color_primary;
+use ockam_api::http_gateway::HttpRoute;
+use ockam_api::nodes::models::services::{StartHttpGatewayRequest, StartServiceRequest};
+use ockam_api::nodes::service::default_address::DefaultAddress;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_api::output::Output;
+use ockam_api::{fmt_log, fmt_ok};
+use ockam_core::api::Request;
+
+use crate::http_gateway::http_gateway_default_addr;
+use crate::node::util::initialize_default_node;
+use crate::node::NodeOpts;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
+
+/// Create an HTTP Gateway
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct CreateCommand {
+    #[command(flatten)]
+    pub node_opts: NodeOpts,
+
+    /// The local address of the service
+    #[arg(long, default_value_t = http_gateway_default_addr())]
+    pub addr: String,
+
+    /// A route of the gateway, as `[HOST]/PATH=HOST:PORT`: the requests for this host,
+    /// with a path starting with this path, are sent to the target `HOST:PORT`.
+    /// Without host, the route matches the requests for any host
+    #[arg(long = "route", value_name = "ROUTE", required = true, value_parser = route_parser)]
+    pub routes: Vec<HttpRoute>,
+
+    /// The policy of a route, as `[HOST]/PATH=EXPRESSION`, evaluated for each request with
+    /// the attributes of the identity of the client.
+    /// If you don't provide it, the policy set for the "tcp-outlet" resource type is used
+    #[arg(long = "route-allow", value_name = "ROUTE_POLICY", value_parser = route_policy_parser)]
+    pub route_policies: Vec<RoutePolicy>,
+
+    /// Policy expression that will be used for access control to the HTTP Gateway.
+    /// If you don't provide it, the policy set for the "tcp-outlet" resource type will be used.
+    ///
+    /// You can check the fallback policy with `ockam policy show --resource-type tcp-outlet`.
+    #[arg(hide = true, long = "allow", id = "EXPRESSION")]
+    pub policy_expression: Option<PolicyExpression>,
+}
+
+#[async_trait]
+impl Command for CreateCommand {
+    const NAME: &'static str = "http-gateway create";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        initialize_default_node(ctx, &opts).await?;
+        let routes = self.routes_with_policies()?;
+
+        let gateway = {
+            let pb = opts.terminal.progress_bar();
+            if let Some(pb) = pb.as_ref() {
+                pb.set_message(format!(
+                    "Creating HTTP Gateway at {}...\n",
+                    color_primary(&self.addr)
+                ));
+            }
+
+            let payload = StartHttpGatewayRequest::new(routes.clone(), self.policy_expression);
+            let payload = StartServiceRequest::new(payload, &self.addr);
+            let req = Request::post(format!("/node/services/{}", DefaultAddress::HTTP_GATEWAY))
+                .body(payload);
+            let node =
+                BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
+            node.tell(ctx, req)
+                .await
+                .map_err(|e| miette!("Failed to start HTTP Gateway: {e}"))?;
+
+            HttpGatewayOutput {
+                node_name: node.node_name(),
+                address: self.addr.clone(),
+                routes: routes.iter().map(|route| route.to_string()).collect(),
+            }
+        };
+
+        opts.terminal
+            .stdout()
+            .plain(gateway.item()?)
+            .json_obj(gateway)?
+            .write_line()?;
+
+        Ok(())
+    }
+}
+
+impl CreateCommand {
+    /// Set the policy of each route given with `--route-allow`
+    fn routes_with_policies(&self) -> crate::Result<Vec<HttpRoute>> {
+        let mut routes = self.routes.clone();
+        for policy in &self.route_policies {
+            let route = routes
+                .iter_mut()
+                .find(|route| {
+                    route.host() == policy.host.as_deref() && route.path_prefix() == policy.path
+                })
+                .ok_or_else(|| miette!("There is no route for the policy of {}", policy.spec))?;
+            *route = route
+                .clone()
+                .with_policy_expression(policy.expression.clone());
+        }
+        Ok(routes)
+    }
+}
+
+/// The policy of the route with a host and a path
+#[derive(Clone, Debug)]
+pub struct RoutePolicy {
+    spec: String,
+    host: Option<String>,
+    path: String,
+    expression: PolicyExpression,
+}
+
+/// Split `[HOST]/PATH=VALUE` into its host, path and value
+fn split_route_spec(input: &str) -> crate::Result<(Option<String>, String, &str)> {
+    let (spec, value) = input
+        .split_once('=')
+        .ok_or_else(|| miette!("Expected [HOST]/PATH=VALUE, got {input}"))?;
+    let path_start = spec
+        .find('/')
+        .ok_or_else(|| miette!("The path of the route {spec} must start with /"))?;
+    let (host, path) = spec.split_at(path_start);
+    let host = (!host.is_empty()).then(|| host.to_string());
+    Ok((host, path.to_string(), value))
+}
+
+fn route_parser(input: &str) -> crate::Result<HttpRoute> {
+    let (host, path, target) = split_route_spec(input)?;
+    let mut route = HttpRoute::new(path, target);
+    if let Some(host) = host {
+        route = route.with_host(host);
+    }
+    Ok(route)
+}
+
+fn route_policy_parser(input: &str) -> crate::Result<RoutePolicy> {
+    let (host, path, expression) = split_route_spec(input)?;
+    Ok(RoutePolicy {
+        spec: format!("{}{path}", host.as_deref().unwrap_or_default()),
+        host,
+        path,
+        expression: PolicyExpression::from_str(expression)
+            .map_err(|e| miette!("Invalid policy for the route {input}: {e}"))?,
+    })
+}
+
+#[derive(Serialize)]
+struct HttpGatewayOutput {
+    node_name: String,
+    address: String,
+    routes: Vec<String>,
+}
+
+impl Output for HttpGatewayOutput {
+    fn item(&self) -> ockam_api::Result<String> {
+        let mut f = String::new();
+        writeln!(
+            f,
+            "{}",
+            fmt_ok!(
+                "Created a new HTTP Gateway at {} in the Node {}",
+                color_primary(&self.address),
+                color_primary(&self.node_name)
+            ),
+        )?;
+        for route in &self.routes {
+            writeln!(f, "{}", fmt_log!("routing {}", color_primary(route)))?;
+        }
+        Ok(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_routes() {
+        let route = route_parser("/api=127.0.0.1:8080").unwrap();
+        assert_eq!(route.host(), None);
+        assert_eq!(route.path_prefix(), "/api");
+        assert_eq!(route.target(), "127.0.0.1:8080");
+
+        let route = route_parser("docs.example.com/=localhost:3000").unwrap();
+        assert_eq!(route.host(), Some("docs.example.com"));
+        assert_eq!(route.path_prefix(), "/");
+        assert_eq!(route.target(), "localhost:3000");
+
+        assert!(route_parser("/api").is_err());
+        assert!(route_parser("api=127.0.0.1:8080").is_err());
+
+        let policy = route_policy_parser("/admin=(= subject.role \"admin\")").unwrap();
+        assert_eq!(policy.spec, "/admin");
+        assert_eq!(policy.host, None);
+        assert_eq!(policy.path, "/admin");
+    }
+}
@@ -0,0 +1,57 @@
+use clap::{Args, Subcommand};
+
+use create::CreateCommand;
+use delete::DeleteCommand;
+use list::ListCommand;
+use ockam_api::nodes::service::default_address::DefaultAddress;
+
+use crate::{docs, Command, CommandGlobalOpts};
+
+mod create;
+mod delete;
+mod list;
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
+
+/// Manage HTTP Gateways
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct HttpGatewayCommand {
+    #[command(subcommand)]
+    pub subcommand: HttpGatewaySubCommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum HttpGatewaySubCommand {
+    Create(CreateCommand),
+    Delete(DeleteCommand),
+    List(ListCommand),
+}
+
+impl HttpGatewayCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        match self.subcommand {
+            HttpGatewaySubCommand::Create(c) => c.run(opts),
+            HttpGatewaySubCommand::Delete(c) => c.run(opts),
+            HttpGatewaySubCommand::List(c) => c.run(opts),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match &self.subcommand {
+            HttpGatewaySubCommand::Create(c) => c.name(),
+            HttpGatewaySubCommand::Delete(c) => c.name(),
+            HttpGatewaySubCommand::List(c) => c.name(),
+        }
+    }
+}
+
+fn http_gateway_default_addr() -> String {
+    DefaultAddress::HTTP_GATEWAY.to_string()
+}
@@ -0,0 +1,75 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::{IntoDiagnostic, WrapErr};
+
+use ockam::Context;
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+
+use crate::{docs, Command, CommandGlobalOpts, Result};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/export/after_long_help.txt");
+
+/// Export an identity, so that it can be imported on another machine.
+/// Only public data is exported, never the private keys of the identity
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct ExportCommand {
+    /// Name of the identity to export.
+    /// If not provided, the default identity is exported.
+    name: Option<String>,
+
+    /// Export the complete signed change history of the identity, hex-encoded.
+    /// Otherwise only the identifier of the identity is exported
+    #[arg(long)]
+    full_history: bool,
+
+    /// Write the export to a file instead of the standard output
+    #[arg(long, short, value_name = "FILE")]
+    output: Option<PathBuf>,
+}
+
+#[async_trait]
+impl Command for ExportCommand {
+    const NAME: &'static str = "identity export";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        let identity = opts.state.get_named_identity_or_default(&self.name).await?;
+        let exported = if self.full_history {
+            opts.state
+                .export_identity_change_history(&identity.name())
+                .await?
+        } else {
+            identity.identifier().to_string()
+        };
+
+        match &self.output {
+            Some(path) => {
+                std::fs::write(path, &exported)
+                    .into_diagnostic()
+                    .wrap_err(format!("Unable to write the file {}", path.display()))?;
+                opts.terminal
+                    .stdout()
+                    .plain(fmt_ok!(
+                        "The identity {} has been exported to {}",
+                        color_primary(identity.name()),
+                        color_primary(path.display().to_string())
+                    ))
+                    .machine(path.display())
+                    .write_line()?;
+            }
+            None => {
+                opts.terminal
+                    .stdout()
+                    .plain(&exported)
+                    .machine(&exported)
+                    .json(serde_json::json!({ "export": exported }))
+                    .write_line()?;
+            }
+        }
+        Ok(())
+    }
+}
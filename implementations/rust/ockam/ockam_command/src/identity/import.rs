@@ -0,0 +1,89 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::{IntoDiagnostic, WrapErr};
+use serde::Serialize;
+
+use ockam::Context;
+use ockam_api::colors::color_primary;
+use ockam_api::{fmt_log, fmt_ok};
+
+use crate::{docs, Command, CommandGlobalOpts, Result};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/import/after_long_help.txt");
+
+/// Import the change history of an identity created on another machine.
+/// Every change of the history is verified before the identity is stored
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct ImportCommand {
+    /// The hex-encoded change history, as exported by `ockam identity export --full-history`,
+    /// or the path to a file containing it
+    #[arg(value_name = "CHANGE_HISTORY_OR_FILE")]
+    change_history: String,
+}
+
+#[async_trait]
+impl Command for ImportCommand {
+    const NAME: &'static str = "identity import";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        let change_history = self.read_change_history()?;
+        let identity = opts
+            .state
+            .import_identity_change_history(&change_history)
+            .await
+            .wrap_err(
+                "The change history is invalid, or conflicts with the change history \
+                already known for that identity",
+            )?;
+
+        let output = ImportOutput {
+            identifier: identity.identifier().to_string(),
+            changes: identity.changes().len(),
+        };
+        opts.terminal
+            .stdout()
+            .plain(format!(
+                "{}\n{}",
+                fmt_ok!(
+                    "The identity {} has been imported",
+                    color_primary(&output.identifier)
+                ),
+                fmt_log!(
+                    "{} verified change(s)",
+                    color_primary(output.changes.to_string())
+                )
+            ))
+            .machine(&output.identifier)
+            .json(serde_json::to_string(&output).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+impl ImportCommand {
+    /// Return the hex-encoded change history given as an argument or contained in a file.
+    /// The file can contain either the hex-encoded or the binary change history
+    fn read_change_history(&self) -> Result<String> {
+        let path = Path::new(&self.change_history);
+        if !path.is_file() {
+            return Ok(self.change_history.trim().to_string());
+        }
+        let contents = std::fs::read(path)
+            .into_diagnostic()
+            .wrap_err(format!("Unable to read the file {}", path.display()))?;
+        Ok(match std::str::from_utf8(&contents) {
+            Ok(text) if hex::decode(text.trim()).is_ok() => text.trim().to_string(),
+            _ => hex::encode(&contents),
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct ImportOutput {
+    identifier: String,
+    changes: usize,
+}
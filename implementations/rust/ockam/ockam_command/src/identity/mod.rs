@@ -6,12 +6,20 @@ pub(crate) use list::ListCommand;
 pub(crate) use show::ShowCommand;
 
 use crate::identity::default::DefaultCommand;
+use crate::identity::export::ExportCommand;
+use crate::identity::import::ImportCommand;
+use crate::identity::purpose_key::PurposeKeyCommand;
+use crate::identity::rotate::RotateCommand;
 use crate::{docs, Command, CommandGlobalOpts};
 
 mod create;
 mod default;
 mod delete;
+mod export;
+mod import;
 mod list;
+mod purpose_key;
+mod rotate;
 mod show;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
@@ -35,6 +43,10 @@ pub enum IdentitySubcommand {
     List(ListCommand),
     Default(DefaultCommand),
     Delete(DeleteCommand),
+    Rotate(RotateCommand),
+    Export(ExportCommand),
+    Import(ImportCommand),
+    PurposeKey(PurposeKeyCommand),
 }
 
 impl IdentityCommand {
@@ -45,6 +57,10 @@ impl IdentityCommand {
             IdentitySubcommand::List(c) => c.run(opts),
             IdentitySubcommand::Delete(c) => c.run(opts),
             IdentitySubcommand::Default(c) => c.run(opts),
+            IdentitySubcommand::Rotate(c) => c.run(opts),
+            IdentitySubcommand::Export(c) => c.run(opts),
+            IdentitySubcommand::Import(c) => c.run(opts),
+            IdentitySubcommand::PurposeKey(c) => c.run(opts),
         }
     }
 
@@ -55,6 +71,10 @@ impl IdentityCommand {
             IdentitySubcommand::List(c) => c.name(),
             IdentitySubcommand::Delete(c) => c.name(),
             IdentitySubcommand::Default(c) => c.name(),
+            IdentitySubcommand::Rotate(c) => c.name(),
+            IdentitySubcommand::Export(c) => c.name(),
+            IdentitySubcommand::Import(c) => c.name(),
+            IdentitySubcommand::PurposeKey(c) => c.name(),
         }
         .to_string()
     }
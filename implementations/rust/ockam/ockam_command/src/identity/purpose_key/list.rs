@@ -0,0 +1,109 @@
+use std::fmt::Write;
+
+use async_trait::async_trait;
+use clap::Args;
+use serde::Serialize;
+
+use ockam::identity::models::{CredentialVerifyingKey, PurposePublicKey};
+use ockam::identity::PurposeKeyInfo;
+use ockam::Context;
+use ockam_api::colors::{color_primary, color_warn};
+use ockam_api::output::Output;
+
+use crate::identity::purpose_key::purpose_name;
+use crate::{docs, Command, CommandGlobalOpts, Result};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
+
+/// List the purpose keys of an identity, including the rotated keys which are still in
+/// their grace period
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct ListCommand {
+    /// Name of the identity.
+    /// If not provided, the default identity is used.
+    name: Option<String>,
+}
+
+#[async_trait]
+impl Command for ListCommand {
+    const NAME: &'static str = "identity purpose-key list";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        let identity = opts.state.get_named_identity_or_default(&self.name).await?;
+        let purpose_keys: Vec<PurposeKeyOutput> = opts
+            .state
+            .list_purpose_keys(&identity.name())
+            .await?
+            .into_iter()
+            .map(PurposeKeyOutput::from)
+            .collect();
+
+        let list = opts.terminal.build_list(
+            &purpose_keys,
+            &format!(
+                "The identity {} has no purpose keys",
+                color_primary(identity.name())
+            ),
+        )?;
+        opts.terminal
+            .stdout()
+            .plain(list)
+            .json_obj(&purpose_keys)?
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct PurposeKeyOutput {
+    pub(crate) purpose: String,
+    pub(crate) public_key: String,
+    pub(crate) created_at: u64,
+    pub(crate) expires_at: u64,
+    pub(crate) retired_until: Option<u64>,
+}
+
+impl From<PurposeKeyInfo> for PurposeKeyOutput {
+    fn from(purpose_key: PurposeKeyInfo) -> Self {
+        let public_key = match purpose_key.public_key {
+            PurposePublicKey::SecureChannelStatic(public_key) => hex::encode(public_key.0),
+            PurposePublicKey::CredentialSigning(CredentialVerifyingKey::EdDSACurve25519(
+                public_key,
+            )) => hex::encode(public_key.0),
+            PurposePublicKey::CredentialSigning(CredentialVerifyingKey::ECDSASHA256CurveP256(
+                public_key,
+            )) => hex::encode(public_key.0),
+        };
+        Self {
+            purpose: purpose_name(purpose_key.purpose).to_string(),
+            public_key,
+            created_at: purpose_key.created_at.0,
+            expires_at: purpose_key.expires_at.0,
+            retired_until: purpose_key.retired_until.map(|t| t.0),
+        }
+    }
+}
+
+impl Output for PurposeKeyOutput {
+    fn item(&self) -> ockam_api::Result<String> {
+        let mut output = String::new();
+        writeln!(
+            output,
+            "Purpose key {} {}",
+            color_primary(&self.purpose),
+            match self.retired_until {
+                Some(retired_until) => color_warn(format!("(retired until {retired_until})")),
+                None => color_primary("(active)"),
+            }
+        )?;
+        writeln!(output, "Public key: {}", color_primary(&self.public_key))?;
+        write!(
+            output,
+            "Created at: {}, expires at: {}",
+            color_primary(self.created_at.to_string()),
+            color_primary(self.expires_at.to_string())
+        )?;
+        Ok(output)
+    }
+}
@@ -0,0 +1,68 @@
+use clap::{Args, Subcommand, ValueEnum};
+
+use ockam::identity::Purpose;
+
+pub(crate) use list::ListCommand;
+pub(crate) use rotate::RotateCommand;
+
+use crate::{Command, CommandGlobalOpts};
+
+mod list;
+mod rotate;
+
+/// Manage the purpose keys of an identity
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, subcommand_required = true)]
+pub struct PurposeKeyCommand {
+    #[command(subcommand)]
+    pub subcommand: PurposeKeySubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum PurposeKeySubcommand {
+    List(ListCommand),
+    Rotate(RotateCommand),
+}
+
+impl PurposeKeyCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        match self.subcommand {
+            PurposeKeySubcommand::List(c) => c.run(opts),
+            PurposeKeySubcommand::Rotate(c) => c.run(opts),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match &self.subcommand {
+            PurposeKeySubcommand::List(c) => c.name(),
+            PurposeKeySubcommand::Rotate(c) => c.name(),
+        }
+        .to_string()
+    }
+}
+
+/// Purpose of a key, as a command argument
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum PurposeArg {
+    /// Key used as the static key of the secure channels
+    SecureChannel,
+    /// Key used to sign credentials
+    Credentials,
+}
+
+impl From<PurposeArg> for Purpose {
+    fn from(purpose: PurposeArg) -> Self {
+        match purpose {
+            PurposeArg::SecureChannel => Purpose::SecureChannel,
+            PurposeArg::Credentials => Purpose::Credentials,
+        }
+    }
+}
+
+/// Return a user-friendly name for a purpose
+pub(crate) fn purpose_name(purpose: Purpose) -> &'static str {
+    match purpose {
+        Purpose::SecureChannel => "secure-channel",
+        Purpose::Credentials => "credentials",
+    }
+}
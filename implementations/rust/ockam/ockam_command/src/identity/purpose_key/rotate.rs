@@ -0,0 +1,122 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+use serde::Serialize;
+
+use ockam::Context;
+use ockam_api::colors::color_primary;
+use ockam_api::{fmt_log, fmt_ok, fmt_warn};
+
+use crate::identity::purpose_key::list::PurposeKeyOutput;
+use crate::identity::purpose_key::PurposeArg;
+use crate::util::parsers::duration_parser;
+use crate::{docs, Command, CommandGlobalOpts, Result};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/rotate/after_long_help.txt");
+
+/// Rotate a purpose key of an identity. The previous key stays valid during a grace period
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct RotateCommand {
+    /// Name of the identity.
+    /// If not provided, the default identity is used.
+    name: Option<String>,
+
+    /// Purpose of the key to rotate
+    #[arg(long, value_enum, default_value = "secure-channel")]
+    purpose: PurposeArg,
+
+    /// Duration during which the previous key stays valid, for example "1h" or "30m"
+    #[arg(long, value_name = "DURATION", default_value = "1h", value_parser = duration_parser)]
+    grace_period: Duration,
+}
+
+#[async_trait]
+impl Command for RotateCommand {
+    const NAME: &'static str = "identity purpose-key rotate";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        let identity = opts.state.get_named_identity_or_default(&self.name).await?;
+        let (purpose_key, nodes) = opts
+            .state
+            .rotate_purpose_key(&identity.name(), self.purpose.into(), self.grace_period)
+            .await?;
+
+        let output = RotateOutput {
+            identity: identity.name(),
+            purpose_key: purpose_key.into(),
+            grace_period_secs: self.grace_period.as_secs(),
+            running_nodes: nodes
+                .into_iter()
+                .filter(|n| n.is_running())
+                .map(|n| n.name())
+                .collect(),
+        };
+        opts.terminal
+            .stdout()
+            .plain(output.plain()?)
+            .machine(&output.purpose_key.public_key)
+            .json(serde_json::to_string(&output).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct RotateOutput {
+    identity: String,
+    purpose_key: PurposeKeyOutput,
+    grace_period_secs: u64,
+    running_nodes: Vec<String>,
+}
+
+impl RotateOutput {
+    fn plain(&self) -> Result<String> {
+        let mut buf = String::new();
+        writeln!(
+            buf,
+            "{}",
+            fmt_ok!(
+                "The {} purpose key of the identity {} has been rotated",
+                color_primary(&self.purpose_key.purpose),
+                color_primary(&self.identity)
+            )
+        )?;
+        writeln!(
+            buf,
+            "{}",
+            fmt_log!(
+                "Public key: {}",
+                color_primary(&self.purpose_key.public_key)
+            )
+        )?;
+        writeln!(
+            buf,
+            "{}",
+            fmt_log!(
+                "The previous key stays valid for {} seconds",
+                color_primary(self.grace_period_secs.to_string())
+            )
+        )?;
+        if !self.running_nodes.is_empty() {
+            writeln!(
+                buf,
+                "{}",
+                fmt_warn!(
+                    "The running nodes {} still use the previous key. Restart them with {}",
+                    self.running_nodes
+                        .iter()
+                        .map(|n| color_primary(n).to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    color_primary("ockam node restart")
+                )
+            )?;
+        }
+        Ok(buf)
+    }
+}
@@ -0,0 +1,124 @@
+use std::fmt::Write;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+use serde::Serialize;
+
+use ockam_api::colors::color_primary;
+use ockam_api::{fmt_log, fmt_ok, fmt_warn};
+use ockam_node::Context;
+
+use crate::node::restart::restart_node;
+use crate::{docs, Command, CommandGlobalOpts, Result};
+
+const LONG_ABOUT: &str = include_str!("./static/rotate/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/rotate/after_long_help.txt");
+
+/// Rotate the key of an identity
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct RotateCommand {
+    /// Name of the identity to rotate.
+    /// If not provided, the default identity is rotated.
+    name: Option<String>,
+
+    /// Restart the running nodes using this identity so that they use the new key
+    #[arg(long)]
+    all_nodes_restart: bool,
+}
+
+#[async_trait]
+impl Command for RotateCommand {
+    const NAME: &'static str = "identity rotate";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        let identity = opts.state.get_named_identity_or_default(&self.name).await?;
+        let nodes = opts.state.rotate_identity_key(&identity.name()).await?;
+        let running_nodes: Vec<_> = nodes.into_iter().filter(|n| n.is_running()).collect();
+
+        let mut restarted_nodes = vec![];
+        if self.all_nodes_restart {
+            for node in &running_nodes {
+                let restarted = restart_node(ctx, opts.clone(), node, false).await?;
+                restarted_nodes.push(restarted.node_name);
+            }
+        }
+
+        let output = RotateOutput {
+            identity: identity.name(),
+            identifier: identity.identifier().to_string(),
+            running_nodes: running_nodes.iter().map(|n| n.name()).collect(),
+            restarted_nodes,
+        };
+        opts.terminal
+            .stdout()
+            .plain(output.plain()?)
+            .machine(&output.identifier)
+            .json(serde_json::to_string(&output).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct RotateOutput {
+    identity: String,
+    identifier: String,
+    running_nodes: Vec<String>,
+    restarted_nodes: Vec<String>,
+}
+
+impl RotateOutput {
+    fn plain(&self) -> Result<String> {
+        let mut buf = String::new();
+        writeln!(
+            buf,
+            "{}",
+            fmt_ok!(
+                "The key of the identity {} has been rotated",
+                color_primary(&self.identity)
+            )
+        )?;
+        writeln!(
+            buf,
+            "{}",
+            fmt_log!("Identifier: {}", color_primary(&self.identifier))
+        )?;
+        for node_name in &self.restarted_nodes {
+            writeln!(
+                buf,
+                "{}",
+                fmt_ok!("The node {} has been restarted", color_primary(node_name))
+            )?;
+        }
+        let not_restarted: Vec<&String> = self
+            .running_nodes
+            .iter()
+            .filter(|n| !self.restarted_nodes.contains(n))
+            .collect();
+        if !not_restarted.is_empty() {
+            writeln!(
+                buf,
+                "{}",
+                fmt_warn!(
+                    "The running nodes {} still use the previous key. Restart them with {} or use {}",
+                    not_restarted
+                        .iter()
+                        .map(|n| color_primary(n).to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    color_primary("ockam node restart"),
+                    color_primary("--all-nodes-restart")
+                )
+            )?;
+        }
+        Ok(buf)
+    }
+}
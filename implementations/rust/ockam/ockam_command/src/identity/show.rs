@@ -6,9 +6,10 @@ use serde::Serialize;
 use serde_json::{json, to_string_pretty};
 
 use ockam::identity::verified_change::VerifiedChange;
-use ockam::identity::{Identifier, Identity};
+use ockam::identity::{Identifier, Identity, TimestampInSeconds};
 use ockam_api::cli_state::NamedIdentity;
-use ockam_api::output::{EncodeFormat, Output};
+use ockam_api::output::{human_readable_time, EncodeFormat, Output};
+use ockam_vault::VerifyingPublicKey;
 
 use crate::identity::list::IdentityListOutput;
 use crate::output::{IdentifierDisplay, VerifyingPublicKeyDisplay};
@@ -189,14 +190,47 @@ impl From<Identity> for ShowIdentity {
 impl Display for ShowIdentity {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Identifier: {}", self.identifier)?;
+        if let (Some(first), Some(last)) = (self.changes.first(), self.changes.last()) {
+            writeln!(
+                f,
+                "  Created at: {}",
+                human_readable_time(first.attestations_valid_from)
+            )?;
+            writeln!(f, "  Key rotations: {}", self.changes.len() - 1)?;
+            if self.changes.len() > 1 {
+                writeln!(
+                    f,
+                    "  Last rotated at: {}",
+                    human_readable_time(last.attestations_valid_from)
+                )?;
+            }
+        }
         for (i_num, change) in self.changes.iter().enumerate() {
             writeln!(f, "  Change[{}]:", i_num)?;
             writeln!(f, "    identifier:              {}", change.identifier)?;
+            if let Some(previous_change) = &change.previous_change {
+                writeln!(f, "    previous_change:         {}", previous_change)?;
+            }
             writeln!(
                 f,
                 "    primary_public_key:      {}",
                 change.primary_public_key
             )?;
+            writeln!(
+                f,
+                "    signature_algorithm:     {}",
+                change.signature_algorithm
+            )?;
+            writeln!(
+                f,
+                "    valid_from:              {}",
+                human_readable_time(change.attestations_valid_from)
+            )?;
+            writeln!(
+                f,
+                "    valid_until:             {}",
+                human_readable_time(change.attestations_valid_until)
+            )?;
             writeln!(
                 f,
                 "    revoke_all_purpose_keys: {}",
@@ -213,18 +247,32 @@ impl Output for ShowIdentity {
     }
 }
 
+/// A change of the identity history: the first change creates the identity
+/// and the following ones rotate its primary key
 #[derive(Serialize)]
 struct Change {
     pub identifier: String,
+    pub previous_change: Option<String>,
     pub primary_public_key: VerifyingPublicKeyDisplay,
+    pub signature_algorithm: String,
+    pub attestations_valid_from: TimestampInSeconds,
+    pub attestations_valid_until: TimestampInSeconds,
     pub revoke_all_purpose_keys: bool,
 }
 
 impl From<VerifiedChange> for Change {
     fn from(value: VerifiedChange) -> Self {
+        let signature_algorithm = match value.primary_public_key() {
+            VerifyingPublicKey::EdDSACurve25519(_) => "EdDSACurve25519",
+            VerifyingPublicKey::ECDSASHA256CurveP256(_) => "ECDSASHA256CurveP256",
+        };
         Self {
             identifier: hex::encode(value.change_hash()),
+            previous_change: value.data().previous_change.as_ref().map(hex::encode),
             primary_public_key: VerifyingPublicKeyDisplay(value.primary_public_key().to_owned()),
+            signature_algorithm: signature_algorithm.to_string(),
+            attestations_valid_from: value.data().attestations_valid_from,
+            attestations_valid_until: value.data().attestations_valid_until,
             revoke_all_purpose_keys: value.data().revoke_all_purpose_keys,
         }
     }
@@ -25,6 +25,7 @@ impl DeleteCommand {
             address: Some(self.address),
             all: false,
             yes: false,
+            stdin: false,
         }
         .run(opts)
     }
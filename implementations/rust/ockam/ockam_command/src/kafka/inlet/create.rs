@@ -2,6 +2,8 @@ use crate::kafka::kafka_default_project_route;
 use async_trait::async_trait;
 use std::fmt::Write;
 use std::net::SocketAddr;
+use std::str::FromStr;
+use std::time::Duration;
 
 use clap::{command, Args};
 use colorful::Colorful;
@@ -9,7 +11,9 @@ use miette::miette;
 use ockam_abac::PolicyExpression;
 use ockam_api::colors::{color_primary, color_warn};
 use ockam_api::config::lookup::InternetAddress;
-use ockam_api::kafka::{ConsumerPublishing, ConsumerResolution};
+use ockam_api::kafka::{
+    ConsumerPublishing, ConsumerResolution, RecordEncryption, TopicMode, TopicPolicies, TopicPolicy,
+};
 use ockam_api::nodes::models::services::{StartKafkaInletRequest, StartServiceRequest};
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_api::output::Output;
@@ -27,7 +31,7 @@ use crate::util::process_nodes_multiaddr;
 use crate::{
     kafka::{kafka_default_inlet_bind_address, kafka_inlet_default_addr},
     node::NodeOpts,
-    util::parsers::socket_addr_parser,
+    util::parsers::{duration_parser, socket_addr_parser},
     Command, CommandGlobalOpts,
 };
 
@@ -113,6 +117,53 @@ pub struct CreateCommand {
     /// You can check the fallback policy with `ockam policy show --resource-type kafka-producer`.
     #[arg(hide = true, long = "allow-producer", id = "PRODUCER-EXPRESSION")]
     pub producer_policy_expression: Option<PolicyExpression>,
+
+    /// The address where to bind a proxy to the Schema Registry exposed by the Kafka Outlet,
+    /// with `ockam kafka-outlet create --schema-registry`. The Kafka clients use it as their
+    /// Schema Registry URL. When it is set, the schema ids of the encrypted records are kept
+    /// readable, so that the consumers can resolve the schemas of the records
+    #[arg(long, value_name = "SOCKET_ADDRESS", value_parser = socket_addr_parser)]
+    pub schema_registry_address: Option<SocketAddr>,
+
+    /// Only allow the topics matching a pattern: a topic name, or a prefix followed by `*`.
+    /// Can be given several times. When set, the other topics can neither be produced to
+    /// nor consumed from
+    #[arg(long, value_name = "TOPIC")]
+    pub allow_topic: Vec<String>,
+
+    /// Deny the topics matching a pattern: a topic name, or a prefix followed by `*`.
+    /// Can be given several times
+    #[arg(long, value_name = "TOPIC")]
+    pub deny_topic: Vec<String>,
+
+    /// Configure the encryption of the topics matching a pattern, as `TOPIC:SETTINGS`.
+    /// `SETTINGS` is `encrypt`, `encrypt,keys` to encrypt the record keys as well, or
+    /// `plaintext` to forward the records as they are. It takes precedence over
+    /// `--disable-content-encryption`. Can be given several times
+    #[arg(long, value_name = "TOPIC_POLICY", value_parser = TopicPolicy::from_str)]
+    pub topic_policy: Vec<TopicPolicy>,
+
+    /// How long a producer encrypts the records of a topic partition with the same key.
+    /// A new key is then exchanged with the consumer of the partition, after checking its
+    /// credentials, so that a consumer removed from the project can't read the new records
+    #[arg(long, value_name = "DURATION", default_value = "1h", value_parser = duration_parser)]
+    pub key_rotation: Duration,
+
+    /// Encrypt the record keys of the topics without a topic policy, in addition to the
+    /// record values. The producers and the consumers of a topic must use the same setting
+    #[arg(long)]
+    pub encrypt_keys: bool,
+
+    /// Always encrypt a given record key the same way, so that the records with the same key
+    /// are still partitioned and compacted together. The keys of each topic are given by the
+    /// Kafka Outlet, created with `ockam kafka-outlet create --deterministic-keys`
+    #[arg(long)]
+    pub deterministic_keys: bool,
+
+    /// Encrypt the values of the record headers with a given name. Can be given several
+    /// times. The producers and the consumers of a topic must encrypt the same headers
+    #[arg(long, value_name = "HEADER")]
+    pub encrypt_header: Vec<String>,
 }
 
 #[async_trait]
@@ -137,6 +188,23 @@ impl Command for CreateCommand {
             )
             .into());
         }
+        if let Some(schema_registry_address) = self.schema_registry_address {
+            if schema_registry_address.port() >= brokers_port_range.start()
+                && schema_registry_address.port() <= brokers_port_range.end()
+            {
+                return Err(miette!(
+                    "The Schema Registry port {} can't overlap with the brokers port range {}",
+                    schema_registry_address.port(),
+                    brokers_port_range.to_string()
+                )
+                .into());
+            }
+        }
+
+        let topic_policies = self.topic_policies();
+        topic_policies.validate()?;
+        let record_encryption = self.record_encryption();
+        record_encryption.validate()?;
 
         let at_node = self.node_opts.at_node.clone();
         let addr = self.addr.clone();
@@ -173,7 +241,7 @@ impl Command for CreateCommand {
                 consumer_publishing = ConsumerPublishing::Relay(to.clone());
             }
 
-            let payload = StartKafkaInletRequest::new(
+            let mut payload = StartKafkaInletRequest::new(
                 self.from,
                 brokers_port_range,
                 to.clone(),
@@ -184,6 +252,16 @@ impl Command for CreateCommand {
                 self.consumer_policy_expression,
                 self.producer_policy_expression,
             );
+            if let Some(schema_registry_address) = self.schema_registry_address {
+                payload.set_schema_registry_bind_address(schema_registry_address);
+            }
+            if !topic_policies.is_empty() {
+                payload.set_topic_policies(topic_policies.clone());
+            }
+            payload.set_key_rotation_interval(self.key_rotation);
+            if record_encryption != RecordEncryption::default() {
+                payload.set_record_encryption(record_encryption.clone());
+            }
             let payload = StartServiceRequest::new(payload, &addr);
             let req = Request::post("/node/services/kafka_inlet").body(payload);
             node.tell(ctx, req)
@@ -195,6 +273,16 @@ impl Command for CreateCommand {
                 from: self.from.into(),
                 brokers_port_range,
                 to,
+                schema_registry_address: self.schema_registry_address.map(InternetAddress::from),
+                allowed_topics: topic_policies.allowed_topics().to_vec(),
+                topic_policies: topic_policies
+                    .policies()
+                    .iter()
+                    .map(|policy| policy.to_string())
+                    .collect(),
+                encrypt_keys: record_encryption.encrypt_keys(),
+                deterministic_keys: record_encryption.deterministic_keys(),
+                encrypted_headers: record_encryption.encrypted_headers().to_vec(),
             }
         };
 
@@ -208,12 +296,49 @@ impl Command for CreateCommand {
     }
 }
 
+impl CreateCommand {
+    /// Topic policies given by the arguments, a denied topic being a policy with the deny mode
+    fn topic_policies(&self) -> TopicPolicies {
+        let topic_policies = self
+            .allow_topic
+            .iter()
+            .fold(TopicPolicies::new(), |policies, topic| {
+                policies.with_allowed_topic(topic)
+            });
+        self.deny_topic
+            .iter()
+            .map(|topic| TopicPolicy::new(topic, TopicMode::Deny))
+            .chain(self.topic_policy.iter().cloned())
+            .fold(topic_policies, TopicPolicies::with_policy)
+    }
+
+    /// Encryption of the record keys and headers given by the arguments
+    fn record_encryption(&self) -> RecordEncryption {
+        let mut record_encryption = RecordEncryption::new();
+        if self.encrypt_keys {
+            record_encryption = record_encryption.with_encrypted_keys();
+        }
+        if self.deterministic_keys {
+            record_encryption = record_encryption.with_deterministic_keys();
+        }
+        self.encrypt_header
+            .iter()
+            .fold(record_encryption, RecordEncryption::with_encrypted_header)
+    }
+}
+
 #[derive(Serialize)]
 struct KafkaInletOutput {
     node_name: String,
     from: InternetAddress,
     brokers_port_range: PortRange,
     to: MultiAddr,
+    schema_registry_address: Option<InternetAddress>,
+    allowed_topics: Vec<String>,
+    topic_policies: Vec<String>,
+    encrypt_keys: bool,
+    deterministic_keys: bool,
+    encrypted_headers: Vec<String>,
 }
 
 impl Output for KafkaInletOutput {
@@ -237,6 +362,63 @@ impl Output for KafkaInletOutput {
             )
         )?;
 
+        if let Some(schema_registry_address) = &self.schema_registry_address {
+            writeln!(
+                f,
+                "{}\n",
+                fmt_log!(
+                    "with a proxy to the Schema Registry bound to {}",
+                    color_primary(schema_registry_address.to_string())
+                )
+            )?;
+        }
+
+        if !self.allowed_topics.is_empty() {
+            writeln!(
+                f,
+                "{}\n",
+                fmt_log!(
+                    "allowing only the topics {}",
+                    color_primary(self.allowed_topics.join(", "))
+                )
+            )?;
+        }
+
+        if !self.topic_policies.is_empty() {
+            writeln!(
+                f,
+                "{}\n",
+                fmt_log!(
+                    "with the topic policies {}",
+                    color_primary(self.topic_policies.join(", "))
+                )
+            )?;
+        }
+
+        if self.encrypt_keys || self.deterministic_keys {
+            let mode = if self.deterministic_keys {
+                "deterministically"
+            } else {
+                "with the record values"
+            };
+            writeln!(
+                f,
+                "{}\n",
+                fmt_log!("encrypting the record keys {}", color_primary(mode))
+            )?;
+        }
+
+        if !self.encrypted_headers.is_empty() {
+            writeln!(
+                f,
+                "{}\n",
+                fmt_log!(
+                    "encrypting the record headers {}",
+                    color_primary(self.encrypted_headers.join(", "))
+                )
+            )?;
+        }
+
         writeln!(
             f,
             "{}\n{}",
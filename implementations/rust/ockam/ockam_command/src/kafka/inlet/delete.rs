@@ -33,6 +33,11 @@ pub struct DeleteCommand {
     /// Delete all the Kafka Inlets
     #[arg(long, short)]
     pub(crate) all: bool,
+
+    /// Read the names of the Kafka Inlets to delete from stdin, one per line or as a JSON array.
+    /// The result of each operation is reported separately
+    #[arg(long, conflicts_with_all = ["address", "all"])]
+    pub(crate) stdin: bool,
 }
 
 #[async_trait]
@@ -76,6 +81,10 @@ impl<'a> DeleteCommandTui for DeleteTui<'a> {
         self.cmd.address.clone()
     }
 
+    fn cmd_arg_stdin(&self) -> bool {
+        self.cmd.stdin
+    }
+
     fn cmd_arg_delete_all(&self) -> bool {
         self.cmd.all
     }
@@ -4,12 +4,14 @@ use crate::kafka::inlet::create::CreateCommand;
 use crate::kafka::inlet::delete::DeleteCommand;
 use crate::kafka::inlet::list::ListCommand;
 use crate::kafka::inlet::show::ShowCommand;
+use crate::kafka::inlet::stats::StatsCommand;
 use crate::{Command, CommandGlobalOpts};
 
 pub(crate) mod create;
 pub(crate) mod delete;
 pub(crate) mod list;
 pub(crate) mod show;
+pub(crate) mod stats;
 
 /// Manage Kafka Inlets
 #[derive(Clone, Debug, Args)]
@@ -25,6 +27,7 @@ pub enum KafkaInletSubcommand {
     Show(ShowCommand),
     Delete(DeleteCommand),
     List(ListCommand),
+    Stats(StatsCommand),
 }
 
 impl KafkaInletCommand {
@@ -34,6 +37,7 @@ impl KafkaInletCommand {
             KafkaInletSubcommand::Show(c) => c.run(opts),
             KafkaInletSubcommand::Delete(c) => c.run(opts),
             KafkaInletSubcommand::List(c) => c.run(opts),
+            KafkaInletSubcommand::Stats(c) => c.run(opts),
         }
     }
 
@@ -43,6 +47,7 @@ impl KafkaInletCommand {
             KafkaInletSubcommand::Show(c) => c.name(),
             KafkaInletSubcommand::Delete(c) => c.name(),
             KafkaInletSubcommand::List(c) => c.name(),
+            KafkaInletSubcommand::Stats(c) => c.name(),
         }
     }
 }
@@ -22,6 +22,11 @@ pub struct ShowCommand {
 
     /// Kafka Inlet service address
     pub address: Option<String>,
+
+    /// Read the names of the Kafka Inlets to show from stdin, one per line or as a JSON array.
+    /// The result of each operation is reported separately
+    #[arg(long, conflicts_with = "address")]
+    pub stdin: bool,
 }
 
 #[async_trait]
@@ -65,6 +70,10 @@ impl<'a> ShowCommandTui for ShowTui<'a> {
         self.cmd.address.clone()
     }
 
+    fn cmd_arg_stdin(&self) -> bool {
+        self.cmd.stdin
+    }
+
     fn terminal(&self) -> Terminal<TerminalStream<Term>> {
         self.opts.terminal.clone()
     }
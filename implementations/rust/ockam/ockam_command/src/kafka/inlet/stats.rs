@@ -0,0 +1,100 @@
+use std::io::Write as _;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use clap::Args;
+use console::Term;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+use ockam_api::fmt_log;
+use ockam_api::nodes::models::services::KafkaInletStats;
+use ockam_api::nodes::service::default_address::DefaultAddress;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_core::api::Request;
+
+use crate::node::NodeOpts;
+use crate::util::parsers::duration_parser;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const PREVIEW_TAG: &str = include_str!("../../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/stats/after_long_help.txt");
+
+/// Show the batches produced and fetched through a Kafka Inlet, for each topic,
+/// and how many of them were encrypted
+#[derive(Clone, Debug, Args)]
+#[command(
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct StatsCommand {
+    /// Kafka Inlet service address
+    #[arg(default_value_t = DefaultAddress::KAFKA_INLET.to_string())]
+    address: String,
+
+    /// Node on which the Kafka Inlet was started
+    #[command(flatten)]
+    node_opts: NodeOpts,
+
+    /// Keep refreshing the stats until the command is interrupted
+    #[arg(long)]
+    watch: bool,
+
+    /// Time to wait between two refreshes of the stats when using `--watch`
+    #[arg(long, value_name = "DURATION", default_value = "2s", value_parser = duration_parser, requires = "watch")]
+    interval: Duration,
+}
+
+#[async_trait]
+impl Command for StatsCommand {
+    const NAME: &'static str = "kafka-inlet stats";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
+        if !self.watch {
+            let stats = get_kafka_inlet_stats(ctx, &node, &self.address).await?;
+            opts.terminal
+                .stdout()
+                .plain(fmt_log!("{stats}"))
+                .json(serde_json::to_string(&stats).into_diagnostic()?)
+                .write_line()?;
+            return Ok(());
+        }
+
+        let is_json = opts.global_args.output_format()?.is_json();
+        let stdout = Term::stdout();
+        // the stats of each topic are displayed on their own line
+        let mut displayed_lines = 0;
+        loop {
+            let stats = get_kafka_inlet_stats(ctx, &node, &self.address).await?;
+            if is_json {
+                // Print one JSON object per line so that the output can be consumed as JSON lines
+                let mut out = std::io::stdout().lock();
+                writeln!(out, "{}", serde_json::to_string(&stats).into_diagnostic()?)
+                    .into_diagnostic()?;
+                out.flush().into_diagnostic()?;
+            } else {
+                if stdout.is_term() {
+                    stdout.clear_last_lines(displayed_lines).into_diagnostic()?;
+                    displayed_lines = stats.topics.len() + 1;
+                }
+                stdout.write_line(&fmt_log!("{stats}")).into_diagnostic()?;
+            }
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+}
+
+async fn get_kafka_inlet_stats(
+    ctx: &Context,
+    node: &BackgroundNodeClient,
+    address: &str,
+) -> miette::Result<KafkaInletStats> {
+    node.ask(
+        ctx,
+        Request::get(format!(
+            "/node/services/{}/{address}/stats",
+            DefaultAddress::KAFKA_INLET
+        )),
+    )
+    .await
+}
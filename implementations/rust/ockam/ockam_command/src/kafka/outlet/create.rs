@@ -5,7 +5,9 @@ use colorful::Colorful;
 use miette::miette;
 use serde::Serialize;
 use std::fmt::Write;
+use std::str::FromStr;
 
+use ockam::tcp::{TcpEgressAllowList, TcpEgressRule};
 use ockam::Context;
 use ockam_abac::PolicyExpression;
 use ockam_api::colors::{color_primary, color_warn};
@@ -47,6 +49,28 @@ pub struct CreateCommand {
     /// You can check the fallback policy with `ockam policy show --resource-type tcp-outlet`.
     #[arg(hide = true, long = "allow", id = "EXPRESSION")]
     pub policy_expression: Option<PolicyExpression>,
+
+    /// Broker the Kafka Outlet is allowed to connect to, as `HOST[:PORTS]`, when it is
+    /// advertised by the cluster. `HOST` can be a hostname, a wildcard like `*.example.com`,
+    /// an IP address or a CIDR. Can be given several times. When set, the brokers which
+    /// don't match any rule can't be reached
+    #[arg(long, value_name = "RULE", value_parser = TcpEgressRule::from_str)]
+    pub allow_destination: Vec<TcpEgressRule>,
+
+    /// The address of a Confluent-compatible Schema Registry, as `HOST:PORT`, to expose
+    /// to the Kafka Inlets created with `--schema-registry-address`
+    #[arg(long, value_name = "HOSTNAME_PORT")]
+    pub schema_registry: Option<String>,
+
+    /// If set, the outlet will establish a TLS connection to the Schema Registry
+    #[arg(long, value_name = "BOOL", requires = "schema_registry")]
+    pub schema_registry_tls: bool,
+
+    /// Give the Kafka Inlets created with `--deterministic-keys` the keys used to encrypt
+    /// the record keys of each topic. The keys are derived from a secret stored by the node,
+    /// so a record key is always encrypted the same way, even after the outlet is recreated
+    #[arg(long)]
+    pub deterministic_keys: bool,
 }
 
 #[async_trait]
@@ -65,11 +89,25 @@ impl Command for CreateCommand {
                 ));
             }
 
-            let payload = StartKafkaOutletRequest::new(
+            let mut payload = StartKafkaOutletRequest::new(
                 self.bootstrap_server.clone(),
                 self.tls,
                 self.policy_expression,
             );
+            if !self.allow_destination.is_empty() {
+                payload.set_egress_allow_list(
+                    self.allow_destination
+                        .iter()
+                        .cloned()
+                        .fold(TcpEgressAllowList::new(), TcpEgressAllowList::with_rule),
+                );
+            }
+            if let Some(schema_registry) = &self.schema_registry {
+                payload.set_schema_registry(schema_registry.clone(), self.schema_registry_tls);
+            }
+            if self.deterministic_keys {
+                payload.set_deterministic_record_keys();
+            }
             let payload = StartServiceRequest::new(payload, &self.addr);
             let req = Request::post("/node/services/kafka_outlet").body(payload);
             let node =
@@ -81,6 +119,8 @@ impl Command for CreateCommand {
             KafkaOutletOutput {
                 node_name: node.node_name(),
                 bootstrap_server: self.bootstrap_server.clone(),
+                schema_registry: self.schema_registry.clone(),
+                deterministic_keys: self.deterministic_keys,
             }
         };
 
@@ -98,6 +138,8 @@ impl Command for CreateCommand {
 struct KafkaOutletOutput {
     node_name: String,
     bootstrap_server: String,
+    schema_registry: Option<String>,
+    deterministic_keys: bool,
 }
 
 impl Output for KafkaOutletOutput {
@@ -116,6 +158,28 @@ impl Output for KafkaOutletOutput {
             ),
         )?;
 
+        if let Some(schema_registry) = &self.schema_registry {
+            writeln!(
+                f,
+                "{}\n",
+                fmt_log!(
+                    "exposing the Schema Registry at {}",
+                    color_primary(schema_registry)
+                )
+            )?;
+        }
+
+        if self.deterministic_keys {
+            writeln!(
+                f,
+                "{}\n",
+                fmt_log!(
+                    "giving the Kafka Inlets the keys to {}",
+                    color_primary("deterministically encrypt the record keys")
+                )
+            )?;
+        }
+
         writeln!(
             f,
             "{}\n{}",
@@ -1,4 +1,5 @@
 use std::net::SocketAddr;
+use std::time::Duration;
 
 use clap::{command, Args};
 
@@ -53,6 +54,14 @@ impl CreateCommand {
             inlet_policy_expression: None,
             consumer_policy_expression: None,
             producer_policy_expression: None,
+            schema_registry_address: None,
+            allow_topic: vec![],
+            deny_topic: vec![],
+            topic_policy: vec![],
+            key_rotation: Duration::from_secs(60 * 60),
+            encrypt_keys: false,
+            deterministic_keys: false,
+            encrypt_header: vec![],
         }
         .run(opts)
     }
@@ -34,6 +34,7 @@ mod command_events;
 mod command_global_opts;
 mod completion;
 mod credential;
+mod dashboard;
 mod docs;
 pub mod enroll;
 pub mod entry_point;
@@ -41,17 +42,21 @@ mod environment;
 pub mod error;
 mod flow_control;
 mod global_args;
+mod history;
+mod http_gateway;
 pub mod identity;
 mod kafka;
 mod lease;
 mod manpages;
 mod markdown;
 mod message;
+mod mqtt;
 pub mod node;
 mod operation;
 mod output;
 pub mod pager;
 mod policy;
+mod postgres;
 mod project;
 mod project_member;
 mod relay;
@@ -64,11 +69,14 @@ mod share;
 mod shared_args;
 mod sidecar;
 mod space;
+mod ssh;
 mod status;
 mod subcommand;
 mod subscription;
 pub mod tcp;
 mod terminal;
+mod tun;
+mod udp;
 mod upgrade;
 pub mod util;
 pub mod value_parsers;
@@ -1,13 +1,18 @@
 use async_trait::async_trait;
 use clap::Args;
-use miette::{Context as _, IntoDiagnostic};
-use tracing::info;
+use colorful::Colorful;
+use miette::{miette, Context as _, IntoDiagnostic};
+use serde::Serialize;
+use tracing::{info, warn};
 
+use ockam::identity::Identifier;
 use ockam::Context;
 use ockam_api::address::extract_address_value;
-use ockam_api::nodes::service::messages::Messages;
+use ockam_api::colors::color_primary;
+use ockam_api::nodes::service::messages::{MessageReport, Messages};
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_api::nodes::InMemoryNode;
+use ockam_api::{fmt_log, fmt_ok, fmt_warn};
 use ockam_multiaddr::MultiAddr;
 
 use crate::project::util::{
@@ -46,6 +51,14 @@ pub struct SendCommand {
     #[command(flatten)]
     pub timeout: TimeoutArg,
 
+    /// Number of times to send the message again if it can't be delivered, or if no reply is received before the timeout
+    #[arg(long, value_name = "RETRIES", default_value_t = 0)]
+    pub retries: u32,
+
+    /// Fail if the reply is different from this value
+    #[arg(long, value_name = "REPLY")]
+    pub expect_reply: Option<String>,
+
     #[command(flatten)]
     pub retry_opts: RetryOpts,
 
@@ -81,12 +94,11 @@ impl Command for SendCommand {
 
         // Setup environment depending on whether we are sending the message from a background node
         // or an in-memory node
-        let response: Vec<u8> = if let Some(node) = &self.from {
-            BackgroundNodeClient::create_to_node(ctx, &opts.state, node.as_str())
+        let (report, attempts) = if let Some(node) = &self.from {
+            let node =
+                BackgroundNodeClient::create_to_node(ctx, &opts.state, node.as_str()).await?;
+            self.send_with_retries(ctx, &opts, &node, &to, msg_bytes)
                 .await?
-                .send_message(ctx, &to, msg_bytes, Some(self.timeout.timeout))
-                .await
-                .map_err(Error::Retry)?
         } else {
             let identity_name = opts
                 .state
@@ -121,21 +133,127 @@ impl Command for SendCommand {
             .map_err(Error::Retry)?;
             let to = clean_projects_multiaddr(to, projects_sc)?;
             info!("sending to {to}");
-            node_manager
-                .send_message(ctx, &to, msg_bytes, Some(self.timeout.timeout))
-                .await
-                .map_err(Error::Retry)?
+            self.send_with_retries(ctx, &opts, &**node_manager, &to, msg_bytes)
+                .await?
         };
 
-        let result = if self.hex {
-            hex::encode(response)
+        let reply = if self.hex {
+            hex::encode(&report.reply)
         } else {
-            String::from_utf8(response)
+            String::from_utf8(report.reply.clone())
                 .into_diagnostic()
                 .context("Received content is not a valid utf8 string")?
         };
 
-        opts.terminal.stdout().plain(result).write_line()?;
+        if let Some(expected) = &self.expect_reply {
+            if reply != *expected {
+                return Err(miette!(
+                    "The reply '{reply}' is different from the expected reply '{expected}'"
+                ))?;
+            }
+        }
+
+        let output = DeliveryReport::new(reply, &report, attempts);
+        opts.terminal
+            .stdout()
+            .plain(output.to_string())
+            .machine(&output.reply)
+            .json(serde_json::to_string(&output).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+impl SendCommand {
+    /// Send the message, and send it again if it fails, at most `retries` times.
+    /// Return the delivery report of the message and the number of attempts
+    async fn send_with_retries(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        node: &(impl Messages + Sync),
+        to: &MultiAddr,
+        message: Vec<u8>,
+    ) -> crate::Result<(MessageReport, u32)> {
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match node
+                .send_message_with_report(ctx, to, message.clone(), Some(self.timeout.timeout))
+                .await
+            {
+                Ok(report) => return Ok((report, attempts)),
+                Err(e) if attempts <= self.retries => {
+                    warn!(%e, attempts, "Failed to send the message");
+                    opts.terminal.write_line(fmt_warn!(
+                        "Attempt {attempts} failed, sending the message again"
+                    ))?;
+                }
+                Err(e) => return Err(Error::Retry(e)),
+            }
+        }
+    }
+}
+
+/// Report of the delivery of a message, with its reply
+#[derive(Serialize)]
+struct DeliveryReport {
+    reply: String,
+    route: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    peer_identifier: Option<Identifier>,
+    round_trip_time_ms: u128,
+    attempts: u32,
+}
+
+impl DeliveryReport {
+    fn new(reply: String, report: &MessageReport, attempts: u32) -> Self {
+        Self {
+            reply,
+            route: report.route.clone(),
+            peer_identifier: report.peer_identifier.clone(),
+            round_trip_time_ms: report.round_trip_time.as_millis(),
+            attempts,
+        }
+    }
+}
+
+impl std::fmt::Display for DeliveryReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{}",
+            fmt_ok!("Received a reply: {}", color_primary(&self.reply))
+        )?;
+        writeln!(
+            f,
+            "{}",
+            fmt_log!(
+                "Round-trip time: {}",
+                color_primary(format!("{} ms", self.round_trip_time_ms))
+            )
+        )?;
+        writeln!(f, "{}", fmt_log!("Route: {}", color_primary(&self.route)))?;
+        if let Some(peer_identifier) = &self.peer_identifier {
+            writeln!(
+                f,
+                "{}",
+                fmt_log!(
+                    "Sent over a secure channel with: {}",
+                    color_primary(peer_identifier.to_string())
+                )
+            )?;
+        }
+        if self.attempts > 1 {
+            writeln!(
+                f,
+                "{}",
+                fmt_log!(
+                    "Delivered after {} attempts",
+                    color_primary(self.attempts.to_string())
+                )
+            )?;
+        }
         Ok(())
     }
 }
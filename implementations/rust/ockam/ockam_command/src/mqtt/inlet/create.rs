@@ -0,0 +1,153 @@
+use async_trait::async_trait;
+use std::fmt::Write;
+use std::net::SocketAddr;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::miette;
+use serde::Serialize;
+
+use ockam::Context;
+use ockam_abac::PolicyExpression;
+use ockam_api::colors::color_primary;
+use ockam_api::config::lookup::InternetAddress;
+use ockam_api::nodes::models::services::{StartMqttInletRequest, StartServiceRequest};
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_api::output::Output;
+use ockam_api::{fmt_log, fmt_ok};
+use ockam_core::api::Request;
+use ockam_multiaddr::MultiAddr;
+
+use crate::mqtt::{mqtt_default_inlet_addr, mqtt_default_project_route};
+use crate::node::util::initialize_default_node;
+use crate::node::NodeOpts;
+use crate::util::parsers::socket_addr_parser;
+use crate::util::process_nodes_multiaddr;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
+
+/// Create an MQTT Inlet
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct CreateCommand {
+    #[command(flatten)]
+    pub node_opts: NodeOpts,
+
+    /// The local address of the service
+    #[arg(long, default_value_t = mqtt_default_inlet_addr())]
+    pub addr: String,
+
+    /// The address where the MQTT clients connect to, as `<address>:<port>`.
+    /// When only a port is given, the loopback address is used
+    #[arg(long, value_name = "SOCKET_ADDRESS", value_parser = socket_addr_parser)]
+    pub from: SocketAddr,
+
+    /// The route to the node of the MQTT Outlet, either the project in ockam orchestrator
+    /// or a rust node, expected something like /project/<name>.
+    /// Use self when the MQTT Outlet is local.
+    #[arg(long, default_value_t = mqtt_default_project_route(), value_name = "ROUTE")]
+    pub to: MultiAddr,
+
+    /// Encrypt the payloads of the messages published by the clients, and decrypt the payloads
+    /// of the messages delivered to them. The key of each topic is given by the MQTT Outlet,
+    /// so the payloads are only readable by the clients of the MQTT Inlets using this option
+    #[arg(long)]
+    pub encrypt_payloads: bool,
+
+    /// Policy expression that will be used for access control to the MQTT Inlet.
+    /// If you don't provide it, the policy set for the "tcp-inlet" resource type will be used.
+    ///
+    /// You can check the fallback policy with `ockam policy show --resource-type tcp-inlet`.
+    #[arg(hide = true, long = "allow", id = "EXPRESSION")]
+    pub policy_expression: Option<PolicyExpression>,
+}
+
+#[async_trait]
+impl Command for CreateCommand {
+    const NAME: &'static str = "mqtt-inlet create";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        initialize_default_node(ctx, &opts).await?;
+        let to = process_nodes_multiaddr(&self.to, &opts.state).await?;
+
+        let inlet = {
+            let pb = opts.terminal.progress_bar();
+            if let Some(pb) = pb.as_ref() {
+                pb.set_message(format!(
+                    "Creating MQTT Inlet at {}...\n",
+                    color_primary(self.from.to_string())
+                ));
+            }
+
+            let payload = StartMqttInletRequest::new(
+                self.from,
+                to.clone(),
+                self.encrypt_payloads,
+                self.policy_expression,
+            );
+            let payload = StartServiceRequest::new(payload, &self.addr);
+            let req = Request::post("/node/services/mqtt_inlet").body(payload);
+            let node =
+                BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
+            node.tell(ctx, req)
+                .await
+                .map_err(|e| miette!("Failed to start MQTT Inlet: {e}"))?;
+
+            MqttInletOutput {
+                node_name: node.node_name(),
+                from: self.from.into(),
+                to,
+                encrypt_payloads: self.encrypt_payloads,
+            }
+        };
+
+        opts.terminal
+            .stdout()
+            .plain(inlet.item()?)
+            .json_obj(inlet)?
+            .write_line()?;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct MqttInletOutput {
+    node_name: String,
+    from: InternetAddress,
+    to: MultiAddr,
+    encrypt_payloads: bool,
+}
+
+impl Output for MqttInletOutput {
+    fn item(&self) -> ockam_api::Result<String> {
+        let mut f = String::new();
+        writeln!(
+            f,
+            "{}\n{}",
+            fmt_ok!(
+                "Created a new MQTT Inlet in the Node {} bound to {}",
+                color_primary(&self.node_name),
+                color_primary(self.from.to_string())
+            ),
+            fmt_log!(
+                "sending the packets to the MQTT Outlet at {}",
+                color_primary(self.to.to_string())
+            ),
+        )?;
+
+        if self.encrypt_payloads {
+            writeln!(
+                f,
+                "{}",
+                fmt_log!(
+                    "with the {} of the published messages",
+                    color_primary("end-to-end encryption")
+                )
+            )?;
+        }
+
+        Ok(f)
+    }
+}
@@ -0,0 +1,52 @@
+use clap::{Args, Subcommand};
+
+use create::CreateCommand;
+use delete::DeleteCommand;
+use list::ListCommand;
+
+use crate::{docs, Command, CommandGlobalOpts};
+
+mod create;
+mod delete;
+mod list;
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
+
+/// Manage MQTT Inlets
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct MqttInletCommand {
+    #[command(subcommand)]
+    pub subcommand: MqttInletSubCommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum MqttInletSubCommand {
+    Create(CreateCommand),
+    Delete(DeleteCommand),
+    List(ListCommand),
+}
+
+impl MqttInletCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        match self.subcommand {
+            MqttInletSubCommand::Create(c) => c.run(opts),
+            MqttInletSubCommand::Delete(c) => c.run(opts),
+            MqttInletSubCommand::List(c) => c.run(opts),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match &self.subcommand {
+            MqttInletSubCommand::Create(c) => c.name(),
+            MqttInletSubCommand::Delete(c) => c.name(),
+            MqttInletSubCommand::List(c) => c.name(),
+        }
+    }
+}
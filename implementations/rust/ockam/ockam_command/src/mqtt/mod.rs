@@ -0,0 +1,26 @@
+use std::str::FromStr;
+
+use ockam_api::nodes::service::default_address::DefaultAddress;
+use ockam_multiaddr::MultiAddr;
+
+pub(crate) mod inlet;
+pub(crate) mod outlet;
+
+const MQTT_DEFAULT_BROKER_ADDRESS: &str = "127.0.0.1:1883";
+const MQTT_DEFAULT_PROJECT_ROUTE: &str = "/project/default";
+
+fn mqtt_default_inlet_addr() -> String {
+    DefaultAddress::MQTT_INLET.to_string()
+}
+
+fn mqtt_default_outlet_addr() -> String {
+    DefaultAddress::MQTT_OUTLET.to_string()
+}
+
+fn mqtt_default_broker() -> String {
+    MQTT_DEFAULT_BROKER_ADDRESS.to_string()
+}
+
+fn mqtt_default_project_route() -> MultiAddr {
+    MultiAddr::from_str(MQTT_DEFAULT_PROJECT_ROUTE).expect("Failed to parse default project route")
+}
@@ -0,0 +1,114 @@
+use async_trait::async_trait;
+use std::fmt::Write;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::miette;
+use serde::Serialize;
+
+use ockam::Context;
+use ockam_abac::PolicyExpression;
+use ockam_api::colors::color_primary;
+use ockam_api::nodes::models::services::{StartMqttOutletRequest, StartServiceRequest};
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_api::output::Output;
+use ockam_api::{fmt_log, fmt_ok};
+use ockam_core::api::Request;
+
+use crate::mqtt::{mqtt_default_broker, mqtt_default_outlet_addr};
+use crate::node::util::initialize_default_node;
+use crate::node::NodeOpts;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
+
+/// Create an MQTT Outlet
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct CreateCommand {
+    #[command(flatten)]
+    pub node_opts: NodeOpts,
+
+    /// The local address of the service
+    #[arg(long, default_value_t = mqtt_default_outlet_addr())]
+    pub addr: String,
+
+    /// The address of the MQTT broker, as `HOST:PORT`
+    #[arg(long, value_name = "HOSTNAME_PORT", default_value_t = mqtt_default_broker())]
+    pub broker: String,
+
+    /// If set, the outlet will establish a TLS connection over TCP
+    #[arg(long, id = "BOOLEAN")]
+    pub tls: bool,
+
+    /// Policy expression that will be used for access control to the MQTT Outlet.
+    /// If you don't provide it, the policy set for the "tcp-outlet" resource type will be used.
+    ///
+    /// You can check the fallback policy with `ockam policy show --resource-type tcp-outlet`.
+    #[arg(hide = true, long = "allow", id = "EXPRESSION")]
+    pub policy_expression: Option<PolicyExpression>,
+}
+
+#[async_trait]
+impl Command for CreateCommand {
+    const NAME: &'static str = "mqtt-outlet create";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        initialize_default_node(ctx, &opts).await?;
+
+        let outlet = {
+            let pb = opts.terminal.progress_bar();
+            if let Some(pb) = pb.as_ref() {
+                pb.set_message(format!(
+                    "Creating MQTT Outlet to the broker {}...\n",
+                    color_primary(&self.broker)
+                ));
+            }
+
+            let payload =
+                StartMqttOutletRequest::new(self.broker.clone(), self.tls, self.policy_expression);
+            let payload = StartServiceRequest::new(payload, &self.addr);
+            let req = Request::post("/node/services/mqtt_outlet").body(payload);
+            let node =
+                BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
+            node.tell(ctx, req)
+                .await
+                .map_err(|e| miette!("Failed to start MQTT Outlet: {e}"))?;
+
+            MqttOutletOutput {
+                node_name: node.node_name(),
+                broker: self.broker.clone(),
+            }
+        };
+
+        opts.terminal
+            .stdout()
+            .plain(outlet.item()?)
+            .json_obj(outlet)?
+            .write_line()?;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct MqttOutletOutput {
+    node_name: String,
+    broker: String,
+}
+
+impl Output for MqttOutletOutput {
+    fn item(&self) -> ockam_api::Result<String> {
+        let mut f = String::new();
+        writeln!(
+            f,
+            "{}\n{}",
+            fmt_ok!(
+                "Created a new MQTT Outlet in the Node {}",
+                color_primary(&self.node_name)
+            ),
+            fmt_log!("connected to the broker at {}", color_primary(&self.broker)),
+        )?;
+        Ok(f)
+    }
+}
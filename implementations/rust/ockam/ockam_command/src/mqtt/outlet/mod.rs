@@ -0,0 +1,52 @@
+use clap::{Args, Subcommand};
+
+use create::CreateCommand;
+use delete::DeleteCommand;
+use list::ListCommand;
+
+use crate::{docs, Command, CommandGlobalOpts};
+
+mod create;
+mod delete;
+mod list;
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
+
+/// Manage MQTT Outlets
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct MqttOutletCommand {
+    #[command(subcommand)]
+    pub subcommand: MqttOutletSubCommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum MqttOutletSubCommand {
+    Create(CreateCommand),
+    Delete(DeleteCommand),
+    List(ListCommand),
+}
+
+impl MqttOutletCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        match self.subcommand {
+            MqttOutletSubCommand::Create(c) => c.run(opts),
+            MqttOutletSubCommand::Delete(c) => c.run(opts),
+            MqttOutletSubCommand::List(c) => c.run(opts),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match &self.subcommand {
+            MqttOutletSubCommand::Create(c) => c.name(),
+            MqttOutletSubCommand::Delete(c) => c.name(),
+            MqttOutletSubCommand::List(c) => c.name(),
+        }
+    }
+}
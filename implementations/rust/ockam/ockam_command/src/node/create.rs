@@ -1,4 +1,5 @@
 use std::fmt::Write;
+use std::time::Duration;
 use std::{path::PathBuf, str::FromStr};
 
 use async_trait::async_trait;
@@ -16,12 +17,13 @@ use ockam_api::{fmt_log, fmt_ok};
 use ockam_core::{opentelemetry_context_parser, OpenTelemetryContext};
 use ockam_node::Context;
 
-use crate::node::create::config::ConfigArgs;
+use crate::node::create::config::{ConfigArgs, STDIN_CONFIGURATION};
 use crate::node::foreground::ForegroundArgs;
 use crate::node::util::NodeManagerDefaults;
 use crate::service::config::Config;
-use crate::shared_args::TrustOpts;
+use crate::shared_args::{TcpSocketOpts, TrustOpts};
 use crate::util::embedded_node_that_is_not_stopped;
+use crate::util::parsers::duration_parser;
 use crate::util::{async_cmd, local_cmd};
 use crate::value_parsers::is_url;
 use crate::{docs, Command, CommandGlobalOpts, Result};
@@ -30,6 +32,9 @@ pub mod background;
 mod config;
 pub mod foreground;
 
+/// Default maximum time to wait for a node to be ready when `--wait-until-ready` is used
+const DEFAULT_WAIT_UNTIL_READY_TIMEOUT: Duration = Duration::from_secs(30);
+
 const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
 
@@ -41,7 +46,7 @@ after_long_help = docs::after_help(AFTER_LONG_HELP)
 )]
 pub struct CreateCommand {
     /// Name of the node or a configuration to set up the node.
-    /// The configuration can be either a path to a local file or a URL.
+    /// The configuration can be either a path to a local file, a URL, or `-` to read it from stdin.
     #[arg(value_name = "NAME_OR_CONFIGURATION", hide_default_value = true, default_value_t = random_name())]
     pub name: String,
 
@@ -81,6 +86,41 @@ pub struct CreateCommand {
     #[arg(long, value_name = "BOOL", default_value_t = false)]
     pub enable_udp: bool,
 
+    /// Start a UDP rendezvous service listening on the given address, for example 0.0.0.0:4000.
+    /// Nodes behind a NAT can then use this node, when it is publicly reachable, to open
+    /// peer-to-peer UDP connections. This enables the UDP transport
+    #[arg(long, value_name = "SOCKET_ADDRESS")]
+    pub udp_rendezvous_service: Option<String>,
+
+    /// Address of the UDP rendezvous service used to open UDP punctures with other nodes,
+    /// for example rendezvous.example.com:4000. Defaults to the `OCKAM_RENDEZVOUS_SERVER`
+    /// environment variable. This enables the UDP transport
+    #[arg(long, value_name = "HOSTNAME_PORT")]
+    pub udp_rendezvous_server: Option<String>,
+
+    /// Advertise the TCP listener and the identifier of the node on the local network with
+    /// mDNS, so that it can be found with `ockam node discover`. The TCP listener must be
+    /// reachable from the network, for example with `--tcp-listener-address 0.0.0.0:4000`
+    #[arg(long, value_name = "BOOL", default_value_t = false)]
+    pub enable_discovery: bool,
+
+    #[command(flatten)]
+    pub tcp_socket_opts: TcpSocketOpts,
+
+    /// Wait until the node has started all its services before returning.
+    /// The command fails if the node is not ready before the `--timeout` duration
+    #[arg(
+        long,
+        value_name = "BOOL",
+        default_value_t = false,
+        conflicts_with = "foreground"
+    )]
+    pub wait_until_ready: bool,
+
+    /// Maximum time to wait for the node to be ready when `--wait-until-ready` is used
+    #[arg(long, value_name = "TIMEOUT", default_value = "30s", value_parser = duration_parser)]
+    pub timeout: Duration,
+
     /// A configuration in JSON format to set up the node services.
     /// Node configuration is run asynchronously and may take several
     /// seconds to complete.
@@ -117,6 +157,12 @@ impl Default for CreateCommand {
             tcp_listener_address: node_manager_defaults.tcp_listener_address,
             enable_http_server: false,
             enable_udp: false,
+            udp_rendezvous_service: None,
+            udp_rendezvous_server: None,
+            enable_discovery: false,
+            tcp_socket_opts: TcpSocketOpts::default(),
+            wait_until_ready: false,
+            timeout: DEFAULT_WAIT_UNTIL_READY_TIMEOUT,
             http_server_port: None,
             launch_config: None,
             identity: None,
@@ -182,10 +228,24 @@ impl CreateCommand {
     fn has_name_arg(&self) -> bool {
         is_url(&self.name).is_none()
             && std::fs::metadata(&self.name).is_err()
+            && self.name != STDIN_CONFIGURATION
             && self.config_args.configuration.is_none()
     }
 
+    /// Return true if the node configuration must be read from stdin
+    fn reads_config_from_stdin(&self) -> bool {
+        self.name == STDIN_CONFIGURATION
+            || self.config_args.configuration.as_deref() == Some(STDIN_CONFIGURATION)
+    }
+
     fn parse_args(&self) -> miette::Result<()> {
+        // stdin can't be used both for the configuration and to stop the node
+        if self.reads_config_from_stdin() && self.foreground_args.exit_on_eof {
+            return Err(miette!(
+                "The --exit-on-eof flag can't be used when the node configuration is read from stdin"
+            ));
+        }
+
         // return error if there are duplicated variables
         let mut variables = std::collections::HashMap::new();
         for (key, value) in self.config_args.variables.iter() {
@@ -11,7 +11,7 @@ use ockam_api::nodes::BackgroundNodeClient;
 use ockam_api::terminal::notification::NotificationHandler;
 use ockam_core::OpenTelemetryContext;
 
-use crate::node::show::get_node_resources;
+use crate::node::show::{get_node_resources, wait_until_node_services_are_initialized};
 use crate::node::util::spawn_node;
 use crate::node::CreateCommand;
 use crate::CommandGlobalOpts;
@@ -67,6 +67,15 @@ impl CreateCommand {
         cmd_with_trace_context.spawn_background_node(&opts).await?;
         let mut node = BackgroundNodeClient::create_to_node(ctx, &opts.state, &node_name).await?;
         let node_resources = get_node_resources(ctx, &opts.state, &mut node, true).await?;
+        if self.wait_until_ready
+            && !wait_until_node_services_are_initialized(ctx, &node, self.timeout).await?
+        {
+            return Err(miette!(
+                "Node {} was not ready after {}",
+                color_primary(&node_name),
+                color_primary(format!("{:?}", self.timeout))
+            ));
+        }
         opts.state
             .add_journey_event(
                 JourneyEvent::NodeCreated,
@@ -4,22 +4,28 @@ use crate::run::parser::building_blocks::ArgValue;
 use crate::run::parser::config::ConfigParser;
 use crate::run::parser::resource::*;
 use crate::run::parser::Version;
+use crate::run::recipe_dir;
 use crate::value_parsers::{async_parse_path_or_url, parse_enrollment_ticket, parse_key_val};
 use crate::CommandGlobalOpts;
 use clap::Args;
-use miette::{miette, IntoDiagnostic};
+use miette::{miette, Context as _, IntoDiagnostic};
 use ockam_api::cli_state::journeys::APPLICATION_EVENT_COMMAND_CONFIGURATION_FILE;
 use ockam_api::cli_state::{random_name, EnrollmentTicket};
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_core::AsyncTryClone;
 use ockam_node::Context;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use tracing::{debug, instrument, Span};
 
+/// The value of the configuration argument used to read a node configuration from stdin
+pub const STDIN_CONFIGURATION: &str = "-";
+
 #[derive(Clone, Debug, Args, Default)]
 pub struct ConfigArgs {
-    /// Inline node configuration
-    #[arg(long, visible_alias = "node-config", value_name = "YAML")]
+    /// Inline node configuration, in YAML or JSON format.
+    /// Use `-` to read the configuration from stdin
+    #[arg(long, visible_aliases = ["node-config", "inline"], value_name = "YAML")]
     pub configuration: Option<String>,
 
     /// A path, URL or inlined hex-encoded enrollment ticket to use for the Ockam Identity associated to this node.
@@ -61,13 +67,21 @@ impl CreateCommand {
     /// Try to read the self.name field as either:
     ///  - a URL to a configuration file
     ///  - a local path to a configuration file
+    ///  - `-`, to read the configuration from stdin
     ///  - an inline configuration
     #[instrument(skip_all, fields(app.event.command.configuration_file))]
     pub async fn get_node_config(&self) -> miette::Result<NodeConfig> {
         let contents = match self.config_args.configuration.clone() {
+            Some(contents) if contents == STDIN_CONFIGURATION => read_config_from_stdin().await?,
             Some(contents) => contents,
+            None if self.name == STDIN_CONFIGURATION => read_config_from_stdin().await?,
             None => async_parse_path_or_url(&self.name).await?,
         };
+        // Files included by a local configuration file are relative to its directory
+        let base_dir = match self.config_args.configuration {
+            None if std::fs::metadata(&self.name).is_ok() => recipe_dir(Path::new(&self.name)),
+            _ => PathBuf::from("."),
+        };
         // Set environment variables from the cli command args
         // This needs to be done before parsing the configuration
         for (key, value) in &self.config_args.variables {
@@ -77,7 +91,7 @@ impl CreateCommand {
             std::env::set_var(key, value);
         }
         // Parse the configuration
-        let node_config = NodeConfig::new(&contents)?;
+        let node_config = NodeConfig::new(&contents, &base_dir)?;
         // Record the configuration contents if the node configuration was successfully parsed
         Span::current().record(
             APPLICATION_EVENT_COMMAND_CONFIGURATION_FILE.as_str(),
@@ -87,6 +101,19 @@ impl CreateCommand {
     }
 }
 
+/// Read the whole node configuration from stdin
+async fn read_config_from_stdin() -> miette::Result<String> {
+    let contents = tokio::task::spawn_blocking(|| std::io::read_to_string(std::io::stdin()))
+        .await
+        .into_diagnostic()?
+        .into_diagnostic()
+        .context("Failed to read the node configuration from stdin")?;
+    if contents.trim().is_empty() {
+        return Err(miette!("The node configuration read from stdin is empty"));
+    }
+    Ok(contents)
+}
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct NodeConfig {
     #[serde(flatten)]
@@ -112,8 +139,8 @@ pub struct NodeConfig {
 impl ConfigParser<'_> for NodeConfig {}
 
 impl NodeConfig {
-    fn new(contents: &str) -> miette::Result<Self> {
-        Self::parse(&Self::resolve(contents)?)
+    fn new(contents: &str, base_dir: &Path) -> miette::Result<Self> {
+        Self::parse(&Self::resolve_in(contents, base_dir)?)
     }
 
     /// Merge the arguments of the node defined in the config with the arguments from the
@@ -81,6 +81,7 @@ impl CreateCommand {
 
         // Create TCP transport
         let tcp = TcpTransport::create(ctx).await.into_diagnostic()?;
+        tcp.set_socket_options(self.tcp_socket_opts.socket_options());
         let tcp_listener = tcp
             .listen(&self.tcp_listener_address, TcpListenerOptions::new())
             .await
@@ -114,7 +115,10 @@ impl CreateCommand {
             None
         };
 
-        let udp_transport = if self.enable_udp {
+        let udp_transport = if self.enable_udp
+            || self.udp_rendezvous_service.is_some()
+            || self.udp_rendezvous_server.is_some()
+        {
             Some(UdpTransport::create(ctx).await.into_diagnostic()?)
         } else {
             None
@@ -133,6 +137,12 @@ impl CreateCommand {
                 tcp_listener.flow_control_id().clone(),
                 tcp,
                 udp_transport,
+            )
+            .with_rendezvous_service(self.udp_rendezvous_service.clone())
+            .with_rendezvous_server(self.udp_rendezvous_server.clone())
+            .with_discovery(
+                self.enable_discovery
+                    .then(|| *tcp_listener.socket_address()),
             ),
             trust_options,
         )
@@ -140,7 +150,8 @@ impl CreateCommand {
         .into_diagnostic()?;
         debug!("in-memory node created");
 
-        let node_manager_worker = NodeManagerWorker::new(Arc::new(node_man));
+        let node_man = Arc::new(node_man);
+        let node_manager_worker = NodeManagerWorker::new(node_man.clone());
         ctx.flow_controls()
             .add_consumer(NODEMANAGER_ADDR, tcp_listener.flow_control_id());
         ctx.start_worker(NODEMANAGER_ADDR, node_manager_worker)
@@ -161,6 +172,7 @@ impl CreateCommand {
             ctx.stop().await.into_diagnostic()?;
             return Err(miette!("Failed to start services"));
         }
+        node_man.set_services_initialized();
 
         if !self.foreground_args.child_process {
             opts.terminal
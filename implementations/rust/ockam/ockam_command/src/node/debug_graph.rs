@@ -0,0 +1,44 @@
+use async_trait::async_trait;
+use clap::Args;
+use miette::IntoDiagnostic;
+
+use ockam_api::nodes::models::node::DebugGraph;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_core::api::Request;
+use ockam_node::Context;
+
+use crate::{docs, Command, CommandGlobalOpts, Result};
+
+const LONG_ABOUT: &str = include_str!("./static/debug_graph/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/debug_graph/after_long_help.txt");
+
+/// Print the context inheritance and message flow graphs recorded by the debugger of a node
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct DebugGraphCommand {
+    /// Name of the node.
+    /// If not provided, the default node is used.
+    node_name: Option<String>,
+}
+
+#[async_trait]
+impl Command for DebugGraphCommand {
+    const NAME: &'static str = "node debug-graph";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_name).await?;
+        let graph: DebugGraph = node.ask(ctx, Request::get("/node/debug/graph")).await?;
+        opts.terminal
+            .stdout()
+            .plain(&graph.dot)
+            .machine(&graph.dot)
+            .json(serde_json::to_string(&graph).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -35,6 +35,11 @@ pub struct DeleteCommand {
     /// Confirm the deletion without prompting
     #[arg(display_order = 901, long, short)]
     yes: bool,
+
+    /// Read the names of the nodes to delete from stdin, one per line or as a JSON array.
+    /// The result of each operation is reported separately
+    #[arg(long, conflicts_with_all = ["node_name", "all"])]
+    stdin: bool,
 }
 
 impl DeleteCommand {
@@ -74,6 +79,10 @@ impl DeleteCommandTui for DeleteTui {
         self.cmd.node_name.clone()
     }
 
+    fn cmd_arg_stdin(&self) -> bool {
+        self.cmd.stdin
+    }
+
     fn cmd_arg_delete_all(&self) -> bool {
         self.cmd.all
     }
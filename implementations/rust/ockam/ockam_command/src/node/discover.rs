@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use clap::Args;
+
+use ockam_api::discovery::discover_nodes;
+use ockam_node::Context;
+
+use crate::util::parsers::duration_parser;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/discover/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/discover/after_long_help.txt");
+
+/// List the nodes advertised on the local network
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct DiscoverCommand {
+    /// How long to wait for the answers of the nodes
+    #[arg(long, value_name = "TIMEOUT", default_value = "2s", value_parser = duration_parser)]
+    pub timeout: Duration,
+}
+
+#[async_trait]
+impl Command for DiscoverCommand {
+    const NAME: &'static str = "node discover";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let nodes = {
+            let pb = opts.terminal.progress_bar();
+            if let Some(pb) = pb.as_ref() {
+                pb.set_message("Looking for nodes on the local network...");
+            }
+            discover_nodes(self.timeout).await?
+        };
+
+        let plain = opts
+            .terminal
+            .build_list(&nodes, "No nodes found on the local network")?;
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json_obj(&nodes)?
+            .write_line()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+
+    use super::*;
+
+    #[test]
+    fn command_can_be_parsed_from_name() {
+        let cmd = parse_cmd_from_args(
+            DiscoverCommand::NAME,
+            &["--timeout".to_string(), "5s".to_string()],
+        );
+        assert!(cmd.is_ok());
+    }
+}
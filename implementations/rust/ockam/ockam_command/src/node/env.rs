@@ -0,0 +1,149 @@
+use async_trait::async_trait;
+use clap::Args;
+use miette::IntoDiagnostic;
+use serde::Serialize;
+
+use ockam_api::cli_state::NodeInfo;
+use ockam_node::Context;
+
+use crate::{docs, Command, CommandGlobalOpts, Result};
+
+const LONG_ABOUT: &str = include_str!("./static/env/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/env/after_long_help.txt");
+
+/// Print the connection details of a node as environment variables
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct EnvCommand {
+    /// The name of the node. If not provided, the default node is used.
+    node_name: Option<String>,
+}
+
+#[async_trait]
+impl Command for EnvCommand {
+    const NAME: &'static str = "node env";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        let node = opts.state.get_node_or_default(&self.node_name).await?;
+        let env = NodeEnv::new(&node);
+        let exports = env.exports();
+        opts.terminal
+            .stdout()
+            .plain(&exports)
+            .machine(&exports)
+            .json(serde_json::to_string(&env).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+/// Connection details of a node which can be used by other processes
+#[derive(Serialize)]
+struct NodeEnv {
+    name: String,
+    identifier: String,
+    status: String,
+    pid: Option<u32>,
+    tcp_listener_address: Option<String>,
+    tcp_listener_multiaddr: Option<String>,
+    status_endpoint: Option<String>,
+}
+
+impl NodeEnv {
+    fn new(node: &NodeInfo) -> Self {
+        let pid = if node.is_running() { node.pid() } else { None };
+        Self {
+            name: node.name(),
+            identifier: node.identifier().to_string(),
+            status: if pid.is_some() { "running" } else { "stopped" }.to_string(),
+            pid,
+            tcp_listener_address: node.tcp_listener_address().map(|a| a.to_string()),
+            tcp_listener_multiaddr: node
+                .tcp_listener_multi_address()
+                .ok()
+                .map(|m| m.to_string()),
+            status_endpoint: node
+                .http_server_address()
+                .map(|a| format!("http://{a}/show")),
+        }
+    }
+
+    /// Return the details of the node as `export KEY=VALUE` lines.
+    /// Missing values are skipped.
+    fn exports(&self) -> String {
+        let variables = [
+            ("OCKAM_NODE_NAME", Some(self.name.clone())),
+            ("OCKAM_NODE_IDENTIFIER", Some(self.identifier.clone())),
+            ("OCKAM_NODE_STATUS", Some(self.status.clone())),
+            ("OCKAM_NODE_PID", self.pid.map(|p| p.to_string())),
+            (
+                "OCKAM_NODE_TCP_LISTENER_ADDRESS",
+                self.tcp_listener_address.clone(),
+            ),
+            (
+                "OCKAM_NODE_TCP_LISTENER_MULTIADDR",
+                self.tcp_listener_multiaddr.clone(),
+            ),
+            ("OCKAM_NODE_STATUS_ENDPOINT", self.status_endpoint.clone()),
+        ];
+        variables
+            .into_iter()
+            .filter_map(|(key, value)| value.map(|v| format!("export {key}={}", quote(&v))))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Quote a value so that it can be safely evaluated by a shell
+fn quote(value: &str) -> String {
+    let is_safe = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:@".contains(c));
+    if is_safe {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_quoted_when_needed() {
+        assert_eq!(
+            quote("/dnsaddr/127.0.0.1/tcp/4000"),
+            "/dnsaddr/127.0.0.1/tcp/4000"
+        );
+        assert_eq!(quote("my node"), "'my node'");
+        assert_eq!(quote("it's"), r"'it'\''s'");
+        assert_eq!(quote(""), "''");
+    }
+
+    #[test]
+    fn missing_values_are_not_exported() {
+        let env = NodeEnv {
+            name: "n1".to_string(),
+            identifier: "I123".to_string(),
+            status: "stopped".to_string(),
+            pid: None,
+            tcp_listener_address: Some("127.0.0.1:4000".to_string()),
+            tcp_listener_multiaddr: None,
+            status_endpoint: None,
+        };
+        assert_eq!(
+            env.exports(),
+            "export OCKAM_NODE_NAME=n1\n\
+             export OCKAM_NODE_IDENTIFIER=I123\n\
+             export OCKAM_NODE_STATUS=stopped\n\
+             export OCKAM_NODE_TCP_LISTENER_ADDRESS=127.0.0.1:4000"
+        );
+    }
+}
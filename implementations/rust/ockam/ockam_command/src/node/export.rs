@@ -0,0 +1,312 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::miette;
+
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+use ockam_api::nodes::models::policies::{Policy, ResourceTypeOrName};
+use ockam_api::nodes::models::portal::{InletStatus, OutletStatus};
+use ockam_api::nodes::models::relay::RelayInfo;
+use ockam_api::nodes::{BackgroundNodeClient, Policies as _};
+use ockam_core::api::Request;
+use ockam_node::Context;
+
+use crate::node::show::is_node_up;
+use crate::run::parser::building_blocks::{
+    ArgValue, Args as ResourceArgs, NamedResources, ResourceNameOrMap, ResourcesContainer,
+    UnnamedResources,
+};
+use crate::run::parser::resource::{
+    Identities, KafkaInlet, KafkaOutlet, Nodes, Policies, ProjectEnroll, Relays, TcpInlets,
+    TcpOutlets, Vaults,
+};
+use crate::run::parser::version::VersionValue;
+use crate::run::parser::Version;
+use crate::run::Config;
+use crate::util::api;
+use crate::{docs, Command, CommandGlobalOpts, Result};
+
+const LONG_ABOUT: &str = include_str!("./static/export/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/export/after_long_help.txt");
+
+/// Export the configuration of a node to a file that can be used with `ockam run`
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ExportCommand {
+    /// Name of the node to export.
+    /// If not provided, the default node is used.
+    node_name: Option<String>,
+
+    /// Path of the file where the configuration is written.
+    /// If not provided, the configuration is printed to stdout
+    #[arg(long, short, value_name = "PATH")]
+    file: Option<PathBuf>,
+}
+
+#[async_trait]
+impl Command for ExportCommand {
+    const NAME: &'static str = "node export";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        let mut node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_name).await?;
+        let node_name = node.node_name();
+        if !is_node_up(ctx, &mut node, false).await? {
+            return Err(miette!(
+                "The node {} must be running to export its configuration",
+                color_primary(&node_name)
+            ))?;
+        }
+
+        let node_info = opts.state.get_node(&node_name).await?;
+        let identity = opts
+            .state
+            .get_named_identity_by_identifier(&node_info.identifier())
+            .await?;
+        let project = opts
+            .state
+            .get_node_project(&node_name)
+            .await
+            .ok()
+            .map(|p| p.name().to_string());
+        let resources: ockam_api::nodes::models::node::NodeResources =
+            node.ask(ctx, api::get_node_resources()).await?;
+        let relays: Vec<RelayInfo> = node.ask(ctx, Request::get("/node/relay")).await?;
+        let policies = node.list_policies(ctx, None).await?.all();
+
+        let export = NodeExport {
+            node_name: node_name.clone(),
+            identity_name: identity.name(),
+            tcp_listener_address: node_info.tcp_listener_address().map(|a| a.to_string()),
+            project,
+            inlets: resources.inlets,
+            outlets: resources.outlets,
+            relays,
+            policies,
+        };
+        let contents = export.into_yaml()?;
+
+        match &self.file {
+            Some(path) => {
+                std::fs::write(path, &contents)?;
+                opts.terminal
+                    .stdout()
+                    .plain(fmt_ok!(
+                        "The configuration of the node {} has been exported to {}",
+                        color_primary(&node_name),
+                        color_primary(path.display().to_string())
+                    ))
+                    .machine(path.display())
+                    .json(serde_json::json!({ "path": path.display().to_string() }))
+                    .write_line()?;
+            }
+            None => {
+                opts.terminal
+                    .stdout()
+                    .plain(&contents)
+                    .machine(&contents)
+                    .json(serde_json::to_string(&serde_yaml::from_str::<
+                        serde_json::Value,
+                    >(&contents)?)?)
+                    .write_line()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Configuration of a running node, which can be turned into an `ockam run` configuration
+pub(crate) struct NodeExport {
+    pub(crate) node_name: String,
+    pub(crate) identity_name: String,
+    pub(crate) tcp_listener_address: Option<String>,
+    pub(crate) project: Option<String>,
+    pub(crate) inlets: Vec<InletStatus>,
+    pub(crate) outlets: Vec<OutletStatus>,
+    pub(crate) relays: Vec<RelayInfo>,
+    pub(crate) policies: Vec<Policy>,
+}
+
+impl NodeExport {
+    /// Return the YAML configuration of the node, omitting the empty sections
+    pub(crate) fn into_yaml(self) -> Result<String> {
+        let mut value = serde_yaml::to_value(self.into_config()?)?;
+        if let serde_yaml::Value::Mapping(mapping) = &mut value {
+            mapping.retain(|_, v| !v.is_null());
+        }
+        Ok(serde_yaml::to_string(&value)?)
+    }
+
+    fn into_config(self) -> Result<Config> {
+        let node_name = ArgValue::String(self.node_name.clone());
+
+        let mut node_args = BTreeMap::new();
+        node_args.insert(
+            "identity".to_string(),
+            ArgValue::String(self.identity_name.clone()),
+        );
+        if let Some(address) = self.tcp_listener_address {
+            node_args.insert(
+                "tcp-listener-address".to_string(),
+                ArgValue::String(address),
+            );
+        }
+        if let Some(project) = self.project {
+            node_args.insert("project".to_string(), ArgValue::String(project));
+        }
+        let nodes = named_resources([(self.node_name.clone(), node_args)].into_iter());
+
+        let tcp_inlets = named_resources(self.inlets.into_iter().map(|inlet| {
+            let mut args = BTreeMap::new();
+            args.insert("at".to_string(), node_name.clone());
+            args.insert("from".to_string(), ArgValue::String(inlet.bind_addr));
+            args.insert("to".to_string(), ArgValue::String(inlet.outlet_addr));
+            (inlet.alias, args)
+        }));
+
+        let tcp_outlets = named_resources(
+            self.outlets
+                .into_iter()
+                .map(|outlet| {
+                    let mut args = BTreeMap::new();
+                    args.insert("at".to_string(), node_name.clone());
+                    args.insert(
+                        "to".to_string(),
+                        ArgValue::String(outlet.socket_addr.to_string()),
+                    );
+                    Ok((outlet.worker_name()?, args))
+                })
+                .collect::<Result<Vec<_>>>()?
+                .into_iter(),
+        );
+
+        let relays = named_resources(self.relays.into_iter().map(|relay| {
+            let mut args = BTreeMap::new();
+            args.insert("to".to_string(), node_name.clone());
+            args.insert(
+                "at".to_string(),
+                ArgValue::String(relay.destination_address().to_string()),
+            );
+            (relay.alias().to_string(), args)
+        }));
+
+        let policies: Vec<ResourceArgs> = self
+            .policies
+            .iter()
+            .map(|policy| {
+                let (resource_key, resource) = match policy.resource() {
+                    ResourceTypeOrName::Type(t) => ("resource-type", t.to_string()),
+                    ResourceTypeOrName::Name(n) => ("resource", n.to_string()),
+                };
+                let mut args = BTreeMap::new();
+                args.insert("at".to_string(), node_name.clone());
+                args.insert(resource_key.to_string(), ArgValue::String(resource));
+                args.insert(
+                    "allow".to_string(),
+                    ArgValue::String(policy.expression().to_string()),
+                );
+                ResourceArgs { args }
+            })
+            .collect();
+
+        Ok(Config {
+            version: Version {
+                version: VersionValue::latest(),
+            },
+            vaults: Vaults { vaults: None },
+            identities: Identities { identities: None },
+            project_enroll: ProjectEnroll { ticket: None },
+            nodes: Nodes {
+                nodes: nodes.map(ResourcesContainer::NameOrMap),
+            },
+            policies: Policies {
+                policies: (!policies.is_empty()).then_some(UnnamedResources::List(policies)),
+            },
+            tcp_outlets: TcpOutlets { tcp_outlets },
+            tcp_inlets: TcpInlets { tcp_inlets },
+            kafka_inlet: KafkaInlet { kafka_inlet: None },
+            kafka_outlet: KafkaOutlet { kafka_outlet: None },
+            relays: Relays {
+                relays: relays.map(ResourcesContainer::NameOrMap),
+            },
+        })
+    }
+}
+
+/// Return a section of named resources, or None if there are no resources
+fn named_resources(
+    resources: impl Iterator<Item = (String, BTreeMap<String, ArgValue>)>,
+) -> Option<ResourceNameOrMap> {
+    let items: BTreeMap<_, _> = resources
+        .map(|(name, args)| (name, ResourceArgs { args }))
+        .collect();
+    if items.is_empty() {
+        None
+    } else {
+        Some(ResourceNameOrMap::NamedMap(NamedResources { items }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run::parser::config::ConfigParser;
+    use ockam_api::ConnectionStatus;
+    use ockam_core::Address;
+
+    #[test]
+    fn exported_configuration_can_be_run() {
+        let export = NodeExport {
+            node_name: "n1".to_string(),
+            identity_name: "i1".to_string(),
+            tcp_listener_address: Some("127.0.0.1:6000".to_string()),
+            project: None,
+            inlets: vec![InletStatus::new(
+                "127.0.0.1:5432",
+                None,
+                "db-inlet",
+                None,
+                None,
+                ConnectionStatus::Up,
+                "/project/default/service/forward_to_n2/secure/api/service/db-outlet",
+            )],
+            outlets: vec![OutletStatus::new(
+                "127.0.0.1:5000".parse().unwrap(),
+                Address::from_string("db-outlet"),
+                None,
+            )],
+            relays: vec![],
+            policies: vec![],
+        };
+        let contents = export.into_yaml().unwrap();
+        assert!(!contents.contains("null"));
+
+        let config = Config::parse(&contents).unwrap();
+        let nodes = config.nodes.into_parsed_commands().unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].name, "n1");
+        assert_eq!(nodes[0].identity, Some("i1".to_string()));
+        assert_eq!(nodes[0].tcp_listener_address, "127.0.0.1:6000");
+
+        let inlets = config.tcp_inlets.into_parsed_commands(None).unwrap();
+        assert_eq!(inlets.len(), 1);
+        assert_eq!(inlets[0].alias, "db-inlet");
+        assert_eq!(inlets[0].at, Some("n1".to_string()));
+        assert_eq!(inlets[0].from.to_string(), "127.0.0.1:5432");
+
+        let outlets = config.tcp_outlets.into_parsed_commands(None).unwrap();
+        assert_eq!(outlets.len(), 1);
+        assert_eq!(outlets[0].from, Some("db-outlet".to_string()));
+        assert_eq!(outlets[0].to.to_string(), "127.0.0.1:5000");
+
+        assert!(config.relays.into_parsed_commands(None).unwrap().is_empty());
+    }
+}
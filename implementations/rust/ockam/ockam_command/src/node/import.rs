@@ -0,0 +1,53 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::{Context as _, IntoDiagnostic};
+
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+use ockam_node::Context;
+
+use crate::run::Config;
+use crate::{docs, Command, CommandGlobalOpts, Result};
+
+const LONG_ABOUT: &str = include_str!("./static/import/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/import/after_long_help.txt");
+
+/// Create a node from a configuration file exported with `ockam node export`
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ImportCommand {
+    /// Path of the exported node configuration
+    #[arg(value_name = "PATH")]
+    file: PathBuf,
+}
+
+#[async_trait]
+impl Command for ImportCommand {
+    const NAME: &'static str = "node import";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        let contents = std::fs::read_to_string(&self.file)
+            .into_diagnostic()
+            .context(format!(
+                "Failed to read the node configuration at {}",
+                self.file.display()
+            ))?;
+        Config::parse_and_run(ctx, opts.clone(), &contents).await?;
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The node configuration at {} has been imported",
+                color_primary(self.file.display().to_string())
+            ))
+            .write_line()?;
+        Ok(())
+    }
+}
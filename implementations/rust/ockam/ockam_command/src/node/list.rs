@@ -2,7 +2,7 @@ use clap::Args;
 use colorful::Colorful;
 use indoc::formatdoc;
 use miette::IntoDiagnostic;
-use ockam_api::cli_state::NodeProcessStatus;
+use ockam_api::cli_state::{NodeHeartbeat, NodeLiveness, NodeProcessStatus};
 use serde::Serialize;
 use tokio::sync::Mutex;
 use tokio::try_join;
@@ -67,8 +67,10 @@ pub async fn get_nodes_info(
 
         let get_node_status = async {
             let node = opts.state.get_node(&node_name).await?;
+            let heartbeat = opts.state.get_node_heartbeat(&node_name).await?;
+            let liveness = opts.state.get_node_liveness(&node_name).await?;
             *is_finished.lock().await = true;
-            Ok(node)
+            Ok((node, heartbeat, liveness))
         };
 
         let output_messages = vec![format!(
@@ -79,9 +81,9 @@ pub async fn get_nodes_info(
         )];
         let progress_output = opts.terminal.loop_messages(&output_messages, &is_finished);
 
-        let (node, _) = try_join!(get_node_status, progress_output)?;
+        let ((node, heartbeat, liveness), _) = try_join!(get_node_status, progress_output)?;
 
-        nodes.push(NodeListOutput::from_node_info(&node));
+        nodes.push(NodeListOutput::from_node_info(&node, heartbeat, liveness));
     }
 
     Ok(nodes)
@@ -111,6 +113,8 @@ pub fn print_nodes_info(
 pub struct NodeListOutput {
     pub node_name: String,
     pub status: NodeProcessStatus,
+    pub liveness: NodeLiveness,
+    pub heartbeat: Option<NodeHeartbeat>,
     pub pid: Option<u32>,
     pub is_default: bool,
 }
@@ -119,21 +123,31 @@ impl NodeListOutput {
     pub fn new(
         node_name: String,
         status: NodeProcessStatus,
+        liveness: NodeLiveness,
+        heartbeat: Option<NodeHeartbeat>,
         pid: Option<u32>,
         is_default: bool,
     ) -> Self {
         Self {
             node_name,
             status,
+            liveness,
+            heartbeat,
             pid,
             is_default,
         }
     }
 
-    pub fn from_node_info(node_info: &NodeInfo) -> Self {
+    pub fn from_node_info(
+        node_info: &NodeInfo,
+        heartbeat: Option<NodeHeartbeat>,
+        liveness: NodeLiveness,
+    ) -> Self {
         Self::new(
             node_info.name(),
             node_info.status(),
+            liveness,
+            heartbeat,
             node_info.pid(),
             node_info.is_default(),
         )
@@ -142,25 +156,39 @@ impl NodeListOutput {
 
 impl Output for NodeListOutput {
     fn item(&self) -> ockam_api::Result<String> {
-        let (status, process) = match self.status {
-            NodeProcessStatus::Running(pid) => (
-                "UP".color(OckamColor::Success.color()),
-                format!(
-                    "Process id {}",
-                    pid.to_string().color(OckamColor::PrimaryResource.color())
-                ),
+        let process = match self.status {
+            NodeProcessStatus::Running(pid) | NodeProcessStatus::Zombie(pid) => format!(
+                "Process id {}",
+                pid.to_string().color(OckamColor::PrimaryResource.color())
             ),
-            NodeProcessStatus::Zombie(pid) => (
-                "ZOMBIE".color(OckamColor::Failure.color()),
+            NodeProcessStatus::Stopped => "No process running".to_string(),
+        };
+
+        let status = match (&self.status, &self.liveness) {
+            (NodeProcessStatus::Zombie(_), _) => "ZOMBIE".color(OckamColor::Failure.color()),
+            (_, NodeLiveness::Up) => "UP".color(OckamColor::Success.color()),
+            (_, NodeLiveness::Stale) => "STALE".color(OckamColor::FmtWARNBackground.color()),
+            (_, NodeLiveness::Down) => "DOWN".color(OckamColor::Failure.color()),
+        };
+
+        // the heartbeat of a node which is not running is not relevant anymore
+        let heartbeat = match (&self.heartbeat, &self.liveness) {
+            (Some(heartbeat), NodeLiveness::Up | NodeLiveness::Stale) => {
+                let elapsed = ockam::identity::utils::now()?
+                    .0
+                    .saturating_sub(heartbeat.last_seen_at);
                 format!(
-                    "Process id {}",
-                    pid.to_string().color(OckamColor::PrimaryResource.color())
-                ),
-            ),
-            NodeProcessStatus::Stopped => (
-                "DOWN".color(OckamColor::Failure.color()),
-                "No process running".to_string(),
-            ),
+                    "\nLast seen {} seconds ago, with {} workers",
+                    elapsed
+                        .to_string()
+                        .color(OckamColor::PrimaryResource.color()),
+                    heartbeat
+                        .workers_count
+                        .to_string()
+                        .color(OckamColor::PrimaryResource.color())
+                )
+            }
+            _ => "".to_string(),
         };
 
         let default = match self.is_default {
@@ -170,7 +198,7 @@ impl Output for NodeListOutput {
 
         let output = formatdoc! {"
         Node {node_name}{default} {status}
-        {process}",
+        {process}{heartbeat}",
         node_name = self
             .node_name
             .to_string()
@@ -179,4 +207,8 @@ impl Output for NodeListOutput {
 
         Ok(output)
     }
+
+    fn as_fields(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(self).ok()
+    }
 }
@@ -1,5 +1,13 @@
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::Duration;
+
 use clap::Args;
 use colorful::Colorful;
+use miette::IntoDiagnostic;
+use tracing::Level;
+
 use ockam_api::fmt_ok;
 
 use crate::util::async_cmd;
@@ -9,6 +17,9 @@ const LONG_ABOUT: &str = include_str!("./static/logs/long_about.txt");
 const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/logs/after_long_help.txt");
 
+/// Time to wait before checking the log file for new lines when following it
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
 /// Get the stdout/stderr log file of a node
 #[derive(Clone, Debug, Args)]
 #[command(
@@ -19,6 +30,19 @@ after_long_help = docs::after_help(AFTER_LONG_HELP)
 pub struct LogCommand {
     /// Name of the node to retrieve the logs from.
     node_name: Option<String>,
+
+    /// Keep printing new log lines as the node writes them
+    #[arg(long, short)]
+    follow: bool,
+
+    /// Only print the last N lines of the log file
+    #[arg(long, short = 'n', value_name = "N")]
+    lines: Option<usize>,
+
+    /// Only print the log lines with the given level or a more severe one
+    /// (error, warn, info, debug, trace)
+    #[arg(long, value_name = "LEVEL", value_parser = parse_level)]
+    level: Option<Level>,
 }
 
 impl LogCommand {
@@ -37,13 +61,192 @@ impl LogCommand {
             .get_node_or_default(&self.node_name)
             .await?
             .name();
-        let log_path = opts.state.stdout_logs(&node_name)?.display().to_string();
-        opts.terminal
-            .stdout()
-            .plain(fmt_ok!("The path for the log file is: {log_path}"))
-            .machine(&log_path)
-            .json(serde_json::json!({ "path": log_path }))
-            .write_line()?;
-        Ok(())
+        let log_path = opts.state.stdout_logs(&node_name)?;
+
+        // Without any of the printing arguments, only return the path to the log file
+        if !self.follow && self.lines.is_none() && self.level.is_none() {
+            let log_path = log_path.display().to_string();
+            opts.terminal
+                .stdout()
+                .plain(fmt_ok!("The path for the log file is: {log_path}"))
+                .machine(&log_path)
+                .json(serde_json::json!({ "path": log_path }))
+                .write_line()?;
+            return Ok(());
+        }
+
+        let mut filter = LevelFilter::new(self.level);
+        let mut position = self.print_last_lines(&log_path, &mut filter)?;
+        if !self.follow {
+            return Ok(());
+        }
+
+        let mut log_path = log_path;
+        loop {
+            tokio::time::sleep(FOLLOW_POLL_INTERVAL).await;
+
+            // The node rotates its log files, in which case we continue with the newest one
+            if let Ok(current_log_path) = opts.state.stdout_logs(&node_name) {
+                if current_log_path != log_path {
+                    log_path = current_log_path;
+                    position = 0;
+                }
+            }
+            position = print_lines_from(&log_path, position, &mut filter)?;
+        }
+    }
+
+    /// Print the existing content of the log file, limited to the last `--lines` lines,
+    /// and return the position reached in the file
+    fn print_last_lines(&self, log_path: &Path, filter: &mut LevelFilter) -> miette::Result<u64> {
+        let mut file = std::fs::File::open(log_path).into_diagnostic()?;
+        let mut lines = VecDeque::new();
+        let mut reader = BufReader::new(&mut file);
+        let mut line = String::new();
+        while reader.read_line(&mut line).into_diagnostic()? > 0 {
+            if filter.accept(&line) {
+                if self.lines.is_some_and(|max| lines.len() == max) {
+                    lines.pop_front();
+                }
+                if self.lines != Some(0) {
+                    lines.push_back(line.clone());
+                }
+            }
+            line.clear();
+        }
+        let position = reader.stream_position().into_diagnostic()?;
+
+        let mut stdout = std::io::stdout().lock();
+        for line in lines {
+            stdout.write_all(line.as_bytes()).into_diagnostic()?;
+        }
+        stdout.flush().into_diagnostic()?;
+        Ok(position)
+    }
+}
+
+/// Print the complete lines written to the log file after the given position
+/// and return the new position in the file
+fn print_lines_from(
+    log_path: &Path,
+    position: u64,
+    filter: &mut LevelFilter,
+) -> miette::Result<u64> {
+    let mut file = std::fs::File::open(log_path).into_diagnostic()?;
+    // If the file was truncated, start again from its beginning
+    let position = if file.metadata().into_diagnostic()?.len() < position {
+        0
+    } else {
+        position
+    };
+    file.seek(SeekFrom::Start(position)).into_diagnostic()?;
+
+    let mut reader = BufReader::new(file);
+    let mut position = position;
+    let mut line = String::new();
+    let mut stdout = std::io::stdout().lock();
+    loop {
+        let read = reader.read_line(&mut line).into_diagnostic()?;
+        // Partial lines are read again once the node finished writing them
+        if read == 0 || !line.ends_with('\n') {
+            break;
+        }
+        position += read as u64;
+        if filter.accept(&line) {
+            stdout.write_all(line.as_bytes()).into_diagnostic()?;
+        }
+        line.clear();
+    }
+    stdout.flush().into_diagnostic()?;
+    Ok(position)
+}
+
+fn parse_level(level: &str) -> Result<Level, String> {
+    level
+        .parse()
+        .map_err(|_| format!("'{level}' is not a valid log level"))
+}
+
+/// Filter log lines by their level.
+///
+/// Lines without a level (for example the continuation of a multi-line message)
+/// are accepted when the last line with a level was accepted.
+struct LevelFilter {
+    level: Option<Level>,
+    last_accepted: bool,
+}
+
+impl LevelFilter {
+    fn new(level: Option<Level>) -> Self {
+        Self {
+            level,
+            last_accepted: true,
+        }
+    }
+
+    fn accept(&mut self, line: &str) -> bool {
+        let level = match self.level {
+            Some(level) => level,
+            None => return true,
+        };
+        if let Some(line_level) = line_level(line) {
+            // More verbose levels are "greater" than less verbose ones
+            self.last_accepted = line_level <= level;
+        }
+        self.last_accepted
+    }
+}
+
+/// Return the level of a log line, when it can be found in the first words of the line
+/// or in the `level` field of a JSON formatted line
+fn line_level(line: &str) -> Option<Level> {
+    let line = console::strip_ansi_codes(line);
+    line.split(|c: char| c.is_whitespace() || c == '"')
+        .filter(|word| !word.is_empty())
+        .take(12)
+        .find_map(|word| match word {
+            "TRACE" => Some(Level::TRACE),
+            "DEBUG" => Some(Level::DEBUG),
+            "INFO" => Some(Level::INFO),
+            "WARN" => Some(Level::WARN),
+            "ERROR" => Some(Level::ERROR),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_the_level_of_a_line() {
+        assert_eq!(
+            line_level("2024-06-10T10:00:00.000000Z  INFO ockam_node: node started"),
+            Some(Level::INFO)
+        );
+        assert_eq!(
+            line_level(
+                "\u{1b}[2m2024-06-10T10:00:00Z\u{1b}[0m \u{1b}[33m WARN\u{1b}[0m ockam: slow"
+            ),
+            Some(Level::WARN)
+        );
+        assert_eq!(
+            line_level(r#"{"timestamp":"2024-06-10T10:00:00Z","level":"ERROR","fields":{}}"#),
+            Some(Level::ERROR)
+        );
+        assert_eq!(line_level("    at src/main.rs:10"), None);
+    }
+
+    #[test]
+    fn filter_lines_by_level() {
+        let mut filter = LevelFilter::new(Some(Level::WARN));
+        assert!(filter.accept("2024-06-10T10:00:00Z ERROR ockam: failure"));
+        assert!(filter.accept("    caused by: something"));
+        assert!(!filter.accept("2024-06-10T10:00:00Z  INFO ockam: started"));
+        assert!(!filter.accept("    with some details"));
+        assert!(filter.accept("2024-06-10T10:00:00Z  WARN ockam: slow"));
+
+        let mut filter = LevelFilter::new(None);
+        assert!(filter.accept("2024-06-10T10:00:00Z TRACE ockam: details"));
     }
 }
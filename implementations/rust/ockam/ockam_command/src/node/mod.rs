@@ -2,25 +2,41 @@ use clap::{Args, Subcommand};
 
 pub use create::CreateCommand;
 pub use create::*;
+use debug_graph::DebugGraphCommand;
 use default::DefaultCommand;
 use delete::DeleteCommand;
+use discover::DiscoverCommand;
+use env::EnvCommand;
+use export::ExportCommand;
+use import::ImportCommand;
 use list::ListCommand;
 use logs::LogCommand;
 use ockam_api::address::extract_address_value;
+use restart::RestartCommand;
 use show::ShowCommand;
 use start::StartCommand;
 use stop::StopCommand;
+use top::TopCommand;
+use trace::TraceCommand;
 
 use crate::{docs, Command, CommandGlobalOpts};
 
 mod create;
+mod debug_graph;
 mod default;
 mod delete;
+mod discover;
+mod env;
+mod export;
+mod import;
 mod list;
 mod logs;
+pub(crate) mod restart;
 pub(crate) mod show;
 mod start;
 mod stop;
+mod top;
+mod trace;
 pub mod util;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
@@ -53,15 +69,31 @@ pub enum NodeSubcommand {
     #[command(display_order = 800)]
     Delete(DeleteCommand),
     #[command(display_order = 800)]
+    Discover(DiscoverCommand),
+    #[command(display_order = 800)]
+    Env(EnvCommand),
+    #[command(display_order = 800)]
+    Export(ExportCommand),
+    #[command(display_order = 800)]
+    Import(ImportCommand),
+    #[command(display_order = 800)]
     List(ListCommand),
     #[command(display_order = 800)]
     Logs(LogCommand),
+    #[command(display_order = 800)]
+    Restart(RestartCommand),
     Show(ShowCommand),
     #[command(display_order = 800)]
     Start(StartCommand),
     #[command(display_order = 800)]
     Stop(StopCommand),
     #[command(display_order = 800)]
+    Top(TopCommand),
+    #[command(display_order = 800)]
+    DebugGraph(DebugGraphCommand),
+    #[command(display_order = 800)]
+    Trace(TraceCommand),
+    #[command(display_order = 800)]
     Default(DefaultCommand),
 }
 
@@ -70,11 +102,19 @@ impl NodeSubcommand {
         match self {
             NodeSubcommand::Create(c) => c.name(),
             NodeSubcommand::Delete(c) => c.name(),
+            NodeSubcommand::Discover(c) => c.name(),
+            NodeSubcommand::Env(c) => c.name(),
+            NodeSubcommand::Export(c) => c.name(),
+            NodeSubcommand::Import(c) => c.name(),
             NodeSubcommand::List(c) => c.name(),
             NodeSubcommand::Logs(c) => c.name(),
+            NodeSubcommand::Restart(c) => c.name(),
             NodeSubcommand::Show(c) => c.name(),
             NodeSubcommand::Start(c) => c.name(),
             NodeSubcommand::Stop(c) => c.name(),
+            NodeSubcommand::Top(c) => c.name(),
+            NodeSubcommand::DebugGraph(c) => c.name(),
+            NodeSubcommand::Trace(c) => c.name(),
             NodeSubcommand::Default(c) => c.name(),
         }
     }
@@ -85,11 +125,19 @@ impl NodeCommand {
         match self.subcommand {
             NodeSubcommand::Create(c) => c.run(opts),
             NodeSubcommand::Delete(c) => c.run(opts),
+            NodeSubcommand::Discover(c) => c.run(opts),
+            NodeSubcommand::Env(c) => c.run(opts),
+            NodeSubcommand::Export(c) => c.run(opts),
+            NodeSubcommand::Import(c) => c.run(opts),
             NodeSubcommand::List(c) => c.run(opts),
             NodeSubcommand::Show(c) => c.run(opts),
             NodeSubcommand::Start(c) => c.run(opts),
             NodeSubcommand::Stop(c) => c.run(opts),
+            NodeSubcommand::Top(c) => c.run(opts),
+            NodeSubcommand::Restart(c) => c.run(opts),
             NodeSubcommand::Logs(c) => c.run(opts),
+            NodeSubcommand::DebugGraph(c) => c.run(opts),
+            NodeSubcommand::Trace(c) => c.run(opts),
             NodeSubcommand::Default(c) => c.run(opts),
         }
     }
@@ -0,0 +1,192 @@
+use std::fmt::Write;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+use serde::Serialize;
+
+use ockam_api::cli_state::NodeInfo;
+use ockam_api::colors::color_primary;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_api::{fmt_log, fmt_ok, fmt_warn};
+use ockam_node::Context;
+
+use crate::node::show::get_node_resources;
+use crate::node::util::{run_ockam, spawn_node};
+use crate::node::CreateCommand;
+use crate::{docs, Command, CommandGlobalOpts, Result};
+
+const LONG_ABOUT: &str = include_str!("./static/restart/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/restart/after_long_help.txt");
+
+/// Restart a node with the same configuration
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct RestartCommand {
+    /// Name of the node to restart.
+    /// If not provided, the default node is restarted.
+    node_name: Option<String>,
+
+    /// Whether to use the SIGTERM or SIGKILL signal to stop the node
+    #[arg(short, long)]
+    force: bool,
+}
+
+#[async_trait]
+impl Command for RestartCommand {
+    const NAME: &'static str = "node restart";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        let node_info = opts.state.get_node_or_default(&self.node_name).await?;
+        let output = restart_node(ctx, opts.clone(), &node_info, self.force).await?;
+        opts.terminal
+            .stdout()
+            .plain(output.plain()?)
+            .machine(&output.node_name)
+            .json(serde_json::to_string(&output).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+/// Stop a node, if it is running, and start it again with the same configuration
+pub(crate) async fn restart_node(
+    ctx: &Context,
+    mut opts: CommandGlobalOpts,
+    node_info: &NodeInfo,
+    force: bool,
+) -> Result<RestartOutput> {
+    let node_name = node_info.name();
+    if node_info.is_authority_node() {
+        return Err(miette!(
+            "The node {} is an authority node. Use `ockam authority create` to restart it",
+            color_primary(&node_name)
+        ))?;
+    }
+
+    // Stop the node and check that its process has exited before starting it again
+    let previous_pid = node_info.pid().filter(|_| node_info.is_running());
+    match previous_pid {
+        Some(pid) => {
+            opts.state.stop_node(&node_name, force).await?;
+            if node_info.is_running() {
+                return Err(miette!(
+                    "The process {} of the node {} did not exit. Use --force to kill it",
+                    color_primary(pid.to_string()),
+                    color_primary(&node_name)
+                ))?;
+            }
+        }
+        None => {
+            opts.terminal.write_line(fmt_warn!(
+                "The node {} was not running, starting it",
+                color_primary(&node_name)
+            ))?;
+        }
+    }
+
+    // Start the node with the arguments it was originally created with.
+    // Nodes created before those arguments were stored are restarted from their
+    // persisted verbosity and configuration
+    match opts.state.get_node_create_arguments(&node_name)? {
+        Some(arguments) => run_ockam(arguments, opts.global_args.quiet).await?,
+        None => {
+            opts.global_args.verbose = node_info.verbosity();
+            let cmd = create_command_from_node_info(&opts, node_info).await?;
+            spawn_node(&opts, cmd).await?;
+        }
+    }
+
+    let mut node = BackgroundNodeClient::create_to_node(ctx, &opts.state, &node_name).await?;
+    let node_resources = get_node_resources(ctx, &opts.state, &mut node, true).await?;
+    if !node_resources.status.is_running() {
+        return Err(miette!(
+            "The node {} was restarted but is not reachable. Check its logs with `ockam node logs {}`",
+            color_primary(&node_name),
+            node_name
+        ))?;
+    }
+
+    let restarted = opts.state.get_node(&node_name).await?;
+    Ok(RestartOutput {
+        node_name,
+        previous_pid,
+        pid: restarted.pid(),
+        tcp_listener_address: restarted.tcp_listener_address().map(|a| a.to_string()),
+    })
+}
+
+/// Return the command used to start a node, for which no create arguments were stored,
+/// with its persisted configuration: its identity, project, TCP listener address and
+/// HTTP server port
+async fn create_command_from_node_info(
+    opts: &CommandGlobalOpts,
+    node_info: &NodeInfo,
+) -> Result<CreateCommand> {
+    let node_name = node_info.name();
+    let identity = opts
+        .state
+        .get_named_identity_by_identifier(&node_info.identifier())
+        .await?;
+    let project_name = opts
+        .state
+        .get_node_project(&node_name)
+        .await
+        .ok()
+        .map(|p| p.name().to_string());
+
+    let mut cmd = CreateCommand {
+        name: node_name,
+        identity: Some(identity.name()),
+        http_server_port: node_info.http_server_address().map(|a| a.port()),
+        ..Default::default()
+    };
+    if let Some(address) = node_info.tcp_listener_address() {
+        cmd.tcp_listener_address = address.to_string();
+    }
+    cmd.trust_opts.project_name = project_name;
+    Ok(cmd)
+}
+
+#[derive(Serialize)]
+pub(crate) struct RestartOutput {
+    pub(crate) node_name: String,
+    previous_pid: Option<u32>,
+    pid: Option<u32>,
+    tcp_listener_address: Option<String>,
+}
+
+impl RestartOutput {
+    pub(crate) fn plain(&self) -> Result<String> {
+        let mut buf = String::new();
+        writeln!(
+            buf,
+            "{}",
+            fmt_ok!(
+                "The node {} has been restarted",
+                color_primary(&self.node_name)
+            )
+        )?;
+        if let Some(pid) = self.pid {
+            writeln!(
+                buf,
+                "{}",
+                fmt_log!("PID: {}", color_primary(pid.to_string()))
+            )?;
+        }
+        if let Some(address) = &self.tcp_listener_address {
+            writeln!(
+                buf,
+                "{}",
+                fmt_log!("TCP listener address: {}", color_primary(address))
+            )?;
+        }
+        Ok(buf)
+    }
+}
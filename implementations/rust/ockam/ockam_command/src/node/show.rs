@@ -42,6 +42,11 @@ pub struct ShowCommand {
     /// The name of the node from which to fetch the details.
     /// If not provided, the default node is used.
     node_name: Option<String>,
+
+    /// Read the names of the nodes to show from stdin, one per line or as a JSON array.
+    /// The result of each operation is reported separately
+    #[arg(long, conflicts_with = "node_name")]
+    stdin: bool,
 }
 
 #[async_trait]
@@ -49,7 +54,7 @@ impl Command for ShowCommand {
     const NAME: &'static str = "node show";
 
     async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
-        Ok(ShowTui::run(ctx, opts, self.node_name.clone()).await?)
+        Ok(ShowTui::run(ctx, opts, self.node_name.clone(), self.stdin).await?)
     }
 }
 
@@ -57,6 +62,7 @@ pub struct ShowTui {
     ctx: Context,
     opts: CommandGlobalOpts,
     node_name: Option<String>,
+    stdin: bool,
 }
 
 impl ShowTui {
@@ -64,11 +70,13 @@ impl ShowTui {
         ctx: &Context,
         opts: CommandGlobalOpts,
         node_name: Option<String>,
+        stdin: bool,
     ) -> miette::Result<()> {
         let tui = Self {
             ctx: ctx.async_try_clone().await.into_diagnostic()?,
             opts,
             node_name,
+            stdin,
         };
         tui.show().await
     }
@@ -82,6 +90,10 @@ impl ShowCommandTui for ShowTui {
         self.node_name.clone()
     }
 
+    fn cmd_arg_stdin(&self) -> bool {
+        self.stdin
+    }
+
     fn terminal(&self) -> Terminal<TerminalStream<Term>> {
         self.opts.terminal.clone()
     }
@@ -234,3 +246,34 @@ async fn is_node_ready(
     }
     Ok(false)
 }
+
+/// Return true once the node has started all the services it was configured with,
+/// or false if it didn't happen before the given timeout
+pub async fn wait_until_node_services_are_initialized(
+    ctx: &Context,
+    node: &BackgroundNodeClient,
+    timeout: Duration,
+) -> Result<bool> {
+    let node_name = node.node_name();
+    let retries = FibonacciBackoff::from_millis(IS_NODE_READY_TIME_BETWEEN_CHECKS_MS)
+        .max_delay(Duration::from_secs(1));
+    let now = std::time::Instant::now();
+    for retry_duration in retries {
+        let result = node
+            .ask_with_timeout::<(), NodeStatus>(ctx, api::query_status(), retry_duration)
+            .await;
+        match result {
+            Ok(node_status) if node_status.is_ready() => {
+                let elapsed = now.elapsed();
+                info!(%node_name, ?elapsed, "node services are initialized");
+                return Ok(true);
+            }
+            _ => trace!(%node_name, "node services are initializing"),
+        }
+        if now.elapsed() >= timeout {
+            return Ok(false);
+        }
+        tokio::time::sleep(retry_duration).await;
+    }
+    Ok(false)
+}
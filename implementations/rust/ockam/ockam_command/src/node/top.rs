@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use clap::Args;
+use console::Term;
+use miette::IntoDiagnostic;
+use serde::Serialize;
+
+use ockam_api::colors::color_primary;
+use ockam_api::nodes::models::node::NodeStats;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_core::api::Request;
+use ockam_node::Context;
+
+use crate::util::parsers::duration_parser;
+use crate::{docs, Command, CommandGlobalOpts, Result};
+
+const LONG_ABOUT: &str = include_str!("./static/top/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/top/after_long_help.txt");
+
+/// Display the resources used by a node and the messages handled by its workers
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct TopCommand {
+    /// Name of the node to monitor.
+    /// If not provided, the default node is used.
+    node_name: Option<String>,
+
+    /// Time to wait between two refreshes of the stats
+    #[arg(long, value_name = "DURATION", default_value = "2s", value_parser = duration_parser)]
+    interval: Duration,
+
+    /// Number of refreshes before exiting.
+    /// If not provided, the stats are refreshed until the command is interrupted.
+    #[arg(long, short = 'n', value_name = "COUNT")]
+    iterations: Option<u32>,
+}
+
+#[async_trait]
+impl Command for TopCommand {
+    const NAME: &'static str = "node top";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_name).await?;
+        let is_json = opts.global_args.output_format()?.is_json();
+        let stdout = Term::stdout();
+
+        let mut previous: Option<(Instant, NodeStats)> = None;
+        let mut iteration = 0;
+        loop {
+            let stats: NodeStats = node.ask(ctx, Request::get("/node/stats")).await?;
+            let now = Instant::now();
+            let top = NodeTop::new(
+                &stats,
+                previous
+                    .as_ref()
+                    .map(|(time, stats)| (now.duration_since(*time), stats)),
+            );
+
+            if is_json {
+                // Print one JSON object per line so that the output can be consumed as JSON lines
+                let mut out = std::io::stdout().lock();
+                writeln!(out, "{}", serde_json::to_string(&top).into_diagnostic()?)
+                    .into_diagnostic()?;
+                out.flush().into_diagnostic()?;
+            } else {
+                if stdout.is_term() {
+                    stdout.clear_screen().into_diagnostic()?;
+                }
+                stdout.write_str(&top.render()).into_diagnostic()?;
+                if !stdout.is_term() {
+                    stdout.write_line("").into_diagnostic()?;
+                }
+            }
+
+            previous = Some((now, stats));
+            iteration += 1;
+            if self.iterations.is_some_and(|n| iteration >= n) {
+                return Ok(());
+            }
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+}
+
+/// Stats of a node, with the messages throughput of its workers since the previous refresh
+#[derive(Debug, Serialize)]
+struct NodeTop {
+    node_name: String,
+    pid: u32,
+    cpu_usage: f32,
+    memory: u64,
+    workers: Vec<WorkerTop>,
+}
+
+#[derive(Debug, Serialize)]
+struct WorkerTop {
+    address: String,
+    worker_type: String,
+    mailbox_count: u64,
+    messages_count: u64,
+    messages_per_second: f64,
+}
+
+impl NodeTop {
+    /// Compute the throughput of each worker from the stats of the previous refresh, if any.
+    /// The workers are sorted by decreasing throughput, then by decreasing number of messages.
+    fn new(stats: &NodeStats, previous: Option<(Duration, &NodeStats)>) -> Self {
+        let previous_counts: HashMap<&str, u64> = previous
+            .map(|(_, stats)| {
+                stats
+                    .workers
+                    .iter()
+                    .map(|w| (w.addr.as_str(), w.messages_count))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let elapsed = previous.map(|(elapsed, _)| elapsed.as_secs_f64());
+
+        let mut workers: Vec<WorkerTop> = stats
+            .workers
+            .iter()
+            .map(|w| {
+                let messages_per_second = match (elapsed, previous_counts.get(w.addr.as_str())) {
+                    (Some(elapsed), Some(count)) if elapsed > 0.0 => {
+                        w.messages_count.saturating_sub(*count) as f64 / elapsed
+                    }
+                    _ => 0.0,
+                };
+                WorkerTop {
+                    address: w.addr.clone(),
+                    worker_type: w.worker_type.clone(),
+                    mailbox_count: w.mailbox_count,
+                    messages_count: w.messages_count,
+                    messages_per_second,
+                }
+            })
+            .collect();
+        workers.sort_by(|a, b| {
+            b.messages_per_second
+                .total_cmp(&a.messages_per_second)
+                .then(b.messages_count.cmp(&a.messages_count))
+                .then(a.address.cmp(&b.address))
+        });
+
+        Self {
+            node_name: stats.name.clone(),
+            pid: stats.pid,
+            cpu_usage: stats.cpu_usage,
+            memory: stats.memory,
+            workers,
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut out = String::new();
+        let _ = self.write(&mut out);
+        out
+    }
+
+    fn write(&self, out: &mut String) -> std::fmt::Result {
+        writeln!(
+            out,
+            "Node {}  PID {}  CPU {:.1}%  Memory {}  Workers {}",
+            color_primary(&self.node_name),
+            self.pid,
+            self.cpu_usage,
+            format_bytes(self.memory),
+            self.workers.len()
+        )?;
+        writeln!(out)?;
+        let width = self
+            .workers
+            .iter()
+            .map(|w| w.address.len())
+            .chain(std::iter::once("ADDRESS".len()))
+            .max()
+            .unwrap_or_default();
+        writeln!(
+            out,
+            "{:width$}  {:9}  {:>7}  {:>9}  {:>9}",
+            "ADDRESS", "TYPE", "MAILBOX", "MSG/S", "TOTAL"
+        )?;
+        for worker in &self.workers {
+            writeln!(
+                out,
+                "{:width$}  {:9}  {:>7}  {:>9.1}  {:>9}",
+                worker.address,
+                worker.worker_type,
+                worker.mailbox_count,
+                worker.messages_per_second,
+                worker.messages_count
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_api::nodes::models::workers::WorkerStatus;
+
+    fn stats(workers: &[(&str, u64)]) -> NodeStats {
+        NodeStats {
+            name: "n".to_string(),
+            pid: 1,
+            cpu_usage: 0.0,
+            memory: 0,
+            workers: workers
+                .iter()
+                .map(|(addr, count)| WorkerStatus {
+                    addr: addr.to_string(),
+                    worker_type: "worker".to_string(),
+                    addresses: vec![addr.to_string()],
+                    mailbox_count: 0,
+                    messages_count: *count,
+                })
+                .collect(),
+            kafka_inlets: vec![],
+        }
+    }
+
+    #[test]
+    fn compute_the_workers_throughput() {
+        let first = stats(&[("a", 10), ("b", 5)]);
+        let top = NodeTop::new(&first, None);
+        assert!(top.workers.iter().all(|w| w.messages_per_second == 0.0));
+        assert_eq!(top.workers[0].address, "a");
+
+        // "c" is a new worker, "b" handled more messages than "a" since the previous refresh
+        let second = stats(&[("a", 12), ("b", 15), ("c", 3)]);
+        let top = NodeTop::new(&second, Some((Duration::from_secs(2), &first)));
+        let rates: Vec<(&str, f64)> = top
+            .workers
+            .iter()
+            .map(|w| (w.address.as_str(), w.messages_per_second))
+            .collect();
+        assert_eq!(rates, vec![("b", 5.0), ("a", 1.0), ("c", 0.0)]);
+    }
+
+    #[test]
+    fn format_memory() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(10 * 1024 * 1024), "10.0 MiB");
+    }
+}
@@ -0,0 +1,131 @@
+use std::io::Write as _;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use clap::Args;
+use miette::IntoDiagnostic;
+
+use ockam_api::colors::color_warn;
+use ockam_api::nodes::models::node::{StartTraceRequest, TraceEventsStatus, TraceSubscription};
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_core::api::Request;
+use ockam_node::Context;
+
+use crate::util::parsers::duration_parser;
+use crate::{docs, Command, CommandGlobalOpts, Result};
+
+const LONG_ABOUT: &str = include_str!("./static/trace/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/trace/after_long_help.txt");
+
+/// Trace the messages sent and received by the workers of a node
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct TraceCommand {
+    /// Name of the node to trace.
+    /// If not provided, the default node is used.
+    node_name: Option<String>,
+
+    /// Only trace the messages sent or received by this worker address.
+    /// Can be repeated to trace several workers.
+    #[arg(long = "address", value_name = "ADDRESS")]
+    addresses: Vec<String>,
+
+    /// Fraction of the messages to trace, between 0 and 1
+    #[arg(long, value_name = "RATE", default_value_t = 1.0, value_parser = sample_rate_parser)]
+    sample_rate: f64,
+
+    /// Time to wait between two polls of the traced messages
+    #[arg(long, value_name = "DURATION", default_value = "500ms", value_parser = duration_parser)]
+    interval: Duration,
+
+    /// Number of polls before exiting.
+    /// If not provided, the messages are traced until the command is interrupted.
+    #[arg(long, short = 'n', value_name = "COUNT")]
+    iterations: Option<u32>,
+}
+
+#[async_trait]
+impl Command for TraceCommand {
+    const NAME: &'static str = "node trace";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_name).await?;
+        let subscription: TraceSubscription = node
+            .ask(
+                ctx,
+                Request::post("/node/debug/trace").body(StartTraceRequest::new(
+                    self.addresses.clone(),
+                    self.sample_rate,
+                )),
+            )
+            .await?;
+        let path = format!("/node/debug/trace/{}", subscription.id);
+
+        let result = self.poll(ctx, &opts, &node, &path).await;
+        // The subscription expires on its own if this request fails
+        let _ = node.tell(ctx, Request::delete(&path)).await;
+        result
+    }
+}
+
+impl TraceCommand {
+    /// Print the traced messages until the command is interrupted or the number of iterations is reached
+    async fn poll(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        node: &BackgroundNodeClient,
+        path: &str,
+    ) -> Result<()> {
+        let is_json = opts.global_args.output_format()?.is_json();
+        let mut iteration = 0;
+        loop {
+            let events: TraceEventsStatus = node.ask(ctx, Request::get(path)).await?;
+            // The stdout lock is released before waiting for the next events
+            {
+                let mut out = std::io::stdout().lock();
+                if events.dropped > 0 && !is_json {
+                    writeln!(
+                        out,
+                        "{}",
+                        color_warn(format!("{} messages were not traced", events.dropped))
+                    )
+                    .into_diagnostic()?;
+                }
+                for event in events.events {
+                    if is_json {
+                        // Print one JSON object per line so that the output can be consumed as JSON lines
+                        writeln!(out, "{}", serde_json::to_string(&event).into_diagnostic()?)
+                    } else {
+                        writeln!(out, "{event}")
+                    }
+                    .into_diagnostic()?;
+                }
+                out.flush().into_diagnostic()?;
+            }
+
+            iteration += 1;
+            if self.iterations.is_some_and(|n| iteration >= n) {
+                return Ok(());
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(self.interval) => {}
+                _ = tokio::signal::ctrl_c() => return Ok(()),
+            }
+        }
+    }
+}
+
+fn sample_rate_parser(value: &str) -> std::result::Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(rate) if (0.0..=1.0).contains(&rate) => Ok(rate),
+        _ => Err(format!(
+            "the sample rate must be a number between 0 and 1, got '{value}'"
+        )),
+    }
+}
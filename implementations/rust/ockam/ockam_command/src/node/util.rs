@@ -79,6 +79,10 @@ pub async fn spawn_node(opts: &CommandGlobalOpts, cmd: CreateCommand) -> miette:
         enable_http_server,
         http_server_port,
         enable_udp,
+        udp_rendezvous_service,
+        udp_rendezvous_server,
+        enable_discovery,
+        tcp_socket_opts,
         launch_config,
         trust_opts,
         opentelemetry_context,
@@ -142,11 +146,6 @@ pub async fn spawn_node(opts: &CommandGlobalOpts, cmd: CreateCommand) -> miette:
         args.push(authority_route.to_string());
     }
 
-    if let Some(opentelemetry_context) = opentelemetry_context {
-        args.push("--opentelemetry-context".to_string());
-        args.push(opentelemetry_context.to_string());
-    }
-
     if enable_http_server {
         args.push("--enable-http-server".to_string());
     }
@@ -160,8 +159,33 @@ pub async fn spawn_node(opts: &CommandGlobalOpts, cmd: CreateCommand) -> miette:
         args.push("--enable-udp".to_string());
     }
 
+    if let Some(udp_rendezvous_service) = udp_rendezvous_service {
+        args.push("--udp-rendezvous-service".to_string());
+        args.push(udp_rendezvous_service);
+    }
+
+    if let Some(udp_rendezvous_server) = udp_rendezvous_server {
+        args.push("--udp-rendezvous-server".to_string());
+        args.push(udp_rendezvous_server);
+    }
+
+    if enable_discovery {
+        args.push("--enable-discovery".to_string());
+    }
+
+    args.extend(tcp_socket_opts.as_args());
+
     args.push(name.to_owned());
 
+    // Store the arguments so that the node can be restarted with the same options.
+    // The OpenTelemetry context is specific to this invocation and is not stored.
+    opts.state.set_node_create_arguments(&name, &args)?;
+
+    if let Some(opentelemetry_context) = opentelemetry_context {
+        args.push("--opentelemetry-context".to_string());
+        args.push(opentelemetry_context.to_string());
+    }
+
     run_ockam(args, opts.global_args.quiet).await
 }
 
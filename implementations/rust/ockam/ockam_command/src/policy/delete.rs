@@ -29,6 +29,11 @@ pub struct DeleteCommand {
     /// Confirm the deletion without prompting
     #[arg(display_order = 901, long, short)]
     yes: bool,
+
+    /// Read the names of the resources whose policies must be deleted from stdin, one per line or as a JSON array.
+    /// The result of each operation is reported separately
+    #[arg(long, conflicts_with = "resource")]
+    stdin: bool,
 }
 
 impl DeleteCommand {
@@ -79,6 +84,10 @@ impl DeleteCommandTui for DeleteTui {
         self.cmd.resource.clone().map(|r| r.to_string())
     }
 
+    fn cmd_arg_stdin(&self) -> bool {
+        self.cmd.stdin
+    }
+
     fn cmd_arg_delete_all(&self) -> bool {
         false
     }
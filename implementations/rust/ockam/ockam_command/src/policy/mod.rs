@@ -9,12 +9,16 @@ pub use crate::policy::create::CreateCommand;
 use crate::policy::delete::DeleteCommand;
 use crate::policy::list::ListCommand;
 use crate::policy::show::ShowCommand;
+use crate::policy::test::TestCommand;
+use crate::policy::validate::ValidateCommand;
 use crate::{Command, CommandGlobalOpts};
 
 mod create;
 mod delete;
 mod list;
 mod show;
+mod test;
+mod validate;
 
 #[derive(Clone, Debug, Args)]
 pub struct PolicyCommand {
@@ -29,6 +33,8 @@ pub enum PolicySubcommand {
     Show(ShowCommand),
     Delete(DeleteCommand),
     List(ListCommand),
+    Validate(ValidateCommand),
+    Test(TestCommand),
 }
 
 impl PolicySubcommand {
@@ -38,6 +44,8 @@ impl PolicySubcommand {
             PolicySubcommand::Show(c) => c.name(),
             PolicySubcommand::Delete(c) => c.name(),
             PolicySubcommand::List(c) => c.name(),
+            PolicySubcommand::Validate(c) => c.name(),
+            PolicySubcommand::Test(c) => c.name(),
         }
     }
 }
@@ -49,6 +57,8 @@ impl PolicyCommand {
             PolicySubcommand::Show(c) => c.run(opts),
             PolicySubcommand::Delete(c) => c.run(opts),
             PolicySubcommand::List(c) => c.run(opts),
+            PolicySubcommand::Validate(c) => c.run(opts),
+            PolicySubcommand::Test(c) => c.run(opts),
         }
     }
 
@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+use serde::Serialize;
+
+use ockam::identity::Identifier;
+use ockam::Context;
+use ockam_abac::{
+    eval, subject_has_credential_attribute, subject_identifier_attribute, Env, Expr, SUBJECT_KEY,
+};
+use ockam_api::colors::color_primary;
+use ockam_api::{fmt_log, fmt_ok, fmt_warn};
+
+use crate::{docs, Command, CommandGlobalOpts};
+
+use super::validate::parse_policy_expression;
+
+const LONG_ABOUT: &str = include_str!("./static/test/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/test/after_long_help.txt");
+
+/// Evaluate a policy expression against a set of subject attributes
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct TestCommand {
+    /// The policy expression to evaluate, either a boolean expression or a full policy expression
+    #[arg(id = "POLICY_EXPRESSION")]
+    pub expression: String,
+
+    /// Attributes of the subject in `key=value` format. You can specify this option multiple times for multiple attributes.
+    /// If no value is provided, the attribute is set to "true"
+    #[arg(short, long = "attribute", value_name = "ATTRIBUTE")]
+    pub attributes: Vec<String>,
+
+    /// Identifier of the subject
+    #[arg(long, value_name = "IDENTIFIER")]
+    pub identifier: Option<Identifier>,
+
+    /// Evaluate the expression for a subject which did not present any credential.
+    /// In that case, the subject has no attributes
+    #[arg(long, conflicts_with = "attributes")]
+    pub no_credential: bool,
+}
+
+#[async_trait]
+impl Command for TestCommand {
+    const NAME: &'static str = "policy test";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let expression = parse_policy_expression(&self.expression)?.to_expression();
+        let environment = self.environment()?;
+        let output = TestOutput::new(&expression, &environment);
+
+        let plain = if output.granted {
+            fmt_ok!("Access would be {}", color_primary("granted"))
+        } else {
+            fmt_warn!("Access would be {}", color_primary("denied"))
+        };
+        let plain = match &output.reason {
+            Some(reason) => plain + "\n" + &fmt_log!("{reason}"),
+            None => plain,
+        };
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .machine(output.granted)
+            .json(serde_json::to_string(&output).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+impl TestCommand {
+    /// Return the environment used to evaluate the policy expression.
+    /// It is built in the same way as when a policy is evaluated for an incoming or outgoing message.
+    fn environment(&self) -> crate::Result<Env> {
+        let mut environment = Env::new();
+        if let Some(identifier) = &self.identifier {
+            environment.put(
+                subject_identifier_attribute().to_string(),
+                Expr::Str(identifier.to_string()),
+            );
+        }
+        environment.put(
+            subject_has_credential_attribute().to_string(),
+            Expr::Bool(!self.no_credential),
+        );
+        for attribute in &self.attributes {
+            let mut parts = attribute.splitn(2, '=');
+            let key = parts
+                .next()
+                .filter(|k| !k.is_empty())
+                .ok_or(miette!("key expected in attribute {attribute}"))?;
+            let value = parts.next().unwrap_or("true");
+            environment.put(format!("{SUBJECT_KEY}.{key}"), Expr::Str(value.to_string()));
+        }
+        Ok(environment)
+    }
+}
+
+#[derive(Serialize)]
+struct TestOutput {
+    expression: String,
+    environment: BTreeMap<String, String>,
+    granted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+impl TestOutput {
+    fn new(expression: &Expr, environment: &Env) -> Self {
+        let (granted, reason) = match eval(expression, environment) {
+            Ok(Expr::Bool(b)) => (b, None),
+            Ok(e) => (
+                false,
+                Some(format!(
+                    "The expression evaluated to {e}, which is not a boolean"
+                )),
+            ),
+            Err(e) if e.is_unbound() => (
+                false,
+                Some(format!(
+                    "The evaluation failed because an attribute is missing: {e}"
+                )),
+            ),
+            Err(e) => (false, Some(format!("The evaluation failed: {e}"))),
+        };
+        Self {
+            expression: expression.to_string(),
+            environment: environment
+                .entries()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            granted,
+            reason,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test(expression: &str, attributes: &[&str]) -> TestOutput {
+        let cmd = TestCommand {
+            expression: expression.to_string(),
+            attributes: attributes.iter().map(|a| a.to_string()).collect(),
+            identifier: None,
+            no_credential: false,
+        };
+        let expression = parse_policy_expression(&cmd.expression)
+            .unwrap()
+            .to_expression();
+        TestOutput::new(&expression, &cmd.environment().unwrap())
+    }
+
+    #[test]
+    fn evaluate_a_policy_expression() {
+        assert!(test("(= subject.component \"db\")", &["component=db"]).granted);
+        assert!(!test("(= subject.component \"db\")", &["component=web"]).granted);
+        assert!(
+            test(
+                "component.db and component.web",
+                &["component.db", "component.web"]
+            )
+            .granted
+        );
+        assert!(test("(= subject.has_credential true)", &[]).granted);
+
+        let output = test("(= subject.component \"db\")", &[]);
+        assert!(!output.granted);
+        assert!(output.reason.unwrap().contains("subject.component"));
+    }
+}
@@ -0,0 +1,136 @@
+use std::fmt::Write;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+use serde::Serialize;
+
+use ockam::Context;
+use ockam_abac::{BooleanExpr, Expr, ParseError, PolicyExpression};
+use ockam_api::colors::color_primary;
+use ockam_api::{fmt_log, fmt_ok};
+
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/validate/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/validate/after_long_help.txt");
+
+/// Check the syntax of a policy expression
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ValidateCommand {
+    /// The policy expression to validate, either a boolean expression or a full policy expression
+    #[arg(id = "POLICY_EXPRESSION")]
+    pub expression: String,
+}
+
+#[async_trait]
+impl Command for ValidateCommand {
+    const NAME: &'static str = "policy validate";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let expression = parse_policy_expression(&self.expression)?;
+        let output = ValidateOutput::new(&expression);
+
+        opts.terminal
+            .stdout()
+            .plain(
+                fmt_ok!("The policy expression is valid\n")
+                    + &fmt_log!(
+                        "It is a {} expression, evaluated as {}",
+                        output.kind,
+                        color_primary(&output.expanded)
+                    ),
+            )
+            .machine(&output.expanded)
+            .json(serde_json::to_string(&output).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct ValidateOutput {
+    expression: String,
+    kind: &'static str,
+    expanded: String,
+}
+
+impl ValidateOutput {
+    fn new(expression: &PolicyExpression) -> Self {
+        Self {
+            expression: expression.to_string(),
+            kind: match expression {
+                PolicyExpression::BooleanExpression(_) => "boolean",
+                PolicyExpression::FullExpression(_) => "full",
+            },
+            expanded: expression.to_expression().to_string(),
+        }
+    }
+}
+
+/// Parse a policy expression, first as a boolean expression, then as a full policy expression.
+/// If both fail, the returned error describes the two parsing errors and points at their position
+/// in the expression.
+pub(super) fn parse_policy_expression(expression: &str) -> miette::Result<PolicyExpression> {
+    let boolean_error = match BooleanExpr::try_from(expression) {
+        Ok(e) => return Ok(PolicyExpression::BooleanExpression(e)),
+        Err(e) => e,
+    };
+    let full_error = match Expr::try_from(expression) {
+        Ok(e) => return Ok(PolicyExpression::FullExpression(e)),
+        Err(e) => e,
+    };
+    Err(miette!(
+        "The policy expression is invalid\n{}{}",
+        describe_error("As a boolean expression", expression, &boolean_error),
+        describe_error("As a full policy expression", expression, &full_error)
+    ))
+}
+
+fn describe_error(title: &str, expression: &str, error: &ParseError) -> String {
+    let mut f = String::new();
+    let _ = writeln!(f, " - {title}: {error}");
+    if let Some(position) = error.position() {
+        let column = expression
+            .char_indices()
+            .take_while(|(i, _)| *i < position)
+            .count();
+        let _ = writeln!(f, "     {expression}");
+        let _ = writeln!(f, "     {}^", " ".repeat(column));
+    }
+    f
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_boolean_and_full_expressions() {
+        let expression = parse_policy_expression("component.db or component.web").unwrap();
+        assert!(matches!(expression, PolicyExpression::BooleanExpression(_)));
+
+        let expression = parse_policy_expression("(= subject.component \"db\")").unwrap();
+        assert!(matches!(expression, PolicyExpression::FullExpression(_)));
+    }
+
+    #[test]
+    fn invalid_expressions_are_reported_with_a_position() {
+        let error = parse_policy_expression("(= subject.component \"db\"")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("unclosed '(' at position 0"));
+        assert!(error.contains("     (= subject.component \"db\"\n     ^\n"));
+
+        let error = parse_policy_expression("component.db or")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("but ` or` cannot be parsed at position 12"));
+        assert!(error.contains("     component.db or\n                 ^\n"));
+    }
+}
@@ -0,0 +1,13 @@
+use ockam_api::nodes::service::default_address::DefaultAddress;
+
+pub(crate) mod outlet;
+
+const POSTGRES_DEFAULT_DATABASE_ADDRESS: &str = "127.0.0.1:5432";
+
+fn postgres_default_outlet_addr() -> String {
+    DefaultAddress::POSTGRES_OUTLET.to_string()
+}
+
+fn postgres_default_database() -> String {
+    POSTGRES_DEFAULT_DATABASE_ADDRESS.to_string()
+}
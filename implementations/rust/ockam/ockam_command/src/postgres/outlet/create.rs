@@ -0,0 +1,151 @@
+use async_trait::async_trait;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+use serde::Serialize;
+
+use ockam::Context;
+use ockam_abac::PolicyExpression;
+use ockam_api::colors::color_primary;
+use ockam_api::nodes::models::services::{StartPostgresOutletRequest, StartServiceRequest};
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_api::output::Output;
+use ockam_api::postgres::{RoleMapping, DEFAULT_ROLE_ATTRIBUTE};
+use ockam_api::{fmt_log, fmt_ok};
+use ockam_core::api::Request;
+
+use crate::node::util::initialize_default_node;
+use crate::node::NodeOpts;
+use crate::postgres::{postgres_default_database, postgres_default_outlet_addr};
+use crate::{docs, Command, CommandGlobalOpts};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
+
+/// Create a Postgres Outlet
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct CreateCommand {
+    #[command(flatten)]
+    pub node_opts: NodeOpts,
+
+    /// The local address of the service
+    #[arg(long, default_value_t = postgres_default_outlet_addr())]
+    pub addr: String,
+
+    /// The address of the database, as `HOST:PORT`
+    #[arg(long, value_name = "HOSTNAME_PORT", default_value_t = postgres_default_database())]
+    pub database: String,
+
+    /// The credential attribute holding the database role of each client
+    #[arg(long, value_name = "ATTRIBUTE", default_value = DEFAULT_ROLE_ATTRIBUTE)]
+    pub role_attribute: String,
+
+    /// The database role of the clients without the role attribute.
+    /// If you don't provide it, these clients can't connect to the database
+    #[arg(long, value_name = "ROLE")]
+    pub default_role: Option<String>,
+
+    /// A password file, in the format of `.pgpass` files, with the passwords of the roles.
+    /// The passwords are only sent to the database, never to the clients
+    #[arg(long, value_name = "PATH")]
+    pub passfile: Option<PathBuf>,
+
+    /// Policy expression that will be used for access control to the Postgres Outlet.
+    /// If you don't provide it, the policy set for the "tcp-outlet" resource type will be used.
+    ///
+    /// You can check the fallback policy with `ockam policy show --resource-type tcp-outlet`.
+    #[arg(hide = true, long = "allow", id = "EXPRESSION")]
+    pub policy_expression: Option<PolicyExpression>,
+}
+
+#[async_trait]
+impl Command for CreateCommand {
+    const NAME: &'static str = "postgres-outlet create";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        initialize_default_node(ctx, &opts).await?;
+
+        let mut role_mapping = RoleMapping::new(&self.role_attribute);
+        if let Some(default_role) = &self.default_role {
+            role_mapping = role_mapping.with_default_role(default_role);
+        }
+        // the password file is read by the node, which may run in another directory
+        let passfile = match &self.passfile {
+            Some(passfile) => Some(
+                std::fs::canonicalize(passfile)
+                    .into_diagnostic()?
+                    .to_string_lossy()
+                    .to_string(),
+            ),
+            None => None,
+        };
+
+        let outlet = {
+            let pb = opts.terminal.progress_bar();
+            if let Some(pb) = pb.as_ref() {
+                pb.set_message(format!(
+                    "Creating Postgres Outlet to the database {}...\n",
+                    color_primary(&self.database)
+                ));
+            }
+
+            let payload = StartPostgresOutletRequest::new(
+                self.database.clone(),
+                role_mapping,
+                passfile,
+                self.policy_expression,
+            );
+            let payload = StartServiceRequest::new(payload, &self.addr);
+            let req = Request::post("/node/services/postgres_outlet").body(payload);
+            let node =
+                BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
+            node.tell(ctx, req)
+                .await
+                .map_err(|e| miette!("Failed to start Postgres Outlet: {e}"))?;
+
+            PostgresOutletOutput {
+                node_name: node.node_name(),
+                database: self.database.clone(),
+                role_attribute: self.role_attribute.clone(),
+            }
+        };
+
+        opts.terminal
+            .stdout()
+            .plain(outlet.item()?)
+            .json_obj(outlet)?
+            .write_line()?;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct PostgresOutletOutput {
+    node_name: String,
+    database: String,
+    role_attribute: String,
+}
+
+impl Output for PostgresOutletOutput {
+    fn item(&self) -> ockam_api::Result<String> {
+        let mut f = String::new();
+        writeln!(
+            f,
+            "{}\n{}",
+            fmt_ok!(
+                "Created a new Postgres Outlet in the Node {}",
+                color_primary(&self.node_name)
+            ),
+            fmt_log!(
+                "connected to the database at {}, with the roles of the {} attribute",
+                color_primary(&self.database),
+                color_primary(&self.role_attribute)
+            ),
+        )?;
+        Ok(f)
+    }
+}
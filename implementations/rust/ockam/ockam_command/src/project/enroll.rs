@@ -76,9 +76,9 @@ impl Command for EnrollCommand {
     }
 
     async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
-        if opts.global_args.output_format()?.is_json() {
+        if !opts.global_args.output_format()?.is_plain() {
             return Err(miette::miette!(
-                "This command does not support JSON output. Please try running it again without '--output json'."
+                "This command does not support JSON or YAML output. Please try running it again without '--output'."
             ).into());
         }
 
@@ -0,0 +1,142 @@
+use std::fmt::Write;
+
+use async_trait::async_trait;
+use clap::Args;
+use serde::Serialize;
+
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam::Context;
+use ockam_api::authenticator::direct::types::TokenInfo;
+use ockam_api::authenticator::enrollment_tokens::TokenIssuer;
+use ockam_api::colors::{color_primary, color_warn};
+use ockam_api::output::{human_readable_time, Output};
+use ockam_api::terminal::fmt;
+use ockam_multiaddr::MultiAddr;
+
+use crate::project_member::authority_client;
+use crate::shared_args::IdentityOpts;
+use crate::{docs, Command, CommandGlobalOpts, Result};
+
+const LONG_ABOUT: &str = include_str!("../static/ticket/list/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("../static/ticket/list/after_long_help.txt");
+
+/// List the enrollment tickets of a Project which have not been used or revoked yet
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct ListCommand {
+    #[command(flatten)]
+    identity_opts: IdentityOpts,
+
+    /// The route to the Project to list the enrollment tickets from
+    #[arg(long, short, value_name = "ROUTE_TO_PROJECT")]
+    to: Option<MultiAddr>,
+}
+
+#[async_trait]
+impl Command for ListCommand {
+    const NAME: &'static str = "project ticket list";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        let (authority_node_client, _) =
+            authority_client(ctx, &opts, &self.identity_opts, &self.to).await?;
+
+        let tickets = authority_node_client
+            .list_tokens(ctx)
+            .await?
+            .into_iter()
+            .map(TicketOutput::from)
+            .collect::<Vec<_>>();
+
+        let plain = opts.terminal.build_list(
+            &tickets,
+            "No enrollment tickets found on the Authority node",
+        )?;
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json_obj(&tickets)?
+            .write_line()?;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct TicketOutput {
+    id: String,
+    issued_by: Identifier,
+    created_at: TimestampInSeconds,
+    expires_at: TimestampInSeconds,
+    remaining_uses: u64,
+    attributes: Vec<String>,
+}
+
+impl From<TokenInfo> for TicketOutput {
+    fn from(token: TokenInfo) -> Self {
+        Self {
+            id: token.reference().to_string(),
+            issued_by: token.issued_by().clone(),
+            created_at: token.created_at(),
+            expires_at: token.expires_at(),
+            remaining_uses: token.remaining_uses(),
+            attributes: token
+                .attributes()
+                .iter()
+                .map(|(k, v)| format!("{k}={v}"))
+                .collect(),
+        }
+    }
+}
+
+impl TicketOutput {
+    fn to_string(&self, padding: &str) -> ockam_api::Result<String> {
+        let mut f = String::new();
+        writeln!(f, "{}Ticket {}", padding, color_primary(&self.id))?;
+        writeln!(
+            f,
+            "{}{}Issued by: {}",
+            padding,
+            fmt::INDENTATION,
+            color_primary(self.issued_by.to_string())
+        )?;
+        writeln!(
+            f,
+            "{}{}Expires at: {}",
+            padding,
+            fmt::INDENTATION,
+            color_warn(human_readable_time(self.expires_at))
+        )?;
+        writeln!(
+            f,
+            "{}{}Remaining uses: {}",
+            padding,
+            fmt::INDENTATION,
+            color_primary(self.remaining_uses.to_string())
+        )?;
+        if self.attributes.is_empty() {
+            writeln!(f, "{}{}Has no attributes", padding, fmt::INDENTATION)?;
+        } else {
+            writeln!(
+                f,
+                "{}{}With attributes: {}",
+                padding,
+                fmt::INDENTATION,
+                color_primary(self.attributes.join(", "))
+            )?;
+        }
+        Ok(f)
+    }
+}
+
+impl Output for TicketOutput {
+    fn item(&self) -> ockam_api::Result<String> {
+        self.to_string(fmt::PADDING)
+    }
+
+    fn as_list_item(&self) -> ockam_api::Result<String> {
+        self.to_string("")
+    }
+}
@@ -2,7 +2,7 @@ use std::collections::BTreeMap;
 use std::time::Duration;
 
 use async_trait::async_trait;
-use clap::Args;
+use clap::{Args, Subcommand};
 use colorful::Colorful;
 use miette::{miette, IntoDiagnostic};
 use tracing::debug;
@@ -22,8 +22,14 @@ use crate::shared_args::{IdentityOpts, RetryOpts, TrustOpts};
 use crate::util::parsers::duration_parser;
 use crate::{docs, Command, CommandGlobalOpts, Error, Result};
 
-const LONG_ABOUT: &str = include_str!("./static/ticket/long_about.txt");
-const AFTER_LONG_HELP: &str = include_str!("./static/ticket/after_long_help.txt");
+pub use list::ListCommand;
+pub use revoke::RevokeCommand;
+
+mod list;
+mod revoke;
+
+const LONG_ABOUT: &str = include_str!("../static/ticket/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("../static/ticket/after_long_help.txt");
 
 /// This attribute in credential allows member to create a relay on the Project node, the name of the relay should be
 /// equal to the value of that attribute. If the value is `*` then any name is allowed
@@ -32,10 +38,14 @@ pub const OCKAM_RELAY_ATTRIBUTE: &str = "ockam-relay";
 /// Add members to a Project, as an authorized enroller, directly, or via an enrollment ticket
 #[derive(Clone, Debug, Args)]
 #[command(
+args_conflicts_with_subcommands = true,
 long_about = docs::about(LONG_ABOUT),
 after_long_help = docs::after_help(AFTER_LONG_HELP),
 )]
 pub struct TicketCommand {
+    #[command(subcommand)]
+    subcommand: Option<TicketSubcommand>,
+
     /// Orchestrator address to resolve projects present in the `at` argument
     #[command(flatten)]
     identity_opts: IdentityOpts,
@@ -56,9 +66,9 @@ pub struct TicketCommand {
     #[arg(long = "expires-in", value_name = "DURATION", value_parser = duration_parser)]
     expires_in: Option<Duration>,
 
-    /// Number of times the ticket can be used to enroll, the default is 1
-    #[arg(long = "usage-count", value_name = "USAGE_COUNT")]
-    usage_count: Option<u64>,
+    /// Maximum number of times the ticket can be used to enroll, the default is 1
+    #[arg(long = "max-uses", visible_alias = "usage-count", value_name = "COUNT", value_parser = clap::value_parser!(u64).range(1..))]
+    max_uses: Option<u64>,
 
     /// Name of the relay that the identity using the ticket will be allowed to create. This name is transformed into attributes to prevent collisions when creating relay names. For example: `--relay foo` is shorthand for `--attribute ockam-relay=foo`
     #[arg(long = "relay", value_name = "ENROLLEE_ALLOWED_RELAY_NAME")]
@@ -72,19 +82,39 @@ pub struct TicketCommand {
     retry_opts: RetryOpts,
 }
 
+#[derive(Clone, Debug, Subcommand)]
+enum TicketSubcommand {
+    List(ListCommand),
+    Revoke(RevokeCommand),
+}
+
 #[async_trait]
 impl Command for TicketCommand {
     const NAME: &'static str = "project ticket";
 
+    fn name(&self) -> String {
+        match &self.subcommand {
+            Some(TicketSubcommand::List(c)) => c.name(),
+            Some(TicketSubcommand::Revoke(c)) => c.name(),
+            None => Self::NAME.into(),
+        }
+    }
+
     fn retry_opts(&self) -> Option<RetryOpts> {
         Some(self.retry_opts.clone())
     }
 
     async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
-        if opts.global_args.output_format()?.is_json() {
+        match self.subcommand {
+            Some(TicketSubcommand::List(c)) => return c.async_run(ctx, opts).await,
+            Some(TicketSubcommand::Revoke(c)) => return c.async_run(ctx, opts).await,
+            None => {}
+        }
+
+        if !opts.global_args.output_format()?.is_plain() {
             return Err(miette::miette!(
                 "This command only outputs a hex encoded string for 'ockam project enroll' to use. \
-                Please try running it again without '--output json'."
+                Please try running it again without '--output'."
             )
             .into());
         }
@@ -113,7 +143,7 @@ impl Command for TicketCommand {
         // Request an enrollment token that a future member can use to get a
         // credential.
         let token = authority_node_client
-            .create_token(ctx, attributes, self.expires_in, self.usage_count)
+            .create_token(ctx, attributes, self.expires_in, self.max_uses)
             .await
             .map_err(Error::Retry)?;
 
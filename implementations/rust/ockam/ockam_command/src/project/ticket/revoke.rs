@@ -0,0 +1,71 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use serde::Serialize;
+
+use ockam::Context;
+use ockam_api::authenticator::enrollment_tokens::TokenIssuer;
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+use ockam_multiaddr::MultiAddr;
+
+use crate::project_member::authority_client;
+use crate::shared_args::IdentityOpts;
+use crate::{docs, Command, CommandGlobalOpts, Result};
+
+const LONG_ABOUT: &str = include_str!("../static/ticket/revoke/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("../static/ticket/revoke/after_long_help.txt");
+
+/// Revoke an enrollment ticket of a Project so that it can't be used anymore
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct RevokeCommand {
+    #[command(flatten)]
+    identity_opts: IdentityOpts,
+
+    /// The route to the Project which issued the enrollment ticket
+    #[arg(long, short, value_name = "ROUTE_TO_PROJECT")]
+    to: Option<MultiAddr>,
+
+    /// The id of the enrollment ticket to revoke, as displayed by `ockam project ticket list`
+    #[arg(value_name = "TICKET_ID")]
+    id: String,
+}
+
+#[async_trait]
+impl Command for RevokeCommand {
+    const NAME: &'static str = "project ticket revoke";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        let (authority_node_client, project_name) =
+            authority_client(ctx, &opts, &self.identity_opts, &self.to).await?;
+
+        authority_node_client.revoke_token(ctx, &self.id).await?;
+
+        let output = RevokeOutput {
+            project: project_name,
+            id: self.id,
+        };
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The enrollment ticket {} of the Project {} has been revoked",
+                color_primary(&output.id),
+                color_primary(&output.project)
+            ))
+            .machine(&output.id)
+            .json_obj(&output)?
+            .write_line()?;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct RevokeOutput {
+    project: String,
+    id: String,
+}
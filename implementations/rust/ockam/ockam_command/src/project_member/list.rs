@@ -1,23 +1,29 @@
 use async_trait::async_trait;
 use clap::Args;
 
+use ockam::identity::AttributesEntry;
 use ockam::Context;
+use ockam_api::authenticator::direct::types::ListMembers;
 use ockam_api::authenticator::direct::{
     Members, OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE, OCKAM_ROLE_ATTRIBUTE_KEY,
 };
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_log;
 use ockam_multiaddr::MultiAddr;
 
 use crate::shared_args::IdentityOpts;
 use crate::{docs, Command, CommandGlobalOpts, Result};
 
-use super::{authority_client, MemberOutput};
+use super::{authority_client, create_member_attributes, MemberOutput};
 
 const LONG_ABOUT: &str = include_str!("./static/list/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
 
 /// List members of a Project
 #[derive(Clone, Debug, Args)]
 #[command(
 long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP),
 )]
 pub struct ListCommand {
     #[command(flatten)]
@@ -30,6 +36,19 @@ pub struct ListCommand {
     /// Return only the enroller members
     #[arg(long, visible_alias = "enroller")]
     enrollers: bool,
+
+    /// Return only the members having this attribute, in `key=value` format.
+    /// You can specify this option multiple times to select members having all the attributes
+    #[arg(short, long = "attribute", value_name = "ATTRIBUTE")]
+    attributes: Vec<String>,
+
+    /// Maximum number of members to return
+    #[arg(long, value_name = "COUNT")]
+    limit: Option<u64>,
+
+    /// Number of members to skip before returning members, to get the next pages of members
+    #[arg(long, value_name = "COUNT", default_value_t = 0)]
+    offset: u64,
 }
 
 #[async_trait]
@@ -40,23 +59,48 @@ impl Command for ListCommand {
         let (authority_node_client, _) =
             authority_client(ctx, &opts, &self.identity_opts, &self.to).await?;
 
-        let members = authority_node_client
-            .list_members(ctx)
-            .await?
-            .into_iter()
-            .filter(|(_, a)| {
-                !self.enrollers
-                    || a.deserialized_key_value_attrs().contains(&format!(
-                        "{}={}",
-                        OCKAM_ROLE_ATTRIBUTE_KEY, OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE
-                    ))
-            })
-            .map(|(i, a)| MemberOutput::new(i, a))
-            .collect::<Vec<_>>();
-
-        let plain = opts
+        // Members are only filtered and paginated by the Authority when needed, so that
+        // listing all the members still works with Authority nodes which don't support it
+        let (members, next_page) =
+            if !self.attributes.is_empty() || self.limit.is_some() || self.offset > 0 {
+                let attributes = create_member_attributes(&self.attributes, &None, self.enrollers)?;
+                let list_members = ListMembers::new()
+                    .with_attributes(attributes)
+                    .with_offset(self.offset)
+                    .with_limit(self.limit);
+                let page = authority_node_client
+                    .list_members_page(ctx, list_members)
+                    .await?;
+                let total = page.total();
+                let next_offset = self.offset + self.limit.unwrap_or(total);
+                let members = page
+                    .into_members()
+                    .into_iter()
+                    .map(|(i, a)| MemberOutput::new(i, a))
+                    .collect::<Vec<_>>();
+                let next_page = (next_offset < total).then_some((total, next_offset));
+                (members, next_page)
+            } else {
+                let members = authority_node_client
+                    .list_members(ctx)
+                    .await?
+                    .into_iter()
+                    .filter(|(_, a)| !self.enrollers || is_enroller(a))
+                    .map(|(i, a)| MemberOutput::new(i, a))
+                    .collect::<Vec<_>>();
+                (members, None)
+            };
+
+        let mut plain = opts
             .terminal
             .build_list(&members, "No members found on the Authority node")?;
+        if let Some((total, next_offset)) = next_page {
+            plain.push_str(&fmt_log!(
+                "{} members match in total. Use {} to get the next page",
+                color_primary(total.to_string()),
+                color_primary(format!("--offset {next_offset}"))
+            ));
+        }
         opts.terminal
             .stdout()
             .plain(plain)
@@ -66,3 +110,10 @@ impl Command for ListCommand {
         Ok(())
     }
 }
+
+fn is_enroller(attributes: &AttributesEntry) -> bool {
+    attributes.deserialized_key_value_attrs().contains(&format!(
+        "{}={}",
+        OCKAM_ROLE_ATTRIBUTE_KEY, OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE
+    ))
+}
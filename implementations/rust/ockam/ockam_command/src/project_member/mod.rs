@@ -85,7 +85,7 @@ pub enum ProjectMemberSubcommand {
     Delete(DeleteCommand),
 }
 
-pub(super) async fn authority_client(
+pub(crate) async fn authority_client(
     ctx: &Context,
     opts: &CommandGlobalOpts,
     identity_opts: &IdentityOpts,
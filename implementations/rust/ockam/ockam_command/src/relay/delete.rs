@@ -35,6 +35,11 @@ pub struct DeleteCommand {
     /// Confirm the deletion without prompting
     #[arg(long, short)]
     yes: bool,
+
+    /// Read the names of the relays to delete from stdin, one per line or as a JSON array.
+    /// The result of each operation is reported separately
+    #[arg(long, conflicts_with = "relay_name")]
+    stdin: bool,
 }
 
 impl DeleteCommand {
@@ -90,6 +95,10 @@ impl DeleteCommandTui for DeleteTui {
         self.cmd.relay_name.clone()
     }
 
+    fn cmd_arg_stdin(&self) -> bool {
+        self.cmd.stdin
+    }
+
     fn cmd_arg_delete_all(&self) -> bool {
         false
     }
@@ -1,15 +1,20 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
 use clap::Args;
 use colorful::Colorful;
+use futures::future::join_all;
 use miette::IntoDiagnostic;
 use tokio::sync::Mutex;
 use tokio::try_join;
-use tracing::trace;
+use tracing::{trace, warn};
 
 use ockam::Context;
 use ockam_api::address::extract_address_value;
-use ockam_api::colors::OckamColor;
+use ockam_api::colors::{color_primary, OckamColor};
 use ockam_api::nodes::models::relay::RelayInfo;
 use ockam_api::nodes::BackgroundNodeClient;
+use ockam_api::{fmt_heading, fmt_warn};
 use ockam_core::api::Request;
 
 use crate::util::async_cmd;
@@ -19,6 +24,9 @@ const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
 const LONG_ABOUT: &str = include_str!("./static/list/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
 
+/// Maximum time to wait for the relays of a node when listing the relays of all nodes
+const NODE_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// List Relays
 #[derive(Clone, Debug, Args)]
 #[command(
@@ -31,6 +39,10 @@ pub struct ListCommand {
     /// Get the list of Relays at the given node
     #[arg(global = true, long, value_name = "NODE", value_parser = extract_address_value)]
     pub to: Option<String>,
+
+    /// Get the list of Relays of all the running local nodes
+    #[arg(long, conflicts_with = "to")]
+    pub all: bool,
 }
 
 impl ListCommand {
@@ -45,6 +57,10 @@ impl ListCommand {
     }
 
     async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        if self.all {
+            return self.list_all(ctx, opts).await;
+        }
+
         let node = BackgroundNodeClient::create(ctx, &opts.state, &self.to).await?;
         let is_finished: Mutex<bool> = Mutex::new(false);
 
@@ -77,4 +93,77 @@ impl ListCommand {
             .write_line()?;
         Ok(())
     }
+
+    /// List the relays of all the running nodes.
+    /// The nodes are queried in parallel and the relays are grouped by node name
+    async fn list_all(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let nodes = opts
+            .state
+            .get_nodes()
+            .await?
+            .into_iter()
+            .filter(|n| n.is_running())
+            .map(|n| n.name())
+            .collect::<Vec<_>>();
+        if nodes.is_empty() {
+            opts.terminal
+                .stdout()
+                .plain(fmt_warn!("No running nodes found"))
+                .json("{}")
+                .write_line()?;
+            return Ok(());
+        }
+
+        let results = join_all(nodes.into_iter().map(|node_name| {
+            let opts = &opts;
+            async move {
+                let relays = get_node_relays(ctx, opts, &node_name).await;
+                (node_name, relays)
+            }
+        }))
+        .await;
+
+        let mut relays_by_node = BTreeMap::new();
+        let mut plain = String::new();
+        for (node_name, relays) in results {
+            match relays {
+                Ok(relays) => {
+                    plain.push_str(&fmt_heading!("Node {}", color_primary(&node_name)));
+                    plain.push('\n');
+                    plain.push_str(
+                        &opts.terminal.build_list(
+                            &relays,
+                            &format!("No Relays found on node {node_name}."),
+                        )?,
+                    );
+                    plain.push('\n');
+                    relays_by_node.insert(node_name, relays);
+                }
+                Err(e) => {
+                    warn!(node = %node_name, %e, "Failed to retrieve the relays of the node");
+                    opts.terminal.write_line(fmt_warn!(
+                        "Failed to retrieve the Relays of node {}",
+                        color_primary(&node_name)
+                    ))?;
+                }
+            }
+        }
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(serde_json::to_string(&relays_by_node).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+async fn get_node_relays(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    node_name: &str,
+) -> miette::Result<Vec<RelayInfo>> {
+    let mut node =
+        BackgroundNodeClient::create(ctx, &opts.state, &Some(node_name.to_string())).await?;
+    node.set_timeout_mut(NODE_REQUEST_TIMEOUT);
+    node.ask(ctx, Request::get("/node/relay")).await
 }
@@ -40,6 +40,11 @@ pub struct ShowCommand {
     /// Node which the relay belongs to
     #[arg(long, value_name = "NODE", value_parser = extract_address_value)]
     pub at: Option<String>,
+
+    /// Read the names of the relays to show from stdin, one per line or as a JSON array.
+    /// The result of each operation is reported separately
+    #[arg(long, conflicts_with = "relay_name")]
+    stdin: bool,
 }
 
 impl ShowCommand {
@@ -94,6 +99,10 @@ impl ShowCommandTui for ShowTui {
         self.cmd.relay_name.clone()
     }
 
+    fn cmd_arg_stdin(&self) -> bool {
+        self.cmd.stdin
+    }
+
     fn terminal(&self) -> Terminal<TerminalStream<Term>> {
         self.opts.terminal.clone()
     }
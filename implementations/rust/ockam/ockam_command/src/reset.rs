@@ -1,14 +1,17 @@
+use std::fmt::{Display, Formatter};
+
 use clap::Args;
 use colorful::Colorful;
-use miette::{miette, WrapErr};
+use miette::{miette, IntoDiagnostic, WrapErr};
+use serde::Serialize;
 use tracing::error;
 
 use crate::CommandGlobalOpts;
 use ockam_api::cloud::space::Spaces;
-use ockam_api::colors::OckamColor;
+use ockam_api::colors::{color_primary, OckamColor};
 use ockam_api::nodes::InMemoryNode;
 use ockam_api::terminal::ConfirmResult;
-use ockam_api::{color, fmt_ok, CliState};
+use ockam_api::{color, fmt_heading, fmt_log, fmt_ok, CliState};
 use ockam_node::Context;
 
 use crate::util::async_cmd;
@@ -23,6 +26,10 @@ pub struct ResetCommand {
     /// Remove your spaces from the Orchestrator
     #[arg(long)]
     all: bool,
+
+    /// Only report what would be deleted, without deleting anything
+    #[arg(long)]
+    dry_run: bool,
 }
 
 impl ResetCommand {
@@ -45,6 +52,15 @@ impl ResetCommand {
     async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
         let delete_orchestrator_resources =
             self.all && opts.state.is_enrolled().await.unwrap_or_default();
+        if self.dry_run {
+            let report = ResetReport::create(&opts.state, delete_orchestrator_resources).await?;
+            opts.terminal
+                .stdout()
+                .plain(&report)
+                .json(serde_json::to_string(&report).into_diagnostic()?)
+                .write_line()?;
+            return Ok(());
+        }
         if !self.yes {
             let msg = if delete_orchestrator_resources {
                 "This will delete the local Ockam configuration and remove your spaces from the Orchestrator. Are you sure?"
@@ -82,6 +98,202 @@ impl ResetCommand {
     }
 }
 
+/// Description of the local state which is deleted by a reset
+#[derive(Serialize)]
+struct ResetReport {
+    directory: String,
+    nodes: Vec<NodeReport>,
+    identities: Vec<IdentityReport>,
+    vaults: Vec<VaultReport>,
+    spaces: Vec<SpaceReport>,
+    projects: Vec<ProjectReport>,
+    /// True if the spaces are also deleted from the Orchestrator
+    delete_orchestrator_spaces: bool,
+}
+
+#[derive(Serialize)]
+struct NodeReport {
+    name: String,
+    pid: Option<u32>,
+    is_running: bool,
+}
+
+#[derive(Serialize)]
+struct IdentityReport {
+    name: String,
+    identifier: String,
+}
+
+#[derive(Serialize)]
+struct VaultReport {
+    name: String,
+    path: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SpaceReport {
+    name: String,
+    id: String,
+}
+
+#[derive(Serialize)]
+struct ProjectReport {
+    name: String,
+    id: String,
+}
+
+impl ResetReport {
+    async fn create(state: &CliState, delete_orchestrator_spaces: bool) -> miette::Result<Self> {
+        let nodes = state
+            .get_nodes()
+            .await?
+            .into_iter()
+            .map(|n| NodeReport {
+                name: n.name(),
+                pid: n.pid(),
+                is_running: n.is_running(),
+            })
+            .collect();
+        let identities = state
+            .get_named_identities()
+            .await?
+            .into_iter()
+            .map(|i| IdentityReport {
+                name: i.name(),
+                identifier: i.identifier().to_string(),
+            })
+            .collect();
+        let vaults = state
+            .get_named_vaults()
+            .await?
+            .into_iter()
+            .map(|v| VaultReport {
+                name: v.name(),
+                path: v.path_as_string(),
+            })
+            .collect();
+        let spaces = state
+            .get_spaces()
+            .await?
+            .into_iter()
+            .map(|s| SpaceReport {
+                name: s.name,
+                id: s.id,
+            })
+            .collect();
+        let projects = state
+            .projects()
+            .get_projects()
+            .await?
+            .into_iter()
+            .map(|p| ProjectReport {
+                name: p.name().to_string(),
+                id: p.project_id().to_string(),
+            })
+            .collect();
+        Ok(Self {
+            directory: state.dir().display().to_string(),
+            nodes,
+            identities,
+            vaults,
+            spaces,
+            projects,
+            delete_orchestrator_spaces,
+        })
+    }
+}
+
+impl Display for ResetReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{}",
+            fmt_log!(
+                "The following resources would be deleted with the directory {}",
+                color_primary(&self.directory)
+            )
+        )?;
+
+        writeln!(f, "{}", fmt_heading!("Nodes"))?;
+        if self.nodes.is_empty() {
+            writeln!(f, "{}", fmt_log!("No nodes"))?;
+        }
+        for node in &self.nodes {
+            let pid = match (node.is_running, node.pid) {
+                (true, Some(pid)) => format!("running with PID {pid}"),
+                _ => "not running".to_string(),
+            };
+            writeln!(f, "{}", fmt_log!("{} ({pid})", color_primary(&node.name)))?;
+        }
+
+        writeln!(f, "{}", fmt_heading!("Identities"))?;
+        if self.identities.is_empty() {
+            writeln!(f, "{}", fmt_log!("No identities"))?;
+        }
+        for identity in &self.identities {
+            writeln!(
+                f,
+                "{}",
+                fmt_log!(
+                    "{} ({})",
+                    color_primary(&identity.name),
+                    identity.identifier
+                )
+            )?;
+        }
+
+        writeln!(f, "{}", fmt_heading!("Vaults"))?;
+        if self.vaults.is_empty() {
+            writeln!(f, "{}", fmt_log!("No vaults"))?;
+        }
+        for vault in &self.vaults {
+            match &vault.path {
+                Some(path) => writeln!(
+                    f,
+                    "{}",
+                    fmt_log!("{} (stored at {path})", color_primary(&vault.name))
+                )?,
+                None => writeln!(f, "{}", fmt_log!("{}", color_primary(&vault.name)))?,
+            }
+        }
+
+        writeln!(f, "{}", fmt_heading!("Orchestrator records"))?;
+        if self.spaces.is_empty() && self.projects.is_empty() {
+            writeln!(f, "{}", fmt_log!("No spaces or projects"))?;
+        }
+        for space in &self.spaces {
+            writeln!(
+                f,
+                "{}",
+                fmt_log!("Space {} ({})", color_primary(&space.name), space.id)
+            )?;
+        }
+        for project in &self.projects {
+            writeln!(
+                f,
+                "{}",
+                fmt_log!("Project {} ({})", color_primary(&project.name), project.id)
+            )?;
+        }
+        if self.delete_orchestrator_spaces {
+            writeln!(
+                f,
+                "{}",
+                fmt_log!("The spaces would also be deleted from the Orchestrator")
+            )?;
+        } else {
+            writeln!(
+                f,
+                "{}",
+                fmt_log!(
+                    "Only the local records would be deleted, the Orchestrator is left unchanged"
+                )
+            )?;
+        }
+        Ok(())
+    }
+}
+
 async fn delete_orchestrator_resources_impl(
     ctx: &Context,
     opts: CommandGlobalOpts,
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use serde::{Deserialize, Serialize};
 
 use ockam_node::Context;
@@ -5,6 +7,7 @@ use ockam_node::Context;
 use crate::run::parser::config::ConfigParser;
 use crate::run::parser::resource::*;
 use crate::run::parser::Version;
+use crate::run::resources::TrackedResource;
 use crate::CommandGlobalOpts;
 
 /// Defines the high-level structure of the configuration file.
@@ -60,6 +63,46 @@ impl Config {
         Ok(())
     }
 
+    /// Executes the commands described in the configuration, like [`Config::run`], and record the
+    /// nodes, relays, policies and TCP portals which are created, so that they can be deleted
+    /// with `ockam run destroy <recipe_name>`.
+    ///
+    /// Resources which already exist are not recorded. The created resources are returned.
+    pub async fn run_recipe(
+        self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        recipe_name: &str,
+    ) -> miette::Result<Vec<TrackedResource>> {
+        let before: Vec<ParsedCommands> = vec![
+            self.vaults.into_parsed_commands()?.into(),
+            self.identities.into_parsed_commands()?.into(),
+            self.project_enroll.into_parsed_commands()?.into(),
+        ];
+        let mut resources = TrackedResource::parse_all(
+            self.nodes,
+            self.relays,
+            self.policies,
+            self.tcp_outlets,
+            self.tcp_inlets,
+        )?;
+        let after: Vec<ParsedCommands> = vec![
+            self.kafka_inlet.into_parsed_commands(None)?.into(),
+            self.kafka_outlet.into_parsed_commands(None)?.into(),
+        ];
+
+        for cmd in before {
+            cmd.run(ctx, opts).await?
+        }
+        for resource in resources.iter_mut() {
+            resource.create(ctx, opts, recipe_name).await?
+        }
+        for cmd in after {
+            cmd.run(ctx, opts).await?
+        }
+        Ok(resources)
+    }
+
     // Build commands and return validation errors
     fn parse_commands(self) -> miette::Result<Vec<ParsedCommands>> {
         Ok(vec![
@@ -81,7 +124,17 @@ impl Config {
         opts: CommandGlobalOpts,
         contents: &str,
     ) -> miette::Result<()> {
-        let config = Config::parse(&Config::resolve(contents)?)?;
+        Self::parse_and_run_in(ctx, opts, contents, Path::new(".")).await
+    }
+
+    /// Parse and run a configuration, resolving its included files relative to `base_dir`
+    pub async fn parse_and_run_in(
+        ctx: &Context,
+        opts: CommandGlobalOpts,
+        contents: &str,
+        base_dir: &Path,
+    ) -> miette::Result<()> {
+        let config = Config::parse(&Config::resolve_in(contents, base_dir)?)?;
         config.run(ctx, &opts).await
     }
 }
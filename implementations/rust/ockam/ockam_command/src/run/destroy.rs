@@ -0,0 +1,137 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::miette;
+use serde::Serialize;
+
+use ockam_api::colors::color_primary;
+use ockam_api::{fmt_ok, fmt_warn};
+use ockam_node::Context;
+
+use crate::run::resources::{delete_resource, ResourceKind};
+use crate::{Command, CommandGlobalOpts};
+
+/// Delete the nodes, relays, policies and TCP portals created by a recipe
+///
+/// The resources are deleted in the reverse order of their creation.
+/// Resources which existed before the recipe was run are not deleted.
+#[derive(Clone, Debug, Args)]
+pub struct DestroyCommand {
+    /// Name of the recipe, as given with `ockam run --name`.
+    /// It defaults to the name of the recipe file, without its extension.
+    /// It can be omitted if only one recipe created resources
+    #[arg(value_name = "RECIPE_NAME")]
+    pub recipe_name: Option<String>,
+
+    /// Confirm the deletion without prompting
+    #[arg(display_order = 901, long, short)]
+    yes: bool,
+}
+
+#[async_trait]
+impl Command for DestroyCommand {
+    const NAME: &'static str = "run destroy";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let recipe_name = self.recipe_name(&opts).await?;
+        let resources = opts.state.get_recipe_resources(&recipe_name).await?;
+        if resources.is_empty() {
+            return Err(miette!(
+                "No resources were created by the recipe {}",
+                color_primary(&recipe_name)
+            )
+            .into());
+        }
+
+        if !opts.terminal.confirmed_with_flag_or_prompt(
+            self.yes,
+            format!(
+                "Are you sure you want to delete the {} resource(s) created by the recipe {}?",
+                resources.len(),
+                recipe_name
+            ),
+        )? {
+            return Ok(());
+        }
+
+        let mut output = DestroyOutput {
+            recipe_name: recipe_name.clone(),
+            deleted: vec![],
+            failed: vec![],
+        };
+        for resource in resources.iter().rev() {
+            let description = format!(
+                "{} {} on the node {}",
+                resource.kind(),
+                resource.name(),
+                resource.node_name()
+            );
+            // Resources hosted on a node which doesn't exist anymore are gone with it
+            let node_exists = opts.state.get_node(&resource.node_name()).await.is_ok();
+            if node_exists {
+                let Some(kind) = ResourceKind::parse(&resource.kind()) else {
+                    opts.terminal
+                        .write_line(&fmt_warn!("Unknown kind of resource: {}", resource.kind()))?;
+                    output.failed.push(description);
+                    continue;
+                };
+                if let Err(e) =
+                    delete_resource(&opts, kind, &resource.name(), Some(&resource.node_name()))
+                        .await
+                {
+                    opts.terminal.write_line(&fmt_warn!("{e}"))?;
+                    output.failed.push(description);
+                    continue;
+                }
+            }
+            opts.state
+                .delete_recipe_resource(&recipe_name, resource)
+                .await?;
+            output.deleted.push(description);
+        }
+
+        if !output.failed.is_empty() {
+            return Err(miette!(
+                "{} resource(s) created by the recipe {} couldn't be deleted. Run this command again to retry",
+                output.failed.len(),
+                color_primary(&recipe_name)
+            )
+            .into());
+        }
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The resources created by the recipe {} have been deleted",
+                color_primary(&recipe_name)
+            ))
+            .json_obj(&output)?
+            .write_line()?;
+        Ok(())
+    }
+}
+
+impl DestroyCommand {
+    /// Return the recipe name, or the name of the only recipe which created resources
+    async fn recipe_name(&self, opts: &CommandGlobalOpts) -> miette::Result<String> {
+        if let Some(recipe_name) = &self.recipe_name {
+            return Ok(recipe_name.clone());
+        }
+        let recipe_names = opts.state.get_recipe_names().await?;
+        match recipe_names.as_slice() {
+            [recipe_name] => Ok(recipe_name.clone()),
+            [] => Err(miette!("No resources were created by a recipe")),
+            _ => Err(miette!(
+                "Several recipes created resources, please specify which one to destroy: {}",
+                recipe_names.join(", ")
+            )),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct DestroyOutput {
+    recipe_name: String,
+    deleted: Vec<String>,
+    failed: Vec<String>,
+}
@@ -1,23 +1,33 @@
-use clap::Args;
+use clap::{Args, Subcommand};
 use miette::Context as _;
 use miette::{miette, IntoDiagnostic};
 
 pub use config::Config;
+pub use destroy::DestroyCommand;
 use ockam::Context;
 use ockam_api::cli_state::journeys::APPLICATION_EVENT_COMMAND_CONFIGURATION_FILE;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tracing::{instrument, Span};
 
+use crate::run::parser::config::ConfigParser;
 use crate::util::async_cmd;
-use crate::{docs, CommandGlobalOpts};
+use crate::util::parsers::duration_parser;
+use crate::{docs, Command, CommandGlobalOpts};
 
 mod config;
+mod destroy;
 pub mod parser;
+mod resources;
+mod watch;
 
 /// Create nodes given a declarative configuration file
 #[derive(Clone, Debug, Args)]
-#[command(hide = docs::hide())]
+#[command(hide = docs::hide(), args_conflicts_with_subcommands = true)]
 pub struct RunCommand {
+    #[command(subcommand)]
+    pub subcommand: Option<RunSubcommand>,
+
     /// Path to the recipe file
     #[arg(conflicts_with = "inline", value_name = "PATH")]
     pub recipe: Option<PathBuf>,
@@ -26,55 +36,64 @@ pub struct RunCommand {
     #[arg(long, conflicts_with = "recipe", value_name = "CONTENTS")]
     pub inline: Option<String>,
 
+    /// Name under which the created nodes, relays, policies and TCP portals are recorded,
+    /// so that they can be deleted with `ockam run destroy`.
+    /// It defaults to the name of the recipe file without its extension, or `inline`
+    #[arg(long = "name", value_name = "RECIPE_NAME")]
+    pub recipe_name: Option<String>,
+
     /// If true, block until all the created node exits it also
     /// propagate signals to created nodes.
     /// To be used with docker or kubernetes.
     #[arg(long)]
     pub blocking: bool,
+
+    /// Keep running and re-apply the recipe file every time it changes.
+    /// Only the differences with the previous version of the recipe are applied:
+    /// removed nodes, relays, policies and TCP portals are deleted, and new ones are created
+    #[arg(long, conflicts_with = "inline")]
+    pub watch: bool,
+
+    /// How often the recipe file is checked for changes when using `--watch`
+    #[arg(long, value_name = "DURATION", default_value = "1s", value_parser = duration_parser, requires = "watch")]
+    pub watch_interval: Duration,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum RunSubcommand {
+    Destroy(DestroyCommand),
 }
 
 impl RunCommand {
     pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        if let Some(RunSubcommand::Destroy(c)) = self.subcommand {
+            return c.run(opts);
+        }
         async_cmd(&self.name(), opts.clone(), |ctx| async move {
             self.async_run(&ctx, opts).await
         })
     }
 
     pub fn name(&self) -> String {
-        "run".to_string()
+        match &self.subcommand {
+            Some(RunSubcommand::Destroy(c)) => c.name(),
+            None => "run".to_string(),
+        }
     }
 
     #[instrument(skip_all, fields(app.event.command.configuration_file))]
     async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
-        let contents = match &self.inline {
-            Some(contents) => contents.to_string(),
+        let recipe_name = self.recipe_name()?;
+        if self.watch {
+            let path = self.recipe_path()?;
+            return watch::watch(ctx, &opts, &path, &recipe_name, self.watch_interval).await;
+        }
+        let (contents, base_dir) = match &self.inline {
+            Some(contents) => (contents.to_string(), PathBuf::from(".")),
             None => {
-                let path = match &self.recipe {
-                    Some(path) => path.clone(),
-                    None => {
-                        let mut path = std::env::current_dir()
-                            .into_diagnostic()
-                            .context("Failed to get current directory")?;
-                        let default_file_names = ["ockam.yml", "ockam.yaml"];
-                        let mut found = false;
-                        for file_name in default_file_names.iter() {
-                            path.push(file_name);
-                            if path.exists() {
-                                found = true;
-                                break;
-                            }
-                            path.pop();
-                        }
-                        if !found {
-                            return Err(miette!(
-                                "No default configuration file found in current directory.\n\
-                    Try passing the path to the config file with the --recipe flag."
-                            ));
-                        }
-                        path
-                    }
-                };
-                std::fs::read_to_string(path).into_diagnostic()?
+                let path = self.recipe_path()?;
+                let contents = std::fs::read_to_string(&path).into_diagnostic()?;
+                (contents, recipe_dir(&path))
             }
         };
         // Record the provided file
@@ -82,6 +101,52 @@ impl RunCommand {
             APPLICATION_EVENT_COMMAND_CONFIGURATION_FILE.as_str(),
             &contents,
         );
-        Config::parse_and_run(ctx, opts, &contents).await
+        let config = Config::parse(&Config::resolve_in(&contents, &base_dir)?)?;
+        config.run_recipe(ctx, &opts, &recipe_name).await?;
+        Ok(())
+    }
+
+    /// Return the name under which the resources created by the recipe are recorded
+    fn recipe_name(&self) -> miette::Result<String> {
+        if let Some(recipe_name) = &self.recipe_name {
+            return Ok(recipe_name.clone());
+        }
+        if self.inline.is_some() {
+            return Ok("inline".to_string());
+        }
+        let path = self.recipe_path()?;
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .ok_or_else(|| miette!("Invalid recipe path {}", path.display()))
+    }
+
+    /// Return the path of the recipe file, or of a default recipe file in the current directory
+    fn recipe_path(&self) -> miette::Result<PathBuf> {
+        if let Some(path) = &self.recipe {
+            return Ok(path.clone());
+        }
+        let mut path = std::env::current_dir()
+            .into_diagnostic()
+            .context("Failed to get current directory")?;
+        let default_file_names = ["ockam.yml", "ockam.yaml"];
+        for file_name in default_file_names.iter() {
+            path.push(file_name);
+            if path.exists() {
+                return Ok(path);
+            }
+            path.pop();
+        }
+        Err(miette!(
+            "No default configuration file found in current directory.\n\
+                    Try passing the path to the config file with the --recipe flag."
+        ))
+    }
+}
+
+/// Return the directory of a recipe file, used to resolve the files it includes
+pub(crate) fn recipe_dir(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
     }
 }
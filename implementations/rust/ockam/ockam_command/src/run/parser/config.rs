@@ -2,12 +2,21 @@ use miette::IntoDiagnostic;
 use ockam_core::errcode::{Kind, Origin};
 use serde::Deserialize;
 
-use crate::run::parser::Variables;
+use std::path::Path;
+
+use crate::run::parser::{Includes, Variables};
 
 pub trait ConfigParser<'de>: Deserialize<'de> {
-    /// Parse variables section and resolve them
+    /// Resolve the included files, relative to the current directory,
+    /// then parse the variables section and resolve the variables
     fn resolve(contents: &str) -> miette::Result<String> {
-        Variables::resolve(contents)
+        Self::resolve_in(contents, Path::new("."))
+    }
+
+    /// Resolve the included files, relative to the given directory,
+    /// then parse the variables section and resolve the variables
+    fn resolve_in(contents: &str, base_dir: &Path) -> miette::Result<String> {
+        Variables::resolve(&Includes::resolve(contents, base_dir)?)
     }
     /// Parses a given yaml configuration
     fn parse(contents: &'de str) -> miette::Result<Self> {
@@ -0,0 +1,253 @@
+use std::path::{Path, PathBuf};
+
+use miette::{miette, Context, IntoDiagnostic, Result};
+use ockam_api::colors::color_primary;
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+
+/// The `include` section of a configuration file.
+///
+/// It lists other configuration files which are loaded before the current one, for example:
+/// ```yaml
+/// include:
+///   - ./common/nodes.yaml
+///   - ./hosts/${HOSTNAME}.yaml
+///
+/// policies:
+///   - at: n1
+///     resource: r1
+///     expression: (= subject.component "c1")
+/// ```
+///
+/// The included files are merged in order, and the current file is merged last:
+///  - maps are merged key by key, so that a file can override some arguments of a resource
+///    defined in a previous file
+///  - lists are concatenated, skipping the items which are already present
+///  - any other value is replaced by the value of the last file defining it
+///
+/// Paths are relative to the directory of the file including them and can refer to environment variables.
+/// Within a file, YAML anchors, aliases and `<<` merge keys can be used to share fragments.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Includes {
+    #[serde(alias = "includes")]
+    pub include: Option<IncludePaths>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum IncludePaths {
+    Single(String),
+    List(Vec<String>),
+}
+
+impl IncludePaths {
+    fn paths(&self) -> Vec<&String> {
+        match self {
+            IncludePaths::Single(path) => vec![path],
+            IncludePaths::List(paths) => paths.iter().collect(),
+        }
+    }
+}
+
+impl Includes {
+    /// Return the contents of the configuration merged with all the files it includes.
+    /// The contents are returned unchanged if the configuration doesn't include other files
+    /// and doesn't use merge keys.
+    pub fn resolve(contents: &str, base_dir: &Path) -> Result<String> {
+        let includes = serde_yaml::from_str::<Includes>(contents).into_diagnostic()?;
+        if includes.include.is_none() {
+            let value: Value = serde_yaml::from_str(contents).into_diagnostic()?;
+            let mut merged = value.clone();
+            merged.apply_merge().into_diagnostic()?;
+            if merged == value {
+                return Ok(contents.to_string());
+            }
+        }
+        let merged = Self::load(contents, base_dir, &mut vec![])?;
+        serde_yaml::to_string(&merged).into_diagnostic()
+    }
+
+    /// Load a configuration and the files it includes, recursively.
+    /// The `visited` list contains the files currently being loaded, to detect cycles.
+    fn load(contents: &str, base_dir: &Path, visited: &mut Vec<PathBuf>) -> Result<Value> {
+        let mut value: Value = serde_yaml::from_str(contents).into_diagnostic()?;
+        value.apply_merge().into_diagnostic()?;
+
+        let includes = match value.as_mapping_mut() {
+            Some(mapping) => mapping
+                .remove("include")
+                .or_else(|| mapping.remove("includes")),
+            None => None,
+        };
+        let Some(includes) = includes else {
+            return Ok(value);
+        };
+        let includes: IncludePaths = serde_yaml::from_value(includes)
+            .into_diagnostic()
+            .context("The include section must be a path or a list of paths")?;
+
+        let mut merged = Value::Mapping(Mapping::new());
+        for path in includes.paths() {
+            let path = shellexpand::env(path).map_err(|e| {
+                miette!(
+                    "Failed to resolve variable '{}' in the included path {}: {}",
+                    color_primary(&e.var_name),
+                    color_primary(path),
+                    e.cause
+                )
+            })?;
+            let path = base_dir.join(path.as_ref());
+            let canonical = path.canonicalize().into_diagnostic().context(format!(
+                "Failed to find the included file {}",
+                path.display()
+            ))?;
+            if visited.contains(&canonical) {
+                return Err(miette!(
+                    "The file {} is included recursively",
+                    color_primary(path.display().to_string())
+                ));
+            }
+            let included = std::fs::read_to_string(&canonical)
+                .into_diagnostic()
+                .context(format!(
+                    "Failed to read the included file {}",
+                    path.display()
+                ))?;
+            let included_dir = canonical
+                .parent()
+                .map(|p| p.to_path_buf())
+                .unwrap_or_default();
+
+            visited.push(canonical);
+            let included = Self::load(&included, &included_dir, visited)?;
+            visited.pop();
+            merge(&mut merged, included);
+        }
+        merge(&mut merged, value);
+        Ok(merged)
+    }
+}
+
+/// Merge a value into another one, the `other` value taking precedence
+fn merge(value: &mut Value, other: Value) {
+    match (value, other) {
+        (Value::Mapping(value), Value::Mapping(other)) => {
+            for (k, v) in other {
+                match value.get_mut(&k) {
+                    Some(existing) => merge(existing, v),
+                    None => {
+                        value.insert(k, v);
+                    }
+                }
+            }
+        }
+        (Value::Sequence(value), Value::Sequence(other)) => {
+            for v in other {
+                if !value.contains(&v) {
+                    value.push(v);
+                }
+            }
+        }
+        (value, other) => *value = other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, contents: &str) {
+        std::fs::write(dir.join(name), contents).unwrap();
+    }
+
+    #[test]
+    fn configuration_without_includes_is_unchanged() {
+        let contents = r#"
+            nodes:
+              - n1
+        "#;
+        let resolved = Includes::resolve(contents, Path::new(".")).unwrap();
+        assert_eq!(resolved, contents);
+    }
+
+    #[test]
+    fn included_files_are_merged() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("hosts")).unwrap();
+        write(
+            dir.path(),
+            "common.yaml",
+            r#"
+            nodes:
+              - n1
+            tcp-outlets:
+              db:
+                to: 5432
+                at: n1
+            "#,
+        );
+        write(
+            dir.path(),
+            "hosts/h1.yaml",
+            r#"
+            include: ../common.yaml
+            nodes:
+              - n2
+            tcp-outlets:
+              db:
+                to: 5433
+            "#,
+        );
+        let contents = r#"
+            include:
+              - hosts/h1.yaml
+            relays: r1
+        "#;
+        let resolved = Includes::resolve(contents, dir.path()).unwrap();
+        let expected: Value = serde_yaml::from_str(
+            r#"
+            nodes:
+              - n1
+              - n2
+            tcp-outlets:
+              db:
+                to: 5433
+                at: n1
+            relays: r1
+            "#,
+        )
+        .unwrap();
+        let resolved: Value = serde_yaml::from_str(&resolved).unwrap();
+        assert_eq!(resolved, expected);
+    }
+
+    #[test]
+    fn fragments_can_be_shared_with_merge_keys() {
+        let contents = r#"
+            x-outlet: &outlet
+              at: n1
+              allow: (= subject.component "db")
+            tcp-outlets:
+              db1:
+                <<: *outlet
+                to: 5432
+              db2:
+                <<: *outlet
+                to: 5433
+        "#;
+        let resolved = Includes::resolve(contents, Path::new(".")).unwrap();
+        let resolved: Value = serde_yaml::from_str(&resolved).unwrap();
+        assert_eq!(resolved["tcp-outlets"]["db1"]["at"], "n1");
+        assert_eq!(resolved["tcp-outlets"]["db2"]["at"], "n1");
+        assert_eq!(resolved["tcp-outlets"]["db2"]["to"], 5433);
+    }
+
+    #[test]
+    fn recursive_includes_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "a.yaml", "include: b.yaml");
+        write(dir.path(), "b.yaml", "include: a.yaml");
+        let result = Includes::resolve("include: a.yaml", dir.path());
+        assert!(result.is_err());
+    }
+}
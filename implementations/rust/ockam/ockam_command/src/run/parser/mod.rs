@@ -1,3 +1,4 @@
+pub use includes::Includes;
 pub use variables::Variables;
 pub use version::Version;
 #[cfg(test)]
@@ -5,6 +6,7 @@ pub use version::VersionValue;
 
 pub(crate) mod building_blocks;
 pub mod config;
+pub mod includes;
 pub(crate) mod resource;
 pub mod variables;
 pub mod version;
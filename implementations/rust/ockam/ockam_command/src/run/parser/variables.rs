@@ -9,29 +9,68 @@ use tracing::warn;
 
 use crate::run::parser::building_blocks::{ArgKey, ArgValue};
 
+/// The `variables` section of a configuration file.
+///
+/// Each variable can be declared:
+///  - with a default value: `NODE_PORT: 3333`
+///  - without a value, when it must be set in the environment: `DB_HOST:`
+///  - with a description and an optional default value:
+///    ```yaml
+///    DB_PORT:
+///      description: Port of the database
+///      default: 5432
+///    ```
+///
+/// Variables can then be used anywhere in the configuration as `$VAR`, `${VAR}`,
+/// or `${VAR:-default}` to provide a default value for that specific use.
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Variables {
-    pub variables: Option<BTreeMap<ArgKey, ArgValue>>,
+    pub variables: Option<BTreeMap<ArgKey, Option<VariableDeclaration>>>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum VariableDeclaration {
+    Value(ArgValue),
+    Detailed {
+        default: Option<ArgValue>,
+        description: Option<String>,
+    },
+}
+
+impl VariableDeclaration {
+    fn default_value(&self) -> Option<&ArgValue> {
+        match self {
+            VariableDeclaration::Value(v) => Some(v),
+            VariableDeclaration::Detailed { default, .. } => default.as_ref(),
+        }
+    }
+
+    fn description(&self) -> Option<&String> {
+        match self {
+            VariableDeclaration::Value(_) => None,
+            VariableDeclaration::Detailed { description, .. } => description.as_ref(),
+        }
+    }
 }
 
 impl Variables {
     pub fn resolve(contents: &str) -> Result<String> {
         let self_ = serde_yaml::from_str::<Variables>(contents).into_diagnostic()?;
-        self_.load()?;
-        shellexpand::env(&contents)
-            .map(|c| c.to_string())
-            .map_err(|e| {
-                miette!(
-                    "Failed to resolve variable '{}': {}",
-                    color_primary(&e.var_name),
-                    e.cause
-                )
-            })
+        let mut missing = self_.load()?;
+        let resolved = interpolate(contents, &mut missing);
+        if missing.is_empty() {
+            Ok(resolved)
+        } else {
+            Err(self_.missing_variables_error(&missing))
+        }
     }
 
     /// Loads the variables into the environment, giving preference to variables set externally.
     /// That is, if one of the variables already exists, it will use the existing value.
-    fn load(&self) -> Result<()> {
+    /// Return the names of the declared variables which have no value.
+    fn load(&self) -> Result<Vec<String>> {
+        let mut missing = vec![];
         if let Some(vars) = &self.variables {
             for (k, v) in vars {
                 if std::env::var(k).is_ok() {
@@ -39,6 +78,10 @@ impl Variables {
                     eprintln!("{}", fmt_warn!("Loading variable '{k}' from environment"));
                     continue;
                 }
+                let Some(v) = v.as_ref().and_then(|v| v.default_value()) else {
+                    missing.push(k.clone());
+                    continue;
+                };
                 let v = v.to_string();
                 if v.is_empty() {
                     return Err(miette!("Empty value for variable '{k}'"));
@@ -46,7 +89,92 @@ impl Variables {
                 std::env::set_var(k, v);
             }
         }
-        Ok(())
+        Ok(missing)
+    }
+
+    /// Return an error listing all the variables which must be set
+    fn missing_variables_error(&self, missing: &[String]) -> miette::Report {
+        let names = missing
+            .iter()
+            .map(|name| {
+                let description = self
+                    .variables
+                    .as_ref()
+                    .and_then(|vars| vars.get(name))
+                    .and_then(|v| v.as_ref())
+                    .and_then(|v| v.description());
+                match description {
+                    Some(description) => format!("{} ({description})", color_primary(name)),
+                    None => color_primary(name).to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        miette!(
+            "The following variables must be set: {names}.\n\
+            Set them as environment variables or give them a default value in the 'variables' section."
+        )
+    }
+}
+
+/// Replace `$VAR`, `${VAR}` and `${VAR:-default}` with the values of the environment variables.
+///
+/// The names of the variables which are not set, and have no default value, are added to `missing`,
+/// so that all of them can be reported at once.
+fn interpolate(contents: &str, missing: &mut Vec<String>) -> String {
+    let mut result = String::with_capacity(contents.len());
+    let mut rest = contents;
+    while let Some(index) = rest.find('$') {
+        result.push_str(&rest[..index]);
+        let after = &rest[index + 1..];
+
+        // ${VAR} or ${VAR:-default}
+        if let Some(braced) = after.strip_prefix('{') {
+            if let Some(end) = braced.find('}') {
+                let (name, default) = match braced[..end].split_once(":-") {
+                    Some((name, default)) => (name, Some(default)),
+                    None => (&braced[..end], None),
+                };
+                if is_variable_name(name) {
+                    result.push_str(&variable_value(name, default, missing));
+                    rest = &braced[end + 1..];
+                    continue;
+                }
+            }
+            result.push('$');
+            rest = after;
+            continue;
+        }
+
+        // $VAR
+        let name_length = after
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(after.len());
+        if name_length == 0 {
+            result.push('$');
+        } else {
+            result.push_str(&variable_value(&after[..name_length], None, missing));
+        }
+        rest = &after[name_length..];
+    }
+    result.push_str(rest);
+    result
+}
+
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn variable_value(name: &str, default: Option<&str>, missing: &mut Vec<String>) -> String {
+    match (std::env::var(name), default) {
+        (Ok(value), _) => value,
+        (Err(_), Some(default)) => default.to_string(),
+        (Err(_), None) => {
+            if !missing.iter().any(|m| m == name) {
+                missing.push(name.to_string());
+            }
+            String::new()
+        }
     }
 }
 
@@ -116,4 +244,57 @@ mod tests {
         let resolved = Variables::resolve(input);
         assert!(resolved.is_err());
     }
+
+    #[test]
+    fn use_default_values_in_interpolation() {
+        std::env::set_var("DEFAULTS_SET_VAR", "set");
+        let input = r#"
+            nodes:
+              - ${DEFAULTS_SET_VAR:-unused}
+              - ${DEFAULTS_UNSET_VAR:-fallback}
+              - costs $ 5
+        "#;
+        let expected = r#"
+            nodes:
+              - set
+              - fallback
+              - costs $ 5
+        "#;
+        let resolved = Variables::resolve(input).unwrap();
+        assert_eq!(resolved, expected);
+    }
+
+    #[test]
+    fn declared_variables_can_have_a_description_and_a_default() {
+        let input = r#"
+            variables:
+              DETAILED_VAR:
+                description: Name of the node
+                default: n1
+
+            nodes:
+              - ${DETAILED_VAR}
+        "#;
+        let resolved = Variables::resolve(input).unwrap();
+        assert!(resolved.contains("- n1"));
+    }
+
+    #[test]
+    fn report_all_missing_variables() {
+        let input = r#"
+            variables:
+              REQUIRED_VAR_1:
+              REQUIRED_VAR_2:
+                description: Port of the database
+
+            nodes:
+              - ${UNDECLARED_VAR}
+              - ${REQUIRED_VAR_1}
+        "#;
+        let error = Variables::resolve(input).unwrap_err().to_string();
+        assert!(error.contains("REQUIRED_VAR_1"));
+        assert!(error.contains("REQUIRED_VAR_2"));
+        assert!(error.contains("Port of the database"));
+        assert!(error.contains("UNDECLARED_VAR"));
+    }
 }
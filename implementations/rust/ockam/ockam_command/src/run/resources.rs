@@ -0,0 +1,282 @@
+use std::sync::Arc;
+
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+use tokio::process::Command as ProcessCommand;
+use tracing::debug;
+
+use ockam_api::cli_state::RecipeResource;
+use ockam_api::colors::color_primary;
+use ockam_api::{fmt_ok, fmt_warn};
+use ockam_node::Context;
+
+use crate::run::parser::resource::utils::{binary_path, subprocess_stdio};
+use crate::run::parser::resource::*;
+use crate::CommandGlobalOpts;
+
+/// The kinds of resources which are tracked when running a recipe, in their order of creation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum ResourceKind {
+    Node,
+    Relay,
+    Policy,
+    TcpOutlet,
+    TcpInlet,
+}
+
+impl ResourceKind {
+    /// Name of the kind of resource, as used by the `ockam` subcommands
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            ResourceKind::Node => "node",
+            ResourceKind::Relay => "relay",
+            ResourceKind::Policy => "policy",
+            ResourceKind::TcpOutlet => "tcp-outlet",
+            ResourceKind::TcpInlet => "tcp-inlet",
+        }
+    }
+
+    pub(crate) fn parse(kind: &str) -> Option<Self> {
+        [
+            ResourceKind::Node,
+            ResourceKind::Relay,
+            ResourceKind::Policy,
+            ResourceKind::TcpOutlet,
+            ResourceKind::TcpInlet,
+        ]
+        .into_iter()
+        .find(|k| k.as_str() == kind)
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            ResourceKind::Node => "Node",
+            ResourceKind::Relay => "Relay",
+            ResourceKind::Policy => "Policy",
+            ResourceKind::TcpOutlet => "TCP Outlet",
+            ResourceKind::TcpInlet => "TCP Inlet",
+        }
+    }
+
+    /// Arguments of the `ockam` command deleting a resource of this kind
+    fn delete_args(&self, name: &str, node: Option<&str>) -> Vec<String> {
+        let mut args = vec![self.as_str().to_string(), "delete".into(), name.to_string()];
+        if let (Some(node), false) = (node, self == &ResourceKind::Node) {
+            args.extend(["--at".to_string(), node.to_string()]);
+        }
+        args.push("--yes".into());
+        args
+    }
+}
+
+/// A resource defined in a recipe, with the command creating it
+pub(crate) struct TrackedResource {
+    pub(crate) kind: ResourceKind,
+    pub(crate) name: String,
+    /// Node hosting the resource, if it's not the default node
+    pub(crate) node: Option<String>,
+    /// Used to detect changes to the resource between two versions of the recipe
+    pub(crate) definition: String,
+    command: Arc<dyn ParsedCommand>,
+    /// False if the resource has no name, in which case it can't be deleted by name
+    pub(crate) deletable: bool,
+    /// Set once the resource has been created and recorded in the state
+    recorded: Option<RecipeResource>,
+}
+
+impl TrackedResource {
+    fn new<C: ParsedCommand + std::fmt::Debug>(
+        kind: ResourceKind,
+        name: String,
+        node: Option<String>,
+        deletable: bool,
+        command: C,
+    ) -> Self {
+        Self {
+            kind,
+            name,
+            node,
+            definition: format!("{command:?}"),
+            command: Arc::new(command),
+            deletable,
+            recorded: None,
+        }
+    }
+
+    /// Return the tracked resources of a recipe, in their order of creation
+    pub(crate) fn parse_all(
+        nodes: Nodes,
+        relays: Relays,
+        policies: Policies,
+        tcp_outlets: TcpOutlets,
+        tcp_inlets: TcpInlets,
+    ) -> miette::Result<Vec<Self>> {
+        let mut resources = vec![];
+        for cmd in nodes.into_parsed_commands()? {
+            let name = cmd.name.clone();
+            resources.push(Self::new(ResourceKind::Node, name, None, true, cmd));
+        }
+        for cmd in relays.into_parsed_commands(None)? {
+            let (name, node) = (cmd.relay_name.clone(), cmd.to.clone());
+            resources.push(Self::new(ResourceKind::Relay, name, node, true, cmd));
+        }
+        for cmd in policies.into_parsed_commands()? {
+            let name = match (&cmd.resource, &cmd.resource_type) {
+                (Some(resource), _) => resource.as_str().to_string(),
+                (None, Some(resource_type)) => resource_type.to_string(),
+                (None, None) => continue,
+            };
+            let node = cmd.at.clone();
+            resources.push(Self::new(ResourceKind::Policy, name, node, true, cmd));
+        }
+        for cmd in tcp_outlets.into_parsed_commands(None)? {
+            // Outlets created without a name get an address chosen by the node,
+            // so they can't be deleted by name
+            let (name, deletable) = match &cmd.from {
+                Some(from) => (from.clone(), true),
+                None => (cmd.to.to_string(), false),
+            };
+            let node = cmd.at.clone();
+            resources.push(Self::new(
+                ResourceKind::TcpOutlet,
+                name,
+                node,
+                deletable,
+                cmd,
+            ));
+        }
+        for cmd in tcp_inlets.into_parsed_commands(None)? {
+            let (name, node) = (cmd.alias.clone(), cmd.at.clone());
+            resources.push(Self::new(ResourceKind::TcpInlet, name, node, true, cmd));
+        }
+        Ok(resources)
+    }
+
+    pub(crate) fn is_same_resource(&self, other: &TrackedResource) -> bool {
+        self.kind == other.kind && self.name == other.name && self.node == other.node
+    }
+
+    fn description(&self) -> String {
+        format!("{} {}", self.kind.description(), color_primary(&self.name))
+    }
+
+    /// Create the resource if it doesn't exist yet.
+    /// When it is created, it is recorded as a resource of the given recipe
+    pub(crate) async fn create(
+        &mut self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        recipe_name: &str,
+    ) -> miette::Result<()> {
+        debug!("creating {:?} {}", self.kind, self.name);
+        if !self.command.is_valid(ctx, opts).await? {
+            return Ok(());
+        }
+        self.command.run(ctx, opts).await?;
+        opts.terminal.write_line("")?;
+
+        if self.deletable {
+            let node_name = match (&self.kind, &self.node) {
+                (ResourceKind::Node, _) => self.name.clone(),
+                (_, Some(node)) => node.clone(),
+                (_, None) => opts.state.get_default_node().await?.name(),
+            };
+            let resource = RecipeResource::new(self.kind.as_str(), &self.name, &node_name);
+            opts.state
+                .store_recipe_resource(recipe_name, &resource)
+                .await?;
+            self.recorded = Some(resource);
+        }
+        Ok(())
+    }
+
+    /// Delete the resource and remove it from the resources of the given recipe
+    pub(crate) async fn delete(
+        &self,
+        opts: &CommandGlobalOpts,
+        recipe_name: &str,
+    ) -> miette::Result<()> {
+        if !self.deletable {
+            opts.terminal.write_line(&fmt_warn!(
+                "The {} has no name and can't be deleted automatically",
+                self.description()
+            ))?;
+            return Ok(());
+        }
+        delete_resource(opts, self.kind, &self.name, self.node.as_deref()).await?;
+        self.forget(opts, recipe_name).await
+    }
+
+    /// Remove the resource from the resources of the given recipe, without deleting it
+    pub(crate) async fn forget(
+        &self,
+        opts: &CommandGlobalOpts,
+        recipe_name: &str,
+    ) -> miette::Result<()> {
+        if let Some(resource) = &self.recorded {
+            opts.state
+                .delete_recipe_resource(recipe_name, resource)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Delete a resource by running the corresponding `ockam ... delete` command
+pub(crate) async fn delete_resource(
+    opts: &CommandGlobalOpts,
+    kind: ResourceKind,
+    name: &str,
+    node: Option<&str>,
+) -> miette::Result<()> {
+    debug!("deleting {:?} {}", kind, name);
+    let description = format!("{} {}", kind.description(), color_primary(name));
+    let status = ProcessCommand::new(binary_path())
+        .args(kind.delete_args(name, node))
+        .stdout(subprocess_stdio(true))
+        .stderr(subprocess_stdio(opts.terminal.is_quiet()))
+        .status()
+        .await
+        .into_diagnostic()?;
+    if !status.success() {
+        return Err(miette::miette!("Failed to delete the {description}"));
+    }
+    opts.terminal
+        .write_line(&fmt_ok!("Deleted the {description}"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resource_kinds_are_parsed_from_their_name() {
+        for kind in [
+            ResourceKind::Node,
+            ResourceKind::Relay,
+            ResourceKind::Policy,
+            ResourceKind::TcpOutlet,
+            ResourceKind::TcpInlet,
+        ] {
+            assert_eq!(ResourceKind::parse(kind.as_str()), Some(kind));
+        }
+        assert_eq!(ResourceKind::parse("vault"), None);
+    }
+
+    #[test]
+    fn delete_args() {
+        assert_eq!(
+            ResourceKind::TcpOutlet.delete_args("db", Some("n1")),
+            ["tcp-outlet", "delete", "db", "--at", "n1", "--yes"].map(String::from)
+        );
+        assert_eq!(
+            ResourceKind::Node.delete_args("n1", Some("n1")),
+            ["node", "delete", "n1", "--yes"].map(String::from)
+        );
+        assert_eq!(
+            ResourceKind::Relay.delete_args("default", None),
+            ["relay", "delete", "default", "--yes"].map(String::from)
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use colorful::Colorful;
+use miette::{Context as _, IntoDiagnostic};
+use tracing::instrument;
+
+use ockam_api::colors::color_primary;
+use ockam_api::{fmt_log, fmt_ok, fmt_warn};
+use ockam_node::Context;
+
+use crate::run::parser::config::ConfigParser;
+use crate::run::resources::{ResourceKind, TrackedResource};
+use crate::run::{recipe_dir, Config};
+use crate::CommandGlobalOpts;
+
+/// Run a recipe file, then re-apply it every time it changes.
+///
+/// The nodes, relays, policies and TCP portals of the recipe are tracked by name.
+/// When the file changes, its new version is compared to the resources created so far:
+///  - resources which are not part of the recipe anymore are deleted
+///  - new resources are created
+///  - resources with a different definition are deleted, then created again
+///
+/// The other sections of the recipe (vaults, identities, enrollment ticket and Kafka services)
+/// are only applied once, when the command starts. Resources relying on random default values,
+/// like a TCP inlet without a `from` address, are created again on every change.
+///
+/// The created resources are recorded under the recipe name, so that they can be deleted
+/// with `ockam run destroy`.
+#[instrument(skip_all, fields(path = %path.display(), recipe_name = recipe_name))]
+pub(super) async fn watch(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    path: &Path,
+    recipe_name: &str,
+    interval: Duration,
+) -> miette::Result<()> {
+    let base_dir = recipe_dir(path);
+    let contents = read_recipe(path)?;
+    let mut last_modified = modified_time(path)?;
+    let config = Config::parse(&Config::resolve_in(&contents, &base_dir)?)?;
+    let static_sections = Recipe::static_sections(&config);
+    let resources = config.run_recipe(ctx, opts, recipe_name).await?;
+    let mut applied = Recipe {
+        static_sections,
+        resources,
+    };
+
+    opts.terminal.write_line(&fmt_log!(
+        "Watching {} for changes. Press Ctrl+C to stop.",
+        color_primary(path.display().to_string())
+    ))?;
+
+    loop {
+        tokio::time::sleep(interval).await;
+
+        // The file might be temporarily missing while an editor saves it
+        let modified = match modified_time(path) {
+            Ok(modified) => modified,
+            Err(_) => continue,
+        };
+        if modified == last_modified {
+            continue;
+        }
+        last_modified = modified;
+
+        let desired =
+            match read_recipe(path).and_then(|contents| Recipe::parse(&contents, &base_dir)) {
+                Ok(desired) => desired,
+                Err(e) => {
+                    opts.terminal.write_line(&fmt_warn!(
+                        "The recipe couldn't be parsed, the changes are ignored: {e}"
+                    ))?;
+                    continue;
+                }
+            };
+        if let Err(e) = applied.reconcile(ctx, opts, recipe_name, desired).await {
+            opts.terminal.write_line(&fmt_warn!(
+                "The recipe could only be partially applied: {e}"
+            ))?;
+        }
+    }
+}
+
+fn read_recipe(path: &Path) -> miette::Result<String> {
+    std::fs::read_to_string(path)
+        .into_diagnostic()
+        .context(format!("Failed to read the recipe {}", path.display()))
+}
+
+fn modified_time(path: &Path) -> miette::Result<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .into_diagnostic()
+}
+
+/// The resources of a recipe which can be reconciled
+struct Recipe {
+    /// Description of the sections which are only applied once
+    static_sections: String,
+    resources: Vec<TrackedResource>,
+}
+
+impl Recipe {
+    fn parse(contents: &str, base_dir: &Path) -> miette::Result<Self> {
+        let config = Config::parse(&Config::resolve_in(contents, base_dir)?)?;
+        let static_sections = Self::static_sections(&config);
+        let resources = TrackedResource::parse_all(
+            config.nodes,
+            config.relays,
+            config.policies,
+            config.tcp_outlets,
+            config.tcp_inlets,
+        )?;
+        Ok(Self {
+            static_sections,
+            resources,
+        })
+    }
+
+    fn static_sections(config: &Config) -> String {
+        format!(
+            "{:?}",
+            (
+                &config.vaults,
+                &config.identities,
+                &config.project_enroll,
+                &config.kafka_inlet,
+                &config.kafka_outlet
+            )
+        )
+    }
+
+    /// Apply the differences between the resources created so far and the desired recipe
+    async fn reconcile(
+        &mut self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        recipe_name: &str,
+        desired: Recipe,
+    ) -> miette::Result<()> {
+        if self.static_sections != desired.static_sections {
+            opts.terminal.write_line(&fmt_warn!(
+                "Changes to vaults, identities, enrollment tickets and Kafka services are only applied when {} starts",
+                color_primary("ockam run")
+            ))?;
+            self.static_sections = desired.static_sections.clone();
+        }
+
+        // Resources which are removed, or changed and need to be created again
+        let mut to_remove: Vec<usize> = vec![];
+        for (index, resource) in self.resources.iter().enumerate() {
+            match desired.find(resource) {
+                Some(other) if other.definition == resource.definition => (),
+                _ => to_remove.push(index),
+            }
+        }
+
+        // Deleting a node also deletes all the resources it hosts
+        let removed_nodes: Vec<String> = to_remove
+            .iter()
+            .map(|i| &self.resources[*i])
+            .filter(|r| r.kind == ResourceKind::Node)
+            .map(|r| r.name.clone())
+            .collect();
+
+        // Delete resources in the reverse order of their creation
+        to_remove.sort_by_key(|i| std::cmp::Reverse(self.resources[*i].kind));
+        let mut removed = vec![];
+        for index in to_remove {
+            let resource = &self.resources[index];
+            let hosted_on_removed_node = resource
+                .node
+                .as_ref()
+                .map(|n| removed_nodes.contains(n))
+                .unwrap_or(false);
+            if hosted_on_removed_node {
+                resource.forget(opts, recipe_name).await?;
+            } else {
+                resource.delete(opts, recipe_name).await?;
+            }
+            removed.push(index);
+        }
+        // Resources hosted on deleted nodes are gone too
+        for (index, resource) in self.resources.iter().enumerate() {
+            if let Some(node) = &resource.node {
+                if removed_nodes.contains(node) && !removed.contains(&index) {
+                    resource.forget(opts, recipe_name).await?;
+                    removed.push(index);
+                }
+            }
+        }
+        removed.sort_unstable();
+        for index in removed.into_iter().rev() {
+            self.resources.remove(index);
+        }
+
+        // Create the missing resources, in the recipe order
+        let mut created = 0;
+        for mut resource in desired.resources {
+            if self.find(&resource).is_some() {
+                continue;
+            }
+            resource.create(ctx, opts, recipe_name).await?;
+            self.resources.push(resource);
+            created += 1;
+        }
+
+        opts.terminal.write_line(&fmt_ok!(
+            "The recipe has been re-applied ({} resource(s) created)",
+            created
+        ))?;
+        Ok(())
+    }
+
+    fn find(&self, resource: &TrackedResource) -> Option<&TrackedResource> {
+        self.resources.iter().find(|r| r.is_same_resource(resource))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resources(recipe: &Recipe) -> Vec<(ResourceKind, String)> {
+        recipe
+            .resources
+            .iter()
+            .map(|r| (r.kind, r.name.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn recipe_resources_are_tracked_by_name() {
+        let recipe = Recipe::parse(
+            r#"
+            nodes:
+              - n1
+            relays:
+              r1:
+                to: n1
+            tcp-outlets:
+              db:
+                to: 5432
+                at: n1
+            tcp-inlets:
+              web:
+                from: 127.0.0.1:6060
+                at: n1
+            "#,
+            Path::new("."),
+        )
+        .unwrap();
+        assert_eq!(
+            resources(&recipe),
+            vec![
+                (ResourceKind::Node, "n1".to_string()),
+                (ResourceKind::Relay, "r1".to_string()),
+                (ResourceKind::TcpOutlet, "db".to_string()),
+                (ResourceKind::TcpInlet, "web".to_string()),
+            ]
+        );
+        let outlet = &recipe.resources[2];
+        assert_eq!(outlet.node, Some("n1".to_string()));
+        assert!(outlet.deletable);
+    }
+
+    #[test]
+    fn changed_resources_have_a_different_definition() {
+        let before =
+            Recipe::parse("tcp-outlets: {db: {to: 5432, at: n1}}", Path::new(".")).unwrap();
+        let same = Recipe::parse("tcp-outlets: {db: {to: 5432, at: n1}}", Path::new(".")).unwrap();
+        let changed =
+            Recipe::parse("tcp-outlets: {db: {to: 5433, at: n1}}", Path::new(".")).unwrap();
+
+        let resource = &before.resources[0];
+        assert_eq!(
+            same.find(resource).map(|r| &r.definition),
+            Some(&resource.definition)
+        );
+        let other = changed.find(resource).unwrap();
+        assert_ne!(other.definition, resource.definition);
+    }
+}
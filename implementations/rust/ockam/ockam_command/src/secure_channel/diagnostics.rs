@@ -0,0 +1,53 @@
+use clap::Args;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+use ockam_api::nodes::models::secure_channel::SecureChannelDiagnosticsResponse;
+use ockam_api::nodes::BackgroundNodeClient;
+
+use crate::util::async_cmd;
+use crate::{docs, util::api, CommandGlobalOpts};
+use ockam_api::output::Output;
+
+const LONG_ABOUT: &str = include_str!("./static/diagnostics/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/diagnostics/after_long_help.txt");
+
+/// Show the diagnostics of the Secure Channel handshakes
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct DiagnosticsCommand {
+    /// Node for which the handshakes diagnostics are shown
+    #[arg(value_name = "NODE_NAME", long, display_order = 800)]
+    at: Option<String>,
+}
+
+impl DiagnosticsCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "secure-channel diagnostics".into()
+    }
+
+    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
+
+        let response: SecureChannelDiagnosticsResponse = node
+            .ask(ctx, api::show_secure_channel_diagnostics())
+            .await?;
+        opts.terminal
+            .stdout()
+            .plain(response.item()?)
+            .json(serde_json::to_string(&response).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -2,10 +2,12 @@ use std::fmt::Write;
 
 use clap::Args;
 use colorful::Colorful;
-use miette::miette;
+use miette::{miette, IntoDiagnostic};
+use serde::Serialize;
 use tokio::sync::Mutex;
 use tokio::try_join;
 
+use ockam::identity::TimestampInSeconds;
 use ockam::Context;
 use ockam_api::colors::OckamColor;
 use ockam_api::nodes::models::secure_channel::ShowSecureChannelResponse;
@@ -15,7 +17,7 @@ use ockam_core::{route, Address, Result};
 
 use crate::util::async_cmd;
 use crate::{docs, util::api, CommandGlobalOpts};
-use ockam_api::output::Output;
+use ockam_api::output::{human_readable_time, Output};
 
 const LONG_ABOUT: &str = include_str!("./static/list/long_about.txt");
 const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
@@ -79,7 +81,14 @@ impl ListCommand {
                 .join("")
         };
 
-        Ok(SecureChannelListOutput { from, to, at })
+        Ok(SecureChannelListOutput {
+            from,
+            to,
+            at,
+            their_identifier: show_response.their_identifier,
+            created_at: show_response.created_at,
+            last_activity: show_response.last_activity,
+        })
     }
 
     async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
@@ -126,16 +135,24 @@ impl ListCommand {
             &responses,
             &format!("No secure channels found on {}", node.node_name()),
         )?;
-        opts.terminal.stdout().plain(list).write_line()?;
+        opts.terminal
+            .stdout()
+            .plain(list)
+            .json(serde_json::to_string(&responses).into_diagnostic()?)
+            .write_line()?;
 
         Ok(())
     }
 }
 
+#[derive(Serialize)]
 pub struct SecureChannelListOutput {
     pub from: String,
     pub to: String,
     pub at: String,
+    pub their_identifier: Option<String>,
+    pub created_at: Option<TimestampInSeconds>,
+    pub last_activity: Option<TimestampInSeconds>,
 }
 
 impl Output for SecureChannelListOutput {
@@ -158,6 +175,29 @@ impl Output for SecureChannelListOutput {
                 .to_string()
                 .color(OckamColor::PrimaryResource.color())
         )?;
+        if let Some(their_identifier) = &self.their_identifier {
+            write!(
+                output,
+                "\nWith {}",
+                their_identifier
+                    .to_string()
+                    .color(OckamColor::PrimaryResource.color())
+            )?;
+        }
+        if let Some(created_at) = self.created_at {
+            write!(
+                output,
+                "\nCreated at {}",
+                human_readable_time(created_at).color(OckamColor::PrimaryResource.color())
+            )?;
+        }
+        if let Some(last_activity) = self.last_activity {
+            write!(
+                output,
+                "\nLast activity at {}",
+                human_readable_time(last_activity).color(OckamColor::PrimaryResource.color())
+            )?;
+        }
 
         Ok(output)
     }
@@ -2,11 +2,13 @@ pub(crate) mod listener;
 
 mod create;
 mod delete;
+mod diagnostics;
 mod list;
 mod show;
 
 pub use create::CreateCommand;
 pub use delete::DeleteCommand;
+pub use diagnostics::DiagnosticsCommand;
 pub use list::ListCommand;
 pub use show::ShowCommand;
 
@@ -36,6 +38,8 @@ enum SecureChannelSubcommand {
     #[command(display_order = 800)]
     Delete(DeleteCommand),
     #[command(display_order = 800)]
+    Diagnostics(DiagnosticsCommand),
+    #[command(display_order = 800)]
     List(ListCommand),
     #[command(display_order = 800)]
     Show(ShowCommand),
@@ -46,6 +50,7 @@ impl SecureChannelCommand {
         match self.subcommand {
             SecureChannelSubcommand::Create(c) => c.run(opts),
             SecureChannelSubcommand::Delete(c) => c.run(opts),
+            SecureChannelSubcommand::Diagnostics(c) => c.run(opts),
             SecureChannelSubcommand::List(c) => c.run(opts),
             SecureChannelSubcommand::Show(c) => c.run(opts),
         }
@@ -55,6 +60,7 @@ impl SecureChannelCommand {
         match &self.subcommand {
             SecureChannelSubcommand::Create(c) => c.name(),
             SecureChannelSubcommand::Delete(c) => c.name(),
+            SecureChannelSubcommand::Diagnostics(c) => c.name(),
             SecureChannelSubcommand::List(c) => c.name(),
             SecureChannelSubcommand::Show(c) => c.name(),
         }
@@ -1,5 +1,6 @@
-use crate::util::parsers::duration_parser;
+use crate::util::parsers::{bytes_parser, bytes_rate_parser, duration_parser};
 use clap::Args;
+use ockam::tcp::{TcpPortalRateLimit, TcpSocketOptions};
 use ockam_core::env::get_env;
 use ockam_multiaddr::MultiAddr;
 use std::time::Duration;
@@ -95,3 +96,100 @@ pub struct OptionalTimeoutArg {
     #[arg(long, value_name = "TIMEOUT", default_value = "5s", value_parser = duration_parser)]
     pub(crate) timeout: Option<Duration>,
 }
+
+#[derive(Clone, Debug, Args, Default, PartialEq)]
+pub struct TcpSocketOpts {
+    /// Idle time of a TCP connection before a keepalive probe is sent, for example `60s`.
+    /// Lower it when idle connections are dropped by a firewall. Defaults to 300s
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser)]
+    pub tcp_keepalive_time: Option<Duration>,
+
+    /// Time between two TCP keepalive probes. Defaults to 75s
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser)]
+    pub tcp_keepalive_interval: Option<Duration>,
+
+    /// Disable Nagle's algorithm on TCP connections (TCP_NODELAY)
+    #[arg(long, value_name = "BOOL", default_value_t = false)]
+    pub tcp_nodelay: bool,
+
+    /// Size, in bytes, of the receive buffer of TCP connections (SO_RCVBUF)
+    #[arg(long, value_name = "BYTES")]
+    pub tcp_recv_buffer_size: Option<u32>,
+
+    /// Size, in bytes, of the send buffer of TCP connections (SO_SNDBUF)
+    #[arg(long, value_name = "BYTES")]
+    pub tcp_send_buffer_size: Option<u32>,
+}
+
+impl TcpSocketOpts {
+    /// Socket options set with the arguments. The other options keep their default values
+    pub fn socket_options(&self) -> TcpSocketOptions {
+        let mut options = TcpSocketOptions::new();
+        if let Some(time) = self.tcp_keepalive_time {
+            options = options.with_keepalive_time(time);
+        }
+        if let Some(interval) = self.tcp_keepalive_interval {
+            options = options.with_keepalive_interval(interval);
+        }
+        if self.tcp_nodelay {
+            options = options.with_nodelay(true);
+        }
+        if let Some(size) = self.tcp_recv_buffer_size {
+            options = options.with_recv_buffer_size(size);
+        }
+        if let Some(size) = self.tcp_send_buffer_size {
+            options = options.with_send_buffer_size(size);
+        }
+        options
+    }
+
+    /// Command line arguments reproducing these options
+    pub fn as_args(&self) -> Vec<String> {
+        let mut args = vec![];
+        if let Some(time) = self.tcp_keepalive_time {
+            args.push("--tcp-keepalive-time".to_string());
+            args.push(format!("{}s", time.as_secs()));
+        }
+        if let Some(interval) = self.tcp_keepalive_interval {
+            args.push("--tcp-keepalive-interval".to_string());
+            args.push(format!("{}s", interval.as_secs()));
+        }
+        if self.tcp_nodelay {
+            args.push("--tcp-nodelay".to_string());
+        }
+        if let Some(size) = self.tcp_recv_buffer_size {
+            args.push("--tcp-recv-buffer-size".to_string());
+            args.push(size.to_string());
+        }
+        if let Some(size) = self.tcp_send_buffer_size {
+            args.push("--tcp-send-buffer-size".to_string());
+            args.push(size.to_string());
+        }
+        args
+    }
+}
+
+#[derive(Clone, Debug, Args, Default, PartialEq)]
+pub struct RateLimitOpts {
+    /// Maximum throughput of all the connections, for example `10MB/s` or `512KiB/s`
+    #[arg(long, value_name = "BYTES_PER_SECOND", value_parser = bytes_rate_parser)]
+    pub max_rate: Option<u64>,
+
+    /// Number of bytes which can be transferred at once after the connections were idle,
+    /// for example `1MB`. Defaults to one second of traffic at the maximum rate
+    #[arg(long, value_name = "BYTES", value_parser = bytes_parser, requires = "max_rate")]
+    pub max_burst: Option<u64>,
+}
+
+impl RateLimitOpts {
+    /// Rate limit set with the arguments, if any
+    pub fn rate_limit(&self) -> Option<TcpPortalRateLimit> {
+        self.max_rate.map(|rate| {
+            let rate_limit = TcpPortalRateLimit::new(rate);
+            match self.max_burst {
+                Some(burst) => rate_limit.with_burst(burst),
+                None => rate_limit,
+            }
+        })
+    }
+}
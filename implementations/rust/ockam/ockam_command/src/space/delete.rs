@@ -37,6 +37,11 @@ pub struct DeleteCommand {
     /// Confirm the deletion without prompting
     #[arg(display_order = 901, long, short)]
     yes: bool,
+
+    /// Read the names of the spaces to delete from stdin, one per line or as a JSON array.
+    /// The result of each operation is reported separately
+    #[arg(long, conflicts_with = "space_name")]
+    stdin: bool,
 }
 
 impl DeleteCommand {
@@ -92,6 +97,10 @@ impl DeleteCommandTui for DeleteTui {
         self.cmd.space_name.clone()
     }
 
+    fn cmd_arg_stdin(&self) -> bool {
+        self.cmd.stdin
+    }
+
     fn cmd_arg_delete_all(&self) -> bool {
         false
     }
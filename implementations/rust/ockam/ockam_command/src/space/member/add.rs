@@ -0,0 +1,70 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+use ockam_api::cloud::email_address::EmailAddress;
+use ockam_api::cloud::share::RoleInShare;
+use ockam_api::cloud::space::Spaces;
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+use ockam_api::nodes::InMemoryNode;
+
+use super::get_space;
+use crate::shared_args::IdentityOpts;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/add/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/add/after_long_help.txt");
+
+/// Add a member to a space
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct AddCommand {
+    /// Email address of the user to add
+    #[arg(value_parser = EmailAddress::parse)]
+    pub email: EmailAddress,
+
+    /// Role of the user in the space
+    #[arg(default_value_t = RoleInShare::Guest, long, short = 'R', value_parser = clap::value_parser!(RoleInShare))]
+    pub role: RoleInShare,
+
+    /// Name of the space. The default space is used if not specified
+    #[arg(long, value_name = "SPACE_NAME")]
+    pub space: Option<String>,
+
+    #[command(flatten)]
+    pub identity_opts: IdentityOpts,
+}
+
+#[async_trait]
+impl Command for AddCommand {
+    const NAME: &'static str = "space member add";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let space = get_space(&opts.state, &self.space).await?;
+        let node = InMemoryNode::start(ctx, &opts.state).await?;
+        let member = node
+            .add_space_member(ctx, &space.id, &self.email, self.role)
+            .await?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "{} has been added to the space {} with the role {}",
+                color_primary(member.email.to_string()),
+                color_primary(&space.name),
+                color_primary(member.role.to_string())
+            ))
+            .machine(member.email.to_string())
+            .json(serde_json::to_string(&member).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
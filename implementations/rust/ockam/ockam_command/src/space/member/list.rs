@@ -0,0 +1,53 @@
+use async_trait::async_trait;
+use clap::Args;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+use ockam_api::cloud::space::Spaces;
+use ockam_api::nodes::InMemoryNode;
+
+use super::get_space;
+use crate::shared_args::IdentityOpts;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/list/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
+
+/// List the members of a space and their roles
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ListCommand {
+    /// Name of the space. The default space is used if not specified
+    #[arg(long, value_name = "SPACE_NAME")]
+    pub space: Option<String>,
+
+    #[command(flatten)]
+    pub identity_opts: IdentityOpts,
+}
+
+#[async_trait]
+impl Command for ListCommand {
+    const NAME: &'static str = "space member list";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let space = get_space(&opts.state, &self.space).await?;
+        let node = InMemoryNode::start(ctx, &opts.state).await?;
+        let members = node.get_space_members(ctx, &space.id).await?;
+
+        let plain = opts.terminal.build_list(
+            &members,
+            &format!("The space {} has no members", space.name),
+        )?;
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(serde_json::to_string(&members).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
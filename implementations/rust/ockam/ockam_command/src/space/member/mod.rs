@@ -0,0 +1,63 @@
+use clap::{Args, Subcommand};
+
+pub use add::AddCommand;
+pub use list::ListCommand;
+pub use remove::RemoveCommand;
+
+use crate::{docs, Command, CommandGlobalOpts};
+use ockam_api::cli_state::CliState;
+use ockam_api::cloud::space::Space;
+
+mod add;
+mod list;
+mod remove;
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+
+/// Manage the members of a Space in Ockam Orchestrator
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    long_about = docs::about(LONG_ABOUT),
+)]
+pub struct MemberCommand {
+    #[command(subcommand)]
+    subcommand: MemberSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum MemberSubcommand {
+    #[command(display_order = 800)]
+    Add(AddCommand),
+    #[command(display_order = 800)]
+    Remove(RemoveCommand),
+    #[command(display_order = 800)]
+    List(ListCommand),
+}
+
+impl MemberCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        match self.subcommand {
+            MemberSubcommand::Add(c) => c.run(opts),
+            MemberSubcommand::Remove(c) => c.run(opts),
+            MemberSubcommand::List(c) => c.run(opts),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match &self.subcommand {
+            MemberSubcommand::Add(c) => c.name(),
+            MemberSubcommand::Remove(c) => c.name(),
+            MemberSubcommand::List(c) => c.name(),
+        }
+    }
+}
+
+/// Return the space with the given name, or the default space if no name is given
+async fn get_space(state: &CliState, space_name: &Option<String>) -> miette::Result<Space> {
+    Ok(match space_name {
+        Some(name) => state.get_space_by_name(name).await?,
+        None => state.get_default_space().await?,
+    })
+}
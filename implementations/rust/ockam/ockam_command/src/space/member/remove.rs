@@ -0,0 +1,76 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+
+use ockam::Context;
+use ockam_api::cloud::email_address::EmailAddress;
+use ockam_api::cloud::space::Spaces;
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+use ockam_api::nodes::InMemoryNode;
+
+use super::get_space;
+use crate::shared_args::IdentityOpts;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/remove/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/remove/after_long_help.txt");
+
+/// Remove a member from a space
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct RemoveCommand {
+    /// Email address of the user to remove
+    #[arg(value_parser = EmailAddress::parse)]
+    pub email: EmailAddress,
+
+    /// Name of the space. The default space is used if not specified
+    #[arg(long, value_name = "SPACE_NAME")]
+    pub space: Option<String>,
+
+    #[command(flatten)]
+    pub identity_opts: IdentityOpts,
+
+    /// Confirm the removal without prompting
+    #[arg(display_order = 901, long, short)]
+    yes: bool,
+}
+
+#[async_trait]
+impl Command for RemoveCommand {
+    const NAME: &'static str = "space member remove";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let space = get_space(&opts.state, &self.space).await?;
+        if !opts.terminal.confirmed_with_flag_or_prompt(
+            self.yes,
+            format!(
+                "Are you sure you want to remove {} from the space {}?",
+                self.email, space.name
+            ),
+        )? {
+            return Ok(());
+        }
+
+        let node = InMemoryNode::start(ctx, &opts.state).await?;
+        node.remove_space_member(ctx, &space.id, &self.email)
+            .await?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "{} has been removed from the space {}",
+                color_primary(self.email.to_string()),
+                color_primary(&space.name)
+            ))
+            .machine(self.email.to_string())
+            .json(serde_json::json!({ "email": self.email, "space": space.name }))
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -3,6 +3,7 @@ use clap::{Args, Subcommand};
 pub use create::CreateCommand;
 pub use delete::DeleteCommand;
 pub use list::ListCommand;
+pub use member::MemberCommand;
 pub use show::ShowCommand;
 
 use crate::{docs, CommandGlobalOpts};
@@ -10,6 +11,7 @@ use crate::{docs, CommandGlobalOpts};
 mod create;
 mod delete;
 mod list;
+mod member;
 mod show;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
@@ -36,6 +38,8 @@ pub enum SpaceSubcommand {
     List(ListCommand),
     #[command(display_order = 800)]
     Show(ShowCommand),
+    #[command(display_order = 800)]
+    Member(MemberCommand),
 }
 
 impl SpaceCommand {
@@ -45,6 +49,7 @@ impl SpaceCommand {
             SpaceSubcommand::Delete(c) => c.run(opts),
             SpaceSubcommand::List(c) => c.run(opts),
             SpaceSubcommand::Show(c) => c.run(opts),
+            SpaceSubcommand::Member(c) => c.run(opts),
         }
     }
 
@@ -54,6 +59,7 @@ impl SpaceCommand {
             SpaceSubcommand::Delete(c) => c.name(),
             SpaceSubcommand::List(c) => c.name(),
             SpaceSubcommand::Show(c) => c.name(),
+            SpaceSubcommand::Member(c) => c.name(),
         }
     }
 }
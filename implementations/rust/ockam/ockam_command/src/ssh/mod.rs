@@ -0,0 +1,176 @@
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use clap::Args;
+use miette::{miette, Context as _, IntoDiagnostic};
+use tracing::warn;
+
+use ockam::Context;
+use ockam_api::address::extract_address_value;
+use ockam_api::cli_state::random_name;
+use ockam_api::colors::color_primary;
+use ockam_api::nodes::models::portal::InletStatus;
+use ockam_api::nodes::service::tcp_inlets::{InletServiceOptions, Inlets};
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_api::{fmt_log, ConnectionStatus};
+use ockam_core::api::Reply;
+use ockam_multiaddr::MultiAddr;
+
+use crate::node::util::initialize_default_node;
+use crate::tcp::inlet::create::{default_from_addr, CreateCommand as CreateInletCommand};
+use crate::tcp::util::alias_parser;
+use crate::util::parsers::duration_parser;
+use crate::{docs, Command, CommandGlobalOpts, Result};
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
+
+/// Open an SSH session through a TCP Inlet
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct SshCommand {
+    /// Route to the TCP Outlet exposing the SSH server, or the name of the TCP Outlet service.
+    /// It accepts the same values as the `--to` argument of `ockam tcp-inlet create`
+    #[arg(value_name = "OUTLET")]
+    to: String,
+
+    /// Name of the relay used to reach the TCP Outlet
+    #[arg(long, value_name = "RELAY_NAME")]
+    via: Option<String>,
+
+    /// Node on which to start the TCP Inlet
+    #[arg(long, value_name = "NODE_NAME", value_parser = extract_address_value)]
+    at: Option<String>,
+
+    /// Alias of the TCP Inlet to use. If a TCP Inlet with this alias exists it is reused,
+    /// otherwise it is created. In both cases, the TCP Inlet is kept after the SSH session ends
+    #[arg(long, value_name = "ALIAS", value_parser = alias_parser)]
+    inlet: Option<String>,
+
+    /// User to log in as on the remote machine
+    #[arg(long, short = 'l', value_name = "USER")]
+    user: Option<String>,
+
+    /// Option passed to ssh with `-o`. This argument can be repeated
+    #[arg(long = "ssh-option", value_name = "OPTION")]
+    ssh_options: Vec<String>,
+
+    /// Path to the ssh executable
+    #[arg(long, value_name = "PATH", default_value = "ssh")]
+    ssh_command: String,
+
+    /// Time to wait for the TCP Outlet to be available
+    #[arg(long, value_name = "DURATION", default_value = "5s", value_parser = duration_parser)]
+    connection_wait: Duration,
+
+    /// Command to execute on the remote machine, passed after `--`
+    #[arg(last = true, value_name = "COMMAND")]
+    remote_command: Vec<String>,
+}
+
+#[async_trait]
+impl Command for SshCommand {
+    const NAME: &'static str = "ssh";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        initialize_default_node(ctx, &opts).await?;
+        let to = CreateInletCommand::parse_arg_to(&opts.state, &self.to, self.via.as_ref()).await?;
+        let to = MultiAddr::from_str(&to).into_diagnostic()?;
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
+
+        // Reuse the TCP Inlet if it already exists. An ephemeral TCP Inlet is created otherwise
+        let (inlet, is_ephemeral) = match &self.inlet {
+            Some(alias) => match node.show_inlet(ctx, alias).await? {
+                Reply::Successful(inlet) => (inlet, false),
+                Reply::Failed(..) => (self.create_inlet(ctx, &node, &to, alias).await?, false),
+            },
+            None => {
+                let alias = format!("ssh-{}", random_name());
+                (self.create_inlet(ctx, &node, &to, &alias).await?, true)
+            }
+        };
+
+        opts.terminal.write_line(&fmt_log!(
+            "Opening an SSH session through the TCP Inlet {} bound to {}",
+            color_primary(&inlet.alias),
+            color_primary(&inlet.bind_addr)
+        ))?;
+        let result = self.run_ssh(&inlet, &to).await;
+
+        if is_ephemeral {
+            if let Err(e) = node.delete_inlet(ctx, &inlet.alias).await {
+                warn!("Failed to delete the TCP Inlet {}: {e:?}", inlet.alias);
+            }
+        }
+        result
+    }
+}
+
+impl SshCommand {
+    /// Create a TCP Inlet to the outlet and wait until it is connected
+    async fn create_inlet(
+        &self,
+        ctx: &Context,
+        node: &BackgroundNodeClient,
+        to: &MultiAddr,
+        alias: &str,
+    ) -> Result<InletStatus> {
+        let reply = node
+            .create_inlet(
+                ctx,
+                &default_from_addr().to_string(),
+                to,
+                alias,
+                &None,
+                &None,
+                self.connection_wait,
+                true,
+                &None,
+                false,
+                false,
+                &InletServiceOptions::default(),
+            )
+            .await?;
+        match reply {
+            Reply::Successful(inlet) if inlet.status == ConnectionStatus::Up => Ok(inlet),
+            Reply::Successful(inlet) => {
+                let _ = node.delete_inlet(ctx, &inlet.alias).await;
+                Err(miette!("The TCP Outlet at {to} is not reachable"))?
+            }
+            Reply::Failed(..) => Err(miette!("Failed to create a TCP Inlet to {to}"))?,
+        }
+    }
+
+    /// Run ssh to the address of the TCP Inlet and wait until the session ends
+    async fn run_ssh(&self, inlet: &InletStatus, to: &MultiAddr) -> Result<()> {
+        let address = SocketAddr::from_str(&inlet.bind_addr).into_diagnostic()?;
+        let destination = match &self.user {
+            Some(user) => format!("{user}@{}", address.ip()),
+            None => address.ip().to_string(),
+        };
+
+        let mut ssh = tokio::process::Command::new(&self.ssh_command);
+        ssh.arg("-p").arg(address.port().to_string());
+        // The port of the TCP Inlet changes across sessions, so the host key is stored for the outlet instead
+        ssh.arg("-o").arg(format!("HostKeyAlias={to}"));
+        for option in &self.ssh_options {
+            ssh.arg("-o").arg(option);
+        }
+        ssh.arg(destination).args(&self.remote_command);
+
+        let status = ssh
+            .status()
+            .await
+            .into_diagnostic()
+            .wrap_err(format!("Could not run {}", self.ssh_command))?;
+        if !status.success() {
+            return Err(miette!("The SSH session ended with {status}"))?;
+        }
+        Ok(())
+    }
+}
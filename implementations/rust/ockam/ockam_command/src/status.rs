@@ -1,8 +1,11 @@
 use async_trait::async_trait;
 use std::fmt::Display;
+use std::io::Write;
+use std::time::Duration;
 
 use clap::Args;
 use colorful::Colorful;
+use console::Term;
 use miette::IntoDiagnostic;
 use serde::Serialize;
 use tracing::warn;
@@ -10,6 +13,7 @@ use tracing::warn;
 use ockam::Context;
 use ockam_api::cli_state::{EnrollmentFilter, IdentityEnrollment};
 use ockam_api::cloud::project::models::OrchestratorVersionInfo;
+use ockam_api::cloud::ControllerClient;
 use ockam_api::colors::color_primary;
 use ockam_api::nodes::models::node::NodeResources;
 use ockam_api::nodes::{BackgroundNodeClient, InMemoryNode};
@@ -17,6 +21,7 @@ use ockam_api::{fmt_heading, fmt_log, fmt_separator, fmt_warn};
 
 use crate::node::show::get_node_resources;
 use crate::shared_args::TimeoutArg;
+use crate::util::parsers::duration_parser;
 use crate::Result;
 use crate::{Command, CommandGlobalOpts};
 
@@ -25,6 +30,15 @@ use crate::{Command, CommandGlobalOpts};
 pub struct StatusCommand {
     #[command(flatten)]
     timeout: TimeoutArg,
+
+    /// Keep refreshing the status until the command is interrupted.
+    /// With `--output json`, one JSON object is printed per line at each refresh
+    #[arg(long, short)]
+    watch: bool,
+
+    /// Time to wait between two refreshes of the status when `--watch` is used
+    #[arg(long, value_name = "DURATION", default_value = "5s", value_parser = duration_parser)]
+    interval: Duration,
 }
 
 #[async_trait]
@@ -32,20 +46,15 @@ impl Command for StatusCommand {
     const NAME: &'static str = "status";
 
     async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
-        let identities_details = self.get_identities_details(&opts).await?;
-        let nodes = self.get_nodes_resources(ctx, &opts).await?;
-        let orchestrator_version = {
-            let node = InMemoryNode::start(ctx, &opts.state)
-                .await?
-                .with_timeout(self.timeout.timeout);
-            let controller = node.create_controller().await?;
-            controller
-                .get_orchestrator_version_info(ctx)
-                .await
-                .map_err(|e| warn!(%e, "Failed to retrieve orchestrator version"))
-                .unwrap_or_default()
-        };
-        let status = StatusData::from_parts(orchestrator_version, identities_details, nodes)?;
+        // the node and the controller client are created once, then reused for each refresh
+        let node = InMemoryNode::start(ctx, &opts.state)
+            .await?
+            .with_timeout(self.timeout.timeout);
+        let controller = node.create_controller().await?;
+        if self.watch {
+            return self.watch_status(ctx, &opts, &controller).await;
+        }
+        let status = self.get_status(ctx, &opts, &controller).await?;
         opts.terminal
             .stdout()
             .plain(&status)
@@ -56,6 +65,62 @@ impl Command for StatusCommand {
 }
 
 impl StatusCommand {
+    async fn get_status(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        controller: &ControllerClient,
+    ) -> Result<StatusData> {
+        let identities_details = self.get_identities_details(opts).await?;
+        let nodes = self.get_nodes_resources(ctx, opts).await?;
+        let orchestrator_version = controller
+            .get_orchestrator_version_info(ctx)
+            .await
+            .map_err(|e| warn!(%e, "Failed to retrieve orchestrator version"))
+            .unwrap_or_default();
+        StatusData::from_parts(orchestrator_version, identities_details, nodes)
+    }
+
+    /// Query the status every `interval` and redraw it, until the command is interrupted
+    async fn watch_status(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        controller: &ControllerClient,
+    ) -> Result<()> {
+        let output_format = opts.global_args.output_format()?;
+        let stdout = Term::stdout();
+        loop {
+            let status = self.get_status(ctx, opts, controller).await?;
+            if output_format.is_json() {
+                // Print one JSON object per line so that the output can be consumed as JSON lines
+                let mut out = std::io::stdout().lock();
+                writeln!(out, "{}", serde_json::to_string(&status).into_diagnostic()?)
+                    .into_diagnostic()?;
+                out.flush().into_diagnostic()?;
+            } else if output_format.is_yaml() {
+                // Print one YAML document per refresh
+                let mut out = std::io::stdout().lock();
+                write!(
+                    out,
+                    "---\n{}",
+                    serde_yaml::to_string(&status).into_diagnostic()?
+                )
+                .into_diagnostic()?;
+                out.flush().into_diagnostic()?;
+            } else {
+                if stdout.is_term() {
+                    stdout.clear_screen().into_diagnostic()?;
+                }
+                opts.terminal.clone().stdout().plain(&status).write_line()?;
+                if !stdout.is_term() {
+                    writeln!(std::io::stdout()).into_diagnostic()?;
+                }
+            }
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+
     async fn get_identities_details(
         &self,
         opts: &CommandGlobalOpts,
@@ -18,9 +18,12 @@ use crate::authority::{AuthorityCommand, AuthoritySubcommand};
 use crate::command_global_opts::CommandGlobalOpts;
 use crate::completion::CompletionCommand;
 use crate::credential::CredentialCommand;
+use crate::dashboard::DashboardCommand;
 use crate::enroll::EnrollCommand;
 use crate::environment::EnvironmentCommand;
 use crate::flow_control::FlowControlCommand;
+use crate::history::HistoryCommand;
+use crate::http_gateway::HttpGatewayCommand;
 use crate::identity::IdentityCommand;
 use crate::kafka::consumer::KafkaConsumerCommand;
 use crate::kafka::inlet::KafkaInletCommand;
@@ -30,9 +33,12 @@ use crate::lease::LeaseCommand;
 use crate::manpages::ManpagesCommand;
 use crate::markdown::MarkdownCommand;
 use crate::message::MessageCommand;
+use crate::mqtt::inlet::MqttInletCommand;
+use crate::mqtt::outlet::MqttOutletCommand;
 use crate::node::NodeCommand;
 use crate::node::NodeSubcommand;
 use crate::policy::PolicyCommand;
+use crate::postgres::outlet::PostgresOutletCommand;
 use crate::project::ProjectCommand;
 use crate::project_member::ProjectMemberCommand;
 use crate::relay::RelayCommand;
@@ -46,12 +52,17 @@ use crate::share::ShareCommand;
 use crate::shared_args::RetryOpts;
 use crate::sidecar::SidecarCommand;
 use crate::space::SpaceCommand;
+use crate::ssh::SshCommand;
 use crate::status::StatusCommand;
 use crate::subscription::SubscriptionCommand;
 use crate::tcp::connection::TcpConnectionCommand;
 use crate::tcp::inlet::TcpInletCommand;
 use crate::tcp::listener::TcpListenerCommand;
 use crate::tcp::outlet::TcpOutletCommand;
+use crate::tun::inlet::TunInletCommand;
+use crate::tun::outlet::TunOutletCommand;
+use crate::udp::inlet::UdpInletCommand;
+use crate::udp::outlet::UdpOutletCommand;
 use crate::util::async_cmd;
 use crate::vault::VaultCommand;
 use crate::worker::WorkerCommand;
@@ -81,10 +92,22 @@ pub enum OckamSubcommand {
     TcpConnection(TcpConnectionCommand),
     TcpOutlet(TcpOutletCommand),
     TcpInlet(TcpInletCommand),
+    UdpOutlet(UdpOutletCommand),
+    UdpInlet(UdpInletCommand),
+    TunOutlet(TunOutletCommand),
+    TunInlet(TunInletCommand),
+    Ssh(SshCommand),
 
     KafkaInlet(KafkaInletCommand),
     KafkaOutlet(KafkaOutletCommand),
 
+    MqttInlet(MqttInletCommand),
+    MqttOutlet(MqttOutletCommand),
+
+    PostgresOutlet(PostgresOutletCommand),
+
+    HttpGateway(HttpGatewayCommand),
+
     KafkaConsumer(KafkaConsumerCommand),
     KafkaProducer(KafkaProducerCommand),
 
@@ -102,7 +125,9 @@ pub enum OckamSubcommand {
 
     Run(RunCommand),
     Status(StatusCommand),
+    Tui(DashboardCommand),
     Reset(ResetCommand),
+    History(HistoryCommand),
 
     Completion(CompletionCommand),
     Markdown(MarkdownCommand),
@@ -136,8 +161,17 @@ impl OckamSubcommand {
             OckamSubcommand::TcpConnection(c) => c.run(opts),
             OckamSubcommand::TcpOutlet(c) => c.run(opts),
             OckamSubcommand::TcpInlet(c) => c.run(opts),
+            OckamSubcommand::UdpOutlet(c) => c.run(opts),
+            OckamSubcommand::UdpInlet(c) => c.run(opts),
+            OckamSubcommand::TunOutlet(c) => c.run(opts),
+            OckamSubcommand::TunInlet(c) => c.run(opts),
+            OckamSubcommand::Ssh(c) => c.run(opts),
 
             OckamSubcommand::KafkaInlet(c) => c.run(opts),
+            OckamSubcommand::MqttInlet(c) => c.run(opts),
+            OckamSubcommand::MqttOutlet(c) => c.run(opts),
+            OckamSubcommand::PostgresOutlet(c) => c.run(opts),
+            OckamSubcommand::HttpGateway(c) => c.run(opts),
             OckamSubcommand::KafkaConsumer(c) => c.run(opts),
             OckamSubcommand::KafkaProducer(c) => c.run(opts),
 
@@ -155,7 +189,9 @@ impl OckamSubcommand {
 
             OckamSubcommand::Run(c) => c.run(opts),
             OckamSubcommand::Status(c) => c.run(opts),
+            OckamSubcommand::Tui(c) => c.run(opts),
             OckamSubcommand::Reset(c) => c.run(opts),
+            OckamSubcommand::History(c) => c.run(opts),
 
             OckamSubcommand::Completion(c) => c.run(),
             OckamSubcommand::Markdown(c) => c.run(),
@@ -266,8 +302,17 @@ impl OckamSubcommand {
             OckamSubcommand::TcpConnection(c) => c.name(),
             OckamSubcommand::TcpOutlet(c) => c.name(),
             OckamSubcommand::TcpInlet(c) => c.name(),
+            OckamSubcommand::UdpOutlet(c) => c.name(),
+            OckamSubcommand::UdpInlet(c) => c.name(),
+            OckamSubcommand::TunOutlet(c) => c.name(),
+            OckamSubcommand::TunInlet(c) => c.name(),
+            OckamSubcommand::Ssh(c) => c.name(),
             OckamSubcommand::KafkaInlet(c) => c.name(),
             OckamSubcommand::KafkaOutlet(c) => c.name(),
+            OckamSubcommand::MqttInlet(c) => c.name(),
+            OckamSubcommand::MqttOutlet(c) => c.name(),
+            OckamSubcommand::PostgresOutlet(c) => c.name(),
+            OckamSubcommand::HttpGateway(c) => c.name(),
             OckamSubcommand::KafkaConsumer(c) => c.name(),
             OckamSubcommand::KafkaProducer(c) => c.name(),
             OckamSubcommand::SecureChannelListener(c) => c.name(),
@@ -280,7 +325,9 @@ impl OckamSubcommand {
             OckamSubcommand::Lease(c) => c.name(),
             OckamSubcommand::Run(c) => c.name(),
             OckamSubcommand::Status(c) => c.name(),
+            OckamSubcommand::Tui(c) => c.name(),
             OckamSubcommand::Reset(c) => c.name(),
+            OckamSubcommand::History(c) => c.name(),
             OckamSubcommand::Completion(c) => c.name(),
             OckamSubcommand::Markdown(c) => c.name(),
             OckamSubcommand::Manpages(c) => c.name(),
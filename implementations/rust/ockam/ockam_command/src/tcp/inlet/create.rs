@@ -1,15 +1,19 @@
 use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Duration;
 
 use async_trait::async_trait;
 use clap::Args;
 use colorful::Colorful;
-use miette::{miette, IntoDiagnostic};
+use miette::{miette, Context as _, IntoDiagnostic};
 use tracing::trace;
 
 use ockam::identity::Identifier;
+use ockam::tcp::{
+    HttpRewrite, PortalCompression, PortalTlsCertificate, TcpInletLimits, TcpInletLoadBalancing,
+};
 use ockam::Context;
 use ockam_abac::PolicyExpression;
 use ockam_api::address::extract_address_value;
@@ -20,7 +24,7 @@ use ockam_api::cli_state::journeys::{
 use ockam_api::cli_state::{random_name, CliState};
 use ockam_api::colors::color_primary;
 use ockam_api::nodes::models::portal::InletStatus;
-use ockam_api::nodes::service::tcp_inlets::Inlets;
+use ockam_api::nodes::service::tcp_inlets::{InletServiceOptions, Inlets};
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_api::{fmt_info, fmt_log, fmt_ok, fmt_warn, ConnectionStatus};
 use ockam_core::api::{Reply, Status};
@@ -28,11 +32,12 @@ use ockam_multiaddr::proto;
 use ockam_multiaddr::{MultiAddr, Protocol as _};
 
 use crate::node::util::initialize_default_node;
-use crate::shared_args::OptionalTimeoutArg;
+use crate::shared_args::{OptionalTimeoutArg, RateLimitOpts, TcpSocketOpts};
 use crate::tcp::util::alias_parser;
 use crate::{docs, Command, CommandGlobalOpts, Error};
 
 use crate::util::parsers::duration_parser;
+use crate::util::parsers::http_header_parser;
 use crate::util::parsers::socket_addr_parser;
 use crate::util::{find_available_port, port_is_free_guard, process_nodes_multiaddr};
 
@@ -59,6 +64,11 @@ pub struct CreateCommand {
     /// or just the name of the service as `outlet` or `/service/outlet`.
     /// If you are passing just the service name, consider using `--via` to specify the
     /// relay name (e.g. `ockam tcp-inlet create --to outlet --via myrelay`).
+    ///
+    /// Several routes can be given, separated by commas, in decreasing order of priority.
+    /// The TCP Inlet uses the first reachable route, and moves back to a route with a higher
+    /// priority as soon as it is reachable again. With `--load-balancing`, the connections are
+    /// spread across all the reachable routes instead.
     #[arg(long, display_order = 900, id = "ROUTE", default_value_t = default_to_addr())]
     pub to: String,
 
@@ -118,6 +128,79 @@ pub struct CreateCommand {
     /// TCP won't be used to transfer data between the Inlet and the Outlet.
     #[arg(long, value_name = "BOOL", default_value_t = false)]
     pub disable_tcp_fallback: bool,
+
+    #[command(flatten)]
+    pub tcp_socket_opts: TcpSocketOpts,
+
+    /// Compress the payloads exchanged with the TCP Outlet, with `lz4` or `zstd`.
+    /// The payloads are sent uncompressed if the TCP Outlet doesn't accept this algorithm
+    #[arg(long, value_name = "ALGORITHM", value_parser = PortalCompression::from_str)]
+    pub compression: Option<PortalCompression>,
+
+    #[command(flatten)]
+    pub rate_limit_opts: RateLimitOpts,
+
+    /// Parse the traffic of the TCP Inlet as HTTP/1.1 requests. The identifier used for the
+    /// secure channel is added to each request in the `X-Ockam-Identifier` header, replacing
+    /// the header sent by the client if any
+    #[arg(long)]
+    pub http: bool,
+
+    /// Header added to each HTTP request, as `name: value`. Can be given several times.
+    /// Implies `--http`
+    #[arg(long = "http-header", value_name = "HEADER", value_parser = http_header_parser)]
+    pub http_headers: Vec<(String, String)>,
+
+    /// Replace the `Host` header of each HTTP request. Implies `--http`
+    #[arg(long, value_name = "HOST")]
+    pub http_host: Option<String>,
+
+    /// PEM file of the certificate chain used to terminate TLS on the connections accepted
+    /// by the TCP Inlet. The decrypted traffic is sent to the TCP Outlet
+    #[arg(long, value_name = "PEM_FILE", requires = "tls_key")]
+    pub tls_cert: Option<PathBuf>,
+
+    /// PEM file of the private key of the certificate given with `--tls-cert`
+    #[arg(long, value_name = "PEM_FILE", requires = "tls_cert")]
+    pub tls_key: Option<PathBuf>,
+
+    /// Maximum number of connections open at the same time. Additional connections are closed
+    /// as soon as they are accepted
+    #[arg(long, value_name = "COUNT")]
+    pub max_connections: Option<u32>,
+
+    /// Maximum number of connections open at the same time from the same IP address
+    #[arg(long, value_name = "COUNT")]
+    pub max_connections_per_ip: Option<u32>,
+
+    /// Close the connections without any traffic, in either direction, during that time
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser)]
+    pub idle_timeout: Option<Duration>,
+
+    /// Spread the connections across all the routes given with `--to`: `round-robin` or
+    /// `least-connections`. The unresponsive TCP Outlets are ejected until they are
+    /// reachable again
+    #[arg(long, value_name = "STRATEGY", value_parser = TcpInletLoadBalancing::from_str)]
+    pub load_balancing: Option<TcpInletLoadBalancing>,
+
+    /// Expect a PROXY protocol v2 header at the start of each connection, sent by a load
+    /// balancer in front of the TCP Inlet. The address of the original client is sent to the
+    /// TCP Outlet instead of the address of the load balancer
+    #[arg(long)]
+    pub proxy_protocol: bool,
+
+    /// Keep the connections open for that duration when the connection to the TCP Outlet is
+    /// lost, for example while a relay reconnects. The connections resume once the TCP Outlet
+    /// is reachable again, without losing any data. The TCP Outlet must be created with the
+    /// same option
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser)]
+    pub resumption_grace_period: Option<Duration>,
+}
+
+fn read_pem_file(path: &Path) -> miette::Result<String> {
+    std::fs::read_to_string(path)
+        .into_diagnostic()
+        .context(format!("Failed to read the PEM file at {}", path.display()))
 }
 
 pub(crate) fn default_from_addr() -> SocketAddr {
@@ -138,6 +221,7 @@ impl Command for CreateCommand {
 
         let cmd = self.parse_args(&opts).await?;
 
+        let tls_certificate = cmd.tls_certificate()?;
         let mut node = BackgroundNodeClient::create(ctx, &opts.state, &cmd.at).await?;
         cmd.timeout.timeout.map(|t| node.set_timeout_mut(t));
 
@@ -150,6 +234,18 @@ impl Command for CreateCommand {
                 ));
             }
 
+            let options = InletServiceOptions {
+                socket_options: cmd.tcp_socket_opts.socket_options(),
+                compression: cmd.compression,
+                rate_limit: cmd.rate_limit_opts.rate_limit(),
+                fallback_outlet_addrs: cmd.fallback_routes(),
+                http_rewrite: cmd.http_rewrite(),
+                tls_certificate,
+                limits: cmd.limits(),
+                load_balancing: cmd.load_balancing,
+                proxy_protocol: cmd.proxy_protocol,
+                resumption: cmd.resumption_grace_period,
+            };
             loop {
                 let result: Reply<InletStatus> = node
                     .create_inlet(
@@ -164,6 +260,7 @@ impl Command for CreateCommand {
                         &cmd.secure_channel_identifier(&opts.state).await?,
                         cmd.enable_udp_puncture,
                         cmd.disable_tcp_fallback,
+                        &options,
                     )
                     .await?;
 
@@ -236,8 +333,66 @@ impl Command for CreateCommand {
 }
 
 impl CreateCommand {
+    /// Routes to the TCP Outlet, in decreasing order of priority
+    fn routes(&self) -> Vec<MultiAddr> {
+        self.to
+            .split(',')
+            .map(|route| MultiAddr::from_str(route).unwrap())
+            .collect()
+    }
+
     fn to(&self) -> MultiAddr {
-        MultiAddr::from_str(&self.to).unwrap()
+        self.routes().remove(0)
+    }
+
+    fn fallback_routes(&self) -> Vec<MultiAddr> {
+        self.routes().into_iter().skip(1).collect()
+    }
+
+    fn http_rewrite(&self) -> Option<HttpRewrite> {
+        if !self.http && self.http_headers.is_empty() && self.http_host.is_none() {
+            return None;
+        }
+        let http_rewrite = self
+            .http_headers
+            .iter()
+            .fold(HttpRewrite::new(), |rewrite, (name, value)| {
+                rewrite.with_header(name, value)
+            });
+        Some(match &self.http_host {
+            Some(host) => http_rewrite.with_host(host),
+            None => http_rewrite,
+        })
+    }
+
+    fn tls_certificate(&self) -> miette::Result<Option<PortalTlsCertificate>> {
+        match (&self.tls_cert, &self.tls_key) {
+            (Some(cert), Some(key)) => Ok(Some(PortalTlsCertificate::new(
+                read_pem_file(cert)?,
+                read_pem_file(key)?,
+            ))),
+            _ => Ok(None),
+        }
+    }
+
+    fn limits(&self) -> Option<TcpInletLimits> {
+        if self.max_connections.is_none()
+            && self.max_connections_per_ip.is_none()
+            && self.idle_timeout.is_none()
+        {
+            return None;
+        }
+        let mut limits = TcpInletLimits::new();
+        if let Some(max_connections) = self.max_connections {
+            limits = limits.with_max_connections(max_connections);
+        }
+        if let Some(max_connections_per_ip) = self.max_connections_per_ip {
+            limits = limits.with_max_connections_per_ip(max_connections_per_ip);
+        }
+        if let Some(idle_timeout) = self.idle_timeout {
+            limits = limits.with_idle_timeout(idle_timeout);
+        }
+        Some(limits)
     }
 
     async fn secure_channel_identifier(
@@ -272,8 +427,17 @@ impl CreateCommand {
 
     async fn parse_args(mut self, opts: &CommandGlobalOpts) -> miette::Result<Self> {
         port_is_free_guard(&self.from)?;
-        self.to = Self::parse_arg_to(&opts.state, self.to, self.via.as_ref()).await?;
-        if self.to().matches(0, &[proto::Project::CODE.into()]) && self.authorized.is_some() {
+        let mut routes = vec![];
+        for route in self.to.split(',') {
+            routes.push(Self::parse_arg_to(&opts.state, route.trim(), self.via.as_ref()).await?);
+        }
+        self.to = routes.join(",");
+        if self
+            .routes()
+            .iter()
+            .any(|route| route.matches(0, &[proto::Project::CODE.into()]))
+            && self.authorized.is_some()
+        {
             return Err(miette!(
                 "--authorized can not be used with project addresses"
             ))?;
@@ -281,7 +445,7 @@ impl CreateCommand {
         Ok(self)
     }
 
-    async fn parse_arg_to(
+    pub(crate) async fn parse_arg_to(
         state: &CliState,
         to: impl Into<String>,
         via: Option<&String>,
@@ -40,6 +40,11 @@ pub struct DeleteCommand {
     /// Delete all the TCP Inlets
     #[arg(long, short)]
     all: bool,
+
+    /// Read the names of the TCP Inlets to delete from stdin, one per line or as a JSON array.
+    /// The result of each operation is reported separately
+    #[arg(long, conflicts_with_all = ["ALIAS", "all"])]
+    stdin: bool,
 }
 
 #[async_trait]
@@ -83,6 +88,10 @@ impl<'a> DeleteCommandTui for DeleteTui<'a> {
         self.cmd.alias.clone()
     }
 
+    fn cmd_arg_stdin(&self) -> bool {
+        self.cmd.stdin
+    }
+
     fn cmd_arg_delete_all(&self) -> bool {
         self.cmd.all
     }
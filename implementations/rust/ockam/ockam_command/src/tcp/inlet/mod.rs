@@ -4,6 +4,7 @@ use create::CreateCommand;
 use delete::DeleteCommand;
 pub(crate) use list::ListCommand;
 pub(crate) use show::ShowCommand;
+use stats::StatsCommand;
 
 use crate::{docs, Command, CommandGlobalOpts};
 
@@ -11,6 +12,7 @@ pub(crate) mod create;
 mod delete;
 mod list;
 mod show;
+mod stats;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
@@ -34,6 +36,7 @@ pub enum TcpInletSubCommand {
     Delete(DeleteCommand),
     List(ListCommand),
     Show(ShowCommand),
+    Stats(StatsCommand),
 }
 
 impl TcpInletCommand {
@@ -43,6 +46,7 @@ impl TcpInletCommand {
             TcpInletSubCommand::Delete(c) => c.run(opts),
             TcpInletSubCommand::List(c) => c.run(opts),
             TcpInletSubCommand::Show(c) => c.run(opts),
+            TcpInletSubCommand::Stats(c) => c.run(opts),
         }
     }
 
@@ -52,6 +56,7 @@ impl TcpInletCommand {
             TcpInletSubCommand::Delete(c) => c.name(),
             TcpInletSubCommand::List(c) => c.name(),
             TcpInletSubCommand::Show(c) => c.name(),
+            TcpInletSubCommand::Stats(c) => c.name(),
         }
     }
 }
@@ -0,0 +1,93 @@
+use std::io::Write as _;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use clap::Args;
+use console::Term;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+use ockam_api::fmt_log;
+use ockam_api::nodes::models::portal::InletStats;
+use ockam_api::nodes::service::tcp_inlets::Inlets;
+use ockam_api::nodes::BackgroundNodeClient;
+
+use crate::node::NodeOpts;
+use crate::tcp::util::alias_parser;
+use crate::util::parsers::duration_parser;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const PREVIEW_TAG: &str = include_str!("../../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/stats/after_long_help.txt");
+
+/// Show the number of bytes transferred and the connections of a TCP Inlet
+#[derive(Clone, Debug, Args)]
+#[command(
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct StatsCommand {
+    /// Name of the inlet
+    #[arg(display_order = 900, required = true, id = "ALIAS", value_parser = alias_parser)]
+    alias: String,
+
+    /// Node on which the inlet was started
+    #[command(flatten)]
+    node_opts: NodeOpts,
+
+    /// Keep refreshing the stats until the command is interrupted
+    #[arg(long)]
+    watch: bool,
+
+    /// Time to wait between two refreshes of the stats when using `--watch`
+    #[arg(long, value_name = "DURATION", default_value = "2s", value_parser = duration_parser, requires = "watch")]
+    interval: Duration,
+}
+
+#[async_trait]
+impl Command for StatsCommand {
+    const NAME: &'static str = "tcp-inlet stats";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
+        if !self.watch {
+            let stats = get_inlet_stats(ctx, &node, &self.alias).await?;
+            opts.terminal
+                .stdout()
+                .plain(fmt_log!("{stats}"))
+                .machine(stats.bytes_in.to_string())
+                .json(serde_json::to_string(&stats).into_diagnostic()?)
+                .write_line()?;
+            return Ok(());
+        }
+
+        let is_json = opts.global_args.output_format()?.is_json();
+        let stdout = Term::stdout();
+        loop {
+            let stats = get_inlet_stats(ctx, &node, &self.alias).await?;
+            if is_json {
+                // Print one JSON object per line so that the output can be consumed as JSON lines
+                let mut out = std::io::stdout().lock();
+                writeln!(out, "{}", serde_json::to_string(&stats).into_diagnostic()?)
+                    .into_diagnostic()?;
+                out.flush().into_diagnostic()?;
+            } else if stdout.is_term() {
+                stdout.clear_line().into_diagnostic()?;
+                stdout.write_str(&fmt_log!("{stats}")).into_diagnostic()?;
+            } else {
+                stdout.write_line(&fmt_log!("{stats}")).into_diagnostic()?;
+            }
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+}
+
+async fn get_inlet_stats(
+    ctx: &Context,
+    node: &BackgroundNodeClient,
+    alias: &str,
+) -> miette::Result<InletStats> {
+    node.get_inlet_stats(ctx, alias)
+        .await?
+        .success()
+        .into_diagnostic()
+}
@@ -1,13 +1,18 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use clap::Args;
 use colorful::Colorful;
-use miette::IntoDiagnostic;
+use miette::{Context as _, IntoDiagnostic};
 
 use crate::node::util::initialize_default_node;
+use crate::shared_args::{RateLimitOpts, TcpSocketOpts};
+use crate::util::parsers::duration_parser;
 use crate::{docs, Command, CommandGlobalOpts};
+use ockam::tcp::{PortalCompression, PortalTlsVerification, TcpEgressAllowList, TcpEgressRule};
 use ockam::transport::HostnamePort;
 use ockam::Address;
 use ockam::Context;
@@ -18,7 +23,7 @@ use ockam_api::cli_state::journeys::{
 };
 use ockam_api::colors::color_primary;
 use ockam_api::nodes::models::portal::OutletStatus;
-use ockam_api::nodes::service::tcp_outlets::Outlets;
+use ockam_api::nodes::service::tcp_outlets::{OutletServiceOptions, Outlets};
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_api::{fmt_log, fmt_ok};
 
@@ -40,6 +45,16 @@ pub struct CreateCommand {
     #[arg(long, display_order = 900, id = "BOOLEAN")]
     pub tls: bool,
 
+    /// PEM file of the certificate authorities trusted to verify the certificate of the TCP
+    /// server, instead of the system ones. Implies `--tls`
+    #[arg(long, display_order = 900, value_name = "PEM_FILE")]
+    pub tls_ca: Option<PathBuf>,
+
+    /// Name expected in the certificate of the TCP server, instead of the hostname given
+    /// with `--to`. Implies `--tls`
+    #[arg(long, display_order = 900, value_name = "SERVER_NAME")]
+    pub tls_server_name: Option<String>,
+
     /// Address of your TCP Outlet, which is part of a route that is used in other
     /// commands. This address must be unique. This address identifies the TCP Outlet
     /// worker, on the node, on your local machine. Examples are `/service/my-outlet` or
@@ -66,6 +81,39 @@ pub struct CreateCommand {
         id = "POLICY_EXPRESSION"
     )]
     pub allow: Option<PolicyExpression>,
+
+    #[command(flatten)]
+    pub tcp_socket_opts: TcpSocketOpts,
+
+    /// Compression algorithms, `lz4` or `zstd`, accepted when a TCP Inlet proposes one.
+    /// Several algorithms can be given, separated by commas
+    #[arg(
+        long,
+        value_name = "ALGORITHM",
+        value_delimiter = ',',
+        value_parser = PortalCompression::from_str
+    )]
+    pub compression: Vec<PortalCompression>,
+
+    #[command(flatten)]
+    pub rate_limit_opts: RateLimitOpts,
+
+    /// Destination the TCP Outlet is allowed to connect to, as `HOST[:PORTS]`. `HOST` can be a
+    /// hostname, a wildcard like `*.example.com`, an IP address or a CIDR, and `PORTS` a port
+    /// or a range like `9092-9094`. Can be given several times. When set, the connections to
+    /// any other destination are refused
+    #[arg(long, value_name = "RULE", value_parser = TcpEgressRule::from_str)]
+    pub allow_destination: Vec<TcpEgressRule>,
+
+    /// Send a PROXY protocol v2 header at the start of each connection to the TCP server. It
+    /// contains the address of the client of the TCP Inlet and the identifier of the Inlet node
+    #[arg(long)]
+    pub proxy_protocol: bool,
+
+    /// Keep the connections to the TCP server open for that duration when the connection to
+    /// a TCP Inlet is lost, so that the TCP Inlet can resume them once it reconnects
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser)]
+    pub resumption_grace_period: Option<Duration>,
 }
 
 #[async_trait]
@@ -82,15 +130,26 @@ impl Command for CreateCommand {
             ));
         }
 
+        let tls_verification = self.tls_verification()?;
+        let options = OutletServiceOptions {
+            socket_options: self.tcp_socket_opts.socket_options(),
+            compressions: self.compression.clone(),
+            rate_limit: self.rate_limit_opts.rate_limit(),
+            tls_verification,
+            egress_allow_list: self.egress_allow_list(),
+            proxy_protocol: self.proxy_protocol,
+            resumption: self.resumption_grace_period,
+        };
         let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
         let node_name = node.node_name();
         let outlet_status = node
             .create_outlet(
                 ctx,
                 self.to.clone(),
-                self.tls,
+                self.tls || options.tls_verification.is_some(),
                 self.from.clone().map(Address::from).as_ref(),
                 self.allow.clone(),
+                &options,
             )
             .await?;
         self.add_outlet_created_journey_event(&opts, &node_name, &outlet_status)
@@ -122,6 +181,40 @@ impl Command for CreateCommand {
 }
 
 impl CreateCommand {
+    /// Return the verification of the TCP server certificate, if it differs from the default one
+    fn tls_verification(&self) -> miette::Result<Option<PortalTlsVerification>> {
+        if self.tls_ca.is_none() && self.tls_server_name.is_none() {
+            return Ok(None);
+        }
+        let mut tls_verification = PortalTlsVerification::new();
+        if let Some(path) = &self.tls_ca {
+            let ca_certificates =
+                std::fs::read_to_string(path)
+                    .into_diagnostic()
+                    .context(format!(
+                        "Failed to read the certificate authorities at {}",
+                        path.display()
+                    ))?;
+            tls_verification = tls_verification.with_ca_certificates(ca_certificates);
+        }
+        if let Some(server_name) = &self.tls_server_name {
+            tls_verification = tls_verification.with_server_name(server_name);
+        }
+        Ok(Some(tls_verification))
+    }
+
+    fn egress_allow_list(&self) -> Option<TcpEgressAllowList> {
+        if self.allow_destination.is_empty() {
+            return None;
+        }
+        Some(
+            self.allow_destination
+                .iter()
+                .cloned()
+                .fold(TcpEgressAllowList::new(), TcpEgressAllowList::with_rule),
+        )
+    }
+
     async fn add_outlet_created_journey_event(
         &self,
         opts: &CommandGlobalOpts,
@@ -3,6 +3,7 @@ use clap::{Args, Subcommand};
 use create::CreateCommand;
 use delete::DeleteCommand;
 use list::ListCommand;
+use ping::PingCommand;
 use show::ShowCommand;
 
 use crate::{docs, Command, CommandGlobalOpts};
@@ -10,6 +11,7 @@ use crate::{docs, Command, CommandGlobalOpts};
 pub mod create;
 mod delete;
 pub mod list;
+mod ping;
 mod show;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
@@ -33,6 +35,7 @@ pub enum TcpOutletSubCommand {
     Create(CreateCommand),
     Delete(DeleteCommand),
     List(ListCommand),
+    Ping(PingCommand),
     Show(ShowCommand),
 }
 
@@ -42,6 +45,7 @@ impl TcpOutletCommand {
             TcpOutletSubCommand::Create(c) => c.run(opts),
             TcpOutletSubCommand::Delete(c) => c.run(opts),
             TcpOutletSubCommand::List(c) => c.run(opts),
+            TcpOutletSubCommand::Ping(c) => c.run(opts),
             TcpOutletSubCommand::Show(c) => c.run(opts),
         }
     }
@@ -51,6 +55,7 @@ impl TcpOutletCommand {
             TcpOutletSubCommand::Create(c) => c.name(),
             TcpOutletSubCommand::Delete(c) => c.name(),
             TcpOutletSubCommand::List(c) => c.name(),
+            TcpOutletSubCommand::Ping(c) => c.name(),
             TcpOutletSubCommand::Show(c) => c.name(),
         }
     }
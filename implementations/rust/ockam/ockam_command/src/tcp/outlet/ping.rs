@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::miette;
+use serde::Serialize;
+
+use ockam::{Address, Context};
+use ockam_api::address::extract_address_value;
+use ockam_api::colors::color_primary;
+use ockam_api::nodes::service::default_address::DefaultAddress;
+use ockam_api::nodes::service::tcp_outlets::Outlets;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_api::{fmt_err, fmt_ok};
+
+use crate::tcp::util::alias_parser;
+use crate::util::parsers::duration_parser;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/ping/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/ping/after_long_help.txt");
+
+/// Check that the TCP server of a TCP Outlet is reachable
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct PingCommand {
+    /// Alias of the TCP Outlet to check. If you don't provide it, `outlet` will be used
+    #[arg(display_order = 900, id = "ALIAS", value_parser = alias_parser)]
+    pub alias: Option<String>,
+
+    /// Node hosting the TCP Outlet. If you don't provide it, the default node will be used
+    #[arg(long, display_order = 903, id = "NODE_NAME", value_parser = extract_address_value)]
+    pub at: Option<String>,
+
+    /// Maximum time to wait for the TCP connection to the TCP server
+    #[arg(long, display_order = 904, value_name = "DURATION", default_value = "5s", value_parser = duration_parser)]
+    pub timeout: Duration,
+}
+
+#[async_trait]
+impl Command for PingCommand {
+    const NAME: &'static str = "tcp-outlet ping";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
+        let worker_addr: Address = self
+            .alias
+            .clone()
+            .unwrap_or_else(|| DefaultAddress::OUTLET_SERVICE.to_string())
+            .into();
+        let status = node.ping_outlet(ctx, &worker_addr, self.timeout).await?;
+
+        let result = PingResult {
+            node_name: node.node_name(),
+            worker_addr: status.worker_addr.address().to_string(),
+            socket_addr: status.socket_addr.to_string(),
+            reachable: status.is_reachable(),
+            latency_ms: status.latency.map(|l| l.as_secs_f64() * 1000.0),
+            error: status.error.clone(),
+        };
+        let plain = match (&result.latency_ms, &result.error) {
+            (Some(latency_ms), _) => fmt_ok!(
+                "The TCP server at {} is reachable from the Outlet {} on node {} in {}",
+                color_primary(&result.socket_addr),
+                color_primary(&result.worker_addr),
+                color_primary(&result.node_name),
+                color_primary(format!("{latency_ms:.2}ms"))
+            ),
+            (None, error) => fmt_err!(
+                "The TCP server at {} is not reachable from the Outlet {} on node {}: {}",
+                color_primary(&result.socket_addr),
+                color_primary(&result.worker_addr),
+                color_primary(&result.node_name),
+                error.as_deref().unwrap_or("unknown error")
+            ),
+        };
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(serde_json::json!(result))
+            .write_line()?;
+
+        if !result.reachable {
+            Err(miette!(
+                "The TCP server at {} is not reachable",
+                result.socket_addr
+            ))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct PingResult {
+    node_name: String,
+    worker_addr: String,
+    socket_addr: String,
+    reachable: bool,
+    latency_ms: Option<f64>,
+    error: Option<String>,
+}
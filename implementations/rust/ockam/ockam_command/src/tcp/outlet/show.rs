@@ -40,6 +40,11 @@ pub struct ShowCommand {
     /// Show Outlet at the specified node. If you don't provide it, the default node will be used
     #[arg(long, display_order = 903, id = "NODE_NAME", value_parser = extract_address_value)]
     pub at: Option<String>,
+
+    /// Read the names of the TCP Outlets to show from stdin, one per line or as a JSON array.
+    /// The result of each operation is reported separately
+    #[arg(long, conflicts_with = "ALIAS")]
+    stdin: bool,
 }
 
 #[async_trait]
@@ -109,6 +114,10 @@ impl ShowCommandTui for ShowTui {
         self.cmd.alias.clone()
     }
 
+    fn cmd_arg_stdin(&self) -> bool {
+        self.cmd.stdin
+    }
+
     fn node_name(&self) -> Option<&str> {
         self.cmd.at.as_deref()
     }
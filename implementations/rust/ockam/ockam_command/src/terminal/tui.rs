@@ -1,10 +1,12 @@
+use std::io::Read;
+
 use colorful::Colorful;
 use console::Term;
-use miette::{miette, IntoDiagnostic};
+use miette::{miette, IntoDiagnostic, WrapErr};
 
 use ockam_api::colors::color_primary;
 use ockam_api::terminal::{Terminal, TerminalStream};
-use ockam_api::{fmt_info, fmt_warn};
+use ockam_api::{fmt_info, fmt_ok, fmt_warn};
 
 #[ockam_core::async_trait]
 pub trait ShowCommandTui {
@@ -14,13 +16,53 @@ pub trait ShowCommandTui {
     fn node_name(&self) -> Option<&str> {
         None
     }
+    /// Return true if the names of the items to show must be read from stdin
+    fn cmd_arg_stdin(&self) -> bool {
+        false
+    }
     fn terminal(&self) -> Terminal<TerminalStream<Term>>;
 
     async fn get_arg_item_name_or_default(&self) -> miette::Result<String>;
     async fn list_items_names(&self) -> miette::Result<Vec<String>>;
     async fn show_single(&self, item_name: &str) -> miette::Result<()>;
 
+    /// Show each of the items read from stdin, reporting whether each item could be shown.
+    /// The output of each item is captured, so that a single record is written per item
+    async fn show_from_stdin(&self) -> miette::Result<()> {
+        let terminal = self.terminal();
+        let mut failures = 0;
+        for item_name in read_items_names_from_stdin()? {
+            terminal.capture_stdout();
+            let result = self.show_single(&item_name).await;
+            let (plain, json) = terminal.take_captured_stdout();
+            match result {
+                Ok(()) => write_item_success(
+                    &terminal,
+                    "shown",
+                    Self::ITEM_NAME.singular(),
+                    &item_name,
+                    plain,
+                    json,
+                )?,
+                Err(e) => {
+                    failures += 1;
+                    write_item_failure(
+                        &terminal,
+                        "show",
+                        Self::ITEM_NAME.singular(),
+                        &item_name,
+                        e,
+                    )?;
+                }
+            }
+        }
+        check_bulk_failures(failures, "show", &Self::ITEM_NAME)
+    }
+
     async fn show(&self) -> miette::Result<()> {
+        if self.cmd_arg_stdin() {
+            return self.show_from_stdin().await;
+        }
         let terminal = self.terminal();
         let items_names = self.list_items_names().await?;
         if items_names.is_empty() {
@@ -115,6 +157,10 @@ pub trait DeleteCommandTui {
     fn cmd_arg_item_name(&self) -> Option<String>;
     fn cmd_arg_delete_all(&self) -> bool;
     fn cmd_arg_confirm_deletion(&self) -> bool;
+    /// Return true if the names of the items to delete must be read from stdin
+    fn cmd_arg_stdin(&self) -> bool {
+        false
+    }
     fn terminal(&self) -> Terminal<TerminalStream<Term>>;
 
     async fn list_items_names(&self) -> miette::Result<Vec<String>>;
@@ -135,7 +181,50 @@ pub trait DeleteCommandTui {
         Ok(())
     }
 
+    /// Delete each of the items read from stdin, reporting whether each item could be deleted.
+    /// Since stdin is used for the items names, the deletion must be confirmed with a flag.
+    /// The output of each deletion is replaced by a single record per item
+    async fn delete_from_stdin(&self) -> miette::Result<()> {
+        if !self.cmd_arg_confirm_deletion() {
+            return Err(miette!(
+                "Use --yes to confirm the deletion of the {} read from stdin",
+                Self::ITEM_NAME.plural()
+            ));
+        }
+        let terminal = self.terminal();
+        let mut failures = 0;
+        for item_name in read_items_names_from_stdin()? {
+            terminal.capture_stdout();
+            let result = self.delete_single(&item_name).await;
+            let _ = terminal.take_captured_stdout();
+            match result {
+                Ok(()) => write_item_success(
+                    &terminal,
+                    "deleted",
+                    Self::ITEM_NAME.singular(),
+                    &item_name,
+                    None,
+                    None,
+                )?,
+                Err(e) => {
+                    failures += 1;
+                    write_item_failure(
+                        &terminal,
+                        "delete",
+                        Self::ITEM_NAME.singular(),
+                        &item_name,
+                        e,
+                    )?;
+                }
+            }
+        }
+        check_bulk_failures(failures, "delete", &Self::ITEM_NAME)
+    }
+
     async fn delete(&self) -> miette::Result<()> {
+        if self.cmd_arg_stdin() {
+            return self.delete_from_stdin().await;
+        }
         let terminal = self.terminal();
         let items_names = self.list_items_names().await?;
 
@@ -245,6 +334,104 @@ pub trait DeleteCommandTui {
     }
 }
 
+/// Read the names of the items to process from stdin.
+/// The names are either given as a JSON array of strings or as one name per line.
+fn read_items_names_from_stdin() -> miette::Result<Vec<String>> {
+    let mut input = String::new();
+    std::io::stdin()
+        .read_to_string(&mut input)
+        .into_diagnostic()
+        .wrap_err("Failed to read the items names from stdin")?;
+    parse_items_names(&input)
+}
+
+fn parse_items_names(input: &str) -> miette::Result<Vec<String>> {
+    let input = input.trim();
+    let names: Vec<String> = if input.starts_with('[') {
+        serde_json::from_str(input)
+            .into_diagnostic()
+            .wrap_err("The items names must be a JSON array of strings")?
+    } else {
+        input.lines().map(|l| l.to_string()).collect()
+    };
+    // Skip empty names and duplicates, preserving the order of the input
+    let mut items_names: Vec<String> = vec![];
+    for name in names {
+        let name = name.trim();
+        if !name.is_empty() && !items_names.iter().any(|n| n == name) {
+            items_names.push(name.to_string());
+        }
+    }
+    Ok(items_names)
+}
+
+/// Write the successful processing of an item as a plain message or as a JSON object
+/// with the item name and an "ok" status, so that scripts can tell which items succeeded.
+/// The output captured while processing the item, if any, replaces the plain message
+/// and is added to the JSON object
+fn write_item_success(
+    terminal: &Terminal<TerminalStream<Term>>,
+    action_done: &str,
+    item_singular: &str,
+    item_name: &str,
+    plain: Option<String>,
+    json: Option<serde_json::Value>,
+) -> miette::Result<()> {
+    let plain = plain.unwrap_or_else(|| {
+        fmt_ok!(
+            "The {item_singular} {} was {action_done}",
+            color_primary(item_name)
+        )
+    });
+    let mut record = serde_json::json!({ "name": item_name, "status": "ok" });
+    if let Some(json) = json {
+        record["item"] = json;
+    }
+    terminal
+        .clone()
+        .stdout()
+        .plain(plain)
+        .json(record)
+        .one_per_line()
+        .write_line()?;
+    Ok(())
+}
+
+/// Write the failure to process an item as a plain warning or as a JSON object
+/// with the item name, an "error" status and the error, so that scripts can tell which
+/// items failed
+fn write_item_failure(
+    terminal: &Terminal<TerminalStream<Term>>,
+    action: &str,
+    item_singular: &str,
+    item_name: &str,
+    error: miette::Report,
+) -> miette::Result<()> {
+    terminal
+        .clone()
+        .stdout()
+        .plain(fmt_warn!(
+            "Failed to {action} {item_singular} {}: {error}",
+            color_primary(item_name)
+        ))
+        .json(serde_json::json!({
+            "name": item_name,
+            "status": "error",
+            "error": error.to_string()
+        }))
+        .one_per_line()
+        .write_line()?;
+    Ok(())
+}
+
+fn check_bulk_failures(failures: usize, action: &str, item: &PluralTerm) -> miette::Result<()> {
+    match failures {
+        0 => Ok(()),
+        1 => Err(miette!("Failed to {action} 1 {}", item.singular())),
+        n => Err(miette!("Failed to {action} {n} {}", item.plural())),
+    }
+}
+
 pub enum PluralTerm {
     Vault,
     Identity,
@@ -254,8 +441,16 @@ pub enum PluralTerm {
     Project,
     TcpInlet,
     TcpOutlet,
+    UdpInlet,
+    UdpOutlet,
+    TunInlet,
+    TunOutlet,
     KafkaInlet,
     KafkaOutlet,
+    MqttInlet,
+    MqttOutlet,
+    PostgresOutlet,
+    HttpGateway,
     Policy,
     Member,
 }
@@ -271,8 +466,16 @@ impl PluralTerm {
             PluralTerm::Project => "project",
             PluralTerm::TcpInlet => "tcp inlet",
             PluralTerm::TcpOutlet => "tcp outlet",
+            PluralTerm::UdpInlet => "udp inlet",
+            PluralTerm::UdpOutlet => "udp outlet",
+            PluralTerm::TunInlet => "tun inlet",
+            PluralTerm::TunOutlet => "tun outlet",
             PluralTerm::KafkaInlet => "kafka inlet",
             PluralTerm::KafkaOutlet => "kafka outlet",
+            PluralTerm::MqttInlet => "mqtt inlet",
+            PluralTerm::MqttOutlet => "mqtt outlet",
+            PluralTerm::PostgresOutlet => "postgres outlet",
+            PluralTerm::HttpGateway => "http gateway",
             PluralTerm::Policy => "policy",
             PluralTerm::Member => "member",
         }
@@ -288,10 +491,38 @@ impl PluralTerm {
             PluralTerm::Project => "projects",
             PluralTerm::TcpInlet => "tcp inlets",
             PluralTerm::TcpOutlet => "tcp outlets",
+            PluralTerm::UdpInlet => "udp inlets",
+            PluralTerm::UdpOutlet => "udp outlets",
+            PluralTerm::TunInlet => "tun inlets",
+            PluralTerm::TunOutlet => "tun outlets",
             PluralTerm::KafkaInlet => "kafka inlets",
             PluralTerm::KafkaOutlet => "kafka outlets",
+            PluralTerm::MqttInlet => "mqtt inlets",
+            PluralTerm::MqttOutlet => "mqtt outlets",
+            PluralTerm::PostgresOutlet => "postgres outlets",
+            PluralTerm::HttpGateway => "http gateways",
             PluralTerm::Policy => "policies",
             PluralTerm::Member => "members",
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_items_names_from_lines() {
+        let names = parse_items_names("n1\n  n2 \n\nn1\nn3\n").unwrap();
+        assert_eq!(names, vec!["n1", "n2", "n3"]);
+    }
+
+    #[test]
+    fn parse_items_names_from_json_array() {
+        let names = parse_items_names(r#" ["n1", "n2", "", "n2"] "#).unwrap();
+        assert_eq!(names, vec!["n1", "n2"]);
+
+        assert!(parse_items_names("[\"n1\", 2]").is_err());
+        assert!(parse_items_names("").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,174 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+
+use ockam::identity::Identifier;
+use ockam::Context;
+use ockam_abac::PolicyExpression;
+use ockam_api::address::extract_address_value;
+use ockam_api::cli_state::random_name;
+use ockam_api::colors::color_primary;
+use ockam_api::nodes::models::tun_portal::TunInterface;
+use ockam_api::nodes::service::tun_portals::TunPortals;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_api::tun::{TunNetwork, DEFAULT_TUN_DEVICE_NAME, DEFAULT_TUN_MTU};
+use ockam_api::{fmt_log, fmt_ok};
+use ockam_multiaddr::proto;
+use ockam_multiaddr::{MultiAddr, Protocol as _};
+
+use crate::node::util::initialize_default_node;
+use crate::tcp::inlet::create::CreateCommand as TcpInletCreateCommand;
+use crate::tcp::util::alias_parser;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
+
+/// Create a TUN Inlet
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct CreateCommand {
+    /// Node on which to start the TUN Inlet.
+    #[arg(long, display_order = 900, id = "NODE_NAME", value_parser = extract_address_value)]
+    pub at: Option<String>,
+
+    /// Address of the TUN device of the Inlet, with the prefix length of the network shared
+    /// with the TUN Outlet, for example `10.20.0.2/24`. Each Inlet of an Outlet must use a
+    /// different address.
+    #[arg(long, display_order = 900, id = "IP_NETWORK", value_parser = TunNetwork::from_str)]
+    pub address: TunNetwork,
+
+    /// Route to a TUN Outlet or the name of the TUN Outlet service you want to connect to,
+    /// for example `/node/n1/service/tun_outlet`.
+    ///
+    /// If you are connecting to a remote node through a relay in the Orchestrator, you can
+    /// pass just the name of the service and use `--via` to specify the relay name.
+    #[arg(long, display_order = 900, id = "ROUTE")]
+    pub to: String,
+
+    /// Name of the relay that this TUN Inlet will use to connect to the TUN Outlet.
+    #[arg(long, display_order = 900, id = "RELAY_NAME")]
+    pub via: Option<String>,
+
+    /// Network reached through the TUN Outlet, for example `192.168.1.0/24`.
+    /// Can be repeated to route several networks through the portal.
+    #[arg(long = "route", display_order = 901, value_name = "IP_NETWORK", value_parser = TunNetwork::from_str)]
+    pub routes: Vec<TunNetwork>,
+
+    /// MTU of the TUN device.
+    #[arg(long, display_order = 901, default_value_t = DEFAULT_TUN_MTU, value_parser = clap::value_parser!(u16).range(68..))]
+    pub mtu: u16,
+
+    /// Name of the TUN device. If it ends with `%d`, the first free number is used.
+    #[arg(long, display_order = 901, default_value = DEFAULT_TUN_DEVICE_NAME)]
+    pub device: String,
+
+    /// Authorized identifier for secure channel connection
+    #[arg(long, name = "AUTHORIZED", display_order = 900)]
+    pub authorized: Option<Identifier>,
+
+    /// Assign a name to this TUN Inlet.
+    #[arg(long, display_order = 900, id = "ALIAS", value_parser = alias_parser, default_value_t = random_name(), hide_default_value = true)]
+    pub alias: String,
+
+    /// Policy expression that will be used for access control to the TUN Inlet.
+    /// If you don't provide it, the policy set for the "tun-inlet" resource type will be used.
+    ///
+    /// You can check the fallback policy with `ockam policy show --resource-type tun-inlet`.
+    #[arg(
+        hide = true,
+        long,
+        visible_alias = "expression",
+        display_order = 900,
+        id = "POLICY_EXPRESSION"
+    )]
+    pub allow: Option<PolicyExpression>,
+}
+
+#[async_trait]
+impl Command for CreateCommand {
+    const NAME: &'static str = "tun-inlet create";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        initialize_default_node(ctx, &opts).await?;
+
+        let to =
+            TcpInletCreateCommand::parse_arg_to(&opts.state, &self.to, self.via.as_ref()).await?;
+        let to = MultiAddr::from_str(&to).into_diagnostic()?;
+        if to.matches(0, &[proto::Project::CODE.into()]) && self.authorized.is_some() {
+            return Err(miette!(
+                "--authorized can not be used with project addresses"
+            ))?;
+        }
+
+        let interface = TunInterface::new(
+            self.device.clone(),
+            self.address,
+            self.mtu,
+            self.routes.clone(),
+        );
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
+        let inlet_status = {
+            let pb = opts.terminal.progress_bar();
+            if let Some(pb) = pb.as_ref() {
+                pb.set_message(format!(
+                    "Creating TUN Inlet with address {}...\n",
+                    color_primary(self.address.to_string())
+                ));
+            }
+            node.create_tun_inlet(
+                ctx,
+                interface,
+                &to,
+                &self.alias,
+                self.authorized.clone(),
+                self.allow.clone(),
+            )
+            .await?
+        };
+
+        opts.terminal
+            .stdout()
+            .plain(
+                fmt_ok!(
+                    "Created a new TUN Inlet in the Node {} on the device {} with address {}\n",
+                    color_primary(node.node_name()),
+                    color_primary(&inlet_status.device),
+                    color_primary(self.address.to_string())
+                ) + &fmt_log!(
+                    "sending packets to the TUN Outlet at {}",
+                    color_primary(to.to_string())
+                ),
+            )
+            .machine(inlet_status.device.to_string())
+            .json(serde_json::json!(&inlet_status))
+            .write_line()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+
+    use super::*;
+
+    #[test]
+    fn command_can_be_parsed_from_name() {
+        let cmd = parse_cmd_from_args(
+            CreateCommand::NAME,
+            &[
+                "--address".to_string(),
+                "10.20.0.2/24".to_string(),
+                "--to".to_string(),
+                "/node/n1/service/tun_outlet".to_string(),
+                "--route".to_string(),
+                "192.168.1.0/24".to_string(),
+            ],
+        );
+        assert!(cmd.is_ok());
+    }
+}
@@ -0,0 +1,129 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use console::Term;
+
+use ockam::Context;
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+use ockam_api::nodes::models::tun_portal::TunInletStatus;
+use ockam_api::nodes::service::tun_portals::TunPortals;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_api::terminal::{Terminal, TerminalStream};
+use ockam_core::api::Request;
+
+use crate::node::NodeOpts;
+use crate::tcp::util::alias_parser;
+use crate::terminal::tui::DeleteCommandTui;
+use crate::tui::PluralTerm;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/delete/after_long_help.txt");
+
+/// Delete a TUN Inlet
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct DeleteCommand {
+    /// Delete the inlet with this alias
+    #[arg(display_order = 900, id = "ALIAS", value_parser = alias_parser)]
+    alias: Option<String>,
+
+    /// Node on which to stop the TUN Inlet. If none are provided, the default node will be used
+    #[command(flatten)]
+    node_opts: NodeOpts,
+
+    /// Confirm the deletion without prompting
+    #[arg(display_order = 901, long, short)]
+    yes: bool,
+
+    /// Delete all the TUN Inlets
+    #[arg(long, short)]
+    all: bool,
+
+    /// Read the names of the TUN Inlets to delete from stdin, one per line or as a JSON array.
+    /// The result of each operation is reported separately
+    #[arg(long, conflicts_with_all = ["ALIAS", "all"])]
+    stdin: bool,
+}
+
+#[async_trait]
+impl Command for DeleteCommand {
+    const NAME: &'static str = "tun-inlet delete";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        Ok(DeleteTui::run(ctx, opts, &self).await?)
+    }
+}
+
+struct DeleteTui<'a> {
+    ctx: &'a Context,
+    opts: CommandGlobalOpts,
+    node: BackgroundNodeClient,
+    cmd: &'a DeleteCommand,
+}
+
+impl<'a> DeleteTui<'a> {
+    pub async fn run(
+        ctx: &'a Context,
+        opts: CommandGlobalOpts,
+        cmd: &'a DeleteCommand,
+    ) -> miette::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &cmd.node_opts.at_node).await?;
+        let tui = Self {
+            ctx,
+            opts,
+            node,
+            cmd,
+        };
+        tui.delete().await
+    }
+}
+
+#[async_trait]
+impl<'a> DeleteCommandTui for DeleteTui<'a> {
+    const ITEM_NAME: PluralTerm = PluralTerm::TunInlet;
+
+    fn cmd_arg_item_name(&self) -> Option<String> {
+        self.cmd.alias.clone()
+    }
+
+    fn cmd_arg_stdin(&self) -> bool {
+        self.cmd.stdin
+    }
+
+    fn cmd_arg_delete_all(&self) -> bool {
+        self.cmd.all
+    }
+
+    fn cmd_arg_confirm_deletion(&self) -> bool {
+        self.cmd.yes
+    }
+
+    fn terminal(&self) -> Terminal<TerminalStream<Term>> {
+        self.opts.terminal.clone()
+    }
+
+    async fn list_items_names(&self) -> miette::Result<Vec<String>> {
+        let inlets: Vec<TunInletStatus> = self
+            .node
+            .ask(self.ctx, Request::get("/node/tun/inlet"))
+            .await?;
+        let names = inlets.into_iter().map(|i| i.alias).collect();
+        Ok(names)
+    }
+
+    async fn delete_single(&self, item_name: &str) -> miette::Result<()> {
+        let node_name = self.node.node_name();
+        self.node.delete_tun_inlet(self.ctx, item_name).await?;
+        self.terminal()
+            .stdout()
+            .plain(fmt_ok!(
+                "TUN Inlet with alias {} on Node {} has been deleted",
+                color_primary(item_name),
+                color_primary(&node_name)
+            ))
+            .json(serde_json::json!({ "alias": item_name, "node": node_name }))
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+
+use ockam::Address;
+use ockam::Context;
+use ockam_abac::PolicyExpression;
+use ockam_api::address::extract_address_value;
+use ockam_api::colors::color_primary;
+use ockam_api::nodes::models::tun_portal::TunInterface;
+use ockam_api::nodes::service::tun_portals::TunPortals;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_api::tun::{TunNetwork, DEFAULT_TUN_DEVICE_NAME, DEFAULT_TUN_MTU};
+use ockam_api::{fmt_log, fmt_ok};
+
+use crate::node::util::initialize_default_node;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
+
+/// Create a TUN Outlet that writes the packets of the TUN Inlets to a TUN device
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct CreateCommand {
+    /// Address of the TUN device of the Outlet, with the prefix length of the network shared
+    /// with the TUN Inlets, for example `10.20.0.1/24`. The packets of the Inlets must have
+    /// a source address in that network
+    #[arg(long, display_order = 900, id = "IP_NETWORK", value_parser = TunNetwork::from_str)]
+    pub address: TunNetwork,
+
+    /// MTU of the TUN device.
+    #[arg(long, display_order = 901, default_value_t = DEFAULT_TUN_MTU, value_parser = clap::value_parser!(u16).range(68..))]
+    pub mtu: u16,
+
+    /// Name of the TUN device. If it ends with `%d`, the first free number is used.
+    #[arg(long, display_order = 901, default_value = DEFAULT_TUN_DEVICE_NAME)]
+    pub device: String,
+
+    /// Address of your TUN Outlet, which is part of a route that is used in other
+    /// commands. This address must be unique. If you don't provide it, `/service/tun_outlet`
+    /// will be used. You will need this address when you create a TUN Inlet (using
+    /// `ockam tun-inlet create --to <OUTLET_ADDRESS>`)
+    #[arg(long, display_order = 902, id = "OUTLET_ADDRESS", value_parser = extract_address_value)]
+    pub from: Option<String>,
+
+    /// Your TUN Outlet will be created on this node. If you don't provide it, the default
+    /// node will be used
+    #[arg(long, display_order = 903, id = "NODE_NAME", value_parser = extract_address_value)]
+    pub at: Option<String>,
+
+    /// Policy expression that will be used for access control to the TUN Outlet.
+    /// If you don't provide it, the policy set for the "tun-outlet" resource type will be used.
+    ///
+    /// You can check the fallback policy with `ockam policy show --resource-type tun-outlet`.
+    #[arg(
+        hide = true,
+        long,
+        visible_alias = "expression",
+        display_order = 904,
+        id = "POLICY_EXPRESSION"
+    )]
+    pub allow: Option<PolicyExpression>,
+}
+
+#[async_trait]
+impl Command for CreateCommand {
+    const NAME: &'static str = "tun-outlet create";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        initialize_default_node(ctx, &opts).await?;
+
+        if let Some(pb) = opts.terminal.progress_bar() {
+            pb.set_message(format!(
+                "Creating a new TUN Outlet with address {}...\n",
+                color_primary(self.address.to_string())
+            ));
+        }
+
+        // The replies are only routed to the addresses of the Inlets
+        let interface = TunInterface::new(self.device.clone(), self.address, self.mtu, vec![]);
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
+        let outlet_status = node
+            .create_tun_outlet(
+                ctx,
+                interface,
+                self.from.clone().map(Address::from).as_ref(),
+                self.allow.clone(),
+            )
+            .await?;
+        let worker_addr = outlet_status.worker_addr.address().to_string();
+
+        opts.terminal
+            .stdout()
+            .plain(
+                fmt_ok!(
+                    "Created a new TUN Outlet in the Node {} at {} on the device {}\n\n",
+                    color_primary(node.node_name()),
+                    color_primary(&worker_addr),
+                    color_primary(&outlet_status.device)
+                ) + &fmt_log!(
+                    "You may want to take a look at the {}, {} commands next",
+                    color_primary("ockam relay"),
+                    color_primary("ockam tun-inlet")
+                ),
+            )
+            .machine(worker_addr)
+            .json(serde_json::json!(&outlet_status))
+            .write_line()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+
+    use super::*;
+
+    #[test]
+    fn command_can_be_parsed_from_name() {
+        let cmd = parse_cmd_from_args(
+            CreateCommand::NAME,
+            &["--address".to_string(), "10.20.0.1/24".to_string()],
+        );
+        assert!(cmd.is_ok());
+    }
+}
@@ -0,0 +1,157 @@
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+
+use ockam::identity::Identifier;
+use ockam::Context;
+use ockam_abac::PolicyExpression;
+use ockam_api::address::extract_address_value;
+use ockam_api::cli_state::random_name;
+use ockam_api::colors::color_primary;
+use ockam_api::nodes::service::udp_portals::UdpPortals;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_api::{fmt_log, fmt_ok};
+use ockam_multiaddr::proto;
+use ockam_multiaddr::{MultiAddr, Protocol as _};
+
+use crate::node::util::initialize_default_node;
+use crate::tcp::inlet::create::CreateCommand as TcpInletCreateCommand;
+use crate::tcp::util::alias_parser;
+use crate::util::parsers::{duration_parser, socket_addr_parser};
+use crate::{docs, Command, CommandGlobalOpts};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
+
+/// Create a UDP Inlet
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct CreateCommand {
+    /// Node on which to start the UDP Inlet.
+    #[arg(long, display_order = 900, id = "NODE_NAME", value_parser = extract_address_value)]
+    pub at: Option<String>,
+
+    /// Address on which to receive UDP datagrams.
+    #[arg(long, display_order = 900, id = "SOCKET_ADDRESS", value_parser = socket_addr_parser)]
+    pub from: SocketAddr,
+
+    /// Route to a UDP Outlet or the name of the UDP Outlet service you want to connect to,
+    /// for example `/node/n1/service/udp_outlet`.
+    ///
+    /// If you are connecting to a remote node through a relay in the Orchestrator, you can
+    /// pass just the name of the service and use `--via` to specify the relay name.
+    #[arg(long, display_order = 900, id = "ROUTE")]
+    pub to: String,
+
+    /// Name of the relay that this UDP Inlet will use to connect to the UDP Outlet.
+    #[arg(long, display_order = 900, id = "RELAY_NAME")]
+    pub via: Option<String>,
+
+    /// Authorized identifier for secure channel connection
+    #[arg(long, name = "AUTHORIZED", display_order = 900)]
+    pub authorized: Option<Identifier>,
+
+    /// Assign a name to this UDP Inlet.
+    #[arg(long, display_order = 900, id = "ALIAS", value_parser = alias_parser, default_value_t = random_name(), hide_default_value = true)]
+    pub alias: String,
+
+    /// Policy expression that will be used for access control to the UDP Inlet.
+    /// If you don't provide it, the policy set for the "udp-inlet" resource type will be used.
+    ///
+    /// You can check the fallback policy with `ockam policy show --resource-type udp-inlet`.
+    #[arg(
+        hide = true,
+        long,
+        visible_alias = "expression",
+        display_order = 900,
+        id = "POLICY_EXPRESSION"
+    )]
+    pub allow: Option<PolicyExpression>,
+
+    /// Time after which the flow of a UDP peer is closed when it doesn't send or receive any
+    /// datagram. Defaults to 1 minute
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser)]
+    pub idle_timeout: Option<Duration>,
+}
+
+#[async_trait]
+impl Command for CreateCommand {
+    const NAME: &'static str = "udp-inlet create";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        initialize_default_node(ctx, &opts).await?;
+
+        let to =
+            TcpInletCreateCommand::parse_arg_to(&opts.state, &self.to, self.via.as_ref()).await?;
+        let to = MultiAddr::from_str(&to).into_diagnostic()?;
+        if to.matches(0, &[proto::Project::CODE.into()]) && self.authorized.is_some() {
+            return Err(miette!(
+                "--authorized can not be used with project addresses"
+            ))?;
+        }
+
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
+        let inlet_status = {
+            let pb = opts.terminal.progress_bar();
+            if let Some(pb) = pb.as_ref() {
+                pb.set_message(format!(
+                    "Creating UDP Inlet at {}...\n",
+                    color_primary(self.from.to_string())
+                ));
+            }
+            node.create_udp_inlet(
+                ctx,
+                &self.from.to_string(),
+                &to,
+                &self.alias,
+                self.authorized.clone(),
+                self.allow.clone(),
+                self.idle_timeout,
+            )
+            .await?
+        };
+
+        opts.terminal
+            .stdout()
+            .plain(
+                fmt_ok!(
+                    "Created a new UDP Inlet in the Node {} bound to {}\n",
+                    color_primary(node.node_name()),
+                    color_primary(&inlet_status.bind_addr)
+                ) + &fmt_log!(
+                    "sending datagrams to the UDP Outlet at {}",
+                    color_primary(to.to_string())
+                ),
+            )
+            .machine(inlet_status.bind_addr.to_string())
+            .json(serde_json::json!(&inlet_status))
+            .write_line()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+
+    use super::*;
+
+    #[test]
+    fn command_can_be_parsed_from_name() {
+        let cmd = parse_cmd_from_args(
+            CreateCommand::NAME,
+            &[
+                "--from".to_string(),
+                "127.0.0.1:5353".to_string(),
+                "--to".to_string(),
+                "/node/n1/service/udp_outlet".to_string(),
+            ],
+        );
+        assert!(cmd.is_ok());
+    }
+}
@@ -0,0 +1,126 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+
+use ockam::transport::HostnamePort;
+use ockam::Address;
+use ockam::Context;
+use ockam_abac::PolicyExpression;
+use ockam_api::address::extract_address_value;
+use ockam_api::colors::color_primary;
+use ockam_api::nodes::service::udp_portals::UdpPortals;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_api::{fmt_log, fmt_ok};
+
+use crate::node::util::initialize_default_node;
+use crate::util::parsers::duration_parser;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
+
+/// Create a UDP Outlet that runs adjacent to a UDP server
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct CreateCommand {
+    /// UDP address where your UDP server is running: domain:port. Your Outlet will send the
+    /// datagrams of the UDP Inlets to it
+    #[arg(long, display_order = 900, id = "HOSTNAME_PORT", value_parser = HostnamePort::from_str)]
+    pub to: HostnamePort,
+
+    /// Address of your UDP Outlet, which is part of a route that is used in other
+    /// commands. This address must be unique. If you don't provide it, `/service/udp_outlet`
+    /// will be used. You will need this address when you create a UDP Inlet (using
+    /// `ockam udp-inlet create --to <OUTLET_ADDRESS>`)
+    #[arg(long, display_order = 902, id = "OUTLET_ADDRESS", value_parser = extract_address_value)]
+    pub from: Option<String>,
+
+    /// Your UDP Outlet will be created on this node. If you don't provide it, the default
+    /// node will be used
+    #[arg(long, display_order = 903, id = "NODE_NAME", value_parser = extract_address_value)]
+    pub at: Option<String>,
+
+    /// Policy expression that will be used for access control to the UDP Outlet.
+    /// If you don't provide it, the policy set for the "udp-outlet" resource type will be used.
+    ///
+    /// You can check the fallback policy with `ockam policy show --resource-type udp-outlet`.
+    #[arg(
+        hide = true,
+        long,
+        visible_alias = "expression",
+        display_order = 904,
+        id = "POLICY_EXPRESSION"
+    )]
+    pub allow: Option<PolicyExpression>,
+
+    /// Time after which the flow of a UDP Inlet peer is closed when it doesn't send or receive
+    /// any datagram. Defaults to 1 minute
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser)]
+    pub idle_timeout: Option<Duration>,
+}
+
+#[async_trait]
+impl Command for CreateCommand {
+    const NAME: &'static str = "udp-outlet create";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        initialize_default_node(ctx, &opts).await?;
+
+        if let Some(pb) = opts.terminal.progress_bar() {
+            pb.set_message(format!(
+                "Creating a new UDP Outlet to {}...\n",
+                color_primary(self.to.to_string())
+            ));
+        }
+
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
+        let outlet_status = node
+            .create_udp_outlet(
+                ctx,
+                self.to.clone(),
+                self.from.clone().map(Address::from).as_ref(),
+                self.allow.clone(),
+                self.idle_timeout,
+            )
+            .await?;
+        let worker_addr = outlet_status.worker_addr.address().to_string();
+
+        opts.terminal
+            .stdout()
+            .plain(
+                fmt_ok!(
+                    "Created a new UDP Outlet in the Node {} at {} sending datagrams to {}\n\n",
+                    color_primary(node.node_name()),
+                    color_primary(&worker_addr),
+                    color_primary(self.to.to_string())
+                ) + &fmt_log!(
+                    "You may want to take a look at the {}, {} commands next",
+                    color_primary("ockam relay"),
+                    color_primary("ockam udp-inlet")
+                ),
+            )
+            .machine(worker_addr)
+            .json(serde_json::json!(&outlet_status))
+            .write_line()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+
+    use super::*;
+
+    #[test]
+    fn command_can_be_parsed_from_name() {
+        let cmd = parse_cmd_from_args(
+            CreateCommand::NAME,
+            &["--to".to_string(), "127.0.0.1:53".to_string()],
+        );
+        assert!(cmd.is_ok());
+    }
+}
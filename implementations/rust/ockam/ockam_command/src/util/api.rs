@@ -53,6 +53,20 @@ pub(crate) fn show_secure_channel(
     Request::get("/node/show_secure_channel").body(payload)
 }
 
+/// Construct a request to unlock the encrypted vault of a node
+pub(crate) fn unlock_vault(
+    vault_name: &str,
+    passphrase: Option<String>,
+) -> Request<models::node::UnlockVaultRequest> {
+    let payload = models::node::UnlockVaultRequest::new(vault_name, passphrase);
+    Request::post("/node/vault/unlock").body(payload)
+}
+
+/// Construct a request to get the diagnostics of the Secure Channel handshakes
+pub(crate) fn show_secure_channel_diagnostics() -> Request<()> {
+    Request::get("/node/secure_channel_diagnostics")
+}
+
 /// Construct a request to create Secure Channel Listeners
 pub(crate) fn create_secure_channel_listener(
     addr: &Address,
@@ -6,6 +6,7 @@ use std::time::Duration;
 use miette::miette;
 
 use ockam::identity::Identifier;
+use ockam::tcp::TcpProxy;
 use ockam::transport::resolve_peer;
 use ockam_api::config::lookup::InternetAddress;
 use ockam_core::env::parse_duration;
@@ -52,6 +53,58 @@ pub(crate) fn project_name_parser(s: &str) -> Result<String> {
     }
 }
 
+/// Helper fn for validating a proxy URL from user input.
+/// The URL is kept as a string so that it can be sent to a node with its credentials
+pub(crate) fn proxy_parser(input: &str) -> Result<String> {
+    TcpProxy::from_str(input).map_err(|e| miette!("{e}"))?;
+    Ok(input.to_string())
+}
+
+/// Helper fn for parsing a number of bytes, with an optional unit:
+/// `B`, `KB`, `MB`, `GB` (powers of 1000) or `KiB`, `MiB`, `GiB` (powers of 1024)
+pub(crate) fn bytes_parser(input: &str) -> Result<u64> {
+    let input = input.trim();
+    let unit_start = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(unit_start);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| miette!("Invalid number of bytes: {input}"))?;
+    let multiplier: u64 = match unit.trim().to_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" => 1_000,
+        "mb" => 1_000_000,
+        "gb" => 1_000_000_000,
+        "kib" => 1 << 10,
+        "mib" => 1 << 20,
+        "gib" => 1 << 30,
+        _ => Err(miette!("Invalid unit of bytes: {unit}"))?,
+    };
+    Ok(number
+        .checked_mul(multiplier)
+        .filter(|bytes| *bytes > 0)
+        .ok_or_else(|| miette!("Invalid number of bytes: {input}"))?)
+}
+
+/// Helper fn for parsing a number of bytes per second, for example `10MB/s`
+pub(crate) fn bytes_rate_parser(input: &str) -> Result<u64> {
+    let input = input.trim();
+    bytes_parser(input.strip_suffix("/s").unwrap_or(input))
+}
+
+/// Helper fn for parsing an HTTP header given as `name: value`
+pub(crate) fn http_header_parser(input: &str) -> Result<(String, String)> {
+    let (name, value) = input
+        .split_once(':')
+        .ok_or_else(|| miette!("Invalid HTTP header, expected `name: value`: {input}"))?;
+    let name = name.trim();
+    if name.is_empty() {
+        Err(miette!("Invalid HTTP header, the name is empty: {input}"))?
+    }
+    Ok((name.to_string(), value.trim().to_string()))
+}
+
 pub(crate) fn duration_parser(arg: &str) -> std::result::Result<Duration, clap::Error> {
     parse_duration(arg).map_err(|_| Error::raw(ErrorKind::InvalidValue, "Invalid duration."))
 }
@@ -109,6 +162,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bytes() {
+        assert_eq!(bytes_parser("1000").unwrap(), 1000);
+        assert_eq!(bytes_parser("64KiB").unwrap(), 65536);
+        assert_eq!(bytes_rate_parser("10MB/s").unwrap(), 10_000_000);
+        assert_eq!(bytes_rate_parser("2 gb/s").unwrap(), 2_000_000_000);
+        assert!(bytes_parser("0").is_err());
+        assert!(bytes_parser("10XB").is_err());
+        assert!(bytes_rate_parser("MB/s").is_err());
+    }
+
     #[test]
     fn test_invalid_inputs() {
         // Test case 3: Any other format will throw an error
@@ -0,0 +1,82 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::{IntoDiagnostic, WrapErr};
+
+use ockam_api::cli_state::OCKAM_VAULT_RECOVERY_KEY;
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+use ockam_core::env::get_env;
+
+use crate::util::async_cmd;
+use crate::{docs, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/backup/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/backup/after_long_help.txt");
+
+/// Export the secrets of a vault, wrapped with a recovery key
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct BackupCommand {
+    /// Name of the vault. The default vault is used if not specified
+    pub name: Option<String>,
+
+    /// File where the backup is written
+    #[arg(long, short, value_name = "FILE")]
+    pub output: PathBuf,
+}
+
+impl BackupCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |_ctx| async move {
+            self.async_run(opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "vault backup".into()
+    }
+
+    async fn async_run(&self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let vault = opts.state.get_named_vault_or_default(&self.name).await?;
+        let vault_name = vault.name();
+        let recovery_key = recovery_key(&opts, true)?;
+        let backup = opts
+            .state
+            .backup_named_vault(&vault_name, &recovery_key)
+            .await?;
+
+        let contents = hex::encode(backup.export_as_bytes().into_diagnostic()?);
+        std::fs::write(&self.output, contents)
+            .into_diagnostic()
+            .wrap_err(format!(
+                "Unable to write the file {}",
+                self.output.display()
+            ))?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The secrets of the vault {} have been backed up to {}",
+                color_primary(&vault_name),
+                color_primary(self.output.display().to_string())
+            ))
+            .machine(self.output.display())
+            .json(serde_json::json!({ "name": &vault_name, "output": &self.output }))
+            .write_line()?;
+        Ok(())
+    }
+}
+
+/// Return the recovery key of a backup,
+/// either from the environment or by prompting the user
+pub(super) fn recovery_key(opts: &CommandGlobalOpts, confirm: bool) -> miette::Result<String> {
+    match get_env::<String>(OCKAM_VAULT_RECOVERY_KEY).into_diagnostic()? {
+        Some(recovery_key) => Ok(recovery_key),
+        None => Ok(opts.terminal.ask_for_password("Recovery key", confirm)?),
+    }
+}
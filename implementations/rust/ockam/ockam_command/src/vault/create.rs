@@ -1,13 +1,16 @@
 use std::path::PathBuf;
+use std::time::Duration;
 
 use async_trait::async_trait;
-use clap::Args;
+use clap::{Args, ValueEnum};
 use colorful::Colorful;
-use ockam_api::cli_state::UseAwsKms;
+use ockam_api::cli_state::{Pkcs11VaultConfig, UseAwsKms, OCKAM_VAULT_PASSPHRASE};
 use ockam_api::{fmt_info, fmt_ok};
+use ockam_core::env::get_env;
 
 use ockam_node::Context;
 
+use crate::util::parsers::duration_parser;
 use crate::{docs, Command, CommandGlobalOpts};
 
 const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
@@ -28,6 +31,68 @@ pub struct CreateCommand {
 
     #[arg(long, default_value = "false")]
     pub aws_kms: bool,
+
+    /// Path of a PKCS#11 module, for example /usr/lib/softhsm/libsofthsm2.so.
+    /// The signing keys of the vault are then stored in the token of the PKCS#11 slot
+    #[arg(
+        long,
+        value_name = "MODULE_PATH",
+        requires = "pkcs11_slot",
+        conflicts_with = "aws_kms"
+    )]
+    pub pkcs11_module: Option<PathBuf>,
+
+    /// Slot of the PKCS#11 token
+    #[arg(long, value_name = "SLOT", requires = "pkcs11_module")]
+    pub pkcs11_slot: Option<u64>,
+
+    /// Name of the environment variable containing the user PIN of the PKCS#11 token.
+    /// The PIN itself is never stored
+    #[arg(long, value_name = "ENV_VAR", requires = "pkcs11_module")]
+    pub pkcs11_pin_env: Option<String>,
+
+    /// Encrypt the secrets stored by the vault.
+    /// The passphrase is prompted for, or read from the OCKAM_VAULT_PASSPHRASE environment variable
+    #[arg(long, value_name = "METHOD", conflicts_with = "pkcs11_module")]
+    pub encrypt_with: Option<EncryptionArg>,
+
+    /// Lock an encrypted vault again after it has not been used for that duration,
+    /// for example "15m". It can then be unlocked with `ockam vault unlock`
+    #[arg(long, value_name = "DURATION", requires = "encrypt_with", value_parser = duration_parser)]
+    pub idle_timeout: Option<Duration>,
+}
+
+/// Source of the key encrypting the secrets of a vault
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum EncryptionArg {
+    /// The key is derived from a passphrase
+    Passphrase,
+    /// The key is generated and stored in the keychain of the operating system
+    Keychain,
+}
+
+impl CreateCommand {
+    fn pkcs11_config(&self) -> Option<Pkcs11VaultConfig> {
+        match (&self.pkcs11_module, self.pkcs11_slot) {
+            (Some(module), Some(slot)) => Some(Pkcs11VaultConfig::new(
+                module,
+                slot,
+                self.pkcs11_pin_env.clone(),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Return the passphrase of an encrypted vault,
+    /// either from the environment or by prompting the user
+    fn passphrase(opts: &CommandGlobalOpts) -> crate::Result<String> {
+        match get_env::<String>(OCKAM_VAULT_PASSPHRASE)? {
+            Some(passphrase) => Ok(passphrase),
+            None => Ok(opts
+                .terminal
+                .ask_for_password("Passphrase of the vault", true)?),
+        }
+    }
 }
 
 #[async_trait]
@@ -41,10 +106,34 @@ impl Command for CreateCommand {
         ))?;
         }
 
-        let vault = opts
-            .state
-            .create_named_vault(self.name, self.path, UseAwsKms::from(self.aws_kms))
-            .await?;
+        let use_aws_kms = UseAwsKms::from(self.aws_kms);
+        let vault = match (self.pkcs11_config(), self.encrypt_with) {
+            (Some(pkcs11), _) => {
+                opts.state
+                    .create_named_pkcs11_vault(self.name, self.path, pkcs11)
+                    .await?
+            }
+            (None, Some(encrypt_with)) => {
+                let passphrase = match encrypt_with {
+                    EncryptionArg::Passphrase => Some(Self::passphrase(&opts)?),
+                    EncryptionArg::Keychain => None,
+                };
+                opts.state
+                    .create_named_encrypted_vault(
+                        self.name,
+                        self.path,
+                        use_aws_kms,
+                        passphrase,
+                        self.idle_timeout,
+                    )
+                    .await?
+            }
+            (None, None) => {
+                opts.state
+                    .create_named_vault(self.name, self.path, use_aws_kms)
+                    .await?
+            }
+        };
 
         opts.terminal
             .stdout()
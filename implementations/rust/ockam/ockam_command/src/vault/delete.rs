@@ -29,6 +29,11 @@ pub struct DeleteCommand {
 
     #[arg(long, short)]
     all: bool,
+
+    /// Read the names of the vaults to delete from stdin, one per line or as a JSON array.
+    /// The result of each operation is reported separately
+    #[arg(long, conflicts_with_all = ["name", "all"])]
+    stdin: bool,
 }
 
 impl DeleteCommand {
@@ -67,6 +72,10 @@ impl DeleteCommandTui for DeleteTui {
         self.cmd.name.clone()
     }
 
+    fn cmd_arg_stdin(&self) -> bool {
+        self.cmd.stdin
+    }
+
     fn cmd_arg_delete_all(&self) -> bool {
         self.cmd.all
     }
@@ -0,0 +1,131 @@
+use std::fmt::Write;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+use serde::Serialize;
+
+use ockam_api::colors::color_primary;
+use ockam_api::{fmt_log, fmt_ok, fmt_warn};
+use ockam_node::Context;
+
+use crate::{docs, Command, CommandGlobalOpts, Result};
+
+const LONG_ABOUT: &str = include_str!("./static/migrate/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/migrate/after_long_help.txt");
+
+/// Migrate the identities of a vault to another vault
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct MigrateCommand {
+    /// Name of the vault currently storing the identity keys
+    #[arg()]
+    name: String,
+
+    /// Name of the vault where the identity keys must be stored
+    #[arg(long, value_name = "VAULT_NAME")]
+    to: String,
+
+    /// Confirm the migration without prompting
+    #[arg(long, short)]
+    yes: bool,
+}
+
+#[async_trait]
+impl Command for MigrateCommand {
+    const NAME: &'static str = "vault migrate";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> Result<()> {
+        // check that both vaults exist before asking for a confirmation
+        let _ = opts.state.get_named_vault(&self.name).await?;
+        let _ = opts.state.get_named_vault(&self.to).await?;
+        if !opts.terminal.confirmed_with_flag_or_prompt(
+            self.yes,
+            format!(
+                "Are you sure you want to move the identity keys of the vault {} to the vault {}?",
+                self.name, self.to
+            ),
+        )? {
+            return Ok(());
+        }
+
+        let migrated = opts.state.migrate_vault(&self.name, &self.to).await?;
+        let identifiers: Vec<_> = migrated.iter().map(|i| i.identifier()).collect();
+        let running_nodes = opts
+            .state
+            .get_nodes()
+            .await?
+            .into_iter()
+            .filter(|n| n.is_running() && identifiers.contains(&n.identifier()))
+            .map(|n| n.name())
+            .collect();
+
+        let output = MigrateOutput {
+            from: self.name,
+            to: self.to,
+            identities: migrated.iter().map(|i| i.name()).collect(),
+            running_nodes,
+        };
+        opts.terminal
+            .stdout()
+            .plain(output.plain()?)
+            .json(serde_json::to_string(&output).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct MigrateOutput {
+    from: String,
+    to: String,
+    identities: Vec<String>,
+    running_nodes: Vec<String>,
+}
+
+impl MigrateOutput {
+    fn plain(&self) -> Result<String> {
+        let mut buf = String::new();
+        if self.identities.is_empty() {
+            writeln!(
+                buf,
+                "{}",
+                fmt_warn!(
+                    "The vault {} is not used by any identity",
+                    color_primary(&self.from)
+                )
+            )?;
+            return Ok(buf);
+        }
+        writeln!(
+            buf,
+            "{}",
+            fmt_ok!(
+                "Migrated the identities of the vault {} to the vault {}",
+                color_primary(&self.from),
+                color_primary(&self.to)
+            )
+        )?;
+        for identity in &self.identities {
+            writeln!(buf, "{}", fmt_log!("{}", color_primary(identity)))?;
+        }
+        if !self.running_nodes.is_empty() {
+            writeln!(
+                buf,
+                "{}",
+                fmt_warn!(
+                    "The running nodes {} still use the previous keys. Restart them with {}",
+                    self.running_nodes.join(", "),
+                    color_primary("ockam node restart")
+                )
+            )?;
+        }
+        Ok(buf)
+    }
+}
@@ -1,17 +1,25 @@
 use clap::{Args, Subcommand};
 
+use crate::vault::backup::BackupCommand;
 pub use crate::vault::create::CreateCommand;
 use crate::vault::delete::DeleteCommand;
 use crate::vault::list::ListCommand;
+use crate::vault::migrate::MigrateCommand;
 use crate::vault::move_vault::MoveCommand;
+use crate::vault::restore::RestoreCommand;
 use crate::vault::show::ShowCommand;
+use crate::vault::unlock::UnlockCommand;
 use crate::{docs, Command, CommandGlobalOpts};
 
+mod backup;
 mod create;
 mod delete;
 mod list;
+mod migrate;
 mod move_vault;
+mod restore;
 mod show;
+mod unlock;
 mod util;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
@@ -32,9 +40,13 @@ pub struct VaultCommand {
 pub enum VaultSubcommand {
     Create(CreateCommand),
     Move(MoveCommand),
+    Migrate(MigrateCommand),
     Show(ShowCommand),
     Delete(DeleteCommand),
     List(ListCommand),
+    Unlock(UnlockCommand),
+    Backup(BackupCommand),
+    Restore(RestoreCommand),
 }
 
 impl VaultCommand {
@@ -42,9 +54,13 @@ impl VaultCommand {
         match self.subcommand {
             VaultSubcommand::Create(cmd) => cmd.run(opts),
             VaultSubcommand::Move(cmd) => cmd.run(opts),
+            VaultSubcommand::Migrate(cmd) => cmd.run(opts),
             VaultSubcommand::Show(cmd) => cmd.run(opts),
             VaultSubcommand::List(cmd) => cmd.run(opts),
             VaultSubcommand::Delete(cmd) => cmd.run(opts),
+            VaultSubcommand::Unlock(cmd) => cmd.run(opts),
+            VaultSubcommand::Backup(cmd) => cmd.run(opts),
+            VaultSubcommand::Restore(cmd) => cmd.run(opts),
         }
     }
 
@@ -52,9 +68,13 @@ impl VaultCommand {
         match &self.subcommand {
             VaultSubcommand::Create(c) => c.name(),
             VaultSubcommand::Move(c) => c.name(),
+            VaultSubcommand::Migrate(c) => c.name(),
             VaultSubcommand::Show(c) => c.name(),
             VaultSubcommand::Delete(c) => c.name(),
             VaultSubcommand::List(c) => c.name(),
+            VaultSubcommand::Unlock(c) => c.name(),
+            VaultSubcommand::Backup(c) => c.name(),
+            VaultSubcommand::Restore(c) => c.name(),
         }
     }
 }
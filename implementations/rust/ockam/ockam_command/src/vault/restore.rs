@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::{IntoDiagnostic, WrapErr};
+
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+use ockam_vault::storage::VaultBackup;
+
+use crate::util::async_cmd;
+use crate::vault::backup::recovery_key;
+use crate::{docs, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/restore/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/restore/after_long_help.txt");
+
+/// Restore the secrets of a vault backup
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct RestoreCommand {
+    /// Name of the vault. The default vault is used if not specified
+    pub name: Option<String>,
+
+    /// File containing the backup, created with `ockam vault backup`
+    #[arg(long, short, value_name = "FILE")]
+    pub input: PathBuf,
+}
+
+impl RestoreCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |_ctx| async move {
+            self.async_run(opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "vault restore".into()
+    }
+
+    async fn async_run(&self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let contents = std::fs::read_to_string(&self.input)
+            .into_diagnostic()
+            .wrap_err(format!("Unable to read the file {}", self.input.display()))?;
+        let bytes = hex::decode(contents.trim())
+            .into_diagnostic()
+            .wrap_err("The backup file must contain a hex-encoded vault backup")?;
+        let backup = VaultBackup::import_from_bytes(&bytes).into_diagnostic()?;
+
+        let vault = opts.state.get_named_vault_or_default(&self.name).await?;
+        let vault_name = vault.name();
+        let recovery_key = recovery_key(&opts, false)?;
+        let restored = opts
+            .state
+            .restore_named_vault(&vault_name, &backup, &recovery_key)
+            .await?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "{} secrets have been restored into the vault {}",
+                restored,
+                color_primary(&vault_name)
+            ))
+            .machine(restored)
+            .json(serde_json::json!({ "name": &vault_name, "restored": restored }))
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -25,6 +25,11 @@ after_long_help = docs::after_help(AFTER_LONG_HELP)
 pub struct ShowCommand {
     /// Name of the vault
     pub name: Option<String>,
+
+    /// Read the names of the vaults to show from stdin, one per line or as a JSON array.
+    /// The result of each operation is reported separately
+    #[arg(long, conflicts_with = "name")]
+    stdin: bool,
 }
 
 impl ShowCommand {
@@ -46,6 +51,7 @@ impl ShowCommand {
 pub struct ShowTui {
     opts: CommandGlobalOpts,
     vault_name: Option<String>,
+    stdin: bool,
 }
 
 impl ShowTui {
@@ -53,6 +59,7 @@ impl ShowTui {
         let tui = Self {
             opts,
             vault_name: cmd.name,
+            stdin: cmd.stdin,
         };
         tui.show().await
     }
@@ -66,6 +73,10 @@ impl ShowCommandTui for ShowTui {
         self.vault_name.clone()
     }
 
+    fn cmd_arg_stdin(&self) -> bool {
+        self.stdin
+    }
+
     fn terminal(&self) -> Terminal<TerminalStream<Term>> {
         self.opts.terminal.clone()
     }
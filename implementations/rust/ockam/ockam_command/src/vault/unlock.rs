@@ -0,0 +1,101 @@
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+use ockam_api::cli_state::{VaultEncryptionMethod, OCKAM_VAULT_PASSPHRASE};
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+use ockam_api::nodes::models::node::UnlockVaultResponse;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_core::env::get_env;
+
+use crate::util::{api, async_cmd};
+use crate::{docs, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/unlock/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/unlock/after_long_help.txt");
+
+/// Unlock an encrypted vault used by a node
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct UnlockCommand {
+    /// Name of the vault. The default vault is used if not specified
+    pub name: Option<String>,
+
+    /// Node using the vault
+    #[arg(value_name = "NODE_NAME", long, display_order = 800)]
+    pub at: Option<String>,
+}
+
+impl UnlockCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "vault unlock".into()
+    }
+
+    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let vault = opts.state.get_named_vault_or_default(&self.name).await?;
+        let vault_name = vault.name();
+        let encryption = vault.encryption().ok_or_else(|| {
+            miette::miette!("The vault {vault_name} is not encrypted and cannot be unlocked")
+        })?;
+
+        let passphrase = match encryption.method() {
+            VaultEncryptionMethod::Passphrase => Some(Self::passphrase(&opts, &vault_name)?),
+            VaultEncryptionMethod::Keychain => None,
+        };
+        // check the passphrase before sending it to the node
+        encryption.make_key(&vault_name, passphrase.clone())?;
+
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
+        let response: UnlockVaultResponse = node
+            .ask(ctx, api::unlock_vault(&vault_name, passphrase))
+            .await?;
+
+        let plain = if response.was_locked {
+            fmt_ok!(
+                "The vault {} is unlocked on the node {}",
+                color_primary(&response.vault_name),
+                color_primary(node.node_name())
+            )
+        } else {
+            fmt_ok!(
+                "The vault {} was already unlocked on the node {}",
+                color_primary(&response.vault_name),
+                color_primary(node.node_name())
+            )
+        };
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .machine(&response.vault_name)
+            .json(serde_json::json!({
+                "name": &response.vault_name,
+                "node": node.node_name(),
+                "was_locked": response.was_locked,
+            }))
+            .write_line()?;
+        Ok(())
+    }
+
+    /// Return the passphrase of the vault,
+    /// either from the environment or by prompting the user
+    fn passphrase(opts: &CommandGlobalOpts, vault_name: &str) -> miette::Result<String> {
+        match get_env::<String>(OCKAM_VAULT_PASSPHRASE).into_diagnostic()? {
+            Some(passphrase) => Ok(passphrase),
+            None => {
+                let prompt = format!("Passphrase of the vault {vault_name}");
+                Ok(opts.terminal.ask_for_password(prompt, false)?)
+            }
+        }
+    }
+}
@@ -57,9 +57,10 @@ impl Output for VaultOutput {
         .to_string()
         .color(OckamColor::PrimaryResource.color());
 
-        Ok(match self.vault.vault_type() {
+        let output = match self.vault.vault_type() {
             VaultType::DatabaseVault {
                 use_aws_kms: UseAwsKms::No,
+                ..
             } => formatdoc!(
                 r#"Name: {name}
                    Type: {vault_type}"#,
@@ -68,6 +69,7 @@ impl Output for VaultOutput {
             ),
             VaultType::DatabaseVault {
                 use_aws_kms: UseAwsKms::Yes,
+                ..
             } => formatdoc!(
                 r#"Name: {name}
             Type: {vault_type}
@@ -78,6 +80,7 @@ impl Output for VaultOutput {
             VaultType::LocalFileVault {
                 path,
                 use_aws_kms: UseAwsKms::No,
+                ..
             } => formatdoc!(
                 r#"Name: {name}
             Type: {vault_type}
@@ -92,6 +95,7 @@ impl Output for VaultOutput {
             VaultType::LocalFileVault {
                 path,
                 use_aws_kms: UseAwsKms::Yes,
+                ..
             } => formatdoc!(
                 r#"Name: {name}
             Type: External
@@ -104,6 +108,28 @@ impl Output for VaultOutput {
                     .color(OckamColor::PrimaryResource.color()),
                 uses_aws_kms = uses_aws_kms,
             ),
+        };
+
+        let output = match self.vault.pkcs11() {
+            Some(pkcs11) => formatdoc!(
+                r#"{output}
+            Uses PKCS#11: {pkcs11}"#,
+                pkcs11 = pkcs11
+                    .to_string()
+                    .color(OckamColor::PrimaryResource.color())
+            ),
+            None => output,
+        };
+
+        Ok(match self.vault.encryption() {
+            Some(encryption) => formatdoc!(
+                r#"{output}
+            Encrypted: {encryption}"#,
+                encryption = encryption
+                    .to_string()
+                    .color(OckamColor::PrimaryResource.color())
+            ),
+            None => output,
         })
     }
 }
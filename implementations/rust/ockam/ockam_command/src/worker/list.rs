@@ -1,5 +1,6 @@
 use clap::Args;
 use colorful::Colorful;
+use miette::IntoDiagnostic;
 use tokio::sync::Mutex;
 use tokio::try_join;
 
@@ -64,7 +65,11 @@ impl ListCommand {
             &workers.list,
             &format!("No workers found on {}.", node.node_name()),
         )?;
-        opts.terminal.stdout().plain(list).write_line()?;
+        opts.terminal
+            .stdout()
+            .plain(list)
+            .json(serde_json::to_string(&workers.list).into_diagnostic()?)
+            .write_line()?;
 
         Ok(())
     }
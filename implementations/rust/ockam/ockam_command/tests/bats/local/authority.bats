@@ -132,6 +132,17 @@ EOF
 
   run "$OCKAM" project enroll $token2 --identity m5
   assert_failure
+
+  # admin can list the tickets which have not been used yet, and revoke them
+  token4=$($OCKAM project ticket --identity admin --max-uses 3 --attribute sample_attr=revoked)
+  run_success "$OCKAM" project ticket list --identity admin --output json
+  assert_output --partial "sample_attr=revoked"
+  ticket_id=$($OCKAM project ticket list --identity admin --output json | jq -r '.[] | select(.attributes | index("sample_attr=revoked")) | .id')
+  run_success "$OCKAM" project ticket revoke --identity admin "$ticket_id"
+  run_success "$OCKAM" project ticket list --identity admin --output json
+  refute_output --partial "sample_attr=revoked"
+  run "$OCKAM" project enroll $token4 --identity m6
+  assert_failure
 }
 
 @test "authority - enrollment ticket ttl" {
@@ -300,6 +311,19 @@ EOF
   assert_output --partial "\"ockam-relay\":\"*\""
   assert_output --partial "\"attested_by\":\"$enroller_identifier\""
 
+  # Members can be filtered by attributes
+  run_success "$OCKAM" project-member list --identity enroller --attribute key=value --output json
+  assert_output --partial "\"identifier\":\"$m_identifier\""
+  refute_output --partial "\"identifier\":\"$enroller_identifier\""
+
+  # Members can be listed one page at a time
+  run_success "$OCKAM" project-member list --identity enroller --limit 1 --jq '.[0].identifier'
+  first_page="$output"
+  run_success "$OCKAM" project-member list --identity enroller --limit 1 --offset 1 --jq '. | length'
+  assert_output "1"
+  run_success "$OCKAM" project-member list --identity enroller --limit 1 --offset 1 --jq '.[0].identifier'
+  refute_output "$first_page"
+
   run_success "$OCKAM" project-member show "$m_identifier" --identity enroller
   assert_output --partial "\"identifier\":\"$m_identifier\""
   assert_output --partial "\"key\":\"value\""
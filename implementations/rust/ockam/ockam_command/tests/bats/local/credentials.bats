@@ -31,6 +31,15 @@ teardown() {
 
   run_success "$OCKAM" credential list
   assert_output --partial "{\"application\":\"Smart Factory\",\"city\":\"New York\""
+  assert_output --partial "expires_in"
+
+  # The credentials can be filtered and verified
+  run_success "$OCKAM" credential list --subject "$idt2_short" --verify --output json
+  assert_output --partial "\"issuer\":\"$idt1_short\""
+  assert_output --partial "\"is_verified\":true"
+
+  run_success "$OCKAM" credential list --issuer "$idt2_short" --output json
+  refute_output --partial "\"issuer\":\"$idt1_short\""
 }
 
 @test "credential - verify rejects invalid credentials" {
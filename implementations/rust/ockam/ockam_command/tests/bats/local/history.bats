@@ -0,0 +1,42 @@
+#!/bin/bash
+
+# ===== SETUP
+
+setup() {
+  load ../load/base.bash
+  load_bats_ext
+  setup_home_dir
+}
+
+teardown() {
+  teardown_home_dir
+}
+
+# ===== TESTS
+
+@test "history - the commands modifying the configuration are recorded" {
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node list
+  run_success "$OCKAM" node delete n1 --yes
+
+  run_success "$OCKAM" history list --output json
+  assert_output --partial "\"command_name\":\"node create\""
+  assert_output --partial "\"arguments\":\"ockam node create n1\""
+  assert_output --partial "\"command_name\":\"node delete\""
+  refute_output --partial "\"command_name\":\"node list\""
+
+  # Only the most recent commands are listed with --limit
+  run_success "$OCKAM" history list --limit 1 --output json
+  assert_output --partial "\"command_name\":\"node delete\""
+  refute_output --partial "\"command_name\":\"node create\""
+}
+
+@test "history - clear" {
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" history clear --yes
+
+  run_success "$OCKAM" history list --output json
+  refute_output --partial "\"command_name\":\"node create\""
+  # The clearing of the history is recorded
+  assert_output --partial "\"command_name\":\"history clear\""
+}
@@ -23,6 +23,10 @@ teardown() {
   assert_output --partial "Change[0]:"
   assert_output --partial "Identifier: "
   assert_output --partial "primary_public_key: "
+  assert_output --partial "Created at: "
+  assert_output --partial "Key rotations: 0"
+  assert_output --partial "signature_algorithm: "
+  assert_output --partial "valid_from: "
 }
 
 @test "identity - CRUD" {
@@ -99,3 +103,27 @@ teardown() {
   run_success "$OCKAM" identity show --full --encoding hex
   assert_output "$exported"
 }
+
+@test "identity - rotate the identity key" {
+  run_success "$OCKAM" identity create i
+  identifier=$($OCKAM identity show i)
+  history=$($OCKAM identity show i --full --encoding hex)
+
+  run_success "$OCKAM" node create n --identity i
+
+  # The identifier is unchanged but the change history has a new change
+  run_success "$OCKAM" identity rotate i --output json
+  assert_output --partial "\"identifier\":\"$identifier\""
+  assert_output --partial "\"running_nodes\":[\"n\"]"
+  assert_output --partial "\"restarted_nodes\":[]"
+  run_success "$OCKAM" identity show i
+  assert_output "$identifier"
+  run_success "$OCKAM" identity show i --full --encoding hex
+  refute_output "$history"
+
+  # The running nodes can be restarted to use the new key
+  run_success "$OCKAM" identity rotate i --all-nodes-restart --output json
+  assert_output --partial "\"restarted_nodes\":[\"n\"]"
+  run_success "$OCKAM" message send hello --to /node/n/secure/api/service/echo
+  assert_output "hello"
+}
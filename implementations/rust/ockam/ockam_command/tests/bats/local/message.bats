@@ -54,3 +54,28 @@ teardown() {
               | $OCKAM message send $msg --from /node/n1 --to -/service/echo"
   assert_output "$msg"
 }
+
+@test "message - send a message and display its delivery report" {
+  run_success "$OCKAM" identity create i1
+  idt1=$($OCKAM identity show i1)
+  run_success "$OCKAM" node create n1 --identity i1
+  run_success "$OCKAM" node create n2
+
+  msg=$(random_str)
+  run_success "$OCKAM" message send "$msg" --from n2 --to /node/n1/service/uppercase --expect-reply "$(to_uppercase "$msg")" --output json
+  assert_output --partial "\"reply\":\"$(to_uppercase "$msg")\""
+  assert_output --partial "\"round_trip_time_ms\":"
+  assert_output --partial "\"attempts\":1"
+
+  # The peer identifier is reported when the message is sent over a secure channel
+  run_success bash -c "$OCKAM secure-channel create --from n2 --to /node/n1/service/api \
+              | $OCKAM message send $msg --from /node/n2 --to -/service/echo --output json"
+  assert_output --partial "\"peer_identifier\":\"$idt1\""
+
+  # The command fails if the reply is not the expected one
+  run_failure "$OCKAM" message send "$msg" --from n2 --to /node/n1/service/uppercase --expect-reply "$msg"
+
+  # The message is sent again when it can't be delivered
+  run_failure "$OCKAM" message send "$msg" --no-retry --timeout 1 --retries 2 --to /node/n1/service/unknown
+  assert_output --partial "Attempt 2 failed"
+}
@@ -45,6 +45,23 @@ force_kill_node() {
   assert_output --partial "\"addr\":\"uppercase\""
 }
 
+@test "node - show the portals and relays of a node" {
+  run_success "$OCKAM" node create n
+  run_success "$OCKAM" tcp-outlet create --at n --to 127.0.0.1:5000 --from db
+
+  run_success "$OCKAM" node show n --output json
+  assert_output --partial "\"outlets\":[{"
+  assert_output --partial "\"inlets\":[]"
+  assert_output --partial "\"relays\":[]"
+}
+
+@test "node - create and wait until the node is ready" {
+  run_success "$OCKAM" node create n --wait-until-ready --timeout 30s
+
+  # the services can be used right away
+  run_success "$OCKAM" tcp-outlet create --at n --to 127.0.0.1:5000
+}
+
 @test "node - start services" {
   run_success "$OCKAM" node create n1
 
@@ -63,6 +80,97 @@ force_kill_node() {
   assert_output --partial "\"addr\":\"echo\""
 }
 
+@test "node - restart a running node" {
+  port="$(random_port)"
+  run_success "$OCKAM" node create n --tcp-listener-address "127.0.0.1:$port"
+
+  run_success "$OCKAM" node restart n --output json
+  assert_output --partial "\"node_name\":\"n\""
+  assert_output --partial "\"tcp_listener_address\":\"127.0.0.1:$port\""
+
+  # the node is running again with its default services
+  run_success "$OCKAM" node show n --output json
+  assert_output --partial "\"addr\":\"echo\""
+}
+
+@test "node - restart a node with its original create options" {
+  run_success "$OCKAM" node create n --enable-udp
+
+  # the restarted node process is started with the same options
+  pid=$($OCKAM node restart n --output json | jq -r '.pid')
+  run_success ps -o args= -p "$pid"
+  assert_output --partial "--enable-udp"
+}
+
+@test "node - delete nodes read from stdin" {
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2
+
+  # the deletion must be confirmed
+  run_failure bash -c "echo n1 | $OCKAM node delete --stdin"
+
+  # the names are read one per line and each failure is reported
+  run_failure bash -c "printf 'n1\\nmissing\\n' | $OCKAM node delete --stdin --yes --output json"
+  assert_output --partial "\"name\":\"missing\""
+  run_failure "$OCKAM" node show n1
+
+  # the names are read as a JSON array
+  run_success bash -c "echo '[\"n2\"]' | $OCKAM node delete --stdin --yes"
+  run_failure "$OCKAM" node show n2
+}
+
+@test "node - report the status of each node read from stdin" {
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2
+
+  # each item is reported with a single JSON record, one per line, and the command fails if one item failed
+  run_failure bash -c "printf 'n1\\nmissing\\nn2\\n' | $OCKAM node delete --stdin --yes --output json 2>/dev/null"
+  assert_equal "${#lines[@]}" 3
+  assert_line --index 0 '{"name":"n1","status":"ok"}'
+  assert_line --index 1 --partial '"name":"missing","status":"error"'
+  assert_line --index 2 '{"name":"n2","status":"ok"}'
+  run_failure "$OCKAM" node show n1
+  run_failure "$OCKAM" node show n2
+
+  # each shown item is reported with a single JSON record containing its output
+  run_success "$OCKAM" node create n3
+  run_failure bash -c "printf 'n3\\nmissing\\n' | $OCKAM node show --stdin --output json 2>/dev/null"
+  assert_equal "${#lines[@]}" 2
+  assert_line --index 0 --partial '"name":"n3","status":"ok"'
+  assert_line --index 0 --partial '"item":{'
+  assert_line --index 1 --partial '"name":"missing","status":"error"'
+}
+
+@test "node - display the stats of a node" {
+  run_success "$OCKAM" node create n
+
+  run_success "$OCKAM" node top n -n 2 --interval 100ms --output json
+  assert_output --partial "\"node_name\":\"n\""
+  assert_output --partial "\"address\":\"echo\""
+  assert_output --partial "\"messages_per_second\""
+
+  run_success "$OCKAM" node top n -n 1
+  assert_output --partial "ADDRESS"
+  assert_output --partial "MSG/S"
+}
+
+@test "node - the debug graph requires the debugger feature" {
+  run_success "$OCKAM" node create n
+
+  run_failure "$OCKAM" node debug-graph n
+  assert_output --partial "debugger"
+}
+
+@test "node - tracing messages requires the debugger feature" {
+  run_success "$OCKAM" node create n
+
+  run_failure "$OCKAM" node trace n --address echo -n 1
+  assert_output --partial "debugger"
+
+  run_failure "$OCKAM" node trace n --sample-rate 2
+  assert_output --partial "sample rate"
+}
+
 @test "node - fail to create two background nodes with the same name" {
   run_success "$OCKAM" node create n
   run_failure "$OCKAM" node create n
@@ -132,6 +240,12 @@ force_kill_node() {
   assert_output --partial "stdout"
 }
 
+@test "node - print the last lines of a background node logs" {
+  run_success "$OCKAM" node create n
+  run_success "$OCKAM" node logs n --lines 5
+  run_success "$OCKAM" node logs n --level error
+}
+
 @test "node - foreground node logs to stdout only" {
   run_success "$OCKAM" node create n -vv -f &
   sleep 1
@@ -146,6 +260,19 @@ force_kill_node() {
   assert_output --partial "127.0.0.1:5432"
 }
 
+@test "node - create a node with a configuration read from stdin" {
+  run_success bash -c "echo '{name: n, tcp-outlets: {db-outlet: {to: 5432, at: n}}}' | $OCKAM node create -"
+  run_success $OCKAM node show n --output json
+  assert_output --partial "\"name\":\"n\""
+  assert_output --partial "127.0.0.1:5432"
+
+  run_success bash -c "echo '{name: m, tcp-outlets: {db-outlet: {to: 5433, at: m}}}' | $OCKAM node create --inline -"
+  run_success $OCKAM node show m --output json
+  assert_output --partial "127.0.0.1:5433"
+
+  run_failure bash -c "echo '{name: o}' | $OCKAM node create - -f --exit-on-eof"
+}
+
 @test "node - node in foreground with configuration is deleted if something fails" {
   # The config file has a typo in the "to" address to trigger an error after the node is created.
   # The command should return an error and the node should be deleted.
@@ -166,6 +293,31 @@ force_kill_node() {
   done
 }
 
+@test "node - list nodes with selected fields, sorted by name" {
+  run_success "$OCKAM" node create n2
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n3
+
+  run_success "$OCKAM" node list --fields node_name,is_default --sort-by node_name
+  assert_line --partial "NODE_NAME  IS_DEFAULT"
+  assert_output --regexp "n1 +false.*n2 +true.*n3 +false"
+  refute_output --partial "Process id"
+
+  run_failure "$OCKAM" node list --fields unknown
+  assert_output --partial "Unknown field 'unknown'"
+}
+
+@test "node - report the node liveness with its heartbeat" {
+  run_success "$OCKAM" node create n1
+
+  run_success bash -c "$OCKAM node list --output json | jq -r '.[] | select(.node_name == \"n1\") | .liveness'"
+  assert_output "up"
+
+  run_success "$OCKAM" node stop n1
+  run_success bash -c "$OCKAM node list --output json | jq -r '.[] | select(.node_name == \"n1\") | .liveness'"
+  assert_output "down"
+}
+
 @test "node - return error if passed variable has no value" {
   run_failure "$OCKAM" node create --configuration "{name: n}" --variable MY_VAR=
   assert_output --partial "Empty value for variable 'MY_VAR'"
@@ -201,3 +353,22 @@ force_kill_node() {
   run_success $OCKAM node create --http-server-port $port
   run_success curl -fsI -m 2 127.0.0.1:$port
 }
+
+@test "node - print the connection details of a node as environment variables" {
+  run_success $OCKAM node create n1 --enable-http-server
+  n1_identifier=$($OCKAM node show n1 --output json | jq -r .identifier)
+
+  run_success $OCKAM node env n1
+  assert_output --partial "export OCKAM_NODE_NAME=n1"
+  assert_output --partial "export OCKAM_NODE_IDENTIFIER=$n1_identifier"
+  assert_output --partial "export OCKAM_NODE_STATUS=running"
+  assert_output --partial "export OCKAM_NODE_TCP_LISTENER_MULTIADDR=/"
+
+  # The variables can be evaluated by a shell
+  eval "$($OCKAM node env n1)"
+  run_success curl -fs -m 2 "$OCKAM_NODE_STATUS_ENDPOINT"
+
+  run_success $OCKAM node env n1 --output json
+  assert_equal "$(echo "$output" | jq -r .name)" "n1"
+  assert_equal "$(echo "$output" | jq -r .status)" "running"
+}
@@ -62,3 +62,26 @@ teardown() {
   assert_output --partial "invalid value 'component.db or'"
   assert_output --partial 'successfully parsed: `component.db`, but ` or` cannot be parsed'
 }
+
+@test "policies - validate a policy expression" {
+  run_success $OCKAM policy validate 'component.db or component.web'
+  assert_output --partial "(or (= subject.component.db \"true\") (= subject.component.web \"true\"))"
+
+  run_success $OCKAM policy validate '(= subject.component "db")' --output json
+  assert_output --partial '"kind":"full"'
+
+  run_failure $OCKAM policy validate '(= subject.component "db"'
+  assert_output --partial "unclosed '(' at position 0"
+}
+
+@test "policies - test a policy expression against subject attributes" {
+  run_success $OCKAM policy test '(= subject.component "db")' --attribute component=db --output json
+  assert_output --partial '"granted":true'
+
+  run_success $OCKAM policy test '(= subject.component "db")' --attribute component=web --output json
+  assert_output --partial '"granted":false'
+
+  run_success $OCKAM policy test 'component.db' --output json
+  assert_output --partial '"granted":false'
+  assert_output --partial "subject.component.db"
+}
@@ -38,6 +38,29 @@ teardown() {
   refute_output --partial "/service/outlet"
 }
 
+@test "portals - ssh through an ephemeral tcp inlet" {
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2
+  run_success $OCKAM tcp-outlet create --at /node/n1 --to "127.0.0.1:$(random_port)"
+
+  # Use a fake ssh executable which only prints its arguments
+  fake_ssh="$OCKAM_HOME/fake_ssh"
+  printf '#!/bin/bash\necho "$@"\n' >"$fake_ssh"
+  chmod +x "$fake_ssh"
+
+  run_success $OCKAM ssh /node/n1/service/outlet --at /node/n2 --user admin --ssh-command "$fake_ssh" -- uptime
+  assert_output --regexp "-p [0-9]+ -o HostKeyAlias=.* admin@127.0.0.1 uptime"
+
+  # The ephemeral inlet is deleted when the ssh session ends
+  run_success $OCKAM tcp-inlet list --at /node/n2 --output json
+  refute_output --partial "ssh-"
+
+  # A named inlet is kept after the ssh session ends
+  run_success $OCKAM ssh /node/n1/service/outlet --at /node/n2 --inlet server-ssh --ssh-command "$fake_ssh"
+  run_success $OCKAM tcp-inlet show server-ssh --at /node/n2 --output json
+  assert_output --partial "\"alias\":\"server-ssh\""
+}
+
 @test "portals - tcp inlet CRUD" {
 
   # Create nodes for inlet/outlet pair
@@ -144,6 +167,24 @@ teardown() {
   assert_output --partial "not found"
 }
 
+@test "portals - ping the tcp server of a tcp outlet" {
+  run_success "$OCKAM" node create n1
+
+  run_success $OCKAM tcp-outlet create --at /node/n1 --to "$PYTHON_SERVER_PORT"
+  run_success $OCKAM tcp-outlet ping --at n1 --output json
+  assert_output --partial "\"reachable\":true"
+  assert_output --partial "\"socket_addr\":\"127.0.0.1:$PYTHON_SERVER_PORT\""
+
+  # No server is listening on the target of this outlet
+  port="$(random_port)"
+  run_success $OCKAM tcp-outlet create --at /node/n1 --to "$port" --from unreachable
+  run_failure $OCKAM tcp-outlet ping unreachable --at n1 --timeout 2s
+  assert_output --partial "not reachable"
+
+  run_failure $OCKAM tcp-outlet ping non-existing-outlet --at n1
+  assert_output --partial "not found"
+}
+
 @test "portals - create an inlet/outlet pair and move tcp traffic through it" {
   run_success "$OCKAM" node create n1
   run_success "$OCKAM" node create n2
@@ -155,6 +196,28 @@ teardown() {
   run_success curl -sfI --retry-connrefused --retry-delay 5 --retry 10 -m 5 "127.0.0.1:$port"
 }
 
+@test "portals - show the traffic stats of a tcp inlet" {
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2
+
+  run_success "$OCKAM" tcp-outlet create --at /node/n1 --to "$PYTHON_SERVER_PORT"
+  port="$(random_port)"
+  run_success "$OCKAM" tcp-inlet create --at /node/n2 --from "$port" --to /node/n1/service/outlet --alias "test-inlet"
+
+  run_success "$OCKAM" tcp-inlet stats "test-inlet" --at /node/n2 --output json
+  assert_output --partial "\"total_connections\":0"
+
+  run_success curl -sfI --retry-connrefused --retry-delay 5 --retry 10 -m 5 "127.0.0.1:$port"
+
+  run_success "$OCKAM" tcp-inlet stats "test-inlet" --at /node/n2 --output json
+  assert_output --partial "\"alias\":\"test-inlet\""
+  refute_output --partial "\"total_connections\":0"
+  refute_output --partial "\"bytes_in\":0,"
+  refute_output --partial "\"bytes_out\":0,"
+
+  run_failure "$OCKAM" tcp-inlet stats "non-existing-inlet" --at /node/n2
+}
+
 @test "portals - create an inlet/outlet, download file" {
   run_success "$OCKAM" node create n1
   run_success "$OCKAM" node create n2
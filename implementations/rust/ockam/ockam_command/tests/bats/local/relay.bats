@@ -47,6 +47,23 @@ teardown() {
   assert_output --partial "[]"
 }
 
+@test "relay - list the relays of all the local nodes" {
+  run_success --separate-stderr "$OCKAM" node create n1
+  run_success --separate-stderr "$OCKAM" node create n2
+  run_success --separate-stderr "$OCKAM" node create n3
+
+  run_success $OCKAM relay create blue --at /node/n1 --to /node/n2
+  run_success $OCKAM relay create red --at /node/n1 --to /node/n3
+
+  run_success $OCKAM relay list --all --output json
+  assert_output --partial "\"n2\":["
+  assert_output --partial "\"remote_address\":\"forward_to_blue\""
+  assert_output --partial "\"remote_address\":\"forward_to_red\""
+  assert_output --partial "\"n1\":[]"
+
+  run_failure $OCKAM relay list --all --to /node/n2
+}
+
 @test "relay - CRUD" {
   run_success --separate-stderr "$OCKAM" node create n1
   run_success --separate-stderr "$OCKAM" node create n2
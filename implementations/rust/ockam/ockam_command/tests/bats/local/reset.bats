@@ -47,3 +47,16 @@ env'
 bin
 env'
 }
+
+@test "reset with --dry-run must not delete anything" {
+  n="$(random_str)"
+  run_success "$OCKAM" node create "$n"
+
+  run_success "$OCKAM" reset --dry-run --output json
+  assert_output --partial "\"name\":\"$n\""
+  assert_output --partial "\"is_running\":true"
+
+  # the node is still there
+  run_success "$OCKAM" node show "$n" --output json
+  assert_output --partial "\"name\":\"$n\""
+}
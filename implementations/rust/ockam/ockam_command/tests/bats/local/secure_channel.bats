@@ -46,3 +46,22 @@ teardown() {
   run_success "$OCKAM" message send "$msg" --timeout 5 --from /node/n1 --to "/node/n2/secure/api/service/uppercase"
   assert_output "$(to_uppercase "$msg")"
 }
+
+@test "secure channel - list secure channels with their peer identity and activity" {
+  run_success "$OCKAM" identity create i2
+  n2_identifier=$($OCKAM identity show i2)
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2 --identity i2
+
+  output=$($OCKAM secure-channel create --from /node/n1 --to /node/n2/service/api)
+  run_success "$OCKAM" message send hello --timeout 5 --from /node/n1 --to "$output/service/echo"
+
+  run_success "$OCKAM" secure-channel list --at n1 --output json
+  assert_output --partial "\"their_identifier\":\"$n2_identifier\""
+  assert_output --partial "\"created_at\":"
+  assert_output --partial "\"last_activity\":"
+
+  run_success "$OCKAM" secure-channel list --at n1
+  assert_output --partial "With $n2_identifier"
+  assert_output --partial "Last activity at"
+}
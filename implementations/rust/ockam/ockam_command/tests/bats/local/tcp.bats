@@ -40,6 +40,22 @@ teardown() {
   refute_output --partial "$addr"
 }
 
+@test "tcp connection - only the final result is printed in quiet mode" {
+  port="$(random_port)"
+  addr="127.0.0.1:$port"
+  run_success "$OCKAM" node create n1 --tcp-listener-address "$addr"
+  run_success "$OCKAM" tcp-connection create --from n1 --to "$addr"
+
+  # The output only contains the JSON result, without any log or progress messages
+  export OCKAM_QUIET=1
+  run_success "$OCKAM" tcp-connection list --at n1 --output json
+  run_success jq -e ". | length == 1" <<<"$output"
+
+  unset OCKAM_QUIET
+  run_success "$OCKAM" tcp-connection list --at n1 --output json --quiet
+  run_success jq -e ". | length == 1" <<<"$output"
+}
+
 @test "tcp listener - CRUD" {
   run_success "$OCKAM" node create n1
 
@@ -72,3 +72,25 @@ teardown() {
   run_success "$OCKAM" vault show --output json v2
   assert_output --partial new-vault-path
 }
+
+@test "vault - migrate the identities of a vault to another vault" {
+  run_success "$OCKAM" vault create v1
+  run_success "$OCKAM" vault create v2
+  run_success "$OCKAM" identity create i --vault v1
+  identifier=$($OCKAM identity show i)
+
+  run_success "$OCKAM" vault migrate v1 --to v2 --yes --output json
+  assert_output --partial "\"identities\":[\"i\"]"
+
+  # The identity keeps its identifier
+  run_success "$OCKAM" identity show i
+  assert_output "$identifier"
+
+  # The first vault is not used anymore by the identity and can be deleted
+  run_success "$OCKAM" vault delete v1 --yes
+
+  # The identity can still be used by a node
+  run_success "$OCKAM" node create n --identity i
+  run_success "$OCKAM" message send hello --to /node/n/secure/api/service/echo
+  assert_output "hello"
+}
@@ -0,0 +1,40 @@
+#!/bin/bash
+
+# ===== SETUP
+
+setup() {
+  load ../load/base.bash
+  load_bats_ext
+  setup_home_dir
+}
+
+teardown() {
+  teardown_home_dir
+}
+
+# ===== TESTS
+
+@test "vault - yaml output" {
+  run_success "$OCKAM" vault create v1
+
+  run_success "$OCKAM" vault show v1 --output yaml
+  assert_output --partial "name: v1"
+
+  run_success "$OCKAM" vault create v2
+  run_success "$OCKAM" vault list --output yaml
+  assert_output --partial "name: v1"
+  assert_output --partial "name: v2"
+}
+
+@test "node - yaml output" {
+  run_success "$OCKAM" node create n
+
+  run_success "$OCKAM" node show n --output yaml
+  assert_output --partial "name: n"
+  assert_output --partial "addr: uppercase"
+}
+
+@test "yaml output can't be used with a jq query" {
+  run_success "$OCKAM" vault create v1
+  run_failure "$OCKAM" vault show v1 --output yaml --jq .
+}
@@ -269,7 +269,7 @@ pub mod test {
         let cloned = RelayMessage::new(
             relay_msg.source().clone(),
             relay_msg.destination().clone(),
-            relay_msg.local_message().clone().with_payload(vec![1]),
+            relay_msg.local_message().clone().with_payload(vec![1u8]),
         );
         assert!(entry.matches(&cloned));
 
@@ -23,6 +23,12 @@ pub mod boxed {
     pub use alloc::boxed::Box;
 }
 
+/// Provides the shared `Bytes` buffers of the `bytes` crate, which can be sliced and
+/// cloned without copying their content.
+pub mod bytes {
+    pub use bytes::{Bytes, BytesMut};
+}
+
 /// Provides `std::collections` and alternate `hashbrown` map and set
 /// implementations.
 pub mod collections {
@@ -1,5 +1,6 @@
 use crate::{
     compat::{
+        bytes::Bytes,
         string::{String, ToString},
         vec::Vec,
     },
@@ -244,7 +245,7 @@ impl<M: Message> Routed<M> {
     /// Consume the message wrapper and return the original message.
     #[inline]
     pub fn into_body(self) -> Result<M> {
-        M::decode(&self.into_payload())
+        M::decode(self.payload())
     }
 
     /// Consume the message wrapper and return the underlying local message.
@@ -270,6 +271,13 @@ impl<M: Message> Routed<M> {
     pub fn into_payload(self) -> Vec<u8> {
         self.local_msg.into_payload()
     }
+
+    /// Consume the message wrapper and return the underlying transport message's binary payload
+    /// as a shared buffer, without copying it.
+    #[inline]
+    pub fn into_payload_bytes(self) -> Bytes {
+        self.local_msg.into_payload_bytes()
+    }
 }
 
 impl<M: Message + Debug> Debug for Routed<M> {
@@ -4,6 +4,20 @@ use crate::{debugger, Address, DenyAll, OutgoingAccessControl, RelayMessage, Res
 use core::cmp::Ordering;
 use core::fmt::{self, Debug};
 
+/// Priority of the messages received by a [`Mailbox`]
+///
+/// The messages sent to a high-priority mailbox are delivered to the worker before the
+/// messages already waiting for its normal-priority mailboxes. This is used for control-plane
+/// messages (handshakes, heartbeats) which must not wait behind bulk traffic
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MessagePriority {
+    /// Messages are delivered in order with the other normal-priority messages
+    #[default]
+    Normal,
+    /// Messages are delivered ahead of the normal-priority messages
+    High,
+}
+
 /// A `Mailbox` controls the dispatch of incoming messages for a particular [`Address`]
 /// Note that [`Worker`](crate::Worker), [`Processor`](crate::Processor) and `Context` may have multiple Mailboxes (with different
 /// addresses), but they always have exactly one mpsc receiver (message queue)
@@ -12,14 +26,15 @@ pub struct Mailbox {
     address: Address,
     incoming: Arc<dyn IncomingAccessControl>,
     outgoing: Arc<dyn OutgoingAccessControl>,
+    priority: MessagePriority,
 }
 
 impl Debug for Mailbox {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "{} {{in:{:?} out:{:?}}}",
-            self.address, self.incoming, self.outgoing
+            "{} {{in:{:?} out:{:?} priority:{:?}}}",
+            self.address, self.incoming, self.outgoing, self.priority
         )
     }
 }
@@ -55,15 +70,23 @@ impl Mailbox {
             address: address.into(),
             incoming,
             outgoing,
+            priority: MessagePriority::default(),
         }
     }
 
+    /// Set the priority of the messages received by this mailbox
+    pub fn with_priority(mut self, priority: MessagePriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
     /// Create a new `Mailbox` not allowed to send nor receive any messages
     pub fn deny_all(address: impl Into<Address>) -> Self {
         Self {
             address: address.into(),
             incoming: Arc::new(DenyAll),
             outgoing: Arc::new(DenyAll),
+            priority: MessagePriority::default(),
         }
     }
 
@@ -81,6 +104,11 @@ impl Mailbox {
     pub fn outgoing_access_control(&self) -> &Arc<dyn OutgoingAccessControl> {
         &self.outgoing
     }
+
+    /// Return the priority of the messages received by this mailbox
+    pub fn priority(&self) -> MessagePriority {
+        self.priority
+    }
 }
 
 /// A collection of [`Mailbox`]es for a specific [`Worker`](crate::Worker), [`Processor`](crate::Processor) or `Context`
@@ -208,6 +236,15 @@ impl Mailboxes {
         addresses
     }
 
+    /// Return the [`Address`]es of the high-priority [`Mailbox`]es
+    pub fn high_priority_addresses(&self) -> Vec<Address> {
+        core::iter::once(&self.main_mailbox)
+            .chain(self.additional_mailboxes.iter())
+            .filter(|m| m.priority == MessagePriority::High)
+            .map(|m| m.address.clone())
+            .collect()
+    }
+
     /// Return a reference to the main [`Mailbox`] for this [`Mailboxes`]
     pub fn main_mailbox(&self) -> &Mailbox {
         &self.main_mailbox
@@ -1,12 +1,15 @@
 #[cfg(feature = "std")]
 use crate::OpenTelemetryContext;
 use crate::{
-    compat::vec::Vec, route, Address, Message, ProtocolVersion, Route, TransportMessage,
-    PROTOCOL_VERSION_V1,
+    compat::bytes::Bytes, compat::vec::Vec, route, Address, Message, ProtocolVersion, Route,
+    TransportMessage, PROTOCOL_VERSION_V1,
 };
 use crate::{LocalInfo, Result};
 use cfg_if::cfg_if;
-use serde::{Deserialize, Serialize};
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// A message type that is routed locally within a single node.
 ///
@@ -49,8 +52,8 @@ pub struct LocalMessage {
     onward_route: Route,
     /// Return message route. This field must be populated by routers handling this message along the way.
     return_route: Route,
-    /// The message payload.
-    payload: Vec<u8>,
+    /// The message payload, which can be shared with the buffer it was received in
+    payload: Payload,
     /// Local information added by workers to give additional context to the message
     /// independently from its payload. For example this can be used to store the identifier that
     /// was used to encrypt the payload
@@ -152,9 +155,28 @@ impl LocalMessage {
             .push_front_return_route(address))
     }
 
-    /// Return the message payload
+    /// Return the message payload.
+    ///
+    /// The payload is copied if its buffer is shared, use [`LocalMessage::into_payload_bytes`]
+    /// to avoid that copy
     pub fn into_payload(self) -> Vec<u8> {
-        self.payload
+        self.payload.into_vec()
+    }
+
+    /// Return the message payload as a shared buffer, without copying it
+    pub fn into_payload_bytes(self) -> Bytes {
+        self.payload.into_bytes()
+    }
+
+    /// Return the message payload as a shared buffer.
+    ///
+    /// The payload is copied if it was modified with [`LocalMessage::payload_mut`],
+    /// use [`LocalMessage::into_payload_bytes`] to avoid that copy
+    pub fn payload_bytes(&self) -> Bytes {
+        match &self.payload {
+            Payload::Shared(bytes) => bytes.clone(),
+            Payload::Owned(vec) => Bytes::copy_from_slice(vec),
+        }
     }
 
     /// Return a reference to the message payload
@@ -162,14 +184,17 @@ impl LocalMessage {
         &self.payload
     }
 
-    /// Return a mutable reference to the message payload
+    /// Return a mutable reference to the message payload.
+    ///
+    /// The payload is copied if its buffer is shared
+    #[deprecated(note = "the payload can be shared with other messages, use `set_payload` instead")]
     pub fn payload_mut(&mut self) -> &mut Vec<u8> {
-        &mut self.payload
+        self.payload.make_mut()
     }
 
     /// Set the message payload
-    pub fn set_payload(mut self, payload: Vec<u8>) -> Self {
-        self.payload = payload;
+    pub fn set_payload(mut self, payload: impl Into<Bytes>) -> Self {
+        self.payload = Payload::Shared(payload.into());
         self
     }
 
@@ -225,7 +250,7 @@ impl LocalMessage {
             self.protocol_version,
             self.onward_route,
             self.return_route,
-            self.payload,
+            self.payload.into_bytes(),
             None,
         );
 
@@ -251,14 +276,14 @@ impl LocalMessage {
     fn make(
         onward_route: Route,
         return_route: Route,
-        payload: Vec<u8>,
+        payload: Bytes,
         local_info: Vec<LocalInfo>,
     ) -> Self {
         LocalMessage {
             protocol_version: PROTOCOL_VERSION_V1,
             onward_route,
             return_route,
-            payload,
+            payload: Payload::Shared(payload),
             local_info,
             #[cfg(feature = "std")]
             tracing_context: OpenTelemetryContext::current(),
@@ -268,7 +293,7 @@ impl LocalMessage {
     /// Create a `LocalMessage` with default values, in order to build it with
     /// the withXXX methods
     pub fn new() -> Self {
-        LocalMessage::make(route![], route![], vec![], vec![])
+        LocalMessage::make(route![], route![], Bytes::new(), vec![])
     }
 
     /// Specify the onward route for the message
@@ -288,8 +313,11 @@ impl LocalMessage {
     }
 
     /// Specify the payload for the message
-    pub fn with_payload(self, payload: Vec<u8>) -> Self {
-        Self { payload, ..self }
+    pub fn with_payload(self, payload: impl Into<Bytes>) -> Self {
+        Self {
+            payload: Payload::Shared(payload.into()),
+            ..self
+        }
     }
 
     /// Specify the local information for the message
@@ -314,3 +342,116 @@ impl LocalMessage {
         }
     }
 }
+
+/// Payload of a [`LocalMessage`].
+///
+/// The payload is a shared buffer, unless it was modified in place
+/// with the deprecated [`LocalMessage::payload_mut`]
+#[derive(Debug, Clone)]
+enum Payload {
+    Shared(Bytes),
+    Owned(Vec<u8>),
+}
+
+impl Payload {
+    fn into_bytes(self) -> Bytes {
+        match self {
+            Payload::Shared(bytes) => bytes,
+            Payload::Owned(vec) => vec.into(),
+        }
+    }
+
+    fn into_vec(self) -> Vec<u8> {
+        match self {
+            Payload::Shared(bytes) => bytes.into(),
+            Payload::Owned(vec) => vec,
+        }
+    }
+
+    fn make_mut(&mut self) -> &mut Vec<u8> {
+        if let Payload::Shared(bytes) = self {
+            *self = Payload::Owned(core::mem::take(bytes).into());
+        }
+        match self {
+            Payload::Owned(vec) => vec,
+            Payload::Shared(_) => unreachable!("the payload was just made owned"),
+        }
+    }
+}
+
+impl Deref for Payload {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Payload::Shared(bytes) => bytes,
+            Payload::Owned(vec) => vec,
+        }
+    }
+}
+
+impl PartialEq for Payload {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for Payload {}
+
+impl PartialOrd for Payload {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Payload {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl Hash for Payload {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl Serialize for Payload {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Payload {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> core::result::Result<Self, D::Error> {
+        Ok(Payload::Shared(Bytes::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_bytes_are_not_copied() {
+        let payload = Bytes::from(vec![1, 2, 3]);
+        let message = LocalMessage::new().with_payload(payload.clone());
+
+        assert_eq!(message.payload_bytes().as_ptr(), payload.as_ptr());
+        assert_eq!(message.into_payload_bytes().as_ptr(), payload.as_ptr());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_payload_mut() {
+        let payload = Bytes::from(vec![1, 2, 3]);
+        let mut message = LocalMessage::new().with_payload(payload.clone());
+        message.payload_mut().push(4);
+
+        // the shared buffer is not modified
+        assert_eq!(payload.as_ref(), &[1, 2, 3]);
+        assert_eq!(message.payload_ref(), &[1, 2, 3, 4]);
+        assert_eq!(message.clone().into_payload(), vec![1, 2, 3, 4]);
+        assert_eq!(message.into_payload_bytes().as_ref(), &[1, 2, 3, 4]);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{Address, LocalMessage, ProtocolVersion, Route};
+use crate::{Address, LocalMessage, MessagePriority, ProtocolVersion, Route};
 
 /// A message addressed to the relay responsible for delivery of the
 /// wrapped [`LocalMessage`]
@@ -7,6 +7,7 @@ pub struct RelayMessage {
     source: Address,
     destination: Address,
     local_msg: LocalMessage,
+    priority: MessagePriority,
 }
 
 impl RelayMessage {
@@ -16,9 +17,21 @@ impl RelayMessage {
             source,
             destination,
             local_msg,
+            priority: MessagePriority::default(),
         }
     }
 
+    /// Set the priority used to deliver this message to the destination worker
+    pub fn with_priority(mut self, priority: MessagePriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Priority used to deliver this message to the destination worker
+    pub fn priority(&self) -> MessagePriority {
+        self.priority
+    }
+
     /// The sender address of the wrapped `LocalMessage`
     /// Note that this may be different from the first hop in the return_route
     /// This address is always equal to the address of the `Context` instance used to
@@ -1,4 +1,5 @@
 use crate::alloc::string::ToString;
+use crate::compat::bytes::Bytes;
 use crate::compat::string::String;
 use crate::errcode::{Kind, Origin};
 #[cfg(feature = "std")]
@@ -8,6 +9,7 @@ use crate::OCKAM_TRACER_NAME;
 use crate::{compat::vec::Vec, Decodable, Encodable, Encoded, Message, Route};
 use crate::{Error, Result};
 use core::fmt::{self, Display, Formatter};
+use core::ops::Range;
 #[cfg(feature = "std")]
 use opentelemetry::{
     global,
@@ -49,7 +51,9 @@ pub struct TransportMessage {
     /// along the way.
     pub return_route: Route,
     /// The message payload.
-    pub payload: Vec<u8>,
+    ///
+    /// When the message is decoded from a received buffer, the payload shares that buffer
+    pub payload: Bytes,
     /// An optional tracing context
     pub tracing_context: Option<String>,
 }
@@ -59,7 +63,7 @@ impl TransportMessage {
     pub fn latest(
         onward_route: impl Into<Route>,
         return_route: impl Into<Route>,
-        payload: Vec<u8>,
+        payload: impl Into<Bytes>,
     ) -> Self {
         TransportMessage::new(
             LATEST_PROTOCOL_VERSION,
@@ -74,7 +78,7 @@ impl TransportMessage {
     pub fn v1(
         onward_route: impl Into<Route>,
         return_route: impl Into<Route>,
-        payload: Vec<u8>,
+        payload: impl Into<Bytes>,
     ) -> Self {
         TransportMessage::new(
             PROTOCOL_VERSION_V1,
@@ -90,20 +94,23 @@ impl TransportMessage {
         version: ProtocolVersion,
         onward_route: impl Into<Route>,
         return_route: impl Into<Route>,
-        payload: Vec<u8>,
+        payload: impl Into<Bytes>,
         tracing_context: Option<String>,
     ) -> Self {
         Self {
             version,
             onward_route: onward_route.into(),
             return_route: return_route.into(),
-            payload,
+            payload: payload.into(),
             tracing_context,
         }
     }
 
-    /// Decode the transport message according to the first byte, which is the version number
-    pub fn decode_message(buf: Vec<u8>) -> Result<TransportMessage> {
+    /// Decode the transport message according to the first byte, which is the version number.
+    ///
+    /// The payload of a message in the latest version is not copied, it shares the buffer
+    pub fn decode_message(buf: impl Into<Bytes>) -> Result<TransportMessage> {
+        let buf = buf.into();
         if buf.is_empty() {
             return Err(Error::new(
                 Origin::Transport,
@@ -122,7 +129,7 @@ impl TransportMessage {
                         format!("Error decoding message: {:?}", e),
                     )
                 }),
-            LATEST_PROTOCOL_VERSION => TransportMessage::decode(&buf).map_err(|e| {
+            LATEST_PROTOCOL_VERSION => TransportMessage::decode_shared(buf).map_err(|e| {
                 Error::new(
                     Origin::Transport,
                     Kind::Serialization,
@@ -195,53 +202,82 @@ impl Display for TransportMessage {
 
 impl Encodable for TransportMessage {
     fn encode(self) -> Result<Encoded> {
+        let mut encoded = Vec::with_capacity(self.encoded_size());
+        self.encode_to(&mut encoded);
+        Ok(encoded)
+    }
+}
+
+impl TransportMessage {
+    /// Size of the encoded message, in bytes
+    pub fn encoded_size(&self) -> usize {
         let tracing = if let Some(tracing_context) = self.tracing_context.as_ref() {
             1 + crate::bare::size_of_slice(tracing_context.as_bytes())
         } else {
             1
         };
 
-        let mut encoded = Vec::with_capacity(
-            1 + self.onward_route.encoded_size()
-                + self.return_route.encoded_size()
-                + crate::bare::size_of_slice(&self.payload)
-                + tracing,
-        );
-        encoded.push(self.version);
-        self.onward_route.manual_encode(&mut encoded);
-        self.return_route.manual_encode(&mut encoded);
-        crate::bare::write_slice(&mut encoded, &self.payload);
+        1 + self.onward_route.encoded_size()
+            + self.return_route.encoded_size()
+            + crate::bare::size_of_slice(&self.payload)
+            + tracing
+    }
+
+    /// Append the encoded message to a buffer.
+    ///
+    /// This allows a transport to write the message after a frame header
+    /// without allocating and copying another buffer
+    pub fn encode_to(&self, buffer: &mut Vec<u8>) {
+        buffer.push(self.version);
+        self.onward_route.manual_encode(buffer);
+        self.return_route.manual_encode(buffer);
+        crate::bare::write_slice(buffer, &self.payload);
         if let Some(tracing_context) = self.tracing_context.as_ref() {
-            encoded.push(1);
-            crate::bare::write_str(&mut encoded, tracing_context);
+            buffer.push(1);
+            crate::bare::write_str(buffer, tracing_context);
         } else {
-            encoded.push(0);
+            buffer.push(0);
         }
-        Ok(encoded)
     }
 }
 
 impl Decodable for TransportMessage {
     fn decode(slice: &[u8]) -> Result<Self> {
-        Self::internal_decode(slice).ok_or_else(|| {
-            Error::new(
-                Origin::Transport,
-                Kind::Protocol,
-                "Failed to decode TransportMessage",
-            )
+        let (message, payload) = Self::decode_without_payload(slice)?;
+        Ok(Self {
+            payload: Bytes::copy_from_slice(&slice[payload]),
+            ..message
         })
     }
 }
 
 impl TransportMessage {
-    fn internal_decode(slice: &[u8]) -> Option<Self> {
+    /// Decode a shared buffer, the payload of the message being a slice of that buffer
+    fn decode_shared(buffer: Bytes) -> Result<Self> {
+        let (message, payload) = Self::decode_without_payload(&buffer)?;
+        Ok(Self {
+            payload: buffer.slice(payload),
+            ..message
+        })
+    }
+
+    /// Decode all the fields of a message except its payload
+    /// and return the range of the payload in the slice
+    fn decode_without_payload(slice: &[u8]) -> Result<(Self, Range<usize>)> {
         let mut index = 0;
-        let version = slice.get(index)?;
+        let version = slice
+            .get(index)
+            .ok_or_else(|| decode_error("missing version"))?;
         index += 1;
 
-        let onward_route = Route::manual_decode(slice, &mut index)?;
-        let return_route = Route::manual_decode(slice, &mut index)?;
-        let payload = crate::bare::read_slice(slice, &mut index)?;
+        let onward_route = Route::manual_decode(slice, &mut index)
+            .ok_or_else(|| decode_error("invalid onward route"))?;
+        let return_route = Route::manual_decode(slice, &mut index)
+            .ok_or_else(|| decode_error("invalid return route"))?;
+        let payload_length = crate::bare::read_slice(slice, &mut index)
+            .ok_or_else(|| decode_error("invalid payload"))?
+            .len();
+        let payload = index - payload_length..index;
 
         let present = slice.get(index).unwrap_or(&0);
         index += 1;
@@ -251,16 +287,27 @@ impl TransportMessage {
             None
         };
 
-        Some(Self {
-            version: *version,
-            onward_route,
-            return_route,
-            payload: payload.to_vec(),
-            tracing_context,
-        })
+        Ok((
+            Self {
+                version: *version,
+                onward_route,
+                return_route,
+                payload: Bytes::new(),
+                tracing_context,
+            },
+            payload,
+        ))
     }
 }
 
+fn decode_error(reason: &str) -> Error {
+    Error::new(
+        Origin::Transport,
+        Kind::Protocol,
+        format!("Failed to decode TransportMessage: {reason}"),
+    )
+}
+
 /// This is version 1 of the transport message without a tracing_context field
 #[derive(Debug, Clone, Eq, PartialEq, Message)]
 pub struct TransportMessageV1 {
@@ -284,7 +331,7 @@ impl TransportMessageV1 {
             version: PROTOCOL_VERSION_V1,
             onward_route: self.onward_route,
             return_route: self.return_route,
-            payload: self.payload,
+            payload: self.payload.into(),
             tracing_context: None,
         }
     }
@@ -361,7 +408,7 @@ mod tests {
         let transport_message_v1 =
             TransportMessageV1::new(route!["onward"], route!["return"], vec![]);
         let transport_message_v2 =
-            TransportMessage::latest(route!["onward"], route!["return"], vec![]);
+            TransportMessage::latest(route!["onward"], route!["return"], Bytes::new());
 
         // a v1 message should be decodable as the latest structure
         let encoded_v1 = transport_message_v1.encode().unwrap();
@@ -369,7 +416,7 @@ mod tests {
             PROTOCOL_VERSION_V1,
             route!["onward"],
             route!["return"],
-            vec![],
+            Bytes::new(),
             None,
         );
         assert_eq!(
@@ -389,11 +436,41 @@ mod tests {
             version: 3,
             onward_route: route![],
             return_route: route![],
-            payload: vec![],
+            payload: Bytes::new(),
             tracing_context: None,
         }
         .encode()
         .unwrap();
         assert!(TransportMessage::decode_message(encoded_v3).is_err());
     }
+
+    #[test]
+    fn test_decode_message_with_payload_and_tracing_context() {
+        let message = TransportMessage::new(
+            LATEST_PROTOCOL_VERSION,
+            route!["onward"],
+            route!["return"],
+            vec![7; 1000],
+            Some("tracing context".to_string()),
+        );
+        let encoded = message.clone().encode().unwrap();
+        assert_eq!(encoded.len(), message.encoded_size());
+        assert_eq!(TransportMessage::decode(&encoded).unwrap(), message);
+        assert_eq!(TransportMessage::decode_message(encoded).unwrap(), message);
+
+        // a truncated payload must fail to be decoded
+        let mut encoded = message.encode().unwrap();
+        encoded.truncate(500);
+        let error = TransportMessage::decode_message(encoded).unwrap_err();
+        assert!(format!("{error:?}").contains("invalid payload"));
+    }
+
+    #[test]
+    fn test_decoded_payload_shares_the_received_buffer() {
+        let message = TransportMessage::latest(route!["onward"], route!["return"], vec![7; 1000]);
+        let encoded = Bytes::from(message.clone().encode().unwrap());
+        let decoded = TransportMessage::decode_message(encoded.clone()).unwrap();
+        assert_eq!(decoded, message);
+        assert!(encoded.as_ptr_range().contains(&decoded.payload.as_ptr()));
+    }
 }
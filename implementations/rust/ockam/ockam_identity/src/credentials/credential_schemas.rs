@@ -0,0 +1,50 @@
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::RwLock;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+
+use crate::models::{CredentialSchema, CredentialSchemaIdentifier};
+use crate::Attributes;
+
+/// Registry of the [`CredentialSchema`]s published by Authorities.
+///
+/// When a schema is registered for a [`CredentialSchemaIdentifier`], the attributes of the
+/// credentials issued or presented with that identifier are validated against it.
+/// Credentials using a schema identifier without a registered schema are not validated
+#[derive(Default)]
+pub struct CredentialSchemas {
+    schemas: RwLock<BTreeMap<u64, CredentialSchema>>,
+}
+
+impl CredentialSchemas {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a schema, replacing the previous schema with the same identifier
+    pub fn register(&self, schema: CredentialSchema) {
+        self.schemas
+            .write()
+            .unwrap()
+            .insert(schema.identifier.0, schema);
+    }
+
+    /// Return the schema registered for a given identifier
+    pub fn get(&self, identifier: CredentialSchemaIdentifier) -> Option<CredentialSchema> {
+        self.schemas.read().unwrap().get(&identifier.0).cloned()
+    }
+
+    /// Return all the registered schemas
+    pub fn list(&self) -> Vec<CredentialSchema> {
+        self.schemas.read().unwrap().values().cloned().collect()
+    }
+
+    /// Validate some attributes against the schema registered for their schema identifier, if any
+    pub fn validate(&self, attributes: &Attributes) -> Result<()> {
+        match self.get(attributes.schema) {
+            Some(schema) => schema.validate(attributes),
+            None => Ok(()),
+        }
+    }
+}
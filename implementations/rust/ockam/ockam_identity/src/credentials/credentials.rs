@@ -1,12 +1,107 @@
+use tracing::warn;
+
+use ockam_core::compat::collections::BTreeSet;
+use ockam_core::compat::string::String;
 use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
 use ockam_vault::{VaultForSigning, VaultForVerifyingSignatures};
 
-use crate::models::{CredentialData, PurposeKeyAttestationData};
+use crate::models::{
+    Attributes, CredentialData, Identifier, PurposeKeyAttestationData, RevocationListAndPurposeKey,
+    RevokedSubject,
+};
+use crate::utils::now;
 use crate::{
-    CredentialsCreation, CredentialsVerification, IdentitiesCreation, IdentityAttributesRepository,
-    PurposeKeys,
+    CredentialSchemas, CredentialsCreation, CredentialsVerification, IdentitiesCreation,
+    IdentityAttributesRepository, IdentityError, PurposeKeys, RevocationListRepository,
 };
 
+/// Attribute that must be present, with the value `true`, in the credential of a delegated issuer
+/// for the credentials issued by that issuer to be accepted
+pub const DELEGATED_ISSUER_ATTRIBUTE: &str = "ockam-delegated-issuer";
+
+/// Attribute of the credential of a delegated issuer listing, separated by commas, the names of
+/// the attributes which that issuer can set in the credentials it issues. When it is not set,
+/// the issuer can set any attribute except the [`DELEGATION_RESERVED_ATTRIBUTES`]
+pub const DELEGATED_ATTRIBUTES_ATTRIBUTE: &str = "ockam-delegated-attributes";
+
+/// Attributes granting privileges, which a delegated issuer can only set if they are
+/// explicitly listed in the [`DELEGATED_ATTRIBUTES_ATTRIBUTE`] attribute of its credential
+pub const DELEGATION_RESERVED_ATTRIBUTES: [&str; 5] = [
+    "ockam-role",
+    "ockam-relay",
+    "trust_context_id",
+    DELEGATED_ISSUER_ATTRIBUTE,
+    DELEGATED_ATTRIBUTES_ATTRIBUTE,
+];
+
+/// Maximum number of delegated issuers between an authority and the subject of a credential
+pub const MAX_DELEGATION_DEPTH: usize = 3;
+
+/// Attributes which a delegated issuer is allowed to set in the credentials it issues
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct DelegationScope {
+    /// Explicitly delegated attributes, any non-reserved attribute when not set
+    allowed: Option<BTreeSet<Vec<u8>>>,
+}
+
+impl DelegationScope {
+    /// Return the scope granted to the subject of a delegation credential. That scope can't be
+    /// larger than the scope of the issuer of the delegation credential
+    pub(crate) fn granted_by(credential_data: &CredentialData, issuer_scope: &Self) -> Self {
+        let granted: Option<BTreeSet<Vec<u8>>> = credential_data
+            .subject_attributes
+            .map
+            .iter()
+            .find(|(k, _)| k.as_slice() == DELEGATED_ATTRIBUTES_ATTRIBUTE.as_bytes())
+            .map(|(_, v)| {
+                String::from_utf8_lossy(v.as_slice())
+                    .split(',')
+                    .map(|name| name.trim().as_bytes().to_vec())
+                    .filter(|name| !name.is_empty())
+                    .collect()
+            });
+        let allowed = match (granted, &issuer_scope.allowed) {
+            (Some(granted), Some(issuer_allowed)) => {
+                Some(granted.intersection(issuer_allowed).cloned().collect())
+            }
+            (Some(granted), None) => Some(granted),
+            (None, issuer_allowed) => issuer_allowed.clone(),
+        };
+        Self { allowed }
+    }
+
+    /// Return the scope of the last delegated issuer of a chain of delegation credentials,
+    /// starting with the credential issued by the authority
+    pub(crate) fn of_chain<'a>(chain: impl Iterator<Item = &'a CredentialData>) -> Self {
+        chain.fold(Self::default(), |scope, credential_data| {
+            Self::granted_by(credential_data, &scope)
+        })
+    }
+
+    /// Check that all the attributes of a credential issued by a delegated issuer are
+    /// within the scope of that issuer
+    pub(crate) fn check(&self, attributes: &Attributes) -> Result<()> {
+        for name in attributes.map.keys() {
+            let allowed = match &self.allowed {
+                Some(allowed) => allowed.contains(name.as_slice()),
+                None => !DELEGATION_RESERVED_ATTRIBUTES
+                    .iter()
+                    .any(|reserved| reserved.as_bytes() == name.as_slice()),
+            };
+            if !allowed {
+                warn!(
+                    "the attribute {} is not delegated to the issuer of a credential",
+                    String::from_utf8_lossy(name.as_slice())
+                );
+                return Err(IdentityError::InvalidCredentialDelegation)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Structure with both [`CredentialData`] and [`PurposeKeyAttestationData`] that we get
 /// after parsing and verifying corresponding [`Credential`] and [`super::super::models::PurposeKeyAttestation`]
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -15,6 +110,9 @@ pub struct CredentialAndPurposeKeyData {
     pub credential_data: CredentialData,
     /// [`PurposeKeyAttestationData`]
     pub purpose_key_data: PurposeKeyAttestationData,
+    /// Authority which issued the credential, or which authorized the delegated issuer
+    /// of the credential. It is the subject of `purpose_key_data` for a non-delegated credential
+    pub authority: Identifier,
 }
 
 /// Service for managing [`Credential`]s
@@ -24,6 +122,8 @@ pub struct Credentials {
     purpose_keys: Arc<PurposeKeys>,
     identities_creation: Arc<IdentitiesCreation>,
     identity_attributes_repository: Arc<dyn IdentityAttributesRepository>,
+    revocation_list_repository: Arc<dyn RevocationListRepository>,
+    credential_schemas: Arc<CredentialSchemas>,
 }
 
 impl Credentials {
@@ -34,6 +134,8 @@ impl Credentials {
         purpose_keys: Arc<PurposeKeys>,
         identities_creation: Arc<IdentitiesCreation>,
         identity_attributes_repository: Arc<dyn IdentityAttributesRepository>,
+        revocation_list_repository: Arc<dyn RevocationListRepository>,
+        credential_schemas: Arc<CredentialSchemas>,
     ) -> Self {
         Self {
             credential_vault,
@@ -41,6 +143,8 @@ impl Credentials {
             purpose_keys,
             identities_creation,
             identity_attributes_repository,
+            revocation_list_repository,
+            credential_schemas,
         }
     }
 
@@ -56,6 +160,7 @@ impl Credentials {
             self.credential_vault.clone(),
             self.verifying_vault.clone(),
             self.identities_creation.identities_verification(),
+            self.credential_schemas.clone(),
         ))
     }
 
@@ -65,8 +170,74 @@ impl Credentials {
             self.purpose_keys.purpose_keys_verification(),
             self.verifying_vault.clone(),
             self.identity_attributes_repository.clone(),
+            self.revocation_list_repository.clone(),
+            self.credential_schemas.clone(),
         ))
     }
+
+    /// Return the registry of [`crate::models::CredentialSchema`]s
+    pub fn credential_schemas(&self) -> Arc<CredentialSchemas> {
+        self.credential_schemas.clone()
+    }
+
+    /// Return the repository for revocation lists
+    pub fn revocation_list_repository(&self) -> Arc<dyn RevocationListRepository> {
+        self.revocation_list_repository.clone()
+    }
+
+    /// Return the current revocation list of an issuer, or issue an empty one if that issuer
+    /// has not revoked any subject yet
+    pub async fn get_or_issue_revocation_list(
+        &self,
+        issuer: &Identifier,
+    ) -> Result<RevocationListAndPurposeKey> {
+        if let Some(revocation_list) = self
+            .revocation_list_repository
+            .get_revocation_list(issuer)
+            .await?
+        {
+            return Ok(revocation_list);
+        };
+
+        let revocation_list = self
+            .credentials_creation()
+            .issue_revocation_list(issuer, vec![])
+            .await?;
+        self.revocation_list_repository
+            .put_revocation_list(issuer, revocation_list.clone())
+            .await?;
+        Ok(revocation_list)
+    }
+
+    /// Revoke all the credentials issued to a subject until now and issue a new revocation list
+    pub async fn revoke_subject(
+        &self,
+        issuer: &Identifier,
+        subject: &Identifier,
+    ) -> Result<RevocationListAndPurposeKey> {
+        let mut revoked = match self
+            .revocation_list_repository
+            .get_revocation_list(issuer)
+            .await?
+        {
+            Some(revocation_list) => revocation_list.get_revocation_list_data()?.revoked,
+            None => vec![],
+        };
+        revoked.retain(|r| &r.subject != subject);
+        revoked.push(RevokedSubject {
+            subject: subject.clone(),
+            revoked_at: now()?,
+        });
+
+        let revocation_list = self
+            .credentials_creation()
+            .issue_revocation_list(issuer, revoked)
+            .await?;
+        self.revocation_list_repository
+            .put_revocation_list(issuer, revocation_list.clone())
+            .await?;
+        Ok(revocation_list)
+    }
 }
 
 #[cfg(test)]
@@ -79,8 +250,12 @@ mod tests {
     use ockam_core::Result;
 
     use crate::identities::identities;
-    use crate::models::CredentialSchemaIdentifier;
-    use crate::Attributes;
+    use crate::models::{
+        AttributeDefinition, AttributeType, CredentialDelegation, CredentialSchema,
+        CredentialSchemaIdentifier,
+    };
+    use crate::utils::AttributesBuilder;
+    use crate::{Attributes, DELEGATED_ATTRIBUTES_ATTRIBUTE, DELEGATED_ISSUER_ATTRIBUTE};
 
     #[tokio::test]
     async fn test_issue_credential() -> Result<()> {
@@ -117,4 +292,391 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_issue_credential_with_schema() -> Result<()> {
+        let identities = identities().await?;
+        let creation = identities.identities_creation();
+
+        let issuer = creation.create_identity().await?;
+        let subject = creation.create_identity().await?;
+        let credentials = identities.credentials();
+        credentials.credential_schemas().register(CredentialSchema {
+            identifier: CredentialSchemaIdentifier(1),
+            attributes: vec![AttributeDefinition {
+                name: "age".to_string(),
+                attribute_type: AttributeType::Integer,
+                allowed_values: vec![],
+                required: true,
+            }],
+        });
+
+        // malformed attributes are rejected
+        let attributes = AttributesBuilder::with_schema(CredentialSchemaIdentifier(1))
+            .with_attribute("age", "twenty")
+            .build();
+        let result = credentials
+            .credentials_creation()
+            .issue_credential(&issuer, &subject, attributes, Duration::from_secs(60))
+            .await;
+        assert!(result.is_err());
+
+        // valid attributes are accepted when issuing and verifying the credential
+        let attributes = AttributesBuilder::with_schema(CredentialSchemaIdentifier(1))
+            .with_attribute("age", "20")
+            .build();
+        let credential = credentials
+            .credentials_creation()
+            .issue_credential(&issuer, &subject, attributes, Duration::from_secs(60))
+            .await?;
+        credentials
+            .credentials_verification()
+            .verify_credential(Some(&subject), &[issuer.clone()], &credential)
+            .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_revoke_credential() -> Result<()> {
+        let identities = identities().await?;
+        let creation = identities.identities_creation();
+
+        let issuer = creation.create_identity().await?;
+        let subject = creation.create_identity().await?;
+        let credentials = identities.credentials();
+        let attributes = Attributes {
+            schema: CredentialSchemaIdentifier(1),
+            map: Default::default(),
+        };
+
+        let credential = credentials
+            .credentials_creation()
+            .issue_credential(
+                &issuer,
+                &subject,
+                attributes.clone(),
+                Duration::from_secs(60 * 60),
+            )
+            .await?;
+
+        // the credential is rejected once its subject is revoked
+        credentials.revoke_subject(&issuer, &subject).await?;
+        let result = credentials
+            .credentials_verification()
+            .verify_credential(Some(&subject), &[issuer.clone()], &credential)
+            .await;
+        assert!(result.is_err());
+
+        // a credential issued after the revocation is accepted again
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        let credential = credentials
+            .credentials_creation()
+            .issue_credential(&issuer, &subject, attributes, Duration::from_secs(60 * 60))
+            .await?;
+        credentials
+            .credentials_verification()
+            .verify_credential(Some(&subject), &[issuer.clone()], &credential)
+            .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_receive_revocation_list() -> Result<()> {
+        let issuer_identities = identities().await?;
+        let issuer = issuer_identities
+            .identities_creation()
+            .create_identity()
+            .await?;
+        let subject = issuer_identities
+            .identities_creation()
+            .create_identity()
+            .await?;
+        let issuer_credentials = issuer_identities.credentials();
+        let revocation_list = issuer_credentials.revoke_subject(&issuer, &subject).await?;
+
+        let identities = identities().await?;
+        identities
+            .identities_verification()
+            .import(
+                Some(&issuer),
+                &issuer_identities.export_identity(&issuer).await?,
+            )
+            .await?;
+        let verification = identities.credentials().credentials_verification();
+
+        // the revocation list must be signed by a known authority
+        let other = identities.identities_creation().create_identity().await?;
+        let result = verification
+            .receive_revocation_list(&[other], &revocation_list)
+            .await;
+        assert!(result.is_err());
+
+        verification
+            .receive_revocation_list(&[issuer.clone()], &revocation_list)
+            .await?;
+        let stored = identities
+            .credentials()
+            .revocation_list_repository()
+            .get_revocation_list(&issuer)
+            .await?;
+        assert_eq!(stored, Some(revocation_list));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delegated_credential() -> Result<()> {
+        let issuer_identities = identities().await?;
+        let creation = issuer_identities.identities_creation();
+        let root = creation.create_identity().await?;
+        let delegate = creation.create_identity().await?;
+        let device = creation.create_identity().await?;
+        let credentials_creation = issuer_identities.credentials().credentials_creation();
+
+        let issuer_credential = credentials_creation
+            .issue_credential(
+                &root,
+                &delegate,
+                AttributesBuilder::with_schema(CredentialSchemaIdentifier(1))
+                    .with_attribute(DELEGATED_ISSUER_ATTRIBUTE, "true")
+                    .build(),
+                Duration::from_secs(60 * 60),
+            )
+            .await?;
+        let credential = credentials_creation
+            .issue_delegated_credential(
+                &delegate,
+                issuer_credential,
+                &device,
+                AttributesBuilder::with_schema(CredentialSchemaIdentifier(1))
+                    .with_attribute("key", "value")
+                    .build(),
+                Duration::from_secs(2 * 60 * 60),
+            )
+            .await?;
+
+        // the delegated issuer doesn't need to be known by the verifier
+        let identities = identities().await?;
+        identities
+            .identities_verification()
+            .import(
+                Some(&root),
+                &issuer_identities.export_identity(&root).await?,
+            )
+            .await?;
+        let data = identities
+            .credentials()
+            .credentials_verification()
+            .verify_credential(Some(&device), &[root.clone()], &credential)
+            .await?;
+        assert_eq!(data.authority, root);
+        assert_eq!(data.purpose_key_data.subject, delegate);
+        // the credential can't outlive the credential of its issuer
+        assert!(data.credential_data.expires_at <= credential.get_chain()[1].get_expires_at()?);
+
+        // the issuer credential must authorize the delegation
+        let not_delegated = credentials_creation
+            .issue_credential(
+                &root,
+                &delegate,
+                AttributesBuilder::with_schema(CredentialSchemaIdentifier(1)).build(),
+                Duration::from_secs(60 * 60),
+            )
+            .await?;
+        let result = credentials_creation
+            .issue_delegated_credential(
+                &delegate,
+                not_delegated,
+                &device,
+                AttributesBuilder::with_schema(CredentialSchemaIdentifier(1)).build(),
+                Duration::from_secs(60 * 60),
+            )
+            .await;
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delegated_credential_scope() -> Result<()> {
+        let issuer_identities = identities().await?;
+        let creation = issuer_identities.identities_creation();
+        let root = creation.create_identity().await?;
+        let delegate = creation.create_identity().await?;
+        let sub_delegate = creation.create_identity().await?;
+        let device = creation.create_identity().await?;
+        let credentials_creation = issuer_identities.credentials().credentials_creation();
+        let attributes = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .fold(
+                    AttributesBuilder::with_schema(CredentialSchemaIdentifier(1)),
+                    |builder, (k, v)| builder.with_attribute(*k, *v),
+                )
+                .build()
+        };
+
+        let identities = identities().await?;
+        identities
+            .identities_verification()
+            .import(
+                Some(&root),
+                &issuer_identities.export_identity(&root).await?,
+            )
+            .await?;
+        let verification = identities.credentials().credentials_verification();
+
+        // without an explicit scope, a delegated issuer can't set the reserved attributes
+        let unscoped = credentials_creation
+            .issue_credential(
+                &root,
+                &delegate,
+                attributes(&[(DELEGATED_ISSUER_ATTRIBUTE, "true")]),
+                Duration::from_secs(60 * 60),
+            )
+            .await?;
+        for reserved in ["ockam-role", DELEGATED_ISSUER_ATTRIBUTE, "trust_context_id"] {
+            let result = credentials_creation
+                .issue_delegated_credential(
+                    &delegate,
+                    unscoped.clone(),
+                    &device,
+                    attributes(&[(reserved, "value")]),
+                    Duration::from_secs(60 * 60),
+                )
+                .await;
+            assert!(result.is_err());
+        }
+
+        // with a scope, a delegated issuer can only set the delegated attributes
+        let scoped = credentials_creation
+            .issue_credential(
+                &root,
+                &delegate,
+                attributes(&[
+                    (DELEGATED_ISSUER_ATTRIBUTE, "true"),
+                    (DELEGATED_ATTRIBUTES_ATTRIBUTE, "key, ockam-role"),
+                ]),
+                Duration::from_secs(60 * 60),
+            )
+            .await?;
+        let result = credentials_creation
+            .issue_delegated_credential(
+                &delegate,
+                scoped.clone(),
+                &device,
+                attributes(&[("other", "value")]),
+                Duration::from_secs(60 * 60),
+            )
+            .await;
+        assert!(result.is_err());
+        let credential = credentials_creation
+            .issue_delegated_credential(
+                &delegate,
+                scoped.clone(),
+                &device,
+                attributes(&[("key", "value"), ("ockam-role", "enroller")]),
+                Duration::from_secs(60 * 60),
+            )
+            .await?;
+        verification
+            .verify_credential(Some(&device), &[root.clone()], &credential)
+            .await?;
+
+        // a nested delegation is only accepted if the delegation attributes are delegated too
+        let result = credentials_creation
+            .issue_delegated_credential(
+                &delegate,
+                scoped,
+                &sub_delegate,
+                attributes(&[(DELEGATED_ISSUER_ATTRIBUTE, "true")]),
+                Duration::from_secs(60 * 60),
+            )
+            .await;
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delegated_credential_out_of_scope_is_rejected_by_verifier() -> Result<()> {
+        let issuer_identities = identities().await?;
+        let creation = issuer_identities.identities_creation();
+        let root = creation.create_identity().await?;
+        let delegate = creation.create_identity().await?;
+        let device = creation.create_identity().await?;
+        let credentials_creation = issuer_identities.credentials().credentials_creation();
+
+        let issuer_credential = credentials_creation
+            .issue_credential(
+                &root,
+                &delegate,
+                AttributesBuilder::with_schema(CredentialSchemaIdentifier(1))
+                    .with_attribute(DELEGATED_ISSUER_ATTRIBUTE, "true")
+                    .build(),
+                Duration::from_secs(60 * 60),
+            )
+            .await?;
+        let credential = credentials_creation
+            .issue_delegated_credential(
+                &delegate,
+                issuer_credential.clone(),
+                &device,
+                AttributesBuilder::with_schema(CredentialSchemaIdentifier(1))
+                    .with_attribute("key", "value")
+                    .build(),
+                Duration::from_secs(60),
+            )
+            .await?;
+
+        // a credential built by the delegate with a reserved attribute is rejected on verification
+        let mut forged = credentials_creation
+            .issue_credential(
+                &delegate,
+                &device,
+                AttributesBuilder::with_schema(CredentialSchemaIdentifier(1))
+                    .with_attribute("ockam-role", "enroller")
+                    .build(),
+                Duration::from_secs(60),
+            )
+            .await?;
+        forged.delegation = Some(Box::new(CredentialDelegation {
+            issuer_change_history: issuer_identities
+                .get_identity(&delegate)
+                .await?
+                .change_history()
+                .clone(),
+            issuer_credential,
+        }));
+
+        let identities = identities().await?;
+        identities
+            .identities_verification()
+            .import(
+                Some(&root),
+                &issuer_identities.export_identity(&root).await?,
+            )
+            .await?;
+        let verification = identities.credentials().credentials_verification();
+        let result = verification
+            .verify_credential(Some(&device), &[root.clone()], &forged)
+            .await;
+        assert!(result.is_err());
+
+        // the attributes of an accepted credential are attested by the root, issued by the delegate
+        verification
+            .receive_presented_credential(&device, &[root.clone()], &credential)
+            .await?;
+        let entry = identities
+            .identities_attributes()
+            .get_attributes(&device, &root)
+            .await?
+            .unwrap();
+        assert_eq!(entry.attested_by(), Some(root));
+        assert_eq!(entry.issued_by(), Some(delegate));
+
+        Ok(())
+    }
 }
@@ -1,12 +1,20 @@
 use core::time::Duration;
 
+use ockam_core::compat::boxed::Box;
 use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
 use ockam_vault::{VaultForSigning, VaultForVerifyingSignatures};
 
-use crate::models::{Attributes, Credential, CredentialAndPurposeKey, CredentialData, Identifier};
+use crate::models::{
+    Attributes, Credential, CredentialAndPurposeKey, CredentialData, CredentialDelegation,
+    Identifier, RevocationList, RevocationListAndPurposeKey, RevocationListData, RevokedSubject,
+};
 use crate::utils::now;
-use crate::{IdentitiesVerification, PurposeKeyCreation, TimestampInSeconds};
+use crate::{
+    CredentialSchemas, DelegationScope, IdentitiesVerification, IdentityError, PurposeKeyCreation,
+    TimestampInSeconds, DELEGATED_ISSUER_ATTRIBUTE, MAX_DELEGATION_DEPTH,
+};
 
 /// Service for managing [`Credential`]s
 pub struct CredentialsCreation {
@@ -14,6 +22,7 @@ pub struct CredentialsCreation {
     credential_vault: Arc<dyn VaultForSigning>,
     verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
     identities_verification: Arc<IdentitiesVerification>,
+    credential_schemas: Arc<CredentialSchemas>,
 }
 
 impl CredentialsCreation {
@@ -23,12 +32,14 @@ impl CredentialsCreation {
         credential_vault: Arc<dyn VaultForSigning>,
         verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
         identities_verification: Arc<IdentitiesVerification>,
+        credential_schemas: Arc<CredentialSchemas>,
     ) -> Self {
         Self {
             purpose_keys_creation,
             verifying_vault,
             credential_vault,
             identities_verification,
+            credential_schemas,
         }
     }
 }
@@ -42,6 +53,102 @@ impl CredentialsCreation {
         subject_attributes: Attributes,
         ttl: Duration,
     ) -> Result<CredentialAndPurposeKey> {
+        let created_at = now()?;
+        let expires_at = created_at + TimestampInSeconds(ttl.as_secs());
+
+        self.issue_credential_with_expiration(
+            issuer,
+            subject,
+            subject_attributes,
+            created_at,
+            expires_at,
+            None,
+        )
+        .await
+    }
+
+    /// Issue a [`Credential`] as a delegated issuer. The credential of that issuer, which
+    /// must contain the [`DELEGATED_ISSUER_ATTRIBUTE`] attribute, is attached to the issued
+    /// credential so that it can be verified with the authority which issued it.
+    /// The issued credential expires at the latest when the issuer credential expires, and can
+    /// only contain the attributes delegated to the issuer
+    pub async fn issue_delegated_credential(
+        &self,
+        issuer: &Identifier,
+        issuer_credential: CredentialAndPurposeKey,
+        subject: &Identifier,
+        subject_attributes: Attributes,
+        ttl: Duration,
+    ) -> Result<CredentialAndPurposeKey> {
+        let issuer_credential_data = issuer_credential.get_credential_data()?;
+        if issuer_credential_data.subject.as_ref() != Some(issuer) {
+            return Err(IdentityError::InvalidCredentialDelegation)?;
+        }
+        let is_delegated_issuer =
+            issuer_credential_data
+                .subject_attributes
+                .map
+                .iter()
+                .any(|(k, v)| {
+                    k.as_slice() == DELEGATED_ISSUER_ATTRIBUTE.as_bytes() && v.as_slice() == b"true"
+                });
+        if !is_delegated_issuer {
+            return Err(IdentityError::InvalidCredentialDelegation)?;
+        }
+        if issuer_credential.get_chain().len() > MAX_DELEGATION_DEPTH {
+            return Err(IdentityError::InvalidCredentialDelegation)?;
+        }
+        let issuer_chain = issuer_credential
+            .get_chain()
+            .into_iter()
+            .rev()
+            .map(|credential| credential.get_credential_data())
+            .collect::<Result<Vec<_>>>()?;
+        DelegationScope::of_chain(issuer_chain.iter()).check(&subject_attributes)?;
+
+        let created_at = now()?;
+        if created_at >= issuer_credential_data.expires_at {
+            return Err(IdentityError::InvalidCredentialDelegation)?;
+        }
+        let expires_at = created_at + TimestampInSeconds(ttl.as_secs());
+        let expires_at = expires_at.min(issuer_credential_data.expires_at);
+        // The issued credential can't be valid before the issuer credential
+        let created_at = created_at.max(issuer_credential_data.created_at);
+
+        let issuer_change_history = self
+            .identities_verification
+            .get_identity(issuer)
+            .await?
+            .change_history()
+            .clone();
+
+        let delegation = CredentialDelegation {
+            issuer_change_history,
+            issuer_credential,
+        };
+
+        self.issue_credential_with_expiration(
+            issuer,
+            subject,
+            subject_attributes,
+            created_at,
+            expires_at,
+            Some(Box::new(delegation)),
+        )
+        .await
+    }
+
+    async fn issue_credential_with_expiration(
+        &self,
+        issuer: &Identifier,
+        subject: &Identifier,
+        subject_attributes: Attributes,
+        created_at: TimestampInSeconds,
+        expires_at: TimestampInSeconds,
+        delegation: Option<Box<CredentialDelegation>>,
+    ) -> Result<CredentialAndPurposeKey> {
+        self.credential_schemas.validate(&subject_attributes)?;
+
         // TODO: Allow manual PurposeKey management
         let issuer_purpose_key = self
             .purpose_keys_creation
@@ -50,9 +157,6 @@ impl CredentialsCreation {
 
         let subject_identity = self.identities_verification.get_identity(subject).await?;
 
-        let created_at = now()?;
-        let expires_at = created_at + TimestampInSeconds(ttl.as_secs());
-
         let credential_data = CredentialData {
             subject: Some(subject.clone()),
             subject_latest_change_hash: Some(subject_identity.latest_change_hash()?.clone()),
@@ -81,8 +185,48 @@ impl CredentialsCreation {
         let res = CredentialAndPurposeKey {
             credential,
             purpose_key_attestation: issuer_purpose_key.attestation().clone(),
+            delegation,
         };
 
         Ok(res)
     }
+
+    /// Issue a [`RevocationList`] listing all the subjects revoked by that issuer
+    pub async fn issue_revocation_list(
+        &self,
+        issuer: &Identifier,
+        revoked: Vec<RevokedSubject>,
+    ) -> Result<RevocationListAndPurposeKey> {
+        let issuer_purpose_key = self
+            .purpose_keys_creation
+            .get_or_create_credential_purpose_key(issuer)
+            .await?;
+
+        let revocation_list_data = RevocationListData {
+            created_at: now()?,
+            revoked,
+        };
+        let revocation_list_data = minicbor::to_vec(revocation_list_data)?;
+
+        let versioned_data = RevocationList::create_versioned_data(revocation_list_data);
+        let versioned_data = minicbor::to_vec(&versioned_data)?;
+
+        let versioned_data_hash = self.verifying_vault.sha256(&versioned_data).await?;
+
+        let signature = self
+            .credential_vault
+            .sign(issuer_purpose_key.key(), &versioned_data_hash.0)
+            .await?;
+        let signature = signature.into();
+
+        let revocation_list = RevocationList {
+            data: versioned_data,
+            signature,
+        };
+
+        Ok(RevocationListAndPurposeKey {
+            revocation_list,
+            purpose_key_attestation: issuer_purpose_key.attestation().clone(),
+        })
+    }
 }
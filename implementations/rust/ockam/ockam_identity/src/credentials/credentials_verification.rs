@@ -8,12 +8,14 @@ use ockam_vault::VaultForVerifyingSignatures;
 
 use crate::identities::AttributesEntry;
 use crate::models::{
-    CredentialAndPurposeKey, CredentialData, Identifier, PurposePublicKey, VersionedData,
+    CredentialAndPurposeKey, CredentialData, Identifier, PurposeKeyAttestationData,
+    PurposePublicKey, RevocationListAndPurposeKey, RevocationListData, VersionedData,
 };
 use crate::utils::now;
 use crate::{
-    CredentialAndPurposeKeyData, IdentityAttributesRepository, IdentityError,
-    PurposeKeyVerification, TimestampInSeconds,
+    CredentialAndPurposeKeyData, CredentialSchemas, DelegationScope, IdentityAttributesRepository,
+    IdentityError, PurposeKeyVerification, RevocationListRepository, TimestampInSeconds,
+    DELEGATED_ISSUER_ATTRIBUTE, MAX_DELEGATION_DEPTH,
 };
 
 /// We allow Credentials to be created in the future related to this machine's time due to
@@ -25,6 +27,8 @@ pub struct CredentialsVerification {
     purpose_keys_verification: Arc<PurposeKeyVerification>,
     verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
     identities_attributes_repository: Arc<dyn IdentityAttributesRepository>,
+    revocation_list_repository: Arc<dyn RevocationListRepository>,
+    credential_schemas: Arc<CredentialSchemas>,
 }
 
 impl CredentialsVerification {
@@ -33,34 +37,51 @@ impl CredentialsVerification {
         purpose_keys_verification: Arc<PurposeKeyVerification>,
         verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
         identities_attributes_repository: Arc<dyn IdentityAttributesRepository>,
+        revocation_list_repository: Arc<dyn RevocationListRepository>,
+        credential_schemas: Arc<CredentialSchemas>,
     ) -> Self {
         Self {
             purpose_keys_verification,
             verifying_vault,
             identities_attributes_repository,
+            revocation_list_repository,
+            credential_schemas,
         }
     }
 }
 
 impl CredentialsVerification {
-    /// Verify a [`Credential`]
+    /// Verify a [`Credential`], check that its subject has not been revoked by its issuer
+    /// and that its attributes are valid for their schema, if that schema is known
     pub async fn verify_credential(
         &self,
         expected_subject: Option<&Identifier>,
         authorities: &[Identifier],
         credential_and_purpose_key: &CredentialAndPurposeKey,
     ) -> Result<CredentialAndPurposeKeyData> {
-        Self::verify_credential_static(
+        let data = Self::verify_credential_static(
             self.purpose_keys_verification.clone(),
             self.verifying_vault.clone(),
             expected_subject,
             authorities,
             credential_and_purpose_key,
         )
-        .await
+        .await?;
+
+        debug!("verify revocation");
+        self.verify_not_revoked(credential_and_purpose_key).await?;
+
+        debug!("verify attributes");
+        self.credential_schemas
+            .validate(&data.credential_data.subject_attributes)?;
+
+        Ok(data)
     }
 
-    /// Verify a [`Credential`]
+    /// Verify a [`Credential`]. If that credential was issued by a delegated issuer, the
+    /// credentials of the delegated issuers are verified as well, up to the credential
+    /// issued by one of the authorities. The attributes of each credential issued by a delegated
+    /// issuer must be within the scope delegated to that issuer
     pub async fn verify_credential_static(
         purpose_keys_verification: Arc<PurposeKeyVerification>,
         verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
@@ -68,6 +89,106 @@ impl CredentialsVerification {
         authorities: &[Identifier],
         credential_and_purpose_key: &CredentialAndPurposeKey,
     ) -> Result<CredentialAndPurposeKeyData> {
+        let chain = credential_and_purpose_key.get_chain();
+        if chain.len() > MAX_DELEGATION_DEPTH + 1 {
+            warn!(
+                "too many delegated issuers for a credential: {}. Maximum: {}",
+                chain.len() - 1,
+                MAX_DELEGATION_DEPTH
+            );
+            return Err(IdentityError::InvalidCredentialDelegation)?;
+        }
+
+        // Verify the chain of credentials, starting with the credential issued by an authority.
+        // Each verified delegation credential makes its subject the only accepted issuer
+        // for the next credential in the chain
+        let mut issuers = authorities.to_vec();
+        let mut authority: Option<Identifier> = None;
+        let mut issuer_credential_data: Option<CredentialData> = None;
+        let mut scope = DelegationScope::default();
+        for (index, credential) in chain.iter().enumerate().rev() {
+            let is_delegation = index > 0;
+            let (credential_data, purpose_key_data) = Self::verify_single_credential(
+                purpose_keys_verification.clone(),
+                verifying_vault.clone(),
+                if is_delegation {
+                    None
+                } else {
+                    expected_subject
+                },
+                &issuers,
+                credential,
+            )
+            .await?;
+
+            let chain_authority = authority
+                .get_or_insert_with(|| purpose_key_data.subject.clone())
+                .clone();
+
+            if let Some(issuer_credential_data) = issuer_credential_data.as_ref() {
+                if credential_data.created_at < issuer_credential_data.created_at
+                    || credential_data.expires_at > issuer_credential_data.expires_at
+                {
+                    // A delegated credential must be valid only while its issuer is authorized
+                    return Err(IdentityError::InvalidCredentialDelegation)?;
+                }
+                scope.check(&credential_data.subject_attributes)?;
+            }
+
+            if !is_delegation {
+                return Ok(CredentialAndPurposeKeyData {
+                    credential_data,
+                    purpose_key_data,
+                    authority: chain_authority,
+                });
+            }
+
+            debug!("verify delegation");
+            let is_delegated_issuer =
+                credential_data.subject_attributes.map.iter().any(|(k, v)| {
+                    k.as_slice() == DELEGATED_ISSUER_ATTRIBUTE.as_bytes() && v.as_slice() == b"true"
+                });
+            let delegated_issuer = match (is_delegated_issuer, credential_data.subject.clone()) {
+                (true, Some(delegated_issuer)) => delegated_issuer,
+                _ => {
+                    warn!(
+                        "the subject of a credential issued by {} is not a delegated issuer",
+                        purpose_key_data.subject
+                    );
+                    return Err(IdentityError::InvalidCredentialDelegation)?;
+                }
+            };
+
+            // The delegated issuer might not be known yet, its change history is imported
+            // in order to verify the purpose key used to sign the next credential in the chain
+            let delegation = chain[index - 1]
+                .delegation
+                .as_deref()
+                .ok_or(IdentityError::InvalidCredentialDelegation)?;
+            purpose_keys_verification
+                .identities_verification()
+                .import_from_change_history(
+                    Some(&delegated_issuer),
+                    delegation.issuer_change_history.clone(),
+                )
+                .await?;
+
+            issuers = vec![delegated_issuer];
+            scope = DelegationScope::granted_by(&credential_data, &scope);
+            issuer_credential_data = Some(credential_data);
+        }
+
+        Err(IdentityError::CredentialVerificationFailed.into())
+    }
+
+    /// Verify a [`Credential`] issued by one of the given issuers, without its delegations
+    async fn verify_single_credential(
+        purpose_keys_verification: Arc<PurposeKeyVerification>,
+        verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
+        expected_subject: Option<&Identifier>,
+        authorities: &[Identifier],
+        credential_and_purpose_key: &CredentialAndPurposeKey,
+    ) -> Result<(CredentialData, PurposeKeyAttestationData)> {
         debug!("verify purpose key attestation");
         let purpose_key_data = purpose_keys_verification
             .verify_purpose_key_attestation(
@@ -177,12 +298,43 @@ impl CredentialsVerification {
             //     In such cases some limited tolerance may be introduced.
         }
 
-        // FIXME: Verify if Schema aligns with Attributes
+        // The attributes are validated against their schema in `verify_credential`,
+        // when that schema has been registered
 
-        Ok(CredentialAndPurposeKeyData {
-            credential_data,
-            purpose_key_data,
-        })
+        Ok((credential_data, purpose_key_data))
+    }
+
+    /// Check that neither the subject of a credential nor any of its delegated issuers
+    /// have been revoked, according to the known revocation lists
+    async fn verify_not_revoked(
+        &self,
+        credential_and_purpose_key: &CredentialAndPurposeKey,
+    ) -> Result<()> {
+        for credential in credential_and_purpose_key.get_chain() {
+            let credential_data = credential.get_credential_data()?;
+            let subject = match credential_data.subject {
+                Some(subject) => subject,
+                None => continue,
+            };
+            let issuer = credential
+                .purpose_key_attestation
+                .get_attestation_data()?
+                .subject;
+            if let Some(revocation_list) = self
+                .revocation_list_repository
+                .get_revocation_list(&issuer)
+                .await?
+            {
+                if revocation_list
+                    .get_revocation_list_data()?
+                    .is_revoked(&subject, credential_data.created_at)
+                {
+                    warn!("the credential of {subject} has been revoked by {issuer}");
+                    return Err(IdentityError::CredentialRevoked)?;
+                }
+            }
+        }
+        Ok(())
     }
 
     /// Receive someone's [`Credential`]: verify and put attributes from it to the storage
@@ -206,6 +358,9 @@ impl CredentialsVerification {
             .map(|(k, v)| (Vec::<u8>::from(k), Vec::<u8>::from(v)))
             .collect();
 
+        // The attributes are attested by the authority, but a delegated issuer is recorded too
+        let issuer = credential_data.purpose_key_data.subject;
+        let issued_by = (issuer != credential_data.authority).then_some(issuer);
         self.identities_attributes_repository
             .put_attributes(
                 subject,
@@ -213,11 +368,137 @@ impl CredentialsVerification {
                     map,
                     now()?,
                     Some(credential_data.credential_data.expires_at),
-                    Some(credential_data.purpose_key_data.subject),
-                ),
+                    Some(credential_data.authority),
+                )
+                .with_issued_by(issued_by),
             )
             .await?;
 
         Ok(())
     }
+
+    /// Verify a [`super::super::models::RevocationList`] signed by one of the authorities
+    pub async fn verify_revocation_list(
+        &self,
+        authorities: &[Identifier],
+        revocation_list_and_purpose_key: &RevocationListAndPurposeKey,
+    ) -> Result<(Identifier, RevocationListData)> {
+        debug!("verify purpose key attestation");
+        let purpose_key_data = self
+            .purpose_keys_verification
+            .verify_purpose_key_attestation(
+                None,
+                &revocation_list_and_purpose_key.purpose_key_attestation,
+            )
+            .await?;
+
+        debug!("verify issuer");
+        if !authorities.contains(&purpose_key_data.subject) {
+            warn!(
+                "unknown authority on a revocation list: {}. Accepted authorities: {:?}",
+                purpose_key_data.subject, authorities
+            );
+            return Err(IdentityError::UnknownAuthority)?;
+        }
+
+        debug!("verify purpose key type");
+        let public_key = match purpose_key_data.public_key.clone() {
+            PurposePublicKey::SecureChannelStatic(_) => {
+                return Err(IdentityError::InvalidKeyType)?;
+            }
+
+            PurposePublicKey::CredentialSigning(public_key) => public_key,
+        };
+
+        debug!("verify signature");
+        let public_key = public_key.into();
+        let versioned_data_hash = self
+            .verifying_vault
+            .sha256(&revocation_list_and_purpose_key.revocation_list.data)
+            .await?;
+
+        let signature = revocation_list_and_purpose_key
+            .revocation_list
+            .signature
+            .clone()
+            .into();
+
+        if !self
+            .verifying_vault
+            .verify_signature(&public_key, &versioned_data_hash.0, &signature)
+            .await?
+        {
+            return Err(IdentityError::RevocationListVerificationFailed)?;
+        }
+
+        let versioned_data: VersionedData =
+            minicbor::decode(&revocation_list_and_purpose_key.revocation_list.data)?;
+        let revocation_list_data = RevocationListData::get_data(&versioned_data)?;
+
+        debug!("verify dates");
+        if revocation_list_data.created_at < purpose_key_data.created_at
+            || revocation_list_data.created_at > purpose_key_data.expires_at
+        {
+            // The revocation list must be signed while the purpose key is valid
+            return Err(IdentityError::RevocationListVerificationFailed)?;
+        }
+
+        let now = now()?;
+        if revocation_list_data.created_at > now
+            && revocation_list_data.created_at - now > MAX_ALLOWED_TIME_DRIFT
+        {
+            // A revocation list can't be created in the future
+            return Err(IdentityError::RevocationListVerificationFailed)?;
+        }
+
+        Ok((purpose_key_data.subject, revocation_list_data))
+    }
+
+    /// Receive the [`super::super::models::RevocationList`] of an issuer: verify it, store it
+    /// if it is more recent than the one already known and remove the attributes attested
+    /// by that issuer for the revoked subjects
+    pub async fn receive_revocation_list(
+        &self,
+        authorities: &[Identifier],
+        revocation_list_and_purpose_key: &RevocationListAndPurposeKey,
+    ) -> Result<()> {
+        let (issuer, revocation_list_data) = self
+            .verify_revocation_list(authorities, revocation_list_and_purpose_key)
+            .await?;
+
+        let current = self
+            .revocation_list_repository
+            .get_revocation_list(&issuer)
+            .await?
+            .map(|current| current.get_revocation_list_data())
+            .transpose()?;
+
+        if let Some(current) = current.as_ref() {
+            if current.created_at > revocation_list_data.created_at {
+                debug!("ignore a revocation list of {issuer} older than the current one");
+                return Ok(());
+            }
+        }
+
+        self.revocation_list_repository
+            .put_revocation_list(&issuer, revocation_list_and_purpose_key.clone())
+            .await?;
+
+        // Only remove the attributes of the newly revoked subjects, since the subjects
+        // revoked previously might have presented a more recent credential since then
+        for revoked in revocation_list_data.revoked.iter() {
+            let already_revoked = current
+                .as_ref()
+                .and_then(|current| current.get_revoked_subject(&revoked.subject))
+                .map(|r| r.revoked_at == revoked.revoked_at)
+                .unwrap_or(false);
+            if !already_revoked {
+                self.identities_attributes_repository
+                    .delete_attributes(&revoked.subject, &issuer)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
 }
@@ -1,9 +1,11 @@
+mod credential_schemas;
 #[allow(clippy::module_inception)]
 mod credentials;
 mod credentials_creation;
 mod credentials_verification;
 mod retriever;
 
+pub use credential_schemas::*;
 pub use credentials::*;
 pub use credentials_creation::*;
 pub use credentials_verification::*;
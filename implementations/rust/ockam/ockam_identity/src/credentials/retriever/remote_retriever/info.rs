@@ -23,6 +23,7 @@ enum CredentialIssuerApiServiceAddress {
     ControllerAccount,
     ControllerProject { project_id: String },
     AuthorityNode,
+    AuthorityNodeSchema,
 }
 
 impl Display for CredentialIssuerApiServiceAddress {
@@ -33,6 +34,7 @@ impl Display for CredentialIssuerApiServiceAddress {
                 write!(f, "/v0/project/{}", project_id)
             }
             CredentialIssuerApiServiceAddress::AuthorityNode => write!(f, "/"),
+            CredentialIssuerApiServiceAddress::AuthorityNodeSchema => write!(f, "/schema"),
         }
     }
 }
@@ -50,6 +52,9 @@ pub struct RemoteCredentialRetrieverInfo {
     pub api_service_address: String,
     /// Request method, e.g. Post or Get
     pub request_method: Method,
+    /// Request path of the credential schema published by the issuer, e.g. "/schema",
+    /// if the issuer publishes one
+    pub schema_api_service_address: Option<String>,
 }
 
 impl RemoteCredentialRetrieverInfo {
@@ -62,6 +67,9 @@ impl RemoteCredentialRetrieverInfo {
             CredentialIssuerApiServiceAddress::AuthorityNode.to_string(),
             Method::Post,
         )
+        .with_schema_api_service_address(
+            CredentialIssuerApiServiceAddress::AuthorityNodeSchema.to_string(),
+        )
     }
 
     /// Create info for a project admin credential that we get from the Orchestrator
@@ -100,6 +108,13 @@ impl RemoteCredentialRetrieverInfo {
             service_address,
             api_service_address,
             request_method,
+            schema_api_service_address: None,
         }
     }
+
+    /// Set the request path used to retrieve the credential schema published by the issuer
+    pub fn with_schema_api_service_address(mut self, schema_api_service_address: String) -> Self {
+        self.schema_api_service_address = Some(schema_api_service_address);
+        self
+    }
 }
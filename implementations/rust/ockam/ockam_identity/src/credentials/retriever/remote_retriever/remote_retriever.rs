@@ -1,7 +1,9 @@
-use core::cmp::max;
+use core::cmp::{max, min};
+use core::sync::atomic::{AtomicU32, Ordering};
 use tracing::{debug, error, info, trace, warn};
 
 use ockam_core::api::Request;
+use ockam_core::compat::rand::{thread_rng, Rng};
 use ockam_core::compat::string::String;
 use ockam_core::compat::sync::{Arc, RwLock};
 use ockam_core::compat::time::Duration;
@@ -9,9 +11,11 @@ use ockam_core::compat::vec::Vec;
 use ockam_core::{route, Address, Result};
 use ockam_node::compat::asynchronous::Mutex;
 use ockam_node::Context;
+#[cfg(feature = "std")]
+use ockam_node::NodeEvent;
 use ockam_transport_core::Transport;
 
-use crate::models::CredentialAndPurposeKey;
+use crate::models::{CredentialAndPurposeKey, CredentialSchema};
 use crate::utils::now;
 use crate::{
     CachedCredentialRetriever, Identifier, RemoteCredentialRetrieverInfo, SecureChannels,
@@ -26,6 +30,12 @@ pub const DEFAULT_PROACTIVE_REFRESH_CREDENTIAL_TIME_GAP: TimestampInSeconds =
 /// Default minimal interval before 2 refreshed in case we retry the refresh.
 pub const DEFAULT_MIN_REFRESH_CREDENTIAL_INTERVAL: Duration = Duration::from_secs(10);
 
+/// Default maximal interval before 2 refreshes, once the retries have been backed off.
+pub const DEFAULT_MAX_REFRESH_CREDENTIAL_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Default interval before a credential expiration when we warn that it could not be refreshed.
+pub const DEFAULT_CREDENTIAL_EXPIRY_WARNING_GAP: TimestampInSeconds = TimestampInSeconds(300);
+
 /// Default timeout for requesting credential from the authority
 pub const DEFAULT_CREDENTIAL_REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
 
@@ -44,11 +54,17 @@ pub struct RemoteCredentialRetrieverTimingOptions {
     pub secure_channel_creation_timeout: Duration,
     /// Minimum interval before refresh requests to the Authority node
     pub min_refresh_interval: Duration,
+    /// Maximum interval before refresh requests to the Authority node.
+    /// The interval is doubled after each failed refresh, up to that value
+    pub max_refresh_interval: Duration,
     /// Time gap used to request a new credential before the old one actually expires
     pub proactive_refresh_gap: TimestampInSeconds,
     /// Time gap used to consider credential expired before its actual expiration
     /// to account for time errors on different machines
     pub clock_skew_gap: TimestampInSeconds,
+    /// Time gap before the expiration of a credential which could not be refreshed, when
+    /// a warning is logged and published on the node event bus
+    pub expiry_warning_gap: TimestampInSeconds,
 }
 
 impl Default for RemoteCredentialRetrieverTimingOptions {
@@ -57,12 +73,32 @@ impl Default for RemoteCredentialRetrieverTimingOptions {
             request_timeout: DEFAULT_CREDENTIAL_REQUEST_TIMEOUT,
             secure_channel_creation_timeout: DEFAULT_CREDENTIAL_SECURE_CHANNEL_CREATION_TIMEOUT,
             min_refresh_interval: DEFAULT_MIN_REFRESH_CREDENTIAL_INTERVAL,
+            max_refresh_interval: DEFAULT_MAX_REFRESH_CREDENTIAL_INTERVAL,
             proactive_refresh_gap: DEFAULT_PROACTIVE_REFRESH_CREDENTIAL_TIME_GAP,
             clock_skew_gap: DEFAULT_CREDENTIAL_CLOCK_SKEW_GAP,
+            expiry_warning_gap: DEFAULT_CREDENTIAL_EXPIRY_WARNING_GAP,
         }
     }
 }
 
+impl RemoteCredentialRetrieverTimingOptions {
+    /// Interval before retrying a refresh after a number of consecutive failures.
+    ///
+    /// The minimum refresh interval is doubled after each failure, up to the maximum
+    /// refresh interval, and reduced by a random jitter of up to a half, so that the nodes
+    /// which lost their connection to the Authority node at the same time don't retry together
+    pub(super) fn retry_interval(&self, failed_refreshes: u32) -> Duration {
+        let min_interval = self.min_refresh_interval;
+        let max_interval = max(self.max_refresh_interval, min_interval);
+
+        let factor = 2u32.saturating_pow(failed_refreshes.saturating_sub(1));
+        let backoff = min(min_interval.saturating_mul(factor), max_interval);
+
+        let jitter = thread_rng().gen_range(0..=backoff.as_millis() as u64 / 2);
+        max(min_interval, backoff - Duration::from_millis(jitter))
+    }
+}
+
 #[derive(Clone)]
 pub(super) struct LastPresentedCredential {
     pub(super) credential: CredentialAndPurposeKey,
@@ -84,6 +120,8 @@ pub struct RemoteCredentialRetriever {
     pub(super) last_presented_credential: Arc<RwLock<Option<LastPresentedCredential>>>,
     /// Subscribers addresses that we will notify when credential is refreshed
     pub(super) subscribers: Arc<RwLock<Vec<Address>>>,
+    /// Number of consecutive failed refreshes, used to back off the retries
+    failed_refreshes: Arc<AtomicU32>,
 }
 
 impl RemoteCredentialRetriever {
@@ -113,6 +151,7 @@ impl RemoteCredentialRetriever {
             is_initialized: Arc::new(Mutex::new(false)),
             last_presented_credential: Arc::new(RwLock::new(None)),
             subscribers: Default::default(),
+            failed_refreshes: Default::default(),
         }
     }
 
@@ -164,7 +203,10 @@ impl RemoteCredentialRetriever {
             );
             self.get_new_credential().await?;
         } else {
-            // We still have a valid credential - schedule refresh in the background
+            // We still have a valid credential - schedule refresh in the background.
+            // The credential schema of the issuer is not cached, retrieve it now so that the
+            // credentials presented by the other members are validated as soon as we return
+            self.refresh_credential_schema(&self.secure_client()).await;
             self.schedule_credentials_refresh_impl(refresh_in.duration, false);
         }
 
@@ -203,6 +245,7 @@ impl RemoteCredentialRetriever {
             0.into()
         } else {
             // Credential is not expired, and will need refresh later
+            has_valid_credential = true;
             last_presented_credential_expires_at
                 - now
                 - self.timing_options.clock_skew_gap
@@ -211,9 +254,13 @@ impl RemoteCredentialRetriever {
         let refresh_in = Duration::from(refresh_in);
 
         let refresh_in = if is_retry {
-            // Avoid too many request to the credential_retriever, the refresh can't be sooner than
-            // self.min_credential_refresh_interval if it's a retry
-            max(self.timing_options.min_refresh_interval, refresh_in)
+            // Avoid too many request to the credential_retriever, the retries are backed off
+            // starting from self.timing_options.min_refresh_interval
+            let failed_refreshes = self.failed_refreshes.load(Ordering::Relaxed);
+            max(
+                self.timing_options.retry_interval(failed_refreshes),
+                refresh_in,
+            )
         } else {
             refresh_in
         };
@@ -233,6 +280,42 @@ impl RemoteCredentialRetriever {
         self.schedule_credentials_refresh_impl(refresh_in.duration, is_retry);
     }
 
+    /// Warn, in the logs and on the node event bus, if the last presented credential
+    /// could not be refreshed and is about to expire, or has already expired
+    fn warn_if_expiring(&self, now: TimestampInSeconds) {
+        let Some(expires_at) = self
+            .last_presented_credential
+            .read()
+            .unwrap()
+            .as_ref()
+            .map(|c| c.expires_at)
+        else {
+            return;
+        };
+
+        if expires_at <= now {
+            error!(
+                "The credential for {} from {} has expired and could not be refreshed",
+                self.subject, self.issuer_info.issuer
+            );
+        } else if expires_at <= now + self.timing_options.expiry_warning_gap {
+            warn!(
+                "The credential for {} from {} could not be refreshed and expires in {} seconds",
+                self.subject,
+                self.issuer_info.issuer,
+                (expires_at - now).0
+            );
+        } else {
+            return;
+        }
+
+        #[cfg(feature = "std")]
+        self.ctx.publish_event(NodeEvent::CredentialExpiring {
+            subject: self.subject.to_string(),
+            expires_at: expires_at.0,
+        });
+    }
+
     async fn notify_subscribers(&self) -> Result<()> {
         let subscribers = self.subscribers.read().unwrap().clone();
         for subscriber in subscribers {
@@ -272,13 +355,8 @@ impl RemoteCredentialRetriever {
 }
 
 impl RemoteCredentialRetriever {
-    async fn get_new_credential(&self) -> Result<()> {
-        let cache = self
-            .secure_channels
-            .identities
-            .cached_credentials_repository();
-
-        let client = SecureClient::new(
+    fn secure_client(&self) -> SecureClient {
+        SecureClient::new(
             self.secure_channels.clone(),
             None,
             self.transport.clone(),
@@ -287,7 +365,16 @@ impl RemoteCredentialRetriever {
             &self.subject,
             self.timing_options.secure_channel_creation_timeout,
             self.timing_options.request_timeout,
-        );
+        )
+    }
+
+    async fn get_new_credential(&self) -> Result<()> {
+        let cache = self
+            .secure_channels
+            .identities
+            .cached_credentials_repository();
+
+        let client = self.secure_client();
 
         let credential = client
             .ask(
@@ -306,6 +393,8 @@ impl RemoteCredentialRetriever {
             self.subject, &self.issuer_info.route
         );
 
+        self.refresh_credential_schema(&client).await;
+
         let credential_and_purpose_key_data = self
             .secure_channels
             .identities()
@@ -343,6 +432,14 @@ impl RemoteCredentialRetriever {
             );
         }
 
+        self.failed_refreshes.store(0, Ordering::Relaxed);
+
+        #[cfg(feature = "std")]
+        self.ctx.publish_event(NodeEvent::CredentialRefreshed {
+            subject: self.subject.to_string(),
+            expires_at: expires_at.0,
+        });
+
         self.notify_subscribers().await?;
         let now = now()?;
 
@@ -351,6 +448,46 @@ impl RemoteCredentialRetriever {
         Ok(())
     }
 
+    /// Retrieve the credential schema published by the issuer, if any, and register it so that
+    /// the credentials presented by the other members are validated against that schema.
+    /// A failure to retrieve the schema doesn't prevent the credential from being refreshed,
+    /// and the previously registered schema is kept
+    async fn refresh_credential_schema(&self, client: &SecureClient) {
+        let Some(schema_api_service_address) = &self.issuer_info.schema_api_service_address else {
+            return;
+        };
+
+        let schema: Result<Option<CredentialSchema>> = client
+            .ask(
+                &self.ctx,
+                &self.issuer_info.service_address,
+                Request::get(schema_api_service_address.clone()),
+            )
+            .await
+            .and_then(|reply| reply.found());
+
+        match schema {
+            Ok(Some(schema)) => {
+                debug!(
+                    "Retrieved the credential schema {} from {}",
+                    schema.identifier.0, self.issuer_info.issuer
+                );
+                self.secure_channels
+                    .identities()
+                    .credential_schemas()
+                    .register(schema);
+            }
+            Ok(None) => debug!(
+                "No credential schema is published by {}",
+                self.issuer_info.issuer
+            ),
+            Err(err) => warn!(
+                "Error retrieving the credential schema from {}. Err={}",
+                self.issuer_info.issuer, err
+            ),
+        }
+    }
+
     fn request_new_credential_in_background(&self, wait: Duration, is_retry: bool) {
         let s = self.clone();
         ockam_node::spawn(async move {
@@ -371,13 +508,42 @@ impl RemoteCredentialRetriever {
             let res = s.get_new_credential().await;
 
             if let Some(err) = res.err() {
+                let failed_refreshes = s.failed_refreshes.fetch_add(1, Ordering::Relaxed) + 1;
                 error!(
-                    "Error refreshing credential for {} in the background: {}",
-                    s.subject, err
+                    "Error refreshing credential for {} in the background ({} consecutive failures): {}",
+                    s.subject, failed_refreshes, err
                 );
 
-                s.schedule_credentials_refresh(now().unwrap(), true);
+                let now = now().unwrap();
+                s.warn_if_expiring(now);
+                s.schedule_credentials_refresh(now, true);
             }
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_interval_is_backed_off() {
+        let timing_options = RemoteCredentialRetrieverTimingOptions {
+            min_refresh_interval: Duration::from_secs(10),
+            max_refresh_interval: Duration::from_secs(100),
+            ..Default::default()
+        };
+
+        assert_eq!(timing_options.retry_interval(0), Duration::from_secs(10));
+        assert_eq!(timing_options.retry_interval(1), Duration::from_secs(10));
+        for failed_refreshes in 2..100 {
+            let expected = min(
+                Duration::from_secs(10) * 2u32.saturating_pow(failed_refreshes - 1),
+                Duration::from_secs(100),
+            );
+            let interval = timing_options.retry_interval(failed_refreshes);
+            assert!(interval <= expected);
+            assert!(interval >= max(expected / 2, Duration::from_secs(10)));
+        }
+    }
+}
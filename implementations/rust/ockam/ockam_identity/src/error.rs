@@ -72,6 +72,32 @@ pub enum IdentityError {
     UnknownRole,
     /// Handshake ended up in an internal invalid state
     HandshakeInternalError,
+    /// The post-quantum key encapsulation failed during the handshake
+    KeyAgreementFailed,
+    /// The other party does not support the hybrid post-quantum key agreement
+    HybridKeyAgreementRequired,
+    /// Unknown name of a secure channel cipher suite
+    UnknownCipherSuite,
+    /// The list of cipher suites accepted for a secure channel must not be empty
+    InvalidCipherSuites,
+    /// The other party does not support any of the accepted cipher suites
+    NoCommonCipherSuite,
+    /// The size of the window of nonces accepted out of order must be between 1 and 127
+    InvalidNonceWindowSize,
+    /// The padding bucket sizes must not be empty and must be strictly positive
+    InvalidPaddingBucketSizes,
+    /// The secure channel cannot be resumed after a restart
+    SecureChannelNotResumable,
+    /// The Credential was issued to a subject which has since been revoked by its issuer
+    CredentialRevoked,
+    /// Unknown version of the RevocationList
+    UnknownRevocationListVersion,
+    /// Invalid data_type value for RevocationList
+    InvalidRevocationListDataType,
+    /// RevocationList Verification Failed
+    RevocationListVerificationFailed,
+    /// The chain of credentials of the delegated issuers of a Credential is invalid
+    InvalidCredentialDelegation,
 }
 
 impl ockam_core::compat::error::Error for IdentityError {}
@@ -12,6 +12,8 @@ use crate::identities::storage::CredentialRepository;
 use crate::identities::storage::CredentialSqlxDatabase;
 #[cfg(feature = "storage")]
 use crate::identities::storage::IdentityAttributesSqlxDatabase;
+#[cfg(feature = "storage")]
+use crate::identities::storage::RevocationListSqlxDatabase;
 use crate::identities::{ChangeHistoryRepository, IdentitiesKeys};
 use crate::models::ChangeHistory;
 use crate::purpose_keys::storage::PurposeKeysRepository;
@@ -20,8 +22,8 @@ use crate::purpose_keys::storage::PurposeKeysSqlxDatabase;
 #[cfg(feature = "storage")]
 use crate::IdentitiesBuilder;
 use crate::{
-    Credentials, Identifier, IdentitiesCreation, IdentitiesVerification, Identity,
-    IdentityAttributesRepository, PurposeKeys, Vault,
+    CredentialSchemas, Credentials, Identifier, IdentitiesCreation, IdentitiesVerification,
+    Identity, IdentityAttributesRepository, PurposeKeys, RevocationListRepository, Vault,
 };
 
 /// This struct supports all the services related to identities
@@ -32,6 +34,8 @@ pub struct Identities {
     identity_attributes_repository: Arc<dyn IdentityAttributesRepository>,
     purpose_keys_repository: Arc<dyn PurposeKeysRepository>,
     cached_credentials_repository: Arc<dyn CredentialRepository>,
+    revocation_list_repository: Arc<dyn RevocationListRepository>,
+    credential_schemas: Arc<CredentialSchemas>,
 }
 
 impl Identities {
@@ -55,6 +59,16 @@ impl Identities {
         self.cached_credentials_repository.clone()
     }
 
+    /// Return the revocation lists repository
+    pub fn revocation_list_repository(&self) -> Arc<dyn RevocationListRepository> {
+        self.revocation_list_repository.clone()
+    }
+
+    /// Return the registry of credential schemas
+    pub fn credential_schemas(&self) -> Arc<CredentialSchemas> {
+        self.credential_schemas.clone()
+    }
+
     /// Get an [`Identity`] from the repository
     pub async fn get_identity(&self, identifier: &Identifier) -> Result<Identity> {
         self.identities_verification()
@@ -124,6 +138,8 @@ impl Identities {
             self.purpose_keys(),
             self.identities_creation().clone(),
             self.identity_attributes_repository.clone(),
+            self.revocation_list_repository.clone(),
+            self.credential_schemas.clone(),
         ))
     }
 }
@@ -136,6 +152,8 @@ impl Identities {
         identity_attributes_repository: Arc<dyn IdentityAttributesRepository>,
         purpose_keys_repository: Arc<dyn PurposeKeysRepository>,
         cached_credentials_repository: Arc<dyn CredentialRepository>,
+        revocation_list_repository: Arc<dyn RevocationListRepository>,
+        credential_schemas: Arc<CredentialSchemas>,
     ) -> Identities {
         Identities {
             vault,
@@ -143,6 +161,8 @@ impl Identities {
             identity_attributes_repository,
             purpose_keys_repository,
             cached_credentials_repository,
+            revocation_list_repository,
+            credential_schemas,
         }
     }
 
@@ -172,8 +192,13 @@ impl Identities {
             )),
             purpose_keys_repository: Arc::new(PurposeKeysSqlxDatabase::new(database.clone())),
             cached_credentials_repository: Arc::new(CredentialSqlxDatabase::new(
+                database.clone(),
+                node_name,
+            )),
+            revocation_list_repository: Arc::new(RevocationListSqlxDatabase::new(
                 database, node_name,
             )),
+            credential_schemas: Arc::new(CredentialSchemas::new()),
         }
     }
 }
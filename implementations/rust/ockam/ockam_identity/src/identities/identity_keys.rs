@@ -16,7 +16,9 @@ pub struct IdentitiesKeys {
 
 impl IdentitiesKeys {
     pub(crate) async fn create_initial_key(&self, options: IdentityOptions) -> Result<Identity> {
-        let change = self.make_change(options, None).await?;
+        let change = self
+            .make_change(&self.identity_vault, options, None)
+            .await?;
         let change_history = ChangeHistory(vec![change]);
 
         let identity = Identity::import_from_change_history(
@@ -58,6 +60,7 @@ impl IdentitiesKeys {
 
         let change = self
             .make_change(
+                &self.identity_vault,
                 options,
                 Some((last_change.change_hash().clone(), last_secret_key.clone())),
             )
@@ -82,6 +85,35 @@ impl IdentitiesKeys {
         Ok(identity)
     }
 
+    /// Rotate the Identity Key to a new key stored in another vault.
+    /// The change is signed with the previous key, which is not deleted from the current vault,
+    /// so that it can still be used if the new version of the identity can't be persisted
+    pub async fn rotate_key_to_vault(
+        &self,
+        identity: Identity,
+        options: IdentityOptions,
+        new_identity_vault: Arc<dyn VaultForSigning>,
+    ) -> Result<Identity> {
+        let last_change = match identity.changes().last() {
+            Some(last_change) => last_change,
+            None => return Err(IdentityError::EmptyIdentity)?,
+        };
+
+        let last_secret_key = self.get_secret_key(&identity).await?;
+
+        let change = self
+            .make_change(
+                &new_identity_vault,
+                options,
+                Some((last_change.change_hash().clone(), last_secret_key)),
+            )
+            .await?;
+
+        identity
+            .add_change(change, self.verifying_vault.clone())
+            .await
+    }
+
     /// Return the secret key of an identity
     pub async fn get_secret_key(&self, identity: &Identity) -> Result<SigningSecretKeyHandle> {
         if let Some(last_change) = identity.changes().last() {
@@ -96,17 +128,16 @@ impl IdentitiesKeys {
 
 /// Private  functions
 impl IdentitiesKeys {
-    /// Create a new key
+    /// Create a new change for a key stored in `new_key_vault`.
+    /// The change is signed with the previous key, if any, stored in the identity vault
     async fn make_change(
         &self,
+        new_key_vault: &Arc<dyn VaultForSigning>,
         identity_options: IdentityOptions,
         previous: Option<(ChangeHash, SigningSecretKeyHandle)>,
     ) -> Result<Change> {
         let secret_key = identity_options.signing_secret_key_handle;
-        let public_key = self
-            .identity_vault
-            .get_verifying_public_key(&secret_key)
-            .await?;
+        let public_key = new_key_vault.get_verifying_public_key(&secret_key).await?;
         let (previous_change, previous_key) = previous
             .map(|(x, y)| (Some(x), Some(y)))
             .unwrap_or((None, None));
@@ -125,7 +156,7 @@ impl IdentitiesKeys {
 
         let hash = self.verifying_vault.sha256(&versioned_data).await?;
 
-        let self_signature = self.identity_vault.sign(&secret_key, &hash.0).await?;
+        let self_signature = new_key_vault.sign(&secret_key, &hash.0).await?;
         let self_signature = self_signature.into();
 
         // If we have previous_key passed we should sign using it
@@ -158,7 +189,7 @@ mod test {
     use core::str::FromStr;
     use ockam_core::errcode::{Kind, Origin};
     use ockam_core::Error;
-    use ockam_vault::SigningKeyType;
+    use ockam_vault::{SigningKeyType, SoftwareVaultForSigning};
 
     fn test_error<S: Into<String>>(error: S) -> Result<()> {
         Err(Error::new_without_cause(Origin::Identity, Kind::Unknown).context("msg", error.into()))
@@ -279,4 +310,46 @@ mod test {
             .is_ok());
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_rotate_key_to_another_vault() -> Result<()> {
+        let identities = identities().await?;
+        let identities_keys = identities.identities_keys();
+        let identity = identities
+            .get_identity(&identities.identities_creation().create_identity().await?)
+            .await?;
+        let previous_key = identities_keys.get_secret_key(&identity).await?;
+
+        let other_vault: Arc<dyn VaultForSigning> = SoftwareVaultForSigning::create().await?;
+        let new_key = other_vault
+            .generate_signing_secret_key(SigningKeyType::ECDSASHA256CurveP256)
+            .await?;
+        let now = now()?;
+        let options = IdentityOptions::new(new_key.clone(), false, now, now + 120u64);
+        let rotated = identities_keys
+            .rotate_key_to_vault(identity.clone(), options, other_vault.clone())
+            .await?;
+
+        // The identifier is unchanged and the change history can be verified
+        assert_eq!(rotated.identifier(), identity.identifier());
+        let _ = Identity::import_from_change_history(
+            Some(identity.identifier()),
+            rotated.change_history().clone(),
+            identities.vault().verifying_vault,
+        )
+        .await?;
+
+        // The new key is in the other vault and the previous key is kept
+        assert_eq!(
+            rotated.get_latest_public_key()?,
+            other_vault.get_verifying_public_key(&new_key).await?
+        );
+        assert!(identities
+            .vault()
+            .identity_vault
+            .get_verifying_public_key(&previous_key)
+            .await
+            .is_ok());
+        Ok(())
+    }
 }
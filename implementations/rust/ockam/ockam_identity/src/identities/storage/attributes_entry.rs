@@ -21,6 +21,7 @@ pub struct AttributesEntry {
     #[n(2)] added_at: TimestampInSeconds,
     #[n(3)] expires_at: Option<TimestampInSeconds>,
     #[n(4)] attested_by: Option<Identifier>,
+    #[n(5)] issued_by: Option<Identifier>,
 }
 
 fn serialize_attributes<S>(
@@ -71,6 +72,13 @@ impl Display for AttributesEntry {
                     .clone()
                     .map_or("n/a".to_string(), |e| e.to_string()),
             )
+            .field(
+                "issued_by",
+                &self
+                    .issued_by
+                    .clone()
+                    .map_or("n/a".to_string(), |e| e.to_string()),
+            )
             .finish()
     }
 }
@@ -88,9 +96,17 @@ impl AttributesEntry {
             added_at,
             expires_at,
             attested_by,
+            issued_by: None,
         }
     }
 
+    /// Set the delegated issuer of the credential containing these attributes,
+    /// when that credential was not directly issued by the attesting authority
+    pub fn with_issued_by(mut self, issued_by: Option<Identifier>) -> Self {
+        self.issued_by = issued_by;
+        self
+    }
+
     /// The entry attributes
     pub fn attrs(&self) -> &BTreeMap<Vec<u8>, Vec<u8>> {
         &self.attributes
@@ -121,6 +137,12 @@ impl AttributesEntry {
     pub fn attested_by(&self) -> Option<Identifier> {
         self.attested_by.to_owned()
     }
+
+    /// Delegated issuer which issued the credential of these attributes on behalf of
+    /// the authority returned by [`Self::attested_by`]
+    pub fn issued_by(&self) -> Option<Identifier> {
+        self.issued_by.to_owned()
+    }
 }
 
 impl AttributesEntry {
@@ -137,6 +159,7 @@ impl AttributesEntry {
             added_at: now()?,
             expires_at,
             attested_by,
+            issued_by: None,
         })
     }
 }
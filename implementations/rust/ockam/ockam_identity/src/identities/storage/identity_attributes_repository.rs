@@ -17,6 +17,11 @@ pub trait IdentityAttributesRepository: Send + Sync + 'static {
     /// Previous values gets overridden.
     async fn put_attributes(&self, subject: &Identifier, entry: AttributesEntry) -> Result<()>;
 
+    /// Remove the attributes associated with the given identity identifier
+    /// when they have been attested by the given issuer
+    async fn delete_attributes(&self, subject: &Identifier, attested_by: &Identifier)
+        -> Result<()>;
+
     /// Remove all expired attributes
     async fn delete_expired_attributes(&self, now: TimestampInSeconds) -> Result<()>;
 }
@@ -47,7 +47,7 @@ impl IdentityAttributesRepository for IdentityAttributesSqlxDatabase {
         attested_by: &Identifier,
     ) -> Result<Option<AttributesEntry>> {
         let query = query_as(
-            "SELECT identifier, attributes, added, expires, attested_by FROM identity_attributes WHERE identifier = $1 AND attested_by = $2 AND node_name = $3"
+            "SELECT identifier, attributes, added, expires, attested_by, issued_by FROM identity_attributes WHERE identifier = $1 AND attested_by = $2 AND node_name = $3"
             )
             .bind(identity)
             .bind(attested_by)
@@ -62,16 +62,31 @@ impl IdentityAttributesRepository for IdentityAttributesSqlxDatabase {
     async fn put_attributes(&self, subject: &Identifier, entry: AttributesEntry) -> Result<()> {
         let query = query(
             r#"
-            INSERT INTO identity_attributes (identifier, attributes, added, expires, attested_by, node_name)
-            VALUES ($1, $2, $3, $4, $5, $6)
+            INSERT INTO identity_attributes (identifier, attributes, added, expires, attested_by, node_name, issued_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             ON CONFLICT (identifier, node_name)
-            DO UPDATE SET attributes = $2, added = $3, expires = $4, attested_by = $5, node_name = $6"#)
+            DO UPDATE SET attributes = $2, added = $3, expires = $4, attested_by = $5, node_name = $6, issued_by = $7"#)
             .bind(subject)
             .bind(&entry)
             .bind(entry.added_at())
             .bind(entry.expires_at())
             .bind(entry.attested_by())
-            .bind(&self.node_name);
+            .bind(&self.node_name)
+            .bind(entry.issued_by());
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn delete_attributes(
+        &self,
+        subject: &Identifier,
+        attested_by: &Identifier,
+    ) -> Result<()> {
+        let query = query(
+            "DELETE FROM identity_attributes WHERE identifier = $1 AND attested_by = $2 AND node_name = $3",
+        )
+        .bind(subject)
+        .bind(attested_by)
+        .bind(&self.node_name);
         query.execute(&*self.database.pool).await.void()
     }
 
@@ -106,6 +121,7 @@ struct IdentityAttributesRow {
     added: i64,
     expires: Nullable<i64>,
     attested_by: Nullable<String>,
+    issued_by: Nullable<String>,
 }
 
 impl IdentityAttributesRow {
@@ -127,13 +143,13 @@ impl IdentityAttributesRow {
             .to_option()
             .map(|v| Identifier::from_str(&v))
             .transpose()?;
+        let issued_by = self
+            .issued_by
+            .to_option()
+            .map(|v| Identifier::from_str(&v))
+            .transpose()?;
 
-        Ok(AttributesEntry::new(
-            attributes,
-            added,
-            expires,
-            attested_by,
-        ))
+        Ok(AttributesEntry::new(attributes, added, expires, attested_by).with_issued_by(issued_by))
     }
 }
 
@@ -252,6 +268,52 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_delete_attributes() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn IdentityAttributesRepository> =
+                Arc::new(IdentityAttributesSqlxDatabase::new(db, "node"));
+
+            let now = now()?;
+            let identifier1 = create_identity().await?;
+            let attributes1 = create_attributes_entry(&identifier1, now, None).await?;
+            let identifier2 = create_identity().await?;
+            let attributes2 = create_attributes_entry(&identifier2, now, None).await?;
+
+            repository
+                .put_attributes(&identifier1, attributes1.clone())
+                .await?;
+            repository
+                .put_attributes(&identifier2, attributes2.clone())
+                .await?;
+
+            // the attributes are only deleted if they were attested by the given issuer
+            repository
+                .delete_attributes(&identifier1, &identifier2)
+                .await?;
+            let result = repository
+                .get_attributes(&identifier1, &identifier1)
+                .await?;
+            assert_eq!(result, Some(attributes1));
+
+            repository
+                .delete_attributes(&identifier1, &identifier1)
+                .await?;
+            let result = repository
+                .get_attributes(&identifier1, &identifier1)
+                .await?;
+            assert_eq!(result, None);
+
+            let result = repository
+                .get_attributes(&identifier2, &identifier2)
+                .await?;
+            assert_eq!(result, Some(attributes2));
+
+            Ok(())
+        })
+        .await
+    }
+
     /// HELPERS
     async fn create_attributes_entry(
         identifier: &Identifier,
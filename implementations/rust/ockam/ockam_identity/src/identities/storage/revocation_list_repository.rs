@@ -0,0 +1,22 @@
+use crate::models::RevocationListAndPurposeKey;
+use crate::Identifier;
+use async_trait::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::Result;
+
+/// This trait supports the persistence of the revocation lists published by credential issuers
+#[async_trait]
+pub trait RevocationListRepository: Send + Sync + 'static {
+    /// Get the latest revocation list published by an issuer
+    async fn get_revocation_list(
+        &self,
+        issuer: &Identifier,
+    ) -> Result<Option<RevocationListAndPurposeKey>>;
+
+    /// Put the revocation list of an issuer (overwriting)
+    async fn put_revocation_list(
+        &self,
+        issuer: &Identifier,
+        revocation_list: RevocationListAndPurposeKey,
+    ) -> Result<()>;
+}
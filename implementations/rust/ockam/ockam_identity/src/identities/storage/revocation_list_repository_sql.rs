@@ -0,0 +1,157 @@
+use sqlx::database::HasArguments;
+use sqlx::encode::IsNull;
+use sqlx::*;
+use tracing::debug;
+
+use ockam_core::async_trait;
+use ockam_core::Result;
+use ockam_node::database::{FromSqlxError, SqlxDatabase, ToVoid};
+
+use crate::models::{Identifier, RevocationListAndPurposeKey};
+use crate::RevocationListRepository;
+
+/// Implementation of [`RevocationListRepository`] trait based on an underlying database
+/// using sqlx as its API, and Sqlite as its driver
+#[derive(Clone)]
+pub struct RevocationListSqlxDatabase {
+    database: SqlxDatabase,
+    node_name: String,
+}
+
+impl RevocationListSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase, node_name: &str) -> Self {
+        debug!("create a repository for revocation lists");
+        Self {
+            database,
+            node_name: node_name.to_string(),
+        }
+    }
+
+    /// Create a new in-memory database
+    pub async fn create() -> Result<Self> {
+        Ok(Self::new(
+            SqlxDatabase::in_memory("revocation list").await?,
+            "default",
+        ))
+    }
+}
+
+#[async_trait]
+impl RevocationListRepository for RevocationListSqlxDatabase {
+    async fn get_revocation_list(
+        &self,
+        issuer: &Identifier,
+    ) -> Result<Option<RevocationListAndPurposeKey>> {
+        let query = query_as(
+            "SELECT revocation_list FROM revocation_list WHERE issuer_identifier = $1 AND node_name = $2",
+        )
+        .bind(issuer)
+        .bind(&self.node_name);
+        let row: Option<RevocationListRow> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        row.map(|r| r.revocation_list()).transpose()
+    }
+
+    async fn put_revocation_list(
+        &self,
+        issuer: &Identifier,
+        revocation_list: RevocationListAndPurposeKey,
+    ) -> Result<()> {
+        let created_at = revocation_list.get_revocation_list_data()?.created_at;
+        let query = query(
+            r#"INSERT INTO revocation_list (issuer_identifier, revocation_list, created_at, node_name)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (issuer_identifier, node_name)
+            DO UPDATE SET revocation_list = $2, created_at = $3"#,
+        )
+        .bind(issuer)
+        .bind(revocation_list)
+        .bind(created_at)
+        .bind(&self.node_name);
+        query.execute(&*self.database.pool).await.void()
+    }
+}
+
+// Database serialization / deserialization
+
+impl Type<Any> for RevocationListAndPurposeKey {
+    fn type_info() -> <Any as Database>::TypeInfo {
+        <Vec<u8> as Type<Any>>::type_info()
+    }
+}
+
+impl Encode<'_, Any> for RevocationListAndPurposeKey {
+    fn encode_by_ref(&self, buf: &mut <Any as HasArguments>::ArgumentBuffer) -> IsNull {
+        <Vec<u8> as Encode<'_, Any>>::encode_by_ref(&self.encode_as_cbor_bytes().unwrap(), buf)
+    }
+}
+
+// Low-level representation of a table row
+#[derive(FromRow)]
+struct RevocationListRow {
+    revocation_list: Vec<u8>,
+}
+
+impl RevocationListRow {
+    fn revocation_list(&self) -> Result<RevocationListAndPurposeKey> {
+        RevocationListAndPurposeKey::decode_from_cbor_bytes(&self.revocation_list)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ockam_core::compat::sync::Arc;
+    use ockam_node::database::with_dbs;
+
+    use super::*;
+    use crate::identities;
+    use crate::models::RevokedSubject;
+    use crate::utils::now;
+
+    #[tokio::test]
+    async fn test_revocation_list_repository() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn RevocationListRepository> =
+                Arc::new(RevocationListSqlxDatabase::new(db, "node"));
+
+            let identities = identities().await?;
+            let issuer = identities.identities_creation().create_identity().await?;
+            let subject = identities.identities_creation().create_identity().await?;
+            let credentials_creation = identities.credentials().credentials_creation();
+
+            // no revocation list has been stored yet
+            let result = repository.get_revocation_list(&issuer).await?;
+            assert_eq!(result, None);
+
+            // store and retrieve a revocation list
+            let revocation_list = credentials_creation
+                .issue_revocation_list(&issuer, vec![])
+                .await?;
+            repository
+                .put_revocation_list(&issuer, revocation_list.clone())
+                .await?;
+            let result = repository.get_revocation_list(&issuer).await?;
+            assert_eq!(result, Some(revocation_list));
+
+            // a new revocation list replaces the previous one
+            let revoked = RevokedSubject {
+                subject: subject.clone(),
+                revoked_at: now()?,
+            };
+            let revocation_list = credentials_creation
+                .issue_revocation_list(&issuer, vec![revoked])
+                .await?;
+            repository
+                .put_revocation_list(&issuer, revocation_list.clone())
+                .await?;
+            let result = repository.get_revocation_list(&issuer).await?;
+            assert_eq!(result, Some(revocation_list));
+
+            Ok(())
+        })
+        .await
+    }
+}
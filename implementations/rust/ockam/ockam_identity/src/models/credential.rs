@@ -5,6 +5,7 @@ use minicbor::{Decode, Encode};
 use ockam_core::compat::string::String;
 use ockam_core::compat::{collections::BTreeMap, vec::Vec};
 use ockam_vault::{ECDSASHA256CurveP256Signature, EdDSACurve25519Signature};
+use serde::{Deserialize, Serialize};
 
 /// `data_type` value in [`VersionedData`] struct when used with [`Credential`]
 pub const CREDENTIAL_DATA_TYPE: u8 = 3;
@@ -50,9 +51,10 @@ pub struct CredentialData {
 }
 
 /// Number that determines which keys&values to expect in the [`Attributes`]
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
 #[rustfmt::skip]
 #[cbor(transparent)]
+#[serde(transparent)]
 pub struct CredentialSchemaIdentifier(#[n(0)] pub u64);
 
 /// Set a keys&values that an Authority (issuer) attests about the Subject
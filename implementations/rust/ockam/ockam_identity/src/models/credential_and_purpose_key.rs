@@ -1,12 +1,13 @@
 use minicbor::{Decode, Encode};
 
+use ockam_core::compat::boxed::Box;
 use ockam_core::compat::string::String;
 use ockam_core::compat::vec::Vec;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Error, Result};
 
 use crate::alloc::string::ToString;
-use crate::models::{Credential, CredentialData, PurposeKeyAttestation};
+use crate::models::{ChangeHistory, Credential, CredentialData, PurposeKeyAttestation};
 use crate::TimestampInSeconds;
 
 /// [`Credential`] and the corresponding [`PurposeKeyAttestation`] that was used to issue that
@@ -19,6 +20,21 @@ pub struct CredentialAndPurposeKey {
     /// Corresponding [`PurposeKeyAttestation`] that was used to issue that
     /// [`Credential`] and will be used to verify it
     #[n(1)] pub purpose_key_attestation: PurposeKeyAttestation,
+    /// Present when the [`Credential`] was not issued by an authority but by a delegated issuer
+    #[n(2)] pub delegation: Option<Box<CredentialDelegation>>,
+}
+
+/// Authorization given by an authority (or by another delegated issuer) to a delegated issuer,
+/// to issue credentials on its behalf
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+pub struct CredentialDelegation {
+    /// [`ChangeHistory`] of the delegated issuer, so that its [`PurposeKeyAttestation`]
+    /// can be verified by parties which don't know that issuer yet
+    #[n(0)] pub issuer_change_history: ChangeHistory,
+    /// Credential of the delegated issuer, containing the
+    /// [`super::super::credentials::DELEGATED_ISSUER_ATTRIBUTE`] attribute
+    #[n(1)] pub issuer_credential: CredentialAndPurposeKey,
 }
 
 impl CredentialAndPurposeKey {
@@ -53,6 +69,18 @@ impl CredentialAndPurposeKey {
     pub fn get_expires_at(&self) -> Result<TimestampInSeconds> {
         Ok(self.get_credential_data()?.expires_at)
     }
+
+    /// Return the chain of credentials starting with this credential, followed by the
+    /// credentials of the delegated issuers, up to the credential issued by an authority
+    pub fn get_chain(&self) -> Vec<&CredentialAndPurposeKey> {
+        let mut chain = vec![self];
+        let mut current = self;
+        while let Some(delegation) = current.delegation.as_deref() {
+            chain.push(&delegation.issuer_credential);
+            current = &delegation.issuer_credential;
+        }
+        chain
+    }
 }
 
 #[cfg(test)]
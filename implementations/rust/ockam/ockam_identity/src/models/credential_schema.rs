@@ -0,0 +1,48 @@
+use minicbor::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+use ockam_core::compat::string::String;
+use ockam_core::compat::vec::Vec;
+
+use crate::models::CredentialSchemaIdentifier;
+
+/// Schema published by an Authority (issuer), describing the [`super::Attributes`]
+/// which can be attested in a [`super::Credential`] with the same [`CredentialSchemaIdentifier`]
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[rustfmt::skip]
+pub struct CredentialSchema {
+    /// [`CredentialSchemaIdentifier`] of the credentials described by this schema
+    #[n(0)] pub identifier: CredentialSchemaIdentifier,
+    /// Definitions of all the attributes which are accepted in a credential
+    #[n(1)] pub attributes: Vec<AttributeDefinition>,
+}
+
+/// Definition of an attribute in a [`CredentialSchema`]
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[rustfmt::skip]
+pub struct AttributeDefinition {
+    /// Name of the attribute
+    #[n(0)] pub name: String,
+    /// Type of the attribute value
+    #[serde(rename = "type")]
+    #[n(1)] pub attribute_type: AttributeType,
+    /// If not empty, the only values accepted for that attribute
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[n(2)] pub allowed_values: Vec<String>,
+    /// True if the attribute must be present in every credential
+    #[serde(default)]
+    #[n(3)] pub required: bool,
+}
+
+/// Type of an attribute value. Values are always encoded as UTF-8 strings
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeType {
+    /// Any UTF-8 string
+    #[n(0)] String,
+    /// A signed 64 bits integer, for example `-42`
+    #[n(1)] Integer,
+    /// `true` or `false`
+    #[n(2)] Boolean,
+}
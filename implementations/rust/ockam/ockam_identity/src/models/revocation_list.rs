@@ -0,0 +1,53 @@
+use minicbor::{Decode, Encode};
+
+use ockam_core::compat::vec::Vec;
+
+use crate::models::{CredentialSignature, Identifier, PurposeKeyAttestation, TimestampInSeconds};
+
+/// `data_type` value in [`super::VersionedData`] struct when used with [`RevocationList`]
+pub const REVOCATION_LIST_DATA_TYPE: u8 = 4;
+
+/// List of the subjects whose [`super::Credential`]s were revoked by their issuer
+/// before their expiration
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+pub struct RevocationList {
+    /// CBOR serialized [`super::VersionedData`]
+    /// where VersionedData::data is CBOR serialized [`RevocationListData`]
+    /// and VersionedData::data_type is [`REVOCATION_LIST_DATA_TYPE`]
+    #[cbor(with = "minicbor::bytes")]
+    #[n(0)] pub data: Vec<u8>,
+    /// Signature over data field using the issuer's Credentials [`PurposeKeyAttestation`]
+    #[n(1)] pub signature: CredentialSignature,
+}
+
+/// Data inside a [`RevocationList`]
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+pub struct RevocationListData {
+    /// Creation [`TimestampInSeconds`] (UTC). A more recent list replaces an older one
+    #[n(0)] pub created_at: TimestampInSeconds,
+    /// Subjects revoked by the issuer
+    #[n(1)] pub revoked: Vec<RevokedSubject>,
+}
+
+/// Subject revoked by an issuer
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+pub struct RevokedSubject {
+    /// Revoked subject
+    #[n(0)] pub subject: Identifier,
+    /// Revocation [`TimestampInSeconds`] (UTC). Credentials issued to that subject
+    /// until that moment are not valid anymore
+    #[n(1)] pub revoked_at: TimestampInSeconds,
+}
+
+/// [`RevocationList`] and the corresponding [`PurposeKeyAttestation`] that was used to sign it
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+pub struct RevocationListAndPurposeKey {
+    /// [`RevocationList`]
+    #[n(0)] pub revocation_list: RevocationList,
+    /// Corresponding [`PurposeKeyAttestation`] that was used to sign that [`RevocationList`]
+    #[n(1)] pub purpose_key_attestation: PurposeKeyAttestation,
+}
@@ -0,0 +1,163 @@
+use core::fmt::{Display, Formatter};
+use core::str;
+
+use ockam_core::compat::string::String;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+
+use crate::models::{AttributeDefinition, AttributeType, CredentialSchema};
+use crate::Attributes;
+
+impl CredentialSchema {
+    /// Return the definition of an attribute
+    pub fn get_attribute(&self, name: &str) -> Option<&AttributeDefinition> {
+        self.attributes.iter().find(|a| a.name == name)
+    }
+
+    /// Check that some [`Attributes`] are valid for this schema:
+    ///  - all the attributes must be defined in the schema
+    ///  - all the values must have the expected type and be part of the allowed values
+    ///  - all the required attributes must be present
+    pub fn validate(&self, attributes: &Attributes) -> Result<()> {
+        if attributes.schema != self.identifier {
+            return Err(invalid_attributes(format!(
+                "expected the schema {}, got {}",
+                self.identifier.0, attributes.schema.0
+            )));
+        }
+
+        for (name, value) in attributes.map.iter() {
+            let name = str::from_utf8(name)
+                .map_err(|_| invalid_attributes("an attribute name is not a UTF-8 string"))?;
+            match self.get_attribute(name) {
+                Some(definition) => definition.validate_value(value)?,
+                None => return Err(invalid_attributes(format!("unknown attribute {name}"))),
+            }
+        }
+
+        for definition in self.attributes.iter().filter(|a| a.required) {
+            if !attributes
+                .map
+                .keys()
+                .any(|name| name.as_slice() == definition.name.as_bytes())
+            {
+                return Err(invalid_attributes(format!(
+                    "missing the required attribute {}",
+                    definition.name
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl AttributeDefinition {
+    /// Check that a value is valid for this attribute
+    pub fn validate_value(&self, value: &[u8]) -> Result<()> {
+        let value = str::from_utf8(value).map_err(|_| {
+            invalid_attributes(format!("the value of {} is not a UTF-8 string", self.name))
+        })?;
+
+        let is_valid = match self.attribute_type {
+            AttributeType::String => true,
+            AttributeType::Integer => value.parse::<i64>().is_ok(),
+            AttributeType::Boolean => value == "true" || value == "false",
+        };
+        if !is_valid {
+            return Err(invalid_attributes(format!(
+                "the value of {} must be of type {}",
+                self.name, self.attribute_type
+            )));
+        }
+
+        if !self.allowed_values.is_empty() && !self.allowed_values.iter().any(|v| v == value) {
+            return Err(invalid_attributes(format!(
+                "the value of {} must be one of: {}",
+                self.name,
+                self.allowed_values.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for AttributeType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AttributeType::String => f.write_str("string"),
+            AttributeType::Integer => f.write_str("integer"),
+            AttributeType::Boolean => f.write_str("boolean"),
+        }
+    }
+}
+
+fn invalid_attributes(message: impl Into<String>) -> Error {
+    Error::new(
+        Origin::Identity,
+        Kind::Invalid,
+        format!("invalid credential attributes: {}", message.into()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CredentialSchemaIdentifier;
+    use crate::utils::AttributesBuilder;
+
+    #[test]
+    fn test_validate_attributes() {
+        let schema = CredentialSchema {
+            identifier: CredentialSchemaIdentifier(1),
+            attributes: vec![
+                AttributeDefinition {
+                    name: "role".to_string(),
+                    attribute_type: AttributeType::String,
+                    allowed_values: vec!["admin".to_string(), "member".to_string()],
+                    required: true,
+                },
+                AttributeDefinition {
+                    name: "level".to_string(),
+                    attribute_type: AttributeType::Integer,
+                    allowed_values: vec![],
+                    required: false,
+                },
+            ],
+        };
+
+        let valid = AttributesBuilder::with_schema(CredentialSchemaIdentifier(1))
+            .with_attribute("role", "admin")
+            .with_attribute("level", "3")
+            .build();
+        assert!(schema.validate(&valid).is_ok());
+
+        let missing_required = AttributesBuilder::with_schema(CredentialSchemaIdentifier(1))
+            .with_attribute("level", "3")
+            .build();
+        assert!(schema.validate(&missing_required).is_err());
+
+        let unknown = AttributesBuilder::with_schema(CredentialSchemaIdentifier(1))
+            .with_attribute("role", "admin")
+            .with_attribute("name", "alice")
+            .build();
+        assert!(schema.validate(&unknown).is_err());
+
+        let malformed = AttributesBuilder::with_schema(CredentialSchemaIdentifier(1))
+            .with_attribute("role", "admin")
+            .with_attribute("level", "high")
+            .build();
+        assert!(schema.validate(&malformed).is_err());
+
+        let not_allowed = AttributesBuilder::with_schema(CredentialSchemaIdentifier(1))
+            .with_attribute("role", "guest")
+            .build();
+        assert!(schema.validate(&not_allowed).is_err());
+
+        let other_schema = AttributesBuilder::with_schema(CredentialSchemaIdentifier(2))
+            .with_attribute("role", "admin")
+            .build();
+        assert!(schema.validate(&other_schema).is_err());
+    }
+}
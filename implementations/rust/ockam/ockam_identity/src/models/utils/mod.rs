@@ -1,5 +1,7 @@
 mod change_history;
+mod credential_schema;
 mod credentials;
 mod identifiers;
 mod purpose_key_attestation;
+mod revocation_list;
 mod timestamp;
@@ -0,0 +1,72 @@
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+
+use crate::models::{
+    RevocationList, RevocationListAndPurposeKey, RevocationListData, RevokedSubject, VersionedData,
+    REVOCATION_LIST_DATA_TYPE,
+};
+use crate::{Identifier, IdentityError, TimestampInSeconds};
+
+impl RevocationList {
+    /// Create [`VersionedData`] with corresponding version and data_type
+    pub fn create_versioned_data(data: Vec<u8>) -> VersionedData {
+        VersionedData {
+            version: 1,
+            data_type: REVOCATION_LIST_DATA_TYPE,
+            data,
+        }
+    }
+
+    /// Extract [`RevocationListData`]
+    pub fn get_revocation_list_data(&self) -> Result<RevocationListData> {
+        RevocationListData::get_data(&minicbor::decode(&self.data)?)
+    }
+}
+
+impl RevocationListData {
+    /// Extract [`RevocationListData`] from [`VersionedData`]
+    pub fn get_data(versioned_data: &VersionedData) -> Result<Self> {
+        if versioned_data.version != 1 {
+            return Err(IdentityError::UnknownRevocationListVersion)?;
+        }
+
+        if versioned_data.data_type != REVOCATION_LIST_DATA_TYPE {
+            return Err(IdentityError::InvalidRevocationListDataType)?;
+        }
+
+        Ok(minicbor::decode(&versioned_data.data)?)
+    }
+
+    /// Return the revocation of a subject if it is part of this list
+    pub fn get_revoked_subject(&self, subject: &Identifier) -> Option<&RevokedSubject> {
+        self.revoked.iter().find(|r| &r.subject == subject)
+    }
+
+    /// Return true if a credential issued to that subject at the given time is revoked
+    pub fn is_revoked(
+        &self,
+        subject: &Identifier,
+        credential_created_at: TimestampInSeconds,
+    ) -> bool {
+        self.get_revoked_subject(subject)
+            .map(|r| r.revoked_at >= credential_created_at)
+            .unwrap_or(false)
+    }
+}
+
+impl RevocationListAndPurposeKey {
+    /// Encode the revocation list as CBOR bytes
+    pub fn encode_as_cbor_bytes(&self) -> Result<Vec<u8>> {
+        Ok(minicbor::to_vec(self)?)
+    }
+
+    /// Decode the revocation list from CBOR bytes
+    pub fn decode_from_cbor_bytes(bytes: &[u8]) -> Result<RevocationListAndPurposeKey> {
+        Ok(minicbor::decode(bytes)?)
+    }
+
+    /// Return the decoded revocation list data
+    pub fn get_revocation_list_data(&self) -> Result<RevocationListData> {
+        self.revocation_list.get_revocation_list_data()
+    }
+}
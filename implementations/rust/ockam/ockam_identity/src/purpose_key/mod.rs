@@ -1,7 +1,9 @@
 mod credential;
 mod purpose;
+mod purpose_key_info;
 mod secure_channel;
 
 pub use credential::*;
 pub use purpose::*;
+pub use purpose_key_info::*;
 pub use secure_channel::*;
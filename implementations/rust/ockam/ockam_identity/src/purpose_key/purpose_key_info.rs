@@ -0,0 +1,42 @@
+use ockam_core::Result;
+
+use crate::models::{PurposeKeyAttestation, PurposePublicKey};
+use crate::{Purpose, TimestampInSeconds};
+
+/// Description of a [`super::super::purpose_key::PurposeKey`] owned by an identity
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PurposeKeyInfo {
+    /// Purpose of the key
+    pub purpose: Purpose,
+    /// Public part of the key
+    pub public_key: PurposePublicKey,
+    /// Creation date of the key
+    pub created_at: TimestampInSeconds,
+    /// Expiration date of the key
+    pub expires_at: TimestampInSeconds,
+    /// End of the grace period of the key, if it has been replaced by a new key
+    pub retired_until: Option<TimestampInSeconds>,
+}
+
+impl PurposeKeyInfo {
+    /// Create a [`PurposeKeyInfo`] from the attestation of a purpose key
+    pub fn new(
+        purpose: Purpose,
+        attestation: &PurposeKeyAttestation,
+        retired_until: Option<TimestampInSeconds>,
+    ) -> Result<Self> {
+        let data = attestation.get_attestation_data()?;
+        Ok(Self {
+            purpose,
+            public_key: data.public_key,
+            created_at: data.created_at,
+            expires_at: data.expires_at,
+            retired_until,
+        })
+    }
+
+    /// Return true if the key has been replaced by a new key
+    pub fn is_retired(&self) -> bool {
+        self.retired_until.is_some()
+    }
+}
@@ -1,4 +1,7 @@
+use core::time::Duration;
+
 use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Error, Result};
 
@@ -6,9 +9,10 @@ use crate::models::{
     Identifier, PurposeKeyAttestation, PurposeKeyAttestationData, PurposePublicKey,
 };
 use crate::purpose_keys::storage::PurposeKeysRepository;
+use crate::utils::now;
 use crate::{
     ChangeHistoryRepository, CredentialPurposeKey, CredentialPurposeKeyBuilder, IdentitiesKeys,
-    IdentitiesVerification, IdentityError, Purpose, PurposeKeyVerification,
+    IdentitiesVerification, IdentityError, Purpose, PurposeKeyInfo, PurposeKeyVerification,
     SecureChannelPurposeKey, SecureChannelPurposeKeyBuilder, TimestampInSeconds, Vault,
 };
 
@@ -311,6 +315,95 @@ impl PurposeKeyCreation {
     }
 }
 
+impl PurposeKeyCreation {
+    /// Return the active and the retired Purpose Keys of an identity
+    pub async fn list_purpose_keys(&self, identifier: &Identifier) -> Result<Vec<PurposeKeyInfo>> {
+        let mut purpose_keys = vec![];
+        for (purpose, attestation) in self.repository.get_purpose_keys(identifier).await? {
+            purpose_keys.push(PurposeKeyInfo::new(purpose, &attestation, None)?);
+        }
+        for retired in self.repository.get_retired_purpose_keys(identifier).await? {
+            purpose_keys.push(PurposeKeyInfo::new(
+                retired.purpose,
+                &retired.purpose_key_attestation,
+                Some(retired.valid_until),
+            )?);
+        }
+        Ok(purpose_keys)
+    }
+
+    /// Replace the Purpose Key of an identity with a new key.
+    /// The previous key is kept during the grace period, so that the secure channels and
+    /// credentials using it can still be used until they are renewed.
+    /// The retired keys whose grace period is over are deleted
+    pub async fn rotate_purpose_key(
+        &self,
+        identifier: &Identifier,
+        purpose: Purpose,
+        grace_period: Duration,
+    ) -> Result<PurposeKeyInfo> {
+        let previous = self.repository.get_purpose_key(identifier, purpose).await?;
+
+        let attestation = match purpose {
+            Purpose::SecureChannel => self
+                .create_secure_channel_purpose_key(identifier)
+                .await?
+                .attestation()
+                .clone(),
+            Purpose::Credentials => self
+                .create_credential_purpose_key(identifier)
+                .await?
+                .attestation()
+                .clone(),
+        };
+
+        if let Some(previous) = previous {
+            // The previous key can't be valid after its own expiration date
+            let expires_at = previous.get_attestation_data()?.expires_at;
+            let valid_until = now()? + TimestampInSeconds(grace_period.as_secs());
+            self.repository
+                .retire_purpose_key(identifier, purpose, &previous, valid_until.min(expires_at))
+                .await?;
+        }
+
+        self.delete_expired_purpose_keys(identifier).await?;
+        PurposeKeyInfo::new(purpose, &attestation, None)
+    }
+
+    /// Delete the retired Purpose Keys of an identity whose grace period is over,
+    /// together with their secret keys
+    pub async fn delete_expired_purpose_keys(&self, identifier: &Identifier) -> Result<()> {
+        let now = now()?;
+        for retired in self.repository.get_retired_purpose_keys(identifier).await? {
+            if retired.valid_until >= now {
+                continue;
+            }
+            // The secret key might have already been deleted
+            match retired
+                .purpose_key_attestation
+                .get_attestation_data()?
+                .public_key
+            {
+                PurposePublicKey::SecureChannelStatic(public_key) => {
+                    let vault = &self.vault.secure_channel_vault;
+                    if let Ok(key) = vault.get_x25519_secret_key_handle(&public_key).await {
+                        vault.delete_static_x25519_secret_key(key).await?;
+                    }
+                }
+                PurposePublicKey::CredentialSigning(public_key) => {
+                    let vault = &self.vault.credential_vault;
+                    if let Ok(key) = vault.get_secret_key_handle(&public_key.into()).await {
+                        vault.delete_signing_secret_key(key).await?;
+                    }
+                }
+            }
+        }
+        self.repository
+            .delete_retired_purpose_keys(identifier, now)
+            .await
+    }
+}
+
 impl PurposeKeyCreation {
     /// Get the [`super::super::super::purpose_key::PurposeKey`]
     /// for given [`Identifier`] and [`Purpose`]
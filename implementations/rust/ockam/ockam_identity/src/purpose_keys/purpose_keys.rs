@@ -56,8 +56,11 @@ impl PurposeKeys {
 
 #[cfg(test)]
 mod tests {
+    use core::time::Duration;
+
     use ockam_core::Result;
 
+    use crate::models::PurposePublicKey;
     use crate::{identities, Purpose};
 
     #[tokio::test]
@@ -163,4 +166,42 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_rotate_purpose_key() -> Result<()> {
+        let identities = identities().await?;
+        let identifier = identities.identities_creation().create_identity().await?;
+        let purpose_keys_creation = identities.purpose_keys().purpose_keys_creation();
+
+        let previous = purpose_keys_creation
+            .create_credential_purpose_key(&identifier)
+            .await?;
+        let rotated = purpose_keys_creation
+            .rotate_purpose_key(&identifier, Purpose::Credentials, Duration::from_secs(0))
+            .await?;
+        assert_ne!(
+            rotated.public_key,
+            PurposePublicKey::CredentialSigning(previous.public_key().clone().into())
+        );
+
+        // the previous key is listed as retired, and can still be used during the grace period
+        let keys = purpose_keys_creation.list_purpose_keys(&identifier).await?;
+        assert_eq!(keys.len(), 2);
+        assert!(keys.contains(&rotated));
+        assert_eq!(keys.iter().filter(|k| k.is_retired()).count(), 1);
+        let vault = &purpose_keys_creation.vault().credential_vault;
+        let previous_key = vault.get_secret_key_handle(previous.public_key()).await?;
+        assert!(vault.sign(&previous_key, b"data").await.is_ok());
+
+        // the previous key is deleted once the grace period is over
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        purpose_keys_creation
+            .delete_expired_purpose_keys(&identifier)
+            .await?;
+        let keys = purpose_keys_creation.list_purpose_keys(&identifier).await?;
+        assert_eq!(keys, vec![rotated]);
+        assert!(vault.sign(&previous_key, b"data").await.is_err());
+
+        Ok(())
+    }
 }
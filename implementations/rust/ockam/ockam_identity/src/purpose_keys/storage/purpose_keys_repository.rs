@@ -1,11 +1,13 @@
 use ockam_core::async_trait;
 use ockam_core::compat::boxed::Box;
+use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
 
 use crate::models::{Identifier, PurposeKeyAttestation};
-use crate::Purpose;
+use crate::{Purpose, TimestampInSeconds};
 
-// TODO: Only one PurposeKey per Purpose per Identity is supported for now
+// TODO: Only one active PurposeKey per Purpose per Identity is supported for now.
+//       Rotated keys are kept as retired keys until the end of their grace period
 
 /// This repository stores [`super::super::super::purpose_key::PurposeKey`]s
 #[async_trait]
@@ -31,6 +33,50 @@ pub trait PurposeKeysRepository: Send + Sync + 'static {
         purpose: Purpose,
     ) -> Result<Option<PurposeKeyAttestation>>;
 
+    /// Retrieve all the active [`super::super::super::purpose_key::PurposeKey`]s
+    /// for given [`Identifier`]
+    async fn get_purpose_keys(
+        &self,
+        identifier: &Identifier,
+    ) -> Result<Vec<(Purpose, PurposeKeyAttestation)>>;
+
+    /// Store a [`super::super::super::purpose_key::PurposeKey`] which has been replaced
+    /// by a new key, but which is still valid until the end of its grace period
+    async fn retire_purpose_key(
+        &self,
+        subject: &Identifier,
+        purpose: Purpose,
+        purpose_key_attestation: &PurposeKeyAttestation,
+        valid_until: TimestampInSeconds,
+    ) -> Result<()>;
+
+    /// Retrieve the retired [`super::super::super::purpose_key::PurposeKey`]s
+    /// for given [`Identifier`]
+    async fn get_retired_purpose_keys(
+        &self,
+        identifier: &Identifier,
+    ) -> Result<Vec<RetiredPurposeKey>>;
+
+    /// Delete the retired [`super::super::super::purpose_key::PurposeKey`]s
+    /// for given [`Identifier`] which are not valid anymore at the given time
+    async fn delete_retired_purpose_keys(
+        &self,
+        identifier: &Identifier,
+        now: TimestampInSeconds,
+    ) -> Result<()>;
+
     /// Delete all keys
     async fn delete_all(&self) -> Result<()>;
 }
+
+/// [`super::super::super::purpose_key::PurposeKey`] which has been rotated
+/// and is only kept during its grace period
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetiredPurposeKey {
+    /// Purpose of the key
+    pub purpose: Purpose,
+    /// Attestation of the key
+    pub purpose_key_attestation: PurposeKeyAttestation,
+    /// End of the grace period for that key
+    pub valid_until: TimestampInSeconds,
+}
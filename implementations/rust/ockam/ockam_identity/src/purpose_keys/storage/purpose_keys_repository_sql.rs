@@ -14,8 +14,8 @@ use ockam_node::database::{FromSqlxError, SqlxDatabase, ToVoid};
 
 use crate::identity::IdentityConstants;
 use crate::models::{Identifier, PurposeKeyAttestation};
-use crate::purpose_keys::storage::PurposeKeysRepository;
-use crate::Purpose;
+use crate::purpose_keys::storage::{PurposeKeysRepository, RetiredPurposeKey};
+use crate::{Purpose, TimestampInSeconds};
 
 /// Storage for own [`super::super::super::purpose_key::PurposeKey`]s
 #[derive(Clone)]
@@ -79,8 +79,66 @@ impl PurposeKeysRepository for PurposeKeysSqlxDatabase {
         Ok(row.map(|r| r.purpose_key_attestation()).transpose()?)
     }
 
+    async fn get_purpose_keys(
+        &self,
+        identifier: &Identifier,
+    ) -> Result<Vec<(Purpose, PurposeKeyAttestation)>> {
+        let query = query_as("SELECT identifier, purpose, purpose_key_attestation FROM purpose_key WHERE identifier = $1")
+            .bind(identifier);
+        let rows: Vec<PurposeKeyRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
+        rows.iter()
+            .map(|r| Ok((r.purpose()?, r.purpose_key_attestation()?)))
+            .collect()
+    }
+
+    async fn retire_purpose_key(
+        &self,
+        subject: &Identifier,
+        purpose: Purpose,
+        purpose_key_attestation: &PurposeKeyAttestation,
+        valid_until: TimestampInSeconds,
+    ) -> Result<()> {
+        let query = query(
+            r#"
+            INSERT INTO retired_purpose_key (identifier, purpose, purpose_key_attestation, valid_until)
+            VALUES ($1, $2, $3, $4)"#,
+        )
+        .bind(subject)
+        .bind(purpose)
+        .bind(purpose_key_attestation)
+        .bind(valid_until);
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn get_retired_purpose_keys(
+        &self,
+        identifier: &Identifier,
+    ) -> Result<Vec<RetiredPurposeKey>> {
+        let query = query_as("SELECT identifier, purpose, purpose_key_attestation, valid_until FROM retired_purpose_key WHERE identifier = $1")
+            .bind(identifier);
+        let rows: Vec<RetiredPurposeKeyRow> =
+            query.fetch_all(&*self.database.pool).await.into_core()?;
+        rows.iter().map(|r| r.retired_purpose_key()).collect()
+    }
+
+    async fn delete_retired_purpose_keys(
+        &self,
+        identifier: &Identifier,
+        now: TimestampInSeconds,
+    ) -> Result<()> {
+        let query =
+            query("DELETE FROM retired_purpose_key WHERE identifier = $1 and valid_until < $2")
+                .bind(identifier)
+                .bind(now);
+        query.execute(&*self.database.pool).await.void()
+    }
+
     async fn delete_all(&self) -> Result<()> {
         query("DELETE FROM purpose_key")
+            .execute(&*self.database.pool)
+            .await
+            .void()?;
+        query("DELETE FROM retired_purpose_key")
             .execute(&*self.database.pool)
             .await
             .void()
@@ -133,17 +191,8 @@ impl PurposeKeyRow {
         Identifier::from_str(&self.identifier)
     }
 
-    #[allow(dead_code)]
     pub(crate) fn purpose(&self) -> Result<Purpose> {
-        match self.purpose.as_str() {
-            IdentityConstants::SECURE_CHANNEL_PURPOSE_KEY => Ok(Purpose::SecureChannel),
-            IdentityConstants::CREDENTIALS_PURPOSE_KEY => Ok(Purpose::Credentials),
-            _ => Err(ockam_core::Error::new(
-                Origin::Api,
-                Kind::Serialization,
-                format!("unknown purpose {}", self.purpose),
-            )),
-        }
+        decode_purpose(&self.purpose)
     }
 
     pub(crate) fn purpose_key_attestation(&self) -> Result<PurposeKeyAttestation> {
@@ -151,6 +200,41 @@ impl PurposeKeyRow {
     }
 }
 
+#[derive(FromRow)]
+pub(crate) struct RetiredPurposeKeyRow {
+    // The identifier who was using this key
+    #[allow(dead_code)]
+    identifier: String,
+    // Purpose of the key (signing, encrypting, etc...)
+    purpose: String,
+    // Attestation that this key is valid
+    purpose_key_attestation: Vec<u8>,
+    // End of the grace period for this key
+    valid_until: i64,
+}
+
+impl RetiredPurposeKeyRow {
+    pub(crate) fn retired_purpose_key(&self) -> Result<RetiredPurposeKey> {
+        Ok(RetiredPurposeKey {
+            purpose: decode_purpose(&self.purpose)?,
+            purpose_key_attestation: minicbor::decode(self.purpose_key_attestation.as_slice())?,
+            valid_until: TimestampInSeconds(self.valid_until as u64),
+        })
+    }
+}
+
+fn decode_purpose(purpose: &str) -> Result<Purpose> {
+    match purpose {
+        IdentityConstants::SECURE_CHANNEL_PURPOSE_KEY => Ok(Purpose::SecureChannel),
+        IdentityConstants::CREDENTIALS_PURPOSE_KEY => Ok(Purpose::Credentials),
+        _ => Err(ockam_core::Error::new(
+            Origin::Api,
+            Kind::Serialization,
+            format!("unknown purpose {}", purpose),
+        )),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -178,7 +262,7 @@ mod tests {
         let result = repository
             .get_purpose_key(&identity1, Purpose::Credentials)
             .await?;
-        assert_eq!(result, Some(attestation1));
+        assert_eq!(result, Some(attestation1.clone()));
 
         // the attestation can be updated
         let attestation2 = PurposeKeyAttestation {
@@ -194,7 +278,45 @@ mod tests {
         let result = repository
             .get_purpose_key(&identity1, Purpose::Credentials)
             .await?;
-        assert_eq!(result, Some(attestation2));
+        assert_eq!(result, Some(attestation2.clone()));
+
+        // all the keys of an identity can be listed
+        let result = repository.get_purpose_keys(&identity1).await?;
+        assert_eq!(result, vec![(Purpose::Credentials, attestation2.clone())]);
+
+        // a retired key is kept until the end of its grace period
+        repository
+            .retire_purpose_key(
+                &identity1,
+                Purpose::Credentials,
+                &attestation1,
+                TimestampInSeconds(100),
+            )
+            .await?;
+        let result = repository.get_retired_purpose_keys(&identity1).await?;
+        assert_eq!(
+            result,
+            vec![RetiredPurposeKey {
+                purpose: Purpose::Credentials,
+                purpose_key_attestation: attestation1,
+                valid_until: TimestampInSeconds(100),
+            }]
+        );
+
+        repository
+            .delete_retired_purpose_keys(&identity1, TimestampInSeconds(100))
+            .await?;
+        assert_eq!(
+            repository.get_retired_purpose_keys(&identity1).await?.len(),
+            1
+        );
+        repository
+            .delete_retired_purpose_keys(&identity1, TimestampInSeconds(101))
+            .await?;
+        assert!(repository
+            .get_retired_purpose_keys(&identity1)
+            .await?
+            .is_empty());
 
         Ok(())
     }
@@ -1,8 +1,28 @@
 use ockam_core::compat::vec::Vec;
-use ockam_core::Error;
 use ockam_core::Message;
+use ockam_core::{route, Address, Error, Result};
+use ockam_node::Context;
 use serde::{Deserialize, Serialize};
 
+use crate::{SecureChannel, SecureChannelRegistryEntry};
+
+/// Version of the encryption and decryption API of the secure channels.
+///
+/// This API lets a worker encrypt a payload with the keys of an established secure channel
+/// without sending it through the channel, transport it with any other protocol, then decrypt
+/// it on the other end of the channel:
+///
+///  - an [`EncryptionRequest`] sent to the encryptor API address of a channel is answered with
+///    an [`EncryptionResponse`] containing the encrypted payload
+///  - a [`DecryptionRequest`] sent to the decryptor API address of the other end of the channel
+///    is answered with a [`DecryptionResponse`] containing the decrypted payload
+///
+/// The encoding of these messages and the format of the encrypted payloads only change along
+/// with this version. The access to the API addresses can be restricted with
+/// [`crate::SecureChannelOptions::with_api_access_control`] and
+/// [`crate::SecureChannelListenerOptions::with_api_access_control`].
+pub const SECURE_CHANNEL_API_VERSION: u8 = 1;
+
 /// Request type for `EncryptorWorker` API Address
 #[derive(Serialize, Deserialize, Message)]
 pub struct EncryptionRequest(pub Vec<u8>);
@@ -28,3 +48,70 @@ pub enum DecryptionResponse {
     /// Error
     Err(Error),
 }
+
+/// Client for the encryption and decryption API of an established secure channel,
+/// see [`SECURE_CHANNEL_API_VERSION`]
+#[derive(Clone, Debug)]
+pub struct SecureChannelApiClient {
+    encryptor_api_address: Address,
+    decryptor_api_address: Address,
+}
+
+impl SecureChannelApiClient {
+    /// Create a client for the API addresses of one end of a secure channel
+    pub fn new(encryptor_api_address: Address, decryptor_api_address: Address) -> Self {
+        Self {
+            encryptor_api_address,
+            decryptor_api_address,
+        }
+    }
+
+    /// Encrypt a payload with the current key of the channel.
+    /// The encrypted payload can be decrypted by the decryptor API of the other end of the channel
+    pub async fn encrypt(&self, ctx: &Context, payload: Vec<u8>) -> Result<Vec<u8>> {
+        let response: EncryptionResponse = ctx
+            .send_and_receive(
+                route![self.encryptor_api_address.clone()],
+                EncryptionRequest(payload),
+            )
+            .await?;
+
+        match response {
+            EncryptionResponse::Ok(encrypted) => Ok(encrypted),
+            EncryptionResponse::Err(err) => Err(err),
+        }
+    }
+
+    /// Decrypt a payload encrypted by the encryptor API of the other end of the channel
+    pub async fn decrypt(&self, ctx: &Context, encrypted: Vec<u8>) -> Result<Vec<u8>> {
+        let response: DecryptionResponse = ctx
+            .send_and_receive(
+                route![self.decryptor_api_address.clone()],
+                DecryptionRequest(encrypted),
+            )
+            .await?;
+
+        match response {
+            DecryptionResponse::Ok(payload) => Ok(payload),
+            DecryptionResponse::Err(err) => Err(err),
+        }
+    }
+}
+
+impl From<&SecureChannel> for SecureChannelApiClient {
+    fn from(secure_channel: &SecureChannel) -> Self {
+        Self::new(
+            secure_channel.encryptor_api_address().clone(),
+            secure_channel.decryptor_api_address().clone(),
+        )
+    }
+}
+
+impl From<&SecureChannelRegistryEntry> for SecureChannelApiClient {
+    fn from(entry: &SecureChannelRegistryEntry) -> Self {
+        Self::new(
+            entry.encryptor_api_address().clone(),
+            entry.decryptor_api_address().clone(),
+        )
+    }
+}
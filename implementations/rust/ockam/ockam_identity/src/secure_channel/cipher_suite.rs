@@ -0,0 +1,54 @@
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+use minicbor::{Decode, Encode};
+use ockam_core::Error;
+use ockam_vault::AeadAlgorithm;
+
+use crate::IdentityError;
+
+/// AEAD cipher suites which can be negotiated during a secure channel handshake.
+///
+/// The handshake messages themselves are always encrypted with AES-256-GCM, the negotiated
+/// cipher suite is used to encrypt the messages exchanged once the secure channel is established.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Encode, Decode)]
+#[cbor(index_only)]
+#[rustfmt::skip]
+pub enum CipherSuite {
+    /// AES-256-GCM, which is the fastest on devices with AES hardware support.
+    /// This is the only cipher suite supported by the previous versions of the protocol
+    #[default]
+    #[n(0)] Aes256Gcm,
+    /// ChaCha20-Poly1305, which is faster on constrained devices without AES hardware support
+    #[n(1)] ChaCha20Poly1305,
+}
+
+impl CipherSuite {
+    /// Return the algorithm used by the vault to encrypt and decrypt messages
+    pub(crate) fn aead_algorithm(&self) -> AeadAlgorithm {
+        match self {
+            CipherSuite::Aes256Gcm => AeadAlgorithm::AesGcm,
+            CipherSuite::ChaCha20Poly1305 => AeadAlgorithm::ChaCha20Poly1305,
+        }
+    }
+}
+
+impl Display for CipherSuite {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CipherSuite::Aes256Gcm => write!(f, "aes256-gcm"),
+            CipherSuite::ChaCha20Poly1305 => write!(f, "chacha20-poly1305"),
+        }
+    }
+}
+
+impl FromStr for CipherSuite {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "aes256-gcm" => Ok(CipherSuite::Aes256Gcm),
+            "chacha20-poly1305" => Ok(CipherSuite::ChaCha20Poly1305),
+            _ => Err(IdentityError::UnknownCipherSuite)?,
+        }
+    }
+}
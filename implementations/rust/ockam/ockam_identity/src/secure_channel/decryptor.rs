@@ -1,4 +1,5 @@
 use core::sync::atomic::Ordering;
+use ockam_core::compat::bytes::Bytes;
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::{route, Any, Result, Route, Routed};
@@ -6,10 +7,11 @@ use ockam_core::{Decodable, LocalMessage};
 use ockam_node::Context;
 
 use crate::models::Identifier;
-use crate::secure_channel::encryptor::{Encryptor, KEY_RENEWAL_INTERVAL};
+use crate::secure_channel::encryptor::{Encryptor, KeyRotation, KEY_RENEWAL_INTERVAL};
 use crate::secure_channel::handshake::handshake_state_machine::CommonStateMachine;
 use crate::secure_channel::key_tracker::KeyTracker;
-use crate::secure_channel::nonce_tracker::NonceTracker;
+use crate::secure_channel::nonce_tracker::{NonceTracker, DEFAULT_NONCE_WINDOW_SIZE};
+use crate::secure_channel::resumption::ChannelResumption;
 use crate::secure_channel::{Addresses, Role};
 use crate::{
     DecryptionRequest, DecryptionResponse, Identities, IdentityError,
@@ -33,6 +35,7 @@ pub(crate) struct DecryptorHandler {
     identities: Arc<Identities>,
     authority: Option<Identifier>,
     shared_state: SecureChannelSharedState,
+    resumption: Option<ChannelResumption>,
 }
 
 impl DecryptorHandler {
@@ -42,6 +45,7 @@ impl DecryptorHandler {
         authority: Option<Identifier>,
         role: Role,
         key_exchange_only: bool,
+        nonce_window_size: u64,
         addresses: Addresses,
         key: AeadSecretKeyHandle,
         vault: Arc<dyn VaultForSecureChannels>,
@@ -51,7 +55,7 @@ impl DecryptorHandler {
         let decryptor = if key_exchange_only {
             Decryptor::new_naive(key, vault)
         } else {
-            Decryptor::new(key, vault)
+            Decryptor::new(key, vault, nonce_window_size)
         };
 
         Self {
@@ -62,9 +66,25 @@ impl DecryptorHandler {
             identities,
             authority,
             shared_state,
+            resumption: None,
         }
     }
 
+    /// Persist the decryption keys when they are rotated, so that the channel can be resumed
+    pub(crate) fn with_resumption(mut self, resumption: Option<ChannelResumption>) -> Self {
+        self.resumption = resumption;
+        self
+    }
+
+    /// Persist the new decryption key if it was just rotated
+    async fn persist_key_rotation(&mut self) -> Result<()> {
+        let key_rotation = self.decryptor.take_key_rotation();
+        if let (Some(resumption), Some(key_rotation)) = (&self.resumption, key_rotation) {
+            resumption.persist_decryption_key(&key_rotation).await?;
+        }
+        Ok(())
+    }
+
     #[instrument(skip_all)]
     pub(crate) async fn handle_decrypt_api(
         &mut self,
@@ -84,6 +104,7 @@ impl DecryptorHandler {
 
         // Decrypt the binary
         let decrypted_payload = self.decryptor.decrypt(&request.0).await;
+        self.persist_key_rotation().await?;
 
         let response = match decrypted_payload {
             Ok((payload, _nonce)) => DecryptionResponse::Ok(payload),
@@ -101,6 +122,7 @@ impl DecryptorHandler {
         &mut self,
         ctx: &mut Context,
         mut msg: PlaintextPayloadMessage<'_>,
+        payload: Bytes,
         nonce: Nonce,
         encrypted_msg_return_route: Route,
     ) -> Result<()> {
@@ -127,7 +149,7 @@ impl DecryptorHandler {
         let msg = LocalMessage::new()
             .with_onward_route(msg.onward_route)
             .with_return_route(msg.return_route)
-            .with_payload(msg.payload.to_vec())
+            .with_payload(payload)
             .with_local_info(local_info);
 
         match ctx
@@ -198,19 +220,28 @@ impl DecryptorHandler {
         let encrypted_msg_return_route = msg.return_route();
 
         // Decode raw payload binary
-        let payload = msg.into_payload();
-        let payload =
-            ockam_core::bare::read_slice(payload.as_slice(), &mut 0).ok_or_else(|| {
-                ockam_core::Error::new(Origin::Transport, Kind::Protocol, "Invalid message")
-            })?;
+        let payload = ockam_core::bare::read_slice(msg.payload(), &mut 0).ok_or_else(|| {
+            ockam_core::Error::new(Origin::Transport, Kind::Protocol, "Invalid message")
+        })?;
 
         // Decrypt the binary
         let (decrypted_payload, nonce) = self.decryptor.decrypt(payload).await?;
+        self.persist_key_rotation().await?;
+        self.shared_state.activity.record();
+        // The decrypted buffer is shared with the payload forwarded to the next worker
+        let decrypted_payload = Bytes::from(decrypted_payload);
         let decrypted_msg: SecureChannelMessage = minicbor::decode(&decrypted_payload)?;
         match decrypted_msg {
             SecureChannelMessage::Payload(decrypted_msg) => {
-                self.handle_payload(ctx, decrypted_msg, nonce, encrypted_msg_return_route)
-                    .await?
+                let payload = decrypted_payload.slice_ref(decrypted_msg.payload);
+                self.handle_payload(
+                    ctx,
+                    decrypted_msg,
+                    payload,
+                    nonce,
+                    encrypted_msg_return_route,
+                )
+                .await?
             }
             SecureChannelMessage::RefreshCredentials(decrypted_msg) => {
                 self.handle_refresh_credentials(ctx, decrypted_msg).await?
@@ -231,14 +262,50 @@ pub(crate) struct Decryptor {
     vault: Arc<dyn VaultForSecureChannels>,
     key_tracker: KeyTracker,
     nonce_tracker: Option<NonceTracker>,
+    /// Last key rotation, not yet persisted
+    key_rotation: Option<KeyRotation>,
 }
 
 impl Decryptor {
-    pub fn new(key: AeadSecretKeyHandle, vault: Arc<dyn VaultForSecureChannels>) -> Self {
+    pub fn new(
+        key: AeadSecretKeyHandle,
+        vault: Arc<dyn VaultForSecureChannels>,
+        nonce_window_size: u64,
+    ) -> Self {
         Self {
             vault,
-            key_tracker: KeyTracker::new(key, KEY_RENEWAL_INTERVAL),
-            nonce_tracker: Some(NonceTracker::new()),
+            key_tracker: KeyTracker::new(key, KEY_RENEWAL_INTERVAL, nonce_window_size),
+            nonce_tracker: Some(NonceTracker::new(nonce_window_size)),
+            key_rotation: None,
+        }
+    }
+
+    /// Create a decryptor for a resumed secure channel, with the last persisted key.
+    ///
+    /// The messages using that key might have been decrypted before the channel was stopped,
+    /// so they are all rejected to prevent them from being replayed.
+    /// The decryption resumes with the messages using the next key.
+    ///
+    /// NOTE: if the other party was not stopped, it keeps using that key until its next key
+    /// rotation. The messages it sends until then are rejected too, and they are lost
+    pub fn resume(
+        key: AeadSecretKeyHandle,
+        first_nonce: Nonce,
+        vault: Arc<dyn VaultForSecureChannels>,
+        nonce_window_size: u64,
+    ) -> Self {
+        let number_of_rekeys = first_nonce.value() / KEY_RENEWAL_INTERVAL;
+        let last_nonce = first_nonce.value().saturating_add(KEY_RENEWAL_INTERVAL - 1);
+        Self {
+            vault,
+            key_tracker: KeyTracker::resume(
+                key,
+                KEY_RENEWAL_INTERVAL,
+                nonce_window_size,
+                number_of_rekeys,
+            ),
+            nonce_tracker: Some(NonceTracker::resume(nonce_window_size, last_nonce.into())),
+            key_rotation: None,
         }
     }
 
@@ -246,8 +313,9 @@ impl Decryptor {
     pub fn new_naive(key: AeadSecretKeyHandle, vault: Arc<dyn VaultForSecureChannels>) -> Self {
         Self {
             vault,
-            key_tracker: KeyTracker::new(key, KEY_RENEWAL_INTERVAL),
+            key_tracker: KeyTracker::new(key, KEY_RENEWAL_INTERVAL, DEFAULT_NONCE_WINDOW_SIZE),
             nonce_tracker: None,
+            key_rotation: None,
         }
     }
 
@@ -281,28 +349,44 @@ impl Decryptor {
         // message with a decryption _before_ committing to the new state
         let result = self
             .vault
-            .aead_decrypt(&key, &payload[8..], &nonce.to_aes_gcm_nonce(), &[])
+            .aead_decrypt(&key, &payload[8..], &nonce.to_aead_nonce(), &[])
             .await;
 
         if result.is_ok() {
             self.nonce_tracker = nonce_tracker;
+            let previous_key = self.key_tracker.current_key.clone();
             if let Some(key_to_delete) = self.key_tracker.update_key(key)? {
                 self.vault.delete_aead_secret_key(key_to_delete).await?;
             }
+            if self.key_tracker.current_key != previous_key {
+                self.key_rotation = Some(KeyRotation {
+                    previous_key,
+                    key: self.key_tracker.current_key.clone(),
+                    first_nonce: (nonce.value() / KEY_RENEWAL_INTERVAL * KEY_RENEWAL_INTERVAL)
+                        .into(),
+                });
+            }
         }
 
         result.map(|payload| (payload, nonce))
     }
 
+    /// Return the last key rotation if it has not been returned yet
+    pub(crate) fn take_key_rotation(&mut self) -> Option<KeyRotation> {
+        self.key_rotation.take()
+    }
+
     /// Remove the channel keys on shutdown
     #[instrument(skip_all)]
     pub(crate) async fn shutdown(&self) -> Result<()> {
         self.vault
             .delete_aead_secret_key(self.key_tracker.current_key.clone())
             .await?;
-        if let Some(previous_key) = self.key_tracker.previous_key.clone() {
-            self.vault.delete_aead_secret_key(previous_key).await?;
-        };
+        for previous_key in self.key_tracker.previous_keys.iter() {
+            self.vault
+                .delete_aead_secret_key(previous_key.clone())
+                .await?;
+        }
         Ok(())
     }
 }
@@ -0,0 +1,239 @@
+use core::fmt::{Display, Formatter};
+use core::time::Duration;
+use ockam_core::compat::collections::{BTreeMap, VecDeque};
+use ockam_core::compat::string::String;
+use ockam_core::compat::sync::{Arc, RwLock};
+
+use crate::models::TimestampInSeconds;
+use crate::secure_channel::Role;
+use crate::utils::now;
+
+/// Maximum number of failures kept to diagnose the last failed handshakes
+pub const MAX_RECENT_HANDSHAKE_FAILURES: usize = 20;
+
+/// Reason of a secure channel handshake failure
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HandshakeFailureReason {
+    /// The identity or the purpose key of the other party could not be verified
+    IdentityVerificationFailed,
+    /// A credential presented by the other party could not be verified.
+    /// The credential is ignored but the channel is still established
+    CredentialRejected,
+    /// The trust policy did not accept the identity of the other party
+    TrustPolicyDenied,
+    /// The handshake was not completed before the timeout
+    Timeout,
+    /// Any other failure: invalid handshake message, local error, ...
+    Other,
+}
+
+impl HandshakeFailureReason {
+    /// Name of the failure reason
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::IdentityVerificationFailed => "identity_verification_failed",
+            Self::CredentialRejected => "credential_rejected",
+            Self::TrustPolicyDenied => "trust_policy_denied",
+            Self::Timeout => "timeout",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl Display for HandshakeFailureReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Failure which occurred during a secure channel handshake
+#[derive(Clone, Debug)]
+pub struct HandshakeFailure {
+    /// True if we were initiating the channel
+    pub is_initiator: bool,
+    /// Reason of the failure
+    pub reason: HandshakeFailureReason,
+    /// Description of the error
+    pub message: String,
+    /// Time of the failure
+    pub failed_at: TimestampInSeconds,
+}
+
+/// Statistics about the secure channel handshakes of a node
+#[derive(Clone, Debug, Default)]
+pub struct HandshakeStatistics {
+    /// Number of started handshakes
+    pub attempts: u64,
+    /// Number of completed handshakes
+    pub successes: u64,
+    /// Number of failures per reason.
+    /// A rejected credential does not stop a handshake, so it can be counted along a success
+    pub failures: BTreeMap<HandshakeFailureReason, u64>,
+    /// Total duration of the completed handshakes
+    pub total_duration: Duration,
+    /// Longest duration of a completed handshake
+    pub max_duration: Duration,
+    /// Last failures, the most recent one first
+    pub recent_failures: VecDeque<HandshakeFailure>,
+}
+
+impl HandshakeStatistics {
+    /// Average duration of the completed handshakes
+    pub fn average_duration(&self) -> Option<Duration> {
+        let successes = u32::try_from(self.successes).ok()?;
+        if successes == 0 {
+            return None;
+        }
+        Some(self.total_duration / successes)
+    }
+
+    /// Total number of failures
+    pub fn failures_count(&self) -> u64 {
+        self.failures.values().sum()
+    }
+}
+
+/// Attempts, durations and failures of the secure channel handshakes.
+/// It is shared by all the channels of a node, along with the [`crate::SecureChannelRegistry`]
+#[derive(Clone, Debug, Default)]
+pub struct HandshakeDiagnostics {
+    statistics: Arc<RwLock<HandshakeStatistics>>,
+}
+
+impl HandshakeDiagnostics {
+    /// Create empty diagnostics
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return a copy of the current statistics
+    pub fn statistics(&self) -> HandshakeStatistics {
+        self.statistics.read().unwrap().clone()
+    }
+
+    /// Record the start of a handshake
+    pub(crate) fn record_attempt(&self) {
+        self.statistics.write().unwrap().attempts += 1;
+    }
+
+    /// Record a completed handshake and its duration, when it could be measured
+    pub(crate) fn record_success(&self, duration: Option<Duration>) {
+        let mut statistics = self.statistics.write().unwrap();
+        statistics.successes += 1;
+        if let Some(duration) = duration {
+            statistics.total_duration += duration;
+            statistics.max_duration = statistics.max_duration.max(duration);
+        }
+    }
+
+    /// Record a failure, and keep its description for the last failures
+    pub(crate) fn record_failure(
+        &self,
+        role: Role,
+        reason: HandshakeFailureReason,
+        message: String,
+    ) {
+        let failure = HandshakeFailure {
+            is_initiator: role.is_initiator(),
+            reason,
+            message,
+            failed_at: now().unwrap_or(TimestampInSeconds(0)),
+        };
+
+        let mut statistics = self.statistics.write().unwrap();
+        *statistics.failures.entry(reason).or_default() += 1;
+        statistics.recent_failures.push_front(failure);
+        statistics
+            .recent_failures
+            .truncate(MAX_RECENT_HANDSHAKE_FAILURES);
+    }
+}
+
+/// Measure the duration of a handshake.
+/// The duration is only available on platforms providing a monotonic clock
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct HandshakeTimer {
+    #[cfg(feature = "std")]
+    started_at: std::time::Instant,
+}
+
+impl HandshakeTimer {
+    pub(crate) fn start() -> Self {
+        Self {
+            #[cfg(feature = "std")]
+            started_at: std::time::Instant::now(),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn elapsed(&self) -> Option<Duration> {
+        Some(self.started_at.elapsed())
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub(crate) fn elapsed(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::compat::string::ToString;
+
+    #[test]
+    fn test_handshake_statistics() {
+        let diagnostics = HandshakeDiagnostics::new();
+        diagnostics.record_attempt();
+        diagnostics.record_attempt();
+        diagnostics.record_attempt();
+        diagnostics.record_success(Some(Duration::from_millis(10)));
+        diagnostics.record_success(Some(Duration::from_millis(30)));
+        diagnostics.record_failure(
+            Role::Responder,
+            HandshakeFailureReason::TrustPolicyDenied,
+            "denied".to_string(),
+        );
+
+        let statistics = diagnostics.statistics();
+        assert_eq!(statistics.attempts, 3);
+        assert_eq!(statistics.successes, 2);
+        assert_eq!(statistics.failures_count(), 1);
+        assert_eq!(
+            statistics.average_duration(),
+            Some(Duration::from_millis(20))
+        );
+        assert_eq!(statistics.max_duration, Duration::from_millis(30));
+        assert_eq!(
+            statistics.recent_failures[0].reason,
+            HandshakeFailureReason::TrustPolicyDenied
+        );
+        assert!(!statistics.recent_failures[0].is_initiator);
+    }
+
+    #[test]
+    fn test_recent_failures_are_bounded() {
+        let diagnostics = HandshakeDiagnostics::new();
+        for i in 0..MAX_RECENT_HANDSHAKE_FAILURES + 5 {
+            diagnostics.record_failure(
+                Role::Initiator,
+                HandshakeFailureReason::Timeout,
+                i.to_string(),
+            );
+        }
+
+        let statistics = diagnostics.statistics();
+        assert_eq!(
+            statistics.failures[&HandshakeFailureReason::Timeout],
+            (MAX_RECENT_HANDSHAKE_FAILURES + 5) as u64
+        );
+        assert_eq!(
+            statistics.recent_failures.len(),
+            MAX_RECENT_HANDSHAKE_FAILURES
+        );
+        assert_eq!(
+            statistics.recent_failures[0].message,
+            (MAX_RECENT_HANDSHAKE_FAILURES + 4).to_string()
+        );
+    }
+}
@@ -1,17 +1,43 @@
+use core::time::Duration;
 use ockam_core::compat::sync::Arc;
+use ockam_core::compat::time::now;
 use ockam_core::compat::vec::Vec;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Error, Result};
 use ockam_vault::{AeadSecretKeyHandle, VaultForSecureChannels};
 use tracing_attributes::instrument;
 
-use crate::{Nonce, MAX_NONCE};
+use crate::{IdentityError, MessagePadding, Nonce, MAX_NONCE};
 
 pub(crate) struct Encryptor {
     key: AeadSecretKeyHandle,
     nonce: Nonce,
     vault: Arc<dyn VaultForSecureChannels>,
     rekeying: bool,
+    rekey_policy: RekeyPolicy,
+    padding: Option<MessagePadding>,
+    /// Time, in seconds, when the current key started to be used
+    key_created_at: Option<u64>,
+    /// Last key rotation, not yet persisted
+    key_rotation: Option<KeyRotation>,
+}
+
+/// Rotation of the key of an encryptor or a decryptor
+#[derive(Debug, Clone)]
+pub(crate) struct KeyRotation {
+    pub(crate) previous_key: AeadSecretKeyHandle,
+    pub(crate) key: AeadSecretKeyHandle,
+    /// First nonce of the interval of the new key
+    pub(crate) first_nonce: Nonce,
+}
+
+/// Thresholds triggering a key rotation before the end of the current key interval
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct RekeyPolicy {
+    /// Maximum number of messages encrypted with the same key, at most [`KEY_RENEWAL_INTERVAL`]
+    pub(crate) max_messages: Option<u64>,
+    /// Maximum duration of use of the same key
+    pub(crate) max_duration: Option<Duration>,
 }
 
 // To simplify the implementation, we use the same constant for the size of the message
@@ -36,7 +62,7 @@ impl Encryptor {
                 &mut new_key_buffer,
                 key,
                 &zeroes,
-                &MAX_NONCE.to_aes_gcm_nonce(),
+                &MAX_NONCE.to_aead_nonce(),
                 &[],
             )
             .await?;
@@ -45,11 +71,29 @@ impl Encryptor {
             .import_secret_buffer(new_key_buffer[0..32].to_vec())
             .await?;
 
-        vault.convert_secret_buffer_to_aead_key(buffer).await
+        // the new key is used with the same algorithm as the previous one
+        let algorithm = vault.get_aead_algorithm(key).await?;
+        vault
+            .convert_secret_buffer_to_aead_key(buffer, algorithm)
+            .await
+    }
+
+    /// Pad an encoded secure channel message before its encryption, if a padding is configured
+    pub fn pad(&self, payload: &mut Vec<u8>) {
+        if let Some(padding) = &self.padding {
+            padding.pad(payload);
+        }
     }
 
     #[instrument(skip_all)]
     pub async fn encrypt(&mut self, destination: &mut Vec<u8>, payload: &[u8]) -> Result<()> {
+        // The other party derives the key of a message from its nonce, so that
+        // a key rotation is signaled by skipping the remaining nonces of the current
+        // key interval. The other party rotates its key on the first nonce of the next interval
+        if self.rekeying && self.is_rekey_due()? {
+            self.nonce = Self::next_key_interval_start(self.nonce)?;
+        }
+
         let current_nonce = self.nonce;
 
         self.nonce.increment()?;
@@ -59,8 +103,14 @@ impl Encryptor {
             && current_nonce.value() % KEY_RENEWAL_INTERVAL == 0
         {
             let new_key = Self::rekey(&self.vault, &self.key).await?;
-            let old_key = core::mem::replace(&mut self.key, new_key);
-            self.vault.delete_aead_secret_key(old_key).await?;
+            let old_key = core::mem::replace(&mut self.key, new_key.clone());
+            self.vault.delete_aead_secret_key(old_key.clone()).await?;
+            self.key_created_at = Some(now()?);
+            self.key_rotation = Some(KeyRotation {
+                previous_key: old_key,
+                key: new_key,
+                first_nonce: current_nonce,
+            });
         }
 
         destination.extend_from_slice(&current_nonce.to_noise_nonce());
@@ -70,7 +120,7 @@ impl Encryptor {
                 destination,
                 &self.key,
                 payload,
-                &current_nonce.to_aes_gcm_nonce(),
+                &current_nonce.to_aead_nonce(),
                 &[],
             )
             .await?;
@@ -83,15 +133,80 @@ impl Encryptor {
         nonce: Nonce,
         vault: Arc<dyn VaultForSecureChannels>,
         rekeying: bool,
+        rekey_policy: RekeyPolicy,
+        padding: Option<MessagePadding>,
     ) -> Self {
         Self {
             key,
             nonce,
             vault,
             rekeying,
+            rekey_policy,
+            padding,
+            key_created_at: None,
+            key_rotation: None,
         }
     }
 
+    /// Create an encryptor for a resumed secure channel, with the last persisted key.
+    ///
+    /// Some messages might have been encrypted with that key before the channel was stopped,
+    /// so the encryption resumes with the next key, in order to never reuse a nonce
+    pub fn resume(
+        key: AeadSecretKeyHandle,
+        first_nonce: Nonce,
+        vault: Arc<dyn VaultForSecureChannels>,
+        rekey_policy: RekeyPolicy,
+        padding: Option<MessagePadding>,
+    ) -> Result<Self> {
+        let nonce = Self::next_key_interval_start(first_nonce)?;
+        Ok(Self::new(key, nonce, vault, true, rekey_policy, padding))
+    }
+
+    /// Return the last key rotation if it has not been returned yet
+    pub(crate) fn take_key_rotation(&mut self) -> Option<KeyRotation> {
+        self.key_rotation.take()
+    }
+
+    /// Return true if the current key must be rotated before the end of its interval,
+    /// because it was used for too many messages or for too long
+    fn is_rekey_due(&mut self) -> Result<bool> {
+        let messages_count = self.nonce.value() % KEY_RENEWAL_INTERVAL;
+
+        let key_age = match self.rekey_policy.max_duration {
+            Some(_) => {
+                let now = now()?;
+                let key_created_at = *self.key_created_at.get_or_insert(now);
+                Some(Duration::from_secs(now.saturating_sub(key_created_at)))
+            }
+            None => None,
+        };
+
+        // the key is rotated anyway when starting a new interval
+        if messages_count == 0 {
+            return Ok(false);
+        }
+
+        let too_many_messages = self
+            .rekey_policy
+            .max_messages
+            .is_some_and(|max_messages| messages_count >= max_messages);
+        let too_old = key_age
+            .zip(self.rekey_policy.max_duration)
+            .is_some_and(|(key_age, max_duration)| key_age >= max_duration);
+
+        Ok(too_many_messages || too_old)
+    }
+
+    /// Return the first nonce of the key interval following the interval of a given nonce
+    fn next_key_interval_start(nonce: Nonce) -> Result<Nonce> {
+        let next = (nonce.value() / KEY_RENEWAL_INTERVAL)
+            .checked_add(1)
+            .and_then(|n| n.checked_mul(KEY_RENEWAL_INTERVAL))
+            .ok_or(IdentityError::NonceOverflow)?;
+        Ok(next.into())
+    }
+
     #[instrument(skip_all)]
     pub(crate) async fn shutdown(&self) -> Result<()> {
         if !self.vault.delete_aead_secret_key(self.key.clone()).await? {
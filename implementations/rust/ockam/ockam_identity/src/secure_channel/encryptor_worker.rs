@@ -15,9 +15,11 @@ use crate::models::CredentialAndPurposeKey;
 use crate::secure_channel::addresses::Addresses;
 use crate::secure_channel::api::{EncryptionRequest, EncryptionResponse};
 use crate::secure_channel::encryptor::{Encryptor, SIZE_OF_ENCRYPT_OVERHEAD};
+use crate::secure_channel::resumption::ChannelResumption;
 use crate::{
     ChangeHistoryRepository, CredentialRetriever, Identifier, IdentityError, Nonce,
-    PlaintextPayloadMessage, RefreshCredentialsMessage, SecureChannelMessage,
+    PlaintextPayloadMessage, RefreshCredentialsMessage, SecureChannelActivity,
+    SecureChannelMessage,
 };
 
 /// Wrap last received (during successful decryption) nonce and current route to the remote in a
@@ -49,6 +51,8 @@ pub(crate) struct SecureChannelSharedState {
     /// Allows Decryptor to flag that we're closing the channel because we received a Close message from the other side,
     /// therefore, we don't need to send that message again to the other side
     pub(crate) should_send_close: Arc<AtomicBool>,
+    /// Time of the last message encrypted or decrypted by this channel
+    pub(crate) activity: SecureChannelActivity,
 }
 
 pub(crate) struct EncryptorWorker {
@@ -61,6 +65,7 @@ pub(crate) struct EncryptorWorker {
     credential_retriever: Option<Arc<dyn CredentialRetriever>>,
     last_presented_credential: Option<CredentialAndPurposeKey>,
     shared_state: SecureChannelSharedState,
+    resumption: Option<ChannelResumption>,
 }
 
 impl EncryptorWorker {
@@ -86,12 +91,31 @@ impl EncryptorWorker {
             credential_retriever,
             last_presented_credential,
             shared_state,
+            resumption: None,
         }
     }
 
+    /// Persist the encryption keys when they are rotated, so that the channel can be resumed
+    pub(crate) fn with_resumption(mut self, resumption: Option<ChannelResumption>) -> Self {
+        self.resumption = resumption;
+        self
+    }
+
+    /// Persist the new encryption key if it was just rotated.
+    /// The message must not be sent if that key could not be persisted, since a resumed
+    /// channel would then reuse its nonce with the previous key
+    async fn persist_key_rotation(&mut self) -> Result<()> {
+        let key_rotation = self.encryptor.take_key_rotation();
+        if let (Some(resumption), Some(key_rotation)) = (&self.resumption, key_rotation) {
+            resumption.persist_encryption_key(&key_rotation).await?;
+        }
+        Ok(())
+    }
+
     /// Encrypt the message
     async fn encrypt(&mut self, ctx: &Context, msg: SecureChannelMessage<'_>) -> Result<Vec<u8>> {
-        let payload = minicbor::to_vec(&msg)?;
+        let mut payload = minicbor::to_vec(&msg)?;
+        self.encryptor.pad(&mut payload);
         let mut buffer = Vec::new();
         self.encrypt_to(ctx, &mut buffer, &payload).await?;
         Ok(buffer)
@@ -106,7 +130,12 @@ impl EncryptorWorker {
         // by reserving the capacity beforehand, we can avoid copying memory later
         destination.reserve(SIZE_OF_ENCRYPT_OVERHEAD + payload.len());
 
-        match self.encryptor.encrypt(destination, payload).await {
+        let result = match self.encryptor.encrypt(destination, payload).await {
+            Ok(()) => self.persist_key_rotation().await,
+            Err(err) => Err(err),
+        };
+
+        match result {
             Ok(()) => Ok(()),
             // If encryption failed, that means we have some internal error,
             // and we may be in an invalid state, it's better to stop the Worker
@@ -139,11 +168,16 @@ impl EncryptorWorker {
         let mut encrypted_payload = Vec::new();
 
         // Encrypt the message
-        let response = match self
+        let result = match self
             .encryptor
             .encrypt(&mut encrypted_payload, &request.0)
             .await
         {
+            Ok(()) => self.persist_key_rotation().await,
+            Err(err) => Err(err),
+        };
+
+        let response = match result {
             Ok(()) => EncryptionResponse::Ok(encrypted_payload),
             // If encryption failed, that means we have some internal error,
             // and we may be in an invalid state, it's better to stop the Worker
@@ -185,11 +219,13 @@ impl EncryptorWorker {
         // Remove our address
         let _ = onward_route.step();
 
-        let payload = msg.into_payload();
+        // The payload shares the buffer it was received or decrypted in,
+        // it is copied once when encoded as the plaintext to encrypt
+        let plaintext_payload = msg.into_payload_bytes();
         let msg = PlaintextPayloadMessage {
             onward_route,
             return_route,
-            payload: &payload,
+            payload: &plaintext_payload,
         };
         let msg = SecureChannelMessage::Payload(msg);
 
@@ -204,7 +240,8 @@ impl EncryptorWorker {
             // before it's actually written, so we can write the whole encrypted
             // payload without any extra copies.
 
-            let encoded_payload = minicbor::to_vec(&msg)?;
+            let mut encoded_payload = minicbor::to_vec(&msg)?;
+            self.encryptor.pad(&mut encoded_payload);
             // we assume this calculation is exact
             let encrypted_payload_size = SIZE_OF_ENCRYPT_OVERHEAD + encoded_payload.len();
             let variable_length_integer =
@@ -226,7 +263,8 @@ impl EncryptorWorker {
         };
 
         let remote_route = self.shared_state.remote_route.read().unwrap().route.clone();
-        // Decryptor doesn't need the return_route since it has `self.remote_route` as well
+        // Decryptor doesn't need the return_route since it has `self.remote_route` as well.
+        // The encrypted buffer becomes the payload of the transport message without being copied
         let msg = LocalMessage::new()
             .with_payload(payload)
             .with_onward_route(remote_route);
@@ -234,6 +272,7 @@ impl EncryptorWorker {
         // Send the message to the decryptor on the other side
         ctx.forward_from_address(msg, self.addresses.encryptor.clone())
             .await?;
+        self.shared_state.activity.record();
 
         Ok(())
     }
@@ -4,8 +4,8 @@ use ockam_core::compat::vec::Vec;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Error, Result};
 use ockam_vault::{
-    AeadSecretKeyHandle, HKDFNumberOfOutputs, SecretBufferHandle, VaultForSecureChannels,
-    X25519PublicKey, X25519SecretKeyHandle, X25519_PUBLIC_KEY_LENGTH,
+    AeadAlgorithm, AeadSecretKeyHandle, HKDFNumberOfOutputs, SecretBufferHandle,
+    VaultForSecureChannels, X25519PublicKey, X25519SecretKeyHandle, X25519_PUBLIC_KEY_LENGTH,
 };
 use sha2::{Digest, Sha256};
 use Status::*;
@@ -13,6 +13,7 @@ use Status::*;
 use crate::secure_channel::handshake::error::XXError;
 use crate::secure_channel::handshake::handshake_state_machine::{HandshakeKeys, Status};
 use crate::secure_channel::Role;
+use crate::CipherSuite;
 
 /// The number of bytes in a SHA256 digest
 pub const SHA256_SIZE: usize = 32;
@@ -215,12 +216,33 @@ impl Handshake {
         Ok(payload)
     }
 
-    /// Set the final state of the state machine by creating the encryption / decryption keys
-    /// and return the other party identity
-    pub(super) async fn set_final_state(&mut self, role: Role) -> Result<()> {
+    /// Mix the shared secret of a post-quantum key encapsulation in the chaining key,
+    /// when a hybrid key agreement has been negotiated. This is done by both parties right
+    /// after message 2 so that message 3 and the final keys depend on that secret
+    pub(super) async fn mix_kem_secret(&mut self, shared_secret: &[u8]) -> Result<()> {
+        let mut state = self.state.clone();
+        // ck, k = HKDF(ck, ss, 2)
+        let secret = self
+            .vault
+            .import_secret_buffer(shared_secret.to_vec())
+            .await?;
+        self.hkdf(&mut state, secret).await?;
+        self.state = state;
+        Ok(())
+    }
+
+    /// Set the final state of the state machine by creating the encryption / decryption keys,
+    /// used with the negotiated cipher suite, and return the other party identity
+    pub(super) async fn set_final_state(
+        &mut self,
+        role: Role,
+        cipher_suite: CipherSuite,
+    ) -> Result<()> {
         // k1, k2 = HKDF(ck, zerolen, 2)
         let mut state = self.state.clone();
-        let (k1, k2) = self.compute_final_keys(&mut state).await?;
+        let (k1, k2) = self
+            .compute_final_keys(&mut state, cipher_suite.aead_algorithm())
+            .await?;
         let (encryption_key, decryption_key) = if role.is_initiator() {
             (k2, k1)
         } else {
@@ -298,7 +320,11 @@ impl Handshake {
              .0
             .try_into()
             .map_err(|_| XXError::InternalVaultError)?;
-        let new_k = self.vault.convert_secret_buffer_to_aead_key(new_k).await?;
+        // the handshake messages are always encrypted with AES-GCM
+        let new_k = self
+            .vault
+            .convert_secret_buffer_to_aead_key(new_k, AeadAlgorithm::AesGcm)
+            .await?;
 
         let old_ck = state.take_ck()?;
         state.ck = Some(new_ck);
@@ -317,6 +343,7 @@ impl Handshake {
     async fn compute_final_keys(
         &self,
         state: &mut HandshakeState,
+        algorithm: AeadAlgorithm,
     ) -> Result<(AeadSecretKeyHandle, AeadSecretKeyHandle)> {
         let hkdf_output = self
             .vault
@@ -329,8 +356,14 @@ impl Handshake {
             .try_into()
             .map_err(|_| XXError::InternalVaultError)?;
 
-        let k1 = self.vault.convert_secret_buffer_to_aead_key(k1).await?;
-        let k2 = self.vault.convert_secret_buffer_to_aead_key(k2).await?;
+        let k1 = self
+            .vault
+            .convert_secret_buffer_to_aead_key(k1, algorithm)
+            .await?;
+        let k2 = self
+            .vault
+            .convert_secret_buffer_to_aead_key(k2, algorithm)
+            .await?;
 
         self.vault.delete_secret_buffer(state.take_ck()?).await?;
         self.vault.delete_aead_secret_key(state.take_k()?).await?;
@@ -748,10 +781,14 @@ mod tests {
         let decoded = responder.decode_message3(&result).await?;
         assert_eq!(decoded, messages.message3_payload);
 
-        let result = initiator.set_final_state(Role::Responder).await;
+        let result = initiator
+            .set_final_state(Role::Responder, CipherSuite::Aes256Gcm)
+            .await;
         assert!(result.is_ok());
 
-        let result = responder.set_final_state(Role::Initiator).await;
+        let result = responder
+            .set_final_state(Role::Initiator, CipherSuite::Aes256Gcm)
+            .await;
         assert!(result.is_ok());
 
         Ok(())
@@ -1,18 +1,20 @@
+use minicbor::bytes::ByteVec;
 use minicbor::{Decode, Encode};
 use tracing::{debug, warn};
 
 use ockam_core::compat::boxed::Box;
-use ockam_core::compat::string::ToString;
+use ockam_core::compat::string::{String, ToString};
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
-use ockam_core::{async_trait, Result};
+use ockam_core::{async_trait, Error, Result};
 use ockam_vault::{AeadSecretKeyHandle, X25519PublicKey};
 
 use crate::models::{
     ChangeHistory, CredentialAndPurposeKey, PurposeKeyAttestation, PurposePublicKey,
 };
 use crate::{
-    CredentialRetriever, Identifier, Identities, IdentityError, SecureChannelTrustInfo, TrustPolicy,
+    CipherSuite, CredentialRetriever, HandshakeFailureReason, Identifier, Identities,
+    IdentityError, SecureChannelTrustInfo, TrustPolicy,
 };
 
 /// Interface for a state machine in a key exchange protocol
@@ -20,6 +22,8 @@ use crate::{
 pub(crate) trait StateMachine: Send + Sync + 'static {
     async fn on_event(&mut self, event: Event) -> Result<Action>;
     fn get_handshake_results(&self) -> Option<HandshakeResults>;
+    /// Reason of the last failed event, when the failure occurred while verifying the other party
+    fn failure_reason(&self) -> Option<HandshakeFailureReason>;
 }
 
 /// Events received by the state machine, either initializing the state machine
@@ -61,6 +65,8 @@ pub(crate) struct HandshakeResults {
     pub(super) handshake_keys: HandshakeKeys,
     pub(super) their_identifier: Identifier,
     pub(super) presented_credential: Option<CredentialAndPurposeKey>,
+    /// Errors of the credentials presented by the other party which could not be verified
+    pub(super) rejected_credentials: Vec<String>,
 }
 
 /// This struct implements functions common to both initiator and the responder state machines
@@ -73,6 +79,8 @@ pub(crate) struct CommonStateMachine {
     pub(super) authority: Option<Identifier>, // TODO: Replace with ABAC
     pub(super) presented_credential: Option<CredentialAndPurposeKey>,
     their_identifier: Option<Identifier>,
+    rejected_credentials: Vec<String>,
+    failure_reason: Option<HandshakeFailureReason>,
 }
 
 impl CommonStateMachine {
@@ -93,6 +101,8 @@ impl CommonStateMachine {
             authority,
             presented_credential: None,
             their_identifier: None,
+            rejected_credentials: vec![],
+            failure_reason: None,
         }
     }
 
@@ -103,7 +113,14 @@ impl CommonStateMachine {
     ///  - the current Secure Channel Purpose Key Attestation
     ///  - the Identity Credentials and corresponding Credentials Purpose Key Attestations
     ///
-    pub(super) async fn make_identity_payload(&mut self) -> Result<Vec<u8>> {
+    /// When a hybrid key agreement is negotiated, the responder also sends the ciphertext
+    /// of the post-quantum key encapsulation. The responder also sends the selected cipher suite
+    ///
+    pub(super) async fn make_identity_payload(
+        &mut self,
+        kem_ciphertext: Option<Vec<u8>>,
+        cipher_suite: Option<CipherSuite>,
+    ) -> Result<Vec<u8>> {
         // prepare the payload that will be sent either in message 2 or message 3
         let change_history = self.identities.get_change_history(&self.identifier).await?;
         let credential = match &self.credential_retriever {
@@ -118,6 +135,8 @@ impl CommonStateMachine {
             change_history,
             purpose_key_attestation: self.purpose_key_attestation.clone(),
             credentials,
+            kem_ciphertext: kem_ciphertext.map(|c| c.into()),
+            cipher_suite,
         };
         Ok(minicbor::to_vec(payload)?)
     }
@@ -130,15 +149,25 @@ impl CommonStateMachine {
         peer: IdentityAndCredentials,
         peer_public_key: X25519PublicKey,
     ) -> Result<()> {
-        let identifier = Self::process_identity_payload_static(
-            self.identities.clone(),
-            Some(self.trust_policy.clone()),
-            self.authority.clone(),
+        let identifier = Self::verify_identity(
+            &self.identities,
             None,
             peer.change_history,
-            peer.credentials,
             Some((peer.purpose_key_attestation, peer_public_key)),
         )
+        .await
+        .map_err(|e| self.fail(HandshakeFailureReason::IdentityVerificationFailed, e))?;
+
+        Self::check_trust_policy(Some(self.trust_policy.clone()), &identifier)
+            .await
+            .map_err(|e| self.fail(HandshakeFailureReason::TrustPolicyDenied, e))?;
+
+        self.rejected_credentials = Self::verify_credentials(
+            self.identities.clone(),
+            self.authority.clone(),
+            &identifier,
+            peer.credentials,
+        )
         .await?;
 
         self.their_identifier = Some(identifier);
@@ -146,6 +175,17 @@ impl CommonStateMachine {
         Ok(())
     }
 
+    /// Reason of the last failure, when it occurred while verifying the other party
+    pub(super) fn failure_reason(&self) -> Option<HandshakeFailureReason> {
+        self.failure_reason
+    }
+
+    /// Keep the reason of a failure to report it in the handshake diagnostics
+    fn fail(&mut self, reason: HandshakeFailureReason, error: Error) -> Error {
+        self.failure_reason = Some(reason);
+        error
+    }
+
     /// Return the results of the full handshake
     ///  - the other party identity
     ///  - the encryption and decryption keys to use on the next messages to exchange
@@ -158,6 +198,7 @@ impl CommonStateMachine {
                 their_identifier,
                 handshake_keys,
                 presented_credential: self.presented_credential.clone(),
+                rejected_credentials: self.rejected_credentials.clone(),
             }),
             _ => None,
         }
@@ -178,10 +219,32 @@ impl CommonStateMachine {
         credentials: Vec<CredentialAndPurposeKey>,
         // Has value if it's the identity payload during the handshake and not credential refresh
         peer_public_key: Option<(PurposeKeyAttestation, X25519PublicKey)>,
+    ) -> Result<Identifier> {
+        let their_identifier = Self::verify_identity(
+            &identities,
+            expected_identifier,
+            change_history,
+            peer_public_key,
+        )
+        .await?;
+
+        Self::check_trust_policy(trust_policy, &their_identifier).await?;
+        Self::verify_credentials(identities, authority, &their_identifier, credentials).await?;
+
+        Ok(their_identifier)
+    }
+
+    /// Import the change history of the other party and, during the handshake,
+    /// verify that its Purpose Key matches the static key used in the handshake
+    async fn verify_identity(
+        identities: &Arc<Identities>,
+        expected_identifier: Option<Identifier>,
+        change_history: ChangeHistory,
+        peer_public_key: Option<(PurposeKeyAttestation, X25519PublicKey)>,
     ) -> Result<Identifier> {
         let their_identifier = identities
             .identities_verification()
-            .import_from_change_history(expected_identifier.as_ref(), change_history.clone())
+            .import_from_change_history(expected_identifier.as_ref(), change_history)
             .await?;
 
         if let Some((purpose_key_attestation, peer_public_key)) = peer_public_key {
@@ -203,9 +266,6 @@ impl CommonStateMachine {
             }
         }
 
-        Self::check_trust_policy(trust_policy, &their_identifier).await?;
-        Self::verify_credentials(identities, authority, &their_identifier, credentials).await?;
-
         Ok(their_identifier)
     }
 
@@ -231,7 +291,8 @@ impl CommonStateMachine {
         Ok(())
     }
 
-    /// Verify that the credentials sent by the other party are valid
+    /// Verify that the credentials sent by the other party are valid.
+    /// Return the errors of the credentials which could not be verified
     async fn verify_credentials(
         identities: Arc<Identities>,
         // TODO: Do we really care if the authority is known here?.
@@ -241,7 +302,7 @@ impl CommonStateMachine {
         authority: Option<Identifier>,
         their_identifier: &Identifier,
         credentials: Vec<CredentialAndPurposeKey>,
-    ) -> Result<()> {
+    ) -> Result<Vec<String>> {
         debug!("verifying {} credentials", credentials.len());
 
         // Let's complete the handshake and keep the secure channel open even if we could not
@@ -253,7 +314,7 @@ impl CommonStateMachine {
             if !credentials.is_empty() {
                 warn!("credentials were presented, but Authority is missing");
             }
-            return Ok(());
+            return Ok(vec![]);
         };
 
         if credentials.is_empty() {
@@ -261,9 +322,10 @@ impl CommonStateMachine {
                 "no credentials were received from {}. Expected authority: {}",
                 their_identifier, authority
             );
-            return Ok(());
+            return Ok(vec![]);
         };
 
+        let mut rejected_credentials = vec![];
         for credential in &credentials {
             let res = identities
                 .credentials()
@@ -284,11 +346,12 @@ impl CommonStateMachine {
                         their_identifier,
                         err.to_string()
                     );
+                    rejected_credentials.push(err.to_string());
                 }
             }
         }
 
-        Ok(())
+        Ok(rejected_credentials)
     }
 }
 
@@ -305,4 +368,9 @@ pub(super) struct IdentityAndCredentials {
     /// Credentials associated to the identity along with corresponding Credentials Purpose Keys
     /// to verify those Credentials
     #[n(2)] pub(super) credentials: Vec<CredentialAndPurposeKey>,
+    /// Ciphertext of the post-quantum key encapsulation, sent by the responder when
+    /// a hybrid key agreement is negotiated
+    #[n(3)] pub(super) kem_ciphertext: Option<ByteVec>,
+    /// Cipher suite selected by the responder to encrypt the messages of the secure channel
+    #[n(4)] pub(super) cipher_suite: Option<CipherSuite>,
 }
@@ -1,15 +1,17 @@
-use core::sync::atomic::AtomicBool;
+use core::sync::atomic::{AtomicBool, Ordering};
 use core::time::Duration;
-use ockam_core::compat::string::ToString;
+use ockam_core::compat::string::{String, ToString};
 use ockam_core::compat::sync::{Arc, RwLock};
 use ockam_core::compat::{boxed::Box, vec::Vec};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{
-    AllowAll, Any, Decodable, DenyAll, Error, Mailbox, Mailboxes, OutgoingAccessControl, Route,
-    Routed,
+    Address, AllowAll, Any, Decodable, DenyAll, Error, IncomingAccessControl, Mailbox, Mailboxes,
+    OutgoingAccessControl, Route, Routed,
 };
 use ockam_core::{Result, Worker};
 use ockam_node::callback::CallbackSender;
+#[cfg(feature = "std")]
+use ockam_node::NodeEvent;
 use ockam_node::{Context, WorkerBuilder};
 use ockam_vault::AeadSecretKeyHandle;
 use tracing::{debug, error, info, warn};
@@ -17,7 +19,8 @@ use tracing_attributes::instrument;
 
 use crate::models::Identifier;
 use crate::secure_channel::decryptor::DecryptorHandler;
-use crate::secure_channel::encryptor::Encryptor;
+use crate::secure_channel::diagnostics::HandshakeTimer;
+use crate::secure_channel::encryptor::{Encryptor, RekeyPolicy};
 use crate::secure_channel::encryptor_worker::{
     EncryptorWorker, RemoteRoute, SecureChannelSharedState,
 };
@@ -30,11 +33,16 @@ use crate::secure_channel::handshake::handshake_state_machine::{
 };
 use crate::secure_channel::handshake::initiator_state_machine::InitiatorStateMachine;
 use crate::secure_channel::handshake::responder_state_machine::ResponderStateMachine;
+use crate::secure_channel::nonce_tracker::DEFAULT_NONCE_WINDOW_SIZE;
+use crate::secure_channel::resumption::ChannelResumption;
 use crate::secure_channel::{Addresses, Role};
+use crate::utils::now;
 use crate::{
-    ChangeHistoryRepository, CredentialRetriever, IdentityError, PersistedSecureChannel,
-    SecureChannelPurposeKey, SecureChannelRegistryEntry, SecureChannelRepository, SecureChannels,
-    TrustPolicy, IDENTITY_SECURE_CHANNEL_IDENTIFIER,
+    ChangeHistoryRepository, CipherSuite, CredentialRetriever, HandshakeDiagnostics,
+    HandshakeFailureReason, IdentityError, KeyAgreementPolicy, MessagePadding,
+    PersistedSecureChannel, ResumableChannelState, SecureChannelActivity, SecureChannelPurposeKey,
+    SecureChannelRegistryEntry, SecureChannelRepository, SecureChannels, TrustPolicy,
+    IDENTITY_SECURE_CHANNEL_IDENTIFIER,
 };
 
 /// This struct implements a Worker receiving and sending messages
@@ -47,6 +55,9 @@ pub(crate) struct HandshakeWorker {
     addresses: Addresses,
     role: Role,
     key_exchange_only: bool,
+    rekey_policy: RekeyPolicy,
+    nonce_window_size: u64,
+    padding: Option<MessagePadding>,
     remote_route: Option<Route>,
     decryptor_handler: Option<DecryptorHandler>,
 
@@ -58,6 +69,12 @@ pub(crate) struct HandshakeWorker {
     secure_channel_repository: Option<Arc<dyn SecureChannelRepository>>,
 
     shared_state: SecureChannelSharedState,
+    api_access_control: Arc<dyn IncomingAccessControl>,
+
+    handshake_diagnostics: HandshakeDiagnostics,
+    handshake_timer: HandshakeTimer,
+    // Set when a failure is recorded, so that the initiator doesn't also record a timeout
+    handshake_failed: Arc<AtomicBool>,
 }
 
 #[ockam_core::worker]
@@ -73,7 +90,14 @@ impl Worker for HandshakeWorker {
         }
 
         if let Some(state_machine) = self.state_machine.as_mut() {
-            match state_machine.on_event(Initialize).await? {
+            let action = match state_machine.on_event(Initialize).await {
+                Ok(action) => action,
+                Err(err) => {
+                    self.record_failure(&err);
+                    return Err(err);
+                }
+            };
+            match action {
                 SendMessage(message) => {
                     debug!(
                         "remote route {:?}, decryptor remote {:?}",
@@ -123,6 +147,10 @@ impl Worker for HandshakeWorker {
             .unregister_channel(&self.addresses.encryptor);
 
         if let Some(handler) = &self.decryptor_handler {
+            #[cfg(feature = "std")]
+            context.publish_event(NodeEvent::SecureChannelClosed {
+                address: self.addresses.encryptor.clone(),
+            });
             handler.shutdown().await?
         }
 
@@ -147,6 +175,12 @@ impl HandshakeWorker {
         timeout: Option<Duration>,
         role: Role,
         key_exchange_only: bool,
+        key_agreement_policy: KeyAgreementPolicy,
+        cipher_suites: Vec<CipherSuite>,
+        rekey_policy: RekeyPolicy,
+        nonce_window_size: u64,
+        padding: Option<MessagePadding>,
+        api_access_control: Arc<dyn IncomingAccessControl>,
         secure_channel_repository: Option<Arc<dyn SecureChannelRepository>>,
         encryptor_remote_route: Arc<RwLock<RemoteRoute>>,
     ) -> Result<Option<Identifier>> {
@@ -163,6 +197,8 @@ impl HandshakeWorker {
                     credential_retriever.clone(),
                     trust_policy,
                     authority.clone(),
+                    key_agreement_policy,
+                    cipher_suites,
                 )
                 .await?,
             )
@@ -176,11 +212,19 @@ impl HandshakeWorker {
                     credential_retriever.clone(),
                     trust_policy,
                     authority.clone(),
+                    key_agreement_policy,
+                    cipher_suites,
                 )
                 .await?,
             )
         };
 
+        let handshake_diagnostics = secure_channels
+            .secure_channel_registry
+            .handshake_diagnostics();
+        handshake_diagnostics.record_attempt();
+        let handshake_failed = Arc::new(AtomicBool::new(false));
+
         let (callback_waiter, callback_sender) = if role.is_initiator() {
             let callback = ockam_node::callback::new_callback();
             (Some(callback.0), Some(callback.1))
@@ -191,6 +235,7 @@ impl HandshakeWorker {
         let shared_state = SecureChannelSharedState {
             should_send_close: Arc::new(AtomicBool::new(true)),
             remote_route: encryptor_remote_route,
+            activity: SecureChannelActivity::new(),
         };
         let worker = Self {
             secure_channels,
@@ -199,6 +244,9 @@ impl HandshakeWorker {
             my_identifier: my_identifier.clone(),
             role,
             key_exchange_only,
+            rekey_policy,
+            nonce_window_size,
+            padding,
             remote_route: remote_route.clone(),
             addresses: addresses.clone(),
             decryptor_handler: None,
@@ -207,12 +255,17 @@ impl HandshakeWorker {
             change_history_repository: identities.change_history_repository(),
             secure_channel_repository,
             shared_state,
+            api_access_control: api_access_control.clone(),
+            handshake_diagnostics: handshake_diagnostics.clone(),
+            handshake_timer: HandshakeTimer::start(),
+            handshake_failed: handshake_failed.clone(),
         };
 
         WorkerBuilder::new(worker)
             .with_mailboxes(Self::create_mailboxes(
                 &addresses,
                 decryptor_outgoing_access_control,
+                api_access_control,
             ))
             .start(context)
             .await?;
@@ -237,6 +290,15 @@ impl HandshakeWorker {
                             "Timeout {:?} reached when creating secure channel for: {}. Encryptor: {}",
                             timeout, my_identifier, addresses.encryptor
                         );
+                            if err.code().kind == Kind::Timeout
+                                && !handshake_failed.swap(true, Ordering::Relaxed)
+                            {
+                                handshake_diagnostics.record_failure(
+                                    role,
+                                    HandshakeFailureReason::Timeout,
+                                    err.to_string(),
+                                );
+                            }
 
                             return Err(err);
                         }
@@ -264,6 +326,20 @@ impl HandshakeWorker {
         &mut self,
         context: &mut Context,
         message: Routed<Any>,
+    ) -> Result<()> {
+        let result = self.process_handshake_message(context, message).await;
+        if let Err(err) = &result {
+            self.record_failure(err);
+        }
+        result
+    }
+
+    /// Process a handshake message with the state machine, and finalize the channel
+    /// once the handshake is complete
+    async fn process_handshake_message(
+        &mut self,
+        context: &mut Context,
+        message: Routed<Any>,
     ) -> Result<()> {
         let payload = message.payload();
 
@@ -298,7 +374,9 @@ impl HandshakeWorker {
         {
             // start the encryptor worker and return the decryptor
             let their_identifier = final_state.their_identifier.clone();
+            let rejected_credentials = final_state.rejected_credentials.clone();
             self.decryptor_handler = Some(self.finalize(context, final_state).await?);
+            self.record_success(rejected_credentials);
             if let Some(callback_sender) = self.callback_sender.take() {
                 callback_sender.send(their_identifier)?;
             }
@@ -332,6 +410,34 @@ impl HandshakeWorker {
         }
     }
 
+    /// Record a completed handshake, along with the credentials which were rejected
+    fn record_success(&self, rejected_credentials: Vec<String>) {
+        for rejected_credential in rejected_credentials {
+            self.handshake_diagnostics.record_failure(
+                self.role,
+                HandshakeFailureReason::CredentialRejected,
+                rejected_credential,
+            );
+        }
+        self.handshake_diagnostics
+            .record_success(self.handshake_timer.elapsed());
+    }
+
+    /// Record a failed handshake, with the reason given by the state machine if it is known
+    fn record_failure(&self, err: &Error) {
+        let reason = self
+            .state_machine
+            .as_ref()
+            .and_then(|state_machine| state_machine.failure_reason())
+            .unwrap_or(HandshakeFailureReason::Other);
+        // the following messages of a failed handshake can only fail again
+        if self.handshake_failed.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        self.handshake_diagnostics
+            .record_failure(self.role, reason, err.to_string());
+    }
+
     /// Return the route for the other party's handshake worker
     fn remote_route(&self) -> Result<Route> {
         self.remote_route.clone().ok_or_else(|| {
@@ -347,6 +453,7 @@ impl HandshakeWorker {
     pub(crate) fn create_mailboxes(
         addresses: &Addresses,
         decryptor_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        api_access_control: Arc<dyn IncomingAccessControl>,
     ) -> Mailboxes {
         let remote_mailbox = Mailbox::new(
             addresses.decryptor_remote.clone(),
@@ -364,7 +471,7 @@ impl HandshakeWorker {
         );
         let api_mailbox = Mailbox::new(
             addresses.decryptor_api.clone(),
-            Arc::new(AllowAll),
+            api_access_control,
             Arc::new(AllowAll),
         );
 
@@ -381,18 +488,46 @@ impl HandshakeWorker {
     ) -> Result<DecryptorHandler> {
         let their_identifier = handshake_results.their_identifier.clone();
 
+        let their_decryptor_address = self
+            .remote_route()?
+            .iter()
+            .last()
+            .expect("the remote route should not be empty")
+            .clone();
+
+        // the keys of a channel which is not key exchange only are persisted on each rotation
+        let resumption = match &self.secure_channel_repository {
+            Some(repository) if !self.key_exchange_only => Some(ChannelResumption::new(
+                repository.clone(),
+                self.secure_channels.identities.vault().secure_channel_vault,
+                self.addresses.decryptor_remote.clone(),
+            )),
+            _ => None,
+        };
+
         // create a decryptor to delegate the processing of all messages after the handshake
         let decryptor = DecryptorHandler::new(
             self.secure_channels.identities.clone(),
             self.authority.clone(),
             self.role,
             self.key_exchange_only,
+            self.nonce_window_size,
             self.addresses.clone(),
             handshake_results.handshake_keys.decryption_key.clone(),
             self.secure_channels.identities.vault().secure_channel_vault,
             handshake_results.their_identifier.clone(),
             self.shared_state.clone(),
-        );
+        )
+        .with_resumption(resumption.clone());
+
+        // the channel must be persisted before the encryptor starts rotating its keys
+        self.persist(
+            their_identifier.clone(),
+            &handshake_results.handshake_keys.decryption_key,
+            &handshake_results.handshake_keys.encryption_key,
+            &their_decryptor_address,
+        )
+        .await;
 
         // create a separate encryptor worker which will be started independently
         {
@@ -413,51 +548,32 @@ impl HandshakeWorker {
                     0.into(),
                     self.secure_channels.identities.vault().secure_channel_vault,
                     rekeying,
+                    self.rekey_policy,
+                    self.padding.clone(),
                 ),
                 self.my_identifier.clone(),
                 self.change_history_repository.clone(),
                 credential_retriever,
                 handshake_results.presented_credential,
                 self.shared_state.clone(),
-            );
-
-            let main_mailbox = Mailbox::new(
-                self.addresses.encryptor.clone(),
-                Arc::new(AllowAll),
-                Arc::new(AllowAll),
-            );
-            let api_mailbox = Mailbox::new(
-                self.addresses.encryptor_api.clone(),
-                Arc::new(AllowAll),
-                Arc::new(AllowAll),
-            );
-            let internal_mailbox = Mailbox::new(
-                self.addresses.encryptor_internal.clone(),
-                Arc::new(AllowAll),
-                Arc::new(DenyAll),
-            );
-
-            WorkerBuilder::new(encryptor)
-                .with_mailboxes(Mailboxes::new(
-                    main_mailbox,
-                    vec![api_mailbox, internal_mailbox],
-                ))
-                .terminal_with_attributes(
-                    self.addresses.encryptor.clone(),
-                    vec![(
-                        IDENTITY_SECURE_CHANNEL_IDENTIFIER.to_string(),
-                        their_identifier.to_string(),
-                    )],
-                )
-                .start(context)
-                .await?;
+            )
+            .with_resumption(resumption);
+
+            Self::start_encryptor(
+                context,
+                &self.addresses,
+                &their_identifier,
+                encryptor,
+                self.api_access_control.clone(),
+            )
+            .await?;
         }
 
-        self.persist(
-            their_identifier,
-            &handshake_results.handshake_keys.decryption_key,
-        )
-        .await;
+        #[cfg(feature = "std")]
+        context.publish_event(NodeEvent::SecureChannelEstablished {
+            address: self.addresses.encryptor.clone(),
+            their_identifier: their_identifier.to_string(),
+        });
 
         info!(
             "Initialized SecureChannel {} at local: {}, remote: {}",
@@ -466,13 +582,6 @@ impl HandshakeWorker {
             &self.addresses.decryptor_remote
         );
 
-        let their_decryptor_address = self
-            .remote_route()?
-            .iter()
-            .last()
-            .expect("the remote route should not be empty")
-            .clone();
-
         let info = SecureChannelRegistryEntry::new(
             self.addresses.encryptor.clone(),
             self.addresses.encryptor_api.clone(),
@@ -482,6 +591,8 @@ impl HandshakeWorker {
             self.my_identifier.clone(),
             handshake_results.their_identifier,
             their_decryptor_address,
+            now()?,
+            self.shared_state.activity.clone(),
         );
 
         self.secure_channels
@@ -491,7 +602,53 @@ impl HandshakeWorker {
         Ok(decryptor)
     }
 
-    async fn persist(&self, their_identifier: Identifier, decryption_key: &AeadSecretKeyHandle) {
+    /// Start the encryptor worker of a secure channel
+    pub(crate) async fn start_encryptor(
+        context: &Context,
+        addresses: &Addresses,
+        their_identifier: &Identifier,
+        encryptor: EncryptorWorker,
+        api_access_control: Arc<dyn IncomingAccessControl>,
+    ) -> Result<()> {
+        let main_mailbox = Mailbox::new(
+            addresses.encryptor.clone(),
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        );
+        let api_mailbox = Mailbox::new(
+            addresses.encryptor_api.clone(),
+            api_access_control,
+            Arc::new(AllowAll),
+        );
+        let internal_mailbox = Mailbox::new(
+            addresses.encryptor_internal.clone(),
+            Arc::new(AllowAll),
+            Arc::new(DenyAll),
+        );
+
+        WorkerBuilder::new(encryptor)
+            .with_mailboxes(Mailboxes::new(
+                main_mailbox,
+                vec![api_mailbox, internal_mailbox],
+            ))
+            .terminal_with_attributes(
+                addresses.encryptor.clone(),
+                vec![(
+                    IDENTITY_SECURE_CHANNEL_IDENTIFIER.to_string(),
+                    their_identifier.to_string(),
+                )],
+            )
+            .start(context)
+            .await
+    }
+
+    async fn persist(
+        &self,
+        their_identifier: Identifier,
+        decryption_key: &AeadSecretKeyHandle,
+        encryption_key: &AeadSecretKeyHandle,
+        their_decryptor_address: &Address,
+    ) {
         let Some(repository) = &self.secure_channel_repository else {
             info!(
                 "Skipping persistence. Local: {}, Remote: {}",
@@ -500,7 +657,7 @@ impl HandshakeWorker {
             return;
         };
 
-        let sc = PersistedSecureChannel::new(
+        let mut sc = PersistedSecureChannel::new(
             self.role,
             self.my_identifier.clone(),
             their_identifier,
@@ -508,6 +665,18 @@ impl HandshakeWorker {
             self.addresses.decryptor_api.clone(),
             decryption_key.clone(),
         );
+        let mut keys = vec![decryption_key];
+        if !self.key_exchange_only {
+            sc = sc.with_resumable_state(ResumableChannelState::new(
+                self.addresses.encryptor.clone(),
+                self.addresses.encryptor_api.clone(),
+                their_decryptor_address.clone(),
+                encryption_key.clone(),
+                0.into(),
+                0.into(),
+            ));
+            keys.push(encryption_key);
+        }
         match repository.put(sc).await {
             Ok(_) => {
                 info!(
@@ -525,19 +694,21 @@ impl HandshakeWorker {
             }
         }
 
-        if let Err(err) = self
-            .secure_channels
-            .identities
-            .vault()
-            .secure_channel_vault
-            .persist_aead_key(decryption_key)
-            .await
-        {
-            warn!(
-                "Error persisting secure channel key: {err}. Local: {}, Remote: {}",
-                self.addresses.encryptor, &self.addresses.decryptor_remote
-            );
-        };
+        for key in keys {
+            if let Err(err) = self
+                .secure_channels
+                .identities
+                .vault()
+                .secure_channel_vault
+                .persist_aead_key(key)
+                .await
+            {
+                warn!(
+                    "Error persisting secure channel key: {err}. Local: {}, Remote: {}",
+                    self.addresses.encryptor, &self.addresses.decryptor_remote
+                );
+            };
+        }
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -557,6 +728,9 @@ impl HandshakeWorker {
         secure_channel_repository: Option<Arc<dyn SecureChannelRepository>>,
         shared_state: SecureChannelSharedState,
     ) -> Self {
+        let handshake_diagnostics = secure_channels
+            .secure_channel_registry
+            .handshake_diagnostics();
         Self {
             secure_channels,
             callback_sender,
@@ -565,6 +739,11 @@ impl HandshakeWorker {
             addresses,
             role,
             key_exchange_only,
+            // these options are only used once the handshake is finalized, which never happens
+            // for the persisted secure channels
+            rekey_policy: RekeyPolicy::default(),
+            nonce_window_size: DEFAULT_NONCE_WINDOW_SIZE,
+            padding: None,
             remote_route,
             decryptor_handler,
             authority,
@@ -572,6 +751,11 @@ impl HandshakeWorker {
             credential_retriever,
             secure_channel_repository,
             shared_state,
+            // the encryptor of the persisted secure channels is started with its own mailboxes
+            api_access_control: Arc::new(AllowAll),
+            handshake_diagnostics,
+            handshake_timer: HandshakeTimer::start(),
+            handshake_failed: Arc::new(AtomicBool::new(false)),
         }
     }
 }
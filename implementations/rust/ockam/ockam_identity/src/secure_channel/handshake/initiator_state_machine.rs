@@ -17,7 +17,11 @@ use crate::secure_channel::handshake::handshake_state_machine::{
     Action, CommonStateMachine, Event, HandshakeKeys, HandshakeResults, IdentityAndCredentials,
     StateMachine, Status,
 };
-use crate::{CredentialRetriever, Identities, Role, SecureChannelPurposeKey, TrustPolicy};
+use crate::secure_channel::KeyAgreement;
+use crate::{
+    CipherSuite, CredentialRetriever, HandshakeFailureReason, Identities, KeyAgreementPolicy, Role,
+    SecureChannelPurposeKey, TrustPolicy,
+};
 
 /// Implementation of a state machine for the key exchange on the initiator side
 #[async_trait]
@@ -28,7 +32,8 @@ impl StateMachine for InitiatorStateMachine {
             // Initialize the handshake and send message 1
             (Initial, Initialize) => {
                 self.initialize_handshake().await?;
-                let message1 = self.encode_message1(&[]).await?;
+                let key_agreement_offer = self.key_agreement.make_offer()?;
+                let message1 = self.encode_message1(&key_agreement_offer).await?;
 
                 // Send message 1 and wait for message 2
                 self.handshake.state.status = WaitingForMessage2;
@@ -37,8 +42,17 @@ impl StateMachine for InitiatorStateMachine {
             // Process message 2 and send message 3
             (WaitingForMessage2, ReceivedMessage(message)) => {
                 let message2_payload = self.decode_message2(&message).await?;
-                let their_identity_payload: IdentityAndCredentials =
+                let mut their_identity_payload: IdentityAndCredentials =
                     minicbor::decode(&message2_payload)?;
+                let kem_ciphertext = their_identity_payload.kem_ciphertext.take();
+                self.key_agreement
+                    .accept_cipher_suite(their_identity_payload.cipher_suite)?;
+                if let Some(shared_secret) = self
+                    .key_agreement
+                    .accept_response(kem_ciphertext.as_deref().map(Vec::as_slice))?
+                {
+                    self.handshake.mix_kem_secret(&shared_secret).await?;
+                }
                 self.process_identity_payload(
                     their_identity_payload,
                     self.handshake.state.rs()?.clone(),
@@ -46,11 +60,12 @@ impl StateMachine for InitiatorStateMachine {
                 .await?;
                 let identity_payload = self
                     .common
-                    .make_identity_payload()
+                    .make_identity_payload(None, None)
                     .await
                     .map_err(|_e| XXError::InvalidInternalState)?;
                 let message3 = self.encode_message3(&identity_payload).await?;
-                self.set_final_state(Initiator).await?;
+                self.set_final_state(Initiator, self.key_agreement.cipher_suite())
+                    .await?;
                 Ok(SendMessage(message3))
             }
             // incorrect state / event
@@ -68,12 +83,17 @@ impl StateMachine for InitiatorStateMachine {
     fn get_handshake_results(&self) -> Option<HandshakeResults> {
         self.make_handshake_results(self.get_handshake_keys())
     }
+
+    fn failure_reason(&self) -> Option<HandshakeFailureReason> {
+        self.common.failure_reason()
+    }
 }
 
 /// Implementation of the state machine actions, delegated to the Handshake module
 pub(super) struct InitiatorStateMachine {
     pub(super) common: CommonStateMachine,
     pub(super) handshake: Handshake,
+    pub(super) key_agreement: KeyAgreement,
 }
 
 impl InitiatorStateMachine {
@@ -90,13 +110,14 @@ impl InitiatorStateMachine {
             async fn encode_message1(&mut self, payload: &[u8]) -> Result<Vec<u8>>;
             async fn decode_message2(&mut self, message: &[u8]) -> Result<Vec<u8>>;
             async fn encode_message3(&mut self, payload: &[u8]) -> Result<Vec<u8>>;
-            async fn set_final_state(&mut self, role: Role) -> Result<()>;
+            async fn set_final_state(&mut self, role: Role, cipher_suite: CipherSuite) -> Result<()>;
             fn get_handshake_keys(&self) -> Option<HandshakeKeys>;
         }
     }
 }
 
 impl InitiatorStateMachine {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         vault: Arc<dyn VaultForSecureChannels>,
         identities: Arc<Identities>,
@@ -105,6 +126,8 @@ impl InitiatorStateMachine {
         credential_retriever: Option<Arc<dyn CredentialRetriever>>,
         trust_policy: Arc<dyn TrustPolicy>,
         authority: Option<Identifier>,
+        key_agreement_policy: KeyAgreementPolicy,
+        cipher_suites: Vec<CipherSuite>,
     ) -> Result<InitiatorStateMachine> {
         let common = CommonStateMachine::new(
             identities,
@@ -118,6 +141,7 @@ impl InitiatorStateMachine {
         Ok(InitiatorStateMachine {
             common,
             handshake: Handshake::new(vault, purpose_key.key().clone()).await?,
+            key_agreement: KeyAgreement::new(key_agreement_policy, cipher_suites),
         })
     }
 }
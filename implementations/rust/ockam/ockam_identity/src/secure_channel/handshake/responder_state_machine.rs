@@ -17,7 +17,11 @@ use crate::secure_channel::handshake::handshake_state_machine::{
     Action, CommonStateMachine, Event, HandshakeKeys, HandshakeResults, IdentityAndCredentials,
     StateMachine, Status,
 };
-use crate::{CredentialRetriever, Identities, Role, SecureChannelPurposeKey, TrustPolicy};
+use crate::secure_channel::KeyAgreement;
+use crate::{
+    CipherSuite, CredentialRetriever, HandshakeFailureReason, Identities, KeyAgreementPolicy, Role,
+    SecureChannelPurposeKey, TrustPolicy,
+};
 
 /// Implementation of a state machine for the key exchange on the responder side
 #[async_trait]
@@ -33,13 +37,21 @@ impl StateMachine for ResponderStateMachine {
             }
             // Process message 1 and send message 2
             (WaitingForMessage1, ReceivedMessage(message)) => {
-                self.decode_message1(&message).await?;
+                let key_agreement_offer = self.decode_message1(&message).await?;
+                let kem = self.key_agreement.accept_offer(&key_agreement_offer)?;
+                let (kem_ciphertext, kem_secret) = match kem {
+                    Some((ciphertext, shared_secret)) => (Some(ciphertext), Some(shared_secret)),
+                    None => (None, None),
+                };
                 let identity_payload = self
                     .common
-                    .make_identity_payload()
+                    .make_identity_payload(kem_ciphertext, Some(self.key_agreement.cipher_suite()))
                     .await
                     .map_err(|_e| XXError::InvalidInternalState)?;
                 let message2 = self.encode_message2(&identity_payload).await?;
+                if let Some(shared_secret) = kem_secret {
+                    self.handshake.mix_kem_secret(&shared_secret).await?;
+                }
 
                 self.handshake.state.status = WaitingForMessage3;
                 Ok(SendMessage(message2))
@@ -54,7 +66,8 @@ impl StateMachine for ResponderStateMachine {
                     self.handshake.state.rs()?.clone(),
                 )
                 .await?;
-                self.set_final_state(Responder).await?;
+                self.set_final_state(Responder, self.key_agreement.cipher_suite())
+                    .await?;
                 Ok(NoAction)
             }
             // incorrect state / event
@@ -72,11 +85,16 @@ impl StateMachine for ResponderStateMachine {
     fn get_handshake_results(&self) -> Option<HandshakeResults> {
         self.make_handshake_results(self.get_handshake_keys())
     }
+
+    fn failure_reason(&self) -> Option<HandshakeFailureReason> {
+        self.common.failure_reason()
+    }
 }
 
 pub struct ResponderStateMachine {
     common: CommonStateMachine,
     handshake: Handshake,
+    key_agreement: KeyAgreement,
 }
 
 impl ResponderStateMachine {
@@ -93,13 +111,14 @@ impl ResponderStateMachine {
             async fn decode_message1(&mut self, message: &[u8]) -> Result<Vec<u8>>;
             async fn encode_message2(&mut self, payload: &[u8]) -> Result<Vec<u8>>;
             async fn decode_message3(&mut self, message: &[u8]) -> Result<Vec<u8>>;
-            async fn set_final_state(&mut self, role: Role) -> Result<()>;
+            async fn set_final_state(&mut self, role: Role, cipher_suite: CipherSuite) -> Result<()>;
             fn get_handshake_keys(&self) -> Option<HandshakeKeys>;
         }
     }
 }
 
 impl ResponderStateMachine {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         vault: Arc<dyn VaultForSecureChannels>,
         identities: Arc<Identities>,
@@ -108,6 +127,8 @@ impl ResponderStateMachine {
         credential_retriever: Option<Arc<dyn CredentialRetriever>>,
         trust_policy: Arc<dyn TrustPolicy>,
         authority: Option<Identifier>,
+        key_agreement_policy: KeyAgreementPolicy,
+        cipher_suites: Vec<CipherSuite>,
     ) -> Result<ResponderStateMachine> {
         let common = CommonStateMachine::new(
             identities,
@@ -121,6 +142,7 @@ impl ResponderStateMachine {
         Ok(ResponderStateMachine {
             common,
             handshake: Handshake::new(vault, purpose_key.key().clone()).await?,
+            key_agreement: KeyAgreement::new(key_agreement_policy, cipher_suites),
         })
     }
 }
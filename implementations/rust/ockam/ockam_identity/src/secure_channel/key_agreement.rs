@@ -0,0 +1,340 @@
+use minicbor::bytes::ByteVec;
+use minicbor::{Decode, Encode};
+use ml_kem::kem::{Decapsulate, Encapsulate};
+use ml_kem::{Ciphertext, Encoded, EncodedSizeUser, KemCore, MlKem768};
+use ockam_core::compat::rand::thread_rng;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+use tracing::{debug, warn};
+use zeroize::Zeroizing;
+
+use crate::{CipherSuite, IdentityError};
+
+/// Key agreement algorithms which can be negotiated during a secure channel handshake
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+#[cbor(index_only)]
+#[rustfmt::skip]
+pub enum KeyAgreementAlgorithm {
+    /// Elliptic curve Diffie-Hellman only, as specified by the Noise XX protocol
+    #[n(0)] X25519,
+    /// Elliptic curve Diffie-Hellman combined with a ML-KEM-768 (FIPS 203) key encapsulation,
+    /// protecting the channel keys against an attacker with a quantum computer
+    #[n(1)] X25519MlKem768,
+}
+
+/// Policy used to negotiate the key agreement algorithm of a secure channel
+///
+/// The hybrid X25519+ML-KEM-768 key agreement protects the recorded traffic of a
+/// secure channel against "harvest now, decrypt later" attacks, at the cost of
+/// larger handshake messages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyAgreementPolicy {
+    /// Only use the X25519 key agreement. This is compatible with all the versions of the
+    /// secure channel protocol
+    #[default]
+    X25519Only,
+    /// Use the hybrid X25519+ML-KEM-768 key agreement when the other party supports it,
+    /// and fall back to the X25519 key agreement otherwise
+    PreferHybrid,
+    /// Use the hybrid X25519+ML-KEM-768 key agreement and fail the handshake if the
+    /// other party does not support it
+    RequireHybrid,
+}
+
+impl KeyAgreementPolicy {
+    fn accepts_hybrid(&self) -> bool {
+        !matches!(self, KeyAgreementPolicy::X25519Only)
+    }
+
+    fn requires_hybrid(&self) -> bool {
+        matches!(self, KeyAgreementPolicy::RequireHybrid)
+    }
+}
+
+/// Payload of the first handshake message, sent by the initiator to offer
+/// the key agreement algorithms and the cipher suites it supports.
+///
+/// The payload is empty when the initiator only supports X25519 and AES-256-GCM, which is what
+/// the previous versions of the protocol send.
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+struct KeyAgreementOffer {
+    #[n(0)] algorithms: Vec<KeyAgreementAlgorithm>,
+    /// Ephemeral ML-KEM-768 public key, used by the responder to encapsulate a shared secret
+    #[n(1)] kem_public_key: Option<ByteVec>,
+    /// Cipher suites supported by the initiator, in order of preference
+    #[n(2)] cipher_suites: Option<Vec<CipherSuite>>,
+}
+
+/// Shared secret resulting from a ML-KEM-768 key encapsulation.
+/// It is mixed in the handshake chaining key, in addition to the Diffie-Hellman secrets
+pub(crate) type KemSharedSecret = Zeroizing<Vec<u8>>;
+
+type KemEncapsulationKey = <MlKem768 as KemCore>::EncapsulationKey;
+type KemDecapsulationKey = <MlKem768 as KemCore>::DecapsulationKey;
+
+/// State of the key agreement negotiation for one side of a handshake
+pub(crate) struct KeyAgreement {
+    policy: KeyAgreementPolicy,
+    /// Cipher suites accepted to encrypt the channel messages, in order of preference
+    cipher_suites: Vec<CipherSuite>,
+    /// Cipher suite selected at the end of the negotiation
+    cipher_suite: CipherSuite,
+    /// Ephemeral ML-KEM-768 decapsulation key of the initiator, deleted (and zeroized) once
+    /// the shared secret is computed
+    kem_secret_key: Option<KemDecapsulationKey>,
+}
+
+impl KeyAgreement {
+    /// Create a new negotiation with a given policy and a list of accepted cipher suites
+    pub(crate) fn new(policy: KeyAgreementPolicy, cipher_suites: Vec<CipherSuite>) -> Self {
+        Self {
+            policy,
+            cipher_suites,
+            cipher_suite: CipherSuite::default(),
+            kem_secret_key: None,
+        }
+    }
+
+    /// Return the cipher suite selected during the negotiation
+    pub(crate) fn cipher_suite(&self) -> CipherSuite {
+        self.cipher_suite
+    }
+
+    /// Initiator: make the payload of message 1, offering the hybrid key agreement if
+    /// the policy allows it, and the accepted cipher suites if other suites than AES-256-GCM
+    /// are accepted
+    pub(crate) fn make_offer(&mut self) -> Result<Vec<u8>> {
+        let offers_cipher_suites = self.cipher_suites != [CipherSuite::Aes256Gcm];
+        if !self.policy.accepts_hybrid() && !offers_cipher_suites {
+            return Ok(Vec::new());
+        }
+
+        let (algorithms, kem_public_key) = if self.policy.accepts_hybrid() {
+            let (decapsulation_key, encapsulation_key) = MlKem768::generate(&mut thread_rng());
+            self.kem_secret_key = Some(decapsulation_key);
+            (
+                vec![
+                    KeyAgreementAlgorithm::X25519MlKem768,
+                    KeyAgreementAlgorithm::X25519,
+                ],
+                Some(encapsulation_key.as_bytes().to_vec().into()),
+            )
+        } else {
+            (vec![KeyAgreementAlgorithm::X25519], None)
+        };
+
+        let offer = KeyAgreementOffer {
+            algorithms,
+            kem_public_key,
+            cipher_suites: offers_cipher_suites.then(|| self.cipher_suites.clone()),
+        };
+        Ok(minicbor::to_vec(offer)?)
+    }
+
+    /// Responder: select an algorithm and a cipher suite based on the payload of message 1.
+    /// If the hybrid key agreement is selected, return the ML-KEM-768 ciphertext to send
+    /// in message 2 and the shared secret to mix in the handshake
+    pub(crate) fn accept_offer(
+        &mut self,
+        payload: &[u8],
+    ) -> Result<Option<(Vec<u8>, KemSharedSecret)>> {
+        let offer: Option<KeyAgreementOffer> = if payload.is_empty() {
+            None
+        } else {
+            Some(minicbor::decode(payload)?)
+        };
+
+        // select the first cipher suite of the initiator which is accepted by the responder.
+        // An initiator which does not offer any cipher suite only supports AES-256-GCM
+        let offered_cipher_suites = offer
+            .as_ref()
+            .and_then(|offer| offer.cipher_suites.clone())
+            .unwrap_or_else(|| vec![CipherSuite::Aes256Gcm]);
+        let Some(cipher_suite) = offered_cipher_suites
+            .into_iter()
+            .find(|cipher_suite| self.cipher_suites.contains(cipher_suite))
+        else {
+            return Err(IdentityError::NoCommonCipherSuite)?;
+        };
+        self.select_cipher_suite(cipher_suite);
+
+        let kem_public_key = offer.and_then(|offer| {
+            if offer
+                .algorithms
+                .contains(&KeyAgreementAlgorithm::X25519MlKem768)
+            {
+                offer.kem_public_key
+            } else {
+                None
+            }
+        });
+
+        match kem_public_key {
+            Some(kem_public_key) if self.policy.accepts_hybrid() => {
+                let kem_public_key =
+                    Encoded::<KemEncapsulationKey>::try_from(kem_public_key.as_slice())
+                        .map_err(|_| IdentityError::KeyAgreementFailed)?;
+                let (ciphertext, shared_secret) = KemEncapsulationKey::from_bytes(&kem_public_key)
+                    .encapsulate(&mut thread_rng())
+                    .map_err(|_| IdentityError::KeyAgreementFailed)?;
+                self.select(KeyAgreementAlgorithm::X25519MlKem768);
+                Ok(Some((
+                    ciphertext.to_vec(),
+                    Zeroizing::new(shared_secret.to_vec()),
+                )))
+            }
+            _ => {
+                self.fall_back()?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Initiator: compute the shared secret from the ML-KEM-768 ciphertext sent in message 2,
+    /// if the responder selected the hybrid key agreement
+    pub(crate) fn accept_response(
+        &mut self,
+        kem_ciphertext: Option<&[u8]>,
+    ) -> Result<Option<KemSharedSecret>> {
+        let secret_key = self.kem_secret_key.take();
+        match (kem_ciphertext, secret_key) {
+            (Some(kem_ciphertext), Some(secret_key)) => {
+                let kem_ciphertext = Ciphertext::<MlKem768>::try_from(kem_ciphertext)
+                    .map_err(|_| IdentityError::KeyAgreementFailed)?;
+                let shared_secret = secret_key
+                    .decapsulate(&kem_ciphertext)
+                    .map_err(|_| IdentityError::KeyAgreementFailed)?;
+                self.select(KeyAgreementAlgorithm::X25519MlKem768);
+                Ok(Some(Zeroizing::new(shared_secret.to_vec())))
+            }
+            // we never offered a hybrid key agreement
+            (Some(_), None) => Err(IdentityError::KeyAgreementFailed.into()),
+            (None, _) => {
+                self.fall_back()?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Initiator: check the cipher suite selected by the responder in message 2.
+    /// A responder which does not send a cipher suite only supports AES-256-GCM
+    pub(crate) fn accept_cipher_suite(&mut self, cipher_suite: Option<CipherSuite>) -> Result<()> {
+        let cipher_suite = cipher_suite.unwrap_or(CipherSuite::Aes256Gcm);
+        if !self.cipher_suites.contains(&cipher_suite) {
+            return Err(IdentityError::NoCommonCipherSuite)?;
+        }
+        self.select_cipher_suite(cipher_suite);
+        Ok(())
+    }
+
+    fn select(&self, algorithm: KeyAgreementAlgorithm) {
+        debug!("selected the {:?} key agreement", algorithm);
+    }
+
+    fn select_cipher_suite(&mut self, cipher_suite: CipherSuite) {
+        debug!("selected the {} cipher suite", cipher_suite);
+        self.cipher_suite = cipher_suite;
+    }
+
+    /// Use the X25519 key agreement, unless the policy requires a hybrid key agreement
+    fn fall_back(&self) -> Result<()> {
+        if self.policy.requires_hybrid() {
+            return Err(IdentityError::HybridKeyAgreementRequired)?;
+        }
+        if self.policy.accepts_hybrid() {
+            warn!("the hybrid key agreement is not supported by the other party, using X25519");
+        }
+        self.select(KeyAgreementAlgorithm::X25519);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hybrid_negotiation() -> Result<()> {
+        let mut initiator = KeyAgreement::new(KeyAgreementPolicy::PreferHybrid, aes());
+        let mut responder = KeyAgreement::new(KeyAgreementPolicy::PreferHybrid, aes());
+
+        let offer = initiator.make_offer()?;
+        let (ciphertext, responder_secret) = responder.accept_offer(&offer)?.unwrap();
+        let initiator_secret = initiator.accept_response(Some(&ciphertext))?.unwrap();
+
+        assert_eq!(initiator_secret, responder_secret);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fallback() -> Result<()> {
+        // the responder does not accept the hybrid key agreement
+        let mut initiator = KeyAgreement::new(KeyAgreementPolicy::PreferHybrid, aes());
+        let mut responder = KeyAgreement::new(KeyAgreementPolicy::X25519Only, aes());
+
+        let offer = initiator.make_offer()?;
+        assert!(responder.accept_offer(&offer)?.is_none());
+        assert!(initiator.accept_response(None)?.is_none());
+
+        // the initiator does not offer the hybrid key agreement
+        let mut initiator = KeyAgreement::new(KeyAgreementPolicy::X25519Only, aes());
+        let mut responder = KeyAgreement::new(KeyAgreementPolicy::RequireHybrid, aes());
+
+        let offer = initiator.make_offer()?;
+        assert!(offer.is_empty());
+        assert!(responder.accept_offer(&offer).is_err());
+
+        // the responder does not answer with a ciphertext
+        let mut initiator = KeyAgreement::new(KeyAgreementPolicy::RequireHybrid, aes());
+        initiator.make_offer()?;
+        assert!(initiator.accept_response(None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_cipher_suite_negotiation() -> Result<()> {
+        let chacha_first = vec![CipherSuite::ChaCha20Poly1305, CipherSuite::Aes256Gcm];
+
+        // the first cipher suite of the initiator accepted by the responder is selected
+        let mut initiator = KeyAgreement::new(KeyAgreementPolicy::X25519Only, chacha_first.clone());
+        let mut responder = KeyAgreement::new(
+            KeyAgreementPolicy::X25519Only,
+            vec![CipherSuite::Aes256Gcm, CipherSuite::ChaCha20Poly1305],
+        );
+        let offer = initiator.make_offer()?;
+        assert!(responder.accept_offer(&offer)?.is_none());
+        assert_eq!(responder.cipher_suite(), CipherSuite::ChaCha20Poly1305);
+        initiator.accept_cipher_suite(Some(responder.cipher_suite()))?;
+        assert_eq!(initiator.cipher_suite(), CipherSuite::ChaCha20Poly1305);
+
+        // a responder which only supports AES-256-GCM does not send a cipher suite
+        let mut initiator = KeyAgreement::new(KeyAgreementPolicy::X25519Only, chacha_first);
+        initiator.make_offer()?;
+        initiator.accept_cipher_suite(None)?;
+        assert_eq!(initiator.cipher_suite(), CipherSuite::Aes256Gcm);
+
+        // an initiator which does not offer any cipher suite only supports AES-256-GCM
+        let mut initiator = KeyAgreement::new(KeyAgreementPolicy::X25519Only, aes());
+        let mut responder = KeyAgreement::new(
+            KeyAgreementPolicy::X25519Only,
+            vec![CipherSuite::ChaCha20Poly1305],
+        );
+        let offer = initiator.make_offer()?;
+        assert!(offer.is_empty());
+        assert!(responder.accept_offer(&offer).is_err());
+
+        // the initiator rejects a cipher suite it did not offer
+        let mut initiator = KeyAgreement::new(
+            KeyAgreementPolicy::X25519Only,
+            vec![CipherSuite::ChaCha20Poly1305],
+        );
+        initiator.make_offer()?;
+        assert!(initiator.accept_cipher_suite(None).is_err());
+        Ok(())
+    }
+
+    fn aes() -> Vec<CipherSuite> {
+        vec![CipherSuite::Aes256Gcm]
+    }
+}
@@ -1,3 +1,4 @@
+use ockam_core::compat::collections::VecDeque;
 use ockam_core::Result;
 use ockam_vault::AeadSecretKeyHandle;
 use tracing::{trace, warn};
@@ -7,22 +8,46 @@ use crate::{IdentityError, Nonce};
 
 pub(crate) struct KeyTracker {
     pub(crate) current_key: AeadSecretKeyHandle,
-    pub(crate) previous_key: Option<AeadSecretKeyHandle>,
+    /// Keys of the previous intervals, from the most recent one to the oldest one
+    pub(crate) previous_keys: VecDeque<AeadSecretKeyHandle>,
+    /// Number of previous keys kept to decrypt messages received out of order
+    max_previous_keys: usize,
     number_of_rekeys: u64,
     max_rekeys_reached: bool,
     renewal_interval: u64,
 }
 
 impl KeyTracker {
-    pub(crate) fn new(current_key: AeadSecretKeyHandle, renewal_interval: u64) -> Self {
+    /// Create a key tracker keeping enough previous keys to decrypt the messages
+    /// received up to `nonce_window_size` nonces late
+    pub(crate) fn new(
+        current_key: AeadSecretKeyHandle,
+        renewal_interval: u64,
+        nonce_window_size: u64,
+    ) -> Self {
         KeyTracker {
             current_key,
             number_of_rekeys: 0,
             max_rekeys_reached: false,
-            previous_key: None,
+            previous_keys: VecDeque::new(),
+            max_previous_keys: ((nonce_window_size + renewal_interval - 1) / renewal_interval)
+                .max(1) as usize,
             renewal_interval,
         }
     }
+
+    /// Create a key tracker for a key which was obtained after a given number of rekeys
+    pub(crate) fn resume(
+        current_key: AeadSecretKeyHandle,
+        renewal_interval: u64,
+        nonce_window_size: u64,
+        number_of_rekeys: u64,
+    ) -> Self {
+        KeyTracker {
+            number_of_rekeys,
+            ..Self::new(current_key, renewal_interval, nonce_window_size)
+        }
+    }
 }
 
 impl KeyTracker {
@@ -33,11 +58,11 @@ impl KeyTracker {
     ///
     /// This is either:
     ///   - the current key if the nonce falls into the current interval
-    ///   - the previous key if the nonce falls before the current interval
+    ///   - a previous key if the nonce falls in one of the previous intervals which keys are kept
     ///   - nothing if the the nonce falls after the current interval -> this indicates that a new key must be created
     ///   - an error if
-    ///      - if the the nonce falls before the previous interval
-    ///      - if it the previous nonce but is not set
+    ///      - if the the nonce falls before the oldest previous interval
+    ///      - if the key of the previous interval is not set
     ///      - we reached the maximum number of rekeyings
     #[instrument(skip_all)]
     pub(crate) fn get_key(&self, nonce: Nonce) -> Result<Option<AeadSecretKeyHandle>> {
@@ -74,17 +99,21 @@ impl KeyTracker {
                 warn!("This nonce is too far in the future: {}", nonce);
                 Err(IdentityError::InvalidNonce)?
             }
-        // else return the previous key (if there is one) if the nonce is not too old
-        } else if current_interval_start - nonce.value() <= self.renewal_interval {
-            if let Some(previous) = self.previous_key.clone() {
-                Ok(Some(previous))
+        // else return a previous key (if there is one) if the nonce is not too old
+        } else {
+            // 0 for the previous interval, 1 for the one before, etc...
+            let intervals_ago =
+                (current_interval_start - nonce.value() - 1) / self.renewal_interval;
+            if intervals_ago >= self.max_previous_keys as u64 {
+                warn!("This nonce is too old: {}", nonce);
+                return Err(IdentityError::InvalidNonce)?;
+            }
+            if let Some(previous) = self.previous_keys.get(intervals_ago as usize) {
+                Ok(Some(previous.clone()))
             } else {
                 warn!("There should be a previous key for this nonce: {}", nonce);
                 Err(IdentityError::InvalidNonce)?
             }
-        } else {
-            warn!("This nonce is too old: {}", nonce);
-            Err(IdentityError::InvalidNonce)?
         }
     }
 
@@ -95,13 +124,13 @@ impl KeyTracker {
         decryption_key: AeadSecretKeyHandle,
     ) -> Result<Option<AeadSecretKeyHandle>> {
         let mut key_to_delete = None;
-        // if the key used for the decryption is not the current key nor the previous key
+        // if the key used for the decryption is not the current key nor a previous key
         // this means that a rekeying happened
-        if decryption_key != self.current_key && Some(decryption_key.clone()) != self.previous_key {
-            if let Some(previous) = self.previous_key.clone() {
-                key_to_delete = Some(previous)
+        if decryption_key != self.current_key && !self.previous_keys.contains(&decryption_key) {
+            self.previous_keys.push_front(self.current_key.clone());
+            if self.previous_keys.len() > self.max_previous_keys {
+                key_to_delete = self.previous_keys.pop_back();
             }
-            self.previous_key.replace(self.current_key.clone());
             self.current_key = decryption_key;
             if u64::MAX - self.number_of_rekeys * self.renewal_interval < self.renewal_interval {
                 self.max_rekeys_reached = true;
@@ -123,7 +152,7 @@ mod tests {
     fn test_get_key_first_interval() {
         let handle = b"handle".to_vec();
         let handle = AeadSecretKeyHandle(Aes256GcmSecretKeyHandle(HandleToSecret::new(handle)));
-        let key_tracker = KeyTracker::new(handle.clone(), 10);
+        let key_tracker = KeyTracker::new(handle.clone(), 10, 10);
 
         assert_eq!(key_tracker.get_key(0.into()).unwrap(), Some(handle.clone()));
         assert_eq!(key_tracker.get_key(5.into()).unwrap(), Some(handle.clone()));
@@ -157,7 +186,8 @@ mod tests {
             current_key: handle.clone(),
             number_of_rekeys: 5,
             max_rekeys_reached: false,
-            previous_key: Some(previous_handle.clone()),
+            previous_keys: VecDeque::from([previous_handle.clone()]),
+            max_previous_keys: 1,
             renewal_interval: 10,
         };
 
@@ -205,6 +235,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_key_with_several_previous_keys() {
+        let handle = b"handle".to_vec();
+        let handle = AeadSecretKeyHandle(Aes256GcmSecretKeyHandle(HandleToSecret::new(handle)));
+        let previous_handle = b"previous_handle".to_vec();
+        let previous_handle = AeadSecretKeyHandle(Aes256GcmSecretKeyHandle(HandleToSecret::new(
+            previous_handle,
+        )));
+        let older_handle = b"older_handle".to_vec();
+        let older_handle =
+            AeadSecretKeyHandle(Aes256GcmSecretKeyHandle(HandleToSecret::new(older_handle)));
+        let mut key_tracker = KeyTracker::new(older_handle.clone(), 10, 15);
+        key_tracker.update_key(previous_handle.clone()).unwrap();
+        key_tracker.update_key(handle.clone()).unwrap();
+
+        assert_eq!(
+            key_tracker.get_key(0.into()).unwrap(),
+            Some(older_handle.clone())
+        );
+        assert_eq!(
+            key_tracker.get_key(15.into()).unwrap(),
+            Some(previous_handle)
+        );
+        assert_eq!(key_tracker.get_key(25.into()).unwrap(), Some(handle));
+
+        // a new key removes the oldest key
+        let new_handle = b"new_handle".to_vec();
+        let new_handle =
+            AeadSecretKeyHandle(Aes256GcmSecretKeyHandle(HandleToSecret::new(new_handle)));
+        assert_eq!(
+            key_tracker.update_key(new_handle).unwrap(),
+            Some(older_handle),
+            "the oldest key must be returned in order to be deleted",
+        );
+        assert_eq!(
+            key_tracker.get_key(9.into()).ok(),
+            None,
+            "this nonce is too far in the past"
+        );
+    }
+
     #[test]
     fn test_get_key_last_interval() {
         let handle = b"handle".to_vec();
@@ -217,7 +288,8 @@ mod tests {
             current_key: handle,
             number_of_rekeys: 5,
             max_rekeys_reached: true,
-            previous_key: Some(previous_handle),
+            previous_keys: VecDeque::from([previous_handle]),
+            max_previous_keys: 1,
             renewal_interval: 10,
         };
 
@@ -243,7 +315,8 @@ mod tests {
             current_key: handle.clone(),
             number_of_rekeys: 5,
             max_rekeys_reached: false,
-            previous_key: Some(previous_handle.clone()),
+            previous_keys: VecDeque::from([previous_handle.clone()]),
+            max_previous_keys: 1,
             renewal_interval: 10,
         };
 
@@ -258,7 +331,7 @@ mod tests {
             "the previous key id must be returned in order to be deleted",
         );
         assert_eq!(key_tracker.current_key, new_handle);
-        assert_eq!(key_tracker.previous_key, Some(handle));
+        assert_eq!(key_tracker.previous_keys, VecDeque::from([handle]));
     }
 
     #[test]
@@ -276,7 +349,8 @@ mod tests {
             current_key: handle,
             number_of_rekeys: u64::MAX / 10 - 1,
             max_rekeys_reached: false,
-            previous_key: Some(previous_handle),
+            previous_keys: VecDeque::from([previous_handle]),
+            max_previous_keys: 1,
             renewal_interval: 10,
         };
 
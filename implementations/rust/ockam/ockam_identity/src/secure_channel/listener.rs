@@ -111,6 +111,12 @@ impl Worker for SecureChannelListenerWorker {
             None,
             Role::Responder,
             self.options.key_exchange_only,
+            self.options.key_agreement_policy,
+            self.options.cipher_suites.clone(),
+            self.options.rekey_policy,
+            self.options.nonce_window_size,
+            self.options.padding.clone(),
+            self.options.api_access_control.clone(),
             self.secure_channel_repository.clone(),
             RemoteRoute::create(),
         )
@@ -2,10 +2,13 @@
 pub mod access_control;
 mod addresses;
 mod api;
+mod cipher_suite;
 mod decryptor;
+mod diagnostics;
 mod encryptor;
 mod encryptor_worker;
 pub(crate) mod handshake;
+mod key_agreement;
 mod key_tracker;
 mod listener;
 mod local_info;
@@ -13,7 +16,9 @@ mod message;
 mod nonce;
 mod nonce_tracker;
 mod options;
+mod padding;
 mod registry;
+mod resumption;
 mod role;
 
 /// List of trust policies to setup ABAC controls
@@ -22,24 +27,39 @@ pub mod trust_policy;
 pub use access_control::*;
 pub(crate) use addresses::*;
 pub use api::*;
+pub use cipher_suite::*;
 pub(crate) use decryptor::*;
+pub use diagnostics::*;
+pub(crate) use encryptor::Encryptor;
 pub(crate) use encryptor_worker::*;
 pub(crate) use handshake::*;
+pub(crate) use key_agreement::KeyAgreement;
+pub use key_agreement::{KeyAgreementAlgorithm, KeyAgreementPolicy};
 pub(crate) use listener::*;
 pub use local_info::*;
 pub use message::*;
 pub use nonce::*;
+pub(crate) use nonce_tracker::DEFAULT_NONCE_WINDOW_SIZE;
 pub use options::*;
+pub use padding::*;
 pub use registry::*;
+pub(crate) use resumption::*;
 pub(crate) use role::*;
 pub use trust_policy::*;
 
 #[cfg(test)]
 mod tests {
-    use crate::secure_channel::{decryptor::Decryptor, encryptor::Encryptor};
+    use crate::secure_channel::decryptor::Decryptor;
+    use crate::secure_channel::encryptor::{Encryptor, RekeyPolicy};
+    use crate::secure_channel::nonce_tracker::DEFAULT_NONCE_WINDOW_SIZE;
+    use crate::Nonce;
+    use core::time::Duration;
     use ockam_core::compat::rand::RngCore;
+    use ockam_core::compat::sync::Arc;
     use ockam_core::Result;
-    use ockam_vault::{SoftwareVaultForSecureChannels, VaultForSecureChannels};
+    use ockam_vault::{
+        AeadAlgorithm, AeadSecretKeyHandle, SoftwareVaultForSecureChannels, VaultForSecureChannels,
+    };
     use rand::seq::SliceRandom;
     use rand::thread_rng;
 
@@ -55,6 +75,29 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_encrypt_decrypt_chacha20_poly1305() {
+        let (vault1, key1, vault2, key2) = create_shared_key(AeadAlgorithm::ChaCha20Poly1305)
+            .await
+            .unwrap();
+        let mut encryptor =
+            Encryptor::new(key1, 0.into(), vault1, true, RekeyPolicy::default(), None);
+        let mut decryptor = Decryptor::new(key2, vault2.clone(), DEFAULT_NONCE_WINDOW_SIZE);
+
+        // the keys resulting from a key rotation keep the same algorithm
+        for n in 0..100 {
+            let msg = vec![n];
+            let mut ciphertext = Vec::new();
+            encryptor.encrypt(&mut ciphertext, &msg).await.unwrap();
+            assert_eq!(msg, decryptor.decrypt(&ciphertext).await.unwrap().0);
+        }
+        let rotation = decryptor.take_key_rotation().unwrap();
+        assert_eq!(
+            vault2.get_aead_algorithm(&rotation.key).await.unwrap(),
+            AeadAlgorithm::ChaCha20Poly1305
+        );
+    }
+
     #[tokio::test]
     async fn test_encrypt_decrypt_with_message_lost() {
         let (mut encryptor, mut decryptor) = create_encryptor_decryptor().await.unwrap();
@@ -132,7 +175,295 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_encrypt_decrypt_with_large_nonce_window() {
+        let (mut encryptor, mut decryptor) =
+            create_encryptor_decryptor_with_options(RekeyPolicy::default(), 100)
+                .await
+                .unwrap();
+
+        let mut ciphertexts = Vec::new();
+        for n in 0..100 {
+            let msg = vec![n];
+            let mut ciphertext = Vec::new();
+            encryptor.encrypt(&mut ciphertext, &msg).await.unwrap();
+            ciphertexts.push((msg, ciphertext));
+        }
+
+        // the first message is received after 90 more recent messages
+        for (msg, ciphertext) in ciphertexts[1..91].iter().chain(ciphertexts[..1].iter()) {
+            assert_eq!(msg, &decryptor.decrypt(ciphertext).await.unwrap().0);
+        }
+
+        // with the default window, the first message would have been rejected
+        let (mut encryptor, mut decryptor) = create_encryptor_decryptor().await.unwrap();
+        let mut ciphertexts = Vec::new();
+        for n in 0..100 {
+            let mut ciphertext = Vec::new();
+            encryptor.encrypt(&mut ciphertext, &[n]).await.unwrap();
+            ciphertexts.push(ciphertext);
+        }
+        for ciphertext in ciphertexts[1..91].iter() {
+            decryptor.decrypt(ciphertext).await.unwrap();
+        }
+        assert!(decryptor.decrypt(&ciphertexts[0]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_decrypt_with_rekey_policy() {
+        let rekey_policy = RekeyPolicy {
+            max_messages: Some(5),
+            max_duration: None,
+        };
+        let (mut encryptor, mut decryptor) =
+            create_encryptor_decryptor_with_rekey_policy(rekey_policy)
+                .await
+                .unwrap();
+
+        let mut nonces = Vec::new();
+        for n in 0..100 {
+            let msg = vec![n];
+            let mut ciphertext = Vec::new();
+            encryptor.encrypt(&mut ciphertext, &msg).await.unwrap();
+            let (plaintext, nonce) = decryptor.decrypt(&ciphertext).await.unwrap();
+            assert_eq!(msg, plaintext);
+            nonces.push(nonce.value());
+        }
+
+        // the key is rotated every 5 messages by moving to the next key interval
+        assert_eq!(&nonces[..6], &[0, 1, 2, 3, 4, 32]);
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_decrypt_with_small_rekey_threshold() {
+        // with a threshold of 2 messages, every other message skips the remaining nonces of
+        // its key interval, so that the nonce window of the decryptor covers fewer messages
+        let rekey_policy = RekeyPolicy {
+            max_messages: Some(2),
+            max_duration: None,
+        };
+
+        // a message received 2 messages late is accepted
+        let (mut encryptor, mut decryptor) =
+            create_encryptor_decryptor_with_rekey_policy(rekey_policy)
+                .await
+                .unwrap();
+        let ciphertexts = encrypt_messages(&mut encryptor, 6).await;
+        assert_eq!(nonces(&ciphertexts), [0, 1, 32, 33, 64, 65]);
+        for i in [0, 1, 3, 4, 2] {
+            decryptor.decrypt(&ciphertexts[i]).await.unwrap();
+        }
+
+        // a message received 3 messages late is rejected, being more than 32 nonces late
+        let (mut encryptor, mut decryptor) =
+            create_encryptor_decryptor_with_rekey_policy(rekey_policy)
+                .await
+                .unwrap();
+        let ciphertexts = encrypt_messages(&mut encryptor, 6).await;
+        for i in [0, 1, 3, 4, 5] {
+            decryptor.decrypt(&ciphertexts[i]).await.unwrap();
+        }
+        assert!(decryptor.decrypt(&ciphertexts[2]).await.is_err());
+
+        // after 2 lost messages, the next messages are more than 32 nonces ahead
+        let (mut encryptor, mut decryptor) =
+            create_encryptor_decryptor_with_rekey_policy(rekey_policy)
+                .await
+                .unwrap();
+        let ciphertexts = encrypt_messages(&mut encryptor, 6).await;
+        for i in [0, 1] {
+            decryptor.decrypt(&ciphertexts[i]).await.unwrap();
+        }
+        assert!(decryptor.decrypt(&ciphertexts[4]).await.is_err());
+        assert!(decryptor.decrypt(&ciphertexts[5]).await.is_err());
+
+        // with the default rekeying threshold, the same messages are accepted
+        let (mut encryptor, mut decryptor) = create_encryptor_decryptor().await.unwrap();
+        let ciphertexts = encrypt_messages(&mut encryptor, 6).await;
+        for i in [0, 1, 4, 5, 2, 3] {
+            decryptor.decrypt(&ciphertexts[i]).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_decrypt_with_rekey_duration() {
+        let rekey_policy = RekeyPolicy {
+            max_messages: None,
+            max_duration: Some(Duration::from_secs(1)),
+        };
+        let (mut encryptor, mut decryptor) =
+            create_encryptor_decryptor_with_rekey_policy(rekey_policy)
+                .await
+                .unwrap();
+
+        for n in 0..3 {
+            let msg = vec![n];
+            let mut ciphertext = Vec::new();
+            encryptor.encrypt(&mut ciphertext, &msg).await.unwrap();
+            assert_eq!(msg, decryptor.decrypt(&ciphertext).await.unwrap().0);
+        }
+
+        tokio::time::sleep(Duration::from_millis(2100)).await;
+
+        let msg = vec![3];
+        let mut ciphertext = Vec::new();
+        encryptor.encrypt(&mut ciphertext, &msg).await.unwrap();
+        let (plaintext, nonce) = decryptor.decrypt(&ciphertext).await.unwrap();
+        assert_eq!(msg, plaintext);
+        assert_eq!(nonce.value(), 32);
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_decrypt_after_resume() {
+        let (vault1, key1, vault2, key2) = create_shared_key(AeadAlgorithm::AesGcm).await.unwrap();
+        let mut encryptor = Encryptor::new(
+            key1,
+            0.into(),
+            vault1.clone(),
+            true,
+            RekeyPolicy::default(),
+            None,
+        );
+        let mut decryptor = Decryptor::new(key2, vault2.clone(), DEFAULT_NONCE_WINDOW_SIZE);
+
+        let mut ciphertexts = Vec::new();
+        let (mut encryption_rotation, mut decryption_rotation) = (None, None);
+        for n in 0..40 {
+            let mut ciphertext = Vec::new();
+            encryptor.encrypt(&mut ciphertext, &[n]).await.unwrap();
+            decryptor.decrypt(&ciphertext).await.unwrap();
+            encryption_rotation = encryptor.take_key_rotation().or(encryption_rotation);
+            decryption_rotation = decryptor.take_key_rotation().or(decryption_rotation);
+            ciphertexts.push(ciphertext);
+        }
+        let encryption_rotation = encryption_rotation.unwrap();
+        let decryption_rotation = decryption_rotation.unwrap();
+        assert_eq!(encryption_rotation.first_nonce.value(), 32);
+        assert_eq!(decryption_rotation.first_nonce.value(), 32);
+
+        let mut encryptor = Encryptor::resume(
+            encryption_rotation.key,
+            encryption_rotation.first_nonce,
+            vault1,
+            RekeyPolicy::default(),
+            None,
+        )
+        .unwrap();
+        let mut decryptor = Decryptor::resume(
+            decryption_rotation.key,
+            decryption_rotation.first_nonce,
+            vault2,
+            DEFAULT_NONCE_WINDOW_SIZE,
+        );
+
+        // the messages of the persisted key interval cannot be replayed
+        assert!(decryptor.decrypt(&ciphertexts[35]).await.is_err());
+
+        // the encryption resumes with the next key interval
+        for n in 0..40 {
+            let msg = vec![n];
+            let mut ciphertext = Vec::new();
+            encryptor.encrypt(&mut ciphertext, &msg).await.unwrap();
+            let (plaintext, nonce) = decryptor.decrypt(&ciphertext).await.unwrap();
+            assert_eq!(msg, plaintext);
+            assert_eq!(nonce.value(), 64 + n as u64);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decrypt_after_resume_loses_the_messages_of_the_current_key() {
+        let (vault1, key1, vault2, key2) = create_shared_key(AeadAlgorithm::AesGcm).await.unwrap();
+        let mut encryptor =
+            Encryptor::new(key1, 0.into(), vault1, true, RekeyPolicy::default(), None);
+        let mut decryptor = Decryptor::new(key2, vault2.clone(), DEFAULT_NONCE_WINDOW_SIZE);
+
+        let mut decryption_rotation = None;
+        for n in 0..40 {
+            let mut ciphertext = Vec::new();
+            encryptor.encrypt(&mut ciphertext, &[n]).await.unwrap();
+            decryptor.decrypt(&ciphertext).await.unwrap();
+            decryption_rotation = decryptor.take_key_rotation().or(decryption_rotation);
+        }
+        let decryption_rotation = decryption_rotation.unwrap();
+
+        // only the node of the decryptor is restarted
+        let mut decryptor = Decryptor::resume(
+            decryption_rotation.key,
+            decryption_rotation.first_nonce,
+            vault2,
+            DEFAULT_NONCE_WINDOW_SIZE,
+        );
+
+        // the messages still encrypted with the persisted key are lost
+        for n in 40..64 {
+            let mut ciphertext = Vec::new();
+            encryptor.encrypt(&mut ciphertext, &[n]).await.unwrap();
+            assert!(decryptor.decrypt(&ciphertext).await.is_err());
+        }
+
+        // the messages are decrypted again after the next key rotation of the encryptor
+        for n in 64..100 {
+            let msg = vec![n];
+            let mut ciphertext = Vec::new();
+            encryptor.encrypt(&mut ciphertext, &msg).await.unwrap();
+            let (plaintext, nonce) = decryptor.decrypt(&ciphertext).await.unwrap();
+            assert_eq!(msg, plaintext);
+            assert_eq!(nonce.value(), n as u64);
+        }
+    }
+
+    /// Encrypt a number of messages, each message containing its index
+    async fn encrypt_messages(encryptor: &mut Encryptor, number_of_messages: u8) -> Vec<Vec<u8>> {
+        let mut ciphertexts = Vec::new();
+        for n in 0..number_of_messages {
+            let mut ciphertext = Vec::new();
+            encryptor.encrypt(&mut ciphertext, &[n]).await.unwrap();
+            ciphertexts.push(ciphertext);
+        }
+        ciphertexts
+    }
+
+    /// Return the nonces of encrypted messages
+    fn nonces(ciphertexts: &[Vec<u8>]) -> Vec<u64> {
+        ciphertexts
+            .iter()
+            .map(|ciphertext| Nonce::try_from(&ciphertext[..8]).unwrap().value())
+            .collect()
+    }
+
     async fn create_encryptor_decryptor() -> Result<(Encryptor, Decryptor)> {
+        create_encryptor_decryptor_with_options(RekeyPolicy::default(), DEFAULT_NONCE_WINDOW_SIZE)
+            .await
+    }
+
+    async fn create_encryptor_decryptor_with_rekey_policy(
+        rekey_policy: RekeyPolicy,
+    ) -> Result<(Encryptor, Decryptor)> {
+        create_encryptor_decryptor_with_options(rekey_policy, DEFAULT_NONCE_WINDOW_SIZE).await
+    }
+
+    async fn create_encryptor_decryptor_with_options(
+        rekey_policy: RekeyPolicy,
+        nonce_window_size: u64,
+    ) -> Result<(Encryptor, Decryptor)> {
+        let (vault1, key_on_v1, vault2, key_on_v2) =
+            create_shared_key(AeadAlgorithm::AesGcm).await?;
+
+        Ok((
+            Encryptor::new(key_on_v1, 0.into(), vault1, true, rekey_policy, None),
+            Decryptor::new(key_on_v2, vault2, nonce_window_size),
+        ))
+    }
+
+    /// Create two vaults sharing the same AEAD key
+    async fn create_shared_key(
+        algorithm: AeadAlgorithm,
+    ) -> Result<(
+        Arc<SoftwareVaultForSecureChannels>,
+        AeadSecretKeyHandle,
+        Arc<SoftwareVaultForSecureChannels>,
+        AeadSecretKeyHandle,
+    )> {
         let vault1 = SoftwareVaultForSecureChannels::create().await?;
         let vault2 = SoftwareVaultForSecureChannels::create().await?;
 
@@ -141,14 +472,15 @@ mod tests {
         rng.fill_bytes(&mut key);
 
         let key_on_v1 = vault1.import_secret_buffer(key.to_vec()).await?;
-        let key_on_v1 = vault1.convert_secret_buffer_to_aead_key(key_on_v1).await?;
+        let key_on_v1 = vault1
+            .convert_secret_buffer_to_aead_key(key_on_v1, algorithm)
+            .await?;
 
         let key_on_v2 = vault2.import_secret_buffer(key.to_vec()).await?;
-        let key_on_v2 = vault2.convert_secret_buffer_to_aead_key(key_on_v2).await?;
+        let key_on_v2 = vault2
+            .convert_secret_buffer_to_aead_key(key_on_v2, algorithm)
+            .await?;
 
-        Ok((
-            Encryptor::new(key_on_v1, 0.into(), vault1, true),
-            Decryptor::new(key_on_v2, vault2),
-        ))
+        Ok((vault1, key_on_v1, vault2, key_on_v2))
     }
 }
@@ -52,8 +52,9 @@ impl Nonce {
     }
 
     /// We use u64 nonce since it's convenient to work with it (e.g. increment)
-    /// But we use 12-byte be format for encryption, since AES-GCM wants 12 bytes
-    pub fn to_aes_gcm_nonce(&self) -> [u8; 12] {
+    /// But we use 12-byte be format for encryption, since both AES-GCM and ChaCha20-Poly1305
+    /// want 12 bytes
+    pub fn to_aead_nonce(&self) -> [u8; 12] {
         let mut n: [u8; 12] = [0; 12];
 
         n[4..].copy_from_slice(&self.to_noise_nonce());
@@ -68,7 +69,7 @@ impl Nonce {
     }
 }
 
-/// Restore 12-byte nonce needed for AES GCM from 8 byte that we use for noise
+/// Restore 12-byte nonce needed for AEAD encryption from 8 byte that we use for noise
 impl From<[u8; 8]> for Nonce {
     fn from(value: [u8; 8]) -> Self {
         let value = u64::from_be_bytes(value);
@@ -77,7 +78,7 @@ impl From<[u8; 8]> for Nonce {
     }
 }
 
-/// Restore 12-byte nonce needed for AES GCM from 8 byte that we use for noise
+/// Restore 12-byte nonce needed for AEAD encryption from 8 byte that we use for noise
 impl TryFrom<&[u8]> for Nonce {
     type Error = IdentityError;
 
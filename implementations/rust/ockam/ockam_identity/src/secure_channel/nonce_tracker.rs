@@ -2,24 +2,46 @@ use crate::secure_channel::encryptor::KEY_RENEWAL_INTERVAL;
 use crate::{IdentityError, Nonce};
 use tracing_attributes::instrument;
 
-/// fails compilation if [`KEY_RENEWAL_INTERVAL`] + 1 is bigger than [`BitmapType::BITS`].
+type BitmapType = u128;
+
+/// Default number of nonces, before the most recent one, which can still be received out of order
+pub(crate) const DEFAULT_NONCE_WINDOW_SIZE: u64 = KEY_RENEWAL_INTERVAL;
+
+/// Maximum size of the out of order window.
 ///
-/// the +1 is needed since the current nonce is also marked as received, taking an extra bit
+/// The current nonce is also marked as received, taking an extra bit in the bitmap
 /// even though we could check `current_nonce`, this compromise is for the sake of simplicity
-const _: [(); (KEY_RENEWAL_INTERVAL + 1 > BitmapType::BITS as u64) as usize] = [];
-type BitmapType = u64;
+pub(crate) const MAX_NONCE_WINDOW_SIZE: u64 = BitmapType::BITS as u64 - 1;
 
-#[derive(Debug)]
+/// Sliding window protecting a secure channel against replayed messages, while
+/// accepting messages received out of order, as done by IPsec (RFC 4303, section 3.4.3).
+///
+/// The window keeps track of the most recent nonce and of the nonces received among the
+/// `window_size` previous ones. Any nonce older than the window is rejected.
+#[derive(Debug, Clone)]
 pub(crate) struct NonceTracker {
     nonce_bitmap: BitmapType,
     current_nonce: Nonce,
+    window_size: u64,
 }
 
 impl NonceTracker {
-    pub(crate) fn new() -> Self {
+    /// Create a tracker accepting nonces up to `window_size` older than the most recent one.
+    /// The size is capped by [`MAX_NONCE_WINDOW_SIZE`]
+    pub(crate) fn new(window_size: u64) -> Self {
         Self {
             nonce_bitmap: 0,
             current_nonce: 0.into(),
+            window_size: window_size.min(MAX_NONCE_WINDOW_SIZE),
+        }
+    }
+
+    /// Create a tracker for which all the nonces up to `last_nonce` were already received
+    pub(crate) fn resume(window_size: u64, last_nonce: Nonce) -> Self {
+        Self {
+            nonce_bitmap: BitmapType::MAX,
+            current_nonce: last_nonce,
+            ..Self::new(window_size)
         }
     }
 
@@ -27,7 +49,8 @@ impl NonceTracker {
     #[instrument(skip_all)]
     pub(crate) fn mark(&self, nonce: Nonce) -> ockam_core::Result<NonceTracker> {
         let new_tracker = if nonce > self.current_nonce {
-            // normal case, we increase the nonce and move the window
+            // normal case, we increase the nonce and move the window.
+            // The key of a nonce can only be derived if it belongs to the next key interval
             let relative_shift: u64 = nonce.value() - self.current_nonce.value();
             if relative_shift > KEY_RENEWAL_INTERVAL {
                 return Err(IdentityError::InvalidNonce)?;
@@ -35,11 +58,12 @@ impl NonceTracker {
             NonceTracker {
                 nonce_bitmap: self.nonce_bitmap.overflowing_shl(relative_shift as u32).0 | 1,
                 current_nonce: nonce,
+                window_size: self.window_size,
             }
         } else {
             // first message or an out of order message
             let relative: u64 = self.current_nonce.value() - nonce.value();
-            if relative > KEY_RENEWAL_INTERVAL {
+            if relative > self.window_size {
                 return Err(IdentityError::InvalidNonce)?;
             }
 
@@ -52,6 +76,7 @@ impl NonceTracker {
             NonceTracker {
                 nonce_bitmap: self.nonce_bitmap | bit,
                 current_nonce: self.current_nonce,
+                window_size: self.window_size,
             }
         };
 
@@ -61,7 +86,7 @@ impl NonceTracker {
 
 #[test]
 pub fn check_nonce_tracker() {
-    let mut tracker = NonceTracker::new();
+    let mut tracker = NonceTracker::new(DEFAULT_NONCE_WINDOW_SIZE);
     tracker = tracker.mark(0.into()).unwrap();
     tracker = tracker.mark(1.into()).unwrap();
     tracker.mark(0.into()).unwrap_err();
@@ -83,3 +108,68 @@ pub fn check_nonce_tracker() {
         tracker = tracker.mark(n.into()).unwrap();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck::{quickcheck, Arbitrary, Gen};
+
+    /// A window size and a sequence of nonces, received out of order
+    #[derive(Debug, Clone)]
+    struct ReorderedNonces {
+        window_size: u64,
+        nonces: Vec<u64>,
+    }
+
+    impl Arbitrary for ReorderedNonces {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let window_size = u64::arbitrary(g) % MAX_NONCE_WINDOW_SIZE + 1;
+            let max_delay = window_size.min(KEY_RENEWAL_INTERVAL);
+
+            // each nonce is delayed by at most `max_delay` positions
+            let mut delayed: Vec<(u64, u64)> = (0..u64::arbitrary(g) % 500)
+                .map(|nonce| (nonce + u64::arbitrary(g) % (max_delay + 1), nonce))
+                .collect();
+            delayed.sort();
+
+            Self {
+                window_size,
+                nonces: delayed.into_iter().map(|(_, nonce)| nonce).collect(),
+            }
+        }
+    }
+
+    quickcheck! {
+        fn prop_nonces_in_window_are_accepted_once(reordered: ReorderedNonces) -> bool {
+            let mut tracker = NonceTracker::new(reordered.window_size);
+            for nonce in reordered.nonces {
+                match tracker.mark(nonce.into()) {
+                    Ok(new_tracker) => tracker = new_tracker,
+                    Err(_) => return false,
+                }
+                // a replayed nonce is always rejected
+                if tracker.mark(nonce.into()).is_ok() {
+                    return false;
+                }
+            }
+            true
+        }
+
+        fn prop_nonces_older_than_window_are_rejected(
+            window_size: u64,
+            current: u64,
+            age: u64
+        ) -> bool {
+            let window_size = window_size % MAX_NONCE_WINDOW_SIZE + 1;
+            let current = current % u32::MAX as u64 + MAX_NONCE_WINDOW_SIZE + 1;
+            let age = age % MAX_NONCE_WINDOW_SIZE + 1;
+
+            let mut tracker = NonceTracker::new(window_size);
+            tracker.current_nonce = current.into();
+            tracker.nonce_bitmap = 1;
+
+            let result = tracker.mark((current - age).into());
+            result.is_ok() == (age <= window_size)
+        }
+    }
+}
@@ -1,13 +1,16 @@
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
 use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
-use ockam_core::{Address, OutgoingAccessControl, Result};
+use ockam_core::{Address, AllowAll, Error, IncomingAccessControl, OutgoingAccessControl, Result};
 
 use crate::models::CredentialAndPurposeKey;
+use crate::secure_channel::encryptor::{RekeyPolicy, KEY_RENEWAL_INTERVAL};
+use crate::secure_channel::nonce_tracker::{DEFAULT_NONCE_WINDOW_SIZE, MAX_NONCE_WINDOW_SIZE};
 use crate::secure_channel::Addresses;
 use crate::{
-    CredentialRetrieverCreator, Identifier, IdentityError, MemoryCredentialRetrieverCreator,
-    TrustEveryonePolicy, TrustPolicy,
+    CipherSuite, CredentialRetrieverCreator, Identifier, IdentityError, KeyAgreementPolicy,
+    MemoryCredentialRetrieverCreator, MessagePadding, TrustEveryonePolicy, TrustPolicy,
 };
 
 use core::fmt;
@@ -27,8 +30,16 @@ pub struct SecureChannelOptions {
     pub(crate) credential_retriever_creator: Option<Arc<dyn CredentialRetrieverCreator>>,
     pub(crate) timeout: Duration,
     pub(crate) key_exchange_only: bool,
-    // Secure Channel will be persisted (currently only supported for key_exchange_only = true)
+    // Secure Channel will be persisted, and can be resumed if it is not key_exchange_only
     pub(crate) is_persistent: bool,
+    pub(crate) key_agreement_policy: KeyAgreementPolicy,
+    // Accepted cipher suites, in order of preference
+    pub(crate) cipher_suites: Vec<CipherSuite>,
+    pub(crate) rekey_policy: RekeyPolicy,
+    pub(crate) nonce_window_size: u64,
+    pub(crate) padding: Option<MessagePadding>,
+    // Access control for the encryption and decryption API addresses of the channel
+    pub(crate) api_access_control: Arc<dyn IncomingAccessControl>,
 }
 
 impl fmt::Debug for SecureChannelOptions {
@@ -49,6 +60,12 @@ impl SecureChannelOptions {
             timeout: DEFAULT_TIMEOUT,
             key_exchange_only: false,
             is_persistent: false,
+            key_agreement_policy: KeyAgreementPolicy::default(),
+            cipher_suites: vec![CipherSuite::default()],
+            rekey_policy: RekeyPolicy::default(),
+            nonce_window_size: DEFAULT_NONCE_WINDOW_SIZE,
+            padding: None,
+            api_access_control: Arc::new(AllowAll),
         }
     }
 
@@ -111,6 +128,86 @@ impl SecureChannelOptions {
         self.is_persistent = true;
         Ok(self)
     }
+
+    /// Secure Channel will be persisted after a successful handshake, along with its keys,
+    /// and can be resumed after a restart of the node, without a new handshake.
+    ///
+    /// NOTE: the encryption and decryption keys of the channel are stored in the vault each time
+    /// they are rotated. Anyone able to read the vault storage can then decrypt the traffic of the
+    /// current key interval, and the channel loses its forward secrecy for that interval.
+    /// A resumed channel starts with the next key interval. In order to prevent replays, all the
+    /// messages of the current key interval are rejected, including the messages sent with that
+    /// key by the other party after the restart, until its next key rotation. Up to 32 messages
+    /// can then be lost
+    pub fn resumable(mut self) -> Result<Self> {
+        if self.key_exchange_only {
+            return Err(IdentityError::SecureChannelNotResumable.into());
+        }
+        self.is_persistent = true;
+        Ok(self)
+    }
+
+    /// Set the policy used to negotiate a hybrid post-quantum key agreement
+    /// with the other party. The default policy is [`KeyAgreementPolicy::X25519Only`]
+    pub fn with_key_agreement_policy(mut self, key_agreement_policy: KeyAgreementPolicy) -> Self {
+        self.key_agreement_policy = key_agreement_policy;
+        self
+    }
+
+    /// Set the cipher suites accepted for the encryption of the channel messages,
+    /// in order of preference. When both parties accept several suites, the preference
+    /// of the initiator wins. By default only [`CipherSuite::Aes256Gcm`] is accepted,
+    /// which keeps the channel compatible with previous versions
+    pub fn with_cipher_suites(mut self, cipher_suites: Vec<CipherSuite>) -> Result<Self> {
+        self.cipher_suites = validate_cipher_suites(cipher_suites)?;
+        Ok(self)
+    }
+
+    /// Rotate the encryption key after a given number of messages, between 1 and 32.
+    /// By default, the key is rotated every 32 messages. Larger values are rejected since
+    /// the other party expects the key to be rotated at least every 32 messages.
+    ///
+    /// The other party infers a rotation from the message nonces: an early rotation skips the
+    /// unused nonces of the current interval of 32 nonces, while the window of messages
+    /// accepted out of order, and the gap accepted after lost messages, are counted in nonces.
+    /// With a threshold of `n` messages, only about `window_size * n / 32` late messages
+    /// are accepted, and the channel stops accepting messages once more than `n - 1`
+    /// consecutive messages are lost. For example, with a threshold of 1 message,
+    /// a single lost message makes the channel unusable
+    pub fn with_rekey_after_messages(mut self, max_messages: u64) -> Result<Self> {
+        self.rekey_policy.max_messages = Some(validate_rekey_after_messages(max_messages)?);
+        Ok(self)
+    }
+
+    /// Rotate the encryption key once it has been used for a given duration,
+    /// even if fewer messages than the rekeying threshold were sent
+    pub fn with_rekey_after_duration(mut self, max_duration: Duration) -> Self {
+        self.rekey_policy.max_duration = Some(max_duration);
+        self
+    }
+
+    /// Accept messages received up to `window_size` messages late, between 1 and 127.
+    /// A larger window avoids dropping the messages reordered on lossy or relayed routes.
+    /// The default window size is 32
+    pub fn with_nonce_window_size(mut self, window_size: u64) -> Result<Self> {
+        self.nonce_window_size = validate_nonce_window_size(window_size)?;
+        Ok(self)
+    }
+
+    /// Pad the encrypted messages to a set of bucket sizes, so that the size of the
+    /// application messages cannot be inferred from the traffic
+    pub fn with_padding(mut self, padding: MessagePadding) -> Self {
+        self.padding = Some(padding);
+        self
+    }
+
+    /// Restrict the local workers allowed to encrypt and decrypt payloads with the keys of the
+    /// channel, using its encryption and decryption API (see [`crate::SecureChannelApiClient`]).
+    /// By default, any local worker can use that API
+    pub fn with_api_access_control(mut self, access_control: impl IncomingAccessControl) -> Self {
+        self.api_access_control = Arc::new(access_control);
+        self
+    }
 }
 
 impl SecureChannelOptions {
@@ -175,8 +272,16 @@ pub struct SecureChannelListenerOptions {
     // To obtain our credentials
     pub(crate) credential_retriever_creator: Option<Arc<dyn CredentialRetrieverCreator>>,
     pub(crate) key_exchange_only: bool,
-    // Secure Channel will be persisted (currently only supported for key_exchange_only = true)
+    // Secure Channel will be persisted, and can be resumed if it is not key_exchange_only
     pub(crate) is_persistent: bool,
+    pub(crate) key_agreement_policy: KeyAgreementPolicy,
+    // Accepted cipher suites, in order of preference
+    pub(crate) cipher_suites: Vec<CipherSuite>,
+    pub(crate) rekey_policy: RekeyPolicy,
+    pub(crate) nonce_window_size: u64,
+    pub(crate) padding: Option<MessagePadding>,
+    // Access control for the encryption and decryption API addresses of the channel
+    pub(crate) api_access_control: Arc<dyn IncomingAccessControl>,
 }
 
 impl fmt::Debug for SecureChannelListenerOptions {
@@ -199,6 +304,12 @@ impl SecureChannelListenerOptions {
             credential_retriever_creator: None,
             key_exchange_only: false,
             is_persistent: false,
+            key_agreement_policy: KeyAgreementPolicy::default(),
+            cipher_suites: vec![CipherSuite::default()],
+            rekey_policy: RekeyPolicy::default(),
+            nonce_window_size: DEFAULT_NONCE_WINDOW_SIZE,
+            padding: None,
+            api_access_control: Arc::new(AllowAll),
         }
     }
 
@@ -264,6 +375,86 @@ impl SecureChannelListenerOptions {
         self.is_persistent = true;
         Ok(self)
     }
+
+    /// Secure Channel will be persisted after a successful handshake, along with its keys,
+    /// and can be resumed after a restart of the node, without a new handshake.
+    ///
+    /// NOTE: the encryption and decryption keys of the channel are stored in the vault each time
+    /// they are rotated. Anyone able to read the vault storage can then decrypt the traffic of the
+    /// current key interval, and the channel loses its forward secrecy for that interval.
+    /// A resumed channel starts with the next key interval. In order to prevent replays, all the
+    /// messages of the current key interval are rejected, including the messages sent with that
+    /// key by the other party after the restart, until its next key rotation. Up to 32 messages
+    /// can then be lost
+    pub fn resumable(mut self) -> Result<Self> {
+        if self.key_exchange_only {
+            return Err(IdentityError::SecureChannelNotResumable.into());
+        }
+        self.is_persistent = true;
+        Ok(self)
+    }
+
+    /// Set the policy used to negotiate a hybrid post-quantum key agreement
+    /// with the other party. The default policy is [`KeyAgreementPolicy::X25519Only`]
+    pub fn with_key_agreement_policy(mut self, key_agreement_policy: KeyAgreementPolicy) -> Self {
+        self.key_agreement_policy = key_agreement_policy;
+        self
+    }
+
+    /// Set the cipher suites accepted for the encryption of the channel messages,
+    /// in order of preference. When both parties accept several suites, the preference
+    /// of the initiator wins. By default only [`CipherSuite::Aes256Gcm`] is accepted,
+    /// which keeps the channel compatible with previous versions
+    pub fn with_cipher_suites(mut self, cipher_suites: Vec<CipherSuite>) -> Result<Self> {
+        self.cipher_suites = validate_cipher_suites(cipher_suites)?;
+        Ok(self)
+    }
+
+    /// Rotate the encryption key after a given number of messages, between 1 and 32.
+    /// By default, the key is rotated every 32 messages. Larger values are rejected since
+    /// the other party expects the key to be rotated at least every 32 messages.
+    ///
+    /// The other party infers a rotation from the message nonces: an early rotation skips the
+    /// unused nonces of the current interval of 32 nonces, while the window of messages
+    /// accepted out of order, and the gap accepted after lost messages, are counted in nonces.
+    /// With a threshold of `n` messages, only about `window_size * n / 32` late messages
+    /// are accepted, and the channel stops accepting messages once more than `n - 1`
+    /// consecutive messages are lost. For example, with a threshold of 1 message,
+    /// a single lost message makes the channel unusable
+    pub fn with_rekey_after_messages(mut self, max_messages: u64) -> Result<Self> {
+        self.rekey_policy.max_messages = Some(validate_rekey_after_messages(max_messages)?);
+        Ok(self)
+    }
+
+    /// Rotate the encryption key once it has been used for a given duration,
+    /// even if fewer messages than the rekeying threshold were sent
+    pub fn with_rekey_after_duration(mut self, max_duration: Duration) -> Self {
+        self.rekey_policy.max_duration = Some(max_duration);
+        self
+    }
+
+    /// Accept messages received up to `window_size` messages late, between 1 and 127.
+    /// A larger window avoids dropping the messages reordered on lossy or relayed routes.
+    /// The default window size is 32
+    pub fn with_nonce_window_size(mut self, window_size: u64) -> Result<Self> {
+        self.nonce_window_size = validate_nonce_window_size(window_size)?;
+        Ok(self)
+    }
+
+    /// Pad the encrypted messages to a set of bucket sizes, so that the size of the
+    /// application messages cannot be inferred from the traffic
+    pub fn with_padding(mut self, padding: MessagePadding) -> Self {
+        self.padding = Some(padding);
+        self
+    }
+
+    /// Restrict the local workers allowed to encrypt and decrypt payloads with the keys of the
+    /// channel, using its encryption and decryption API (see [`crate::SecureChannelApiClient`]).
+    /// By default, any local worker can use that API
+    pub fn with_api_access_control(mut self, access_control: impl IncomingAccessControl) -> Self {
+        self.api_access_control = Arc::new(access_control);
+        self
+    }
 }
 
 impl SecureChannelListenerOptions {
@@ -325,3 +516,35 @@ impl SecureChannelListenerOptions {
         Arc::new(ac)
     }
 }
+
+/// The other party of a secure channel infers key rotations from the message nonces,
+/// so that a key can be used at most for [`KEY_RENEWAL_INTERVAL`] messages
+fn validate_rekey_after_messages(max_messages: u64) -> Result<u64> {
+    if max_messages == 0 || max_messages > KEY_RENEWAL_INTERVAL {
+        return Err(Error::new(
+            Origin::Identity,
+            Kind::Invalid,
+            format!(
+                "invalid rekeying threshold {max_messages}: the number of messages before a key \
+                 rotation must be between 1 and {KEY_RENEWAL_INTERVAL}, since the other party \
+                 of the secure channel expects the key to be rotated at least every \
+                 {KEY_RENEWAL_INTERVAL} messages"
+            ),
+        ));
+    }
+    Ok(max_messages)
+}
+
+fn validate_nonce_window_size(window_size: u64) -> Result<u64> {
+    if window_size == 0 || window_size > MAX_NONCE_WINDOW_SIZE {
+        return Err(IdentityError::InvalidNonceWindowSize.into());
+    }
+    Ok(window_size)
+}
+
+fn validate_cipher_suites(cipher_suites: Vec<CipherSuite>) -> Result<Vec<CipherSuite>> {
+    if cipher_suites.is_empty() {
+        return Err(IdentityError::InvalidCipherSuites.into());
+    }
+    Ok(cipher_suites)
+}
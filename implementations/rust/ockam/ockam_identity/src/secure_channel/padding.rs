@@ -0,0 +1,85 @@
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+
+use crate::IdentityError;
+
+/// Padding of the messages sent on a secure channel.
+///
+/// Each encrypted payload is padded to the smallest bucket size which can contain it,
+/// so that an observer of the transport cannot infer the exact size of the application messages.
+/// Payloads larger than the largest bucket are padded to a multiple of the largest bucket size.
+///
+/// The padding is added after the encoded secure channel message, which is ignored when
+/// the message is decoded. It is then compatible with the parties not using any padding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessagePadding {
+    bucket_sizes: Vec<usize>,
+}
+
+impl Default for MessagePadding {
+    /// Buckets of 256, 1024 and 4096 bytes
+    fn default() -> Self {
+        Self {
+            bucket_sizes: vec![256, 1024, 4096],
+        }
+    }
+}
+
+impl MessagePadding {
+    /// Create a padding with a list of bucket sizes, in bytes.
+    /// The list must not be empty and the sizes must be strictly positive
+    pub fn new(bucket_sizes: impl Into<Vec<usize>>) -> Result<Self> {
+        let mut bucket_sizes = bucket_sizes.into();
+        if bucket_sizes.is_empty() || bucket_sizes.contains(&0) {
+            return Err(IdentityError::InvalidPaddingBucketSizes.into());
+        }
+        bucket_sizes.sort_unstable();
+        bucket_sizes.dedup();
+        Ok(Self { bucket_sizes })
+    }
+
+    /// Sizes of the buckets, in increasing order
+    pub fn bucket_sizes(&self) -> &[usize] {
+        &self.bucket_sizes
+    }
+
+    /// Return the size of a payload once padded
+    pub(crate) fn padded_len(&self, len: usize) -> usize {
+        if let Some(size) = self.bucket_sizes.iter().find(|size| **size >= len) {
+            return *size;
+        }
+        // the list of buckets is never empty
+        let largest = self.bucket_sizes[self.bucket_sizes.len() - 1];
+        len.div_ceil(largest) * largest
+    }
+
+    /// Pad a payload with zeros
+    pub(crate) fn pad(&self, payload: &mut Vec<u8>) {
+        let padded_len = self.padded_len(payload.len());
+        payload.resize(padded_len, 0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_padded_len() -> Result<()> {
+        let padding = MessagePadding::default();
+        assert_eq!(padding.padded_len(0), 256);
+        assert_eq!(padding.padded_len(256), 256);
+        assert_eq!(padding.padded_len(257), 1024);
+        assert_eq!(padding.padded_len(4000), 4096);
+        assert_eq!(padding.padded_len(4097), 8192);
+
+        let padding = MessagePadding::new(vec![100, 10])?;
+        assert_eq!(padding.bucket_sizes(), &[10, 100]);
+        assert_eq!(padding.padded_len(5), 10);
+        assert_eq!(padding.padded_len(50), 100);
+
+        assert!(MessagePadding::new(Vec::new()).is_err());
+        assert!(MessagePadding::new(vec![0, 10]).is_err());
+        Ok(())
+    }
+}
@@ -1,10 +1,40 @@
+use core::sync::atomic::{AtomicU64, Ordering};
 use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::sync::{Arc, RwLock};
 use ockam_core::compat::vec::Vec;
 use ockam_core::{Address, Result};
 
-use crate::models::Identifier;
-use crate::IdentityError;
+use crate::models::{Identifier, TimestampInSeconds};
+use crate::utils::now;
+use crate::{HandshakeDiagnostics, IdentityError};
+
+/// Time of the last message sent or received on a SecureChannel.
+/// It is shared between the encryptor, the decryptor and the registry entry of the channel
+#[derive(Clone, Debug, Default)]
+pub struct SecureChannelActivity {
+    last_activity: Arc<AtomicU64>,
+}
+
+impl SecureChannelActivity {
+    /// Create a new activity tracker, initialized with the current time
+    pub fn new() -> Self {
+        let activity = Self::default();
+        activity.record();
+        activity
+    }
+
+    /// Record that a message was just sent or received
+    pub fn record(&self) {
+        if let Ok(now) = now() {
+            self.last_activity.store(now.0, Ordering::Relaxed);
+        }
+    }
+
+    /// Time of the last message sent or received
+    pub fn last_activity(&self) -> TimestampInSeconds {
+        TimestampInSeconds(self.last_activity.load(Ordering::Relaxed))
+    }
+}
 
 /// Known information about particular SecureChannel
 #[derive(Clone, Debug)]
@@ -17,6 +47,8 @@ pub struct SecureChannelRegistryEntry {
     my_id: Identifier,
     their_id: Identifier,
     their_decryptor_address: Address,
+    created_at: TimestampInSeconds,
+    activity: SecureChannelActivity,
 }
 
 impl SecureChannelRegistryEntry {
@@ -31,6 +63,8 @@ impl SecureChannelRegistryEntry {
         my_id: Identifier,
         their_id: Identifier,
         their_decryptor_address: Address,
+        created_at: TimestampInSeconds,
+        activity: SecureChannelActivity,
     ) -> Self {
         Self {
             encryptor_messaging_address,
@@ -41,6 +75,8 @@ impl SecureChannelRegistryEntry {
             my_id,
             their_id,
             their_decryptor_address,
+            created_at,
+            activity,
         }
     }
 
@@ -83,6 +119,16 @@ impl SecureChannelRegistryEntry {
     pub fn their_decryptor_address(&self) -> Address {
         self.their_decryptor_address.clone()
     }
+
+    /// Time when the channel was established
+    pub fn created_at(&self) -> TimestampInSeconds {
+        self.created_at
+    }
+
+    /// Time of the last message sent or received on the channel
+    pub fn last_activity(&self) -> TimestampInSeconds {
+        self.activity.last_activity()
+    }
 }
 
 /// Registry of all known Secure Channels
@@ -90,6 +136,7 @@ impl SecureChannelRegistryEntry {
 pub struct SecureChannelRegistry {
     // Encryptor address is used as a key
     registry: Arc<RwLock<BTreeMap<Address, SecureChannelRegistryEntry>>>,
+    handshake_diagnostics: HandshakeDiagnostics,
 }
 
 impl SecureChannelRegistry {
@@ -97,8 +144,15 @@ impl SecureChannelRegistry {
     pub fn new() -> Self {
         Self {
             registry: Default::default(),
+            handshake_diagnostics: Default::default(),
         }
     }
+
+    /// Return the attempts, durations and failures of the handshakes
+    /// of the channels registered here
+    pub fn handshake_diagnostics(&self) -> HandshakeDiagnostics {
+        self.handshake_diagnostics.clone()
+    }
 }
 
 impl SecureChannelRegistry {
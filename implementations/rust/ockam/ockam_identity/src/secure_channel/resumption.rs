@@ -0,0 +1,64 @@
+use ockam_core::compat::sync::Arc;
+use ockam_core::{Address, Result};
+use ockam_vault::VaultForSecureChannels;
+
+use crate::secure_channel::encryptor::KeyRotation;
+use crate::SecureChannelRepository;
+
+/// Persistence of the keys of a resumable secure channel, each time they are rotated.
+///
+/// The new key is stored before the channel state is updated, and the previous key is only
+/// deleted once the channel state refers to the new key, so that the persisted state always
+/// refers to a stored key
+#[derive(Clone)]
+pub(crate) struct ChannelResumption {
+    secure_channel_repository: Arc<dyn SecureChannelRepository>,
+    vault: Arc<dyn VaultForSecureChannels>,
+    decryptor_remote: Address,
+}
+
+impl ChannelResumption {
+    pub(crate) fn new(
+        secure_channel_repository: Arc<dyn SecureChannelRepository>,
+        vault: Arc<dyn VaultForSecureChannels>,
+        decryptor_remote: Address,
+    ) -> Self {
+        Self {
+            secure_channel_repository,
+            vault,
+            decryptor_remote,
+        }
+    }
+
+    /// Persist the new encryption key after a key rotation
+    pub(crate) async fn persist_encryption_key(&self, key_rotation: &KeyRotation) -> Result<()> {
+        self.vault.persist_aead_key(&key_rotation.key).await?;
+        self.secure_channel_repository
+            .update_encryption_key(
+                &self.decryptor_remote,
+                &key_rotation.key,
+                key_rotation.first_nonce,
+            )
+            .await?;
+        self.vault
+            .delete_persisted_aead_key(&key_rotation.previous_key)
+            .await?;
+        Ok(())
+    }
+
+    /// Persist the new decryption key after a key rotation
+    pub(crate) async fn persist_decryption_key(&self, key_rotation: &KeyRotation) -> Result<()> {
+        self.vault.persist_aead_key(&key_rotation.key).await?;
+        self.secure_channel_repository
+            .update_decryption_key(
+                &self.decryptor_remote,
+                &key_rotation.key,
+                key_rotation.first_nonce,
+            )
+            .await?;
+        self.vault
+            .delete_persisted_aead_key(&key_rotation.previous_key)
+            .await?;
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@ use core::sync::atomic::AtomicBool;
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::FlowControls;
 use ockam_core::Result;
-use ockam_core::{Address, Route};
+use ockam_core::{route, Address, AllowAll, IncomingAccessControl, Route};
 use ockam_node::{Context, WorkerBuilder};
 use tracing::info;
 
@@ -10,15 +10,17 @@ use crate::identities::Identities;
 use crate::models::Identifier;
 use crate::secure_channel::handshake_worker::HandshakeWorker;
 use crate::secure_channel::{
-    Addresses, DecryptorHandler, RemoteRoute, Role, SecureChannelListenerOptions,
-    SecureChannelListenerWorker, SecureChannelOptions, SecureChannelRegistry,
-    SecureChannelSharedState,
+    Addresses, ChannelResumption, Decryptor, DecryptorHandler, Encryptor, EncryptorWorker,
+    RemoteRoute, Role, SecureChannelListenerOptions, SecureChannelListenerWorker,
+    SecureChannelOptions, SecureChannelRegistry, SecureChannelSharedState,
+    DEFAULT_NONCE_WINDOW_SIZE,
 };
+use crate::utils::now;
 #[cfg(feature = "storage")]
 use crate::SecureChannelsBuilder;
 use crate::{
-    IdentityError, SecureChannel, SecureChannelListener, SecureChannelRegistryEntry,
-    SecureChannelRepository, Vault,
+    IdentityError, SecureChannel, SecureChannelActivity, SecureChannelListener,
+    SecureChannelRegistryEntry, SecureChannelRepository, Vault,
 };
 
 /// Identity implementation
@@ -172,6 +174,12 @@ impl SecureChannels {
             Some(options.timeout),
             Role::Initiator,
             options.key_exchange_only,
+            options.key_agreement_policy,
+            options.cipher_suites,
+            options.rekey_policy,
+            options.nonce_window_size,
+            options.padding,
+            options.api_access_control,
             secure_channel_repository,
             encryptor_remote_route.clone(),
         )
@@ -196,6 +204,23 @@ impl SecureChannels {
         &self,
         ctx: &Context,
         decryptor_remote_address: &Address,
+    ) -> Result<SecureChannel> {
+        self.start_persisted_secure_channel_decryptor_with_access_control(
+            ctx,
+            decryptor_remote_address,
+            Arc::new(AllowAll),
+        )
+        .await
+    }
+
+    /// Start a decryptor side for a previously existed and persisted secure channel
+    /// Only decryptor api part is started, and only accepts the requests allowed by the
+    /// given access control
+    pub async fn start_persisted_secure_channel_decryptor_with_access_control(
+        &self,
+        ctx: &Context,
+        decryptor_remote_address: &Address,
+        api_access_control: Arc<dyn IncomingAccessControl>,
     ) -> Result<SecureChannel> {
         info!(
             "Starting persisted secure channel: {}",
@@ -223,6 +248,7 @@ impl SecureChannels {
         let shared_state = SecureChannelSharedState {
             remote_route: RemoteRoute::create(),                 // Unused
             should_send_close: Arc::new(AtomicBool::new(false)), // Don't need to send anything
+            activity: SecureChannelActivity::new(),
         };
 
         let mut addresses = Addresses::generate(role);
@@ -236,6 +262,7 @@ impl SecureChannels {
             None, // We don't need authority, we won't verify any credentials
             role,
             true,
+            DEFAULT_NONCE_WINDOW_SIZE,
             addresses.clone(),
             decryption_key,
             self.vault().secure_channel_vault.clone(),
@@ -263,6 +290,7 @@ impl SecureChannels {
 
         WorkerBuilder::new(decryptor_worker)
             .with_address(addresses.decryptor_api.clone()) // We only need API address here
+            .with_incoming_access_control_arc(api_access_control)
             .start(ctx)
             .await?;
 
@@ -284,6 +312,8 @@ impl SecureChannels {
             my_identifier.clone(),
             their_identifier.clone(),
             Address::random_local(), // Random, unused for now
+            now()?,
+            shared_state.activity.clone(),
         );
 
         self.secure_channel_registry.register_channel(info)?;
@@ -291,6 +321,189 @@ impl SecureChannels {
         Ok(sc)
     }
 
+    /// Resume a secure channel created with [`SecureChannelOptions::resumable`], or accepted by
+    /// a listener created with [`SecureChannelListenerOptions::resumable`], after a restart of
+    /// the node.
+    ///
+    /// The channel keeps its addresses and keys, without a new handshake. Messages are sent to
+    /// the decryptor of the other party via `route`, which can be empty on the responder side,
+    /// in which case the route is updated when the first message is received from the initiator.
+    /// The key rotation, nonce window and padding settings are taken from `options`
+    pub async fn resume_secure_channel(
+        &self,
+        ctx: &Context,
+        decryptor_remote_address: &Address,
+        route: impl Into<Route>,
+        options: impl Into<SecureChannelOptions>,
+    ) -> Result<SecureChannel> {
+        info!("Resuming secure channel: {}", decryptor_remote_address);
+
+        let Some(persisted_secure_channel) = self
+            .secure_channel_repository
+            .get(decryptor_remote_address)
+            .await?
+        else {
+            return Err(IdentityError::PersistentSecureChannelNotFound)?;
+        };
+
+        let Some(resumable_state) = persisted_secure_channel.resumable_state() else {
+            return Err(IdentityError::SecureChannelNotResumable)?;
+        };
+
+        let vault = self.vault().secure_channel_vault;
+        let decryption_key = persisted_secure_channel.decryption_key_handle().clone();
+        let encryption_key = resumable_state.encryption_key_handle().clone();
+        vault.load_aead_key(&decryption_key).await?;
+        vault.load_aead_key(&encryption_key).await?;
+
+        let options = options.into();
+        let flow_control_id = options.flow_control_id.clone();
+        let my_identifier = persisted_secure_channel.my_identifier().clone();
+        let their_identifier = persisted_secure_channel.their_identifier().clone();
+        let role = persisted_secure_channel.role();
+
+        let mut addresses = Addresses::generate(role);
+        addresses.decryptor_remote = persisted_secure_channel.decryptor_remote().clone();
+        addresses.decryptor_api = persisted_secure_channel.decryptor_api().clone();
+        addresses.encryptor = resumable_state.encryptor().clone();
+        addresses.encryptor_api = resumable_state.encryptor_api().clone();
+
+        let route = route.into();
+        match route.next() {
+            Ok(next) => options.setup_flow_control(ctx.flow_controls(), &addresses, next),
+            Err(_) => SecureChannelOptions::setup_flow_control_producer(
+                &flow_control_id,
+                ctx.flow_controls(),
+                &addresses,
+            ),
+        }
+        let decryptor_outgoing_access_control =
+            options.create_decryptor_outgoing_access_control(ctx.flow_controls());
+
+        let credential_retriever = match &options.credential_retriever_creator {
+            Some(credential_retriever_creator) => {
+                let credential_retriever =
+                    credential_retriever_creator.create(&my_identifier).await?;
+                credential_retriever.initialize().await?;
+                Some(credential_retriever)
+            }
+            None => None,
+        };
+
+        let remote_route = RemoteRoute::create();
+        remote_route.write().unwrap().route =
+            route![route, resumable_state.their_decryptor().clone()];
+        let shared_state = SecureChannelSharedState {
+            remote_route,
+            should_send_close: Arc::new(AtomicBool::new(true)),
+            activity: SecureChannelActivity::new(),
+        };
+
+        let resumption = ChannelResumption::new(
+            self.secure_channel_repository(),
+            vault.clone(),
+            addresses.decryptor_remote.clone(),
+        );
+
+        let mut decryptor_handler = DecryptorHandler::new(
+            self.identities(),
+            options.authority.clone(),
+            role,
+            false,
+            options.nonce_window_size,
+            addresses.clone(),
+            decryption_key.clone(),
+            vault.clone(),
+            their_identifier.clone(),
+            shared_state.clone(),
+        )
+        .with_resumption(Some(resumption.clone()));
+        decryptor_handler.decryptor = Decryptor::resume(
+            decryption_key,
+            resumable_state.decryption_nonce(),
+            vault.clone(),
+            options.nonce_window_size,
+        );
+
+        let decryptor_worker = HandshakeWorker::new(
+            Arc::new(self.clone()),
+            None, // The handshake was already performed
+            None,
+            my_identifier.clone(),
+            addresses.clone(),
+            role,
+            false,
+            None, // No handshake messages will be sent
+            Some(decryptor_handler),
+            options.authority.clone(),
+            self.identities.change_history_repository(),
+            credential_retriever.clone(),
+            Some(self.secure_channel_repository()),
+            shared_state.clone(),
+        );
+
+        WorkerBuilder::new(decryptor_worker)
+            .with_mailboxes(HandshakeWorker::create_mailboxes(
+                &addresses,
+                decryptor_outgoing_access_control,
+                options.api_access_control.clone(),
+            ))
+            .start(ctx)
+            .await?;
+
+        let encryptor = EncryptorWorker::new(
+            role.str(),
+            false,
+            addresses.clone(),
+            Encryptor::resume(
+                encryption_key,
+                resumable_state.encryption_nonce(),
+                vault,
+                options.rekey_policy,
+                options.padding,
+            )?,
+            my_identifier.clone(),
+            self.identities.change_history_repository(),
+            credential_retriever,
+            None,
+            shared_state.clone(),
+        )
+        .with_resumption(Some(resumption));
+
+        HandshakeWorker::start_encryptor(
+            ctx,
+            &addresses,
+            &their_identifier,
+            encryptor,
+            options.api_access_control.clone(),
+        )
+        .await?;
+
+        let info = SecureChannelRegistryEntry::new(
+            addresses.encryptor.clone(),
+            addresses.encryptor_api.clone(),
+            addresses.decryptor_remote.clone(),
+            addresses.decryptor_api.clone(),
+            role.is_initiator(),
+            my_identifier,
+            their_identifier.clone(),
+            resumable_state.their_decryptor().clone(),
+            now()?,
+            shared_state.activity.clone(),
+        );
+
+        self.secure_channel_registry.register_channel(info)?;
+
+        Ok(SecureChannel::new(
+            ctx.flow_controls().clone(),
+            their_identifier,
+            shared_state.remote_route,
+            addresses,
+            false,
+            flow_control_id,
+        ))
+    }
+
     /// Stop a SecureChannel given an encryptor address
     pub async fn stop_secure_channel(&self, ctx: &Context, channel: &Address) -> Result<()> {
         ctx.stop_worker(channel.clone()).await
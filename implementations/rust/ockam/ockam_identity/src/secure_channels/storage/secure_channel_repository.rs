@@ -1,5 +1,5 @@
 use crate::secure_channel::Role;
-use crate::Identifier;
+use crate::{Identifier, Nonce};
 use async_trait::async_trait;
 use core::fmt::Debug;
 use ockam_core::compat::boxed::Box;
@@ -15,6 +15,7 @@ pub struct PersistedSecureChannel {
     decryptor_remote: Address,
     decryptor_api: Address,
     decryption_key_handle: AeadSecretKeyHandle,
+    resumable_state: Option<ResumableChannelState>,
 }
 
 impl PersistedSecureChannel {
@@ -33,9 +34,16 @@ impl PersistedSecureChannel {
             decryptor_remote,
             decryptor_api,
             decryption_key_handle,
+            resumable_state: None,
         }
     }
 
+    /// Add the state needed to resume the secure channel
+    pub(crate) fn with_resumable_state(mut self, resumable_state: ResumableChannelState) -> Self {
+        self.resumable_state = Some(resumable_state);
+        self
+    }
+
     /// Role
     pub fn role(&self) -> Role {
         self.role
@@ -65,6 +73,73 @@ impl PersistedSecureChannel {
     pub fn decryption_key_handle(&self) -> &AeadSecretKeyHandle {
         &self.decryption_key_handle
     }
+
+    /// State needed to resume the secure channel, if it is resumable
+    pub fn resumable_state(&self) -> Option<&ResumableChannelState> {
+        self.resumable_state.as_ref()
+    }
+}
+
+/// Encryption state of a secure channel which can be resumed after a restart of the node.
+/// The decryption state is given by the decryption key of the [`PersistedSecureChannel`]
+#[derive(Clone, Eq, Debug, PartialEq)]
+pub struct ResumableChannelState {
+    encryptor: Address,
+    encryptor_api: Address,
+    their_decryptor: Address,
+    encryption_key_handle: AeadSecretKeyHandle,
+    encryption_nonce: Nonce,
+    decryption_nonce: Nonce,
+}
+
+impl ResumableChannelState {
+    pub(crate) fn new(
+        encryptor: Address,
+        encryptor_api: Address,
+        their_decryptor: Address,
+        encryption_key_handle: AeadSecretKeyHandle,
+        encryption_nonce: Nonce,
+        decryption_nonce: Nonce,
+    ) -> Self {
+        Self {
+            encryptor,
+            encryptor_api,
+            their_decryptor,
+            encryption_key_handle,
+            encryption_nonce,
+            decryption_nonce,
+        }
+    }
+
+    /// Encryptor address. See [`Addresses`]
+    pub fn encryptor(&self) -> &Address {
+        &self.encryptor
+    }
+
+    /// Encryptor api address. See [`Addresses`]
+    pub fn encryptor_api(&self) -> &Address {
+        &self.encryptor_api
+    }
+
+    /// Address of the decryptor of the other party
+    pub fn their_decryptor(&self) -> &Address {
+        &self.their_decryptor
+    }
+
+    /// Current encryption key
+    pub fn encryption_key_handle(&self) -> &AeadSecretKeyHandle {
+        &self.encryption_key_handle
+    }
+
+    /// First nonce encrypted with the current encryption key
+    pub fn encryption_nonce(&self) -> Nonce {
+        self.encryption_nonce
+    }
+
+    /// First nonce decrypted with the current decryption key
+    pub fn decryption_nonce(&self) -> Nonce {
+        self.decryption_nonce
+    }
 }
 
 /// Repository for persisted Secure Channels
@@ -79,6 +154,22 @@ pub trait SecureChannelRepository: Send + Sync + 'static {
     /// Store a secure channel
     async fn put(&self, secure_channel: PersistedSecureChannel) -> Result<()>;
 
+    /// Update the encryption key of a resumable secure channel, after a key rotation
+    async fn update_encryption_key(
+        &self,
+        decryptor_remote_address: &Address,
+        encryption_key_handle: &AeadSecretKeyHandle,
+        encryption_nonce: Nonce,
+    ) -> Result<()>;
+
+    /// Update the decryption key of a resumable secure channel, after a key rotation
+    async fn update_decryption_key(
+        &self,
+        decryptor_remote_address: &Address,
+        decryption_key_handle: &AeadSecretKeyHandle,
+        decryption_nonce: Nonce,
+    ) -> Result<()>;
+
     /// Delete a secure channel
     async fn delete(&self, decryptor_remote_address: &Address) -> Result<()>;
 }
@@ -2,14 +2,14 @@ use sqlx::*;
 use tracing::debug;
 
 use crate::secure_channel::Role;
-use crate::Identifier;
+use crate::{Identifier, Nonce};
 use ockam_core::{async_trait, Address};
 use ockam_core::{Error, Result};
-use ockam_node::database::{FromSqlxError, SqlxDatabase, ToVoid};
+use ockam_node::database::{FromSqlxError, Nullable, SqlxDatabase, ToVoid};
 use ockam_vault::{AeadSecretKeyHandle, HandleToSecret};
 
 use crate::secure_channels::storage::secure_channel_repository::{
-    PersistedSecureChannel, SecureChannelRepository,
+    PersistedSecureChannel, ResumableChannelState, SecureChannelRepository,
 };
 
 /// Implementation of `CredentialRepository` trait based on an underlying database
@@ -39,7 +39,7 @@ impl SecureChannelRepository for SecureChannelSqlxDatabase {
         decryptor_remote_address: &Address,
     ) -> Result<Option<PersistedSecureChannel>> {
         let query = query_as(
-            "SELECT role, my_identifier, their_identifier, decryptor_remote_address, decryptor_api_address, decryption_key_handle FROM secure_channel WHERE decryptor_remote_address = $1"
+            "SELECT role, my_identifier, their_identifier, decryptor_remote_address, decryptor_api_address, decryption_key_handle, encryptor_address, encryptor_api_address, their_decryptor_address, encryption_key_handle, encryption_nonce, decryption_nonce FROM secure_channel WHERE decryptor_remote_address = $1"
             )
             .bind(decryptor_remote_address.to_string());
         let secure_channel: Option<SecureChannelRow> = query
@@ -51,18 +51,55 @@ impl SecureChannelRepository for SecureChannelSqlxDatabase {
     }
 
     async fn put(&self, secure_channel: PersistedSecureChannel) -> Result<()> {
+        let resumable_state = secure_channel.resumable_state();
         let query = query(
-            r#"INSERT INTO secure_channel (role, my_identifier, their_identifier, decryptor_remote_address, decryptor_api_address, decryption_key_handle)
-            VALUES ($1, $2, $3, $4, $5, $6)
+            r#"INSERT INTO secure_channel (role, my_identifier, their_identifier, decryptor_remote_address, decryptor_api_address, decryption_key_handle, encryptor_address, encryptor_api_address, their_decryptor_address, encryption_key_handle, encryption_nonce, decryption_nonce)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
             ON CONFLICT (decryptor_remote_address)
-            DO UPDATE SET role = $1, my_identifier = $2, their_identifier = $3, decryptor_api_address = $5, decryption_key_handle = $6"#
+            DO UPDATE SET role = $1, my_identifier = $2, their_identifier = $3, decryptor_api_address = $5, decryption_key_handle = $6, encryptor_address = $7, encryptor_api_address = $8, their_decryptor_address = $9, encryption_key_handle = $10, encryption_nonce = $11, decryption_nonce = $12"#
             )
             .bind(secure_channel.role().str())
             .bind(secure_channel.my_identifier())
             .bind(secure_channel.their_identifier())
             .bind(secure_channel.decryptor_remote().to_string())
             .bind(secure_channel.decryptor_api().to_string())
-            .bind(secure_channel.decryption_key_handle());
+            .bind(secure_channel.decryption_key_handle())
+            .bind(resumable_state.map(|s| s.encryptor().to_string()))
+            .bind(resumable_state.map(|s| s.encryptor_api().to_string()))
+            .bind(resumable_state.map(|s| s.their_decryptor().to_string()))
+            .bind(resumable_state.map(|s| s.encryption_key_handle().clone()))
+            .bind(resumable_state.map(|s| s.encryption_nonce().value() as i64))
+            .bind(resumable_state.map(|s| s.decryption_nonce().value() as i64));
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn update_encryption_key(
+        &self,
+        decryptor_remote_address: &Address,
+        encryption_key_handle: &AeadSecretKeyHandle,
+        encryption_nonce: Nonce,
+    ) -> Result<()> {
+        let query = query(
+            "UPDATE secure_channel SET encryption_key_handle = $1, encryption_nonce = $2 WHERE decryptor_remote_address = $3",
+        )
+        .bind(encryption_key_handle)
+        .bind(encryption_nonce.value() as i64)
+        .bind(decryptor_remote_address.to_string());
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn update_decryption_key(
+        &self,
+        decryptor_remote_address: &Address,
+        decryption_key_handle: &AeadSecretKeyHandle,
+        decryption_nonce: Nonce,
+    ) -> Result<()> {
+        let query = query(
+            "UPDATE secure_channel SET decryption_key_handle = $1, decryption_nonce = $2 WHERE decryptor_remote_address = $3",
+        )
+        .bind(decryption_key_handle)
+        .bind(decryption_nonce.value() as i64)
+        .bind(decryptor_remote_address.to_string());
         query.execute(&*self.database.pool).await.void()
     }
 
@@ -82,12 +119,49 @@ struct SecureChannelRow {
     decryptor_remote_address: String,
     decryptor_api_address: String,
     decryption_key_handle: Vec<u8>,
+    encryptor_address: Nullable<String>,
+    encryptor_api_address: Nullable<String>,
+    their_decryptor_address: Nullable<String>,
+    encryption_key_handle: Nullable<Vec<u8>>,
+    encryption_nonce: Nullable<i64>,
+    decryption_nonce: Nullable<i64>,
+}
+
+impl SecureChannelRow {
+    fn resumable_state(&self) -> Option<ResumableChannelState> {
+        match (
+            self.encryptor_address.to_option(),
+            self.encryptor_api_address.to_option(),
+            self.their_decryptor_address.to_option(),
+            self.encryption_key_handle.to_option(),
+            self.encryption_nonce.to_option(),
+            self.decryption_nonce.to_option(),
+        ) {
+            (
+                Some(encryptor_address),
+                Some(encryptor_api_address),
+                Some(their_decryptor_address),
+                Some(encryption_key_handle),
+                Some(encryption_nonce),
+                Some(decryption_nonce),
+            ) => Some(ResumableChannelState::new(
+                Address::from_string(encryptor_address),
+                Address::from_string(encryptor_api_address),
+                Address::from_string(their_decryptor_address),
+                AeadSecretKeyHandle::new(HandleToSecret::new(encryption_key_handle)),
+                (encryption_nonce as u64).into(),
+                (decryption_nonce as u64).into(),
+            )),
+            _ => None,
+        }
+    }
 }
 
 impl TryFrom<SecureChannelRow> for PersistedSecureChannel {
     type Error = Error;
 
     fn try_from(value: SecureChannelRow) -> std::result::Result<Self, Self::Error> {
+        let resumable_state = value.resumable_state();
         let role = Role::try_from(value.role.as_str())?;
         let my_identifier = Identifier::try_from(value.my_identifier)?;
         let their_identifier = Identifier::try_from(value.their_identifier)?;
@@ -96,14 +170,19 @@ impl TryFrom<SecureChannelRow> for PersistedSecureChannel {
         let decryption_key_handle = HandleToSecret::new(value.decryption_key_handle);
         let decryption_key_handle = AeadSecretKeyHandle::new(decryption_key_handle);
 
-        Ok(PersistedSecureChannel::new(
+        let secure_channel = PersistedSecureChannel::new(
             role,
             my_identifier,
             their_identifier,
             decryptor_remote_address,
             decryptor_api_address,
             decryption_key_handle,
-        ))
+        );
+
+        Ok(match resumable_state {
+            Some(resumable_state) => secure_channel.with_resumable_state(resumable_state),
+            None => secure_channel,
+        })
     }
 }
 
@@ -158,4 +237,65 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_resumable_secure_channel_repository() -> Result<()> {
+        let repository = Arc::new(SecureChannelSqlxDatabase::create().await?);
+
+        let decryptor_remote = Address::random_local();
+        let my_identifier = Identifier::try_from(
+            "Ie70dc5545d64724880257acb32b8851e7dd1dd57076838991bc343165df71bfe",
+        )?;
+        let their_identifier = Identifier::try_from(
+            "Ife42b412ecdb7fda4421bd5046e33c1017671ce7a320c3342814f0b99df9ab60",
+        )?;
+
+        let sc = PersistedSecureChannel::new(
+            Role::Responder,
+            my_identifier,
+            their_identifier,
+            decryptor_remote.clone(),
+            Address::random_local(),
+            random_key_handle(),
+        )
+        .with_resumable_state(ResumableChannelState::new(
+            Address::random_local(),
+            Address::random_local(),
+            Address::random_local(),
+            random_key_handle(),
+            0.into(),
+            0.into(),
+        ));
+
+        repository.put(sc.clone()).await?;
+        let sc2 = repository.get(&decryptor_remote).await?;
+        assert_eq!(sc2, Some(sc));
+
+        let encryption_key_handle = random_key_handle();
+        repository
+            .update_encryption_key(&decryptor_remote, &encryption_key_handle, 32.into())
+            .await?;
+        let decryption_key_handle = random_key_handle();
+        repository
+            .update_decryption_key(&decryptor_remote, &decryption_key_handle, 64.into())
+            .await?;
+
+        let sc = repository.get(&decryptor_remote).await?.unwrap();
+        assert_eq!(sc.decryption_key_handle(), &decryption_key_handle);
+        let resumable_state = sc.resumable_state().unwrap();
+        assert_eq!(
+            resumable_state.encryption_key_handle(),
+            &encryption_key_handle
+        );
+        assert_eq!(resumable_state.encryption_nonce(), 32.into());
+        assert_eq!(resumable_state.decryption_nonce(), 64.into());
+
+        Ok(())
+    }
+
+    fn random_key_handle() -> AeadSecretKeyHandle {
+        let mut key_handle = [0u8; 32];
+        thread_rng().fill_bytes(&mut key_handle);
+        AeadSecretKeyHandle::new(HandleToSecret::new(key_handle.to_vec()))
+    }
 }
@@ -7,12 +7,13 @@ use ockam_identity::models::{CredentialSchemaIdentifier, Identifier};
 use ockam_identity::secure_channels::secure_channels;
 use ockam_identity::utils::AttributesBuilder;
 use ockam_identity::{
-    DecryptionResponse, EncryptionRequest, EncryptionResponse, IdentityAccessControlBuilder,
-    IdentitySecureChannelLocalInfo, SecureChannelListenerOptions, SecureChannelOptions,
-    SecureChannels, TrustEveryonePolicy, TrustIdentifierPolicy, Vault,
+    DecryptionRequest, DecryptionResponse, EncryptionRequest, EncryptionResponse,
+    HandshakeFailureReason, IdentityAccessControlBuilder, IdentitySecureChannelLocalInfo,
+    KeyAgreementPolicy, MessagePadding, SecureChannelApiClient, SecureChannelListenerOptions,
+    SecureChannelOptions, SecureChannels, TrustEveryonePolicy, TrustIdentifierPolicy, Vault,
     IDENTITY_SECURE_CHANNEL_IDENTIFIER,
 };
-use ockam_node::{Context, MessageReceiveOptions, WorkerBuilder};
+use ockam_node::{Context, MessageReceiveOptions, MessageSendReceiveOptions, WorkerBuilder};
 use ockam_vault::{
     SoftwareVaultForSecureChannels, SoftwareVaultForSigning, SoftwareVaultForVerifyingSignatures,
 };
@@ -212,6 +213,179 @@ async fn test_channel_send_credentials(context: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn test_channel_hybrid_key_agreement(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_options = SecureChannelListenerOptions::new()
+        .with_key_agreement_policy(KeyAgreementPolicy::RequireHybrid);
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(ctx, &bob, "bob_listener", bob_options)
+        .await?;
+
+    let alice_options =
+        SecureChannelOptions::new().with_key_agreement_policy(KeyAgreementPolicy::PreferHybrid);
+    let alice_channel = secure_channels
+        .create_secure_channel(ctx, &alice, route!["bob_listener"], alice_options)
+        .await?;
+
+    let mut child_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "child",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+
+    ctx.flow_controls()
+        .add_consumer("child", bob_listener.flow_control_id());
+
+    child_ctx
+        .send(
+            route![alice_channel, child_ctx.address()],
+            "Hello, Bob!".to_string(),
+        )
+        .await?;
+
+    let msg = child_ctx.receive::<String>().await?;
+    assert_eq!("Hello, Bob!", msg.into_body()?);
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn test_channel_rekey_after_messages(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    assert!(SecureChannelOptions::new()
+        .with_rekey_after_messages(0)
+        .is_err());
+    let error = SecureChannelOptions::new()
+        .with_rekey_after_messages(33)
+        .err()
+        .unwrap();
+    assert!(error.to_string().contains("between 1 and 32"));
+
+    let bob_options = SecureChannelListenerOptions::new().with_rekey_after_messages(3)?;
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(ctx, &bob, "bob_listener", bob_options)
+        .await?;
+
+    let alice_options = SecureChannelOptions::new()
+        .with_rekey_after_messages(2)?
+        .with_rekey_after_duration(Duration::from_secs(60));
+    let alice_channel = secure_channels
+        .create_secure_channel(ctx, &alice, route!["bob_listener"], alice_options)
+        .await?;
+
+    let mut child_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "child",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+
+    ctx.flow_controls()
+        .add_consumer("child", bob_listener.flow_control_id());
+
+    for n in 0..20 {
+        let payload = format!("Hello, Bob! {n}");
+        child_ctx
+            .send(
+                route![alice_channel.clone(), child_ctx.address()],
+                payload.clone(),
+            )
+            .await?;
+
+        let msg = child_ctx.receive::<String>().await?;
+        assert_eq!(payload, msg.into_body()?);
+    }
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn test_channel_padding(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    // only alice pads her messages
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &bob,
+            "bob_listener",
+            SecureChannelListenerOptions::new(),
+        )
+        .await?;
+
+    let alice_options = SecureChannelOptions::new().with_padding(MessagePadding::default());
+    let alice_channel = secure_channels
+        .create_secure_channel(ctx, &alice, route!["bob_listener"], alice_options)
+        .await?;
+
+    let mut child_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "child",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+
+    ctx.flow_controls()
+        .add_consumer("child", bob_listener.flow_control_id());
+
+    for payload in ["Hello, Bob!".to_string(), "a".repeat(5000)] {
+        child_ctx
+            .send(
+                route![alice_channel.clone(), child_ctx.address()],
+                payload.clone(),
+            )
+            .await?;
+
+        let msg = child_ctx.receive::<String>().await?;
+        assert_eq!(payload, msg.into_body()?);
+    }
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn test_channel_hybrid_key_agreement_required(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_options = SecureChannelListenerOptions::new()
+        .with_key_agreement_policy(KeyAgreementPolicy::RequireHybrid);
+    secure_channels
+        .create_secure_channel_listener(ctx, &bob, "bob_listener", bob_options)
+        .await?;
+
+    // alice does not offer the hybrid key agreement, so bob aborts the handshake
+    let alice_options = SecureChannelOptions::new().with_timeout(Duration::from_millis(500));
+    let result = secure_channels
+        .create_secure_channel(ctx, &alice, route!["bob_listener"], alice_options)
+        .await;
+    assert!(result.is_err());
+
+    Ok(())
+}
+
 #[ockam_macros::test]
 async fn test_channel_rejected_trust_policy(ctx: &mut Context) -> Result<()> {
     let secure_channels = secure_channels().await?;
@@ -266,6 +440,28 @@ async fn test_channel_rejected_trust_policy(ctx: &mut Context) -> Result<()> {
 
     assert!(result.is_err());
 
+    // alice completed the handshake but bob rejected it with its trust policy,
+    // wait until bob has processed the last handshake message
+    let handshake_diagnostics = secure_channels
+        .secure_channel_registry()
+        .handshake_diagnostics();
+    for _ in 0..50 {
+        if !handshake_diagnostics.statistics().failures.is_empty() {
+            break;
+        }
+        ctx.sleep(Duration::from_millis(100)).await;
+    }
+    let statistics = handshake_diagnostics.statistics();
+    assert_eq!(statistics.attempts, 2);
+    assert_eq!(statistics.successes, 1);
+    assert_eq!(
+        statistics
+            .failures
+            .get(&HandshakeFailureReason::TrustPolicyDenied),
+        Some(&1)
+    );
+    assert!(!statistics.recent_failures[0].is_initiator);
+
     Ok(())
 }
 
@@ -362,6 +558,8 @@ async fn test_channel_registry(ctx: &mut Context) -> Result<()> {
     assert!(alice_channel_data.is_initiator());
     assert_eq!(alice_channel_data.my_id(), &alice);
     assert_eq!(alice_channel_data.their_id(), &bob);
+    assert!(alice_channel_data.created_at().0 > 0);
+    assert!(alice_channel_data.last_activity() >= alice_channel_data.created_at());
 
     let mut bob_ctx = ctx
         .new_detached_with_mailboxes(Mailboxes::main(
@@ -395,6 +593,7 @@ async fn test_channel_registry(ctx: &mut Context) -> Result<()> {
     assert!(!bob_channel_data.is_initiator());
     assert_eq!(bob_channel_data.my_id(), &bob);
     assert_eq!(bob_channel_data.their_id(), &alice);
+    assert!(bob_channel_data.last_activity() >= bob_channel_data.created_at());
 
     Ok(())
 }
@@ -509,6 +708,97 @@ async fn test_channel_api(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn test_channel_api_client(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &bob,
+            "bob_listener",
+            SecureChannelListenerOptions::new(),
+        )
+        .await?;
+
+    let alice_channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice,
+            route!["bob_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+
+    let mut bob_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "bob",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+
+    ctx.flow_controls()
+        .add_consumer("bob", bob_listener.flow_control_id());
+
+    ctx.send(route![alice_channel.clone(), "bob"], "Hello".to_string())
+        .await?;
+
+    let msg = bob_ctx.receive::<String>().await?;
+    let bob_channel = msg.return_route().next().unwrap().clone();
+
+    let bob_channel_data = secure_channels
+        .secure_channel_registry()
+        .get_channel_by_encryptor_address(&bob_channel)
+        .unwrap();
+
+    let alice_client = SecureChannelApiClient::from(&alice_channel);
+    let bob_client = SecureChannelApiClient::from(&bob_channel_data);
+
+    let encrypted = alice_client.encrypt(ctx, b"Ping".to_vec()).await?;
+    assert_ne!(encrypted, b"Ping");
+    let decrypted = bob_client.decrypt(ctx, encrypted).await?;
+    assert_eq!(decrypted, b"Ping");
+
+    let encrypted = bob_client.encrypt(ctx, b"Pong".to_vec()).await?;
+    let decrypted = alice_client.decrypt(ctx, encrypted).await?;
+    assert_eq!(decrypted, b"Pong");
+
+    // The api of this channel can't be used by any worker
+    let closed_channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice,
+            route!["bob_listener"],
+            SecureChannelOptions::new().with_api_access_control(DenyAll),
+        )
+        .await?;
+
+    let res = ctx
+        .send_and_receive_extended::<EncryptionResponse>(
+            route![closed_channel.encryptor_api_address().clone()],
+            EncryptionRequest(b"Ping".to_vec()),
+            MessageSendReceiveOptions::new().with_timeout(Duration::from_millis(250)),
+        )
+        .await;
+    assert!(res.is_err());
+
+    let res = ctx
+        .send_and_receive_extended::<DecryptionResponse>(
+            route![closed_channel.decryptor_api_address().clone()],
+            DecryptionRequest(b"Pong".to_vec()),
+            MessageSendReceiveOptions::new().with_timeout(Duration::from_millis(250)),
+        )
+        .await;
+    assert!(res.is_err());
+
+    Ok(())
+}
+
 #[ockam_macros::test]
 async fn test_tunneled_secure_channel_works(ctx: &mut Context) -> Result<()> {
     let secure_channels = secure_channels().await?;
@@ -1,19 +1,23 @@
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::Duration;
 
-use ockam_core::api::Response;
+use minicbor::Decoder;
+use ockam_core::api::{Method, RequestHeader, Response};
 use ockam_core::compat::sync::Arc;
-use ockam_core::{async_trait, Any, AsyncTryClone, Routed, Worker};
+use ockam_core::{async_trait, AsyncTryClone, Routed, Worker};
 use ockam_core::{route, Result};
-use ockam_identity::models::CredentialSchemaIdentifier;
+use ockam_identity::models::{
+    AttributeDefinition, AttributeType, CredentialSchema, CredentialSchemaIdentifier,
+};
 use ockam_identity::secure_channels::secure_channels;
 use ockam_identity::utils::AttributesBuilder;
 use ockam_identity::{
-    Credentials, Identifier, IdentitySecureChannelLocalInfo, RemoteCredentialRetrieverCreator,
+    CredentialRetrieverCreator, Credentials, Identifier, Identities,
+    IdentitySecureChannelLocalInfo, RemoteCredentialRetrieverCreator,
     RemoteCredentialRetrieverInfo, RemoteCredentialRetrieverTimingOptions,
     SecureChannelListenerOptions, SecureChannelOptions, SecureChannels,
 };
-use ockam_node::Context;
+use ockam_node::{Context, NodeEvent};
 use ockam_transport_tcp::TcpTransport;
 
 struct CredentialIssuer {
@@ -23,11 +27,12 @@ struct CredentialIssuer {
     credentials: Arc<Credentials>,
     authority: Identifier,
     ttl: Duration,
+    schema: Option<CredentialSchema>,
 }
 
 #[async_trait]
 impl Worker for CredentialIssuer {
-    type Message = Any;
+    type Message = Vec<u8>;
     type Context = Context;
 
     async fn handle_message(
@@ -41,6 +46,22 @@ impl Worker for CredentialIssuer {
 
         let subject =
             IdentitySecureChannelLocalInfo::find_info(msg.local_message())?.their_identity_id();
+
+        let return_route = msg.return_route();
+        let body = msg.into_body()?;
+        let request: RequestHeader = Decoder::new(&body).decode()?;
+        if let (Some(Method::Get), "/schema") = (request.method(), request.path()) {
+            let response = match &self.schema {
+                Some(schema) => Response::ok()
+                    .with_headers(&request)
+                    .body(schema)
+                    .to_vec()?,
+                None => Response::not_found(&request, "no credential schema").to_vec()?,
+            };
+            ctx.send(return_route, response).await?;
+            return Ok(());
+        }
+
         let credential = self
             .credentials
             .credentials_creation()
@@ -59,7 +80,7 @@ impl Worker for CredentialIssuer {
         self.call_counter.fetch_add(1, Ordering::Relaxed);
 
         ctx.sleep(self.delay).await;
-        ctx.send(msg.return_route(), response).await?;
+        ctx.send(return_route, response).await?;
 
         Ok(())
     }
@@ -69,6 +90,7 @@ impl Worker for CredentialIssuer {
 async fn autorefresh(ctx: &mut Context) -> Result<()> {
     let timing_options = RemoteCredentialRetrieverTimingOptions {
         min_refresh_interval: Duration::from_secs(1),
+        max_refresh_interval: Duration::from_secs(1),
         proactive_refresh_gap: 1.into(),
         clock_skew_gap: 0.into(),
         request_timeout: Duration::from_secs(2),
@@ -79,6 +101,7 @@ async fn autorefresh(ctx: &mut Context) -> Result<()> {
         Duration::from_secs(0),
         Duration::from_secs(5),
         timing_options,
+        None,
     )
     .await?;
 
@@ -150,10 +173,63 @@ async fn autorefresh(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn expiry_warning(ctx: &mut Context) -> Result<()> {
+    let timing_options = RemoteCredentialRetrieverTimingOptions {
+        min_refresh_interval: Duration::from_secs(1),
+        max_refresh_interval: Duration::from_secs(1),
+        proactive_refresh_gap: 1.into(),
+        clock_skew_gap: 0.into(),
+        expiry_warning_gap: 5.into(),
+        request_timeout: Duration::from_secs(1),
+        ..Default::default()
+    };
+    let res = init(
+        ctx,
+        Duration::from_secs(0),
+        Duration::from_secs(5),
+        timing_options,
+        None,
+    )
+    .await?;
+
+    let _channel = res
+        .client_secure_channels
+        .create_secure_channel(
+            ctx,
+            &res.client,
+            route!["server_api"],
+            SecureChannelOptions::new()
+                .with_credential_retriever_creator(res.retriever)?
+                .with_authority(res.authority.clone()),
+        )
+        .await?;
+
+    // The Authority node stops responding, the credential can't be refreshed anymore
+    let mut events = ctx.subscribe_events();
+    res.pause.store(true, Ordering::Relaxed);
+
+    let expiring = tokio::time::timeout(Duration::from_secs(10), async {
+        while let Some(event) = events.next().await {
+            if let NodeEvent::CredentialExpiring { subject, .. } = event {
+                return Some(subject);
+            }
+        }
+        None
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(expiring, Some(res.client.to_string()));
+
+    Ok(())
+}
+
 #[ockam_macros::test]
 async fn init_fail(ctx: &mut Context) -> Result<()> {
     let timing_options = RemoteCredentialRetrieverTimingOptions {
         min_refresh_interval: Duration::from_secs(1),
+        max_refresh_interval: Duration::from_secs(1),
         proactive_refresh_gap: 1.into(),
         clock_skew_gap: 0.into(),
         request_timeout: Duration::from_secs(2),
@@ -164,6 +240,7 @@ async fn init_fail(ctx: &mut Context) -> Result<()> {
         Duration::from_secs(0),
         Duration::from_secs(5),
         timing_options,
+        None,
     )
     .await?;
 
@@ -186,6 +263,190 @@ async fn init_fail(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn reject_credential_breaking_schema(ctx: &mut Context) -> Result<()> {
+    let schema = CredentialSchema {
+        identifier: CredentialSchemaIdentifier(1),
+        attributes: vec![AttributeDefinition {
+            name: "key".to_string(),
+            attribute_type: AttributeType::String,
+            allowed_values: vec!["value".to_string()],
+            required: true,
+        }],
+    };
+    let res = init(
+        ctx,
+        Duration::from_secs(0),
+        Duration::from_secs(60),
+        Default::default(),
+        Some(schema.clone()),
+    )
+    .await?;
+
+    // The server retrieves its own credential and the schema published by the Authority
+    res.server_retriever
+        .create(&res.server)
+        .await?
+        .initialize()
+        .await?;
+    assert_eq!(
+        res.server_secure_channels
+            .identities()
+            .credential_schemas()
+            .get(CredentialSchemaIdentifier(1)),
+        Some(schema)
+    );
+
+    // The Authority issues the credentials of the client directly, it needs to know its identity
+    let client_identity = res
+        .client_secure_channels
+        .identities()
+        .export_identity(&res.client)
+        .await?;
+    res.authority_secure_channels
+        .identities()
+        .identities_verification()
+        .import(Some(&res.client), &client_identity)
+        .await?;
+
+    // A credential which breaks the schema is rejected by the server
+    let issue_credential = |value: &'static [u8]| {
+        let credentials = res.authority_secure_channels.identities().credentials();
+        let authority = res.authority.clone();
+        let client = res.client.clone();
+        async move {
+            credentials
+                .credentials_creation()
+                .issue_credential(
+                    &authority,
+                    &client,
+                    AttributesBuilder::with_schema(CredentialSchemaIdentifier(1))
+                        .with_attribute(b"key", value)
+                        .build(),
+                    Duration::from_secs(60),
+                )
+                .await
+        }
+    };
+    let _channel = res
+        .client_secure_channels
+        .create_secure_channel(
+            ctx,
+            &res.client,
+            route!["server_api"],
+            SecureChannelOptions::new()
+                .with_credential(issue_credential(b"other value").await?)?
+                .with_authority(res.authority.clone()),
+        )
+        .await?;
+    ctx.sleep(Duration::from_secs(1)).await;
+    assert!(res
+        .server_secure_channels
+        .identities()
+        .identities_attributes()
+        .get_attributes(&res.client, &res.authority)
+        .await?
+        .is_none());
+
+    // A credential which is valid for the schema is accepted
+    let _channel = res
+        .client_secure_channels
+        .create_secure_channel(
+            ctx,
+            &res.client,
+            route!["server_api"],
+            SecureChannelOptions::new()
+                .with_credential(issue_credential(b"value").await?)?
+                .with_authority(res.authority.clone()),
+        )
+        .await?;
+    ctx.sleep(Duration::from_secs(1)).await;
+    assert!(res
+        .server_secure_channels
+        .identities()
+        .identities_attributes()
+        .get_attributes(&res.client, &res.authority)
+        .await?
+        .is_some());
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn retrieve_schema_with_cached_credential(ctx: &mut Context) -> Result<()> {
+    let schema = CredentialSchema {
+        identifier: CredentialSchemaIdentifier(1),
+        attributes: vec![AttributeDefinition {
+            name: "key".to_string(),
+            attribute_type: AttributeType::String,
+            allowed_values: vec![],
+            required: true,
+        }],
+    };
+    let res = init(
+        ctx,
+        Duration::from_secs(0),
+        Duration::from_secs(3600),
+        Default::default(),
+        Some(schema.clone()),
+    )
+    .await?;
+
+    // The server retrieves its own credential, which is then cached
+    res.server_retriever
+        .create(&res.server)
+        .await?
+        .initialize()
+        .await?;
+    assert_eq!(res.call_counter.load(Ordering::Relaxed), 1);
+
+    // After a restart, the server uses its cached credential but the schema, which is not
+    // cached, is registered again before the initialization returns
+    let server_identities = res.server_secure_channels.identities();
+    let identities = Identities::builder()
+        .await?
+        .with_vault(server_identities.vault())
+        .with_change_history_repository(server_identities.change_history_repository())
+        .with_purpose_keys_repository(server_identities.purpose_keys_repository())
+        .with_cached_credential_repository(server_identities.cached_credentials_repository())
+        .build();
+    let restarted_secure_channels = SecureChannels::from_identities(
+        identities.clone(),
+        res.server_secure_channels.secure_channel_repository(),
+    );
+    assert_eq!(
+        identities
+            .credential_schemas()
+            .get(CredentialSchemaIdentifier(1)),
+        None
+    );
+
+    RemoteCredentialRetrieverCreator::new_extended(
+        ctx.async_try_clone().await?,
+        Arc::new(TcpTransport::create(ctx).await?),
+        restarted_secure_channels,
+        RemoteCredentialRetrieverInfo::create_for_project_member(
+            res.authority.clone(),
+            route!["authority_api"],
+        ),
+        "test".to_string(),
+        Default::default(),
+    )
+    .create(&res.server)
+    .await?
+    .initialize()
+    .await?;
+    assert_eq!(res.call_counter.load(Ordering::Relaxed), 1);
+    assert_eq!(
+        identities
+            .credential_schemas()
+            .get(CredentialSchemaIdentifier(1)),
+        Some(schema)
+    );
+
+    Ok(())
+}
+
 #[allow(dead_code)]
 struct InitResult {
     call_counter: Arc<AtomicU64>,
@@ -200,6 +461,7 @@ struct InitResult {
     authority_secure_channels: Arc<SecureChannels>,
 
     retriever: Arc<RemoteCredentialRetrieverCreator>,
+    server_retriever: Arc<RemoteCredentialRetrieverCreator>,
 }
 
 async fn init(
@@ -207,6 +469,7 @@ async fn init(
     delay: Duration,
     ttl: Duration,
     timing_options: RemoteCredentialRetrieverTimingOptions,
+    schema: Option<CredentialSchema>,
 ) -> Result<InitResult> {
     let tcp = TcpTransport::create(ctx).await?;
 
@@ -248,6 +511,7 @@ async fn init(
         credentials: authority_identities.credentials(),
         authority: authority.clone(),
         ttl,
+        schema,
     };
 
     ctx.start_worker("credential_issuer", issuer).await?;
@@ -275,7 +539,7 @@ async fn init(
 
     let retriever = Arc::new(RemoteCredentialRetrieverCreator::new_extended(
         ctx.async_try_clone().await?,
-        Arc::new(tcp),
+        Arc::new(tcp.clone()),
         client_secure_channels.clone(),
         RemoteCredentialRetrieverInfo::create_for_project_member(
             authority.clone(),
@@ -285,6 +549,18 @@ async fn init(
         timing_options,
     ));
 
+    let server_retriever = Arc::new(RemoteCredentialRetrieverCreator::new_extended(
+        ctx.async_try_clone().await?,
+        Arc::new(tcp),
+        server_secure_channels.clone(),
+        RemoteCredentialRetrieverInfo::create_for_project_member(
+            authority.clone(),
+            route!["authority_api"],
+        ),
+        "test".to_string(),
+        timing_options,
+    ));
+
     Ok(InitResult {
         call_counter,
         pause,
@@ -295,5 +571,6 @@ async fn init(
         server_secure_channels,
         authority_secure_channels,
         retriever,
+        server_retriever,
     })
 }
@@ -1,12 +1,15 @@
-use ockam_core::{route, Address};
+use ockam_core::{route, Address, AllowAll, Mailboxes};
+use ockam_identity::models::Identifier;
 use ockam_identity::{
     secure_channels, DecryptionRequest, DecryptionResponse, EncryptionRequest, EncryptionResponse,
-    SecureChannelListenerOptions, SecureChannelOptions, SecureChannelSqlxDatabase, SecureChannels,
+    IdentitySecureChannelLocalInfo, SecureChannelListenerOptions, SecureChannelOptions,
+    SecureChannelSqlxDatabase, SecureChannels,
 };
 use ockam_node::compat::futures::FutureExt;
 use ockam_node::database::SqlxDatabase;
 use ockam_node::{Context, NodeBuilder};
 use ockam_vault::storage::SecretsSqlxDatabase;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 use tempfile::NamedTempFile;
@@ -382,3 +385,185 @@ fn test_persistence() -> ockam_core::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_resumable_persistence() -> ockam_core::Result<()> {
+    let (_db_file_alice, db_file_alice_path) = NamedTempFile::new().unwrap().keep().unwrap();
+    let db_file_alice_path_clone = db_file_alice_path.clone();
+
+    let (_db_file_bob, db_file_bob_path) = NamedTempFile::new().unwrap().keep().unwrap();
+    let db_file_bob_path_clone = db_file_bob_path.clone();
+
+    struct PassBetweenEnv {
+        alice: Identifier,
+        bob: Identifier,
+        decryptor_remote_address_alice: Address,
+        decryptor_remote_address_bob: Address,
+    }
+
+    let (ctx1, mut executor1) = NodeBuilder::new().build();
+    let data = executor1
+        .execute(async move {
+            let data = std::panic::AssertUnwindSafe(async {
+                let secure_channels_alice =
+                    create_secure_channels(db_file_alice_path_clone.as_path()).await?;
+                let secure_channels_bob =
+                    create_secure_channels(db_file_bob_path_clone.as_path()).await?;
+
+                let alice = secure_channels_alice
+                    .identities()
+                    .identities_creation()
+                    .create_identity()
+                    .await?;
+                let bob = secure_channels_bob
+                    .identities()
+                    .identities_creation()
+                    .create_identity()
+                    .await?;
+
+                let bob_options = SecureChannelListenerOptions::new().resumable()?;
+                let bob_listener = secure_channels_bob
+                    .create_secure_channel_listener(&ctx1, &bob, "bob_listener", bob_options)
+                    .await?;
+
+                let alice_options = SecureChannelOptions::new().resumable()?;
+                let alice_channel = secure_channels_alice
+                    .create_secure_channel(&ctx1, &alice, route!["bob_listener"], alice_options)
+                    .await?;
+
+                let mut child_ctx = ctx1
+                    .new_detached_with_mailboxes(Mailboxes::main(
+                        "child",
+                        Arc::new(AllowAll),
+                        Arc::new(AllowAll),
+                    ))
+                    .await?;
+                ctx1.flow_controls()
+                    .add_consumer("child", bob_listener.flow_control_id());
+                ctx1.flow_controls()
+                    .add_consumer("child", alice_channel.flow_control_id());
+
+                // exchange enough messages for the keys to be rotated on both sides
+                for n in 0..40u8 {
+                    child_ctx
+                        .send(route![alice_channel.clone(), "child"], vec![n])
+                        .await?;
+                    let msg = child_ctx.receive::<Vec<u8>>().await?;
+                    let return_route = msg.return_route();
+                    assert_eq!(msg.into_body()?, vec![n]);
+
+                    child_ctx.send(return_route, vec![n]).await?;
+                    assert_eq!(child_ctx.receive::<Vec<u8>>().await?.into_body()?, vec![n]);
+                }
+
+                let bob_channel = secure_channels_bob
+                    .secure_channel_registry()
+                    .get_channel_list()[0]
+                    .clone();
+
+                let data = PassBetweenEnv {
+                    alice,
+                    bob,
+                    decryptor_remote_address_alice: alice_channel
+                        .decryptor_remote_address()
+                        .clone(),
+                    decryptor_remote_address_bob: bob_channel.decryptor_messaging_address().clone(),
+                };
+
+                Result::<PassBetweenEnv, ockam_core::Error>::Ok(data)
+            })
+            .catch_unwind()
+            .await;
+
+            ctx1.stop().await?;
+
+            data.unwrap()
+        })
+        .unwrap()
+        .unwrap();
+
+    let (ctx2, mut executor2) = NodeBuilder::new().build();
+    executor2
+        .execute(async move {
+            let res = std::panic::AssertUnwindSafe(async {
+                let secure_channels_alice =
+                    create_secure_channels(db_file_alice_path.as_path()).await?;
+                let secure_channels_bob =
+                    create_secure_channels(db_file_bob_path.as_path()).await?;
+
+                // both nodes run in the same process, so the route to the other decryptor is local
+                let alice_channel = secure_channels_alice
+                    .resume_secure_channel(
+                        &ctx2,
+                        &data.decryptor_remote_address_alice,
+                        route![],
+                        SecureChannelOptions::new(),
+                    )
+                    .await?;
+                let bob_channel = secure_channels_bob
+                    .resume_secure_channel(
+                        &ctx2,
+                        &data.decryptor_remote_address_bob,
+                        route![],
+                        SecureChannelOptions::new(),
+                    )
+                    .await?;
+
+                let mut child_ctx = ctx2
+                    .new_detached_with_mailboxes(Mailboxes::main(
+                        "child",
+                        Arc::new(AllowAll),
+                        Arc::new(AllowAll),
+                    ))
+                    .await?;
+                ctx2.flow_controls()
+                    .add_consumer("child", alice_channel.flow_control_id());
+                ctx2.flow_controls()
+                    .add_consumer("child", bob_channel.flow_control_id());
+
+                for n in 0..40u8 {
+                    child_ctx
+                        .send(route![alice_channel.clone(), "child"], vec![n])
+                        .await?;
+                    let msg = child_ctx.receive::<Vec<u8>>().await?;
+                    let local_info =
+                        IdentitySecureChannelLocalInfo::find_info(msg.local_message())?;
+                    assert_eq!(local_info.their_identity_id(), data.alice);
+                    assert_eq!(msg.into_body()?, vec![n]);
+
+                    child_ctx
+                        .send(route![bob_channel.clone(), "child"], vec![n])
+                        .await?;
+                    let msg = child_ctx.receive::<Vec<u8>>().await?;
+                    let local_info =
+                        IdentitySecureChannelLocalInfo::find_info(msg.local_message())?;
+                    assert_eq!(local_info.their_identity_id(), data.bob);
+                    assert_eq!(msg.into_body()?, vec![n]);
+                }
+
+                ockam_core::Result::<()>::Ok(())
+            })
+            .catch_unwind()
+            .await;
+
+            ctx2.stop().await?;
+
+            res.unwrap()
+        })
+        .unwrap()
+        .unwrap();
+
+    Ok(())
+}
+
+async fn create_secure_channels(path: &Path) -> ockam_core::Result<Arc<SecureChannels>> {
+    let db = SqlxDatabase::create_sqlite(path).await?;
+    let secure_channel_repository = Arc::new(SecureChannelSqlxDatabase::new(db.clone()));
+    let secrets_repository = Arc::new(SecretsSqlxDatabase::new(db));
+
+    Ok(SecureChannels::builder()
+        .await?
+        .with_secure_channel_repository(secure_channel_repository)
+        .with_secrets_repository(secrets_repository)
+        .build())
+}
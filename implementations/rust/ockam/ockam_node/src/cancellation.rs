@@ -0,0 +1,80 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+use ockam_core::compat::sync::Arc;
+use tokio::sync::Notify;
+
+/// A token used to cancel the operations waiting on it, for example
+/// [`Context::receive_with_deadline`](crate::Context::receive_with_deadline).
+///
+/// Clones of a token share the same state: cancelling one of them cancels all of them.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancellationToken {
+    /// Create a new token which is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the token and wake up all the operations waiting on it
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::Release);
+        self.inner.notify.notify_waiters();
+    }
+
+    /// Return true if the token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Wait until the token is cancelled
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.inner.notify.notified();
+            tokio::pin!(notified);
+            // register the waiter before checking the state so that a concurrent
+            // cancellation can not be missed
+            notified.as_mut().enable();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::time::Duration;
+    use tokio::time::timeout;
+
+    #[tokio::test]
+    async fn test_cancel_wakes_up_waiters() {
+        let token = CancellationToken::new();
+        let waiter = {
+            let token = token.clone();
+            tokio::spawn(async move { token.cancelled().await })
+        };
+        assert!(!token.is_cancelled());
+
+        token.cancel();
+        assert!(token.is_cancelled());
+        timeout(Duration::from_secs(1), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // a cancelled token returns immediately
+        timeout(Duration::from_secs(1), token.cancelled())
+            .await
+            .unwrap();
+    }
+}
@@ -1,9 +1,17 @@
+use core::fmt::{Debug, Formatter};
+
 #[cfg(feature = "std")]
 use tokio::sync;
 
 #[cfg(not(feature = "std"))]
 use crate::tokio::sync;
 
+#[cfg(feature = "std")]
+use crate::compat::asynchronous::Mutex as AsyncMutex;
+use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
+use ockam_core::{Address, MessagePriority, RelayMessage};
+
 /// Sender used to send payload messages
 pub type MessageSender<T> = sync::mpsc::Sender<T>;
 /// Receiver used to receive payload messages
@@ -14,6 +22,270 @@ pub fn message_channel<T>() -> (MessageSender<T>, MessageReceiver<T>) {
     sync::mpsc::channel(8)
 }
 
+/// Default capacity of a worker mailbox
+pub const DEFAULT_MAILBOX_CAPACITY: usize = 8;
+
+/// Policy applied when a message is sent to a full mailbox
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Wait until the mailbox has room for the new message, which slows down the sender
+    #[default]
+    Backpressure,
+    /// Drop the oldest message of the mailbox to make room for the new message
+    DropOldest,
+    /// Drop the new message
+    DropNewest,
+}
+
+/// Capacity and overflow policy of a worker mailbox
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MailboxConfig {
+    capacity: usize,
+    overflow_policy: OverflowPolicy,
+}
+
+impl Default for MailboxConfig {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAILBOX_CAPACITY, OverflowPolicy::default())
+    }
+}
+
+impl MailboxConfig {
+    /// Create a mailbox configuration. The capacity is at least 1
+    pub fn new(capacity: usize, overflow_policy: OverflowPolicy) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            overflow_policy,
+        }
+    }
+
+    /// Maximum number of messages waiting in the mailbox
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Policy applied when a message is sent to a full mailbox
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.overflow_policy
+    }
+}
+
+/// Messages which can be delivered through the high-priority lane of a mailbox
+pub trait Prioritized {
+    /// Priority of the message
+    fn priority(&self) -> MessagePriority;
+}
+
+impl Prioritized for RelayMessage {
+    fn priority(&self) -> MessagePriority {
+        RelayMessage::priority(self)
+    }
+}
+
+/// Sender used to send messages to a worker mailbox.
+/// The overflow policy of the mailbox is applied when the mailbox is full.
+///
+/// High-priority messages are sent to a separate lane which is always serviced first.
+///
+/// Note: without the `std` feature mailboxes are unbounded, the overflow policy is not applied
+/// and all the messages are delivered in order, regardless of their priority
+pub struct MailboxSender<T> {
+    sender: MessageSender<T>,
+    overflow_policy: OverflowPolicy,
+    /// Addresses of the high-priority mailboxes
+    high_priority_addresses: Arc<Vec<Address>>,
+    /// Sender for the high-priority lane, if some mailboxes have a high priority
+    #[cfg(feature = "std")]
+    high_priority_sender: Option<MessageSender<T>>,
+    /// The receiver is shared with the senders when the oldest messages must be dropped
+    #[cfg(feature = "std")]
+    shared_receiver: Option<Arc<AsyncMutex<MessageReceiver<T>>>>,
+}
+
+impl<T> Clone for MailboxSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            overflow_policy: self.overflow_policy,
+            high_priority_addresses: self.high_priority_addresses.clone(),
+            #[cfg(feature = "std")]
+            high_priority_sender: self.high_priority_sender.clone(),
+            #[cfg(feature = "std")]
+            shared_receiver: self.shared_receiver.clone(),
+        }
+    }
+}
+
+impl<T> Debug for MailboxSender<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MailboxSender")
+            .field("overflow_policy", &self.overflow_policy)
+            .finish()
+    }
+}
+
+impl<T> MailboxSender<T> {
+    /// Return the priority of the messages sent to a given mailbox address
+    pub fn priority_of(&self, address: &Address) -> MessagePriority {
+        if self.high_priority_addresses.contains(address) {
+            MessagePriority::High
+        } else {
+            MessagePriority::Normal
+        }
+    }
+}
+
+impl<T: Prioritized> MailboxSender<T> {
+    /// Send a message to the mailbox, applying the overflow policy if the mailbox is full.
+    /// An error is only returned if the mailbox is closed
+    #[cfg(feature = "std")]
+    pub async fn send(&self, msg: T) -> Result<(), sync::mpsc::error::SendError<T>> {
+        use sync::mpsc::error::{SendError, TrySendError};
+
+        // high-priority messages are never dropped
+        if let Some(high_priority_sender) = &self.high_priority_sender {
+            if msg.priority() == MessagePriority::High {
+                return high_priority_sender.send(msg).await;
+            }
+        }
+
+        match (self.overflow_policy, &self.shared_receiver) {
+            (OverflowPolicy::DropNewest, _) => match self.sender.try_send(msg) {
+                Err(TrySendError::Full(_)) => {
+                    warn!("A mailbox is full, the new message is dropped");
+                    Ok(())
+                }
+                Err(TrySendError::Closed(msg)) => Err(SendError(msg)),
+                Ok(()) => Ok(()),
+            },
+            (OverflowPolicy::DropOldest, Some(receiver)) => {
+                let mut msg = msg;
+                loop {
+                    match self.sender.try_send(msg) {
+                        Err(TrySendError::Full(m)) => {
+                            msg = m;
+                            // the receiver only holds the lock while it waits for a message
+                            // so it is released as soon as the mailbox is not empty
+                            if receiver.lock().await.try_recv().is_ok() {
+                                warn!("A mailbox is full, the oldest message is dropped");
+                            }
+                        }
+                        Err(TrySendError::Closed(msg)) => return Err(SendError(msg)),
+                        Ok(()) => return Ok(()),
+                    }
+                }
+            }
+            _ => self.sender.send(msg).await,
+        }
+    }
+
+    /// Send a message to the mailbox
+    #[cfg(not(feature = "std"))]
+    pub async fn send(&self, msg: T) -> Result<(), sync::mpsc::error::SendError<T>> {
+        self.sender.send(msg).await
+    }
+}
+
+/// Receiver used to receive the messages of a worker mailbox
+pub struct MailboxReceiver<T> {
+    receiver: MailboxReceiverKind<T>,
+    /// Receiver for the high-priority lane, if some mailboxes have a high priority
+    #[cfg(feature = "std")]
+    high_priority_receiver: Option<MessageReceiver<T>>,
+}
+
+enum MailboxReceiverKind<T> {
+    Owned(MessageReceiver<T>),
+    #[cfg(feature = "std")]
+    Shared(Arc<AsyncMutex<MessageReceiver<T>>>),
+}
+
+impl<T> Debug for MailboxReceiver<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MailboxReceiver").finish()
+    }
+}
+
+impl<T> MailboxReceiver<T> {
+    /// Receive the next message of the mailbox, starting with the high-priority messages.
+    /// Return `None` when the mailbox is closed and all its messages have been received
+    pub async fn recv(&mut self) -> Option<T> {
+        #[cfg(feature = "std")]
+        if let Some(high_priority_receiver) = &mut self.high_priority_receiver {
+            if let Ok(msg) = high_priority_receiver.try_recv() {
+                return Some(msg);
+            }
+            let msg = tokio::select! {
+                biased;
+                Some(msg) = high_priority_receiver.recv() => return Some(msg),
+                msg = Self::recv_lane(&mut self.receiver) => msg,
+            };
+            return match msg {
+                Some(msg) => Some(msg),
+                // the mailbox is closed, return the remaining high-priority messages
+                None => high_priority_receiver.recv().await,
+            };
+        }
+        Self::recv_lane(&mut self.receiver).await
+    }
+
+    /// Receive the next message of the normal-priority lane
+    async fn recv_lane(receiver: &mut MailboxReceiverKind<T>) -> Option<T> {
+        match receiver {
+            MailboxReceiverKind::Owned(receiver) => receiver.recv().await,
+            #[cfg(feature = "std")]
+            MailboxReceiverKind::Shared(receiver) => receiver.lock().await.recv().await,
+        }
+    }
+}
+
+/// Create a worker mailbox channel with a given configuration.
+/// The messages sent to the `high_priority_addresses` are delivered first
+pub fn mailbox_channel<T>(
+    config: MailboxConfig,
+    high_priority_addresses: Vec<Address>,
+) -> (MailboxSender<T>, MailboxReceiver<T>) {
+    let (sender, receiver) = sync::mpsc::channel(config.capacity());
+
+    #[cfg(feature = "std")]
+    let (high_priority_sender, high_priority_receiver) = if high_priority_addresses.is_empty() {
+        (None, None)
+    } else {
+        let (sender, receiver) = sync::mpsc::channel(config.capacity());
+        (Some(sender), Some(receiver))
+    };
+
+    #[cfg(feature = "std")]
+    let (receiver, shared_receiver) = if config.overflow_policy() == OverflowPolicy::DropOldest {
+        let receiver = Arc::new(AsyncMutex::new(receiver));
+        (
+            MailboxReceiverKind::Shared(receiver.clone()),
+            Some(receiver),
+        )
+    } else {
+        (MailboxReceiverKind::Owned(receiver), None)
+    };
+    #[cfg(not(feature = "std"))]
+    let receiver = MailboxReceiverKind::Owned(receiver);
+
+    (
+        MailboxSender {
+            sender,
+            overflow_policy: config.overflow_policy(),
+            high_priority_addresses: Arc::new(high_priority_addresses),
+            #[cfg(feature = "std")]
+            high_priority_sender,
+            #[cfg(feature = "std")]
+            shared_receiver,
+        },
+        MailboxReceiver {
+            receiver,
+            #[cfg(feature = "std")]
+            high_priority_receiver,
+        },
+    )
+}
+
 /// Router sender
 pub type RouterSender<T> = sync::mpsc::Sender<T>;
 /// Router receiver
@@ -45,3 +317,91 @@ pub type OneshotReceiver<T> = sync::oneshot::Receiver<T>;
 pub fn oneshot_channel<T>() -> (OneshotSender<T>, OneshotReceiver<T>) {
     sync::oneshot::channel()
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use core::time::Duration;
+    use tokio::time::timeout;
+
+    impl Prioritized for i32 {
+        fn priority(&self) -> MessagePriority {
+            if *self < 0 {
+                MessagePriority::High
+            } else {
+                MessagePriority::Normal
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mailbox_high_priority_messages_first() {
+        let (sender, mut receiver) =
+            mailbox_channel(MailboxConfig::default(), vec!["control".into()]);
+        assert_eq!(sender.priority_of(&"control".into()), MessagePriority::High);
+        assert_eq!(sender.priority_of(&"data".into()), MessagePriority::Normal);
+
+        for i in [1, 2, -1, 3, -2] {
+            sender.send(i).await.unwrap();
+        }
+        drop(sender);
+
+        let mut received = vec![];
+        while let Some(msg) = receiver.recv().await {
+            received.push(msg);
+        }
+        assert_eq!(received, vec![-1, -2, 1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_mailbox_drop_newest() {
+        let (sender, mut receiver) =
+            mailbox_channel(MailboxConfig::new(2, OverflowPolicy::DropNewest), vec![]);
+        for i in 0..4 {
+            sender.send(i).await.unwrap();
+        }
+        drop(sender);
+
+        assert_eq!(receiver.recv().await, Some(0));
+        assert_eq!(receiver.recv().await, Some(1));
+        assert_eq!(receiver.recv().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_mailbox_drop_oldest() {
+        let (sender, mut receiver) =
+            mailbox_channel(MailboxConfig::new(2, OverflowPolicy::DropOldest), vec![]);
+        for i in 0..4 {
+            sender.send(i).await.unwrap();
+        }
+        drop(sender);
+
+        assert_eq!(receiver.recv().await, Some(2));
+        assert_eq!(receiver.recv().await, Some(3));
+        assert_eq!(receiver.recv().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_mailbox_backpressure() {
+        let (sender, mut receiver) =
+            mailbox_channel(MailboxConfig::new(1, OverflowPolicy::Backpressure), vec![]);
+        sender.send(0).await.unwrap();
+
+        // the mailbox is full, the sender waits
+        assert!(timeout(Duration::from_millis(50), sender.send(1))
+            .await
+            .is_err());
+
+        assert_eq!(receiver.recv().await, Some(0));
+        sender.send(1).await.unwrap();
+        assert_eq!(receiver.recv().await, Some(1));
+    }
+
+    #[test]
+    fn test_mailbox_config_minimum_capacity() {
+        assert_eq!(
+            MailboxConfig::new(0, OverflowPolicy::DropNewest).capacity(),
+            1
+        );
+    }
+}
@@ -23,4 +23,6 @@ pub mod asynchronous {
 }
 
 pub use crate::tokio;
-pub use tokio::time::timeout;
+pub use time::timeout;
+
+pub mod time;
@@ -0,0 +1,78 @@
+//! Timers used by the node.
+//!
+//! The Tokio timers are used by default. With the `smol` feature, the timers of the smol
+//! reactor are used instead. They don't need to be polled within a Tokio runtime, so that
+//! the tasks of a node can run on any executor, like the `SmolExecutor`.
+
+#[cfg(not(feature = "smol"))]
+pub use crate::tokio::time::{error::Elapsed, sleep, timeout};
+#[cfg(all(feature = "std", not(feature = "smol")))]
+pub use tokio_timers::{sleep_until, timeout_at};
+
+#[cfg(feature = "smol")]
+pub use smol_timers::{sleep, sleep_until, timeout, timeout_at, Elapsed};
+
+#[cfg(all(feature = "std", not(feature = "smol")))]
+mod tokio_timers {
+    use super::Elapsed;
+    use core::future::Future;
+    use std::time::Instant;
+
+    /// Wait until a deadline is reached
+    pub async fn sleep_until(deadline: Instant) {
+        tokio::time::sleep_until(deadline.into()).await
+    }
+
+    /// Wait for a future to complete before a deadline, return an error otherwise
+    pub async fn timeout_at<F: Future>(deadline: Instant, future: F) -> Result<F::Output, Elapsed> {
+        tokio::time::timeout_at(deadline.into(), future).await
+    }
+}
+
+#[cfg(feature = "smol")]
+mod smol_timers {
+    use core::fmt;
+    use core::future::Future;
+    use core::time::Duration;
+    use std::time::Instant;
+
+    /// Error returned when a future did not complete before its deadline
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Elapsed;
+
+    impl fmt::Display for Elapsed {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            "deadline has elapsed".fmt(f)
+        }
+    }
+
+    impl std::error::Error for Elapsed {}
+
+    /// Wait for a given duration
+    pub async fn sleep(duration: Duration) {
+        smol::Timer::after(duration).await;
+    }
+
+    /// Wait for a future to complete within a given duration, return an error otherwise
+    pub async fn timeout<F: Future>(duration: Duration, future: F) -> Result<F::Output, Elapsed> {
+        match Instant::now().checked_add(duration) {
+            Some(deadline) => timeout_at(deadline, future).await,
+            // the deadline is too far in the future to ever be reached
+            None => Ok(future.await),
+        }
+    }
+
+    /// Wait until a deadline is reached
+    pub async fn sleep_until(deadline: Instant) {
+        smol::Timer::at(deadline).await;
+    }
+
+    /// Wait for a future to complete before a deadline, return an error otherwise
+    pub async fn timeout_at<F: Future>(deadline: Instant, future: F) -> Result<F::Output, Elapsed> {
+        smol::future::or(async { Ok(future.await) }, async {
+            smol::Timer::at(deadline).await;
+            Err(Elapsed)
+        })
+        .await
+    }
+}
@@ -1,6 +1,13 @@
-use crate::channel_types::{SmallReceiver, SmallSender};
+use crate::channel_types::{MailboxConfig, MailboxReceiver, SmallSender};
 use crate::tokio::runtime::Handle;
-use crate::{error::*, AsyncDropSender, NodeMessage};
+#[cfg(feature = "std")]
+use crate::NodeEventBus;
+#[cfg(feature = "simulation")]
+use crate::Simulation;
+use crate::{
+    error::*, AsyncDropSender, NodeExecutor, NodeMessage, ShutdownHooks, WorkerInfo, WorkerMetrics,
+    WorkerMetricsRecorder,
+};
 use core::sync::atomic::AtomicUsize;
 use ockam_core::compat::collections::HashMap;
 use ockam_core::compat::sync::{Arc, RwLock};
@@ -27,16 +34,34 @@ pub struct Context {
     pub(super) mailboxes: Mailboxes,
     pub(super) sender: SmallSender<NodeMessage>,
     pub(super) rt: Handle,
-    pub(super) receiver: SmallReceiver<RelayMessage>,
+    /// Executor used to spawn the tasks of the workers and processors
+    pub(super) executor: Arc<dyn NodeExecutor>,
+    pub(super) receiver: MailboxReceiver<RelayMessage>,
     pub(super) async_drop_sender: Option<AsyncDropSender>,
     pub(super) mailbox_count: Arc<AtomicUsize>,
+    /// Message handling metrics of the worker using this context
+    pub(super) metrics: Arc<WorkerMetricsRecorder>,
     /// List of transports used to resolve external addresses to local workers in routes
     pub(super) transports: Arc<RwLock<HashMap<TransportType, Arc<dyn Transport>>>>,
     pub(super) flow_controls: FlowControls,
+    /// Hooks run during a graceful shutdown of the node
+    pub(super) shutdown_hooks: ShutdownHooks,
+    /// Bus publishing the events of the node
+    #[cfg(feature = "std")]
+    pub(super) event_bus: NodeEventBus,
+    /// Simulation intercepting the messages sent by this context, in tests
+    #[cfg(feature = "simulation")]
+    pub(super) simulation: Option<Simulation>,
+    /// Namespace of the workers started from this context, isolating them from
+    /// the workers of other namespaces
+    pub(super) namespace: Option<String>,
     #[cfg(feature = "std")]
     pub(super) tracing_context: OpenTelemetryContext,
     /// Protocol version of the message currently being processed by a worker
     pub(super) protocol_version: ProtocolVersion,
+    /// Mailbox configuration used by the workers and processors started from this context,
+    /// unless they specify their own configuration
+    pub(super) default_mailbox_config: MailboxConfig,
 }
 
 /// This trait can be used to integrate transports into a node
@@ -63,11 +88,21 @@ impl Context {
         &self.rt
     }
 
+    /// Return the executor used to spawn the tasks of the node
+    pub fn executor(&self) -> &Arc<dyn NodeExecutor> {
+        &self.executor
+    }
+
     /// Return mailbox_count clone
     pub(crate) fn mailbox_count(&self) -> Arc<AtomicUsize> {
         self.mailbox_count.clone()
     }
 
+    /// Return the message handling metrics recorder of this context
+    pub(crate) fn metrics_recorder(&self) -> Arc<WorkerMetricsRecorder> {
+        self.metrics.clone()
+    }
+
     /// Return a reference to sender
     pub(crate) fn sender(&self) -> &SmallSender<NodeMessage> {
         &self.sender
@@ -78,6 +113,11 @@ impl Context {
         self.mailboxes.main_address()
     }
 
+    /// Return the namespace of the current worker, if any
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
     /// Return the primary address of the current worker
     pub fn address_ref(&self) -> &Address {
         self.mailboxes.main_address_ref()
@@ -98,6 +138,12 @@ impl Context {
         &self.flow_controls
     }
 
+    /// Mailbox configuration used by the workers and processors started from this context,
+    /// unless they specify their own configuration
+    pub fn default_mailbox_config(&self) -> MailboxConfig {
+        self.default_mailbox_config
+    }
+
     /// Return the tracing context
     #[cfg(feature = "std")]
     pub fn tracing_context(&self) -> OpenTelemetryContext {
@@ -165,6 +211,49 @@ impl Context {
             .take_workers()
     }
 
+    /// Return the description of all the workers and processors registered on a node
+    pub async fn list_workers_info(&self) -> Result<Vec<WorkerInfo>> {
+        let (msg, mut reply_rx) = NodeMessage::list_workers_info();
+
+        self.sender
+            .send(msg)
+            .await
+            .map_err(NodeError::from_send_err)?;
+
+        reply_rx
+            .recv()
+            .await
+            .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??
+            .take_workers_info()
+    }
+
+    /// Return a snapshot of the runtime metrics of all the workers and processors of the node:
+    /// message counts, mailbox queue depth and message processing latency
+    pub async fn metrics(&self) -> Result<Vec<WorkerMetrics>> {
+        let (msg, mut reply_rx) = NodeMessage::get_metrics();
+
+        self.sender
+            .send(msg)
+            .await
+            .map_err(NodeError::from_send_err)?;
+
+        reply_rx
+            .recv()
+            .await
+            .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??
+            .take_metrics()
+    }
+
+    /// Return a snapshot of the runtime metrics of the worker or processor
+    /// with the given primary address
+    pub async fn worker_metrics(&self, address: &Address) -> Result<Option<WorkerMetrics>> {
+        Ok(self
+            .metrics()
+            .await?
+            .into_iter()
+            .find(|m| &m.address == address))
+    }
+
     /// Send a shutdown acknowledgement to the router
     pub(crate) async fn send_stop_ack(&self) -> Result<()> {
         self.sender
@@ -4,7 +4,7 @@ use core::time::Duration;
 use crate::tokio;
 use ockam_core::compat::collections::HashMap;
 use ockam_core::compat::time::now;
-use ockam_core::compat::{boxed::Box, sync::Arc, sync::RwLock};
+use ockam_core::compat::{boxed::Box, string::String, sync::Arc, sync::RwLock};
 use ockam_core::flow_control::FlowControls;
 #[cfg(feature = "std")]
 use ockam_core::OpenTelemetryContext;
@@ -18,8 +18,14 @@ use ockam_transport_core::Transport;
 use tokio::runtime::Handle;
 
 use crate::async_drop::AsyncDrop;
-use crate::channel_types::{message_channel, small_channel, SmallReceiver, SmallSender};
-use crate::{debugger, Context};
+use crate::channel_types::{
+    mailbox_channel, small_channel, MailboxConfig, SmallReceiver, SmallSender,
+};
+#[cfg(feature = "std")]
+use crate::NodeEventBus;
+#[cfg(feature = "simulation")]
+use crate::Simulation;
+use crate::{debugger, Context, NodeExecutor, ShutdownHooks};
 use crate::{error::*, relay::CtrlSignal, router::SenderPair, NodeMessage};
 
 /// A special type of `Context` that has no worker relay and inherits
@@ -65,26 +71,43 @@ impl Context {
     pub(crate) fn new(
         protocol_version: ProtocolVersion,
         rt: Handle,
+        executor: Arc<dyn NodeExecutor>,
         sender: SmallSender<NodeMessage>,
         mailboxes: Mailboxes,
         async_drop_sender: Option<AsyncDropSender>,
         transports: Arc<RwLock<HashMap<TransportType, Arc<dyn Transport>>>>,
         flow_controls: &FlowControls,
+        shutdown_hooks: ShutdownHooks,
+        #[cfg(feature = "std")] event_bus: NodeEventBus,
+        #[cfg(feature = "simulation")] simulation: Option<Simulation>,
+        namespace: Option<String>,
+        mailbox_config: MailboxConfig,
+        default_mailbox_config: MailboxConfig,
         #[cfg(feature = "std")] tracing_context: OpenTelemetryContext,
     ) -> (Self, SenderPair, SmallReceiver<CtrlSignal>) {
-        let (mailbox_tx, receiver) = message_channel();
+        let (mailbox_tx, receiver) =
+            mailbox_channel(mailbox_config, mailboxes.high_priority_addresses());
         let (ctrl_tx, ctrl_rx) = small_channel();
         (
             Self {
                 protocol_version,
                 rt,
+                executor,
                 sender,
                 mailboxes,
                 receiver,
                 async_drop_sender,
                 mailbox_count: Arc::new(0.into()),
+                metrics: Default::default(),
                 transports,
                 flow_controls: flow_controls.clone(),
+                shutdown_hooks,
+                #[cfg(feature = "std")]
+                event_bus,
+                #[cfg(feature = "simulation")]
+                simulation,
+                namespace,
+                default_mailbox_config,
                 #[cfg(feature = "std")]
                 tracing_context,
             },
@@ -99,15 +122,25 @@ impl Context {
     pub(crate) fn copy_with_mailboxes(
         &self,
         mailboxes: Mailboxes,
+        mailbox_config: MailboxConfig,
     ) -> (Context, SenderPair, SmallReceiver<CtrlSignal>) {
         Context::new(
             self.protocol_version(),
             self.runtime().clone(),
+            self.executor.clone(),
             self.sender().clone(),
             mailboxes,
             None,
             self.transports.clone(),
             &self.flow_controls,
+            self.shutdown_hooks.clone(),
+            #[cfg(feature = "std")]
+            self.event_bus.clone(),
+            #[cfg(feature = "simulation")]
+            self.simulation.clone(),
+            self.namespace.clone(),
+            mailbox_config,
+            self.default_mailbox_config,
             #[cfg(feature = "std")]
             self.tracing_context(),
         )
@@ -121,20 +154,59 @@ impl Context {
         Context::new(
             self.protocol_version(),
             self.runtime().clone(),
+            self.executor.clone(),
             self.sender().clone(),
             mailboxes,
             Some(drop_sender),
             self.transports.clone(),
             &self.flow_controls,
+            self.shutdown_hooks.clone(),
+            #[cfg(feature = "std")]
+            self.event_bus.clone(),
+            #[cfg(feature = "simulation")]
+            self.simulation.clone(),
+            self.namespace.clone(),
+            self.default_mailbox_config,
+            self.default_mailbox_config,
             #[cfg(feature = "std")]
             OpenTelemetryContext::current(),
         )
     }
 
+    /// Create a fresh context for a restarted worker.
+    ///
+    /// The new context takes over the mailbox of this context so that
+    /// the messages already sent to the worker are not lost.
+    pub(crate) fn renew(&mut self) -> Context {
+        let (_, placeholder) = mailbox_channel(MailboxConfig::default(), vec![]);
+        Self {
+            protocol_version: self.protocol_version,
+            rt: self.rt.clone(),
+            executor: self.executor.clone(),
+            sender: self.sender.clone(),
+            mailboxes: self.mailboxes.clone(),
+            receiver: core::mem::replace(&mut self.receiver, placeholder),
+            async_drop_sender: self.async_drop_sender.take(),
+            mailbox_count: self.mailbox_count.clone(),
+            metrics: self.metrics.clone(),
+            transports: self.transports.clone(),
+            flow_controls: self.flow_controls.clone(),
+            shutdown_hooks: self.shutdown_hooks.clone(),
+            #[cfg(feature = "std")]
+            event_bus: self.event_bus.clone(),
+            #[cfg(feature = "simulation")]
+            simulation: self.simulation.clone(),
+            namespace: self.namespace.clone(),
+            default_mailbox_config: self.default_mailbox_config,
+            #[cfg(feature = "std")]
+            tracing_context: OpenTelemetryContext::current(),
+        }
+    }
+
     /// Utility function to sleep tasks from other crates
     #[doc(hidden)]
     pub async fn sleep(&self, duration: Duration) {
-        tokio::time::sleep(duration).await;
+        crate::compat::time::sleep(duration).await;
     }
 
     /// Utility function to sleep tasks for long periods of time (seconds precision)
@@ -172,7 +244,9 @@ impl Context {
         &self,
         mailboxes: Mailboxes,
     ) -> Result<DetachedContext> {
-        let ctx = self.new_detached_impl(mailboxes).await?;
+        let ctx = self
+            .new_detached_impl(mailboxes, self.namespace.clone())
+            .await?;
 
         debugger::log_inherit_context("DETACHED_WITH_MB", self, &ctx);
 
@@ -216,14 +290,60 @@ impl Context {
         outgoing: impl OutgoingAccessControl,
     ) -> Result<DetachedContext> {
         let mailboxes = Mailboxes::main(address.into(), Arc::new(incoming), Arc::new(outgoing));
-        let ctx = self.new_detached_impl(mailboxes).await?;
+        let ctx = self
+            .new_detached_impl(mailboxes, self.namespace.clone())
+            .await?;
 
         debugger::log_inherit_context("DETACHED", self, &ctx);
 
         Ok(ctx)
     }
 
-    async fn new_detached_impl(&self, mailboxes: Mailboxes) -> Result<DetachedContext> {
+    /// Create a new detached `Context` in a namespace
+    ///
+    /// The workers and processors started from the returned context
+    /// belong to the same namespace. The router rejects the messages
+    /// sent from one namespace to the workers of another namespace,
+    /// so that several logical nodes can be embedded in the same
+    /// process without being able to address each other directly.
+    /// Workers without a namespace stay reachable from every namespace.
+    ///
+    /// A context which is already in a namespace can only create
+    /// contexts in that same namespace.
+    pub async fn new_detached_in_namespace(
+        &self,
+        namespace: impl Into<String>,
+        address: impl Into<Address>,
+        incoming: impl IncomingAccessControl,
+        outgoing: impl OutgoingAccessControl,
+    ) -> Result<DetachedContext> {
+        let namespace = namespace.into();
+        if let Some(current) = &self.namespace {
+            if current != &namespace {
+                return Err(Error::new(
+                    Origin::Node,
+                    Kind::Misuse,
+                    format!(
+                        "cannot create a context in the namespace '{}' from the namespace '{}'",
+                        namespace, current
+                    ),
+                ));
+            }
+        }
+
+        let mailboxes = Mailboxes::main(address.into(), Arc::new(incoming), Arc::new(outgoing));
+        let ctx = self.new_detached_impl(mailboxes, Some(namespace)).await?;
+
+        debugger::log_inherit_context("DETACHED_IN_NAMESPACE", self, &ctx);
+
+        Ok(ctx)
+    }
+
+    async fn new_detached_impl(
+        &self,
+        mailboxes: Mailboxes,
+        namespace: Option<String>,
+    ) -> Result<DetachedContext> {
         // A detached Context exists without a worker relay, which
         // requires special shutdown handling.  To allow the Drop
         // handler to interact with the Node runtime, we use an
@@ -233,19 +353,22 @@ impl Context {
         // Drop handler, and then forwards a message to the Node
         // router.
         let (async_drop, drop_sender) = AsyncDrop::new(self.sender.clone());
-        self.rt.spawn(async_drop.run());
+        self.executor.spawn(Box::pin(async_drop.run()));
 
         // Create a new context and get access to the mailbox senders
         let addresses = mailboxes.addresses();
-        let (ctx, sender, _) = self.copy_with_mailboxes_detached(mailboxes, drop_sender);
+        let (mut ctx, sender, _) = self.copy_with_mailboxes_detached(mailboxes, drop_sender);
+        ctx.namespace = namespace;
 
         // Create a "detached relay" and register it with the router
         let (msg, mut rx) = NodeMessage::start_worker(
             addresses,
             sender,
             true,
-            Arc::clone(&self.mailbox_count),
+            ctx.mailbox_count(),
+            ctx.metrics_recorder(),
             vec![],
+            ctx.namespace.clone(),
         );
         self.sender
             .send(msg)
@@ -272,7 +395,7 @@ mod tests {
 
         // after a copy with new mailboxes the list of transports should be intact
         let mailboxes = Mailboxes::new(Mailbox::deny_all("address"), vec![]);
-        let (copy, _, _) = ctx.copy_with_mailboxes(mailboxes.clone());
+        let (copy, _, _) = ctx.copy_with_mailboxes(mailboxes.clone(), MailboxConfig::default());
         assert!(copy.is_transport_registered(transport.transport_type()));
 
         // after a detached copy with new mailboxes the list of transports should be intact
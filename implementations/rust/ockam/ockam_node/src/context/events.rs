@@ -0,0 +1,18 @@
+use crate::{Context, NodeEvent, NodeEventBus, NodeEventSubscription};
+
+impl Context {
+    /// Publish an event to all the subscribers of the node events
+    pub fn publish_event(&self, event: NodeEvent) {
+        self.event_bus.publish(event)
+    }
+
+    /// Subscribe to the events published on this node after this call
+    pub fn subscribe_events(&self) -> NodeEventSubscription {
+        self.event_bus.subscribe()
+    }
+
+    /// Return the event bus of the node
+    pub fn event_bus(&self) -> &NodeEventBus {
+        &self.event_bus
+    }
+}
@@ -1,6 +1,8 @@
 #[allow(clippy::module_inception)]
 mod context;
 mod context_lifecycle;
+#[cfg(feature = "std")]
+mod events;
 mod receive_message;
 mod register_router;
 mod send_message;
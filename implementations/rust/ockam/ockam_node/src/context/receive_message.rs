@@ -3,19 +3,29 @@ use core::time::Duration;
 
 use ockam_core::{Message, RelayMessage, Result, Routed};
 
+use crate::compat::time::timeout;
 use crate::debugger;
 use crate::error::*;
-use crate::tokio::time::timeout;
+#[cfg(feature = "std")]
+use crate::CancellationToken;
 use crate::{Context, DEFAULT_TIMEOUT};
+#[cfg(feature = "std")]
+use ockam_core::errcode::{Kind, Origin};
+#[cfg(feature = "std")]
+use std::time::Instant;
 
 pub(super) enum MessageWait {
     Timeout(Duration),
+    #[cfg(feature = "std")]
+    Deadline(Instant),
     Blocking,
 }
 
 /// Full set of options to `send_and_receive_extended` function
 pub struct MessageReceiveOptions {
     message_wait: MessageWait,
+    #[cfg(feature = "std")]
+    cancellation: Option<CancellationToken>,
 }
 
 impl Default for MessageReceiveOptions {
@@ -29,6 +39,8 @@ impl MessageReceiveOptions {
     pub fn new() -> Self {
         Self {
             message_wait: MessageWait::Timeout(DEFAULT_TIMEOUT),
+            #[cfg(feature = "std")]
+            cancellation: None,
         }
     }
 
@@ -54,6 +66,26 @@ impl MessageReceiveOptions {
         self.message_wait = MessageWait::Blocking;
         self
     }
+
+    /// Wait for the message until a given point in time
+    #[cfg(feature = "std")]
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.message_wait = MessageWait::Deadline(deadline);
+        self
+    }
+
+    /// Stop waiting for the message as soon as the token is cancelled
+    #[cfg(feature = "std")]
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    #[cfg(feature = "std")]
+    pub(super) fn with_cancellation_option(mut self, token: Option<CancellationToken>) -> Self {
+        self.cancellation = token;
+        self
+    }
 }
 
 impl Context {
@@ -121,17 +153,62 @@ impl Context {
         self.receive_extended(MessageReceiveOptions::new()).await
     }
 
+    /// Wait to receive a typed message until a deadline, or until the cancellation token
+    /// is cancelled, for example when the application shuts down.
+    ///
+    /// This function returns an error of kind `Timeout` if the deadline is reached
+    /// and an error of kind `Cancelled` if the token is cancelled first.
+    #[cfg(feature = "std")]
+    pub async fn receive_with_deadline<M: Message>(
+        &mut self,
+        deadline: Instant,
+        cancellation: &CancellationToken,
+    ) -> Result<Routed<M>> {
+        self.receive_extended(
+            MessageReceiveOptions::new()
+                .with_deadline(deadline)
+                .with_cancellation(cancellation.clone()),
+        )
+        .await
+    }
+
     /// Wait to receive a typed message
     pub async fn receive_extended<M: Message>(
         &mut self,
         options: MessageReceiveOptions,
     ) -> Result<Routed<M>> {
-        match options.message_wait {
+        #[cfg(feature = "std")]
+        if let Some(token) = options.cancellation {
+            let message_wait = options.message_wait;
+            return crate::tokio::select! {
+                biased;
+                _ = token.cancelled() => Err(ockam_core::Error::new(
+                    Origin::Node,
+                    Kind::Cancelled,
+                    "the reception of the message was cancelled",
+                )),
+                result = self.wait_for_message(message_wait) => result,
+            };
+        }
+        self.wait_for_message(options.message_wait).await
+    }
+
+    async fn wait_for_message<M: Message>(
+        &mut self,
+        message_wait: MessageWait,
+    ) -> Result<Routed<M>> {
+        match message_wait {
             MessageWait::Timeout(timeout_duration) => {
                 timeout(timeout_duration, async { self.next_from_mailbox().await })
                     .await
                     .map_err(|e| NodeError::Data.with_elapsed(e))?
             }
+            #[cfg(feature = "std")]
+            MessageWait::Deadline(deadline) => {
+                crate::compat::time::timeout_at(deadline, async { self.next_from_mailbox().await })
+                    .await
+                    .map_err(|e| NodeError::Data.with_elapsed(e))?
+            }
             MessageWait::Blocking => self.next_from_mailbox().await,
         }
     }
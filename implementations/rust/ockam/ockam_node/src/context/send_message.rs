@@ -1,5 +1,7 @@
-use crate::channel_types::small_channel;
+use crate::channel_types::{small_channel, MailboxSender};
 use crate::context::MessageWait;
+#[cfg(feature = "std")]
+use crate::CancellationToken;
 use crate::{debugger, Context, MessageReceiveOptions, DEFAULT_TIMEOUT};
 use crate::{error::*, NodeMessage};
 use cfg_if::cfg_if;
@@ -15,6 +17,8 @@ use ockam_core::{LocalInfo, Mailbox};
 /// Full set of options to `send_and_receive_extended` function
 pub struct MessageSendReceiveOptions {
     message_wait: MessageWait,
+    #[cfg(feature = "std")]
+    cancellation: Option<CancellationToken>,
 }
 
 impl Default for MessageSendReceiveOptions {
@@ -28,6 +32,8 @@ impl MessageSendReceiveOptions {
     pub fn new() -> Self {
         Self {
             message_wait: MessageWait::Timeout(DEFAULT_TIMEOUT),
+            #[cfg(feature = "std")]
+            cancellation: None,
         }
     }
 
@@ -42,6 +48,20 @@ impl MessageSendReceiveOptions {
         self.message_wait = MessageWait::Blocking;
         self
     }
+
+    /// Wait for the reply until a given point in time
+    #[cfg(feature = "std")]
+    pub fn with_deadline(mut self, deadline: std::time::Instant) -> Self {
+        self.message_wait = MessageWait::Deadline(deadline);
+        self
+    }
+
+    /// Stop waiting for the reply as soon as the token is cancelled
+    #[cfg(feature = "std")]
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
 }
 
 impl Context {
@@ -111,11 +131,10 @@ impl Context {
         child_ctx.set_protocol_version(self.protocol_version());
 
         child_ctx.send(route, msg).await?;
-        child_ctx
-            .receive_extended::<M>(
-                MessageReceiveOptions::new().with_message_wait(options.message_wait),
-            )
-            .await
+        let receive_options = MessageReceiveOptions::new().with_message_wait(options.message_wait);
+        #[cfg(feature = "std")]
+        let receive_options = receive_options.with_cancellation_option(options.cancellation);
+        child_ctx.receive_extended::<M>(receive_options).await
     }
 
     /// Send a message to another address associated with this worker
@@ -243,7 +262,7 @@ impl Context {
             }
         };
 
-        let req = NodeMessage::SenderReq(addr, reply_tx);
+        let req = NodeMessage::SenderReq(addr, self.namespace.clone(), reply_tx);
         self.sender
             .send(req)
             .await
@@ -279,7 +298,9 @@ impl Context {
         }
 
         // Pack local message into a RelayMessage wrapper
-        let relay_msg = RelayMessage::new(sending_address.clone(), addr, local_msg);
+        let priority = sender.priority_of(&addr);
+        let relay_msg =
+            RelayMessage::new(sending_address.clone(), addr, local_msg).with_priority(priority);
 
         debugger::log_outgoing_message(self, &relay_msg);
 
@@ -293,12 +314,7 @@ impl Context {
         }
 
         // Send the packed user message with associated route
-        sender
-            .send(relay_msg)
-            .await
-            .map_err(NodeError::from_send_err)?;
-
-        Ok(())
+        self.deliver(sender, relay_msg).await
     }
 
     /// Forward a transport message to its next routing destination
@@ -352,7 +368,7 @@ impl Context {
                 return Err(err);
             }
         };
-        let req = NodeMessage::SenderReq(addr, reply_tx);
+        let req = NodeMessage::SenderReq(addr, self.namespace.clone(), reply_tx);
         self.sender
             .send(req)
             .await
@@ -367,7 +383,8 @@ impl Context {
         let mut local_msg = local_msg;
         local_msg = local_msg.with_protocol_version(self.protocol_version());
 
-        let relay_msg = RelayMessage::new(sending_address, addr, local_msg);
+        let priority = sender.priority_of(&addr);
+        let relay_msg = RelayMessage::new(sending_address, addr, local_msg).with_priority(priority);
 
         debugger::log_outgoing_message(self, &relay_msg);
 
@@ -381,6 +398,25 @@ impl Context {
         }
 
         // Forward the message
+        self.deliver(sender, relay_msg).await
+    }
+
+    /// Deliver a message to the mailbox of its recipient, unless the
+    /// message is intercepted by a simulation
+    async fn deliver(
+        &self,
+        sender: MailboxSender<RelayMessage>,
+        relay_msg: RelayMessage,
+    ) -> Result<()> {
+        #[cfg(feature = "simulation")]
+        let (sender, relay_msg) = match &self.simulation {
+            Some(simulation) => match simulation.intercept(sender, relay_msg) {
+                Some(message) => message,
+                None => return Ok(()),
+            },
+            None => (sender, relay_msg),
+        };
+
         sender
             .send(relay_msg)
             .await
@@ -1,5 +1,9 @@
-use crate::Context;
+use crate::compat::time::timeout;
 use crate::{error::*, NodeMessage, ShutdownType};
+use crate::{Context, ShutdownHooks, ShutdownPhase};
+use core::future::Future;
+use core::time::Duration;
+use ockam_core::compat::string::String;
 use ockam_core::{
     errcode::{Kind, Origin},
     Error, Result,
@@ -32,11 +36,48 @@ impl Context {
         self.stop_timeout(1).await
     }
 
+    /// Register an async hook run during a graceful shutdown of the node,
+    /// before the workers are stopped.
+    ///
+    /// The hooks are run phase by phase (see [`ShutdownPhase`]) when calling
+    /// [`Context::stop`] or [`Context::stop_timeout`], so that workers can,
+    /// for example, flush their buffers while messages can still be sent.
+    /// The hooks are not run by [`Context::stop_now`].
+    pub fn register_shutdown_hook<F, Fut>(
+        &self,
+        phase: ShutdownPhase,
+        name: impl Into<String>,
+        hook: F,
+    ) where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.shutdown_hooks.register(phase, name, hook)
+    }
+
+    /// Return the shutdown hooks registered on this node
+    pub fn shutdown_hooks(&self) -> &ShutdownHooks {
+        &self.shutdown_hooks
+    }
+
     /// Signal to the local runtime to shut down
     ///
     /// This call will hang until a safe shutdown has been completed
     /// or the desired timeout has been reached.
+    ///
+    /// The shutdown hooks are run first, within the same timeout.
     pub async fn stop_timeout(&self, seconds: u8) -> Result<()> {
+        if !self.shutdown_hooks.is_empty()
+            && timeout(
+                Duration::from_secs(seconds as u64),
+                self.shutdown_hooks.run(),
+            )
+            .await
+            .is_err()
+        {
+            warn!("Shutdown timeout reached while running the shutdown hooks");
+        }
+
         let (req, mut rx) = NodeMessage::stop_node(ShutdownType::Graceful(seconds));
         self.sender
             .send(req)
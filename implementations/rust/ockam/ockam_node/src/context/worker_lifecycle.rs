@@ -1,7 +1,10 @@
 use crate::{Context, NodeError, NodeMessage, NodeReason};
 use crate::{ProcessorBuilder, WorkerBuilder};
+use ockam_core::compat::sync::Arc;
+use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{
-    Address, IncomingAccessControl, OutgoingAccessControl, Processor, Result, Worker,
+    Address, AllowAll, Error, IncomingAccessControl, Mailbox, Mailboxes, OutgoingAccessControl,
+    Processor, Result, Worker,
 };
 
 enum AddressType {
@@ -130,6 +133,111 @@ impl Context {
         Ok(())
     }
 
+    /// Start `instances` instances of a stateless worker behind the same address.
+    /// Default AccessControl is AllowAll
+    ///
+    /// The messages sent to the address are dispatched to the instances
+    /// in a round-robin fashion, so that CPU-bound workers can process
+    /// several messages concurrently. Each instance is created with
+    /// `factory` and gets its own primary address, `<address>.<index>`.
+    ///
+    /// Stopping the pool address with [`stop_worker()`](Self::stop_worker)
+    /// stops all the instances.
+    ///
+    /// ```rust
+    /// use ockam_core::{Result, Worker, worker};
+    /// use ockam_node::Context;
+    ///
+    /// struct MyWorker;
+    ///
+    /// #[worker]
+    /// impl Worker for MyWorker {
+    ///     type Context = Context;
+    ///     type Message = String;
+    /// }
+    ///
+    /// async fn start_my_workers(ctx: &mut Context) -> Result<()> {
+    ///     ctx.start_worker_pool("my-worker-address", 4, || MyWorker).await
+    /// }
+    /// ```
+    pub async fn start_worker_pool<W, F>(
+        &self,
+        address: impl Into<Address>,
+        instances: usize,
+        factory: F,
+    ) -> Result<()>
+    where
+        W: Worker<Context = Context>,
+        F: Fn() -> W,
+    {
+        self.start_worker_pool_with_access_control(address, instances, factory, AllowAll, AllowAll)
+            .await
+    }
+
+    /// Start `instances` instances of a stateless worker behind the same address,
+    /// with the given access controls
+    ///
+    /// See [`start_worker_pool()`](Self::start_worker_pool)
+    pub async fn start_worker_pool_with_access_control<W, F>(
+        &self,
+        address: impl Into<Address>,
+        instances: usize,
+        factory: F,
+        incoming: impl IncomingAccessControl,
+        outgoing: impl OutgoingAccessControl,
+    ) -> Result<()>
+    where
+        W: Worker<Context = Context>,
+        F: Fn() -> W,
+    {
+        let address = address.into();
+        if instances == 0 {
+            return Err(Error::new(
+                Origin::Node,
+                Kind::Invalid,
+                "a worker pool needs at least one instance",
+            ));
+        }
+
+        // Register the pool first so that the instances are attached to it
+        let (msg, mut rx) = NodeMessage::start_worker_pool(address.clone());
+        self.sender
+            .send(msg)
+            .await
+            .map_err(NodeError::from_send_err)?;
+        rx.recv()
+            .await
+            .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??;
+
+        let incoming: Arc<dyn IncomingAccessControl> = Arc::new(incoming);
+        let outgoing: Arc<dyn OutgoingAccessControl> = Arc::new(outgoing);
+        for i in 0..instances {
+            let instance_address = Address::new(
+                address.transport_type(),
+                format!("{}.{}", address.address(), i),
+            );
+            let mailboxes = Mailboxes::new(
+                Mailbox::new(instance_address, incoming.clone(), outgoing.clone()),
+                vec![Mailbox::new(
+                    address.clone(),
+                    incoming.clone(),
+                    outgoing.clone(),
+                )],
+            );
+            if let Err(e) = WorkerBuilder::new(factory())
+                .with_mailboxes(mailboxes)
+                .start(self)
+                .await
+            {
+                // Stop the instances which were already started
+                let _ = self.stop_worker(address).await;
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Start a new processor instance at the given address. Default AccessControl is DenyAll
     ///
     /// A processor is an asynchronous piece of code that runs a
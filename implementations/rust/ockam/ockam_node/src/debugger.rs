@@ -3,14 +3,16 @@
 use crate::Context;
 use ockam_core::RelayMessage;
 
+use ockam_core::compat::{string::String, vec::Vec};
+use ockam_core::Address;
+
 #[cfg(feature = "debugger")]
-use ockam_core::{Address, Mailbox, Mailboxes};
+use ockam_core::{Mailbox, Mailboxes};
 
 #[cfg(feature = "debugger")]
 use ockam_core::compat::{
     collections::BTreeMap,
     sync::{Arc, RwLock},
-    vec::Vec,
 };
 
 #[cfg(feature = "debugger")]
@@ -30,6 +32,8 @@ struct Debugger {
     incoming_mb: Arc<RwLock<BTreeMap<Mailbox, Vec<Address>>>>,
     /// Map message source to destinations
     outgoing: Arc<RwLock<BTreeMap<Address, Vec<Address>>>>,
+    /// Live subscriptions to the message events, by subscription id
+    trace_subscribers: Arc<RwLock<BTreeMap<u64, TraceSubscriber>>>,
 }
 
 /// Return a mutable reference to the global debugger instance
@@ -93,6 +97,8 @@ pub fn log_incoming_message(_receiving_ctx: &Context, _relay_msg: &RelayMessage)
             _receiving_ctx.address(), // actual receiving context address
         );
 
+        record_trace_event(TraceDirection::Incoming, _receiving_ctx, _relay_msg);
+
         match instance().incoming.write() {
             Ok(mut incoming) => {
                 let source = _relay_msg.source().clone();
@@ -142,6 +148,8 @@ pub fn log_outgoing_message(_sending_ctx: &Context, _relay_msg: &RelayMessage) {
             _relay_msg.destination(), // receiving address
         );
 
+        record_trace_event(TraceDirection::Outgoing, _sending_ctx, _relay_msg);
+
         match instance().outgoing.write() {
             Ok(mut outgoing) => {
                 let source = _relay_msg.source().clone();
@@ -159,6 +167,242 @@ pub fn log_outgoing_message(_sending_ctx: &Context, _relay_msg: &RelayMessage) {
     }
 }
 
+/// Maximum number of events kept for a trace subscription between two polls.
+/// The oldest events are dropped when the buffer is full
+pub const TRACE_BUFFER_CAPACITY: usize = 1000;
+
+/// A trace subscription which is not polled during this number of seconds is removed
+pub const TRACE_SUBSCRIPTION_TIMEOUT_SECS: u64 = 60;
+
+/// Direction of a traced message, relative to the context logging it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceDirection {
+    /// The message is received by a context
+    Incoming,
+    /// The message is sent by a context
+    Outgoing,
+}
+
+/// A message sent or received by a context of the node
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceEvent {
+    /// Number of the event in its subscription, starting at 1
+    pub sequence: u64,
+    /// Direction of the message
+    pub direction: TraceDirection,
+    /// Address of the sender
+    pub source: Address,
+    /// Address of the recipient
+    pub destination: Address,
+    /// Address of the context sending or receiving the message
+    pub context: Address,
+    /// Size of the message payload, in bytes
+    pub payload_size: usize,
+    /// Time of the event, in seconds since the Unix epoch
+    pub timestamp: u64,
+}
+
+/// Selection of the message events sent to a trace subscription
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceFilter {
+    addresses: Vec<Address>,
+    sample_rate: f64,
+}
+
+impl Default for TraceFilter {
+    fn default() -> Self {
+        Self {
+            addresses: Vec::new(),
+            sample_rate: 1.0,
+        }
+    }
+}
+
+impl TraceFilter {
+    /// Keep only the messages sent or received by one of the given addresses.
+    /// All the messages are kept when no address is given
+    pub fn with_addresses(mut self, addresses: Vec<Address>) -> Self {
+        self.addresses = addresses;
+        self
+    }
+
+    /// Keep only a fraction, between 0 and 1, of the matching messages
+    pub fn with_sample_rate(mut self, sample_rate: f64) -> Self {
+        self.sample_rate = sample_rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Return true if a message is selected by the addresses of this filter
+    fn matches(&self, source: &Address, destination: &Address, context: &Address) -> bool {
+        self.addresses.is_empty()
+            || self
+                .addresses
+                .iter()
+                .any(|a| a == source || a == destination || a == context)
+    }
+}
+
+/// Events received by a trace subscription since the previous poll
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TraceEvents {
+    /// Events, in order
+    pub events: Vec<TraceEvent>,
+    /// Number of events dropped because the subscription buffer was full
+    pub dropped: u64,
+}
+
+#[cfg(feature = "debugger")]
+struct TraceSubscriber {
+    filter: TraceFilter,
+    /// Number of events matching the filter addresses, before sampling
+    matched: u64,
+    sequence: u64,
+    events: ockam_core::compat::collections::VecDeque<TraceEvent>,
+    dropped: u64,
+    last_poll: u64,
+}
+
+#[cfg(feature = "debugger")]
+impl TraceSubscriber {
+    /// Sample the events deterministically: exactly `sample_rate * n` events are kept
+    /// among `n` matching events
+    fn is_sampled(&mut self) -> bool {
+        self.matched += 1;
+        let kept = |n: u64| (n as f64 * self.filter.sample_rate) as u64;
+        kept(self.matched) > kept(self.matched - 1)
+    }
+
+    fn push(&mut self, mut event: TraceEvent) {
+        self.sequence += 1;
+        event.sequence = self.sequence;
+        if self.events.len() >= TRACE_BUFFER_CAPACITY {
+            self.events.pop_front();
+            self.dropped += 1;
+        }
+        self.events.push_back(event);
+    }
+}
+
+#[cfg(feature = "debugger")]
+fn record_trace_event(direction: TraceDirection, ctx: &Context, relay_msg: &RelayMessage) {
+    match instance().trace_subscribers.read() {
+        Ok(subscribers) if subscribers.is_empty() => return,
+        Ok(_) => {}
+        Err(e) => {
+            tracing::error!("debugger panicked: {}", e);
+            panic!("record_trace_event");
+        }
+    }
+
+    let context = ctx.address();
+    let timestamp = ockam_core::compat::time::now().unwrap_or_default();
+    match instance().trace_subscribers.write() {
+        Ok(mut subscribers) => {
+            for subscriber in subscribers.values_mut() {
+                if subscriber
+                    .filter
+                    .matches(relay_msg.source(), relay_msg.destination(), &context)
+                    && subscriber.is_sampled()
+                {
+                    subscriber.push(TraceEvent {
+                        sequence: 0,
+                        direction,
+                        source: relay_msg.source().clone(),
+                        destination: relay_msg.destination().clone(),
+                        context: context.clone(),
+                        payload_size: relay_msg.payload().len(),
+                        timestamp,
+                    });
+                }
+            }
+        }
+        Err(e) => {
+            tracing::error!("debugger panicked: {}", e);
+            panic!("record_trace_event");
+        }
+    }
+}
+
+/// Subscribe to the messages sent and received by the contexts of the node.
+///
+/// The events are buffered until they are polled with [`poll_trace_events`].
+/// Return the id of the subscription, or `None` if the `debugger` feature is disabled
+pub fn subscribe(_filter: TraceFilter) -> Option<u64> {
+    #[cfg(feature = "debugger")]
+    {
+        let now = ockam_core::compat::time::now().unwrap_or_default();
+        match instance().trace_subscribers.write() {
+            Ok(mut subscribers) => {
+                // remove the subscriptions of the clients which went away
+                subscribers.retain(|_, s| {
+                    now.saturating_sub(s.last_poll) < TRACE_SUBSCRIPTION_TIMEOUT_SECS
+                });
+                let id = subscribers.keys().next_back().map(|id| id + 1).unwrap_or(1);
+                subscribers.insert(
+                    id,
+                    TraceSubscriber {
+                        filter: _filter,
+                        matched: 0,
+                        sequence: 0,
+                        events: Default::default(),
+                        dropped: 0,
+                        last_poll: now,
+                    },
+                );
+                Some(id)
+            }
+            Err(e) => {
+                tracing::error!("debugger panicked: {}", e);
+                panic!("subscribe");
+            }
+        }
+    }
+
+    #[cfg(not(feature = "debugger"))]
+    None
+}
+
+/// Return the events received by a trace subscription since the previous poll,
+/// or `None` if there is no such subscription
+pub fn poll_trace_events(_subscription_id: u64) -> Option<TraceEvents> {
+    #[cfg(feature = "debugger")]
+    {
+        match instance().trace_subscribers.write() {
+            Ok(mut subscribers) => subscribers.get_mut(&_subscription_id).map(|s| {
+                s.last_poll = ockam_core::compat::time::now().unwrap_or_default();
+                TraceEvents {
+                    events: s.events.drain(..).collect(),
+                    dropped: core::mem::take(&mut s.dropped),
+                }
+            }),
+            Err(e) => {
+                tracing::error!("debugger panicked: {}", e);
+                panic!("poll_trace_events");
+            }
+        }
+    }
+
+    #[cfg(not(feature = "debugger"))]
+    None
+}
+
+/// Remove a trace subscription. Return false if there is no such subscription
+pub fn unsubscribe(_subscription_id: u64) -> bool {
+    #[cfg(feature = "debugger")]
+    {
+        match instance().trace_subscribers.write() {
+            Ok(mut subscribers) => subscribers.remove(&_subscription_id).is_some(),
+            Err(e) => {
+                tracing::error!("debugger panicked: {}", e);
+                panic!("unsubscribe");
+            }
+        }
+    }
+
+    #[cfg(not(feature = "debugger"))]
+    false
+}
+
 /// Log Context creation
 ///
 /// This debug function builds an inheritance tree of the contexts
@@ -227,129 +471,173 @@ use ockam_core::compat::io::{self, BufWriter, Write};
 ///    dot 07-inlet.dot -Tpdf -o 07-inlet.pdf
 #[cfg(all(feature = "debugger", feature = "std"))]
 pub fn generate_graphs<W: Write>(w: &mut BufWriter<W>) -> io::Result<()> {
-    fn id(mailbox: &Mailbox) -> String {
-        mailbox.address().address().replace('.', "_")
+    if let Some(graph) = graph() {
+        w.write_all(graph.to_dot().as_bytes())?;
     }
+    w.flush()?;
+    Ok(())
+}
+
+/// A mailbox seen by the Debugger
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DebuggerMailbox {
+    /// Address of the mailbox
+    pub address: Address,
+    /// Description of the incoming access control of the mailbox
+    pub incoming_access_control: String,
+    /// Description of the outgoing access control of the mailbox
+    pub outgoing_access_control: String,
+}
+
+/// Snapshot of the context inheritance and message flow graphs logged by the Debugger
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DebuggerGraph {
+    /// Mailboxes of all the contexts created so far
+    pub mailboxes: Vec<DebuggerMailbox>,
+    /// Edges from the main address of a context to the addresses of the contexts created from it
+    pub inheritance: Vec<(Address, Address)>,
+    /// Edges from the source address of the messages to the address of their destination
+    pub message_flow: Vec<(Address, Address)>,
+}
 
-    fn write_mailbox<W: Write>(
-        w: &mut BufWriter<W>,
-        mailbox: &Mailbox,
-        tag: &str,
-    ) -> io::Result<()> {
-        write!(
-            w,
-            "    {}{} [label=\"{{ {} | in: {:?} | out: {:?}  }} \"]",
-            tag,
-            id(mailbox),
-            mailbox.address(),
-            mailbox.incoming_access_control(),
-            mailbox.outgoing_access_control(),
-        )?;
-        writeln!(w)?;
-        Ok(())
+impl DebuggerGraph {
+    /// Render the graphs in the graphviz DOT format
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        // writing to a String can not fail
+        let _ = self.write_dot(&mut dot);
+        dot
     }
 
-    // generate mailboxes set
-    use ockam_core::compat::collections::BTreeSet;
-    let mut mailboxes = BTreeSet::new();
-    if let Ok(inherited_mb) = instance().inherited_mb.read() {
-        for (parent, children) in inherited_mb.iter() {
-            for child in children.iter() {
-                mailboxes.insert(parent.clone());
-                mailboxes.insert(child.main_mailbox().clone());
-                for mailbox in child.additional_mailboxes().iter() {
-                    mailboxes.insert(mailbox.clone());
-                }
+    fn write_dot(&self, w: &mut String) -> core::fmt::Result {
+        use core::fmt::Write;
+
+        fn id(address: &Address) -> String {
+            address.address().replace('.', "_")
+        }
+
+        let write_mailboxes = |w: &mut String, tag: &str| -> core::fmt::Result {
+            for mailbox in self.mailboxes.iter() {
+                writeln!(
+                    w,
+                    "    {}{} [label=\"{{ {} | in: {} | out: {}  }} \"]",
+                    tag,
+                    id(&mailbox.address),
+                    mailbox.address,
+                    mailbox.incoming_access_control,
+                    mailbox.outgoing_access_control,
+                )?;
             }
+            Ok(())
+        };
+
+        writeln!(w, "digraph ockam_node {{")?;
+        writeln!(w, "  fontname=Arial;")?;
+        writeln!(w, "  rankdir=TB;")?;
+
+        // - inheritance ----------------------------------------------------------
+        writeln!(w, "  subgraph cluster_Inheritance {{")?;
+        writeln!(w, "    label=\"Inheritance\";")?;
+        writeln!(w, "    fontsize=24.0;")?;
+        writeln!(w, "    labelloc=\"t\";")?;
+        writeln!(w, "    rankdir=TB;")?;
+        writeln!(w, "    edge [fillcolor=\"#a6cee3\"];")?;
+        writeln!(w, "    edge [color=\"#1f78b4\"];")?;
+        writeln!(w, "    node [shape=record];")?;
+        writeln!(w, "    node [fontname=Arial];")?;
+        writeln!(w, "    node [fontsize=12.0];")?;
+        write_mailboxes(w, "")?;
+        for (parent, child) in self.inheritance.iter() {
+            writeln!(w, "    {} -> {};", id(parent), id(child))?;
         }
-    }
+        writeln!(w, "  }}\n")?;
+
+        // - message flow ---------------------------------------------------------
+        writeln!(w, "  subgraph cluster_MessageFlow {{")?;
+        writeln!(w, "    label=\"MessageFlow\";")?;
+        writeln!(w, "    fontsize=24.0;")?;
+        writeln!(w, "    fontname=Arial;")?;
+        writeln!(w, "    labelloc=\"t\";")?;
+        writeln!(w, "    rankdir=TB;")?;
+        writeln!(w, "    edge [fillcolor=\"#a60000\"];")?;
+        writeln!(w, "    edge [color=\"#1f0000\"];")?;
+        writeln!(w, "    node [shape=Mrecord];")?;
+        writeln!(w, "    node [fontname=Arial];")?;
+        writeln!(w, "    node [fontsize=12.0];")?;
+        write_mailboxes(w, "MF_")?;
+        for (source, destination) in self.message_flow.iter() {
+            writeln!(w, "    MF_{} -> MF_{};", id(source), id(destination))?;
+        }
+        writeln!(w, "  }}")?;
 
-    writeln!(w, "digraph ockam_node {{")?;
-    writeln!(w, "  fontname=Arial;")?;
-    writeln!(w, "  rankdir=TB;")?;
-
-    // - inheritance ----------------------------------------------------------
-    writeln!(w, "  subgraph cluster_Inheritance {{")?;
-    writeln!(w, "    label=\"Inheritance\";")?;
-    writeln!(w, "    fontsize=24.0;")?;
-    writeln!(w, "    labelloc=\"t\";")?;
-    writeln!(w, "    rankdir=TB;")?;
-    writeln!(w, "    edge [fillcolor=\"#a6cee3\"];")?;
-    writeln!(w, "    edge [color=\"#1f78b4\"];")?;
-    writeln!(w, "    node [shape=record];")?;
-    writeln!(w, "    node [fontname=Arial];")?;
-    writeln!(w, "    node [fontsize=12.0];")?;
-    // metadata
-    for mailbox in mailboxes.iter() {
-        write_mailbox(w, mailbox, "")?;
+        writeln!(w, "}}")
     }
-    // topology
-    match instance().inherited_mb.read() {
-        Ok(inherited_mb) => {
-            for (parent, children) in inherited_mb.iter() {
-                for child in children.iter() {
-                    let mut child_ids = vec![id(child.main_mailbox())];
-                    for mailbox in child.additional_mailboxes().iter() {
-                        let child_id = id(mailbox);
-                        child_ids.push(child_id);
-                    }
-                    for child_id in child_ids.iter() {
-                        writeln!(w, "    {} -> {};", id(parent), child_id,)?;
+}
+
+/// Return a snapshot of the graphs logged by the Debugger so far,
+/// or `None` if the `debugger` feature is disabled
+pub fn graph() -> Option<DebuggerGraph> {
+    #[cfg(feature = "debugger")]
+    {
+        use ockam_core::compat::collections::BTreeSet;
+
+        // generate mailboxes set
+        let mut mailboxes = BTreeSet::new();
+        let mut inheritance = Vec::new();
+        match instance().inherited_mb.read() {
+            Ok(inherited_mb) => {
+                for (parent, children) in inherited_mb.iter() {
+                    for child in children.iter() {
+                        mailboxes.insert(parent.clone());
+                        mailboxes.insert(child.main_mailbox().clone());
+                        inheritance.push((parent.address().clone(), child.main_address().clone()));
+                        for mailbox in child.additional_mailboxes().iter() {
+                            mailboxes.insert(mailbox.clone());
+                            inheritance.push((parent.address().clone(), mailbox.address().clone()));
+                        }
                     }
                 }
             }
+            Err(e) => {
+                tracing::error!("debugger panicked: {}", e);
+                panic!("graph");
+            }
         }
-        Err(e) => {
-            tracing::error!("debugger panicked: {}", e);
-            panic!("display_log");
-        }
-    }
-    writeln!(w, "  }}\n")?;
-
-    // - message flow ---------------------------------------------------------
-    writeln!(w, "  subgraph cluster_MessageFlow {{")?;
-    writeln!(w, "    label=\"MessageFlow\";")?;
-    writeln!(w, "    fontsize=24.0;")?;
-    writeln!(w, "    fontname=Arial;")?;
-    writeln!(w, "    labelloc=\"t\";")?;
-    writeln!(w, "    rankdir=TB;")?;
-    writeln!(w, "    edge [fillcolor=\"#a60000\"];")?;
-    writeln!(w, "    edge [color=\"#1f0000\"];")?;
-    writeln!(w, "    node [shape=Mrecord];")?;
-    writeln!(w, "    node [fontname=Arial];")?;
-    writeln!(w, "    node [fontsize=12.0];")?;
-    // metadata
-    for mailbox in mailboxes.iter() {
-        write_mailbox(w, mailbox, "MF_")?;
-    }
-    match instance().incoming_mb.read() {
-        Ok(incoming_mb) => {
-            for (destination, sources) in incoming_mb.iter() {
-                let mut sources = sources.clone();
-                sources.sort();
-                sources.dedup();
-                for source in sources.iter() {
-                    writeln!(
-                        w,
-                        "    MF_{} -> MF_{};",
-                        //"    {} -> {};",
-                        source.address().replace('.', "_"),
-                        id(destination),
-                    )?;
+
+        let mut message_flow = Vec::new();
+        match instance().incoming_mb.read() {
+            Ok(incoming_mb) => {
+                for (destination, sources) in incoming_mb.iter() {
+                    let mut sources = sources.clone();
+                    sources.sort();
+                    sources.dedup();
+                    for source in sources {
+                        message_flow.push((source, destination.address().clone()));
+                    }
                 }
             }
+            Err(e) => {
+                tracing::error!("debugger panicked: {}", e);
+                panic!("graph");
+            }
         }
-        Err(e) => {
-            tracing::error!("debugger panicked: {}", e);
-            panic!("display_log");
-        }
-    }
-    writeln!(w, "  }}")?;
 
-    writeln!(w, "}}")?;
-    w.flush()?;
+        Some(DebuggerGraph {
+            mailboxes: mailboxes
+                .iter()
+                .map(|mailbox| DebuggerMailbox {
+                    address: mailbox.address().clone(),
+                    incoming_access_control: format!("{:?}", mailbox.incoming_access_control()),
+                    outgoing_access_control: format!("{:?}", mailbox.outgoing_access_control()),
+                })
+                .collect(),
+            inheritance,
+            message_flow,
+        })
+    }
 
-    Ok(())
+    #[cfg(not(feature = "debugger"))]
+    None
 }
 
 /// Displays a summary of the data logged by the Debugger
@@ -427,3 +715,55 @@ pub fn display_log() {
         }
     }*/
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_to_dot() {
+        let mailbox = |address: &str| DebuggerMailbox {
+            address: address.into(),
+            incoming_access_control: "AllowAll".into(),
+            outgoing_access_control: "DenyAll".into(),
+        };
+        let graph = DebuggerGraph {
+            mailboxes: vec![mailbox("app"), mailbox("worker.1")],
+            inheritance: vec![("app".into(), "worker.1".into())],
+            message_flow: vec![("app".into(), "worker.1".into())],
+        };
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph ockam_node {"));
+        assert!(
+            dot.contains("    worker_1 [label=\"{ 0#worker.1 | in: AllowAll | out: DenyAll  } \"]")
+        );
+        assert!(dot.contains("    app -> worker_1;"));
+        assert!(dot.contains("    MF_app -> MF_worker_1;"));
+    }
+
+    #[test]
+    fn test_trace_filter_addresses() {
+        let (a, b, c) = ("a".into(), "b".into(), "c".into());
+        assert!(TraceFilter::default().matches(&a, &b, &c));
+
+        let filter = TraceFilter::default().with_addresses(vec!["b".into()]);
+        assert!(filter.matches(&a, &b, &c));
+        assert!(!filter.matches(&a, &c, &c));
+    }
+
+    #[cfg(feature = "debugger")]
+    #[test]
+    fn test_trace_sampling() {
+        let mut subscriber = TraceSubscriber {
+            filter: TraceFilter::default().with_sample_rate(0.25),
+            matched: 0,
+            sequence: 0,
+            events: Default::default(),
+            dropped: 0,
+            last_poll: 0,
+        };
+        let sampled = (0..100).filter(|_| subscriber.is_sampled()).count();
+        assert_eq!(sampled, 25);
+    }
+}
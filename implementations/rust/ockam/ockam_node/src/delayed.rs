@@ -1,7 +1,7 @@
 use crate::Context;
 use core::time::Duration;
 use futures::future::{AbortHandle, Abortable};
-use ockam_core::compat::sync::Arc;
+use ockam_core::compat::{boxed::Box, sync::Arc};
 use ockam_core::{Address, AllowOnwardAddress, DenyAll, Mailboxes, Message, Result};
 
 /// Allow to send message to destination address periodically after some delay
@@ -84,7 +84,9 @@ impl<M: Message + Clone> DelayedEvent<M> {
         );
 
         self.abort_handle = Some(handle);
-        self.ctx.runtime().spawn(future);
+        self.ctx.executor().spawn(Box::pin(async move {
+            let _ = future.await;
+        }));
 
         Ok(())
     }
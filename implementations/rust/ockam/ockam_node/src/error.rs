@@ -1,4 +1,5 @@
-use crate::tokio::{sync::mpsc::error::SendError, time::error::Elapsed};
+use crate::compat::time::Elapsed;
+use crate::tokio::sync::mpsc::error::SendError;
 use core::fmt;
 use ockam_core::{
     compat::error::Error as StdError,
@@ -56,7 +57,7 @@ impl NodeError {
         .context("SendError", err)
     }
 
-    /// Create an ockam_core::Error from a tokio::Elapsed
+    /// Create an ockam_core::Error from a timer Elapsed error
     #[track_caller]
     pub(crate) fn with_elapsed(self, err: Elapsed) -> Error {
         Error::new(Origin::Node, Kind::Timeout, err).context("Type", self)
@@ -6,10 +6,13 @@ use crate::runtime;
 use crate::{
     router::{Router, SenderPair},
     tokio::runtime::Runtime,
-    NodeMessage,
+    NodeExecutor, NodeMessage,
 };
 use core::future::Future;
-use ockam_core::{compat::sync::Arc, Address, Result};
+use ockam_core::{
+    compat::{boxed::Box, sync::Arc},
+    Address, Result,
+};
 
 #[cfg(feature = "metrics")]
 use crate::metrics::Metrics;
@@ -19,6 +22,8 @@ use crate::metrics::Metrics;
 #[cfg(feature = "metrics")]
 use core::sync::atomic::{AtomicBool, Ordering};
 
+#[cfg(feature = "std")]
+use crate::tokio::sync::oneshot;
 #[cfg(feature = "std")]
 use opentelemetry::trace::FutureExt;
 
@@ -35,8 +40,12 @@ use ockam_core::{
 /// default) and the Ockam router. In most cases it is recommended you use the
 /// `ockam::node` function annotation instead!
 pub struct Executor {
-    /// Reference to the runtime needed to spawn tasks
+    /// Reference to the runtime used as a reactor for timers and IO
+    #[cfg_attr(feature = "std", allow(dead_code))]
     rt: Arc<Runtime>,
+    /// Executor used to spawn the tasks of the node
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    executor: Arc<dyn NodeExecutor>,
     /// Main worker and application router
     router: Router,
     /// Metrics collection endpoint
@@ -46,12 +55,17 @@ pub struct Executor {
 
 impl Executor {
     /// Create a new Ockam node [`Executor`] instance
-    pub fn new(rt: Arc<Runtime>, flow_controls: &FlowControls) -> Self {
-        let router = Router::new(flow_controls);
+    pub fn new(
+        rt: Arc<Runtime>,
+        executor: Arc<dyn NodeExecutor>,
+        flow_controls: &FlowControls,
+    ) -> Self {
+        let router = Router::new(flow_controls, executor.clone());
         #[cfg(feature = "metrics")]
         let metrics = Metrics::new(&rt, router.get_metrics_readout());
         Self {
             rt,
+            executor,
             router,
             #[cfg(feature = "metrics")]
             metrics,
@@ -92,32 +106,27 @@ impl Executor {
         #[cfg(feature = "metrics")]
         let alive = Arc::new(AtomicBool::from(true));
         #[cfg(feature = "metrics")]
-        self.rt.spawn(
+        self.executor.spawn(Box::pin(
             self.metrics
                 .clone()
                 .run(alive.clone())
                 .with_current_context(),
-        );
+        ));
 
         // Spawn user code second
         let sender = self.sender();
         let future = Executor::wrapper(sender, future);
-        let join_body = self.rt.spawn(future.with_current_context());
+        let join_body = self.spawn_with_result(future);
 
         // Then block on the execution of the router
-        self.rt.block_on(self.router.run().with_current_context())?;
+        self.run_router()?;
 
         // Shut down metrics collector
         #[cfg(feature = "metrics")]
         alive.fetch_or(true, Ordering::Acquire);
 
         // Last join user code
-        let res = self
-            .rt
-            .block_on(join_body)
-            .map_err(|e| Error::new(Origin::Executor, Kind::Unknown, e))?;
-
-        Ok(res)
+        self.join(join_body)
     }
 
     /// Initialise and run the Ockam node executor context
@@ -139,30 +148,70 @@ impl Executor {
         #[cfg(feature = "metrics")]
         let alive = Arc::new(AtomicBool::from(true));
         #[cfg(feature = "metrics")]
-        self.rt.spawn(
+        self.executor.spawn(Box::pin(
             self.metrics
                 .clone()
                 .run(alive.clone())
                 .with_current_context(),
-        );
+        ));
 
         // Spawn user code second
-        let join_body = self.rt.spawn(future.with_current_context());
+        let join_body = self.spawn_with_result(future);
 
         // Then block on the execution of the router
-        self.rt.block_on(self.router.run().with_current_context())?;
+        self.run_router()?;
 
         // Shut down metrics collector
         #[cfg(feature = "metrics")]
         alive.fetch_or(true, Ordering::Acquire);
 
         // Last join user code
-        let res = self
-            .rt
-            .block_on(join_body)
-            .map_err(|e| Error::new(Origin::Executor, Kind::Unknown, e))?;
+        self.join(join_body)
+    }
 
-        Ok(res)
+    /// Spawn a future on the node executor and return a receiver for its result
+    #[cfg(feature = "std")]
+    fn spawn_with_result<F>(&self, future: F) -> oneshot::Receiver<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        self.executor.spawn(Box::pin(
+            async move {
+                let _ = sender.send(future.await);
+            }
+            .with_current_context(),
+        ));
+        receiver
+    }
+
+    /// Block on the execution of the router
+    #[cfg(feature = "std")]
+    fn run_router(&mut self) -> Result<()> {
+        let mut result = Ok(());
+        let router = &mut self.router;
+        self.executor.block_on(Box::pin(async {
+            result = router.run().with_current_context().await;
+        }));
+        result
+    }
+
+    /// Wait for the result of a future spawned with `spawn_with_result`.
+    /// An error is returned if the future panicked
+    #[cfg(feature = "std")]
+    fn join<T>(&self, receiver: oneshot::Receiver<T>) -> Result<T> {
+        let mut result = None;
+        self.executor.block_on(Box::pin(async {
+            result = receiver.await.ok();
+        }));
+        result.ok_or_else(|| {
+            Error::new(
+                Origin::Executor,
+                Kind::Unknown,
+                "the node task did not complete",
+            )
+        })
     }
 
     /// Wrapper around the user provided future that will shut down the node on error
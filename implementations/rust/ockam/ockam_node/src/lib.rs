@@ -53,25 +53,39 @@ pub mod callback;
 pub mod workers;
 
 mod async_drop;
+#[cfg(feature = "std")]
+mod cancellation;
 mod context;
 mod delayed;
 mod error;
 mod executor;
 mod messages;
 mod node;
+#[cfg(feature = "std")]
+mod node_events;
+mod node_executor;
 mod processor_builder;
 mod relay;
 mod router;
+#[cfg(feature = "std")]
+mod schedule;
+mod shutdown_hooks;
+#[cfg(feature = "simulation")]
+mod simulation;
+mod supervisor;
 
 /// Support for storing persistent values
 pub mod storage;
 
 mod worker_builder;
+mod worker_metrics;
 
 /// Singleton for the runtime executor
 #[cfg(feature = "std")]
 pub mod runtime;
 
+#[cfg(feature = "std")]
+pub use cancellation::CancellationToken;
 pub use context::*;
 pub use delayed::*;
 pub use error::*;
@@ -79,10 +93,20 @@ pub use executor::*;
 pub use messages::*;
 pub use processor_builder::ProcessorBuilder;
 #[cfg(feature = "std")]
+pub use schedule::{CronExpression, Schedule};
+pub use shutdown_hooks::*;
+#[cfg(feature = "simulation")]
+pub use simulation::*;
+#[cfg(feature = "std")]
 pub use storage::database;
+pub use supervisor::*;
 pub use worker_builder::WorkerBuilder;
+pub use worker_metrics::*;
 
 pub use node::{NodeBuilder, NullWorker};
+#[cfg(feature = "std")]
+pub use node_events::*;
+pub use node_executor::*;
 
 #[cfg(feature = "std")]
 use core::future::Future;
@@ -1,7 +1,8 @@
-use crate::channel_types::{small_channel, MessageSender, SmallReceiver, SmallSender};
+use crate::channel_types::{small_channel, MailboxSender, SmallReceiver, SmallSender};
 use crate::{
     error::{NodeError, NodeReason, RouterReason, WorkerReason},
     router::SenderPair,
+    WorkerMetrics, WorkerMetricsRecorder,
 };
 use core::{fmt, sync::atomic::AtomicUsize};
 use ockam_core::compat::{string::String, sync::Arc, vec::Vec};
@@ -22,13 +23,23 @@ pub enum NodeMessage {
         detached: bool,
         /// A mechanism to read channel fill-state for a worker
         mailbox_count: Arc<AtomicUsize>,
+        /// Message handling metrics of the worker
+        metrics: Arc<WorkerMetricsRecorder>,
         /// Reply channel for command confirmation
         reply: SmallSender<NodeReplyResult>,
         /// List of metadata for each address
         addresses_metadata: Vec<AddressAndMetadata>,
+        /// Namespace of the worker, if any
+        namespace: Option<String>,
     },
+    /// Register a pool of worker instances sharing the same address
+    StartWorkerPool(Address, SmallSender<NodeReplyResult>),
     /// Return a list of all worker addresses
     ListWorkers(SmallSender<NodeReplyResult>),
+    /// Return the description of all workers and processors
+    ListWorkersInfo(SmallSender<NodeReplyResult>),
+    /// Return the runtime metrics of all workers and processors
+    GetMetrics(SmallSender<NodeReplyResult>),
     /// Add an existing address to a cluster
     SetCluster(Address, String, SmallSender<NodeReplyResult>),
     /// Stop an existing worker
@@ -43,6 +54,8 @@ pub enum NodeMessage {
         reply: SmallSender<NodeReplyResult>,
         /// List of metadata for each address
         addresses_metadata: Vec<AddressAndMetadata>,
+        /// Namespace of the processor, if any
+        namespace: Option<String>,
     },
     /// Stop an existing processor
     StopProcessor(Address, SmallSender<NodeReplyResult>),
@@ -52,8 +65,8 @@ pub enum NodeMessage {
     AbortNode,
     /// Let the router know a particular address has stopped
     StopAck(Address),
-    /// Request the sender for a worker address
-    SenderReq(Address, SmallSender<NodeReplyResult>),
+    /// Request the sender for a worker address, from a worker in an optional namespace
+    SenderReq(Address, Option<String>, SmallSender<NodeReplyResult>),
     /// Register a new router for a route id type
     Router(TransportType, Address, SmallSender<NodeReplyResult>),
     /// Message the router to set an address as "ready"
@@ -70,7 +83,10 @@ impl fmt::Display for NodeMessage {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             NodeMessage::StartWorker { .. } => write!(f, "StartWorker"),
+            NodeMessage::StartWorkerPool(_, _) => write!(f, "StartWorkerPool"),
             NodeMessage::ListWorkers(_) => write!(f, "ListWorkers"),
+            NodeMessage::ListWorkersInfo(_) => write!(f, "ListWorkersInfo"),
+            NodeMessage::GetMetrics(_) => write!(f, "GetMetrics"),
             NodeMessage::SetCluster(_, _, _) => write!(f, "SetCluster"),
             NodeMessage::StopWorker(_, _, _) => write!(f, "StopWorker"),
             NodeMessage::StartProcessor { .. } => write!(f, "StartProcessor"),
@@ -78,7 +94,7 @@ impl fmt::Display for NodeMessage {
             NodeMessage::StopNode(_, _) => write!(f, "StopNode"),
             NodeMessage::AbortNode => write!(f, "AbortNode"),
             NodeMessage::StopAck(_) => write!(f, "StopAck"),
-            NodeMessage::SenderReq(_, _) => write!(f, "SenderReq"),
+            NodeMessage::SenderReq(_, _, _) => write!(f, "SenderReq"),
             NodeMessage::Router(_, _, _) => write!(f, "Router"),
             NodeMessage::SetReady(_) => write!(f, "SetReady"),
             NodeMessage::CheckReady(_, _) => write!(f, "CheckReady"),
@@ -97,12 +113,17 @@ impl NodeMessage {
     ///               relay behind it that can respond to shutdown
     ///               commands.  Setting this to `true` will disable
     ///               stop ACK support in the router
+    ///
+    /// * `namespace`: namespace isolating the worker from the workers
+    ///                of other namespaces
     pub fn start_worker(
         addrs: Vec<Address>,
         senders: SenderPair,
         detached: bool,
         mailbox_count: Arc<AtomicUsize>,
+        metrics: Arc<WorkerMetricsRecorder>,
         metadata: Vec<AddressAndMetadata>,
+        namespace: Option<String>,
     ) -> (Self, SmallReceiver<NodeReplyResult>) {
         let (reply, rx) = small_channel();
         (
@@ -111,18 +132,27 @@ impl NodeMessage {
                 senders,
                 detached,
                 mailbox_count,
+                metrics,
                 reply,
                 addresses_metadata: metadata,
+                namespace,
             },
             rx,
         )
     }
 
+    /// Create a start worker pool message and reply receiver
+    pub fn start_worker_pool(address: Address) -> (Self, SmallReceiver<NodeReplyResult>) {
+        let (tx, rx) = small_channel();
+        (Self::StartWorkerPool(address, tx), rx)
+    }
+
     /// Create a start worker message
     pub fn start_processor(
         addrs: Vec<Address>,
         senders: SenderPair,
         metadata: Vec<AddressAndMetadata>,
+        namespace: Option<String>,
     ) -> (Self, SmallReceiver<NodeReplyResult>) {
         let (tx, rx) = small_channel();
         (
@@ -131,6 +161,7 @@ impl NodeMessage {
                 senders,
                 reply: tx,
                 addresses_metadata: metadata,
+                namespace,
             },
             rx,
         )
@@ -148,6 +179,18 @@ impl NodeMessage {
         (Self::ListWorkers(tx), rx)
     }
 
+    /// Create a list workers info message and reply receiver
+    pub fn list_workers_info() -> (Self, SmallReceiver<NodeReplyResult>) {
+        let (tx, rx) = small_channel();
+        (Self::ListWorkersInfo(tx), rx)
+    }
+
+    /// Create a get metrics message and reply receiver
+    pub fn get_metrics() -> (Self, SmallReceiver<NodeReplyResult>) {
+        let (tx, rx) = small_channel();
+        (Self::GetMetrics(tx), rx)
+    }
+
     /// Create a set cluster message and reply receiver
     pub fn set_cluster(addr: Address, label: String) -> (Self, SmallReceiver<NodeReplyResult>) {
         let (tx, rx) = small_channel();
@@ -167,9 +210,12 @@ impl NodeMessage {
     }
 
     /// Create a sender request message and reply receiver
-    pub fn sender_request(route: Address) -> (Self, SmallReceiver<NodeReplyResult>) {
+    pub fn sender_request(
+        route: Address,
+        namespace: Option<String>,
+    ) -> (Self, SmallReceiver<NodeReplyResult>) {
         let (tx, rx) = small_channel();
-        (Self::SenderReq(route, tx), rx)
+        (Self::SenderReq(route, namespace, tx), rx)
     }
 
     /// Create a SetReady message and reply receiver
@@ -206,12 +252,16 @@ pub enum RouterReply {
     Ok,
     /// A list of worker addresses
     Workers(Vec<Address>),
+    /// A list of worker descriptions
+    WorkersInfo(Vec<WorkerInfo>),
+    /// The runtime metrics of a list of workers
+    Metrics(Vec<WorkerMetrics>),
     /// Message sender to a specific worker
     Sender {
         /// The address a message is being sent to
         addr: Address,
         /// The relay sender
-        sender: MessageSender<RelayMessage>,
+        sender: MailboxSender<RelayMessage>,
     },
     /// Indicate the 'ready' state of an address
     State(bool),
@@ -221,6 +271,23 @@ pub enum RouterReply {
     Metadata(Option<AddressMetadata>),
 }
 
+/// Description of a worker or processor registered on a node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerInfo {
+    /// The primary address of the worker
+    pub primary_address: Address,
+    /// All the addresses of the worker, including the primary one
+    pub addresses: Vec<Address>,
+    /// True if this is a processor rather than a worker
+    pub is_processor: bool,
+    /// True for a detached context, which has no relay
+    pub is_detached: bool,
+    /// Number of messages waiting in the worker mailbox
+    pub mailbox_count: usize,
+    /// Total number of messages sent to the worker since it was started
+    pub messages_count: usize,
+}
+
 /// Specify the type of node shutdown
 ///
 /// For most users `ShutdownType::Graceful()` is recommended.  The
@@ -310,6 +377,16 @@ impl RouterReply {
         Ok(Self::Workers(v))
     }
 
+    /// Return [RouterReply::WorkersInfo] for the given worker descriptions
+    pub fn workers_info(v: Vec<WorkerInfo>) -> NodeReplyResult {
+        Ok(Self::WorkersInfo(v))
+    }
+
+    /// Return [RouterReply::Metrics] for the given worker metrics
+    pub fn metrics(v: Vec<WorkerMetrics>) -> NodeReplyResult {
+        Ok(Self::Metrics(v))
+    }
+
     /// Returns [RouterReply::TerminalAddress] for the given address
     pub fn terminal_address(address: Option<AddressAndMetadata>) -> NodeReplyResult {
         Ok(Self::TerminalAddress(address))
@@ -321,12 +398,12 @@ impl RouterReply {
     }
 
     /// Return [RouterReply::Sender] for the given information
-    pub fn sender(addr: Address, sender: MessageSender<RelayMessage>) -> NodeReplyResult {
+    pub fn sender(addr: Address, sender: MailboxSender<RelayMessage>) -> NodeReplyResult {
         Ok(RouterReply::Sender { addr, sender })
     }
 
     /// Consume the wrapper and return [RouterReply::Sender]
-    pub fn take_sender(self) -> Result<(Address, MessageSender<RelayMessage>)> {
+    pub fn take_sender(self) -> Result<(Address, MailboxSender<RelayMessage>)> {
         match self {
             Self::Sender { addr, sender } => Ok((addr, sender)),
             _ => Err(NodeError::NodeState(NodeReason::Unknown).internal()),
@@ -341,6 +418,22 @@ impl RouterReply {
         }
     }
 
+    /// Consume the wrapper and return [RouterReply::WorkersInfo]
+    pub fn take_workers_info(self) -> Result<Vec<WorkerInfo>> {
+        match self {
+            Self::WorkersInfo(w) => Ok(w),
+            _ => Err(NodeError::NodeState(NodeReason::Unknown).internal()),
+        }
+    }
+
+    /// Consume the wrapper and return [RouterReply::Metrics]
+    pub fn take_metrics(self) -> Result<Vec<WorkerMetrics>> {
+        match self {
+            Self::Metrics(m) => Ok(m),
+            _ => Err(NodeError::NodeState(NodeReason::Unknown).internal()),
+        }
+    }
+
     /// Consume the wrapper and return [RouterReply::State]
     pub fn take_state(self) -> Result<bool> {
         match self {
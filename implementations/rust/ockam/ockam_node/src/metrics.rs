@@ -1,4 +1,5 @@
-use crate::tokio::{runtime::Runtime, time};
+use crate::compat::time;
+use crate::tokio::runtime::Runtime;
 use core::{
     sync::atomic::{AtomicBool, AtomicUsize, Ordering},
     time::Duration,
@@ -1,5 +1,8 @@
+use crate::channel_types::MailboxConfig;
 use crate::tokio::runtime::Runtime;
-use crate::{debugger, Context, Executor};
+#[cfg(feature = "simulation")]
+use crate::Simulation;
+use crate::{debugger, Context, Executor, NodeExecutor, TokioExecutor};
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::FlowControls;
 #[cfg(feature = "std")]
@@ -23,6 +26,10 @@ pub struct NodeBuilder {
     logging: bool,
     exit_on_panic: bool,
     rt: Option<Arc<Runtime>>,
+    executor: Option<Arc<dyn NodeExecutor>>,
+    mailbox_config: MailboxConfig,
+    #[cfg(feature = "simulation")]
+    simulation: Option<Simulation>,
 }
 
 impl Default for NodeBuilder {
@@ -38,6 +45,10 @@ impl NodeBuilder {
             logging: true,
             exit_on_panic: true,
             rt: None,
+            executor: None,
+            mailbox_config: MailboxConfig::default(),
+            #[cfg(feature = "simulation")]
+            simulation: None,
         }
     }
 
@@ -47,6 +58,10 @@ impl NodeBuilder {
             logging: false,
             exit_on_panic: self.exit_on_panic,
             rt: self.rt,
+            executor: self.executor,
+            mailbox_config: self.mailbox_config,
+            #[cfg(feature = "simulation")]
+            simulation: self.simulation,
         }
     }
 
@@ -56,6 +71,10 @@ impl NodeBuilder {
             logging: self.logging,
             exit_on_panic: false,
             rt: self.rt,
+            executor: self.executor,
+            mailbox_config: self.mailbox_config,
+            #[cfg(feature = "simulation")]
+            simulation: self.simulation,
         }
     }
 
@@ -65,6 +84,53 @@ impl NodeBuilder {
             logging: self.logging,
             exit_on_panic: self.exit_on_panic,
             rt: Some(rt),
+            executor: self.executor,
+            mailbox_config: self.mailbox_config,
+            #[cfg(feature = "simulation")]
+            simulation: self.simulation,
+        }
+    }
+
+    /// Use a specific executor to run the tasks of the node, instead of the Tokio runtime.
+    /// The node timers still use Tokio, unless the `smol` feature is enabled
+    pub fn with_executor(self, executor: Arc<dyn NodeExecutor>) -> Self {
+        Self {
+            logging: self.logging,
+            exit_on_panic: self.exit_on_panic,
+            rt: self.rt,
+            executor: Some(executor),
+            mailbox_config: self.mailbox_config,
+            #[cfg(feature = "simulation")]
+            simulation: self.simulation,
+        }
+    }
+
+    /// Set the default mailbox configuration of the workers and processors started on this node.
+    /// It can be overridden for a given worker with `WorkerBuilder::with_mailbox_config`
+    /// or for a given processor with `ProcessorBuilder::with_mailbox_config`
+    pub fn with_mailbox_config(self, mailbox_config: MailboxConfig) -> Self {
+        Self {
+            logging: self.logging,
+            exit_on_panic: self.exit_on_panic,
+            rt: self.rt,
+            executor: self.executor,
+            mailbox_config,
+            #[cfg(feature = "simulation")]
+            simulation: self.simulation,
+        }
+    }
+
+    /// Intercept the messages sent on this node with a [`Simulation`], so that
+    /// a test can control their delivery
+    #[cfg(feature = "simulation")]
+    pub fn with_simulation(self, simulation: Simulation) -> Self {
+        Self {
+            logging: self.logging,
+            exit_on_panic: self.exit_on_panic,
+            rt: self.rt,
+            executor: self.executor,
+            mailbox_config: self.mailbox_config,
+            simulation: Some(simulation),
         }
     }
 
@@ -116,7 +182,10 @@ impl NodeBuilder {
             #[cfg(not(feature = "std"))]
             Arc::new(Runtime::new().expect("cannot initialize the tokio runtime"))
         });
-        let mut exe = Executor::new(rt.clone(), &flow_controls);
+        let executor = self
+            .executor
+            .unwrap_or_else(|| Arc::new(TokioExecutor::new(rt.handle().clone())));
+        let mut exe = Executor::new(rt.clone(), executor.clone(), &flow_controls);
         let addr: Address = "app".into();
 
         // The root application worker needs a mailbox and relay to accept
@@ -124,6 +193,7 @@ impl NodeBuilder {
         let (ctx, sender, _) = Context::new(
             LATEST_PROTOCOL_VERSION,
             rt.handle().clone(),
+            executor,
             exe.sender(),
             Mailboxes::new(
                 Mailbox::new(addr, Arc::new(AllowAll), Arc::new(AllowAll)),
@@ -132,6 +202,14 @@ impl NodeBuilder {
             None,
             Default::default(),
             &flow_controls,
+            Default::default(),
+            #[cfg(feature = "std")]
+            Default::default(),
+            #[cfg(feature = "simulation")]
+            self.simulation,
+            None,
+            self.mailbox_config,
+            self.mailbox_config,
             #[cfg(feature = "std")]
             OpenTelemetryContext::current(),
         );
@@ -0,0 +1,205 @@
+use core::fmt::{Display, Formatter};
+use ockam_core::Address;
+use tokio::sync::broadcast;
+
+/// Maximum number of events buffered for a subscriber which is not reading them fast enough
+const EVENT_BUS_CAPACITY: usize = 1024;
+
+/// Events published on the event bus of a node
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NodeEvent {
+    /// A worker has been started
+    WorkerStarted {
+        /// Main address of the worker
+        address: Address,
+    },
+    /// A worker has been stopped
+    WorkerStopped {
+        /// Main address of the worker
+        address: Address,
+    },
+    /// A processor has been started
+    ProcessorStarted {
+        /// Main address of the processor
+        address: Address,
+    },
+    /// A processor has been stopped
+    ProcessorStopped {
+        /// Main address of the processor
+        address: Address,
+    },
+    /// A secure channel has been established
+    SecureChannelEstablished {
+        /// Encryptor address of the secure channel
+        address: Address,
+        /// Identifier of the other side of the secure channel
+        their_identifier: String,
+    },
+    /// A secure channel has been closed
+    SecureChannelClosed {
+        /// Encryptor address of the secure channel
+        address: Address,
+    },
+    /// A TCP portal connection has been established
+    PortalConnected {
+        /// Address of the portal worker handling the connection
+        address: Address,
+        /// Socket address of the connected peer
+        peer: String,
+    },
+    /// A credential has been retrieved again before expiring
+    CredentialRefreshed {
+        /// Identifier of the subject of the credential
+        subject: String,
+        /// Expiration time of the new credential, in seconds since the Unix epoch
+        expires_at: u64,
+    },
+    /// A credential could not be refreshed and is about to expire, or has expired
+    CredentialExpiring {
+        /// Identifier of the subject of the credential
+        subject: String,
+        /// Expiration time of the credential, in seconds since the Unix epoch
+        expires_at: u64,
+    },
+}
+
+impl NodeEvent {
+    /// Name of the event kind, for example "worker_started"
+    pub fn kind(&self) -> &'static str {
+        match self {
+            NodeEvent::WorkerStarted { .. } => "worker_started",
+            NodeEvent::WorkerStopped { .. } => "worker_stopped",
+            NodeEvent::ProcessorStarted { .. } => "processor_started",
+            NodeEvent::ProcessorStopped { .. } => "processor_stopped",
+            NodeEvent::SecureChannelEstablished { .. } => "secure_channel_established",
+            NodeEvent::SecureChannelClosed { .. } => "secure_channel_closed",
+            NodeEvent::PortalConnected { .. } => "portal_connected",
+            NodeEvent::CredentialRefreshed { .. } => "credential_refreshed",
+            NodeEvent::CredentialExpiring { .. } => "credential_expiring",
+        }
+    }
+}
+
+impl Display for NodeEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            NodeEvent::WorkerStarted { address }
+            | NodeEvent::WorkerStopped { address }
+            | NodeEvent::ProcessorStarted { address }
+            | NodeEvent::ProcessorStopped { address }
+            | NodeEvent::SecureChannelClosed { address } => {
+                write!(f, "{} {}", self.kind(), address)
+            }
+            NodeEvent::SecureChannelEstablished {
+                address,
+                their_identifier,
+            } => write!(f, "{} {} with {}", self.kind(), address, their_identifier),
+            NodeEvent::PortalConnected { address, peer } => {
+                write!(f, "{} {} to {}", self.kind(), address, peer)
+            }
+            NodeEvent::CredentialRefreshed {
+                subject,
+                expires_at,
+            }
+            | NodeEvent::CredentialExpiring {
+                subject,
+                expires_at,
+            } => write!(
+                f,
+                "{} for {} expiring at {}",
+                self.kind(),
+                subject,
+                expires_at
+            ),
+        }
+    }
+}
+
+/// Bus publishing the events of a node to all its subscribers.
+///
+/// The bus is shared by all the contexts of a node
+#[derive(Clone, Debug)]
+pub struct NodeEventBus {
+    sender: broadcast::Sender<NodeEvent>,
+}
+
+impl Default for NodeEventBus {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUS_CAPACITY);
+        Self { sender }
+    }
+}
+
+impl NodeEventBus {
+    /// Publish an event to all the current subscribers
+    pub fn publish(&self, event: NodeEvent) {
+        trace!("Publishing the node event {}", event);
+        // an error is only returned when there are no subscribers
+        let _ = self.sender.send(event);
+    }
+
+    /// Subscribe to the events published after this call
+    pub fn subscribe(&self) -> NodeEventSubscription {
+        NodeEventSubscription {
+            receiver: self.sender.subscribe(),
+        }
+    }
+
+    /// Number of current subscribers
+    pub fn subscribers_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+/// Subscription to the events of a node.
+///
+/// Dropping the subscription unsubscribes from the bus
+#[derive(Debug)]
+pub struct NodeEventSubscription {
+    receiver: broadcast::Receiver<NodeEvent>,
+}
+
+impl NodeEventSubscription {
+    /// Wait for the next event.
+    ///
+    /// If the subscriber is too slow, the oldest events are skipped.
+    /// `None` is returned when the node is stopped
+    pub async fn next(&mut self) -> Option<NodeEvent> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(event) => return Some(event),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("{} node events were skipped by a slow subscriber", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_subscribers_receive_events_published_after_subscribing() {
+        let bus = NodeEventBus::default();
+        bus.publish(NodeEvent::WorkerStarted {
+            address: "before".into(),
+        });
+
+        let mut subscription1 = bus.subscribe();
+        let mut subscription2 = bus.subscribe();
+        assert_eq!(bus.subscribers_count(), 2);
+
+        let event = NodeEvent::WorkerStopped {
+            address: "worker".into(),
+        };
+        bus.publish(event.clone());
+        assert_eq!(subscription1.next().await, Some(event.clone()));
+        assert_eq!(subscription2.next().await, Some(event));
+
+        drop(subscription2);
+        assert_eq!(bus.subscribers_count(), 1);
+    }
+}
@@ -0,0 +1,194 @@
+use crate::tokio::runtime::Handle;
+use core::future::Future;
+use core::pin::Pin;
+use ockam_core::compat::boxed::Box;
+
+/// A task spawned on a [`NodeExecutor`]
+pub type ExecutorTask = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// A task run to completion with [`NodeExecutor::block_on`]
+#[cfg(feature = "std")]
+pub type BlockingTask<'a> = Pin<Box<dyn Future<Output = ()> + 'a>>;
+
+/// Async runtime driving the router, the workers and the processors of a node.
+///
+/// A Tokio runtime is used by default. Another runtime can be plugged in with
+/// [`NodeBuilder::with_executor`](crate::NodeBuilder::with_executor), for example
+/// the `SmolExecutor` provided with the `smol` feature. On `no_std` targets,
+/// an embedded runtime (like embassy) can be used by implementing this trait.
+///
+/// The timers of the node use Tokio by default, and smol with the `smol` feature,
+/// see [`crate::compat::time`].
+pub trait NodeExecutor: Send + Sync + 'static {
+    /// Spawn a task running in the background
+    fn spawn(&self, task: ExecutorTask);
+
+    /// Run a task to completion, blocking the current thread
+    #[cfg(feature = "std")]
+    fn block_on(&self, task: BlockingTask<'_>);
+}
+
+/// Default [`NodeExecutor`], spawning the tasks on a Tokio runtime.
+///
+/// The runtime must be multi-threaded since its timers and IO are not
+/// driven by [`NodeExecutor::block_on`]
+#[derive(Clone)]
+pub struct TokioExecutor {
+    handle: Handle,
+}
+
+impl TokioExecutor {
+    /// Create an executor for a Tokio runtime
+    pub fn new(handle: Handle) -> Self {
+        Self { handle }
+    }
+}
+
+impl NodeExecutor for TokioExecutor {
+    fn spawn(&self, task: ExecutorTask) {
+        self.handle.spawn(task);
+    }
+
+    #[cfg(feature = "std")]
+    fn block_on(&self, task: BlockingTask<'_>) {
+        self.handle.block_on(task)
+    }
+}
+
+#[cfg(feature = "smol")]
+pub use smol_executor::SmolExecutor;
+
+#[cfg(feature = "smol")]
+mod smol_executor {
+    use super::{BlockingTask, ExecutorTask, NodeExecutor};
+    use core::future::Future;
+    use core::pin::Pin;
+    use core::task::{Context, Poll};
+    use ockam_core::compat::sync::Arc;
+    use ockam_core::compat::vec::Vec;
+    use ockam_core::errcode::{Kind, Origin};
+    use ockam_core::{Error, Result};
+    use std::thread::JoinHandle;
+    use tokio::runtime::Handle;
+
+    /// [`NodeExecutor`] spawning the tasks on a smol executor.
+    ///
+    /// The timers of the node are driven by the smol reactor, so no Tokio runtime is needed
+    /// to run the tasks. Tasks using Tokio sockets, like the TCP transport, can still be
+    /// polled within the context of a Tokio runtime, see [`SmolExecutor::with_tokio_reactor`].
+    ///
+    /// The threads of the executor are stopped once the executor and all its clones are dropped
+    #[derive(Clone)]
+    pub struct SmolExecutor {
+        threads: Arc<SmolThreads>,
+        reactor: Option<Handle>,
+    }
+
+    impl core::fmt::Debug for SmolExecutor {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_struct("SmolExecutor").finish()
+        }
+    }
+
+    impl SmolExecutor {
+        /// Create an executor running its tasks on a number of dedicated threads
+        pub fn new(threads: usize) -> Result<Self> {
+            Ok(Self {
+                threads: Arc::new(SmolThreads::start(threads)?),
+                reactor: None,
+            })
+        }
+
+        /// Poll the tasks within the context of a Tokio runtime, used as a reactor
+        /// for the Tokio timers and sockets
+        pub fn with_tokio_reactor(self, reactor: Handle) -> Self {
+            Self {
+                threads: self.threads,
+                reactor: Some(reactor),
+            }
+        }
+
+        fn with_reactor<T: Future<Output = ()> + Unpin>(&self, task: T) -> WithReactor<T> {
+            WithReactor {
+                reactor: self.reactor.clone(),
+                task,
+            }
+        }
+    }
+
+    impl NodeExecutor for SmolExecutor {
+        fn spawn(&self, task: ExecutorTask) {
+            self.threads
+                .executor
+                .spawn(self.with_reactor(task))
+                .detach();
+        }
+
+        fn block_on(&self, task: BlockingTask<'_>) {
+            smol::block_on(self.threads.executor.run(self.with_reactor(task)))
+        }
+    }
+
+    /// Threads running the tasks of a smol executor until they are dropped
+    struct SmolThreads {
+        executor: Arc<smol::Executor<'static>>,
+        /// Dropping this sender stops the threads
+        stop: Option<smol::channel::Sender<()>>,
+        handles: Vec<JoinHandle<()>>,
+    }
+
+    impl SmolThreads {
+        fn start(threads: usize) -> Result<Self> {
+            let executor = Arc::new(smol::Executor::new());
+            let (stop, stopped) = smol::channel::bounded::<()>(1);
+            let mut this = Self {
+                executor,
+                stop: Some(stop),
+                handles: Vec::with_capacity(threads),
+            };
+            for i in 0..threads {
+                let executor = this.executor.clone();
+                let stopped = stopped.clone();
+                let handle = std::thread::Builder::new()
+                    .name(format!("ockam-smol-{i}"))
+                    .spawn(move || {
+                        let _ = smol::block_on(executor.run(stopped.recv()));
+                    })
+                    // the threads started so far are stopped when `this` is dropped
+                    .map_err(|e| Error::new(Origin::Executor, Kind::Io, e))?;
+                this.handles.push(handle);
+            }
+            Ok(this)
+        }
+    }
+
+    impl Drop for SmolThreads {
+        fn drop(&mut self) {
+            // closing the channel makes all the threads return
+            self.stop.take();
+            let current = std::thread::current().id();
+            for handle in self.handles.drain(..) {
+                // the last reference to the executor can be dropped by one of its own tasks
+                if handle.thread().id() != current {
+                    let _ = handle.join();
+                }
+            }
+        }
+    }
+
+    /// Task polled within the context of a Tokio runtime, if any
+    struct WithReactor<T> {
+        reactor: Option<Handle>,
+        task: T,
+    }
+
+    impl<T: Future<Output = ()> + Unpin> Future for WithReactor<T> {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            let this = self.get_mut();
+            let _guard = this.reactor.as_ref().map(|reactor| reactor.enter());
+            Pin::new(&mut this.task).poll(cx)
+        }
+    }
+}
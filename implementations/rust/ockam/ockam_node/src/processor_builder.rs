@@ -1,5 +1,8 @@
+use crate::channel_types::MailboxConfig;
 use crate::debugger;
 use crate::error::{NodeError, NodeReason};
+#[cfg(feature = "std")]
+use crate::Schedule;
 use crate::{relay::ProcessorRelay, Context, NodeMessage};
 use alloc::string::String;
 use ockam_core::compat::{sync::Arc, vec::Vec};
@@ -42,6 +45,9 @@ where
             processor: self.processor,
             address: address.into(),
             metadata: None,
+            mailbox_config: None,
+            #[cfg(feature = "std")]
+            schedule: None,
         }
     }
 
@@ -51,6 +57,9 @@ where
             mailboxes,
             processor: self.processor,
             metadata_list: vec![],
+            mailbox_config: None,
+            #[cfg(feature = "std")]
+            schedule: None,
         }
     }
 }
@@ -62,6 +71,9 @@ where
     mailboxes: Mailboxes,
     processor: P,
     metadata_list: Vec<AddressAndMetadata>,
+    mailbox_config: Option<MailboxConfig>,
+    #[cfg(feature = "std")]
+    schedule: Option<Schedule>,
 }
 
 impl<P> ProcessorBuilderMultipleAddresses<P>
@@ -124,9 +136,33 @@ where
         self
     }
 
+    /// Set the capacity and overflow policy of the [`Processor`] mailbox.
+    /// The default configuration is the one of the node
+    pub fn with_mailbox_config(mut self, mailbox_config: MailboxConfig) -> Self {
+        self.mailbox_config = Some(mailbox_config);
+        self
+    }
+
+    /// Run the [`Processor`] according to a [`Schedule`] instead of calling
+    /// its `process()` function in a loop
+    #[cfg(feature = "std")]
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.schedule = Some(schedule);
+        self
+    }
+
     /// Consume this builder and start a new Ockam [`Processor`] from the given context
     pub async fn start(self, context: &Context) -> Result<()> {
-        start(context, self.mailboxes, self.processor, self.metadata_list).await
+        start(
+            context,
+            self.mailboxes,
+            self.processor,
+            self.metadata_list,
+            self.mailbox_config,
+            #[cfg(feature = "std")]
+            self.schedule,
+        )
+        .await
     }
 }
 
@@ -139,6 +175,9 @@ where
     address: Address,
     processor: P,
     metadata: Option<AddressAndMetadata>,
+    mailbox_config: Option<MailboxConfig>,
+    #[cfg(feature = "std")]
+    schedule: Option<Schedule>,
 }
 
 impl<P> ProcessorBuilderOneAddress<P>
@@ -197,6 +236,9 @@ where
             Mailboxes::main(self.address, self.incoming_ac, self.outgoing_ac),
             self.processor,
             self.metadata.map(|m| vec![m]).unwrap_or_default(),
+            self.mailbox_config,
+            #[cfg(feature = "std")]
+            self.schedule,
         )
         .await
     }
@@ -241,6 +283,21 @@ where
         self.outgoing_ac = outgoing_access_control.clone();
         self
     }
+
+    /// Set the capacity and overflow policy of the [`Processor`] mailbox.
+    /// The default configuration is the one of the node
+    pub fn with_mailbox_config(mut self, mailbox_config: MailboxConfig) -> Self {
+        self.mailbox_config = Some(mailbox_config);
+        self
+    }
+
+    /// Run the [`Processor`] according to a [`Schedule`] instead of calling
+    /// its `process()` function in a loop
+    #[cfg(feature = "std")]
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.schedule = Some(schedule);
+        self
+    }
 }
 
 /// Consume this builder and start a new Ockam [`Processor`] from the given context
@@ -250,6 +307,8 @@ pub async fn start<P>(
     mailboxes: Mailboxes,
     processor: P,
     metadata: Vec<AddressAndMetadata>,
+    mailbox_config: Option<MailboxConfig>,
+    #[cfg(feature = "std")] schedule: Option<Schedule>,
 ) -> Result<()>
 where
     P: Processor<Context = Context>,
@@ -264,12 +323,16 @@ where
     let addresses = mailboxes.addresses();
 
     // Pass it to the context
-    let (ctx, sender, ctrl_rx) = context.copy_with_mailboxes(mailboxes);
+    let (ctx, sender, ctrl_rx) = context.copy_with_mailboxes(
+        mailboxes,
+        mailbox_config.unwrap_or(context.default_mailbox_config()),
+    );
 
     debugger::log_inherit_context("PROCESSOR", context, &ctx);
 
     // Send start request to router
-    let (msg, mut rx) = NodeMessage::start_processor(addresses, sender, metadata);
+    let (msg, mut rx) =
+        NodeMessage::start_processor(addresses, sender, metadata, ctx.namespace().map(Into::into));
     context
         .sender()
         .send(msg)
@@ -281,8 +344,20 @@ where
         .await
         .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??;
 
+    #[cfg(feature = "std")]
+    context.publish_event(crate::NodeEvent::ProcessorStarted {
+        address: ctx.address(),
+    });
+
     // Then initialise the processor message relay
-    ProcessorRelay::<P>::init(context.runtime(), processor, ctx, ctrl_rx);
+    ProcessorRelay::<P>::init(
+        context.executor(),
+        processor,
+        ctx,
+        ctrl_rx,
+        #[cfg(feature = "std")]
+        schedule,
+    );
 
     Ok(())
 }
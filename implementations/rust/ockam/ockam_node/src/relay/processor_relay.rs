@@ -1,5 +1,8 @@
 use crate::channel_types::SmallReceiver;
-use crate::{relay::CtrlSignal, tokio::runtime::Handle, Context};
+#[cfg(feature = "std")]
+use crate::schedule::{Schedule, Ticker};
+use crate::{relay::CtrlSignal, Context, NodeExecutor};
+use ockam_core::compat::{boxed::Box, sync::Arc};
 use ockam_core::{Processor, Result};
 
 pub struct ProcessorRelay<P>
@@ -8,14 +11,25 @@ where
 {
     processor: P,
     ctx: Context,
+    #[cfg(feature = "std")]
+    schedule: Option<Schedule>,
 }
 
 impl<P> ProcessorRelay<P>
 where
     P: Processor<Context = Context>,
 {
-    pub fn new(processor: P, ctx: Context) -> Self {
-        Self { processor, ctx }
+    pub fn new(
+        processor: P,
+        ctx: Context,
+        #[cfg(feature = "std")] schedule: Option<Schedule>,
+    ) -> Self {
+        Self {
+            processor,
+            ctx,
+            #[cfg(feature = "std")]
+            schedule,
+        }
     }
 
     #[cfg_attr(not(feature = "std"), allow(unused_mut))]
@@ -24,6 +38,8 @@ where
         let mut ctx = self.ctx;
         let mut processor = self.processor;
         let ctx_addr = ctx.address();
+        #[cfg(feature = "std")]
+        let mut ticker = self.schedule.map(Ticker::new);
 
         match processor.initialize(&mut ctx).await {
             Ok(()) => {}
@@ -45,6 +61,15 @@ where
         // This future encodes the main processor run loop logic
         let run_loop = async {
             loop {
+                // wait for the next scheduled run, if the processor has a schedule
+                #[cfg(feature = "std")]
+                if let Some(ticker) = ticker.as_mut() {
+                    if !ticker.tick().await {
+                        info!("The schedule of processor '{}' has no next run", ctx_addr);
+                        break;
+                    }
+                }
+
                 match processor.process(&mut ctx).await {
                     Ok(should_continue) => {
                         if !should_continue {
@@ -93,13 +118,19 @@ where
 
     /// Create a processor relay with two node contexts
     pub(crate) fn init(
-        rt: &Handle,
+        executor: &Arc<dyn NodeExecutor>,
         processor: P,
         ctx: Context,
         ctrl_rx: SmallReceiver<CtrlSignal>,
+        #[cfg(feature = "std")] schedule: Option<Schedule>,
     ) {
-        let relay = ProcessorRelay::<P>::new(processor, ctx);
-        rt.spawn(relay.run(ctrl_rx));
+        let relay = ProcessorRelay::<P>::new(
+            processor,
+            ctx,
+            #[cfg(feature = "std")]
+            schedule,
+        );
+        executor.spawn(Box::pin(relay.run(ctrl_rx)));
     }
 }
 
@@ -117,6 +148,11 @@ async fn shutdown_and_stop_ack<P>(
         }
     }
 
+    #[cfg(feature = "std")]
+    ctx.publish_event(crate::NodeEvent::ProcessorStopped {
+        address: ctx_addr.clone(),
+    });
+
     // Finally send the router a stop ACK -- log errors
     trace!("Sending shutdown ACK");
     if let Err(e) = ctx.send_stop_ack().await {
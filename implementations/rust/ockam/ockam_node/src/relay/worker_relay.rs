@@ -1,9 +1,12 @@
 use crate::channel_types::SmallReceiver;
 use crate::relay::CtrlSignal;
-use crate::tokio::runtime::Handle;
-use crate::Context;
+use crate::{Context, NodeExecutor, RestartPolicy, SupervisionEvent, Supervisor};
 use cfg_if::cfg_if;
-use ockam_core::{Message, RelayMessage, Result, Routed, Worker};
+use ockam_core::compat::string::ToString;
+use ockam_core::compat::{boxed::Box, sync::Arc};
+#[cfg(feature = "std")]
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Message, RelayMessage, Result, Routed, Worker};
 #[cfg(feature = "std")]
 use opentelemetry::trace::FutureExt;
 
@@ -15,11 +18,22 @@ use opentelemetry::trace::FutureExt;
 pub struct WorkerRelay<W> {
     worker: W,
     ctx: Context,
+    supervisor: Option<Supervisor<W>>,
+    /// Number of consecutive restarts of a supervised worker
+    restarts: u32,
+    /// True if the last failure of a supervised worker was a panic
+    panicked: bool,
 }
 
 impl<W: Worker> WorkerRelay<W> {
-    pub fn new(worker: W, ctx: Context) -> Self {
-        Self { worker, ctx }
+    pub fn new(worker: W, ctx: Context, supervisor: Option<Supervisor<W>>) -> Self {
+        Self {
+            worker,
+            ctx,
+            supervisor,
+            restarts: 0,
+            panicked: false,
+        }
     }
 }
 
@@ -65,7 +79,30 @@ where
             }
         };
 
-        // Call the worker handle function - pass errors up
+        #[cfg(feature = "std")]
+        let started_at = std::time::Instant::now();
+        let result = self.handle_message(relay_msg).await;
+        cfg_if! {
+            if #[cfg(feature = "std")] {
+                let processing_time = Some(started_at.elapsed());
+            } else {
+                let processing_time = None;
+            }
+        }
+        self.ctx
+            .metrics_recorder()
+            .record(processing_time, result.is_ok());
+        result?;
+
+        // The worker is healthy again
+        self.restarts = 0;
+
+        // Signal to the outer loop that we would like to run again
+        Ok(true)
+    }
+
+    /// Call the worker handle function - pass errors up
+    async fn handle_message(&mut self, relay_msg: RelayMessage) -> Result<()> {
         cfg_if! {
             if #[cfg(feature = "std")] {
                 let tracing_context = relay_msg.local_message().tracing_context();
@@ -76,12 +113,30 @@ where
                 self.ctx.set_tracing_context(tracing_context.clone());
                 self.ctx.set_protocol_version(relay_msg.protocol_version());
 
-                self.worker
+                let supervised = self.supervisor.is_some();
+                let handle_message = self.worker
                     .handle_message(&mut self.ctx, Self::wrap_direct_message(relay_msg))
                     // make sure we are using the latest tracing context to handle the message
                     // the handle_message future
-                    .with_context(tracing_context.update().extract())
-                    .await?;
+                    .with_context(tracing_context.update().extract());
+
+                if supervised {
+                    // recover from panics so that the supervisor can restart the worker
+                    use futures::FutureExt;
+                    match core::panic::AssertUnwindSafe(handle_message).catch_unwind().await {
+                        Ok(result) => result?,
+                        Err(_) => {
+                            self.panicked = true;
+                            return Err(Error::new(
+                                Origin::Node,
+                                Kind::Internal,
+                                "the worker panicked",
+                            ));
+                        }
+                    }
+                } else {
+                    handle_message.await?;
+                }
             } else {
                 self.ctx.set_protocol_version(relay_msg.protocol_version());
                 let routed = Self::wrap_direct_message(relay_msg);
@@ -91,8 +146,7 @@ where
                 }
         }
 
-        // Signal to the outer loop that we would like to run again
-        Ok(true)
+        Ok(())
     }
 
     #[cfg_attr(not(feature = "std"), allow(unused_mut))]
@@ -106,8 +160,10 @@ where
                     self.ctx.address(),
                     e
                 );
-                self.shutdown_and_stop_ack().await;
-                return;
+                if !self.is_restartable() || !self.restart(e, &mut ctrl_rx).await {
+                    self.shutdown_and_stop_ack().await;
+                    return;
+                }
             }
         }
 
@@ -128,12 +184,15 @@ where
                         Ok(false) => {
                             break;
                         },
-                        // An error occurred -- log and continue
+                        // An error occurred -- log and continue, or restart a supervised worker
                         Err(e) => {
                             #[cfg(feature = "debugger")]
                             error!("Error encountered during '{}' message handling: {:?}", address, e);
                             #[cfg(not(feature = "debugger"))]
                             error!("Error encountered during '{}' message handling: {}", address, e);
+                            if self.is_restartable() && !self.restart(e, &mut ctrl_rx).await {
+                                break;
+                            }
                         }
                     }
                 },
@@ -156,17 +215,120 @@ where
                 Ok(false) => {
                     break;
                 }
-                // An error occurred -- log and continue
-                Err(e) => error!(
-                    "Error encountered during '{}' message handling: {}",
-                    address, e
-                ),
+                // An error occurred -- log and continue, or restart a supervised worker
+                Err(e) => {
+                    error!(
+                        "Error encountered during '{}' message handling: {}",
+                        address, e
+                    );
+                    if self.is_restartable() && !self.restart(e, &mut ctrl_rx).await {
+                        break;
+                    }
+                }
             }
         }
 
         self.shutdown_and_stop_ack().await;
     }
 
+    /// Return true if the worker is supervised and must be restarted after a failure
+    fn is_restartable(&self) -> bool {
+        self.supervisor
+            .as_ref()
+            .map(|s| s.policy() != RestartPolicy::Never)
+            .unwrap_or(false)
+    }
+
+    /// Restart a supervised worker after a failure: the failed worker is shut down, then
+    /// a new instance is created and initialized with a fresh context.
+    ///
+    /// Return false if the worker must be stopped, either because the maximum number of
+    /// restarts has been reached or because a shutdown signal was received
+    async fn restart(&mut self, error: Error, ctrl_rx: &mut SmallReceiver<CtrlSignal>) -> bool {
+        let supervisor = match self.supervisor.take() {
+            Some(supervisor) => supervisor,
+            None => return false,
+        };
+        let result = self.restart_with(&supervisor, error, ctrl_rx).await;
+        self.supervisor = Some(supervisor);
+        result
+    }
+
+    #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+    async fn restart_with(
+        &mut self,
+        supervisor: &Supervisor<W>,
+        error: Error,
+        ctrl_rx: &mut SmallReceiver<CtrlSignal>,
+    ) -> bool {
+        let address = self.ctx.address();
+        let mut error = error;
+        loop {
+            if self.panicked {
+                self.panicked = false;
+                supervisor.emit(SupervisionEvent::Panicked {
+                    address: address.clone(),
+                });
+            } else {
+                supervisor.emit(SupervisionEvent::Failed {
+                    address: address.clone(),
+                    error: error.to_string(),
+                });
+            }
+
+            let attempt = self.restarts + 1;
+            let delay = match supervisor.next_restart_delay(attempt) {
+                Some(delay) => delay,
+                None => {
+                    supervisor.emit(SupervisionEvent::GaveUp {
+                        address: address.clone(),
+                        restarts: self.restarts,
+                    });
+                    return false;
+                }
+            };
+            self.restarts = attempt;
+            supervisor.emit(SupervisionEvent::Restarting {
+                address: address.clone(),
+                attempt,
+                delay,
+            });
+
+            #[cfg(feature = "std")]
+            if !delay.is_zero() {
+                crate::tokio::select! {
+                    _ = crate::compat::time::sleep(delay) => {},
+                    result = ctrl_rx.recv() => {
+                        if result.is_some() {
+                            debug!("Relay received shutdown signal while restarting a worker, terminating!");
+                            return false;
+                        }
+                    }
+                }
+            }
+
+            if let Err(e) = self.worker.shutdown(&mut self.ctx).await {
+                warn!("Failure during '{}' failed worker shutdown: {}", address, e);
+            }
+            self.worker = supervisor.create_worker();
+            self.ctx = self.ctx.renew();
+
+            match self.worker.initialize(&mut self.ctx).await {
+                Ok(()) => {
+                    supervisor.emit(SupervisionEvent::Restarted {
+                        address: address.clone(),
+                        attempt,
+                    });
+                    return true;
+                }
+                Err(e) => {
+                    error!("Failure during '{}' worker initialisation: {}", address, e);
+                    error = e;
+                }
+            }
+        }
+    }
+
     async fn shutdown_and_stop_ack(&mut self) {
         // Run the shutdown hook for this worker
         match self.worker.shutdown(&mut self.ctx).await {
@@ -180,6 +342,11 @@ where
             }
         }
 
+        #[cfg(feature = "std")]
+        self.ctx.publish_event(crate::NodeEvent::WorkerStopped {
+            address: self.ctx.address(),
+        });
+
         // Finally send the router a stop ACK -- log errors
         trace!("Sending shutdown ACK");
         if let Err(e) = self.ctx.send_stop_ack().await {
@@ -188,8 +355,14 @@ where
     }
 
     /// Build and spawn a new worker relay, returning a send handle to it
-    pub(crate) fn init(rt: &Handle, worker: W, ctx: Context, ctrl_rx: SmallReceiver<CtrlSignal>) {
-        let relay = WorkerRelay::new(worker, ctx);
-        rt.spawn(relay.run(ctrl_rx));
+    pub(crate) fn init(
+        executor: &Arc<dyn NodeExecutor>,
+        worker: W,
+        ctx: Context,
+        ctrl_rx: SmallReceiver<CtrlSignal>,
+        supervisor: Option<Supervisor<W>>,
+    ) {
+        let relay = WorkerRelay::new(worker, ctx, supervisor);
+        executor.spawn(Box::pin(relay.run(ctrl_rx)));
     }
 }
@@ -13,11 +13,11 @@ use std::sync::atomic::AtomicUsize;
 use record::{AddressRecord, InternalMap, WorkerMeta};
 use state::{NodeState, RouterState};
 
-use crate::channel_types::{router_channel, MessageSender, RouterReceiver, SmallSender};
+use crate::channel_types::{router_channel, MailboxSender, RouterReceiver, SmallSender};
 use crate::{
     error::{NodeError, NodeReason},
     relay::CtrlSignal,
-    NodeMessage, NodeReplyResult, RouterReply, ShutdownType,
+    NodeExecutor, NodeMessage, NodeReplyResult, RouterReply, ShutdownType,
 };
 use ockam_core::compat::{collections::BTreeMap, sync::Arc};
 use ockam_core::flow_control::FlowControls;
@@ -26,7 +26,7 @@ use ockam_core::{Address, RelayMessage, Result, TransportType};
 /// A pair of senders to a worker relay
 #[derive(Debug)]
 pub struct SenderPair {
-    pub msgs: MessageSender<RelayMessage>,
+    pub msgs: MailboxSender<RelayMessage>,
     pub ctrl: SmallSender<CtrlSignal>,
 }
 
@@ -48,6 +48,9 @@ pub struct Router {
     external: BTreeMap<TransportType, Address>,
     /// Receiver for messages from node
     receiver: Option<RouterReceiver<NodeMessage>>,
+    /// Executor used to spawn the tasks of the router
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    executor: Arc<dyn NodeExecutor>,
 }
 
 enum RouteType {
@@ -64,13 +67,14 @@ fn determine_type(next: &Address) -> RouteType {
 }
 
 impl Router {
-    pub fn new(flow_controls: &FlowControls) -> Self {
+    pub fn new(flow_controls: &FlowControls, executor: Arc<dyn NodeExecutor>) -> Self {
         let (sender, receiver) = router_channel();
         Self {
             state: RouterState::new(sender),
             map: InternalMap::new(flow_controls),
             external: BTreeMap::new(),
             receiver: Some(receiver),
+            executor,
         }
     }
 
@@ -94,6 +98,7 @@ impl Router {
                 senders.msgs,
                 senders.ctrl,
                 Arc::new(0.into()), // don't track for app worker (yet?)
+                Default::default(),
                 WorkerMeta {
                     processor: false,
                     detached: true,
@@ -131,7 +136,7 @@ impl Router {
         addr: &Address,
         reply: &SmallSender<NodeReplyResult>,
     ) -> Result<()> {
-        if self.map.address_records_map().contains_key(addr) {
+        if self.map.address_records_map().contains_key(addr) || self.map.is_pool(addr) {
             let node = NodeError::Address(addr.clone());
 
             reply
@@ -182,8 +187,10 @@ impl Router {
                 senders,
                 detached,
                 mailbox_count,
+                metrics,
                 ref reply,
                 addresses_metadata,
+                namespace,
             } => {
                 start_worker::exec(
                     self,
@@ -191,11 +198,27 @@ impl Router {
                     senders,
                     detached,
                     addresses_metadata,
+                    namespace,
                     mailbox_count,
+                    metrics,
                     reply,
                 )
                 .await?
             }
+            StartWorkerPool(addr, ref reply) => {
+                debug!("Registering the worker pool '{}'", addr);
+                let msg = if !self.state.running() {
+                    RouterReply::node_rejected(NodeReason::Shutdown)
+                } else if self.map.insert_pool(addr.clone()) {
+                    RouterReply::ok()
+                } else {
+                    RouterReply::worker_exists(addr)
+                };
+                reply
+                    .send(msg)
+                    .await
+                    .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?;
+            }
             StopWorker(ref addr, ref detached, ref reply) => {
                 stop_worker::exec(self, addr, *detached, reply).await?
             }
@@ -206,7 +229,11 @@ impl Router {
                 senders,
                 ref reply,
                 addresses_metadata,
-            } => start_processor::exec(self, addrs, senders, addresses_metadata, reply).await?,
+                namespace,
+            } => {
+                start_processor::exec(self, addrs, senders, addresses_metadata, namespace, reply)
+                    .await?
+            }
             StopProcessor(ref addr, ref reply) => stop_processor::exec(self, addr, reply).await?,
 
             //// ==! Core node controls
@@ -264,6 +291,29 @@ impl Router {
                 .await
                 .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?,
 
+            ListWorkersInfo(sender) => sender
+                .send(RouterReply::workers_info(
+                    self.map
+                        .address_records_map()
+                        .iter()
+                        .map(|(primary, record)| record.info(primary))
+                        .collect(),
+                ))
+                .await
+                .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?,
+
+            GetMetrics(sender) => sender
+                .send(RouterReply::metrics(
+                    self.map
+                        .address_records_map()
+                        .iter()
+                        .filter(|(_, record)| !record.is_detached())
+                        .map(|(primary, record)| record.metrics(primary))
+                        .collect(),
+                ))
+                .await
+                .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?,
+
             SetCluster(addr, label, reply) => {
                 debug!("Setting cluster on address {}", addr);
                 let msg = self.map.set_cluster(label, addr);
@@ -298,12 +348,14 @@ impl Router {
             }
 
             // Handle route/ sender requests
-            SenderReq(addr, ref reply) => match determine_type(&addr) {
-                RouteType::Internal => utils::resolve(self, addr, reply).await?,
+            SenderReq(addr, ref namespace, ref reply) => match determine_type(&addr) {
+                RouteType::Internal => {
+                    utils::resolve(self, addr, namespace.as_deref(), reply).await?
+                }
                 // TODO: Remove after other transport implementations are moved to new architecture
                 RouteType::External(tt) => {
                     let addr = utils::router_addr(self, tt)?;
-                    utils::resolve(self, addr, reply).await?
+                    utils::resolve(self, addr, namespace.as_deref(), reply).await?
                 }
             },
             FindTerminalAddress(ref addresses, ref reply) => {
@@ -1,8 +1,8 @@
-use crate::channel_types::{MessageSender, SmallSender};
+use crate::channel_types::{MailboxSender, SmallSender};
 use crate::relay::CtrlSignal;
 use crate::{
     error::{NodeError, NodeReason},
-    NodeReplyResult, RouterReply,
+    NodeReplyResult, RouterReply, WorkerInfo, WorkerMetrics, WorkerMetricsRecorder,
 };
 use core::sync::atomic::{AtomicUsize, Ordering};
 use ockam_core::{
@@ -24,6 +24,10 @@ pub struct InternalMap {
     alias_map: BTreeMap<Address, Address>,
     /// Registry of arbitrary metadata for each address, lazily populated
     address_metadata_map: BTreeMap<Address, AddressMetadata>,
+    /// Namespace of each primary address started in a namespace
+    namespaces: BTreeMap<Address, String>,
+    /// Registry of worker pools, dispatching the messages sent to one address to their instances
+    pools: BTreeMap<Address, WorkerPool>,
     /// The order in which clusters are allocated and de-allocated
     cluster_order: Vec<String>,
     /// Cluster data records
@@ -43,6 +47,8 @@ impl InternalMap {
             address_records_map: Default::default(),
             alias_map: Default::default(),
             address_metadata_map: Default::default(),
+            namespaces: Default::default(),
+            pools: Default::default(),
             cluster_order: Default::default(),
             clusters: Default::default(),
             stopping: Default::default(),
@@ -113,6 +119,19 @@ impl InternalMap {
         self.address_metadata_map.get(address).cloned()
     }
 
+    pub(super) fn set_namespace(&mut self, primary_address: Address, namespace: String) {
+        _ = self.namespaces.insert(primary_address, namespace)
+    }
+
+    /// Return true if a worker in the given namespace can send messages to a primary address.
+    /// Workers outside of any namespace can reach, and be reached from, every namespace
+    pub(super) fn is_reachable(&self, primary_address: &Address, namespace: Option<&str>) -> bool {
+        match (namespace, self.namespaces.get(primary_address)) {
+            (Some(source), Some(destination)) => source == destination,
+            _ => true,
+        }
+    }
+
     pub(super) fn remove_alias(&mut self, alias_address: &Address) -> Option<Address> {
         self.alias_map.remove(alias_address)
     }
@@ -126,6 +145,77 @@ impl InternalMap {
     pub(super) fn get_primary_address(&self, alias_address: &Address) -> Option<&Address> {
         self.alias_map.get(alias_address)
     }
+
+    /// Return true if the address is already used by a worker, a processor or a pool
+    pub(super) fn address_exists(&self, address: &Address) -> bool {
+        self.alias_map.contains_key(address) || self.pools.contains_key(address)
+    }
+
+    /// Register a new pool without instances.
+    /// Return false if the address is already in use
+    pub(super) fn insert_pool(&mut self, pool_address: Address) -> bool {
+        if self.address_exists(&pool_address) {
+            return false;
+        }
+        self.pools.insert(pool_address, WorkerPool::default());
+        true
+    }
+
+    pub(super) fn remove_pool(&mut self, pool_address: &Address) {
+        self.pools.remove(pool_address);
+    }
+
+    pub(super) fn is_pool(&self, address: &Address) -> bool {
+        self.pools.contains_key(address)
+    }
+
+    pub(super) fn add_pool_instance(&mut self, pool_address: &Address, primary_address: &Address) {
+        if let Some(pool) = self.pools.get_mut(pool_address) {
+            pool.instances.push(primary_address.clone());
+        }
+    }
+
+    /// Primary addresses of all the instances of a pool
+    pub(super) fn get_pool_instances(&self, pool_address: &Address) -> Option<Vec<Address>> {
+        self.pools
+            .get(pool_address)
+            .map(|pool| pool.instances.clone())
+    }
+
+    /// Select the next running instance of a pool, in a round-robin fashion
+    pub(super) fn next_pool_instance(&mut self, pool_address: &Address) -> Option<Address> {
+        let pool = self.pools.get_mut(pool_address)?;
+        let count = pool.instances.len();
+        for _ in 0..count {
+            let instance = pool.instances[pool.next % count].clone();
+            pool.next = (pool.next + 1) % count;
+            if self
+                .address_records_map
+                .get(&instance)
+                .map_or(false, |record| record.check())
+            {
+                return Some(instance);
+            }
+        }
+        None
+    }
+
+    /// Remove an instance from its pool, and the pool itself once it has no more instances
+    fn remove_pool_instance(&mut self, primary_address: &Address) {
+        self.pools
+            .values_mut()
+            .for_each(|pool| pool.instances.retain(|a| a != primary_address));
+        self.pools.retain(|_, pool| !pool.instances.is_empty());
+    }
+}
+
+/// Instances of a worker sharing the same address
+#[derive(Debug, Default)]
+pub(super) struct WorkerPool {
+    /// Primary addresses of the instances
+    instances: Vec<Address>,
+    /// Index of the next instance receiving a message
+    next: usize,
 }
 
 impl InternalMap {
@@ -255,10 +345,15 @@ impl InternalMap {
         self.stopping.remove(&primary);
         if let Some(record) = self.remove_address_record(&primary) {
             for addr in record.address_set {
+                if self.is_pool(&addr) {
+                    continue;
+                }
                 self.remove_alias(&addr);
                 self.address_metadata_map.remove(&addr);
             }
+            self.remove_pool_instance(&primary);
         }
+        self.namespaces.remove(&primary);
     }
 }
 
@@ -272,12 +367,14 @@ pub struct WorkerMeta {
 #[derive(Debug)]
 pub struct AddressRecord {
     address_set: Vec<Address>,
-    sender: Option<MessageSender<RelayMessage>>,
+    sender: Option<MailboxSender<RelayMessage>>,
     ctrl_tx: SmallSender<CtrlSignal>, // Unused for not-detached workers
     state: AddressState,
     ready: ReadyState,
     meta: WorkerMeta,
     msg_count: Arc<AtomicUsize>,
+    total_msg_count: AtomicUsize,
+    metrics: Arc<WorkerMetricsRecorder>,
 }
 
 impl AddressRecord {
@@ -285,7 +382,7 @@ impl AddressRecord {
         &self.address_set
     }
 
-    pub fn sender(&self) -> MessageSender<RelayMessage> {
+    pub fn sender(&self) -> MailboxSender<RelayMessage> {
         self.sender.clone().expect("No such sender!")
     }
 
@@ -295,9 +392,10 @@ impl AddressRecord {
 
     pub fn new(
         address_set: Vec<Address>,
-        sender: MessageSender<RelayMessage>,
+        sender: MailboxSender<RelayMessage>,
         ctrl_tx: SmallSender<CtrlSignal>,
         msg_count: Arc<AtomicUsize>,
+        metrics: Arc<WorkerMetricsRecorder>,
         meta: WorkerMeta,
     ) -> Self {
         AddressRecord {
@@ -307,6 +405,8 @@ impl AddressRecord {
             state: AddressState::Running,
             ready: ReadyState::Initialising(vec![]),
             msg_count,
+            total_msg_count: AtomicUsize::new(0),
+            metrics,
             meta,
         }
     }
@@ -314,6 +414,34 @@ impl AddressRecord {
     #[inline]
     pub fn increment_msg_count(&self) {
         self.msg_count.fetch_add(1, Ordering::Relaxed);
+        self.total_msg_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Return the description of this worker
+    pub fn info(&self, primary_address: &Address) -> WorkerInfo {
+        WorkerInfo {
+            primary_address: primary_address.clone(),
+            addresses: self.address_set.clone(),
+            is_processor: self.meta.processor,
+            is_detached: self.meta.detached,
+            mailbox_count: self.msg_count.load(Ordering::Relaxed),
+            messages_count: self.total_msg_count.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Return true for a detached context, which has no relay
+    pub fn is_detached(&self) -> bool {
+        self.meta.detached
+    }
+
+    /// Return the runtime metrics of this worker
+    pub fn metrics(&self, primary_address: &Address) -> WorkerMetrics {
+        self.metrics.snapshot(
+            primary_address.clone(),
+            self.meta.processor,
+            self.msg_count.load(Ordering::Relaxed),
+            self.total_msg_count.load(Ordering::Relaxed),
+        )
     }
 
     /// Signal this worker to stop -- it will no longer be able to receive messages
@@ -382,7 +510,7 @@ pub enum ReadyState {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::channel_types::small_channel;
+    use crate::channel_types::{mailbox_channel, small_channel, MailboxConfig};
     use crate::router::record::InternalMap;
 
     #[test]
@@ -416,13 +544,14 @@ mod test {
 
     /// HELPERS
     fn create_address_record(primary: &str) -> AddressRecord {
-        let (tx1, _) = small_channel();
+        let (tx1, _) = mailbox_channel(MailboxConfig::default(), vec![]);
         let (tx2, _) = small_channel();
         AddressRecord::new(
             vec![primary.into()],
             tx1,
             tx2,
             Arc::new(AtomicUsize::new(1)),
+            Default::default(),
             WorkerMeta {
                 processor: false,
                 detached: false,
@@ -96,20 +96,20 @@ pub(super) async fn graceful(
     // Start a timeout task to interrupt us...
     #[cfg(feature = "std")]
     {
+        use crate::compat::time;
         use crate::NodeMessage;
         use core::time::Duration;
-        use tokio::{task, time};
 
         let sender = router.sender();
         let dur = Duration::from_secs(seconds as u64);
-        task::spawn(async move {
+        router.executor.spawn(Box::pin(async move {
             time::sleep(dur).await;
             warn!("Shutdown timeout reached; aborting node!");
             // This works only because the state of the router is `Stopping`
             if sender.send(NodeMessage::AbortNode).await.is_err() {
                 error!("Failed to send node abort signal to router");
             }
-        });
+        }));
     }
 
     // Return but DO NOT stop the router
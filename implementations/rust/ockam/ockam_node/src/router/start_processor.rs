@@ -4,7 +4,7 @@ use crate::{
     error::{NodeError, NodeReason},
     NodeReplyResult, RouterReason, RouterReply,
 };
-use ockam_core::compat::{sync::Arc, vec::Vec};
+use ockam_core::compat::{string::String, sync::Arc, vec::Vec};
 #[cfg(feature = "std")]
 use ockam_core::env::get_env;
 use ockam_core::{Address, AddressAndMetadata, Result};
@@ -15,10 +15,13 @@ pub(super) async fn exec(
     addrs: Vec<Address>,
     senders: SenderPair,
     addresses_metadata: Vec<AddressAndMetadata>,
+    namespace: Option<String>,
     reply: &SmallSender<NodeReplyResult>,
 ) -> Result<()> {
     match router.state.node_state() {
-        NodeState::Running => start(router, addrs, senders, addresses_metadata, reply).await,
+        NodeState::Running => {
+            start(router, addrs, senders, addresses_metadata, namespace, reply).await
+        }
         NodeState::Stopping(_) => reject(reply).await,
         NodeState::Dead => unreachable!(),
     }?;
@@ -30,6 +33,7 @@ async fn start(
     addrs: Vec<Address>,
     senders: SenderPair,
     addresses_metadata: Vec<AddressAndMetadata>,
+    namespace: Option<String>,
     reply: &SmallSender<NodeReplyResult>,
 ) -> Result<()> {
     let primary_addr = addrs
@@ -52,6 +56,7 @@ async fn start(
         // irrelevant.  We may want to re-visit this decision in the
         // future, if the way processors are used changes.
         Arc::new(0.into()),
+        Default::default(),
         WorkerMeta {
             processor: true,
             detached: false,
@@ -74,6 +79,10 @@ async fn start(
         router.map.set_address_metadata(metadata);
     }
 
+    if let Some(namespace) = namespace {
+        router.map.set_namespace(primary_addr.clone(), namespace);
+    }
+
     #[cfg(feature = "std")]
     if let Ok(Some(dump_internals)) = get_env::<bool>("OCKAM_DUMP_INTERNALS") {
         if dump_internals {
@@ -2,24 +2,27 @@ use super::{AddressRecord, NodeState, Router, SenderPair, WorkerMeta};
 use crate::channel_types::SmallSender;
 use crate::{
     error::{NodeError, NodeReason},
-    NodeReplyResult, RouterReason, RouterReply,
+    NodeReplyResult, RouterReason, RouterReply, WorkerMetricsRecorder,
 };
 use core::sync::atomic::AtomicUsize;
 #[cfg(feature = "std")]
 use ockam_core::env::get_env;
 use ockam_core::{
-    compat::{sync::Arc, vec::Vec},
+    compat::{string::String, sync::Arc, vec::Vec},
     Address, AddressAndMetadata, Result,
 };
 
 /// Execute a `StartWorker` command
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn exec(
     router: &mut Router,
     addrs: Vec<Address>,
     senders: SenderPair,
     detached: bool,
     addresses_metadata: Vec<AddressAndMetadata>,
-    metrics: Arc<AtomicUsize>,
+    namespace: Option<String>,
+    mailbox_count: Arc<AtomicUsize>,
+    metrics: Arc<WorkerMetricsRecorder>,
     reply: &SmallSender<NodeReplyResult>,
 ) -> Result<()> {
     match router.state.node_state() {
@@ -30,6 +33,8 @@ pub(super) async fn exec(
                 senders,
                 detached,
                 addresses_metadata,
+                namespace,
+                mailbox_count,
                 metrics,
                 reply,
             )
@@ -41,13 +46,16 @@ pub(super) async fn exec(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn start(
     router: &mut Router,
     addrs: Vec<Address>,
     senders: SenderPair,
     detached: bool,
     addresses_metadata: Vec<AddressAndMetadata>,
-    metrics: Arc<AtomicUsize>,
+    namespace: Option<String>,
+    mailbox_count: Arc<AtomicUsize>,
+    metrics: Arc<WorkerMetricsRecorder>,
     reply: &SmallSender<NodeReplyResult>,
 ) -> Result<()> {
     let primary_addr = addrs
@@ -67,6 +75,7 @@ async fn start(
         addrs.clone(),
         msgs,
         ctrl,
+        mailbox_count,
         metrics,
         WorkerMeta {
             processor: false,
@@ -90,6 +99,10 @@ async fn start(
         router.map.set_address_metadata(metadata);
     }
 
+    if let Some(namespace) = namespace {
+        router.map.set_namespace(primary_addr.clone(), namespace);
+    }
+
     #[cfg(feature = "std")]
     if let Ok(Some(_)) = get_env::<String>("OCKAM_DUMP_INTERNALS") {
         trace!("{:#?}", router.map.address_records_map());
@@ -97,8 +110,14 @@ async fn start(
     #[cfg(all(not(feature = "std"), feature = "dump_internals"))]
     trace!("{:#?}", router.map.internal);
 
+    // Messages sent to the address of a pool are dispatched by the pool, so
+    // its instances are registered with the pool rather than as aliases
     addrs.iter().for_each(|addr| {
-        router.map.insert_alias(addr, primary_addr);
+        if router.map.is_pool(addr) {
+            router.map.add_pool_instance(addr, primary_addr);
+        } else {
+            router.map.insert_alias(addr, primary_addr);
+        }
     });
 
     // For now we just send an OK back -- in the future we need to
@@ -14,6 +14,24 @@ pub(super) async fn exec(
 ) -> Result<()> {
     trace!("Stopping worker '{}'", addr);
 
+    // Stopping a pool stops all its instances
+    if let Some(instances) = router.map.get_pool_instances(addr) {
+        if instances.is_empty() {
+            router.map.remove_pool(addr);
+        }
+        for instance in instances {
+            if let Some(record) = router.map.get_address_record_mut(&instance) {
+                record.drop_sender();
+            }
+        }
+        reply
+            .send(RouterReply::ok())
+            .await
+            .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?;
+
+        return Ok(());
+    }
+
     // Resolve any secondary address to the primary address
     let primary_address = match router.map.get_primary_address(addr) {
         Some(p) => p.clone(),
@@ -11,15 +11,33 @@ use ockam_core::{Address, Result, TransportType};
 /// This function only applies to local address types, and will
 /// fail to resolve a correct address if it given a remote
 /// address.
+///
+/// A worker in another namespace than the requesting worker is
+/// reported as not found.
 pub(super) async fn resolve(
     router: &mut Router,
     addr: Address,
+    namespace: Option<&str>,
     reply: &SmallSender<NodeReplyResult>,
 ) -> Result<()> {
     let base = format!("Resolving worker address '{}'...", addr);
 
-    let address_record = if let Some(primary_address) = router.map.get_primary_address(&addr) {
-        router.map.get_address_record(primary_address)
+    // The address of a pool resolves to one of its instances, in turn
+    let primary_address = match router.map.next_pool_instance(&addr) {
+        Some(instance) => Some(instance),
+        None => router.map.get_primary_address(&addr).cloned(),
+    };
+
+    let primary_address = match primary_address {
+        Some(primary_address) if !router.map.is_reachable(&primary_address, namespace) => {
+            trace!("{} REJECTED; worker in another namespace", base);
+            None
+        }
+        primary_address => primary_address,
+    };
+
+    let address_record = if let Some(primary_address) = primary_address {
+        router.map.get_address_record(&primary_address)
     } else {
         trace!("{} FAILED; no such worker", base);
         reply
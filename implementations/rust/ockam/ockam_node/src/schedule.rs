@@ -0,0 +1,390 @@
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+use core::time::Duration;
+use ockam_core::compat::rand::{thread_rng, Rng};
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Schedule used to run a [`Processor`](ockam_core::Processor) periodically
+/// instead of calling its `process()` function in a loop.
+///
+/// ```rust
+/// # use core::time::Duration;
+/// # use ockam_node::Schedule;
+/// # fn main() -> ockam_core::Result<()> {
+/// // run every 10 seconds, with a random delay of at most 1 second
+/// let every_10_seconds = Schedule::interval(Duration::from_secs(10))
+///     .with_jitter(Duration::from_secs(1));
+///
+/// // run every day at 03:30 UTC
+/// let every_night = Schedule::cron("30 3 * * *")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Schedule {
+    kind: ScheduleKind,
+    drift_correction: bool,
+    jitter: Duration,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ScheduleKind {
+    Interval(Duration),
+    Cron(CronExpression),
+}
+
+impl Schedule {
+    /// Run the processor immediately and then every `period`.
+    ///
+    /// By default the runs are aligned on the start time: a long run delays the next one
+    /// but the following runs catch up, and the ticks missed during a run are skipped
+    pub fn interval(period: Duration) -> Self {
+        Self {
+            kind: ScheduleKind::Interval(period),
+            drift_correction: true,
+            jitter: Duration::ZERO,
+        }
+    }
+
+    /// Run the processor at the times matching a cron expression, evaluated in UTC.
+    ///
+    /// The expression has 5 fields: minute, hour, day of month, month, day of week,
+    /// each field accepting `*`, values, ranges `a-b`, lists `a,b` and steps `*/n` or `a-b/n`.
+    /// The aliases `@hourly`, `@daily`, `@weekly`, `@monthly` and `@yearly` are also supported
+    pub fn cron(expression: &str) -> Result<Self> {
+        Ok(Self {
+            kind: ScheduleKind::Cron(expression.parse()?),
+            drift_correction: true,
+            jitter: Duration::ZERO,
+        })
+    }
+
+    /// Delay each run by a random duration between 0 and `jitter`,
+    /// to avoid running many processors at the same time
+    pub fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Wait for a full period after the end of each run, instead of aligning the runs
+    /// on the start time. This has no effect on cron schedules
+    pub fn without_drift_correction(mut self) -> Self {
+        self.drift_correction = false;
+        self
+    }
+}
+
+/// Computes the time of the successive runs of a schedule
+pub(crate) struct Ticker {
+    schedule: Schedule,
+    next: Option<Instant>,
+}
+
+impl Ticker {
+    pub(crate) fn new(schedule: Schedule) -> Self {
+        Self {
+            schedule,
+            next: None,
+        }
+    }
+
+    /// Wait until the next run.
+    /// Return false if the schedule has no next run
+    pub(crate) async fn tick(&mut self) -> bool {
+        let now_unix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let next = match self.next_tick(Instant::now(), now_unix) {
+            Some(next) => next,
+            None => return false,
+        };
+        let jitter = if self.schedule.jitter.is_zero() {
+            Duration::ZERO
+        } else {
+            Duration::from_nanos(thread_rng().gen_range(0..=self.schedule.jitter.as_nanos() as u64))
+        };
+        crate::compat::time::sleep_until(next + jitter).await;
+        true
+    }
+
+    /// Return the instant of the next run, without jitter
+    fn next_tick(&mut self, now: Instant, now_unix: u64) -> Option<Instant> {
+        match &self.schedule.kind {
+            ScheduleKind::Interval(period) => {
+                let next = match self.next {
+                    Some(previous) if self.schedule.drift_correction && !period.is_zero() => {
+                        let mut next = previous + *period;
+                        if next < now {
+                            // skip the ticks missed during the last run
+                            let missed = (now - next).as_nanos() / period.as_nanos() + 1;
+                            next += *period * missed as u32;
+                        }
+                        next
+                    }
+                    Some(_) => now + *period,
+                    None => now,
+                };
+                self.next = Some(next);
+                Some(next)
+            }
+            ScheduleKind::Cron(expression) => {
+                let next_unix = expression.next_after(now_unix)?;
+                Some(now + Duration::from_secs(next_unix - now_unix))
+            }
+        }
+    }
+}
+
+/// A parsed cron expression: minute, hour, day of month, month, day of week
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CronExpression {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+/// Number of days searched for the next run of a cron expression.
+/// Some expressions, like `0 0 30 2 *`, never match
+const CRON_SEARCH_DAYS: u64 = 366 * 5;
+
+impl CronExpression {
+    /// Return the first time, in seconds since the Unix epoch, strictly after `after`
+    /// matching this expression
+    pub fn next_after(&self, after: u64) -> Option<u64> {
+        let start_minute = after / 60 + 1;
+        let start_day = start_minute / (24 * 60);
+        for day in start_day..start_day + CRON_SEARCH_DAYS {
+            if !self.matches_day(day) {
+                continue;
+            }
+            let first_minute_of_day = if day == start_day {
+                start_minute % (24 * 60)
+            } else {
+                0
+            };
+            for minute_of_day in first_minute_of_day..24 * 60 {
+                if is_set(self.hours, minute_of_day / 60)
+                    && is_set(self.minutes, minute_of_day % 60)
+                {
+                    return Some((day * 24 * 60 + minute_of_day) * 60);
+                }
+            }
+        }
+        None
+    }
+
+    fn matches_day(&self, days_since_epoch: u64) -> bool {
+        let (_, month, day) = civil_from_days(days_since_epoch);
+        if !is_set(self.months, month) {
+            return false;
+        }
+        // 1970-01-01 was a thursday
+        let day_of_week = (days_since_epoch + 4) % 7;
+        let day_of_month_matches = is_set(self.days_of_month, day);
+        let day_of_week_matches = is_set(self.days_of_week, day_of_week);
+        // like cron, the day matches either field when both are restricted
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (false, false) => day_of_month_matches || day_of_week_matches,
+            _ => day_of_month_matches && day_of_week_matches,
+        }
+    }
+}
+
+impl FromStr for CronExpression {
+    type Err = Error;
+
+    fn from_str(expression: &str) -> Result<Self> {
+        let expanded = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let (minutes, hours, days_of_month, months, days_of_week) = match fields[..] {
+            [minutes, hours, days_of_month, months, days_of_week] => {
+                (minutes, hours, days_of_month, months, days_of_week)
+            }
+            _ => {
+                return Err(cron_error(
+                    expression,
+                    "expected 5 fields: minute hour day-of-month month day-of-week",
+                ))
+            }
+        };
+        let mut days_of_week_mask = parse_field(expression, days_of_week, 0, 7)?;
+        // both 0 and 7 are sunday
+        if is_set(days_of_week_mask, 7) {
+            days_of_week_mask |= 1;
+        }
+        Ok(Self {
+            expression: expression.trim().to_string(),
+            minutes: parse_field(expression, minutes, 0, 59)?,
+            hours: parse_field(expression, hours, 0, 23)?,
+            days_of_month: parse_field(expression, days_of_month, 1, 31)?,
+            months: parse_field(expression, months, 1, 12)?,
+            days_of_week: days_of_week_mask,
+            any_day_of_month: days_of_month == "*",
+            any_day_of_week: days_of_week == "*",
+        })
+    }
+}
+
+impl Display for CronExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+/// Parse a cron field into a bit mask of the allowed values
+fn parse_field(expression: &str, field: &str, min: u64, max: u64) -> Result<u64> {
+    let mut mask = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, parse_value(expression, step)?),
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            range => match range.split_once('-') {
+                Some((start, end)) => (
+                    parse_value(expression, start)?,
+                    parse_value(expression, end)?,
+                ),
+                // a single value with a step, like `5/10`, runs until the end of the range
+                None if step > 1 => (parse_value(expression, range)?, max),
+                None => {
+                    let value = parse_value(expression, range)?;
+                    (value, value)
+                }
+            },
+        };
+        if step == 0 || start < min || end > max || start > end {
+            return Err(cron_error(
+                expression,
+                &format!("invalid field '{part}', values must be between {min} and {max}"),
+            ));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+fn parse_value(expression: &str, value: &str) -> Result<u64> {
+    value
+        .parse()
+        .map_err(|_| cron_error(expression, &format!("'{value}' is not a number")))
+}
+
+fn cron_error(expression: &str, reason: &str) -> Error {
+    Error::new(
+        Origin::Node,
+        Kind::Parse,
+        format!("invalid cron expression '{expression}': {reason}"),
+    )
+}
+
+fn is_set(mask: u64, value: u64) -> bool {
+    mask & (1 << value) != 0
+}
+
+/// Convert a number of days since the Unix epoch to a (year, month, day) date
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-03-15T10:20:30Z, a friday
+    const NOW: u64 = 1_710_498_030;
+
+    #[test]
+    fn test_civil_from_days() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(NOW / 86_400), (2024, 3, 15));
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+    }
+
+    #[test]
+    fn test_cron_next_after() -> Result<()> {
+        let every_5_minutes: CronExpression = "*/5 * * * *".parse()?;
+        assert_eq!(every_5_minutes.next_after(NOW), Some(NOW - 30 + 5 * 60));
+
+        let daily: CronExpression = "@daily".parse()?;
+        assert_eq!(daily.next_after(NOW), Some(1_710_547_200)); // 2024-03-16T00:00:00Z
+
+        let mondays: CronExpression = "30 3 * * 1".parse()?;
+        assert_eq!(mondays.next_after(NOW), Some(1_710_732_600)); // 2024-03-18T03:30:00Z
+
+        let never: CronExpression = "0 0 30 2 *".parse()?;
+        assert_eq!(never.next_after(NOW), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_cron_expressions() {
+        for expression in [
+            "* * * *",
+            "60 * * * *",
+            "*/0 * * * *",
+            "a * * * *",
+            "5-1 * * * *",
+        ] {
+            assert!(
+                expression.parse::<CronExpression>().is_err(),
+                "{expression}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_interval_drift_correction() {
+        let period = Duration::from_secs(10);
+        let start = Instant::now();
+
+        let mut ticker = Ticker::new(Schedule::interval(period));
+        assert_eq!(ticker.next_tick(start, NOW), Some(start));
+        // a short run does not delay the next tick
+        assert_eq!(
+            ticker.next_tick(start + Duration::from_secs(3), NOW),
+            Some(start + period)
+        );
+        // the ticks missed during a long run are skipped
+        assert_eq!(
+            ticker.next_tick(start + Duration::from_secs(35), NOW),
+            Some(start + Duration::from_secs(40))
+        );
+
+        let mut ticker = Ticker::new(Schedule::interval(period).without_drift_correction());
+        assert_eq!(ticker.next_tick(start, NOW), Some(start));
+        assert_eq!(
+            ticker.next_tick(start + Duration::from_secs(3), NOW),
+            Some(start + Duration::from_secs(13))
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use core::fmt::{Debug, Formatter};
+use core::future::Future;
+use core::pin::Pin;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::string::String;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+
+/// Phases of a graceful node shutdown.
+///
+/// The shutdown hooks are run phase by phase, in the order of this enum,
+/// before the workers of the node are stopped
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ShutdownPhase {
+    /// Stop accepting new work, for example close listeners or stop polling sources
+    StopAccepting,
+    /// Process or flush the work in progress, for example flush buffers
+    Drain,
+    /// Release the remaining resources, for example close connections or files
+    Close,
+}
+
+type ShutdownHookFuture = Pin<Box<dyn Future<Output = Result<()>> + Send + 'static>>;
+type ShutdownHook = Box<dyn FnOnce() -> ShutdownHookFuture + Send + 'static>;
+
+struct RegisteredHook {
+    phase: ShutdownPhase,
+    name: String,
+    hook: ShutdownHook,
+}
+
+/// Async hooks run during a graceful shutdown of the node.
+///
+/// The hooks are shared by all the contexts of a node
+#[derive(Clone, Default)]
+pub struct ShutdownHooks {
+    hooks: Arc<Mutex<Vec<RegisteredHook>>>,
+}
+
+impl Debug for ShutdownHooks {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ShutdownHooks")
+            .field("count", &self.len())
+            .finish()
+    }
+}
+
+impl ShutdownHooks {
+    /// Register a hook to run during a given shutdown phase.
+    /// The hooks of a same phase are run in their registration order
+    pub fn register<F, Fut>(&self, phase: ShutdownPhase, name: impl Into<String>, hook: F)
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let hook: ShutdownHook = Box::new(move || Box::pin(hook()));
+        self.hooks.lock().unwrap().push(RegisteredHook {
+            phase,
+            name: name.into(),
+            hook,
+        });
+    }
+
+    /// Number of hooks which have not been run yet
+    pub fn len(&self) -> usize {
+        self.hooks.lock().unwrap().len()
+    }
+
+    /// Return true if there are no hooks left to run
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Run all the registered hooks, phase by phase.
+    /// Failing hooks are logged and don't prevent the next hooks from running.
+    /// Each hook is only run once
+    pub(crate) async fn run(&self) {
+        let mut hooks: Vec<RegisteredHook> = core::mem::take(&mut *self.hooks.lock().unwrap());
+        // the sort is stable so the registration order is kept within a phase
+        hooks.sort_by_key(|h| h.phase);
+        for registered in hooks {
+            debug!(
+                "Running the shutdown hook '{}' ({:?})",
+                registered.name, registered.phase
+            );
+            if let Err(e) = (registered.hook)().await {
+                warn!(
+                    "The shutdown hook '{}' ({:?}) failed: {}",
+                    registered.name, registered.phase, e
+                );
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_hooks_are_run_by_phase_and_only_once() {
+        let hooks = ShutdownHooks::default();
+        let calls = Arc::new(Mutex::new(vec![]));
+        for (phase, name) in [
+            (ShutdownPhase::Close, "close"),
+            (ShutdownPhase::Drain, "drain 1"),
+            (ShutdownPhase::StopAccepting, "stop accepting"),
+            (ShutdownPhase::Drain, "drain 2"),
+        ] {
+            let calls = calls.clone();
+            hooks.register(phase, name, move || async move {
+                calls.lock().unwrap().push(name);
+                Ok(())
+            });
+        }
+
+        hooks.run().await;
+        hooks.run().await;
+        assert!(hooks.is_empty());
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec!["stop accepting", "drain 1", "drain 2", "close"]
+        );
+    }
+}
@@ -0,0 +1,283 @@
+use crate::channel_types::MailboxSender;
+use crate::compat::time;
+use crate::error::NodeError;
+use core::time::Duration;
+use ockam_core::compat::collections::{BTreeSet, VecDeque};
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Address, Error, RelayMessage, Result};
+use std::sync::MutexGuard;
+use tokio::sync::Notify;
+
+/// Fault injected on the messages sent from one address to another
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkFault {
+    /// The messages are dropped
+    Drop,
+    /// The messages can only be delivered after a number of simulation steps
+    Delay(u64),
+}
+
+/// Description of a message intercepted by a [`Simulation`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulatedMessage {
+    /// Identifier of the message, in the order of interception
+    pub id: u64,
+    /// Address of the sender
+    pub source: Address,
+    /// Address of the recipient
+    pub destination: Address,
+    /// Step from which the message can be delivered
+    pub deliverable_at: u64,
+}
+
+/// A message waiting to be delivered
+struct PendingMessage {
+    info: SimulatedMessage,
+    relay_msg: RelayMessage,
+    sender: MailboxSender<RelayMessage>,
+}
+
+#[derive(Default)]
+struct SimulationState {
+    step: u64,
+    next_id: u64,
+    /// Only the messages sent from or to these addresses are intercepted, if set
+    intercepted: Option<BTreeSet<Address>>,
+    faults: Vec<(Address, Address, LinkFault)>,
+    pending: VecDeque<PendingMessage>,
+    delivered: Vec<SimulatedMessage>,
+    dropped: Vec<SimulatedMessage>,
+}
+
+impl SimulationState {
+    fn is_intercepted(&self, relay_msg: &RelayMessage) -> bool {
+        match &self.intercepted {
+            None => true,
+            Some(addresses) => {
+                addresses.contains(relay_msg.source())
+                    || addresses.contains(relay_msg.destination())
+            }
+        }
+    }
+
+    fn fault(&self, source: &Address, destination: &Address) -> Option<LinkFault> {
+        self.faults
+            .iter()
+            .rev()
+            .find(|(from, to, _)| from == source && to == destination)
+            .map(|(_, _, fault)| *fault)
+    }
+}
+
+/// Deterministic scheduler for the delivery of local messages, to be used in tests.
+///
+/// When a simulation is installed on a node with
+/// [`NodeBuilder::with_simulation`](crate::NodeBuilder::with_simulation), the messages sent
+/// by the workers are not delivered to their recipients right away. They are queued until
+/// the test delivers them, one at a time, with [`Simulation::step`] or [`Simulation::deliver`].
+///
+/// Faults can be injected between two addresses so that their messages are
+/// delayed by a number of steps or dropped. This allows protocols to be tested
+/// for ordering and timeout issues in a reproducible way.
+#[derive(Clone, Default)]
+pub struct Simulation {
+    state: Arc<Mutex<SimulationState>>,
+    intercepted_messages: Arc<Notify>,
+}
+
+impl core::fmt::Debug for Simulation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Simulation").finish()
+    }
+}
+
+impl Simulation {
+    /// Create a simulation intercepting all the messages of the node
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only intercept the messages sent from or to the given addresses.
+    /// The other messages are delivered immediately
+    pub fn intercept_only(self, addresses: impl IntoIterator<Item = impl Into<Address>>) -> Self {
+        self.state().intercepted = Some(addresses.into_iter().map(|a| a.into()).collect());
+        self
+    }
+
+    /// Inject a fault on the messages sent from `from` to `to`.
+    /// The fault applies to the messages sent after this call
+    pub fn inject_fault(&self, from: impl Into<Address>, to: impl Into<Address>, fault: LinkFault) {
+        self.state().faults.push((from.into(), to.into(), fault));
+    }
+
+    /// Remove all the injected faults
+    pub fn clear_faults(&self) {
+        self.state().faults.clear();
+    }
+
+    /// Current step of the simulation
+    pub fn current_step(&self) -> u64 {
+        self.state().step
+    }
+
+    /// Messages waiting to be delivered, in the order of interception
+    pub fn pending(&self) -> Vec<SimulatedMessage> {
+        self.state()
+            .pending
+            .iter()
+            .map(|m| m.info.clone())
+            .collect()
+    }
+
+    /// Messages delivered so far, in the order of delivery
+    pub fn delivered(&self) -> Vec<SimulatedMessage> {
+        self.state().delivered.clone()
+    }
+
+    /// Messages dropped so far
+    pub fn dropped(&self) -> Vec<SimulatedMessage> {
+        self.state().dropped.clone()
+    }
+
+    /// Advance the simulation by one step and deliver the oldest message
+    /// which can be delivered at that step, if any
+    pub async fn step(&self) -> Result<Option<SimulatedMessage>> {
+        let message = {
+            let mut state = self.state();
+            state.step += 1;
+            let step = state.step;
+            state
+                .pending
+                .iter()
+                .position(|m| m.info.deliverable_at <= step)
+                .and_then(|index| state.pending.remove(index))
+        };
+        match message {
+            Some(message) => self.send(message).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Deliver a specific pending message, regardless of its delay and of the
+    /// messages intercepted before it. This can be used to reorder messages
+    pub async fn deliver(&self, id: u64) -> Result<SimulatedMessage> {
+        let message = self.take(id)?;
+        self.send(message).await
+    }
+
+    /// Drop a specific pending message
+    pub fn drop_message(&self, id: u64) -> Result<SimulatedMessage> {
+        let message = self.take(id)?;
+        self.state().dropped.push(message.info.clone());
+        Ok(message.info)
+    }
+
+    /// Wait until at least `count` messages are waiting to be delivered
+    pub async fn wait_for_pending(&self, count: usize, timeout: Duration) -> Result<()> {
+        let wait = async {
+            loop {
+                let notified = self.intercepted_messages.notified();
+                if self.state().pending.len() >= count {
+                    return;
+                }
+                notified.await;
+            }
+        };
+        time::timeout(timeout, wait).await.map_err(|_| {
+            Error::new(
+                Origin::Node,
+                Kind::Timeout,
+                format!("less than {count} messages are pending"),
+            )
+        })
+    }
+
+    /// Deliver messages step by step, until no message is pending and no new message
+    /// is sent during the `settle` period. Return the number of delivered messages
+    pub async fn run_until_idle(&self, settle: Duration) -> Result<usize> {
+        let mut delivered = 0;
+        loop {
+            if self.state().pending.is_empty() && self.wait_for_pending(1, settle).await.is_err() {
+                return Ok(delivered);
+            }
+            if self.step().await?.is_some() {
+                delivered += 1;
+            }
+        }
+    }
+
+    /// Intercept a message sent to a worker mailbox.
+    /// The message and the sender are returned if the message must be delivered right away
+    pub(crate) fn intercept(
+        &self,
+        sender: MailboxSender<RelayMessage>,
+        relay_msg: RelayMessage,
+    ) -> Option<(MailboxSender<RelayMessage>, RelayMessage)> {
+        let mut state = self.state();
+        if !state.is_intercepted(&relay_msg) {
+            return Some((sender, relay_msg));
+        }
+
+        let fault = state.fault(relay_msg.source(), relay_msg.destination());
+        let delay = match fault {
+            Some(LinkFault::Delay(steps)) => steps,
+            _ => 0,
+        };
+        let info = SimulatedMessage {
+            id: state.next_id,
+            source: relay_msg.source().clone(),
+            destination: relay_msg.destination().clone(),
+            deliverable_at: state.step + delay,
+        };
+        state.next_id += 1;
+
+        if fault == Some(LinkFault::Drop) {
+            trace!("Simulation: dropping the message {:?}", info);
+            state.dropped.push(info);
+        } else {
+            trace!("Simulation: intercepting the message {:?}", info);
+            state.pending.push_back(PendingMessage {
+                info,
+                relay_msg,
+                sender,
+            });
+            self.intercepted_messages.notify_waiters();
+        }
+        None
+    }
+
+    fn take(&self, id: u64) -> Result<PendingMessage> {
+        let mut state = self.state();
+        let index = state
+            .pending
+            .iter()
+            .position(|m| m.info.id == id)
+            .ok_or_else(|| {
+                Error::new(
+                    Origin::Node,
+                    Kind::NotFound,
+                    format!("no pending message with id {id}"),
+                )
+            })?;
+        Ok(state
+            .pending
+            .remove(index)
+            .expect("the index must be valid"))
+    }
+
+    async fn send(&self, message: PendingMessage) -> Result<SimulatedMessage> {
+        trace!("Simulation: delivering the message {:?}", message.info);
+        message
+            .sender
+            .send(message.relay_msg)
+            .await
+            .map_err(NodeError::from_send_err)?;
+        self.state().delivered.push(message.info.clone());
+        Ok(message.info)
+    }
+
+    fn state(&self) -> MutexGuard<'_, SimulationState> {
+        self.state.lock().unwrap()
+    }
+}
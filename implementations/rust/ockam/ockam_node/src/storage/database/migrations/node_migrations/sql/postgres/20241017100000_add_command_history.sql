@@ -0,0 +1,9 @@
+-- This migration creates a table to store the executions of the commands modifying the Ockam configuration
+CREATE TABLE command_execution
+(
+    command_name TEXT NOT NULL, -- Name of the command, for example `tcp-inlet create`
+    arguments    TEXT NOT NULL, -- Arguments of the command, without secrets
+    user_name    TEXT NOT NULL, -- Name of the OS user who executed the command
+    executed_at  TEXT NOT NULL, -- Date and time of the execution, in the RFC 3339 format
+    error        TEXT           -- Error message if the command failed, NULL if it succeeded
+);
@@ -0,0 +1,10 @@
+-- This migration creates a table to store the last heartbeat of each running node
+CREATE TABLE node_heartbeat
+(
+    node_name          TEXT PRIMARY KEY, -- Node name
+    last_seen_at       INTEGER NOT NULL, -- UNIX timestamp in seconds of the last heartbeat
+    workers_count      INTEGER NOT NULL, -- Number of workers running on the node
+    secure_channels    INTEGER NOT NULL, -- Number of secure channels created by the node
+    tcp_inlets         INTEGER NOT NULL, -- Number of TCP inlets created on the node
+    tcp_outlets        INTEGER NOT NULL  -- Number of TCP outlets created on the node
+);
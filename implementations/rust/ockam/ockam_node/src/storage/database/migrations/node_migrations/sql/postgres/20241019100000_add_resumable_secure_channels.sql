@@ -0,0 +1,9 @@
+-- This migration adds the encryption and decryption state of the secure channels
+-- which can be resumed after a restart of the node, without a new handshake.
+-- The columns are NULL for the secure channels which are only used to exchange keys
+ALTER TABLE secure_channel ADD COLUMN encryptor_address TEXT;       -- Address of the encryptor
+ALTER TABLE secure_channel ADD COLUMN encryptor_api_address TEXT;   -- Address of the encryptor API
+ALTER TABLE secure_channel ADD COLUMN their_decryptor_address TEXT; -- Address of the decryptor of the other party
+ALTER TABLE secure_channel ADD COLUMN encryption_key_handle BYTEA;  -- Handle of the current encryption key
+ALTER TABLE secure_channel ADD COLUMN encryption_nonce BIGINT;      -- First nonce encrypted with the current encryption key
+ALTER TABLE secure_channel ADD COLUMN decryption_nonce BIGINT;      -- First nonce decrypted with the current decryption key
@@ -0,0 +1,12 @@
+-- This table stores the purpose keys which have been replaced by a new key after a rotation.
+-- They are kept until the end of their grace period, so that the secure channels and credentials
+-- using them can still be used, and their secret is then deleted from the vault
+CREATE TABLE retired_purpose_key
+(
+    identifier              TEXT    NOT NULL, -- Identity identifier
+    purpose                 TEXT    NOT NULL, -- Purpose of the key: SecureChannels, or Credentials
+    purpose_key_attestation BYTEA   NOT NULL, -- Encoded attestation: attestation data and attestation signature
+    valid_until             BIGINT  NOT NULL  -- End of the grace period of the key
+);
+
+CREATE INDEX retired_purpose_key_index ON retired_purpose_key (identifier);
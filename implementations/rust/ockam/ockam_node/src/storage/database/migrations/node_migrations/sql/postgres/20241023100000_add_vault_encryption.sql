@@ -0,0 +1,7 @@
+-- The secrets of a vault can be encrypted at rest with a key derived from a passphrase
+-- or with a random key stored in the keychain of the operating system.
+-- Neither the passphrase nor the key are stored in the database
+ALTER TABLE vault ADD COLUMN encryption_method TEXT NULL;          -- 'passphrase' or 'keychain'
+ALTER TABLE vault ADD COLUMN encryption_salt BYTEA NULL;           -- Salt used to derive the key
+ALTER TABLE vault ADD COLUMN encryption_key_check BYTEA NULL;      -- Used to check the key
+ALTER TABLE vault ADD COLUMN encryption_idle_timeout BIGINT NULL;  -- Seconds before re-locking
@@ -0,0 +1,8 @@
+-- This migration creates a table to reference the secret of the Kafka outlets
+-- used to derive the deterministic encryption keys of the record keys.
+-- The secret itself is stored in the vault of the node
+CREATE TABLE kafka_record_keys_secret
+(
+    node_name     TEXT  NOT NULL PRIMARY KEY, -- Name of the node running the Kafka outlet
+    secret_handle BYTEA NOT NULL              -- Handle of the secret in the vault of the node
+);
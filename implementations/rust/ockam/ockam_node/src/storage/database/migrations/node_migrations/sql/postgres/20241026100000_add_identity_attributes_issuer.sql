@@ -0,0 +1,3 @@
+-- The attributes of a credential issued by a delegated issuer are attested by the authority
+-- which authorized that issuer. The delegated issuer is recorded as well
+ALTER TABLE identity_attributes ADD COLUMN issued_by TEXT NULL; -- Identifier of the delegated issuer, if any
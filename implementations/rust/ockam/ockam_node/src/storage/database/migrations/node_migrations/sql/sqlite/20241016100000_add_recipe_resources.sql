@@ -0,0 +1,10 @@
+-- This migration creates a table to store the resources created by the recipes run with `ockam run`
+CREATE TABLE recipe_resource
+(
+    recipe_name   TEXT    NOT NULL, -- Name of the recipe which created the resource
+    resource_kind TEXT    NOT NULL, -- Kind of resource: node, relay, policy, tcp-outlet, tcp-inlet
+    resource_name TEXT    NOT NULL, -- Name of the resource
+    node_name     TEXT    NOT NULL, -- Node hosting the resource, or the node itself for a node
+    position      INTEGER NOT NULL, -- Creation order of the resource for its recipe
+    PRIMARY KEY (recipe_name, resource_kind, resource_name, node_name)
+);
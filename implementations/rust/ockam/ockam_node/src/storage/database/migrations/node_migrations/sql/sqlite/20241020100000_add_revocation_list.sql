@@ -0,0 +1,12 @@
+-- This table stores the revocation lists published by credential issuers.
+-- An authority node stores the list that it issues, other nodes store the lists that they receive,
+-- in order to reject the credentials of revoked subjects before their expiration
+CREATE TABLE revocation_list
+(
+    issuer_identifier TEXT    NOT NULL, -- Identifier of the issuer of the revocation list
+    revocation_list   BLOB    NOT NULL, -- Signed revocation list and the purpose key attestation used to sign it
+    created_at        INTEGER NOT NULL, -- Creation date of the revocation list
+    node_name         TEXT    NOT NULL  -- Node name to isolate the revocation lists that each node has
+);
+
+CREATE UNIQUE INDEX revocation_list_issuer_node_index ON revocation_list (issuer_identifier, node_name);
@@ -0,0 +1,6 @@
+-- A vault can store its signing keys in a PKCS#11 token (SoftHSM, YubiHSM, smartcard, ...).
+-- In that case only key handles are stored in the database.
+-- The PIN of the token is not stored, only the name of the environment variable containing it
+ALTER TABLE vault ADD COLUMN pkcs11_module TEXT NULL;   -- Path of the PKCS#11 module
+ALTER TABLE vault ADD COLUMN pkcs11_slot INTEGER NULL;  -- Slot of the token
+ALTER TABLE vault ADD COLUMN pkcs11_pin_env TEXT NULL;  -- Environment variable containing the user PIN
@@ -0,0 +1,10 @@
+-- This migration creates a table to store the topic policies of the Kafka inlets
+CREATE TABLE kafka_topic_policy
+(
+    node_name     TEXT    NOT NULL, -- Name of the node running the Kafka inlet
+    inlet_address TEXT    NOT NULL, -- Address of the Kafka inlet service
+    topic         TEXT    NOT NULL, -- Topic name, or topic prefix followed by '*'
+    mode          TEXT    NOT NULL, -- 'allow', 'encrypt', 'plaintext' or 'deny'
+    encrypt_keys  INTEGER NOT NULL, -- 1 if the record keys are encrypted as well
+    PRIMARY KEY (node_name, inlet_address, topic, mode)
+);
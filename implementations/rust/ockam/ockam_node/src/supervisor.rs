@@ -0,0 +1,239 @@
+use core::fmt::{Debug, Formatter};
+use core::time::Duration;
+use ockam_core::compat::string::String;
+use ockam_core::compat::sync::Arc;
+use ockam_core::Address;
+
+/// Exponential backoff used to delay the restarts of a supervised worker
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExponentialBackoff {
+    initial_delay: Duration,
+    max_delay: Duration,
+    max_restarts: Option<u32>,
+}
+
+impl Default for ExponentialBackoff {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(100), Duration::from_secs(30))
+    }
+}
+
+impl ExponentialBackoff {
+    /// Create a backoff starting with `initial_delay` and doubling the delay after each
+    /// consecutive failure, without exceeding `max_delay`
+    pub fn new(initial_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            initial_delay,
+            max_delay: max_delay.max(initial_delay),
+            max_restarts: None,
+        }
+    }
+
+    /// Stop the worker after `max_restarts` consecutive restarts
+    pub fn with_max_restarts(mut self, max_restarts: u32) -> Self {
+        self.max_restarts = Some(max_restarts);
+        self
+    }
+
+    /// Maximum number of consecutive restarts, if any
+    pub fn max_restarts(&self) -> Option<u32> {
+        self.max_restarts
+    }
+
+    /// Delay before the restart number `attempt` (starting at 1)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_delay
+            .checked_mul(factor)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay)
+    }
+}
+
+/// Policy used to restart a supervised worker when it fails, i.e. when its initialization
+/// or its `handle_message` function returns an error or panics
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// Never restart the worker: errors are logged and the same worker keeps handling messages
+    #[default]
+    Never,
+    /// Restart the worker after each failure, waiting longer after each consecutive failure.
+    /// The worker is stopped once the maximum number of restarts is reached
+    OnFailure(ExponentialBackoff),
+    /// Restart the worker immediately after each failure, without any limit
+    Always,
+}
+
+/// Lifecycle events of a supervised worker
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SupervisionEvent {
+    /// The worker failed with an error
+    Failed {
+        /// Address of the worker
+        address: Address,
+        /// Description of the error
+        error: String,
+    },
+    /// The worker panicked while handling a message
+    Panicked {
+        /// Address of the worker
+        address: Address,
+    },
+    /// The worker is going to be restarted after a delay
+    Restarting {
+        /// Address of the worker
+        address: Address,
+        /// Number of consecutive restarts, including this one
+        attempt: u32,
+        /// Delay before the restart
+        delay: Duration,
+    },
+    /// The worker has been restarted with a fresh context
+    Restarted {
+        /// Address of the worker
+        address: Address,
+        /// Number of consecutive restarts
+        attempt: u32,
+    },
+    /// The maximum number of restarts has been reached and the worker is stopped
+    GaveUp {
+        /// Address of the worker
+        address: Address,
+        /// Number of consecutive restarts
+        restarts: u32,
+    },
+}
+
+type WorkerFactory<W> = Arc<dyn Fn() -> W + Send + Sync + 'static>;
+type EventListener = Arc<dyn Fn(&SupervisionEvent) + Send + Sync + 'static>;
+
+/// Supervise a worker started with a [`WorkerBuilder`](crate::WorkerBuilder):
+/// when the worker fails, a new instance is created with the `factory` function
+/// and started with a fresh [`Context`](crate::Context), according to the [`RestartPolicy`].
+///
+/// The messages already sent to the worker mailbox are kept across restarts.
+///
+/// Note: panics can only be recovered when the node is built with
+/// `NodeBuilder::no_exit_on_panic` and the `std` feature
+pub struct Supervisor<W> {
+    policy: RestartPolicy,
+    factory: WorkerFactory<W>,
+    listener: Option<EventListener>,
+}
+
+impl<W> Debug for Supervisor<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Supervisor")
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
+
+impl<W> Supervisor<W> {
+    /// Create a supervisor with a restart policy and a function creating new worker instances
+    pub fn new(policy: RestartPolicy, factory: impl Fn() -> W + Send + Sync + 'static) -> Self {
+        Self {
+            policy,
+            factory: Arc::new(factory),
+            listener: None,
+        }
+    }
+
+    /// Call `listener` for each lifecycle event of the supervised worker
+    pub fn with_event_listener(
+        mut self,
+        listener: impl Fn(&SupervisionEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.listener = Some(Arc::new(listener));
+        self
+    }
+
+    /// Restart policy of the supervised worker
+    pub fn policy(&self) -> RestartPolicy {
+        self.policy
+    }
+
+    /// Create a new worker instance
+    pub(crate) fn create_worker(&self) -> W {
+        (self.factory)()
+    }
+
+    /// Log a lifecycle event and forward it to the event listener
+    pub(crate) fn emit(&self, event: SupervisionEvent) {
+        match &event {
+            SupervisionEvent::Failed { address, error } => {
+                warn!("Supervised worker '{}' failed: {}", address, error)
+            }
+            SupervisionEvent::Panicked { address } => {
+                warn!("Supervised worker '{}' panicked", address)
+            }
+            SupervisionEvent::Restarting {
+                address,
+                attempt,
+                delay,
+            } => info!(
+                "Restarting worker '{}' in {:?} (attempt {})",
+                address, delay, attempt
+            ),
+            SupervisionEvent::Restarted { address, attempt } => {
+                info!("Restarted worker '{}' (attempt {})", address, attempt)
+            }
+            SupervisionEvent::GaveUp { address, restarts } => error!(
+                "Worker '{}' is stopped after {} consecutive restarts",
+                address, restarts
+            ),
+        }
+        if let Some(listener) = &self.listener {
+            listener(&event)
+        }
+    }
+
+    /// Return the delay before the next restart, or `None` if the worker must not be restarted
+    pub(crate) fn next_restart_delay(&self, attempt: u32) -> Option<Duration> {
+        match self.policy {
+            RestartPolicy::Never => None,
+            RestartPolicy::Always => Some(Duration::ZERO),
+            RestartPolicy::OnFailure(backoff) => match backoff.max_restarts() {
+                Some(max_restarts) if attempt > max_restarts => None,
+                _ => Some(backoff.delay(attempt)),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exponential_backoff() {
+        let backoff = ExponentialBackoff::new(Duration::from_millis(100), Duration::from_secs(1));
+        assert_eq!(backoff.delay(1), Duration::from_millis(100));
+        assert_eq!(backoff.delay(2), Duration::from_millis(200));
+        assert_eq!(backoff.delay(4), Duration::from_millis(800));
+        assert_eq!(backoff.delay(5), Duration::from_secs(1));
+        assert_eq!(backoff.delay(100), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_restart_policies() {
+        let never = Supervisor::new(RestartPolicy::Never, || ());
+        assert_eq!(never.next_restart_delay(1), None);
+
+        let always = Supervisor::new(RestartPolicy::Always, || ());
+        assert_eq!(always.next_restart_delay(1000), Some(Duration::ZERO));
+
+        let on_failure = Supervisor::new(
+            RestartPolicy::OnFailure(
+                ExponentialBackoff::new(Duration::from_millis(10), Duration::from_secs(1))
+                    .with_max_restarts(2),
+            ),
+            || (),
+        );
+        assert_eq!(
+            on_failure.next_restart_delay(2),
+            Some(Duration::from_millis(20))
+        );
+        assert_eq!(on_failure.next_restart_delay(3), None);
+    }
+}
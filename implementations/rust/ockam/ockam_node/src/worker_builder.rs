@@ -1,6 +1,7 @@
+use crate::channel_types::MailboxConfig;
 use crate::debugger;
 use crate::error::{NodeError, NodeReason};
-use crate::{relay::WorkerRelay, Context, NodeMessage};
+use crate::{relay::WorkerRelay, Context, NodeMessage, Supervisor};
 use alloc::string::String;
 use ockam_core::compat::{sync::Arc, vec::Vec};
 use ockam_core::{
@@ -42,6 +43,8 @@ where
             worker: self.worker,
             address: address.into(),
             metadata: None,
+            mailbox_config: None,
+            supervisor: None,
         }
     }
 
@@ -51,6 +54,8 @@ where
             mailboxes,
             worker: self.worker,
             metadata_list: vec![],
+            mailbox_config: None,
+            supervisor: None,
         }
     }
 }
@@ -62,6 +67,8 @@ where
     mailboxes: Mailboxes,
     worker: W,
     metadata_list: Vec<AddressAndMetadata>,
+    mailbox_config: Option<MailboxConfig>,
+    supervisor: Option<Supervisor<W>>,
 }
 
 impl<W> WorkerBuilderMultipleAddresses<W>
@@ -124,9 +131,31 @@ where
         self
     }
 
+    /// Set the capacity and overflow policy of the [`Worker`] mailbox.
+    /// The default configuration is the one of the node
+    pub fn with_mailbox_config(mut self, mailbox_config: MailboxConfig) -> Self {
+        self.mailbox_config = Some(mailbox_config);
+        self
+    }
+
+    /// Supervise the [`Worker`] so that it is restarted according to the [`Supervisor`]
+    /// restart policy when it fails
+    pub fn with_supervisor(mut self, supervisor: Supervisor<W>) -> Self {
+        self.supervisor = Some(supervisor);
+        self
+    }
+
     /// Consume this builder and start a new Ockam [`Worker`] from the given context
     pub async fn start(self, context: &Context) -> Result<()> {
-        start(context, self.mailboxes, self.worker, self.metadata_list).await
+        start(
+            context,
+            self.mailboxes,
+            self.worker,
+            self.metadata_list,
+            self.mailbox_config,
+            self.supervisor,
+        )
+        .await
     }
 }
 
@@ -139,6 +168,8 @@ where
     address: Address,
     worker: W,
     metadata: Option<AddressAndMetadata>,
+    mailbox_config: Option<MailboxConfig>,
+    supervisor: Option<Supervisor<W>>,
 }
 
 impl<W> WorkerBuilderOneAddress<W>
@@ -197,6 +228,8 @@ where
             Mailboxes::main(self.address, self.incoming_ac, self.outgoing_ac),
             self.worker,
             self.metadata.map(|m| vec![m]).unwrap_or_default(),
+            self.mailbox_config,
+            self.supervisor,
         )
         .await
     }
@@ -241,6 +274,20 @@ where
         self.outgoing_ac = outgoing_access_control.clone();
         self
     }
+
+    /// Set the capacity and overflow policy of the [`Worker`] mailbox.
+    /// The default configuration is the one of the node
+    pub fn with_mailbox_config(mut self, mailbox_config: MailboxConfig) -> Self {
+        self.mailbox_config = Some(mailbox_config);
+        self
+    }
+
+    /// Supervise the [`Worker`] so that it is restarted according to the [`Supervisor`]
+    /// restart policy when it fails
+    pub fn with_supervisor(mut self, supervisor: Supervisor<W>) -> Self {
+        self.supervisor = Some(supervisor);
+        self
+    }
 }
 
 /// Consume this builder and start a new Ockam [`Worker`] from the given context
@@ -249,6 +296,8 @@ async fn start<W>(
     mailboxes: Mailboxes,
     worker: W,
     metadata: Vec<AddressAndMetadata>,
+    mailbox_config: Option<MailboxConfig>,
+    supervisor: Option<Supervisor<W>>,
 ) -> Result<()>
 where
     W: Worker<Context = Context>,
@@ -263,13 +312,23 @@ where
     let addresses = mailboxes.addresses();
 
     // Pass it to the context
-    let (ctx, sender, ctrl_rx) = context.copy_with_mailboxes(mailboxes);
+    let (ctx, sender, ctrl_rx) = context.copy_with_mailboxes(
+        mailboxes,
+        mailbox_config.unwrap_or(context.default_mailbox_config()),
+    );
 
     debugger::log_inherit_context("WORKER", context, &ctx);
 
     // Send start request to router
-    let (msg, mut rx) =
-        NodeMessage::start_worker(addresses, sender, false, context.mailbox_count(), metadata);
+    let (msg, mut rx) = NodeMessage::start_worker(
+        addresses,
+        sender,
+        false,
+        ctx.mailbox_count(),
+        ctx.metrics_recorder(),
+        metadata,
+        ctx.namespace().map(Into::into),
+    );
     context
         .sender()
         .send(msg)
@@ -281,8 +340,13 @@ where
         .await
         .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??;
 
+    #[cfg(feature = "std")]
+    context.publish_event(crate::NodeEvent::WorkerStarted {
+        address: ctx.address(),
+    });
+
     // Then initialise the worker message relay
-    WorkerRelay::init(context.runtime(), worker, ctx, ctrl_rx);
+    WorkerRelay::init(context.executor(), worker, ctx, ctrl_rx, supervisor);
 
     Ok(())
 }
@@ -0,0 +1,195 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Address;
+
+/// Upper bounds of the processing latency histogram buckets.
+/// The last bucket of a histogram contains all the latencies above the last bound
+const LATENCY_BUCKETS_BOUNDS: [Duration; 6] = [
+    Duration::from_micros(100),
+    Duration::from_millis(1),
+    Duration::from_millis(10),
+    Duration::from_millis(100),
+    Duration::from_secs(1),
+    Duration::from_secs(10),
+];
+
+const LATENCY_BUCKETS_COUNT: usize = LATENCY_BUCKETS_BOUNDS.len() + 1;
+
+/// Counters updated by a worker relay each time a message is handled.
+///
+/// They are shared between the worker context and the router record of the worker
+#[derive(Debug, Default)]
+pub struct WorkerMetricsRecorder {
+    handled: AtomicUsize,
+    errors: AtomicUsize,
+    total_processing_time_us: AtomicUsize,
+    max_processing_time_us: AtomicUsize,
+    latency_buckets: [AtomicUsize; LATENCY_BUCKETS_COUNT],
+}
+
+impl WorkerMetricsRecorder {
+    /// Record the handling of a message.
+    /// The processing time is only available with the `std` feature
+    pub(crate) fn record(&self, processing_time: Option<Duration>, success: bool) {
+        self.handled.fetch_add(1, Ordering::Relaxed);
+        if !success {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        if let Some(processing_time) = processing_time {
+            let us = processing_time.as_micros().min(usize::MAX as u128) as usize;
+            self.total_processing_time_us
+                .fetch_add(us, Ordering::Relaxed);
+            self.max_processing_time_us.fetch_max(us, Ordering::Relaxed);
+            let bucket = LATENCY_BUCKETS_BOUNDS
+                .iter()
+                .position(|bound| processing_time <= *bound)
+                .unwrap_or(LATENCY_BUCKETS_COUNT - 1);
+            self.latency_buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Return a snapshot of the metrics of a worker
+    pub(crate) fn snapshot(
+        &self,
+        address: Address,
+        is_processor: bool,
+        queue_depth: usize,
+        messages_received: usize,
+    ) -> WorkerMetrics {
+        let buckets = self
+            .latency_buckets
+            .iter()
+            .enumerate()
+            .map(|(i, count)| LatencyBucket {
+                upper_bound: LATENCY_BUCKETS_BOUNDS.get(i).copied(),
+                count: count.load(Ordering::Relaxed) as u64,
+            })
+            .collect();
+        WorkerMetrics {
+            address,
+            is_processor,
+            queue_depth: queue_depth as u64,
+            messages_received: messages_received as u64,
+            messages_handled: self.handled.load(Ordering::Relaxed) as u64,
+            errors: self.errors.load(Ordering::Relaxed) as u64,
+            total_processing_time: Duration::from_micros(
+                self.total_processing_time_us.load(Ordering::Relaxed) as u64,
+            ),
+            max_processing_time: Duration::from_micros(
+                self.max_processing_time_us.load(Ordering::Relaxed) as u64,
+            ),
+            latency: LatencyHistogram { buckets },
+        }
+    }
+}
+
+/// Snapshot of the runtime metrics of a worker or processor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerMetrics {
+    /// Primary address of the worker
+    pub address: Address,
+    /// True if this is a processor rather than a worker
+    pub is_processor: bool,
+    /// Number of messages waiting in the worker mailbox
+    pub queue_depth: u64,
+    /// Number of messages sent to the worker since it was started
+    pub messages_received: u64,
+    /// Number of messages handled by the worker since it was started
+    pub messages_handled: u64,
+    /// Number of messages for which the worker returned an error
+    pub errors: u64,
+    /// Total time spent handling messages
+    pub total_processing_time: Duration,
+    /// Longest time spent handling a single message
+    pub max_processing_time: Duration,
+    /// Distribution of the time spent handling messages
+    pub latency: LatencyHistogram,
+}
+
+impl WorkerMetrics {
+    /// Average time spent handling a message
+    pub fn mean_processing_time(&self) -> Option<Duration> {
+        let count = self.latency.count();
+        if count == 0 {
+            return None;
+        }
+        Some(Duration::from_micros(
+            self.total_processing_time.as_micros() as u64 / count,
+        ))
+    }
+}
+
+/// Histogram of message processing latencies
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    /// Buckets sorted by increasing upper bound
+    pub buckets: Vec<LatencyBucket>,
+}
+
+/// Number of messages handled within a given time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyBucket {
+    /// Maximum processing time of the messages of this bucket,
+    /// `None` for the last bucket which has no upper bound
+    pub upper_bound: Option<Duration>,
+    /// Number of messages in this bucket
+    pub count: u64,
+}
+
+impl LatencyHistogram {
+    /// Total number of messages in the histogram
+    pub fn count(&self) -> u64 {
+        self.buckets.iter().map(|b| b.count).sum()
+    }
+
+    /// Return the upper bound of the bucket containing the given percentile (between 0 and 100)
+    /// or `None` if the histogram is empty or if the percentile falls into the unbounded bucket
+    pub fn percentile_upper_bound(&self, percentile: f64) -> Option<Duration> {
+        let count = self.count();
+        if count == 0 {
+            return None;
+        }
+        // rank of the percentile, rounded up
+        let exact_rank = percentile.clamp(0.0, 100.0) * count as f64 / 100.0;
+        let rank = exact_rank as u64 + u64::from((exact_rank as u64 as f64) < exact_rank);
+        let mut seen = 0;
+        for bucket in &self.buckets {
+            seen += bucket.count;
+            if seen >= rank.max(1) {
+                return bucket.upper_bound;
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_metrics() {
+        let recorder = WorkerMetricsRecorder::default();
+        recorder.record(Some(Duration::from_micros(50)), true);
+        recorder.record(Some(Duration::from_millis(5)), true);
+        recorder.record(Some(Duration::from_millis(5)), false);
+        recorder.record(Some(Duration::from_secs(20)), true);
+
+        let metrics = recorder.snapshot("worker".into(), false, 2, 6);
+        assert_eq!(metrics.queue_depth, 2);
+        assert_eq!(metrics.messages_received, 6);
+        assert_eq!(metrics.messages_handled, 4);
+        assert_eq!(metrics.errors, 1);
+        assert_eq!(metrics.max_processing_time, Duration::from_secs(20));
+        assert_eq!(metrics.latency.count(), 4);
+        assert_eq!(metrics.latency.buckets[0].count, 1);
+        assert_eq!(metrics.latency.buckets[2].count, 2);
+        assert_eq!(metrics.latency.buckets[6].count, 1);
+        assert_eq!(
+            metrics.latency.percentile_upper_bound(50.0),
+            Some(Duration::from_millis(10))
+        );
+        assert_eq!(metrics.latency.percentile_upper_bound(99.0), None);
+    }
+}
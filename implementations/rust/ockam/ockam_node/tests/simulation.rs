@@ -0,0 +1,84 @@
+#![cfg(feature = "simulation")]
+
+use core::time::Duration;
+use ockam_core::{route, AllowAll, Result};
+use ockam_node::{LinkFault, NodeBuilder, Simulation};
+
+#[allow(non_snake_case)]
+#[test]
+fn simulation__messages_are_delivered_step_by_step() {
+    let simulation = Simulation::new().intercept_only(["sender", "receiver"]);
+    let (ctx, mut executor) = NodeBuilder::new()
+        .with_simulation(simulation.clone())
+        .build();
+    executor
+        .execute(async move {
+            let res: Result<()> = async {
+                let sender = ctx.new_detached("sender", AllowAll, AllowAll).await?;
+                let mut receiver = ctx.new_detached("receiver", AllowAll, AllowAll).await?;
+
+                sender.send(route!["receiver"], "first".to_string()).await?;
+                sender
+                    .send(route!["receiver"], "second".to_string())
+                    .await?;
+                simulation
+                    .wait_for_pending(2, Duration::from_secs(1))
+                    .await?;
+                assert!(simulation.delivered().is_empty());
+
+                // deliver the messages in the reverse order
+                let pending = simulation.pending();
+                simulation.deliver(pending[1].id).await?;
+                assert_eq!(receiver.receive::<String>().await?.into_body()?, "second");
+                simulation.step().await?;
+                assert_eq!(receiver.receive::<String>().await?.into_body()?, "first");
+                assert_eq!(simulation.delivered().len(), 2);
+                Ok(())
+            }
+            .await;
+            ctx.stop().await.unwrap();
+            res
+        })
+        .unwrap()
+        .unwrap();
+}
+
+#[allow(non_snake_case)]
+#[test]
+fn simulation__faults_delay_and_drop_messages() {
+    let simulation = Simulation::new().intercept_only(["sender", "receiver"]);
+    let (ctx, mut executor) = NodeBuilder::new()
+        .with_simulation(simulation.clone())
+        .build();
+    executor
+        .execute(async move {
+            let res: Result<()> = async {
+                let sender = ctx.new_detached("sender", AllowAll, AllowAll).await?;
+                let mut receiver = ctx.new_detached("receiver", AllowAll, AllowAll).await?;
+
+                simulation.inject_fault("sender", "receiver", LinkFault::Drop);
+                sender
+                    .send(route!["receiver"], "dropped".to_string())
+                    .await?;
+                assert_eq!(simulation.dropped().len(), 1);
+                assert!(simulation.pending().is_empty());
+
+                simulation.inject_fault("sender", "receiver", LinkFault::Delay(2));
+                sender
+                    .send(route!["receiver"], "delayed".to_string())
+                    .await?;
+                simulation
+                    .wait_for_pending(1, Duration::from_secs(1))
+                    .await?;
+                assert!(simulation.step().await?.is_none());
+                assert!(simulation.step().await?.is_some());
+                assert_eq!(receiver.receive::<String>().await?.into_body()?, "delayed");
+                Ok(())
+            }
+            .await;
+            ctx.stop().await.unwrap();
+            res
+        })
+        .unwrap()
+        .unwrap();
+}
@@ -3,16 +3,24 @@ use core::time::Duration;
 use ockam_core::compat::{
     boxed::Box,
     string::{String, ToString},
-    sync::Arc,
+    sync::{Arc, Mutex},
+    vec::Vec,
 };
 use ockam_core::errcode::{Kind, Origin};
-use ockam_core::{async_trait, Address, AllowAll, Any, Decodable, DenyAll, Message, LOCAL};
+use ockam_core::{
+    async_trait, Address, AllowAll, Any, Decodable, DenyAll, Mailbox, Mailboxes, Message,
+    MessagePriority, LOCAL,
+};
 use ockam_core::{route, Processor, Result, Routed, Worker};
 use ockam_node::compat::futures::FutureExt;
-use ockam_node::{Context, MessageReceiveOptions, NodeBuilder};
+use ockam_node::{
+    BlockingTask, CancellationToken, Context, ExecutorTask, ExponentialBackoff,
+    MessageReceiveOptions, NodeBuilder, NodeExecutor, ProcessorBuilder, RestartPolicy, Schedule,
+    ShutdownPhase, SupervisionEvent, Supervisor, TokioExecutor, WorkerBuilder,
+};
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicI8, AtomicU32};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tokio::time::sleep;
 use tracing::info;
 
@@ -66,6 +74,92 @@ fn start_and_shutdown_node__many_iterations__should_not_fail() {
             .unwrap()
     }
 }
+
+/// Executor delegating to Tokio and counting the spawned tasks
+struct CountingExecutor {
+    inner: TokioExecutor,
+    spawned: Arc<AtomicU32>,
+}
+
+impl NodeExecutor for CountingExecutor {
+    fn spawn(&self, task: ExecutorTask) {
+        self.spawned.fetch_add(1, Ordering::Relaxed);
+        self.inner.spawn(task)
+    }
+
+    fn block_on(&self, task: BlockingTask<'_>) {
+        self.inner.block_on(task)
+    }
+}
+
+#[allow(non_snake_case)]
+#[test]
+fn custom_executor__start_worker__should_spawn_tasks_on_executor() {
+    let rt = Arc::new(tokio::runtime::Runtime::new().unwrap());
+    let spawned = Arc::new(AtomicU32::new(0));
+    let (ctx, mut executor) = NodeBuilder::new()
+        .no_logging()
+        .with_runtime(rt.clone())
+        .with_executor(Arc::new(CountingExecutor {
+            inner: TokioExecutor::new(rt.handle().clone()),
+            spawned: spawned.clone(),
+        }))
+        .build();
+
+    executor
+        .execute(async move {
+            let res = std::panic::AssertUnwindSafe(async {
+                let worker = SimpleWorker {
+                    initialize_was_called: Arc::new(AtomicBool::new(false)),
+                    shutdown_was_called: Arc::new(AtomicBool::new(false)),
+                };
+                ctx.start_worker("simple_worker", worker).await?;
+                let msg: String = ctx
+                    .send_and_receive(route!["simple_worker"], "Hello".to_string())
+                    .await?;
+                assert_eq!(msg, "Hello");
+                Result::<()>::Ok(())
+            })
+            .catch_unwind()
+            .await;
+
+            ctx.stop().await?;
+
+            res.unwrap()
+        })
+        .unwrap()
+        .unwrap();
+
+    // the user code, the worker relay and the detached contexts are spawned on the executor
+    assert!(spawned.load(Ordering::Relaxed) >= 2);
+}
+
+#[cfg(feature = "smol")]
+#[allow(non_snake_case)]
+#[test]
+fn smol_executor__send_and_receive__should_not_need_a_tokio_reactor() {
+    let executor = ockam_node::SmolExecutor::new(2).unwrap();
+    let (ctx, mut node) = NodeBuilder::new()
+        .no_logging()
+        .with_executor(Arc::new(executor))
+        .build();
+
+    node.execute(async move {
+        let worker = SimpleWorker {
+            initialize_was_called: Arc::new(AtomicBool::new(false)),
+            shutdown_was_called: Arc::new(AtomicBool::new(false)),
+        };
+        ctx.start_worker("simple_worker", worker).await?;
+        let msg: String = ctx
+            .send_and_receive(route!["simple_worker"], "Hello".to_string())
+            .await?;
+        assert_eq!(msg, "Hello");
+        ctx.stop().await
+    })
+    .unwrap()
+    .unwrap();
+}
+
 struct SimpleWorker {
     initialize_was_called: Arc<AtomicBool>,
     shutdown_was_called: Arc<AtomicBool>,
@@ -608,6 +702,59 @@ async fn use_context_send_and_receive(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+/// Worker replying with its own primary address
+struct InstanceWorker;
+
+#[async_trait]
+impl Worker for InstanceWorker {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Self::Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        ctx.send(msg.return_route(), ctx.address().to_string())
+            .await
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn worker_pool__send_messages__should_dispatch_round_robin(ctx: &mut Context) -> Result<()> {
+    ctx.start_worker_pool("pool", 3, || InstanceWorker).await?;
+
+    let mut replies: Vec<String> = Vec::new();
+    for _ in 0..6 {
+        let reply: String = ctx
+            .send_and_receive(route!["pool"], "Hello".to_string())
+            .await?;
+        replies.push(reply);
+    }
+    for instance in ["0#pool.0", "0#pool.1", "0#pool.2"] {
+        assert_eq!(replies.iter().filter(|r| r.as_str() == instance).count(), 2);
+    }
+
+    // the pool address can not be used by another worker
+    assert!(ctx.start_worker("pool", InstanceWorker).await.is_err());
+
+    // stopping the pool stops all its instances
+    ctx.stop_worker("pool").await?;
+    sleep(Duration::from_millis(100)).await;
+    let workers = ctx.list_workers().await?;
+    assert!(!workers.iter().any(|w| w.address().starts_with("pool")));
+
+    // the address can be reused once the pool is stopped
+    ctx.start_worker("pool", InstanceWorker).await?;
+    let reply: String = ctx
+        .send_and_receive(route!["pool"], "Hello".to_string())
+        .await?;
+    assert_eq!(reply, "0#pool");
+
+    ctx.stop().await
+}
+
 struct DummyWorker;
 
 #[async_trait]
@@ -643,6 +790,39 @@ async fn starting_worker_with_dup_address_should_fail(ctx: &mut Context) -> Resu
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn list_workers_info(ctx: &mut Context) -> Result<()> {
+    ctx.start_worker("info_worker", DummyWorker).await?;
+    ctx.start_processor("info_processor", DummyProcessor)
+        .await?;
+
+    let workers = ctx.list_workers_info().await?;
+    let worker = workers
+        .iter()
+        .find(|w| w.primary_address == "info_worker".into())
+        .unwrap();
+    assert!(!worker.is_processor);
+    assert_eq!(worker.addresses, vec!["info_worker".into()]);
+    assert_eq!(worker.mailbox_count, 0);
+    assert_eq!(worker.messages_count, 0);
+
+    ctx.send("info_worker", "hello".to_string()).await?;
+    let workers = ctx.list_workers_info().await?;
+    let worker = workers
+        .iter()
+        .find(|w| w.primary_address == "info_worker".into())
+        .unwrap();
+    assert_eq!(worker.messages_count, 1);
+
+    let processor = workers
+        .iter()
+        .find(|w| w.primary_address == "info_processor".into())
+        .unwrap();
+    assert!(processor.is_processor);
+
+    Ok(())
+}
+
 struct CountingErrorWorker {
     pub(crate) counter: Arc<AtomicI8>,
 }
@@ -691,3 +871,337 @@ async fn message_handle__error_during_handling__keep_worker_running(
 
     Ok(())
 }
+
+/// Worker failing when it receives the "fail" message, and replying with its instance number otherwise
+struct SupervisedWorker {
+    instance: u32,
+}
+
+#[async_trait]
+impl Worker for SupervisedWorker {
+    type Context = Context;
+    type Message = String;
+
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Self::Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        let return_route = msg.return_route();
+        if msg.into_body()? == "fail" {
+            return Err(ockam_core::Error::new(Origin::Core, Kind::Internal, "test"));
+        }
+        ctx.send(return_route, self.instance.to_string()).await
+    }
+}
+
+fn supervised_worker_factory() -> impl Fn() -> SupervisedWorker + Send + Sync + 'static {
+    let instances = Arc::new(AtomicU32::new(1));
+    move || SupervisedWorker {
+        instance: instances.fetch_add(1, Ordering::Relaxed) + 1,
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn supervised_worker__failure__should_restart(ctx: &mut Context) -> Result<()> {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let events_clone = events.clone();
+    let supervisor = Supervisor::new(RestartPolicy::Always, supervised_worker_factory())
+        .with_event_listener(move |event| events_clone.lock().unwrap().push(event.clone()));
+
+    WorkerBuilder::new(SupervisedWorker { instance: 1 })
+        .with_address("supervised")
+        .with_supervisor(supervisor)
+        .start(ctx)
+        .await?;
+
+    let reply: String = ctx
+        .send_and_receive(route!["supervised"], "ping".to_string())
+        .await?;
+    assert_eq!(reply, "1");
+
+    ctx.send(route!["supervised"], "fail".to_string()).await?;
+    let reply: String = ctx
+        .send_and_receive(route!["supervised"], "ping".to_string())
+        .await?;
+    assert_eq!(reply, "2");
+
+    let events = events.lock().unwrap().clone();
+    assert!(matches!(events[0], SupervisionEvent::Failed { .. }));
+    assert!(matches!(
+        events[1],
+        SupervisionEvent::Restarting { attempt: 1, .. }
+    ));
+    assert!(matches!(
+        events[2],
+        SupervisionEvent::Restarted { attempt: 1, .. }
+    ));
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn supervised_worker__too_many_failures__should_stop(ctx: &mut Context) -> Result<()> {
+    let backoff = ExponentialBackoff::new(Duration::from_millis(10), Duration::from_millis(100))
+        .with_max_restarts(1);
+    let supervisor = Supervisor::new(
+        RestartPolicy::OnFailure(backoff),
+        supervised_worker_factory(),
+    );
+    let address = Address::from_string("supervised_with_backoff");
+
+    WorkerBuilder::new(SupervisedWorker { instance: 1 })
+        .with_address(address.clone())
+        .with_supervisor(supervisor)
+        .start(ctx)
+        .await?;
+
+    ctx.send(route![address.clone()], "fail".to_string())
+        .await?;
+    ctx.send(route![address.clone()], "fail".to_string())
+        .await?;
+    ctx.sleep(Duration::from_millis(200)).await;
+
+    assert!(!ctx.list_workers().await?.contains(&address));
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn worker_metrics__handled_messages__should_be_counted(ctx: &mut Context) -> Result<()> {
+    let address = Address::from_string("measured");
+    ctx.start_worker(address.clone(), SupervisedWorker { instance: 1 })
+        .await?;
+
+    let _: String = ctx
+        .send_and_receive(route![address.clone()], "ping".to_string())
+        .await?;
+    ctx.send(route![address.clone()], "fail".to_string())
+        .await?;
+    let _: String = ctx
+        .send_and_receive(route![address.clone()], "ping".to_string())
+        .await?;
+    // the metrics are recorded once the reply has been sent
+    ctx.sleep(Duration::from_millis(100)).await;
+
+    let metrics = ctx.worker_metrics(&address).await?.unwrap();
+    assert!(!metrics.is_processor);
+    assert_eq!(metrics.messages_received, 3);
+    assert_eq!(metrics.messages_handled, 3);
+    assert_eq!(metrics.errors, 1);
+    assert_eq!(metrics.queue_depth, 0);
+    assert_eq!(metrics.latency.count(), 3);
+    assert!(metrics.mean_processing_time().is_some());
+    Ok(())
+}
+
+/// Worker recording the messages it receives, slowly
+struct RecordingWorker {
+    received: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl Worker for RecordingWorker {
+    type Context = Context;
+    type Message = String;
+
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Self::Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        self.received.lock().unwrap().push(msg.into_body()?);
+        ctx.sleep(Duration::from_millis(50)).await;
+        Ok(())
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn high_priority_mailbox__messages__should_be_handled_first(ctx: &mut Context) -> Result<()> {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let mailboxes = Mailboxes::new(
+        Mailbox::new("data", Arc::new(AllowAll), Arc::new(AllowAll)),
+        vec![
+            Mailbox::new("control", Arc::new(AllowAll), Arc::new(AllowAll))
+                .with_priority(MessagePriority::High),
+        ],
+    );
+    WorkerBuilder::new(RecordingWorker {
+        received: received.clone(),
+    })
+    .with_mailboxes(mailboxes)
+    .start(ctx)
+    .await?;
+
+    // the first message keeps the worker busy while the other ones are queued
+    ctx.send(route!["data"], "data 1".to_string()).await?;
+    ctx.sleep(Duration::from_millis(10)).await;
+    ctx.send(route!["data"], "data 2".to_string()).await?;
+    ctx.send(route!["data"], "data 3".to_string()).await?;
+    ctx.send(route!["control"], "control".to_string()).await?;
+    ctx.sleep(Duration::from_millis(300)).await;
+
+    assert_eq!(
+        received.lock().unwrap().clone(),
+        vec!["data 1", "control", "data 2", "data 3"]
+    );
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn receive_with_deadline__deadline_or_cancellation__should_return_from_call(
+    ctx: &mut Context,
+) -> Result<()> {
+    let mut child_ctx = ctx.new_detached("random", AllowAll, AllowAll).await?;
+    let token = CancellationToken::new();
+
+    // the deadline is reached
+    let res = child_ctx
+        .receive_with_deadline::<String>(Instant::now() + Duration::from_millis(100), &token)
+        .await;
+    assert_eq!(res.err().map(|e| e.code().kind), Some(Kind::Timeout));
+
+    // the token is cancelled before the deadline
+    let cancel = token.clone();
+    tokio::spawn(async move {
+        sleep(Duration::from_millis(100)).await;
+        cancel.cancel();
+    });
+    let res = child_ctx
+        .receive_with_deadline::<String>(Instant::now() + Duration::from_secs(10), &token)
+        .await;
+    assert_eq!(res.err().map(|e| e.code().kind), Some(Kind::Cancelled));
+
+    // a message is received before the deadline
+    ctx.send(route!["random"], "hello".to_string()).await?;
+    let res = child_ctx
+        .receive_with_deadline::<String>(
+            Instant::now() + Duration::from_secs(1),
+            &CancellationToken::new(),
+        )
+        .await?;
+    assert_eq!(res.into_body()?, "hello");
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn shutdown_hooks__graceful_stop__should_run_by_phase_before_workers_stop(
+    ctx: &mut Context,
+) -> Result<()> {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    WorkerBuilder::new(RecordingWorker {
+        received: received.clone(),
+    })
+    .with_address("buffer")
+    .start(ctx)
+    .await?;
+
+    let phases = Arc::new(Mutex::new(Vec::new()));
+    let sender = ctx.new_detached("flusher", AllowAll, AllowAll).await?;
+    let drain_phases = phases.clone();
+    ctx.register_shutdown_hook(ShutdownPhase::Drain, "flush", move || async move {
+        drain_phases.lock().unwrap().push(ShutdownPhase::Drain);
+        // workers are still running and can receive messages
+        sender.send(route!["buffer"], "flushed".to_string()).await
+    });
+    let stop_accepting_phases = phases.clone();
+    ctx.register_shutdown_hook(ShutdownPhase::StopAccepting, "stop", move || async move {
+        stop_accepting_phases
+            .lock()
+            .unwrap()
+            .push(ShutdownPhase::StopAccepting);
+        Ok(())
+    });
+
+    ctx.stop().await?;
+    sleep(Duration::from_millis(100)).await;
+
+    assert_eq!(
+        phases.lock().unwrap().clone(),
+        vec![ShutdownPhase::StopAccepting, ShutdownPhase::Drain]
+    );
+    assert_eq!(received.lock().unwrap().clone(), vec!["flushed"]);
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn scheduled_processor__interval__should_process_periodically(
+    ctx: &mut Context,
+) -> Result<()> {
+    let initialize_was_called = Arc::new(AtomicBool::new(false));
+    let shutdown_was_called = Arc::new(AtomicBool::new(false));
+    let run_called_count = Arc::new(AtomicI8::new(0));
+
+    let processor = CountingProcessor {
+        initialize_was_called: initialize_was_called.clone(),
+        shutdown_was_called: shutdown_was_called.clone(),
+        run_called_count: run_called_count.clone(),
+    };
+
+    ProcessorBuilder::new(processor)
+        .with_address("scheduled_processor")
+        .with_schedule(Schedule::interval(Duration::from_millis(200)))
+        .start(ctx)
+        .await?;
+
+    // the processor runs immediately and then every 200ms
+    sleep(Duration::from_millis(300)).await;
+    assert_eq!(2, run_called_count.load(Ordering::Relaxed));
+    assert!(!shutdown_was_called.load(Ordering::Relaxed));
+
+    sleep(Duration::from_millis(800)).await;
+    assert_eq!(5, run_called_count.load(Ordering::Relaxed));
+    assert!(shutdown_was_called.load(Ordering::Relaxed));
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn namespaces__workers_in_different_namespaces__cannot_address_each_other(
+    ctx: &mut Context,
+) -> Result<()> {
+    let tenant1 = ctx
+        .new_detached_in_namespace("tenant1", "tenant1_app", AllowAll, AllowAll)
+        .await?;
+    let tenant2 = ctx
+        .new_detached_in_namespace("tenant2", "tenant2_app", AllowAll, AllowAll)
+        .await?;
+    assert_eq!(tenant1.namespace(), Some("tenant1"));
+
+    WorkerBuilder::new(InstanceWorker)
+        .with_address("tenant1_echoer")
+        .start(&tenant1)
+        .await?;
+    WorkerBuilder::new(InstanceWorker)
+        .with_address("shared_echoer")
+        .start(ctx)
+        .await?;
+
+    // a worker in another namespace is not found
+    let res = tenant2
+        .send(route!["tenant1_echoer"], "Hello".to_string())
+        .await;
+    assert_eq!(res.unwrap_err().code().kind, Kind::NotFound);
+
+    // workers of the same namespace and workers without a namespace are reachable
+    let msg: String = tenant1
+        .send_and_receive(route!["tenant1_echoer"], "Hello".to_string())
+        .await?;
+    assert_eq!(msg, "0#tenant1_echoer");
+    let msg: String = tenant2
+        .send_and_receive(route!["shared_echoer"], "Hello".to_string())
+        .await?;
+    assert_eq!(msg, "0#shared_echoer");
+
+    // a context cannot escape its namespace
+    assert!(tenant1
+        .new_detached_in_namespace("tenant2", "escaped", AllowAll, AllowAll)
+        .await
+        .is_err());
+    Ok(())
+}
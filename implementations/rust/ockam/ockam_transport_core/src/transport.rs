@@ -1,6 +1,6 @@
 use crate::TransportError;
 use ockam_core::compat::{boxed::Box, vec::Vec};
-use ockam_core::{async_trait, Address, Encodable, Result, TransportMessage, TransportType};
+use ockam_core::{async_trait, Address, Result, TransportMessage, TransportType};
 
 /// Generic representation of a Transport
 /// At minimum, a Transport must be able
@@ -28,24 +28,16 @@ pub trait Transport: Send + Sync + 'static {
 /// The length-prefix is encoded as a big-endian 16-bit unsigned
 /// integer.
 pub fn encode_transport_message(msg: TransportMessage) -> Result<Vec<u8>> {
-    let mut msg_buf = msg.encode().map_err(|_| TransportError::SendBadMessage)?;
-
-    if msg_buf.len() > MAXIMUM_MESSAGE_LENGTH {
+    let msg_len = msg.encoded_size();
+    if msg_len > MAXIMUM_MESSAGE_LENGTH {
         Err(TransportError::Capacity)?;
     }
 
-    // Create a buffer that includes the message length in big endian
-    let mut len = (msg_buf.len() as u16).to_be_bytes().to_vec();
-
-    // Fun fact: reversing a vector in place, appending the length,
-    // and then reversing it again is faster for large message sizes
-    // than adding the large chunk of data.
-    //
-    // https://play.rust-lang.org/?version=stable&mode=release&edition=2018&gist=8669a640004ac85c7be38b19e3e73dcb
-    msg_buf.reverse();
-    len.reverse();
-    msg_buf.append(&mut len);
-    msg_buf.reverse();
+    // Create a buffer that starts with the message length in big endian
+    // and encode the message right after it, without any intermediate copy
+    let mut msg_buf = Vec::with_capacity(2 + msg_len);
+    msg_buf.extend_from_slice(&(msg_len as u16).to_be_bytes());
+    msg.encode_to(&mut msg_buf);
 
     Ok(msg_buf)
 }
@@ -61,4 +53,16 @@ mod test {
         let result = encode_transport_message(msg);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn prepare_message_should_prepend_the_message_length() {
+        let msg = TransportMessage::latest(route!["onward"], route!["return"], vec![1; 1000]);
+        let result = encode_transport_message(msg.clone()).unwrap();
+        let len = u16::from_be_bytes([result[0], result[1]]) as usize;
+        assert_eq!(len, result.len() - 2);
+        assert_eq!(
+            TransportMessage::decode_message(result[2..].to_vec()).unwrap(),
+            msg
+        );
+    }
 }
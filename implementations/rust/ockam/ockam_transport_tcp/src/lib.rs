@@ -27,7 +27,13 @@ mod workers;
 pub(crate) use workers::*;
 
 pub use options::{TcpConnectionOptions, TcpListenerOptions};
-pub use portal::{PortalInternalMessage, PortalMessage, MAX_PAYLOAD_SIZE};
+pub use portal::{
+    HttpRequestHead, HttpRequestPart, HttpRequestReader, HttpResponsePart, HttpResponseReader,
+    HttpRewrite, PortalCompression, PortalInternalMessage, PortalMessage, PortalPeerIdentifier,
+    PortalTlsCertificate, PortalTlsVerification, TcpEgressAllowList, TcpEgressRule, TcpInletLimits,
+    TcpInletLoadBalancing, TcpPortalRateLimit, TcpPortalStats, MAX_PAYLOAD_SIZE,
+    PROXY_PROTOCOL_OCKAM_IDENTIFIER_TLV,
+};
 pub use registry::*;
 pub use transport::*;
 
@@ -1,13 +1,27 @@
 use crate::workers::Addresses;
+use crate::{TcpProxy, TcpSocketOptions};
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
-use ockam_core::{Address, OutgoingAccessControl};
+use ockam_core::{Address, OutgoingAccessControl, Result};
 
 /// Trust Options for a TCP connection
 #[derive(Debug)]
 pub struct TcpConnectionOptions {
     pub(super) consumer: Vec<FlowControlId>,
     pub(crate) flow_control_id: FlowControlId,
+    pub(crate) socket_options: TcpSocketOptions,
+    proxy: TcpProxyOption,
+}
+
+/// Proxy used by a TCP connection
+#[derive(Debug)]
+enum TcpProxyOption {
+    /// Use the proxy set with `OCKAM_TCP_PROXY`, if any
+    Environment,
+    /// Use a specific proxy
+    Proxy(TcpProxy),
+    /// Connect directly
+    Disabled,
 }
 
 impl TcpConnectionOptions {
@@ -17,9 +31,30 @@ impl TcpConnectionOptions {
         Self {
             consumer: vec![],
             flow_control_id: FlowControls::generate_flow_control_id(),
+            socket_options: TcpSocketOptions::new(),
+            proxy: TcpProxyOption::Environment,
         }
     }
 
+    /// Connect through a proxy instead of the proxy set in the environment
+    pub fn with_proxy(mut self, proxy: TcpProxy) -> Self {
+        self.proxy = TcpProxyOption::Proxy(proxy);
+        self
+    }
+
+    /// Set the socket options of the connection.
+    /// The options which are not set are taken from the [`TcpTransport`](crate::TcpTransport)
+    pub fn with_socket_options(mut self, socket_options: TcpSocketOptions) -> Self {
+        self.socket_options = socket_options;
+        self
+    }
+
+    /// Connect directly, even if a proxy is set in the environment
+    pub fn without_proxy(mut self) -> Self {
+        self.proxy = TcpProxyOption::Disabled;
+        self
+    }
+
     /// Mark that this Connection is a Consumer for to the given [`FlowControlId`]
     pub fn as_consumer(mut self, id: &FlowControlId) -> Self {
         self.consumer.push(id.clone());
@@ -34,6 +69,18 @@ impl TcpConnectionOptions {
 }
 
 impl TcpConnectionOptions {
+    /// Return the proxy to use to connect to a peer
+    pub(crate) fn proxy(&self, peer: &str) -> Result<Option<TcpProxy>> {
+        match &self.proxy {
+            TcpProxyOption::Environment => match TcpProxy::from_env()? {
+                Some(proxy) if !TcpProxy::is_bypassed(peer)? => Ok(Some(proxy)),
+                _ => Ok(None),
+            },
+            TcpProxyOption::Proxy(proxy) => Ok(Some(proxy.clone())),
+            TcpProxyOption::Disabled => Ok(None),
+        }
+    }
+
     pub(crate) fn setup_flow_control(&self, flow_controls: &FlowControls, addresses: &Addresses) {
         flow_controls.add_producer(
             addresses.receiver_address().clone(),
@@ -63,6 +110,7 @@ impl TcpConnectionOptions {
 #[derive(Debug)]
 pub struct TcpListenerOptions {
     pub(crate) flow_control_id: FlowControlId,
+    pub(crate) socket_options: TcpSocketOptions,
 }
 
 impl TcpListenerOptions {
@@ -73,9 +121,17 @@ impl TcpListenerOptions {
     pub fn new() -> Self {
         Self {
             flow_control_id: FlowControls::generate_flow_control_id(),
+            socket_options: TcpSocketOptions::new(),
         }
     }
 
+    /// Set the socket options of the accepted connections.
+    /// The options which are not set are taken from the [`TcpTransport`](crate::TcpTransport)
+    pub fn with_socket_options(mut self, socket_options: TcpSocketOptions) -> Self {
+        self.socket_options = socket_options;
+        self
+    }
+
     /// Getter for freshly generated [`FlowControlId`]
     pub fn spawner_flow_control_id(&self) -> FlowControlId {
         self.flow_control_id.clone()
@@ -0,0 +1,138 @@
+use crate::MAX_PAYLOAD_SIZE;
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+use minicbor::{Decode, Encode};
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// Length of the prefix containing the size of an lz4 payload once decompressed
+const LZ4_SIZE_PREFIX_LENGTH: usize = 4;
+
+/// Algorithm used to compress the payloads exchanged by an Inlet and an Outlet.
+///
+/// The Inlet proposes an algorithm when it connects to the Outlet and the payloads are only
+/// compressed if the Outlet accepts it. Payloads which don't shrink are sent uncompressed.
+#[derive(Encode, Decode, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum PortalCompression {
+    /// Fast compression, with a lower compression ratio
+    #[n(1)] Lz4,
+    /// Higher compression ratio, using more CPU
+    #[n(2)] Zstd,
+}
+
+impl PortalCompression {
+    /// Code of the algorithm in the portal messages
+    pub(crate) fn code(&self) -> u8 {
+        match self {
+            PortalCompression::Lz4 => 1,
+            PortalCompression::Zstd => 2,
+        }
+    }
+
+    /// Return the algorithm corresponding to a code, if it is supported
+    pub(crate) fn from_code(code: u8) -> Option<Self> {
+        match code {
+            1 => Some(PortalCompression::Lz4),
+            2 => Some(PortalCompression::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Compress a payload
+    pub(crate) fn compress(&self, payload: &[u8]) -> Result<Vec<u8>> {
+        let compressed = match self {
+            PortalCompression::Lz4 => lz4::block::compress(payload, None, true),
+            PortalCompression::Zstd => {
+                zstd::bulk::compress(payload, zstd::DEFAULT_COMPRESSION_LEVEL)
+            }
+        };
+        compressed.map_err(|e| Self::error(format!("cannot compress a payload: {e}")))
+    }
+
+    /// Decompress a payload.
+    /// Payloads larger than [`MAX_PAYLOAD_SIZE`] once decompressed are rejected
+    pub(crate) fn decompress(&self, payload: &[u8]) -> Result<Vec<u8>> {
+        let decompressed = match self {
+            PortalCompression::Lz4 => {
+                if payload.len() < LZ4_SIZE_PREFIX_LENGTH {
+                    return Err(Self::error("the compressed payload is too short"));
+                }
+                let (size, compressed) = payload.split_at(LZ4_SIZE_PREFIX_LENGTH);
+                let size = u32::from_le_bytes(size.try_into().unwrap()) as usize;
+                if size > MAX_PAYLOAD_SIZE {
+                    return Err(Self::error("the decompressed payload is too large"));
+                }
+                lz4::block::decompress(compressed, Some(size as i32))
+            }
+            PortalCompression::Zstd => zstd::bulk::decompress(payload, MAX_PAYLOAD_SIZE),
+        };
+        decompressed.map_err(|e| Self::error(format!("cannot decompress a payload: {e}")))
+    }
+
+    fn error(message: impl Into<String>) -> Error {
+        Error::new(Origin::Transport, Kind::Invalid, message.into())
+    }
+}
+
+impl Display for PortalCompression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PortalCompression::Lz4 => f.write_str("lz4"),
+            PortalCompression::Zstd => f.write_str("zstd"),
+        }
+    }
+}
+
+impl FromStr for PortalCompression {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "lz4" => Ok(PortalCompression::Lz4),
+            "zstd" => Ok(PortalCompression::Zstd),
+            other => Err(Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                format!("unknown compression algorithm {other}, expected lz4 or zstd"),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compress_decompress() -> Result<()> {
+        let payload = "hello ".repeat(1000).into_bytes();
+        for compression in [PortalCompression::Lz4, PortalCompression::Zstd] {
+            let compressed = compression.compress(&payload)?;
+            assert!(compressed.len() < payload.len());
+            assert_eq!(compression.decompress(&compressed)?, payload);
+            assert_eq!(
+                PortalCompression::from_code(compression.code()),
+                Some(compression)
+            );
+            assert_eq!(
+                compression.to_string().parse::<PortalCompression>()?,
+                compression
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_reject_large_payloads() -> Result<()> {
+        let payload = vec![0u8; 2 * MAX_PAYLOAD_SIZE];
+        for compression in [PortalCompression::Lz4, PortalCompression::Zstd] {
+            let compressed = compression.compress(&payload)?;
+            assert!(compression.decompress(&compressed).is_err());
+        }
+        Ok(())
+    }
+}
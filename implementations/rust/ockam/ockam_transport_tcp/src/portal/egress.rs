@@ -0,0 +1,291 @@
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+use minicbor::{Decode, Encode};
+use ockam_core::compat::net::{IpAddr, SocketAddr};
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+
+/// Destinations which an Outlet is allowed to connect to.
+///
+/// This is useful for Outlets whose destination is decided in-band, for example the Outlets
+/// created for the brokers advertised by a Kafka cluster. An empty list denies everything.
+#[derive(Encode, Decode, Clone, Debug, Default, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TcpEgressAllowList {
+    #[n(1)] rules: Vec<TcpEgressRule>,
+}
+
+impl TcpEgressAllowList {
+    /// Create an allow-list denying all the destinations
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow the destinations matching a rule
+    pub fn with_rule(mut self, rule: TcpEgressRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Rules of the allow-list
+    pub fn rules(&self) -> &[TcpEgressRule] {
+        &self.rules
+    }
+
+    /// Check that all the rules are valid
+    pub fn validate(&self) -> Result<()> {
+        self.rules.iter().try_for_each(|rule| rule.validate())
+    }
+
+    /// Return true if a destination, given by its hostname and resolved address, is allowed
+    pub fn is_allowed(&self, hostname: &str, address: &SocketAddr) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| rule.matches(hostname, address))
+    }
+}
+
+/// A destination allowed by a [`TcpEgressAllowList`].
+///
+/// It's parsed from `HOST[:PORTS]`, where `HOST` is a hostname, a wildcard like
+/// `*.example.com` or `*`, an IP address or a CIDR like `10.0.0.0/8`. IPv6 hosts must be
+/// bracketed when ports are given. `PORTS` is a port or a range like `9092-9094`.
+/// All the ports are allowed when they are not given.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TcpEgressRule {
+    #[n(1)] host: String,
+    #[n(2)] first_port: Option<u16>,
+    #[n(3)] last_port: Option<u16>,
+}
+
+impl TcpEgressRule {
+    /// Allow all the ports of the hosts matching a pattern
+    pub fn new(host: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            first_port: None,
+            last_port: None,
+        }
+    }
+
+    /// Only allow a range of ports, bounds included
+    pub fn with_ports(mut self, first_port: u16, last_port: u16) -> Self {
+        self.first_port = Some(first_port);
+        self.last_port = Some(last_port);
+        self
+    }
+
+    /// Host pattern of the rule
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    fn validate(&self) -> Result<()> {
+        HostPattern::parse(&self.host)?;
+        if let (Some(first_port), Some(last_port)) = (self.first_port, self.last_port) {
+            if first_port > last_port {
+                return Err(egress_error(format!("Invalid port range in {self}")));
+            }
+        }
+        Ok(())
+    }
+
+    fn matches(&self, hostname: &str, address: &SocketAddr) -> bool {
+        let port = address.port();
+        if self.first_port.is_some_and(|first_port| port < first_port)
+            || self.last_port.is_some_and(|last_port| port > last_port)
+        {
+            return false;
+        }
+        match HostPattern::parse(&self.host) {
+            Ok(pattern) => pattern.matches(hostname, &address.ip()),
+            Err(_) => false,
+        }
+    }
+}
+
+impl Display for TcpEgressRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let host = if self.host.contains(':') && self.first_port.is_some() {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+        match (self.first_port, self.last_port) {
+            (Some(first_port), Some(last_port)) if first_port == last_port => {
+                write!(f, "{host}:{first_port}")
+            }
+            (Some(first_port), Some(last_port)) => write!(f, "{host}:{first_port}-{last_port}"),
+            _ => write!(f, "{host}"),
+        }
+    }
+}
+
+impl FromStr for TcpEgressRule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (host, ports) = if let Some(bracketed) = s.strip_prefix('[') {
+            let (host, rest) = bracketed
+                .split_once(']')
+                .ok_or_else(|| egress_error(format!("Missing ']' in {s}")))?;
+            match rest {
+                "" => (host, None),
+                _ => match rest.strip_prefix(':') {
+                    Some(ports) => (host, Some(ports)),
+                    None => return Err(egress_error(format!("Invalid rule {s}"))),
+                },
+            }
+        } else if s.matches(':').count() == 1 {
+            let (host, ports) = s.split_once(':').unwrap_or((s, ""));
+            (host, Some(ports))
+        } else {
+            (s, None)
+        };
+
+        let rule = Self::new(host);
+        let rule = match ports {
+            None => rule,
+            Some(ports) => {
+                let (first_port, last_port) = ports.split_once('-').unwrap_or((ports, ports));
+                let parse_port = |port: &str| {
+                    u16::from_str(port.trim())
+                        .map_err(|_| egress_error(format!("Invalid port {port} in {s}")))
+                };
+                rule.with_ports(parse_port(first_port)?, parse_port(last_port)?)
+            }
+        };
+        rule.validate()?;
+        Ok(rule)
+    }
+}
+
+enum HostPattern<'a> {
+    Any,
+    Suffix(&'a str),
+    Hostname(&'a str),
+    Cidr(IpAddr, u8),
+}
+
+impl<'a> HostPattern<'a> {
+    fn parse(host: &'a str) -> Result<Self> {
+        if host.is_empty() {
+            return Err(egress_error("The host of an egress rule can't be empty"));
+        }
+        if host == "*" {
+            return Ok(Self::Any);
+        }
+        if let Some(suffix) = host.strip_prefix("*.") {
+            return Ok(Self::Suffix(suffix));
+        }
+        if let Some((ip, prefix)) = host.split_once('/') {
+            let ip = IpAddr::from_str(ip)
+                .map_err(|_| egress_error(format!("Invalid CIDR address {host}")))?;
+            let max_prefix = if ip.is_ipv4() { 32 } else { 128 };
+            let prefix = u8::from_str(prefix)
+                .ok()
+                .filter(|prefix| *prefix <= max_prefix)
+                .ok_or_else(|| egress_error(format!("Invalid CIDR prefix {host}")))?;
+            return Ok(Self::Cidr(ip, prefix));
+        }
+        if let Ok(ip) = IpAddr::from_str(host) {
+            let prefix = if ip.is_ipv4() { 32 } else { 128 };
+            return Ok(Self::Cidr(ip, prefix));
+        }
+        if host.contains('*') {
+            return Err(egress_error(format!(
+                "Wildcards are only supported as a first label: {host}"
+            )));
+        }
+        Ok(Self::Hostname(host))
+    }
+
+    fn matches(&self, hostname: &str, ip: &IpAddr) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Suffix(suffix) => {
+                let hostname = hostname.to_ascii_lowercase();
+                let suffix = suffix.to_ascii_lowercase();
+                hostname.len() > suffix.len()
+                    && hostname.ends_with(&suffix)
+                    && hostname[..hostname.len() - suffix.len()].ends_with('.')
+            }
+            Self::Hostname(expected) => hostname.eq_ignore_ascii_case(expected),
+            Self::Cidr(network, prefix) => match (network, ip) {
+                (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                    let mask = u32::MAX.checked_shl(32 - *prefix as u32).unwrap_or(0);
+                    u32::from(*network) & mask == u32::from(*ip) & mask
+                }
+                (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                    let mask = u128::MAX.checked_shl(128 - *prefix as u32).unwrap_or(0);
+                    u128::from(*network) & mask == u128::from(*ip) & mask
+                }
+                _ => false,
+            },
+        }
+    }
+}
+
+fn egress_error(message: impl ToString) -> Error {
+    Error::new(Origin::Transport, Kind::Invalid, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(s: &str) -> SocketAddr {
+        SocketAddr::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        let rule = TcpEgressRule::from_str("*.kafka.example.com:9092-9094").unwrap();
+        assert_eq!(
+            rule,
+            TcpEgressRule::new("*.kafka.example.com").with_ports(9092, 9094)
+        );
+        assert_eq!(rule.to_string(), "*.kafka.example.com:9092-9094");
+
+        let rule = TcpEgressRule::from_str("[fd00::/8]:443").unwrap();
+        assert_eq!(rule, TcpEgressRule::new("fd00::/8").with_ports(443, 443));
+        assert_eq!(rule.to_string(), "[fd00::/8]:443");
+
+        assert_eq!(
+            TcpEgressRule::from_str("fd00::1").unwrap(),
+            TcpEgressRule::new("fd00::1")
+        );
+
+        assert!(TcpEgressRule::from_str("10.0.0.0/33").is_err());
+        assert!(TcpEgressRule::from_str("broker.*.com").is_err());
+        assert!(TcpEgressRule::from_str("broker:9094-9092").is_err());
+        assert!(TcpEgressRule::from_str("broker:http").is_err());
+    }
+
+    #[test]
+    fn test_allow_list() {
+        let allow_list = TcpEgressAllowList::new()
+            .with_rule(TcpEgressRule::from_str("*.kafka.example.com:9092").unwrap())
+            .with_rule(TcpEgressRule::from_str("10.1.0.0/16").unwrap())
+            .with_rule(TcpEgressRule::from_str("Registry.local").unwrap());
+
+        let broker = address("192.168.1.1:9092");
+        assert!(allow_list.is_allowed("b1.kafka.example.com", &broker));
+        assert!(allow_list.is_allowed("B1.KAFKA.example.com", &broker));
+        assert!(!allow_list.is_allowed("kafka.example.com", &broker));
+        assert!(!allow_list.is_allowed("b1.evilkafka.example.com.attacker", &broker));
+        assert!(!allow_list.is_allowed("b1.kafka.example.com", &address("192.168.1.1:22")));
+
+        assert!(allow_list.is_allowed("anything", &address("10.1.200.3:5432")));
+        assert!(!allow_list.is_allowed("anything", &address("10.2.0.1:5432")));
+        assert!(allow_list.is_allowed("registry.local", &address("127.0.0.1:8081")));
+
+        assert!(!TcpEgressAllowList::new().is_allowed("localhost", &address("127.0.0.1:80")));
+    }
+}
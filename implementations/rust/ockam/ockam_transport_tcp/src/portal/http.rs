@@ -0,0 +1,825 @@
+use core::str;
+use minicbor::{Decode, Encode};
+use ockam_core::compat::collections::VecDeque;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+
+/// Maximum size of the head of an HTTP request: request line and headers
+const MAX_HEAD_SIZE: usize = 64 * 1024;
+
+/// Maximum number of headers of an HTTP request
+const MAX_HEADERS: usize = 128;
+
+/// Maximum size of a line of a chunked body: chunk size or trailer
+const MAX_CHUNK_LINE_SIZE: usize = 4 * 1024;
+
+/// Rewriting of the HTTP/1.1 requests sent by the clients of an Inlet.
+///
+/// The headers are added to every request, and replace the headers with the same name
+/// sent by the client, so that they can't be spoofed. The `Host` header can be replaced as
+/// well, when the target of the Outlet expects a different host name.
+#[derive(Encode, Decode, Clone, Debug, Default, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct HttpRewrite {
+    #[n(1)] headers: Vec<(String, String)>,
+    #[n(2)] host: Option<String>,
+}
+
+impl HttpRewrite {
+    /// Don't modify the requests
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a header to every request
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let name = name.into();
+        self.headers
+            .retain(|(existing, _)| !existing.eq_ignore_ascii_case(&name));
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    /// Replace the `Host` header of every request
+    pub fn with_host(mut self, host: impl Into<String>) -> Self {
+        self.host = Some(host.into());
+        self
+    }
+
+    /// Headers added to every request
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// Value of the `Host` header of every request
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+
+    /// Check that the headers can be written in a request without changing its structure
+    pub fn validate(&self) -> Result<()> {
+        for (name, value) in &self.headers {
+            if !is_valid_header_name(name) {
+                return Err(Self::error(format!("invalid HTTP header name: {name}")));
+            }
+            if !is_valid_header_value(value) {
+                return Err(Self::error(format!("invalid value for HTTP header {name}")));
+            }
+        }
+        if let Some(host) = &self.host {
+            if host.is_empty() || !is_valid_header_value(host) {
+                return Err(Self::error(format!("invalid HTTP host: {host}")));
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace the headers of a request
+    fn apply(&self, head: &mut HttpRequestHead) {
+        head.headers.retain(|(name, _)| !self.is_replaced(name));
+        if let Some(host) = &self.host {
+            head.headers.push(("Host".into(), host.as_bytes().to_vec()));
+        }
+        for (name, value) in &self.headers {
+            head.headers.push((name.clone(), value.as_bytes().to_vec()));
+        }
+    }
+
+    fn is_replaced(&self, name: &str) -> bool {
+        (self.host.is_some() && name.eq_ignore_ascii_case("host"))
+            || self
+                .headers
+                .iter()
+                .any(|(header, _)| header.eq_ignore_ascii_case(name))
+    }
+
+    fn error(message: impl Into<String>) -> Error {
+        Error::new(Origin::Transport, Kind::Invalid, message.into())
+    }
+}
+
+fn is_valid_header_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+fn is_valid_header_value(value: &str) -> bool {
+    value
+        .bytes()
+        .all(|b| b == b'\t' || (b >= 0x20 && b != 0x7f))
+}
+
+/// Position of the HTTP request stream being read
+#[derive(Debug, PartialEq, Eq)]
+enum HttpState {
+    /// Reading the request line and the headers
+    Head,
+    /// Reading a body with a known length
+    Body { remaining: u64 },
+    /// Reading the line with the size of the next chunk
+    ChunkSize,
+    /// Reading the data of a chunk
+    ChunkData { remaining: u64 },
+    /// Reading the line break at the end of a chunk
+    ChunkEnd { remaining: u64 },
+    /// Reading the trailers after the last chunk
+    Trailers,
+    /// The connection was upgraded to another protocol, the data is not modified anymore
+    Passthrough,
+}
+
+/// Request line and headers of an HTTP/1.1 request
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpRequestHead {
+    method: String,
+    path: String,
+    version: u8,
+    headers: Vec<(String, Vec<u8>)>,
+}
+
+impl HttpRequestHead {
+    /// Method of the request
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    /// Target of the request, with its query
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Value of the first header with a given name
+    pub fn header(&self, name: &str) -> Option<&[u8]> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_slice())
+    }
+
+    /// Host name of the `Host` header, without its port
+    pub fn host(&self) -> Option<&str> {
+        let host = str::from_utf8(self.header("host")?).ok()?.trim();
+        // an IPv6 address is enclosed in brackets
+        let host = match host.strip_prefix('[') {
+            Some(ipv6) => ipv6.split(']').next().unwrap_or_default(),
+            None => host.split(':').next().unwrap_or_default(),
+        };
+        Some(host)
+    }
+
+    /// Write the head of the request, as sent by a client
+    pub fn encode(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(
+            format!("{} {} HTTP/1.{}\r\n", self.method, self.path, self.version).as_bytes(),
+        );
+        for (name, value) in &self.headers {
+            output.extend_from_slice(name.as_bytes());
+            output.extend_from_slice(b": ");
+            output.extend_from_slice(value);
+            output.extend_from_slice(b"\r\n");
+        }
+        output.extend_from_slice(b"\r\n");
+    }
+
+    /// Parse the head of a request and return the state for reading its body
+    fn parse(head: &[u8]) -> Result<(Self, HttpState)> {
+        let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+        let mut request = httparse::Request::new(&mut headers);
+        match request.parse(head) {
+            Ok(httparse::Status::Complete(_)) => {}
+            Ok(httparse::Status::Partial) => {
+                return Err(HttpRewrite::error("incomplete HTTP request head"));
+            }
+            Err(e) => return Err(HttpRewrite::error(format!("invalid HTTP request: {e}"))),
+        }
+        let (Some(method), Some(path), Some(version)) =
+            (request.method, request.path, request.version)
+        else {
+            return Err(HttpRewrite::error("incomplete HTTP request line"));
+        };
+
+        let mut content_length: Option<u64> = None;
+        let mut chunked = false;
+        let mut upgrade = method.eq_ignore_ascii_case("CONNECT");
+
+        for header in request.headers.iter() {
+            if header.name.eq_ignore_ascii_case("content-length") {
+                let length = str::from_utf8(header.value)
+                    .ok()
+                    .and_then(|v| v.trim().parse::<u64>().ok())
+                    .ok_or_else(|| HttpRewrite::error("invalid HTTP Content-Length header"))?;
+                if content_length.is_some_and(|existing| existing != length) {
+                    return Err(HttpRewrite::error(
+                        "conflicting HTTP Content-Length headers",
+                    ));
+                }
+                content_length = Some(length);
+            } else if header.name.eq_ignore_ascii_case("transfer-encoding") {
+                // Only the chunked coding, applied last, delimits the body
+                chunked = str::from_utf8(header.value)
+                    .ok()
+                    .and_then(|codings| codings.rsplit(',').next())
+                    .is_some_and(|coding| coding.trim().eq_ignore_ascii_case("chunked"));
+                if !chunked {
+                    return Err(HttpRewrite::error("unsupported HTTP Transfer-Encoding"));
+                }
+            } else if header.name.eq_ignore_ascii_case("upgrade") {
+                upgrade = true;
+            }
+        }
+
+        // A request with both headers could be read differently by the target of the Outlet
+        if chunked && content_length.is_some() {
+            return Err(HttpRewrite::error(
+                "HTTP requests can't have both a Content-Length and a Transfer-Encoding",
+            ));
+        }
+
+        let state = if upgrade {
+            HttpState::Passthrough
+        } else if chunked {
+            HttpState::ChunkSize
+        } else {
+            match content_length {
+                Some(remaining) if remaining > 0 => HttpState::Body { remaining },
+                _ => HttpState::Head,
+            }
+        };
+        let head = Self {
+            method: method.into(),
+            path: path.into(),
+            version,
+            headers: request
+                .headers
+                .iter()
+                .map(|header| (header.name.into(), header.value.to_vec()))
+                .collect(),
+        };
+        Ok((head, state))
+    }
+}
+
+/// Part of the HTTP requests read from a connection
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HttpRequestPart {
+    /// Head of the next request
+    Head(HttpRequestHead),
+    /// Body of the current request, or data of an upgraded connection
+    Data(Vec<u8>),
+}
+
+/// Split the HTTP/1.1 requests read from a connection into their heads and bodies.
+/// Requests can be split across reads and several requests can be sent on the same connection.
+#[derive(Debug)]
+pub struct HttpRequestReader {
+    state: HttpState,
+    /// Part of the head of a request, or of a chunk line, which was not processed yet
+    pending: Vec<u8>,
+}
+
+impl Default for HttpRequestReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpRequestReader {
+    /// Create a reader expecting the head of a request
+    pub fn new() -> Self {
+        Self {
+            state: HttpState::Head,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Return the parts of the requests completed by `input`.
+    /// Incomplete request heads are kept until the rest of the head is read
+    pub fn read(&mut self, input: &[u8]) -> Result<Vec<HttpRequestPart>> {
+        let mut parts = Vec::new();
+        let mut input = input;
+
+        while !input.is_empty() {
+            match self.state {
+                HttpState::Head => {
+                    let searched_from = self.pending.len().saturating_sub(3);
+                    self.pending.extend_from_slice(input);
+                    let Some(end) = find(&self.pending[searched_from..], b"\r\n\r\n") else {
+                        if self.pending.len() > MAX_HEAD_SIZE {
+                            return Err(HttpRewrite::error("the HTTP request head is too large"));
+                        }
+                        break;
+                    };
+                    let head_len = searched_from + end + 4;
+                    // The bytes following the head are processed in the next iterations
+                    let rest = self.pending.len() - head_len;
+                    input = &input[input.len() - rest..];
+                    self.pending.truncate(head_len);
+                    let head = core::mem::take(&mut self.pending);
+                    let (head, state) = HttpRequestHead::parse(&head)?;
+                    parts.push(HttpRequestPart::Head(head));
+                    self.state = state;
+                }
+                HttpState::Body { remaining } => {
+                    let output = data(&mut parts);
+                    self.state = match remaining - forward(&mut input, remaining, output) {
+                        0 => HttpState::Head,
+                        remaining => HttpState::Body { remaining },
+                    };
+                }
+                HttpState::ChunkData { remaining } => {
+                    let output = data(&mut parts);
+                    self.state = match remaining - forward(&mut input, remaining, output) {
+                        0 => HttpState::ChunkEnd { remaining: 2 },
+                        remaining => HttpState::ChunkData { remaining },
+                    };
+                }
+                HttpState::ChunkEnd { remaining } => {
+                    let output = data(&mut parts);
+                    self.state = match remaining - forward(&mut input, remaining, output) {
+                        0 => HttpState::ChunkSize,
+                        remaining => HttpState::ChunkEnd { remaining },
+                    };
+                }
+                HttpState::ChunkSize | HttpState::Trailers => {
+                    let Some(line) = self.read_line(&mut input)? else {
+                        break;
+                    };
+                    data(&mut parts).extend_from_slice(&line);
+                    self.state = if self.state == HttpState::Trailers {
+                        if line == b"\r\n" {
+                            HttpState::Head
+                        } else {
+                            HttpState::Trailers
+                        }
+                    } else {
+                        match parse_chunk_size(&line)? {
+                            0 => HttpState::Trailers,
+                            size => HttpState::ChunkData { remaining: size },
+                        }
+                    };
+                }
+                HttpState::Passthrough => {
+                    data(&mut parts).extend_from_slice(input);
+                    break;
+                }
+            }
+        }
+
+        Ok(parts)
+    }
+
+    /// Read a line ending with `\n`, possibly across several reads
+    fn read_line(&mut self, input: &mut &[u8]) -> Result<Option<Vec<u8>>> {
+        read_line(&mut self.pending, input)
+    }
+}
+
+/// Part of the HTTP responses read from a connection
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HttpResponsePart {
+    /// Bytes of the current response, head included
+    Data(Vec<u8>),
+    /// End of the current response
+    End,
+}
+
+/// Find the end of each HTTP/1.1 response read from a connection, so that the responses to
+/// pipelined requests can be told apart.
+///
+/// The responses to `HEAD` and `CONNECT` requests are delimited differently, so the method of
+/// each request sent on the connection must be given with [`Self::expect_response`].
+/// A response without length ends with the connection, and is never complete.
+#[derive(Debug)]
+pub struct HttpResponseReader {
+    state: HttpState,
+    /// Part of the head of a response, or of a chunk line, which was not processed yet
+    pending: Vec<u8>,
+    /// Methods of the requests which were not answered yet
+    methods: VecDeque<String>,
+}
+
+impl Default for HttpResponseReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpResponseReader {
+    /// Create a reader expecting the head of a response
+    pub fn new() -> Self {
+        Self {
+            state: HttpState::Head,
+            pending: Vec::new(),
+            methods: VecDeque::new(),
+        }
+    }
+
+    /// Expect the response to a request sent with a given method
+    pub fn expect_response(&mut self, method: &str) {
+        self.methods.push_back(method.to_string());
+    }
+
+    /// Return the parts of the responses read in `input`, with the end of each completed response
+    pub fn read(&mut self, input: &[u8]) -> Result<Vec<HttpResponsePart>> {
+        let mut parts = Vec::new();
+        let mut input = input;
+
+        while !input.is_empty() {
+            match self.state {
+                HttpState::Head => {
+                    let searched_from = self.pending.len().saturating_sub(3);
+                    self.pending.extend_from_slice(input);
+                    let Some(end) = find(&self.pending[searched_from..], b"\r\n\r\n") else {
+                        if self.pending.len() > MAX_HEAD_SIZE {
+                            return Err(HttpRewrite::error("the HTTP response head is too large"));
+                        }
+                        break;
+                    };
+                    let head_len = searched_from + end + 4;
+                    // The bytes following the head are processed in the next iterations
+                    let rest = self.pending.len() - head_len;
+                    input = &input[input.len() - rest..];
+                    self.pending.truncate(head_len);
+                    let head = core::mem::take(&mut self.pending);
+                    let state = self.parse_head(&head)?;
+                    response_data(&mut parts).extend_from_slice(&head);
+                    match state {
+                        // an interim response is followed by another response to the same request
+                        None => {}
+                        Some(HttpState::Head) => parts.push(HttpResponsePart::End),
+                        Some(state) => self.state = state,
+                    }
+                }
+                HttpState::Body { remaining } => {
+                    let output = response_data(&mut parts);
+                    self.state = match remaining - forward(&mut input, remaining, output) {
+                        0 => {
+                            parts.push(HttpResponsePart::End);
+                            HttpState::Head
+                        }
+                        remaining => HttpState::Body { remaining },
+                    };
+                }
+                HttpState::ChunkData { remaining } => {
+                    let output = response_data(&mut parts);
+                    self.state = match remaining - forward(&mut input, remaining, output) {
+                        0 => HttpState::ChunkEnd { remaining: 2 },
+                        remaining => HttpState::ChunkData { remaining },
+                    };
+                }
+                HttpState::ChunkEnd { remaining } => {
+                    let output = response_data(&mut parts);
+                    self.state = match remaining - forward(&mut input, remaining, output) {
+                        0 => HttpState::ChunkSize,
+                        remaining => HttpState::ChunkEnd { remaining },
+                    };
+                }
+                HttpState::ChunkSize | HttpState::Trailers => {
+                    let Some(line) = read_line(&mut self.pending, &mut input)? else {
+                        break;
+                    };
+                    response_data(&mut parts).extend_from_slice(&line);
+                    self.state = if self.state == HttpState::Trailers {
+                        if line == b"\r\n" {
+                            parts.push(HttpResponsePart::End);
+                            HttpState::Head
+                        } else {
+                            HttpState::Trailers
+                        }
+                    } else {
+                        match parse_chunk_size(&line)? {
+                            0 => HttpState::Trailers,
+                            size => HttpState::ChunkData { remaining: size },
+                        }
+                    };
+                }
+                HttpState::Passthrough => {
+                    response_data(&mut parts).extend_from_slice(input);
+                    break;
+                }
+            }
+        }
+
+        Ok(parts)
+    }
+
+    /// Parse the head of a response and return the state for reading its body,
+    /// or None if this is an interim response, like `100 Continue`
+    fn parse_head(&mut self, head: &[u8]) -> Result<Option<HttpState>> {
+        let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+        let mut response = httparse::Response::new(&mut headers);
+        match response.parse(head) {
+            Ok(httparse::Status::Complete(_)) => {}
+            Ok(httparse::Status::Partial) => {
+                return Err(HttpRewrite::error("incomplete HTTP response head"));
+            }
+            Err(e) => return Err(HttpRewrite::error(format!("invalid HTTP response: {e}"))),
+        }
+        let Some(status) = response.code else {
+            return Err(HttpRewrite::error("incomplete HTTP status line"));
+        };
+        if (100..200).contains(&status) && status != 101 {
+            return Ok(None);
+        }
+
+        let method = self.methods.pop_front().unwrap_or_default();
+        let is_tunnel = method.eq_ignore_ascii_case("CONNECT") && (200..300).contains(&status);
+        if status == 101 || is_tunnel {
+            return Ok(Some(HttpState::Passthrough));
+        }
+        if method.eq_ignore_ascii_case("HEAD") || status == 204 || status == 304 {
+            return Ok(Some(HttpState::Head));
+        }
+
+        let mut content_length: Option<u64> = None;
+        let mut chunked = false;
+        for header in response.headers.iter() {
+            if header.name.eq_ignore_ascii_case("content-length") {
+                let length = str::from_utf8(header.value)
+                    .ok()
+                    .and_then(|v| v.trim().parse::<u64>().ok())
+                    .ok_or_else(|| HttpRewrite::error("invalid HTTP Content-Length header"))?;
+                content_length = Some(length);
+            } else if header.name.eq_ignore_ascii_case("transfer-encoding") {
+                chunked = str::from_utf8(header.value)
+                    .ok()
+                    .and_then(|codings| codings.rsplit(',').next())
+                    .is_some_and(|coding| coding.trim().eq_ignore_ascii_case("chunked"));
+            }
+        }
+
+        let state = if chunked {
+            HttpState::ChunkSize
+        } else {
+            match content_length {
+                Some(0) => HttpState::Head,
+                Some(remaining) => HttpState::Body { remaining },
+                // the body ends when the connection is closed
+                None => HttpState::Passthrough,
+            }
+        };
+        Ok(Some(state))
+    }
+}
+
+/// Rewrite the HTTP/1.1 requests read from a connection of an Inlet, following a
+/// [`HttpRewrite`]
+pub(crate) struct HttpRequestRewriter {
+    rewrite: Arc<HttpRewrite>,
+    reader: HttpRequestReader,
+}
+
+impl HttpRequestRewriter {
+    pub(crate) fn new(rewrite: Arc<HttpRewrite>) -> Self {
+        Self {
+            rewrite,
+            reader: HttpRequestReader::new(),
+        }
+    }
+
+    /// Return the data which must be sent to the Outlet after reading `input`.
+    /// Incomplete request heads are kept until the rest of the head is read
+    pub(crate) fn rewrite(&mut self, input: &[u8]) -> Result<Vec<u8>> {
+        let mut output = Vec::with_capacity(input.len());
+        for part in self.reader.read(input)? {
+            match part {
+                HttpRequestPart::Head(mut head) => {
+                    self.rewrite.apply(&mut head);
+                    head.encode(&mut output);
+                }
+                HttpRequestPart::Data(data) => output.extend_from_slice(&data),
+            }
+        }
+        Ok(output)
+    }
+}
+
+/// Read a line ending with `\n`, possibly across several reads
+fn read_line(pending: &mut Vec<u8>, input: &mut &[u8]) -> Result<Option<Vec<u8>>> {
+    match input.iter().position(|b| *b == b'\n') {
+        Some(position) => {
+            pending.extend_from_slice(&input[..=position]);
+            *input = &input[position + 1..];
+            Ok(Some(core::mem::take(pending)))
+        }
+        None => {
+            pending.extend_from_slice(input);
+            *input = &[];
+            if pending.len() > MAX_CHUNK_LINE_SIZE {
+                return Err(HttpRewrite::error("the HTTP chunk line is too large"));
+            }
+            Ok(None)
+        }
+    }
+}
+
+/// Return the data part at the end of the parts, to append the data read next
+fn data(parts: &mut Vec<HttpRequestPart>) -> &mut Vec<u8> {
+    if !matches!(parts.last(), Some(HttpRequestPart::Data(_))) {
+        parts.push(HttpRequestPart::Data(Vec::new()));
+    }
+    match parts.last_mut() {
+        Some(HttpRequestPart::Data(data)) => data,
+        _ => unreachable!("a data part was just added"),
+    }
+}
+
+/// Return the data part at the end of the response parts, to append the data read next
+fn response_data(parts: &mut Vec<HttpResponsePart>) -> &mut Vec<u8> {
+    if !matches!(parts.last(), Some(HttpResponsePart::Data(_))) {
+        parts.push(HttpResponsePart::Data(Vec::new()));
+    }
+    match parts.last_mut() {
+        Some(HttpResponsePart::Data(data)) => data,
+        _ => unreachable!("a data part was just added"),
+    }
+}
+
+/// Copy up to `remaining` bytes of the input to the output and return the number of bytes copied
+fn forward(input: &mut &[u8], remaining: u64, output: &mut Vec<u8>) -> u64 {
+    let len = remaining.min(input.len() as u64) as usize;
+    output.extend_from_slice(&input[..len]);
+    *input = &input[len..];
+    len as u64
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn parse_chunk_size(line: &[u8]) -> Result<u64> {
+    let size = line.split(|b| *b == b';').next().unwrap_or_default();
+    str::from_utf8(size)
+        .ok()
+        .and_then(|size| u64::from_str_radix(size.trim(), 16).ok())
+        .ok_or_else(|| HttpRewrite::error("invalid HTTP chunk size"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewriter() -> HttpRequestRewriter {
+        let rewrite = HttpRewrite::new()
+            .with_header("X-Ockam-Identifier", "I123")
+            .with_host("backend.local");
+        HttpRequestRewriter::new(Arc::new(rewrite))
+    }
+
+    #[test]
+    fn test_rewrite_headers() {
+        let mut rewriter = rewriter();
+        let output = rewriter
+            .rewrite(b"GET / HTTP/1.1\r\nHost: localhost\r\nx-ockam-identifier: spoofed\r\n\r\n")
+            .unwrap();
+        assert_eq!(
+            output,
+            b"GET / HTTP/1.1\r\nHost: backend.local\r\nX-Ockam-Identifier: I123\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_requests_split_across_reads() {
+        let mut rewriter = rewriter();
+        let mut output = rewriter.rewrite(b"POST /a HTTP/1.1\r\nContent-Le").unwrap();
+        assert!(output.is_empty());
+        output.extend(rewriter.rewrite(b"ngth: 4\r\n\r\nab").unwrap());
+        output.extend(rewriter.rewrite(b"cdGET /b HTTP/1.1\r\n\r\n").unwrap());
+        assert_eq!(
+            output,
+            b"POST /a HTTP/1.1\r\nContent-Length: 4\r\nHost: backend.local\r\n\
+              X-Ockam-Identifier: I123\r\n\r\nabcd\
+              GET /b HTTP/1.1\r\nHost: backend.local\r\nX-Ockam-Identifier: I123\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_chunked_body() {
+        let mut rewriter = HttpRequestRewriter::new(Arc::new(HttpRewrite::new()));
+        let output = rewriter
+            .rewrite(
+                b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n\
+                  4\r\nGET \r\n0\r\n\r\nGET / HTTP/1.1\r\n\r\n",
+            )
+            .unwrap();
+        assert_eq!(
+            output,
+            b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n\
+              4\r\nGET \r\n0\r\n\r\nGET / HTTP/1.1\r\n\r\n"
+        );
+        assert_eq!(rewriter.reader.state, HttpState::Head);
+    }
+
+    #[test]
+    fn test_read_request_parts() {
+        let mut reader = HttpRequestReader::new();
+        let parts = reader
+            .read(
+                b"POST /api/users?id=1 HTTP/1.1\r\nHost: api.local:8080\r\n\
+                  Content-Length: 2\r\n\r\nab",
+            )
+            .unwrap();
+        let HttpRequestPart::Head(head) = &parts[0] else {
+            panic!("the head of the request must be read first");
+        };
+        assert_eq!(head.method(), "POST");
+        assert_eq!(head.path(), "/api/users?id=1");
+        assert_eq!(head.host(), Some("api.local"));
+        assert_eq!(head.header("content-length"), Some(b"2".as_slice()));
+        assert_eq!(parts[1], HttpRequestPart::Data(b"ab".to_vec()));
+
+        let parts = reader
+            .read(b"GET / HTTP/1.1\r\nHost: [::1]:8080\r\n\r\n")
+            .unwrap();
+        let HttpRequestPart::Head(head) = &parts[0] else {
+            panic!("the head of the next request must be read");
+        };
+        assert_eq!(head.host(), Some("::1"));
+        assert_eq!(parts.len(), 1);
+    }
+
+    #[test]
+    fn test_reject_ambiguous_requests() {
+        assert!(rewriter()
+            .rewrite(b"POST / HTTP/1.1\r\nContent-Length: 1\r\nContent-Length: 2\r\n\r\n")
+            .is_err());
+
+        assert!(rewriter()
+            .rewrite(b"POST / HTTP/1.1\r\nContent-Length: 1\r\nTransfer-Encoding: chunked\r\n\r\n")
+            .is_err());
+
+        assert!(HttpRewrite::new()
+            .with_header("X-Test", "a\r\nX-Ockam-Identifier: spoofed")
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_read_pipelined_responses() {
+        let mut reader = HttpResponseReader::new();
+        reader.expect_response("GET");
+        reader.expect_response("GET");
+        reader.expect_response("GET");
+
+        let mut parts = reader.read(b"HTTP/1.1 200 OK\r\nContent-Len").unwrap();
+        assert!(parts.is_empty());
+        parts.extend(reader.read(b"gth: 2\r\n\r\na").unwrap());
+        parts.extend(
+            reader
+                .read(b"bHTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n")
+                .unwrap(),
+        );
+        parts.extend(reader.read(b"\r\n2\r\ncd\r\n0\r\n\r\n").unwrap());
+        parts.extend(reader.read(b"HTTP/1.1 204 No Content\r\n\r\n").unwrap());
+        assert_eq!(
+            parts,
+            vec![
+                HttpResponsePart::Data(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\na".to_vec()),
+                HttpResponsePart::Data(b"b".to_vec()),
+                HttpResponsePart::End,
+                HttpResponsePart::Data(
+                    b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n2\r\ncd\r\n0\r\n\r\n"
+                        .to_vec()
+                ),
+                HttpResponsePart::End,
+                HttpResponsePart::Data(b"HTTP/1.1 204 No Content\r\n\r\n".to_vec()),
+                HttpResponsePart::End,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_responses_without_body() {
+        let mut reader = HttpResponseReader::new();
+        reader.expect_response("HEAD");
+        reader.expect_response("POST");
+        let parts = reader
+            .read(
+                b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\n\
+                  HTTP/1.1 100 Continue\r\n\r\n\
+                  HTTP/1.1 201 Created\r\nContent-Length: 0\r\n\r\n",
+            )
+            .unwrap();
+        assert_eq!(
+            parts,
+            vec![
+                HttpResponsePart::Data(b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\n".to_vec()),
+                HttpResponsePart::End,
+                HttpResponsePart::Data(
+                    b"HTTP/1.1 100 Continue\r\n\r\n\
+                      HTTP/1.1 201 Created\r\nContent-Length: 0\r\n\r\n"
+                        .to_vec()
+                ),
+                HttpResponsePart::End,
+            ]
+        );
+    }
+}
@@ -1,22 +1,37 @@
 use crate::portal::addresses::{Addresses, PortalType};
+use crate::portal::{ConnectionTracker, InletLoadBalancer};
 use crate::{portal::TcpPortalWorker, TcpInlet, TcpInletOptions, TcpRegistry};
 use ockam_core::compat::net::SocketAddr;
 use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{async_trait, compat::boxed::Box};
-use ockam_core::{Address, Processor, Result, Route};
+use ockam_core::{Address, Error, Processor, Result, Route};
 use ockam_node::Context;
 use ockam_transport_core::{HostnamePort, TransportError};
 use tokio::net::TcpListener;
-use tracing::{debug, error, instrument};
+use tokio::sync::watch;
+use tokio_rustls::TlsAcceptor;
+use tracing::{debug, error, instrument, warn};
 
 /// State shared between `TcpInletListenProcessor` and `TcpInlet` to allow manipulating its state
 /// from outside the worker: update the route to the outlet or pause it.
 #[derive(Debug, Clone)]
 pub struct InletSharedState {
     pub route: Route,
+    /// Other routes used by a load balanced inlet, in addition to `route`
+    pub additional_routes: Vec<Route>,
     pub is_paused: bool,
 }
 
+impl InletSharedState {
+    /// All the routes to the outlets
+    pub fn routes(&self) -> Vec<Route> {
+        let mut routes = vec![self.route.clone()];
+        routes.extend(self.additional_routes.iter().cloned());
+        routes
+    }
+}
+
 /// A TCP Portal Inlet listen processor
 ///
 /// TCP Portal Inlet listen processors are created by `TcpTransport`
@@ -27,6 +42,10 @@ pub(crate) struct TcpInletListenProcessor {
     inner: TcpListener,
     outlet_shared_state: Arc<RwLock<InletSharedState>>,
     options: TcpInletOptions,
+    tls_acceptor: Option<TlsAcceptor>,
+    connection_tracker: Option<Arc<ConnectionTracker>>,
+    load_balancer: Option<InletLoadBalancer>,
+    outlet_routes: watch::Receiver<Route>,
 }
 
 impl TcpInletListenProcessor {
@@ -35,12 +54,23 @@ impl TcpInletListenProcessor {
         inner: TcpListener,
         outlet_shared_state: Arc<RwLock<InletSharedState>>,
         options: TcpInletOptions,
+        tls_acceptor: Option<TlsAcceptor>,
+        outlet_routes: watch::Receiver<Route>,
     ) -> Self {
+        let connection_tracker = options
+            .limits
+            .limits_connections()
+            .then(|| Arc::new(ConnectionTracker::new(options.limits)));
+        let load_balancer = options.load_balancing.map(InletLoadBalancer::new);
         Self {
             registry,
             inner,
             outlet_shared_state,
             options,
+            tls_acceptor,
+            connection_tracker,
+            load_balancer,
+            outlet_routes,
         }
     }
 
@@ -53,6 +83,22 @@ impl TcpInletListenProcessor {
         addr: SocketAddr,
         options: TcpInletOptions,
     ) -> Result<TcpInlet> {
+        if let Some(http_rewrite) = &options.http_rewrite {
+            http_rewrite.validate()?;
+        }
+        // A resumed connection must keep the same outlet
+        if options.resumption.is_some() && options.load_balancing.is_some() {
+            return Err(Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                "an inlet can't resume connections if it's load balanced",
+            ));
+        }
+        let tls_acceptor = match &options.tls_certificate {
+            Some(tls_certificate) => Some(tls_certificate.acceptor()?),
+            None => None,
+        };
+
         let processor_address = Address::random_tagged("TcpInletListenProcessor");
 
         debug!("Binding TcpPortalListenerWorker to {}", addr);
@@ -66,19 +112,37 @@ impl TcpInletListenProcessor {
         let socket_addr = inner.local_addr().map_err(TransportError::from)?;
         let outlet_shared_state = InletSharedState {
             route: outlet_listener_route,
+            additional_routes: vec![],
             is_paused: options.is_paused,
         };
         let outlet_shared_state = Arc::new(RwLock::new(outlet_shared_state));
-        let processor = Self::new(registry, inner, outlet_shared_state.clone(), options);
-
-        ctx.start_processor(processor_address.clone(), processor)
-            .await?;
-
-        Ok(TcpInlet::new(
+        let inlet = TcpInlet::new(
             socket_addr,
-            processor_address,
+            processor_address.clone(),
+            outlet_shared_state.clone(),
+            options.stats.clone(),
+        );
+        let processor = Self::new(
+            registry,
+            inner,
             outlet_shared_state,
-        ))
+            options,
+            tls_acceptor,
+            inlet.outlet_routes(),
+        );
+
+        ctx.start_processor(processor_address, processor).await?;
+
+        Ok(inlet)
+    }
+
+    /// Changes of the route to the outlet, after the current one, if the connections can be
+    /// resumed
+    fn outlet_routes(&self) -> Option<watch::Receiver<Route>> {
+        self.options.resumption?;
+        let mut outlet_routes = self.outlet_routes.clone();
+        outlet_routes.borrow_and_update();
+        Some(outlet_routes)
     }
 }
 
@@ -104,6 +168,9 @@ impl Processor for TcpInletListenProcessor {
     #[instrument(skip_all, name = "TcpInletListenProcessor::process")]
     async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
         let (stream, socket_addr) = self.inner.accept().await.map_err(TransportError::from)?;
+        if let Err(err) = self.options.socket_options.apply(&stream) {
+            warn!(%socket_addr, %err, "Cannot set the socket options of an inlet connection");
+        }
 
         let addresses = Addresses::generate(PortalType::Inlet);
 
@@ -114,21 +181,52 @@ impl Processor for TcpInletListenProcessor {
             return Ok(true);
         }
 
-        self.options.setup_flow_control(
-            ctx.flow_controls(),
-            &addresses,
-            outlet_shared_state.route.next()?,
-        );
+        let connection_permit = match &self.connection_tracker {
+            Some(connection_tracker) => match connection_tracker.try_acquire(socket_addr.ip()) {
+                Ok(connection_permit) => Some(connection_permit),
+                Err(reason) => {
+                    // Dropping the stream closes the connection
+                    warn!(%socket_addr, %reason, "Rejected an inlet connection");
+                    self.options.stats.connection_rejected(reason);
+                    return Ok(true);
+                }
+            },
+            None => None,
+        };
+
+        let (outlet_route, route_permit) = match &mut self.load_balancer {
+            Some(load_balancer) if !outlet_shared_state.additional_routes.is_empty() => {
+                match load_balancer.select(&outlet_shared_state.routes()) {
+                    Some((route, route_permit)) => (route, Some(route_permit)),
+                    None => (outlet_shared_state.route, None),
+                }
+            }
+            _ => (outlet_shared_state.route, None),
+        };
+
+        self.options
+            .setup_flow_control(ctx.flow_controls(), &addresses, outlet_route.next()?);
 
         TcpPortalWorker::start_new_inlet(
             ctx,
             self.registry.clone(),
             stream,
             HostnamePort::from_socket_addr(socket_addr)?,
-            outlet_shared_state.route,
+            outlet_route,
             addresses,
             self.options.incoming_access_control.clone(),
             self.options.outgoing_access_control.clone(),
+            self.options.stats.clone(),
+            self.options.compression,
+            self.options.rate_limiter.clone(),
+            self.options.http_rewrite.clone(),
+            self.tls_acceptor.clone(),
+            connection_permit,
+            route_permit,
+            self.options.limits.idle_timeout(),
+            self.options.proxy_protocol,
+            self.options.resumption,
+            self.outlet_routes(),
         )
         .await?;
 
@@ -0,0 +1,233 @@
+use core::fmt::{Display, Formatter};
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+use minicbor::{Decode, Encode};
+use ockam_core::compat::collections::HashMap;
+use ockam_core::compat::net::IpAddr;
+use ockam_core::compat::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Limits of the connections accepted by an Inlet.
+///
+/// They protect the node hosting an exposed Inlet from running out of file descriptors.
+/// The connections exceeding a limit are closed as soon as they are accepted.
+#[derive(Encode, Decode, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TcpInletLimits {
+    #[n(1)] max_connections: Option<u32>,
+    #[n(2)] max_connections_per_ip: Option<u32>,
+    #[n(3)] idle_timeout: Option<Duration>,
+}
+
+impl TcpInletLimits {
+    /// No limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maximum number of connections open at the same time
+    pub fn with_max_connections(mut self, max_connections: u32) -> Self {
+        self.max_connections = Some(max_connections);
+        self
+    }
+
+    /// Maximum number of connections open at the same time from the same IP address
+    pub fn with_max_connections_per_ip(mut self, max_connections_per_ip: u32) -> Self {
+        self.max_connections_per_ip = Some(max_connections_per_ip);
+        self
+    }
+
+    /// Close the connections which don't send or receive any data during that time
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = Some(idle_timeout);
+        self
+    }
+
+    /// Maximum number of connections open at the same time
+    pub fn max_connections(&self) -> Option<u32> {
+        self.max_connections
+    }
+
+    /// Maximum number of connections open at the same time from the same IP address
+    pub fn max_connections_per_ip(&self) -> Option<u32> {
+        self.max_connections_per_ip
+    }
+
+    /// Time after which the connections without any traffic are closed
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.idle_timeout
+    }
+
+    /// Return true if the number of connections is limited
+    pub(crate) fn limits_connections(&self) -> bool {
+        self.max_connections.is_some() || self.max_connections_per_ip.is_some()
+    }
+}
+
+/// Reason why a connection was refused by an Inlet
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ConnectionRejection {
+    MaxConnections,
+    MaxConnectionsPerIp,
+}
+
+impl Display for ConnectionRejection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::MaxConnections => write!(f, "max_connections"),
+            Self::MaxConnectionsPerIp => write!(f, "max_connections_per_ip"),
+        }
+    }
+}
+
+/// Count of the open connections of an Inlet, in total and per IP address
+#[derive(Debug)]
+pub(crate) struct ConnectionTracker {
+    limits: TcpInletLimits,
+    counts: Mutex<ConnectionCounts>,
+}
+
+#[derive(Debug, Default)]
+struct ConnectionCounts {
+    total: u32,
+    per_ip: HashMap<IpAddr, u32>,
+}
+
+impl ConnectionTracker {
+    pub(crate) fn new(limits: TcpInletLimits) -> Self {
+        Self {
+            limits,
+            counts: Mutex::new(ConnectionCounts::default()),
+        }
+    }
+
+    /// Count a new connection from an IP address, if it doesn't exceed the limits.
+    /// The connection is counted until the returned permit is dropped
+    pub(crate) fn try_acquire(
+        self: &Arc<Self>,
+        ip: IpAddr,
+    ) -> Result<ConnectionPermit, ConnectionRejection> {
+        let mut counts = self.counts.lock().unwrap();
+        if self
+            .limits
+            .max_connections
+            .is_some_and(|max| counts.total >= max)
+        {
+            return Err(ConnectionRejection::MaxConnections);
+        }
+        let per_ip = counts.per_ip.get(&ip).copied().unwrap_or(0);
+        if self
+            .limits
+            .max_connections_per_ip
+            .is_some_and(|max| per_ip >= max)
+        {
+            return Err(ConnectionRejection::MaxConnectionsPerIp);
+        }
+        counts.total += 1;
+        counts.per_ip.insert(ip, per_ip + 1);
+        Ok(ConnectionPermit {
+            tracker: self.clone(),
+            ip,
+        })
+    }
+
+    fn release(&self, ip: &IpAddr) {
+        let mut counts = self.counts.lock().unwrap();
+        counts.total = counts.total.saturating_sub(1);
+        if let Some(per_ip) = counts.per_ip.get_mut(ip) {
+            *per_ip -= 1;
+            if *per_ip == 0 {
+                counts.per_ip.remove(ip);
+            }
+        }
+    }
+}
+
+/// A connection counted by a [`ConnectionTracker`], until it's dropped
+#[derive(Debug)]
+pub(crate) struct ConnectionPermit {
+    tracker: Arc<ConnectionTracker>,
+    ip: IpAddr,
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        self.tracker.release(&self.ip);
+    }
+}
+
+/// Time of the last traffic of a connection, in either direction
+#[derive(Debug)]
+pub(crate) struct ConnectionActivity {
+    idle_timeout: Duration,
+    start: Instant,
+    last_activity_ms: AtomicU64,
+}
+
+impl ConnectionActivity {
+    pub(crate) fn new(idle_timeout: Duration) -> Self {
+        Self {
+            idle_timeout,
+            start: Instant::now(),
+            last_activity_ms: AtomicU64::new(0),
+        }
+    }
+
+    /// Record some traffic on the connection
+    pub(crate) fn touch(&self) {
+        let elapsed_ms = self.start.elapsed().as_millis() as u64;
+        self.last_activity_ms
+            .fetch_max(elapsed_ms, Ordering::Relaxed);
+    }
+
+    /// Remaining time before the connection is considered idle, zero if it already is
+    pub(crate) fn remaining(&self) -> Duration {
+        let last_activity = Duration::from_millis(self.last_activity_ms.load(Ordering::Relaxed));
+        let idle_for = self.start.elapsed().saturating_sub(last_activity);
+        self.idle_timeout.saturating_sub(idle_for)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::compat::net::Ipv4Addr;
+
+    #[test]
+    fn test_connection_limits() {
+        let limits = TcpInletLimits::new()
+            .with_max_connections(3)
+            .with_max_connections_per_ip(2);
+        let tracker = Arc::new(ConnectionTracker::new(limits));
+        let ip1 = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let ip2 = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+
+        let first = tracker.try_acquire(ip1).unwrap();
+        let _second = tracker.try_acquire(ip1).unwrap();
+        assert_eq!(
+            tracker.try_acquire(ip1).unwrap_err(),
+            ConnectionRejection::MaxConnectionsPerIp
+        );
+
+        let _third = tracker.try_acquire(ip2).unwrap();
+        assert_eq!(
+            tracker.try_acquire(ip2).unwrap_err(),
+            ConnectionRejection::MaxConnections
+        );
+
+        // closing a connection frees a slot for its IP address
+        drop(first);
+        assert!(tracker.try_acquire(ip1).is_ok());
+    }
+
+    #[test]
+    fn test_connection_activity() {
+        let activity = ConnectionActivity::new(Duration::from_secs(60));
+        activity.touch();
+        assert!(activity.remaining() > Duration::from_secs(59));
+
+        let activity = ConnectionActivity::new(Duration::ZERO);
+        assert_eq!(activity.remaining(), Duration::ZERO);
+    }
+}
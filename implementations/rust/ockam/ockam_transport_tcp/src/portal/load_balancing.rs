@@ -0,0 +1,176 @@
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+use core::sync::atomic::{AtomicU64, Ordering};
+use minicbor::{Decode, Encode};
+use ockam_core::compat::collections::HashMap;
+use ockam_core::compat::sync::Arc;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result, Route};
+
+/// Strategy used by an Inlet to spread its connections across the routes to several Outlets
+#[derive(Encode, Decode, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum TcpInletLoadBalancing {
+    /// Each new connection uses the next route
+    #[default]
+    #[n(1)] RoundRobin,
+    /// Each new connection uses the route with the fewest open connections
+    #[n(2)] LeastConnections,
+}
+
+impl Display for TcpInletLoadBalancing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TcpInletLoadBalancing::RoundRobin => f.write_str("round-robin"),
+            TcpInletLoadBalancing::LeastConnections => f.write_str("least-connections"),
+        }
+    }
+}
+
+impl FromStr for TcpInletLoadBalancing {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "round-robin" => Ok(TcpInletLoadBalancing::RoundRobin),
+            "least-connections" => Ok(TcpInletLoadBalancing::LeastConnections),
+            other => Err(Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                format!(
+                    "unknown load balancing strategy {other}, \
+                    expected round-robin or least-connections"
+                ),
+            )),
+        }
+    }
+}
+
+/// Selection of the route used by each connection accepted by a load balanced Inlet
+#[derive(Debug)]
+pub(crate) struct InletLoadBalancer {
+    strategy: TcpInletLoadBalancing,
+    next: usize,
+    active_connections: HashMap<Route, Arc<AtomicU64>>,
+}
+
+impl InletLoadBalancer {
+    pub(crate) fn new(strategy: TcpInletLoadBalancing) -> Self {
+        Self {
+            strategy,
+            next: 0,
+            active_connections: HashMap::new(),
+        }
+    }
+
+    /// Select the route of a new connection among the current routes of the Inlet.
+    /// The connection is counted for that route until the returned permit is dropped
+    pub(crate) fn select(&mut self, routes: &[Route]) -> Option<(Route, RoutePermit)> {
+        if routes.is_empty() {
+            return None;
+        }
+
+        // Forget the routes which were removed, once all their connections are closed
+        self.active_connections.retain(|route, active_connections| {
+            routes.contains(route) || active_connections.load(Ordering::Relaxed) > 0
+        });
+
+        let index = match self.strategy {
+            TcpInletLoadBalancing::RoundRobin => {
+                let index = self.next % routes.len();
+                self.next = index + 1;
+                index
+            }
+            TcpInletLoadBalancing::LeastConnections => routes
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, route)| self.active_connections(route))
+                .map(|(index, _)| index)
+                .unwrap_or(0),
+        };
+
+        let route = routes[index].clone();
+        let active_connections = self.active_connections.entry(route.clone()).or_default();
+        Some((route, RoutePermit::new(active_connections.clone())))
+    }
+
+    fn active_connections(&self, route: &Route) -> u64 {
+        self.active_connections
+            .get(route)
+            .map(|active_connections| active_connections.load(Ordering::Relaxed))
+            .unwrap_or(0)
+    }
+}
+
+/// A connection counted for a route of a load balanced Inlet, until it's dropped
+#[derive(Debug)]
+pub(crate) struct RoutePermit {
+    active_connections: Arc<AtomicU64>,
+}
+
+impl RoutePermit {
+    fn new(active_connections: Arc<AtomicU64>) -> Self {
+        active_connections.fetch_add(1, Ordering::Relaxed);
+        Self { active_connections }
+    }
+}
+
+impl Drop for RoutePermit {
+    fn drop(&mut self) {
+        self.active_connections.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::route;
+
+    #[test]
+    fn test_round_robin() {
+        let routes = [route!["outlet1"], route!["outlet2"], route!["outlet3"]];
+        let mut load_balancer = InletLoadBalancer::new(TcpInletLoadBalancing::RoundRobin);
+
+        let selected: Vec<Route> = (0..4)
+            .map(|_| load_balancer.select(&routes).unwrap().0)
+            .collect();
+        assert_eq!(selected, [&routes[..], &routes[..1]].concat());
+
+        // The rotation goes on when a route is removed
+        let (route, _) = load_balancer.select(&routes[..2]).unwrap();
+        assert_eq!(route, routes[1]);
+        assert!(load_balancer.select(&[]).is_none());
+    }
+
+    #[test]
+    fn test_least_connections() {
+        let routes = [route!["outlet1"], route!["outlet2"]];
+        let mut load_balancer = InletLoadBalancer::new(TcpInletLoadBalancing::LeastConnections);
+
+        let (route1, permit1) = load_balancer.select(&routes).unwrap();
+        let (route2, _permit2) = load_balancer.select(&routes).unwrap();
+        assert_eq!(route1, routes[0]);
+        assert_eq!(route2, routes[1]);
+
+        // The first route has no connections anymore
+        drop(permit1);
+        let (route, _permit) = load_balancer.select(&routes).unwrap();
+        assert_eq!(route, routes[0]);
+    }
+
+    #[test]
+    fn test_parse_strategy() -> Result<()> {
+        for strategy in [
+            TcpInletLoadBalancing::RoundRobin,
+            TcpInletLoadBalancing::LeastConnections,
+        ] {
+            assert_eq!(
+                strategy.to_string().parse::<TcpInletLoadBalancing>()?,
+                strategy
+            );
+        }
+        assert!("random".parse::<TcpInletLoadBalancing>().is_err());
+        Ok(())
+    }
+}
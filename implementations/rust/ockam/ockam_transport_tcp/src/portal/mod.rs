@@ -1,13 +1,45 @@
 mod addresses;
+mod compression;
+mod egress;
+mod http;
 mod inlet_listener;
+mod limits;
+mod load_balancing;
 pub mod options;
 mod outlet_listener;
 mod portal_message;
 mod portal_receiver;
 mod portal_worker;
+mod proxy_protocol;
+mod rate_limit;
+mod resumption;
+mod stats;
+mod tls;
 
+pub use compression::*;
+pub use egress::{TcpEgressAllowList, TcpEgressRule};
+pub(crate) use http::HttpRequestRewriter;
+pub use http::{
+    HttpRequestHead, HttpRequestPart, HttpRequestReader, HttpResponsePart, HttpResponseReader,
+    HttpRewrite,
+};
 pub(crate) use inlet_listener::*;
+pub use limits::TcpInletLimits;
+pub(crate) use limits::{
+    ConnectionActivity, ConnectionPermit, ConnectionRejection, ConnectionTracker,
+};
+pub use load_balancing::TcpInletLoadBalancing;
+pub(crate) use load_balancing::{InletLoadBalancer, RoutePermit};
 pub(crate) use outlet_listener::*;
 pub use portal_message::*;
 pub(crate) use portal_receiver::*;
 pub(crate) use portal_worker::*;
+pub use proxy_protocol::{PortalPeerIdentifier, PROXY_PROTOCOL_OCKAM_IDENTIFIER_TLV};
+pub(crate) use proxy_protocol::{ProxyProtocolClient, ProxyProtocolHeader};
+pub use rate_limit::TcpPortalRateLimit;
+pub(crate) use rate_limit::TokenBucket;
+pub(crate) use resumption::{
+    resumed_route, ConnectionResumption, ResumedRoute, ResumptionEvent, ResumptionReceiver,
+};
+pub use stats::*;
+pub use tls::{PortalTlsCertificate, PortalTlsVerification};
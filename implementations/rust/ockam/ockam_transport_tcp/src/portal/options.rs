@@ -1,5 +1,12 @@
 use crate::portal::addresses::Addresses;
-use ockam_core::compat::sync::Arc;
+use crate::portal::TokenBucket;
+use crate::{
+    HttpRewrite, PortalCompression, PortalPeerIdentifier, PortalTlsCertificate,
+    PortalTlsVerification, TcpEgressAllowList, TcpInletLimits, TcpInletLoadBalancing,
+    TcpPortalRateLimit, TcpPortalStats, TcpSocketOptions,
+};
+use core::time::Duration;
+use ockam_core::compat::sync::{Arc, RwLock};
 use ockam_core::flow_control::{FlowControlId, FlowControls};
 use ockam_core::{Address, AllowAll, IncomingAccessControl, OutgoingAccessControl};
 
@@ -9,6 +16,16 @@ pub struct TcpInletOptions {
     pub(super) incoming_access_control: Arc<dyn IncomingAccessControl>,
     pub(super) outgoing_access_control: Arc<dyn OutgoingAccessControl>,
     pub(super) is_paused: bool,
+    pub(super) stats: Arc<TcpPortalStats>,
+    pub(crate) socket_options: TcpSocketOptions,
+    pub(super) compression: Option<PortalCompression>,
+    pub(super) rate_limiter: Option<Arc<TokenBucket>>,
+    pub(super) http_rewrite: Option<Arc<HttpRewrite>>,
+    pub(super) tls_certificate: Option<PortalTlsCertificate>,
+    pub(super) limits: TcpInletLimits,
+    pub(super) load_balancing: Option<TcpInletLoadBalancing>,
+    pub(super) proxy_protocol: bool,
+    pub(super) resumption: Option<Duration>,
 }
 
 impl TcpInletOptions {
@@ -18,9 +35,90 @@ impl TcpInletOptions {
             incoming_access_control: Arc::new(AllowAll),
             outgoing_access_control: Arc::new(AllowAll),
             is_paused: false,
+            stats: Arc::new(TcpPortalStats::default()),
+            socket_options: TcpSocketOptions::new(),
+            compression: None,
+            rate_limiter: None,
+            http_rewrite: None,
+            tls_certificate: None,
+            limits: TcpInletLimits::new(),
+            load_balancing: None,
+            proxy_protocol: false,
+            resumption: None,
         }
     }
 
+    /// Keep the accepted connections open when the route to the Outlet breaks, for at most
+    /// the given grace period. The connections resume once the route is replaced with
+    /// [`TcpInlet::update_outlet_node_route`](crate::TcpInlet::update_outlet_node_route),
+    /// and the data lost in the meantime is sent again. The Outlet must accept resumption
+    pub fn with_resumption(mut self, grace_period: Duration) -> Self {
+        self.resumption = Some(grace_period);
+        self
+    }
+
+    /// Expect a PROXY protocol v2 header at the start of each accepted connection, sent by a
+    /// load balancer in front of the Inlet. The address of the original client is then sent
+    /// to the Outlet instead of the address of the load balancer
+    pub fn with_proxy_protocol(mut self, proxy_protocol: bool) -> Self {
+        self.proxy_protocol = proxy_protocol;
+        self
+    }
+
+    /// Spread the connections across all the routes of the Inlet, set with
+    /// [`TcpInlet::update_routes`](crate::TcpInlet::update_routes)
+    pub fn with_load_balancing(mut self, load_balancing: TcpInletLoadBalancing) -> Self {
+        self.load_balancing = Some(load_balancing);
+        self
+    }
+
+    /// Limit the number of connections accepted by the Inlet, and close the idle ones
+    pub fn with_limits(mut self, limits: TcpInletLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Terminate TLS on the accepted connections with the given certificate.
+    /// The decrypted traffic is sent to the Outlet
+    pub fn with_tls_certificate(mut self, tls_certificate: PortalTlsCertificate) -> Self {
+        self.tls_certificate = Some(tls_certificate);
+        self
+    }
+
+    /// Parse the traffic of the accepted connections as HTTP/1.1 requests and rewrite them
+    /// before sending them to the Outlet
+    pub fn with_http_rewrite(mut self, http_rewrite: HttpRewrite) -> Self {
+        self.http_rewrite = Some(Arc::new(http_rewrite));
+        self
+    }
+
+    /// Limit the throughput of all the connections accepted by the Inlet
+    pub fn with_rate_limit(mut self, rate_limit: TcpPortalRateLimit) -> Self {
+        self.rate_limiter = Some(Arc::new(TokenBucket::new(rate_limit)));
+        self
+    }
+
+    /// Propose to the Outlet to compress the payloads with the given algorithm.
+    /// The payloads are sent uncompressed if the Outlet doesn't accept it
+    pub fn with_compression(mut self, compression: PortalCompression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Set the socket options of the accepted connections.
+    /// The options which are not set are taken from the [`TcpTransport`](crate::TcpTransport)
+    pub fn with_socket_options(mut self, socket_options: TcpSocketOptions) -> Self {
+        self.socket_options = socket_options;
+        self
+    }
+
+    /// Count the traffic of the Inlet connections with the given counters.
+    /// This allows keeping the same counters when the Inlet is re-created
+    pub fn with_stats(mut self, stats: Arc<TcpPortalStats>) -> Self {
+        self.stats = stats;
+        self
+    }
+
     /// Set TCP inlet to paused mode after start. No unpause call [`TcpInlet::unpause`]
     pub fn paused(mut self) -> Self {
         self.is_paused = true;
@@ -92,6 +190,14 @@ pub struct TcpOutletOptions {
     pub(super) incoming_access_control: Arc<dyn IncomingAccessControl>,
     pub(super) outgoing_access_control: Arc<dyn OutgoingAccessControl>,
     pub(super) tls: bool,
+    pub(super) tls_verification: PortalTlsVerification,
+    pub(crate) socket_options: TcpSocketOptions,
+    pub(super) compressions: Vec<PortalCompression>,
+    pub(super) rate_limiter: Option<Arc<TokenBucket>>,
+    pub(super) egress_allow_list: Option<Arc<RwLock<TcpEgressAllowList>>>,
+    pub(super) proxy_protocol: bool,
+    pub(super) peer_identifier: Option<Arc<dyn PortalPeerIdentifier>>,
+    pub(super) resumption: Option<Duration>,
 }
 
 impl TcpOutletOptions {
@@ -102,9 +208,64 @@ impl TcpOutletOptions {
             incoming_access_control: Arc::new(AllowAll),
             outgoing_access_control: Arc::new(AllowAll),
             tls: false,
+            tls_verification: PortalTlsVerification::new(),
+            socket_options: TcpSocketOptions::new(),
+            compressions: vec![],
+            rate_limiter: None,
+            egress_allow_list: None,
+            proxy_protocol: false,
+            peer_identifier: None,
+            resumption: None,
         }
     }
 
+    /// Keep the connections to the target open when the route to an Inlet breaks, for at most
+    /// the given grace period, so that the Inlet can resume them over a new route
+    pub fn with_resumption(mut self, grace_period: Duration) -> Self {
+        self.resumption = Some(grace_period);
+        self
+    }
+
+    /// Send a PROXY protocol v2 header at the start of each connection to the target, with
+    /// the address of the client of the Inlet when the Inlet sent it
+    pub fn with_proxy_protocol(mut self, proxy_protocol: bool) -> Self {
+        self.proxy_protocol = proxy_protocol;
+        self
+    }
+
+    /// Identify the Inlet of each connection, for example with the identifier of its secure
+    /// channel. The identifier is added to the PROXY protocol header as a
+    /// [`PROXY_PROTOCOL_OCKAM_IDENTIFIER_TLV`](crate::PROXY_PROTOCOL_OCKAM_IDENTIFIER_TLV)
+    pub fn with_peer_identifier(mut self, peer_identifier: impl PortalPeerIdentifier) -> Self {
+        self.peer_identifier = Some(Arc::new(peer_identifier));
+        self
+    }
+
+    /// Only connect to the target if it's allowed by the given allow-list.
+    /// The allow-list is shared with the caller so that it can be updated while the Outlet runs
+    pub fn with_egress_allow_list(
+        mut self,
+        egress_allow_list: Arc<RwLock<TcpEgressAllowList>>,
+    ) -> Self {
+        self.egress_allow_list = Some(egress_allow_list);
+        self
+    }
+
+    /// Limit the throughput of all the connections of the Outlet to its target
+    pub fn with_rate_limit(mut self, rate_limit: TcpPortalRateLimit) -> Self {
+        self.rate_limiter = Some(Arc::new(TokenBucket::new(rate_limit)));
+        self
+    }
+
+    /// Accept to compress the payloads with the given algorithm, when an Inlet proposes it.
+    /// Can be called several times to accept several algorithms
+    pub fn with_compression(mut self, compression: PortalCompression) -> Self {
+        if !self.compressions.contains(&compression) {
+            self.compressions.push(compression);
+        }
+        self
+    }
+
     /// Set Incoming Access Control
     pub fn with_incoming_access_control_impl(
         mut self,
@@ -129,6 +290,20 @@ impl TcpOutletOptions {
         self
     }
 
+    /// Connect to the target using TLS, verifying its certificate with the given options
+    pub fn with_tls_verification(mut self, tls_verification: PortalTlsVerification) -> Self {
+        self.tls = true;
+        self.tls_verification = tls_verification;
+        self
+    }
+
+    /// Set the socket options of the connections to the target of the Outlet.
+    /// The options which are not set are taken from the [`TcpTransport`](crate::TcpTransport)
+    pub fn with_socket_options(mut self, socket_options: TcpSocketOptions) -> Self {
+        self.socket_options = socket_options;
+        self
+    }
+
     /// Set Outgoing Access Control
     pub fn with_outgoing_access_control_impl(
         mut self,
@@ -181,6 +356,13 @@ impl TcpOutletOptions {
         {
             flow_controls.add_consumer(addresses.sender_remote.clone(), &producer_flow_control_id);
         }
+
+        // A resumed connection receives messages from a new producer, spawned like the first one
+        if self.resumption.is_some() {
+            for id in &self.consumer {
+                flow_controls.add_consumer(addresses.sender_remote.clone(), id);
+            }
+        }
     }
 }
 
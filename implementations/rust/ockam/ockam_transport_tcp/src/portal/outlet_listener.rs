@@ -1,5 +1,6 @@
 use crate::portal::addresses::{Addresses, PortalType};
-use crate::{portal::TcpPortalWorker, PortalMessage, TcpOutletOptions, TcpRegistry};
+use crate::portal::{ProxyProtocolClient, TcpPortalWorker};
+use crate::{PortalCompression, PortalMessage, TcpOutletOptions, TcpRegistry};
 use ockam_core::{async_trait, Address, DenyAll, NeutralMessage, Result, Routed, Worker};
 use ockam_node::{Context, WorkerBuilder};
 use ockam_transport_core::{HostnamePort, TransportError};
@@ -34,6 +35,12 @@ impl TcpOutletListenWorker {
         hostname_port: HostnamePort,
         options: TcpOutletOptions,
     ) -> Result<()> {
+        if options.tls {
+            options.tls_verification.validate()?;
+        }
+        if let Some(egress_allow_list) = &options.egress_allow_list {
+            egress_allow_list.read().unwrap().validate()?;
+        }
         let access_control = options.incoming_access_control.clone();
 
         options.setup_flow_control_for_outlet_listener(ctx.flow_controls(), &address);
@@ -50,6 +57,16 @@ impl TcpOutletListenWorker {
     }
 }
 
+impl TcpOutletListenWorker {
+    /// Only use the compression proposed by the Inlet if this Outlet accepts it
+    fn accepted_compression(&self, compression: PortalCompression) -> Option<PortalCompression> {
+        self.options
+            .compressions
+            .contains(&compression)
+            .then_some(compression)
+    }
+}
+
 #[async_trait]
 impl Worker for TcpOutletListenWorker {
     type Context = Context;
@@ -77,12 +94,30 @@ impl Worker for TcpOutletListenWorker {
     ) -> Result<()> {
         let return_route = msg.return_route();
         let src_addr = msg.src_addr();
+        let peer_identifier = match &self.options.peer_identifier {
+            Some(peer_identifier) if self.options.proxy_protocol => {
+                peer_identifier.peer_identifier(msg.local_message().local_info_ref())
+            }
+            _ => None,
+        };
         let body = msg.into_body()?.into_vec();
         let msg = PortalMessage::decode(&body)?;
 
-        if !matches!(msg, PortalMessage::Ping) {
-            return Err(TransportError::Protocol)?;
-        }
+        let (compression, client_address) = match msg {
+            PortalMessage::Ping => (None, None),
+            PortalMessage::PingWithCompression(compression) => {
+                (self.accepted_compression(compression), None)
+            }
+            PortalMessage::PingWithClient(compression, client_address) => (
+                compression.and_then(|compression| self.accepted_compression(compression)),
+                Some(client_address),
+            ),
+            _ => return Err(TransportError::Protocol)?,
+        };
+        let proxy_protocol_client = self.options.proxy_protocol.then(|| ProxyProtocolClient {
+            address: client_address,
+            identifier: peer_identifier,
+        });
 
         let addresses = Addresses::generate(PortalType::Outlet);
 
@@ -93,7 +128,15 @@ impl Worker for TcpOutletListenWorker {
             ctx,
             self.registry.clone(),
             self.hostname_port.clone(),
-            self.options.tls,
+            self.options
+                .tls
+                .then(|| self.options.tls_verification.clone()),
+            self.options.socket_options.clone(),
+            compression,
+            self.options.rate_limiter.clone(),
+            self.options.egress_allow_list.clone(),
+            proxy_protocol_client,
+            self.options.resumption,
             return_route.clone(),
             addresses.clone(),
             self.options.incoming_access_control.clone(),
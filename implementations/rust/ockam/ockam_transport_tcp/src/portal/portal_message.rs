@@ -1,4 +1,6 @@
+use crate::PortalCompression;
 use ockam_core::bare::{read_slice, write_slice};
+use ockam_core::compat::net::{IpAddr, SocketAddr};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Encodable, Encoded, Message, NeutralMessage};
 use serde::{Deserialize, Serialize};
@@ -15,6 +17,23 @@ pub enum PortalMessage<'de> {
     Disconnect,
     /// Message with binary payload and packet counter
     Payload(&'de [u8], Option<u16>),
+    /// First message that Inlet sends to the Outlet, proposing a compression algorithm.
+    /// Encoded as a [`PortalMessage::Ping`] followed by the algorithm code, so that
+    /// older Outlets see a regular Ping
+    PingWithCompression(PortalCompression),
+    /// First message that Outlet sends to the Inlet, accepting the proposed compression
+    /// algorithm. Encoded as a [`PortalMessage::Pong`] followed by the algorithm code
+    PongWithCompression(PortalCompression),
+    /// Message with a binary payload compressed with the negotiated algorithm
+    CompressedPayload(&'de [u8]),
+    /// First message that Inlet sends to the Outlet, with the address of the client of the
+    /// Inlet and the compression algorithm it proposes, if any.
+    /// Encoded as a [`PortalMessage::PingWithCompression`] followed by the address, the
+    /// algorithm code being 0 when no compression is proposed
+    PingWithClient(Option<PortalCompression>, SocketAddr),
+    /// Message sent when a connection resumes on a new route, with the number of bytes
+    /// received so far. The other side sends again the bytes which were lost in the meantime
+    Resume(u64),
 }
 
 impl<'de> PortalMessage<'de> {
@@ -30,8 +49,23 @@ impl<'de> PortalMessage<'de> {
         let enum_variant = slice.get(0)?;
         let mut index = 1;
         match enum_variant {
-            0 => Some(PortalMessage::Ping),
-            1 => Some(PortalMessage::Pong),
+            0 => {
+                let compression = slice.get(1).and_then(|c| PortalCompression::from_code(*c));
+                let client = slice.get(2..).and_then(Self::decode_client_address);
+                match (compression, client) {
+                    (compression, Some(client)) => {
+                        Some(PortalMessage::PingWithClient(compression, client))
+                    }
+                    (Some(compression), None) => {
+                        Some(PortalMessage::PingWithCompression(compression))
+                    }
+                    (None, None) => Some(PortalMessage::Ping),
+                }
+            }
+            1 => match slice.get(1).and_then(|c| PortalCompression::from_code(*c)) {
+                Some(compression) => Some(PortalMessage::PongWithCompression(compression)),
+                None => Some(PortalMessage::Pong),
+            },
             2 => Some(PortalMessage::Disconnect),
             3 => {
                 if let Some(payload) = read_slice(slice, &mut index) {
@@ -53,10 +87,45 @@ impl<'de> PortalMessage<'de> {
                     None
                 }
             }
+            4 => read_slice(slice, &mut index).map(PortalMessage::CompressedPayload),
+            5 => {
+                let received: [u8; 8] = slice.get(1..9)?.try_into().ok()?;
+                Some(PortalMessage::Resume(u64::from_le_bytes(received)))
+            }
             _ => None,
         }
     }
 
+    /// Decode an address encoded as an IP version, the IP address and the port
+    fn decode_client_address(slice: &[u8]) -> Option<SocketAddr> {
+        let (ip, port) = match slice.first()? {
+            4 => (
+                IpAddr::from(<[u8; 4]>::try_from(slice.get(1..5)?).ok()?),
+                slice.get(5..7)?,
+            ),
+            6 => (
+                IpAddr::from(<[u8; 16]>::try_from(slice.get(1..17)?).ok()?),
+                slice.get(17..19)?,
+            ),
+            _ => return None,
+        };
+        Some(SocketAddr::new(ip, u16::from_be_bytes([port[0], port[1]])))
+    }
+
+    fn encode_client_address(vec: &mut Vec<u8>, client: &SocketAddr) {
+        match client.ip() {
+            IpAddr::V4(ip) => {
+                vec.push(4);
+                vec.extend_from_slice(&ip.octets());
+            }
+            IpAddr::V6(ip) => {
+                vec.push(6);
+                vec.extend_from_slice(&ip.octets());
+            }
+        }
+        vec.extend_from_slice(&client.port().to_be_bytes());
+    }
+
     /// Shortcut to encode a PortalMessage into a NeutralMessage
     pub fn to_neutral_message(self) -> ockam_core::Result<NeutralMessage> {
         Ok(NeutralMessage::from(self.encode()?))
@@ -93,6 +162,27 @@ impl PortalMessage<'_> {
                 // }
                 Ok(vec)
             }
+            PortalMessage::PingWithCompression(compression) => Ok(vec![0, compression.code()]),
+            PortalMessage::PongWithCompression(compression) => Ok(vec![1, compression.code()]),
+            PortalMessage::PingWithClient(compression, client) => {
+                let mut vec = vec![0, compression.map(|c| c.code()).unwrap_or(0)];
+                Self::encode_client_address(&mut vec, &client);
+                Ok(vec)
+            }
+            PortalMessage::CompressedPayload(payload) => {
+                let capacity = 1
+                    + payload.len()
+                    + ockam_core::bare::size_of_variable_length(payload.len() as u64);
+                let mut vec = Vec::with_capacity(capacity);
+                vec.push(4);
+                write_slice(&mut vec, payload);
+                Ok(vec)
+            }
+            PortalMessage::Resume(received) => {
+                let mut vec = vec![5];
+                vec.extend_from_slice(&received.to_le_bytes());
+                Ok(vec)
+            }
         }
     }
 }
@@ -109,7 +199,7 @@ pub const MAX_PAYLOAD_SIZE: usize = 48 * 1024;
 
 #[cfg(test)]
 mod test {
-    use crate::PortalMessage;
+    use crate::{PortalCompression, PortalMessage};
     use ockam_core::Message;
     use ockam_core::{Decodable, Encodable};
     use serde::{Deserialize, Serialize};
@@ -207,4 +297,58 @@ mod test {
             panic!("Decoded message is not a Payload");
         }
     }
+
+    #[test]
+    fn compression_messages_can_be_decoded() {
+        let payload = "hello".as_bytes().to_vec();
+
+        for compression in [PortalCompression::Lz4, PortalCompression::Zstd] {
+            let encoded = PortalMessage::PingWithCompression(compression)
+                .encode()
+                .unwrap();
+            let decoded = PortalMessage::decode(&encoded).unwrap();
+            assert_eq!(decoded, PortalMessage::PingWithCompression(compression));
+
+            let encoded = PortalMessage::PongWithCompression(compression)
+                .encode()
+                .unwrap();
+            let decoded = PortalMessage::decode(&encoded).unwrap();
+            assert_eq!(decoded, PortalMessage::PongWithCompression(compression));
+        }
+
+        let encoded = PortalMessage::CompressedPayload(&payload).encode().unwrap();
+        let decoded = PortalMessage::decode(&encoded).unwrap();
+        assert_eq!(decoded, PortalMessage::CompressedPayload(&payload));
+
+        // unknown algorithms are ignored
+        let decoded = PortalMessage::decode(&[0, 99]).unwrap();
+        assert_eq!(decoded, PortalMessage::Ping);
+    }
+
+    #[test]
+    fn ping_with_client_can_be_decoded() {
+        for client in ["10.0.0.1:51000", "[fd00::1]:443"] {
+            let client = client.parse().unwrap();
+            for compression in [None, Some(PortalCompression::Zstd)] {
+                let encoded = PortalMessage::PingWithClient(compression, client)
+                    .encode()
+                    .unwrap();
+                let decoded = PortalMessage::decode(&encoded).unwrap();
+                assert_eq!(decoded, PortalMessage::PingWithClient(compression, client));
+            }
+        }
+
+        // an invalid address is ignored
+        let decoded = PortalMessage::decode(&[0, 0, 5, 1]).unwrap();
+        assert_eq!(decoded, PortalMessage::Ping);
+    }
+
+    #[test]
+    fn resume_can_be_decoded() {
+        let encoded = PortalMessage::Resume(1 << 40).encode().unwrap();
+        let decoded = PortalMessage::decode(&encoded).unwrap();
+        assert_eq!(decoded, PortalMessage::Resume(1 << 40));
+
+        assert!(PortalMessage::decode(&[5, 1, 2]).is_err());
+    }
 }
@@ -1,6 +1,11 @@
 use crate::portal::addresses::Addresses;
 use crate::portal::portal_message::MAX_PAYLOAD_SIZE;
-use crate::{PortalInternalMessage, PortalMessage, TcpRegistry};
+use crate::portal::{
+    resumed_route, ConnectionActivity, HttpRequestRewriter, ResumedRoute, ResumptionEvent,
+    ResumptionReceiver, TokenBucket,
+};
+use crate::{PortalCompression, PortalInternalMessage, PortalMessage, TcpPortalStats, TcpRegistry};
+use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::{
     async_trait, Encodable, LocalMessage, OpenTelemetryContext, Route, OCKAM_TRACER_NAME,
@@ -11,7 +16,7 @@ use opentelemetry::global;
 use opentelemetry::trace::Tracer;
 use tokio::io::AsyncRead;
 use tokio::io::AsyncReadExt;
-use tracing::{error, instrument, warn};
+use tracing::{debug, error, info, instrument, warn};
 
 /// A TCP Portal receiving message processor
 ///
@@ -25,15 +30,36 @@ pub(crate) struct TcpPortalRecvProcessor<R> {
     addresses: Addresses,
     onward_route: Route,
     payload_packet_counter: u16,
+    stats: Option<Arc<TcpPortalStats>>,
+    compression: Option<PortalCompression>,
+    rate_limiter: Option<Arc<TokenBucket>>,
+    http_rewriter: Option<HttpRequestRewriter>,
+    activity: Option<Arc<ConnectionActivity>>,
+    /// Sent data and new routes of a connection which can be resumed
+    resumption: Option<ResumptionReceiver>,
+}
+
+/// Result of sending again the data lost by a suspended connection
+enum Replay {
+    Resumed,
+    Suspended,
+    Lost,
 }
 
 impl<R: AsyncRead + Unpin + Send + Sync + 'static> TcpPortalRecvProcessor<R> {
     /// Create a new `TcpPortalRecvProcessor`
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         registry: TcpRegistry,
         read_half: R,
         addresses: Addresses,
         onward_route: Route,
+        stats: Option<Arc<TcpPortalStats>>,
+        compression: Option<PortalCompression>,
+        rate_limiter: Option<Arc<TokenBucket>>,
+        http_rewriter: Option<HttpRequestRewriter>,
+        activity: Option<Arc<ConnectionActivity>>,
+        resumption: Option<ResumptionReceiver>,
     ) -> Self {
         Self {
             registry,
@@ -42,6 +68,43 @@ impl<R: AsyncRead + Unpin + Send + Sync + 'static> TcpPortalRecvProcessor<R> {
             addresses,
             onward_route,
             payload_packet_counter: 0,
+            stats,
+            compression,
+            rate_limiter,
+            http_rewriter,
+            activity,
+            resumption,
+        }
+    }
+
+    /// Read from the connection. Nothing is read if the connection stays idle for too long,
+    /// which closes it like a disconnection from the client
+    async fn read(&mut self) -> std::io::Result<usize> {
+        let activity = match &self.activity {
+            Some(activity) => activity.clone(),
+            None => return self.read_half.read_buf(&mut self.buf).await,
+        };
+
+        loop {
+            // The remaining time is extended by the data sent to the connection in the meantime
+            let remaining = activity.remaining();
+            if remaining.is_zero() {
+                debug!(
+                    "Closing the idle Tcp Portal connection at: {}",
+                    self.addresses.receiver_remote
+                );
+                if let Some(stats) = &self.stats {
+                    stats.connection_idle_timed_out();
+                }
+                return Ok(0);
+            }
+
+            if let Ok(result) =
+                tokio::time::timeout(remaining, self.read_half.read_buf(&mut self.buf)).await
+            {
+                activity.touch();
+                return result;
+            }
         }
     }
 }
@@ -69,7 +132,28 @@ impl<R: AsyncRead + Unpin + Send + Sync + 'static> Processor for TcpPortalRecvPr
     async fn process(&mut self, ctx: &mut Context) -> Result<bool> {
         self.buf.clear();
 
-        let _len = match self.read_half.read_buf(&mut self.buf).await {
+        let read = match self.resumption.take() {
+            None => self.read().await,
+            // The reading is interrupted when the connection must resume on a new route
+            Some(mut resumption) => {
+                let read = tokio::select! {
+                    read = self.read() => read,
+                    event = resumption.next_event() => {
+                        let resumed = self.handle_resumption_event(ctx, &mut resumption, event)
+                            .await?;
+                        self.resumption = Some(resumption);
+                        if !resumed {
+                            self.notify_disconnection(ctx).await?;
+                        }
+                        return Ok(resumed);
+                    }
+                };
+                self.resumption = Some(resumption);
+                read
+            }
+        };
+
+        let _len = match read {
             Ok(len) => len,
             Err(err) => {
                 error!("Tcp Portal connection read failed with error: {}", err);
@@ -77,56 +161,275 @@ impl<R: AsyncRead + Unpin + Send + Sync + 'static> Processor for TcpPortalRecvPr
             }
         };
 
+        if self.buf.is_empty() {
+            self.notify_disconnection(ctx).await?;
+            return Ok(false);
+        }
+
+        if let Some(stats) = &self.stats {
+            stats.add_bytes_received(self.buf.len());
+        }
+
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.consume(self.buf.len()).await;
+        }
+
+        let rewritten = match &mut self.http_rewriter {
+            Some(http_rewriter) => match http_rewriter.rewrite(&self.buf) {
+                Ok(data) => Some(data),
+                Err(err) => {
+                    error!(
+                        "Tcp Portal connection sent an invalid HTTP request: {}",
+                        err
+                    );
+                    return Ok(false);
+                }
+            },
+            None => None,
+        };
+        let buf = core::mem::take(&mut self.buf);
+        let data = rewritten.as_deref().unwrap_or(&buf[..]);
+
+        // Keep the data until the other side received it, in case the connection is suspended
+        if let Some(resumption) = &mut self.resumption {
+            resumption.buffer.push(data);
+        }
+
+        let tracing_context = Self::tracing_context();
+        let mut result = Ok(());
+        // Loop just in case buf was extended, or if the HTTP request head was rewritten
+        for chunk in data.chunks(MAX_PAYLOAD_SIZE) {
+            result = self.forward_chunk(ctx, chunk, &tracing_context).await;
+            if result.is_err() {
+                break;
+            }
+        }
+        self.buf = buf;
+
+        match (result, self.resumption.take()) {
+            (Ok(()), resumption) => {
+                self.resumption = resumption;
+                Ok(true)
+            }
+            (Err(err), None) => Err(err),
+            // The route to the other side is broken, wait for a new one
+            (Err(err), Some(mut resumption)) => {
+                debug!("Tcp Portal connection suspended after an error: {}", err);
+                let resumed = self.suspend(ctx, &mut resumption).await?;
+                self.resumption = Some(resumption);
+                if !resumed {
+                    self.notify_disconnection(ctx).await?;
+                }
+                Ok(resumed)
+            }
+        }
+    }
+}
+
+impl<R: AsyncRead + Unpin + Send + Sync + 'static> TcpPortalRecvProcessor<R> {
+    fn tracing_context() -> OpenTelemetryContext {
         let tracer = global::tracer(OCKAM_TRACER_NAME);
-        let tracing_context = tracer.in_span("TcpPortalRecvProcessor::forward_message", |cx| {
+        tracer.in_span("TcpPortalRecvProcessor::forward_message", |cx| {
             OpenTelemetryContext::inject(&cx)
-        });
+        })
+    }
 
-        if self.buf.is_empty() {
-            // Notify Sender that connection was closed
-            ctx.set_tracing_context(tracing_context.clone());
-            if let Err(err) = ctx
-                .send_from_address(
-                    route![self.addresses.sender_internal.clone()],
-                    PortalInternalMessage::Disconnect,
-                    self.addresses.receiver_internal.clone(),
-                )
-                .await
-            {
-                warn!(
-                    "Error notifying Tcp Portal Sender about dropped connection {}",
-                    err
-                );
+    /// Send a chunk of data read from the connection to the other side
+    async fn forward_chunk(
+        &mut self,
+        ctx: &Context,
+        chunk: &[u8],
+        tracing_context: &OpenTelemetryContext,
+    ) -> Result<()> {
+        // Payloads which don't shrink once compressed are sent as they are
+        let compressed = match self.compression {
+            Some(compression) => Some(compression.compress(chunk)?),
+            None => None,
+        };
+        let payload = match &compressed {
+            Some(compressed) if compressed.len() < chunk.len() => {
+                PortalMessage::CompressedPayload(compressed).encode()?
             }
+            _ => PortalMessage::Payload(chunk, Some(self.payload_packet_counter)).encode()?,
+        };
+        let msg = LocalMessage::new()
+            .with_tracing_context(tracing_context.clone())
+            .with_onward_route(self.onward_route.clone())
+            .with_return_route(route![self.addresses.sender_remote.clone()])
+            .with_payload(payload);
+
+        self.payload_packet_counter += 1;
+        ctx.forward_from_address(msg, self.addresses.receiver_remote.clone())
+            .await
+    }
 
-            ctx.forward_from_address(
+    /// Notify the Sender and the other side that the connection was closed
+    async fn notify_disconnection(&self, ctx: &mut Context) -> Result<()> {
+        let tracing_context = Self::tracing_context();
+        ctx.set_tracing_context(tracing_context.clone());
+        if let Err(err) = ctx
+            .send_from_address(
+                route![self.addresses.sender_internal.clone()],
+                PortalInternalMessage::Disconnect,
+                self.addresses.receiver_internal.clone(),
+            )
+            .await
+        {
+            warn!(
+                "Error notifying Tcp Portal Sender about dropped connection {}",
+                err
+            );
+        }
+
+        let result = ctx
+            .forward_from_address(
                 LocalMessage::new()
-                    .with_tracing_context(tracing_context.clone())
+                    .with_tracing_context(tracing_context)
                     .with_onward_route(self.onward_route.clone())
                     .with_return_route(route![self.addresses.sender_remote.clone()])
                     .with_payload(PortalMessage::Disconnect.encode()?),
                 self.addresses.receiver_remote.clone(),
             )
-            .await?;
+            .await;
+        match result {
+            // The other side can't be reached if the connection couldn't be resumed
+            Err(err) if self.resumption.is_some() => {
+                debug!(
+                    "The other side wasn't notified about the disconnection: {}",
+                    err
+                );
+                Ok(())
+            }
+            result => result,
+        }
+    }
 
-            return Ok(false);
+    /// Return true if the connection can go on after a resumption event
+    async fn handle_resumption_event(
+        &mut self,
+        ctx: &Context,
+        resumption: &mut ResumptionReceiver,
+        event: ResumptionEvent,
+    ) -> Result<bool> {
+        match event {
+            ResumptionEvent::OutletRoute(outlet_route) => {
+                self.announce(ctx, resumption, outlet_route).await?;
+                self.suspend(ctx, resumption).await
+            }
+            ResumptionEvent::Resumed(resumed) => {
+                match self.replay(ctx, resumption, resumed).await? {
+                    Replay::Resumed => Ok(true),
+                    Replay::Suspended => self.suspend(ctx, resumption).await,
+                    Replay::Lost => Ok(false),
+                }
+            }
         }
+    }
 
-        // Loop just in case buf was extended (should not happen though)
-        for chunk in self.buf.chunks(MAX_PAYLOAD_SIZE) {
-            let msg = LocalMessage::new()
-                .with_tracing_context(tracing_context.clone())
-                .with_onward_route(self.onward_route.clone())
-                .with_return_route(route![self.addresses.sender_remote.clone()])
-                .with_payload(
-                    PortalMessage::Payload(chunk, Some(self.payload_packet_counter)).encode()?,
-                );
+    /// Wait until the connection is resumed on a new route, for at most the grace period.
+    /// Return false if it wasn't resumed
+    async fn suspend(
+        &mut self,
+        ctx: &Context,
+        resumption: &mut ResumptionReceiver,
+    ) -> Result<bool> {
+        info!(
+            "Tcp Portal connection at: {} suspended until it's resumed",
+            self.addresses.receiver_remote
+        );
+        let deadline = tokio::time::Instant::now() + resumption.state.grace_period();
+        loop {
+            let event = match tokio::time::timeout_at(deadline, resumption.next_event()).await {
+                Ok(event) => event,
+                Err(_) => {
+                    warn!(
+                        "Tcp Portal connection at: {} wasn't resumed in time",
+                        self.addresses.receiver_remote
+                    );
+                    return Ok(false);
+                }
+            };
+            match event {
+                ResumptionEvent::OutletRoute(outlet_route) => {
+                    self.announce(ctx, resumption, outlet_route).await?
+                }
+                ResumptionEvent::Resumed(resumed) => {
+                    match self.replay(ctx, resumption, resumed).await? {
+                        Replay::Resumed => return Ok(true),
+                        Replay::Suspended => {}
+                        Replay::Lost => return Ok(false),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Ask the other side to resume the connection on a new route to the Outlet
+    async fn announce(
+        &self,
+        ctx: &Context,
+        resumption: &ResumptionReceiver,
+        outlet_route: Route,
+    ) -> Result<()> {
+        let route = resumed_route(&outlet_route, &self.onward_route)?;
+        // Accept the answer of the other side, like for the first route
+        let flow_controls = ctx.flow_controls();
+        if let Some(flow_control_id) = flow_controls
+            .find_flow_control_with_producer_address(route.next()?)
+            .map(|x| x.flow_control_id().clone())
+        {
+            flow_controls.add_consumer(self.addresses.sender_remote.clone(), &flow_control_id);
+        }
 
-            self.payload_packet_counter += 1;
-            ctx.forward_from_address(msg, self.addresses.receiver_remote.clone())
-                .await?;
+        let msg = LocalMessage::new()
+            .with_tracing_context(Self::tracing_context())
+            .with_onward_route(route.clone())
+            .with_return_route(route![self.addresses.sender_remote.clone()])
+            .with_payload(PortalMessage::Resume(resumption.state.announce()).encode()?);
+        if let Err(err) = ctx
+            .forward_from_address(msg, self.addresses.receiver_remote.clone())
+            .await
+        {
+            // Another route may still be set before the end of the grace period
+            warn!(
+                "Tcp Portal connection couldn't be resumed on {}: {}",
+                route, err
+            );
         }
+        Ok(())
+    }
 
-        Ok(true)
+    /// Continue on the route of the other side, sending again the data it didn't receive
+    async fn replay(
+        &mut self,
+        ctx: &Context,
+        resumption: &ResumptionReceiver,
+        resumed: ResumedRoute,
+    ) -> Result<Replay> {
+        self.onward_route = resumed.route;
+        let data = match resumption.buffer.since(resumed.peer_received) {
+            Some(data) => data,
+            None => {
+                warn!(
+                    "Tcp Portal connection at: {} lost too much data to be resumed",
+                    self.addresses.receiver_remote
+                );
+                return Ok(Replay::Lost);
+            }
+        };
+
+        let tracing_context = Self::tracing_context();
+        for chunk in data.chunks(MAX_PAYLOAD_SIZE) {
+            if let Err(err) = self.forward_chunk(ctx, chunk, &tracing_context).await {
+                debug!("Tcp Portal connection suspended after an error: {}", err);
+                return Ok(Replay::Suspended);
+            }
+        }
+        info!(
+            "Tcp Portal connection at: {} resumed, {} bytes sent again",
+            self.addresses.receiver_remote,
+            data.len()
+        );
+        Ok(Replay::Resumed)
     }
 }
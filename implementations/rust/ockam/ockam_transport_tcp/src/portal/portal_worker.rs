@@ -1,21 +1,36 @@
 use crate::portal::addresses::{Addresses, PortalType};
 use crate::portal::portal_worker::ReadHalfMaybeTls::{ReadHalfNoTls, ReadHalfWithTls};
 use crate::portal::portal_worker::WriteHalfMaybeTls::{WriteHalfNoTls, WriteHalfWithTls};
+use crate::portal::{
+    ConnectionActivity, ConnectionPermit, ConnectionResumption, HttpRequestRewriter,
+    ProxyProtocolClient, ProxyProtocolHeader, ResumptionReceiver, RoutePermit,
+    TcpPortalRecvProcessor, TokenBucket,
+};
 use crate::transport::{connect, connect_tls};
-use crate::{portal::TcpPortalRecvProcessor, PortalInternalMessage, PortalMessage, TcpRegistry};
-use ockam_core::compat::{boxed::Box, sync::Arc};
+use crate::{
+    HttpRewrite, PortalCompression, PortalInternalMessage, PortalMessage, PortalTlsVerification,
+    TcpEgressAllowList, TcpPortalStats, TcpRegistry, TcpSocketOptions,
+};
+use ockam_core::compat::{
+    boxed::Box,
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+    vec::Vec,
+};
+use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{
     async_trait, AllowOnwardAddress, AllowSourceAddress, Decodable, DenyAll, IncomingAccessControl,
     Mailbox, Mailboxes, OutgoingAccessControl,
 };
 use ockam_core::{Any, Result, Route, Routed, Worker};
-use ockam_node::{Context, ProcessorBuilder, WorkerBuilder};
+use ockam_node::{Context, NodeEvent, ProcessorBuilder, WorkerBuilder};
 use ockam_transport_core::{HostnamePort, TransportError};
 use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncWriteExt, ReadHalf, WriteHalf};
 use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::TcpStream;
-use tokio_rustls::TlsStream;
+use tokio::sync::watch;
+use tokio_rustls::{TlsAcceptor, TlsStream};
 use tracing::{debug, info, instrument, trace, warn};
 
 /// Enumerate all `TcpPortalWorker` states
@@ -24,6 +39,8 @@ use tracing::{debug, info, instrument, trace, warn};
 ///
 /// `Outlet`: `SendPong` -> `Initialized`
 /// `Inlet`: `SendPing` -> `ReceivePong` -> `Initialized`
+///
+/// When an Inlet terminates TLS, the handshake with its client is done before sending the ping
 #[derive(Clone)]
 enum State {
     SendPing { ping_route: Route },
@@ -50,7 +67,52 @@ pub(crate) struct TcpPortalWorker {
     portal_type: PortalType,
     last_received_packet_counter: u16,
     outgoing_access_control: Arc<dyn OutgoingAccessControl>,
-    is_tls: bool,
+    /// Verification of the certificate of the target of an outlet, if it's reached using TLS
+    tls_verification: Option<PortalTlsVerification>,
+    /// Connection accepted by an inlet terminating TLS, until the TLS handshake is done
+    pending_tls_stream: Option<PendingTlsStream>,
+    /// Socket options of the connection to the target of an outlet
+    socket_options: TcpSocketOptions,
+    /// Compression of the payloads: proposed by an inlet until the pong is received,
+    /// then negotiated with the other side
+    compression: Option<PortalCompression>,
+    /// Rate limit shared by all the connections of the inlet or outlet
+    rate_limiter: Option<Arc<TokenBucket>>,
+    /// Traffic counters of the inlet which accepted the connection
+    stats: Option<Arc<TcpPortalStats>>,
+    /// Rewriting of the HTTP requests sent by the client of an inlet
+    http_rewrite: Option<Arc<HttpRewrite>>,
+    /// Destinations an outlet is allowed to connect to
+    egress_allow_list: Option<Arc<RwLock<TcpEgressAllowList>>>,
+    /// Slot of the connection in the connection limits of an inlet, released when dropped
+    _connection_permit: Option<ConnectionPermit>,
+    /// Slot of the connection in the route chosen by a load balanced inlet, released when dropped
+    _route_permit: Option<RoutePermit>,
+    /// Last traffic of the connection, if an inlet closes idle connections
+    activity: Option<Arc<ConnectionActivity>>,
+    /// True if an inlet expects a PROXY protocol header at the start of the connection
+    accept_proxy_protocol: bool,
+    /// Address of the client of an inlet, sent to the outlet with the ping
+    client_address: Option<SocketAddr>,
+    /// Client of the inlet, described to the target of an outlet with a PROXY protocol header
+    proxy_protocol_client: Option<ProxyProtocolClient>,
+    /// State of the connection if it can be resumed on a new route
+    resumption: Option<Arc<ConnectionResumption>>,
+    /// Changes of the route to the outlet, given to the receiver of a resumable inlet
+    outlet_routes: Option<watch::Receiver<Route>>,
+    /// Number of bytes sent again by the other side which were already written
+    skip: u64,
+}
+
+/// Maximum duration of the TLS handshake with the client of an inlet
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum duration to receive the PROXY protocol header of a connection accepted by an inlet
+const PROXY_PROTOCOL_HEADER_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct PendingTlsStream {
+    acceptor: TlsAcceptor,
+    stream: TcpStream,
 }
 
 enum ReadHalfMaybeTls {
@@ -76,17 +138,42 @@ impl TcpPortalWorker {
         addresses: Addresses,
         incoming_access_control: Arc<dyn IncomingAccessControl>,
         outgoing_access_control: Arc<dyn OutgoingAccessControl>, // To propagate to the receiver
+        stats: Arc<TcpPortalStats>,
+        compression: Option<PortalCompression>,
+        rate_limiter: Option<Arc<TokenBucket>>,
+        http_rewrite: Option<Arc<HttpRewrite>>,
+        tls_acceptor: Option<TlsAcceptor>,
+        connection_permit: Option<ConnectionPermit>,
+        route_permit: Option<RoutePermit>,
+        idle_timeout: Option<Duration>,
+        accept_proxy_protocol: bool,
+        resumption: Option<Duration>,
+        outlet_routes: Option<watch::Receiver<Route>>,
     ) -> Result<()> {
         Self::start(
             ctx,
             registry,
             hostname_port,
-            false,
+            None,
+            TcpSocketOptions::new(),
+            compression,
+            rate_limiter,
             State::SendPing { ping_route },
             Some(stream),
             addresses,
             incoming_access_control,
             outgoing_access_control,
+            Some(stats),
+            http_rewrite,
+            tls_acceptor,
+            None,
+            connection_permit,
+            route_permit,
+            idle_timeout,
+            accept_proxy_protocol,
+            None,
+            resumption,
+            outlet_routes,
         )
         .await
     }
@@ -98,7 +185,13 @@ impl TcpPortalWorker {
         ctx: &Context,
         registry: TcpRegistry,
         hostname_port: HostnamePort,
-        tls: bool,
+        tls_verification: Option<PortalTlsVerification>,
+        socket_options: TcpSocketOptions,
+        compression: Option<PortalCompression>,
+        rate_limiter: Option<Arc<TokenBucket>>,
+        egress_allow_list: Option<Arc<RwLock<TcpEgressAllowList>>>,
+        proxy_protocol_client: Option<ProxyProtocolClient>,
+        resumption: Option<Duration>,
         pong_route: Route,
         addresses: Addresses,
         incoming_access_control: Arc<dyn IncomingAccessControl>,
@@ -108,12 +201,26 @@ impl TcpPortalWorker {
             ctx,
             registry,
             hostname_port,
-            tls,
+            tls_verification,
+            socket_options,
+            compression,
+            rate_limiter,
             State::SendPong { pong_route },
             None,
             addresses,
             incoming_access_control,
             outgoing_access_control,
+            None,
+            None,
+            None,
+            egress_allow_list,
+            None,
+            None,
+            None,
+            false,
+            proxy_protocol_client,
+            resumption,
+            None,
         )
         .await
     }
@@ -125,12 +232,26 @@ impl TcpPortalWorker {
         ctx: &Context,
         registry: TcpRegistry,
         hostname_port: HostnamePort,
-        is_tls: bool,
+        tls_verification: Option<PortalTlsVerification>,
+        socket_options: TcpSocketOptions,
+        compression: Option<PortalCompression>,
+        rate_limiter: Option<Arc<TokenBucket>>,
         state: State,
         stream: Option<TcpStream>,
         addresses: Addresses,
         incoming_access_control: Arc<dyn IncomingAccessControl>,
         outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        stats: Option<Arc<TcpPortalStats>>,
+        http_rewrite: Option<Arc<HttpRewrite>>,
+        tls_acceptor: Option<TlsAcceptor>,
+        egress_allow_list: Option<Arc<RwLock<TcpEgressAllowList>>>,
+        connection_permit: Option<ConnectionPermit>,
+        route_permit: Option<RoutePermit>,
+        idle_timeout: Option<Duration>,
+        accept_proxy_protocol: bool,
+        proxy_protocol_client: Option<ProxyProtocolClient>,
+        resumption: Option<Duration>,
+        outlet_routes: Option<watch::Receiver<Route>>,
     ) -> Result<()> {
         let portal_type = if stream.is_some() {
             PortalType::Inlet
@@ -143,16 +264,27 @@ impl TcpPortalWorker {
             addresses.sender_remote
         );
 
-        let (rx, tx) = match stream {
+        let client_address = stream.as_ref().and_then(|stream| stream.peer_addr().ok());
+        let mut pending_tls_stream = None;
+        let (rx, tx) = match (stream, tls_acceptor) {
             // A TcpStream is provided in case of an inlet
-            Some(s) => {
+            (Some(stream), Some(acceptor)) => {
+                debug!("Connected to {} (TLS handshake pending)", &hostname_port);
+                pending_tls_stream = Some(PendingTlsStream { acceptor, stream });
+                (None, None)
+            }
+            (Some(s), None) => {
                 debug!("Connected to {} (with no TLS)", &hostname_port);
                 let (rx, tx) = s.into_split();
                 (Some(ReadHalfNoTls(rx)), Some(WriteHalfNoTls(tx)))
             }
-            None => (None, None),
+            (None, _) => (None, None),
         };
-        debug!("The {} supports TLS: {}", portal_type.str(), is_tls);
+        debug!(
+            "The {} supports TLS: {}",
+            portal_type.str(),
+            tls_verification.is_some() || pending_tls_stream.is_some()
+        );
 
         let worker = Self {
             registry,
@@ -165,8 +297,25 @@ impl TcpPortalWorker {
             is_disconnecting: false,
             portal_type,
             last_received_packet_counter: u16::MAX,
-            is_tls,
+            tls_verification,
+            pending_tls_stream,
+            socket_options,
+            compression,
+            rate_limiter,
             outgoing_access_control: outgoing_access_control.clone(),
+            stats,
+            http_rewrite,
+            egress_allow_list,
+            _connection_permit: connection_permit,
+            _route_permit: route_permit,
+            activity: idle_timeout.map(|timeout| Arc::new(ConnectionActivity::new(timeout))),
+            accept_proxy_protocol,
+            client_address,
+            proxy_protocol_client,
+            resumption: resumption
+                .map(|grace_period| Arc::new(ConnectionResumption::new(grace_period))),
+            outlet_routes,
+            skip: 0,
         };
 
         let internal_mailbox = Mailbox::new(
@@ -228,6 +377,14 @@ impl TcpPortalWorker {
             rx,
             self.addresses.clone(),
             onward_route,
+            self.stats.clone(),
+            self.compression,
+            self.rate_limiter.clone(),
+            self.http_rewrite.clone().map(HttpRequestRewriter::new),
+            self.activity.clone(),
+            self.resumption
+                .clone()
+                .map(|state| ResumptionReceiver::new(state, self.outlet_routes.take())),
         );
 
         let remote = Mailbox::new(
@@ -350,12 +507,96 @@ impl TcpPortalWorker {
         Ok(())
     }
 
+    /// Read the PROXY protocol header sent by a load balancer in front of an inlet, and keep
+    /// the address of the original client
+    #[instrument(skip_all)]
+    async fn accept_proxy_protocol(&mut self) -> Result<()> {
+        if !self.accept_proxy_protocol {
+            return Ok(());
+        }
+
+        let header = match (&mut self.pending_tls_stream, &mut self.read_half) {
+            (Some(pending_tls_stream), _) => {
+                let read_header = ProxyProtocolHeader::read(&mut pending_tls_stream.stream);
+                tokio::time::timeout(PROXY_PROTOCOL_HEADER_TIMEOUT, read_header).await
+            }
+            (None, Some(ReadHalfNoTls(rx))) => {
+                let read_header = ProxyProtocolHeader::read(rx);
+                tokio::time::timeout(PROXY_PROTOCOL_HEADER_TIMEOUT, read_header).await
+            }
+            _ => return Err(TransportError::PortalInvalidState)?,
+        };
+        let header = match header {
+            Ok(Ok(header)) => header,
+            Ok(Err(err)) => {
+                warn!(
+                    "Invalid PROXY protocol header from {}: {}",
+                    self.hostname_port, err
+                );
+                return Err(err);
+            }
+            Err(_) => {
+                warn!(
+                    "PROXY protocol header from {} timed out",
+                    self.hostname_port
+                );
+                return Err(TransportError::ConnectionDrop)?;
+            }
+        };
+
+        // The LOCAL command, used by health checks, doesn't carry any client address
+        if let Some(source) = header.source() {
+            debug!(
+                "Connection from {} proxied for {}",
+                self.hostname_port, source
+            );
+            self.client_address = Some(source);
+        }
+        Ok(())
+    }
+
+    /// Perform the TLS handshake with the client of an inlet terminating TLS
+    #[instrument(skip_all)]
+    async fn accept_tls(&mut self) -> Result<()> {
+        let PendingTlsStream { acceptor, stream } = match self.pending_tls_stream.take() {
+            Some(pending_tls_stream) => pending_tls_stream,
+            None => return Ok(()),
+        };
+
+        let stream =
+            match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                Ok(Ok(stream)) => stream,
+                Ok(Err(err)) => {
+                    warn!("TLS handshake with {} failed: {}", self.hostname_port, err);
+                    return Err(TransportError::from(err))?;
+                }
+                Err(_) => {
+                    warn!("TLS handshake with {} timed out", self.hostname_port);
+                    return Err(TransportError::ConnectionDrop)?;
+                }
+            };
+        debug!("Accepted a TLS connection from {}", self.hostname_port);
+
+        let (rx, tx) = tokio::io::split(TlsStream::from(stream));
+        self.read_half = Some(ReadHalfWithTls(rx));
+        self.write_half = Some(WriteHalfWithTls(tx));
+        Ok(())
+    }
+
     #[instrument(skip_all)]
     async fn handle_send_ping(&self, ctx: &Context, ping_route: Route) -> Result<State> {
+        let ping = match (self.client_address, self.compression) {
+            (Some(client_address), compression) => {
+                PortalMessage::PingWithClient(compression, client_address)
+            }
+            (None, Some(compression)) => PortalMessage::PingWithCompression(compression),
+            (None, None) => PortalMessage::Ping,
+        };
+
         // Force creation of Outlet on the other side
         ctx.send_from_address(
             ping_route,
-            PortalMessage::Ping.to_neutral_message()?,
+            ping.to_neutral_message()?,
             self.addresses.sender_remote.clone(),
         )
         .await?;
@@ -371,14 +612,28 @@ impl TcpPortalWorker {
             // Should not happen
             return Err(TransportError::PortalInvalidState)?;
         }
-        if self.is_tls {
+        self.check_egress_allow_list()?;
+        let proxy_protocol_header = self.proxy_protocol_header()?;
+        if let Some(tls_verification) = &self.tls_verification {
             debug!("Connect to {} via TLS", &self.hostname_port);
-            let (rx, tx) = connect_tls(&self.hostname_port).await?;
+            let (rx, tx) = connect_tls(
+                &self.hostname_port,
+                &self.socket_options,
+                tls_verification,
+                proxy_protocol_header,
+            )
+            .await?;
             self.write_half = Some(WriteHalfWithTls(tx));
             self.read_half = Some(ReadHalfWithTls(rx));
         } else {
             debug!("Connect to {}", self.hostname_port);
-            let (rx, tx) = connect(self.hostname_port.to_socket_addr()?).await?;
+            let socket_address = self.hostname_port.to_socket_addr()?;
+            let (rx, mut tx) = connect(socket_address, &self.socket_options).await?;
+            if let Some(proxy_protocol_header) = proxy_protocol_header {
+                tx.write_all(&proxy_protocol_header)
+                    .await
+                    .map_err(TransportError::from)?;
+            }
             self.write_half = Some(WriteHalfNoTls(tx));
             self.read_half = Some(ReadHalfNoTls(rx));
         }
@@ -386,9 +641,13 @@ impl TcpPortalWorker {
         // Respond to Inlet before starting the processor but
         // after the connection has been established
         // to avoid a payload being sent before the pong
+        let pong = match self.compression {
+            Some(compression) => PortalMessage::PongWithCompression(compression),
+            None => PortalMessage::Pong,
+        };
         ctx.send_from_address(
             pong_route.clone(),
-            PortalMessage::Pong.to_neutral_message()?,
+            pong.to_neutral_message()?,
             self.addresses.sender_remote.clone(),
         )
         .await?;
@@ -403,8 +662,57 @@ impl TcpPortalWorker {
         debug!("Outlet at: {} sent pong", self.addresses.sender_internal);
 
         self.remote_route = Some(pong_route);
+        self.publish_connected(ctx);
         Ok(State::Initialized)
     }
+
+    /// PROXY protocol header sent to the target of an outlet, before any payload
+    fn proxy_protocol_header(&self) -> Result<Option<Vec<u8>>> {
+        match &self.proxy_protocol_client {
+            Some(client) => {
+                let destination = self.hostname_port.to_socket_addr()?;
+                Ok(Some(
+                    ProxyProtocolHeader::for_client(client, destination).encode(),
+                ))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Check that the target of an outlet is allowed, before connecting to it
+    fn check_egress_allow_list(&self) -> Result<()> {
+        let egress_allow_list = match &self.egress_allow_list {
+            Some(egress_allow_list) => egress_allow_list,
+            None => return Ok(()),
+        };
+        let socket_address = self.hostname_port.to_socket_addr()?;
+        let hostname = self.hostname_port.hostname();
+        if egress_allow_list
+            .read()
+            .unwrap()
+            .is_allowed(&hostname, &socket_address)
+        {
+            Ok(())
+        } else {
+            warn!(
+                "Outlet at: {} is not allowed to connect to {} ({})",
+                self.addresses.sender_internal, self.hostname_port, socket_address
+            );
+            Err(ockam_core::Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                format!("{} is not in the egress allow-list", self.hostname_port),
+            ))
+        }
+    }
+
+    /// Notify the subscribers of the node events that the portal connection is established
+    fn publish_connected(&self, ctx: &Context) {
+        ctx.publish_event(NodeEvent::PortalConnected {
+            address: self.addresses.sender_remote.clone(),
+            peer: self.hostname_port.to_string(),
+        });
+    }
 }
 
 #[async_trait]
@@ -418,6 +726,8 @@ impl Worker for TcpPortalWorker {
 
         match state {
             State::SendPing { ping_route } => {
+                self.accept_proxy_protocol().await?;
+                self.accept_tls().await?;
                 self.state = self.handle_send_ping(ctx, ping_route.clone()).await?;
             }
             State::SendPong { pong_route } => {
@@ -430,6 +740,9 @@ impl Worker for TcpPortalWorker {
 
         self.registry
             .add_portal_worker(&self.addresses.sender_remote);
+        if let Some(stats) = &self.stats {
+            stats.connection_opened();
+        }
 
         Ok(())
     }
@@ -438,6 +751,9 @@ impl Worker for TcpPortalWorker {
     async fn shutdown(&mut self, _ctx: &mut Self::Context) -> Result<()> {
         self.registry
             .remove_portal_worker(&self.addresses.sender_remote);
+        if let Some(stats) = &self.stats {
+            stats.connection_closed();
+        }
 
         Ok(())
     }
@@ -460,15 +776,23 @@ impl Worker for TcpPortalWorker {
         }
         let return_route = msg.return_route();
         let remote_packet = recipient != self.addresses.sender_internal;
-        let payload = msg.into_payload();
+        // The payload shares the buffer it was received or decrypted in, it is not copied
+        let payload = msg.into_local_message().into_payload_bytes();
 
         match state {
             State::ReceivePong => {
                 if !remote_packet {
                     return Err(TransportError::PortalInvalidState)?;
                 };
-                if PortalMessage::decode(&payload)? != PortalMessage::Pong {
-                    return Err(TransportError::Protocol)?;
+                // The compression is only used if the outlet accepted the proposed one
+                self.compression = match PortalMessage::decode(&payload)? {
+                    PortalMessage::Pong => None,
+                    PortalMessage::PongWithCompression(compression)
+                        if self.compression == Some(compression) =>
+                    {
+                        Some(compression)
+                    }
+                    _ => return Err(TransportError::Protocol)?,
                 };
                 self.handle_receive_pong(ctx, return_route).await
             }
@@ -482,16 +806,44 @@ impl Worker for TcpPortalWorker {
 
                 if remote_packet {
                     let msg = PortalMessage::decode(&payload)?;
+                    // Once a connection is resumed, the messages still arriving on the old route
+                    // are sent again on the new one
+                    if self.resumption.is_some()
+                        && !matches!(msg, PortalMessage::Resume(_))
+                        && self.remote_route.as_ref() != Some(&return_route)
+                    {
+                        debug!(
+                            "{:?} at: {} dropped a message from a previous route",
+                            self.portal_type.str(),
+                            self.addresses.sender_internal
+                        );
+                        return Ok(());
+                    }
                     // Send to Tcp stream
                     match msg {
                         PortalMessage::Payload(payload, packet_counter) => {
                             self.handle_payload(ctx, payload, packet_counter).await
                         }
+                        PortalMessage::CompressedPayload(payload) => {
+                            let compression = match self.compression {
+                                Some(compression) => compression,
+                                None => return Err(TransportError::Protocol)?,
+                            };
+                            let payload = compression.decompress(payload)?;
+                            self.handle_payload(ctx, &payload, None).await
+                        }
                         PortalMessage::Disconnect => {
                             self.start_disconnection(ctx, DisconnectionReason::Remote)
                                 .await
                         }
-                        PortalMessage::Ping | PortalMessage::Pong => {
+                        PortalMessage::Resume(peer_received) => {
+                            self.handle_resume(ctx, peer_received, return_route).await
+                        }
+                        PortalMessage::Ping
+                        | PortalMessage::Pong
+                        | PortalMessage::PingWithCompression(_)
+                        | PortalMessage::PingWithClient(..)
+                        | PortalMessage::PongWithCompression(_) => {
                             return Err(TransportError::Protocol)?;
                         }
                     }
@@ -517,6 +869,44 @@ impl TcpPortalWorker {
         debug!("Inlet at: {} received pong", self.addresses.sender_internal);
         self.remote_route = Some(return_route);
         self.state = State::Initialized;
+        self.publish_connected(ctx);
+        Ok(())
+    }
+
+    /// Continue the connection on the route of the other side, and send again what it missed
+    #[instrument(skip_all)]
+    async fn handle_resume(
+        &mut self,
+        ctx: &Context,
+        peer_received: u64,
+        return_route: Route,
+    ) -> Result<()> {
+        let resumption = match &self.resumption {
+            Some(resumption) => resumption.clone(),
+            None => return Err(TransportError::Protocol)?,
+        };
+        info!(
+            "{:?} at: {} resumed its connection on the route {}",
+            self.portal_type.str(),
+            self.addresses.sender_internal,
+            return_route
+        );
+
+        self.remote_route = Some(return_route.clone());
+        match self.portal_type {
+            // The outlet answers with what it received, so that the inlet sends again the rest
+            PortalType::Outlet => {
+                ctx.send_from_address(
+                    return_route.clone(),
+                    PortalMessage::Resume(resumption.received()).to_neutral_message()?,
+                    self.addresses.sender_remote.clone(),
+                )
+                .await?;
+            }
+            // The outlet sends again what the inlet received after asking to resume
+            PortalType::Inlet => self.skip = resumption.bytes_to_skip(),
+        }
+        resumption.resume(return_route, peer_received);
         Ok(())
     }
 
@@ -540,6 +930,16 @@ impl TcpPortalWorker {
     ) -> Result<()> {
         // detects both missing or out of order packets
         self.check_packet_counter(ctx, packet_counter).await?;
+        // skip the bytes sent again by the other side which were already written
+        let skipped = self.skip.min(payload.len() as u64);
+        self.skip -= skipped;
+        let payload = &payload[skipped as usize..];
+        if payload.is_empty() {
+            return Ok(());
+        }
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.consume(payload.len()).await;
+        }
         let tx = if let Some(tx) = &mut self.write_half {
             tx
         } else {
@@ -550,13 +950,26 @@ impl TcpPortalWorker {
             WriteHalfNoTls(tx) => tx.write_all(payload).await,
             WriteHalfWithTls(tx) => tx.write_all(payload).await,
         };
-        if let Err(err) = result {
-            warn!(
-                "Failed to send message to peer {} with error: {}",
-                self.hostname_port, err
-            );
-            self.start_disconnection(ctx, DisconnectionReason::FailedTx)
-                .await?;
+        match result {
+            Ok(()) => {
+                if let Some(stats) = &self.stats {
+                    stats.add_bytes_sent(payload.len());
+                }
+                if let Some(activity) = &self.activity {
+                    activity.touch();
+                }
+                if let Some(resumption) = &self.resumption {
+                    resumption.add_received(payload.len());
+                }
+            }
+            Err(err) => {
+                warn!(
+                    "Failed to send message to peer {} with error: {}",
+                    self.hostname_port, err
+                );
+                self.start_disconnection(ctx, DisconnectionReason::FailedTx)
+                    .await?;
+            }
         }
 
         Ok(())
@@ -0,0 +1,303 @@
+use core::fmt::Debug;
+use ockam_core::compat::net::{IpAddr, Ipv6Addr, SocketAddr};
+use ockam_core::compat::string::String;
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, LocalInfo, Result};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Signature starting a PROXY protocol v2 header
+const SIGNATURE: [u8; 12] = *b"\r\n\r\n\0\r\nQUIT\n";
+
+/// Size of the fixed part of a PROXY protocol v2 header
+const FIXED_HEADER_SIZE: usize = 16;
+
+/// Version 2 with the LOCAL command: the connection was not proxied, e.g. a health check
+const LOCAL_COMMAND: u8 = 0x20;
+
+/// Version 2 with the PROXY command: the connection was proxied on behalf of a client
+const PROXY_COMMAND: u8 = 0x21;
+
+const UNSPECIFIED_FAMILY: u8 = 0x00;
+const TCP_OVER_IPV4: u8 = 0x11;
+const TCP_OVER_IPV6: u8 = 0x21;
+
+/// Type of the PROXY protocol TLV containing the Ockam identifier of the Inlet which connected
+/// to an Outlet. It's the first type of the range reserved for custom TLVs
+pub const PROXY_PROTOCOL_OCKAM_IDENTIFIER_TLV: u8 = 0xE0;
+
+/// Identification of the Inlet which connected to an Outlet, from the local info of its first
+/// message. For example, the identifier of the other side of a secure channel
+pub trait PortalPeerIdentifier: Debug + Send + Sync + 'static {
+    /// Return the identifier of the peer, if it's known
+    fn peer_identifier(&self, local_info: &[LocalInfo]) -> Option<String>;
+}
+
+/// Client of an Inlet, described to the target of an Outlet with a PROXY protocol header
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct ProxyProtocolClient {
+    /// Address of the client of the Inlet, if the Inlet sent it
+    pub(crate) address: Option<SocketAddr>,
+    /// Identifier of the Inlet, if it's known
+    pub(crate) identifier: Option<String>,
+}
+
+/// A PROXY protocol v2 header
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ProxyProtocolHeader {
+    /// Original source and destination of the connection. Not set for the LOCAL command
+    addresses: Option<(SocketAddr, SocketAddr)>,
+    tlvs: Vec<(u8, Vec<u8>)>,
+}
+
+impl ProxyProtocolHeader {
+    /// Header of a connection proxied on behalf of a client
+    pub(crate) fn proxy(source: SocketAddr, destination: SocketAddr) -> Self {
+        Self {
+            addresses: Some((source, destination)),
+            tlvs: Vec::new(),
+        }
+    }
+
+    /// Header of a connection which doesn't proxy any client
+    pub(crate) fn local() -> Self {
+        Self {
+            addresses: None,
+            tlvs: Vec::new(),
+        }
+    }
+
+    /// Header of a connection made by an Outlet to its target on behalf of a client of an Inlet
+    pub(crate) fn for_client(client: &ProxyProtocolClient, destination: SocketAddr) -> Self {
+        let header = match client.address {
+            Some(source) => Self::proxy(source, destination),
+            None => Self::local(),
+        };
+        match &client.identifier {
+            Some(identifier) => header.with_tlv(
+                PROXY_PROTOCOL_OCKAM_IDENTIFIER_TLV,
+                identifier.as_bytes().to_vec(),
+            ),
+            None => header,
+        }
+    }
+
+    /// Add a TLV to the header
+    pub(crate) fn with_tlv(mut self, tlv_type: u8, value: Vec<u8>) -> Self {
+        self.tlvs.push((tlv_type, value));
+        self
+    }
+
+    /// Address of the original client, not set for the LOCAL command
+    pub(crate) fn source(&self) -> Option<SocketAddr> {
+        self.addresses.map(|(source, _)| source)
+    }
+
+    /// Value of the first TLV of a given type
+    #[cfg(test)]
+    pub(crate) fn tlv(&self, tlv_type: u8) -> Option<&[u8]> {
+        self.tlvs
+            .iter()
+            .find(|(t, _)| *t == tlv_type)
+            .map(|(_, value)| value.as_slice())
+    }
+
+    /// Encode the header. An IPv4 address is mapped to IPv6 if the other address is IPv6
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut addresses = Vec::new();
+        let (command, family) = match self.addresses {
+            None => (LOCAL_COMMAND, UNSPECIFIED_FAMILY),
+            Some((source, destination)) => match (source.ip(), destination.ip()) {
+                (IpAddr::V4(source_ip), IpAddr::V4(destination_ip)) => {
+                    addresses.extend_from_slice(&source_ip.octets());
+                    addresses.extend_from_slice(&destination_ip.octets());
+                    addresses.extend_from_slice(&source.port().to_be_bytes());
+                    addresses.extend_from_slice(&destination.port().to_be_bytes());
+                    (PROXY_COMMAND, TCP_OVER_IPV4)
+                }
+                (source_ip, destination_ip) => {
+                    addresses.extend_from_slice(&to_ipv6(source_ip).octets());
+                    addresses.extend_from_slice(&to_ipv6(destination_ip).octets());
+                    addresses.extend_from_slice(&source.port().to_be_bytes());
+                    addresses.extend_from_slice(&destination.port().to_be_bytes());
+                    (PROXY_COMMAND, TCP_OVER_IPV6)
+                }
+            },
+        };
+        for (tlv_type, value) in &self.tlvs {
+            addresses.push(*tlv_type);
+            addresses.extend_from_slice(&(value.len() as u16).to_be_bytes());
+            addresses.extend_from_slice(value);
+        }
+
+        let mut header = Vec::with_capacity(FIXED_HEADER_SIZE + addresses.len());
+        header.extend_from_slice(&SIGNATURE);
+        header.push(command);
+        header.push(family);
+        header.extend_from_slice(&(addresses.len() as u16).to_be_bytes());
+        header.extend_from_slice(&addresses);
+        header
+    }
+
+    /// Read a header at the start of a connection, without reading anything past the header
+    pub(crate) async fn read<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Self> {
+        let mut fixed = [0u8; FIXED_HEADER_SIZE];
+        reader
+            .read_exact(&mut fixed)
+            .await
+            .map_err(|e| proxy_protocol_error(format!("cannot read the header: {e}")))?;
+        if fixed[..12] != SIGNATURE {
+            return Err(proxy_protocol_error(
+                "the connection didn't start with a header",
+            ));
+        }
+        let length = u16::from_be_bytes([fixed[14], fixed[15]]) as usize;
+        let mut rest = vec![0u8; length];
+        reader
+            .read_exact(&mut rest)
+            .await
+            .map_err(|e| proxy_protocol_error(format!("cannot read the header: {e}")))?;
+        Self::decode(fixed[12], fixed[13], &rest)
+    }
+
+    fn decode(command: u8, family: u8, rest: &[u8]) -> Result<Self> {
+        if command >> 4 != 2 {
+            return Err(proxy_protocol_error("only the version 2 is supported"));
+        }
+        let (addresses, tlvs) = match (command, family) {
+            (LOCAL_COMMAND, _) => (None, Self::address_block_size(family).unwrap_or(0)),
+            (PROXY_COMMAND, TCP_OVER_IPV4) => {
+                let bytes = rest
+                    .get(..12)
+                    .ok_or_else(|| proxy_protocol_error("the IPv4 addresses are truncated"))?;
+                let ip = |i: usize| IpAddr::from(<[u8; 4]>::try_from(&bytes[i..i + 4]).unwrap());
+                let port = |i: usize| u16::from_be_bytes([bytes[i], bytes[i + 1]]);
+                let source = SocketAddr::new(ip(0), port(8));
+                let destination = SocketAddr::new(ip(4), port(10));
+                (Some((source, destination)), 12)
+            }
+            (PROXY_COMMAND, TCP_OVER_IPV6) => {
+                let bytes = rest
+                    .get(..36)
+                    .ok_or_else(|| proxy_protocol_error("the IPv6 addresses are truncated"))?;
+                let ip = |i: usize| IpAddr::from(<[u8; 16]>::try_from(&bytes[i..i + 16]).unwrap());
+                let port = |i: usize| u16::from_be_bytes([bytes[i], bytes[i + 1]]);
+                let source = SocketAddr::new(ip(0), port(32));
+                let destination = SocketAddr::new(ip(16), port(34));
+                (Some((source, destination)), 36)
+            }
+            _ => {
+                return Err(proxy_protocol_error(format!(
+                    "unsupported command {command:#x} or address family {family:#x}"
+                )))
+            }
+        };
+
+        let mut header = Self {
+            addresses,
+            tlvs: Vec::new(),
+        };
+        let mut remaining = rest.get(tlvs..).unwrap_or_default();
+        while remaining.len() >= 3 {
+            let tlv_type = remaining[0];
+            let length = u16::from_be_bytes([remaining[1], remaining[2]]) as usize;
+            let value = remaining
+                .get(3..3 + length)
+                .ok_or_else(|| proxy_protocol_error("a TLV is truncated"))?;
+            header.tlvs.push((tlv_type, value.to_vec()));
+            remaining = &remaining[3 + length..];
+        }
+        Ok(header)
+    }
+
+    fn address_block_size(family: u8) -> Option<usize> {
+        match family {
+            TCP_OVER_IPV4 => Some(12),
+            TCP_OVER_IPV6 => Some(36),
+            _ => None,
+        }
+    }
+}
+
+fn to_ipv6(ip: IpAddr) -> Ipv6Addr {
+    match ip {
+        IpAddr::V4(ip) => ip.to_ipv6_mapped(),
+        IpAddr::V6(ip) => ip,
+    }
+}
+
+fn proxy_protocol_error(message: impl Into<String>) -> Error {
+    Error::new(
+        Origin::Transport,
+        Kind::Protocol,
+        format!("PROXY protocol: {}", message.into()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    fn address(s: &str) -> SocketAddr {
+        SocketAddr::from_str(s).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_encode_decode() -> Result<()> {
+        let headers = [
+            ProxyProtocolHeader::proxy(address("10.0.0.1:51000"), address("10.0.0.2:443")),
+            ProxyProtocolHeader::proxy(address("[fd00::1]:51000"), address("[fd00::2]:443"))
+                .with_tlv(PROXY_PROTOCOL_OCKAM_IDENTIFIER_TLV, b"I1234".to_vec()),
+            ProxyProtocolHeader::local(),
+        ];
+        for header in headers {
+            let mut encoded = header.encode();
+            encoded.extend_from_slice(b"GET / HTTP/1.1\r\n");
+            let mut reader = encoded.as_slice();
+            assert_eq!(ProxyProtocolHeader::read(&mut reader).await?, header);
+            // The data following the header is left unread
+            assert_eq!(reader, b"GET / HTTP/1.1\r\n");
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_mixed_families() -> Result<()> {
+        let header = ProxyProtocolHeader::proxy(address("10.0.0.1:51000"), address("[::1]:443"));
+        let decoded = ProxyProtocolHeader::read(&mut header.encode().as_slice()).await?;
+        assert_eq!(decoded.source(), Some(address("[::ffff:10.0.0.1]:51000")));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reject_invalid_headers() {
+        let mut reader = b"PROXY TCP4 10.0.0.1 10.0.0.2 51000 443\r\n".as_slice();
+        assert!(ProxyProtocolHeader::read(&mut reader).await.is_err());
+
+        let mut truncated =
+            ProxyProtocolHeader::proxy(address("10.0.0.1:51000"), address("10.0.0.2:443")).encode();
+        truncated.truncate(20);
+        assert!(ProxyProtocolHeader::read(&mut truncated.as_slice())
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_client_header() {
+        let client = ProxyProtocolClient {
+            address: Some(address("10.0.0.1:51000")),
+            identifier: Some("I1234".into()),
+        };
+        let header = ProxyProtocolHeader::for_client(&client, address("10.0.0.2:5432"));
+        assert_eq!(header.source(), client.address);
+        assert_eq!(
+            header.tlv(PROXY_PROTOCOL_OCKAM_IDENTIFIER_TLV),
+            Some(b"I1234".as_slice())
+        );
+
+        let header =
+            ProxyProtocolHeader::for_client(&ProxyProtocolClient::default(), address("10.0.0.2:1"));
+        assert_eq!(header, ProxyProtocolHeader::local());
+    }
+}
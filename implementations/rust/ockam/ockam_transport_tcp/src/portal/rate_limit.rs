@@ -0,0 +1,117 @@
+use core::time::Duration;
+use minicbor::{Decode, Encode};
+use ockam_core::compat::sync::Mutex;
+use std::time::Instant;
+
+/// Maximum throughput of the connections of an Inlet or an Outlet.
+///
+/// The limit is shared by all the connections of the portal and applies to the bytes
+/// read from and written to these connections.
+#[derive(Encode, Decode, Clone, Copy, Debug, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TcpPortalRateLimit {
+    #[n(1)] bytes_per_second: u64,
+    #[n(2)] burst: Option<u64>,
+}
+
+impl TcpPortalRateLimit {
+    /// Limit the throughput to a number of bytes per second.
+    /// By default, up to one second of traffic can be sent at once
+    pub fn new(bytes_per_second: u64) -> Self {
+        Self {
+            bytes_per_second: bytes_per_second.max(1),
+            burst: None,
+        }
+    }
+
+    /// Set the number of bytes which can be sent at once, after the portal was idle
+    pub fn with_burst(mut self, burst: u64) -> Self {
+        self.burst = Some(burst.max(1));
+        self
+    }
+
+    /// Maximum number of bytes per second
+    pub fn bytes_per_second(&self) -> u64 {
+        self.bytes_per_second
+    }
+
+    /// Maximum number of bytes which can be sent at once
+    pub fn burst(&self) -> u64 {
+        self.burst.unwrap_or(self.bytes_per_second)
+    }
+}
+
+/// Token bucket enforcing a [`TcpPortalRateLimit`] for all the connections of a portal
+#[derive(Debug)]
+pub(crate) struct TokenBucket {
+    limit: TcpPortalRateLimit,
+    state: Mutex<TokenBucketState>,
+}
+
+#[derive(Debug)]
+struct TokenBucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Create a full bucket
+    pub(crate) fn new(limit: TcpPortalRateLimit) -> Self {
+        Self {
+            limit,
+            state: Mutex::new(TokenBucketState {
+                tokens: limit.burst() as f64,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Wait until `bytes` can be transferred without exceeding the limit
+    pub(crate) async fn consume(&self, bytes: usize) {
+        if let Some(wait) = self.reserve(bytes, Instant::now()) {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Take `bytes` tokens from the bucket and return how long to wait before using them.
+    /// The bucket can go into debt, so that payloads larger than the burst are still sent
+    fn reserve(&self, bytes: usize, now: Instant) -> Option<Duration> {
+        let rate = self.limit.bytes_per_second() as f64;
+        let mut state = self.state.lock().unwrap();
+
+        let elapsed = now.saturating_duration_since(state.last_refill);
+        state.tokens = (state.tokens + elapsed.as_secs_f64() * rate).min(self.limit.burst() as f64);
+        state.last_refill = now;
+        state.tokens -= bytes as f64;
+
+        if state.tokens < 0.0 {
+            Some(Duration::from_secs_f64(-state.tokens / rate))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket() {
+        let bucket = TokenBucket::new(TcpPortalRateLimit::new(1000).with_burst(500));
+        let start = bucket.state.lock().unwrap().last_refill;
+
+        // the burst can be sent right away
+        assert_eq!(bucket.reserve(500, start), None);
+
+        // then the traffic is limited to the rate
+        let wait = bucket.reserve(250, start).unwrap();
+        assert_eq!(wait, Duration::from_millis(250));
+
+        // the bucket is refilled over time, up to the burst
+        let later = start + Duration::from_secs(10);
+        assert_eq!(bucket.reserve(500, later), None);
+        assert!(bucket.reserve(1, later).is_some());
+    }
+}
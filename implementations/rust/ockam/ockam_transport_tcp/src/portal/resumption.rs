@@ -0,0 +1,236 @@
+use crate::MAX_PAYLOAD_SIZE;
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+use ockam_core::compat::collections::VecDeque;
+use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
+use ockam_core::{Address, Result, Route};
+use tokio::sync::watch;
+
+/// Number of bytes sent by a connection which are kept to be sent again once it resumes.
+/// A connection which lost more bytes than that while suspended can't be resumed
+pub(crate) const RESUMPTION_BUFFER_SIZE: usize = 16 * MAX_PAYLOAD_SIZE;
+
+/// State of a connection which can be resumed on a new route, shared between
+/// the portal worker writing to the connection and the processor reading from it
+#[derive(Debug)]
+pub(crate) struct ConnectionResumption {
+    grace_period: Duration,
+    /// Number of bytes received from the other side and written to the connection
+    received: AtomicU64,
+    /// Value of `received` when this side asked the other side to resume
+    announced: AtomicU64,
+    resumed: watch::Sender<Option<ResumedRoute>>,
+}
+
+/// New route of a resumed connection
+#[derive(Debug, Clone)]
+pub(crate) struct ResumedRoute {
+    pub(crate) route: Route,
+    /// Number of bytes the other side received before the connection was suspended
+    pub(crate) peer_received: u64,
+}
+
+impl ConnectionResumption {
+    pub(crate) fn new(grace_period: Duration) -> Self {
+        Self {
+            grace_period,
+            received: AtomicU64::new(0),
+            announced: AtomicU64::new(0),
+            resumed: watch::channel(None).0,
+        }
+    }
+
+    /// Maximum time during which a connection stays suspended
+    pub(crate) fn grace_period(&self) -> Duration {
+        self.grace_period
+    }
+
+    /// Number of bytes received from the other side so far
+    pub(crate) fn received(&self) -> u64 {
+        self.received.load(Ordering::Acquire)
+    }
+
+    /// Count bytes received from the other side
+    pub(crate) fn add_received(&self, len: usize) {
+        self.received.fetch_add(len as u64, Ordering::AcqRel);
+    }
+
+    /// Remember the number of bytes received when asking the other side to resume,
+    /// and return it
+    pub(crate) fn announce(&self) -> u64 {
+        let received = self.received();
+        self.announced.store(received, Ordering::Release);
+        received
+    }
+
+    /// Bytes received on the old route after the resumption was announced.
+    /// They are sent again by the other side, so they must be skipped
+    pub(crate) fn bytes_to_skip(&self) -> u64 {
+        self.received()
+            .saturating_sub(self.announced.load(Ordering::Acquire))
+    }
+
+    /// Notify the processor reading from the connection that it can send on a new route
+    pub(crate) fn resume(&self, route: Route, peer_received: u64) {
+        self.resumed.send_replace(Some(ResumedRoute {
+            route,
+            peer_received,
+        }));
+    }
+
+    pub(crate) fn subscribe(&self) -> watch::Receiver<Option<ResumedRoute>> {
+        self.resumed.subscribe()
+    }
+}
+
+/// Bytes most recently sent by a connection
+#[derive(Debug, Default)]
+pub(crate) struct ReplayBuffer {
+    data: VecDeque<u8>,
+    /// Total number of bytes sent
+    sent: u64,
+}
+
+impl ReplayBuffer {
+    pub(crate) fn push(&mut self, chunk: &[u8]) {
+        self.data.extend(chunk);
+        self.sent += chunk.len() as u64;
+        if self.data.len() > RESUMPTION_BUFFER_SIZE {
+            let excess = self.data.len() - RESUMPTION_BUFFER_SIZE;
+            self.data.drain(..excess);
+        }
+    }
+
+    /// Bytes sent after a position in the stream, `None` if they are not buffered anymore
+    pub(crate) fn since(&self, position: u64) -> Option<Vec<u8>> {
+        let missing = self.sent.checked_sub(position)?;
+        if missing > self.data.len() as u64 {
+            return None;
+        }
+        let start = self.data.len() - missing as usize;
+        Some(self.data.range(start..).copied().collect())
+    }
+}
+
+/// Event interrupting the reading of a connection which can be resumed
+pub(crate) enum ResumptionEvent {
+    /// The other side resumed the connection on a new route
+    Resumed(ResumedRoute),
+    /// The route to the Outlet was replaced, the Inlet must ask it to resume
+    OutletRoute(Route),
+}
+
+/// Resumption state used by the processor reading from a connection
+pub(crate) struct ResumptionReceiver {
+    pub(crate) state: Arc<ConnectionResumption>,
+    pub(crate) buffer: ReplayBuffer,
+    resumed: watch::Receiver<Option<ResumedRoute>>,
+    /// Routes to the Outlet, for an Inlet
+    outlet_routes: Option<watch::Receiver<Route>>,
+}
+
+impl ResumptionReceiver {
+    pub(crate) fn new(
+        state: Arc<ConnectionResumption>,
+        outlet_routes: Option<watch::Receiver<Route>>,
+    ) -> Self {
+        Self {
+            resumed: state.subscribe(),
+            state,
+            buffer: ReplayBuffer::default(),
+            outlet_routes,
+        }
+    }
+
+    /// Wait for the next resumption event
+    pub(crate) async fn next_event(&mut self) -> ResumptionEvent {
+        loop {
+            let resumed = &mut self.resumed;
+            let outlet_routes = &mut self.outlet_routes;
+            let outlet_route_changed = async {
+                match outlet_routes {
+                    Some(outlet_routes) => outlet_routes
+                        .changed()
+                        .await
+                        .map(|_| outlet_routes.borrow_and_update().clone()),
+                    None => core::future::pending().await,
+                }
+            };
+
+            let outlet_route = tokio::select! {
+                // The channel can't be closed since its sender is kept in `state`
+                _ = resumed.changed() => {
+                    let resumed = resumed.borrow_and_update().clone();
+                    if let Some(resumed) = resumed {
+                        return ResumptionEvent::Resumed(resumed);
+                    }
+                    continue;
+                }
+                outlet_route = outlet_route_changed => outlet_route,
+            };
+
+            match outlet_route {
+                Ok(route) => return ResumptionEvent::OutletRoute(route),
+                // The Inlet was dropped, only the other side can resume now
+                Err(_) => self.outlet_routes = None,
+            }
+        }
+    }
+}
+
+/// Route to the portal worker of the other side, over a new route to the Outlet
+pub(crate) fn resumed_route(outlet_route: &Route, onward_route: &Route) -> Result<Route> {
+    let mut addresses: Vec<Address> = outlet_route.iter().cloned().collect();
+    addresses.pop();
+    addresses.push(onward_route.recipient()?);
+    Ok(Route::create(addresses))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::route;
+
+    #[test]
+    fn test_replay_buffer() {
+        let mut buffer = ReplayBuffer::default();
+        buffer.push(b"hello ");
+        buffer.push(b"world");
+
+        assert_eq!(buffer.since(11), Some(vec![]));
+        assert_eq!(buffer.since(6), Some(b"world".to_vec()));
+        assert_eq!(buffer.since(0), Some(b"hello world".to_vec()));
+        assert_eq!(buffer.since(12), None);
+
+        // The oldest bytes are dropped once the buffer is full
+        buffer.push(&vec![0; RESUMPTION_BUFFER_SIZE]);
+        assert_eq!(buffer.since(0), None);
+        assert_eq!(
+            buffer.since(11).map(|data| data.len()),
+            Some(RESUMPTION_BUFFER_SIZE)
+        );
+    }
+
+    #[test]
+    fn test_bytes_to_skip() {
+        let resumption = ConnectionResumption::new(Duration::from_secs(10));
+        resumption.add_received(10);
+        assert_eq!(resumption.announce(), 10);
+
+        // Bytes still received on the old route are sent again on the new one
+        resumption.add_received(5);
+        assert_eq!(resumption.bytes_to_skip(), 5);
+    }
+
+    #[test]
+    fn test_resumed_route() -> Result<()> {
+        let outlet_route = route!["secure_channel_2", "outlet"];
+        let onward_route = route!["secure_channel_1", "outlet_worker"];
+        assert_eq!(
+            resumed_route(&outlet_route, &onward_route)?,
+            route!["secure_channel_2", "outlet_worker"]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use crate::portal::ConnectionRejection;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// Traffic counters of a TCP Inlet, shared by all the connections it accepted
+#[derive(Debug, Default)]
+pub struct TcpPortalStats {
+    bytes_received: AtomicU64,
+    bytes_sent: AtomicU64,
+    active_connections: AtomicU64,
+    total_connections: AtomicU64,
+    rejected_max_connections: AtomicU64,
+    rejected_max_connections_per_ip: AtomicU64,
+    idle_timeouts: AtomicU64,
+}
+
+impl TcpPortalStats {
+    /// Number of bytes read from the TCP connections and sent to the other side of the portal
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received.load(Ordering::Relaxed)
+    }
+
+    /// Number of bytes received from the other side of the portal and written to the TCP connections
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent.load(Ordering::Relaxed)
+    }
+
+    /// Number of TCP connections currently open
+    pub fn active_connections(&self) -> u64 {
+        self.active_connections.load(Ordering::Relaxed)
+    }
+
+    /// Number of TCP connections opened since the portal was created
+    pub fn total_connections(&self) -> u64 {
+        self.total_connections.load(Ordering::Relaxed)
+    }
+
+    /// Number of TCP connections refused because the Inlet had too many open connections
+    pub fn rejected_max_connections(&self) -> u64 {
+        self.rejected_max_connections.load(Ordering::Relaxed)
+    }
+
+    /// Number of TCP connections refused because their IP address had too many open connections
+    pub fn rejected_max_connections_per_ip(&self) -> u64 {
+        self.rejected_max_connections_per_ip.load(Ordering::Relaxed)
+    }
+
+    /// Number of TCP connections closed because they were idle
+    pub fn idle_timeouts(&self) -> u64 {
+        self.idle_timeouts.load(Ordering::Relaxed)
+    }
+
+    pub(super) fn add_bytes_received(&self, bytes: usize) {
+        self.bytes_received
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub(super) fn add_bytes_sent(&self, bytes: usize) {
+        self.bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub(super) fn connection_opened(&self) {
+        self.active_connections.fetch_add(1, Ordering::Relaxed);
+        self.total_connections.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(super) fn connection_rejected(&self, rejection: ConnectionRejection) {
+        let counter = match rejection {
+            ConnectionRejection::MaxConnections => &self.rejected_max_connections,
+            ConnectionRejection::MaxConnectionsPerIp => &self.rejected_max_connections_per_ip,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(super) fn connection_idle_timed_out(&self) {
+        self.idle_timeouts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(super) fn connection_closed(&self) {
+        // Never go below 0, even if a connection is reported as closed twice
+        let _ = self
+            .active_connections
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_connections_and_bytes() {
+        let stats = TcpPortalStats::default();
+        stats.connection_opened();
+        stats.connection_opened();
+        stats.add_bytes_received(10);
+        stats.add_bytes_sent(5);
+        stats.add_bytes_sent(7);
+        stats.connection_closed();
+
+        assert_eq!(stats.active_connections(), 1);
+        assert_eq!(stats.total_connections(), 2);
+        assert_eq!(stats.bytes_received(), 10);
+        assert_eq!(stats.bytes_sent(), 12);
+
+        stats.connection_closed();
+        stats.connection_closed();
+        assert_eq!(stats.active_connections(), 0);
+    }
+
+    #[test]
+    fn count_rejected_connections() {
+        let stats = TcpPortalStats::default();
+        stats.connection_rejected(ConnectionRejection::MaxConnections);
+        stats.connection_rejected(ConnectionRejection::MaxConnectionsPerIp);
+        stats.connection_rejected(ConnectionRejection::MaxConnectionsPerIp);
+        stats.connection_idle_timed_out();
+
+        assert_eq!(stats.rejected_max_connections(), 1);
+        assert_eq!(stats.rejected_max_connections_per_ip(), 2);
+        assert_eq!(stats.idle_timeouts(), 1);
+    }
+}
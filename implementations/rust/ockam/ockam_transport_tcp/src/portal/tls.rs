@@ -0,0 +1,175 @@
+use minicbor::{Decode, Encode};
+use ockam_core::compat::string::String;
+use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::{RootCertStore, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+use tracing::debug;
+
+/// Verification of the certificate of the target of an Outlet, when the Outlet connects to
+/// it using TLS.
+///
+/// By default, the certificate must be issued for the hostname of the target by one of the
+/// certificate authorities trusted by the system.
+#[derive(Encode, Decode, Clone, Debug, Default, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PortalTlsVerification {
+    #[n(1)] ca_certificates: Option<String>,
+    #[n(2)] server_name: Option<String>,
+}
+
+impl PortalTlsVerification {
+    /// Verify the certificate of the target with the system certificate authorities
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only trust the certificate authorities of a PEM bundle, instead of the system ones.
+    /// This allows reaching targets using a private PKI or self-signed certificates
+    pub fn with_ca_certificates(mut self, ca_certificates_pem: impl Into<String>) -> Self {
+        self.ca_certificates = Some(ca_certificates_pem.into());
+        self
+    }
+
+    /// Expect a certificate issued for this name, instead of the hostname of the target
+    pub fn with_server_name(mut self, server_name: impl Into<String>) -> Self {
+        self.server_name = Some(server_name.into());
+        self
+    }
+
+    /// Name expected in the certificate of the target, if it's not its hostname
+    pub fn server_name(&self) -> Option<&str> {
+        self.server_name.as_deref()
+    }
+
+    /// Check that the certificate authorities, if any, can be parsed
+    pub fn validate(&self) -> Result<()> {
+        if let Some(ca_certificates) = &self.ca_certificates {
+            parse_certificates(ca_certificates)?;
+        }
+        Ok(())
+    }
+
+    /// Return the certificate authorities used to verify the certificate of the target
+    pub(crate) fn root_cert_store(&self) -> Result<RootCertStore> {
+        let mut root_cert_store = RootCertStore::empty();
+        match &self.ca_certificates {
+            Some(ca_certificates) => {
+                for certificate in parse_certificates(ca_certificates)? {
+                    root_cert_store
+                        .add(certificate)
+                        .map_err(|e| tls_error(format!("Invalid CA certificate: {e}")))?;
+                }
+            }
+            None => {
+                let certificates = rustls_native_certs::load_native_certs().map_err(|e| {
+                    Error::new(
+                        Origin::Transport,
+                        Kind::Io,
+                        format!("Cannot load the native certificates: {e:?}"),
+                    )
+                })?;
+                debug!("there are {} certificates", certificates.len());
+                root_cert_store.add_parsable_certificates(certificates);
+            }
+        }
+        Ok(root_cert_store)
+    }
+}
+
+/// Certificate and private key used by an Inlet to accept TLS connections from its clients.
+/// The TLS connections are terminated by the Inlet, and their content is sent to the Outlet
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PortalTlsCertificate {
+    #[n(1)] certificate_chain: String,
+    #[n(2)] private_key: String,
+}
+
+impl core::fmt::Debug for PortalTlsCertificate {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PortalTlsCertificate")
+            .field("certificate_chain", &self.certificate_chain)
+            .field("private_key", &"<redacted>")
+            .finish()
+    }
+}
+
+impl PortalTlsCertificate {
+    /// Create a certificate from a PEM chain of certificates, starting with the certificate
+    /// of the Inlet, and the PEM private key of that certificate
+    pub fn new(
+        certificate_chain_pem: impl Into<String>,
+        private_key_pem: impl Into<String>,
+    ) -> Self {
+        Self {
+            certificate_chain: certificate_chain_pem.into(),
+            private_key: private_key_pem.into(),
+        }
+    }
+
+    /// Check that the certificate chain and the private key can be parsed and match
+    pub fn validate(&self) -> Result<()> {
+        self.acceptor().map(|_| ())
+    }
+
+    /// Create the acceptor performing the TLS handshake with the clients of an Inlet
+    pub(crate) fn acceptor(&self) -> Result<TlsAcceptor> {
+        let certificates = parse_certificates(&self.certificate_chain)?;
+        let private_key = parse_private_key(&self.private_key)?;
+        let config = ServerConfig::builder()
+            .with_no_client_auth()
+            .with_single_cert(certificates, private_key)
+            .map_err(|e| tls_error(format!("Invalid TLS certificate or private key: {e}")))?;
+        Ok(TlsAcceptor::from(Arc::new(config)))
+    }
+}
+
+fn parse_certificates(pem: &str) -> Result<Vec<CertificateDer<'static>>> {
+    let certificates = rustls_pemfile::certs(&mut pem.as_bytes())
+        .collect::<core::result::Result<Vec<_>, _>>()
+        .map_err(|e| tls_error(format!("Cannot parse the PEM certificates: {e}")))?;
+    if certificates.is_empty() {
+        return Err(tls_error("No PEM certificate found"));
+    }
+    Ok(certificates)
+}
+
+fn parse_private_key(pem: &str) -> Result<PrivateKeyDer<'static>> {
+    rustls_pemfile::private_key(&mut pem.as_bytes())
+        .map_err(|e| tls_error(format!("Cannot parse the PEM private key: {e}")))?
+        .ok_or_else(|| tls_error("No PEM private key found"))
+}
+
+fn tls_error(message: impl Into<String>) -> Error {
+    Error::new(Origin::Transport, Kind::Invalid, message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_ca_certificates_are_rejected() {
+        let verification = PortalTlsVerification::new().with_ca_certificates("not a certificate");
+        assert!(verification.validate().is_err());
+        assert!(PortalTlsVerification::new().validate().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_certificate_is_rejected() {
+        let certificate = PortalTlsCertificate::new("not a certificate", "not a key");
+        assert!(certificate.validate().is_err());
+    }
+
+    #[test]
+    fn test_private_key_is_not_displayed() {
+        let certificate = PortalTlsCertificate::new("certificate", "secret key");
+        assert!(!format!("{certificate:?}").contains("secret key"));
+    }
+}
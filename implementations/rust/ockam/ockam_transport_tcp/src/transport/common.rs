@@ -1,27 +1,33 @@
-use cfg_if::cfg_if;
+use crate::{PortalTlsVerification, TcpSocketOptions};
 use ockam_core::compat::net::SocketAddr;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Error, Result};
 use ockam_transport_core::{HostnamePort, TransportError};
-use socket2::{SockRef, TcpKeepalive};
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::io::{ReadHalf, WriteHalf};
+use tokio::io::{AsyncWriteExt, ReadHalf, WriteHalf};
 use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::TcpStream;
 use tokio_rustls::rustls::pki_types::ServerName;
-use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+use tokio_rustls::rustls::ClientConfig;
 use tokio_rustls::{TlsConnector, TlsStream};
 use tracing::{debug, instrument};
 
 /// Connect to a socket address via a regular TcpStream
 #[instrument(skip_all)]
-pub(crate) async fn connect(socket_address: SocketAddr) -> Result<(OwnedReadHalf, OwnedWriteHalf)> {
-    Ok(create_tcp_stream(socket_address).await?.into_split())
+pub(crate) async fn connect(
+    socket_address: SocketAddr,
+    socket_options: &TcpSocketOptions,
+) -> Result<(OwnedReadHalf, OwnedWriteHalf)> {
+    Ok(create_tcp_stream(socket_address, socket_options)
+        .await?
+        .into_split())
 }
 
 /// Create a TCP stream to a given socket address
-pub(crate) async fn create_tcp_stream(socket_address: SocketAddr) -> Result<TcpStream> {
+pub(crate) async fn create_tcp_stream(
+    socket_address: SocketAddr,
+    socket_options: &TcpSocketOptions,
+) -> Result<TcpStream> {
     debug!(addr = %socket_address, "Connecting");
     let connection = match TcpStream::connect(socket_address).await {
         Ok(c) => {
@@ -34,29 +40,20 @@ pub(crate) async fn create_tcp_stream(socket_address: SocketAddr) -> Result<TcpS
         }
     };
 
-    let mut keepalive = TcpKeepalive::new()
-        .with_time(Duration::from_secs(300))
-        .with_interval(Duration::from_secs(75));
-
-    cfg_if! {
-        if #[cfg(unix)] {
-           keepalive = keepalive.with_retries(2);
-        }
-    }
-
-    let socket = SockRef::from(&connection);
-    socket
-        .set_tcp_keepalive(&keepalive)
-        .map_err(TransportError::from)?;
+    socket_options.apply(&connection)?;
 
     Ok(connection)
 }
 
-/// Connect to a socket address via a TlsStream
+/// Connect to a socket address via a TlsStream.
+/// A PROXY protocol header can be sent on the TCP stream before the TLS handshake
 #[allow(clippy::type_complexity)]
 #[instrument(skip_all)]
 pub(crate) async fn connect_tls(
     hostname_port: &HostnamePort,
+    socket_options: &TcpSocketOptions,
+    verification: &PortalTlsVerification,
+    proxy_protocol_header: Option<Vec<u8>>,
 ) -> Result<(
     ReadHalf<TlsStream<TcpStream>>,
     WriteHalf<TlsStream<TcpStream>>,
@@ -65,13 +62,23 @@ pub(crate) async fn connect_tls(
     debug!(hostname_port = %hostname_port, addr = %socket_address, "Trying to connect using TLS");
 
     // create a tcp stream
-    let connection = create_tcp_stream(socket_address).await?;
+    let mut connection = create_tcp_stream(socket_address, socket_options).await?;
+    if let Some(proxy_protocol_header) = proxy_protocol_header {
+        connection
+            .write_all(&proxy_protocol_header)
+            .await
+            .map_err(TransportError::from)?;
+    }
 
     // create a TLS connector
-    let tls_connector = create_tls_connector().await?;
-
-    // parse destination hostname
-    let hostname = ServerName::try_from(hostname_port.hostname()).map_err(|e| {
+    let tls_connector = create_tls_connector(verification).await?;
+
+    // parse the name expected in the certificate, by default the destination hostname
+    let hostname = verification
+        .server_name()
+        .map(|server_name| server_name.to_string())
+        .unwrap_or_else(|| hostname_port.hostname());
+    let hostname = ServerName::try_from(hostname).map_err(|e| {
         Error::new(
             Origin::Transport,
             Kind::Io,
@@ -95,19 +102,12 @@ pub(crate) async fn connect_tls(
     Ok(tokio::io::split(TlsStream::from(client_tls_stream)))
 }
 
-/// Create a TLS connector using the system certificates
-pub(crate) async fn create_tls_connector() -> Result<TlsConnector> {
-    let certificates = rustls_native_certs::load_native_certs().map_err(|e| {
-        Error::new(
-            Origin::Transport,
-            Kind::Io,
-            format!("Cannot load the native certificates: {e:?}"),
-        )
-    })?;
-    debug!("there are {} certificates", certificates.len());
-
-    let mut root_cert_store = RootCertStore::empty();
-    root_cert_store.add_parsable_certificates(certificates);
+/// Create a TLS connector using the system certificates, or the certificate authorities
+/// given in the verification options
+pub(crate) async fn create_tls_connector(
+    verification: &PortalTlsVerification,
+) -> Result<TlsConnector> {
+    let root_cert_store = verification.root_cert_store()?;
 
     let config = ClientConfig::builder()
         .with_root_certificates(root_cert_store)
@@ -3,10 +3,11 @@ use crate::workers::{Addresses, TcpRecvProcessor, TcpSendWorker};
 use crate::{TcpConnectionMode, TcpConnectionOptions, TcpTransport};
 use core::fmt;
 use core::fmt::Formatter;
+use core::str::FromStr;
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{Address, Result};
 use ockam_node::Context;
-use ockam_transport_core::resolve_peer;
+use ockam_transport_core::{resolve_peer, HostnamePort};
 use std::net::SocketAddr;
 use tracing::debug;
 
@@ -85,6 +86,9 @@ impl TcpConnection {
 impl TcpTransport {
     /// Establish an outgoing TCP connection.
     ///
+    /// The connection goes through the proxy set in the [`TcpConnectionOptions`], or through
+    /// the proxy set with `OCKAM_TCP_PROXY`, unless the peer is a loopback address.
+    ///
     /// ```rust
     /// use ockam_transport_tcp::{TcpConnectionOptions, TcpListenerOptions, TcpTransport};
     /// # use ockam_node::Context;
@@ -101,9 +105,20 @@ impl TcpTransport {
         options: TcpConnectionOptions,
     ) -> Result<TcpConnection> {
         let peer = peer.into();
-        let socket = resolve_peer(peer.clone())?;
-        debug!("Connecting to {}", peer.clone());
-        let (read_half, write_half) = connect(socket).await?;
+        let socket_options = options.socket_options.or(&self.socket_options());
+        let (socket, (read_half, write_half)) = match options.proxy(&peer)? {
+            Some(proxy) => {
+                debug!("Connecting to {} via the proxy {}", peer, proxy);
+                let hostname_port = HostnamePort::from_str(&peer)?;
+                let (socket, stream) = proxy.connect(&hostname_port, &socket_options).await?;
+                (socket, stream.into_split())
+            }
+            None => {
+                let socket = resolve_peer(peer.clone())?;
+                debug!("Connecting to {}", peer.clone());
+                (socket, connect(socket, &socket_options).await?)
+            }
+        };
 
         let mode = TcpConnectionMode::Outgoing;
         let addresses = Addresses::generate(mode);
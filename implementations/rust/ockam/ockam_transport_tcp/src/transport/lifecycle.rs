@@ -3,10 +3,13 @@ use ockam_core::{async_trait, Address, AsyncTryClone, Error, Result, TransportTy
 use ockam_node::Context;
 use ockam_transport_core::Transport;
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use tracing::instrument;
 
-use crate::{TcpConnectionOptions, TcpListenerInfo, TcpRegistry, TcpSenderInfo, TcpTransport, TCP};
+use crate::{
+    TcpConnectionOptions, TcpListenerInfo, TcpRegistry, TcpSenderInfo, TcpSocketOptions,
+    TcpTransport, TCP,
+};
 
 impl TcpTransport {
     /// Create a TCP transport
@@ -24,6 +27,7 @@ impl TcpTransport {
         let tcp = Self {
             ctx: Arc::new(ctx.async_try_clone().await?),
             registry: TcpRegistry::default(),
+            socket_options: Arc::new(RwLock::new(TcpSocketOptions::new())),
         };
         // make the TCP transport available in the list of supported transports for
         // later address resolution when socket addresses will need to be instantiated as TCP
@@ -43,6 +47,17 @@ impl TcpTransport {
         &self.registry
     }
 
+    /// Socket options used by the connections and listeners created after this call,
+    /// when their own options don't set them
+    pub fn set_socket_options(&self, socket_options: TcpSocketOptions) {
+        *self.socket_options.write().unwrap() = socket_options;
+    }
+
+    /// Default socket options of the connections and listeners
+    pub fn socket_options(&self) -> TcpSocketOptions {
+        self.socket_options.read().unwrap().clone()
+    }
+
     /// Search for a connection with the provided socket address
     pub fn find_connection_by_socketaddr(
         &self,
@@ -75,8 +75,9 @@ impl TcpTransport {
     pub async fn listen(
         &self,
         bind_addr: impl AsRef<str>,
-        options: TcpListenerOptions,
+        mut options: TcpListenerOptions,
     ) -> Result<TcpListener> {
+        options.socket_options = options.socket_options.or(&self.socket_options());
         let flow_control_id = options.flow_control_id.clone();
         let bind_addr = parse_socket_addr(bind_addr.as_ref())?;
         // Could be different from the bind_addr, e.g., if binding to port 0\
@@ -3,6 +3,8 @@ mod connection;
 mod lifecycle;
 mod listener;
 mod portals;
+mod proxy;
+mod socket_options;
 
 pub(crate) use common::*;
 
@@ -10,9 +12,11 @@ pub use crate::portal::options::*;
 pub use connection::*;
 pub use listener::*;
 pub use portals::*;
+pub use proxy::*;
+pub use socket_options::*;
 
 use crate::TcpRegistry;
-use ockam_core::compat::sync::Arc;
+use ockam_core::compat::sync::{Arc, RwLock};
 use ockam_core::{async_trait, Result};
 use ockam_node::{Context, HasContext};
 
@@ -58,6 +62,7 @@ use ockam_node::{Context, HasContext};
 pub struct TcpTransport {
     ctx: Arc<Context>,
     registry: TcpRegistry,
+    socket_options: Arc<RwLock<TcpSocketOptions>>,
 }
 
 /// This trait adds a `create_tcp_transport` method to any struct returning a Context.
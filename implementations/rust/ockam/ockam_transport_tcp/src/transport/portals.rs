@@ -1,12 +1,16 @@
 use crate::portal::{InletSharedState, TcpInletListenProcessor};
-use crate::{portal::TcpOutletListenWorker, TcpInletOptions, TcpOutletOptions, TcpTransport};
+use crate::{
+    portal::TcpOutletListenWorker, TcpInletOptions, TcpOutletOptions, TcpPortalStats, TcpTransport,
+};
 use core::fmt;
 use core::fmt::{Debug, Formatter};
 use ockam_core::compat::net::SocketAddr;
 use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{route, Address, Error, Result, Route};
 use ockam_node::Context;
 use ockam_transport_core::{parse_socket_addr, HostnamePort};
+use tokio::sync::watch;
 use tracing::instrument;
 
 impl TcpTransport {
@@ -32,8 +36,9 @@ impl TcpTransport {
         &self,
         bind_addr: impl Into<String> + Clone + Debug,
         outlet_route: impl Into<Route> + Clone + Debug,
-        options: TcpInletOptions,
+        mut options: TcpInletOptions,
     ) -> Result<TcpInlet> {
+        options.socket_options = options.socket_options.or(&self.socket_options());
         let socket_address = parse_socket_addr(&bind_addr.into())?;
         TcpInletListenProcessor::start(
             &self.ctx,
@@ -88,10 +93,11 @@ impl TcpTransport {
         &self,
         address: impl Into<Address> + Clone + Debug,
         hostname_port: impl TryInto<HostnamePort, Error = Error> + Clone + Debug,
-        options: TcpOutletOptions,
+        mut options: TcpOutletOptions,
     ) -> Result<()> {
         // Resolve peer address as a host name and port
         let peer = hostname_port.try_into()?;
+        options.socket_options = options.socket_options.or(&self.socket_options());
         tracing::Span::current().record("peer", peer.to_string());
 
         TcpOutletListenWorker::start(
@@ -112,8 +118,9 @@ impl TcpTransport {
         &self,
         address: Address,
         hostname_port: HostnamePort,
-        options: TcpOutletOptions,
+        mut options: TcpOutletOptions,
     ) -> Result<()> {
+        options.socket_options = options.socket_options.or(&self.socket_options());
         TcpOutletListenWorker::start(
             &self.ctx,
             self.registry.clone(),
@@ -151,6 +158,9 @@ pub struct TcpInlet {
     socket_address: SocketAddr,
     processor_address: Address,
     outlet_state: Arc<RwLock<InletSharedState>>,
+    stats: Arc<TcpPortalStats>,
+    /// Route to the outlet, watched by the connections which can be resumed
+    outlet_routes: Arc<watch::Sender<Route>>,
 }
 
 impl fmt::Display for TcpInlet {
@@ -169,11 +179,15 @@ impl TcpInlet {
         socket_address: SocketAddr,
         processor_address: Address,
         outlet_state: Arc<RwLock<InletSharedState>>,
+        stats: Arc<TcpPortalStats>,
     ) -> Self {
+        let route = outlet_state.read().unwrap().route.clone();
         Self {
             socket_address,
             processor_address,
             outlet_state,
+            stats,
+            outlet_routes: Arc::new(watch::channel(route).0),
         }
     }
 
@@ -187,20 +201,64 @@ impl TcpInlet {
         &self.processor_address
     }
 
+    /// Traffic counters of the Inlet connections
+    pub fn stats(&self) -> Arc<TcpPortalStats> {
+        self.stats.clone()
+    }
+
+    /// Changes of the route to the outlet
+    pub(crate) fn outlet_routes(&self) -> watch::Receiver<Route> {
+        self.outlet_routes.subscribe()
+    }
+
     fn build_new_full_route(new_route: Route, old_route: &Route) -> Result<Route> {
         let their_outlet_address = old_route.recipient()?;
         Ok(route![new_route, their_outlet_address])
     }
 
+    fn publish_route(&self, route: &Route) {
+        self.outlet_routes.send_if_modified(|current| {
+            let modified = current != route;
+            if modified {
+                *current = route.clone();
+            }
+            modified
+        });
+    }
+
     /// Update the route to the outlet node.
     /// This is useful if we re-create a secure channel if because, e.g., the other node wasn't
     /// reachable, or if we want to switch transport, e.g., from relayed to UDP NAT puncture.
     ///  NOTE: Existing TCP connections will still use the old route,
-    ///        only newly accepted connections will use the new route.
+    ///        only newly accepted connections will use the new route,
+    ///        unless the connections can be resumed.
     pub fn update_outlet_node_route(&self, new_route: Route) -> Result<()> {
         let mut outlet_state = self.outlet_state.write().unwrap();
 
         outlet_state.route = Self::build_new_full_route(new_route, &outlet_state.route)?;
+        self.publish_route(&outlet_state.route);
+
+        Ok(())
+    }
+
+    /// Update all the routes to the outlets, when the Inlet spreads its connections across
+    /// several outlets. The routes are full routes, including the address of each outlet.
+    ///  NOTE: Existing TCP connections will still use their route,
+    ///        only newly accepted connections are spread across the new routes.
+    pub fn update_routes(&self, routes: Vec<Route>) -> Result<()> {
+        let mut routes = routes.into_iter();
+        let route = routes.next().ok_or_else(|| {
+            Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                "an inlet needs at least one route to an outlet",
+            )
+        })?;
+
+        let mut outlet_state = self.outlet_state.write().unwrap();
+        outlet_state.route = route;
+        outlet_state.additional_routes = routes.collect();
+        self.publish_route(&outlet_state.route);
 
         Ok(())
     }
@@ -218,6 +276,7 @@ impl TcpInlet {
 
         outlet_state.route = Self::build_new_full_route(new_route, &outlet_state.route)?;
         outlet_state.is_paused = false;
+        self.publish_route(&outlet_state.route);
 
         Ok(())
     }
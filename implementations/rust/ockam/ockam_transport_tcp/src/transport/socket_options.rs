@@ -0,0 +1,194 @@
+use cfg_if::cfg_if;
+use core::time::Duration;
+use minicbor::{Decode, Encode};
+use ockam_core::Result;
+use ockam_transport_core::TransportError;
+use socket2::{SockRef, TcpKeepalive};
+use tokio::net::TcpStream;
+
+/// Idle time before the first keepalive probe is sent, when not configured
+pub const DEFAULT_TCP_KEEPALIVE_TIME: Duration = Duration::from_secs(300);
+
+/// Time between two keepalive probes, when not configured
+pub const DEFAULT_TCP_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(75);
+
+/// Options applied to the sockets of TCP connections.
+///
+/// Options which are not set fall back to the options of the [`TcpTransport`](crate::TcpTransport)
+/// and then to the operating system defaults, except for keepalive which is always enabled,
+/// with [`DEFAULT_TCP_KEEPALIVE_TIME`] and [`DEFAULT_TCP_KEEPALIVE_INTERVAL`] by default.
+#[derive(Encode, Decode, Clone, Debug, Default, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TcpSocketOptions {
+    #[n(1)] keepalive_time_secs: Option<u64>,
+    #[n(2)] keepalive_interval_secs: Option<u64>,
+    #[n(3)] nodelay: Option<bool>,
+    #[n(4)] recv_buffer_size: Option<u32>,
+    #[n(5)] send_buffer_size: Option<u32>,
+}
+
+impl TcpSocketOptions {
+    /// Create options where nothing is set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the idle time before the first keepalive probe is sent
+    pub fn with_keepalive_time(mut self, time: Duration) -> Self {
+        self.keepalive_time_secs = Some(time.as_secs());
+        self
+    }
+
+    /// Set the time between two keepalive probes
+    pub fn with_keepalive_interval(mut self, interval: Duration) -> Self {
+        self.keepalive_interval_secs = Some(interval.as_secs());
+        self
+    }
+
+    /// Enable or disable Nagle's algorithm (`TCP_NODELAY`)
+    pub fn with_nodelay(mut self, nodelay: bool) -> Self {
+        self.nodelay = Some(nodelay);
+        self
+    }
+
+    /// Set the size of the receive buffer (`SO_RCVBUF`)
+    pub fn with_recv_buffer_size(mut self, size: u32) -> Self {
+        self.recv_buffer_size = Some(size);
+        self
+    }
+
+    /// Set the size of the send buffer (`SO_SNDBUF`)
+    pub fn with_send_buffer_size(mut self, size: u32) -> Self {
+        self.send_buffer_size = Some(size);
+        self
+    }
+
+    /// Idle time before the first keepalive probe is sent
+    pub fn keepalive_time(&self) -> Duration {
+        self.keepalive_time_secs
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_TCP_KEEPALIVE_TIME)
+    }
+
+    /// Time between two keepalive probes
+    pub fn keepalive_interval(&self) -> Duration {
+        self.keepalive_interval_secs
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_TCP_KEEPALIVE_INTERVAL)
+    }
+
+    /// `TCP_NODELAY` value, if set
+    pub fn nodelay(&self) -> Option<bool> {
+        self.nodelay
+    }
+
+    /// Size of the receive buffer, if set
+    pub fn recv_buffer_size(&self) -> Option<u32> {
+        self.recv_buffer_size
+    }
+
+    /// Size of the send buffer, if set
+    pub fn send_buffer_size(&self) -> Option<u32> {
+        self.send_buffer_size
+    }
+}
+
+impl TcpSocketOptions {
+    /// Return these options, completed with the options set in `defaults`
+    pub(crate) fn or(&self, defaults: &TcpSocketOptions) -> TcpSocketOptions {
+        TcpSocketOptions {
+            keepalive_time_secs: self.keepalive_time_secs.or(defaults.keepalive_time_secs),
+            keepalive_interval_secs: self
+                .keepalive_interval_secs
+                .or(defaults.keepalive_interval_secs),
+            nodelay: self.nodelay.or(defaults.nodelay),
+            recv_buffer_size: self.recv_buffer_size.or(defaults.recv_buffer_size),
+            send_buffer_size: self.send_buffer_size.or(defaults.send_buffer_size),
+        }
+    }
+
+    /// Set the options on the socket of a TCP stream
+    pub(crate) fn apply(&self, stream: &TcpStream) -> Result<()> {
+        let mut keepalive = TcpKeepalive::new()
+            .with_time(self.keepalive_time())
+            .with_interval(self.keepalive_interval());
+
+        cfg_if! {
+            if #[cfg(unix)] {
+               keepalive = keepalive.with_retries(2);
+            }
+        }
+
+        let socket = SockRef::from(stream);
+        socket
+            .set_tcp_keepalive(&keepalive)
+            .map_err(TransportError::from)?;
+
+        if let Some(nodelay) = self.nodelay {
+            socket.set_nodelay(nodelay).map_err(TransportError::from)?;
+        }
+        if let Some(size) = self.recv_buffer_size {
+            socket
+                .set_recv_buffer_size(size as usize)
+                .map_err(TransportError::from)?;
+        }
+        if let Some(size) = self.send_buffer_size {
+            socket
+                .set_send_buffer_size(size as usize)
+                .map_err(TransportError::from)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_fallback_to_defaults() {
+        let defaults = TcpSocketOptions::new()
+            .with_keepalive_time(Duration::from_secs(30))
+            .with_nodelay(false);
+        let options = TcpSocketOptions::new()
+            .with_nodelay(true)
+            .with_recv_buffer_size(65536)
+            .or(&defaults);
+
+        assert_eq!(options.keepalive_time(), Duration::from_secs(30));
+        assert_eq!(options.keepalive_interval(), DEFAULT_TCP_KEEPALIVE_INTERVAL);
+        assert_eq!(options.nodelay(), Some(true));
+        assert_eq!(options.recv_buffer_size(), Some(65536));
+        assert_eq!(options.send_buffer_size(), None);
+    }
+
+    #[tokio::test]
+    async fn test_apply() -> Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(TransportError::from)?;
+        let address = listener.local_addr().map_err(TransportError::from)?;
+        let stream = TcpStream::connect(address)
+            .await
+            .map_err(TransportError::from)?;
+
+        let options = TcpSocketOptions::new()
+            .with_keepalive_time(Duration::from_secs(30))
+            .with_keepalive_interval(Duration::from_secs(10))
+            .with_nodelay(true);
+        options.apply(&stream)?;
+
+        let socket = SockRef::from(&stream);
+        assert!(socket.keepalive().map_err(TransportError::from)?);
+        assert!(stream.nodelay().map_err(TransportError::from)?);
+        #[cfg(target_os = "linux")]
+        {
+            let time = socket.keepalive_time().map_err(TransportError::from)?;
+            assert_eq!(time, Duration::from_secs(30));
+        }
+        Ok(())
+    }
+}
@@ -5,7 +5,7 @@ use ockam_core::{Address, Processor, Result};
 use ockam_node::Context;
 use ockam_transport_core::TransportError;
 use tokio::net::TcpListener;
-use tracing::{debug, instrument};
+use tracing::{debug, instrument, warn};
 
 /// A TCP Listen processor
 ///
@@ -80,6 +80,9 @@ impl Processor for TcpListenProcessor {
         // Wait for an incoming connection
         let (stream, peer) = self.inner.accept().await.map_err(TransportError::from)?;
         debug!("TCP connection accepted");
+        if let Err(err) = self.options.socket_options.apply(&stream) {
+            warn!(%peer, %err, "Cannot set the socket options of an accepted connection");
+        }
 
         let mode = TcpConnectionMode::Incoming;
         let addresses = Addresses::generate(mode);
@@ -165,7 +165,7 @@ impl Processor for TcpRecvProcessor {
             }
         }
 
-        // Deserialize the message now
+        // Deserialize the message now, its payload shares the received buffer
         let transport_message = TransportMessage::decode_message(buf).map_err(|e| {
             error!("{e:?}");
             TransportError::RecvBadMessage
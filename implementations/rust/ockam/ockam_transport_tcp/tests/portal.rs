@@ -7,7 +7,8 @@ use ockam_core::compat::rand::random;
 use ockam_core::{route, Result};
 use ockam_node::Context;
 use ockam_transport_tcp::{
-    TcpConnectionOptions, TcpInletOptions, TcpListenerOptions, TcpOutletOptions, TcpTransport,
+    HttpRewrite, PortalCompression, TcpConnectionOptions, TcpInletOptions, TcpListenerOptions,
+    TcpOutletOptions, TcpTransport,
 };
 
 const LENGTH: usize = 32;
@@ -303,3 +304,87 @@ async fn portal__update_route__should_succeed(ctx: &mut Context) -> Result<()> {
 
     Ok(())
 }
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__compression__should_succeed(ctx: &mut Context) -> Result<()> {
+    // a compressible payload, larger than a single chunk
+    let payload = "hello ".repeat(20_000).into_bytes();
+    let expected = payload.clone();
+
+    let tcp = TcpTransport::create(ctx).await?;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet(
+        "outlet",
+        bind_address,
+        TcpOutletOptions::new().with_compression(PortalCompression::Zstd),
+    )
+    .await?;
+    let inlet = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            TcpInletOptions::new().with_compression(PortalCompression::Zstd),
+        )
+        .await?;
+
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut received = vec![0u8; expected.len()];
+        stream.read_exact(&mut received).await.unwrap();
+        assert_eq!(received, expected);
+        stream.write_all(&received).await.unwrap();
+        stream
+    });
+
+    let mut stream = TcpStream::connect(inlet.socket_address()).await.unwrap();
+    stream.write_all(&payload).await.unwrap();
+    let mut received = vec![0u8; payload.len()];
+    stream.read_exact(&mut received).await.unwrap();
+    assert_eq!(received, payload);
+
+    let res = handle.await;
+    assert!(res.is_ok());
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__http_rewrite__should_rewrite_requests(ctx: &mut Context) -> Result<()> {
+    let request = b"GET / HTTP/1.1\r\nHost: localhost\r\nX-Identifier: spoofed\r\n\r\n";
+    let expected = b"GET / HTTP/1.1\r\nHost: app.internal\r\nX-Identifier: I1234\r\n\r\n";
+
+    let tcp = TcpTransport::create(ctx).await?;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet("outlet", bind_address, TcpOutletOptions::new())
+        .await?;
+    let http_rewrite = HttpRewrite::new()
+        .with_header("X-Identifier", "I1234")
+        .with_host("app.internal");
+    let inlet = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            TcpInletOptions::new().with_http_rewrite(http_rewrite),
+        )
+        .await?;
+
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut received = vec![0u8; expected.len()];
+        stream.read_exact(&mut received).await.unwrap();
+        assert_eq!(received, expected);
+        stream
+    });
+
+    let mut stream = TcpStream::connect(inlet.socket_address()).await.unwrap();
+    stream.write_all(request).await.unwrap();
+
+    let res = handle.await;
+    assert!(res.is_ok());
+
+    Ok(())
+}
@@ -18,12 +18,14 @@ extern crate core;
 extern crate alloc;
 
 mod options;
+mod portal;
 mod puncture;
 mod transport;
 
 mod workers;
 
 pub use options::UdpBindOptions;
+pub use portal::{UdpInlet, UdpInletOptions, UdpOutletOptions, DEFAULT_UDP_FLOW_IDLE_TIMEOUT};
 pub use puncture::*;
 pub use transport::{UdpBind, UdpBindArguments, UdpTransport, UdpTransportExtension};
 
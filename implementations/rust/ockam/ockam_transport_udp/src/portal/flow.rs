@@ -0,0 +1,39 @@
+use core::time::Duration;
+use ockam_core::compat::sync::Mutex;
+use std::time::Instant;
+
+/// Maximum size of a UDP datagram payload
+pub(super) const MAX_DATAGRAM_SIZE: usize = 65_535;
+
+/// Maximum time between two checks for idle flows
+pub(super) const FLOW_EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Time of the last datagram sent or received on a flow, shared by the workers handling
+/// both directions of that flow
+#[derive(Debug)]
+pub(super) struct UdpFlowActivity {
+    last_activity: Mutex<Instant>,
+}
+
+impl UdpFlowActivity {
+    pub(super) fn new() -> Self {
+        Self {
+            last_activity: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Record some traffic on the flow
+    pub(super) fn touch(&self) {
+        *self.last_activity.lock().unwrap() = Instant::now();
+    }
+
+    /// Return true if there was no traffic on the flow for at least `idle_timeout`
+    pub(super) fn is_idle(&self, idle_timeout: Duration) -> bool {
+        self.last_activity.lock().unwrap().elapsed() >= idle_timeout
+    }
+
+    /// Time left before the flow becomes idle
+    pub(super) fn time_left(&self, idle_timeout: Duration) -> Duration {
+        idle_timeout.saturating_sub(self.last_activity.lock().unwrap().elapsed())
+    }
+}
@@ -0,0 +1,284 @@
+use crate::portal::flow::{UdpFlowActivity, FLOW_EXPIRY_CHECK_INTERVAL, MAX_DATAGRAM_SIZE};
+use crate::portal::message::UdpPortalMessage;
+use crate::portal::options::UdpInletOptions;
+use core::fmt;
+use core::fmt::Formatter;
+use ockam_core::compat::collections::HashMap;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::{
+    async_trait, route, Address, Any, Decodable, DenyAll, Encodable, LocalMessage, Processor,
+    Result, Route, Routed, Worker,
+};
+use ockam_node::{Context, ProcessorBuilder, WorkerBuilder};
+use ockam_transport_core::TransportError;
+use std::net::SocketAddr;
+use std::time::Instant;
+use tokio::net::UdpSocket;
+use tracing::{debug, error, instrument, warn};
+
+/// Result of [`UdpTransport::create_inlet`](crate::UdpTransport::create_inlet) call.
+#[derive(Clone, Debug)]
+pub struct UdpInlet {
+    socket_address: SocketAddr,
+    processor_address: Address,
+}
+
+impl fmt::Display for UdpInlet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Socket: {}, Processor: {}",
+            self.socket_address, self.processor_address
+        )
+    }
+}
+
+impl UdpInlet {
+    /// Socket Address
+    pub fn socket_address(&self) -> SocketAddr {
+        self.socket_address
+    }
+
+    /// Processor address
+    pub fn processor_address(&self) -> &Address {
+        &self.processor_address
+    }
+}
+
+/// State of the flow of a UDP peer, shared by the processor reading the Inlet socket and the
+/// worker receiving the datagrams sent back by the Outlet
+#[derive(Debug)]
+struct InletFlowState {
+    activity: UdpFlowActivity,
+    /// Route to the Outlet worker dedicated to this flow, once the Outlet has replied
+    outlet_route: Mutex<Option<Route>>,
+}
+
+#[derive(Debug)]
+struct InletFlow {
+    address: Address,
+    state: Arc<InletFlowState>,
+}
+
+/// A UDP Portal Inlet processor
+///
+/// It receives the datagrams sent to the Inlet socket and forwards them to the Outlet.
+/// Each UDP peer gets its own flow, with a [`UdpInletFlowWorker`] sending the replies of the
+/// Outlet back to that peer. Flows are closed after some time without any traffic.
+pub(crate) struct UdpInletProcessor {
+    socket: Arc<UdpSocket>,
+    outlet_route: Route,
+    options: UdpInletOptions,
+    flows: HashMap<SocketAddr, InletFlow>,
+    last_expiry_check: Instant,
+    buf: Vec<u8>,
+}
+
+impl UdpInletProcessor {
+    /// Start a new `UdpInletProcessor`
+    #[instrument(skip_all, name = "UdpInletProcessor::start")]
+    pub(crate) async fn start(
+        ctx: &Context,
+        outlet_route: Route,
+        bind_address: SocketAddr,
+        options: UdpInletOptions,
+    ) -> Result<UdpInlet> {
+        let processor_address = Address::random_tagged("UdpInletProcessor");
+
+        debug!("Binding UdpInletProcessor to {}", bind_address);
+        let socket = match UdpSocket::bind(bind_address).await {
+            Ok(socket) => socket,
+            Err(err) => {
+                error!(%bind_address, %err, "could not bind to address");
+                return Err(TransportError::from(err))?;
+            }
+        };
+        let socket_address = socket.local_addr().map_err(TransportError::from)?;
+
+        let outgoing_access_control = options.outgoing_access_control.clone();
+        let processor = Self {
+            socket: Arc::new(socket),
+            outlet_route,
+            options,
+            flows: HashMap::new(),
+            last_expiry_check: Instant::now(),
+            buf: vec![0; MAX_DATAGRAM_SIZE],
+        };
+
+        ProcessorBuilder::new(processor)
+            .with_address(processor_address.clone())
+            .with_incoming_access_control(DenyAll)
+            .with_outgoing_access_control_arc(outgoing_access_control)
+            .start(ctx)
+            .await?;
+
+        Ok(UdpInlet {
+            socket_address,
+            processor_address,
+        })
+    }
+
+    /// Return the flow of a UDP peer, starting it if this is the first datagram of that peer
+    async fn get_or_start_flow(
+        &mut self,
+        ctx: &Context,
+        peer: SocketAddr,
+    ) -> Result<(Address, Arc<InletFlowState>)> {
+        if let Some(flow) = self.flows.get(&peer) {
+            return Ok((flow.address.clone(), flow.state.clone()));
+        }
+
+        let address = Address::random_tagged("UdpInletFlowWorker");
+        debug!(%peer, %address, "Starting a new UDP inlet flow");
+
+        UdpInletOptions::setup_flow_control(
+            ctx.flow_controls(),
+            &address,
+            self.outlet_route.next()?,
+        );
+
+        let state = Arc::new(InletFlowState {
+            activity: UdpFlowActivity::new(),
+            outlet_route: Mutex::new(None),
+        });
+        let worker = UdpInletFlowWorker {
+            socket: self.socket.clone(),
+            peer,
+            state: state.clone(),
+        };
+        WorkerBuilder::new(worker)
+            .with_address(address.clone())
+            .with_incoming_access_control_arc(self.options.incoming_access_control.clone())
+            .with_outgoing_access_control(DenyAll)
+            .start(ctx)
+            .await?;
+
+        self.flows.insert(
+            peer,
+            InletFlow {
+                address: address.clone(),
+                state: state.clone(),
+            },
+        );
+
+        Ok((address, state))
+    }
+
+    /// Stop the flows which didn't have any traffic for longer than the idle timeout
+    async fn expire_flows(&mut self, ctx: &Context) {
+        self.last_expiry_check = Instant::now();
+
+        let idle_timeout = self.options.idle_timeout;
+        let expired: Vec<SocketAddr> = self
+            .flows
+            .iter()
+            .filter(|(_, flow)| flow.state.activity.is_idle(idle_timeout))
+            .map(|(peer, _)| *peer)
+            .collect();
+
+        for peer in expired {
+            if let Some(flow) = self.flows.remove(&peer) {
+                debug!(%peer, address = %flow.address, "Closing an idle UDP inlet flow");
+                if let Err(err) = ctx.stop_worker(flow.address).await {
+                    debug!(%peer, %err, "Failed to stop a UDP inlet flow worker");
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Processor for UdpInletProcessor {
+    type Context = Context;
+
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.set_cluster(crate::CLUSTER_NAME).await
+    }
+
+    #[instrument(skip_all, name = "UdpInletProcessor::shutdown")]
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        for (_, flow) in self.flows.drain() {
+            let _ = ctx.stop_worker(flow.address).await;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "UdpInletProcessor::process")]
+    async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
+        if self.last_expiry_check.elapsed() >= FLOW_EXPIRY_CHECK_INTERVAL {
+            self.expire_flows(ctx).await;
+        }
+
+        let received = tokio::time::timeout(
+            FLOW_EXPIRY_CHECK_INTERVAL,
+            self.socket.recv_from(&mut self.buf),
+        )
+        .await;
+
+        let (len, peer) = match received {
+            Ok(Ok(received)) => received,
+            Ok(Err(err)) => {
+                // Errors can be reported for a single peer, for example after an ICMP message
+                warn!(%err, "Failed to read a datagram on a UDP inlet");
+                return Ok(true);
+            }
+            // No datagram for a while, the idle flows are checked on the next call
+            Err(_) => return Ok(true),
+        };
+
+        let (flow_address, state) = self.get_or_start_flow(ctx, peer).await?;
+        state.activity.touch();
+
+        let onward_route = state
+            .outlet_route
+            .lock()
+            .unwrap()
+            .clone()
+            .unwrap_or_else(|| self.outlet_route.clone());
+
+        let msg = LocalMessage::new()
+            .with_onward_route(onward_route)
+            .with_return_route(route![flow_address])
+            .with_payload(UdpPortalMessage::Datagram(self.buf[..len].to_vec()).encode()?);
+
+        if let Err(err) = ctx.forward(msg).await {
+            warn!(%peer, %err, "Failed to forward a datagram to the UDP outlet");
+        }
+
+        Ok(true)
+    }
+}
+
+/// Worker sending the datagrams received from the Outlet back to the UDP peer of a flow
+pub(crate) struct UdpInletFlowWorker {
+    socket: Arc<UdpSocket>,
+    peer: SocketAddr,
+    state: Arc<InletFlowState>,
+}
+
+#[async_trait]
+impl Worker for UdpInletFlowWorker {
+    type Message = Any;
+    type Context = Context;
+
+    #[instrument(skip_all, name = "UdpInletFlowWorker::handle_message")]
+    async fn handle_message(&mut self, _ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        let return_route = msg.return_route();
+        let UdpPortalMessage::Datagram(datagram) = UdpPortalMessage::decode(msg.payload())?;
+
+        self.state.activity.touch();
+        // Next datagrams are sent directly to the Outlet worker handling this flow
+        self.state
+            .outlet_route
+            .lock()
+            .unwrap()
+            .get_or_insert(return_route);
+
+        if let Err(err) = self.socket.send_to(&datagram, self.peer).await {
+            warn!(peer = %self.peer, %err, "Failed to send a datagram to a UDP inlet peer");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+use minicbor::{Decode, Encode};
+use ockam_core::{Decodable, Encodable, Message, Result};
+
+/// Message exchanged between a UDP Inlet and a UDP Outlet
+#[derive(Encode, Decode, Debug, Clone)]
+#[rustfmt::skip]
+pub(crate) enum UdpPortalMessage {
+    /// A datagram received by one side of the portal, to be sent by the other side
+    #[n(0)] Datagram(#[n(0)] Vec<u8>),
+}
+
+impl Encodable for UdpPortalMessage {
+    fn encode(self) -> Result<Vec<u8>> {
+        Ok(minicbor::to_vec(self)?)
+    }
+}
+
+impl Decodable for UdpPortalMessage {
+    fn decode(data: &[u8]) -> Result<Self> {
+        Ok(minicbor::decode(data)?)
+    }
+}
+
+impl Message for UdpPortalMessage {}
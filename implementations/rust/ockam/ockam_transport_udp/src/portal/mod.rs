@@ -0,0 +1,10 @@
+mod flow;
+mod inlet;
+mod message;
+mod options;
+mod outlet;
+
+pub use inlet::UdpInlet;
+pub(crate) use inlet::UdpInletProcessor;
+pub use options::{UdpInletOptions, UdpOutletOptions, DEFAULT_UDP_FLOW_IDLE_TIMEOUT};
+pub(crate) use outlet::UdpOutletListenWorker;
@@ -0,0 +1,193 @@
+use core::time::Duration;
+use ockam_core::compat::sync::Arc;
+use ockam_core::flow_control::{FlowControlId, FlowControls};
+use ockam_core::{Address, AllowAll, IncomingAccessControl, OutgoingAccessControl};
+
+/// Default time after which a flow without any traffic is closed
+pub const DEFAULT_UDP_FLOW_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Trust Options for an Inlet
+#[derive(Debug)]
+pub struct UdpInletOptions {
+    pub(super) incoming_access_control: Arc<dyn IncomingAccessControl>,
+    pub(super) outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    pub(super) idle_timeout: Duration,
+}
+
+impl UdpInletOptions {
+    /// Default constructor without Access Control
+    pub fn new() -> Self {
+        Self {
+            incoming_access_control: Arc::new(AllowAll),
+            outgoing_access_control: Arc::new(AllowAll),
+            idle_timeout: DEFAULT_UDP_FLOW_IDLE_TIMEOUT,
+        }
+    }
+
+    /// Close the flow of a UDP peer after it has not sent or received any datagram for that long
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Set Incoming Access Control
+    pub fn with_incoming_access_control_impl(
+        mut self,
+        access_control: impl IncomingAccessControl,
+    ) -> Self {
+        self.incoming_access_control = Arc::new(access_control);
+        self
+    }
+
+    /// Set Incoming Access Control
+    pub fn with_incoming_access_control(
+        mut self,
+        access_control: Arc<dyn IncomingAccessControl>,
+    ) -> Self {
+        self.incoming_access_control = access_control;
+        self
+    }
+
+    /// Set Outgoing Access Control
+    pub fn with_outgoing_access_control_impl(
+        mut self,
+        access_control: impl OutgoingAccessControl,
+    ) -> Self {
+        self.outgoing_access_control = Arc::new(access_control);
+        self
+    }
+
+    /// Set Outgoing Access Control
+    pub fn with_outgoing_access_control(
+        mut self,
+        access_control: Arc<dyn OutgoingAccessControl>,
+    ) -> Self {
+        self.outgoing_access_control = access_control;
+        self
+    }
+
+    pub(super) fn setup_flow_control(
+        flow_controls: &FlowControls,
+        flow_address: &Address,
+        next: &Address,
+    ) {
+        if let Some(flow_control_id) = flow_controls
+            .find_flow_control_with_producer_address(next)
+            .map(|x| x.flow_control_id().clone())
+        {
+            // Allow a sender with corresponding flow_control_id send messages to this address
+            flow_controls.add_consumer(flow_address.clone(), &flow_control_id);
+        }
+    }
+}
+
+impl Default for UdpInletOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Trust Options for an Outlet
+#[derive(Debug)]
+pub struct UdpOutletOptions {
+    pub(super) consumer: Vec<FlowControlId>,
+    pub(super) incoming_access_control: Arc<dyn IncomingAccessControl>,
+    pub(super) outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    pub(super) idle_timeout: Duration,
+}
+
+impl UdpOutletOptions {
+    /// Default constructor without Access Control
+    pub fn new() -> Self {
+        Self {
+            consumer: vec![],
+            incoming_access_control: Arc::new(AllowAll),
+            outgoing_access_control: Arc::new(AllowAll),
+            idle_timeout: DEFAULT_UDP_FLOW_IDLE_TIMEOUT,
+        }
+    }
+
+    /// Close the flow of an Inlet peer after it has not sent or received any datagram for that long
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Set Incoming Access Control
+    pub fn with_incoming_access_control_impl(
+        mut self,
+        access_control: impl IncomingAccessControl,
+    ) -> Self {
+        self.incoming_access_control = Arc::new(access_control);
+        self
+    }
+
+    /// Set Incoming Access Control
+    pub fn with_incoming_access_control(
+        mut self,
+        access_control: Arc<dyn IncomingAccessControl>,
+    ) -> Self {
+        self.incoming_access_control = access_control;
+        self
+    }
+
+    /// Set Outgoing Access Control
+    pub fn with_outgoing_access_control_impl(
+        mut self,
+        access_control: impl OutgoingAccessControl,
+    ) -> Self {
+        self.outgoing_access_control = Arc::new(access_control);
+        self
+    }
+
+    /// Set Outgoing Access Control
+    pub fn with_outgoing_access_control(
+        mut self,
+        access_control: Arc<dyn OutgoingAccessControl>,
+    ) -> Self {
+        self.outgoing_access_control = access_control;
+        self
+    }
+
+    /// Mark that this Outlet listener is a Consumer for to the given [`FlowControlId`]
+    /// Also, in this case spawned flow workers will be marked as Consumers with [`FlowControlId`]
+    /// of the message that was used to create the flow
+    #[allow(clippy::wrong_self_convention)]
+    pub fn as_consumer(mut self, id: &FlowControlId) -> Self {
+        self.consumer.push(id.clone());
+
+        self
+    }
+
+    pub(super) fn setup_flow_control_for_outlet_listener(
+        &self,
+        flow_controls: &FlowControls,
+        address: &Address,
+    ) {
+        for id in &self.consumer {
+            flow_controls.add_consumer(address.clone(), id);
+        }
+    }
+
+    pub(super) fn setup_flow_control_for_flow(
+        flow_controls: &FlowControls,
+        flow_address: &Address,
+        src_addr: &Address,
+    ) {
+        // Check if the Worker that send us this message is a Producer
+        // If yes - the flow worker will be added to that flow control to be able to receive
+        // further messages from that Producer
+        if let Some(producer_flow_control_id) = flow_controls
+            .get_flow_control_with_producer(src_addr)
+            .map(|x| x.flow_control_id().clone())
+        {
+            flow_controls.add_consumer(flow_address.clone(), &producer_flow_control_id);
+        }
+    }
+}
+
+impl Default for UdpOutletOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
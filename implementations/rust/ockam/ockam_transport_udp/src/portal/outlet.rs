@@ -0,0 +1,253 @@
+use crate::portal::flow::{UdpFlowActivity, MAX_DATAGRAM_SIZE};
+use crate::portal::message::UdpPortalMessage;
+use crate::portal::options::UdpOutletOptions;
+use core::time::Duration;
+use ockam_core::compat::collections::HashMap;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::{
+    async_trait, route, Address, AllowAll, Any, Decodable, DenyAll, Encodable, LocalMessage,
+    Processor, Result, Route, Routed, Worker,
+};
+use ockam_node::{Context, ProcessorBuilder, WorkerBuilder};
+use ockam_transport_core::{HostnamePort, TransportError};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use tokio::net::UdpSocket;
+use tracing::{debug, instrument, warn};
+
+/// Addresses of the workers handling a flow, indexed by the route to the Inlet worker of that flow
+type OutletFlows = Arc<Mutex<HashMap<Route, OutletFlow>>>;
+
+#[derive(Clone, Debug)]
+struct OutletFlow {
+    worker_address: Address,
+    processor_address: Address,
+}
+
+/// A UDP Portal Outlet listen worker
+///
+/// It receives the datagrams of the Inlets and starts a flow for each Inlet peer, with its own
+/// UDP socket connected to the target. Flows are closed after some time without any traffic.
+pub(crate) struct UdpOutletListenWorker {
+    peer: HostnamePort,
+    options: UdpOutletOptions,
+    flows: OutletFlows,
+}
+
+impl UdpOutletListenWorker {
+    /// Start a new `UdpOutletListenWorker`
+    #[instrument(skip_all, name = "UdpOutletListenWorker::start")]
+    pub(crate) async fn start(
+        ctx: &Context,
+        address: Address,
+        peer: HostnamePort,
+        options: UdpOutletOptions,
+    ) -> Result<()> {
+        let access_control = options.incoming_access_control.clone();
+
+        options.setup_flow_control_for_outlet_listener(ctx.flow_controls(), &address);
+
+        let worker = Self {
+            peer,
+            options,
+            flows: Default::default(),
+        };
+
+        WorkerBuilder::new(worker)
+            .with_address(address)
+            .with_incoming_access_control_arc(access_control)
+            // The listener only relays datagrams to the local flow workers
+            .with_outgoing_access_control(AllowAll)
+            .start(ctx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Start the workers handling the flow of an Inlet peer
+    async fn start_flow(
+        &self,
+        ctx: &Context,
+        inlet_route: Route,
+        src_addr: &Address,
+    ) -> Result<OutletFlow> {
+        let target = tokio::net::lookup_host(self.peer.to_string())
+            .await
+            .map_err(TransportError::from)?
+            .next()
+            .ok_or(TransportError::InvalidAddress)?;
+        let bind_address = match target {
+            SocketAddr::V4(_) => SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0),
+            SocketAddr::V6(_) => SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0),
+        };
+        let socket = UdpSocket::bind(bind_address)
+            .await
+            .map_err(|_| TransportError::BindFailed)?;
+        socket.connect(target).await.map_err(TransportError::from)?;
+        let socket = Arc::new(socket);
+
+        let flow = OutletFlow {
+            worker_address: Address::random_tagged("UdpOutletFlowWorker"),
+            processor_address: Address::random_tagged("UdpOutletFlowProcessor"),
+        };
+        debug!(%target, worker = %flow.worker_address, "Starting a new UDP outlet flow");
+
+        UdpOutletOptions::setup_flow_control_for_flow(
+            ctx.flow_controls(),
+            &flow.worker_address,
+            src_addr,
+        );
+
+        let activity = Arc::new(UdpFlowActivity::new());
+
+        let worker = UdpOutletFlowWorker {
+            socket: socket.clone(),
+            activity: activity.clone(),
+        };
+        WorkerBuilder::new(worker)
+            .with_address(flow.worker_address.clone())
+            .with_incoming_access_control_arc(self.options.incoming_access_control.clone())
+            .with_outgoing_access_control(DenyAll)
+            .start(ctx)
+            .await?;
+
+        let processor = UdpOutletFlowProcessor {
+            socket,
+            activity,
+            idle_timeout: self.options.idle_timeout,
+            inlet_route: inlet_route.clone(),
+            worker_address: flow.worker_address.clone(),
+            flows: self.flows.clone(),
+            buf: vec![0; MAX_DATAGRAM_SIZE],
+        };
+        ProcessorBuilder::new(processor)
+            .with_address(flow.processor_address.clone())
+            .with_incoming_access_control(DenyAll)
+            .with_outgoing_access_control_arc(self.options.outgoing_access_control.clone())
+            .start(ctx)
+            .await?;
+
+        self.flows.lock().unwrap().insert(inlet_route, flow.clone());
+
+        Ok(flow)
+    }
+}
+
+#[async_trait]
+impl Worker for UdpOutletListenWorker {
+    type Message = Any;
+    type Context = Context;
+
+    #[instrument(skip_all, name = "UdpOutletListenWorker::shutdown")]
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        let flows: Vec<OutletFlow> = self.flows.lock().unwrap().values().cloned().collect();
+        for flow in flows {
+            let _ = ctx.stop_processor(flow.processor_address).await;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "UdpOutletListenWorker::handle_message")]
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        let inlet_route = msg.return_route();
+        let src_addr = msg.src_addr();
+
+        let flow = self.flows.lock().unwrap().get(&inlet_route).cloned();
+        let flow = match flow {
+            Some(flow) => flow,
+            None => self.start_flow(ctx, inlet_route, &src_addr).await?,
+        };
+
+        // The datagrams received before the Inlet knows the route to the flow worker
+        // are relayed to it
+        let msg = msg
+            .into_local_message()
+            .pop_front_onward_route()?
+            .push_front_onward_route(&flow.worker_address);
+        ctx.forward(msg).await
+    }
+}
+
+/// Worker sending the datagrams received from an Inlet to the target of the Outlet
+pub(crate) struct UdpOutletFlowWorker {
+    socket: Arc<UdpSocket>,
+    activity: Arc<UdpFlowActivity>,
+}
+
+#[async_trait]
+impl Worker for UdpOutletFlowWorker {
+    type Message = Any;
+    type Context = Context;
+
+    #[instrument(skip_all, name = "UdpOutletFlowWorker::handle_message")]
+    async fn handle_message(&mut self, _ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        let UdpPortalMessage::Datagram(datagram) = UdpPortalMessage::decode(msg.payload())?;
+
+        self.activity.touch();
+        if let Err(err) = self.socket.send(&datagram).await {
+            warn!(%err, "Failed to send a datagram to the target of a UDP outlet");
+        }
+
+        Ok(())
+    }
+}
+
+/// Processor sending the datagrams received from the target of the Outlet back to an Inlet.
+/// It stops the flow once it has been idle for too long.
+pub(crate) struct UdpOutletFlowProcessor {
+    socket: Arc<UdpSocket>,
+    activity: Arc<UdpFlowActivity>,
+    idle_timeout: Duration,
+    inlet_route: Route,
+    worker_address: Address,
+    flows: OutletFlows,
+    buf: Vec<u8>,
+}
+
+#[async_trait]
+impl Processor for UdpOutletFlowProcessor {
+    type Context = Context;
+
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.set_cluster(crate::CLUSTER_NAME).await
+    }
+
+    #[instrument(skip_all, name = "UdpOutletFlowProcessor::shutdown")]
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.flows.lock().unwrap().remove(&self.inlet_route);
+        let _ = ctx.stop_worker(self.worker_address.clone()).await;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "UdpOutletFlowProcessor::process")]
+    async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
+        let time_left = self.activity.time_left(self.idle_timeout);
+        if time_left.is_zero() {
+            debug!(worker = %self.worker_address, "Closing an idle UDP outlet flow");
+            return Ok(false);
+        }
+
+        let len = match tokio::time::timeout(time_left, self.socket.recv(&mut self.buf)).await {
+            Ok(Ok(len)) => len,
+            Ok(Err(err)) => {
+                // For example, the target is not listening and an ICMP message was received
+                warn!(%err, "Failed to read a datagram from the target of a UDP outlet");
+                return Ok(true);
+            }
+            Err(_) => return Ok(true),
+        };
+        self.activity.touch();
+
+        let msg = LocalMessage::new()
+            .with_onward_route(self.inlet_route.clone())
+            .with_return_route(route![self.worker_address.clone()])
+            .with_payload(UdpPortalMessage::Datagram(self.buf[..len].to_vec()).encode()?);
+
+        if let Err(err) = ctx.forward(msg).await {
+            warn!(%err, "Failed to forward a datagram to a UDP inlet");
+        }
+
+        Ok(true)
+    }
+}
@@ -3,9 +3,10 @@ use crate::puncture::rendezvous_service::RendezvousClient;
 use crate::{PunctureError, UdpBind, UdpPuncture, UdpPunctureOptions};
 use ockam_core::{async_trait, route, Address, Any, Decodable, Route, Routed, Worker};
 use ockam_node::Context;
+use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::broadcast::Sender;
 use tokio::task::JoinHandle;
-use tracing::{debug, error, info};
+use tracing::{debug, info};
 
 pub struct UdpPunctureNegotiationWorker {
     onward_route: Option<Route>,
@@ -240,8 +241,11 @@ impl Worker for UdpPunctureNegotiationWorker {
                                         _ = notify_reachable.send(route![])
                                     }
                                 }
-                                Err(err) => {
-                                    error!("Error receiving UDP puncture notification: {}", err);
+                                Err(RecvError::Lagged(_)) => continue,
+                                Err(err @ RecvError::Closed) => {
+                                    // The puncture was stopped, there is nothing left to notify
+                                    debug!("Stopped receiving UDP puncture notifications: {}", err);
+                                    break;
                                 }
                             }
                         }
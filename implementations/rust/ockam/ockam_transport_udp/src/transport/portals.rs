@@ -0,0 +1,78 @@
+use crate::portal::{UdpInletProcessor, UdpOutletListenWorker};
+use crate::{UdpInlet, UdpInletOptions, UdpOutletOptions, UdpTransport};
+use core::fmt::Debug;
+use ockam_core::{Address, Result, Route};
+use ockam_transport_core::{parse_socket_addr, HostnamePort};
+use tracing::instrument;
+
+impl UdpTransport {
+    /// Create a UDP Inlet that listens on a UDP socket and forwards the datagrams of each UDP
+    /// peer to the Outlet at `outlet_route`. The datagrams sent back by the Outlet are returned
+    /// to the corresponding peer.
+    /// Pair of corresponding Inlet and Outlet is called Portal.
+    ///
+    /// ```rust
+    /// use ockam_transport_udp::{UdpInletOptions, UdpTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::{Result, route};
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let route = route!["outlet"];
+    ///
+    /// let udp = UdpTransport::create(&ctx).await?;
+    /// let inlet = udp.create_inlet("127.0.0.1:5353", route, UdpInletOptions::new()).await?;
+    /// # udp.stop_inlet(inlet.processor_address().clone()).await?;
+    /// # Ok(()) }
+    /// ```
+    #[instrument(skip(self), fields(address = ? bind_addr.clone().into(), outlet_route = ? outlet_route.clone()))]
+    pub async fn create_inlet(
+        &self,
+        bind_addr: impl Into<String> + Clone + Debug,
+        outlet_route: impl Into<Route> + Clone + Debug,
+        options: UdpInletOptions,
+    ) -> Result<UdpInlet> {
+        let bind_address = parse_socket_addr(&bind_addr.into())?;
+        UdpInletProcessor::start(&self.ctx, outlet_route.into(), bind_address, options).await
+    }
+
+    /// Stop inlet at addr
+    #[instrument(skip(self), fields(address = ? addr.clone().into()))]
+    pub async fn stop_inlet(&self, addr: impl Into<Address> + Clone + Debug) -> Result<()> {
+        self.ctx.stop_processor(addr).await?;
+
+        Ok(())
+    }
+
+    /// Create a UDP Outlet at `address`. Each peer of an Inlet gets its own UDP socket to
+    /// `peer`, so that the replies of the UDP server can be routed back to that peer.
+    ///
+    /// ```rust
+    /// use ockam_transport_udp::{UdpOutletOptions, UdpTransport};
+    /// # use ockam_transport_core::HostnamePort;
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    ///
+    /// let udp = UdpTransport::create(&ctx).await?;
+    /// let peer = HostnamePort::new("localhost", 53);
+    /// udp.create_outlet("outlet", peer, UdpOutletOptions::new()).await?;
+    /// # udp.stop_outlet("outlet").await?;
+    /// # Ok(()) }
+    /// ```
+    #[instrument(skip(self), fields(address = ? address.clone().into(), peer = %peer))]
+    pub async fn create_outlet(
+        &self,
+        address: impl Into<Address> + Clone + Debug,
+        peer: HostnamePort,
+        options: UdpOutletOptions,
+    ) -> Result<()> {
+        UdpOutletListenWorker::start(&self.ctx, address.into(), peer, options).await
+    }
+
+    /// Stop outlet at addr
+    #[instrument(skip(self), fields(address = % addr.clone().into()))]
+    pub async fn stop_outlet(&self, addr: impl Into<Address> + Clone + Debug) -> Result<()> {
+        self.ctx.stop_worker(addr).await?;
+
+        Ok(())
+    }
+}
@@ -4,14 +4,17 @@ use ockam_core::{Decodable, Encodable};
 use ockam_transport_core::TransportError;
 use tokio_util::codec::{Decoder, Encoder};
 
+/// Length of the prefix containing the length of an encoded message
+const LENGTH_PREFIX_SIZE: usize = 2;
+
 pub(crate) struct TransportMessageCodec;
 
 impl Encoder<TransportMessage> for TransportMessageCodec {
     type Error = TransportError;
     fn encode(&mut self, item: TransportMessage, dst: &mut BytesMut) -> Result<(), Self::Error> {
         let msg_buf = item.encode().map_err(|_| TransportError::SendBadMessage)?;
-        let len = msg_buf.len();
-        dst.put_u16(len as u16);
+        let len = u16::try_from(msg_buf.len()).map_err(|_| TransportError::SendBadMessage)?;
+        dst.put_u16(len);
         dst.put(&msg_buf[..]);
         Ok(())
     }
@@ -25,11 +28,57 @@ impl Decoder for TransportMessageCodec {
             return Ok(None);
         }
 
+        // A truncated datagram is dropped entirely. Otherwise the same bytes
+        // would be decoded again when the next frame is read
+        if src.len() < LENGTH_PREFIX_SIZE {
+            src.clear();
+            return Err(TransportError::RecvBadMessage);
+        }
         let len = src.get_u16() as usize;
-        // FIXME: CAN PANIC
+        if src.len() < len {
+            src.clear();
+            return Err(TransportError::RecvBadMessage);
+        }
+
         let msg = TransportMessage::decode(&src.split_to(len)[..])
             .map_err(|_| TransportError::RecvBadMessage)?;
 
         Ok(Some(msg))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::route;
+
+    #[test]
+    fn test_encode_decode() {
+        let message = TransportMessage::v1(route!["a", "b"], route!["c"], b"hello".to_vec());
+        let mut buffer = BytesMut::new();
+        TransportMessageCodec
+            .encode(message.clone(), &mut buffer)
+            .unwrap();
+
+        let decoded = TransportMessageCodec.decode(&mut buffer).unwrap();
+        assert_eq!(decoded, Some(message));
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_decode_truncated_datagram() {
+        let message = TransportMessage::v1(route!["a"], route!["b"], b"hello".to_vec());
+        let mut buffer = BytesMut::new();
+        TransportMessageCodec.encode(message, &mut buffer).unwrap();
+
+        // the datagram is shorter than its declared length
+        let mut truncated = BytesMut::from(&buffer[..buffer.len() - 1]);
+        assert!(TransportMessageCodec.decode(&mut truncated).is_err());
+        assert!(truncated.is_empty());
+
+        // the datagram is shorter than the length prefix
+        let mut truncated = BytesMut::from(&buffer[..1]);
+        assert!(TransportMessageCodec.decode(&mut truncated).is_err());
+        assert!(truncated.is_empty());
+    }
+}
@@ -1,9 +1,15 @@
 use ockam_core::compat::rand::{self, Rng};
 use ockam_core::{route, Result, Routed, Worker};
 use ockam_node::{Context, MessageSendReceiveOptions};
-use ockam_transport_udp::{UdpBindArguments, UdpBindOptions, UdpTransport, UDP};
+use ockam_transport_core::HostnamePort;
+use ockam_transport_udp::{
+    RendezvousService, UdpBindArguments, UdpBindOptions, UdpInletOptions, UdpOutletOptions,
+    UdpPunctureNegotiation, UdpPunctureNegotiationListener, UdpPunctureNegotiationListenerOptions,
+    UdpTransport, UDP,
+};
 use std::net::SocketAddr;
 use std::time::Duration;
+use tokio::net::UdpSocket;
 use tracing::{debug, error, trace};
 
 mod utils;
@@ -367,6 +373,120 @@ async fn send_receive_two_known_udp_peers(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+/// Each UDP peer of an inlet gets the replies of the UDP server it sent datagrams to
+#[ockam_macros::test]
+async fn portal_routes_replies_to_each_peer(ctx: &mut Context) -> Result<()> {
+    let transport = UdpTransport::create(ctx).await?;
+
+    // UDP echo server
+    let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let server_address = server.local_addr().unwrap();
+    tokio::spawn(async move {
+        let mut buf = vec![0; 1024];
+        while let Ok((len, peer)) = server.recv_from(&mut buf).await {
+            let _ = server.send_to(&buf[..len], peer).await;
+        }
+    });
+
+    transport
+        .create_outlet(
+            "outlet",
+            HostnamePort::from_socket_addr(server_address)?,
+            UdpOutletOptions::new(),
+        )
+        .await?;
+    let inlet = transport
+        .create_inlet("127.0.0.1:0", route!["outlet"], UdpInletOptions::new())
+        .await?;
+
+    let peer1 = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let peer2 = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    peer1.connect(inlet.socket_address()).await.unwrap();
+    peer2.connect(inlet.socket_address()).await.unwrap();
+
+    let mut buf = vec![0; 1024];
+    for i in 0..3 {
+        for (name, peer) in [("peer1", &peer1), ("peer2", &peer2)] {
+            let datagram = format!("{name} {i}");
+            peer.send(datagram.as_bytes()).await.unwrap();
+            let len = tokio::time::timeout(TIMEOUT, peer.recv(&mut buf))
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(&buf[..len], datagram.as_bytes());
+        }
+    }
+
+    transport
+        .stop_inlet(inlet.processor_address().clone())
+        .await?;
+    transport.stop_outlet("outlet").await?;
+
+    Ok(())
+}
+
+/// Two nodes are paired through a rendezvous service, then send messages to each other
+/// over a UDP puncture, without going through the rendezvous service
+#[ockam_macros::test]
+async fn pair_two_nodes_through_a_rendezvous_service(ctx: &mut Context) -> Result<()> {
+    let transport = UdpTransport::create(ctx).await?;
+
+    // Publicly reachable rendezvous service
+    RendezvousService::start(ctx, "rendezvous").await?;
+    let rendezvous_bind = transport
+        .bind(
+            UdpBindArguments::new().with_bind_address("127.0.0.1:0")?,
+            UdpBindOptions::new(),
+        )
+        .await?;
+    ctx.flow_controls()
+        .add_consumer("rendezvous", rendezvous_bind.flow_control_id());
+    let rendezvous_route = route![
+        (UDP, rendezvous_bind.bind_address().to_string()),
+        "rendezvous"
+    ];
+
+    // The responder listens to the puncture requests sent via a side channel,
+    // a local route in this test, and hosts the echoer
+    UdpPunctureNegotiationListener::create(
+        ctx,
+        "puncture_listener",
+        &transport,
+        rendezvous_route.clone(),
+        UdpPunctureNegotiationListenerOptions::new(),
+    )
+    .await?;
+    ctx.start_worker("echoer", Echoer::new(false)).await?;
+
+    // The initiator asks the responder to open a puncture
+    let (mut receiver, _sender) = UdpPunctureNegotiation::start_negotiation(
+        ctx,
+        route!["puncture_listener"],
+        &transport,
+        rendezvous_route,
+    )
+    .await?;
+    let puncture_route = tokio::time::timeout(TIMEOUT, receiver.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!puncture_route.is_empty(), "the puncture must be open");
+
+    let reply: Routed<String> = ctx
+        .send_and_receive_extended(
+            route![puncture_route.clone(), "echoer"],
+            String::from("Hola"),
+            MessageSendReceiveOptions::new().with_timeout(TIMEOUT),
+        )
+        .await?;
+
+    // The reply came back through the puncture
+    assert_eq!(reply.return_route().next()?, puncture_route.next()?);
+    assert_eq!(reply.into_body()?, "Hola");
+
+    Ok(())
+}
+
 pub struct Echoer {
     check_sender_is_the_same: bool,
     prev_src_addr: Option<String>,
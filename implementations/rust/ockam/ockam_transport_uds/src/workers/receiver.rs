@@ -1,8 +1,6 @@
 use crate::workers::UdsSendWorkerMsg;
 
-use ockam_core::{
-    async_trait, Address, Decodable, LocalMessage, Processor, Result, TransportMessage,
-};
+use ockam_core::{async_trait, Address, LocalMessage, Processor, Result, TransportMessage};
 use ockam_node::Context;
 use ockam_transport_core::TransportError;
 use tokio::{io::AsyncReadExt, net::unix::OwnedReadHalf};
@@ -79,7 +77,8 @@ impl Processor for UdsRecvProcessor {
         }
 
         // Deserialize the message now
-        let msg = TransportMessage::decode(&buf).map_err(|_| TransportError::RecvBadMessage)?;
+        let msg =
+            TransportMessage::decode_message(buf).map_err(|_| TransportError::RecvBadMessage)?;
         let mut msg = LocalMessage::from_transport_message(msg);
 
         // Heartbeat message
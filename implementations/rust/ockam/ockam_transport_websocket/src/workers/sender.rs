@@ -191,7 +191,7 @@ where
 
         let recipient = msg.msg_addr();
         if recipient == self.internal_addr {
-            let msg = TransportMessage::latest(route![], route![], vec![]);
+            let msg = TransportMessage::latest(route![], route![], Vec::<u8>::new());
             // Sending empty heartbeat
             if ws_sink
                 .send(WebSocketMessage::from(msg.encode()?))
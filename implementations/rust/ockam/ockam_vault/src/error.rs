@@ -27,6 +27,10 @@ pub enum VaultError {
     AeadAesGcmEncrypt,
     /// AES decryption failed
     AeadAesGcmDecrypt,
+    /// ChaCha20-Poly1305 encryption failed
+    AeadChaCha20Poly1305Encrypt,
+    /// ChaCha20-Poly1305 decryption failed
+    AeadChaCha20Poly1305Decrypt,
     /// HKDF key expansion failed
     HkdfExpandError,
     /// Invalid Sha256 Output length
@@ -35,6 +39,10 @@ pub enum VaultError {
     InvalidSignatureSize,
     /// Aead secret was not found in the storage
     AeadSecretNotFound,
+    /// The secrets are encrypted and the vault must be unlocked first
+    VaultLocked,
+    /// The key used to decrypt the secrets of a vault is incorrect
+    InvalidSecretsEncryptionKey,
 }
 
 impl ockam_core::compat::error::Error for VaultError {}
@@ -52,11 +60,17 @@ impl core::fmt::Display for VaultError {
             Self::InvalidHkdfOutputType => write!(f, "invalid HKDF output type"),
             Self::AeadAesGcmEncrypt => write!(f, "aes encryption failed"),
             Self::AeadAesGcmDecrypt => write!(f, "aes decryption failed"),
+            Self::AeadChaCha20Poly1305Encrypt => write!(f, "chacha20-poly1305 encryption failed"),
+            Self::AeadChaCha20Poly1305Decrypt => write!(f, "chacha20-poly1305 decryption failed"),
             Self::HkdfExpandError => write!(f, "hkdf key expansion failed"),
             Self::KeyNotFound => write!(f, "key not found"),
             Self::InvalidSha256Len => write!(f, "invalid sha256 len"),
             Self::InvalidSignatureSize => write!(f, "invalid signature len"),
             Self::AeadSecretNotFound => write!(f, "aead secret was not found in the storage"),
+            Self::VaultLocked => write!(f, "the vault is locked"),
+            Self::InvalidSecretsEncryptionKey => {
+                write!(f, "the key used to decrypt the secrets is incorrect")
+            }
         }
     }
 }
@@ -68,6 +82,7 @@ impl From<VaultError> for Error {
         let kind = match err {
             InvalidPublicKey | InvalidKeyType | InvalidHkdfOutputType => Kind::Misuse,
             UnknownEcdhKeyType => Kind::NotFound,
+            VaultLocked => Kind::Conflict,
             _ => Kind::Invalid,
         };
 
@@ -0,0 +1,67 @@
+use aws_lc_rs::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305};
+
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+
+use crate::{AeadSecret, VaultError};
+
+const TAG_LENGTH: usize = 16;
+
+pub struct ChaChaGen(LessSafeKey);
+
+impl ChaChaGen {
+    pub fn encrypt_message(
+        &self,
+        destination: &mut Vec<u8>,
+        msg: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<()> {
+        destination.reserve(msg.len() + TAG_LENGTH);
+        let encrypted_payload_start = destination.len();
+        destination.extend_from_slice(msg);
+
+        let tag = self
+            .0
+            .seal_in_place_separate_tag(
+                Nonce::try_assume_unique_for_key(nonce)
+                    .map_err(|_| VaultError::AeadChaCha20Poly1305Encrypt)?,
+                Aad::from(aad),
+                &mut destination[encrypted_payload_start..],
+            )
+            .map_err(|_| VaultError::AeadChaCha20Poly1305Encrypt)?;
+
+        destination.extend_from_slice(tag.as_ref());
+
+        Ok(())
+    }
+
+    pub fn decrypt_message(&self, msg: &[u8], nonce: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        if msg.len() < TAG_LENGTH {
+            return Err(VaultError::AeadChaCha20Poly1305Decrypt)?;
+        }
+
+        // the tag is stored at the end of the message
+        let (msg, tag) = msg.split_at(msg.len() - TAG_LENGTH);
+        let mut out = vec![0u8; msg.len()];
+        self.0
+            .open_separate_gather(
+                Nonce::try_assume_unique_for_key(nonce)
+                    .map_err(|_| VaultError::AeadChaCha20Poly1305Decrypt)?,
+                Aad::from(aad),
+                msg,
+                tag,
+                &mut out,
+            )
+            .map_err(|_| VaultError::AeadChaCha20Poly1305Decrypt)?;
+
+        Ok(out)
+    }
+}
+
+/// Make a ChaCha20-Poly1305 cipher. The secret must have a length of 32 bytes
+pub(super) fn make_chacha(secret: &AeadSecret) -> Result<ChaChaGen> {
+    let unbound_key = UnboundKey::new(&CHACHA20_POLY1305, &secret.0)
+        .map_err(|_| VaultError::InvalidSecretLength)?;
+    Ok(ChaChaGen(LessSafeKey::new(unbound_key)))
+}
@@ -0,0 +1,68 @@
+use crate::{AeadSecret, VaultError, AES_NONCE_LENGTH};
+
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+
+use chacha20poly1305::aead::{Aead, AeadInPlace, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, KeyInit};
+
+/// ChaCha20-Poly1305 uses the same 96 bits nonces as AES-GCM
+const CHACHA_NONCE_LENGTH: usize = AES_NONCE_LENGTH;
+
+/// ChaCha20-Poly1305 key length
+const CHACHA_KEY_LENGTH: usize = 32;
+
+/// ChaCha20-Poly1305 tag length
+const TAG_LENGTH: usize = 16;
+
+pub struct ChaChaGen(ChaCha20Poly1305);
+
+impl ChaChaGen {
+    pub fn encrypt_message(
+        &self,
+        destination: &mut Vec<u8>,
+        msg: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<()> {
+        if nonce.len() != CHACHA_NONCE_LENGTH {
+            return Err(VaultError::AeadChaCha20Poly1305Encrypt)?;
+        }
+
+        destination.reserve(msg.len() + TAG_LENGTH);
+        let encrypted_payload_start = destination.len();
+        destination.extend_from_slice(msg);
+
+        let tag = self
+            .0
+            .encrypt_in_place_detached(
+                nonce.into(),
+                aad,
+                &mut destination[encrypted_payload_start..],
+            )
+            .map_err(|_| VaultError::AeadChaCha20Poly1305Encrypt)?;
+
+        destination.extend_from_slice(tag.as_slice());
+
+        Ok(())
+    }
+
+    pub fn decrypt_message(&self, msg: &[u8], nonce: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        if nonce.len() != CHACHA_NONCE_LENGTH {
+            return Err(VaultError::AeadChaCha20Poly1305Decrypt)?;
+        }
+
+        Ok(self
+            .0
+            .decrypt(nonce.into(), Payload { aad, msg })
+            .map_err(|_| VaultError::AeadChaCha20Poly1305Decrypt)?)
+    }
+}
+
+/// Make a ChaCha20-Poly1305 cipher. The secret must have a length of 32 bytes
+pub(super) fn make_chacha(secret: &AeadSecret) -> Result<ChaChaGen> {
+    if secret.0.len() != CHACHA_KEY_LENGTH {
+        return Err(VaultError::InvalidSecretLength.into());
+    }
+    Ok(ChaChaGen(ChaCha20Poly1305::new(Key::from_slice(&secret.0))))
+}
@@ -11,10 +11,14 @@ compile_error! {"One feature must be enabled: \"aws-lc\" or \"rust-crypto\""}
 cfg_if! {
     if #[cfg(feature = "aws-lc")] {
         mod aes_aws_lc;
-        use aes_aws_lc::make_aes;
+        mod chacha_aws_lc;
+        pub(crate) use aes_aws_lc::make_aes;
+        use chacha_aws_lc::make_chacha;
     } else {
         mod aes_rs;
-        use aes_rs::make_aes;
+        mod chacha_rs;
+        pub(crate) use aes_rs::make_aes;
+        use chacha_rs::make_chacha;
     }
 }
 
@@ -14,18 +14,18 @@ use crate::storage::SecretsRepository;
 use crate::storage::SecretsSqlxDatabase;
 
 use crate::{
-    AeadSecret, AeadSecretKeyHandle, BufferSecret, HKDFNumberOfOutputs, HandleToSecret, HashOutput,
-    HkdfOutput, SecretBufferHandle, SoftwareVaultForVerifyingSignatures, VaultError,
-    VaultForSecureChannels, X25519PublicKey, X25519SecretKey, X25519SecretKeyHandle,
-    AEAD_SECRET_LENGTH,
+    AeadAlgorithm, AeadSecret, AeadSecretKeyHandle, BufferSecret, HKDFNumberOfOutputs,
+    HandleToSecret, HashOutput, HkdfOutput, SecretBufferHandle,
+    SoftwareVaultForVerifyingSignatures, VaultError, VaultForSecureChannels, X25519PublicKey,
+    X25519SecretKey, X25519SecretKeyHandle, AEAD_SECRET_LENGTH,
 };
 
-use super::make_aes;
+use super::{make_aes, make_chacha};
 
 /// [`SecureChannelVault`] implementation using software
 pub struct SoftwareVaultForSecureChannels {
     ephemeral_buffer_secrets: Arc<RwLock<BTreeMap<SecretBufferHandle, BufferSecret>>>,
-    ephemeral_aead_secrets: Arc<RwLock<BTreeMap<AeadSecretKeyHandle, AeadSecret>>>,
+    ephemeral_aead_secrets: Arc<RwLock<BTreeMap<AeadSecretKeyHandle, (AeadAlgorithm, AeadSecret)>>>,
     ephemeral_x25519_secrets: Arc<RwLock<BTreeMap<X25519SecretKeyHandle, X25519SecretKey>>>,
     secrets_repository: Arc<dyn SecretsRepository>,
 }
@@ -201,7 +201,10 @@ impl SoftwareVaultForSecureChannels {
         }
     }
 
-    async fn get_aead_secret(&self, handle: &AeadSecretKeyHandle) -> Result<AeadSecret> {
+    async fn get_aead_secret(
+        &self,
+        handle: &AeadSecretKeyHandle,
+    ) -> Result<(AeadAlgorithm, AeadSecret)> {
         match self.ephemeral_aead_secrets.read().unwrap().get(handle) {
             Some(secret) => Ok(secret.clone()),
             None => Err(VaultError::KeyNotFound)?,
@@ -286,9 +289,15 @@ impl VaultForSecureChannels for SoftwareVaultForSecureChannels {
         nonce: &[u8],
         aad: &[u8],
     ) -> Result<()> {
-        let secret = self.get_aead_secret(secret_key_handle).await?;
-        let aes = make_aes(&secret);
-        aes.encrypt_message(destination, plain_text, nonce, aad)
+        let (algorithm, secret) = self.get_aead_secret(secret_key_handle).await?;
+        match algorithm {
+            AeadAlgorithm::AesGcm => {
+                make_aes(&secret).encrypt_message(destination, plain_text, nonce, aad)
+            }
+            AeadAlgorithm::ChaCha20Poly1305 => {
+                make_chacha(&secret)?.encrypt_message(destination, plain_text, nonce, aad)
+            }
+        }
     }
 
     #[instrument(skip_all)]
@@ -299,22 +308,36 @@ impl VaultForSecureChannels for SoftwareVaultForSecureChannels {
         nonce: &[u8],
         aad: &[u8],
     ) -> Result<Vec<u8>> {
-        let secret = self.get_aead_secret(secret_key_handle).await?;
-        let aes = make_aes(&secret);
-        aes.decrypt_message(cipher_text, nonce, aad)
+        let (algorithm, secret) = self.get_aead_secret(secret_key_handle).await?;
+        match algorithm {
+            AeadAlgorithm::AesGcm => make_aes(&secret).decrypt_message(cipher_text, nonce, aad),
+            AeadAlgorithm::ChaCha20Poly1305 => {
+                make_chacha(&secret)?.decrypt_message(cipher_text, nonce, aad)
+            }
+        }
     }
 
     #[instrument(skip_all)]
     async fn persist_aead_key(&self, secret_key_handle: &AeadSecretKeyHandle) -> Result<()> {
-        let secret = self.get_aead_secret(secret_key_handle).await?;
+        let (algorithm, secret) = self.get_aead_secret(secret_key_handle).await?;
+        self.secrets_repository
+            .store_aead_secret(secret_key_handle, algorithm, secret)
+            .await
+    }
+
+    #[instrument(skip_all)]
+    async fn delete_persisted_aead_key(
+        &self,
+        secret_key_handle: &AeadSecretKeyHandle,
+    ) -> Result<bool> {
         self.secrets_repository
-            .store_aead_secret(secret_key_handle, secret)
+            .delete_aead_secret(secret_key_handle)
             .await
     }
 
     #[instrument(skip_all)]
     async fn load_aead_key(&self, secret_key_handle: &AeadSecretKeyHandle) -> Result<()> {
-        let Some(secret) = self
+        let Some((algorithm, secret)) = self
             .secrets_repository
             .get_aead_secret(secret_key_handle)
             .await?
@@ -325,11 +348,19 @@ impl VaultForSecureChannels for SoftwareVaultForSecureChannels {
         self.ephemeral_aead_secrets
             .write()
             .unwrap()
-            .insert(secret_key_handle.clone(), secret);
+            .insert(secret_key_handle.clone(), (algorithm, secret));
 
         Ok(())
     }
 
+    async fn get_aead_algorithm(
+        &self,
+        secret_key_handle: &AeadSecretKeyHandle,
+    ) -> Result<AeadAlgorithm> {
+        let (algorithm, _) = self.get_aead_secret(secret_key_handle).await?;
+        Ok(algorithm)
+    }
+
     async fn generate_static_x25519_secret_key(&self) -> Result<X25519SecretKeyHandle> {
         let secret = Self::generate_x25519_secret();
 
@@ -395,6 +426,7 @@ impl VaultForSecureChannels for SoftwareVaultForSecureChannels {
     async fn convert_secret_buffer_to_aead_key(
         &self,
         secret_buffer_handle: SecretBufferHandle,
+        algorithm: AeadAlgorithm,
     ) -> Result<AeadSecretKeyHandle> {
         let buffer = match self
             .ephemeral_buffer_secrets
@@ -420,7 +452,7 @@ impl VaultForSecureChannels for SoftwareVaultForSecureChannels {
         self.ephemeral_aead_secrets
             .write()
             .unwrap()
-            .insert(handle.clone(), secret);
+            .insert(handle.clone(), (algorithm, secret));
 
         Ok(handle)
     }
@@ -0,0 +1,318 @@
+use minicbor::{Decode, Encode};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::Result;
+
+use crate::storage::{SecretsEncryptionKey, SecretsRepository};
+use crate::{
+    ECDSASHA256CurveP256SecretKey, EdDSACurve25519SecretKey, HandleToSecret, SigningSecret,
+    SigningSecretKeyHandle, X25519SecretKey, X25519SecretKeyHandle,
+};
+
+/// Version of the format of a [`VaultBackup`]
+pub const VAULT_BACKUP_VERSION: u8 = 1;
+
+const ED_DSA_CURVE_25519: &str = "EdDSACurve25519";
+const EC_DSA_SHA256_CURVE_P256: &str = "ECDSASHA256CurveP256";
+const X25519: &str = "X25519";
+
+/// Backup of the secrets of a vault, wrapped with a recovery key.
+///
+/// The recovery key is derived with argon2 from a passphrase chosen by the user, and the
+/// secrets are encrypted with AES-GCM. The authentication tag is checked when the backup is
+/// opened so that a corrupted backup, or an incorrect recovery key, is detected before any
+/// secret is restored.
+///
+/// Only the signing secrets and the X25519 secrets are backed up. The AEAD secrets are
+/// short-lived secure channel keys.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VaultBackup {
+    #[n(1)] version: u8,
+    #[cbor(n(2), with = "minicbor::bytes")] salt: Vec<u8>,
+    #[cbor(n(3), with = "minicbor::bytes")] encrypted: Vec<u8>,
+}
+
+/// Secrets contained in a backup
+#[derive(Encode, Decode, Zeroize, ZeroizeOnDrop)]
+#[rustfmt::skip]
+#[cbor(map)]
+struct VaultBackupContents {
+    #[n(1)] secrets: Vec<BackupSecret>,
+}
+
+#[derive(Encode, Decode, Zeroize, ZeroizeOnDrop)]
+#[rustfmt::skip]
+#[cbor(map)]
+struct BackupSecret {
+    #[n(1)] secret_type: String,
+    #[cbor(n(2), with = "minicbor::bytes")] handle: Vec<u8>,
+    #[cbor(n(3), with = "minicbor::bytes")] secret: Vec<u8>,
+}
+
+/// A secret read from a backup, ready to be stored
+enum RestoredSecret {
+    Signing(SigningSecretKeyHandle, SigningSecret),
+    X25519(X25519SecretKeyHandle, X25519SecretKey),
+}
+
+impl VaultBackup {
+    /// Export all the signing and X25519 secrets of a repository
+    pub async fn export(
+        repository: &dyn SecretsRepository,
+        recovery_passphrase: &str,
+    ) -> Result<Self> {
+        let mut secrets = vec![];
+        for handle in repository.get_signing_secret_handles().await? {
+            if let Some(secret) = repository.get_signing_secret(&handle).await? {
+                let secret_type = match secret {
+                    SigningSecret::EdDSACurve25519(_) => ED_DSA_CURVE_25519,
+                    SigningSecret::ECDSASHA256CurveP256(_) => EC_DSA_SHA256_CURVE_P256,
+                };
+                secrets.push(BackupSecret {
+                    secret_type: secret_type.to_string(),
+                    handle: handle.handle().value().clone(),
+                    secret: secret.key().to_vec(),
+                });
+            }
+        }
+        for handle in repository.get_x25519_secret_handles().await? {
+            if let Some(secret) = repository.get_x25519_secret(&handle).await? {
+                secrets.push(BackupSecret {
+                    secret_type: X25519.to_string(),
+                    handle: handle.0.value().clone(),
+                    secret: secret.key().to_vec(),
+                });
+            }
+        }
+
+        let contents = minicbor::to_vec(VaultBackupContents { secrets })?;
+        let salt = SecretsEncryptionKey::random_salt();
+        let key = SecretsEncryptionKey::from_passphrase(recovery_passphrase, &salt)?;
+        let encrypted = key.encrypt(&Self::aad(VAULT_BACKUP_VERSION, &salt), &contents)?;
+        Ok(Self {
+            version: VAULT_BACKUP_VERSION,
+            salt,
+            encrypted,
+        })
+    }
+
+    /// Restore the secrets of the backup into a repository and return the number of restored
+    /// secrets.
+    ///
+    /// The restoration fails, without storing any secret, if the backup cannot be decrypted or
+    /// if the repository already contains a different secret for one of the handles of the
+    /// backup. The secrets already present in the repository are not stored again
+    pub async fn restore(
+        &self,
+        repository: &dyn SecretsRepository,
+        recovery_passphrase: &str,
+    ) -> Result<usize> {
+        let secrets = self.open(recovery_passphrase)?;
+
+        let mut to_store = vec![];
+        for secret in secrets {
+            let existing = match &secret {
+                RestoredSecret::Signing(handle, secret) => repository
+                    .get_signing_secret(handle)
+                    .await?
+                    .map(|existing| (&existing == secret, handle.handle().clone())),
+                RestoredSecret::X25519(handle, secret) => repository
+                    .get_x25519_secret(handle)
+                    .await?
+                    .map(|existing| (&existing == secret, handle.0.clone())),
+            };
+            match existing {
+                None => to_store.push(secret),
+                Some((true, _)) => (),
+                Some((false, handle)) => {
+                    return Err(ockam_core::Error::new(
+                        Origin::Vault,
+                        Kind::Conflict,
+                        format!(
+                            "the vault already contains a different secret for the key id {}",
+                            hex::encode(handle.value())
+                        ),
+                    ))
+                }
+            }
+        }
+
+        let restored = to_store.len();
+        for secret in to_store {
+            match secret {
+                RestoredSecret::Signing(handle, secret) => {
+                    repository.store_signing_secret(&handle, secret).await?
+                }
+                RestoredSecret::X25519(handle, secret) => {
+                    repository.store_x25519_secret(&handle, secret).await?
+                }
+            }
+        }
+        Ok(restored)
+    }
+
+    /// Encode the backup
+    pub fn export_as_bytes(&self) -> Result<Vec<u8>> {
+        Ok(minicbor::to_vec(self)?)
+    }
+
+    /// Decode a backup
+    pub fn import_from_bytes(bytes: &[u8]) -> Result<Self> {
+        let backup: Self = minicbor::decode(bytes)?;
+        if backup.version != VAULT_BACKUP_VERSION {
+            return Err(ockam_core::Error::new(
+                Origin::Vault,
+                Kind::Unsupported,
+                format!("unsupported vault backup version {}", backup.version),
+            ));
+        }
+        Ok(backup)
+    }
+
+    /// Decrypt the backup and check the integrity of its secrets
+    fn open(&self, recovery_passphrase: &str) -> Result<Vec<RestoredSecret>> {
+        let key = SecretsEncryptionKey::from_passphrase(recovery_passphrase, &self.salt)?;
+        let aad = Self::aad(self.version, &self.salt);
+        let contents = key.decrypt(&aad, &self.encrypted).map_err(|_| {
+            ockam_core::Error::new(
+                Origin::Vault,
+                Kind::Invalid,
+                "the recovery key is incorrect or the backup is corrupted",
+            )
+        })?;
+        let contents: VaultBackupContents = minicbor::decode(&contents)?;
+        contents
+            .secrets
+            .iter()
+            .map(|s| s.restored_secret())
+            .collect()
+    }
+
+    /// The version and the salt are authenticated along with the secrets
+    fn aad(version: u8, salt: &[u8]) -> Vec<u8> {
+        let mut aad = vec![version];
+        aad.extend_from_slice(salt);
+        aad
+    }
+}
+
+impl BackupSecret {
+    fn restored_secret(&self) -> Result<RestoredSecret> {
+        let handle = HandleToSecret::new(self.handle.clone());
+        let secret: [u8; 32] = self.secret.as_slice().try_into().map_err(|_| {
+            ockam_core::Error::new(
+                Origin::Vault,
+                Kind::Serialization,
+                "cannot convert a backed up secret to [u8; 32]",
+            )
+        })?;
+        match self.secret_type.as_str() {
+            ED_DSA_CURVE_25519 => Ok(RestoredSecret::Signing(
+                SigningSecretKeyHandle::EdDSACurve25519(handle),
+                SigningSecret::EdDSACurve25519(EdDSACurve25519SecretKey::new(secret)),
+            )),
+            EC_DSA_SHA256_CURVE_P256 => {
+                let secret = ECDSASHA256CurveP256SecretKey::new(secret);
+                Ok(RestoredSecret::Signing(
+                    SigningSecretKeyHandle::ECDSASHA256CurveP256(handle),
+                    SigningSecret::ECDSASHA256CurveP256(secret),
+                ))
+            }
+            X25519 => Ok(RestoredSecret::X25519(
+                X25519SecretKeyHandle(handle),
+                X25519SecretKey::new(secret),
+            )),
+            other => Err(ockam_core::Error::new(
+                Origin::Vault,
+                Kind::Serialization,
+                format!("unknown backed up secret type {other}"),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::SecretsSqlxDatabase;
+
+    #[tokio::test]
+    async fn test_backup_and_restore() -> Result<()> {
+        let source = SecretsSqlxDatabase::create().await?;
+        let signing_handle =
+            SigningSecretKeyHandle::EdDSACurve25519(HandleToSecret::new(vec![1, 2, 3]));
+        let signing_secret = SigningSecret::EdDSACurve25519(EdDSACurve25519SecretKey::new([1; 32]));
+        source
+            .store_signing_secret(&signing_handle, signing_secret.clone())
+            .await?;
+        let x25519_handle = X25519SecretKeyHandle(HandleToSecret::new(vec![4, 5, 6]));
+        let x25519_secret = X25519SecretKey::new([2; 32]);
+        source
+            .store_x25519_secret(&x25519_handle, x25519_secret.clone())
+            .await?;
+
+        let backup = VaultBackup::export(&source, "recovery").await?;
+        let backup = VaultBackup::import_from_bytes(&backup.export_as_bytes()?)?;
+
+        // the backup cannot be restored with an incorrect recovery key
+        let target = SecretsSqlxDatabase::create().await?;
+        assert!(backup.restore(&target, "incorrect").await.is_err());
+        assert!(target.get_signing_secret_handles().await?.is_empty());
+
+        // the secrets are restored with the recovery key
+        assert_eq!(backup.restore(&target, "recovery").await?, 2);
+        let restored = target.get_signing_secret(&signing_handle).await?;
+        assert!(restored == Some(signing_secret));
+        let restored = target.get_x25519_secret(&x25519_handle).await?;
+        assert!(restored == Some(x25519_secret));
+
+        // restoring the same backup again is a no-op
+        assert_eq!(backup.restore(&target, "recovery").await?, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_refuse_conflicting_restore() -> Result<()> {
+        let handle = X25519SecretKeyHandle(HandleToSecret::new(vec![1, 2, 3]));
+        let source = SecretsSqlxDatabase::create().await?;
+        source
+            .store_x25519_secret(&handle, X25519SecretKey::new([1; 32]))
+            .await?;
+        let backup = VaultBackup::export(&source, "recovery").await?;
+
+        // the target vault uses the same key id for another secret
+        let target = SecretsSqlxDatabase::create().await?;
+        target
+            .store_x25519_secret(&handle, X25519SecretKey::new([2; 32]))
+            .await?;
+        assert!(backup.restore(&target, "recovery").await.is_err());
+
+        let secret = target.get_x25519_secret(&handle).await?;
+        assert!(secret == Some(X25519SecretKey::new([2; 32])));
+        Ok(())
+    }
+
+    #[test]
+    fn test_corrupted_backup() -> Result<()> {
+        let salt = SecretsEncryptionKey::random_salt();
+        let key = SecretsEncryptionKey::from_passphrase("recovery", &salt)?;
+        let contents = minicbor::to_vec(VaultBackupContents { secrets: vec![] })?;
+        let aad = VaultBackup::aad(VAULT_BACKUP_VERSION, &salt);
+        let mut encrypted = key.encrypt(&aad, &contents)?;
+        let last = encrypted.len() - 1;
+        encrypted[last] ^= 1;
+
+        let backup = VaultBackup {
+            version: VAULT_BACKUP_VERSION,
+            salt,
+            encrypted,
+        };
+        assert!(backup.open("recovery").is_err());
+        Ok(())
+    }
+}
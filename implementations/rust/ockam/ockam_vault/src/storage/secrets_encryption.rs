@@ -0,0 +1,249 @@
+use argon2::Argon2;
+use core::fmt::{Debug, Formatter};
+use std::time::{Duration, Instant};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use ockam_core::compat::rand::{thread_rng, RngCore};
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::Result;
+
+use crate::{make_aes, AeadSecret, VaultError, AEAD_SECRET_LENGTH};
+
+/// Length of the salt used to derive a key from a passphrase
+pub const SECRETS_ENCRYPTION_SALT_LENGTH: usize = 16;
+
+const NONCE_LENGTH: usize = 12;
+const KEY_CHECK_PLAINTEXT: &[u8] = b"ockam vault secrets encryption key";
+
+/// Key used to encrypt the secrets of a vault when they are stored.
+///
+/// The key is either derived from a passphrase with argon2, or generated randomly
+/// and kept outside of the database, for example in the keychain of the operating system.
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct SecretsEncryptionKey(AeadSecret);
+
+impl SecretsEncryptionKey {
+    /// Create a key from its bytes
+    pub fn new(secret: AeadSecret) -> Self {
+        Self(secret)
+    }
+
+    /// Generate a random key
+    pub fn random() -> Self {
+        let mut secret = [0u8; AEAD_SECRET_LENGTH];
+        thread_rng().fill_bytes(&mut secret);
+        Self(AeadSecret(secret))
+    }
+
+    /// Generate a random salt for the derivation of a key from a passphrase
+    pub fn random_salt() -> Vec<u8> {
+        let mut salt = vec![0u8; SECRETS_ENCRYPTION_SALT_LENGTH];
+        thread_rng().fill_bytes(&mut salt);
+        salt
+    }
+
+    /// Derive a key from a passphrase and a salt, with argon2
+    pub fn from_passphrase(passphrase: &str, salt: &[u8]) -> Result<Self> {
+        let mut secret = [0u8; AEAD_SECRET_LENGTH];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut secret)
+            .map_err(|e| {
+                ockam_core::Error::new(
+                    Origin::Vault,
+                    Kind::Invalid,
+                    format!("cannot derive a key from the passphrase: {e}"),
+                )
+            })?;
+        Ok(Self(AeadSecret(secret)))
+    }
+
+    /// Return the bytes of the key
+    pub fn secret(&self) -> &AeadSecret {
+        &self.0
+    }
+
+    /// Encrypt a known value with this key.
+    /// The result can be stored to later check that a key is the correct one
+    pub fn make_key_check(&self) -> Result<Vec<u8>> {
+        self.encrypt(&[], KEY_CHECK_PLAINTEXT)
+    }
+
+    /// Check that this key is the one which was used to create a key check
+    pub fn verify_key_check(&self, key_check: &[u8]) -> Result<()> {
+        match self.decrypt(&[], key_check) {
+            Ok(plaintext) if plaintext == KEY_CHECK_PLAINTEXT => Ok(()),
+            _ => Err(VaultError::InvalidSecretsEncryptionKey)?,
+        }
+    }
+
+    /// Encrypt a secret. The nonce is prepended to the encrypted secret
+    pub(crate) fn encrypt(&self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce = [0u8; NONCE_LENGTH];
+        thread_rng().fill_bytes(&mut nonce);
+
+        let mut result = nonce.to_vec();
+        let aes = make_aes(&self.0);
+        aes.encrypt_message(&mut result, plaintext, &nonce, aad)?;
+        Ok(result)
+    }
+
+    /// Decrypt a secret encrypted with [`SecretsEncryptionKey::encrypt`]
+    pub(crate) fn decrypt(&self, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        if ciphertext.len() < NONCE_LENGTH {
+            return Err(VaultError::InvalidSecretsEncryptionKey)?;
+        }
+        let (nonce, encrypted) = ciphertext.split_at(NONCE_LENGTH);
+        make_aes(&self.0)
+            .decrypt_message(encrypted, nonce, aad)
+            .map_err(|_| VaultError::InvalidSecretsEncryptionKey.into())
+    }
+}
+
+impl Debug for SecretsEncryptionKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str("SecretsEncryptionKey(<redacted>)")
+    }
+}
+
+/// Lock protecting the secrets of an encrypted vault.
+///
+/// The secrets can only be read or written while the lock holds the encryption key.
+/// When an idle timeout is set, the key is dropped after not being used for that duration
+/// and the vault must be unlocked again.
+///
+/// The lock can be cloned and shared, unlocking one of the clones unlocks all of them.
+#[derive(Clone)]
+pub struct SecretsLock {
+    state: Arc<Mutex<SecretsLockState>>,
+}
+
+struct SecretsLockState {
+    key: Option<SecretsEncryptionKey>,
+    idle_timeout: Option<Duration>,
+    last_used_at: Instant,
+}
+
+impl SecretsLock {
+    /// Create a new locked lock
+    pub fn new(idle_timeout: Option<Duration>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(SecretsLockState {
+                key: None,
+                idle_timeout,
+                last_used_at: Instant::now(),
+            })),
+        }
+    }
+
+    /// Unlock the secrets with a key
+    pub fn unlock(&self, key: SecretsEncryptionKey) {
+        let mut state = self.state.lock().unwrap();
+        state.key = Some(key);
+        state.last_used_at = Instant::now();
+    }
+
+    /// Lock the secrets. The key is dropped
+    pub fn lock(&self) {
+        self.state.lock().unwrap().key = None;
+    }
+
+    /// Return true if the secrets are locked, either explicitly or after being idle
+    pub fn is_locked(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        state.relock_if_idle();
+        state.key.is_none()
+    }
+
+    /// Return the idle timeout after which the secrets are locked again
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.state.lock().unwrap().idle_timeout
+    }
+
+    /// Encrypt a secret, using its handle as associated data
+    pub(crate) fn encrypt(&self, handle: &[u8], secret: &[u8]) -> Result<Vec<u8>> {
+        self.current_key()?.encrypt(handle, secret)
+    }
+
+    /// Decrypt a secret, using its handle as associated data
+    pub(crate) fn decrypt(&self, handle: &[u8], encrypted: &[u8]) -> Result<Vec<u8>> {
+        self.current_key()?.decrypt(handle, encrypted)
+    }
+
+    fn current_key(&self) -> Result<SecretsEncryptionKey> {
+        let mut state = self.state.lock().unwrap();
+        state.relock_if_idle();
+        state.last_used_at = Instant::now();
+        match &state.key {
+            Some(key) => Ok(key.clone()),
+            None => Err(VaultError::VaultLocked)?,
+        }
+    }
+}
+
+impl SecretsLockState {
+    fn relock_if_idle(&mut self) {
+        if let Some(idle_timeout) = self.idle_timeout {
+            if self.last_used_at.elapsed() > idle_timeout {
+                self.key = None;
+            }
+        }
+    }
+}
+
+impl Debug for SecretsLock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SecretsLock")
+            .field("locked", &self.is_locked())
+            .field("idle_timeout", &self.idle_timeout())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_passphrase_key_check() -> Result<()> {
+        let salt = SecretsEncryptionKey::random_salt();
+        let key = SecretsEncryptionKey::from_passphrase("correct horse", &salt)?;
+        let key_check = key.make_key_check()?;
+
+        let same_key = SecretsEncryptionKey::from_passphrase("correct horse", &salt)?;
+        assert!(same_key.verify_key_check(&key_check).is_ok());
+
+        let other_key = SecretsEncryptionKey::from_passphrase("battery staple", &salt)?;
+        assert!(other_key.verify_key_check(&key_check).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_lock_unlock() -> Result<()> {
+        let lock = SecretsLock::new(None);
+        assert!(lock.is_locked());
+        assert!(lock.encrypt(b"handle", b"secret").is_err());
+
+        lock.unlock(SecretsEncryptionKey::random());
+        let encrypted = lock.encrypt(b"handle", b"secret")?;
+        assert_eq!(lock.decrypt(b"handle", &encrypted)?, b"secret".to_vec());
+
+        // the secret is bound to its handle
+        assert!(lock.decrypt(b"other handle", &encrypted).is_err());
+
+        lock.lock();
+        assert!(lock.decrypt(b"handle", &encrypted).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_relock_when_idle() {
+        let lock = SecretsLock::new(Some(Duration::from_millis(10)));
+        lock.unlock(SecretsEncryptionKey::random());
+        assert!(!lock.is_locked());
+
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(lock.is_locked());
+    }
+}
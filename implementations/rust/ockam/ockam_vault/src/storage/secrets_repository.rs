@@ -1,6 +1,6 @@
 use crate::{
-    AeadSecret, AeadSecretKeyHandle, SigningSecret, SigningSecretKeyHandle, X25519SecretKey,
-    X25519SecretKeyHandle,
+    AeadAlgorithm, AeadSecret, AeadSecretKeyHandle, SigningSecret, SigningSecretKeyHandle,
+    X25519SecretKey, X25519SecretKeyHandle,
 };
 use ockam_core::async_trait;
 use ockam_core::compat::boxed::Box;
@@ -48,18 +48,22 @@ pub trait SecretsRepository: Send + Sync + 'static {
     /// Get the list of all X25519 secret handles
     async fn get_x25519_secret_handles(&self) -> Result<Vec<X25519SecretKeyHandle>>;
 
-    /// Store AEAD secret.
+    /// Store AEAD secret, with the algorithm it is used with.
     async fn store_aead_secret(
         &self,
         handle: &AeadSecretKeyHandle,
+        algorithm: AeadAlgorithm,
         secret: AeadSecret,
     ) -> Result<()>;
 
     /// Delete AEAD secret.
     async fn delete_aead_secret(&self, handle: &AeadSecretKeyHandle) -> Result<bool>;
 
-    /// Get AEAD secret.
-    async fn get_aead_secret(&self, handle: &AeadSecretKeyHandle) -> Result<Option<AeadSecret>>;
+    /// Get AEAD secret and the algorithm it is used with.
+    async fn get_aead_secret(
+        &self,
+        handle: &AeadSecretKeyHandle,
+    ) -> Result<Option<(AeadAlgorithm, AeadSecret)>>;
 
     /// Delete all secrets
     async fn delete_all(&self) -> Result<()>;
@@ -11,30 +11,58 @@ use ockam_core::Result;
 use ockam_node::database::{FromSqlxError, SqlxDatabase, ToVoid};
 
 use crate::storage::secrets_repository::SecretsRepository;
+use crate::storage::SecretsLock;
 
 use crate::{
-    AeadSecret, AeadSecretKeyHandle, ECDSASHA256CurveP256SecretKey, EdDSACurve25519SecretKey,
-    HandleToSecret, SigningSecret, SigningSecretKeyHandle, X25519SecretKey, X25519SecretKeyHandle,
-    AEAD_TYPE,
+    AeadAlgorithm, AeadSecret, AeadSecretKeyHandle, ECDSASHA256CurveP256SecretKey,
+    EdDSACurve25519SecretKey, HandleToSecret, SigningSecret, SigningSecretKeyHandle,
+    X25519SecretKey, X25519SecretKeyHandle,
 };
 
 /// Implementation of a secrets repository using a SQL database
 #[derive(Clone)]
 pub struct SecretsSqlxDatabase {
     database: SqlxDatabase,
+    lock: Option<SecretsLock>,
 }
 
 impl SecretsSqlxDatabase {
     /// Create a new database for secrets
     pub fn new(database: SqlxDatabase) -> Self {
         debug!("create a repository for secrets");
-        Self { database }
+        Self {
+            database,
+            lock: None,
+        }
     }
 
     /// Create a new in-memory database for secrets
     pub async fn create() -> Result<Self> {
         Ok(Self::new(SqlxDatabase::in_memory("secrets").await?))
     }
+
+    /// Encrypt the secrets with the key held by a lock before storing them.
+    /// The secrets can then only be stored or retrieved while the lock is unlocked
+    pub fn with_encryption(mut self, lock: SecretsLock) -> Self {
+        self.lock = Some(lock);
+        self
+    }
+
+    /// Encrypt a secret if the secrets are encrypted at rest
+    fn seal(&self, handle: &[u8], secret: &[u8]) -> Result<Vec<u8>> {
+        match &self.lock {
+            Some(lock) => lock.encrypt(handle, secret),
+            None => Ok(secret.to_vec()),
+        }
+    }
+
+    /// Decrypt a stored secret if the secrets are encrypted at rest
+    fn open(&self, handle: &[u8], secret: &mut Vec<u8>) -> Result<()> {
+        if let Some(lock) = &self.lock {
+            *secret = lock.decrypt(handle, secret)?;
+        }
+        Ok(())
+    }
 }
 
 const ED_DSA_CURVE_25519: &str = "EdDSACurve25519";
@@ -52,6 +80,7 @@ impl SecretsRepository for SecretsSqlxDatabase {
             SigningSecretKeyHandle::ECDSASHA256CurveP256(_) => EC_DSA_SHA256_CURVE_P256.into(),
         };
 
+        let secret = self.seal(handle.handle().value(), secret.key())?;
         let query = query(
             r#"
             INSERT INTO signing_secret (handle, secret_type, secret)
@@ -79,10 +108,13 @@ impl SecretsRepository for SecretsSqlxDatabase {
         let query =
             query_as("SELECT handle, secret_type, secret FROM signing_secret WHERE handle = $1")
                 .bind(handle);
-        let row: Option<SigningSecretRow> = query
+        let mut row: Option<SigningSecretRow> = query
             .fetch_optional(&*self.database.pool)
             .await
             .into_core()?;
+        if let Some(row) = row.as_mut() {
+            self.open(handle.handle().value(), &mut row.secret)?;
+        }
         Ok(row.map(|r| r.signing_secret()).transpose()?)
     }
 
@@ -101,6 +133,7 @@ impl SecretsRepository for SecretsSqlxDatabase {
         handle: &X25519SecretKeyHandle,
         secret: X25519SecretKey,
     ) -> Result<()> {
+        let secret = self.seal(handle.0.value(), secret.key())?;
         let query = query(
             r#"
         INSERT INTO x25519_secret (handle, secret)
@@ -126,10 +159,13 @@ impl SecretsRepository for SecretsSqlxDatabase {
     ) -> Result<Option<X25519SecretKey>> {
         let query =
             query_as("SELECT handle, secret FROM x25519_secret WHERE handle = $1").bind(handle);
-        let row: Option<X25519SecretRow> = query
+        let mut row: Option<X25519SecretRow> = query
             .fetch_optional(&*self.database.pool)
             .await
             .into_core()?;
+        if let Some(row) = row.as_mut() {
+            self.open(handle.0.value(), &mut row.secret)?;
+        }
         Ok(row.map(|r| r.x25519_secret()).transpose()?)
     }
 
@@ -145,8 +181,10 @@ impl SecretsRepository for SecretsSqlxDatabase {
     async fn store_aead_secret(
         &self,
         handle: &AeadSecretKeyHandle,
+        algorithm: AeadAlgorithm,
         secret: AeadSecret,
     ) -> Result<()> {
+        let secret = self.seal(handle.0 .0.value(), &secret.0)?;
         let query = query(
             r#"
                 INSERT INTO aead_secret (handle, type, secret)
@@ -155,7 +193,7 @@ impl SecretsRepository for SecretsSqlxDatabase {
                 DO UPDATE SET type = $2, secret = $3"#,
         )
         .bind(handle)
-        .bind(AEAD_TYPE)
+        .bind(algorithm.name())
         .bind(secret);
         query.execute(&*self.database.pool).await.void()
     }
@@ -167,14 +205,20 @@ impl SecretsRepository for SecretsSqlxDatabase {
         Ok(res.rows_affected() != 0)
     }
 
-    async fn get_aead_secret(&self, handle: &AeadSecretKeyHandle) -> Result<Option<AeadSecret>> {
-        let query = query_as("SELECT secret FROM aead_secret WHERE handle = $1 AND type = $2")
-            .bind(handle)
-            .bind(AEAD_TYPE);
-        let row: Option<AeadSecretRow> = query
+    async fn get_aead_secret(
+        &self,
+        handle: &AeadSecretKeyHandle,
+    ) -> Result<Option<(AeadAlgorithm, AeadSecret)>> {
+        let query =
+            query_as("SELECT type AS secret_type, secret FROM aead_secret WHERE handle = $1")
+                .bind(handle);
+        let mut row: Option<AeadSecretRow> = query
             .fetch_optional(&*self.database.pool)
             .await
             .into_core()?;
+        if let Some(row) = row.as_mut() {
+            self.open(handle.0 .0.value(), &mut row.secret)?;
+        }
         Ok(row.map(|r| r.aead_secret()).transpose()?)
     }
 
@@ -352,11 +396,20 @@ impl X25519SecretRow {
 
 #[derive(FromRow, Zeroize, ZeroizeOnDrop)]
 struct AeadSecretRow {
+    secret_type: String,
     secret: Vec<u8>,
 }
 
 impl AeadSecretRow {
-    fn aead_secret(&self) -> Result<AeadSecret> {
+    fn aead_secret(&self) -> Result<(AeadAlgorithm, AeadSecret)> {
+        let algorithm = AeadAlgorithm::from_name(&self.secret_type).ok_or_else(|| {
+            ockam_core::Error::new(
+                Origin::Api,
+                Kind::Serialization,
+                format!("unknown AEAD secret type {}", self.secret_type),
+            )
+        })?;
+
         let secret = self.secret.as_slice().try_into().map_err(|_| {
             ockam_core::Error::new(
                 Origin::Api,
@@ -365,7 +418,7 @@ impl AeadSecretRow {
             )
         })?;
 
-        Ok(AeadSecret(secret))
+        Ok((algorithm, AeadSecret(secret)))
     }
 }
 
@@ -450,17 +503,17 @@ mod test {
         let secret2 = AeadSecret([2; 32]);
 
         repository
-            .store_aead_secret(&handle1, secret1.clone())
+            .store_aead_secret(&handle1, AeadAlgorithm::AesGcm, secret1.clone())
             .await?;
         repository
-            .store_aead_secret(&handle2, secret2.clone())
+            .store_aead_secret(&handle2, AeadAlgorithm::ChaCha20Poly1305, secret2.clone())
             .await?;
 
         let result = repository.get_aead_secret(&handle1).await?;
-        assert!(result == Some(secret1));
+        assert!(result == Some((AeadAlgorithm::AesGcm, secret1)));
 
         let result = repository.get_aead_secret(&handle2).await?;
-        assert!(result == Some(secret2));
+        assert!(result == Some((AeadAlgorithm::ChaCha20Poly1305, secret2)));
 
         repository.delete_aead_secret(&handle1).await?;
 
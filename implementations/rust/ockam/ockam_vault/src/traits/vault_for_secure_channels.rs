@@ -1,6 +1,6 @@
 use crate::{
-    AeadSecretKeyHandle, HashOutput, HkdfOutput, SecretBufferHandle, X25519PublicKey,
-    X25519SecretKeyHandle,
+    AeadAlgorithm, AeadSecretKeyHandle, HashOutput, HkdfOutput, SecretBufferHandle,
+    X25519PublicKey, X25519SecretKeyHandle,
 };
 
 use ockam_core::compat::vec::Vec;
@@ -65,6 +65,18 @@ pub trait VaultForSecureChannels: Send + Sync + 'static {
     /// Load an AEAD key from the storage.
     async fn load_aead_key(&self, secret_key_handle: &AeadSecretKeyHandle) -> Result<()>;
 
+    /// Return the AEAD algorithm used with an AEAD key.
+    async fn get_aead_algorithm(
+        &self,
+        secret_key_handle: &AeadSecretKeyHandle,
+    ) -> Result<AeadAlgorithm>;
+
+    /// Delete an AEAD key from the storage. The key is still usable if it was loaded.
+    async fn delete_persisted_aead_key(
+        &self,
+        secret_key_handle: &AeadSecretKeyHandle,
+    ) -> Result<bool>;
+
     /// Generate a fresh static (persisted) X25519 Key.
     async fn generate_static_x25519_secret_key(&self) -> Result<X25519SecretKeyHandle>;
 
@@ -101,10 +113,11 @@ pub trait VaultForSecureChannels: Send + Sync + 'static {
     /// Delete Secret Buffer.
     async fn delete_secret_buffer(&self, secret_buffer_handle: SecretBufferHandle) -> Result<bool>;
 
-    /// Convert a Secret Buffer to an AEAD Key.
+    /// Convert a Secret Buffer to an AEAD Key, used with the given AEAD algorithm.
     async fn convert_secret_buffer_to_aead_key(
         &self,
         secret_buffer_handle: SecretBufferHandle,
+        algorithm: AeadAlgorithm,
     ) -> Result<AeadSecretKeyHandle>;
 
     /// Delete AEAD Key.
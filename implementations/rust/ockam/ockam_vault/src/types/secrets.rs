@@ -57,3 +57,37 @@ pub struct X25519SecretKeyHandle(pub HandleToSecret);
 /// A handle to a secret Buffer (like an HKDF output).
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
 pub struct SecretBufferHandle(pub HandleToSecret);
+
+/// AEAD algorithm used with an AEAD Secret Key.
+#[derive(Debug, Clone, Copy, Default, Ord, PartialOrd, Eq, PartialEq)]
+pub enum AeadAlgorithm {
+    /// AES-GCM, with the key size of the selected Noise protocol.
+    #[default]
+    AesGcm,
+    /// ChaCha20-Poly1305, which is faster than AES-GCM on devices without AES hardware support.
+    ChaCha20Poly1305,
+}
+
+/// ChaCha20-Poly1305 AEAD type string.
+pub const CHACHA20_POLY1305_TYPE: &str = "CHACHA20_POLY1305";
+
+impl AeadAlgorithm {
+    /// Name of the algorithm, used when persisting an AEAD key.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AeadAlgorithm::AesGcm => crate::AEAD_TYPE,
+            AeadAlgorithm::ChaCha20Poly1305 => CHACHA20_POLY1305_TYPE,
+        }
+    }
+
+    /// Return the algorithm corresponding to a name returned by [`AeadAlgorithm::name`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name == crate::AEAD_TYPE {
+            Some(AeadAlgorithm::AesGcm)
+        } else if name == CHACHA20_POLY1305_TYPE {
+            Some(AeadAlgorithm::ChaCha20Poly1305)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use ockam_core::errcode::{Kind, Origin};
+use thiserror::Error;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("the pkcs11 module could not be loaded: {0}")]
+    Module(String),
+    #[error("no token was found in the pkcs11 slot {0}")]
+    SlotNotFound(u64),
+    #[error("a session could not be opened on the pkcs11 slot {slot}: {error}")]
+    Session { slot: u64, error: String },
+    #[error("the login to the pkcs11 token failed: {0}")]
+    Login(String),
+    #[error("pkcs11 error creating new key: {0}")]
+    Create(String),
+    #[error("pkcs11 error signing message with key {keyid}: {error}")]
+    Sign { keyid: String, error: String },
+    #[error("pkcs11 error exporting public key {keyid}: {error}")]
+    Export { keyid: String, error: String },
+    #[error("pkcs11 error deleting key {keyid}: {error}")]
+    Delete { keyid: String, error: String },
+    #[error("pkcs11 error listing the existing keys: {0}")]
+    ListKeys(String),
+    #[error("public key ec point is incorrect")]
+    InvalidPublicKey,
+    #[error("signature is incorrect")]
+    InvalidSignature,
+    #[error("key was not found")]
+    KeyNotFound,
+    #[error("invalid handle")]
+    InvalidHandle,
+}
+
+impl From<Error> for ockam_core::Error {
+    #[track_caller]
+    fn from(e: Error) -> Self {
+        ockam_core::Error::new(Origin::Vault, Kind::Io, e)
+    }
+}
@@ -0,0 +1,352 @@
+use crate::error::Error;
+use cryptoki::context::{CInitializeArgs, Pkcs11};
+use cryptoki::error::{Error as CryptokiError, RvError};
+use cryptoki::mechanism::Mechanism;
+use cryptoki::object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use cryptoki::types::AuthPin;
+use ockam_core::compat::rand::random;
+use ockam_core::compat::sync::Mutex;
+use ockam_core::Result;
+use ockam_vault::{
+    ECDSASHA256CurveP256PublicKey, ECDSASHA256CurveP256Signature, HandleToSecret, Signature,
+    SigningSecretKeyHandle, VerifyingPublicKey,
+};
+use sha2::{Digest, Sha256};
+use std::fmt::{Debug, Formatter};
+use std::path::{Path, PathBuf};
+use tracing as log;
+
+/// DER encoding of the OID of the NIST P-256 curve (prime256v1)
+const P256_EC_PARAMS: [u8; 10] = [0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+
+/// Length of an uncompressed P-256 point
+const P256_POINT_LENGTH: usize = 65;
+
+/// PKCS#11 configuration: module, slot and PIN of the token
+#[derive(Clone)]
+pub struct Pkcs11Config {
+    module_path: PathBuf,
+    slot: u64,
+    pin: Option<String>,
+}
+
+impl Debug for Pkcs11Config {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pkcs11Config")
+            .field("module_path", &self.module_path)
+            .field("slot", &self.slot)
+            .field("pin", &self.pin.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+impl Pkcs11Config {
+    /// Create a new configuration for the token present in a slot of a PKCS#11 module,
+    /// for example `/usr/lib/softhsm/libsofthsm2.so`
+    pub fn new(module_path: impl Into<PathBuf>, slot: u64) -> Pkcs11Config {
+        Pkcs11Config {
+            module_path: module_path.into(),
+            slot,
+            pin: None,
+        }
+    }
+
+    /// Log in to the token with a user PIN
+    pub fn with_pin(self, pin: impl Into<String>) -> Self {
+        Self {
+            pin: Some(pin.into()),
+            ..self
+        }
+    }
+
+    /// Path of the PKCS#11 module
+    pub fn module_path(&self) -> &Path {
+        self.module_path.as_path()
+    }
+
+    /// Slot of the token
+    pub fn slot(&self) -> u64 {
+        self.slot
+    }
+}
+
+/// PKCS#11 client.
+///
+/// The calls to the PKCS#11 module are blocking and made through a single session,
+/// opened on the configured slot when the client is created.
+pub struct Pkcs11Client {
+    slot: u64,
+    // A session can be sent to another thread but not shared between threads
+    session: Mutex<Session>,
+}
+
+impl Pkcs11Client {
+    /// Load the PKCS#11 module, open a session on the configured slot
+    /// and log in if a PIN is configured
+    pub fn new(config: &Pkcs11Config) -> Result<Pkcs11Client> {
+        let pkcs11 =
+            Pkcs11::new(config.module_path()).map_err(|err| Error::Module(err.to_string()))?;
+        match pkcs11.initialize(CInitializeArgs::OsThreads) {
+            // The module can already have been initialized by another vault of this process
+            Ok(()) | Err(CryptokiError::Pkcs11(RvError::CryptokiAlreadyInitialized, _)) => {}
+            Err(err) => return Err(Error::Module(err.to_string()))?,
+        };
+
+        let slot = pkcs11
+            .get_slots_with_token()
+            .map_err(|err| Error::Module(err.to_string()))?
+            .into_iter()
+            .find(|slot| slot.id() == config.slot)
+            .ok_or(Error::SlotNotFound(config.slot))?;
+
+        let session = pkcs11.open_rw_session(slot).map_err(|err| Error::Session {
+            slot: config.slot,
+            error: err.to_string(),
+        })?;
+
+        if let Some(pin) = &config.pin {
+            match session.login(UserType::User, Some(&AuthPin::new(pin.clone()))) {
+                Ok(()) | Err(CryptokiError::Pkcs11(RvError::UserAlreadyLoggedIn, _)) => {}
+                Err(err) => return Err(Error::Login(err.to_string()))?,
+            }
+        }
+
+        log::debug!(slot = %config.slot, "opened a pkcs11 session");
+        Ok(Pkcs11Client {
+            slot: config.slot,
+            session: Mutex::new(session),
+        })
+    }
+
+    /// Slot of the token used by this client
+    pub fn slot(&self) -> u64 {
+        self.slot
+    }
+
+    /// Return the `CKA_ID` of a key, and its printable version for logs and errors
+    fn cast_handle_to_key_id(handle: &SigningSecretKeyHandle) -> Result<(Vec<u8>, String)> {
+        let key_id = match handle {
+            SigningSecretKeyHandle::EdDSACurve25519(_) => return Err(Error::InvalidHandle)?,
+            SigningSecretKeyHandle::ECDSASHA256CurveP256(handle) => handle.value().clone(),
+        };
+        let kid = String::from_utf8_lossy(&key_id).to_string();
+        Ok((key_id, kid))
+    }
+
+    /// Find the object of a given class for a key id
+    fn find_object(
+        session: &Session,
+        class: ObjectClass,
+        key_id: &[u8],
+    ) -> core::result::Result<Option<ObjectHandle>, CryptokiError> {
+        let template = [Attribute::Class(class), Attribute::Id(key_id.to_vec())];
+        Ok(session.find_objects(&template)?.into_iter().next())
+    }
+
+    /// Create a new NIST P-256 key-pair in the token and return its handle.
+    /// The private key is not extractable and is referenced by a random `CKA_ID`
+    pub fn create_key(&self) -> Result<SigningSecretKeyHandle> {
+        log::trace!("create new key");
+        let key_id = hex::encode(random::<[u8; 16]>()).into_bytes();
+
+        let public_key_template = [
+            Attribute::Token(true),
+            Attribute::Private(false),
+            Attribute::Verify(true),
+            Attribute::EcParams(P256_EC_PARAMS.to_vec()),
+            Attribute::Id(key_id.clone()),
+        ];
+        let private_key_template = [
+            Attribute::Token(true),
+            Attribute::Private(true),
+            Attribute::Sensitive(true),
+            Attribute::Extractable(false),
+            Attribute::Sign(true),
+            Attribute::Id(key_id.clone()),
+        ];
+
+        let session = self.session.lock().unwrap();
+        session
+            .generate_key_pair(
+                &Mechanism::EccKeyPairGen,
+                &public_key_template,
+                &private_key_template,
+            )
+            .map_err(|err| {
+                log::error!(%err, "failed to create new key");
+                Error::Create(err.to_string())
+            })?;
+
+        log::debug!(kid = %String::from_utf8_lossy(&key_id), "created new key");
+        Ok(SigningSecretKeyHandle::ECDSASHA256CurveP256(
+            HandleToSecret::new(key_id),
+        ))
+    }
+
+    /// Delete both parts of a key-pair. Return false if the key does not exist
+    pub fn delete_key(&self, key: &SigningSecretKeyHandle) -> Result<bool> {
+        let (key_id, kid) = Self::cast_handle_to_key_id(key)?;
+        log::trace!(%kid, "delete key");
+        let delete_error = |err: CryptokiError| Error::Delete {
+            keyid: kid.clone(),
+            error: err.to_string(),
+        };
+
+        let session = self.session.lock().unwrap();
+        let mut deleted = false;
+        for class in [ObjectClass::PRIVATE_KEY, ObjectClass::PUBLIC_KEY] {
+            if let Some(object) =
+                Self::find_object(&session, class, &key_id).map_err(delete_error)?
+            {
+                session.destroy_object(object).map_err(delete_error)?;
+                deleted = true;
+            }
+        }
+
+        if deleted {
+            log::debug!(%kid, "deleted key");
+        } else {
+            log::debug!(%kid, "key does not exist");
+        }
+        Ok(deleted)
+    }
+
+    /// Get the public key part of a key-pair, from the `CKA_EC_POINT` attribute
+    pub fn public_key(&self, key: &SigningSecretKeyHandle) -> Result<VerifyingPublicKey> {
+        let (key_id, kid) = Self::cast_handle_to_key_id(key)?;
+        log::trace!(%kid, "get public key");
+        let export_error = |err: CryptokiError| Error::Export {
+            keyid: kid.clone(),
+            error: err.to_string(),
+        };
+
+        let session = self.session.lock().unwrap();
+        let public_key = Self::find_object(&session, ObjectClass::PUBLIC_KEY, &key_id)
+            .map_err(export_error)?
+            .ok_or(Error::KeyNotFound)?;
+
+        let attributes = session
+            .get_attributes(
+                public_key,
+                &[AttributeType::KeyType, AttributeType::EcPoint],
+            )
+            .map_err(export_error)?;
+
+        let mut ec_point = None;
+        for attribute in attributes {
+            match attribute {
+                Attribute::KeyType(key_type) if key_type != KeyType::EC => {
+                    log::error!(%kid, "key type not supported to get a public key");
+                    return Err(Error::InvalidPublicKey)?;
+                }
+                Attribute::EcPoint(point) => ec_point = Some(point),
+                _ => {}
+            }
+        }
+
+        let point = decode_ec_point(ec_point.ok_or(Error::InvalidPublicKey)?)?;
+        log::debug!(%kid, "received public key");
+        Ok(VerifyingPublicKey::ECDSASHA256CurveP256(
+            ECDSASHA256CurveP256PublicKey(point),
+        ))
+    }
+
+    /// Return the handles of all the private EC keys of the token which can be used to sign
+    pub fn list_keys(&self) -> Result<Vec<SigningSecretKeyHandle>> {
+        let session = self.session.lock().unwrap();
+        let template = [
+            Attribute::Class(ObjectClass::PRIVATE_KEY),
+            Attribute::KeyType(KeyType::EC),
+            Attribute::Sign(true),
+        ];
+        let objects = session
+            .find_objects(&template)
+            .map_err(|err| Error::ListKeys(err.to_string()))?;
+
+        let mut result = vec![];
+        for object in objects {
+            let attributes = session
+                .get_attributes(object, &[AttributeType::Id])
+                .map_err(|err| Error::ListKeys(err.to_string()))?;
+            for attribute in attributes {
+                if let Attribute::Id(key_id) = attribute {
+                    result.push(SigningSecretKeyHandle::ECDSASHA256CurveP256(
+                        HandleToSecret::new(key_id),
+                    ));
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Sign the SHA-256 digest of a message with the private key of a key-pair
+    pub fn sign(&self, key: &SigningSecretKeyHandle, message: &[u8]) -> Result<Signature> {
+        let (key_id, kid) = Self::cast_handle_to_key_id(key)?;
+        log::trace!(%kid, "sign message");
+        let sign_error = |err: CryptokiError| Error::Sign {
+            keyid: kid.clone(),
+            error: err.to_string(),
+        };
+
+        let session = self.session.lock().unwrap();
+        let private_key = Self::find_object(&session, ObjectClass::PRIVATE_KEY, &key_id)
+            .map_err(sign_error)?
+            .ok_or(Error::KeyNotFound)?;
+
+        // CKM_ECDSA signs a digest and returns the r || s concatenation
+        let signature = session
+            .sign(&Mechanism::Ecdsa, private_key, &Sha256::digest(message))
+            .map_err(|err| {
+                log::error!(%kid, %err, "failed to sign message");
+                sign_error(err)
+            })?;
+
+        let signature = ECDSASHA256CurveP256Signature(
+            signature.try_into().map_err(|_| Error::InvalidSignature)?,
+        );
+        log::debug!(%kid, "signed message");
+        Ok(Signature::ECDSASHA256CurveP256(signature))
+    }
+}
+
+/// The `CKA_EC_POINT` attribute is an uncompressed point, which is wrapped in a DER
+/// octet string by most tokens
+fn decode_ec_point(ec_point: Vec<u8>) -> Result<[u8; P256_POINT_LENGTH]> {
+    let point = match ec_point.as_slice() {
+        [0x04, length, point @ ..]
+            if *length as usize == P256_POINT_LENGTH && point.len() == P256_POINT_LENGTH =>
+        {
+            point.to_vec()
+        }
+        _ => ec_point,
+    };
+    match point.first() {
+        Some(0x04) => Ok(point.try_into().map_err(|_| Error::InvalidPublicKey)?),
+        _ => Err(Error::InvalidPublicKey)?,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_ec_point() {
+        let mut point = vec![0x04];
+        point.extend_from_slice(&[1u8; 64]);
+
+        // raw point
+        assert_eq!(decode_ec_point(point.clone()).unwrap().to_vec(), point);
+
+        // point wrapped in a DER octet string
+        let mut wrapped = vec![0x04, 0x41];
+        wrapped.extend_from_slice(&point);
+        assert_eq!(decode_ec_point(wrapped).unwrap().to_vec(), point);
+
+        // compressed points are not supported
+        let mut compressed = vec![0x02];
+        compressed.extend_from_slice(&[1u8; 32]);
+        assert!(decode_ec_point(compressed).is_err());
+    }
+}
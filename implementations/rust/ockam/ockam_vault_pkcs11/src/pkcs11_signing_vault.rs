@@ -0,0 +1,150 @@
+use crate::error::Error;
+use crate::pkcs11_client::{Pkcs11Client, Pkcs11Config};
+use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::{async_trait, Result};
+use ockam_vault::{
+    Signature, SigningKeyType, SigningSecretKeyHandle, VaultError, VaultForSigning,
+    VerifyingPublicKey,
+};
+use tracing::error;
+
+struct Pkcs11KeyPair {
+    key: SigningSecretKeyHandle,
+    public_key: VerifyingPublicKey,
+}
+
+/// Security module implementation using a PKCS#11 token.
+///
+/// Only the handles of the keys are returned by this vault, the private keys never leave the token
+pub struct Pkcs11SigningVault {
+    client: Arc<Pkcs11Client>,
+    // Store mapping from PublicKey to key handle in memory
+    // This is fetched at the Vault initialization
+    // and is updated locally during add/delete operations
+    // WARNING: The assumption is that there is no concurrent access to the same keys from
+    // different places.
+    keys: Arc<RwLock<Vec<Pkcs11KeyPair>>>,
+}
+
+impl Pkcs11SigningVault {
+    /// Create a new PKCS#11 security module
+    pub async fn create(config: Pkcs11Config) -> Result<Self> {
+        let client = Pkcs11Client::new(&config)?;
+
+        let mut key_pairs: Vec<Pkcs11KeyPair> = vec![];
+        // Fetch list of all signing keys, then fetch the public key for each key
+        let keys = client.list_keys()?;
+
+        for key in keys {
+            match client.public_key(&key) {
+                Ok(public_key) => key_pairs.push(Pkcs11KeyPair { key, public_key }),
+                // The token can contain keys created by other applications, for example
+                // keys on another curve, or keys without a public key object.
+                // Therefore, the best strategy is to just skip that key
+                Err(err) => error!("Error exporting public key: {err}"),
+            }
+        }
+
+        Ok(Self {
+            client: Arc::new(client),
+            keys: Arc::new(RwLock::new(key_pairs)),
+        })
+    }
+
+    /// Return list of all keys
+    pub fn keys(&self) -> Vec<SigningSecretKeyHandle> {
+        self.keys
+            .read()
+            .unwrap()
+            .iter()
+            .map(|x| x.key.clone())
+            .collect()
+    }
+
+    /// Return number of keys
+    pub async fn number_of_keys(&self) -> Result<usize> {
+        Ok(self.keys.read().unwrap().len())
+    }
+}
+
+#[async_trait]
+impl VaultForSigning for Pkcs11SigningVault {
+    async fn sign(
+        &self,
+        signing_secret_key_handle: &SigningSecretKeyHandle,
+        data: &[u8],
+    ) -> Result<Signature> {
+        self.client.sign(signing_secret_key_handle, data)
+    }
+
+    async fn generate_signing_secret_key(
+        &self,
+        signing_key_type: SigningKeyType,
+    ) -> Result<SigningSecretKeyHandle> {
+        if signing_key_type != SigningKeyType::ECDSASHA256CurveP256 {
+            return Err(VaultError::InvalidKeyType)?;
+        }
+
+        let key = self.client.create_key()?;
+        let public_key = self.client.public_key(&key)?;
+
+        self.keys.write().unwrap().push(Pkcs11KeyPair {
+            key: key.clone(),
+            public_key,
+        });
+
+        Ok(key)
+    }
+
+    async fn get_verifying_public_key(
+        &self,
+        signing_secret_key_handle: &SigningSecretKeyHandle,
+    ) -> Result<VerifyingPublicKey> {
+        self.keys
+            .read()
+            .unwrap()
+            .iter()
+            .find_map(|x| {
+                if &x.key == signing_secret_key_handle {
+                    Some(x.public_key.clone())
+                } else {
+                    None
+                }
+            })
+            .ok_or(Error::KeyNotFound.into())
+    }
+
+    async fn get_secret_key_handle(
+        &self,
+        verifying_public_key: &VerifyingPublicKey,
+    ) -> Result<SigningSecretKeyHandle> {
+        self.keys
+            .read()
+            .unwrap()
+            .iter()
+            .find_map(|x| {
+                if &x.public_key == verifying_public_key {
+                    Some(x.key.clone())
+                } else {
+                    None
+                }
+            })
+            .ok_or(Error::KeyNotFound.into())
+    }
+
+    async fn delete_signing_secret_key(
+        &self,
+        signing_secret_key_handle: SigningSecretKeyHandle,
+    ) -> Result<bool> {
+        if self.client.delete_key(&signing_secret_key_handle)? {
+            self.keys
+                .write()
+                .unwrap()
+                .retain(|x| x.key != signing_secret_key_handle);
+
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use ockam_core::Result;
+use ockam_vault::{
+    SigningKeyType, SoftwareVaultForVerifyingSignatures, VaultForSigning,
+    VaultForVerifyingSignatures,
+};
+use ockam_vault_pkcs11::{Pkcs11Config, Pkcs11SigningVault};
+
+/// These tests need to be executed with a PKCS#11 token, for example with SoftHSM:
+///
+///   softhsm2-util --init-token --free --label ockam --so-pin 1234 --pin 1234
+///
+/// and the following environment variables
+/// PKCS11_MODULE: path to the module, for example /usr/lib/softhsm/libsofthsm2.so
+/// PKCS11_SLOT: slot of the token, as displayed by softhsm2-util --show-slots
+/// PKCS11_PIN: user PIN of the token
+fn config() -> Pkcs11Config {
+    let module = std::env::var("PKCS11_MODULE").unwrap();
+    let slot = std::env::var("PKCS11_SLOT").unwrap().parse().unwrap();
+    let pin = std::env::var("PKCS11_PIN").unwrap();
+    Pkcs11Config::new(module, slot).with_pin(pin)
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_sign_verify() -> Result<()> {
+    let signing_vault = Pkcs11SigningVault::create(config()).await?;
+    let handle = signing_vault
+        .generate_signing_secret_key(SigningKeyType::ECDSASHA256CurveP256)
+        .await?;
+    let message = b"hello world";
+    let signature = signing_vault.sign(&handle, message.as_slice()).await?;
+    let public_key = signing_vault.get_verifying_public_key(&handle).await?;
+
+    let verifier = SoftwareVaultForVerifyingSignatures::new();
+    assert!(
+        verifier
+            .verify_signature(&public_key, message, &signature)
+            .await?
+    );
+
+    signing_vault.delete_signing_secret_key(handle).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_keys_management() -> Result<()> {
+    let signing_vault = Pkcs11SigningVault::create(config()).await?;
+
+    let number_of_keys1 = signing_vault.number_of_keys().await?;
+
+    let handle = signing_vault
+        .generate_signing_secret_key(SigningKeyType::ECDSASHA256CurveP256)
+        .await?;
+
+    let number_of_keys2 = signing_vault.number_of_keys().await?;
+    assert_eq!(number_of_keys1 + 1, number_of_keys2);
+
+    let public_key = signing_vault.get_verifying_public_key(&handle).await?;
+
+    let handle2 = signing_vault.get_secret_key_handle(&public_key).await?;
+    assert_eq!(handle, handle2);
+
+    // The keys are found again when the token is reopened
+    let reopened_vault = Pkcs11SigningVault::create(config()).await?;
+    let public_key2 = reopened_vault.get_verifying_public_key(&handle).await?;
+    assert_eq!(public_key, public_key2);
+
+    assert!(signing_vault.delete_signing_secret_key(handle).await?);
+    let number_of_keys3 = signing_vault.number_of_keys().await?;
+    assert_eq!(number_of_keys2, number_of_keys3 + 1);
+
+    Ok(())
+}